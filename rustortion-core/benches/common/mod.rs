@@ -15,15 +15,17 @@ pub fn create_test_cabinet(ir_length: usize, sample_rate: usize) -> IrCabinet {
     }
 
     let max_ir_samples = (sample_rate * DEFAULT_MAX_IR_MS) / 1000;
-    let mut cabinet = IrCabinet::new(ConvolverType::Fir, max_ir_samples);
+    let mut cabinet = IrCabinet::new(ConvolverType::Auto, max_ir_samples);
 
     let loader = IrLoader::new(&ir_dir, sample_rate).unwrap();
     let ir_samples = loader
         .load_by_name(&format!("test_ir_{ir_length}.wav"))
         .unwrap();
 
-    let mut convolver = Convolver::new_fir(max_ir_samples);
-    convolver.set_ir(&ir_samples).unwrap();
+    // Cut back to the cabinet's limit, as the IR load service does.
+    let ir_samples = &ir_samples[..ir_samples.len().min(max_ir_samples)];
+    let mut convolver = Convolver::new_auto(ir_samples.len());
+    convolver.set_ir(ir_samples).unwrap();
     cabinet.set_convolver(convolver);
 
     cabinet
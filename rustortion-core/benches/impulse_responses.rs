@@ -3,7 +3,9 @@
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
 use std::hint::black_box;
 
-use rustortion_core::ir::convolver::{FirConvolver, TwoStageConvolver};
+use rustfft::num_complex::Complex;
+use rustortion_core::ir::convolver::{FirConvolver, MAX_FIR_TAPS, TwoStageConvolver};
+use rustortion_core::ir::convolver::{cmac, dot};
 
 const SAMPLE_RATE: usize = 48000;
 const BUFFER_SIZE: usize = 128;
@@ -30,63 +32,118 @@ fn generate_test_input(size: usize) -> Vec<f32> {
         .collect()
 }
 
+/// Cost per block of each convolver by IR length: 256 taps (head only), 512
+/// (the FIR's cap), 4096 (a long cabinet) and 48000 (a one-second room).
+///
+/// "Direct" is a dense dot product over every tap, what an uncapped FIR
+/// would cost; where it overtakes "TwoStage" is the crossover that sets
+/// `MAX_FIR_TAPS`, so `Convolver::new_auto` picks the FIR only below it.
 pub fn fir_vs_two_stage_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("FIR vs TwoStage");
+    let input = generate_test_input(BUFFER_SIZE);
 
-    // Test at different IR lengths relevant to cabinet simulation
-    // 1000 = ~21ms, 2400 = 50ms, 4800 = 100ms
-    for &ir_len in &[1_000, 2_400, 4_800] {
+    for &ir_len in &[256, MAX_FIR_TAPS, 4_096, 48_000] {
         let ir = generate_test_ir(ir_len);
-        let input = generate_test_input(BUFFER_SIZE);
-        let ir_ms = (ir_len as f32 / SAMPLE_RATE as f32) * 1000.0;
-
-        // FIR Convolver
-        group.bench_with_input(
-            BenchmarkId::new("FIR", format!("{ir_ms:.0}ms")),
-            &ir_len,
-            |b, _| {
+
+        if ir_len <= MAX_FIR_TAPS {
+            group.bench_with_input(BenchmarkId::new("FIR", ir_len), &ir_len, |b, _| {
                 let mut conv = FirConvolver::new(ir_len);
                 conv.set_ir(&ir).unwrap();
 
-                // Warmup
-                for _ in 0..100 {
-                    let mut buf = input.clone();
-                    conv.process_block(&mut buf);
-                }
-
                 b.iter(|| {
                     let mut buf = input.clone();
                     conv.process_block(black_box(&mut buf));
                     black_box(&buf);
                 });
-            },
-        );
-
-        // TwoStage Convolver
-        group.bench_with_input(
-            BenchmarkId::new("TwoStage", format!("{ir_ms:.0}ms")),
-            &ir_len,
-            |b, _| {
-                let mut conv = TwoStageConvolver::new();
-                conv.set_ir(&ir).unwrap();
+            });
+        }
 
-                // Warmup
-                for _ in 0..100 {
-                    let mut buf = input.clone();
-                    conv.process_block(&mut buf);
+        group.bench_with_input(BenchmarkId::new("Direct", ir_len), &ir_len, |b, _| {
+            let dot_product = dot::detect();
+            let history = generate_test_input(ir_len);
+
+            b.iter(|| {
+                let mut buf = input.clone();
+                for sample in &mut buf {
+                    *sample += dot_product(black_box(&history), &ir);
                 }
+                black_box(&buf);
+            });
+        });
 
-                b.iter(|| {
-                    let mut buf = input.clone();
-                    conv.process_block(black_box(&mut buf));
-                    black_box(&buf);
-                });
-            },
-        );
+        group.bench_with_input(BenchmarkId::new("TwoStage", ir_len), &ir_len, |b, _| {
+            let mut conv = TwoStageConvolver::new();
+            conv.set_ir(&ir).unwrap();
+
+            // Warmup
+            for _ in 0..100 {
+                let mut buf = input.clone();
+                conv.process_block(&mut buf);
+            }
+
+            b.iter(|| {
+                let mut buf = input.clone();
+                conv.process_block(black_box(&mut buf));
+                black_box(&buf);
+            });
+        });
     }
 
     group.finish();
 }
 
-criterion_group!(benches, fir_vs_two_stage_benchmark,);
+/// Tail partitions in a long cabinet/room IR: 512 head samples plus 40
+/// partitions of 512, about 440 ms at 48 kHz.
+const TAIL_PARTITIONS: usize = 40;
+const PARTITION_SIZE: usize = 512;
+const NUM_BINS: usize = PARTITION_SIZE + 1;
+
+/// The tail's complex multiply-accumulate on its own (scalar is the "before"),
+/// and the whole convolver on a 40-partition IR with one partition per block.
+pub fn tail_mac_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Tail MAC (40 partitions)");
+
+    let spectra: Vec<Vec<Complex<f32>>> = (0..TAIL_PARTITIONS)
+        .map(|p| {
+            (0..NUM_BINS)
+                .map(|k| Complex::new(((p + k) as f32).sin(), ((p * k) as f32).cos()))
+                .collect()
+        })
+        .collect();
+    let partitions = spectra.clone();
+
+    for (name, mac) in [
+        ("scalar", cmac::mac_scalar as cmac::ComplexMac),
+        ("detected", cmac::detect()),
+    ] {
+        group.bench_function(name, |b| {
+            let mut acc = vec![Complex::new(0.0, 0.0); NUM_BINS];
+            b.iter(|| {
+                acc.fill(Complex::new(0.0, 0.0));
+                for (x, h) in spectra.iter().zip(&partitions) {
+                    mac(black_box(&mut acc), x, h);
+                }
+                black_box(&acc);
+            });
+        });
+    }
+
+    let ir = generate_test_ir(PARTITION_SIZE + TAIL_PARTITIONS * PARTITION_SIZE);
+    let input = generate_test_input(PARTITION_SIZE);
+    group.bench_function("TwoStage block", |b| {
+        let mut conv = TwoStageConvolver::new();
+        conv.set_ir(&ir).unwrap();
+        assert_eq!(conv.num_tail_partitions(), TAIL_PARTITIONS);
+
+        b.iter(|| {
+            let mut buf = input.clone();
+            conv.process_block(black_box(&mut buf));
+            black_box(&buf);
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, fir_vs_two_stage_benchmark, tail_mac_benchmark);
 criterion_main!(benches);
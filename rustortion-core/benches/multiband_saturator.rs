@@ -0,0 +1,223 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Per-sample cost of the multiband saturator against the biquad crossover
+//! it replaced: six LR4 biquad cascades, including a dedicated allpass pair
+//! for the low band, and the drive scaled on every sample.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::amp::stages::common::{DcBlocker, EnvelopeFollower};
+use rustortion_core::amp::stages::multiband_saturator::MultibandSaturatorStage;
+use std::f32::consts::PI;
+use std::hint::black_box;
+
+const SAMPLE_RATE: f32 = 48000.0;
+const BUFFER_SIZE: usize = 128;
+
+fn generate_test_signal(size: usize) -> Vec<f32> {
+    (0..size)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE;
+            (2.0 * PI * 110.0 * t).sin() * 0.4 + (2.0 * PI * 1760.0 * t).sin() * 0.1
+        })
+        .collect()
+}
+
+// ============================================================================
+// Legacy biquad crossover
+// ============================================================================
+
+#[derive(Clone)]
+struct LR4Filter {
+    // First biquad state
+    x1_1: f32,
+    x2_1: f32,
+    y1_1: f32,
+    y2_1: f32,
+    // Second biquad state
+    x1_2: f32,
+    x2_2: f32,
+    y1_2: f32,
+    y2_2: f32,
+    // Coefficients (same for both biquads)
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    is_highpass: bool,
+}
+
+impl LR4Filter {
+    fn new(cutoff_hz: f32, sample_rate: f32, is_highpass: bool) -> Self {
+        let mut filter = Self {
+            x1_1: 0.0,
+            x2_1: 0.0,
+            y1_1: 0.0,
+            y2_1: 0.0,
+            x1_2: 0.0,
+            x2_2: 0.0,
+            y1_2: 0.0,
+            y2_2: 0.0,
+            b0: 0.0,
+            b1: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            is_highpass,
+        };
+        filter.set_cutoff(cutoff_hz, sample_rate);
+        filter
+    }
+
+    fn set_cutoff(&mut self, cutoff_hz: f32, sample_rate: f32) {
+        // Butterworth Q for LR4 cascade
+        let q = std::f32::consts::FRAC_1_SQRT_2;
+        let omega = 2.0 * PI * cutoff_hz / sample_rate;
+        let sin_omega = omega.sin();
+        let cos_omega = omega.cos();
+        let alpha = sin_omega / (2.0 * q);
+
+        let a0 = 1.0 + alpha;
+
+        if self.is_highpass {
+            self.b0 = f32::midpoint(1.0, cos_omega) / a0;
+            self.b1 = (-(1.0 + cos_omega)) / a0;
+            self.b2 = f32::midpoint(1.0, cos_omega) / a0;
+        } else {
+            self.b0 = ((1.0 - cos_omega) / 2.0) / a0;
+            self.b1 = (1.0 - cos_omega) / a0;
+            self.b2 = ((1.0 - cos_omega) / 2.0) / a0;
+        }
+        self.a1 = (-2.0 * cos_omega) / a0;
+        self.a2 = (1.0 - alpha) / a0;
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        // First biquad
+        let y1 = self.a2.mul_add(
+            -self.y2_1,
+            self.a1.mul_add(
+                -self.y1_1,
+                self.b2
+                    .mul_add(self.x2_1, self.b0.mul_add(input, self.b1 * self.x1_1)),
+            ),
+        );
+        self.x2_1 = self.x1_1;
+        self.x1_1 = input;
+        self.y2_1 = self.y1_1;
+        self.y1_1 = y1;
+
+        // Second biquad (cascade)
+        let y2 = self.a2.mul_add(
+            -self.y2_2,
+            self.a1.mul_add(
+                -self.y1_2,
+                self.b2
+                    .mul_add(self.x2_2, self.b0.mul_add(y1, self.b1 * self.x1_2)),
+            ),
+        );
+        self.x2_2 = self.x1_2;
+        self.x1_2 = y1;
+        self.y2_2 = self.y1_2;
+        self.y1_2 = y2;
+
+        y2
+    }
+}
+
+fn saturate(input: f32, drive: f32) -> f32 {
+    let x = input * drive.mul_add(9.0, 1.0);
+    x / (1.0 + x.abs())
+}
+
+/// The stage as it was before the SVF crossover, mute/solo aside.
+struct LegacyMultiband {
+    low_lp: LR4Filter,
+    mid_hp_low: LR4Filter,
+    mid_lp_high: LR4Filter,
+    high_hp: LR4Filter,
+    low_allpass_lp: LR4Filter,
+    low_allpass_hp: LR4Filter,
+    envs: [EnvelopeFollower; 3],
+    dcs: [DcBlocker; 3],
+    drives: [f32; 3],
+    levels: [f32; 3],
+}
+
+impl LegacyMultiband {
+    fn new(low_freq: f32, high_freq: f32, sample_rate: f32) -> Self {
+        Self {
+            low_lp: LR4Filter::new(low_freq, sample_rate, false),
+            mid_hp_low: LR4Filter::new(low_freq, sample_rate, true),
+            mid_lp_high: LR4Filter::new(high_freq, sample_rate, false),
+            high_hp: LR4Filter::new(high_freq, sample_rate, true),
+            low_allpass_lp: LR4Filter::new(high_freq, sample_rate, false),
+            low_allpass_hp: LR4Filter::new(high_freq, sample_rate, true),
+            envs: std::array::from_fn(|_| EnvelopeFollower::from_ms(1.0, 50.0, sample_rate)),
+            dcs: std::array::from_fn(|_| DcBlocker::new(15.0, sample_rate)),
+            drives: [0.3, 0.5, 0.4],
+            levels: [1.0; 3],
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let low_raw = self.low_lp.process(input);
+        let high_side = self.mid_hp_low.process(input);
+        let mid = self.mid_lp_high.process(high_side);
+        let high = self.high_hp.process(high_side);
+        let low = self.low_allpass_lp.process(low_raw) + self.low_allpass_hp.process(low_raw);
+
+        let mut out = 0.0;
+        for (band, signal) in [low, mid, high].into_iter().enumerate() {
+            let env = self.envs[band].process(signal);
+            let sat = if env > 0.0001 {
+                saturate(signal / (1.0 + env), self.drives[band]) * env.mul_add(0.5, 1.0)
+            } else {
+                saturate(signal, self.drives[band])
+            };
+            out += self.dcs[band].process(sat) * self.levels[band];
+        }
+        out
+    }
+}
+
+// ============================================================================
+// Benchmarks
+// ============================================================================
+
+fn bench_multiband_saturator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Multiband Saturator");
+    group.throughput(Throughput::Elements(BUFFER_SIZE as u64));
+
+    let input = generate_test_signal(BUFFER_SIZE);
+
+    group.bench_with_input(
+        BenchmarkId::new("legacy_biquad", BUFFER_SIZE),
+        &input,
+        |b, input| {
+            let mut stage = LegacyMultiband::new(200.0, 2500.0, SAMPLE_RATE);
+            b.iter(|| {
+                for &sample in input {
+                    black_box(stage.process(black_box(sample)));
+                }
+            });
+        },
+    );
+
+    group.bench_with_input(BenchmarkId::new("svf", BUFFER_SIZE), &input, |b, input| {
+        let mut stage =
+            MultibandSaturatorStage::new(0.3, 0.5, 0.4, 1.0, 1.0, 1.0, 200.0, 2500.0, SAMPLE_RATE);
+        b.iter(|| {
+            for &sample in input {
+                black_box(stage.process(black_box(sample)));
+            }
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_multiband_saturator);
+criterion_main!(benches);
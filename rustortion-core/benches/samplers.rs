@@ -3,6 +3,8 @@
 use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
 use std::hint::black_box;
 
+use rustortion_core::audio::samplers::{ResamplerQuality, Samplers};
+
 use rubato::audioadapter_buffers::direct::SequentialSliceOfVecs;
 use rubato::{
     Async, Fft, FixedAsync, FixedSync, PolynomialDegree, Resampler, SincInterpolationParameters,
@@ -264,5 +266,141 @@ fn bench_resampler_roundtrip(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_resampler_roundtrip);
+// ============================================================================
+// Quality Profiles
+// ============================================================================
+
+const QUALITY_FACTOR: usize = 4;
+const ALIAS_TONE_HZ: f64 = 18_000.0;
+/// Blocks skipped before measuring so the resampler delay line is full.
+const ALIAS_WARMUP_BLOCKS: usize = 16;
+const ALIAS_MEASURE_BLOCKS: usize = 64;
+
+/// Single-bin DFT magnitude of `signal` at `freq`, Hann-windowed to keep
+/// leakage from the fundamental out of the image bin.
+fn tone_magnitude(signal: &[f32], freq: f64, sample_rate: f64) -> f64 {
+    let n = signal.len() as f64;
+    let (mut re, mut im) = (0.0f64, 0.0f64);
+    for (i, &x) in signal.iter().enumerate() {
+        let window = 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / n).cos();
+        let phase = 2.0 * std::f64::consts::PI * freq * i as f64 / sample_rate;
+        re += f64::from(x) * window * phase.cos();
+        im -= f64::from(x) * window * phase.sin();
+    }
+    re.hypot(im)
+}
+
+/// Level (dB relative to the tone) of the image of an 18 kHz tone after
+/// upsampling to 4x. The image sits at `fs - 18 kHz` = 30 kHz; whatever the
+/// upsampler leaves there is what a downstream nonlinearity folds back into
+/// the audible band.
+fn image_level_db(quality: ResamplerQuality) -> f64 {
+    let mut samplers =
+        Samplers::with_quality(BUFFER_SIZE, QUALITY_FACTOR as f64, SAMPLE_RATE, quality).unwrap();
+    let oversampled_rate = (SAMPLE_RATE * QUALITY_FACTOR) as f64;
+
+    let mut captured = Vec::new();
+    let mut block = [0.0f32; BUFFER_SIZE];
+    for b in 0..ALIAS_WARMUP_BLOCKS + ALIAS_MEASURE_BLOCKS {
+        for (i, sample) in block.iter_mut().enumerate() {
+            let t = (b * BUFFER_SIZE + i) as f64 / SAMPLE_RATE as f64;
+            *sample = (0.5 * (2.0 * std::f64::consts::PI * ALIAS_TONE_HZ * t).sin()) as f32;
+        }
+        samplers.copy_input(&block).unwrap();
+        let up = samplers.upsample().unwrap();
+        if b >= ALIAS_WARMUP_BLOCKS {
+            captured.extend_from_slice(up);
+        }
+    }
+
+    let tone = tone_magnitude(&captured, ALIAS_TONE_HZ, oversampled_rate);
+    let image = tone_magnitude(
+        &captured,
+        SAMPLE_RATE as f64 - ALIAS_TONE_HZ,
+        oversampled_rate,
+    );
+    20.0 * (image / tone).max(1e-12).log10()
+}
+
+fn bench_quality_profiles(c: &mut Criterion) {
+    println!("\n18 kHz image level at {QUALITY_FACTOR}x (lower is better):");
+    for quality in ResamplerQuality::ALL {
+        println!(
+            "  {:<9} {:>8.1} dB",
+            quality.to_string(),
+            image_level_db(quality)
+        );
+    }
+
+    let mut group = c.benchmark_group("Resampler Quality");
+    group.throughput(Throughput::Elements(BUFFER_SIZE as u64));
+
+    let input = generate_test_signal(BUFFER_SIZE).remove(0);
+
+    for quality in ResamplerQuality::ALL {
+        group.bench_with_input(
+            BenchmarkId::new(quality.to_string(), format!("{QUALITY_FACTOR}x")),
+            &quality,
+            |b, &quality| {
+                let mut samplers = Samplers::with_quality(
+                    BUFFER_SIZE,
+                    QUALITY_FACTOR as f64,
+                    SAMPLE_RATE,
+                    quality,
+                )
+                .unwrap();
+                b.iter(|| {
+                    samplers.copy_input(black_box(&input)).unwrap();
+                    samplers.upsample().unwrap();
+                    black_box(samplers.downsample().unwrap());
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+// ============================================================================
+// Unity Bypass
+// ============================================================================
+
+/// At 1x the samplers skip resampling, so a round trip is two block copies
+/// and adds no latency; 2x is the cheapest factor that still resamples.
+fn bench_unity_bypass(c: &mut Criterion) {
+    println!("\nRound-trip latency at the default quality:");
+    for factor in [1.0, 2.0] {
+        let samplers = Samplers::new(BUFFER_SIZE, factor, SAMPLE_RATE).unwrap();
+        println!("  {factor}x {:>4} samples", samplers.latency_samples());
+    }
+
+    let mut group = c.benchmark_group("Unity Bypass");
+    group.throughput(Throughput::Elements(BUFFER_SIZE as u64));
+
+    let input = generate_test_signal(BUFFER_SIZE).remove(0);
+
+    for factor in [1usize, 2] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{factor}x")),
+            &factor,
+            |b, &factor| {
+                let mut samplers = Samplers::new(BUFFER_SIZE, factor as f64, SAMPLE_RATE).unwrap();
+                b.iter(|| {
+                    samplers.copy_input(black_box(&input)).unwrap();
+                    samplers.upsample().unwrap();
+                    black_box(samplers.downsample().unwrap());
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_resampler_roundtrip,
+    bench_quality_profiles,
+    bench_unity_bypass
+);
 criterion_main!(benches);
@@ -1,8 +1,46 @@
+use std::ops::Range;
+
+use crate::amp::crossfade::StageCrossfade;
+use crate::amp::stage_peaks::StagePeaks;
 use crate::amp::stages::Stage;
+use crate::audio::standby::Standby;
 
 struct BypassableStage {
     inner: Box<dyn Stage>,
     bypassed: bool,
+    /// The stage `inner` replaced, while it fades out.
+    fade: Option<Box<StageCrossfade>>,
+}
+
+impl BypassableStage {
+    const fn new(inner: Box<dyn Stage>) -> Self {
+        Self {
+            inner,
+            bypassed: false,
+            fade: None,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        match self.fade.as_deref_mut() {
+            Some(fade) => fade.process(self.inner.as_mut(), input),
+            None => self.inner.process(input),
+        }
+    }
+
+    fn process_block(&mut self, input: &mut [f32]) {
+        match self.fade.as_deref_mut() {
+            Some(fade) => fade.process_block(self.inner.as_mut(), input),
+            None => self.inner.process_block(input),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+        if let Some(fade) = self.fade.as_deref_mut() {
+            fade.reset();
+        }
+    }
 }
 
 /// Stage capacity reserved up front, and the hard cap on chain length.
@@ -13,9 +51,23 @@ struct BypassableStage {
 /// ~1.5 KB reserved.
 pub const DEFAULT_CHAIN_CAPACITY: usize = 64;
 
+/// Fades taken off their stage before they were over, held for
+/// [`AmplifierChain::drain_fades`]. Every message can cut short at most one,
+/// and the engine drains after each.
+const RETIRED_FADE_CAPACITY: usize = 4;
+
 // AmplifierChain holds a sequence of processing stages.
 pub struct AmplifierChain {
     stages: Vec<BypassableStage>,
+    /// Last tempo passed to `set_tempo`; handed to stages added later.
+    tempo_bpm: Option<f32>,
+    /// Soloed stage: processing stops after it, so its output is the chain's.
+    monitor_point: Option<usize>,
+    /// Where standby mutes the chain: just after the last power amp stage,
+    /// or at the end without one. Kept up to date as stages come and go.
+    standby_split: usize,
+    /// Fades waiting to be retired off the RT thread.
+    retired_fades: Vec<Box<StageCrossfade>>,
 }
 
 impl Default for AmplifierChain {
@@ -35,33 +87,97 @@ impl AmplifierChain {
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             stages: Vec::with_capacity(capacity),
+            tempo_bpm: None,
+            monitor_point: None,
+            standby_split: 0,
+            retired_fades: Vec::with_capacity(RETIRED_FADE_CAPACITY),
         }
     }
 
-    pub fn add_stage(&mut self, stage: Box<dyn Stage>) {
-        self.stages.push(BypassableStage {
-            inner: stage,
-            bypassed: false,
-        });
+    pub fn add_stage(&mut self, mut stage: Box<dyn Stage>) {
+        if let Some(bpm) = self.tempo_bpm {
+            stage.set_tempo(bpm);
+        }
+        self.stages.push(BypassableStage::new(stage));
+        self.find_standby_split();
     }
 
     pub fn process(&mut self, input: f32) -> f32 {
         let mut signal = input;
 
-        for stage in &mut self.stages {
+        let end = self.active_len();
+        for stage in &mut self.stages[..end] {
             if !stage.bypassed {
-                signal = stage.inner.process(signal);
+                signal = stage.process(signal);
             }
         }
 
         signal
     }
 
-    // process_block processes a block of samples through the entire chain.
+    // process_block processes a block of samples through the entire chain,
+    // or up to the monitor point when one is set.
     pub fn process_block(&mut self, input: &mut [f32]) {
-        for stage in &mut self.stages {
+        let end = self.active_len();
+        for stage in &mut self.stages[..end] {
             if !stage.bypassed {
-                stage.inner.process_block(input);
+                stage.process_block(input);
+            }
+        }
+    }
+
+    /// Like [`Self::process_block`], also recording each active stage's
+    /// output peak into `peaks` while anyone is reading them.
+    pub fn process_block_metered(&mut self, input: &mut [f32], peaks: &StagePeaks) {
+        if !peaks.is_metering() {
+            self.process_block(input);
+            return;
+        }
+        let end = self.active_len();
+        self.process_stages(0..end, input, Some(peaks));
+    }
+
+    /// Like [`Self::process_block_metered`], with `standby` applied just
+    /// after the last power amp stage. Every stage keeps running: those
+    /// ahead of it on the signal, those after it on what standby lets
+    /// through. A solo ending the chain earlier takes the mute with it.
+    pub fn process_block_standby(
+        &mut self,
+        input: &mut [f32],
+        standby: &mut Standby,
+        peaks: Option<&StagePeaks>,
+    ) {
+        let peaks = peaks.filter(|p| p.is_metering());
+        if standby.is_idle() {
+            match peaks {
+                Some(peaks) => self.process_block_metered(input, peaks),
+                None => self.process_block(input),
+            }
+            return;
+        }
+        let end = self.active_len();
+        let split = self.standby_split.min(end);
+        self.process_stages(0..split, input, peaks);
+        standby.process_block(input);
+        self.process_stages(split..end, input, peaks);
+    }
+
+    fn process_stages(
+        &mut self,
+        range: Range<usize>,
+        input: &mut [f32],
+        peaks: Option<&StagePeaks>,
+    ) {
+        let start = range.start;
+        for (offset, stage) in self.stages[range].iter_mut().enumerate() {
+            if !stage.bypassed {
+                stage.process_block(input);
+                if let Some(peaks) = peaks {
+                    peaks.record(
+                        start + offset,
+                        input.iter().fold(0.0_f32, |m, s| m.max(s.abs())),
+                    );
+                }
             }
         }
     }
@@ -78,6 +194,19 @@ impl AmplifierChain {
             .map(|s| s.inner.set_parameter(name, value))
     }
 
+    /// Forward a parameter change to a stage inside a live group.
+    pub fn set_child_parameter(
+        &mut self,
+        idx: usize,
+        child: usize,
+        name: &str,
+        value: f32,
+    ) -> Option<Result<(), &'static str>> {
+        self.stages
+            .get_mut(idx)
+            .map(|s| s.inner.set_child_parameter(child, name, value))
+    }
+
     /// Read a parameter from a live stage.
     pub fn get_parameter(&self, idx: usize, name: &str) -> Option<Result<f32, &'static str>> {
         self.stages.get(idx).map(|s| s.inner.get_parameter(name))
@@ -91,25 +220,34 @@ impl AmplifierChain {
     /// would allocate, and dropping the rejected box here would free, on the
     /// audio thread). Returns `None` when the stage was inserted.
     #[must_use]
-    pub fn insert_stage(&mut self, idx: usize, stage: Box<dyn Stage>) -> Option<Box<dyn Stage>> {
+    pub fn insert_stage(
+        &mut self,
+        idx: usize,
+        mut stage: Box<dyn Stage>,
+    ) -> Option<Box<dyn Stage>> {
         if self.stages.len() == self.stages.capacity() {
             return Some(stage);
         }
+        self.set_monitor_point(None);
+        if let Some(bpm) = self.tempo_bpm {
+            stage.set_tempo(bpm);
+        }
         let idx = idx.min(self.stages.len());
-        self.stages.insert(
-            idx,
-            BypassableStage {
-                inner: stage,
-                bypassed: false,
-            },
-        );
+        self.stages.insert(idx, BypassableStage::new(stage));
+        self.find_standby_split();
         None
     }
 
     /// Remove and return the stage at the given index.
     pub fn remove_stage(&mut self, idx: usize) -> Option<Box<dyn Stage>> {
         if idx < self.stages.len() {
-            Some(self.stages.remove(idx).inner)
+            self.set_monitor_point(None);
+            let removed = self.stages.remove(idx);
+            if let Some(fade) = removed.fade {
+                self.park_fade(fade);
+            }
+            self.find_standby_split();
+            Some(removed.inner)
         } else {
             None
         }
@@ -118,7 +256,9 @@ impl AmplifierChain {
     /// Swap two stages by index.
     pub fn swap_stages(&mut self, a: usize, b: usize) {
         if a < self.stages.len() && b < self.stages.len() {
+            self.set_monitor_point(None);
             self.stages.swap(a, b);
+            self.find_standby_split();
         }
     }
 
@@ -126,20 +266,165 @@ impl AmplifierChain {
     pub fn replace_stage(
         &mut self,
         idx: usize,
-        new_stage: Box<dyn Stage>,
+        mut new_stage: Box<dyn Stage>,
     ) -> Option<Box<dyn Stage>> {
         if idx < self.stages.len() {
+            if let Some(bpm) = self.tempo_bpm {
+                new_stage.set_tempo(bpm);
+            }
             let old = std::mem::replace(&mut self.stages[idx].inner, new_stage);
+            if let Some(fade) = self.stages[idx].fade.take() {
+                self.park_fade(fade);
+            }
+            self.find_standby_split();
             Some(old)
         } else {
             None
         }
     }
 
+    /// Swap in the stage `fade` was built around at `idx`, fading over to it
+    /// from the one there instead of cutting. A bypassed stage isn't heard,
+    /// so it's swapped outright. A fade still running at `idx` is cut short,
+    /// its old stage dropped from the mix.
+    ///
+    /// Returns `fade` if `idx` is out of bounds, so the caller can dispose of
+    /// it off the RT thread. Finished fades come back from
+    /// [`Self::drain_fades`].
+    #[must_use]
+    pub fn crossfade_stage(
+        &mut self,
+        idx: usize,
+        mut fade: Box<StageCrossfade>,
+    ) -> Option<Box<StageCrossfade>> {
+        let Some(slot) = self.stages.get_mut(idx) else {
+            return Some(fade);
+        };
+        fade.begin(&mut slot.inner);
+        if let Some(bpm) = self.tempo_bpm {
+            slot.inner.set_tempo(bpm);
+        }
+        let cut_short = if slot.bypassed {
+            fade.finish();
+            Some(fade)
+        } else {
+            slot.fade.replace(fade)
+        };
+        if let Some(fade) = cut_short {
+            self.park_fade(fade);
+        }
+        self.find_standby_split();
+        None
+    }
+
+    /// Hand `retire` every fade that's over or was cut short, with the stage
+    /// it faded out. Called on the RT thread after messages, so `retire`
+    /// must send them elsewhere to be dropped.
+    pub fn drain_fades(&mut self, mut retire: impl FnMut(Box<StageCrossfade>)) {
+        while let Some(fade) = self.retired_fades.pop() {
+            retire(fade);
+        }
+        for stage in &mut self.stages {
+            if let Some(fade) = stage.fade.take_if(|fade| fade.is_finished()) {
+                retire(fade);
+            }
+        }
+    }
+
+    /// Hold a fade taken off its stage until the next drain. Past capacity
+    /// it's leaked rather than freed on the RT thread, as
+    /// [`crate::audio::rt_drop::RtDropHandle`] does when its queue is full.
+    fn park_fade(&mut self, fade: Box<StageCrossfade>) {
+        if self.retired_fades.len() < self.retired_fades.capacity() {
+            self.retired_fades.push(fade);
+        } else {
+            std::mem::forget(fade);
+        }
+    }
+
+    /// Pass the current tempo to every stage, bypassed or not. Does nothing if
+    /// `bpm` is unchanged or not a usable tempo, so it's cheap to call every block.
+    pub fn set_tempo(&mut self, bpm: f32) {
+        if !(bpm > 0.0 && bpm.is_finite()) || self.tempo_bpm == Some(bpm) {
+            return;
+        }
+        self.tempo_bpm = Some(bpm);
+        for stage in &mut self.stages {
+            stage.inner.set_tempo(bpm);
+            if let Some(fade) = stage.fade.as_deref_mut() {
+                fade.set_tempo(bpm);
+            }
+        }
+    }
+
+    /// Clear the internal state of every stage, bypassed or not.
+    pub fn reset(&mut self) {
+        for stage in &mut self.stages {
+            stage.reset();
+        }
+    }
+
+    /// Solo the stage at `idx`, so the chain outputs what that stage does and
+    /// the stages after it sit idle; `None` plays the whole chain again.
+    ///
+    /// Stages left idle are reset when they come back, so they don't resume
+    /// from a stale tail. Adding, removing or moving a stage clears the
+    /// monitor point rather than leave it on whatever slides into its place.
+    /// Returns `false` if `idx` is out of bounds, leaving the monitor point
+    /// as it was.
+    pub fn set_monitor_point(&mut self, idx: Option<usize>) -> bool {
+        if idx.is_some_and(|idx| idx >= self.stages.len()) {
+            return false;
+        }
+        let resumed = idx.map_or(self.stages.len(), |idx| idx + 1);
+        if let Some(old) = self.monitor_point
+            && old + 1 < resumed
+        {
+            for stage in &mut self.stages[old + 1..resumed] {
+                stage.reset();
+            }
+        }
+        self.monitor_point = idx;
+        true
+    }
+
+    pub const fn monitor_point(&self) -> Option<usize> {
+        self.monitor_point
+    }
+
+    /// Index standby mutes the chain at; see [`Self::process_block_standby`].
+    pub const fn standby_split(&self) -> usize {
+        self.standby_split
+    }
+
+    /// Whether any stage is, or holds, a power amp.
+    pub fn has_power_amp(&self) -> bool {
+        self.stages.iter().any(|s| s.inner.is_power_amp())
+    }
+
+    fn find_standby_split(&mut self) {
+        self.standby_split = self
+            .stages
+            .iter()
+            .rposition(|s| s.inner.is_power_amp())
+            .map_or(self.stages.len(), |idx| idx + 1);
+    }
+
+    /// Number of leading stages that run, up to and including the monitor
+    /// point.
+    fn active_len(&self) -> usize {
+        self.monitor_point
+            .map_or(self.stages.len(), |idx| (idx + 1).min(self.stages.len()))
+    }
+
     /// Set the bypass state of a stage. Returns `true` if the index was valid.
+    /// A fade still running on a stage being bypassed is over.
     pub fn set_bypassed(&mut self, idx: usize, bypassed: bool) -> bool {
         if let Some(stage) = self.stages.get_mut(idx) {
             stage.bypassed = bypassed;
+            if bypassed && let Some(fade) = stage.fade.as_deref_mut() {
+                fade.finish();
+            }
             true
         } else {
             false
@@ -192,6 +477,26 @@ mod tests {
         assert!((out - 1.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn metered_block_records_active_stage_peaks() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(2.0));
+        chain.add_stage(make_level(0.5));
+        chain.add_stage(make_level(0.5));
+        chain.set_bypassed(1, true);
+        let peaks = StagePeaks::new();
+
+        let mut buf = [0.5_f32, -0.25];
+        chain.process_block_metered(&mut buf, &peaks);
+        assert_eq!(peaks.take(3), vec![0.0; 3]);
+
+        peaks.set_enabled(true);
+        let mut buf = [0.5_f32, -0.25];
+        chain.process_block_metered(&mut buf, &peaks);
+        assert_eq!(peaks.take(3), vec![1.0, 0.0, 0.5]);
+        assert!((buf[0] - 0.5).abs() < 1e-6);
+    }
+
     #[test]
     fn remove_stage_out_of_bounds() {
         let mut chain = AmplifierChain::new();
@@ -219,6 +524,59 @@ mod tests {
         assert!((out - 0.25).abs() < 1e-6);
     }
 
+    /// A fade over 20 samples, 20 ms at 1 kHz.
+    fn fade_to_level(gain: f32) -> Box<StageCrossfade> {
+        Box::new(StageCrossfade::new(make_level(gain), 1_000.0))
+    }
+
+    #[test]
+    fn crossfaded_stage_is_drained_once_the_fade_is_over() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        assert!(chain.crossfade_stage(0, fade_to_level(0.0)).is_none());
+
+        let mut drained = 0;
+        chain.drain_fades(|_| drained += 1);
+        assert_eq!(drained, 0, "drained mid-fade");
+
+        let mut block = [1.0; 32];
+        chain.process_block(&mut block);
+        assert!(block[0] > 0.9);
+        assert!(block.windows(2).all(|w| w[1] <= w[0]));
+        assert_eq!(block[31], 0.0);
+
+        chain.drain_fades(|_| drained += 1);
+        assert_eq!(drained, 1);
+    }
+
+    #[test]
+    fn crossfade_into_bypassed_stage_swaps_outright() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        chain.set_bypassed(0, true);
+        assert!(chain.crossfade_stage(0, fade_to_level(0.5)).is_none());
+
+        let mut drained = 0;
+        chain.drain_fades(|_| drained += 1);
+        assert_eq!(drained, 1);
+
+        chain.set_bypassed(0, false);
+        assert!((chain.process(1.0) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn removing_a_fading_stage_keeps_its_fade_for_draining() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        assert!(chain.crossfade_stage(0, fade_to_level(0.5)).is_none());
+        assert!(chain.crossfade_stage(3, fade_to_level(0.5)).is_some());
+
+        assert!(chain.remove_stage(0).is_some());
+        let mut drained = 0;
+        chain.drain_fades(|_| drained += 1);
+        assert_eq!(drained, 1);
+    }
+
     #[test]
     fn bypassed_stage_passes_signal_through() {
         let mut chain = AmplifierChain::new();
@@ -308,4 +666,195 @@ mod tests {
             "swapped: active 2x, bypassed 0.5x"
         );
     }
+
+    /// Sample offset of the first echo of an impulse, after letting the
+    /// delay-time smoother settle.
+    fn echo_offset(chain: &mut AmplifierChain) -> usize {
+        let mut settle = vec![0.0; 48_000];
+        chain.process_block(&mut settle);
+        let mut block = vec![0.0; 48_000];
+        block[0] = 1.0;
+        chain.process_block(&mut block);
+        block.iter().position(|s| s.abs() > 0.5).unwrap()
+    }
+
+    fn synced_delay() -> Box<dyn Stage> {
+        use crate::amp::stages::delay::{DelayStage, NoteDivision};
+        // 1/8 at 100 BPM is 300 ms; the unsynced time is 100 ms.
+        Box::new(DelayStage::new(100.0, 0.0, 1.0, 48_000.0).with_sync(true, NoteDivision::Eighth))
+    }
+
+    #[test]
+    fn set_tempo_reaches_existing_stages() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(synced_delay());
+        chain.set_tempo(100.0);
+        assert_eq!(echo_offset(&mut chain), 14_400);
+    }
+
+    #[test]
+    fn stages_added_later_get_current_tempo() {
+        let mut chain = AmplifierChain::new();
+        chain.set_tempo(100.0);
+        chain.add_stage(synced_delay());
+        assert_eq!(echo_offset(&mut chain), 14_400);
+
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        chain.set_tempo(100.0);
+        chain.replace_stage(0, synced_delay());
+        assert_eq!(echo_offset(&mut chain), 14_400);
+    }
+
+    #[test]
+    fn invalid_tempo_is_ignored() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(synced_delay());
+        chain.set_tempo(0.0);
+        chain.set_tempo(f32::NAN);
+        assert_eq!(echo_offset(&mut chain), 4_800);
+    }
+
+    #[test]
+    fn monitor_point_outputs_the_soloed_stage() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(2.0));
+        chain.add_stage(make_level(0.5));
+        chain.add_stage(make_level(0.5));
+
+        assert!(chain.set_monitor_point(Some(1)));
+        let mut buf = [1.0_f32];
+        chain.process_block(&mut buf);
+        assert!((buf[0] - 1.0).abs() < 1e-6);
+
+        // Moving the solo moves where the chain stops.
+        assert!(chain.set_monitor_point(Some(0)));
+        assert!((chain.process(1.0) - 2.0).abs() < 1e-6);
+
+        assert!(chain.set_monitor_point(None));
+        assert!((chain.process(1.0) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn metered_block_stops_at_the_monitor_point() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(2.0));
+        chain.add_stage(make_level(0.5));
+        chain.set_monitor_point(Some(0));
+        let peaks = StagePeaks::new();
+        peaks.set_enabled(true);
+
+        let mut buf = [0.5_f32];
+        chain.process_block_metered(&mut buf, &peaks);
+        assert_eq!(peaks.take(2), vec![1.0, 0.0]);
+        assert!((buf[0] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn out_of_bounds_monitor_point_is_rejected() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        chain.set_monitor_point(Some(0));
+        assert!(!chain.set_monitor_point(Some(1)));
+        assert_eq!(chain.monitor_point(), Some(0));
+    }
+
+    #[test]
+    fn changing_the_stages_clears_the_monitor_point() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        chain.add_stage(make_level(1.0));
+
+        chain.set_monitor_point(Some(1));
+        chain.swap_stages(0, 1);
+        assert_eq!(chain.monitor_point(), None);
+
+        chain.set_monitor_point(Some(1));
+        assert!(chain.insert_stage(0, make_level(1.0)).is_none());
+        assert_eq!(chain.monitor_point(), None);
+
+        chain.set_monitor_point(Some(1));
+        assert!(chain.remove_stage(0).is_some());
+        assert_eq!(chain.monitor_point(), None);
+
+        // Parameter and bypass changes leave the solo where it is.
+        chain.set_monitor_point(Some(1));
+        chain.set_bypassed(1, true);
+        chain.set_parameter(0, "gain", 0.5);
+        assert_eq!(chain.monitor_point(), Some(1));
+    }
+
+    fn make_power_amp() -> Box<dyn Stage> {
+        use crate::amp::stages::poweramp::{PowerAmpStage, PowerAmpType};
+        Box::new(PowerAmpStage::new(
+            0.5,
+            PowerAmpType::ClassAB,
+            0.0,
+            100.0,
+            48_000.0,
+        ))
+    }
+
+    #[test]
+    fn standby_split_follows_the_last_power_amp() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(1.0));
+        assert_eq!(chain.standby_split(), 1);
+
+        chain.add_stage(make_power_amp());
+        chain.add_stage(make_level(1.0));
+        assert_eq!(chain.standby_split(), 2);
+
+        assert!(chain.insert_stage(0, make_power_amp()).is_none());
+        assert_eq!(chain.standby_split(), 3);
+        chain.swap_stages(2, 3);
+        assert_eq!(chain.standby_split(), 4);
+        chain.remove_stage(3);
+        assert_eq!(chain.standby_split(), 1);
+        chain.replace_stage(0, make_level(1.0));
+        assert_eq!(chain.standby_split(), 3);
+    }
+
+    #[test]
+    fn standby_split_sees_power_amps_built_from_configs() {
+        use crate::amp::stages::group::GroupConfig;
+        use crate::preset::{StageConfig, StageType};
+
+        // Built as presets are, so every stage is wrapped in its trims.
+        let mut chain = AmplifierChain::new();
+        for kind in [StageType::Preamp, StageType::PowerAmp, StageType::Reverb] {
+            chain.add_stage(StageConfig::from(kind).to_runtime(48_000.0));
+        }
+        assert!(chain.has_power_amp());
+        assert_eq!(chain.standby_split(), 2);
+
+        let group = StageConfig::Group(GroupConfig {
+            stages: vec![StageConfig::from(StageType::PowerAmp)],
+            ..GroupConfig::default()
+        });
+        chain.add_stage(group.to_runtime(48_000.0));
+        chain.add_stage(StageConfig::from(StageType::Delay).to_runtime(48_000.0));
+        assert_eq!(chain.standby_split(), 4);
+    }
+
+    #[test]
+    fn standby_mutes_after_the_split_and_keeps_later_stages_running() {
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(make_level(2.0));
+        chain.add_stage(make_power_amp());
+        chain.add_stage(make_level(0.5));
+        let peaks = StagePeaks::new();
+        peaks.set_enabled(true);
+
+        let mut standby = Standby::new(true, 48_000.0);
+        let mut buf = [0.25_f32; 8];
+        chain.process_block_standby(&mut buf, &mut standby, Some(&peaks));
+        assert_eq!(buf, [0.0; 8]);
+        // The stage ahead of the power amp still saw the signal, and the
+        // one after it ran on the muted output.
+        let stage_peaks = peaks.take(3);
+        assert!((stage_peaks[0] - 0.5).abs() < 1e-6);
+        assert!(stage_peaks[1] > 0.0);
+        assert!(stage_peaks[2].abs() < 1e-6);
+    }
 }
@@ -0,0 +1,136 @@
+use crate::amp::stages::Stage;
+
+/// How long a swapped stage takes to fade over to its replacement.
+pub const STAGE_CROSSFADE_MS: f32 = 20.0;
+
+/// Samples run through the outgoing stage at a time, in a buffer on the
+/// stack so the fade never allocates.
+const CHUNK: usize = 64;
+
+/// Hot-swap of one stage for another built from a different type or model.
+/// The two can't be morphed like a float parameter, so both run on the same
+/// input for [`STAGE_CROSSFADE_MS`] and the output fades linearly from the
+/// old to the new; they hear the same signal, so their outputs are
+/// correlated and a linear fade keeps the level.
+///
+/// Built off the RT thread around the new stage. The chain swaps that in
+/// and keeps the old one here until the fade is over, then hands the whole
+/// fade back to be retired off the RT thread.
+pub struct StageCrossfade {
+    /// The new stage until the fade starts, the old one after.
+    stage: Box<dyn Stage>,
+    remaining: usize,
+    length: usize,
+}
+
+impl StageCrossfade {
+    pub fn new(incoming: Box<dyn Stage>, sample_rate: f32) -> Self {
+        let length = ((STAGE_CROSSFADE_MS / 1000.0 * sample_rate) as usize).max(1);
+        Self {
+            stage: incoming,
+            remaining: length,
+            length,
+        }
+    }
+
+    /// Put the new stage in `current`'s place and start fading out the one
+    /// that was there.
+    pub(crate) fn begin(&mut self, current: &mut Box<dyn Stage>) {
+        std::mem::swap(current, &mut self.stage);
+        self.remaining = self.length;
+    }
+
+    /// The old stage is no longer heard.
+    pub const fn is_finished(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Cut straight over to the new stage.
+    pub(crate) const fn finish(&mut self) {
+        self.remaining = 0;
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.stage.reset();
+    }
+
+    pub(crate) fn set_tempo(&mut self, bpm: f32) {
+        self.stage.set_tempo(bpm);
+    }
+
+    pub(crate) fn process(&mut self, incoming: &mut dyn Stage, input: f32) -> f32 {
+        let new = incoming.process(input);
+        if self.is_finished() {
+            return new;
+        }
+        let old = self.stage.process(input);
+        (new - old).mul_add(self.next_gain(), old)
+    }
+
+    pub(crate) fn process_block(&mut self, incoming: &mut dyn Stage, input: &mut [f32]) {
+        let mut outgoing = [0.0; CHUNK];
+        let mut rest = input;
+        while !rest.is_empty() && !self.is_finished() {
+            let (chunk, tail) = rest.split_at_mut(CHUNK.min(rest.len()));
+            let outgoing = &mut outgoing[..chunk.len()];
+            outgoing.copy_from_slice(chunk);
+            self.stage.process_block(outgoing);
+            incoming.process_block(chunk);
+            for (new, &old) in chunk.iter_mut().zip(outgoing.iter()) {
+                *new = (*new - old).mul_add(self.next_gain(), old);
+            }
+            rest = tail;
+        }
+        incoming.process_block(rest);
+    }
+
+    /// Gain of the new stage for the next sample; the old one gets the rest.
+    fn next_gain(&mut self) -> f32 {
+        self.remaining = self.remaining.saturating_sub(1);
+        1.0 - self.remaining as f32 / self.length as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::level::LevelStage;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+
+    fn fade_between(old: f32, new: f32) -> (Box<dyn Stage>, StageCrossfade) {
+        let mut current: Box<dyn Stage> = Box::new(LevelStage::new(old));
+        let mut fade = StageCrossfade::new(Box::new(LevelStage::new(new)), SAMPLE_RATE);
+        fade.begin(&mut current);
+        (current, fade)
+    }
+
+    #[test]
+    fn fade_ramps_from_old_to_new() {
+        let (mut current, mut fade) = fade_between(1.0, 0.0);
+        let length = (STAGE_CROSSFADE_MS / 1000.0 * SAMPLE_RATE) as usize;
+
+        let mut block = vec![1.0; length + 100];
+        fade.process_block(current.as_mut(), &mut block);
+
+        assert!(block[0] > 0.99);
+        assert!((block[length / 2] - 0.5).abs() < 0.01);
+        assert!(block[length - 1..].iter().all(|&s| s == 0.0));
+        assert!(block.windows(2).all(|w| w[1] <= w[0]));
+        assert!(fade.is_finished());
+    }
+
+    #[test]
+    fn per_sample_and_block_fades_match() {
+        let (mut block_stage, mut block_fade) = fade_between(1.0, 0.25);
+        let (mut sample_stage, mut sample_fade) = fade_between(1.0, 0.25);
+
+        let mut block = vec![0.5; 1_500];
+        block_fade.process_block(block_stage.as_mut(), &mut block);
+        let samples: Vec<f32> = (0..block.len())
+            .map(|_| sample_fade.process(sample_stage.as_mut(), 0.5))
+            .collect();
+
+        assert_eq!(block, samples);
+    }
+}
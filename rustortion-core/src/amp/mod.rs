@@ -1,2 +1,4 @@
 pub mod chain;
+pub mod crossfade;
+pub mod stage_peaks;
 pub mod stages;
@@ -0,0 +1,176 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use crate::amp::chain::DEFAULT_CHAIN_CAPACITY;
+
+/// Per-stage output peaks for the gain staging assistant and the live level
+/// bars on the stage cards.
+///
+/// While either is enabled, [`AmplifierChain::process_block_metered`]
+/// records the block peak of every active stage; while both are off it
+/// costs two relaxed loads per block. Peaks are linear, stored as `f32`
+/// bits, and held at their maximum until read with [`Self::take`] or
+/// [`Self::take_live`]. The two keep their own peaks, so neither reader
+/// empties the other's.
+///
+/// [`AmplifierChain::process_block_metered`]: crate::amp::chain::AmplifierChain::process_block_metered
+pub struct StagePeaks {
+    enabled: AtomicBool,
+    peaks: [AtomicU32; DEFAULT_CHAIN_CAPACITY],
+    live_enabled: AtomicBool,
+    live_peaks: [AtomicU32; DEFAULT_CHAIN_CAPACITY],
+}
+
+impl Default for StagePeaks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StagePeaks {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            peaks: std::array::from_fn(|_| AtomicU32::new(0)),
+            live_enabled: AtomicBool::new(false),
+            live_peaks: std::array::from_fn(|_| AtomicU32::new(0)),
+        }
+    }
+
+    /// Start or stop metering. Starting clears the held peaks.
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled {
+            for peak in &self.peaks {
+                peak.store(0, Ordering::Relaxed);
+            }
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Start or stop the live level bars' metering. Starting clears their
+    /// held peaks.
+    pub fn set_live(&self, enabled: bool) {
+        if enabled {
+            for peak in &self.live_peaks {
+                peak.store(0, Ordering::Relaxed);
+            }
+        }
+        self.live_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.live_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Whether anyone is reading peaks, so the chain should record them.
+    pub fn is_metering(&self) -> bool {
+        self.is_enabled() || self.is_live()
+    }
+
+    /// Raise the held peak of stage `idx` to `peak` for each reader that's
+    /// enabled. Called on the RT thread, which is the only writer, so a load
+    /// and a store are enough.
+    pub fn record(&self, idx: usize, peak: f32) {
+        if self.is_enabled() {
+            raise(self.peaks.get(idx), peak);
+        }
+        if self.is_live() {
+            raise(self.live_peaks.get(idx), peak);
+        }
+    }
+
+    /// Held peaks of the first `len` stages, clearing them.
+    pub fn take(&self, len: usize) -> Vec<f32> {
+        take(&self.peaks, len)
+    }
+
+    /// Held peaks of the first `len` stages for the level bars, clearing
+    /// them.
+    pub fn take_live(&self, len: usize) -> Vec<f32> {
+        take(&self.live_peaks, len)
+    }
+}
+
+fn raise(held: Option<&AtomicU32>, peak: f32) {
+    if let Some(held) = held
+        && peak > f32::from_bits(held.load(Ordering::Relaxed))
+    {
+        held.store(peak.to_bits(), Ordering::Relaxed);
+    }
+}
+
+fn take(peaks: &[AtomicU32], len: usize) -> Vec<f32> {
+    peaks
+        .iter()
+        .take(len)
+        .map(|held| f32::from_bits(held.swap(0, Ordering::Relaxed)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_the_highest_peak_until_taken() {
+        let peaks = StagePeaks::new();
+        peaks.set_enabled(true);
+        peaks.record(1, 0.5);
+        peaks.record(1, 0.25);
+        peaks.record(DEFAULT_CHAIN_CAPACITY, 1.0);
+        assert_eq!(peaks.take(2), vec![0.0, 0.5]);
+        assert_eq!(peaks.take(2), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn enabling_clears_old_peaks() {
+        let peaks = StagePeaks::new();
+        peaks.record(0, 0.5);
+        peaks.set_enabled(true);
+        assert!(peaks.is_enabled());
+        assert_eq!(peaks.take(1), vec![0.0]);
+    }
+
+    #[test]
+    fn records_nothing_while_off() {
+        let peaks = StagePeaks::new();
+        assert!(!peaks.is_metering());
+        peaks.record(0, 0.5);
+        assert_eq!(peaks.take(1), vec![0.0]);
+        assert_eq!(peaks.take_live(1), vec![0.0]);
+    }
+
+    #[test]
+    fn live_peaks_aggregate_blocks_between_reads() {
+        let peaks = StagePeaks::new();
+        peaks.set_live(true);
+        assert!(peaks.is_metering());
+        for block_peak in [0.1, 0.7, 0.3] {
+            peaks.record(0, block_peak);
+            peaks.record(1, block_peak / 2.0);
+        }
+        assert_eq!(peaks.take_live(2), vec![0.7, 0.35]);
+        peaks.record(0, 0.2);
+        assert_eq!(peaks.take_live(2), vec![0.2, 0.0]);
+    }
+
+    #[test]
+    fn live_and_assistant_keep_their_own_peaks() {
+        let peaks = StagePeaks::new();
+        peaks.set_enabled(true);
+        peaks.set_live(true);
+        peaks.record(0, 0.5);
+
+        assert_eq!(peaks.take_live(1), vec![0.5]);
+        assert_eq!(peaks.take(1), vec![0.5]);
+
+        // The assistant finishing leaves the bars running.
+        peaks.set_enabled(false);
+        peaks.record(0, 0.25);
+        assert_eq!(peaks.take(1), vec![0.0]);
+        assert_eq!(peaks.take_live(1), vec![0.25]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Output compensation that keeps a drive control from also being a volume
+//! control. Each stage bakes in a polynomial per clipper/amp type, fitted by
+//! its ignored `calibrate_auto_level` test:
+//!
+//! ```text
+//! cargo test -p rustortion-core calibrate_auto_level -- --ignored --nocapture
+//! ```
+
+use crate::amp::stages::common::db_to_lin;
+
+/// Coefficients of the compensation curve in dB, constant term first.
+pub type AutoLevelCurve = [f32; 5];
+
+/// Linear output gain for `x` on `curve`.
+pub fn compensation(curve: &AutoLevelCurve, x: f32) -> f32 {
+    let db = curve
+        .iter()
+        .rev()
+        .fold(0.0_f32, |acc, &c| acc.mul_add(x, c));
+    db_to_lin(db)
+}
+
+#[cfg(test)]
+pub mod calibration {
+    use crate::amp::stages::Stage;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+    /// Calibration signal: a −12 dBFS sine at 440 Hz.
+    const LEVEL_DBFS: f32 = -12.0;
+    const FREQUENCY: f32 = 440.0;
+
+    /// RMS output level in dB for the calibration sine, after half a second
+    /// for the DC blockers to settle.
+    pub fn sine_level_db(stage: &mut dyn Stage) -> f32 {
+        let amplitude = 10f32.powf(LEVEL_DBFS / 20.0);
+        let step = std::f32::consts::TAU * FREQUENCY / SAMPLE_RATE;
+        let settle = (SAMPLE_RATE * 0.5) as usize;
+        let measure = SAMPLE_RATE as usize;
+
+        let mut sum = 0.0_f64;
+        for i in 0..settle + measure {
+            let out = stage.process((i as f32 * step).sin() * amplitude);
+            if i >= settle {
+                sum += f64::from(out) * f64::from(out);
+            }
+        }
+        (10.0 * (sum / measure as f64).log10()) as f32
+    }
+
+    /// Least-squares fit of a quartic through `(xs, ys)`.
+    pub fn fit_quartic(xs: &[f32], ys: &[f32]) -> [f32; 5] {
+        const N: usize = 5;
+        let mut m = [[0.0_f64; N + 1]; N];
+        for (&x, &y) in xs.iter().zip(ys) {
+            let (x, y) = (f64::from(x), f64::from(y));
+            for (r, row) in m.iter_mut().enumerate() {
+                for (c, cell) in row.iter_mut().take(N).enumerate() {
+                    *cell += x.powi((r + c) as i32);
+                }
+                row[N] += y * x.powi(r as i32);
+            }
+        }
+        // Gauss-Jordan with partial pivoting.
+        for i in 0..N {
+            let pivot = (i..N)
+                .max_by(|&a, &b| m[a][i].abs().total_cmp(&m[b][i].abs()))
+                .unwrap_or(i);
+            m.swap(i, pivot);
+            let pivot_row = m[i];
+            for (r, row) in m.iter_mut().enumerate() {
+                if r != i {
+                    let f = row[i] / pivot_row[i];
+                    for (cell, p) in row.iter_mut().zip(pivot_row).skip(i) {
+                        *cell -= f * p;
+                    }
+                }
+            }
+        }
+        std::array::from_fn(|i| (m[i][N] / m[i][i]) as f32)
+    }
+
+    /// Fit the compensation for levels measured at `xs`, relative to the level
+    /// at `reference` so the default setting sounds the same with it on or off.
+    pub fn fit_compensation(xs: &[f32], levels_db: &[f32], reference_db: f32) -> [f32; 5] {
+        let compensation: Vec<f32> = levels_db.iter().map(|l| reference_db - l).collect();
+        fit_quartic(xs, &compensation)
+    }
+
+    /// Largest minus smallest of `levels_db`.
+    pub fn spread_db(levels_db: &[f32]) -> f32 {
+        let max = levels_db.iter().copied().fold(f32::MIN, f32::max);
+        let min = levels_db.iter().copied().fold(f32::MAX, f32::min);
+        max - min
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::calibration::fit_quartic;
+    use super::*;
+
+    #[test]
+    fn flat_curve_is_unity_gain() {
+        assert_eq!(compensation(&[0.0; 5], 3.0), 1.0);
+    }
+
+    #[test]
+    fn quartic_fit_recovers_its_coefficients() {
+        let curve = [1.0, -2.0, 0.5, 0.25, -0.125];
+        let xs: Vec<f32> = (0..20).map(|i| i as f32 * 0.2).collect();
+        let ys: Vec<f32> = xs
+            .iter()
+            .map(|&x| {
+                curve
+                    .iter()
+                    .rev()
+                    .fold(0.0_f32, |acc, &c| acc.mul_add(x, c))
+            })
+            .collect();
+        let fitted = fit_quartic(&xs, &ys);
+        for (f, c) in fitted.iter().zip(curve) {
+            assert!(
+                (f - c).abs() < 1e-2,
+                "fitted {fitted:?}, expected {curve:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::Result;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::Stage;
+use crate::amp::stages::common::db_to_lin;
+use crate::amp::stages::trim::CommonStageParams;
+use crate::capture::registry;
+use crate::capture::{CaptureArchitecture, CaptureProfile, Waveshaper};
+use crate::ir::convolver::Convolver;
+
+/// Valid range for the input/output gain knobs, matching the UI and plugin params.
+const GAIN_DB_MIN: f32 = -24.0;
+const GAIN_DB_MAX: f32 = 24.0;
+
+/// The loaded half of a capture stage: the profile's waveshaper and its
+/// kernel, resampled to the engine rate and loaded into a convolver.
+struct CaptureModel {
+    architecture: CaptureArchitecture,
+    waveshaper: Waveshaper,
+    convolver: Convolver,
+}
+
+/// Runs a "linear + static nonlinearity" capture profile: a waveshaper lookup
+/// table and a linear kernel, in the order the profile's architecture says.
+///
+/// With no capture loaded the stage is a passthrough. Input/output gain are
+/// applied around the capture and the wet output is blended with the dry
+/// signal via `mix`.
+pub struct CaptureStage {
+    model: Option<CaptureModel>,
+    input_gain: f32,
+    output_gain: f32,
+    mix: f32,
+    /// Dry signal during block processing, grown on the first block of a given
+    /// size like the NAM stage's; steady-state processing never allocates.
+    dry: Vec<f32>,
+}
+
+impl CaptureStage {
+    const fn passthrough(input_gain: f32, output_gain: f32, mix: f32) -> Self {
+        Self {
+            model: None,
+            input_gain,
+            output_gain,
+            mix,
+            dry: Vec::new(),
+        }
+    }
+
+    fn with_profile(
+        profile: &CaptureProfile,
+        sample_rate: f32,
+        input_gain: f32,
+        output_gain: f32,
+        mix: f32,
+    ) -> Result<Self> {
+        let mut convolver = Convolver::new_two_stage();
+        convolver.set_ir(&profile.kernel_at(sample_rate)?)?;
+
+        Ok(Self {
+            model: Some(CaptureModel {
+                architecture: profile.architecture,
+                waveshaper: profile.waveshaper.clone(),
+                convolver,
+            }),
+            ..Self::passthrough(input_gain, output_gain, mix)
+        })
+    }
+
+    /// True when a capture is loaded and running.
+    #[must_use]
+    pub const fn is_active(&self) -> bool {
+        self.model.is_some()
+    }
+}
+
+impl Stage for CaptureStage {
+    fn process(&mut self, input: f32) -> f32 {
+        let Some(model) = self.model.as_mut() else {
+            return input;
+        };
+        let x = input * self.input_gain;
+        let wet = match model.architecture {
+            CaptureArchitecture::Hammerstein => {
+                model.convolver.process_sample(model.waveshaper.process(x))
+            }
+            CaptureArchitecture::Wiener => {
+                model.waveshaper.process(model.convolver.process_sample(x))
+            }
+        } * self.output_gain;
+        self.mix.mul_add(wet - input, input)
+    }
+
+    fn process_block(&mut self, input: &mut [f32]) {
+        let Some(model) = self.model.as_mut() else {
+            return;
+        };
+
+        if self.dry.len() < input.len() {
+            self.dry.resize(input.len(), 0.0);
+        }
+        let dry = &mut self.dry[..input.len()];
+        for (d, x) in dry.iter_mut().zip(input.iter_mut()) {
+            *d = *x;
+            *x *= self.input_gain;
+        }
+
+        // The waveshaper is memoryless, so it runs as its own pass on either
+        // side of the block convolution.
+        if model.architecture == CaptureArchitecture::Hammerstein {
+            for x in input.iter_mut() {
+                *x = model.waveshaper.process(*x);
+            }
+        }
+        model.convolver.process_block(input);
+        if model.architecture == CaptureArchitecture::Wiener {
+            for x in input.iter_mut() {
+                *x = model.waveshaper.process(*x);
+            }
+        }
+
+        for (x, &d) in input.iter_mut().zip(dry.iter()) {
+            let wet = *x * self.output_gain;
+            *x = self.mix.mul_add(wet - d, d);
+        }
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
+        match name {
+            "input_gain_db" => {
+                if (GAIN_DB_MIN..=GAIN_DB_MAX).contains(&value) {
+                    self.input_gain = db_to_lin(value);
+                    Ok(())
+                } else {
+                    Err("Input gain must be between -24 and 24 dB")
+                }
+            }
+            "output_gain_db" => {
+                if (GAIN_DB_MIN..=GAIN_DB_MAX).contains(&value) {
+                    self.output_gain = db_to_lin(value);
+                    Ok(())
+                } else {
+                    Err("Output gain must be between -24 and 24 dB")
+                }
+            }
+            "mix" => {
+                if (0.0..=1.0).contains(&value) {
+                    self.mix = value;
+                    Ok(())
+                } else {
+                    Err("Mix must be between 0.0 and 1.0")
+                }
+            }
+            _ => Err("Unknown parameter"),
+        }
+    }
+
+    fn get_parameter(&self, name: &str) -> Result<f32, &'static str> {
+        match name {
+            "input_gain_db" => Ok(20.0 * self.input_gain.log10()),
+            "output_gain_db" => Ok(20.0 * self.output_gain.log10()),
+            "mix" => Ok(self.mix),
+            _ => Err("Unknown parameter"),
+        }
+    }
+
+    fn reset(&mut self) {
+        if let Some(model) = self.model.as_mut() {
+            model.convolver.reset();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+
+    /// Hard clip at ±0.5 and a half-gain single-tap kernel, so the two
+    /// architectures give different results for loud input.
+    fn profile(architecture: &str) -> CaptureProfile {
+        CaptureProfile::from_json_str(&format!(
+            r#"{{
+                "architecture": "{architecture}",
+                "waveshaper": {{ "input_range": 0.5, "table": [-0.5, 0.5] }},
+                "kernel": [0.5]
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn passthrough_when_no_capture() {
+        let mut stage = CaptureConfig::default().to_stage(SAMPLE_RATE);
+        assert!(!stage.is_active());
+        for x in [-1.0, 0.0, 0.25, 0.9] {
+            assert_eq!(stage.process(x), x);
+        }
+    }
+
+    #[test]
+    fn missing_capture_is_passthrough() {
+        let config = CaptureConfig {
+            capture: Some("does/not/exist.json".into()),
+            ..CaptureConfig::default()
+        };
+        let mut stage = config.to_stage(SAMPLE_RATE);
+        assert!(!stage.is_active());
+        assert_eq!(stage.process(0.3), 0.3);
+    }
+
+    #[test]
+    fn architecture_sets_processing_order() {
+        let mut hammerstein =
+            CaptureStage::with_profile(&profile("Hammerstein"), SAMPLE_RATE, 1.0, 1.0, 1.0)
+                .unwrap();
+        let mut wiener =
+            CaptureStage::with_profile(&profile("Wiener"), SAMPLE_RATE, 1.0, 1.0, 1.0).unwrap();
+
+        // Clip then halve: 0.8 -> 0.5 -> 0.25. Halve then clip: 0.8 -> 0.4 -> 0.4.
+        assert!((hammerstein.process(0.8) - 0.25).abs() < 1e-5);
+        assert!((wiener.process(0.8) - 0.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn block_matches_per_sample() {
+        let mut per_sample =
+            CaptureStage::with_profile(&profile("Hammerstein"), SAMPLE_RATE, 2.0, 0.5, 0.7)
+                .unwrap();
+        let mut block =
+            CaptureStage::with_profile(&profile("Hammerstein"), SAMPLE_RATE, 2.0, 0.5, 0.7)
+                .unwrap();
+
+        let input: Vec<f32> = (0..256).map(|i| (i as f32 * 0.05).sin() * 0.6).collect();
+        let expected: Vec<f32> = input.iter().map(|&x| per_sample.process(x)).collect();
+        let mut got = input;
+        block.process_block(&mut got);
+
+        for (i, (e, g)) in expected.iter().zip(got.iter()).enumerate() {
+            assert!((e - g).abs() < 1e-5, "mismatch at {i}: {e} vs {g}");
+        }
+    }
+
+    #[test]
+    fn gain_and_mix_round_trip() {
+        let mut stage = CaptureConfig::default().to_stage(SAMPLE_RATE);
+        stage.set_parameter("mix", 0.5).unwrap();
+        assert!((stage.get_parameter("mix").unwrap() - 0.5).abs() < 1e-6);
+        stage.set_parameter("output_gain_db", -6.0).unwrap();
+        assert!((stage.get_parameter("output_gain_db").unwrap() + 6.0).abs() < 1e-3);
+
+        assert!(stage.set_parameter("mix", 2.0).is_err());
+        assert!(stage.set_parameter("input_gain_db", 30.0).is_err());
+    }
+}
+
+// --- Config ---
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CaptureConfig {
+    /// Path of the selected capture relative to the captures directory, or
+    /// `None` for passthrough.
+    #[serde(default)]
+    pub capture: Option<String>,
+    pub input_gain_db: f32,
+    pub output_gain_db: f32,
+    pub mix: f32,
+    #[serde(default)]
+    pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            capture: None,
+            input_gain_db: 0.0,
+            output_gain_db: 0.0,
+            mix: 1.0,
+            bypassed: false,
+            common: CommonStageParams::default(),
+        }
+    }
+}
+
+impl CaptureConfig {
+    /// Build a runnable stage. Resolves the capture from the global registry
+    /// and resamples its kernel here (off the real-time thread). A missing or
+    /// unusable capture falls back to passthrough with a warning.
+    pub fn to_stage(&self, sample_rate: f32) -> CaptureStage {
+        let input_gain = db_to_lin(self.input_gain_db.clamp(GAIN_DB_MIN, GAIN_DB_MAX));
+        let output_gain = db_to_lin(self.output_gain_db.clamp(GAIN_DB_MIN, GAIN_DB_MAX));
+        let mix = self.mix.clamp(0.0, 1.0);
+
+        let Some(name) = self.capture.as_deref() else {
+            return CaptureStage::passthrough(input_gain, output_gain, mix);
+        };
+
+        let profile = match registry::get(name) {
+            Some(Ok(profile)) => profile,
+            Some(Err(reason)) => {
+                warn!("Capture '{name}' can't be used ({reason}); using passthrough");
+                return CaptureStage::passthrough(input_gain, output_gain, mix);
+            }
+            None => {
+                warn!("Capture '{name}' not found in registry; using passthrough");
+                return CaptureStage::passthrough(input_gain, output_gain, mix);
+            }
+        };
+
+        CaptureStage::with_profile(&profile, sample_rate, input_gain, output_gain, mix)
+            .unwrap_or_else(|e| {
+                warn!("Failed to build capture '{name}': {e:#}; using passthrough");
+                CaptureStage::passthrough(input_gain, output_gain, mix)
+            })
+    }
+}
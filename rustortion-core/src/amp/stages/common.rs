@@ -44,6 +44,11 @@ impl DcBlocker {
         self.y_prev = output;
         output
     }
+
+    pub const fn reset(&mut self) {
+        self.x_prev = 0.0;
+        self.y_prev = 0.0;
+    }
 }
 
 /// One-pole low-pass filter.
@@ -67,6 +72,10 @@ impl OnePoleLP {
         self.y_prev = self.coeff.mul_add(input - self.y_prev, self.y_prev);
         self.y_prev
     }
+
+    pub const fn reset(&mut self) {
+        self.y_prev = 0.0;
+    }
 }
 
 /// One-pole envelope follower with configurable attack and release coefficients.
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
 use crate::amp::stages::common::{EnvelopeFollower, calculate_coefficient, db_to_lin};
+use crate::amp::stages::trim::CommonStageParams;
 
 pub struct CompressorStage {
     attack_ms: f32,  // Attack time in milliseconds
@@ -128,6 +129,10 @@ impl Stage for CompressorStage {
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn reset(&mut self) {
+        self.envelope.reset();
+    }
 }
 
 #[cfg(test)]
@@ -322,7 +327,7 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct CompressorConfig {
     pub attack_ms: f32,
     pub release_ms: f32,
@@ -331,6 +336,8 @@ pub struct CompressorConfig {
     pub makeup_db: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for CompressorConfig {
@@ -342,6 +349,7 @@ impl Default for CompressorConfig {
             ratio: 4.0,
             makeup_db: 0.0,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -1,28 +1,155 @@
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
-use crate::amp::stages::common::calculate_coefficient;
+use crate::amp::stages::common::{EnvelopeFollower, calculate_coefficient, db_to_lin};
+use crate::amp::stages::trim::CommonStageParams;
 
 const MAX_DELAY_MS: f32 = 2000.0;
 const MAX_FEEDBACK: f32 = 0.95;
 const SMOOTH_TIME_MS: f32 = 50.0;
 const DENORMAL_THRESHOLD: f32 = 1e-20;
+/// Highest rate the delay line runs at. When oversampling pushes the stage
+/// rate above this, the line stores averaged blocks of input instead, so its
+/// memory doesn't grow with the oversampling factor. Echoes don't need
+/// content above the base rate's Nyquist anyway.
+const MAX_LINE_RATE: f32 = 96_000.0;
+const MAX_DUCK_DEPTH_DB: f32 = 24.0;
+const MIN_DUCK_RELEASE_MS: f32 = 50.0;
+const MAX_DUCK_RELEASE_MS: f32 = 1000.0;
+const DEFAULT_DUCK_RELEASE_MS: f32 = 200.0;
+/// Dry level at and above which the input counts as playing and the echoes
+/// are ducked by the full depth, about -26 dBFS.
+const DUCK_THRESHOLD: f32 = 0.05;
+/// How quickly the echoes duck once playing starts.
+const DUCK_ATTACK_MS: f32 = 5.0;
+
+/// Note length a tempo-synced delay repeats at.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteDivision {
+    Half,
+    DottedQuarter,
+    #[default]
+    Quarter,
+    QuarterTriplet,
+    DottedEighth,
+    Eighth,
+    EighthTriplet,
+    Sixteenth,
+}
+
+impl std::fmt::Display for NoteDivision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Half => write!(f, "1/2"),
+            Self::DottedQuarter => write!(f, "1/4 dotted"),
+            Self::Quarter => write!(f, "1/4"),
+            Self::QuarterTriplet => write!(f, "1/4 triplet"),
+            Self::DottedEighth => write!(f, "1/8 dotted"),
+            Self::Eighth => write!(f, "1/8"),
+            Self::EighthTriplet => write!(f, "1/8 triplet"),
+            Self::Sixteenth => write!(f, "1/16"),
+        }
+    }
+}
+
+impl NoteDivision {
+    /// Longest first. The position in this list is the `"division"` parameter value.
+    pub const ALL: [Self; 8] = [
+        Self::Half,
+        Self::DottedQuarter,
+        Self::Quarter,
+        Self::QuarterTriplet,
+        Self::DottedEighth,
+        Self::Eighth,
+        Self::EighthTriplet,
+        Self::Sixteenth,
+    ];
+
+    /// Length in quarter-note beats.
+    pub const fn beats(self) -> f32 {
+        match self {
+            Self::Half => 2.0,
+            Self::DottedQuarter => 1.5,
+            Self::Quarter => 1.0,
+            Self::QuarterTriplet => 2.0 / 3.0,
+            Self::DottedEighth => 0.75,
+            Self::Eighth => 0.5,
+            Self::EighthTriplet => 1.0 / 3.0,
+            Self::Sixteenth => 0.25,
+        }
+    }
+
+    /// Delay time in ms at `bpm`, clamped to the delay buffer.
+    pub fn delay_ms(self, bpm: f32) -> f32 {
+        (self.beats() * 60_000.0 / bpm).min(MAX_DELAY_MS)
+    }
+
+    /// Position in [`Self::ALL`].
+    pub const fn index(self) -> usize {
+        match self {
+            Self::Half => 0,
+            Self::DottedQuarter => 1,
+            Self::Quarter => 2,
+            Self::QuarterTriplet => 3,
+            Self::DottedEighth => 4,
+            Self::Eighth => 5,
+            Self::EighthTriplet => 6,
+            Self::Sixteenth => 7,
+        }
+    }
+
+    pub const fn from_index(index: usize) -> Option<Self> {
+        if index < Self::ALL.len() {
+            Some(Self::ALL[index])
+        } else {
+            None
+        }
+    }
+}
 
 /// Delay stage for echo and slapback effects.
 ///
 /// Uses a pre-allocated ring buffer (max 2 s) with linear interpolation
 /// for fractional delay lengths and one-pole smoothing on the delay time
-/// parameter to prevent clicks when the time slider is moved.
+/// parameter to prevent clicks when the time slider is moved. Above
+/// [`MAX_LINE_RATE`] the ring buffer runs at a fraction of the stage rate and
+/// its output is interpolated back up.
+///
+/// In sync mode the delay time follows the tempo given to
+/// [`Stage::set_tempo`] as a [`NoteDivision`]; until a tempo arrives it
+/// falls back to `delay_ms`.
+///
+/// With a ducking depth set, an envelope follower on the dry input pulls
+/// the echoes down by up to that many dB while the input is above
+/// [`DUCK_THRESHOLD`], and lets them back up over the ducking release once
+/// it stops, so repeats fill the gaps rather than smearing fast playing.
 pub struct DelayStage {
     delay_ms: f32,
     feedback: f32,
     mix: f32,
+    sync: bool,
+    division: NoteDivision,
+    tempo_bpm: Option<f32>,
     buffer: Vec<f32>,
     write_pos: usize,
     sample_rate: f32,
+    /// Stage samples per delay line sample.
+    decimation: usize,
+    /// Input summed since the line last advanced, and how many samples.
+    accumulated: f32,
+    phase: usize,
+    /// The line's last two outputs, interpolated between at the stage rate.
+    previous: f32,
+    current: f32,
+    /// In delay line samples.
     delay_samples_smoothed: f32,
     delay_samples_target: f32,
     smooth_coeff: f32,
+    duck_depth_db: f32,
+    duck_release_ms: f32,
+    /// How far into the ducking depth the echoes are pulled, 0 to 1.
+    duck_envelope: EnvelopeFollower,
 }
 
 impl DelayStage {
@@ -31,30 +158,91 @@ impl DelayStage {
         let feedback = feedback.clamp(0.0, MAX_FEEDBACK);
         let mix = mix.clamp(0.0, 1.0);
 
-        let max_samples = (MAX_DELAY_MS * 0.001 * sample_rate) as usize + 2;
-        let delay_samples = delay_ms * 0.001 * sample_rate;
-        let smooth_coeff = calculate_coefficient(SMOOTH_TIME_MS, sample_rate);
+        let decimation = (sample_rate / MAX_LINE_RATE).ceil().max(1.0) as usize;
+        let line_rate = sample_rate / decimation as f32;
+        let max_samples = (MAX_DELAY_MS * 0.001 * line_rate) as usize + 2;
+        let smooth_coeff = calculate_coefficient(SMOOTH_TIME_MS, line_rate);
 
-        Self {
+        let mut stage = Self {
             delay_ms,
             feedback,
             mix,
+            sync: false,
+            division: NoteDivision::Quarter,
+            tempo_bpm: None,
             buffer: vec![0.0; max_samples],
             write_pos: 0,
             sample_rate,
-            delay_samples_smoothed: delay_samples,
-            delay_samples_target: delay_samples,
+            decimation,
+            accumulated: 0.0,
+            phase: 0,
+            previous: 0.0,
+            current: 0.0,
+            delay_samples_smoothed: 0.0,
+            delay_samples_target: 0.0,
             smooth_coeff,
+            duck_depth_db: 0.0,
+            duck_release_ms: DEFAULT_DUCK_RELEASE_MS,
+            duck_envelope: EnvelopeFollower::from_ms(
+                DUCK_ATTACK_MS,
+                DEFAULT_DUCK_RELEASE_MS,
+                sample_rate,
+            ),
+        };
+        stage.update_delay_target();
+        stage.delay_samples_smoothed = stage.delay_samples_target;
+        stage
+    }
+
+    /// Start in sync mode at `division`. The delay line stays at `delay_ms`
+    /// until the chain passes in a tempo.
+    #[must_use]
+    pub const fn with_sync(mut self, sync: bool, division: NoteDivision) -> Self {
+        self.sync = sync;
+        self.division = division;
+        self
+    }
+
+    /// Duck the echoes by up to `depth_db` while playing, recovering over
+    /// `release_ms`. Both are clamped to their ranges.
+    #[must_use]
+    pub fn with_ducking(mut self, depth_db: f32, release_ms: f32) -> Self {
+        self.duck_depth_db = depth_db.clamp(0.0, MAX_DUCK_DEPTH_DB);
+        self.set_duck_release(release_ms.clamp(MIN_DUCK_RELEASE_MS, MAX_DUCK_RELEASE_MS));
+        self
+    }
+
+    fn set_duck_release(&mut self, release_ms: f32) {
+        self.duck_release_ms = release_ms;
+        self.duck_envelope
+            .set_release_coeff(calculate_coefficient(release_ms, self.sample_rate));
+    }
+
+    /// Gain for the echoes given the dry `input`, stepping the follower.
+    fn duck_gain(&mut self, input: f32) -> f32 {
+        let playing = (input.abs() / DUCK_THRESHOLD).min(1.0);
+        let amount = self.duck_envelope.process(playing);
+        db_to_lin(-self.duck_depth_db * amount)
+    }
+
+    /// The delay time currently being aimed at: the synced note length when
+    /// syncing to a known tempo, otherwise `delay_ms`.
+    fn effective_delay_ms(&self) -> f32 {
+        match self.tempo_bpm {
+            Some(bpm) if self.sync => self.division.delay_ms(bpm),
+            _ => self.delay_ms,
         }
     }
 
     fn update_delay_target(&mut self) {
-        self.delay_samples_target = self.delay_ms * 0.001 * self.sample_rate;
+        let line_rate = self.sample_rate / self.decimation as f32;
+        // Interpolating between line outputs lags by one line sample.
+        let lag = if self.decimation > 1 { 1.0 } else { 0.0 };
+        self.delay_samples_target = self.effective_delay_ms().mul_add(0.001 * line_rate, -lag);
     }
-}
 
-impl Stage for DelayStage {
-    fn process(&mut self, input: f32) -> f32 {
+    /// Advance the delay line by one of its samples, returning what it read.
+    fn tick(&mut self, input: f32) -> f32 {
         // Smooth delay time to prevent clicks
         self.delay_samples_smoothed = self.smooth_coeff.mul_add(
             self.delay_samples_smoothed,
@@ -87,6 +275,46 @@ impl Stage for DelayStage {
         // Advance write position
         self.write_pos = (self.write_pos + 1) % buf_len;
 
+        delayed
+    }
+}
+
+/// Sync on/off goes through `set_parameter` as 0.0/1.0.
+fn switch_value(value: f32) -> Result<bool, &'static str> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value >= 0.5)
+    } else {
+        Err("Sync must be 0.0 or 1.0")
+    }
+}
+
+const fn switch_param(on: bool) -> f32 {
+    if on { 1.0 } else { 0.0 }
+}
+
+impl Stage for DelayStage {
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = if self.decimation == 1 {
+            self.tick(input)
+        } else {
+            self.accumulated += input;
+            self.phase += 1;
+            if self.phase == self.decimation {
+                let block = self.accumulated / self.decimation as f32;
+                self.accumulated = 0.0;
+                self.phase = 0;
+                self.previous = self.current;
+                self.current = self.tick(block);
+            }
+            let t = self.phase as f32 / self.decimation as f32;
+            t.mul_add(self.current - self.previous, self.previous)
+        };
+        let delayed = if self.duck_depth_db > 0.0 {
+            delayed * self.duck_gain(input)
+        } else {
+            delayed
+        };
+
         // Dry/wet mix
         (1.0 - self.mix).mul_add(input, self.mix * delayed)
     }
@@ -118,6 +346,36 @@ impl Stage for DelayStage {
                     Err("Mix must be between 0.0 and 1.0")
                 }
             }
+            "duck_depth" => {
+                if (0.0..=MAX_DUCK_DEPTH_DB).contains(&value) {
+                    self.duck_depth_db = value;
+                    Ok(())
+                } else {
+                    Err("Duck depth must be between 0 dB and 24 dB")
+                }
+            }
+            "duck_release" => {
+                if (MIN_DUCK_RELEASE_MS..=MAX_DUCK_RELEASE_MS).contains(&value) {
+                    self.set_duck_release(value);
+                    Ok(())
+                } else {
+                    Err("Duck release must be between 50 ms and 1000 ms")
+                }
+            }
+            "sync" => {
+                self.sync = switch_value(value)?;
+                self.update_delay_target();
+                Ok(())
+            }
+            "division" => {
+                let division = (value >= 0.0)
+                    .then(|| NoteDivision::from_index(value.round() as usize))
+                    .flatten()
+                    .ok_or("Division must be an index into NoteDivision::ALL")?;
+                self.division = division;
+                self.update_delay_target();
+                Ok(())
+            }
             _ => Err("Unknown parameter"),
         }
     }
@@ -127,9 +385,31 @@ impl Stage for DelayStage {
             "delay_time" => Ok(self.delay_ms),
             "feedback" => Ok(self.feedback),
             "mix" => Ok(self.mix),
+            "duck_depth" => Ok(self.duck_depth_db),
+            "duck_release" => Ok(self.duck_release_ms),
+            "sync" => Ok(switch_param(self.sync)),
+            "division" => Ok(self.division.index() as f32),
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn set_tempo(&mut self, bpm: f32) {
+        if bpm > 0.0 && bpm.is_finite() {
+            self.tempo_bpm = Some(bpm);
+            self.update_delay_target();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.write_pos = 0;
+        self.accumulated = 0.0;
+        self.phase = 0;
+        self.previous = 0.0;
+        self.current = 0.0;
+        self.delay_samples_smoothed = self.delay_samples_target;
+        self.duck_envelope.reset();
+    }
 }
 
 #[cfg(test)]
@@ -336,8 +616,12 @@ mod tests {
             delay.process(0.0);
         }
 
-        // Send impulse and verify it comes back
-        let _ = delay.process(1.0);
+        // Send a 1 ms pulse and verify it comes back. A single-sample impulse
+        // at this rate is averaged into one line sample and mostly lost.
+        let pulse = (0.001 * high_rate) as usize;
+        for _ in 0..pulse {
+            delay.process(1.0);
+        }
         let delay_samples = (delay_ms * 0.001 * high_rate) as usize;
         let mut max_out: f32 = 0.0;
         for _ in 0..delay_samples + 100 {
@@ -346,10 +630,197 @@ mod tests {
         }
         assert!(
             max_out > 0.9,
-            "Impulse should survive at high sample rate, got max {max_out}"
+            "Pulse should survive at high sample rate, got max {max_out}"
         );
     }
 
+    #[test]
+    fn oversampled_line_memory_is_bounded() {
+        let base = DelayStage::new(300.0, 0.3, 0.5, 96_000.0);
+        for factor in [2.0, 4.0, 8.0, 16.0] {
+            let oversampled = DelayStage::new(300.0, 0.3, 0.5, 48_000.0 * factor);
+            assert!(
+                oversampled.buffer.len() <= base.buffer.len(),
+                "{factor}x buffer {} exceeds {}",
+                oversampled.buffer.len(),
+                base.buffer.len()
+            );
+        }
+    }
+
+    #[test]
+    fn oversampled_echo_arrives_on_time() {
+        let sample_rate = 48_000.0 * 16.0;
+        let delay_ms = 50.0;
+        let mut delay = DelayStage::new(delay_ms, 0.0, 1.0, sample_rate);
+        assert!(delay.decimation > 1);
+
+        // A step: the echo crosses half level at the delay time.
+        let mut crossing = None;
+        for i in 0..(sample_rate * 0.1) as usize {
+            if delay.process(1.0) >= 0.5 && crossing.is_none() {
+                crossing = Some(i);
+            }
+        }
+        let expected = delay_ms * 0.001 * sample_rate;
+        let crossing = crossing.expect("echo never arrived") as f32;
+        assert!(
+            (crossing - expected).abs() <= delay.decimation as f32,
+            "echo at sample {crossing}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn synced_delay_follows_tempo() {
+        let mut delay =
+            DelayStage::new(300.0, 0.0, 1.0, SAMPLE_RATE).with_sync(true, NoteDivision::Quarter);
+
+        delay.set_tempo(120.0);
+        assert!((delay.effective_delay_ms() - 500.0).abs() < 1e-3);
+
+        delay.set_tempo(150.0);
+        assert!((delay.effective_delay_ms() - 400.0).abs() < 1e-3);
+        assert!((delay.delay_samples_target - 0.4 * SAMPLE_RATE).abs() < 1e-2);
+
+        delay.set_parameter("division", 4.0).unwrap();
+        assert_eq!(delay.division, NoteDivision::DottedEighth);
+        assert!((delay.effective_delay_ms() - 300.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn synced_delay_without_tempo_uses_delay_time() {
+        let mut delay =
+            DelayStage::new(300.0, 0.0, 1.0, SAMPLE_RATE).with_sync(true, NoteDivision::Eighth);
+        assert!((delay.effective_delay_ms() - 300.0).abs() < 1e-6);
+
+        // Bogus host tempos are ignored.
+        delay.set_tempo(0.0);
+        delay.set_tempo(f32::NAN);
+        assert!((delay.effective_delay_ms() - 300.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn unsynced_delay_ignores_tempo() {
+        let mut delay = DelayStage::new(300.0, 0.0, 1.0, SAMPLE_RATE);
+        delay.set_tempo(90.0);
+        assert!((delay.effective_delay_ms() - 300.0).abs() < 1e-6);
+
+        delay.set_parameter("sync", 1.0).unwrap();
+        assert!((delay.effective_delay_ms() - 666.666_7).abs() < 1e-2);
+        delay.set_parameter("sync", 0.0).unwrap();
+        assert!((delay.effective_delay_ms() - 300.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn synced_delay_clamps_to_buffer() {
+        let mut delay =
+            DelayStage::new(300.0, 0.0, 1.0, SAMPLE_RATE).with_sync(true, NoteDivision::Half);
+        delay.set_tempo(40.0);
+        assert!((delay.effective_delay_ms() - MAX_DELAY_MS).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sync_parameter_validation() {
+        let mut delay = DelayStage::new(300.0, 0.3, 0.3, SAMPLE_RATE);
+        assert!(delay.set_parameter("sync", 2.0).is_err());
+        assert!(delay.set_parameter("division", -1.0).is_err());
+        assert!(delay.set_parameter("division", 8.0).is_err());
+        assert!(delay.set_parameter("division", 7.0).is_ok());
+        assert!((delay.get_parameter("division").unwrap() - 7.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn division_index_matches_all() {
+        for (i, division) in NoteDivision::ALL.into_iter().enumerate() {
+            assert_eq!(division.index(), i);
+            assert_eq!(NoteDivision::from_index(i), Some(division));
+        }
+        assert_eq!(NoteDivision::from_index(NoteDivision::ALL.len()), None);
+    }
+
+    #[test]
+    fn config_without_sync_fields_loads_unsynced() {
+        let cfg: DelayConfig =
+            serde_json::from_str(r#"{"delay_ms":250.0,"feedback":0.4,"mix":0.5}"#).unwrap();
+        assert!(!cfg.sync);
+        assert_eq!(cfg.division, NoteDivision::Quarter);
+
+        let mut stage = cfg.to_stage(SAMPLE_RATE);
+        stage.set_tempo(120.0);
+        assert!((stage.effective_delay_ms() - 250.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn config_round_trips_sync() {
+        let cfg = DelayConfig {
+            sync: true,
+            division: NoteDivision::DottedEighth,
+            ..DelayConfig::default()
+        };
+        let json = serde_json::to_string(&cfg).unwrap();
+        let restored: DelayConfig = serde_json::from_str(&json).unwrap();
+        assert!(restored.sync);
+        assert_eq!(restored.division, NoteDivision::DottedEighth);
+    }
+
+    #[test]
+    fn ducking_keeps_echoes_down_while_playing() {
+        let sample_rate = 48_000.0;
+        // Long enough that the echo of the held note is still playing 500 ms
+        // after it stops.
+        let mut delay =
+            DelayStage::new(1000.0, 0.0, 1.0, sample_rate).with_ducking(MAX_DUCK_DEPTH_DB, 200.0);
+        let ms = |ms: f32| (ms * 0.001 * sample_rate) as usize;
+        let note = |i: usize| 0.5 * (std::f32::consts::TAU * 220.0 * i as f32 / sample_rate).sin();
+        let rms = |samples: &[f32]| {
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+
+        // Hold the note for 1.5 s, past the first echo's arrival.
+        let held: Vec<f32> = (0..ms(1500.0)).map(|i| delay.process(note(i))).collect();
+        let ducked = rms(&held[ms(1200.0)..]);
+
+        let after: Vec<f32> = (0..ms(520.0)).map(|_| delay.process(0.0)).collect();
+        let bloomed = rms(&after[ms(480.0)..]);
+
+        let difference_db = 20.0 * (bloomed / ducked).log10();
+        assert!(
+            difference_db >= 20.0,
+            "echoes only {difference_db:.1} dB louder after playing stops"
+        );
+    }
+
+    #[test]
+    fn ducking_off_leaves_echoes_alone() {
+        let mut plain = DelayStage::new(100.0, 0.3, 0.5, SAMPLE_RATE);
+        let mut ducking = DelayStage::new(100.0, 0.3, 0.5, SAMPLE_RATE).with_ducking(0.0, 500.0);
+        for i in 0..SAMPLE_RATE as usize {
+            let input = (i as f32 * 0.01).sin();
+            assert_eq!(plain.process(input), ducking.process(input));
+        }
+    }
+
+    #[test]
+    fn duck_parameter_validation() {
+        let mut delay = DelayStage::new(300.0, 0.3, 0.3, SAMPLE_RATE);
+        assert!(delay.set_parameter("duck_depth", -1.0).is_err());
+        assert!(delay.set_parameter("duck_depth", 24.5).is_err());
+        assert!(delay.set_parameter("duck_depth", 12.0).is_ok());
+        assert!(delay.set_parameter("duck_release", 49.0).is_err());
+        assert!(delay.set_parameter("duck_release", 1001.0).is_err());
+        assert!(delay.set_parameter("duck_release", 400.0).is_ok());
+        assert!((delay.get_parameter("duck_depth").unwrap() - 12.0).abs() < 1e-6);
+        assert!((delay.get_parameter("duck_release").unwrap() - 400.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn config_without_duck_fields_does_not_duck() {
+        let cfg: DelayConfig =
+            serde_json::from_str(r#"{"delay_ms":250.0,"feedback":0.4,"mix":0.5}"#).unwrap();
+        assert_eq!(cfg.duck_depth_db, 0.0);
+        assert_eq!(cfg.duck_release_ms, DEFAULT_DUCK_RELEASE_MS);
+    }
+
     #[test]
     fn parameter_change_mid_processing() {
         let mut delay = DelayStage::new(500.0, 0.0, 1.0, SAMPLE_RATE);
@@ -373,13 +844,25 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct DelayConfig {
     pub delay_ms: f32,
     pub feedback: f32,
     pub mix: f32,
+    /// Follow the host/metronome tempo at `division` instead of `delay_ms`.
+    #[serde(default)]
+    pub sync: bool,
+    #[serde(default)]
+    pub division: NoteDivision,
+    /// How far the echoes duck while playing, in dB; 0 turns ducking off.
+    #[serde(default)]
+    pub duck_depth_db: f32,
+    #[serde(default = "default_duck_release_ms")]
+    pub duck_release_ms: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for DelayConfig {
@@ -388,7 +871,12 @@ impl Default for DelayConfig {
             delay_ms: 300.0,
             feedback: 0.3,
             mix: 0.5,
+            sync: false,
+            division: NoteDivision::Quarter,
+            duck_depth_db: 0.0,
+            duck_release_ms: DEFAULT_DUCK_RELEASE_MS,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -396,5 +884,11 @@ impl Default for DelayConfig {
 impl DelayConfig {
     pub fn to_stage(&self, sample_rate: f32) -> DelayStage {
         DelayStage::new(self.delay_ms, self.feedback, self.mix, sample_rate)
+            .with_sync(self.sync, self.division)
+            .with_ducking(self.duck_depth_db, self.duck_release_ms)
     }
 }
+
+const fn default_duck_release_ms() -> f32 {
+    DEFAULT_DUCK_RELEASE_MS
+}
@@ -3,12 +3,18 @@ use std::f64::consts::PI;
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
+use crate::amp::stages::trim::CommonStageParams;
 
 pub const NUM_BANDS: usize = 16;
 pub const BAND_FREQS: [f64; NUM_BANDS] = [
     25.0, 40.0, 63.0, 100.0, 160.0, 250.0, 400.0, 630.0, 1000.0, 1600.0, 2500.0, 4000.0, 6300.0,
     10000.0, 16000.0, 20000.0,
 ];
+/// Parameter name of each band, as `set_parameter` knows it.
+pub const BAND_NAMES: [&str; NUM_BANDS] = [
+    "band_0", "band_1", "band_2", "band_3", "band_4", "band_5", "band_6", "band_7", "band_8",
+    "band_9", "band_10", "band_11", "band_12", "band_13", "band_14", "band_15",
+];
 pub const MIN_GAIN_DB: f32 = -12.0;
 pub const MAX_GAIN_DB: f32 = 12.0;
 const DENORMAL_THRESHOLD: f64 = 1e-20;
@@ -118,6 +124,27 @@ impl Biquad {
         self.a2 = a2 * inv_a0;
     }
 
+    /// Magnitude response at `freq`, in dB.
+    fn magnitude_db(&self, freq: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * PI * freq / sample_rate;
+        let (sin1, cos1) = w.sin_cos();
+        let (sin2, cos2) = (2.0 * w).sin_cos();
+        let num_re = self.b2.mul_add(cos2, self.b1.mul_add(cos1, self.b0));
+        let num_im = -self.b2.mul_add(sin2, self.b1 * sin1);
+        let den_re = self.a2.mul_add(cos2, self.a1.mul_add(cos1, 1.0));
+        let den_im = -self.a2.mul_add(sin2, self.a1 * sin1);
+        let num = num_re.mul_add(num_re, num_im * num_im);
+        let den = den_re.mul_add(den_re, den_im * den_im);
+        10.0 * (num / den).log10()
+    }
+
+    const fn reset(&mut self) {
+        self.x1 = 0.0;
+        self.x2 = 0.0;
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+    }
+
     /// Process a single sample through the DF1 difference equation.
     #[inline]
     fn process(&mut self, input: f64) -> f64 {
@@ -172,6 +199,21 @@ impl EqStage {
     }
 }
 
+/// Steady-state response of an EQ set to `gains_db` at `freq`, in dB, with
+/// the overlap between neighbouring bands included.
+pub fn response_db(gains_db: &[f32; NUM_BANDS], freq: f64, sample_rate: f64) -> f64 {
+    gains_db
+        .iter()
+        .zip(BAND_FREQS)
+        .map(|(&gain, band)| {
+            let mut biquad = Biquad::new();
+            let gain = f64::from(gain.clamp(MIN_GAIN_DB, MAX_GAIN_DB));
+            biquad.set_peaking_eq(band, gain, BANDWIDTH, sample_rate);
+            biquad.magnitude_db(freq, sample_rate)
+        })
+        .sum()
+}
+
 impl Stage for EqStage {
     fn process(&mut self, input: f32) -> f32 {
         let mut sample = f64::from(input);
@@ -210,6 +252,12 @@ impl Stage for EqStage {
         }
         Ok(self.gains_db[idx])
     }
+
+    fn reset(&mut self) {
+        for biquad in &mut self.biquads {
+            biquad.reset();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -290,6 +338,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_peaks_at_the_boosted_band() {
+        let sr = f64::from(SAMPLE_RATE);
+        assert!(response_db(&flat_gains(), 1000.0, sr).abs() < 1e-9);
+
+        let mut gains = flat_gains();
+        gains[8] = 6.0;
+        assert!((response_db(&gains, 1000.0, sr) - 6.0).abs() < 0.01);
+        assert!(response_db(&gains, 100.0, sr).abs() < 0.1);
+    }
+
     #[test]
     fn parameter_validation() {
         let mut eq = EqStage::new(flat_gains(), SAMPLE_RATE);
@@ -481,11 +540,13 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct EqConfig {
     pub gains: [f32; NUM_BANDS],
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for EqConfig {
@@ -493,6 +554,7 @@ impl Default for EqConfig {
         Self {
             gains: [0.0; NUM_BANDS],
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -31,6 +31,13 @@ impl FilterStage {
     /// Minimum cutoff frequency to avoid division-by-zero in the RC calculation.
     /// 0.1 Hz makes a highpass effectively a passthrough.
     const MIN_CUTOFF_HZ: f32 = 0.1;
+    const MAX_CUTOFF_HZ: f32 = 20_000.0;
+
+    /// Input filters run at the base rate, before oversampling, so cutoffs
+    /// are kept below its Nyquist rather than whatever rate the chain runs at.
+    fn max_cutoff(sample_rate: f32) -> f32 {
+        Self::MAX_CUTOFF_HZ.min(sample_rate * 0.499)
+    }
 
     fn compute_alpha(filter_type: FilterType, cutoff: f32, sample_rate: f32) -> f32 {
         let rc = 1.0 / (2.0 * PI * cutoff.max(Self::MIN_CUTOFF_HZ));
@@ -42,6 +49,7 @@ impl FilterStage {
     }
 
     pub fn new(filter_type: FilterType, cutoff: f32, sample_rate: f32) -> Self {
+        let cutoff = cutoff.clamp(0.0, Self::max_cutoff(sample_rate));
         let alpha = Self::compute_alpha(filter_type, cutoff, sample_rate);
 
         Self {
@@ -84,12 +92,12 @@ impl Stage for FilterStage {
     fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
         match name {
             "cutoff" => {
-                if (0.0..=20000.0).contains(&value) {
+                if (0.0..=Self::max_cutoff(self.sample_rate)).contains(&value) {
                     self.cutoff = value;
                     self.update_coefficients();
                     Ok(())
                 } else {
-                    Err("Cutoff must be between 0Hz and 20kHz")
+                    Err("Cutoff must be between 0Hz and 20kHz, below half the sample rate")
                 }
             }
             _ => Err("Unknown parameter name"),
@@ -102,6 +110,11 @@ impl Stage for FilterStage {
             _ => Err("Unknown parameter name"),
         }
     }
+
+    fn reset(&mut self) {
+        self.prev_input = 0.0;
+        self.prev_output = 0.0;
+    }
 }
 
 #[cfg(test)]
@@ -170,4 +183,16 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn cutoff_stays_below_nyquist() {
+        let mut lp = FilterStage::new(FilterType::Lowpass, 20_000.0, 32_000.0);
+        assert!(lp.get_parameter("cutoff").unwrap() < 16_000.0);
+        assert!(lp.set_parameter("cutoff", 16_000.0).is_err());
+        assert!(lp.set_parameter("cutoff", 12_000.0).is_ok());
+
+        let mut lp = FilterStage::new(FilterType::Lowpass, 20_000.0, 44_100.0);
+        assert_eq!(lp.get_parameter("cutoff").unwrap(), 20_000.0);
+        assert!(lp.set_parameter("cutoff", 20_001.0).is_err());
+    }
 }
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::amp::chain::AmplifierChain;
+use crate::amp::stages::Stage;
+use crate::amp::stages::trim::CommonStageParams;
+use crate::preset::StageConfig;
+
+/// Most stages a group holds. Its chain is built to size off the RT thread
+/// and never grows in place, so this only keeps the card manageable.
+pub const MAX_GROUP_STAGES: usize = 16;
+
+/// A reusable block of stages run in order as one stage.
+pub struct GroupStage {
+    chain: AmplifierChain,
+}
+
+impl GroupStage {
+    pub const fn new(chain: AmplifierChain) -> Self {
+        Self { chain }
+    }
+}
+
+impl Stage for GroupStage {
+    fn process(&mut self, input: f32) -> f32 {
+        self.chain.process(input)
+    }
+
+    fn process_block(&mut self, input: &mut [f32]) {
+        self.chain.process_block(input);
+    }
+
+    fn set_parameter(&mut self, _name: &str, _value: f32) -> Result<(), &'static str> {
+        Err("Groups have no parameters of their own")
+    }
+
+    fn get_parameter(&self, _name: &str) -> Result<f32, &'static str> {
+        Err("Groups have no parameters of their own")
+    }
+
+    fn set_child_parameter(
+        &mut self,
+        child: usize,
+        name: &str,
+        value: f32,
+    ) -> Result<(), &'static str> {
+        self.chain
+            .set_parameter(child, name, value)
+            .unwrap_or(Err("Unknown group child"))
+    }
+
+    fn set_tempo(&mut self, bpm: f32) {
+        self.chain.set_tempo(bpm);
+    }
+
+    fn reset(&mut self) {
+        self.chain.reset();
+    }
+
+    fn is_power_amp(&self) -> bool {
+        self.chain.has_power_amp()
+    }
+}
+
+// --- Config ---
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GroupConfig {
+    pub stages: Vec<StageConfig>,
+    #[serde(default)]
+    pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
+    /// Which children are collapsed in the GUI. Not saved.
+    #[serde(skip)]
+    pub collapsed: Vec<bool>,
+}
+
+// `collapsed` is view state: folding a child must not count as an edit.
+impl PartialEq for GroupConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.stages == other.stages
+            && self.bypassed == other.bypassed
+            && self.common == other.common
+    }
+}
+
+impl GroupConfig {
+    pub fn to_stage(&self, sample_rate: f32) -> GroupStage {
+        let mut chain = AmplifierChain::with_capacity(self.stages.len());
+        for (idx, cfg) in self.stages.iter().enumerate() {
+            chain.add_stage(cfg.to_runtime(sample_rate));
+            if cfg.bypassed() {
+                chain.set_bypassed(idx, true);
+            }
+        }
+        GroupStage::new(chain)
+    }
+
+    pub fn is_child_collapsed(&self, child: usize) -> bool {
+        self.collapsed.get(child).copied().unwrap_or(false)
+    }
+
+    pub fn toggle_child_collapsed(&mut self, child: usize) {
+        if self.collapsed.len() < self.stages.len() {
+            self.collapsed.resize(self.stages.len(), false);
+        }
+        if let Some(c) = self.collapsed.get_mut(child) {
+            *c = !*c;
+        }
+    }
+
+    /// Splice any group nested inside this one into its place. Groups nest
+    /// one level deep; a hand-edited preset may go further.
+    pub fn flatten_nested(&mut self) {
+        if !self
+            .stages
+            .iter()
+            .any(|s| matches!(s, StageConfig::Group(_)))
+        {
+            return;
+        }
+        let mut flat = Vec::with_capacity(self.stages.len());
+        for stage in self.stages.drain(..) {
+            match stage {
+                StageConfig::Group(mut inner) => {
+                    inner.flatten_nested();
+                    flat.extend(inner.stages);
+                }
+                other => flat.push(other),
+            }
+        }
+        self.stages = flat;
+        self.collapsed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::level::LevelConfig;
+
+    fn level(gain: f32, bypassed: bool) -> StageConfig {
+        StageConfig::Level(LevelConfig {
+            gain,
+            bypassed,
+            ..LevelConfig::default()
+        })
+    }
+
+    #[test]
+    fn group_runs_children_in_order_and_skips_bypassed() {
+        let cfg = GroupConfig {
+            stages: vec![level(0.5, false), level(2.0, true), level(0.5, false)],
+            ..GroupConfig::default()
+        };
+        let mut stage = cfg.to_stage(48_000.0);
+        assert!((stage.process(1.0) - 0.25).abs() < 1e-6);
+
+        let mut block = [1.0, -1.0];
+        stage.process_block(&mut block);
+        assert!((block[0] - 0.25).abs() < 1e-6);
+        assert!((block[1] + 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn child_parameters_reach_the_inner_chain() {
+        let cfg = GroupConfig {
+            stages: vec![level(1.0, false)],
+            ..GroupConfig::default()
+        };
+        let mut stage = cfg.to_stage(48_000.0);
+        stage.set_child_parameter(0, "gain", 0.5).unwrap();
+        assert!((stage.process(1.0) - 0.5).abs() < 1e-6);
+        assert!(stage.set_child_parameter(1, "gain", 0.5).is_err());
+        assert!(stage.set_parameter("gain", 0.5).is_err());
+    }
+
+    #[test]
+    fn nested_groups_are_flattened() {
+        let mut cfg = GroupConfig {
+            stages: vec![
+                level(1.0, false),
+                StageConfig::Group(GroupConfig {
+                    stages: vec![level(0.5, false), level(0.25, false)],
+                    ..GroupConfig::default()
+                }),
+            ],
+            ..GroupConfig::default()
+        };
+        cfg.flatten_nested();
+        assert_eq!(
+            cfg.stages,
+            vec![level(1.0, false), level(0.5, false), level(0.25, false)]
+        );
+    }
+
+    #[test]
+    fn collapse_state_is_not_an_edit() {
+        let a = GroupConfig {
+            stages: vec![level(1.0, false)],
+            ..GroupConfig::default()
+        };
+        let mut b = a.clone();
+        b.toggle_child_collapsed(0);
+        assert!(b.is_child_collapsed(0));
+        assert_eq!(a, b);
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
+use crate::amp::stages::trim::CommonStageParams;
 
 pub struct LevelStage {
     gain: f32,
@@ -60,11 +61,13 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct LevelConfig {
     pub gain: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for LevelConfig {
@@ -72,6 +75,7 @@ impl Default for LevelConfig {
         Self {
             gain: 1.0,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -1,9 +1,12 @@
+pub mod auto_level;
+pub mod capture;
 pub mod clipper;
 pub mod common;
 pub mod compressor;
 pub mod delay;
 pub mod eq;
 pub mod filter;
+pub mod group;
 pub mod level;
 pub mod multiband_saturator;
 pub mod nam;
@@ -13,6 +16,8 @@ pub mod preamp;
 pub mod reverb;
 pub mod tonestack;
 pub mod tremolo;
+pub mod trim;
+pub mod wah;
 
 // The core trait that all processing stages must implement
 pub trait Stage: Send + Sync + 'static {
@@ -31,4 +36,29 @@ pub trait Stage: Send + Sync + 'static {
 
     // Get a parameter value by name
     fn get_parameter(&self, name: &str) -> Result<f32, &'static str>;
+
+    // Set a parameter on a stage nested inside this one. Only groups have
+    // children.
+    fn set_child_parameter(
+        &mut self,
+        _child: usize,
+        _name: &str,
+        _value: f32,
+    ) -> Result<(), &'static str> {
+        Err("Stage has no children")
+    }
+
+    // Tell the stage the current tempo in BPM. Only called with a positive,
+    // finite tempo, and only when it changes. Called on the RT thread.
+    fn set_tempo(&mut self, _bpm: f32) {}
+
+    // Clear internal state (delay lines, filter memories, envelopes) without
+    // touching parameters. Called on the RT thread, so it must not allocate.
+    fn reset(&mut self) {}
+
+    // Whether this is a power amp, or holds one. Standby mutes the chain just
+    // after the last such stage.
+    fn is_power_amp(&self) -> bool {
+        false
+    }
 }
@@ -2,130 +2,150 @@ use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
 use crate::amp::stages::common::{DcBlocker, EnvelopeFollower};
+use crate::amp::stages::trim::CommonStageParams;
 use std::f32::consts::PI;
 
-/// Linkwitz-Riley 4th order crossover filter (cascaded 2nd order Butterworth)
-/// This creates a flat summed response at the crossover frequency
-#[derive(Clone)]
-struct LR4Filter {
-    // First biquad state
-    x1_1: f32,
-    x2_1: f32,
-    y1_1: f32,
-    y2_1: f32,
-    // Second biquad state
-    x1_2: f32,
-    x2_2: f32,
-    y1_2: f32,
-    y2_2: f32,
-    // Coefficients (same for both biquads)
-    b0: f32,
-    b1: f32,
-    b2: f32,
+/// Damping of a Butterworth (Q = 1/sqrt(2)) section, `k = 1/Q`.
+const BUTTERWORTH_K: f32 = std::f32::consts::SQRT_2;
+
+/// Coefficients of a Butterworth topology-preserving-transform state-variable
+/// filter. Worked out once per cutoff change rather than per sample.
+#[derive(Clone, Copy)]
+struct SvfCoefficients {
     a1: f32,
     a2: f32,
-    is_highpass: bool,
+    a3: f32,
 }
 
-impl LR4Filter {
-    fn new(cutoff_hz: f32, sample_rate: f32, is_highpass: bool) -> Self {
-        let mut filter = Self {
-            x1_1: 0.0,
-            x2_1: 0.0,
-            y1_1: 0.0,
-            y2_1: 0.0,
-            x1_2: 0.0,
-            x2_2: 0.0,
-            y1_2: 0.0,
-            y2_2: 0.0,
-            b0: 0.0,
-            b1: 0.0,
-            b2: 0.0,
-            a1: 0.0,
-            a2: 0.0,
-            is_highpass,
-        };
-        filter.set_cutoff(cutoff_hz, sample_rate);
-        filter
+impl SvfCoefficients {
+    fn new(cutoff_hz: f32, sample_rate: f32) -> Self {
+        let g = (PI * cutoff_hz / sample_rate).tan();
+        let a1 = 1.0 / g.mul_add(g + BUTTERWORTH_K, 1.0);
+        let a2 = g * a1;
+        Self { a1, a2, a3: g * a2 }
     }
+}
 
-    fn set_cutoff(&mut self, cutoff_hz: f32, sample_rate: f32) {
-        // Butterworth Q for LR4 cascade
-        let q = std::f32::consts::FRAC_1_SQRT_2;
-        let omega = 2.0 * PI * cutoff_hz / sample_rate;
-        let sin_omega = omega.sin();
-        let cos_omega = omega.cos();
-        let alpha = sin_omega / (2.0 * q);
-
-        let a0 = 1.0 + alpha;
-
-        if self.is_highpass {
-            self.b0 = f32::midpoint(1.0, cos_omega) / a0;
-            self.b1 = (-(1.0 + cos_omega)) / a0;
-            self.b2 = f32::midpoint(1.0, cos_omega) / a0;
-        } else {
-            self.b0 = ((1.0 - cos_omega) / 2.0) / a0;
-            self.b1 = (1.0 - cos_omega) / a0;
-            self.b2 = ((1.0 - cos_omega) / 2.0) / a0;
+/// State of one SVF section. The state is the integrators' charge rather
+/// than past outputs, so a cutoff change mid-stream doesn't click.
+#[derive(Clone, Copy, Default)]
+struct SvfState {
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl SvfState {
+    /// Run one sample, returning the bandpass and lowpass outputs.
+    #[inline]
+    fn tick(&mut self, coeffs: &SvfCoefficients, input: f32) -> (f32, f32) {
+        let v3 = input - self.ic2eq;
+        let v1 = coeffs.a1.mul_add(self.ic1eq, coeffs.a2 * v3);
+        let v2 = coeffs
+            .a2
+            .mul_add(self.ic1eq, coeffs.a3.mul_add(v3, self.ic2eq));
+        self.ic1eq = 2.0f32.mul_add(v1, -self.ic1eq);
+        self.ic2eq = 2.0f32.mul_add(v2, -self.ic2eq);
+        (v1, v2)
+    }
+
+    /// Second-order allpass: the same phase an LR4 split at this cutoff
+    /// puts on its summed output.
+    #[inline]
+    fn allpass(&mut self, coeffs: &SvfCoefficients, input: f32) -> f32 {
+        let (band, _) = self.tick(coeffs, input);
+        (-2.0 * BUTTERWORTH_K).mul_add(band, input)
+    }
+
+    const fn reset(&mut self) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+    }
+}
+
+/// Linkwitz-Riley 4th order crossover built from two SVF sections.
+///
+/// The first section's lowpass feeds the second, giving the LR4 lowpass.
+/// An LR4 pair sums to the second-order allpass the first section already
+/// produces, so the highpass is that allpass minus the lowpass: the bands
+/// sum flat by construction, with half the sections of two biquad cascades.
+#[derive(Clone)]
+struct Lr4Crossover {
+    coeffs: SvfCoefficients,
+    first: SvfState,
+    second: SvfState,
+}
+
+impl Lr4Crossover {
+    fn new(cutoff_hz: f32, sample_rate: f32) -> Self {
+        Self {
+            coeffs: SvfCoefficients::new(cutoff_hz, sample_rate),
+            first: SvfState::default(),
+            second: SvfState::default(),
         }
-        self.a1 = (-2.0 * cos_omega) / a0;
-        self.a2 = (1.0 - alpha) / a0;
     }
 
+    fn set_cutoff(&mut self, cutoff_hz: f32, sample_rate: f32) {
+        self.coeffs = SvfCoefficients::new(cutoff_hz, sample_rate);
+    }
+
+    /// Split one sample into its low and high bands.
     #[inline]
-    fn process(&mut self, input: f32) -> f32 {
-        // First biquad
-        let y1 = self.a2.mul_add(
-            -self.y2_1,
-            self.a1.mul_add(
-                -self.y1_1,
-                self.b2
-                    .mul_add(self.x2_1, self.b0.mul_add(input, self.b1 * self.x1_1)),
-            ),
-        );
-        self.x2_1 = self.x1_1;
-        self.x1_1 = input;
-        self.y2_1 = self.y1_1;
-        self.y1_1 = y1;
-
-        // Second biquad (cascade)
-        let y2 = self.a2.mul_add(
-            -self.y2_2,
-            self.a1.mul_add(
-                -self.y1_2,
-                self.b2
-                    .mul_add(self.x2_2, self.b0.mul_add(y1, self.b1 * self.x1_2)),
-            ),
-        );
-        self.x2_2 = self.x1_2;
-        self.x1_2 = y1;
-        self.y2_2 = self.y1_2;
-        self.y1_2 = y2;
+    fn process(&mut self, input: f32) -> (f32, f32) {
+        let (band, low_half) = self.first.tick(&self.coeffs, input);
+        let allpass = (-2.0 * BUTTERWORTH_K).mul_add(band, input);
+        let (_, low) = self.second.tick(&self.coeffs, low_half);
+        (low, allpass - low)
+    }
 
-        y2
+    const fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
     }
 }
 
-/// Soft saturation function with drive control
+/// Drive scales from 1.0 (clean) to ~10 (heavy saturation). Cached per band
+/// so the per-sample path is a multiply.
 #[inline]
-fn saturate(input: f32, drive: f32) -> f32 {
-    // Drive scales from 1.0 (clean) to ~10 (heavy saturation)
-    let drive_scaled = drive.mul_add(9.0, 1.0);
-    let x = input * drive_scaled;
+fn drive_scale(drive: f32) -> f32 {
+    drive.mul_add(9.0, 1.0)
+}
+
+/// Soft saturation of `input` pre-scaled by [`drive_scale`]
+#[inline]
+fn saturate(input: f32, drive_scale: f32) -> f32 {
+    let x = input * drive_scale;
     // Soft clipping bounded to (-1, 1)
     x / (1.0 + x.abs())
 }
 
+/// Whether a band is heard. Any solo silences every band that isn't soloed,
+/// and a soloed band plays even if it is also muted.
+#[inline]
+const fn band_audible(muted: bool, soloed: bool, any_soloed: bool) -> bool {
+    if any_soloed { soloed } else { !muted }
+}
+
+/// Mute/solo switches go through `set_parameter` as 0.0/1.0.
+fn switch_value(value: f32) -> Result<bool, &'static str> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value >= 0.5)
+    } else {
+        Err("Mute/solo must be 0.0 or 1.0")
+    }
+}
+
+const fn switch_param(on: bool) -> f32 {
+    if on { 1.0 } else { 0.0 }
+}
+
 pub struct MultibandSaturatorStage {
-    // Crossover filters for low/mid split
-    low_lp: LR4Filter,
-    mid_hp_low: LR4Filter,
-    // Crossover filters for mid/high split
-    mid_lp_high: LR4Filter,
-    high_hp: LR4Filter,
-    // Allpass phase compensation for low band (LP + HP at high_freq = allpass)
-    low_allpass_lp: LR4Filter,
-    low_allpass_hp: LR4Filter,
+    // Low/mid split at low_freq
+    low_split: Lr4Crossover,
+    // Mid/high split at high_freq
+    high_split: Lr4Crossover,
+    // Phase compensation for the low band: the allpass the mid/high split
+    // puts on everything above low_freq, run on its coefficients
+    low_allpass: SvfState,
 
     // Per-band envelope followers
     low_env: EnvelopeFollower,
@@ -141,12 +161,24 @@ pub struct MultibandSaturatorStage {
     low_drive: f32,
     mid_drive: f32,
     high_drive: f32,
+    // drive_scale() of each drive, updated with it
+    low_drive_scale: f32,
+    mid_drive_scale: f32,
+    high_drive_scale: f32,
     low_level: f32,
     mid_level: f32,
     high_level: f32,
     low_freq: f32,
     high_freq: f32,
 
+    // Audition switches for tuning the crossovers
+    low_mute: bool,
+    mid_mute: bool,
+    high_mute: bool,
+    low_solo: bool,
+    mid_solo: bool,
+    high_solo: bool,
+
     sample_rate: f32,
 }
 
@@ -163,16 +195,17 @@ impl MultibandSaturatorStage {
         high_freq: f32,
         sample_rate: f32,
     ) -> Self {
+        // Clamp before building the crossovers so they match the stored values.
+        let low_freq = low_freq.clamp(50.0, 500.0);
+        let high_freq = high_freq.clamp(1000.0, 6000.0);
+        let low_drive = low_drive.clamp(0.0, 1.0);
+        let mid_drive = mid_drive.clamp(0.0, 1.0);
+        let high_drive = high_drive.clamp(0.0, 1.0);
+
         Self {
-            // Low/mid crossover
-            low_lp: LR4Filter::new(low_freq, sample_rate, false),
-            mid_hp_low: LR4Filter::new(low_freq, sample_rate, true),
-            // Mid/high crossover
-            mid_lp_high: LR4Filter::new(high_freq, sample_rate, false),
-            high_hp: LR4Filter::new(high_freq, sample_rate, true),
-            // Allpass compensation for low band (matches phase delay of mid/high split)
-            low_allpass_lp: LR4Filter::new(high_freq, sample_rate, false),
-            low_allpass_hp: LR4Filter::new(high_freq, sample_rate, true),
+            low_split: Lr4Crossover::new(low_freq, sample_rate),
+            high_split: Lr4Crossover::new(high_freq, sample_rate),
+            low_allpass: SvfState::default(),
 
             low_env: EnvelopeFollower::from_ms(1.0, 50.0, sample_rate),
             mid_env: EnvelopeFollower::from_ms(1.0, 50.0, sample_rate),
@@ -182,29 +215,42 @@ impl MultibandSaturatorStage {
             mid_dc: DcBlocker::new(15.0, sample_rate),
             high_dc: DcBlocker::new(15.0, sample_rate),
 
-            low_drive: low_drive.clamp(0.0, 1.0),
-            mid_drive: mid_drive.clamp(0.0, 1.0),
-            high_drive: high_drive.clamp(0.0, 1.0),
+            low_drive,
+            mid_drive,
+            high_drive,
+            low_drive_scale: drive_scale(low_drive),
+            mid_drive_scale: drive_scale(mid_drive),
+            high_drive_scale: drive_scale(high_drive),
             low_level: low_level.clamp(0.0, 2.0),
             mid_level: mid_level.clamp(0.0, 2.0),
             high_level: high_level.clamp(0.0, 2.0),
-            low_freq: low_freq.clamp(50.0, 500.0),
-            high_freq: high_freq.clamp(1000.0, 6000.0),
+            low_freq,
+            high_freq,
+
+            low_mute: false,
+            mid_mute: false,
+            high_mute: false,
+            low_solo: false,
+            mid_solo: false,
+            high_solo: false,
 
             sample_rate,
         }
     }
 
+    /// Per-band gain after mute/solo: 1.0 if the band is heard, else 0.0.
+    const fn band_gains(&self) -> [f32; 3] {
+        let any_soloed = self.low_solo || self.mid_solo || self.high_solo;
+        [
+            switch_param(band_audible(self.low_mute, self.low_solo, any_soloed)),
+            switch_param(band_audible(self.mid_mute, self.mid_solo, any_soloed)),
+            switch_param(band_audible(self.high_mute, self.high_solo, any_soloed)),
+        ]
+    }
+
     fn update_crossover_frequencies(&mut self) {
-        self.low_lp.set_cutoff(self.low_freq, self.sample_rate);
-        self.mid_hp_low.set_cutoff(self.low_freq, self.sample_rate);
-        self.mid_lp_high
-            .set_cutoff(self.high_freq, self.sample_rate);
-        self.high_hp.set_cutoff(self.high_freq, self.sample_rate);
-        self.low_allpass_lp
-            .set_cutoff(self.high_freq, self.sample_rate);
-        self.low_allpass_hp
-            .set_cutoff(self.high_freq, self.sample_rate);
+        self.low_split.set_cutoff(self.low_freq, self.sample_rate);
+        self.high_split.set_cutoff(self.high_freq, self.sample_rate);
     }
 }
 
@@ -212,15 +258,13 @@ impl Stage for MultibandSaturatorStage {
     fn process(&mut self, input: f32) -> f32 {
         // Split into three bands using LR4 crossovers
         // First split: low vs high_side at low_freq
-        let low_raw = self.low_lp.process(input);
-        let high_side = self.mid_hp_low.process(input);
+        let (low_raw, high_side) = self.low_split.process(input);
 
         // Second split: mid vs high from high_side at high_freq
-        let mid = self.mid_lp_high.process(high_side);
-        let high = self.high_hp.process(high_side);
+        let (mid, high) = self.high_split.process(high_side);
 
-        // Allpass phase compensation for low band (LP + HP at high_freq = allpass)
-        let low = self.low_allpass_lp.process(low_raw) + self.low_allpass_hp.process(low_raw);
+        // Allpass phase compensation for low band, matching the second split
+        let low = self.low_allpass.allpass(&self.high_split.coeffs, low_raw);
 
         // Track envelopes for adaptive saturation
         let low_env = self.low_env.process(low);
@@ -230,21 +274,21 @@ impl Stage for MultibandSaturatorStage {
         // Apply saturation with envelope-based gain compensation
         // This helps maintain consistent apparent loudness
         let low_sat = if low_env > 0.0001 {
-            saturate(low / (1.0 + low_env), self.low_drive) * low_env.mul_add(0.5, 1.0)
+            saturate(low / (1.0 + low_env), self.low_drive_scale) * low_env.mul_add(0.5, 1.0)
         } else {
-            saturate(low, self.low_drive)
+            saturate(low, self.low_drive_scale)
         };
 
         let mid_sat = if mid_env > 0.0001 {
-            saturate(mid / (1.0 + mid_env), self.mid_drive) * mid_env.mul_add(0.5, 1.0)
+            saturate(mid / (1.0 + mid_env), self.mid_drive_scale) * mid_env.mul_add(0.5, 1.0)
         } else {
-            saturate(mid, self.mid_drive)
+            saturate(mid, self.mid_drive_scale)
         };
 
         let high_sat = if high_env > 0.0001 {
-            saturate(high / (1.0 + high_env), self.high_drive) * high_env.mul_add(0.5, 1.0)
+            saturate(high / (1.0 + high_env), self.high_drive_scale) * high_env.mul_add(0.5, 1.0)
         } else {
-            saturate(high, self.high_drive)
+            saturate(high, self.high_drive_scale)
         };
 
         // Apply DC blocking to remove any DC offset from saturation
@@ -253,7 +297,10 @@ impl Stage for MultibandSaturatorStage {
         let high_clean = self.high_dc.process(high_sat);
 
         // Mix bands with level controls and sum
-        low_clean * self.low_level + mid_clean * self.mid_level + high_clean * self.high_level
+        let [low_gain, mid_gain, high_gain] = self.band_gains();
+        low_clean * self.low_level * low_gain
+            + mid_clean * self.mid_level * mid_gain
+            + high_clean * self.high_level * high_gain
     }
 
     fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
@@ -261,6 +308,7 @@ impl Stage for MultibandSaturatorStage {
             "low_drive" => {
                 if (0.0..=1.0).contains(&value) {
                     self.low_drive = value;
+                    self.low_drive_scale = drive_scale(value);
                     Ok(())
                 } else {
                     Err("Low drive must be 0.0-1.0")
@@ -269,6 +317,7 @@ impl Stage for MultibandSaturatorStage {
             "mid_drive" => {
                 if (0.0..=1.0).contains(&value) {
                     self.mid_drive = value;
+                    self.mid_drive_scale = drive_scale(value);
                     Ok(())
                 } else {
                     Err("Mid drive must be 0.0-1.0")
@@ -277,6 +326,7 @@ impl Stage for MultibandSaturatorStage {
             "high_drive" => {
                 if (0.0..=1.0).contains(&value) {
                     self.high_drive = value;
+                    self.high_drive_scale = drive_scale(value);
                     Ok(())
                 } else {
                     Err("High drive must be 0.0-1.0")
@@ -324,6 +374,30 @@ impl Stage for MultibandSaturatorStage {
                     Err("High freq must be 1000-6000 Hz")
                 }
             }
+            "low_mute" => {
+                self.low_mute = switch_value(value)?;
+                Ok(())
+            }
+            "mid_mute" => {
+                self.mid_mute = switch_value(value)?;
+                Ok(())
+            }
+            "high_mute" => {
+                self.high_mute = switch_value(value)?;
+                Ok(())
+            }
+            "low_solo" => {
+                self.low_solo = switch_value(value)?;
+                Ok(())
+            }
+            "mid_solo" => {
+                self.mid_solo = switch_value(value)?;
+                Ok(())
+            }
+            "high_solo" => {
+                self.high_solo = switch_value(value)?;
+                Ok(())
+            }
             _ => Err("Unknown parameter"),
         }
     }
@@ -338,9 +412,27 @@ impl Stage for MultibandSaturatorStage {
             "high_level" => Ok(self.high_level),
             "low_freq" => Ok(self.low_freq),
             "high_freq" => Ok(self.high_freq),
+            "low_mute" => Ok(switch_param(self.low_mute)),
+            "mid_mute" => Ok(switch_param(self.mid_mute)),
+            "high_mute" => Ok(switch_param(self.high_mute)),
+            "low_solo" => Ok(switch_param(self.low_solo)),
+            "mid_solo" => Ok(switch_param(self.mid_solo)),
+            "high_solo" => Ok(switch_param(self.high_solo)),
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn reset(&mut self) {
+        self.low_split.reset();
+        self.high_split.reset();
+        self.low_allpass.reset();
+        self.low_env.reset();
+        self.mid_env.reset();
+        self.high_env.reset();
+        self.low_dc.reset();
+        self.mid_dc.reset();
+        self.high_dc.reset();
+    }
 }
 
 #[cfg(test)]
@@ -403,21 +495,34 @@ mod tests {
         assert!(stage.get_parameter("unknown").is_err());
     }
 
+    #[test]
+    fn test_constructor_clamps_crossovers_it_builds() {
+        let mut clamped =
+            MultibandSaturatorStage::new(0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 10.0, 20_000.0, 48000.0);
+        let mut in_range =
+            MultibandSaturatorStage::new(0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 50.0, 6000.0, 48000.0);
+
+        for i in 0..1000 {
+            let input = (i as f32 * 0.05).sin() * 0.5;
+            assert_eq!(clamped.process(input), in_range.process(input));
+        }
+    }
+
     #[test]
     fn test_saturation_function() {
         // Clean signal (drive = 0)
-        let clean = saturate(0.5, 0.0);
+        let clean = saturate(0.5, drive_scale(0.0));
         // drive=0 → drive_scaled=1.0, x=0.5, output = 0.5/1.5 ≈ 0.333
         assert!((clean - 1.0 / 3.0).abs() < 0.01);
 
         // Heavy saturation (drive = 1)
-        let saturated = saturate(0.5, 1.0);
+        let saturated = saturate(0.5, drive_scale(1.0));
         // drive=1 → drive_scaled=10.0, x=5.0, output = 5.0/6.0 ≈ 0.833
         assert!(saturated > 0.0);
         assert!(saturated < 1.0); // Bounded waveshaper stays below 1.0
 
         // Negative values
-        let neg = saturate(-0.5, 0.5);
+        let neg = saturate(-0.5, drive_scale(0.5));
         assert!(neg < 0.0);
         assert!(neg > -1.0); // Bounded below too
     }
@@ -427,8 +532,8 @@ mod tests {
         // Verify saturate() output is always in (-1, 1) for extreme inputs
         for &drive in &[0.0, 0.25, 0.5, 0.75, 1.0] {
             for &input in &[0.0, 0.1, 0.5, 1.0, 5.0, 100.0, 10000.0] {
-                let pos = saturate(input, drive);
-                let neg = saturate(-input, drive);
+                let pos = saturate(input, drive_scale(drive));
+                let neg = saturate(-input, drive_scale(drive));
                 assert!(
                     pos.abs() < 1.0,
                     "saturate({input}, {drive}) = {pos}, expected |output| < 1.0"
@@ -498,6 +603,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_crossover_sweep_is_click_free() {
+        // Sweep both crossovers across their range in 100 ms, a block at a
+        // time as automation would. A click shows up as a sample-to-sample
+        // step well beyond what the tone itself takes.
+        let mut stage =
+            MultibandSaturatorStage::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 50.0, 1000.0, 48000.0);
+        let tone = |i: usize| 0.00001 * (2.0 * PI * 300.0 * i as f32 / 48000.0).sin();
+
+        let mut previous = 0.0;
+        let mut steady_step = 0.0f32;
+        for i in 0..24000 {
+            let output = stage.process(tone(i));
+            if i >= 12000 {
+                steady_step = steady_step.max((output - previous).abs());
+            }
+            previous = output;
+        }
+
+        let mut sweep_step = 0.0f32;
+        for i in 24000..28800 {
+            if i % 64 == 0 {
+                let position = (i - 24000) as f32 / 4800.0;
+                stage
+                    .set_parameter("low_freq", 450.0f32.mul_add(position, 50.0))
+                    .unwrap();
+                stage
+                    .set_parameter("high_freq", 5000.0f32.mul_add(position, 1000.0))
+                    .unwrap();
+            }
+            let output = stage.process(tone(i));
+            sweep_step = sweep_step.max((output - previous).abs());
+            previous = output;
+        }
+
+        assert!(
+            sweep_step < steady_step * 1.25,
+            "crossover sweep clicked: step {sweep_step} vs steady {steady_step}"
+        );
+    }
+
+    #[test]
+    fn test_band_audible_precedence() {
+        // No solo: mute decides
+        assert!(band_audible(false, false, false));
+        assert!(!band_audible(true, false, false));
+        // Any solo: only soloed bands play, regardless of mute
+        assert!(!band_audible(false, false, true));
+        assert!(!band_audible(true, false, true));
+        assert!(band_audible(false, true, true));
+        assert!(band_audible(true, true, true));
+    }
+
+    #[test]
+    fn test_mute_solo_parameters() {
+        let mut stage =
+            MultibandSaturatorStage::new(0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 200.0, 2000.0, 48000.0);
+        assert_eq!(stage.band_gains(), [1.0, 1.0, 1.0]);
+
+        stage.set_parameter("mid_mute", 1.0).unwrap();
+        assert_eq!(stage.band_gains(), [1.0, 0.0, 1.0]);
+
+        // Soloing a subset silences the rest; solo beats mute on the same band
+        stage.set_parameter("mid_solo", 1.0).unwrap();
+        stage.set_parameter("high_solo", 1.0).unwrap();
+        assert_eq!(stage.band_gains(), [0.0, 1.0, 1.0]);
+
+        // Clearing every solo restores the mutes
+        stage.set_parameter("mid_solo", 0.0).unwrap();
+        stage.set_parameter("high_solo", 0.0).unwrap();
+        assert_eq!(stage.band_gains(), [1.0, 0.0, 1.0]);
+
+        assert_eq!(stage.get_parameter("mid_mute").unwrap(), 1.0);
+        assert_eq!(stage.get_parameter("low_solo").unwrap(), 0.0);
+        assert!(stage.set_parameter("low_mute", 2.0).is_err());
+    }
+
+    #[test]
+    fn test_solo_isolates_band() {
+        // A low tone with only the high band soloed should be almost silent
+        let mut stage =
+            MultibandSaturatorStage::new(0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 200.0, 2000.0, 48000.0);
+        stage.set_parameter("high_solo", 1.0).unwrap();
+
+        let mut peak = 0.0f32;
+        for i in 0..48000 {
+            let t = i as f32 / 48000.0;
+            let out = stage.process(0.1 * (2.0 * PI * 60.0 * t).sin());
+            if i > 24000 {
+                peak = peak.max(out.abs());
+            }
+        }
+        assert!(peak < 0.001, "low tone leaked through high solo: {peak}");
+    }
+
+    #[test]
+    fn test_config_does_not_save_solo() {
+        let cfg = MultibandSaturatorConfig {
+            low_mute: true,
+            mid_solo: true,
+            ..MultibandSaturatorConfig::default()
+        };
+        assert!(cfg.any_soloed());
+
+        let json = serde_json::to_string(&cfg).unwrap();
+        let restored: MultibandSaturatorConfig = serde_json::from_str(&json).unwrap();
+        assert!(restored.low_mute);
+        assert!(!restored.any_soloed());
+    }
+
     #[test]
     fn test_dc_blocking() {
         let mut stage =
@@ -516,7 +731,7 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MultibandSaturatorConfig {
     pub low_drive: f32,
     pub mid_drive: f32,
@@ -527,7 +742,23 @@ pub struct MultibandSaturatorConfig {
     pub low_freq: f32,
     pub high_freq: f32,
     #[serde(default)]
+    pub low_mute: bool,
+    #[serde(default)]
+    pub mid_mute: bool,
+    #[serde(default)]
+    pub high_mute: bool,
+    /// Solo is for auditioning while tuning and is never saved: a preset
+    /// stored with a band soloed would load silencing the other two.
+    #[serde(skip)]
+    pub low_solo: bool,
+    #[serde(skip)]
+    pub mid_solo: bool,
+    #[serde(skip)]
+    pub high_solo: bool,
+    #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for MultibandSaturatorConfig {
@@ -541,14 +772,21 @@ impl Default for MultibandSaturatorConfig {
             high_level: 1.0,
             low_freq: 200.0,
             high_freq: 2500.0,
+            low_mute: false,
+            mid_mute: false,
+            high_mute: false,
+            low_solo: false,
+            mid_solo: false,
+            high_solo: false,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
 
 impl MultibandSaturatorConfig {
     pub fn to_stage(&self, sample_rate: f32) -> MultibandSaturatorStage {
-        MultibandSaturatorStage::new(
+        let mut stage = MultibandSaturatorStage::new(
             self.low_drive,
             self.mid_drive,
             self.high_drive,
@@ -558,6 +796,17 @@ impl MultibandSaturatorConfig {
             self.low_freq,
             self.high_freq,
             sample_rate,
-        )
+        );
+        stage.low_mute = self.low_mute;
+        stage.mid_mute = self.mid_mute;
+        stage.high_mute = self.high_mute;
+        stage.low_solo = self.low_solo;
+        stage.mid_solo = self.mid_solo;
+        stage.high_solo = self.high_solo;
+        stage
+    }
+
+    pub const fn any_soloed(&self) -> bool {
+        self.low_solo || self.mid_solo || self.high_solo
     }
 }
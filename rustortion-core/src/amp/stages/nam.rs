@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
 use crate::amp::stages::common::db_to_lin;
+use crate::amp::stages::trim::CommonStageParams;
 use crate::nam::registry;
 
 /// Valid range for the input/output gain knobs, matching the UI and plugin params.
@@ -152,7 +153,7 @@ impl Stage for NamStage {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct NamConfig {
     /// Display name of the selected model, or `None` for passthrough.
     #[serde(default)]
@@ -162,6 +163,8 @@ pub struct NamConfig {
     pub mix: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for NamConfig {
@@ -172,6 +175,7 @@ impl Default for NamConfig {
             output_gain_db: 0.0,
             mix: 1.0,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -302,7 +306,7 @@ mod tests {
             input_gain_db: 6.0,
             output_gain_db: -3.0,
             mix: 0.5,
-            bypassed: false,
+            ..NamConfig::default()
         };
 
         // Two stages from the same config evolve identical internal state given the
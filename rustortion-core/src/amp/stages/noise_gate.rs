@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
 use crate::amp::stages::common::{EnvelopeFollower, calculate_coefficient, db_to_lin};
+use crate::amp::stages::trim::CommonStageParams;
 
 /// Noise gate stage for eliminating unwanted noise when not playing
 /// Features:
@@ -178,6 +179,12 @@ impl Stage for NoiseGateStage {
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn reset(&mut self) {
+        self.envelope.reset();
+        self.gate_state = 0.0;
+        self.hold_counter = 0;
+    }
 }
 
 #[cfg(test)]
@@ -375,7 +382,7 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct NoiseGateConfig {
     pub threshold_db: f32,
     pub ratio: f32,
@@ -384,6 +391,8 @@ pub struct NoiseGateConfig {
     pub release_ms: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for NoiseGateConfig {
@@ -395,6 +404,7 @@ impl Default for NoiseGateConfig {
             hold_ms: 10.0,
             release_ms: 100.0,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -1,5 +1,7 @@
 use crate::amp::stages::Stage;
+use crate::amp::stages::auto_level::{AutoLevelCurve, compensation};
 use crate::amp::stages::common::{DcBlocker, EnvelopeFollower, calculate_coefficient};
+use crate::amp::stages::trim::CommonStageParams;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +30,9 @@ pub struct PowerAmpStage {
     sag_envelope: EnvelopeFollower,
     dc_blocker: DcBlocker,
     sample_rate: f32,
+    auto_level: bool,
+    /// Output gain from the auto-level curve, 1.0 when it's off.
+    level_comp: f32,
 }
 
 /// Sag release range in milliseconds: tight (40ms) to spongy (200ms).
@@ -51,8 +56,50 @@ impl PowerAmpStage {
             sag_envelope: EnvelopeFollower::from_ms(10.0, sag_release_ms, sample_rate),
             dc_blocker: DcBlocker::new(10.0, sample_rate),
             sample_rate,
+            auto_level: false,
+            level_comp: 1.0,
         }
     }
+
+    /// Compensate the output for the drive setting, so turning up the drive
+    /// adds saturation rather than loudness.
+    pub fn with_auto_level(mut self, auto_level: bool) -> Self {
+        self.auto_level = auto_level;
+        self.update_level_comp();
+        self
+    }
+
+    fn update_level_comp(&mut self) {
+        self.level_comp = if self.auto_level {
+            compensation(&auto_level_curve(self.amp_type), self.drive)
+        } else {
+            1.0
+        };
+    }
+}
+
+/// Compensation in dB against drive, levelled to the default drive of 0.5
+/// with the default sag. Fitted by `calibrate_auto_level` below.
+const fn auto_level_curve(amp_type: PowerAmpType) -> AutoLevelCurve {
+    match amp_type {
+        PowerAmpType::ClassA => [
+            7.269_967_6,
+            -24.080_17,
+            27.622_215,
+            -20.418_653,
+            6.720_194_3,
+        ],
+        PowerAmpType::ClassAB => [8.622_905, -31.595_936, 43.724_63, -36.319_218, 12.663_814],
+        PowerAmpType::ClassB => [12.699_952, -49.580_353, 74.781_38, -63.904_59, 22.313_066],
+    }
+}
+
+fn switch_value(value: f32) -> Result<bool, &'static str> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value >= 0.5)
+    } else {
+        Err("Auto level must be 0.0 or 1.0")
+    }
 }
 
 impl Stage for PowerAmpStage {
@@ -91,7 +138,7 @@ impl Stage for PowerAmpStage {
             }
         };
 
-        self.dc_blocker.process(clipped)
+        self.dc_blocker.process(clipped) * self.level_comp
     }
 
     fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
@@ -99,6 +146,7 @@ impl Stage for PowerAmpStage {
             "drive" => {
                 if (0.0..=1.0).contains(&value) {
                     self.drive = value;
+                    self.update_level_comp();
                     Ok(())
                 } else {
                     Err("Drive must be between 0.0 and 1.0")
@@ -122,6 +170,11 @@ impl Stage for PowerAmpStage {
                     Err("Sag release must be between 40.0 and 200.0 ms")
                 }
             }
+            "auto_level" => {
+                self.auto_level = switch_value(value)?;
+                self.update_level_comp();
+                Ok(())
+            }
             _ => Err("Unknown parameter name"),
         }
     }
@@ -131,14 +184,25 @@ impl Stage for PowerAmpStage {
             "drive" => Ok(self.drive),
             "sag" => Ok(self.sag),
             "sag_release" => Ok(self.sag_release),
+            "auto_level" => Ok(if self.auto_level { 1.0 } else { 0.0 }),
             _ => Err("Unknown parameter name"),
         }
     }
+
+    fn reset(&mut self) {
+        self.sag_envelope.reset();
+        self.dc_blocker.reset();
+    }
+
+    fn is_power_amp(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::amp::stages::auto_level::calibration::{fit_compensation, sine_level_db, spread_db};
 
     const SAMPLE_RATE: f32 = 48000.0;
 
@@ -151,6 +215,51 @@ mod tests {
         PowerAmpStage::new(drive, amp_type, sag, sag_release_ms, SAMPLE_RATE)
     }
 
+    const AMP_TYPES: [PowerAmpType; 3] = [
+        PowerAmpType::ClassA,
+        PowerAmpType::ClassAB,
+        PowerAmpType::ClassB,
+    ];
+
+    /// Drive sweep for auto-level calibration and its test: 0 to 1 in twentieths.
+    fn auto_level_drives() -> [f32; 21] {
+        std::array::from_fn(|i| i as f32 * 0.05)
+    }
+
+    fn level_db(amp_type: PowerAmpType, drive: f32, auto_level: bool) -> f32 {
+        let mut stage = make_stage(amp_type, drive, 0.3, 120.0).with_auto_level(auto_level);
+        sine_level_db(&mut stage)
+    }
+
+    #[test]
+    #[ignore = "prints the curves baked into auto_level_curve"]
+    fn calibrate_auto_level() {
+        let drives = auto_level_drives();
+        for amp_type in AMP_TYPES {
+            let levels: Vec<f32> = drives
+                .iter()
+                .map(|&d| level_db(amp_type, d, false))
+                .collect();
+            let curve = fit_compensation(&drives, &levels, level_db(amp_type, 0.5, false));
+            eprintln!("{amp_type:?}: {curve:?}");
+        }
+    }
+
+    #[test]
+    fn auto_level_holds_loudness_across_drive() {
+        for amp_type in AMP_TYPES {
+            let levels: Vec<f32> = auto_level_drives()
+                .iter()
+                .map(|&d| level_db(amp_type, d, true))
+                .collect();
+            let spread = spread_db(&levels);
+            assert!(
+                spread < 1.5,
+                "{amp_type:?} level varies by {spread:.2} dB with auto level on: {levels:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_class_ab_symmetric() {
         let mut stage_pos = make_stage(PowerAmpType::ClassAB, 0.5, 0.0, 120.0);
@@ -270,6 +379,14 @@ mod tests {
         assert!(stage.set_parameter("sag_release", 39.0).is_err());
     }
 
+    #[test]
+    fn auto_level_only_takes_a_switch_value() {
+        let mut stage = make_stage(PowerAmpType::ClassAB, 0.5, 0.5, 120.0);
+        stage.set_parameter("auto_level", 1.0).unwrap();
+        assert!(stage.set_parameter("auto_level", 2.0).is_err());
+        assert_eq!(stage.get_parameter("auto_level"), Ok(1.0));
+    }
+
     #[test]
     fn test_denormal_protection() {
         let mut stage = make_stage(PowerAmpType::ClassAB, 0.5, 0.5, 120.0);
@@ -344,14 +461,18 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(default)]
 pub struct PowerAmpConfig {
     pub drive: f32,
     pub amp_type: PowerAmpType,
     pub sag: f32,
     pub sag_release: f32,
+    /// Compensate the output for the drive setting.
+    pub auto_level: bool,
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for PowerAmpConfig {
@@ -361,7 +482,9 @@ impl Default for PowerAmpConfig {
             amp_type: PowerAmpType::ClassAB,
             sag: 0.3,
             sag_release: 120.0,
+            auto_level: false,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -375,5 +498,6 @@ impl PowerAmpConfig {
             self.sag_release,
             sample_rate,
         )
+        .with_auto_level(self.auto_level)
     }
 }
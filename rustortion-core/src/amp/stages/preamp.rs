@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
+use crate::amp::stages::auto_level::{AutoLevelCurve, compensation};
 use crate::amp::stages::clipper::ClipperType;
 use crate::amp::stages::common::{DcBlocker, OnePoleLP};
+use crate::amp::stages::trim::CommonStageParams;
+
+const DRIVE_MIN: f32 = 1.0;
+const DRIVE_SCALE: f32 = 1.8;
 
 pub struct PreampStage {
     gain: f32,      // 0..10
@@ -11,6 +16,9 @@ pub struct PreampStage {
     clipper_type: ClipperType,
     interstage_lp: OnePoleLP,
     dc_blocker: DcBlocker,
+    auto_level: bool,
+    /// Output gain from the auto-level curve, 1.0 when it's off.
+    level_comp: f32,
 }
 
 impl PreampStage {
@@ -23,17 +31,88 @@ impl PreampStage {
             clipper_type: clipper,
             interstage_lp: OnePoleLP::new(10_000.0, sample_rate),
             dc_blocker: DcBlocker::new(15.0, sample_rate),
+            auto_level: false,
+            level_comp: 1.0,
         }
     }
+
+    /// Compensate the output for the gain setting, so turning up the gain
+    /// adds distortion rather than loudness.
+    pub fn with_auto_level(mut self, auto_level: bool) -> Self {
+        self.auto_level = auto_level;
+        self.update_level_comp();
+        self
+    }
+
+    fn drive(&self) -> f32 {
+        self.gain.mul_add(DRIVE_SCALE, DRIVE_MIN)
+    }
+
+    fn update_level_comp(&mut self) {
+        self.level_comp = if self.auto_level {
+            compensation(&auto_level_curve(self.clipper_type), self.drive().ln())
+        } else {
+            1.0
+        };
+    }
+}
+
+/// Compensation in dB against ln(drive), levelled to the default gain of 5.
+/// Fitted by `calibrate_auto_level` below.
+const fn auto_level_curve(clipper: ClipperType) -> AutoLevelCurve {
+    match clipper {
+        ClipperType::Soft => [
+            14.638_958,
+            -9.325_499,
+            -1.243_034,
+            1.795_425_4,
+            -0.302_711_64,
+        ],
+        ClipperType::Medium => [
+            16.015_543,
+            -9.491_735,
+            -0.720_737_34,
+            1.274_245_4,
+            -0.210_086_1,
+        ],
+        ClipperType::Hard => [14.886_781, -9.068_294, -4.658_867, 4.237_93, -0.750_398_7],
+        ClipperType::Asymmetric => [
+            14.458_179,
+            -9.159_845,
+            -1.120_388_6,
+            1.683_446_6,
+            -0.284_323_25,
+        ],
+        ClipperType::ClassA => [
+            11.984_302,
+            -9.165_408,
+            -2.956_66,
+            3.623_617_6,
+            -0.695_644_74,
+        ],
+        ClipperType::Triode => [
+            20.615_482,
+            -10.013_508,
+            0.479_108_57,
+            0.062_516_96,
+            -0.030_467_704,
+        ],
+    }
+}
+
+fn switch_value(value: f32) -> Result<bool, &'static str> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value >= 0.5)
+    } else {
+        Err("Auto level must be 0.0 or 1.0")
+    }
 }
 
 impl Stage for PreampStage {
     fn process(&mut self, input: f32) -> f32 {
-        const DRIVE_MIN: f32 = 1.0;
-        const DRIVE_SCALE: f32 = 1.8;
         const CLIPPER_SCALE: f32 = 0.3;
 
-        let drive = self.gain.mul_add(DRIVE_SCALE, DRIVE_MIN);
+        let drive = self.drive();
 
         // --- Initial asymmetric soft clip with DC compensation ---
         // Instead of adding DC to the input, shift the tanh curve, recenter, and apply
@@ -51,7 +130,7 @@ impl Stage for PreampStage {
             .process(filtered, self.gain.mul_add(CLIPPER_SCALE, 1.0));
 
         // Remove any residual DC so next stage gets a clean, centered signal
-        self.dc_blocker.process(clipped)
+        self.dc_blocker.process(clipped) * self.level_comp
     }
 
     fn set_parameter(&mut self, p: &str, v: f32) -> Result<(), &'static str> {
@@ -59,6 +138,7 @@ impl Stage for PreampStage {
             "gain" => {
                 if (0.0..=10.0).contains(&v) {
                     self.gain = v;
+                    self.update_level_comp();
                     Ok(())
                 } else {
                     Err("Gain 0-10")
@@ -73,6 +153,11 @@ impl Stage for PreampStage {
                     Err("Bias −1-1")
                 }
             }
+            "auto_level" => {
+                self.auto_level = switch_value(v)?;
+                self.update_level_comp();
+                Ok(())
+            }
             _ => Err("Unknown parameter"),
         }
     }
@@ -81,17 +166,84 @@ impl Stage for PreampStage {
         match p {
             "gain" => Ok(self.gain),
             "bias" => Ok(self.bias),
+            "auto_level" => Ok(if self.auto_level { 1.0 } else { 0.0 }),
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn reset(&mut self) {
+        self.interstage_lp.reset();
+        self.dc_blocker.reset();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::amp::stages::auto_level::calibration::{fit_compensation, sine_level_db, spread_db};
 
     const SR: f32 = 44100.0;
 
+    const CLIPPERS: [ClipperType; 6] = [
+        ClipperType::Soft,
+        ClipperType::Medium,
+        ClipperType::Hard,
+        ClipperType::Asymmetric,
+        ClipperType::ClassA,
+        ClipperType::Triode,
+    ];
+
+    /// Gain sweep for auto-level calibration and its test: 0 to 10 in halves.
+    fn auto_level_gains() -> [f32; 21] {
+        std::array::from_fn(|i| i as f32 * 0.5)
+    }
+
+    fn level_db(clipper: ClipperType, gain: f32, auto_level: bool) -> f32 {
+        let mut stage = PreampStage::new(gain, 0.0, clipper, 48_000.0).with_auto_level(auto_level);
+        sine_level_db(&mut stage)
+    }
+
+    #[test]
+    #[ignore = "prints the curves baked into auto_level_curve"]
+    fn calibrate_auto_level() {
+        let gains = auto_level_gains();
+        let xs: Vec<f32> = gains
+            .iter()
+            .map(|&g| g.mul_add(DRIVE_SCALE, DRIVE_MIN).ln())
+            .collect();
+        for clipper in CLIPPERS {
+            let levels: Vec<f32> = gains.iter().map(|&g| level_db(clipper, g, false)).collect();
+            let curve = fit_compensation(&xs, &levels, level_db(clipper, 5.0, false));
+            eprintln!("{clipper:?}: {curve:?}");
+        }
+    }
+
+    #[test]
+    fn auto_level_holds_loudness_across_gain() {
+        for clipper in CLIPPERS {
+            let levels: Vec<f32> = auto_level_gains()
+                .iter()
+                .map(|&g| level_db(clipper, g, true))
+                .collect();
+            let spread = spread_db(&levels);
+            assert!(
+                spread < 1.5,
+                "{clipper:?} level varies by {spread:.2} dB with auto level on: {levels:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn auto_level_leaves_default_gain_alone() {
+        for clipper in CLIPPERS {
+            let diff = level_db(clipper, 5.0, true) - level_db(clipper, 5.0, false);
+            assert!(
+                diff.abs() < 0.5,
+                "{clipper:?} default gain moved {diff:.2} dB"
+            );
+        }
+    }
+
     fn make_preamp(gain: f32, bias: f32) -> PreampStage {
         PreampStage::new(gain, bias, ClipperType::Soft, SR)
     }
@@ -213,6 +365,9 @@ mod tests {
         assert!(stage.set_parameter("bias", 1.0).is_ok());
         assert!(stage.set_parameter("bias", -1.1).is_err());
         assert!(stage.set_parameter("bias", 1.1).is_err());
+        assert!(stage.set_parameter("auto_level", 1.0).is_ok());
+        assert!(stage.set_parameter("auto_level", 2.0).is_err());
+        assert!(stage.set_parameter("auto_level", -1.0).is_err());
         assert!(stage.set_parameter("unknown", 0.0).is_err());
     }
 
@@ -306,13 +461,18 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct PreampConfig {
     pub gain: f32,
     pub bias: f32,
     pub clipper_type: ClipperType,
+    /// Compensate the output for the gain setting.
+    #[serde(default)]
+    pub auto_level: bool,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for PreampConfig {
@@ -321,7 +481,9 @@ impl Default for PreampConfig {
             gain: 5.0,
             bias: 0.0,
             clipper_type: ClipperType::Soft,
+            auto_level: false,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -329,5 +491,6 @@ impl Default for PreampConfig {
 impl PreampConfig {
     pub fn to_stage(&self, sample_rate: f32) -> PreampStage {
         PreampStage::new(self.gain, self.bias, self.clipper_type, sample_rate)
+            .with_auto_level(self.auto_level)
     }
 }
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
+use crate::amp::stages::trim::CommonStageParams;
 
 // Freeverb tuning constants (reference values at 44100 Hz)
 const COMB_DELAYS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
@@ -16,7 +17,7 @@ const ALLPASS_FEEDBACK: f32 = 0.5;
 const DENORMAL_THRESHOLD: f32 = 1e-20;
 
 /// Lowpass-feedback comb filter used in Freeverb.
-struct CombFilter {
+pub(crate) struct CombFilter {
     buffer: Vec<f32>,
     write_pos: usize,
     filterstore: f32,
@@ -26,7 +27,7 @@ struct CombFilter {
 }
 
 impl CombFilter {
-    fn new(size: usize) -> Self {
+    pub(crate) fn new(size: usize) -> Self {
         Self {
             buffer: vec![0.0; size],
             write_pos: 0,
@@ -37,7 +38,7 @@ impl CombFilter {
         }
     }
 
-    fn process(&mut self, input: f32) -> f32 {
+    pub(crate) fn process(&mut self, input: f32) -> f32 {
         let output = self.buffer[self.write_pos];
 
         // One-pole lowpass in feedback path
@@ -61,31 +62,42 @@ impl CombFilter {
         output
     }
 
-    const fn set_feedback(&mut self, feedback: f32) {
+    pub(crate) fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.write_pos = 0;
+        self.filterstore = 0.0;
+    }
+
+    pub(crate) const fn set_feedback(&mut self, feedback: f32) {
         self.feedback = feedback;
     }
 
-    const fn set_damp(&mut self, damp1: f32, damp2: f32) {
+    pub(crate) const fn set_damp(&mut self, damp1: f32, damp2: f32) {
         self.damp1 = damp1;
         self.damp2 = damp2;
     }
 }
 
 /// Allpass filter used in Freeverb with fixed coefficient of 0.5.
-struct AllpassFilter {
+pub(crate) struct AllpassFilter {
     buffer: Vec<f32>,
     write_pos: usize,
 }
 
 impl AllpassFilter {
-    fn new(size: usize) -> Self {
+    pub(crate) fn new(size: usize) -> Self {
         Self {
             buffer: vec![0.0; size],
             write_pos: 0,
         }
     }
 
-    fn process(&mut self, input: f32) -> f32 {
+    pub(crate) fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.write_pos = 0;
+    }
+
+    pub(crate) fn process(&mut self, input: f32) -> f32 {
         let bufout = self.buffer[self.write_pos];
         let output = bufout - input;
 
@@ -106,7 +118,7 @@ impl AllpassFilter {
 }
 
 /// Scale a reference delay length (at 44100 Hz) to the actual sample rate.
-fn scale_delay(reference_len: usize, sample_rate: f32) -> usize {
+pub(crate) fn scale_delay(reference_len: usize, sample_rate: f32) -> usize {
     (reference_len as f32 * sample_rate / REFERENCE_SAMPLE_RATE)
         .round()
         .max(1.0) as usize
@@ -220,6 +232,15 @@ impl Stage for ReverbStage {
             _ => Err("Unknown parameter"),
         }
     }
+
+    fn reset(&mut self) {
+        for comb in &mut self.combs {
+            comb.reset();
+        }
+        for allpass in &mut self.allpasses {
+            allpass.reset();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -452,13 +473,15 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ReverbConfig {
     pub room_size: f32,
     pub damping: f32,
     pub mix: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for ReverbConfig {
@@ -468,6 +491,7 @@ impl Default for ReverbConfig {
             damping: 0.5,
             mix: 0.2,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
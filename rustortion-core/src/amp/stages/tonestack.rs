@@ -1,4 +1,5 @@
 use crate::amp::stages::Stage;
+use crate::amp::stages::trim::CommonStageParams;
 use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
@@ -186,6 +187,13 @@ impl Stage for ToneStackStage {
             _ => Err("Unknown parameter name"),
         }
     }
+
+    fn reset(&mut self) {
+        self.dc_hp = 0.0;
+        self.bass_lp = 0.0;
+        self.treble_lp = 0.0;
+        self.presence_lp = 0.0;
+    }
 }
 
 #[cfg(test)]
@@ -377,7 +385,7 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct ToneStackConfig {
     pub model: ToneStackModel,
     pub bass: f32,
@@ -386,6 +394,8 @@ pub struct ToneStackConfig {
     pub presence: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for ToneStackConfig {
@@ -397,6 +407,7 @@ impl Default for ToneStackConfig {
             treble: 0.5,
             presence: 0.5,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
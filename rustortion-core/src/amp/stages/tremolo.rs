@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
 use crate::amp::stages::common::calculate_coefficient;
+use crate::amp::stages::trim::CommonStageParams;
 
 const MIN_RATE_HZ: f32 = 0.1;
 const MAX_RATE_HZ: f32 = 20.0;
@@ -308,13 +309,15 @@ mod tests {
 
 // --- Config ---
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct TremoloConfig {
     pub rate_hz: f32,
     pub depth: f32,
     pub shape: f32,
     #[serde(default)]
     pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
 }
 
 impl Default for TremoloConfig {
@@ -324,6 +327,7 @@ impl Default for TremoloConfig {
             depth: 0.5,
             shape: 0.0,
             bypassed: false,
+            common: CommonStageParams::default(),
         }
     }
 }
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::Stage;
+use crate::amp::stages::common::db_to_lin;
+
+/// Input and output trims go this far either side of unity.
+pub const MAX_TRIM_DB: f32 = 12.0;
+
+/// Gain trims and polarity every stage has, whatever its type. Flattened into
+/// each stage config; all-neutral values are left out of the saved JSON so
+/// untouched presets don't change on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommonStageParams {
+    #[serde(default, skip_serializing_if = "is_unity")]
+    pub in_trim_db: f32,
+    #[serde(default, skip_serializing_if = "is_unity")]
+    pub out_trim_db: f32,
+    /// Flip the output's polarity.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub invert: bool,
+    /// Keep the stage out of randomizing and templates, and across preset
+    /// switches if the user asks. Only the editor reads it; the running
+    /// stage doesn't.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub locked: bool,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes a reference
+const fn is_unity(db: &f32) -> bool {
+    *db == 0.0
+}
+
+impl CommonStageParams {
+    /// No trim and no polarity flip. A lock changes nothing heard.
+    pub fn is_neutral(&self) -> bool {
+        Self {
+            locked: false,
+            ..*self
+        } == Self::default()
+    }
+
+    fn in_gain(&self) -> f32 {
+        db_to_lin(self.in_trim_db)
+    }
+
+    /// Output trim with the polarity folded in, so inverting costs nothing.
+    fn out_gain(&self) -> f32 {
+        let gain = db_to_lin(self.out_trim_db);
+        if self.invert { -gain } else { gain }
+    }
+}
+
+/// Runs a stage between an input and an output gain. Every runtime stage is
+/// wrapped, so a trim never needs a rebuild; neutral trims cost two
+/// multiplies per sample and no branch.
+pub struct TrimmedStage {
+    inner: Box<dyn Stage>,
+    params: CommonStageParams,
+    in_gain: f32,
+    out_gain: f32,
+}
+
+impl TrimmedStage {
+    pub fn new(inner: Box<dyn Stage>, params: CommonStageParams) -> Self {
+        Self {
+            inner,
+            params,
+            in_gain: params.in_gain(),
+            out_gain: params.out_gain(),
+        }
+    }
+
+    fn update_gains(&mut self) {
+        self.in_gain = self.params.in_gain();
+        self.out_gain = self.params.out_gain();
+    }
+}
+
+fn switch_value(value: f32) -> Result<bool, &'static str> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value >= 0.5)
+    } else {
+        Err("Invert must be 0.0 or 1.0")
+    }
+}
+
+impl Stage for TrimmedStage {
+    fn process(&mut self, input: f32) -> f32 {
+        self.inner.process(input * self.in_gain) * self.out_gain
+    }
+
+    fn process_block(&mut self, input: &mut [f32]) {
+        for sample in input.iter_mut() {
+            *sample *= self.in_gain;
+        }
+        self.inner.process_block(input);
+        for sample in input.iter_mut() {
+            *sample *= self.out_gain;
+        }
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
+        match name {
+            "in_trim_db" | "out_trim_db" => {
+                if !(-MAX_TRIM_DB..=MAX_TRIM_DB).contains(&value) {
+                    return Err("Trim must be between -12 and 12 dB");
+                }
+                if name == "in_trim_db" {
+                    self.params.in_trim_db = value;
+                } else {
+                    self.params.out_trim_db = value;
+                }
+            }
+            "invert" => self.params.invert = switch_value(value)?,
+            _ => return self.inner.set_parameter(name, value),
+        }
+        self.update_gains();
+        Ok(())
+    }
+
+    fn get_parameter(&self, name: &str) -> Result<f32, &'static str> {
+        match name {
+            "in_trim_db" => Ok(self.params.in_trim_db),
+            "out_trim_db" => Ok(self.params.out_trim_db),
+            "invert" => Ok(if self.params.invert { 1.0 } else { 0.0 }),
+            _ => self.inner.get_parameter(name),
+        }
+    }
+
+    fn set_child_parameter(
+        &mut self,
+        child: usize,
+        name: &str,
+        value: f32,
+    ) -> Result<(), &'static str> {
+        self.inner.set_child_parameter(child, name, value)
+    }
+
+    fn set_tempo(&mut self, bpm: f32) {
+        self.inner.set_tempo(bpm);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn is_power_amp(&self) -> bool {
+        self.inner.is_power_amp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::level::LevelStage;
+
+    fn trimmed(params: CommonStageParams) -> TrimmedStage {
+        TrimmedStage::new(Box::new(LevelStage::new(1.0)), params)
+    }
+
+    #[test]
+    fn neutral_trims_pass_the_stage_through() {
+        let mut stage = trimmed(CommonStageParams::default());
+        assert_eq!(stage.process(0.25), 0.25);
+
+        let mut block = [0.5, -0.5, 0.125];
+        stage.process_block(&mut block);
+        assert_eq!(block, [0.5, -0.5, 0.125]);
+    }
+
+    #[test]
+    fn trims_and_invert_apply_around_the_stage() {
+        let mut stage = trimmed(CommonStageParams {
+            in_trim_db: 6.0,
+            out_trim_db: -6.0,
+            invert: true,
+            locked: false,
+        });
+        let out = stage.process(0.5);
+        assert!((out + 0.5).abs() < 1e-4, "got {out}");
+
+        stage.set_parameter("in_trim_db", 0.0).unwrap();
+        stage.set_parameter("out_trim_db", 0.0).unwrap();
+        stage.set_parameter("invert", 0.0).unwrap();
+        assert_eq!(stage.process(0.5), 0.5);
+    }
+
+    #[test]
+    fn invert_only_takes_a_switch_value() {
+        let mut stage = trimmed(CommonStageParams::default());
+        assert!(stage.set_parameter("invert", 2.0).is_err());
+        assert!(stage.set_parameter("invert", -1.0).is_err());
+        assert_eq!(stage.get_parameter("invert"), Ok(0.0));
+    }
+
+    #[test]
+    fn other_parameters_reach_the_wrapped_stage() {
+        let mut stage = trimmed(CommonStageParams::default());
+        stage.set_parameter("gain", 2.0).unwrap();
+        assert_eq!(stage.get_parameter("gain"), Ok(2.0));
+        assert_eq!(stage.process(0.25), 0.5);
+        assert!(stage.set_parameter("in_trim_db", 13.0).is_err());
+    }
+
+    #[test]
+    fn neutral_params_are_not_serialized() {
+        let json = serde_json::to_string(&CommonStageParams::default()).unwrap();
+        assert_eq!(json, "{}");
+
+        let params: CommonStageParams = serde_json::from_str("{}").unwrap();
+        assert!(params.is_neutral());
+    }
+}
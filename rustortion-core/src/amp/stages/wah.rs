@@ -0,0 +1,442 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::Stage;
+use crate::amp::stages::common::calculate_coefficient;
+use crate::amp::stages::trim::CommonStageParams;
+
+/// Bottom of the sweep (heel down).
+const MIN_FREQ_HZ: f32 = 400.0;
+/// Top of the sweep (toe down).
+const MAX_FREQ_HZ: f32 = 2200.0;
+
+const MIN_Q: f32 = 0.5;
+const MAX_Q: f32 = 10.0;
+
+const MIN_ATTACK_MS: f32 = 1.0;
+const MAX_ATTACK_MS: f32 = 100.0;
+const MIN_RELEASE_MS: f32 = 10.0;
+const MAX_RELEASE_MS: f32 = 1000.0;
+
+/// One-pole smoothing time for the center frequency. Short enough that a
+/// pedal sweep feels immediate, long enough to hide parameter steps (the GUI
+/// and MIDI CCs both deliver position in coarse increments).
+const FREQ_SMOOTH_MS: f32 = 5.0;
+
+/// Envelope level (linear peak) that drives the auto-wah to the top of its
+/// range at full sensitivity. A hard-picked guitar DI sits around here.
+const ENVELOPE_FULL_SCALE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WahMode {
+    /// Sweep follows `position` — meant to be driven by an expression pedal.
+    #[default]
+    Manual,
+    /// An envelope follower on the input drives the sweep upward from `position`.
+    Auto,
+    /// Parked ("cocked") wah: `position` sets a static filter, no smoothing.
+    Fixed,
+}
+
+impl std::fmt::Display for WahMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Manual => write!(f, "Manual"),
+            Self::Auto => write!(f, "Auto"),
+            Self::Fixed => write!(f, "Fixed"),
+        }
+    }
+}
+
+/// Map a sweep position in `[0, 1]` onto the exponential wah range.
+fn position_to_freq(position: f32) -> f32 {
+    MIN_FREQ_HZ * (MAX_FREQ_HZ / MIN_FREQ_HZ).powf(position.clamp(0.0, 1.0))
+}
+
+/// `tan(x)` for the small arguments a wah sees (`x = pi * fc / fs < 0.16` at
+/// 44.1 kHz). The fifth-order Taylor series is within 1e-5 there and avoids a
+/// libm call per sample.
+#[inline]
+fn tan_approx(x: f32) -> f32 {
+    let x2 = x * x;
+    x * (2.0 / 15.0f32).mul_add(x2 * x2, (1.0 / 3.0f32).mul_add(x2, 1.0))
+}
+
+/// Wah — a resonant bandpass swept between ~400 Hz and 2.2 kHz.
+///
+/// Implemented as a topology-preserving-transform state-variable filter, which
+/// stays stable when its cutoff moves every sample. The center frequency is
+/// one-pole smoothed toward its target per sample (except in `Fixed` mode), so
+/// fast sweeps don't zipper. The bandpass output is normalised to unity gain
+/// at the center frequency.
+pub struct WahStage {
+    mode: WahMode,
+    position: f32,
+    q: f32,
+    sensitivity: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    sample_rate: f32,
+
+    // Filter state
+    ic1eq: f32,
+    ic2eq: f32,
+
+    // Smoothed center frequency
+    freq_smoothed: f32,
+    freq_coeff: f32,
+
+    // Envelope follower
+    envelope: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+}
+
+impl WahStage {
+    pub fn new(
+        mode: WahMode,
+        position: f32,
+        q: f32,
+        sensitivity: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        sample_rate: f32,
+    ) -> Self {
+        let position = position.clamp(0.0, 1.0);
+        let attack_ms = attack_ms.clamp(MIN_ATTACK_MS, MAX_ATTACK_MS);
+        let release_ms = release_ms.clamp(MIN_RELEASE_MS, MAX_RELEASE_MS);
+
+        Self {
+            mode,
+            position,
+            q: q.clamp(MIN_Q, MAX_Q),
+            sensitivity: sensitivity.clamp(0.0, 1.0),
+            attack_ms,
+            release_ms,
+            sample_rate,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+            freq_smoothed: position_to_freq(position),
+            freq_coeff: calculate_coefficient(FREQ_SMOOTH_MS, sample_rate),
+            envelope: 0.0,
+            attack_coeff: calculate_coefficient(attack_ms, sample_rate),
+            release_coeff: calculate_coefficient(release_ms, sample_rate),
+        }
+    }
+
+    /// Center frequency the filter is currently tuned to, in Hz.
+    pub const fn center_frequency(&self) -> f32 {
+        self.freq_smoothed
+    }
+
+    fn follow_envelope(&mut self, input: f32) {
+        let rectified = input.abs();
+        let coeff = if rectified > self.envelope {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+        self.envelope = coeff.mul_add(self.envelope, (1.0 - coeff) * rectified);
+    }
+
+    fn target_frequency(&self) -> f32 {
+        match self.mode {
+            WahMode::Manual | WahMode::Fixed => position_to_freq(self.position),
+            WahMode::Auto => {
+                let sweep = self.sensitivity * self.envelope / ENVELOPE_FULL_SCALE;
+                position_to_freq(self.position + sweep)
+            }
+        }
+    }
+}
+
+impl Stage for WahStage {
+    fn process(&mut self, input: f32) -> f32 {
+        if self.mode == WahMode::Auto {
+            self.follow_envelope(input);
+        }
+
+        let target = self.target_frequency();
+        self.freq_smoothed = if self.mode == WahMode::Fixed {
+            target
+        } else {
+            self.freq_coeff
+                .mul_add(self.freq_smoothed, (1.0 - self.freq_coeff) * target)
+        };
+
+        // TPT state-variable filter (Zavalishin), bandpass tap.
+        let g = tan_approx(PI * self.freq_smoothed / self.sample_rate);
+        let k = 1.0 / self.q;
+        let a1 = 1.0 / g.mul_add(g + k, 1.0);
+        let a2 = g * a1;
+
+        let v3 = input - self.ic2eq;
+        let v1 = a1.mul_add(self.ic1eq, a2 * v3);
+        let v2 = g.mul_add(v1, self.ic2eq);
+        self.ic1eq = 2.0f32.mul_add(v1, -self.ic1eq);
+        self.ic2eq = 2.0f32.mul_add(v2, -self.ic2eq);
+
+        // `v1` peaks at `Q` at the center frequency; scale back to unity.
+        k * v1
+    }
+
+    fn set_parameter(&mut self, name: &str, value: f32) -> Result<(), &'static str> {
+        match name {
+            "position" => {
+                if (0.0..=1.0).contains(&value) {
+                    self.position = value;
+                    Ok(())
+                } else {
+                    Err("Position must be between 0.0 and 1.0")
+                }
+            }
+            "q" => {
+                if (MIN_Q..=MAX_Q).contains(&value) {
+                    self.q = value;
+                    Ok(())
+                } else {
+                    Err("Q must be between 0.5 and 10.0")
+                }
+            }
+            "sensitivity" => {
+                if (0.0..=1.0).contains(&value) {
+                    self.sensitivity = value;
+                    Ok(())
+                } else {
+                    Err("Sensitivity must be between 0.0 and 1.0")
+                }
+            }
+            "attack" => {
+                if (MIN_ATTACK_MS..=MAX_ATTACK_MS).contains(&value) {
+                    self.attack_ms = value;
+                    self.attack_coeff = calculate_coefficient(value, self.sample_rate);
+                    Ok(())
+                } else {
+                    Err("Attack must be between 1 ms and 100 ms")
+                }
+            }
+            "release" => {
+                if (MIN_RELEASE_MS..=MAX_RELEASE_MS).contains(&value) {
+                    self.release_ms = value;
+                    self.release_coeff = calculate_coefficient(value, self.sample_rate);
+                    Ok(())
+                } else {
+                    Err("Release must be between 10 ms and 1000 ms")
+                }
+            }
+            _ => Err("Unknown parameter"),
+        }
+    }
+
+    fn get_parameter(&self, name: &str) -> Result<f32, &'static str> {
+        match name {
+            "position" => Ok(self.position),
+            "q" => Ok(self.q),
+            "sensitivity" => Ok(self.sensitivity),
+            "attack" => Ok(self.attack_ms),
+            "release" => Ok(self.release_ms),
+            _ => Err("Unknown parameter"),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.ic1eq = 0.0;
+        self.ic2eq = 0.0;
+        self.freq_smoothed = position_to_freq(self.position);
+        self.envelope = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+    const TOL: f32 = 1e-3;
+
+    fn manual_wah(position: f32, q: f32) -> WahStage {
+        WahStage::new(WahMode::Manual, position, q, 0.5, 10.0, 200.0, SAMPLE_RATE)
+    }
+
+    /// Steady-state peak output amplitude of a fresh wah fed a unit sine.
+    fn tone_gain(position: f32, freq: f32) -> f32 {
+        let mut wah = manual_wah(position, 5.0);
+        let settle = 2048;
+        let measure = 2048;
+        let mut peak = 0.0f32;
+        for i in 0..(settle + measure) {
+            let x = (2.0 * PI * freq * i as f32 / SAMPLE_RATE).sin();
+            let y = wah.process(x);
+            if i >= settle {
+                peak = peak.max(y.abs());
+            }
+        }
+        peak
+    }
+
+    #[test]
+    fn center_frequency_tracks_position() {
+        // Stepped log sweep (0.5% steps) from 300 Hz to 3 kHz; the loudest
+        // tone marks the measured center frequency.
+        for &position in &[0.0, 0.25, 0.5, 0.75, 1.0] {
+            let expected = position_to_freq(position);
+
+            let mut best_freq = 0.0;
+            let mut best_gain = 0.0;
+            let mut freq = 300.0f32;
+            while freq < 3000.0 {
+                let gain = tone_gain(position, freq);
+                if gain > best_gain {
+                    best_gain = gain;
+                    best_freq = freq;
+                }
+                freq *= 1.005;
+            }
+
+            let error = (best_freq - expected).abs() / expected;
+            assert!(
+                error < 0.03,
+                "position {position}: measured {best_freq:.1} Hz, expected {expected:.1} Hz"
+            );
+            assert!(
+                (best_gain - 1.0).abs() < 0.05,
+                "peak gain should be ~unity, got {best_gain}"
+            );
+        }
+    }
+
+    #[test]
+    fn position_change_is_smoothed() {
+        let mut wah = manual_wah(0.0, 2.0);
+        wah.set_parameter("position", 1.0).unwrap();
+
+        wah.process(0.0);
+        let after_one = wah.center_frequency();
+        assert!(
+            after_one < MIN_FREQ_HZ * 1.1,
+            "center frequency should glide, jumped to {after_one}"
+        );
+
+        for _ in 0..(SAMPLE_RATE as usize / 10) {
+            wah.process(0.0);
+        }
+        assert!((wah.center_frequency() - MAX_FREQ_HZ).abs() < 1.0);
+    }
+
+    #[test]
+    fn fixed_mode_snaps_to_position() {
+        let mut wah = WahStage::new(WahMode::Fixed, 0.0, 2.0, 0.5, 10.0, 200.0, SAMPLE_RATE);
+        wah.set_parameter("position", 1.0).unwrap();
+        wah.process(0.0);
+        assert!((wah.center_frequency() - MAX_FREQ_HZ).abs() < 1.0);
+    }
+
+    #[test]
+    fn auto_wah_opens_with_input_level() {
+        let mut wah = WahStage::new(WahMode::Auto, 0.0, 2.0, 1.0, 5.0, 200.0, SAMPLE_RATE);
+        for _ in 0..1000 {
+            wah.process(0.0);
+        }
+        assert!((wah.center_frequency() - MIN_FREQ_HZ).abs() < 1.0);
+
+        for i in 0..4800 {
+            let x = 0.5 * (2.0 * PI * 220.0 * i as f32 / SAMPLE_RATE).sin();
+            wah.process(x);
+        }
+        assert!(
+            wah.center_frequency() > MIN_FREQ_HZ * 2.0,
+            "loud input should sweep the filter up, got {}",
+            wah.center_frequency()
+        );
+    }
+
+    #[test]
+    fn output_stays_finite_under_fast_sweeps() {
+        let mut wah = manual_wah(0.0, MAX_Q);
+        for i in 0..48000 {
+            if i % 64 == 0 {
+                let pos = if (i / 64) % 2 == 0 { 1.0 } else { 0.0 };
+                wah.set_parameter("position", pos).unwrap();
+            }
+            let x = (2.0 * PI * 1000.0 * i as f32 / SAMPLE_RATE).sin();
+            let y = wah.process(x);
+            assert!(y.is_finite() && y.abs() < 4.0, "unstable at {i}: {y}");
+        }
+    }
+
+    #[test]
+    fn parameter_validation() {
+        let mut wah = manual_wah(0.5, 2.0);
+
+        assert!(wah.set_parameter("position", -0.1).is_err());
+        assert!(wah.set_parameter("position", 1.1).is_err());
+        assert!(wah.set_parameter("position", 0.3).is_ok());
+
+        assert!(wah.set_parameter("q", 0.1).is_err());
+        assert!(wah.set_parameter("q", 11.0).is_err());
+        assert!(wah.set_parameter("q", 4.0).is_ok());
+
+        assert!(wah.set_parameter("sensitivity", 1.5).is_err());
+        assert!(wah.set_parameter("attack", 0.5).is_err());
+        assert!(wah.set_parameter("release", 5000.0).is_err());
+
+        assert!(wah.set_parameter("unknown", 0.0).is_err());
+        assert!((wah.get_parameter("q").unwrap() - 4.0).abs() < TOL);
+        assert!(wah.get_parameter("unknown").is_err());
+    }
+
+    #[test]
+    fn default_config() {
+        let cfg = WahConfig::default();
+        assert_eq!(cfg.mode, WahMode::Manual);
+        assert!((cfg.position - 0.5).abs() < TOL);
+        assert!(!cfg.bypassed);
+    }
+}
+
+// --- Config ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WahConfig {
+    #[serde(default)]
+    pub mode: WahMode,
+    pub position: f32,
+    pub q: f32,
+    pub sensitivity: f32,
+    pub attack_ms: f32,
+    pub release_ms: f32,
+    #[serde(default)]
+    pub bypassed: bool,
+    #[serde(flatten)]
+    pub common: CommonStageParams,
+}
+
+impl Default for WahConfig {
+    fn default() -> Self {
+        Self {
+            mode: WahMode::Manual,
+            position: 0.5,
+            q: 4.0,
+            sensitivity: 0.5,
+            attack_ms: 10.0,
+            release_ms: 200.0,
+            bypassed: false,
+            common: CommonStageParams::default(),
+        }
+    }
+}
+
+impl WahConfig {
+    pub fn to_stage(&self, sample_rate: f32) -> WahStage {
+        WahStage::new(
+            self.mode,
+            self.position,
+            self.q,
+            self.sensitivity,
+            self.attack_ms,
+            self.release_ms,
+            sample_rate,
+        )
+    }
+}
@@ -0,0 +1,280 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::reverb::{AllpassFilter, CombFilter, scale_delay};
+
+// Shorter than the chain reverb's Freeverb tank: a spring-and-room glue, not a hall.
+const COMB_DELAYS: [usize; 4] = [1116, 1277, 1422, 1557];
+// The side allpasses use the Freeverb stereo spread (+23 samples) so the
+// two channels diffuse differently.
+const MID_ALLPASS_DELAYS: [usize; 2] = [556, 341];
+const SIDE_ALLPASS_DELAYS: [usize; 2] = [579, 364];
+
+const INPUT_GAIN: f32 = 0.1;
+const WET_GAIN: f32 = 1.0;
+
+const MIN_FEEDBACK: f32 = 0.68;
+const FEEDBACK_RANGE: f32 = 0.25;
+const MAX_DAMP: f32 = 0.5;
+const MIN_TONE_HZ: f32 = 1000.0;
+const MAX_TONE_HZ: f32 = 8000.0;
+
+/// Settings for the post-IR ambience, saved with each preset. All three are
+/// in `0.0..=1.0`; an `amount` of zero is off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbienceConfig {
+    /// Wet level added on top of the dry signal.
+    pub amount: f32,
+    /// Tail length, from a small room to a long spring.
+    pub decay: f32,
+    /// Brightness of the tail.
+    pub tone: f32,
+}
+
+impl Default for AmbienceConfig {
+    fn default() -> Self {
+        Self::OFF
+    }
+}
+
+impl AmbienceConfig {
+    /// No ambience, with decay and tone left mid-way for when it's raised.
+    pub const OFF: Self = Self {
+        amount: 0.0,
+        decay: 0.4,
+        tone: 0.5,
+    };
+
+    pub const fn is_off(&self) -> bool {
+        self.amount <= 0.0
+    }
+
+    const fn clamped(self) -> Self {
+        Self {
+            amount: self.amount.clamp(0.0, 1.0),
+            decay: self.decay.clamp(0.0, 1.0),
+            tone: self.tone.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// Small stereo reverb run on the engine output after the IR cabinet.
+///
+/// One comb tank feeds both channels. The mid tail is the combs' sum and the
+/// side tail their alternating-sign sum, each through its own allpasses, and
+/// the output is left = mid + side, right = mid − side. Summed to mono the
+/// side cancels exactly, so the channels differ without any comb filtering
+/// in mono.
+pub struct Ambience {
+    config: AmbienceConfig,
+    sample_rate: f32,
+    combs: [CombFilter; 4],
+    mid_allpasses: [AllpassFilter; 2],
+    side_allpasses: [AllpassFilter; 2],
+    tone_coeff: f32,
+    tone_state: f32,
+}
+
+impl Ambience {
+    pub fn new(config: AmbienceConfig, sample_rate: usize) -> Self {
+        let sample_rate = sample_rate as f32;
+        let mut ambience = Self {
+            config: AmbienceConfig::default(),
+            sample_rate,
+            combs: COMB_DELAYS.map(|d| CombFilter::new(scale_delay(d, sample_rate))),
+            mid_allpasses: MID_ALLPASS_DELAYS
+                .map(|d| AllpassFilter::new(scale_delay(d, sample_rate))),
+            side_allpasses: SIDE_ALLPASS_DELAYS
+                .map(|d| AllpassFilter::new(scale_delay(d, sample_rate))),
+            tone_coeff: 1.0,
+            tone_state: 0.0,
+        };
+        ambience.set_config(config);
+        ambience
+    }
+
+    pub const fn config(&self) -> AmbienceConfig {
+        self.config
+    }
+
+    /// Takes effect from the next sample. Turning it off clears the tail, so
+    /// turning it back on doesn't replay what was ringing before.
+    pub fn set_config(&mut self, config: AmbienceConfig) {
+        let config = config.clamped();
+        if config.is_off() && !self.config.is_off() {
+            self.reset();
+        }
+        self.config = config;
+
+        let feedback = config.decay.mul_add(FEEDBACK_RANGE, MIN_FEEDBACK);
+        let damp1 = (1.0 - config.tone) * MAX_DAMP;
+        for comb in &mut self.combs {
+            comb.set_feedback(feedback);
+            comb.set_damp(damp1, 1.0 - damp1);
+        }
+
+        let cutoff_hz = MIN_TONE_HZ * (MAX_TONE_HZ / MIN_TONE_HZ).powf(config.tone);
+        self.tone_coeff = 1.0 - (-2.0 * PI * cutoff_hz / self.sample_rate).exp();
+    }
+
+    /// Add the mid tail to `mid` in place and write the side tail to `side`,
+    /// when there is one. Off, `mid` is untouched and `side` is silence.
+    pub fn process_block(&mut self, mid: &mut [f32], mut side: Option<&mut [f32]>) {
+        if self.config.is_off() {
+            if let Some(side) = side {
+                side.fill(0.0);
+            }
+            return;
+        }
+
+        let wet_gain = self.config.amount * WET_GAIN;
+        for (i, sample) in mid.iter_mut().enumerate() {
+            self.tone_state = self
+                .tone_coeff
+                .mul_add(*sample - self.tone_state, self.tone_state);
+            let input = self.tone_state * INPUT_GAIN;
+
+            let mut mid_tail = 0.0;
+            let mut side_tail = 0.0;
+            for (n, comb) in self.combs.iter_mut().enumerate() {
+                let out = comb.process(input);
+                mid_tail += out;
+                side_tail += if n % 2 == 0 { out } else { -out };
+            }
+            for allpass in &mut self.mid_allpasses {
+                mid_tail = allpass.process(mid_tail);
+            }
+            for allpass in &mut self.side_allpasses {
+                side_tail = allpass.process(side_tail);
+            }
+
+            *sample = wet_gain.mul_add(mid_tail, *sample);
+            if let Some(side) = side.as_deref_mut()
+                && let Some(s) = side.get_mut(i)
+            {
+                *s = wet_gain * side_tail;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        for comb in &mut self.combs {
+            comb.reset();
+        }
+        for allpass in self
+            .mid_allpasses
+            .iter_mut()
+            .chain(self.side_allpasses.iter_mut())
+        {
+            allpass.reset();
+        }
+        self.tone_state = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::common::db_to_lin;
+
+    const SAMPLE_RATE: usize = 48000;
+
+    /// Deterministic noise in `-0.5..0.5`.
+    fn noise(len: usize) -> Vec<f32> {
+        crate::test_util::noise(0x1234_5678, len)
+            .into_iter()
+            .map(|s| s * 0.5)
+            .collect()
+    }
+
+    fn rms(buf: &[f32]) -> f32 {
+        (buf.iter().map(|s| s * s).sum::<f32>() / buf.len() as f32).sqrt()
+    }
+
+    /// Run `input` through in 128-sample blocks, as the engine would, and
+    /// return the (left, right) pair.
+    fn render(config: AmbienceConfig, input: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        let mut ambience = Ambience::new(config, SAMPLE_RATE);
+        let mut mid = input.to_vec();
+        let mut side = vec![0.0; input.len()];
+        for (m, s) in mid.chunks_mut(128).zip(side.chunks_mut(128)) {
+            ambience.process_block(m, Some(s));
+        }
+        let left = mid.iter().zip(&side).map(|(m, s)| m + s).collect();
+        let right = mid.iter().zip(&side).map(|(m, s)| m - s).collect();
+        (left, right)
+    }
+
+    fn on() -> AmbienceConfig {
+        AmbienceConfig {
+            amount: 1.0,
+            decay: 0.8,
+            tone: 0.5,
+        }
+    }
+
+    #[test]
+    fn off_passes_dry_and_identical_channels() {
+        let input = noise(4800);
+        let (left, right) = render(AmbienceConfig::default(), &input);
+        assert_eq!(left, input);
+        assert_eq!(right, input);
+    }
+
+    #[test]
+    fn channels_differ_when_on() {
+        let input = noise(SAMPLE_RATE);
+        let (left, right) = render(on(), &input);
+
+        let side: Vec<f32> = left.iter().zip(&right).map(|(l, r)| l - r).collect();
+        assert!(
+            rms(&side) > 0.05 * rms(&left),
+            "left and right should differ: side RMS {}",
+            rms(&side)
+        );
+    }
+
+    #[test]
+    fn mono_sum_keeps_its_level() {
+        let input = noise(SAMPLE_RATE);
+        let mono = |(left, right): (Vec<f32>, Vec<f32>)| -> Vec<f32> {
+            left.iter()
+                .zip(&right)
+                .map(|(l, r)| 0.5 * (l + r))
+                .collect()
+        };
+
+        let dry = rms(&mono(render(AmbienceConfig::default(), &input)));
+        for decay in [0.0, 0.5, 1.0] {
+            for tone in [0.0, 1.0] {
+                let config = AmbienceConfig {
+                    decay,
+                    tone,
+                    ..on()
+                };
+                let wet = rms(&mono(render(config, &input)));
+                // Summing must not cancel anything (no phasey drop below the
+                // dry level) and the tail must not swamp the guitar.
+                assert!(
+                    wet >= dry * db_to_lin(-0.5) && wet <= dry * db_to_lin(6.0),
+                    "mono sum at decay {decay}, tone {tone}: {wet} vs dry {dry}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn turning_off_clears_the_tail() {
+        let mut ambience = Ambience::new(on(), SAMPLE_RATE);
+        let mut block = noise(4800);
+        ambience.process_block(&mut block, None);
+
+        ambience.set_config(AmbienceConfig::default());
+        ambience.set_config(on());
+        let mut silence = vec![0.0; 256];
+        ambience.process_block(&mut silence, None);
+        assert!(silence.iter().all(|&s| s == 0.0));
+    }
+}
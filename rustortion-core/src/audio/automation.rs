@@ -0,0 +1,171 @@
+//! Parameter automation captured alongside a recording.
+//!
+//! While a take is recording, every parameter change sent to the engine is
+//! logged with the engine frame it takes effect at. When the take stops the
+//! log is written as a JSON sidecar next to the WAV, together with the preset
+//! that was loaded when the take started, so the same moves can be replayed
+//! over the dry signal with [`crate::audio::offline::render_automation`].
+//!
+//! Nothing here runs on the RT thread: the log is kept by whoever sends the
+//! parameter changes, and timestamps come from the engine's frame counter
+//! ([`crate::audio::engine::EngineHandle::frames_processed`]).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::preset::Preset;
+
+/// Appended to the recording's file stem for the sidecar file.
+const SIDECAR_SUFFIX: &str = ".automation.json";
+
+/// One parameter change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutomationEvent {
+    /// Frame the change took effect at, counted from the first frame of the
+    /// take, at the engine (device) sample rate.
+    pub frame: u64,
+    pub stage: usize,
+    pub param: String,
+    pub value: f32,
+}
+
+/// The sidecar written next to a recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Automation {
+    pub sample_rate: usize,
+    /// The chain at the start of the take; stage indices refer to it.
+    pub preset: Preset,
+    /// In frame order.
+    pub events: Vec<AutomationEvent>,
+}
+
+impl Automation {
+    /// `take.wav` → `take.automation.json` in the same directory.
+    pub fn sidecar_path(recording: &Path) -> PathBuf {
+        let stem = recording
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        recording.with_file_name(format!("{stem}{SIDECAR_SUFFIX}"))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse automation file {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize automation")?;
+        fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Collects the parameter changes of one take, stamped with absolute engine
+/// frames. The take's first frame is only known once the engine has started
+/// the recorder, so events are rebased when the take is finished.
+#[derive(Debug)]
+pub struct AutomationLog {
+    recording: PathBuf,
+    sample_rate: usize,
+    preset: Preset,
+    events: Vec<AutomationEvent>,
+}
+
+impl AutomationLog {
+    pub const fn new(recording: PathBuf, sample_rate: usize, preset: Preset) -> Self {
+        Self {
+            recording,
+            sample_rate,
+            preset,
+            events: Vec::new(),
+        }
+    }
+
+    /// Log a change that takes effect at absolute engine frame `frame`.
+    pub fn push(&mut self, frame: u64, stage: usize, param: &str, value: f32) {
+        self.events.push(AutomationEvent {
+            frame,
+            stage,
+            param: param.to_owned(),
+            value,
+        });
+    }
+
+    /// Rebase the events onto the take's first frame and build the sidecar.
+    /// Changes made before the recorder started land on frame 0.
+    pub fn finish(self, take_start_frame: u64) -> (PathBuf, Automation) {
+        let mut events = self.events;
+        for event in &mut events {
+            event.frame = event.frame.saturating_sub(take_start_frame);
+        }
+        events.sort_by_key(|event| event.frame);
+
+        (
+            Automation::sidecar_path(&self.recording),
+            Automation {
+                sample_rate: self.sample_rate,
+                preset: self.preset,
+                events,
+            },
+        )
+    }
+}
+
+/// The engine takes parameter names as `&'static str`: a name read back from
+/// a sidecar is swapped for the stage's own, so nothing is leaked for it.
+/// `None` if the preset has no stage `stage` or it no parameter `name`.
+pub(crate) fn param_name(preset: &Preset, stage: usize, name: &str) -> Option<&'static str> {
+    preset
+        .stages
+        .get(stage)?
+        .parameter_names()
+        .into_iter()
+        .find(|known| *known == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::preamp::PreampConfig;
+    use crate::preset::StageConfig;
+
+    #[test]
+    fn sidecar_sits_next_to_the_recording() {
+        assert_eq!(
+            Automation::sidecar_path(Path::new("/takes/recording_1.wav")),
+            PathBuf::from("/takes/recording_1.automation.json")
+        );
+    }
+
+    #[test]
+    fn finish_rebases_and_sorts() {
+        let mut log = AutomationLog::new(PathBuf::from("take.wav"), 48_000, Preset::default());
+        log.push(1_256, 0, "gain", 0.5);
+        log.push(1_000, 1, "bass", 0.2);
+        log.push(1_128, 0, "gain", 0.4);
+
+        let (path, automation) = log.finish(1_128);
+        assert_eq!(path, PathBuf::from("take.automation.json"));
+        let frames: Vec<u64> = automation.events.iter().map(|e| e.frame).collect();
+        assert_eq!(frames, vec![0, 0, 128]);
+        assert_eq!(automation.events[2].param, "gain");
+    }
+
+    #[test]
+    fn sidecar_names_resolve_to_the_stages_own() {
+        let mut preset = Preset::default();
+        preset
+            .stages
+            .push(StageConfig::Preamp(PreampConfig::default()));
+
+        let name = param_name(&preset, 0, &String::from("gain")).unwrap();
+        assert_eq!(name, "gain");
+        assert_eq!(param_name(&preset, 0, "no such parameter"), None);
+        assert_eq!(param_name(&preset, 1, "gain"), None);
+    }
+}
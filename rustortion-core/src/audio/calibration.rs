@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+/// Where a calibrated interface's hardest note should peak. Leaves the chain
+/// the same headroom whatever interface the preset was made on.
+pub const REFERENCE_PEAK_DBFS: f32 = -12.0;
+
+/// How long the player is given to hit the note.
+pub const CAPTURE_DURATION: Duration = Duration::from_secs(4);
+
+/// A capture peaking below this heard nothing usable: wrong port, or a cable
+/// left unplugged.
+pub const MIN_SIGNAL_DBFS: f32 = -60.0;
+
+/// The trim never moves the input further than this either way.
+pub const MAX_TRIM_DB: f32 = 24.0;
+
+/// Trim that moves `peak_db` onto [`REFERENCE_PEAK_DBFS`], or `None` if the
+/// peak is too quiet to calibrate from.
+pub fn recommended_trim_db(peak_db: f32) -> Option<f32> {
+    (peak_db >= MIN_SIGNAL_DBFS)
+        .then(|| (REFERENCE_PEAK_DBFS - peak_db).clamp(-MAX_TRIM_DB, MAX_TRIM_DB))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CalibrationState {
+    Idle,
+    Capturing { started: Instant, peak_db: f32 },
+    Done { peak_db: f32, trim_db: f32 },
+    NoSignal { peak_db: f32 },
+}
+
+/// Input calibration capture: the loudest input meter reading over
+/// [`CAPTURE_DURATION`], turned into a recommended input trim.
+///
+/// Driven by the GUI's meter polling: feed each reading to [`Self::observe`].
+#[derive(Debug, Clone, Copy)]
+pub struct CalibrationCapture {
+    state: CalibrationState,
+}
+
+impl Default for CalibrationCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalibrationCapture {
+    pub const fn new() -> Self {
+        Self {
+            state: CalibrationState::Idle,
+        }
+    }
+
+    pub const fn state(&self) -> CalibrationState {
+        self.state
+    }
+
+    pub const fn is_capturing(&self) -> bool {
+        matches!(self.state, CalibrationState::Capturing { .. })
+    }
+
+    /// Start (or restart) a capture at `now`.
+    pub const fn start(&mut self, now: Instant) {
+        self.state = CalibrationState::Capturing {
+            started: now,
+            peak_db: f32::NEG_INFINITY,
+        };
+    }
+
+    pub const fn reset(&mut self) {
+        self.state = CalibrationState::Idle;
+    }
+
+    /// Record a meter reading taken at `now`. Finishes the capture once
+    /// [`CAPTURE_DURATION`] has passed; ignored unless capturing.
+    pub fn observe(&mut self, now: Instant, reading_db: f32) {
+        let CalibrationState::Capturing { started, peak_db } = self.state else {
+            return;
+        };
+        let peak_db = peak_db.max(reading_db);
+
+        self.state = if now.duration_since(started) < CAPTURE_DURATION {
+            CalibrationState::Capturing { started, peak_db }
+        } else {
+            recommended_trim_db(peak_db).map_or(CalibrationState::NoSignal { peak_db }, |trim_db| {
+                CalibrationState::Done { peak_db, trim_db }
+            })
+        };
+    }
+
+    /// Fraction of the capture window elapsed at `now`, `0.0..=1.0`.
+    pub fn progress(&self, now: Instant) -> f32 {
+        match self.state {
+            CalibrationState::Idle => 0.0,
+            CalibrationState::Capturing { started, .. } => {
+                (now.duration_since(started).as_secs_f32() / CAPTURE_DURATION.as_secs_f32())
+                    .min(1.0)
+            }
+            CalibrationState::Done { .. } | CalibrationState::NoSignal { .. } => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trim_puts_peak_on_reference() {
+        assert_eq!(recommended_trim_db(-3.0), Some(-9.0));
+        assert_eq!(recommended_trim_db(-30.0), Some(18.0));
+        assert_eq!(recommended_trim_db(REFERENCE_PEAK_DBFS), Some(0.0));
+    }
+
+    #[test]
+    fn trim_is_clamped_and_needs_signal() {
+        assert_eq!(recommended_trim_db(-55.0), Some(MAX_TRIM_DB));
+        assert_eq!(recommended_trim_db(-100.0), None);
+    }
+
+    #[test]
+    fn capture_keeps_loudest_reading_until_window_ends() {
+        let start = Instant::now();
+        let mut capture = CalibrationCapture::new();
+        capture.observe(start, -6.0);
+        assert_eq!(capture.state(), CalibrationState::Idle);
+
+        capture.start(start);
+        capture.observe(start + Duration::from_secs(1), -20.0);
+        capture.observe(start + Duration::from_secs(2), -6.0);
+        capture.observe(start + Duration::from_secs(3), -18.0);
+        assert!(capture.is_capturing());
+
+        capture.observe(start + CAPTURE_DURATION, -30.0);
+        assert_eq!(
+            capture.state(),
+            CalibrationState::Done {
+                peak_db: -6.0,
+                trim_db: -6.0
+            }
+        );
+    }
+
+    #[test]
+    fn silent_capture_reports_no_signal() {
+        let start = Instant::now();
+        let mut capture = CalibrationCapture::new();
+        capture.start(start);
+        capture.observe(start + CAPTURE_DURATION, -100.0);
+        assert_eq!(
+            capture.state(),
+            CalibrationState::NoSignal { peak_db: -100.0 }
+        );
+    }
+}
@@ -0,0 +1,602 @@
+use std::f64::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex;
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::common::db_to_lin;
+
+const FFT_SIZE: usize = 1024;
+const HOP_SIZE: usize = FFT_SIZE / 4; // 75% overlap
+/// Bins in a noise profile: one per FFT bin, DC to Nyquist.
+pub const NUM_BINS: usize = FFT_SIZE / 2 + 1;
+const OUTPUT_SIZE: usize = FFT_SIZE * 2;
+
+/// How long "Learn" listens to the background noise.
+pub const LEARN_DURATION: Duration = Duration::from_secs(2);
+/// A learn that hasn't finished by then never will: the engine stopped, or
+/// the tuner muted the input.
+pub const LEARN_TIMEOUT: Duration = Duration::from_secs(6);
+
+pub const MAX_REDUCTION_DB: f32 = 30.0;
+pub const DEFAULT_REDUCTION_DB: f32 = 12.0;
+/// The noise print is subtracted this many times over: the noise in any one
+/// frame swings well above its average, and what's left over would warble.
+const OVER_SUBTRACTION: f32 = 2.0;
+
+/// Average magnitude of the background noise in each FFT bin, as learned at
+/// `sample_rate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NoiseProfile {
+    pub sample_rate: u32,
+    pub magnitudes: Vec<f32>,
+}
+
+impl NoiseProfile {
+    /// Average level of the profile in dB, for showing one apart from
+    /// another.
+    pub fn level_db(&self) -> f32 {
+        let mean = self.magnitudes.iter().sum::<f32>() / self.magnitudes.len().max(1) as f32;
+        20.0 * mean.max(1e-12).log10()
+    }
+
+    /// The profile at the bins of an FFT at `sample_rate`, interpolated by
+    /// frequency; learned at another rate, it still describes the same hiss.
+    fn magnitudes_at(&self, sample_rate: usize) -> Vec<f32> {
+        let learned = &self.magnitudes;
+        if learned.is_empty() {
+            return vec![0.0; NUM_BINS];
+        }
+        if self.sample_rate as usize == sample_rate && learned.len() == NUM_BINS {
+            return learned.clone();
+        }
+
+        let last = learned.len() - 1;
+        // Bin k sits at k * rate / FFT_SIZE; the learned one at
+        // j * learned_rate / ((len - 1) * 2).
+        let scale = sample_rate as f32 / self.sample_rate.max(1) as f32 * last as f32
+            / (NUM_BINS - 1) as f32;
+        (0..NUM_BINS)
+            .map(|k| {
+                let source = k as f32 * scale;
+                let j = source.floor() as usize;
+                if j >= last {
+                    return learned[last];
+                }
+                let frac = source - j as f32;
+                (learned[j + 1] - learned[j]).mul_add(frac, learned[j])
+            })
+            .collect()
+    }
+}
+
+/// The input denoiser's settings, saved with the preset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DenoiseConfig {
+    pub bypassed: bool,
+    /// How far below the input the noise is pushed, in dB.
+    pub reduction_db: f32,
+    /// Learned from the rig's own noise; nothing is removed without one.
+    pub profile: Option<NoiseProfile>,
+}
+
+impl Default for DenoiseConfig {
+    fn default() -> Self {
+        Self::UNLEARNED
+    }
+}
+
+impl DenoiseConfig {
+    /// Nothing learned yet, so nothing removed.
+    pub const UNLEARNED: Self = Self {
+        bypassed: false,
+        reduction_db: DEFAULT_REDUCTION_DB,
+        profile: None,
+    };
+
+    /// Whether the engine should run a [`Denoiser`] for these settings.
+    pub const fn is_active(&self) -> bool {
+        !self.bypassed && self.reduction_db > 0.0 && self.profile.is_some()
+    }
+}
+
+/// Hann window, and the overlap-add scale that undoes it applied twice at
+/// [`HOP_SIZE`].
+fn window() -> (Vec<f32>, f32) {
+    let window: Vec<f32> = (0..FFT_SIZE)
+        .map(|i| 0.5 * (1.0 - (2.0 * PI * i as f64 / FFT_SIZE as f64).cos()) as f32)
+        .collect();
+    let num_overlaps = FFT_SIZE / HOP_SIZE;
+    let cola_sum: f32 = (0..HOP_SIZE)
+        .map(|i| {
+            (0..num_overlaps)
+                .map(|m| window[i + m * HOP_SIZE].powi(2))
+                .sum::<f32>()
+        })
+        .sum::<f32>()
+        / HOP_SIZE as f32;
+    let scale = 1.0 / (FFT_SIZE as f32 * cola_sum);
+    (window, scale)
+}
+
+/// Sliding STFT analysis: a windowed spectrum of the last [`FFT_SIZE`]
+/// samples every [`HOP_SIZE`].
+struct Analysis {
+    r2c: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    ring: Vec<f32>,
+    pos: usize,
+    hop_counter: usize,
+    frame: Vec<f32>,
+    spectrum: Vec<Complex<f32>>,
+    scratch: Vec<Complex<f32>>,
+}
+
+impl Analysis {
+    fn new(planner: &mut RealFftPlanner<f32>, window: Vec<f32>) -> Self {
+        let r2c = planner.plan_fft_forward(FFT_SIZE);
+        let scratch = r2c.make_scratch_vec();
+        Self {
+            r2c,
+            window,
+            ring: vec![0.0; FFT_SIZE],
+            pos: 0,
+            hop_counter: 0,
+            frame: vec![0.0; FFT_SIZE],
+            spectrum: vec![Complex::new(0.0, 0.0); NUM_BINS],
+            scratch,
+        }
+    }
+
+    /// Feed one sample. Returns true when it completes a hop, with the new
+    /// frame's spectrum in `spectrum`.
+    fn push(&mut self, sample: f32) -> bool {
+        self.ring[self.pos] = sample;
+        self.pos = (self.pos + 1) % FFT_SIZE;
+        self.hop_counter += 1;
+        if self.hop_counter < HOP_SIZE {
+            return false;
+        }
+        self.hop_counter = 0;
+
+        for i in 0..FFT_SIZE {
+            self.frame[i] = self.ring[(self.pos + i) % FFT_SIZE] * self.window[i];
+        }
+        if self
+            .r2c
+            .process_with_scratch(&mut self.frame, &mut self.spectrum, &mut self.scratch)
+            .is_err()
+        {
+            // A silent frame keeps the hop timing of whatever follows.
+            self.spectrum.fill(Complex::new(0.0, 0.0));
+        }
+        true
+    }
+
+    fn reset(&mut self) {
+        self.ring.fill(0.0);
+        self.pos = 0;
+        self.hop_counter = 0;
+    }
+}
+
+/// Spectral-subtraction denoiser on the input, from a learned
+/// [`NoiseProfile`].
+///
+/// Each bin of each STFT frame keeps `1 - noise / magnitude` of itself, with
+/// the noise over-subtracted and the gain held above a floor set by the
+/// reduction. Bins well above the noise, the notes, pass nearly untouched;
+/// bins at the noise drop to the floor.
+///
+/// Adds [`Self::latency_samples`] of latency (≈21 ms at 48 kHz).
+pub struct Denoiser {
+    analysis: Analysis,
+    c2r: Arc<dyn ComplexToReal<f32>>,
+    c2r_scratch: Vec<Complex<f32>>,
+    /// The profile at this rate's bins, over-subtraction included.
+    noise: Vec<f32>,
+    floor: f32,
+    synth_frame: Vec<f32>,
+    output_accum: Vec<f32>,
+    output_read: usize,
+    output_write: usize,
+    output_scale: f32,
+}
+
+impl Denoiser {
+    pub fn new(profile: &NoiseProfile, reduction_db: f32, sample_rate: usize) -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let c2r = planner.plan_fft_inverse(FFT_SIZE);
+        let c2r_scratch = c2r.make_scratch_vec();
+        let (window, output_scale) = window();
+
+        let mut noise = profile.magnitudes_at(sample_rate);
+        for n in &mut noise {
+            *n *= OVER_SUBTRACTION;
+        }
+
+        let mut denoiser = Self {
+            analysis: Analysis::new(&mut planner, window),
+            c2r,
+            c2r_scratch,
+            noise,
+            floor: 1.0,
+            synth_frame: vec![0.0; FFT_SIZE],
+            output_accum: vec![0.0; OUTPUT_SIZE],
+            output_read: 0,
+            output_write: HOP_SIZE,
+            output_scale,
+        };
+        denoiser.set_reduction_db(reduction_db);
+        denoiser
+    }
+
+    /// Build the denoiser for `config`, or `None` if it has nothing to do.
+    pub fn for_config(config: &DenoiseConfig, sample_rate: usize) -> Option<Box<Self>> {
+        if !config.is_active() {
+            return None;
+        }
+        let profile = config.profile.as_ref()?;
+        Some(Box::new(Self::new(
+            profile,
+            config.reduction_db,
+            sample_rate,
+        )))
+    }
+
+    pub fn set_reduction_db(&mut self, reduction_db: f32) {
+        self.floor = db_to_lin(-reduction_db.clamp(0.0, MAX_REDUCTION_DB));
+    }
+
+    /// Delay from input to output: a sample is heard once the last frame
+    /// covering it has been overlap-added.
+    pub const fn latency_samples(&self) -> usize {
+        FFT_SIZE
+    }
+
+    /// Pick up where `previous` left off, so swapping in new settings
+    /// doesn't restart the frames mid-note. Copies only; never allocates.
+    pub fn continue_from(&mut self, previous: &Self) {
+        self.analysis.ring.copy_from_slice(&previous.analysis.ring);
+        self.analysis.pos = previous.analysis.pos;
+        self.analysis.hop_counter = previous.analysis.hop_counter;
+        self.output_accum.copy_from_slice(&previous.output_accum);
+        self.output_read = previous.output_read;
+        self.output_write = previous.output_write;
+    }
+
+    pub fn process_block(&mut self, data: &mut [f32]) {
+        for sample in data.iter_mut() {
+            if self.analysis.push(*sample) {
+                self.process_frame();
+            }
+
+            *sample = self.output_accum[self.output_read];
+            self.output_accum[self.output_read] = 0.0;
+            self.output_read = (self.output_read + 1) % OUTPUT_SIZE;
+        }
+    }
+
+    fn process_frame(&mut self) {
+        let spectrum = &mut self.analysis.spectrum;
+        for (bin, noise) in spectrum.iter_mut().zip(&self.noise) {
+            let magnitude = bin.norm();
+            let gain = if magnitude > 0.0 {
+                (1.0 - noise / magnitude).max(self.floor)
+            } else {
+                self.floor
+            };
+            *bin *= gain;
+        }
+        spectrum[0].im = 0.0;
+        spectrum[NUM_BINS - 1].im = 0.0;
+
+        if self
+            .c2r
+            .process_with_scratch(spectrum, &mut self.synth_frame, &mut self.c2r_scratch)
+            .is_ok()
+        {
+            let window = &self.analysis.window;
+            for i in 0..FFT_SIZE {
+                let pos = (self.output_write + i) % OUTPUT_SIZE;
+                self.output_accum[pos] = (self.synth_frame[i] * window[i])
+                    .mul_add(self.output_scale, self.output_accum[pos]);
+            }
+        }
+        // Advanced on error too, to stay aligned with the read position.
+        self.output_write = (self.output_write + HOP_SIZE) % OUTPUT_SIZE;
+    }
+
+    pub fn reset(&mut self) {
+        self.analysis.reset();
+        self.output_accum.fill(0.0);
+        self.output_read = 0;
+        self.output_write = HOP_SIZE;
+    }
+}
+
+/// What a [`NoiseCapture`] hands back to its [`NoiseLearn`]: the profile,
+/// written once, then `done` set.
+struct LearnShared {
+    sample_rate: usize,
+    magnitudes: Mutex<Vec<f32>>,
+    done: AtomicBool,
+}
+
+/// The RT side of a learn: averages the magnitude spectrum of the input for
+/// [`LEARN_DURATION`]. Built with every buffer it needs by
+/// [`NoiseLearn::start`].
+pub struct NoiseCapture {
+    analysis: Analysis,
+    sums: Vec<f32>,
+    /// Frames still to skip while the ring fills from silence.
+    warmup: usize,
+    frames: usize,
+    frames_wanted: usize,
+    shared: Arc<LearnShared>,
+}
+
+impl NoiseCapture {
+    /// Analyse `block`. Returns true once the capture is complete and its
+    /// profile has been handed over; later blocks are ignored.
+    pub fn feed(&mut self, block: &[f32]) -> bool {
+        if self.frames == self.frames_wanted {
+            return true;
+        }
+        for &sample in block {
+            if !self.analysis.push(sample) {
+                continue;
+            }
+            if self.warmup > 0 {
+                self.warmup -= 1;
+                continue;
+            }
+            for (sum, bin) in self.sums.iter_mut().zip(&self.analysis.spectrum) {
+                *sum += bin.norm();
+            }
+            self.frames += 1;
+            if self.frames == self.frames_wanted {
+                self.publish();
+                return true;
+            }
+        }
+        false
+    }
+
+    fn publish(&mut self) {
+        let frames = self.frames as f32;
+        for sum in &mut self.sums {
+            *sum /= frames;
+        }
+        // Uncontended: the GUI only locks it once `done` is set.
+        if let Ok(mut magnitudes) = self.shared.magnitudes.try_lock() {
+            magnitudes.copy_from_slice(&self.sums);
+            self.shared.done.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// The GUI side of a learn, for the countdown and the result.
+pub struct NoiseLearn {
+    shared: Arc<LearnShared>,
+    started: Instant,
+}
+
+impl NoiseLearn {
+    /// Start a learn at `sample_rate`, returning the capture to hand to the
+    /// engine along with it.
+    pub fn start(sample_rate: usize) -> (Self, Box<NoiseCapture>) {
+        let shared = Arc::new(LearnShared {
+            sample_rate,
+            magnitudes: Mutex::new(vec![0.0; NUM_BINS]),
+            done: AtomicBool::new(false),
+        });
+        let frames_wanted =
+            ((LEARN_DURATION.as_secs_f32() * sample_rate as f32 / HOP_SIZE as f32) as usize).max(1);
+        let mut planner = RealFftPlanner::<f32>::new();
+        let capture = NoiseCapture {
+            analysis: Analysis::new(&mut planner, window().0),
+            sums: vec![0.0; NUM_BINS],
+            warmup: FFT_SIZE / HOP_SIZE - 1,
+            frames: 0,
+            frames_wanted,
+            shared: Arc::clone(&shared),
+        };
+        let learn = Self {
+            shared,
+            started: Instant::now(),
+        };
+        (learn, Box::new(capture))
+    }
+
+    /// Time left on the countdown at `now`.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        LEARN_DURATION.saturating_sub(now.saturating_duration_since(self.started))
+    }
+
+    /// Whether it should have finished long ago, at `now`.
+    pub fn timed_out(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) > LEARN_TIMEOUT
+    }
+
+    /// The learned profile, once the capture has finished.
+    pub fn try_finish(&self) -> Option<NoiseProfile> {
+        if !self.shared.done.load(Ordering::Acquire) {
+            return None;
+        }
+        let magnitudes = self.shared.magnitudes.lock().ok()?.clone();
+        Some(NoiseProfile {
+            sample_rate: self.shared.sample_rate as u32,
+            magnitudes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+    const TONE_HZ: f32 = 1_000.0;
+    const TONE_AMPLITUDE: f32 = 0.5;
+    const NOISE_AMPLITUDE: f32 = 0.1;
+
+    /// Deterministic noise at `NOISE_AMPLITUDE`.
+    fn noise(seed: u32, len: usize) -> Vec<f32> {
+        crate::test_util::noise(seed, len)
+            .into_iter()
+            .map(|s| s * NOISE_AMPLITUDE)
+            .collect()
+    }
+
+    fn learn(input: &[f32]) -> NoiseProfile {
+        let (learn, mut capture) = NoiseLearn::start(SAMPLE_RATE);
+        for block in input.chunks(128) {
+            if capture.feed(block) {
+                break;
+            }
+        }
+        learn
+            .try_finish()
+            .expect("the capture should have finished")
+    }
+
+    fn tone(range: std::ops::Range<usize>) -> Vec<f32> {
+        range
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                TONE_AMPLITUDE * (std::f32::consts::TAU * TONE_HZ * t).sin()
+            })
+            .collect()
+    }
+
+    /// Amplitude of the tone in `signal`, and the power of everything else.
+    fn tone_and_residual(signal: &[f32], start: usize) -> (f32, f32) {
+        let (mut sin, mut cos) = (0.0f64, 0.0f64);
+        for (i, s) in signal.iter().enumerate() {
+            let phase = f64::from(std::f32::consts::TAU * TONE_HZ) * (start + i) as f64
+                / SAMPLE_RATE as f64;
+            sin += f64::from(*s) * phase.sin();
+            cos += f64::from(*s) * phase.cos();
+        }
+        let n = signal.len() as f64;
+        let (a, b) = (2.0 * sin / n, 2.0 * cos / n);
+        let residual = signal
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let phase = f64::from(std::f32::consts::TAU * TONE_HZ) * (start + i) as f64
+                    / SAMPLE_RATE as f64;
+                (f64::from(*s) - a.mul_add(phase.sin(), b * phase.cos())).powi(2)
+            })
+            .sum::<f64>()
+            / n;
+        (a.hypot(b) as f32, residual as f32)
+    }
+
+    fn db(power_ratio: f32) -> f32 {
+        10.0 * power_ratio.log10()
+    }
+
+    #[test]
+    fn learns_the_level_of_the_noise() {
+        let quiet = learn(&noise(1, 3 * SAMPLE_RATE));
+        let loud: Vec<f32> = noise(1, 3 * SAMPLE_RATE).iter().map(|s| s * 4.0).collect();
+        let loud = learn(&loud);
+        assert_eq!(quiet.magnitudes.len(), NUM_BINS);
+        assert!(quiet.magnitudes.iter().all(|m| m.is_finite() && *m > 0.0));
+        let ratio = loud.magnitudes[100] / quiet.magnitudes[100];
+        assert!((ratio - 4.0).abs() < 0.01, "ratio {ratio}");
+    }
+
+    #[test]
+    fn a_learn_needs_the_whole_duration() {
+        let (learn, mut capture) = NoiseLearn::start(SAMPLE_RATE);
+        assert!(!capture.feed(&noise(1, SAMPLE_RATE)));
+        assert!(learn.try_finish().is_none());
+    }
+
+    #[test]
+    fn removes_the_noise_but_not_the_tone() {
+        let profile = learn(&noise(1, 3 * SAMPLE_RATE));
+        let mut denoiser = Denoiser::new(&profile, 20.0, SAMPLE_RATE);
+        let latency = denoiser.latency_samples();
+
+        let len = 3 * SAMPLE_RATE;
+        let clean = tone(0..len);
+        let hiss = noise(7, len);
+        let mut signal: Vec<f32> = clean.iter().zip(&hiss).map(|(t, n)| t + n).collect();
+        let input = signal.clone();
+        for block in signal.chunks_mut(128) {
+            denoiser.process_block(block);
+        }
+
+        // The last second, lined up with the input it came from.
+        let start = 2 * SAMPLE_RATE;
+        let (tone_in, noise_in) =
+            tone_and_residual(&input[start - latency..len - latency], start - latency);
+        let (tone_out, noise_out) = tone_and_residual(&signal[start..], start - latency);
+
+        let notch_db = 20.0 * (tone_in / tone_out).log10();
+        assert!(notch_db.abs() <= 1.0, "the tone moved by {notch_db} dB");
+
+        let snr_in = db(tone_in.powi(2) / 2.0 / noise_in);
+        let snr_out = db(tone_out.powi(2) / 2.0 / noise_out);
+        assert!(
+            snr_out - snr_in >= 10.0,
+            "SNR went from {snr_in} dB to {snr_out} dB"
+        );
+    }
+
+    #[test]
+    fn no_reduction_passes_the_input_through() {
+        let profile = learn(&noise(1, 3 * SAMPLE_RATE));
+        let mut denoiser = Denoiser::new(&profile, 0.0, SAMPLE_RATE);
+        let latency = denoiser.latency_samples();
+        let input = noise(3, SAMPLE_RATE);
+        let mut output = input.clone();
+        denoiser.process_block(&mut output);
+        for i in latency + FFT_SIZE..input.len() {
+            assert!((output[i] - input[i - latency]).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn a_profile_learned_at_another_rate_is_rescaled_by_frequency() {
+        let profile = NoiseProfile {
+            sample_rate: 96_000,
+            magnitudes: (0..NUM_BINS).map(|k| k as f32).collect(),
+        };
+        let at_48k = profile.magnitudes_at(48_000);
+        assert_eq!(at_48k.len(), NUM_BINS);
+        // 48 kHz's bin k is 96 kHz's bin k / 2.
+        assert!((at_48k[100] - 50.0).abs() < 1e-3);
+        assert!((at_48k[NUM_BINS - 1] - (NUM_BINS - 1) as f32 / 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn continuing_keeps_the_output_running() {
+        let profile = learn(&noise(1, 3 * SAMPLE_RATE));
+        let mut reference = Denoiser::new(&profile, 12.0, SAMPLE_RATE);
+        let mut swapped = Denoiser::new(&profile, 12.0, SAMPLE_RATE);
+        let input = tone(0..SAMPLE_RATE);
+        let (first, second) = input.split_at(SAMPLE_RATE / 2);
+
+        let mut expected = input.clone();
+        reference.process_block(&mut expected);
+
+        let mut output = first.to_vec();
+        swapped.process_block(&mut output);
+        let mut replacement = Denoiser::new(&profile, 12.0, SAMPLE_RATE);
+        replacement.continue_from(&swapped);
+        let mut rest = second.to_vec();
+        replacement.process_block(&mut rest);
+        output.extend(rest);
+
+        assert_eq!(output, expected);
+    }
+}
@@ -0,0 +1,375 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU64, AtomicUsize, Ordering};
+
+use serde::Serialize;
+
+/// Number of most recent engine messages kept in the diagnostics ring.
+pub const MESSAGE_HISTORY_LEN: usize = 16;
+
+/// Slot value for a ring entry that has never been written.
+const EMPTY_SLOT: u8 = 0;
+
+/// Compact, allocation-free tag for an `EngineMessage` that has been processed
+/// on the RT thread. Stored as a `u8` in the diagnostics ring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[repr(u8)]
+pub enum MessageKind {
+    SetAmpChain = 1,
+    SetInputFilters,
+    SetParameter,
+    ReplaceStage,
+    AddStage,
+    RemoveStage,
+    SwapStages,
+    StartRecording,
+    StopRecording,
+    SwapIrConvolver,
+    ClearIr,
+    SetIrBypass,
+    SetIrGain,
+    SetTunerEnabled,
+    SetTunerAlgorithm,
+    SetPitchShift,
+    SetStageBypassed,
+    SetSamplers,
+    SetInputTrim,
+    SetChildParameter,
+    SetAmbience,
+    SetTestSource,
+    SetDoubleTracker,
+    SetIrTrim,
+    SetSampleRate,
+    SetLoadGuard,
+    SetMetronomeSounds,
+    SetMonitorPoint,
+    SetChainMode,
+    Right,
+    ApplyPreset,
+    SetTilt,
+    SetMetronome,
+    SetStandby,
+    SetDenoiser,
+    LearnNoise,
+    CrossfadeStage,
+    SetTakePlayer,
+    Take,
+    QueueAmpChain,
+    HoldAmpChain,
+}
+
+impl MessageKind {
+    const ALL: [Self; 41] = [
+        Self::SetAmpChain,
+        Self::SetInputFilters,
+        Self::SetParameter,
+        Self::ReplaceStage,
+        Self::AddStage,
+        Self::RemoveStage,
+        Self::SwapStages,
+        Self::StartRecording,
+        Self::StopRecording,
+        Self::SwapIrConvolver,
+        Self::ClearIr,
+        Self::SetIrBypass,
+        Self::SetIrGain,
+        Self::SetTunerEnabled,
+        Self::SetTunerAlgorithm,
+        Self::SetPitchShift,
+        Self::SetStageBypassed,
+        Self::SetSamplers,
+        Self::SetInputTrim,
+        Self::SetChildParameter,
+        Self::SetAmbience,
+        Self::SetTestSource,
+        Self::SetDoubleTracker,
+        Self::SetIrTrim,
+        Self::SetSampleRate,
+        Self::SetLoadGuard,
+        Self::SetMetronomeSounds,
+        Self::SetMonitorPoint,
+        Self::SetChainMode,
+        Self::Right,
+        Self::ApplyPreset,
+        Self::SetTilt,
+        Self::SetMetronome,
+        Self::SetStandby,
+        Self::SetDenoiser,
+        Self::LearnNoise,
+        Self::CrossfadeStage,
+        Self::SetTakePlayer,
+        Self::Take,
+        Self::QueueAmpChain,
+        Self::HoldAmpChain,
+    ];
+
+    fn from_u8(value: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| *kind as u8 == value)
+    }
+}
+
+/// Shared state between the RT-side [`DiagnosticsProbe`] and the GUI-side
+/// [`DiagnosticsHandle`].
+///
+/// Everything is a plain atomic or a fixed-size slot so the RT thread never
+/// allocates or formats. All access is `Relaxed` apart from the message
+/// counter, which publishes the ring slot written just before it.
+struct DiagnosticsShared {
+    sample_rate: AtomicUsize,
+    buffer_size: AtomicUsize,
+    oversample_factor: AtomicU64,
+    ir_length: AtomicUsize,
+    ir_partitions: AtomicUsize,
+    messages_processed: AtomicU64,
+    non_finite_resets: AtomicU64,
+    reduced_quality: AtomicBool,
+    history: [AtomicU8; MESSAGE_HISTORY_LEN],
+}
+
+impl DiagnosticsShared {
+    fn new() -> Self {
+        Self {
+            sample_rate: AtomicUsize::new(0),
+            buffer_size: AtomicUsize::new(0),
+            oversample_factor: AtomicU64::new(1.0f64.to_bits()),
+            ir_length: AtomicUsize::new(0),
+            ir_partitions: AtomicUsize::new(0),
+            messages_processed: AtomicU64::new(0),
+            non_finite_resets: AtomicU64::new(0),
+            reduced_quality: AtomicBool::new(false),
+            history: std::array::from_fn(|_| AtomicU8::new(EMPTY_SLOT)),
+        }
+    }
+}
+
+/// RT-side writer, owned by the `Engine`.
+pub struct DiagnosticsProbe {
+    shared: Arc<DiagnosticsShared>,
+}
+
+/// GUI-side reader. Cheap to clone; take a [`Diagnostics`] snapshot at any time.
+#[derive(Clone)]
+pub struct DiagnosticsHandle {
+    shared: Arc<DiagnosticsShared>,
+}
+
+impl DiagnosticsProbe {
+    pub fn new() -> (Self, DiagnosticsHandle) {
+        let shared = Arc::new(DiagnosticsShared::new());
+
+        (
+            Self {
+                shared: Arc::clone(&shared),
+            },
+            DiagnosticsHandle { shared },
+        )
+    }
+
+    pub fn record_message(&self, kind: MessageKind) {
+        // Single writer (the RT thread), so a load/store pair is enough.
+        let count = self.shared.messages_processed.load(Ordering::Relaxed);
+        let slot = (count % MESSAGE_HISTORY_LEN as u64) as usize;
+        self.shared.history[slot].store(kind as u8, Ordering::Relaxed);
+        self.shared
+            .messages_processed
+            .store(count + 1, Ordering::Release);
+    }
+
+    /// Count a block in which NaN/Inf was caught and the chain was reset.
+    pub fn record_non_finite(&self) {
+        self.shared
+            .non_finite_resets
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Whether the engine is shedding load at reduced quality.
+    pub fn set_reduced_quality(&self, reduced: bool) {
+        self.shared
+            .reduced_quality
+            .store(reduced, Ordering::Relaxed);
+    }
+
+    pub fn set_buffer_size(&self, buffer_size: usize) {
+        self.shared
+            .buffer_size
+            .store(buffer_size, Ordering::Relaxed);
+    }
+
+    pub fn set_sampling(&self, sample_rate: usize, oversample_factor: f64) {
+        self.shared
+            .sample_rate
+            .store(sample_rate, Ordering::Relaxed);
+        self.shared
+            .oversample_factor
+            .store(oversample_factor.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn set_ir(&self, length: usize, partitions: usize) {
+        self.shared.ir_length.store(length, Ordering::Relaxed);
+        self.shared
+            .ir_partitions
+            .store(partitions, Ordering::Relaxed);
+    }
+}
+
+impl DiagnosticsHandle {
+    /// Whether the engine is shedding load at reduced quality right now.
+    /// Cheap enough to poll with the meters.
+    pub fn reduced_quality(&self) -> bool {
+        self.shared.reduced_quality.load(Ordering::Relaxed)
+    }
+
+    /// Read the engine-side diagnostics. Host stats, stage list and IR name are
+    /// not known to the engine and are left empty for the caller to fill in.
+    pub fn snapshot(&self) -> Diagnostics {
+        let shared = &self.shared;
+        let processed = shared.messages_processed.load(Ordering::Acquire);
+        let count = processed.min(MESSAGE_HISTORY_LEN as u64);
+
+        let recent_messages = (processed - count..processed)
+            .filter_map(|i| {
+                let slot = (i % MESSAGE_HISTORY_LEN as u64) as usize;
+                MessageKind::from_u8(shared.history[slot].load(Ordering::Relaxed))
+            })
+            .collect();
+
+        Diagnostics {
+            sample_rate: shared.sample_rate.load(Ordering::Relaxed),
+            buffer_size: shared.buffer_size.load(Ordering::Relaxed),
+            oversampling_factor: f64::from_bits(shared.oversample_factor.load(Ordering::Relaxed)),
+            stages: Vec::new(),
+            ir_name: None,
+            ir_length: shared.ir_length.load(Ordering::Relaxed),
+            ir_partitions: shared.ir_partitions.load(Ordering::Relaxed),
+            dsp_load: None,
+            xrun_count: None,
+            messages_processed: processed,
+            non_finite_resets: shared.non_finite_resets.load(Ordering::Relaxed),
+            reduced_quality: shared.reduced_quality.load(Ordering::Relaxed),
+            recent_messages,
+        }
+    }
+}
+
+/// Point-in-time diagnostic report, intended to be pasted into bug reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub sample_rate: usize,
+    pub buffer_size: usize,
+    pub oversampling_factor: f64,
+    pub stages: Vec<String>,
+    pub ir_name: Option<String>,
+    pub ir_length: usize,
+    pub ir_partitions: usize,
+    pub dsp_load: Option<f32>,
+    pub xrun_count: Option<u64>,
+    pub messages_processed: u64,
+    /// Blocks in which NaN/Inf was flushed and the chain reset.
+    pub non_finite_resets: u64,
+    /// Whether the engine was shedding load at reduced quality.
+    pub reduced_quality: bool,
+    /// Oldest first.
+    pub recent_messages: Vec<MessageKind>,
+}
+
+impl Diagnostics {
+    #[must_use]
+    pub fn with_stages(mut self, stages: Vec<String>) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    #[must_use]
+    pub fn with_ir_name(mut self, ir_name: Option<String>) -> Self {
+        self.ir_name = ir_name;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_host_stats(mut self, dsp_load: f32, xrun_count: u64) -> Self {
+        self.dsp_load = Some(dsp_load);
+        self.xrun_count = Some(xrun_count);
+        self
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::engine::Engine;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn test_ring_keeps_last_messages_in_order() {
+        let (probe, handle) = DiagnosticsProbe::new();
+
+        for _ in 0..MESSAGE_HISTORY_LEN {
+            probe.record_message(MessageKind::SetParameter);
+        }
+        probe.record_message(MessageKind::AddStage);
+        probe.record_message(MessageKind::RemoveStage);
+
+        let snapshot = handle.snapshot();
+        assert_eq!(snapshot.messages_processed, MESSAGE_HISTORY_LEN as u64 + 2);
+        assert_eq!(snapshot.recent_messages.len(), MESSAGE_HISTORY_LEN);
+        assert_eq!(
+            &snapshot.recent_messages[MESSAGE_HISTORY_LEN - 2..],
+            &[MessageKind::AddStage, MessageKind::RemoveStage]
+        );
+    }
+
+    #[test]
+    fn test_message_kind_round_trips_through_u8() {
+        for kind in MessageKind::ALL {
+            assert_eq!(MessageKind::from_u8(kind as u8), Some(kind));
+        }
+        assert_eq!(MessageKind::from_u8(EMPTY_SLOT), None);
+    }
+
+    #[test]
+    fn test_snapshot_serializes_while_engine_processes() {
+        let (mut engine, engine_handle, _rt_drop_rx) =
+            Engine::new_for_plugin(48_000, 128, None, 1.0).unwrap();
+        let diagnostics = engine.diagnostics();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let audio_thread = {
+            let running = Arc::clone(&running);
+            std::thread::spawn(move || {
+                let input = [0.1f32; 128];
+                let mut output = [0.0f32; 128];
+                while running.load(Ordering::Relaxed) {
+                    engine.process(&input, &mut output).unwrap();
+                }
+                engine
+            })
+        };
+
+        for i in 0..200 {
+            engine_handle.set_ir_gain(i as f32 / 200.0);
+            let json = diagnostics
+                .snapshot()
+                .with_stages(vec!["Level".to_string()])
+                .with_host_stats(0.5, 0)
+                .to_json()
+                .unwrap();
+            assert!(json.contains("\"sample_rate\": 48000"));
+        }
+
+        running.store(false, Ordering::Relaxed);
+        let mut engine = audio_thread.join().unwrap();
+
+        // Drain anything still queued so the final state is deterministic.
+        engine.process(&[0.0; 128], &mut [0.0; 128]).unwrap();
+
+        let snapshot = diagnostics.snapshot();
+        assert_eq!(snapshot.buffer_size, 128);
+        assert_eq!(
+            snapshot.recent_messages.last(),
+            Some(&MessageKind::SetIrGain)
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::f32::consts::TAU;
+
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::common::calculate_coefficient;
+
+/// Shortest and longest delay of the double: past the point where it fuses
+/// with the dry take, short of a slapback.
+pub const MIN_DELAY_MS: f32 = 12.0;
+pub const MAX_DELAY_MS: f32 = 25.0;
+/// Largest pitch wander of the double.
+pub const MAX_DETUNE_CENTS: f32 = 10.0;
+
+/// The detune is a slow sine on the delay time, like tape wow, rather than
+/// a true pitch shift.
+const LFO_HZ: f32 = 0.6;
+/// Room in the buffer for the LFO swing past [`MAX_DELAY_MS`].
+const MAX_SWING_MS: f32 = 2.0;
+const SMOOTH_TIME_MS: f32 = 50.0;
+
+/// Settings for the post-IR double tracker, saved with each preset. A `mix`
+/// of zero is off.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DoubleTrackerConfig {
+    /// Level of the doubled take, `0.0..=1.0`.
+    pub mix: f32,
+    /// How much of the double is spread to the sides rather than added to
+    /// the centre, `0.0..=1.0`. Only the centre part is heard in mono.
+    pub width: f32,
+    /// Delay of the double behind the dry take.
+    pub delay_ms: f32,
+    /// How far the double's pitch wanders either way, `0.0` for none.
+    pub detune_cents: f32,
+}
+
+impl Default for DoubleTrackerConfig {
+    fn default() -> Self {
+        Self::OFF
+    }
+}
+
+impl DoubleTrackerConfig {
+    /// No double, with the rest set up for when the mix is raised.
+    pub const OFF: Self = Self {
+        mix: 0.0,
+        width: 0.9,
+        delay_ms: 18.0,
+        detune_cents: 6.0,
+    };
+
+    pub const fn is_off(&self) -> bool {
+        self.mix <= 0.0
+    }
+
+    const fn clamped(self) -> Self {
+        Self {
+            mix: self.mix.clamp(0.0, 1.0),
+            width: self.width.clamp(0.0, 1.0),
+            delay_ms: self.delay_ms.clamp(MIN_DELAY_MS, MAX_DELAY_MS),
+            detune_cents: self.detune_cents.clamp(0.0, MAX_DETUNE_CENTS),
+        }
+    }
+}
+
+/// Artificial double tracking run on the engine output after the IR cabinet.
+///
+/// A delayed copy of the signal, its delay time swung slowly so its pitch
+/// drifts a few cents, stands in for a second take. Like the ambience it
+/// works in mid/side: `width` of the double goes to the side, so one channel
+/// hears it in each polarity and it cancels exactly in mono; the rest is
+/// added to the mid. At the default width the mono sum combs by under 2 dB.
+pub struct DoubleTracker {
+    config: DoubleTrackerConfig,
+    sample_rate: f32,
+    /// Sized for the longest delay plus the LFO swing, so nothing is
+    /// allocated on the audio thread.
+    buffer: Vec<f32>,
+    write_pos: usize,
+    delay_samples: f32,
+    smooth_coeff: f32,
+    lfo_phase: f32,
+    lfo_step: f32,
+    /// LFO swing of the delay, in samples.
+    swing_samples: f32,
+}
+
+impl DoubleTracker {
+    pub fn new(config: DoubleTrackerConfig, sample_rate: usize) -> Self {
+        let sample_rate = sample_rate as f32;
+        let max_samples = ((MAX_DELAY_MS + MAX_SWING_MS) * 0.001 * sample_rate) as usize + 2;
+        let mut tracker = Self {
+            config: DoubleTrackerConfig::default(),
+            sample_rate,
+            buffer: vec![0.0; max_samples],
+            write_pos: 0,
+            delay_samples: 0.0,
+            smooth_coeff: calculate_coefficient(SMOOTH_TIME_MS, sample_rate),
+            lfo_phase: 0.0,
+            lfo_step: TAU * LFO_HZ / sample_rate,
+            swing_samples: 0.0,
+        };
+        tracker.set_config(config);
+        tracker.delay_samples = tracker.target_delay_samples();
+        tracker
+    }
+
+    pub const fn config(&self) -> DoubleTrackerConfig {
+        self.config
+    }
+
+    /// Takes effect from the next sample, with the delay time glided so a
+    /// change doesn't click. Turning it off clears the delay line.
+    pub fn set_config(&mut self, config: DoubleTrackerConfig) {
+        let config = config.clamped();
+        if config.is_off() && !self.config.is_off() {
+            self.reset();
+        }
+        self.config = config;
+
+        // A sine swing of the delay by `A` seconds at `f` Hz bends the pitch
+        // by up to 2πfA; solve for the swing giving the requested cents.
+        let ratio = (config.detune_cents / 1200.0).exp2() - 1.0;
+        self.swing_samples = ratio / (TAU * LFO_HZ) * self.sample_rate;
+    }
+
+    fn target_delay_samples(&self) -> f32 {
+        self.config.delay_ms * 0.001 * self.sample_rate
+    }
+
+    /// Add the double's centre part to `mid` in place and its side part to
+    /// `side`, when there is one. Off, both are untouched.
+    pub fn process_block(&mut self, mid: &mut [f32], mut side: Option<&mut [f32]>) {
+        if self.config.is_off() {
+            return;
+        }
+
+        let target = self.target_delay_samples();
+        let side_gain = self.config.mix * self.config.width;
+        let mid_gain = self.config.mix - side_gain;
+        let buf_len = self.buffer.len();
+
+        for (i, sample) in mid.iter_mut().enumerate() {
+            self.buffer[self.write_pos] = *sample;
+
+            self.delay_samples = self
+                .smooth_coeff
+                .mul_add(self.delay_samples, (1.0 - self.smooth_coeff) * target);
+            let delay = self
+                .swing_samples
+                .mul_add(self.lfo_phase.sin(), self.delay_samples);
+            self.lfo_phase = (self.lfo_phase + self.lfo_step) % TAU;
+
+            let whole = delay as usize;
+            let frac = delay - whole as f32;
+            let read_idx = (self.write_pos + buf_len - whole) % buf_len;
+            let prev_idx = (read_idx + buf_len - 1) % buf_len;
+            let double = (1.0 - frac).mul_add(self.buffer[read_idx], frac * self.buffer[prev_idx]);
+
+            self.write_pos = (self.write_pos + 1) % buf_len;
+
+            *sample = mid_gain.mul_add(double, *sample);
+            if let Some(s) = side.as_deref_mut().and_then(|side| side.get_mut(i)) {
+                *s = side_gain.mul_add(double, *s);
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.lfo_phase = 0.0;
+        self.delay_samples = self.target_delay_samples();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48000;
+
+    fn rms(buf: &[f32]) -> f32 {
+        (buf.iter().map(|s| s * s).sum::<f32>() / buf.len() as f32).sqrt()
+    }
+
+    fn sine(freq: f32, len: usize) -> Vec<f32> {
+        let step = TAU * freq / SAMPLE_RATE as f32;
+        (0..len).map(|i| (i as f32 * step).sin() * 0.5).collect()
+    }
+
+    /// Run `input` through in 128-sample blocks, as the engine would, and
+    /// return the (left, right) pair.
+    fn render(config: DoubleTrackerConfig, input: &[f32]) -> (Vec<f32>, Vec<f32>) {
+        let mut tracker = DoubleTracker::new(config, SAMPLE_RATE);
+        let mut mid = input.to_vec();
+        let mut side = vec![0.0; input.len()];
+        for (m, s) in mid.chunks_mut(128).zip(side.chunks_mut(128)) {
+            tracker.process_block(m, Some(s));
+        }
+        let left = mid.iter().zip(&side).map(|(m, s)| m + s).collect();
+        let right = mid.iter().zip(&side).map(|(m, s)| m - s).collect();
+        (left, right)
+    }
+
+    #[test]
+    fn off_passes_dry_and_identical_channels() {
+        let input = sine(440.0, 4800);
+        let (left, right) = render(DoubleTrackerConfig::default(), &input);
+        assert_eq!(left, input);
+        assert_eq!(right, input);
+    }
+
+    #[test]
+    fn channels_differ_when_on() {
+        let input = sine(440.0, SAMPLE_RATE / 2);
+        let config = DoubleTrackerConfig {
+            mix: 0.5,
+            ..DoubleTrackerConfig::default()
+        };
+        let (left, right) = render(config, &input);
+
+        let side: Vec<f32> = left.iter().zip(&right).map(|(l, r)| l - r).collect();
+        assert!(
+            rms(&side) > 0.2 * rms(&left),
+            "left and right should differ: side RMS {}",
+            rms(&side)
+        );
+    }
+
+    #[test]
+    fn mono_sum_barely_combs_below_1khz() {
+        for mix in [0.25, 0.5, 1.0] {
+            let config = DoubleTrackerConfig {
+                mix,
+                ..DoubleTrackerConfig::default()
+            };
+            let gains_db: Vec<f32> = (2..=50)
+                .map(|n| {
+                    let freq = n as f32 * 20.0;
+                    let input = sine(freq, SAMPLE_RATE / 2);
+                    let (left, right) = render(config, &input);
+                    let mono: Vec<f32> = left
+                        .iter()
+                        .zip(&right)
+                        .map(|(l, r)| 0.5 * (l + r))
+                        .collect();
+                    // Skip the first 50 ms, before the delay line has filled.
+                    let settle = SAMPLE_RATE / 20;
+                    20.0 * (rms(&mono[settle..]) / rms(&input[settle..])).log10()
+                })
+                .collect();
+            let max = gains_db.iter().copied().fold(f32::MIN, f32::max);
+            let min = gains_db.iter().copied().fold(f32::MAX, f32::min);
+            assert!(
+                max - min <= 2.0,
+                "mono sum at mix {mix} combs by {:.2} dB",
+                max - min
+            );
+        }
+    }
+
+    #[test]
+    fn detune_swings_the_delay_within_the_buffer() {
+        let config = DoubleTrackerConfig {
+            mix: 1.0,
+            delay_ms: MAX_DELAY_MS,
+            detune_cents: MAX_DETUNE_CENTS,
+            ..DoubleTrackerConfig::default()
+        };
+        let tracker = DoubleTracker::new(config, SAMPLE_RATE);
+        let longest = tracker.target_delay_samples() + tracker.swing_samples;
+        assert!(tracker.swing_samples > 0.0);
+        assert!((longest as usize) + 1 < tracker.buffer.len());
+    }
+
+    #[test]
+    fn turning_off_clears_the_delay_line() {
+        let on = DoubleTrackerConfig {
+            mix: 1.0,
+            ..DoubleTrackerConfig::default()
+        };
+        let mut tracker = DoubleTracker::new(on, SAMPLE_RATE);
+        let mut block = sine(440.0, 4800);
+        tracker.process_block(&mut block, None);
+
+        tracker.set_config(DoubleTrackerConfig::default());
+        tracker.set_config(on);
+        let mut silence = vec![0.0; 256];
+        tracker.process_block(&mut silence, None);
+        assert!(silence.iter().all(|&s| s == 0.0));
+    }
+}
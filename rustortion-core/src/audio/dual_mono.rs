@@ -0,0 +1,261 @@
+//! Dual-mono stereo: the right input runs through a chain and cabinet of
+//! its own, built from the same stages as the left, so a stereo source keeps
+//! its image.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::amp::chain::AmplifierChain;
+use crate::amp::crossfade::StageCrossfade;
+use crate::amp::stages::Stage;
+use crate::audio::engine::{EngineMessage, PreparedIr, flush_non_finite};
+use crate::audio::load_guard::QUALITY_FADE_SECONDS;
+use crate::audio::peak_meter::PeakMeter;
+use crate::audio::rt_drop::RtDropHandle;
+use crate::audio::samplers::Samplers;
+use crate::audio::standby::Standby;
+use crate::ir::cabinet::IrCabinet;
+
+/// How many chains the engine runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChainMode {
+    /// One chain on one input; the ambience and double tracker make the
+    /// stereo output.
+    #[default]
+    Mono,
+    /// Left and right inputs each through their own chain and cabinet, at
+    /// roughly twice the CPU. The pitch shifter, ambience and double tracker
+    /// are mono and sit out, and recordings take the left channel.
+    DualMono,
+}
+
+impl std::fmt::Display for ChainMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mono => write!(f, "Mono"),
+            Self::DualMono => write!(f, "Dual-mono"),
+        }
+    }
+}
+
+/// Twins of the engine messages that carry something built for one chain,
+/// sent again for the right channel in [`ChainMode::DualMono`]. Everything
+/// else (parameters, bypass, removals, the IR's gain) is applied to both
+/// chains from the one message.
+pub enum RightMessage {
+    SetAmpChain(Box<AmplifierChain>),
+    ReplaceStage(usize, Box<dyn Stage>),
+    CrossfadeStage(usize, Box<StageCrossfade>),
+    AddStage(usize, Box<dyn Stage>),
+    SetInputFilters(Option<Box<dyn Stage>>, Option<Box<dyn Stage>>),
+    SetSamplers(Box<Samplers>),
+    SwapIrConvolver(Box<PreparedIr>),
+}
+
+impl RightMessage {
+    /// Hand what the message carries to `rt_drop`, when there's no right
+    /// channel to take it.
+    pub(crate) fn retire(self, rt_drop: &RtDropHandle) {
+        match self {
+            Self::SetAmpChain(chain) => rt_drop.retire(chain),
+            Self::ReplaceStage(_, stage) | Self::AddStage(_, stage) => rt_drop.retire(stage),
+            Self::CrossfadeStage(_, fade) => rt_drop.retire(fade),
+            Self::SetInputFilters(hp, lp) => {
+                for filter in [hp, lp].into_iter().flatten() {
+                    rt_drop.retire(filter);
+                }
+            }
+            Self::SetSamplers(samplers) => rt_drop.retire(samplers),
+            Self::SwapIrConvolver(prepared) => rt_drop.retire(prepared),
+        }
+    }
+}
+
+/// The right channel's own chain, resamplers, input filters and cabinet.
+/// Built off the RT thread with an empty chain and no IR; those follow in
+/// [`RightMessage`]s.
+pub struct RightChannel {
+    pub(crate) chain: Box<AmplifierChain>,
+    pub(crate) samplers: Box<Samplers>,
+    input_highpass: Option<Box<dyn Stage>>,
+    input_lowpass: Option<Box<dyn Stage>>,
+    pub(crate) ir_cabinet: Option<IrCabinet>,
+    pub(crate) peak_meter: Option<PeakMeter>,
+    /// Follows the left's; the engine hands it over with the channel.
+    pub(crate) standby: Standby,
+}
+
+impl RightChannel {
+    pub fn new(
+        samplers: Samplers,
+        ir_cabinet: Option<IrCabinet>,
+        peak_meter: Option<PeakMeter>,
+    ) -> Self {
+        let standby = Standby::new(false, samplers.chain_rate());
+        Self {
+            chain: Box::new(AmplifierChain::new()),
+            samplers: Box::new(samplers),
+            input_highpass: None,
+            input_lowpass: None,
+            ir_cabinet,
+            peak_meter,
+            standby,
+        }
+    }
+
+    /// Apply `message`, retiring whatever it replaces through `rt_drop`.
+    pub(crate) fn handle(&mut self, message: RightMessage, rt_drop: &RtDropHandle) {
+        match message {
+            RightMessage::SetAmpChain(chain) => {
+                rt_drop.retire(std::mem::replace(&mut self.chain, chain));
+            }
+            RightMessage::ReplaceStage(idx, stage) => {
+                if let Some(old) = self.chain.replace_stage(idx, stage) {
+                    rt_drop.retire(old);
+                }
+            }
+            RightMessage::CrossfadeStage(idx, fade) => {
+                if let Some(rejected) = self.chain.crossfade_stage(idx, fade) {
+                    rt_drop.retire(rejected);
+                }
+            }
+            RightMessage::AddStage(idx, stage) => {
+                if let Some(rejected) = self.chain.insert_stage(idx, stage) {
+                    rt_drop.retire(rejected);
+                }
+            }
+            RightMessage::SetInputFilters(hp, lp) => {
+                if let Some(old) = std::mem::replace(&mut self.input_highpass, hp) {
+                    rt_drop.retire(old);
+                }
+                if let Some(old) = std::mem::replace(&mut self.input_lowpass, lp) {
+                    rt_drop.retire(old);
+                }
+            }
+            RightMessage::SetSamplers(samplers) => {
+                rt_drop.retire(std::mem::replace(&mut self.samplers, samplers));
+            }
+            RightMessage::SwapIrConvolver(mut prepared) => {
+                if let Some(ref mut cab) = self.ir_cabinet {
+                    cab.swap_convolver(&mut prepared.convolver);
+                }
+                rt_drop.retire(prepared);
+            }
+        }
+    }
+
+    /// Follow an engine message meant for the left chain that applies to
+    /// both as it is: parameters, bypass, reordering, solo, standby and the
+    /// IR's settings. The engine logs any that don't fit.
+    pub(crate) fn mirror(&mut self, message: &EngineMessage, rt_drop: &RtDropHandle) {
+        match *message {
+            EngineMessage::SetParameter(idx, name, value) => {
+                let _ = self.chain.set_parameter(idx, name, value);
+            }
+            EngineMessage::SetChildParameter(idx, child, name, value) => {
+                let _ = self.chain.set_child_parameter(idx, child, name, value);
+            }
+            EngineMessage::RemoveStage(idx) => {
+                if let Some(old) = self.chain.remove_stage(idx) {
+                    rt_drop.retire(old);
+                }
+            }
+            EngineMessage::SwapStages(a, b) => self.chain.swap_stages(a, b),
+            EngineMessage::SetStageBypassed(idx, bypassed) => {
+                self.chain.set_bypassed(idx, bypassed);
+            }
+            EngineMessage::SetMonitorPoint(point) => {
+                self.chain.set_monitor_point(point.map(|p| p.stage));
+            }
+            EngineMessage::SetStandby(on) => self.standby.set(on),
+            _ => {}
+        }
+        if let Some(ref mut cab) = self.ir_cabinet {
+            match *message {
+                EngineMessage::ClearIr => cab.clear_convolver(),
+                EngineMessage::SetIrBypass(bypass) => cab.set_bypass(bypass),
+                EngineMessage::SetIrGain(gain) => cab.set_gain(gain),
+                EngineMessage::SetIrTrim(trim) => cab.set_trim(trim),
+                // The right chain and IR come in their own messages.
+                EngineMessage::ApplyPreset(ref swap) => {
+                    if let Some(bypass) = swap.ir_bypass {
+                        cab.set_bypass(bypass);
+                    }
+                    cab.set_gain(swap.ir_gain);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Run `buf`, holding the right input, through the input trim and
+    /// filters, the chain and, with `cabinet`, the cabinet. Returns whether
+    /// non-finite samples were flushed.
+    pub(crate) fn process(
+        &mut self,
+        buf: &mut [f32],
+        input_trim: f32,
+        cabinet: bool,
+    ) -> Result<bool> {
+        if input_trim != 1.0 {
+            for s in buf.iter_mut() {
+                *s *= input_trim;
+            }
+        }
+        for filter in [&mut self.input_highpass, &mut self.input_lowpass]
+            .into_iter()
+            .flatten()
+        {
+            for s in buf.iter_mut() {
+                *s = filter.process(*s);
+            }
+        }
+
+        let mut non_finite = if self.samplers.passes_through() {
+            self.chain
+                .process_block_standby(buf, &mut self.standby, None);
+            flush_non_finite(buf)
+        } else {
+            self.samplers.copy_input(buf)?;
+            let upsampled = self.samplers.upsample()?;
+            self.chain
+                .process_block_standby(upsampled, &mut self.standby, None);
+            let non_finite = flush_non_finite(upsampled);
+            let downsampled = self.samplers.downsample()?;
+            buf[..downsampled.len()].copy_from_slice(downsampled);
+            non_finite
+        };
+
+        if cabinet && let Some(ref mut cab) = self.ir_cabinet {
+            cab.process_block(buf);
+        }
+        non_finite |= flush_non_finite(buf);
+        Ok(non_finite)
+    }
+
+    /// Follow the load guard onto the light resamplers and the IR's head, or
+    /// back.
+    pub(crate) fn apply_quality(&mut self, reduced: bool, sample_rate: usize) {
+        self.samplers.set_light_path(reduced);
+        if let Some(ref mut cab) = self.ir_cabinet {
+            let fade = (QUALITY_FADE_SECONDS * sample_rate as f32) as usize;
+            cab.set_head_only(reduced, fade);
+        }
+    }
+
+    /// Clear every stateful part after non-finite samples, like the engine
+    /// does for the left.
+    pub(crate) fn reset(&mut self) {
+        self.chain.reset();
+        for filter in [&mut self.input_highpass, &mut self.input_lowpass]
+            .into_iter()
+            .flatten()
+        {
+            filter.reset();
+        }
+        self.samplers.reset();
+        if let Some(ref mut cab) = self.ir_cabinet {
+            cab.clear_convolver();
+        }
+    }
+}
@@ -1,18 +1,265 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
 use anyhow::Result;
 use crossbeam::channel::{Receiver, Sender, bounded};
 use log::{debug, error};
 
 use crate::amp::chain::AmplifierChain;
+use crate::amp::crossfade::StageCrossfade;
+use crate::amp::stage_peaks::StagePeaks;
 use crate::amp::stages::Stage;
+use crate::amp::stages::common::db_to_lin;
+use crate::audio::ambience::{Ambience, AmbienceConfig};
+use crate::audio::denoiser::{DenoiseConfig, Denoiser, NoiseCapture, NoiseLearn};
+use crate::audio::diagnostics::{DiagnosticsHandle, DiagnosticsProbe, MessageKind};
+use crate::audio::double_tracker::{DoubleTracker, DoubleTrackerConfig};
+use crate::audio::dual_mono::{ChainMode, RightChannel, RightMessage};
+use crate::audio::load_guard::{LoadGuard, QUALITY_FADE_SECONDS, Quality};
 use crate::audio::peak_meter::PeakMeter;
 use crate::audio::pitch_shifter::PitchShifter;
-use crate::audio::recorder::Recorder;
+use crate::audio::recorder::{Recorder, RecordingProgress, RecordingStatus, TransportStart};
+use crate::audio::recording_info::RecordingMetadata;
 use crate::audio::rt_drop::RtDropHandle;
 use crate::audio::samplers::Samplers;
+use crate::audio::standby::Standby;
+use crate::audio::take_player::{TakeCommand, TakePlayer};
+use crate::audio::test_source::{TestSource, TestSourceConfig, TestSourceKind};
+use crate::audio::tilt::Tilt;
 use crate::ir::cabinet::IrCabinet;
 use crate::ir::convolver::Convolver;
-use crate::metronome::Metronome;
-use crate::tuner::Tuner;
+use crate::metronome::{Metronome, MetronomeConfig, MetronomeSounds};
+use crate::tuner::{Tuner, TunerAlgorithm};
+
+/// How long the output takes to fade out when the tuner mutes it, and back in
+/// when it's turned off.
+const TUNER_FADE_SECONDS: f32 = 0.005;
+
+/// Messages that can wait for the engine. Also the most it applies in one
+/// block, so a sender flooding the queue can't stall a callback.
+const MESSAGE_QUEUE_CAPACITY: usize = 128;
+
+/// How long a chain held for its preset's IR waits before it plays through
+/// whatever IR is loaded, e.g. because the load failed.
+const HELD_CHAIN_TIMEOUT_SECONDS: f32 = 0.5;
+
+/// How long the output stays muted after a sample-rate change for the IR
+/// resampled to the new rate, before it plays through the old one.
+const RETUNED_IR_TIMEOUT_SECONDS: f32 = 2.0;
+
+/// Frame positions the engine publishes for timestamping events off the RT
+/// thread, e.g. automation logged alongside a recording, and the state it has
+/// applied.
+#[derive(Debug)]
+struct FrameClock {
+    /// Frames handed to `process` so far. Read between callbacks, this is the
+    /// frame the next block starts at — where a message sent now is applied.
+    processed: AtomicU64,
+    /// Value of `processed` when the current (or last) recording started.
+    recording_start: AtomicU64,
+    /// When the chain last changed, in nanoseconds after `epoch`; 0 if it
+    /// never has. Lets the GUI time a preset switch to the block it landed in.
+    chain_changed_at: AtomicU64,
+    /// A chain is waiting for the metronome's next bar.
+    chain_queued: AtomicBool,
+    /// Frame of the block the last queued chain landed in; `u64::MAX` if
+    /// none has.
+    queued_chain_landed: AtomicU64,
+    epoch: Instant,
+    /// The engine's [`Latency`], republished after every batch of messages.
+    latency_oversampling: AtomicUsize,
+    latency_denoiser: AtomicUsize,
+    latency_pitch_shifter: AtomicUsize,
+    latency_cabinet: AtomicUsize,
+    /// Messages queued through any handle, and messages the engine has
+    /// applied. While they differ the published state is behind.
+    sent: AtomicU64,
+    applied: AtomicU64,
+    /// The engine's [`EngineState`], republished after every batch of
+    /// messages, before `applied` counts them.
+    ir_id: AtomicU64,
+    ir_gain: AtomicU32,
+    ir_bypassed: AtomicBool,
+    recording: AtomicBool,
+    tuner_enabled: AtomicBool,
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self {
+            processed: AtomicU64::new(0),
+            recording_start: AtomicU64::new(0),
+            chain_changed_at: AtomicU64::new(0),
+            chain_queued: AtomicBool::new(false),
+            queued_chain_landed: AtomicU64::new(u64::MAX),
+            epoch: Instant::now(),
+            latency_oversampling: AtomicUsize::new(0),
+            latency_denoiser: AtomicUsize::new(0),
+            latency_pitch_shifter: AtomicUsize::new(0),
+            latency_cabinet: AtomicUsize::new(0),
+            sent: AtomicU64::new(0),
+            applied: AtomicU64::new(0),
+            ir_id: AtomicU64::new(0),
+            ir_gain: AtomicU32::new(0.0f32.to_bits()),
+            ir_bypassed: AtomicBool::new(false),
+            recording: AtomicBool::new(false),
+            tuner_enabled: AtomicBool::new(false),
+        }
+    }
+}
+
+impl FrameClock {
+    /// Stamp a chain change with the current time. Reading the monotonic
+    /// clock doesn't block or allocate, so this is fine on the RT thread.
+    fn mark_chain_changed(&self) {
+        let nanos = u64::try_from(self.epoch.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.chain_changed_at.store(nanos.max(1), Ordering::Relaxed);
+    }
+
+    fn store_latency(&self, latency: Latency) {
+        self.latency_oversampling
+            .store(latency.oversampling, Ordering::Relaxed);
+        self.latency_denoiser
+            .store(latency.denoiser, Ordering::Relaxed);
+        self.latency_pitch_shifter
+            .store(latency.pitch_shifter, Ordering::Relaxed);
+        self.latency_cabinet
+            .store(latency.cabinet, Ordering::Relaxed);
+    }
+
+    fn latency(&self) -> Latency {
+        Latency {
+            oversampling: self.latency_oversampling.load(Ordering::Relaxed),
+            denoiser: self.latency_denoiser.load(Ordering::Relaxed),
+            pitch_shifter: self.latency_pitch_shifter.load(Ordering::Relaxed),
+            cabinet: self.latency_cabinet.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Publish `state`, then count the `applied` messages it reflects.
+    fn store_state(&self, state: EngineState, applied: u64) {
+        self.ir_id.store(state.ir_id, Ordering::Relaxed);
+        self.ir_gain
+            .store(state.ir_gain.to_bits(), Ordering::Relaxed);
+        self.ir_bypassed.store(state.ir_bypassed, Ordering::Relaxed);
+        self.recording.store(state.recording, Ordering::Relaxed);
+        self.tuner_enabled
+            .store(state.tuner_enabled, Ordering::Relaxed);
+        if applied > 0 {
+            self.applied.fetch_add(applied, Ordering::Release);
+        }
+    }
+
+    /// Whether the engine has applied every message sent so far.
+    fn caught_up(&self) -> bool {
+        self.applied.load(Ordering::Acquire) == self.sent.load(Ordering::Acquire)
+    }
+
+    /// The published state, or `None` while messages are still queued.
+    fn state(&self) -> Option<EngineState> {
+        if !self.caught_up() {
+            return None;
+        }
+        Some(EngineState {
+            ir_id: self.ir_id.load(Ordering::Relaxed),
+            ir_gain: f32::from_bits(self.ir_gain.load(Ordering::Relaxed)),
+            ir_bypassed: self.ir_bypassed.load(Ordering::Relaxed),
+            recording: self.recording.load(Ordering::Relaxed),
+            tuner_enabled: self.tuner_enabled.load(Ordering::Relaxed),
+        })
+    }
+
+    fn chain_changed_at(&self) -> Option<Instant> {
+        match self.chain_changed_at.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(self.epoch + std::time::Duration::from_nanos(nanos)),
+        }
+    }
+}
+
+/// What the engine has applied, as opposed to what it was sent. The GUI
+/// compares it with what it shows, so a message that never arrived doesn't
+/// leave the display wrong. See [`EngineHandle::state`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EngineState {
+    /// [`ir_name_id`] of the IR in the cabinet, 0 without one.
+    pub ir_id: u64,
+    pub ir_gain: f32,
+    pub ir_bypassed: bool,
+    pub recording: bool,
+    pub tuner_enabled: bool,
+}
+
+/// Id of an IR name, so the RT thread can publish which IR it plays without
+/// handing strings across threads. FNV-1a, never 0.
+pub const fn ir_name_id(name: &str) -> u64 {
+    let bytes = name.as_bytes();
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    if hash == 0 { 1 } else { hash }
+}
+
+/// Delay the engine adds on top of the host's buffering, in samples at the
+/// engine's rate, by where it comes from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Latency {
+    /// Group delay of the oversampling resamplers, there and back.
+    pub oversampling: usize,
+    pub denoiser: usize,
+    pub pitch_shifter: usize,
+    pub cabinet: usize,
+}
+
+impl Latency {
+    pub const fn total(&self) -> usize {
+        self.oversampling + self.denoiser + self.pitch_shifter + self.cabinet
+    }
+}
+
+/// Where the engine's output is tapped while a stage is soloed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorPoint {
+    /// Index of the soloed stage in the chain.
+    pub stage: usize,
+    /// Run the soloed signal through the pitch shifter, cabinet and
+    /// ambience as usual, rather than hear the stage dry.
+    pub keep_post: bool,
+}
+
+/// What a [`PresetSwap`] does to the cabinet's IR.
+pub enum IrChange {
+    Keep,
+    Clear,
+    Swap(Box<PreparedIr>),
+}
+
+/// A preset's chain and cabinet settings, built off the RT thread and applied
+/// in one block, so no block plays the new chain through the old IR or the
+/// other way round.
+pub struct PresetSwap {
+    pub chain: Box<AmplifierChain>,
+    pub ir: IrChange,
+    pub ir_gain: f32,
+    /// `None` leaves the bypass as it is.
+    pub ir_bypass: Option<bool>,
+}
+
+/// A chain the engine holds back, see [`EngineMessage::QueueAmpChain`] and
+/// [`EngineMessage::HoldAmpChain`].
+struct QueuedChain {
+    chain: Box<AmplifierChain>,
+    /// Engine frame it lands on at the latest: the bar, or the IR's deadline.
+    frame: u64,
+    /// Lands early, with the next IR swapped in.
+    with_ir: bool,
+}
 
 pub struct PreparedIr {
     pub name: String,
@@ -22,26 +269,162 @@ pub struct PreparedIr {
     pub convolver: Box<Convolver>,
 }
 
+/// The sample-rate-dependent parts of the engine, built off the RT thread for
+/// a new server rate and swapped in together. The chain and input filters
+/// aren't included: they're rebuilt from the GUI's configs and sent as usual.
+pub struct SampleRateParts {
+    pub samplers: Samplers,
+    /// Should report to the running tuner's handle, see [`Tuner::for_handle`].
+    pub tuner: Tuner,
+    pub metronome: Metronome,
+    pub ambience: Ambience,
+    pub double_tracker: DoubleTracker,
+    /// An IR resampled to the new rate follows, so the cabinet's current one
+    /// stays muted until it's swapped in.
+    pub ir_follows: bool,
+}
+
 pub enum EngineMessage {
     SetAmpChain(Box<AmplifierChain>),
+    /// Swap in a chain on the metronome's next bar, or in the next block
+    /// while it's stopped. Edits sent in the meantime go to the waiting
+    /// chain; a chain sent any other way replaces it.
+    QueueAmpChain(Box<AmplifierChain>),
+    /// Swap in a chain with the next IR, in the same block, so a preset
+    /// whose IR loads asynchronously isn't heard through the last one's.
+    /// Waits like [`Self::QueueAmpChain`], for at most
+    /// [`HELD_CHAIN_TIMEOUT_SECONDS`].
+    HoldAmpChain(Box<AmplifierChain>),
+    /// Chain and cabinet together, see [`PresetSwap`].
+    ApplyPreset(Box<PresetSwap>),
     SetInputFilters(Option<Box<dyn Stage>>, Option<Box<dyn Stage>>),
     SetParameter(usize, &'static str, f32),
+    /// Parameter of a stage inside a group: (group, child, name, value).
+    SetChildParameter(usize, usize, &'static str, f32),
     ReplaceStage(usize, Box<dyn Stage>),
+    /// Swap in a stage with a crossfade, see [`StageCrossfade`].
+    CrossfadeStage(usize, Box<StageCrossfade>),
     AddStage(usize, Box<dyn Stage>),
     RemoveStage(usize),
     SwapStages(usize, usize),
-    StartRecording(Recorder),
+    /// A take, and its click track if one was asked for.
+    StartRecording(Recorder, Option<Box<Recorder>>),
     StopRecording,
     SwapIrConvolver(Box<PreparedIr>),
     ClearIr,
     SetIrBypass(bool),
     SetIrGain(f32),
     SetTunerEnabled(bool),
+    SetTunerAlgorithm(TunerAlgorithm),
     /// Carries a fully-constructed pitch shifter (built off the RT thread), or
     /// `None` to disable pitch shifting (the `0` semitones bypass case).
     SetPitchShift(Option<Box<PitchShifter>>),
     SetStageBypassed(usize, bool),
     SetSamplers(Box<Samplers>),
+    /// Linear gain applied to the raw input before anything else.
+    SetInputTrim(f32),
+    /// Carries a denoiser built off the RT thread, or `None` to stop
+    /// denoising the input.
+    SetDenoiser(Option<Box<Denoiser>>),
+    /// Start learning the input's noise print, see [`NoiseLearn`].
+    LearnNoise(Box<NoiseCapture>),
+    SetAmbience(AmbienceConfig),
+    /// Carries a generator built off the RT thread to play in place of the
+    /// input, or `None` to go back to the input.
+    SetTestSource(Option<Box<TestSource>>),
+    /// Carries takes decoded off the RT thread to compare, or `None` to
+    /// stop comparing.
+    SetTakePlayer(Option<Box<TakePlayer>>),
+    /// For the take player; dropped without one.
+    Take(TakeCommand),
+    SetDoubleTracker(DoubleTrackerConfig),
+    /// Linear level-matching trim on the IR, on top of its gain.
+    SetIrTrim(f32),
+    /// Retune to a new sample rate. The output stays muted until the chain
+    /// rebuilt for that rate arrives.
+    SetSampleRate(Box<SampleRateParts>),
+    /// Output tilt in dB, see [`Tilt`].
+    SetTilt(f32),
+    /// Turn the load guard on or off, see [`LoadGuard`].
+    SetLoadGuard(bool),
+    /// Clicks decoded off the RT thread at the engine's sample rate.
+    SetMetronomeSounds(Box<MetronomeSounds>),
+    SetMetronome(MetronomeConfig),
+    /// Solo a stage, or `None` to hear the whole chain. Cleared by any
+    /// change to which stages are in the chain.
+    SetMonitorPoint(Option<MonitorPoint>),
+    /// Go into standby or come out of it, see [`Standby`]. Kept across
+    /// chain and preset changes.
+    SetStandby(bool),
+    /// Switch to [`ChainMode::DualMono`] with a right channel built off the
+    /// RT thread, or back to mono with `None`.
+    SetChainMode(Option<Box<RightChannel>>),
+    /// For the right channel only; dropped in mono.
+    Right(RightMessage),
+}
+
+impl EngineMessage {
+    pub const fn kind(&self) -> MessageKind {
+        match self {
+            Self::SetAmpChain(_) => MessageKind::SetAmpChain,
+            Self::QueueAmpChain(_) => MessageKind::QueueAmpChain,
+            Self::HoldAmpChain(_) => MessageKind::HoldAmpChain,
+            Self::ApplyPreset(_) => MessageKind::ApplyPreset,
+            Self::SetInputFilters(..) => MessageKind::SetInputFilters,
+            Self::SetParameter(..) => MessageKind::SetParameter,
+            Self::SetChildParameter(..) => MessageKind::SetChildParameter,
+            Self::ReplaceStage(..) => MessageKind::ReplaceStage,
+            Self::CrossfadeStage(..) => MessageKind::CrossfadeStage,
+            Self::AddStage(..) => MessageKind::AddStage,
+            Self::RemoveStage(_) => MessageKind::RemoveStage,
+            Self::SwapStages(..) => MessageKind::SwapStages,
+            Self::StartRecording(..) => MessageKind::StartRecording,
+            Self::StopRecording => MessageKind::StopRecording,
+            Self::SwapIrConvolver(_) => MessageKind::SwapIrConvolver,
+            Self::ClearIr => MessageKind::ClearIr,
+            Self::SetIrBypass(_) => MessageKind::SetIrBypass,
+            Self::SetIrGain(_) => MessageKind::SetIrGain,
+            Self::SetTunerEnabled(_) => MessageKind::SetTunerEnabled,
+            Self::SetTunerAlgorithm(_) => MessageKind::SetTunerAlgorithm,
+            Self::SetPitchShift(_) => MessageKind::SetPitchShift,
+            Self::SetStageBypassed(..) => MessageKind::SetStageBypassed,
+            Self::SetSamplers(_) => MessageKind::SetSamplers,
+            Self::SetInputTrim(_) => MessageKind::SetInputTrim,
+            Self::SetDenoiser(_) => MessageKind::SetDenoiser,
+            Self::LearnNoise(_) => MessageKind::LearnNoise,
+            Self::SetAmbience(_) => MessageKind::SetAmbience,
+            Self::SetTestSource(_) => MessageKind::SetTestSource,
+            Self::SetTakePlayer(_) => MessageKind::SetTakePlayer,
+            Self::Take(_) => MessageKind::Take,
+            Self::SetDoubleTracker(_) => MessageKind::SetDoubleTracker,
+            Self::SetIrTrim(_) => MessageKind::SetIrTrim,
+            Self::SetSampleRate(_) => MessageKind::SetSampleRate,
+            Self::SetTilt(_) => MessageKind::SetTilt,
+            Self::SetLoadGuard(_) => MessageKind::SetLoadGuard,
+            Self::SetMetronomeSounds(_) => MessageKind::SetMetronomeSounds,
+            Self::SetMetronome(_) => MessageKind::SetMetronome,
+            Self::SetMonitorPoint(_) => MessageKind::SetMonitorPoint,
+            Self::SetStandby(_) => MessageKind::SetStandby,
+            Self::SetChainMode(_) => MessageKind::SetChainMode,
+            Self::Right(_) => MessageKind::Right,
+        }
+    }
+
+    /// Whether this message changes the stages in the chain or which of
+    /// them play, rather than just their parameters.
+    pub const fn changes_chain(&self) -> bool {
+        matches!(
+            self,
+            Self::SetAmpChain(_)
+                | Self::ApplyPreset(_)
+                | Self::ReplaceStage(..)
+                | Self::CrossfadeStage(..)
+                | Self::AddStage(..)
+                | Self::RemoveStage(_)
+                | Self::SwapStages(..)
+                | Self::SetStageBypassed(..)
+        )
+    }
 }
 
 pub struct Engine {
@@ -49,6 +432,15 @@ pub struct Engine {
     chain: Box<AmplifierChain>,
     /// IR Cabinet processor
     ir_cabinet: Option<IrCabinet>,
+    /// Room and spring tail after the cabinet. Always present, silent until
+    /// its amount is raised.
+    ambience: Ambience,
+    /// Brightness correction on everything after the IR. Flat, and skipped,
+    /// until it's set.
+    tilt: Tilt,
+    /// Second-take double after the ambience; with it, the only stereo part
+    /// of the engine. Always present, off until its mix is raised.
+    double_tracker: DoubleTracker,
     /// Channel for updating the amplifier chain.
     engine_receiver: Receiver<EngineMessage>,
     /// Handle for sending arbitrary objects off the RT thread for deallocation.
@@ -57,19 +449,76 @@ pub struct Engine {
     /// thread exchanges pointers and retires the old box directly.
     samplers: Box<Samplers>,
     tuner: Option<Tuner>,
+    /// Output level under the tuner's mute: ramps to 0 while the tuner is on
+    /// and back to 1 once it's off, so toggling it mid-note doesn't click.
+    tuner_gain: f32,
+    /// Set by a sample-rate change and cleared by the next chain: the old
+    /// chain's filters are tuned for the old rate, so it stays muted.
+    awaiting_chain: bool,
+    /// Set by a sample-rate change whose IR is being resampled, to the frame
+    /// it gives up waiting, and cleared by the next IR: the old one is
+    /// recorded at the old rate, so it stays muted too.
+    awaiting_ir: Option<u64>,
     recorder: Option<Recorder>,
+    /// The metronome's track for the take, written block for block with it.
+    click_recorder: Option<Box<Recorder>>,
     peak_meter: Option<PeakMeter>,
+    /// Meters the raw input, ahead of the trim, for input calibration.
+    input_meter: Option<PeakMeter>,
+    /// Linear input gain, so presets see the same level whatever the interface.
+    input_trim: f32,
     metronome: Option<Metronome>,
     pitch_shifter: Option<Box<PitchShifter>>,
     input_highpass: Option<Box<dyn Stage>>,
     input_lowpass: Option<Box<dyn Stage>>,
+    /// Spectral denoiser after the input filters. Sits out in dual-mono,
+    /// where the right input has no noise print of its own.
+    denoiser: Option<Box<Denoiser>>,
+    /// A noise print being learned, at the denoiser's input.
+    noise_capture: Option<Box<NoiseCapture>>,
+    /// Signal generator replacing the input, for profiling without a guitar.
+    test_source: Option<Box<TestSource>>,
+    /// Recorded takes played in place of the output while comparing them.
+    take_player: Option<Box<TakePlayer>>,
+    /// A chain waiting for its bar or its IR, see
+    /// [`EngineMessage::QueueAmpChain`] and [`EngineMessage::HoldAmpChain`].
+    queued_chain: Option<QueuedChain>,
+    /// Tempo reported by the plugin host, if it reports one.
+    host_tempo: Option<f32>,
     /// When true, skip tuner, peak meter, recorder, and metronome processing.
     lightweight: bool,
+    /// Times each block and decides when to shed work. Off until turned on,
+    /// and never on for offline renders, which may run slower than real time.
+    load_guard: LoadGuard,
+    /// While a stage is soloed, keep the stages after the chain rather than
+    /// skip them.
+    monitor_keep_post: bool,
+    /// Mutes the power amp's output while the chain keeps running. Lives
+    /// here rather than in the chain so it outlasts chain swaps.
+    standby: Standby,
+    /// RT-side writer for the diagnostics snapshot (atomics only).
+    diagnostics: DiagnosticsProbe,
+    diagnostics_handle: DiagnosticsHandle,
+    /// Per-stage output peaks for the gain staging assistant.
+    stage_peaks: Arc<StagePeaks>,
+    /// Second chain for the right input, in [`ChainMode::DualMono`].
+    right: Option<Box<RightChannel>>,
+    /// [`ir_name_id`] of the IR in the cabinet, 0 without one.
+    ir_id: u64,
+    /// Engine frame the current block starts at; the metronome is scheduled
+    /// from it.
+    block_frame: u64,
+    clock: Arc<FrameClock>,
 }
 
 #[derive(Clone)]
 pub struct EngineHandle {
     engine_sender: Sender<EngineMessage>,
+    clock: Arc<FrameClock>,
+    /// Progress of the take started through this handle, if one is running.
+    recording: Arc<Mutex<Option<Arc<RecordingProgress>>>>,
+    /// Whether the engine was last sent a right channel.
+    dual_mono: Arc<AtomicBool>,
 }
 
 impl Engine {
@@ -81,25 +530,67 @@ impl Engine {
         metronome: Metronome,
         rt_drop: RtDropHandle,
     ) -> Result<(Self, EngineHandle)> {
-        let (engine_sender, engine_receiver) = bounded::<EngineMessage>(128);
+        let (engine_sender, engine_receiver) = bounded::<EngineMessage>(MESSAGE_QUEUE_CAPACITY);
+        let (diagnostics, diagnostics_handle) = DiagnosticsProbe::new();
+        diagnostics.set_sampling(samplers.sample_rate(), samplers.get_oversample_factor());
+        let tuner_gain = if tuner.is_enabled() { 0.0 } else { 1.0 };
+        let clock = Arc::new(FrameClock::default());
+        let ambience = Ambience::new(AmbienceConfig::default(), samplers.sample_rate());
+        let double_tracker =
+            DoubleTracker::new(DoubleTrackerConfig::default(), samplers.sample_rate());
+
+        let standby = Standby::new(false, samplers.chain_rate());
+        let engine = Self {
+            chain: Box::new(AmplifierChain::new()),
+            ir_cabinet,
+            ambience,
+            tilt: Tilt::new(0.0, samplers.sample_rate()),
+            double_tracker,
+            engine_receiver,
+            rt_drop,
+            samplers: Box::new(samplers),
+            tuner: Some(tuner),
+            tuner_gain,
+            awaiting_chain: false,
+            awaiting_ir: None,
+            recorder: None,
+            click_recorder: None,
+            peak_meter: Some(peak_meter),
+            input_meter: None,
+            input_trim: 1.0,
+            metronome: Some(metronome),
+            pitch_shifter: None,
+            input_highpass: None,
+            input_lowpass: None,
+            denoiser: None,
+            noise_capture: None,
+            test_source: None,
+            take_player: None,
+            queued_chain: None,
+            host_tempo: None,
+            lightweight: false,
+            load_guard: LoadGuard::new(),
+            monitor_keep_post: false,
+            standby,
+            diagnostics,
+            diagnostics_handle,
+            stage_peaks: Arc::new(StagePeaks::new()),
+            right: None,
+            ir_id: 0,
+            block_frame: 0,
+            clock: Arc::clone(&clock),
+        };
+        clock.store_latency(engine.latency());
+        clock.store_state(engine.state(), 0);
 
         Ok((
-            Self {
-                chain: Box::new(AmplifierChain::new()),
-                ir_cabinet,
-                engine_receiver,
-                rt_drop,
-                samplers: Box::new(samplers),
-                tuner: Some(tuner),
-                recorder: None,
-                peak_meter: Some(peak_meter),
-                metronome: Some(metronome),
-                pitch_shifter: None,
-                input_highpass: None,
-                input_lowpass: None,
-                lightweight: false,
+            engine,
+            EngineHandle {
+                engine_sender,
+                clock,
+                recording: Arc::default(),
+                dual_mono: Arc::default(),
             },
-            EngineHandle { engine_sender },
         ))
     }
 
@@ -114,28 +605,172 @@ impl Engine {
     ) -> Result<(Self, EngineHandle, crate::audio::rt_drop::RtDropReceiver)> {
         let samplers = Samplers::new(max_buffer_size, oversample_factor, sample_rate)?;
         let (rt_drop_handle, rt_drop_rx) = RtDropHandle::new();
-        let (engine_sender, engine_receiver) = bounded::<EngineMessage>(128);
+        let (engine_sender, engine_receiver) = bounded::<EngineMessage>(MESSAGE_QUEUE_CAPACITY);
+        let (diagnostics, diagnostics_handle) = DiagnosticsProbe::new();
+        diagnostics.set_sampling(sample_rate, oversample_factor);
+        let clock = Arc::new(FrameClock::default());
 
+        let standby = Standby::new(false, samplers.chain_rate());
         let engine = Self {
             chain: Box::new(AmplifierChain::new()),
             ir_cabinet,
+            ambience: Ambience::new(AmbienceConfig::default(), sample_rate),
+            tilt: Tilt::new(0.0, sample_rate),
+            double_tracker: DoubleTracker::new(DoubleTrackerConfig::default(), sample_rate),
             engine_receiver,
             rt_drop: rt_drop_handle,
             samplers: Box::new(samplers),
             tuner: None,
+            tuner_gain: 1.0,
+            awaiting_chain: false,
+            awaiting_ir: None,
             recorder: None,
+            click_recorder: None,
             peak_meter: None,
+            input_meter: None,
+            input_trim: 1.0,
             metronome: None,
             pitch_shifter: None,
             input_highpass: None,
             input_lowpass: None,
+            denoiser: None,
+            noise_capture: None,
+            test_source: None,
+            take_player: None,
+            queued_chain: None,
+            host_tempo: None,
             lightweight: true,
+            load_guard: LoadGuard::new(),
+            monitor_keep_post: false,
+            standby,
+            diagnostics,
+            diagnostics_handle,
+            stage_peaks: Arc::new(StagePeaks::new()),
+            right: None,
+            ir_id: 0,
+            block_frame: 0,
+            clock: Arc::clone(&clock),
+        };
+        clock.store_latency(engine.latency());
+        clock.store_state(engine.state(), 0);
+
+        let handle = EngineHandle {
+            engine_sender,
+            clock,
+            recording: Arc::default(),
+            dual_mono: Arc::default(),
         };
+        Ok((engine, handle, rt_drop_rx))
+    }
+
+    /// Start in standby, already faded out, so nothing is heard until it's
+    /// switched off. Call before moving the engine onto the audio thread.
+    pub fn start_in_standby(&mut self) {
+        self.standby = Standby::new(true, self.samplers.chain_rate());
+    }
+
+    /// Meter the raw input ahead of the input trim. Call before moving the
+    /// engine onto the audio thread.
+    pub fn set_input_meter(&mut self, meter: PeakMeter) {
+        self.input_meter = Some(meter);
+    }
+
+    /// Reader for the engine's diagnostics. Grab this before moving the engine
+    /// onto the audio thread.
+    pub fn diagnostics(&self) -> DiagnosticsHandle {
+        self.diagnostics_handle.clone()
+    }
 
-        Ok((engine, EngineHandle { engine_sender }, rt_drop_rx))
+    /// Per-stage output peaks, for the gain staging assistant. Grab this
+    /// before moving the engine onto the audio thread.
+    pub fn stage_peaks(&self) -> Arc<StagePeaks> {
+        Arc::clone(&self.stage_peaks)
     }
 
+    /// Process one block to a mono output. The ambience and double tracker,
+    /// if on, are folded into it as their mono sum.
     pub fn process(&mut self, input: &[f32], output: &mut [f32]) -> Result<()> {
+        self.process_block(input, output, None, None)
+    }
+
+    /// Process one block to a stereo pair. Left and right are identical
+    /// unless the ambience or double tracker is on.
+    pub fn process_stereo(
+        &mut self,
+        input: &[f32],
+        left: &mut [f32],
+        right: &mut [f32],
+    ) -> Result<()> {
+        if right.len() != left.len() {
+            return Err(anyhow::anyhow!(
+                "left and right buffer size mismatch: left {}, right {}",
+                left.len(),
+                right.len()
+            ));
+        }
+        self.process_block(input, left, Some(&mut *right), None)?;
+        mid_side_to_stereo(left, right);
+        Ok(())
+    }
+
+    /// Process a block from a stereo pair of inputs. In
+    /// [`ChainMode::DualMono`] each input runs through its own chain to its
+    /// own output; otherwise the right input is ignored, as in
+    /// [`Self::process_stereo`].
+    pub fn process_stereo_input(
+        &mut self,
+        left_in: &[f32],
+        right_in: &[f32],
+        left: &mut [f32],
+        right: &mut [f32],
+    ) -> Result<()> {
+        if right_in.len() != left_in.len() || right.len() != left.len() {
+            return Err(anyhow::anyhow!(
+                "left and right buffer size mismatch: inputs {} and {}, outputs {} and {}",
+                left_in.len(),
+                right_in.len(),
+                left.len(),
+                right.len()
+            ));
+        }
+        self.process_block(left_in, left, Some(&mut *right), Some(right_in))?;
+        // A right channel arriving or leaving is picked up at the top of the
+        // block, so this is the mode the block was rendered in.
+        if self.right.is_none() {
+            mid_side_to_stereo(left, right);
+        }
+        // A take being compared plays over what the chain rendered, which
+        // the recorder has already had.
+        if let Some(player) = self.take_player.as_mut() {
+            player.render(left, right);
+        }
+        Ok(())
+    }
+
+    /// Time a block against its period for the load guard.
+    fn process_block(
+        &mut self,
+        input: &[f32],
+        output: &mut [f32],
+        side: Option<&mut [f32]>,
+        right_in: Option<&[f32]>,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let result = self.render_block(input, output, side, right_in);
+        self.watch_load(started.elapsed(), input.len());
+        result
+    }
+
+    /// Run the engine into `output`, and the side signal of the ambience and
+    /// double tracker into `side` when the caller wants stereo. With a right
+    /// channel and `right_in`, `side` gets the right channel instead.
+    fn render_block(
+        &mut self,
+        input: &[f32],
+        output: &mut [f32],
+        mut side: Option<&mut [f32]>,
+        right_in: Option<&[f32]>,
+    ) -> Result<()> {
         if input.len() != output.len() {
             return Err(anyhow::anyhow!(
                 "input and output buffer size mismatch: input {}, output {}",
@@ -144,48 +779,200 @@ impl Engine {
             ));
         }
 
+        self.diagnostics.set_buffer_size(input.len());
         self.handle_messages();
-
-        if let Some(ref mut tuner) = self.tuner
-            && tuner.is_enabled()
+        self.block_frame = self
+            .clock
+            .processed
+            .fetch_add(input.len() as u64, Ordering::Relaxed);
+        self.land_queued_chain(self.block_frame, self.block_frame + input.len() as u64);
+        if self
+            .awaiting_ir
+            .is_some_and(|deadline| deadline <= self.block_frame)
         {
-            tuner.process(input);
+            self.awaiting_ir = None;
+            debug!("Gave up waiting for the resampled IR");
+        }
+
+        if let Some(bpm) = self.tempo() {
+            self.chain.set_tempo(bpm);
+            if let Some(ref mut right) = self.right {
+                right.chain.set_tempo(bpm);
+            }
+        }
+        let dual = self.right.is_some() && right_in.is_some() && side.is_some();
+
+        // A test source is rendered straight into `output`, which from here
+        // on stands in for the input: the meter, tuner and recorder all see it.
+        let generated = self
+            .test_source
+            .as_mut()
+            .is_some_and(|source| source.render(output));
+        let tuner_enabled = if generated {
+            self.analyse_input(output)
+        } else {
+            let tuner_enabled = self.analyse_input(input);
+            // Apply input filters in-place via output buffer to avoid allocation.
+            // Skip copy when input and output alias (same base pointer).
+            if !std::ptr::eq(input.as_ptr(), output.as_ptr()) {
+                output[..input.len()].copy_from_slice(input);
+            }
+            tuner_enabled
+        };
+        if dual && let (Some(right_in), Some(side)) = (right_in, side.as_deref_mut()) {
+            // A test source plays into both channels.
+            let source = if generated { &*output } else { right_in };
+            side[..source.len()].copy_from_slice(source);
+        }
+
+        // Once faded out under the tuner there's nothing to hear; skip the chain.
+        if tuner_enabled && self.tuner_gain == 0.0 {
             output.fill(0.0);
+            if let Some(side) = side {
+                side.fill(0.0);
+            }
             return Ok(());
         }
 
-        // Apply input filters in-place via output buffer to avoid allocation.
-        // Skip copy when input and output alias (same base pointer).
-        if !std::ptr::eq(input.as_ptr(), output.as_ptr()) {
-            output[..input.len()].copy_from_slice(input);
+        if self.input_trim != 1.0 {
+            for s in &mut output[..input.len()] {
+                *s *= self.input_trim;
+            }
         }
         self.apply_input_filters(&mut output[..input.len()]);
+        self.apply_denoiser(&mut output[..input.len()], dual);
 
-        if self.samplers.get_oversample_factor() == 1.0 {
-            self.process_without_upsampling(output)?;
+        let mut non_finite = if self.samplers.passes_through() {
+            self.process_without_upsampling(output)?
         } else {
-            self.process_with_upsampling(output)?;
-        }
+            self.process_with_upsampling(output)?
+        };
 
-        if let Some(ref mut shifter) = self.pitch_shifter {
-            shifter.process_block(output);
+        let skips_post = self.skips_post();
+        if dual {
+            // Pitch shifter, ambience and double tracker are mono; they sit
+            // out while each side has its own chain.
+            if !skips_post && let Some(ref mut cab) = self.ir_cabinet {
+                cab.process_block(output);
+            }
+            if let (Some(right), Some(side)) = (self.right.as_deref_mut(), side.as_deref_mut()) {
+                non_finite |= right.process(side, self.input_trim, !skips_post)?;
+            }
+        } else if skips_post {
+            if let Some(side) = side.as_deref_mut() {
+                side.fill(0.0);
+            }
+        } else {
+            if let Some(ref mut shifter) = self.pitch_shifter {
+                shifter.process_block(output);
+            }
+
+            if let Some(ref mut cab) = self.ir_cabinet {
+                cab.process_block(output);
+            }
+
+            self.ambience.process_block(output, side.as_deref_mut());
+            self.double_tracker
+                .process_block(output, side.as_deref_mut());
         }
+        // Linear, so tilting mid and side, or each side of a dual-mono pair,
+        // tilts left and right alike.
+        self.tilt.process_block(output, side.as_deref_mut());
 
-        if let Some(ref mut cab) = self.ir_cabinet {
-            cab.process_block(output);
+        non_finite |= flush_non_finite(output);
+        if let Some(side) = side.as_deref_mut() {
+            non_finite |= flush_non_finite(side);
+        }
+        if non_finite {
+            self.recover_from_non_finite();
         }
 
+        let muted = tuner_enabled || self.awaiting_chain || self.awaiting_ir.is_some();
+        self.apply_tuner_fade(output, side.as_deref_mut(), muted);
+
         if let Some(ref mut peak_meter) = self.peak_meter {
             peak_meter.process(output);
         }
+        if dual
+            && let Some(side) = side
+            && let Some(meter) = self
+                .right
+                .as_deref_mut()
+                .and_then(|r| r.peak_meter.as_mut())
+        {
+            meter.process(side);
+        }
 
+        if !self.lightweight {
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.record_block(output);
+            }
+            // Skipped with the take above, so the two stay the same length.
+            if let Some(click) = self.click_recorder.as_deref() {
+                let frame = self.block_frame;
+                let metronome = self.metronome.as_ref();
+                click.record_with(output.len(), |i| {
+                    metronome.map_or(0.0, |m| m.sample_at(frame + i as u64))
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hand a block's time to the load guard, and shed or restore work if it
+    /// says so.
+    fn watch_load(&mut self, busy: Duration, frames: usize) {
+        let period = frames as f32 / self.samplers.sample_rate() as f32;
+        if let Some(quality) = self.load_guard.observe(busy.as_secs_f32(), period) {
+            self.apply_quality(quality);
+        }
+    }
+
+    /// Switch the oversampling to the light resamplers and IRs to their head,
+    /// or both back. Both crossfade, and neither allocates.
+    fn apply_quality(&mut self, quality: Quality) {
+        let reduced = quality == Quality::Reduced;
+        self.samplers.set_light_path(reduced);
+        if let Some(ref mut cab) = self.ir_cabinet {
+            let fade = (QUALITY_FADE_SECONDS * self.samplers.sample_rate() as f32) as usize;
+            cab.set_head_only(reduced, fade);
+        }
+        if let Some(ref mut right) = self.right {
+            right.apply_quality(reduced, self.samplers.sample_rate());
+        }
+        self.diagnostics.set_reduced_quality(reduced);
+        debug!("Load guard: {quality:?} quality");
+    }
+
+    /// Feed the raw input to the input meter and, if it's on, the tuner.
+    /// Returns whether the tuner is on.
+    fn analyse_input(&mut self, input: &[f32]) -> bool {
         if !self.lightweight
-            && let Some(recorder) = self.recorder.as_mut()
+            && let Some(ref mut input_meter) = self.input_meter
         {
-            recorder.record_block(output);
+            input_meter.process(input);
         }
 
-        Ok(())
+        self.tuner
+            .as_mut()
+            .is_some_and(|tuner| tuner.process(input))
+    }
+
+    /// Set the tempo from the plugin host's transport, once per block before
+    /// `process`. `None` when the host doesn't report one.
+    pub const fn set_host_tempo(&mut self, bpm: Option<f32>) {
+        self.host_tempo = bpm;
+    }
+
+    /// Tempo for synced stages: the host's, else the metronome's while it runs.
+    fn tempo(&self) -> Option<f32> {
+        self.host_tempo.or_else(|| {
+            self.metronome
+                .as_ref()
+                .filter(|m| m.is_enabled())
+                .map(Metronome::bpm)
+        })
     }
 
     fn apply_input_filters(&mut self, buf: &mut [f32]) {
@@ -201,52 +988,203 @@ impl Engine {
         }
     }
 
-    fn process_without_upsampling(&mut self, output: &mut [f32]) -> Result<()> {
-        self.chain.as_mut().process_block(output);
+    fn apply_denoiser(&mut self, buf: &mut [f32], dual: bool) {
+        let learned = self
+            .noise_capture
+            .as_mut()
+            .is_some_and(|capture| capture.feed(buf));
+        if learned && let Some(done) = self.noise_capture.take() {
+            self.rt_drop.retire(done);
+        }
+        if !dual && let Some(ref mut denoiser) = self.denoiser {
+            denoiser.process_block(buf);
+        }
+    }
+
+    /// Returns `true` if the chain produced non-finite samples (already flushed).
+    fn process_without_upsampling(&mut self, output: &mut [f32]) -> Result<bool> {
+        self.chain.as_mut().process_block_standby(
+            output,
+            &mut self.standby,
+            Some(&self.stage_peaks),
+        );
 
-        Ok(())
+        Ok(flush_non_finite(output))
     }
 
-    fn process_with_upsampling(&mut self, output: &mut [f32]) -> Result<()> {
+    /// Returns `true` if the chain produced non-finite samples (already flushed).
+    fn process_with_upsampling(&mut self, output: &mut [f32]) -> Result<bool> {
         self.samplers.copy_input(output)?;
 
         let upsampled = self.samplers.upsample()?;
 
-        self.chain.as_mut().process_block(upsampled);
+        self.chain.as_mut().process_block_standby(
+            upsampled,
+            &mut self.standby,
+            Some(&self.stage_peaks),
+        );
+
+        // Flush before downsampling so the downsampler's history stays clean.
+        let non_finite = flush_non_finite(upsampled);
 
         let downsampled = self.samplers.downsample()?;
 
         output[..downsampled.len()].copy_from_slice(downsampled);
 
-        Ok(())
+        Ok(non_finite)
     }
 
-    //need to process metronome separately
-    pub fn process_metronome(&mut self, output: &mut [f32]) -> bool {
-        if let Some(ref mut metronome) = self.metronome
+    /// Ramp the output (and the side, if any) toward silence while
+    /// the tuner is on, or back up to full level after it's turned off.
+    /// A sample-rate change waiting for its chain mutes the same way.
+    fn apply_tuner_fade(
+        &mut self,
+        output: &mut [f32],
+        mut side: Option<&mut [f32]>,
+        tuner_enabled: bool,
+    ) {
+        let target = if tuner_enabled { 0.0 } else { 1.0 };
+        if self.tuner_gain == target {
+            return;
+        }
+
+        let step = 1.0 / (TUNER_FADE_SECONDS * self.samplers.sample_rate() as f32);
+        for (i, sample) in output.iter_mut().enumerate() {
+            self.tuner_gain = if tuner_enabled {
+                (self.tuner_gain - step).max(0.0)
+            } else {
+                (self.tuner_gain + step).min(1.0)
+            };
+            *sample *= self.tuner_gain;
+            if let Some(s) = side.as_deref_mut().and_then(|side| side.get_mut(i)) {
+                *s *= self.tuner_gain;
+            }
+        }
+    }
+
+    /// A NaN or Inf lodged in a feedback path (delay line, filter memory,
+    /// convolver history) would otherwise recirculate forever. Clear every
+    /// stateful processor so the next block starts clean.
+    fn recover_from_non_finite(&mut self) {
+        self.chain.reset();
+        if let Some(ref mut hp) = self.input_highpass {
+            hp.reset();
+        }
+        if let Some(ref mut lp) = self.input_lowpass {
+            lp.reset();
+        }
+        if let Some(ref mut denoiser) = self.denoiser {
+            denoiser.reset();
+        }
+        self.samplers.reset();
+        if let Some(ref mut cab) = self.ir_cabinet {
+            cab.clear_convolver();
+        }
+        if let Some(ref mut right) = self.right {
+            right.reset();
+        }
+        self.ambience.reset();
+        self.tilt.reset();
+        self.double_tracker.reset();
+        self.diagnostics.record_non_finite();
+    }
+
+    /// Render the click for the block just processed into `output`, for
+    /// its own output port. Returns false, leaving `output` alone, while the
+    /// metronome is off.
+    pub fn process_metronome(&self, output: &mut [f32]) -> bool {
+        if let Some(ref metronome) = self.metronome
             && metronome.is_enabled()
         {
-            metronome.process_block(output);
+            metronome.process_block(output, self.block_frame);
             return true;
         }
 
         false
     }
+
+    /// Whether the click should be mixed into the main outputs too.
+    pub fn metronome_in_monitor(&self) -> bool {
+        self.metronome
+            .as_ref()
+            .is_some_and(|m| m.is_enabled() && m.in_monitor())
+    }
+
+    /// Whether a take is being recorded, as of the messages handled so far.
+    pub const fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Whether the take's writers can accept the next block without
+    /// dropping it; always true when not recording. Real-time safe.
+    pub fn recorder_has_room(&self) -> bool {
+        self.recorder.as_ref().is_none_or(Recorder::has_room)
+            && self.click_recorder.as_ref().is_none_or(|r| r.has_room())
+    }
+
+    /// Frames processed so far: the engine frame the next block starts at.
+    pub fn frames_processed(&self) -> u64 {
+        self.clock.processed.load(Ordering::Relaxed)
+    }
+
+    /// Rate the engine is currently tuned for.
+    pub fn sample_rate(&self) -> usize {
+        self.samplers.sample_rate()
+    }
+
     pub fn update_buffer_size(&mut self, new_size: usize) -> Result<()> {
+        if let Some(ref mut right) = self.right {
+            right.samplers.resize_buffers(new_size)?;
+        }
         self.samplers.resize_buffers(new_size)
     }
 
+    /// Apply every pending message. Runs at the start of each block, so a
+    /// chain swap sent between callbacks is heard in the very next one.
+    ///
+    /// Messages are applied in the order they were sent, and everything
+    /// queued before the block starts lands in that block: a burst sent
+    /// together is never heard half applied, unless it races the callback
+    /// itself. Changes that must land together whatever the timing go in
+    /// one message, like [`EngineMessage::ApplyPreset`]. At most
+    /// [`MESSAGE_QUEUE_CAPACITY`] are applied per block; the rest wait for
+    /// the next.
     #[allow(clippy::cognitive_complexity)]
     pub fn handle_messages(&mut self) {
-        while let Ok(message) = self.engine_receiver.try_recv() {
+        let mut chain_changed = false;
+        let mut applied = 0;
+        let skipped_post = self.skips_post();
+        for _ in 0..MESSAGE_QUEUE_CAPACITY {
+            let Ok(message) = self.engine_receiver.try_recv() else {
+                break;
+            };
+            applied += 1;
+            self.diagnostics.record_message(message.kind());
+            chain_changed |= message.changes_chain();
+            if let Some(ref mut right) = self.right {
+                right.mirror(&message, &self.rt_drop);
+            }
+
             match message {
                 EngineMessage::SetAmpChain(new_chain) => {
+                    self.cancel_queued_chain();
                     let old = std::mem::replace(&mut self.chain, new_chain);
                     self.rt_drop.retire(old);
+                    self.awaiting_chain = false;
                     debug!("Received new amplifier chain");
                 }
+                EngineMessage::QueueAmpChain(new_chain) => {
+                    self.handle_queue_chain(new_chain);
+                }
+                EngineMessage::HoldAmpChain(new_chain) => {
+                    self.handle_hold_chain(new_chain);
+                }
+                EngineMessage::ApplyPreset(change) => {
+                    self.cancel_queued_chain();
+                    self.handle_apply_preset(change);
+                }
                 EngineMessage::SetParameter(idx, name, value) => {
-                    if let Some(result) = self.chain.set_parameter(idx, name, value) {
+                    if let Some(result) = self.edited_chain().set_parameter(idx, name, value) {
                         if let Err(e) = result {
                             error!("Failed to set parameter '{name}' on stage {idx}: {e}");
                         }
@@ -254,16 +1192,38 @@ impl Engine {
                         error!("SetParameter: stage index {idx} out of bounds");
                     }
                 }
+                EngineMessage::SetChildParameter(idx, child, name, value) => {
+                    if let Some(result) = self
+                        .edited_chain()
+                        .set_child_parameter(idx, child, name, value)
+                    {
+                        if let Err(e) = result {
+                            error!(
+                                "Failed to set parameter '{name}' on child {child} of stage {idx}: {e}"
+                            );
+                        }
+                    } else {
+                        error!("SetChildParameter: stage index {idx} out of bounds");
+                    }
+                }
                 EngineMessage::ReplaceStage(idx, new_stage) => {
-                    if let Some(old) = self.chain.replace_stage(idx, new_stage) {
+                    if let Some(old) = self.edited_chain().replace_stage(idx, new_stage) {
                         self.rt_drop.retire(old);
                         debug!("Replaced stage at index {idx}");
                     } else {
                         error!("ReplaceStage: stage index {idx} out of bounds");
                     }
                 }
+                EngineMessage::CrossfadeStage(idx, fade) => {
+                    if let Some(rejected) = self.edited_chain().crossfade_stage(idx, fade) {
+                        self.rt_drop.retire(rejected);
+                        error!("CrossfadeStage: stage index {idx} out of bounds");
+                    } else {
+                        debug!("Crossfading stage at index {idx}");
+                    }
+                }
                 EngineMessage::AddStage(idx, stage) => {
-                    if let Some(rejected) = self.chain.insert_stage(idx, stage) {
+                    if let Some(rejected) = self.edited_chain().insert_stage(idx, stage) {
                         // Chain is at its reserved capacity. Retire the rejected
                         // stage off the RT thread rather than dropping (freeing)
                         // it here. The UI caps stage count, so this is a backstop.
@@ -273,7 +1233,7 @@ impl Engine {
                     }
                 }
                 EngineMessage::RemoveStage(idx) => {
-                    if let Some(old) = self.chain.remove_stage(idx) {
+                    if let Some(old) = self.edited_chain().remove_stage(idx) {
                         self.rt_drop.retire(old);
                         debug!("Removed stage at index {idx}");
                     } else {
@@ -281,11 +1241,11 @@ impl Engine {
                     }
                 }
                 EngineMessage::SwapStages(a, b) => {
-                    self.chain.swap_stages(a, b);
+                    self.edited_chain().swap_stages(a, b);
                     debug!("Swapped stages {a} and {b}");
                 }
                 EngineMessage::SetStageBypassed(idx, bypassed) => {
-                    if self.chain.set_bypassed(idx, bypassed) {
+                    if self.edited_chain().set_bypassed(idx, bypassed) {
                         debug!("Stage {idx} bypass: {bypassed}");
                     } else {
                         error!("SetStageBypassed: stage index {idx} out of bounds");
@@ -310,14 +1270,23 @@ impl Engine {
                         // convolver + name `String`) off the RT thread so
                         // nothing deallocates here.
                         cab.swap_convolver(&mut prepared.convolver);
+                        self.ir_id = ir_name_id(&prepared.name);
+                        self.diagnostics.set_ir(
+                            cab.convolver().ir_length(),
+                            cab.convolver().num_partitions(),
+                        );
                     }
+                    self.awaiting_ir = None;
                     self.rt_drop.retire(prepared);
+                    self.land_held_chain();
                 }
                 EngineMessage::ClearIr => {
                     if let Some(ref mut cab) = self.ir_cabinet {
                         cab.clear_convolver();
+                        self.ir_id = 0;
                         debug!("IR cleared");
                     }
+                    self.awaiting_ir = None;
                 }
                 EngineMessage::SetIrBypass(bypass) => {
                     if let Some(ref mut cab) = self.ir_cabinet {
@@ -331,13 +1300,24 @@ impl Engine {
                         debug!("IR Cabinet gain: {gain}");
                     }
                 }
+                EngineMessage::SetIrTrim(trim) => {
+                    if let Some(ref mut cab) = self.ir_cabinet {
+                        cab.set_trim(trim);
+                        debug!("IR Cabinet trim: {trim}");
+                    }
+                }
                 EngineMessage::SetTunerEnabled(enabled) => {
                     if let Some(ref mut tuner) = self.tuner {
                         tuner.set_enabled(enabled);
                     }
                 }
-                EngineMessage::StartRecording(recorder) => {
-                    self.handle_start_recording(recorder);
+                EngineMessage::SetTunerAlgorithm(algorithm) => {
+                    if let Some(ref mut tuner) = self.tuner {
+                        tuner.set_algorithm(algorithm);
+                    }
+                }
+                EngineMessage::StartRecording(recorder, click) => {
+                    self.handle_start_recording(recorder, click);
                 }
                 EngineMessage::StopRecording => {
                     self.handle_stop_recording();
@@ -348,20 +1328,375 @@ impl Engine {
                 EngineMessage::SetSamplers(new_samplers) => {
                     let old = std::mem::replace(&mut self.samplers, new_samplers);
                     self.rt_drop.retire(old);
+                    self.samplers
+                        .set_light_path(self.load_guard.quality() == Quality::Reduced);
+                    self.retime_standby();
+                    self.diagnostics.set_sampling(
+                        self.samplers.sample_rate(),
+                        self.samplers.get_oversample_factor(),
+                    );
                     debug!("Samplers swapped");
                 }
+                EngineMessage::SetInputTrim(gain) => {
+                    self.input_trim = gain;
+                    debug!("Input trim set to {gain}");
+                }
+                EngineMessage::SetDenoiser(denoiser) => {
+                    self.handle_denoiser(denoiser);
+                }
+                EngineMessage::LearnNoise(capture) => {
+                    if let Some(old) = self.noise_capture.replace(capture) {
+                        self.rt_drop.retire(old);
+                    }
+                    debug!("Learning the noise print");
+                }
+                EngineMessage::SetAmbience(config) => {
+                    self.ambience.set_config(config);
+                    debug!("Ambience set to {config:?}");
+                }
+                EngineMessage::SetTilt(tilt_db) => {
+                    self.tilt.set_tilt_db(tilt_db);
+                    debug!("Tilt set to {tilt_db} dB");
+                }
+                EngineMessage::SetDoubleTracker(config) => {
+                    self.double_tracker.set_config(config);
+                    debug!("Double tracker set to {config:?}");
+                }
+                EngineMessage::SetTestSource(source) => {
+                    if let Some(old) = std::mem::replace(&mut self.test_source, source) {
+                        self.rt_drop.retire(old);
+                    }
+                    debug!("Test source updated");
+                }
+                EngineMessage::SetTakePlayer(player) => {
+                    if let Some(old) = std::mem::replace(&mut self.take_player, player) {
+                        self.rt_drop.retire(old);
+                    }
+                    debug!("Take player updated");
+                }
+                EngineMessage::Take(command) => {
+                    if let Some(player) = self.take_player.as_mut() {
+                        player.apply(command);
+                    }
+                }
+                EngineMessage::SetSampleRate(parts) => {
+                    // Built for the old rate; the GUI sends the chain again.
+                    self.cancel_queued_chain();
+                    self.handle_sample_rate(parts);
+                }
+                EngineMessage::SetLoadGuard(enabled) => {
+                    if let Some(quality) = self.load_guard.set_enabled(enabled) {
+                        self.apply_quality(quality);
+                    }
+                    debug!("Load guard enabled: {enabled}");
+                }
+                EngineMessage::SetMetronomeSounds(sounds) => {
+                    let frame = self.clock.processed.load(Ordering::Relaxed);
+                    let old = match self.metronome {
+                        Some(ref mut metronome) => metronome.set_sounds(sounds, frame),
+                        None => sounds,
+                    };
+                    self.rt_drop.retire(old);
+                    debug!("Metronome sounds updated");
+                }
+                EngineMessage::SetMetronome(config) => {
+                    // Messages are handled before the block's frames are
+                    // counted: this is the block's first frame.
+                    let frame = self.clock.processed.load(Ordering::Relaxed);
+                    if let Some(ref mut metronome) = self.metronome {
+                        metronome.set_config(config, frame);
+                    }
+                    debug!("Metronome: {config:?}");
+                }
+                EngineMessage::SetMonitorPoint(point) => {
+                    self.handle_monitor_point(point);
+                }
+                EngineMessage::SetStandby(on) => {
+                    self.standby.set(on);
+                    debug!("Standby: {on}");
+                }
+                EngineMessage::SetChainMode(right) => {
+                    // The right chain is sent as it stands, so the left one
+                    // can't wait for the bar.
+                    let frame = self.clock.processed.load(Ordering::Relaxed);
+                    self.land_queued_chain(frame, u64::MAX);
+                    self.handle_chain_mode(right);
+                }
+                EngineMessage::Right(message) => match self.right {
+                    Some(ref mut right) => {
+                        right.handle(message, &self.rt_drop);
+                        right
+                            .samplers
+                            .set_light_path(self.load_guard.quality() == Quality::Reduced);
+                    }
+                    None => message.retire(&self.rt_drop),
+                },
+            }
+            // Each message cuts short at most one fade per chain; keep the
+            // chains' holding space for them clear.
+            self.retire_fades();
+        }
+        self.retire_fades();
+
+        if chain_changed {
+            self.clock.mark_chain_changed();
+        }
+        if skipped_post && !self.skips_post() {
+            // A solo ended, by message or by the chain changing under it;
+            // don't bring back tails from before it.
+            self.ambience.reset();
+            self.double_tracker.reset();
+        }
+        self.clock.store_latency(self.latency());
+        self.clock.store_state(self.state(), applied);
+    }
+
+    /// Send stage crossfades that are over, and the stages they faded out,
+    /// off the RT thread.
+    fn retire_fades(&mut self) {
+        let rt_drop = &self.rt_drop;
+        self.chain.drain_fades(|fade| rt_drop.retire(fade));
+        if let Some(ref mut queued) = self.queued_chain {
+            queued.chain.drain_fades(|fade| rt_drop.retire(fade));
+        }
+        if let Some(ref mut right) = self.right {
+            right.chain.drain_fades(|fade| rt_drop.retire(fade));
+        }
+    }
+
+    /// What the engine has applied, as published to [`EngineHandle::state`].
+    pub fn state(&self) -> EngineState {
+        let cabinet = self.ir_cabinet.as_ref();
+        EngineState {
+            ir_id: self.ir_id,
+            ir_gain: cabinet.map_or(0.0, IrCabinet::gain),
+            ir_bypassed: cabinet.is_some_and(IrCabinet::is_bypassed),
+            recording: self.recorder.is_some(),
+            tuner_enabled: self.tuner.as_ref().is_some_and(Tuner::is_enabled),
+        }
+    }
+
+    /// Delay between a sample going in and coming out, with the stages and
+    /// settings in place now.
+    pub fn latency(&self) -> Latency {
+        Latency {
+            oversampling: self.samplers.latency_samples(),
+            denoiser: if self.right.is_some() {
+                0
+            } else {
+                self.denoiser
+                    .as_deref()
+                    .map_or(0, Denoiser::latency_samples)
+            },
+            pitch_shifter: if self.right.is_some() {
+                0
+            } else {
+                self.pitch_shifter
+                    .as_deref()
+                    .map_or(0, PitchShifter::latency_samples)
+            },
+            cabinet: self
+                .ir_cabinet
+                .as_ref()
+                .map_or(0, IrCabinet::latency_samples),
+        }
+    }
+
+    fn handle_apply_preset(&mut self, mut swap: Box<PresetSwap>) {
+        // Exchange rather than move out of the box, so the old chain and IR
+        // end up in `swap` and are retired together without freeing anything
+        // here.
+        std::mem::swap(&mut self.chain, &mut swap.chain);
+        self.awaiting_chain = false;
+        if let Some(ref mut cab) = self.ir_cabinet {
+            // Bypass first, so an IR swapped in while bypassing doesn't fade.
+            if let Some(bypass) = swap.ir_bypass {
+                cab.set_bypass(bypass);
+            }
+            match swap.ir {
+                IrChange::Keep => {}
+                IrChange::Clear => {
+                    cab.clear_convolver();
+                    self.ir_id = 0;
+                    self.awaiting_ir = None;
+                }
+                IrChange::Swap(ref mut prepared) => {
+                    cab.swap_convolver(&mut prepared.convolver);
+                    self.awaiting_ir = None;
+                    self.ir_id = ir_name_id(&prepared.name);
+                    self.diagnostics.set_ir(
+                        cab.convolver().ir_length(),
+                        cab.convolver().num_partitions(),
+                    );
+                }
             }
+            cab.set_gain(swap.ir_gain);
         }
+        self.rt_drop.retire(swap);
+        debug!("Applied preset chain and cabinet");
     }
 
-    fn handle_start_recording(&mut self, recorder: Recorder) {
+    /// Hold `chain` back until the metronome's next bar, replacing any
+    /// chain already waiting. With the metronome stopped, in dual-mono, or
+    /// while the old chain is muted for a new sample rate, it lands in this
+    /// block.
+    fn handle_queue_chain(&mut self, chain: Box<AmplifierChain>) {
+        // Messages are handled before the block's frames are counted: this
+        // is the block's first frame.
+        let frame = self.clock.processed.load(Ordering::Relaxed);
+        let bar = self
+            .metronome
+            .as_ref()
+            .and_then(|metronome| metronome.next_bar(frame))
+            .filter(|_| self.right.is_none() && !self.awaiting_chain);
+        self.replace_queued_chain(QueuedChain {
+            chain,
+            frame: bar.unwrap_or(frame),
+            with_ir: false,
+        });
+        debug!("Queued amplifier chain for frame {}", bar.unwrap_or(frame));
+    }
+
+    /// Hold `chain` back until the next IR is swapped in, or
+    /// [`HELD_CHAIN_TIMEOUT_SECONDS`] have passed, replacing any chain
+    /// already waiting. In dual-mono, or while the old chain is muted for a
+    /// new sample rate, it lands in this block.
+    fn handle_hold_chain(&mut self, chain: Box<AmplifierChain>) {
+        let frame = self.clock.processed.load(Ordering::Relaxed);
+        let timeout = (HELD_CHAIN_TIMEOUT_SECONDS * self.samplers.sample_rate() as f32) as u64;
+        let deadline = if self.right.is_none() && !self.awaiting_chain {
+            frame + timeout
+        } else {
+            frame
+        };
+        self.replace_queued_chain(QueuedChain {
+            chain,
+            frame: deadline,
+            with_ir: true,
+        });
+        debug!("Holding amplifier chain for the next IR");
+    }
+
+    fn replace_queued_chain(&mut self, queued: QueuedChain) {
+        if let Some(old) = self.queued_chain.replace(queued) {
+            self.rt_drop.retire(old.chain);
+        }
+        self.clock.chain_queued.store(true, Ordering::Relaxed);
+    }
+
+    /// Swap in a chain held for the IR just swapped in. Messages are
+    /// applied before the block runs, so both are heard from the same one.
+    fn land_held_chain(&mut self) {
+        if self
+            .queued_chain
+            .as_ref()
+            .is_some_and(|queued| queued.with_ir)
+        {
+            let frame = self.clock.processed.load(Ordering::Relaxed);
+            self.land_queued_chain(frame, u64::MAX);
+        }
+    }
+
+    /// Swap in the queued chain if its frame comes before engine frame
+    /// `until`, stamping it as landed at `frame`.
+    ///
+    /// Called with the block's bounds, the whole block holding the downbeat
+    /// plays the new chain, so a note struck on the one is heard through it.
+    fn land_queued_chain(&mut self, frame: u64, until: u64) {
+        if !self
+            .queued_chain
+            .as_ref()
+            .is_some_and(|queued| queued.frame < until)
+        {
+            return;
+        }
+        if let Some(mut queued) = self.queued_chain.take() {
+            std::mem::swap(&mut self.chain, &mut queued.chain);
+            self.rt_drop.retire(queued.chain);
+            self.awaiting_chain = false;
+            self.clock.chain_queued.store(false, Ordering::Relaxed);
+            self.clock
+                .queued_chain_landed
+                .store(frame, Ordering::Relaxed);
+            self.clock.mark_chain_changed();
+            debug!("Queued amplifier chain landed at frame {frame}");
+        }
+    }
+
+    /// Drop a queued chain that another chain has overtaken.
+    fn cancel_queued_chain(&mut self) {
+        if let Some(old) = self.queued_chain.take() {
+            self.rt_drop.retire(old.chain);
+            self.clock.chain_queued.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// The chain edits apply to: one waiting to land, since it's the chain
+    /// the GUI shows, or else the one playing.
+    fn edited_chain(&mut self) -> &mut AmplifierChain {
+        match self.queued_chain {
+            Some(ref mut queued) => queued.chain.as_mut(),
+            None => self.chain.as_mut(),
+        }
+    }
+
+    fn handle_monitor_point(&mut self, point: Option<MonitorPoint>) {
+        if !self
+            .edited_chain()
+            .set_monitor_point(point.map(|p| p.stage))
+        {
+            error!("SetMonitorPoint: stage index out of bounds");
+            return;
+        }
+        self.monitor_keep_post = point.is_some_and(|p| p.keep_post);
+        debug!("Monitor point set to {point:?}");
+    }
+
+    fn handle_chain_mode(&mut self, right: Option<Box<RightChannel>>) {
+        let old = std::mem::replace(&mut self.right, right);
+        if let Some(old) = old {
+            self.rt_drop.retire(old);
+        }
+        if let Some(ref mut right) = self.right {
+            let reduced = self.load_guard.quality() == Quality::Reduced;
+            right.apply_quality(reduced, self.samplers.sample_rate());
+            right.standby = self.standby;
+        }
+        debug!("Dual-mono: {}", self.right.is_some());
+    }
+
+    /// Keep the standby fade's length as the chain's rate changes.
+    fn retime_standby(&mut self) {
+        let rate = self.samplers.chain_rate();
+        self.standby.set_sample_rate(rate);
+        if let Some(ref mut right) = self.right {
+            right.standby.set_sample_rate(rate);
+        }
+    }
+
+    /// A stage is soloed dry, so nothing after the chain runs.
+    fn skips_post(&self) -> bool {
+        self.chain.monitor_point().is_some() && !self.monitor_keep_post
+    }
+
+    fn handle_start_recording(&mut self, recorder: Recorder, click: Option<Box<Recorder>>) {
         if self.recorder.is_some() {
             debug!("Recorder already active, ignoring start request");
             return;
         }
 
         debug!("Recorder updated");
+        // Messages are handled before the block's frames are counted, so this
+        // is the first frame the recorder writes. A take aligned to the
+        // transport starts earlier, padded up to here.
+        let now = self.clock.processed.load(Ordering::Relaxed);
+        let start = recorder.start_at(now);
+        if let Some(ref click) = click {
+            click.start_at(now);
+        }
+        self.clock.recording_start.store(start, Ordering::Relaxed);
         self.recorder = Some(recorder);
+        self.click_recorder = click;
     }
 
     fn handle_stop_recording(&mut self) {
@@ -376,10 +1711,58 @@ impl Engine {
         {
             error!("Failed to stop recorder: {e}");
         }
+        if let Some(click) = self.click_recorder.take()
+            && let Err(e) = click.stop()
+        {
+            error!("Failed to stop click track recorder: {e}");
+        }
 
         self.recorder = None;
     }
 
+    fn handle_sample_rate(&mut self, mut parts: Box<SampleRateParts>) {
+        std::mem::swap(&mut *self.samplers, &mut parts.samplers);
+        std::mem::swap(&mut self.ambience, &mut parts.ambience);
+        std::mem::swap(&mut self.double_tracker, &mut parts.double_tracker);
+        if let Some(ref mut tuner) = self.tuner {
+            parts.tuner.set_enabled(tuner.is_enabled());
+            parts.tuner.set_algorithm(tuner.algorithm());
+            std::mem::swap(tuner, &mut parts.tuner);
+        }
+        if let Some(ref mut metronome) = self.metronome {
+            // Carries on from here, on a downbeat, at the new rate.
+            let frame = self.clock.processed.load(Ordering::Relaxed);
+            parts.metronome.set_config(metronome.config(), frame);
+            std::mem::swap(metronome, &mut parts.metronome);
+        }
+        let ir_follows = parts.ir_follows;
+        // `parts` now holds the old components.
+        self.rt_drop.retire(parts);
+        self.samplers
+            .set_light_path(self.load_guard.quality() == Quality::Reduced);
+
+        let sample_rate = self.samplers.sample_rate();
+        self.tilt.set_sample_rate(sample_rate);
+        self.retime_standby();
+        let right_meter = self.right.as_deref_mut().map(|r| &mut r.peak_meter);
+        for meter in [&mut self.peak_meter, &mut self.input_meter]
+            .into_iter()
+            .chain(right_meter)
+            .flatten()
+        {
+            meter.set_sample_rate(sample_rate);
+        }
+        self.diagnostics
+            .set_sampling(sample_rate, self.samplers.get_oversample_factor());
+        // Start from silence and fade back in once the new chain is in.
+        self.tuner_gain = 0.0;
+        self.awaiting_chain = true;
+        let frame = self.clock.processed.load(Ordering::Relaxed);
+        let timeout = (RETUNED_IR_TIMEOUT_SECONDS * sample_rate as f32) as u64;
+        self.awaiting_ir = ir_follows.then_some(frame + timeout);
+        debug!("Sample rate changed to {sample_rate}");
+    }
+
     fn handle_pitch_shift(&mut self, shifter: Option<Box<PitchShifter>>) {
         // The shifter (if any) is constructed off the RT thread in
         // `EngineHandle::set_pitch_shift`; here we just swap it in and retire
@@ -390,6 +1773,40 @@ impl Engine {
         }
         debug!("Pitch shifter updated");
     }
+
+    fn handle_denoiser(&mut self, mut denoiser: Option<Box<Denoiser>>) {
+        // New settings carry on from the running denoiser's frames, so
+        // moving the reduction doesn't restart them.
+        if let (Some(new), Some(old)) = (denoiser.as_deref_mut(), self.denoiser.as_deref()) {
+            new.continue_from(old);
+        }
+        let old = std::mem::replace(&mut self.denoiser, denoiser);
+        if let Some(old) = old {
+            self.rt_drop.retire(old);
+        }
+        debug!("Denoiser updated");
+    }
+}
+
+/// Turn a mid signal in `left` and a side signal in `right` into a left and
+/// right pair.
+fn mid_side_to_stereo(left: &mut [f32], right: &mut [f32]) {
+    for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+        let (mid, side) = (*l, *r);
+        *l = mid + side;
+        *r = mid - side;
+    }
+}
+
+/// Zero any NaN/Inf samples in `buf`. Returns `true` if any were found.
+pub(crate) fn flush_non_finite(buf: &mut [f32]) -> bool {
+    if buf.iter().all(|s| s.is_finite()) {
+        return false;
+    }
+    for s in buf.iter_mut().filter(|s| !s.is_finite()) {
+        *s = 0.0;
+    }
+    true
 }
 
 impl Drop for Engine {
@@ -400,12 +1817,20 @@ impl Drop for Engine {
                 error!("Failed to stop recorder: {e}");
             }
         }
+        if let Some(click) = self.click_recorder.take()
+            && let Err(e) = click.stop()
+        {
+            error!("Failed to stop click track recorder: {e}");
+        }
     }
 }
 
 impl EngineHandle {
     pub fn send(&self, message: EngineMessage) {
+        // Counted before it can be applied, so `applied` never overtakes it.
+        self.clock.sent.fetch_add(1, Ordering::AcqRel);
         self.engine_sender.try_send(message).unwrap_or_else(|e| {
+            self.clock.sent.fetch_sub(1, Ordering::AcqRel);
             error!("Failed to send engine message: {e}");
         });
     }
@@ -415,6 +1840,31 @@ impl EngineHandle {
         self.send(update);
     }
 
+    /// Run a second chain on the right input, or go back to one chain with
+    /// `None`. The right channel starts empty; send it the current chain,
+    /// filters and IR with [`Self::send_right`] straight after.
+    pub fn set_chain_mode(&self, right: Option<RightChannel>) {
+        self.dual_mono.store(right.is_some(), Ordering::Relaxed);
+        self.send(EngineMessage::SetChainMode(right.map(Box::new)));
+    }
+
+    /// The mode last set through [`Self::set_chain_mode`].
+    pub fn chain_mode(&self) -> ChainMode {
+        if self.dual_mono.load(Ordering::Relaxed) {
+            ChainMode::DualMono
+        } else {
+            ChainMode::Mono
+        }
+    }
+
+    /// Send the right channel its own copy of something built for the left.
+    /// Does nothing in mono.
+    pub fn send_right(&self, message: RightMessage) {
+        if self.chain_mode() == ChainMode::DualMono {
+            self.send(EngineMessage::Right(message));
+        }
+    }
+
     pub fn clear_ir(&self) {
         self.send(EngineMessage::ClearIr);
     }
@@ -429,19 +1879,77 @@ impl EngineHandle {
         self.send(update);
     }
 
+    /// Level-matching trim for comparing IRs, applied on top of the IR gain
+    /// so the gain itself (and the preset) are left alone.
+    pub fn set_ir_trim_db(&self, trim_db: f32) {
+        self.send(EngineMessage::SetIrTrim(db_to_lin(trim_db)));
+    }
+
     pub fn set_tuner_enabled(&self, enabled: bool) {
         let update = EngineMessage::SetTunerEnabled(enabled);
         self.send(update);
     }
 
+    pub fn set_tuner_algorithm(&self, algorithm: TunerAlgorithm) {
+        let update = EngineMessage::SetTunerAlgorithm(algorithm);
+        self.send(update);
+    }
+
+    /// The engine's latency as of its last block.
+    pub fn latency(&self) -> Latency {
+        self.clock.latency()
+    }
+
+    /// What the engine has applied, or `None` while messages sent to it are
+    /// still queued and it would be behind. A message that couldn't be sent
+    /// doesn't hold it up, so the difference shows.
+    pub fn state(&self) -> Option<EngineState> {
+        self.clock.state()
+    }
+
+    /// Frames the engine has processed. A message sent now takes effect at
+    /// this frame (give or take a callback racing the send).
+    pub fn frames_processed(&self) -> u64 {
+        self.clock.processed.load(Ordering::Relaxed)
+    }
+
+    /// Engine frame the most recent recording's first sample belongs to. Only
+    /// meaningful once the engine has picked up the recorder.
+    pub fn recording_start_frame(&self) -> u64 {
+        self.clock.recording_start.load(Ordering::Relaxed)
+    }
+
+    /// When the engine last applied a change to the chain's stages, at the
+    /// start of the block that first plays it. `None` if it never has.
+    pub fn last_chain_change(&self) -> Option<Instant> {
+        self.clock.chain_changed_at()
+    }
+
     pub fn set_parameter(&self, stage_idx: usize, name: &'static str, value: f32) {
         self.send(EngineMessage::SetParameter(stage_idx, name, value));
     }
 
+    pub fn set_child_parameter(
+        &self,
+        stage_idx: usize,
+        child: usize,
+        name: &'static str,
+        value: f32,
+    ) {
+        self.send(EngineMessage::SetChildParameter(
+            stage_idx, child, name, value,
+        ));
+    }
+
     pub fn replace_stage(&self, idx: usize, stage: Box<dyn Stage>) {
         self.send(EngineMessage::ReplaceStage(idx, stage));
     }
 
+    /// Replace the stage at `idx`, fading over to the new one.
+    pub fn crossfade_stage(&self, idx: usize, fade: StageCrossfade) {
+        self.send(EngineMessage::CrossfadeStage(idx, Box::new(fade)));
+    }
+
     pub fn add_stage(&self, idx: usize, stage: Box<dyn Stage>) {
         self.send(EngineMessage::AddStage(idx, stage));
     }
@@ -459,6 +1967,38 @@ impl EngineHandle {
         self.send(update);
     }
 
+    /// Swap in `new_chain` on the metronome's next bar, see
+    /// [`EngineMessage::QueueAmpChain`].
+    pub fn queue_amp_chain(&self, new_chain: AmplifierChain) {
+        self.send(EngineMessage::QueueAmpChain(Box::new(new_chain)));
+    }
+
+    /// Swap in `new_chain` with the next IR, see
+    /// [`EngineMessage::HoldAmpChain`].
+    pub fn hold_amp_chain(&self, new_chain: AmplifierChain) {
+        self.send(EngineMessage::HoldAmpChain(Box::new(new_chain)));
+    }
+
+    /// Whether a chain sent with [`Self::queue_amp_chain`] is still waiting
+    /// for its bar.
+    pub fn chain_queued(&self) -> bool {
+        self.clock.chain_queued.load(Ordering::Relaxed) || !self.clock.caught_up()
+    }
+
+    /// Engine frame of the block the last queued chain landed in; `None`
+    /// if none has.
+    pub fn queued_chain_landed_at(&self) -> Option<u64> {
+        match self.clock.queued_chain_landed.load(Ordering::Relaxed) {
+            u64::MAX => None,
+            frame => Some(frame),
+        }
+    }
+
+    /// Swap in a preset's chain and cabinet settings in the same block.
+    pub fn apply_preset(&self, swap: PresetSwap) {
+        self.send(EngineMessage::ApplyPreset(Box::new(swap)));
+    }
+
     pub fn set_pitch_shift(&self, semitones: i32) {
         // Construct the pitch shifter here (GUI thread) so the RT thread never
         // allocates its FFT plans / scratch buffers. `0` semitones == bypass.
@@ -470,10 +2010,73 @@ impl EngineHandle {
         self.send(EngineMessage::SetPitchShift(shifter));
     }
 
+    /// Denoise the input as `config` says, at `sample_rate`. The denoiser is
+    /// built here so the RT thread never allocates its FFT plans.
+    pub fn set_denoiser(&self, config: &DenoiseConfig, sample_rate: usize) {
+        self.send(EngineMessage::SetDenoiser(Denoiser::for_config(
+            config,
+            sample_rate,
+        )));
+    }
+
+    /// Start learning the input's noise print at `sample_rate`; poll the
+    /// returned [`NoiseLearn`] for the result. Replaces a learn in progress.
+    pub fn learn_noise(&self, sample_rate: usize) -> NoiseLearn {
+        let (learn, capture) = NoiseLearn::start(sample_rate);
+        self.send(EngineMessage::LearnNoise(capture));
+        learn
+    }
+
     pub fn set_stage_bypassed(&self, idx: usize, bypassed: bool) {
         self.send(EngineMessage::SetStageBypassed(idx, bypassed));
     }
 
+    /// Gain the raw input by `trim_db` before the input filters and the chain.
+    /// Callers combining several trims (e.g. interface calibration and a
+    /// preset's own) sum them in dB and send the total.
+    pub fn set_input_trim_db(&self, trim_db: f32) {
+        self.send(EngineMessage::SetInputTrim(db_to_lin(trim_db)));
+    }
+
+    pub fn set_ambience(&self, config: AmbienceConfig) {
+        self.send(EngineMessage::SetAmbience(config));
+    }
+
+    pub fn set_double_tracker(&self, config: DoubleTrackerConfig) {
+        self.send(EngineMessage::SetDoubleTracker(config));
+    }
+
+    /// Tilt everything after the IR by `tilt_db`, gliding from the last
+    /// setting.
+    pub fn set_tilt_db(&self, tilt_db: f32) {
+        self.send(EngineMessage::SetTilt(tilt_db));
+    }
+
+    /// Play a generated signal in place of the input, or go back to the
+    /// input when `config` is off. The generator is built here so the RT
+    /// thread never allocates its wavetable; `di` is the sample looped by
+    /// [`TestSourceKind::DiSample`].
+    pub fn set_test_source(
+        &self,
+        config: TestSourceConfig,
+        sample_rate: usize,
+        di: Option<Arc<[f32]>>,
+    ) {
+        let source = (config.kind != TestSourceKind::Off)
+            .then(|| Box::new(TestSource::new(config, sample_rate, di)));
+        self.send(EngineMessage::SetTestSource(source));
+    }
+
+    /// Play `player`'s takes in place of the output, stopped at the start,
+    /// or stop comparing takes with `None`.
+    pub fn set_take_player(&self, player: Option<TakePlayer>) {
+        self.send(EngineMessage::SetTakePlayer(player.map(Box::new)));
+    }
+
+    pub fn take_command(&self, command: TakeCommand) {
+        self.send(EngineMessage::Take(command));
+    }
+
     pub fn set_input_filters(&self, hp: Option<Box<dyn Stage>>, lp: Option<Box<dyn Stage>>) {
         let update = EngineMessage::SetInputFilters(hp, lp);
         self.send(update);
@@ -484,21 +2087,109 @@ impl EngineHandle {
         sample_rate: usize,
         output_dir: &str,
         max_block_samples: usize,
-    ) -> Result<()> {
-        let recorder = Recorder::new(sample_rate as u32, output_dir, max_block_samples)?;
+        transport: Option<TransportStart>,
+        metadata: Option<RecordingMetadata>,
+        softclip: bool,
+    ) -> Result<PathBuf> {
+        let align_to = transport.map(|t| t.engine_frame);
+        let click_track = metadata
+            .as_ref()
+            .and_then(|m| m.metronome)
+            .is_some_and(|m| m.click_track);
+        let recorder = Recorder::new(
+            sample_rate as u32,
+            output_dir,
+            max_block_samples,
+            transport.map(|t| t.frame),
+            metadata,
+        )?
+        .with_softclip(softclip)
+        .aligned_to(align_to);
+        let path = recorder.path().to_path_buf();
+        let progress = recorder.progress();
+        let click = if click_track {
+            Some(Box::new(
+                Recorder::create(
+                    Recorder::click_track_path(&path),
+                    sample_rate as u32,
+                    max_block_samples,
+                    None,
+                )?
+                .aligned_to(align_to),
+            ))
+        } else {
+            None
+        };
 
-        let update = EngineMessage::StartRecording(recorder);
+        let update = EngineMessage::StartRecording(recorder, click);
         self.send(update);
+        *self.lock_recording() = Some(progress);
 
-        Ok(())
+        Ok(path)
     }
 
-    pub fn stop_recording(&self) {
+    /// Stop the take. Returns whether the soft clip saturated any of it.
+    pub fn stop_recording(&self) -> bool {
         let update = EngineMessage::StopRecording;
         self.send(update);
+        self.lock_recording()
+            .take()
+            .is_some_and(|p| p.softclipped())
+    }
+
+    /// Elapsed time, level and size of the running take, or `None` when not
+    /// recording. The peak is since the previous call.
+    pub fn recording_status(&self) -> Option<RecordingStatus> {
+        self.lock_recording().as_ref().map(|p| p.status())
+    }
+
+    /// Blocks lost from the running take so far, see
+    /// [`RecordingProgress::overruns`]; zero when not recording. Unlike
+    /// [`Self::recording_status`] this leaves the peak alone.
+    pub fn recording_overruns(&self) -> u64 {
+        self.lock_recording().as_ref().map_or(0, |p| p.overruns())
+    }
+
+    fn lock_recording(&self) -> MutexGuard<'_, Option<Arc<RecordingProgress>>> {
+        self.recording
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Let the engine trade quality for headroom when blocks run close to
+    /// their deadline, rather than crackle.
+    pub fn set_load_guard(&self, enabled: bool) {
+        self.send(EngineMessage::SetLoadGuard(enabled));
+    }
+
+    /// Swap the metronome's clicks; `sounds` must already be decoded at the
+    /// engine's sample rate.
+    pub fn set_metronome_sounds(&self, sounds: MetronomeSounds) {
+        self.send(EngineMessage::SetMetronomeSounds(Box::new(sounds)));
+    }
+
+    /// Start, stop or retime the metronome; see [`MetronomeConfig`].
+    pub fn set_metronome(&self, config: MetronomeConfig) {
+        self.send(EngineMessage::SetMetronome(config));
+    }
+
+    /// Solo a stage, or pass `None` to hear the whole chain again.
+    pub fn set_monitor_point(&self, point: Option<MonitorPoint>) {
+        self.send(EngineMessage::SetMonitorPoint(point));
+    }
+
+    /// Mute the power amp's output, fading over
+    /// [`STANDBY_FADE_SECONDS`](crate::audio::standby::STANDBY_FADE_SECONDS),
+    /// while the stages keep running; or bring it back.
+    pub fn set_standby(&self, on: bool) {
+        self.send(EngineMessage::SetStandby(on));
     }
 
     pub fn set_samplers(&self, samplers: Samplers) {
         self.send(EngineMessage::SetSamplers(Box::new(samplers)));
     }
+
+    pub fn set_sample_rate(&self, parts: SampleRateParts) {
+        self.send(EngineMessage::SetSampleRate(Box::new(parts)));
+    }
 }
@@ -0,0 +1,299 @@
+use std::time::{Duration, Instant};
+
+use crate::amp::chain::DEFAULT_CHAIN_CAPACITY;
+use crate::amp::stages::common::db_to_lin;
+use crate::amp::stages::level::LevelConfig;
+use crate::preset::{StageCategory, StageConfig};
+
+/// How long the player is given to play their loudest passage.
+pub const ANALYSIS_DURATION: Duration = Duration::from_secs(5);
+
+/// A stage whose output peaks above this gets a suggestion. Nothing clips in
+/// floating point, but level-sensitive stages after it (NAM, captures, the
+/// compressor) stop behaving as voiced.
+pub const HOT_STAGE_DBFS: f32 = 0.0;
+
+/// Where a suggestion brings a hot stage's output back down to.
+pub const TARGET_PEAK_DBFS: f32 = -6.0;
+
+/// Highest gain a Level stage accepts.
+const MAX_LEVEL_GAIN: f32 = 2.0;
+
+/// Gain staging capture: the loudest output of every stage over
+/// [`ANALYSIS_DURATION`], read from the engine's per-stage peaks.
+///
+/// Driven by the GUI's meter polling: feed each reading to [`Self::observe`].
+#[derive(Debug, Clone, Default)]
+pub struct GainStagingCapture {
+    started: Option<Instant>,
+    /// Linear peak per stage, held at its maximum.
+    peaks: Vec<f32>,
+    done: bool,
+}
+
+impl GainStagingCapture {
+    pub const fn new() -> Self {
+        Self {
+            started: None,
+            peaks: Vec::new(),
+            done: false,
+        }
+    }
+
+    pub const fn is_capturing(&self) -> bool {
+        self.started.is_some() && !self.done
+    }
+
+    pub const fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Start (or restart) a capture of a `stage_count`-stage chain at `now`.
+    pub fn start(&mut self, now: Instant, stage_count: usize) {
+        self.started = Some(now);
+        self.peaks = vec![0.0; stage_count];
+        self.done = false;
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Record per-stage peaks read at `now`. Finishes the capture once
+    /// [`ANALYSIS_DURATION`] has passed; ignored unless capturing.
+    pub fn observe(&mut self, now: Instant, peaks: &[f32]) {
+        let Some(started) = self.started.filter(|_| !self.done) else {
+            return;
+        };
+        for (held, &peak) in self.peaks.iter_mut().zip(peaks) {
+            *held = held.max(peak);
+        }
+        self.done = now.duration_since(started) >= ANALYSIS_DURATION;
+    }
+
+    /// Fraction of the capture window elapsed at `now`, `0.0..=1.0`.
+    pub fn progress(&self, now: Instant) -> f32 {
+        match self.started {
+            _ if self.done => 1.0,
+            Some(started) => (now.duration_since(started).as_secs_f32()
+                / ANALYSIS_DURATION.as_secs_f32())
+            .min(1.0),
+            None => 0.0,
+        }
+    }
+
+    /// Peak output of each stage in dBFS; `-inf` for a stage that stayed
+    /// silent or was bypassed.
+    pub fn peaks_db(&self) -> Vec<f32> {
+        self.peaks.iter().map(|p| 20.0 * p.log10()).collect()
+    }
+}
+
+/// What a suggestion does to the chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fix {
+    /// Set the Level stage at `stage_idx` to `gain`.
+    SetLevel { stage_idx: usize, gain: f32 },
+    /// Insert a Level stage with `gain` right after stage `after`.
+    InsertLevel { after: usize, gain: f32 },
+    /// Nothing to do automatically: Level stages can't follow effects, so
+    /// the stage's own output has to come down.
+    Manual,
+}
+
+/// A stage that runs hot, and how to bring it down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+    pub stage_idx: usize,
+    pub peak_db: f32,
+    pub fix: Fix,
+}
+
+/// Suggestions for every stage of `stages` whose output, given the fixes
+/// already suggested before it, peaks above [`HOT_STAGE_DBFS`].
+///
+/// Later peaks are estimated by shifting them by the cuts made upstream,
+/// which is only exact for linear stages; run the analysis again after
+/// applying.
+pub fn suggest(stages: &[StageConfig], peaks_db: &[f32]) -> Vec<Suggestion> {
+    // Level gains as the suggestions so far leave them.
+    let mut gains: Vec<Option<f32>> = stages
+        .iter()
+        .map(|s| match s {
+            StageConfig::Level(cfg) if !cfg.bypassed => Some(cfg.gain),
+            _ => None,
+        })
+        .collect();
+    let mut offset_db = 0.0;
+    let mut out = Vec::new();
+
+    for (idx, (stage, &measured)) in stages.iter().zip(peaks_db).enumerate() {
+        let peak_db = measured + offset_db;
+        if stage.bypassed() || !peak_db.is_finite() || peak_db <= HOT_STAGE_DBFS {
+            continue;
+        }
+        let cut_db = TARGET_PEAK_DBFS - peak_db;
+
+        // Prefer a Level stage already in place: this one, or the next.
+        let level = [idx, idx + 1]
+            .into_iter()
+            .find(|&i| gains.get(i).copied().flatten().is_some());
+        let fix = if let Some(level_idx) = level {
+            let gain = gains[level_idx].unwrap_or(1.0);
+            let gain = (gain * db_to_lin(cut_db)).clamp(0.0, MAX_LEVEL_GAIN);
+            gains[level_idx] = Some(gain);
+            Fix::SetLevel {
+                stage_idx: level_idx,
+                gain,
+            }
+        } else if stage.category() == StageCategory::Amp {
+            Fix::InsertLevel {
+                after: idx,
+                gain: db_to_lin(cut_db),
+            }
+        } else {
+            Fix::Manual
+        };
+        if fix != Fix::Manual {
+            offset_db += cut_db;
+        }
+        out.push(Suggestion {
+            stage_idx: idx,
+            peak_db,
+            fix,
+        });
+    }
+
+    out
+}
+
+/// `stages` with every automatic fix in `suggestions` applied. Inserts stop
+/// once the chain reaches [`DEFAULT_CHAIN_CAPACITY`].
+pub fn apply_suggestions(stages: &[StageConfig], suggestions: &[Suggestion]) -> Vec<StageConfig> {
+    let mut out = stages.to_vec();
+
+    // Gains are absolute and already cumulative, so apply them in order,
+    // before any insert shifts the indices.
+    for s in suggestions {
+        if let Fix::SetLevel { stage_idx, gain } = s.fix
+            && let Some(StageConfig::Level(cfg)) = out.get_mut(stage_idx)
+        {
+            cfg.gain = gain;
+        }
+    }
+
+    let mut room = DEFAULT_CHAIN_CAPACITY.saturating_sub(out.len());
+    let inserts: Vec<(usize, f32)> = suggestions
+        .iter()
+        .filter_map(|s| match s.fix {
+            Fix::InsertLevel { after, gain } => Some((after, gain)),
+            _ => None,
+        })
+        .take_while(|_| {
+            let fits = room > 0;
+            room = room.saturating_sub(1);
+            fits
+        })
+        .collect();
+    // Back to front, so each insert leaves the earlier indices alone.
+    for &(after, gain) in inserts.iter().rev() {
+        let at = (after + 1).min(out.len());
+        out.insert(
+            at,
+            StageConfig::Level(LevelConfig {
+                gain,
+                ..LevelConfig::default()
+            }),
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset::StageType;
+
+    const TOL: f32 = 1e-4;
+
+    fn level(gain: f32) -> StageConfig {
+        StageConfig::Level(LevelConfig {
+            gain,
+            ..LevelConfig::default()
+        })
+    }
+
+    #[test]
+    fn capture_holds_each_stages_loudest_block() {
+        let start = Instant::now();
+        let mut capture = GainStagingCapture::new();
+        capture.observe(start, &[1.0]);
+        assert!(!capture.is_capturing());
+
+        capture.start(start, 2);
+        capture.observe(start + Duration::from_secs(1), &[0.5, 0.1]);
+        capture.observe(start + Duration::from_secs(2), &[0.25, 1.0]);
+        assert!(capture.is_capturing());
+
+        capture.observe(start + ANALYSIS_DURATION, &[0.0, 0.0]);
+        assert!(capture.is_done());
+        let db = capture.peaks_db();
+        assert!((db[0] - 20.0 * 0.5_f32.log10()).abs() < TOL);
+        assert!(db[1].abs() < TOL);
+    }
+
+    #[test]
+    fn cool_chain_gets_no_suggestions() {
+        let stages = vec![StageConfig::from(StageType::Preamp), level(1.0)];
+        assert!(suggest(&stages, &[-3.0, -3.0]).is_empty());
+    }
+
+    #[test]
+    fn hot_stage_turns_down_the_next_level_stage() {
+        let stages = vec![StageConfig::from(StageType::Preamp), level(1.0)];
+        let suggestions = suggest(&stages, &[6.0, 6.0]);
+        assert_eq!(suggestions.len(), 1);
+        let Fix::SetLevel { stage_idx, gain } = suggestions[0].fix else {
+            panic!("expected a level change, got {:?}", suggestions[0].fix);
+        };
+        assert_eq!(stage_idx, 1);
+        assert!((gain - db_to_lin(-12.0)).abs() < TOL);
+
+        let applied = apply_suggestions(&stages, &suggestions);
+        assert!(matches!(&applied[1], StageConfig::Level(cfg) if (cfg.gain - gain).abs() < TOL));
+    }
+
+    #[test]
+    fn hot_stage_without_a_level_gets_one_inserted() {
+        let stages = vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::PowerAmp),
+        ];
+        // The cut after the preamp brings the power amp down with it.
+        let suggestions = suggest(&stages, &[7.0, 9.0]);
+        assert_eq!(suggestions.len(), 1);
+        assert!((suggestions[0].peak_db - 7.0).abs() < TOL);
+
+        let applied = apply_suggestions(&stages, &suggestions);
+        assert_eq!(applied.len(), 3);
+        assert_eq!(applied[1].stage_type(), StageType::Level);
+        assert_eq!(applied[2].stage_type(), StageType::PowerAmp);
+    }
+
+    #[test]
+    fn hot_effects_are_left_to_the_player() {
+        let stages = vec![StageConfig::from(StageType::Delay)];
+        let suggestions = suggest(&stages, &[3.0]);
+        assert_eq!(suggestions[0].fix, Fix::Manual);
+        assert_eq!(apply_suggestions(&stages, &suggestions), stages);
+    }
+
+    #[test]
+    fn silent_and_bypassed_stages_are_skipped() {
+        let mut preamp = StageConfig::from(StageType::Preamp);
+        preamp.set_bypassed(true);
+        let stages = vec![preamp, StageConfig::from(StageType::Compressor)];
+        assert!(suggest(&stages, &[9.0, f32::NEG_INFINITY]).is_empty());
+    }
+}
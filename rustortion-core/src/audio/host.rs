@@ -0,0 +1,32 @@
+//! Which audio system the standalone app runs its stream on.
+
+use serde::{Deserialize, Serialize};
+
+/// The audio API asked for in the settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HostApi {
+    /// JACK when a server is running, otherwise the system's own audio
+    /// through CPAL.
+    #[default]
+    Auto,
+    /// JACK or PipeWire-JACK only; startup fails without a server.
+    Jack,
+    /// The system's own audio (ALSA, CoreAudio, WASAPI) through CPAL, even
+    /// when JACK is running. Picks devices instead of routing ports, and its
+    /// latency can't be changed while it runs.
+    Cpal,
+}
+
+impl HostApi {
+    pub const ALL: [Self; 3] = [Self::Auto, Self::Jack, Self::Cpal];
+}
+
+impl std::fmt::Display for HostApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "Auto"),
+            Self::Jack => write!(f, "JACK"),
+            Self::Cpal => write!(f, "System audio (CPAL)"),
+        }
+    }
+}
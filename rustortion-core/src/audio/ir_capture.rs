@@ -0,0 +1,119 @@
+//! IR capture: the chain's linear response, cabinet included, as an impulse
+//! response another device can load.
+//!
+//! The chain is measured offline on its own engine with the stages an IR
+//! can't hold (clipping, dynamics, modulation and time effects) bypassed.
+//! What's left is linear and time-invariant, and an offline render has no
+//! noise to average out, so a single impulse measures it exactly.
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use log::info;
+
+use crate::audio::ambience::AmbienceConfig;
+use crate::audio::double_tracker::DoubleTrackerConfig;
+use crate::audio::offline::{preset_convolver, render_samples, write_mono_wav};
+use crate::ir::convolver::Convolver;
+use crate::ir::loader::IrLoader;
+use crate::preset::{Preset, StageConfig};
+
+/// Shortest capture, in samples.
+pub const MIN_CAPTURE_LEN: usize = 1024;
+/// Longest capture, in samples.
+pub const MAX_CAPTURE_LEN: usize = 8192;
+pub const DEFAULT_CAPTURE_LEN: usize = 4096;
+/// Height of the measuring impulse, low so nothing near clipping is reached.
+const IMPULSE_LEVEL: f32 = 0.1;
+/// Silence played before the impulse, so glides the engine starts with, like
+/// the tilt's, have settled when it arrives.
+const SETTLE_SAMPLES: usize = 4096;
+/// Peak of an exported IR.
+const NORMALIZED_PEAK: f32 = 0.9;
+/// Below this peak the chain is taken to pass nothing.
+const SILENCE: f32 = 1e-6;
+
+/// Whether a stage's effect fits in an impulse response.
+fn is_linear(cfg: &StageConfig) -> bool {
+    match cfg {
+        StageConfig::ToneStack(_) | StageConfig::Level(_) | StageConfig::Eq(_) => true,
+        StageConfig::Group(group) => group.stages.iter().all(is_linear),
+        _ => false,
+    }
+}
+
+/// `preset` with everything an IR can't hold turned off: non-linear stages
+/// are bypassed, and the pitch shifter, ambience and double tracker are off.
+pub fn linearized(preset: &Preset) -> Preset {
+    let mut preset = preset.clone();
+    for cfg in &mut preset.stages {
+        if !is_linear(cfg) {
+            cfg.set_bypassed(true);
+        }
+    }
+    preset.pitch_shift_semitones = 0;
+    preset.ambience = AmbienceConfig::OFF;
+    preset.double_tracker = DoubleTrackerConfig::OFF;
+    preset
+}
+
+/// The first `length` samples of `preset`'s linear response through
+/// `convolver`, at unity scale, so convolving a signal with it sounds like
+/// the linear part of the chain. Runs on an engine of its own.
+pub fn capture_ir(
+    preset: &Preset,
+    sample_rate: usize,
+    oversampling_factor: u32,
+    convolver: Option<Convolver>,
+    length: usize,
+) -> Result<Vec<f32>> {
+    if !(MIN_CAPTURE_LEN..=MAX_CAPTURE_LEN).contains(&length) {
+        bail!("IR capture length must be {MIN_CAPTURE_LEN} to {MAX_CAPTURE_LEN} samples");
+    }
+
+    let mut impulse = vec![0.0f32; SETTLE_SAMPLES + length];
+    impulse[SETTLE_SAMPLES] = IMPULSE_LEVEL;
+    let rendered = render_samples(
+        &linearized(preset),
+        &impulse,
+        sample_rate,
+        oversampling_factor,
+        convolver,
+        |_| {},
+    )?;
+    Ok(rendered[SETTLE_SAMPLES..]
+        .iter()
+        .map(|sample| sample / IMPULSE_LEVEL)
+        .collect())
+}
+
+/// Capture `preset`'s linear response, its IR loaded through `ir_loader`,
+/// and write it to `output` as a normalized mono WAV at `sample_rate`.
+pub fn export_ir(
+    preset: &Preset,
+    sample_rate: usize,
+    oversampling_factor: u32,
+    ir_loader: Option<&IrLoader>,
+    length: usize,
+    output: &Path,
+) -> Result<()> {
+    let convolver = preset_convolver(preset, sample_rate, ir_loader);
+    let mut ir = capture_ir(preset, sample_rate, oversampling_factor, convolver, length)?;
+
+    let peak = ir.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak < SILENCE {
+        bail!("The chain passes no signal once its non-linear stages are bypassed");
+    }
+    let scale = NORMALIZED_PEAK / peak;
+    for sample in &mut ir {
+        *sample *= scale;
+    }
+
+    write_mono_wav(output, &ir, sample_rate)?;
+    info!(
+        "Captured the linear response of '{}' to {}",
+        preset.name,
+        output.display()
+    );
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! Watches how long each block takes against the time the callback has for
+//! it, and decides when the engine should shed work rather than glitch.
+
+/// Share of the period a block may take before it counts as overloaded.
+const OVERLOAD_RATIO: f32 = 0.8;
+/// Blocks under this share of the period count towards recovery.
+const CALM_RATIO: f32 = 0.5;
+/// Overloaded blocks in a row before quality is reduced.
+const OVERLOAD_BLOCKS: u32 = 3;
+/// Seconds of calm blocks in a row before full quality comes back.
+const RECOVERY_SECONDS: f32 = 3.0;
+
+/// How long switching quality either way crossfades.
+pub const QUALITY_FADE_SECONDS: f32 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Quality {
+    Full,
+    /// Oversampling runs through cheap resamplers and IRs play only their
+    /// head.
+    Reduced,
+}
+
+/// Hysteresis between full and reduced quality.
+///
+/// Quality drops after a few overloaded blocks in a row, and only comes back
+/// once the load has stayed well below the limit for a few seconds, so a
+/// borderline load doesn't flap between the two.
+#[derive(Debug)]
+pub struct LoadGuard {
+    enabled: bool,
+    quality: Quality,
+    overloaded_blocks: u32,
+    calm_seconds: f32,
+}
+
+impl Default for LoadGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoadGuard {
+    /// Starts off, at full quality.
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            quality: Quality::Full,
+            overloaded_blocks: 0,
+            calm_seconds: 0.0,
+        }
+    }
+
+    pub const fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn the guard on or off. Turning it off restores full quality at
+    /// once; returns the new quality if that changed it.
+    pub const fn set_enabled(&mut self, enabled: bool) -> Option<Quality> {
+        self.enabled = enabled;
+        self.overloaded_blocks = 0;
+        self.calm_seconds = 0.0;
+        if !enabled && matches!(self.quality, Quality::Reduced) {
+            self.quality = Quality::Full;
+            return Some(Quality::Full);
+        }
+        None
+    }
+
+    /// Record a block that took `busy` seconds of the `period` seconds it
+    /// had. Returns the new quality when this block changes it.
+    pub fn observe(&mut self, busy: f32, period: f32) -> Option<Quality> {
+        if !self.enabled || period <= 0.0 {
+            return None;
+        }
+        let load = busy / period;
+
+        match self.quality {
+            Quality::Full => {
+                if load <= OVERLOAD_RATIO {
+                    self.overloaded_blocks = 0;
+                    return None;
+                }
+                self.overloaded_blocks += 1;
+                if self.overloaded_blocks < OVERLOAD_BLOCKS {
+                    return None;
+                }
+                self.overloaded_blocks = 0;
+                self.calm_seconds = 0.0;
+                self.quality = Quality::Reduced;
+            }
+            Quality::Reduced => {
+                if load >= CALM_RATIO {
+                    self.calm_seconds = 0.0;
+                    return None;
+                }
+                self.calm_seconds += period;
+                if self.calm_seconds < RECOVERY_SECONDS {
+                    return None;
+                }
+                self.calm_seconds = 0.0;
+                self.quality = Quality::Full;
+            }
+        }
+        Some(self.quality)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// About 188 frames at 48 kHz; a power of two so the guard's running
+    /// total of calm time adds up exactly.
+    const PERIOD: f32 = 1.0 / 256.0;
+
+    fn enabled() -> LoadGuard {
+        let mut guard = LoadGuard::new();
+        guard.set_enabled(true);
+        guard
+    }
+
+    fn run(guard: &mut LoadGuard, load: f32, blocks: usize) -> Vec<Quality> {
+        (0..blocks)
+            .filter_map(|_| guard.observe(load * PERIOD, PERIOD))
+            .collect()
+    }
+
+    fn blocks_for(seconds: f32) -> usize {
+        (seconds / PERIOD).ceil() as usize
+    }
+
+    #[test]
+    fn reduces_after_consecutive_overloads_only() {
+        let mut guard = enabled();
+
+        // Isolated spikes, however bad, don't trip it.
+        for _ in 0..10 {
+            assert_eq!(run(&mut guard, 2.0, OVERLOAD_BLOCKS as usize - 1), []);
+            assert_eq!(run(&mut guard, 0.3, 1), []);
+        }
+        assert_eq!(guard.quality(), Quality::Full);
+
+        assert_eq!(
+            run(&mut guard, 0.9, OVERLOAD_BLOCKS as usize),
+            [Quality::Reduced]
+        );
+        assert_eq!(guard.quality(), Quality::Reduced);
+    }
+
+    #[test]
+    fn restores_after_sustained_calm() {
+        let mut guard = enabled();
+        run(&mut guard, 1.5, OVERLOAD_BLOCKS as usize);
+
+        let recovery = blocks_for(RECOVERY_SECONDS);
+        assert_eq!(run(&mut guard, 0.2, recovery - 1), []);
+        assert_eq!(run(&mut guard, 0.2, 1), [Quality::Full]);
+    }
+
+    #[test]
+    fn moderate_load_holds_reduced_quality() {
+        let mut guard = enabled();
+        run(&mut guard, 1.5, OVERLOAD_BLOCKS as usize);
+
+        // Between the two thresholds: neither overloaded nor calm.
+        assert_eq!(run(&mut guard, 0.6, blocks_for(RECOVERY_SECONDS * 4.0)), []);
+
+        // A busy block part-way through recovery starts the count again.
+        let recovery = blocks_for(RECOVERY_SECONDS);
+        run(&mut guard, 0.2, recovery - 1);
+        run(&mut guard, 0.6, 1);
+        assert_eq!(run(&mut guard, 0.2, recovery - 1), []);
+        assert_eq!(guard.quality(), Quality::Reduced);
+        assert_eq!(run(&mut guard, 0.2, 1), [Quality::Full]);
+    }
+
+    #[test]
+    fn load_at_the_threshold_is_not_overload() {
+        let mut guard = enabled();
+        assert_eq!(run(&mut guard, OVERLOAD_RATIO, 1000), []);
+    }
+
+    #[test]
+    fn disabled_guard_never_reduces() {
+        let mut guard = LoadGuard::new();
+        assert_eq!(run(&mut guard, 5.0, 1000), []);
+        assert_eq!(guard.quality(), Quality::Full);
+    }
+
+    #[test]
+    fn disabling_restores_full_quality() {
+        let mut guard = enabled();
+        run(&mut guard, 1.5, OVERLOAD_BLOCKS as usize);
+
+        assert_eq!(guard.set_enabled(false), Some(Quality::Full));
+        assert_eq!(guard.quality(), Quality::Full);
+        assert_eq!(guard.set_enabled(false), None);
+
+        // Re-enabling starts the overload count from scratch.
+        guard.set_enabled(true);
+        assert_eq!(run(&mut guard, 1.5, OVERLOAD_BLOCKS as usize - 1), []);
+    }
+}
@@ -1,6 +1,24 @@
+pub mod ambience;
+pub mod automation;
+pub mod calibration;
+pub mod denoiser;
+pub mod diagnostics;
+pub mod double_tracker;
+pub mod dual_mono;
 pub mod engine;
+pub mod gain_staging;
+pub mod host;
+pub mod ir_capture;
+pub mod load_guard;
+pub mod offline;
 pub mod peak_meter;
 pub mod pitch_shifter;
 pub mod recorder;
+pub mod recording_info;
 pub mod rt_drop;
 pub mod samplers;
+pub mod standby;
+pub mod take_player;
+pub mod test_source;
+pub mod tilt;
+pub mod tone_match;
@@ -0,0 +1,357 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use log::{info, warn};
+
+use crate::amp::chain::AmplifierChain;
+use crate::amp::stages::Stage;
+use crate::amp::stages::filter::{FilterStage, FilterType};
+use crate::audio::automation::{Automation, AutomationEvent, param_name};
+use crate::audio::engine::{Engine, EngineHandle};
+use crate::audio::rt_drop::RtDropReceiver;
+use crate::ir::cabinet::{ConvolverType, DEFAULT_MAX_IR_MS, IrCabinet};
+use crate::ir::convolver::Convolver;
+use crate::ir::load_service;
+use crate::ir::loader::{IrLoader, read_mono, read_stereo, resample};
+use crate::preset::Preset;
+
+/// Frames processed per engine call. Offline there is no deadline, so this
+/// only trades progress granularity against per-block overhead.
+pub const OFFLINE_BLOCK_SIZE: usize = 512;
+/// Smallest block automation is replayed in. Events can only be applied
+/// between blocks, so replay uses the largest block that divides every
+/// event's frame, which live is the buffer size or a multiple of it. If none
+/// this size or larger does, e.g. the take was recorded at an odd buffer
+/// size, replay uses blocks of this size and each change moves to the start
+/// of its block.
+pub const AUTOMATION_BLOCK_SIZE: usize = 16;
+
+/// Render a mono WAV file through `preset` faster than realtime and write the
+/// result to `output` as 32-bit float mono.
+///
+/// The preset's IR is resolved through `ir_loader`; without one (or if the IR
+/// fails to load) the cabinet passes the signal through, as it does live before
+/// an IR arrives. `progress` is called with the completed fraction (0.0..=1.0)
+/// after every block.
+pub fn render_file(
+    preset: &Preset,
+    input: &Path,
+    output: &Path,
+    sample_rate: usize,
+    oversampling_factor: u32,
+    ir_loader: Option<&IrLoader>,
+    progress: impl FnMut(f32),
+) -> Result<()> {
+    let (samples, file_rate) = read_mono_wav(input)?;
+    if file_rate as usize != sample_rate {
+        bail!(
+            "{} is {file_rate} Hz, but rendering was requested at {sample_rate} Hz",
+            input.display()
+        );
+    }
+
+    let convolver = preset_convolver(preset, sample_rate, ir_loader);
+
+    let rendered = render_samples(
+        preset,
+        &samples,
+        sample_rate,
+        oversampling_factor,
+        convolver,
+        progress,
+    )?;
+
+    write_mono_wav(output, &rendered, sample_rate)?;
+    info!(
+        "Rendered {} through '{}' to {}",
+        input.display(),
+        preset.name,
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// Load the preset's IR for an offline render. Without a loader, or if the
+/// IR fails to load, the cabinet passes the signal through.
+pub(crate) fn preset_convolver(
+    preset: &Preset,
+    sample_rate: usize,
+    ir_loader: Option<&IrLoader>,
+) -> Option<Convolver> {
+    match (&preset.ir_name, ir_loader) {
+        (Some(name), Some(loader)) => {
+            let convolver = load_service::prepare_convolver(
+                loader,
+                name,
+                preset.ir_alignment,
+                sample_rate,
+                DEFAULT_MAX_IR_MS,
+                ConvolverType::default(),
+            );
+            if convolver.is_none() {
+                warn!("IR '{name}' could not be loaded, rendering without it");
+            }
+            convolver
+        }
+        (Some(name), None) => {
+            warn!("No IR directory available, rendering without IR '{name}'");
+            None
+        }
+        (None, _) => None,
+    }
+}
+
+/// Replay a recorded take's automation over its dry signal: render `input`
+/// through the sidecar's preset, applying each logged parameter change at the
+/// frame it was made, and write the result to `output`.
+///
+/// `input` must be at the rate the take was recorded at, since that is the
+/// rate the event frames count in.
+pub fn render_automation(
+    automation: &Automation,
+    input: &Path,
+    output: &Path,
+    oversampling_factor: u32,
+    ir_loader: Option<&IrLoader>,
+    progress: impl FnMut(f32),
+) -> Result<()> {
+    let sample_rate = automation.sample_rate;
+    let (samples, file_rate) = read_mono_wav(input)?;
+    if file_rate as usize != sample_rate {
+        bail!(
+            "{} is {file_rate} Hz, but the automation was recorded at {sample_rate} Hz",
+            input.display()
+        );
+    }
+
+    let preset = &automation.preset;
+    let convolver = preset_convolver(preset, sample_rate, ir_loader);
+    let rendered = render_blocks(
+        preset,
+        &samples,
+        (sample_rate, oversampling_factor),
+        convolver,
+        &automation.events,
+        progress,
+    )?;
+
+    write_mono_wav(output, &rendered, sample_rate)?;
+    info!(
+        "Rendered {} with {} automation events to {}",
+        input.display(),
+        automation.events.len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// Process `samples` through `preset` using the realtime engine itself, fed
+/// whole blocks so its output matches a live session at the same settings.
+/// The output has the same length as the input; effect tails are cut.
+pub fn render_samples(
+    preset: &Preset,
+    samples: &[f32],
+    sample_rate: usize,
+    oversampling_factor: u32,
+    convolver: Option<Convolver>,
+    progress: impl FnMut(f32),
+) -> Result<Vec<f32>> {
+    render_blocks(
+        preset,
+        samples,
+        (sample_rate, oversampling_factor),
+        convolver,
+        &[],
+        progress,
+    )
+}
+
+/// Shared body of [`render_samples`] and [`render_automation`]. `rates` is
+/// the device sample rate and oversampling factor. Each event in `events` is
+/// sent before the block containing its frame; see [`automation_block_size`]
+/// for how long the blocks are.
+///
+/// An event for a stage or parameter the preset doesn't have is skipped.
+fn render_blocks(
+    preset: &Preset,
+    samples: &[f32],
+    (sample_rate, oversampling_factor): (usize, u32),
+    convolver: Option<Convolver>,
+    events: &[AutomationEvent],
+    mut progress: impl FnMut(f32),
+) -> Result<Vec<f32>> {
+    let block_size = automation_block_size(events);
+
+    // Keep the drop receiver alive so objects the engine retires while applying
+    // the preset are freed with it rather than leaked.
+    let (mut engine, handle, _rt_drop_rx) = build_engine(
+        preset,
+        sample_rate,
+        oversampling_factor,
+        convolver,
+        block_size,
+    )?;
+
+    let mut rendered = Vec::with_capacity(samples.len());
+    let mut input = [0.0f32; OFFLINE_BLOCK_SIZE];
+    let mut block = [0.0f32; OFFLINE_BLOCK_SIZE];
+    let mut pending = events.iter().peekable();
+
+    for chunk in samples.chunks(block_size) {
+        let block_end = (rendered.len() + block_size) as u64;
+        while let Some(event) = pending.next_if(|event| event.frame < block_end) {
+            match param_name(preset, event.stage, &event.param) {
+                Some(name) => handle.set_parameter(event.stage, name, event.value),
+                None => warn!(
+                    "Skipping automation of unknown parameter '{}' on stage {}",
+                    event.param, event.stage
+                ),
+            }
+        }
+
+        // The engine's resamplers need full blocks: zero-pad the last one.
+        let (input, block) = (&mut input[..block_size], &mut block[..block_size]);
+        input[..chunk.len()].copy_from_slice(chunk);
+        input[chunk.len()..].fill(0.0);
+
+        engine.process(input, block)?;
+        rendered.extend_from_slice(&block[..chunk.len()]);
+
+        progress(rendered.len() as f32 / samples.len() as f32);
+    }
+
+    Ok(rendered)
+}
+
+/// The largest block, up to [`OFFLINE_BLOCK_SIZE`], that every event's frame
+/// is a multiple of, so each change lands on the frame it was recorded at;
+/// [`AUTOMATION_BLOCK_SIZE`] if that would be smaller.
+fn automation_block_size(events: &[AutomationEvent]) -> usize {
+    // Greatest common divisor of the frames.
+    let gcd = events.iter().fold(0, |gcd, event| {
+        let (mut a, mut b) = (gcd, event.frame);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        a
+    });
+    if gcd == 0 {
+        return OFFLINE_BLOCK_SIZE;
+    }
+    (AUTOMATION_BLOCK_SIZE..=OFFLINE_BLOCK_SIZE)
+        .rev()
+        .find(|&size| gcd % size as u64 == 0)
+        .unwrap_or(AUTOMATION_BLOCK_SIZE)
+}
+
+/// Build an engine at `block_size` with the preset's full state queued.
+/// The queue is drained at the top of the first `process` call, so the first
+/// block is already rendered with the preset applied.
+fn build_engine(
+    preset: &Preset,
+    sample_rate: usize,
+    oversampling_factor: u32,
+    convolver: Option<Convolver>,
+    block_size: usize,
+) -> Result<(Engine, EngineHandle, RtDropReceiver)> {
+    let max_ir_samples = (sample_rate * DEFAULT_MAX_IR_MS) / 1000;
+    let mut cabinet = IrCabinet::new(ConvolverType::default(), max_ir_samples);
+    if let Some(convolver) = convolver {
+        cabinet.set_convolver(convolver);
+    }
+    cabinet.set_gain(preset.ir_gain);
+
+    let (engine, handle, rt_drop_rx) = Engine::new_for_plugin(
+        sample_rate,
+        block_size,
+        Some(cabinet),
+        f64::from(oversampling_factor),
+    )?;
+
+    let filters = &preset.input_filters;
+    let filter = |enabled: bool, filter_type: FilterType, cutoff: f32| -> Option<Box<dyn Stage>> {
+        enabled.then(|| {
+            Box::new(FilterStage::new(filter_type, cutoff, sample_rate as f32)) as Box<dyn Stage>
+        })
+    };
+    handle.set_input_filters(
+        filter(filters.hp_enabled, FilterType::Highpass, filters.hp_cutoff),
+        filter(filters.lp_enabled, FilterType::Lowpass, filters.lp_cutoff),
+    );
+
+    let effective_rate = (sample_rate * oversampling_factor as usize) as f32;
+    let mut chain = AmplifierChain::new();
+    for cfg in &preset.stages {
+        chain.add_stage(cfg.to_runtime(effective_rate));
+    }
+    for (i, cfg) in preset.stages.iter().enumerate() {
+        if cfg.bypassed() {
+            chain.set_bypassed(i, true);
+        }
+    }
+    handle.set_amp_chain(chain);
+    handle.set_denoiser(&preset.denoise, sample_rate);
+    handle.set_pitch_shift(preset.pitch_shift_semitones);
+    handle.set_ambience(preset.ambience);
+    handle.set_double_tracker(preset.double_tracker);
+    // The preset's own correction only; the global tilt is for the room.
+    handle.set_tilt_db(preset.tilt_offset_db);
+
+    Ok((engine, handle, rt_drop_rx))
+}
+
+/// Read a WAV file, mixing multi-channel audio down to mono.
+pub fn read_mono_wav(path: &Path) -> Result<(Vec<f32>, u32)> {
+    let reader =
+        WavReader::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let spec = reader.spec();
+    let mono = read_mono(reader, usize::MAX)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    Ok((mono, spec.sample_rate))
+}
+
+/// Read a WAV file as mono at `sample_rate`, resampling it if it was
+/// recorded at another rate.
+pub fn read_mono_wav_at(path: &Path, sample_rate: usize) -> Result<Vec<f32>> {
+    let (samples, file_rate) = read_mono_wav(path)?;
+    resample(&samples, file_rate, sample_rate as u32)
+        .with_context(|| format!("Failed to resample {}", path.display()))
+}
+
+/// Read a WAV file as its left and right channels at `sample_rate`; see
+/// [`read_mono_wav_at`]. A mono file plays in both.
+pub fn read_stereo_wav_at(path: &Path, sample_rate: usize) -> Result<[Vec<f32>; 2]> {
+    let reader =
+        WavReader::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let file_rate = reader.spec().sample_rate;
+    let [left, right] =
+        read_stereo(reader).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let resampled = |channel: &[f32]| {
+        resample(channel, file_rate, sample_rate as u32)
+            .with_context(|| format!("Failed to resample {}", path.display()))
+    };
+    Ok([resampled(&left)?, resampled(&right)?])
+}
+
+pub(crate) fn write_mono_wav(path: &Path, samples: &[f32], sample_rate: usize) -> Result<()> {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: u32::try_from(sample_rate).context("sample rate out of range")?,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+
+    let mut writer = WavWriter::create(path, spec)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+
+    Ok(())
+}
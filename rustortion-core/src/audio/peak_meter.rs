@@ -78,6 +78,11 @@ impl PeakMeter {
         )
     }
 
+    /// Keep holding peaks for the same time at a new sample rate.
+    pub const fn set_sample_rate(&mut self, sample_rate: usize) {
+        self.peak_hold_samples = sample_rate * 2;
+    }
+
     pub fn process(&mut self, samples: &[f32]) {
         let block_peak = samples.iter().map(|s| s.abs()).fold(0.0f32, f32::max);
 
@@ -127,6 +127,12 @@ impl PitchShifter {
         }
     }
 
+    /// Delay from input to output: a sample is heard once the frame it
+    /// closes has been overlap-added, one full FFT later.
+    pub const fn latency_samples(&self) -> usize {
+        FFT_SIZE
+    }
+
     /// Update the pitch ratio without reallocating buffers.
     pub fn set_semitones(&mut self, semitones: f32) {
         self.ratio = (semitones as f64 / 12.0).exp2();
@@ -2,10 +2,15 @@ use anyhow::Result;
 use crossbeam::channel::{Receiver, Sender, TrySendError, bounded};
 use hound::WavWriter;
 use log::{error, info};
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
 use std::{fs, thread};
 
+use crate::audio::recording_info::RecordingMetadata;
+
 type AudioBlock = Vec<i16>;
 
 /// Pre-allocate enough buffering for this many seconds of audio. Bounded (so the
@@ -16,6 +21,127 @@ type AudioBlock = Vec<i16>;
 const BUFFER_SECONDS: usize = 8;
 /// Floor on the buffer size in blocks, in case the host block size is huge.
 const MIN_BUFFER_BLOCKS: usize = 16;
+/// Size of the canonical WAV header hound writes before the samples.
+const WAV_HEADER_BYTES: u64 = 44;
+/// Bytes per stereo 16-bit frame.
+const BYTES_PER_FRAME: u64 = 4;
+/// Replaces `.wav` on a take for its click track.
+const CLICK_TRACK_SUFFIX: &str = ".click.wav";
+/// Where the recording soft clip starts to bend, about −6 dBFS.
+pub const SOFTCLIP_KNEE: f32 = 0.5;
+
+/// Gentle tape-style saturation for the recording path: linear up to
+/// [`SOFTCLIP_KNEE`], then rounding off towards full scale without reaching
+/// it, so a hot take is squashed rather than clipped flat.
+pub fn soft_clip(sample: f32) -> f32 {
+    let level = sample.abs();
+    if level <= SOFTCLIP_KNEE {
+        return sample;
+    }
+    let headroom = 1.0 - SOFTCLIP_KNEE;
+    let shaped = SOFTCLIP_KNEE + headroom * ((level - SOFTCLIP_KNEE) / headroom).tanh();
+    shaped.copysign(sample)
+}
+
+/// What the writer thread has put on disk so far, published for the GUI.
+#[derive(Debug)]
+pub struct RecordingProgress {
+    path: PathBuf,
+    sample_rate: u32,
+    frames_written: AtomicU64,
+    /// Bits of the `f32` peak written since the last [`Self::status`].
+    peak: AtomicU32,
+    failed: AtomicBool,
+    /// The soft clip bent at least one sample of the take.
+    softclipped: AtomicBool,
+    /// Frames of silence written ahead of the first block, set by the RT
+    /// thread when it starts the take; see [`Recorder::start_at`].
+    lead_in: AtomicU64,
+    /// Blocks that never made it into the file: dropped on the RT thread
+    /// because the writer couldn't keep up, or by the writer after it
+    /// failed. The RT thread never blocks on the writer, so this surfaces
+    /// any lost audio.
+    overruns: AtomicU64,
+}
+
+/// A snapshot of a take in progress, derived from the frames actually
+/// written rather than the wall clock, so xruns and dropped blocks don't
+/// skew it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordingStatus {
+    pub path: PathBuf,
+    pub elapsed: Duration,
+    /// Linear peak of what was written since the previous snapshot.
+    pub peak: f32,
+    /// Size of the WAV file once the pending samples are flushed.
+    pub file_bytes: u64,
+    /// The file couldn't be created or written (e.g. the disk is full);
+    /// nothing more is being saved.
+    pub failed: bool,
+    /// The soft clip has saturated some of the take.
+    pub softclipped: bool,
+}
+
+impl RecordingProgress {
+    fn new(path: PathBuf, sample_rate: u32) -> Self {
+        Self {
+            path,
+            sample_rate,
+            frames_written: AtomicU64::new(0),
+            peak: AtomicU32::new(0),
+            failed: AtomicBool::new(false),
+            softclipped: AtomicBool::new(false),
+            lead_in: AtomicU64::new(0),
+            overruns: AtomicU64::new(0),
+        }
+    }
+
+    fn wrote(&self, frames: u64, peak: f32) {
+        self.frames_written.fetch_add(frames, Ordering::Relaxed);
+        let _ = self
+            .peak
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                (peak > f32::from_bits(bits)).then_some(peak.to_bits())
+            });
+    }
+
+    /// Snapshot the take. Resets the peak, so it's meant to be polled from a
+    /// single place.
+    pub fn status(&self) -> RecordingStatus {
+        let frames = self.frames_written.load(Ordering::Relaxed);
+        RecordingStatus {
+            path: self.path.clone(),
+            elapsed: Duration::from_secs_f64(frames as f64 / f64::from(self.sample_rate)),
+            peak: f32::from_bits(self.peak.swap(0, Ordering::Relaxed)),
+            file_bytes: WAV_HEADER_BYTES + frames * BYTES_PER_FRAME,
+            failed: self.failed.load(Ordering::Relaxed),
+            softclipped: self.softclipped(),
+        }
+    }
+
+    /// Whether the soft clip has saturated any of the take so far.
+    pub fn softclipped(&self) -> bool {
+        self.softclipped.load(Ordering::Relaxed)
+    }
+
+    /// Number of audio blocks lost from the take so far. Zero in normal
+    /// operation; non-zero means the disk couldn't keep up or failed.
+    pub fn overruns(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+
+    fn overrun(&self) {
+        self.overruns.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Where a take started by the transport lines up: the transport rolled
+/// from `frame` in the engine's block starting at `engine_frame`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransportStart {
+    pub frame: u64,
+    pub engine_frame: u64,
+}
 
 pub struct Recorder {
     /// Non-blocking handoff of filled buffers to the writer thread.
@@ -28,10 +154,12 @@ pub struct Recorder {
     /// Largest input block (in samples) the pre-allocated buffers can hold
     /// without reallocating. Blocks larger than this are dropped.
     max_block_samples: usize,
-    /// Count of blocks dropped because the writer couldn't keep up (disk
-    /// stall). The RT thread never blocks on the writer — it drops instead —
-    /// so this surfaces any lost audio.
-    overruns: Arc<AtomicU64>,
+    /// Run samples through [`soft_clip`] before converting them to 16 bits.
+    softclip: bool,
+    /// Engine frame the take's first sample belongs to, if it isn't the
+    /// frame the engine picks the recorder up at.
+    align_to: Option<u64>,
+    progress: Arc<RecordingProgress>,
     handle: thread::JoinHandle<()>,
 }
 
@@ -41,7 +169,79 @@ impl Recorder {
     /// `max_block_samples` is the largest input block size the recorder will be
     /// asked to handle; the buffer pool is pre-sized to it so that
     /// `record_block` performs no allocation on the RT thread.
-    pub fn new(sample_rate: u32, record_dir: &str, max_block_samples: usize) -> Result<Self> {
+    ///
+    /// `transport_frame` is the JACK transport position the take started at,
+    /// if it was started by the transport. It is appended to the filename so
+    /// the take can be lined up with the DAW session.
+    ///
+    /// `metadata`, the preset the take was started with, is written into
+    /// and next to the WAV once it is finalized.
+    pub fn new(
+        sample_rate: u32,
+        record_dir: &str,
+        max_block_samples: usize,
+        transport_frame: Option<u64>,
+        metadata: Option<RecordingMetadata>,
+    ) -> Result<Self> {
+        fs::create_dir_all(record_dir)?;
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let filename = match transport_frame {
+            Some(frame) => format!("{record_dir}/recording_{timestamp}_frame{frame}.wav"),
+            None => format!("{record_dir}/recording_{timestamp}.wav"),
+        };
+        Self::create(
+            PathBuf::from(filename),
+            sample_rate,
+            max_block_samples,
+            metadata,
+        )
+    }
+
+    /// A recorder writing to `path`, e.g. a second file alongside a take.
+    /// See [`Self::new`] for the other arguments.
+    pub fn create(
+        path: PathBuf,
+        sample_rate: u32,
+        max_block_samples: usize,
+        metadata: Option<RecordingMetadata>,
+    ) -> Result<Self> {
+        let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+        let filename = path.to_string_lossy().into_owned();
+        info!("Recording to: {filename}");
+        Ok(Self::spawn(
+            path,
+            sample_rate,
+            max_block_samples,
+            move |spec| WavWriter::create(&filename, spec),
+            metadata.map(|metadata| (metadata, date)),
+        ))
+    }
+
+    /// A recorder writing the WAV into `writer` rather than a file, e.g. to
+    /// stream it elsewhere. `path` only names the take. See [`Self::new`]
+    /// for the other arguments.
+    pub fn with_writer<W: Write + Seek + Send + 'static>(
+        path: PathBuf,
+        writer: W,
+        sample_rate: u32,
+        max_block_samples: usize,
+    ) -> Self {
+        Self::spawn(
+            path,
+            sample_rate,
+            max_block_samples,
+            move |spec| WavWriter::new(writer, spec),
+            None,
+        )
+    }
+
+    fn spawn<W: Write + Seek + 'static>(
+        path: PathBuf,
+        sample_rate: u32,
+        max_block_samples: usize,
+        open: impl FnOnce(hound::WavSpec) -> hound::Result<WavWriter<W>> + Send + 'static,
+        metadata: Option<(RecordingMetadata, String)>,
+    ) -> Self {
         // Size the buffer pool / handoff channel by time so it absorbs several
         // seconds of writer lag before ever dropping a block. Both the channel
         // and the pool hold the same number of buffers so the producer never
@@ -52,7 +252,6 @@ impl Recorder {
 
         let (recorder_sender, recorder_receiver) = bounded::<AudioBlock>(buffer_blocks);
         let (recycle_sender, recycle_receiver) = bounded::<AudioBlock>(buffer_blocks);
-        fs::create_dir_all(record_dir)?;
 
         // Pre-allocate the buffer pool. Each input sample becomes two
         // interleaved stereo `i16`s, so size for `max_block_samples * 2`.
@@ -61,36 +260,97 @@ impl Recorder {
             let _ = recycle_sender.try_send(AudioBlock::with_capacity(max_block_samples * 2));
         }
 
-        let filename = format!(
-            "{record_dir}/recording_{}.wav",
-            chrono::Local::now().format("%Y%m%d_%H%M%S")
-        );
-        info!("Recording to: {filename}");
-
+        let filename = path.to_string_lossy().into_owned();
+        let progress = Arc::new(RecordingProgress::new(path, sample_rate));
+        let writer_progress = Arc::clone(&progress);
         let writer_recycle_sender = recycle_sender.clone();
         let handle = thread::spawn(move || {
             run_writer_thread(
                 sample_rate,
-                filename,
+                &filename,
+                open,
                 recorder_receiver,
                 &writer_recycle_sender,
+                &writer_progress,
+                metadata,
             );
         });
 
-        Ok(Self {
+        Self {
             recorder_sender,
             recycle_receiver,
             recycle_sender,
             max_block_samples,
-            overruns: Arc::new(AtomicU64::new(0)),
+            softclip: false,
+            align_to: None,
+            progress,
             handle,
-        })
+        }
+    }
+
+    /// Saturate the take with [`soft_clip`] as it's converted to 16 bits.
+    /// Only what's written is shaped; the caller's samples are untouched.
+    #[must_use]
+    pub const fn with_softclip(mut self, enabled: bool) -> Self {
+        self.softclip = enabled;
+        self
+    }
+
+    /// Start the take at engine frame `align_to` rather than wherever the
+    /// engine picks the recorder up, e.g. the block the transport rolled in.
+    #[must_use]
+    pub const fn aligned_to(mut self, align_to: Option<u64>) -> Self {
+        self.align_to = align_to;
+        self
+    }
+
+    /// Called on the RT thread as the engine picks the recorder up at
+    /// engine frame `now`. A take aligned to an earlier frame is padded with
+    /// silence up to `now`, so its first sample is still that frame; the
+    /// audio in between was never handed to the recorder. Returns the
+    /// engine frame the file starts at.
+    pub fn start_at(&self, now: u64) -> u64 {
+        let start = self.align_to.map_or(now, |frame| frame.min(now));
+        self.progress.lead_in.store(now - start, Ordering::Relaxed);
+        start
+    }
+
+    /// `take.wav` → `take.click.wav`, the metronome's track for a take.
+    pub fn click_track_path(take: &Path) -> PathBuf {
+        let stem = take
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        take.with_file_name(format!("{stem}{CLICK_TRACK_SUFFIX}"))
     }
 
-    /// Number of audio blocks dropped because the writer thread fell behind.
-    /// Zero in normal operation; non-zero indicates the disk couldn't keep up.
+    /// Whether `path` is a click track rather than a take.
+    pub fn is_click_track(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().ends_with(CLICK_TRACK_SUFFIX))
+    }
+
+    /// The WAV file this take is written to.
+    pub fn path(&self) -> &Path {
+        &self.progress.path
+    }
+
+    /// Progress of this take, readable from any thread.
+    pub fn progress(&self) -> Arc<RecordingProgress> {
+        Arc::clone(&self.progress)
+    }
+
+    /// Number of audio blocks lost from the take, see
+    /// [`RecordingProgress::overruns`].
     pub fn overruns(&self) -> u64 {
-        self.overruns.load(Ordering::Relaxed)
+        self.progress.overruns()
+    }
+
+    /// Whether the next block can be handed to the writer rather than
+    /// dropped. A writer that has failed drops everything anyway, so it
+    /// always has room. Real-time safe.
+    pub fn has_room(&self) -> bool {
+        !self.recycle_receiver.is_empty() || self.progress.failed.load(Ordering::Relaxed)
     }
 
     /// Stops the recording and waits for the writer thread to finish.
@@ -114,43 +374,81 @@ impl Recorder {
     /// (e.g. it failed to create the WAV file), every block is likewise counted
     /// as an overrun; the climbing count surfaces the failure off-RT.
     pub fn record_block(&self, samples: &[f32]) {
-        if samples.len() > self.max_block_samples {
-            self.overruns.fetch_add(1, Ordering::Relaxed);
+        self.record_with(samples.len(), |i| samples[i]);
+    }
+
+    /// Record `len` samples, the `i`th being `sample(i)`, without a buffer to
+    /// hold them first. Real-time safe like [`Self::record_block`].
+    pub fn record_with(&self, len: usize, sample: impl Fn(usize) -> f32) {
+        if len > self.max_block_samples {
+            self.progress.overrun();
             return;
         }
         let Ok(mut block) = self.recycle_receiver.try_recv() else {
-            self.overruns.fetch_add(1, Ordering::Relaxed);
+            self.progress.overrun();
             return;
         };
         block.clear();
-        for sample in samples {
-            let v = (sample * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
+        let mut bent = false;
+        for i in 0..len {
+            let mut s = sample(i);
+            if self.softclip {
+                bent |= s.abs() > SOFTCLIP_KNEE;
+                s = soft_clip(s);
+            }
+            let v = (s * i16::MAX as f32).clamp(i16::MIN as f32, i16::MAX as f32) as i16;
             block.push(v);
             block.push(v);
         }
+        if bent {
+            self.progress.softclipped.store(true, Ordering::Relaxed);
+        }
         match self.recorder_sender.try_send(block) {
             Ok(()) => {}
             Err(TrySendError::Full(block)) => {
                 // Writer behind: return the buffer to the pool, drop the audio.
                 let _ = self.recycle_sender.try_send(block);
-                self.overruns.fetch_add(1, Ordering::Relaxed);
+                self.progress.overrun();
             }
             Err(TrySendError::Disconnected(_)) => {
                 // Writer thread is gone. Constructing an `anyhow` error here
                 // would allocate on the RT thread; count it as an overrun like
                 // any other lost block instead.
-                self.overruns.fetch_add(1, Ordering::Relaxed);
+                self.progress.overrun();
             }
         }
     }
 }
 
+fn write_block<W: std::io::Write + std::io::Seek>(
+    writer: &mut WavWriter<W>,
+    block: &[i16],
+) -> hound::Result<()> {
+    for &sample in block {
+        writer.write_sample(sample)?;
+    }
+    Ok(())
+}
+
+fn write_silence<W: std::io::Write + std::io::Seek>(
+    writer: &mut WavWriter<W>,
+    frames: u64,
+) -> hound::Result<()> {
+    for _ in 0..frames * 2 {
+        writer.write_sample(0i16)?;
+    }
+    Ok(())
+}
+
 /// Runs the writer thread, that writes audio blocks received over its channel to a WAV file.
-fn run_writer_thread(
+fn run_writer_thread<W: Write + Seek>(
     sample_rate: u32,
-    filename: String,
+    filename: &str,
+    open: impl FnOnce(hound::WavSpec) -> hound::Result<WavWriter<W>>,
     recorder_receiver: Receiver<AudioBlock>,
     recycle_sender: &Sender<AudioBlock>,
+    progress: &RecordingProgress,
+    metadata: Option<(RecordingMetadata, String)>,
 ) {
     let spec = hound::WavSpec {
         channels: 2,
@@ -159,18 +457,48 @@ fn run_writer_thread(
         sample_format: hound::SampleFormat::Int,
     };
 
-    let mut writer = match WavWriter::create(&filename, spec) {
+    let mut writer = match open(spec) {
         Ok(w) => w,
         Err(e) => {
             error!("Failed to create WAV file '{filename}': {e}");
+            progress.failed.store(true, Ordering::Relaxed);
             return;
         }
     };
 
+    let mut padded = false;
     for block in recorder_receiver {
-        for &sample in &block {
-            if let Err(e) = writer.write_sample(sample) {
-                error!("Failed to write sample to WAV file '{filename}': {e}");
+        // The lead-in is known once the RT thread has started the take,
+        // which is before it sends the first block.
+        if !padded {
+            padded = true;
+            let lead_in = progress.lead_in.load(Ordering::Relaxed);
+            if lead_in > 0 {
+                if let Err(e) = write_silence(&mut writer, lead_in) {
+                    error!("Failed to write to WAV file '{filename}': {e}");
+                    progress.failed.store(true, Ordering::Relaxed);
+                } else {
+                    progress.wrote(lead_in, 0.0);
+                }
+            }
+        }
+        // After a write error (typically a full disk) the rest of the take
+        // is drained and dropped; what was written is still finalized.
+        if progress.failed.load(Ordering::Relaxed) {
+            progress.overrun();
+        } else {
+            match write_block(&mut writer, &block) {
+                Ok(()) => {
+                    let peak = block.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+                    progress.wrote(
+                        block.len() as u64 / 2,
+                        f32::from(peak) / f32::from(i16::MAX),
+                    );
+                }
+                Err(e) => {
+                    error!("Failed to write to WAV file '{filename}': {e}");
+                    progress.failed.store(true, Ordering::Relaxed);
+                }
             }
         }
         // Return the buffer to the pool for reuse. If the pool is full or the
@@ -180,8 +508,14 @@ fn run_writer_thread(
 
     if let Err(e) = writer.finalize() {
         error!("Failed to finalize WAV file: {e}");
-    } else {
-        info!("Recording saved: {filename}");
+        return;
+    }
+    info!("Recording saved: {filename}");
+
+    if let Some((metadata, date)) = metadata
+        && let Err(e) = metadata.write(Path::new(&filename), &date)
+    {
+        error!("Failed to write recording metadata: {e:#}");
     }
 }
 
@@ -203,7 +537,7 @@ mod tests {
         let record_dir = temp_dir.path().to_str().unwrap();
 
         let block_size = 256;
-        let recorder = Recorder::new(SAMPLE_RATE, record_dir, block_size)?;
+        let recorder = Recorder::new(SAMPLE_RATE, record_dir, block_size, None, None)?;
 
         let total_samples = (SAMPLE_RATE as f32 * DURATION_SECS) as usize;
         let mut generated_samples = 0;
@@ -332,4 +666,156 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_recorder_reports_progress_from_frames_written() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let record_dir = temp_dir.path().to_str().unwrap();
+
+        let recorder = Recorder::new(48000, record_dir, 480, None, None)?;
+        let progress = recorder.progress();
+        for _ in 0..100 {
+            recorder.record_block(&[0.25; 480]);
+        }
+        let path = recorder.path().to_path_buf();
+        recorder.stop()?;
+
+        let status = progress.status();
+        assert_eq!(status.path, path);
+        assert_eq!(status.elapsed, Duration::from_secs(1));
+        assert!((status.peak - 0.25).abs() < 1e-3, "peak {}", status.peak);
+        assert_eq!(status.file_bytes, std::fs::metadata(&path)?.len());
+        assert!(!status.failed);
+
+        // The peak is since the last snapshot.
+        assert_eq!(progress.status().peak, 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorder_names_file_after_transport_frame() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let record_dir = temp_dir.path().to_str().unwrap();
+
+        let recorder = Recorder::new(48000, record_dir, 64, Some(96_000), None)?;
+        recorder.record_block(&[0.0; 64]);
+        recorder.stop()?;
+
+        let name = std::fs::read_dir(record_dir)?
+            .filter_map(std::result::Result::ok)
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .next()
+            .expect("No WAV file found");
+        assert!(name.ends_with("_frame96000.wav"), "unexpected name {name}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorder_started_late_is_padded_to_its_frame() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let record_dir = temp_dir.path().to_str().unwrap();
+
+        let recorder =
+            Recorder::new(48000, record_dir, 64, Some(96_000), None)?.aligned_to(Some(1000));
+        assert_eq!(recorder.start_at(1960), 1000);
+        recorder.record_block(&[0.5; 64]);
+        let path = recorder.path().to_path_buf();
+        recorder.stop()?;
+
+        let samples: Vec<i16> = WavReader::open(&path)?
+            .samples::<i16>()
+            .collect::<Result<_, _>>()?;
+        assert_eq!(samples.len(), (960 + 64) * 2);
+        assert!(samples[..960 * 2].iter().all(|&s| s == 0));
+        assert!(samples[960 * 2..].iter().all(|&s| s > 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_click_track_is_written_next_to_the_take() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let take = temp_dir.path().join("take.wav");
+        let click = Recorder::click_track_path(&take);
+        assert_eq!(click, temp_dir.path().join("take.click.wav"));
+
+        let recorder = Recorder::create(click.clone(), 48000, 64, None)?;
+        recorder.record_with(64, |i| if i == 0 { 0.5 } else { 0.0 });
+        recorder.stop()?;
+
+        let mut reader = WavReader::open(&click)?;
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(samples.len(), 128);
+        assert_eq!(samples[0], i16::MAX / 2);
+        assert_eq!(samples[2], 0);
+
+        Ok(())
+    }
+
+    /// Records one second of a full-scale 1 kHz sine, returning the left
+    /// channel and whether the take was flagged as soft-clipped.
+    fn record_full_scale_sine(softclip: bool) -> Result<(Vec<i16>, bool)> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("take.wav");
+        let recorder = Recorder::create(path.clone(), 48000, 480, None)?.with_softclip(softclip);
+        let progress = recorder.progress();
+        for block in 0..100 {
+            recorder.record_with(480, |i| {
+                (2.0 * PI * 1000.0 * (block * 480 + i) as f32 / 48000.0).sin()
+            });
+        }
+        recorder.stop()?;
+
+        let left = WavReader::open(&path)?
+            .samples::<i16>()
+            .step_by(2)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((left, progress.status().softclipped))
+    }
+
+    /// Total harmonic distortion of a 1 kHz tone spanning whole cycles at
+    /// 48 kHz, from its 2nd to 9th harmonics.
+    fn thd(samples: &[i16]) -> f64 {
+        let magnitude = |harmonic: usize| {
+            let (mut re, mut im) = (0.0, 0.0);
+            for (i, &s) in samples.iter().enumerate() {
+                let phase = std::f64::consts::TAU * (harmonic * 1000 * i) as f64 / 48000.0;
+                re += f64::from(s) * phase.cos();
+                im += f64::from(s) * phase.sin();
+            }
+            re.hypot(im)
+        };
+        let harmonics: f64 = (2..10).map(|h| magnitude(h).powi(2)).sum();
+        harmonics.sqrt() / magnitude(1)
+    }
+
+    #[test]
+    fn test_soft_clip_is_linear_up_to_the_knee() {
+        for x in [0.0, 0.1, -0.3, SOFTCLIP_KNEE, -SOFTCLIP_KNEE] {
+            assert_eq!(soft_clip(x), x);
+        }
+        assert!(soft_clip(0.9) < 0.9 && soft_clip(0.9) > SOFTCLIP_KNEE);
+        assert_eq!(soft_clip(-0.9), -soft_clip(0.9));
+        assert!(soft_clip(4.0) < 1.0);
+    }
+
+    #[test]
+    fn test_softclip_saturates_a_full_scale_sine_gently() -> Result<()> {
+        let (left, softclipped) = record_full_scale_sine(true)?;
+        assert!(softclipped);
+        let peak = left.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+        assert!(peak < i16::MAX.unsigned_abs(), "peak {peak}");
+        assert!(peak > i16::MAX.unsigned_abs() / 2, "peak {peak}");
+        let thd = thd(&left);
+        assert!(thd < 0.06, "THD {:.2}%", thd * 100.0);
+
+        // Off, the sine is written as is and nothing is flagged.
+        let (left, softclipped) = record_full_scale_sine(false)?;
+        assert!(!softclipped);
+        let peak = left.iter().copied().max().unwrap_or(0);
+        assert!(peak >= i16::MAX - 1, "peak {peak}");
+        Ok(())
+    }
 }
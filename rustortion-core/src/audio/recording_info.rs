@@ -0,0 +1,340 @@
+//! What a recording was made with, written next to and into the WAV once the
+//! take is finalized: the full preset as a JSON sidecar, with the metronome's
+//! tempo if it ran, and a RIFF `INFO` list with the preset name and a
+//! one-line chain summary that other tools can show.
+//!
+//! hound has no support for `LIST` chunks, so the list is appended to the
+//! finished file and the RIFF size patched. Readers skip chunks they don't
+//! know, hound included.
+
+use std::fs::{self, OpenOptions};
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::audio::recorder::Recorder;
+use crate::preset::Preset;
+
+/// Appended to the recording's file stem for the preset sidecar.
+const SIDECAR_SUFFIX: &str = ".preset.json";
+/// Written as the artist and software of every take.
+const APP_NAME: &str = "Rustortion";
+
+/// Key of the metronome's entry in the sidecar, next to the preset's fields.
+const METRONOME_KEY: &str = "metronome";
+
+/// The preset a take was started with, and the metronome if it was running.
+#[derive(Debug, Clone)]
+pub struct RecordingMetadata {
+    pub preset: Preset,
+    pub metronome: Option<TakeMetronome>,
+}
+
+/// The click a take was played to. The click itself is never in the take.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TakeMetronome {
+    pub bpm: f32,
+    pub beats_per_bar: usize,
+    /// Also write the click to its own WAV, aligned with the take; see
+    /// [`Recorder::click_track_path`].
+    pub click_track: bool,
+}
+
+/// How [`TakeMetronome`] reads in the sidecar.
+#[derive(Serialize)]
+struct MetronomeEntry {
+    bpm: f32,
+    time_signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    click_track: Option<String>,
+}
+
+impl RecordingMetadata {
+    pub const fn new(preset: Preset) -> Self {
+        Self {
+            preset,
+            metronome: None,
+        }
+    }
+
+    pub const fn with_metronome(mut self, metronome: TakeMetronome) -> Self {
+        self.metronome = Some(metronome);
+        self
+    }
+
+    /// `take.wav` → `take.preset.json` in the same directory.
+    pub fn sidecar_path(recording: &Path) -> PathBuf {
+        let stem = recording
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        recording.with_file_name(format!("{stem}{SIDECAR_SUFFIX}"))
+    }
+
+    /// The stages that are heard, in order, and the IR, e.g.
+    /// `Gate>Preamp>TS>PA>Level + mesa_v30.wav`.
+    pub fn chain_summary(&self) -> String {
+        let stages: Vec<&str> = self
+            .preset
+            .stages
+            .iter()
+            .filter(|stage| !stage.bypassed())
+            .map(|stage| stage.stage_type().short_name())
+            .collect();
+        let mut summary = stages.join(">");
+        if let Some(ir) = &self.preset.ir_name {
+            if !summary.is_empty() {
+                summary.push(' ');
+            }
+            summary.push_str("+ ");
+            summary.push_str(ir);
+        }
+        summary
+    }
+
+    /// `INFO` fields for a take started on `date` (`YYYY-MM-DD`).
+    fn info_fields(&self, date: &str) -> Vec<([u8; 4], String)> {
+        let mut fields = vec![(*b"IART", APP_NAME.to_string())];
+        if !self.preset.name.is_empty() {
+            fields.push((*b"INAM", self.preset.name.clone()));
+        }
+        fields.push((*b"ICRD", date.to_string()));
+        fields.push((*b"ICMT", self.chain_summary()));
+        fields.push((
+            *b"ISFT",
+            format!("{APP_NAME} {}", env!("CARGO_PKG_VERSION")),
+        ));
+        fields
+    }
+
+    /// Write the sidecar and append the `INFO` list to the finalized WAV at
+    /// `recording`, a take started on `date`.
+    pub fn write(&self, recording: &Path, date: &str) -> Result<()> {
+        let sidecar = Self::sidecar_path(recording);
+        let mut value = serde_json::to_value(&self.preset).context("Failed to serialize preset")?;
+        // Alongside the preset's fields, so the sidecar still loads as one.
+        if let (Some(metronome), Some(fields)) = (self.metronome, value.as_object_mut()) {
+            let click_track = metronome.click_track.then(|| {
+                Recorder::click_track_path(recording)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            });
+            let entry = MetronomeEntry {
+                bpm: metronome.bpm,
+                time_signature: format!("{}/4", metronome.beats_per_bar),
+                click_track,
+            };
+            fields.insert(
+                METRONOME_KEY.to_string(),
+                serde_json::to_value(entry).context("Failed to serialize metronome")?,
+            );
+        }
+        let json = serde_json::to_string_pretty(&value).context("Failed to serialize preset")?;
+        fs::write(&sidecar, json)
+            .with_context(|| format!("Failed to write {}", sidecar.display()))?;
+
+        append_info_chunk(recording, &self.info_fields(date))
+            .with_context(|| format!("Failed to tag {}", recording.display()))
+    }
+}
+
+/// Append `fields` to the WAV at `path` as a `LIST`/`INFO` chunk, and grow
+/// the RIFF size to cover it.
+fn append_info_chunk(path: &Path, fields: &[([u8; 4], String)]) -> Result<()> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut len = file.seek(SeekFrom::End(0))?;
+    // Chunks start on even offsets.
+    if len % 2 == 1 {
+        file.write_all(&[0])?;
+        len += 1;
+    }
+    let chunk = info_chunk(fields);
+    file.write_all(&chunk)?;
+    len += chunk.len() as u64;
+
+    let riff_size = u32::try_from(len - 8).context("Recording too large to tag")?;
+    file.seek(SeekFrom::Start(4))?;
+    file.write_all(&riff_size.to_le_bytes())?;
+    Ok(())
+}
+
+/// A `LIST` chunk of type `INFO` holding `fields` as NUL-terminated strings,
+/// each padded to an even length.
+fn info_chunk(fields: &[([u8; 4], String)]) -> Vec<u8> {
+    let mut body = b"INFO".to_vec();
+    for (id, value) in fields {
+        let size = value.len() + 1;
+        body.extend_from_slice(id);
+        body.extend_from_slice(&(size as u32).to_le_bytes());
+        body.extend_from_slice(value.as_bytes());
+        body.push(0);
+        if size % 2 == 1 {
+            body.push(0);
+        }
+    }
+
+    let mut chunk = b"LIST".to_vec();
+    chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    chunk.extend_from_slice(&body);
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset::manager::parse_preset;
+    use crate::preset::stage_config::{StageConfig, StageType};
+    use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+    use tempfile::TempDir;
+
+    fn preset() -> Preset {
+        let mut level = StageConfig::from(StageType::Level);
+        level.set_bypassed(true);
+        Preset {
+            name: "Lead".to_string(),
+            stages: vec![
+                StageConfig::from(StageType::NoiseGate),
+                StageConfig::from(StageType::Preamp),
+                StageConfig::from(StageType::ToneStack),
+                StageConfig::from(StageType::PowerAmp),
+                level,
+            ],
+            ir_name: Some("mesa_v30.wav".to_string()),
+            ..Preset::default()
+        }
+    }
+
+    fn write_wav(path: &Path, samples: &[i16]) {
+        let spec = WavSpec {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(path, spec).unwrap();
+        for &s in samples {
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+    }
+
+    /// Walk the RIFF chunks the way a generic reader would, and return the
+    /// `INFO` fields.
+    fn read_info(bytes: &[u8]) -> Vec<(String, String)> {
+        assert_eq!(&bytes[..4], b"RIFF");
+        let riff_size = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        assert_eq!(
+            riff_size,
+            bytes.len() - 8,
+            "RIFF size doesn't cover the file"
+        );
+        assert_eq!(&bytes[8..12], b"WAVE");
+
+        let mut fields = Vec::new();
+        let mut pos = 12;
+        while pos + 8 <= bytes.len() {
+            let id = &bytes[pos..pos + 4];
+            let size = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+            let data = &bytes[pos + 8..pos + 8 + size];
+            if id == b"LIST" && &data[..4] == b"INFO" {
+                let mut sub = 4;
+                while sub + 8 <= data.len() {
+                    let key = String::from_utf8(data[sub..sub + 4].to_vec()).unwrap();
+                    let len =
+                        u32::from_le_bytes(data[sub + 4..sub + 8].try_into().unwrap()) as usize;
+                    let value = &data[sub + 8..sub + 8 + len];
+                    let value =
+                        String::from_utf8(value.split(|&b| b == 0).next().unwrap().to_vec())
+                            .unwrap();
+                    fields.push((key, value));
+                    sub += 8 + len + len % 2;
+                }
+            }
+            pos += 8 + size + size % 2;
+        }
+        fields
+    }
+
+    #[test]
+    fn summary_lists_the_heard_stages_and_the_ir() {
+        let metadata = RecordingMetadata::new(preset());
+        assert_eq!(metadata.chain_summary(), "Gate>Preamp>TS>PA + mesa_v30.wav");
+
+        let empty = RecordingMetadata::new(Preset::default());
+        assert_eq!(empty.chain_summary(), "");
+    }
+
+    #[test]
+    fn info_chunk_parses_and_leaves_the_audio_alone() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("take.wav");
+        let samples: Vec<i16> = (0..2_000).map(|i| (i % 300) as i16).collect();
+        write_wav(&path, &samples);
+
+        RecordingMetadata::new(preset())
+            .write(&path, "2026-10-17")
+            .unwrap();
+
+        let fields = read_info(&fs::read(&path).unwrap());
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("IART"), Some("Rustortion"));
+        assert_eq!(get("INAM"), Some("Lead"));
+        assert_eq!(get("ICRD"), Some("2026-10-17"));
+        assert_eq!(get("ICMT"), Some("Gate>Preamp>TS>PA + mesa_v30.wav"));
+
+        let mut reader = WavReader::open(&path).unwrap();
+        let read: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(read, samples);
+    }
+
+    #[test]
+    fn sidecar_round_trips_the_preset() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("take.wav");
+        write_wav(&path, &[0; 64]);
+
+        let preset = preset();
+        RecordingMetadata::new(preset.clone())
+            .write(&path, "2026-10-17")
+            .unwrap();
+
+        let sidecar = dir.path().join("take.preset.json");
+        assert_eq!(RecordingMetadata::sidecar_path(&path), sidecar);
+        let loaded = parse_preset(&fs::read_to_string(sidecar).unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&loaded).unwrap(),
+            serde_json::to_value(&preset).unwrap()
+        );
+    }
+
+    #[test]
+    fn sidecar_notes_the_metronome_and_still_loads_as_a_preset() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("take.wav");
+        write_wav(&path, &[0; 64]);
+
+        RecordingMetadata::new(preset())
+            .with_metronome(TakeMetronome {
+                bpm: 96.0,
+                beats_per_bar: 3,
+                click_track: true,
+            })
+            .write(&path, "2026-10-18")
+            .unwrap();
+
+        let json = fs::read_to_string(dir.path().join("take.preset.json")).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["metronome"]["bpm"], 96.0);
+        assert_eq!(value["metronome"]["time_signature"], "3/4");
+        assert_eq!(value["metronome"]["click_track"], "take.click.wav");
+        assert_eq!(parse_preset(&json).unwrap().name, "Lead");
+    }
+}
@@ -1,13 +1,171 @@
 use anyhow::{Context, Result};
 use log::debug;
 use rubato::audioadapter_buffers::direct::SequentialSliceOfVecs;
-use rubato::{Fft, FixedSync, Resampler};
+use rubato::{
+    Async, Fft, FixedAsync, FixedSync, Resampler, SincInterpolationParameters,
+    SincInterpolationType, WindowFunction,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::audio::load_guard::QUALITY_FADE_SECONDS;
 
 const CHANNELS: usize = 1;
 
+/// Resampler profile used for oversampling. Trades anti-aliasing quality for
+/// CPU, which matters most at 8x/16x where resampling dominates the budget.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResamplerQuality {
+    /// Short sinc, linear interpolation.
+    Fast,
+    /// Medium sinc, cubic interpolation.
+    Balanced,
+    /// FFT resampler: steepest filter, highest CPU.
+    #[default]
+    High,
+}
+
+impl ResamplerQuality {
+    pub const ALL: [Self; 3] = [Self::Fast, Self::Balanced, Self::High];
+
+    /// Sinc parameters for the async profiles; `None` for the FFT resampler.
+    const fn sinc_parameters(self) -> Option<SincInterpolationParameters> {
+        match self {
+            Self::Fast => Some(SincInterpolationParameters {
+                sinc_len: 32,
+                f_cutoff: 0.9,
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 256,
+                window: WindowFunction::Hann2,
+            }),
+            Self::Balanced => Some(SincInterpolationParameters {
+                sinc_len: 64,
+                f_cutoff: 0.93,
+                interpolation: SincInterpolationType::Cubic,
+                oversampling_factor: 128,
+                window: WindowFunction::BlackmanHarris2,
+            }),
+            Self::High => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ResamplerQuality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Fast => write!(f, "Fast"),
+            Self::Balanced => write!(f, "Balanced"),
+            Self::High => write!(f, "High"),
+        }
+    }
+}
+
+type BoxedResampler = Box<dyn Resampler<f32>>;
+
+/// The up/down resamplers for one oversampling factor.
+struct ResamplerPair {
+    up: BoxedResampler,
+    down: BoxedResampler,
+}
+
+impl ResamplerPair {
+    fn reset(&mut self) {
+        self.up.reset();
+        self.down.reset();
+    }
+}
+
+/// Fixed delay, for lining the light path up with the resamplers.
+struct DelayLine {
+    buffer: Vec<f32>,
+    pos: usize,
+}
+
+impl DelayLine {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len],
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let Some(slot) = self.buffer.get_mut(self.pos) else {
+            return input;
+        };
+        let output = std::mem::replace(slot, input);
+        self.pos = (self.pos + 1) % self.buffer.len();
+        output
+    }
+
+    fn reset(&mut self) {
+        self.buffer.fill(0.0);
+        self.pos = 0;
+    }
+}
+
+/// Cheap stand-in for the resamplers while the engine sheds load: linear
+/// interpolation on the way up, block averaging on the way down. Each half
+/// is delayed to match the resampler it replaces, so the two can crossfade.
+///
+/// The chain still runs at the oversampled rate: its stages are tuned for
+/// it, and feeding them base-rate samples would shift every filter and delay.
+struct LightPath {
+    upsampled: Vec<f32>,
+    downsampled: Vec<f32>,
+    input_delay: DelayLine,
+    output_delay: DelayLine,
+    /// Last input sample, where the next interpolation starts from.
+    previous: f32,
+}
+
+impl LightPath {
+    fn new(buffer_size: usize, factor: usize, input_delay: usize, output_delay: usize) -> Self {
+        Self {
+            upsampled: vec![0.0; buffer_size * factor],
+            downsampled: vec![0.0; buffer_size],
+            input_delay: DelayLine::new(input_delay),
+            output_delay: DelayLine::new(output_delay),
+            previous: 0.0,
+        }
+    }
+
+    fn upsample(&mut self, input: &[f32], factor: usize) {
+        let step_scale = 1.0 / factor as f32;
+        for (&sample, out) in input.iter().zip(self.upsampled.chunks_exact_mut(factor)) {
+            let sample = self.input_delay.process(sample);
+            let step = (sample - self.previous) * step_scale;
+            for (k, o) in out.iter_mut().enumerate() {
+                *o = step.mul_add((k + 1) as f32, self.previous);
+            }
+            self.previous = sample;
+        }
+    }
+
+    fn downsample(&mut self, upsampled: &[f32], factor: usize) {
+        let scale = 1.0 / factor as f32;
+        for (out, chunk) in self
+            .downsampled
+            .iter_mut()
+            .zip(upsampled.chunks_exact(factor))
+        {
+            *out = self.output_delay.process(chunk.iter().sum::<f32>() * scale);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.upsampled.fill(0.0);
+        self.downsampled.fill(0.0);
+        self.input_delay.reset();
+        self.output_delay.reset();
+        self.previous = 0.0;
+    }
+}
+
 pub struct Samplers {
-    upsampler: Fft<f32>,
-    downsampler: Fft<f32>,
+    /// `None` at 1x, where blocks pass straight through: no resampler is
+    /// built, so there's no filter delay and no resampling cost.
+    resamplers: Option<ResamplerPair>,
     input_buffer: Vec<Vec<f32>>,
     upsampled_buffer: Vec<Vec<f32>>,
     downsampled_buffer: Vec<Vec<f32>>,
@@ -17,53 +175,143 @@ pub struct Samplers {
     upsampled_frames: usize,
     oversample_factor: f64,
     sample_rate: usize,
+    quality: ResamplerQuality,
+    light: LightPath,
+    /// Whether the light path should replace the resamplers.
+    light_wanted: bool,
+    /// Share of the light path in the signal: 0 on the resamplers, 1 on the
+    /// light path, in between while crossfading.
+    light_mix: f32,
+    /// Base-rate samples both paths still run side by side, warming up the
+    /// one switched to, before the crossfade starts.
+    warmup: usize,
+    /// Which paths run this block, and how the mix moves per base sample.
+    run_resamplers: bool,
+    run_light: bool,
+    mix_step: f32,
 }
 
 impl Samplers {
     pub fn new(buffer_size: usize, oversample_factor: f64, sample_rate: usize) -> Result<Self> {
-        let upsampler = Fft::<f32>::new(
-            sample_rate,
-            sample_rate * oversample_factor as usize,
+        Self::with_quality(
             buffer_size,
-            1,
-            CHANNELS,
-            FixedSync::Both,
-        )
-        .context("failed to create upsampler")?;
-
-        let downsampler = Fft::<f32>::new(
-            sample_rate * oversample_factor as usize,
+            oversample_factor,
             sample_rate,
-            buffer_size * oversample_factor as usize,
-            1,
-            CHANNELS,
-            FixedSync::Both,
+            ResamplerQuality::default(),
         )
-        .context("failed to create downsampler")?;
+    }
+
+    pub fn with_quality(
+        buffer_size: usize,
+        oversample_factor: f64,
+        sample_rate: usize,
+        quality: ResamplerQuality,
+    ) -> Result<Self> {
+        let resamplers = if is_unity_factor(oversample_factor) {
+            None
+        } else {
+            Some(build_resamplers(
+                quality,
+                sample_rate,
+                oversample_factor,
+                buffer_size,
+            )?)
+        };
 
         let mut input_vec = Vec::with_capacity(buffer_size);
         input_vec.resize(buffer_size, 0.0);
         let input_buffer = vec![input_vec];
-        let upsampled_buffer = vec![vec![0.0; upsampler.output_frames_max()]; CHANNELS];
-        let downsampled_buffer = vec![vec![0.0; downsampler.output_frames_max()]; CHANNELS];
+        let (upsampled_buffer, downsampled_buffer) =
+            scratch_buffers(resamplers.as_ref(), buffer_size);
         let upsampled_frames = upsampled_buffer[0].len();
+        let light = build_light_path(resamplers.as_ref(), buffer_size, oversample_factor);
 
         Ok(Self {
-            upsampler,
-            downsampler,
+            resamplers,
             input_buffer,
             upsampled_buffer,
             downsampled_buffer,
             upsampled_frames,
             oversample_factor,
             sample_rate,
+            quality,
+            light,
+            light_wanted: false,
+            light_mix: 0.0,
+            warmup: 0,
+            run_resamplers: true,
+            run_light: false,
+            mix_step: 0.0,
         })
     }
 
+    /// Swap the resamplers for the cheap light path, or back. Either way
+    /// the path switched to warms up alongside first, then the two
+    /// crossfade. Allocation-free, so it's safe on the RT thread.
+    pub fn set_light_path(&mut self, light: bool) {
+        // Nothing to lighten when there's no resampling.
+        if light == self.light_wanted || self.passes_through() {
+            return;
+        }
+        self.light_wanted = light;
+        if light && self.light_mix == 0.0 {
+            self.light.reset();
+            self.warmup = self.warmup_len();
+        } else if !light && self.light_mix == 1.0 {
+            // Idle since the switch, so their history is stale.
+            if let Some(resamplers) = &mut self.resamplers {
+                resamplers.reset();
+            }
+            self.warmup = self.warmup_len();
+        } else {
+            // Mid-crossfade: both are warm, just turn around.
+            self.warmup = 0;
+        }
+    }
+
+    /// Whether the light path is playing, in full or in part.
+    pub const fn light_path_active(&self) -> bool {
+        self.light_wanted || self.light_mix > 0.0
+    }
+
+    /// How long the path switched to runs before the crossfade: twice the
+    /// latency, since a filter centred on its delay needs that long to fill.
+    const fn warmup_len(&self) -> usize {
+        2 * self.latency_samples() + 1
+    }
+
+    /// Base-rate delay of the resampler pair, up and back down, which the
+    /// light path matches. Zero at 1x.
+    pub const fn latency_samples(&self) -> usize {
+        self.light.input_delay.buffer.len() + self.light.output_delay.buffer.len()
+    }
+
+    /// Whether blocks skip resampling altogether, as they do at 1x.
+    pub const fn passes_through(&self) -> bool {
+        self.resamplers.is_none()
+    }
+
+    const fn factor(&self) -> usize {
+        self.oversample_factor as usize
+    }
+
     pub const fn get_oversample_factor(&self) -> f64 {
         self.oversample_factor
     }
 
+    pub const fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    /// Rate the chain runs at between the resamplers.
+    pub fn chain_rate(&self) -> f32 {
+        (self.sample_rate as f64 * self.oversample_factor) as f32
+    }
+
+    pub const fn quality(&self) -> ResamplerQuality {
+        self.quality
+    }
+
     pub fn copy_input(&mut self, input: &[f32]) -> Result<()> {
         if input.len() != self.input_buffer[0].len() {
             return Err(anyhow::anyhow!(
@@ -78,6 +326,52 @@ impl Samplers {
     }
 
     pub fn upsample(&mut self) -> Result<&mut [f32]> {
+        let in_frames = self.input_buffer[0].len();
+        let factor = self.factor();
+
+        if self.passes_through() {
+            self.upsampled_buffer[0][..in_frames].copy_from_slice(&self.input_buffer[0]);
+            self.upsampled_frames = in_frames;
+            return Ok(&mut self.upsampled_buffer[0][..in_frames]);
+        }
+
+        self.run_resamplers = !(self.light_wanted && self.light_mix == 1.0);
+        self.run_light = self.light_wanted || self.light_mix > 0.0;
+        let target = if self.light_wanted { 1.0 } else { 0.0 };
+        self.mix_step = if self.warmup > 0 || self.light_mix == target {
+            0.0
+        } else {
+            let step = 1.0 / (QUALITY_FADE_SECONDS * self.sample_rate as f32);
+            if self.light_wanted { step } else { -step }
+        };
+
+        if self.run_resamplers {
+            self.resample_up()?;
+        } else {
+            self.upsampled_frames = in_frames * factor;
+        }
+
+        if self.run_light {
+            self.light.upsample(&self.input_buffer[0], factor);
+            let frames = self.upsampled_frames.min(self.light.upsampled.len());
+            let light = &self.light.upsampled[..frames];
+            if self.run_resamplers {
+                for (i, (out, &l)) in self.upsampled_buffer[0].iter_mut().zip(light).enumerate() {
+                    let mix = light_mix_at(self.light_mix, self.mix_step, i / factor);
+                    *out = mix.mul_add(l - *out, *out);
+                }
+            } else {
+                self.upsampled_buffer[0][..frames].copy_from_slice(light);
+            }
+        }
+
+        Ok(&mut self.upsampled_buffer[0][..self.upsampled_frames])
+    }
+
+    fn resample_up(&mut self) -> Result<()> {
+        let Some(resamplers) = &mut self.resamplers else {
+            return Ok(());
+        };
         let in_frames = self.input_buffer[0].len();
         let out_frames = self.upsampled_buffer[0].len();
 
@@ -87,16 +381,57 @@ impl Samplers {
             SequentialSliceOfVecs::new_mut(&mut self.upsampled_buffer, CHANNELS, out_frames)
                 .map_err(|e| anyhow::anyhow!("upsampler output adapter: {e:?}"))?;
 
-        let (_, upsampled_frames) = self
-            .upsampler
+        let (_, upsampled_frames) = resamplers
+            .up
             .process_into_buffer(&input, &mut output, None)
             .context("Upsampler failed")?;
         self.upsampled_frames = upsampled_frames;
 
-        Ok(&mut self.upsampled_buffer[0][..upsampled_frames])
+        Ok(())
     }
 
     pub fn downsample(&mut self) -> Result<&mut [f32]> {
+        let factor = self.factor();
+
+        if self.passes_through() {
+            let frames = self.upsampled_frames;
+            self.downsampled_buffer[0][..frames]
+                .copy_from_slice(&self.upsampled_buffer[0][..frames]);
+            return Ok(&mut self.downsampled_buffer[0][..frames]);
+        }
+        let mut frames = 0;
+        if self.run_resamplers {
+            frames = self.resample_down()?;
+        }
+
+        if self.run_light {
+            let upsampled = &self.upsampled_buffer[0][..self.upsampled_frames];
+            self.light.downsample(upsampled, factor);
+            let light_frames = (self.upsampled_frames / factor).min(self.light.downsampled.len());
+            let light = &self.light.downsampled[..light_frames];
+            if self.run_resamplers {
+                for (i, (out, &l)) in self.downsampled_buffer[0].iter_mut().zip(light).enumerate() {
+                    let mix = light_mix_at(self.light_mix, self.mix_step, i);
+                    *out = mix.mul_add(l - *out, *out);
+                }
+            } else {
+                self.downsampled_buffer[0][..light_frames].copy_from_slice(light);
+                frames = light_frames;
+            }
+        }
+
+        let block = self.input_buffer[0].len();
+        self.light_mix = light_mix_at(self.light_mix, self.mix_step, block);
+        self.warmup = self.warmup.saturating_sub(block);
+
+        Ok(&mut self.downsampled_buffer[0][..frames])
+    }
+
+    /// Run the downsampler; returns the frames it wrote.
+    fn resample_down(&mut self) -> Result<usize> {
+        let Some(resamplers) = &mut self.resamplers else {
+            return Ok(0);
+        };
         // Feed back exactly the frames the chain just processed in place,
         // not the full buffer capacity, so no stale tail can leak through.
         let in_frames = self.upsampled_frames;
@@ -108,12 +443,28 @@ impl Samplers {
             SequentialSliceOfVecs::new_mut(&mut self.downsampled_buffer, CHANNELS, out_frames)
                 .map_err(|e| anyhow::anyhow!("downsampler output adapter: {e:?}"))?;
 
-        let (_, downsampled_frames) = self
-            .downsampler
+        let (_, downsampled_frames) = resamplers
+            .down
             .process_into_buffer(&input, &mut output, None)
             .context("Downsampler failed")?;
 
-        Ok(&mut self.downsampled_buffer[0][..downsampled_frames])
+        Ok(downsampled_frames)
+    }
+
+    /// Clear resampler history and scratch buffers. RT-safe.
+    pub fn reset(&mut self) {
+        if let Some(resamplers) = &mut self.resamplers {
+            resamplers.reset();
+        }
+        for buf in self
+            .input_buffer
+            .iter_mut()
+            .chain(&mut self.upsampled_buffer)
+            .chain(&mut self.downsampled_buffer)
+        {
+            buf.fill(0.0);
+        }
+        self.light.reset();
     }
 
     pub fn resize_buffers(&mut self, new_size: usize) -> Result<()> {
@@ -129,29 +480,232 @@ impl Samplers {
 
         self.input_buffer[0].resize(new_size, 0.0);
 
-        self.upsampler = Fft::<f32>::new(
-            self.sample_rate,
-            self.sample_rate * self.oversample_factor as usize,
-            new_size,
+        if self.resamplers.is_some() {
+            self.resamplers = Some(
+                build_resamplers(
+                    self.quality,
+                    self.sample_rate,
+                    self.oversample_factor,
+                    new_size,
+                )
+                .context("failed to recreate resamplers")?,
+            );
+        }
+
+        (self.upsampled_buffer, self.downsampled_buffer) =
+            scratch_buffers(self.resamplers.as_ref(), new_size);
+        self.upsampled_frames = self.upsampled_buffer[0].len();
+
+        self.light = build_light_path(self.resamplers.as_ref(), new_size, self.oversample_factor);
+
+        Ok(())
+    }
+}
+
+/// Share of the light path at base-rate sample `i` of a block that starts at
+/// `start` and moves by `step` per sample.
+#[inline]
+const fn light_mix_at(start: f32, step: f32, i: usize) -> f32 {
+    (i as f32).mul_add(step, start).clamp(0.0, 1.0)
+}
+
+/// Whether `oversample_factor` needs no resampling at all.
+const fn is_unity_factor(oversample_factor: f64) -> bool {
+    oversample_factor <= 1.0
+}
+
+/// Upsampled and downsampled scratch buffers for `resamplers`, or a block
+/// each when passing through.
+fn scratch_buffers(
+    resamplers: Option<&ResamplerPair>,
+    buffer_size: usize,
+) -> (Vec<Vec<f32>>, Vec<Vec<f32>>) {
+    let (up, down) = resamplers.map_or((buffer_size, buffer_size), |r| {
+        (r.up.output_frames_max(), r.down.output_frames_max())
+    });
+    (
+        vec![vec![0.0; up]; CHANNELS],
+        vec![vec![0.0; down]; CHANNELS],
+    )
+}
+
+/// Light path for a block of `buffer_size`, delayed to match `resamplers`.
+/// Their delays are in output frames, so the upsampler's is at the
+/// oversampled rate. Undelayed when passing through.
+fn build_light_path(
+    resamplers: Option<&ResamplerPair>,
+    buffer_size: usize,
+    oversample_factor: f64,
+) -> LightPath {
+    let factor = (oversample_factor as usize).max(1);
+    let (input_delay, output_delay) = resamplers.map_or((0, 0), |r| {
+        (
+            (r.up.output_delay() + factor / 2) / factor,
+            r.down.output_delay(),
+        )
+    });
+    LightPath::new(buffer_size, factor, input_delay, output_delay)
+}
+
+/// Build the up/down resampler pair for `quality`. Each processes one block
+/// per call: `buffer_size` frames in on the way up, `buffer_size * factor`
+/// frames in on the way down.
+fn build_resamplers(
+    quality: ResamplerQuality,
+    sample_rate: usize,
+    oversample_factor: f64,
+    buffer_size: usize,
+) -> Result<ResamplerPair> {
+    let factor = oversample_factor as usize;
+    let oversampled_rate = sample_rate * factor;
+
+    let Some(params) = quality.sinc_parameters() else {
+        let upsampler = Fft::<f32>::new(
+            sample_rate,
+            oversampled_rate,
+            buffer_size,
             1,
             CHANNELS,
             FixedSync::Both,
         )
-        .context("failed to recreate upsampler")?;
-        self.upsampled_buffer = vec![vec![0.0; self.upsampler.output_frames_max()]; CHANNELS];
-        self.upsampled_frames = self.upsampled_buffer[0].len();
+        .context("failed to create upsampler")?;
 
-        self.downsampler = Fft::<f32>::new(
-            self.sample_rate * self.oversample_factor as usize,
-            self.sample_rate,
-            new_size * self.oversample_factor as usize,
+        let downsampler = Fft::<f32>::new(
+            oversampled_rate,
+            sample_rate,
+            buffer_size * factor,
             1,
             CHANNELS,
             FixedSync::Both,
         )
-        .context("failed to recreate downsampler")?;
-        self.downsampled_buffer = vec![vec![0.0; self.downsampler.output_frames_max()]; CHANNELS];
+        .context("failed to create downsampler")?;
 
-        Ok(())
+        return Ok(ResamplerPair {
+            up: Box::new(upsampler),
+            down: Box::new(downsampler),
+        });
+    };
+
+    let upsampler = Async::<f32>::new_sinc(
+        oversample_factor,
+        1.0,
+        &params,
+        buffer_size,
+        CHANNELS,
+        FixedAsync::Input,
+    )
+    .context("failed to create upsampler")?;
+
+    let downsampler = Async::<f32>::new_sinc(
+        1.0 / oversample_factor,
+        1.0,
+        &params,
+        buffer_size * factor,
+        CHANNELS,
+        FixedAsync::Input,
+    )
+    .context("failed to create downsampler")?;
+
+    Ok(ResamplerPair {
+        up: Box::new(upsampler),
+        down: Box::new(downsampler),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_quality_round_trips_full_blocks() {
+        const BUFFER_SIZE: usize = 128;
+        const FACTOR: f64 = 4.0;
+
+        for quality in ResamplerQuality::ALL {
+            let mut samplers =
+                Samplers::with_quality(BUFFER_SIZE, FACTOR, 48_000, quality).unwrap();
+            let input = [0.25f32; BUFFER_SIZE];
+
+            for _ in 0..8 {
+                samplers.copy_input(&input).unwrap();
+                let up = samplers.upsample().unwrap().len();
+                assert_eq!(up, BUFFER_SIZE * FACTOR as usize, "{quality} upsample");
+                let down = samplers.downsample().unwrap().len();
+                assert_eq!(down, BUFFER_SIZE, "{quality} downsample");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unity_factor_passes_blocks_through_untouched() {
+        const BUFFER_SIZE: usize = 128;
+
+        for quality in ResamplerQuality::ALL {
+            let mut samplers = Samplers::with_quality(BUFFER_SIZE, 1.0, 48_000, quality).unwrap();
+            assert!(samplers.passes_through());
+            assert_eq!(samplers.latency_samples(), 0);
+
+            // The light path has nothing to stand in for.
+            samplers.set_light_path(true);
+            assert!(!samplers.light_path_active());
+
+            for block in 0..4 {
+                let input: Vec<f32> = (0..BUFFER_SIZE)
+                    .map(|i| ((block * BUFFER_SIZE + i) as f32 * 0.37).sin())
+                    .collect();
+                samplers.copy_input(&input).unwrap();
+                assert_eq!(samplers.upsample().unwrap(), input.as_slice());
+                assert_eq!(samplers.downsample().unwrap(), input.as_slice());
+            }
+
+            samplers.resize_buffers(64).unwrap();
+            let input = [0.5f32; 64];
+            samplers.copy_input(&input).unwrap();
+            assert_eq!(samplers.upsample().unwrap(), input.as_slice());
+            assert_eq!(samplers.downsample().unwrap(), input.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_light_path_switches_without_dropping_the_signal() {
+        const BUFFER_SIZE: usize = 128;
+        const FACTOR: f64 = 4.0;
+
+        /// Blocks of DC through the samplers, with an identity chain.
+        fn run(samplers: &mut Samplers, blocks: usize) -> Vec<f32> {
+            let input = [0.25f32; BUFFER_SIZE];
+            let mut output = Vec::new();
+            for _ in 0..blocks {
+                samplers.copy_input(&input).unwrap();
+                let up = samplers.upsample().unwrap().len();
+                assert_eq!(up, BUFFER_SIZE * FACTOR as usize);
+                let down = samplers.downsample().unwrap();
+                assert_eq!(down.len(), BUFFER_SIZE);
+                output.extend_from_slice(down);
+            }
+            output
+        }
+
+        for quality in ResamplerQuality::ALL {
+            let mut samplers =
+                Samplers::with_quality(BUFFER_SIZE, FACTOR, 48_000, quality).unwrap();
+            run(&mut samplers, 64);
+
+            samplers.set_light_path(true);
+            let reducing = run(&mut samplers, 64);
+            assert!(samplers.light_path_active());
+            assert!(!samplers.run_resamplers, "{quality}: resamplers still run");
+
+            samplers.set_light_path(false);
+            let restoring = run(&mut samplers, 64);
+            assert!(!samplers.light_path_active());
+            assert!(!samplers.run_light, "{quality}: light path still runs");
+
+            for (phase, output) in [("reducing", reducing), ("restoring", restoring)] {
+                if let Some(i) = output.iter().position(|s| (s - 0.25).abs() > 0.02) {
+                    panic!("{quality} {phase}: sample {i} is {}", output[i]);
+                }
+            }
+        }
     }
 }
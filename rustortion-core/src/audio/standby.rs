@@ -0,0 +1,130 @@
+//! Standby, as on a tube amp: the power amp's output is muted while the
+//! stages ahead of it keep running, so gates and compressors stay settled
+//! and coming off standby doesn't pop.
+
+/// How long the power amp takes to fade out going into standby, and back in
+/// coming out of it.
+pub const STANDBY_FADE_SECONDS: f32 = 0.2;
+
+/// The standby mute and its fade. Applied by the chain just after its last
+/// power amp stage, at the chain's (possibly oversampled) rate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Standby {
+    on: bool,
+    /// Level passed through: 0 once faded out, 1 once back.
+    gain: f32,
+    /// Gain change per sample while fading.
+    step: f32,
+}
+
+impl Standby {
+    /// Start settled: fully muted when `on`, else fully open.
+    pub fn new(on: bool, sample_rate: f32) -> Self {
+        Self {
+            on,
+            gain: if on { 0.0 } else { 1.0 },
+            step: fade_step(sample_rate),
+        }
+    }
+
+    /// Go into standby, or come out of it, fading from wherever the fade is.
+    pub const fn set(&mut self, on: bool) {
+        self.on = on;
+    }
+
+    pub const fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub const fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Retime the fade for the rate the chain now runs at.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.step = fade_step(sample_rate);
+    }
+
+    /// Out of standby and done fading in, so there's nothing to apply.
+    pub fn is_idle(&self) -> bool {
+        !self.on && self.gain >= 1.0
+    }
+
+    /// Scale `buf` by the standby gain, stepping the fade along.
+    pub fn process_block(&mut self, buf: &mut [f32]) {
+        if self.is_idle() {
+            return;
+        }
+        if self.on && self.gain <= 0.0 {
+            buf.fill(0.0);
+            return;
+        }
+        // Snapped to the end once within half a step, so rounding in the
+        // sum can't leave the fade a hair short of it.
+        let snap = self.step * 0.5;
+        for sample in buf.iter_mut() {
+            self.gain = if self.on {
+                let gain = self.gain - self.step;
+                if gain < snap { 0.0 } else { gain }
+            } else {
+                let gain = self.gain + self.step;
+                if gain > 1.0 - snap { 1.0 } else { gain }
+            };
+            *sample *= self.gain;
+        }
+    }
+}
+
+fn fade_step(sample_rate: f32) -> f32 {
+    1.0 / (STANDBY_FADE_SECONDS * sample_rate).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: f32 = 1_000.0;
+
+    #[test]
+    fn fades_over_the_fade_time() {
+        let mut standby = Standby::new(false, RATE);
+        assert!(standby.is_idle());
+
+        standby.set(true);
+        // Half way through the 200-sample fade.
+        let mut buf = [1.0f32; 100];
+        standby.process_block(&mut buf);
+        assert!((buf[99] - 0.5).abs() < 1e-3, "got {}", buf[99]);
+        assert!(buf.windows(2).all(|w| w[1] < w[0]));
+
+        let mut buf = [1.0f32; 100];
+        standby.process_block(&mut buf);
+        assert!(buf[99].abs() < 1e-3);
+        let mut buf = [1.0f32; 10];
+        standby.process_block(&mut buf);
+        assert_eq!(buf, [0.0; 10]);
+
+        // Coming back fades in from silence, not a jump.
+        standby.set(false);
+        let mut buf = [1.0f32; 200];
+        standby.process_block(&mut buf);
+        assert!(buf[0] < 0.01);
+        assert!((buf[99] - 0.5).abs() < 1e-3);
+        assert!(standby.is_idle());
+    }
+
+    #[test]
+    fn starts_settled_and_reverses_mid_fade() {
+        let mut standby = Standby::new(true, RATE);
+        let mut buf = [1.0f32; 4];
+        standby.process_block(&mut buf);
+        assert_eq!(buf, [0.0; 4]);
+
+        standby.set(false);
+        standby.process_block(&mut [1.0f32; 50]);
+        standby.set(true);
+        let mut buf = [1.0f32; 1];
+        standby.process_block(&mut buf);
+        assert!((buf[0] - 0.245).abs() < 1e-3, "got {}", buf[0]);
+    }
+}
@@ -0,0 +1,565 @@
+//! Up to four recorded takes played against one playhead, for picking the
+//! best of them: switching takes keeps the place in the song, so the only
+//! thing that changes is the take.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result};
+
+use crate::amp::stages::common::db_to_lin;
+use crate::audio::offline::read_stereo_wav_at;
+use crate::audio::recorder::Recorder;
+
+/// Takes compared at once, one per number key.
+pub const MAX_TAKES: usize = 4;
+/// Peak a normalised take is brought to.
+pub const NORMALIZED_PEAK_DB: f32 = -1.0;
+
+/// Frames a switch crossfades over, so it doesn't click.
+const SWITCH_FADE_FRAMES: usize = 256;
+/// Frames of energy averaged when looking for a take's first note.
+const ONSET_WINDOW: usize = 64;
+/// A take's first note is the first window this close to its loudest one.
+const ONSET_THRESHOLD_DB: f32 = -24.0;
+
+/// Where a take's first note starts: the first [`ONSET_WINDOW`] whose energy
+/// is within [`ONSET_THRESHOLD_DB`] of the loudest window, narrowed down to
+/// the first sample in it over the same threshold. Judging against the
+/// loudest window rather than a fixed level finds the same note in a quiet
+/// take as in a loud one. 0 for silence.
+pub fn detect_onset(samples: &[f32]) -> usize {
+    let energy = |window: &[f32]| window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32;
+    let loudest = samples.chunks(ONSET_WINDOW).map(energy).fold(0.0, f32::max);
+    if loudest <= 0.0 {
+        return 0;
+    }
+
+    // Energy is a power ratio, hence the dB over 10.
+    let threshold = loudest * 10.0_f32.powf(ONSET_THRESHOLD_DB / 10.0);
+    let Some(window) = samples
+        .chunks(ONSET_WINDOW)
+        .position(|window| energy(window) >= threshold)
+    else {
+        return 0;
+    };
+    let start = window * ONSET_WINDOW;
+    let level = threshold.sqrt();
+    samples[start..]
+        .iter()
+        .take(ONSET_WINDOW)
+        .position(|s| s.abs() >= level)
+        .map_or(start, |offset| start + offset)
+}
+
+/// A decoded take, in stereo at the engine's sample rate.
+pub struct Take {
+    pub name: String,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    /// Frame its first note starts on, see [`detect_onset`].
+    onset: usize,
+    peak: f32,
+}
+
+impl Take {
+    /// A take from its channels, which are cut to the shorter of the two.
+    pub fn new(name: String, mut left: Vec<f32>, mut right: Vec<f32>) -> Self {
+        let len = left.len().min(right.len());
+        left.truncate(len);
+        right.truncate(len);
+        let mid: Vec<f32> = left
+            .iter()
+            .zip(&right)
+            .map(|(l, r)| 0.5 * (l + r))
+            .collect();
+        let peak = left
+            .iter()
+            .chain(&right)
+            .fold(0.0_f32, |peak, s| peak.max(s.abs()));
+
+        Self {
+            name,
+            onset: detect_onset(&mid),
+            left,
+            right,
+            peak,
+        }
+    }
+
+    /// Read a take, resampled to `sample_rate` if it was recorded at
+    /// another rate, and named after its file.
+    pub fn load(path: &Path, sample_rate: usize) -> Result<Self> {
+        let [left, right] = read_stereo_wav_at(path, sample_rate)?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self::new(name, left, right))
+    }
+
+    pub const fn onset(&self) -> usize {
+        self.onset
+    }
+
+    pub const fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    /// Gain that brings the take to [`NORMALIZED_PEAK_DB`]; 1 for silence.
+    fn normalizing_gain(&self) -> f32 {
+        if self.peak > 0.0 {
+            db_to_lin(NORMALIZED_PEAK_DB) / self.peak
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Sent to the player on the RT thread, see [`TakePlayer::apply`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TakeCommand {
+    /// Switch to this take at the current playhead.
+    Select(usize),
+    Play,
+    Pause,
+    /// Back to the first take's first note.
+    Rewind,
+}
+
+/// The playhead as the GUI sees it, written by the player each block.
+#[derive(Debug, Default)]
+pub struct TakeTransport {
+    frame: AtomicUsize,
+    playing: AtomicBool,
+}
+
+impl TakeTransport {
+    /// Frames into the aligned takes.
+    pub fn frame(&self) -> usize {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    /// Stays true until a pause, or until the longest take runs out.
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Relaxed)
+    }
+}
+
+/// Plays one of its takes in place of the engine's output. The takes are
+/// lined up on their first notes, so frame `n` of the playhead is the same
+/// moment in every take.
+///
+/// Everything is decoded before the player reaches the RT thread, so
+/// rendering and switching never allocate.
+pub struct TakePlayer {
+    takes: Vec<Take>,
+    /// Frames cut from the front of each take to line it up: how much later
+    /// its first note starts than the earliest one's.
+    offsets: Vec<usize>,
+    /// Frames of the aligned timeline: until the longest take ends.
+    length: usize,
+    /// Per take, 1 or its normalising gain.
+    gains: Vec<f32>,
+    playhead: usize,
+    active: usize,
+    /// The take faded out of after a switch, and frames of fade left.
+    fading: Option<(usize, usize)>,
+    playing: bool,
+    transport: Arc<TakeTransport>,
+}
+
+impl TakePlayer {
+    /// Line `takes` up on their first notes, keeping at most [`MAX_TAKES`].
+    /// With `normalize`, each is brought to a peak of
+    /// [`NORMALIZED_PEAK_DB`] so the louder take doesn't win on level.
+    pub fn new(mut takes: Vec<Take>, normalize: bool) -> Self {
+        takes.truncate(MAX_TAKES);
+        let earliest = takes.iter().map(Take::onset).min().unwrap_or(0);
+        let offsets: Vec<usize> = takes.iter().map(|t| t.onset() - earliest).collect();
+        let length = takes
+            .iter()
+            .zip(&offsets)
+            .map(|(take, offset)| take.len() - offset)
+            .max()
+            .unwrap_or(0);
+        let gains = takes
+            .iter()
+            .map(|take| {
+                if normalize {
+                    take.normalizing_gain()
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+
+        Self {
+            takes,
+            offsets,
+            length,
+            gains,
+            playhead: 0,
+            active: 0,
+            fading: None,
+            playing: false,
+            transport: Arc::new(TakeTransport::default()),
+        }
+    }
+
+    /// Load the takes at `paths`, in order, at `sample_rate`; see
+    /// [`Self::new`].
+    pub fn load(paths: &[PathBuf], sample_rate: usize, normalize: bool) -> Result<Self> {
+        let takes = paths
+            .iter()
+            .take(MAX_TAKES)
+            .map(|path| {
+                Take::load(path, sample_rate)
+                    .with_context(|| format!("Failed to load take {}", path.display()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(takes, normalize))
+    }
+
+    /// For the GUI to follow the playhead once the player is on the RT
+    /// thread.
+    pub fn transport(&self) -> Arc<TakeTransport> {
+        Arc::clone(&self.transport)
+    }
+
+    pub fn take_names(&self) -> Vec<String> {
+        self.takes.iter().map(|take| take.name.clone()).collect()
+    }
+
+    /// Frames cut from the front of each take to line it up.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Frames until the longest take ends.
+    pub const fn length(&self) -> usize {
+        self.length
+    }
+
+    pub const fn playhead(&self) -> usize {
+        self.playhead
+    }
+
+    pub const fn active(&self) -> usize {
+        self.active
+    }
+
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn apply(&mut self, command: TakeCommand) {
+        match command {
+            TakeCommand::Select(take) => self.select(take),
+            TakeCommand::Play => self.playing = !self.takes.is_empty(),
+            TakeCommand::Pause => self.playing = false,
+            TakeCommand::Rewind => {
+                self.playhead = 0;
+                self.fading = None;
+            }
+        }
+        self.publish();
+    }
+
+    /// Play `take` from where the playhead is, fading over from the one
+    /// that was playing. Out of range, or already playing, does nothing.
+    pub fn select(&mut self, take: usize) {
+        if take >= self.takes.len() || take == self.active {
+            return;
+        }
+        self.fading = Some((self.active, SWITCH_FADE_FRAMES));
+        self.active = take;
+    }
+
+    /// Write the active take into `left` and `right`. Returns `false`,
+    /// leaving them alone, while paused. Playing past the longest take's end
+    /// stops and rewinds, with silence for the rest of that block.
+    pub fn render(&mut self, left: &mut [f32], right: &mut [f32]) -> bool {
+        if !self.playing {
+            return false;
+        }
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            if self.playhead >= self.length {
+                *l = 0.0;
+                *r = 0.0;
+                continue;
+            }
+            let (mut out_l, mut out_r) = self.frame(self.active);
+            if let Some((from, remaining)) = self.fading {
+                let (from_l, from_r) = self.frame(from);
+                let mix = remaining as f32 / SWITCH_FADE_FRAMES as f32;
+                out_l = mix.mul_add(from_l - out_l, out_l);
+                out_r = mix.mul_add(from_r - out_r, out_r);
+                self.fading = (remaining > 1).then_some((from, remaining - 1));
+            }
+            *l = out_l;
+            *r = out_r;
+            self.playhead += 1;
+        }
+
+        if self.playhead >= self.length {
+            self.playing = false;
+            self.playhead = 0;
+            self.fading = None;
+        }
+        self.publish();
+        true
+    }
+
+    /// `take`'s frame at the playhead, silent past its end.
+    fn frame(&self, take: usize) -> (f32, f32) {
+        let data = &self.takes[take];
+        let gain = self.gains[take];
+        let at = self.playhead + self.offsets[take];
+        match (data.left.get(at), data.right.get(at)) {
+            (Some(l), Some(r)) => (l * gain, r * gain),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    fn publish(&self) {
+        self.transport.frame.store(self.playhead, Ordering::Relaxed);
+        self.transport
+            .playing
+            .store(self.playing, Ordering::Relaxed);
+    }
+}
+
+/// The most recent takes in `dir` that share a name with the newest one,
+/// oldest first so they number in the order they were played. Click tracks
+/// are left out. A folder nothing has been recorded into yet has none.
+pub fn recent_takes(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    let takes = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+                && !Recorder::is_click_track(path)
+        })
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    Ok(pick_takes(takes))
+}
+
+/// [`recent_takes`] from a listing of takes and when each was written.
+pub fn pick_takes(mut takes: Vec<(PathBuf, SystemTime)>) -> Vec<PathBuf> {
+    takes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+    let Some(prefix) = takes.first().map(|(path, _)| take_prefix(path)) else {
+        return Vec::new();
+    };
+    let mut picked: Vec<PathBuf> = takes
+        .into_iter()
+        .map(|(path, _)| path)
+        .filter(|path| take_prefix(path) == prefix)
+        .take(MAX_TAKES)
+        .collect();
+    picked.reverse();
+    picked
+}
+
+/// A take's name without the number or timestamp that tells it from its
+/// siblings: `recording_20260101_120000` and `recording_20260101_120500`
+/// are both `recording`, `solo 2` is `solo`.
+pub fn take_prefix(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = stem
+        .split(|c: char| c.is_ascii_digit())
+        .next()
+        .unwrap_or("");
+    name.trim_end_matches(['_', '-', ' ', '.']).to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const LEN: usize = 4800;
+
+    /// A decaying 220 Hz note starting at `onset`, `level` loud, over a
+    /// little noise floor.
+    fn note(onset: usize, level: f32) -> Vec<f32> {
+        (0..LEN)
+            .map(|i| {
+                let floor = if i % 2 == 0 { 1e-4 } else { -1e-4 };
+                if i < onset {
+                    return floor;
+                }
+                let t = (i - onset) as f32 / 48_000.0;
+                floor + level * (-t * 20.0).exp() * (std::f32::consts::TAU * 220.0 * t).sin()
+            })
+            .collect()
+    }
+
+    fn take(name: &str, samples: Vec<f32>) -> Take {
+        Take::new(name.to_owned(), samples.clone(), samples)
+    }
+
+    /// The player's output for `frames` frames, left channel.
+    fn play(player: &mut TakePlayer, frames: usize) -> Vec<f32> {
+        let mut left = vec![0.0; frames];
+        let mut right = vec![0.0; frames];
+        player.render(&mut left, &mut right);
+        left
+    }
+
+    #[test]
+    fn onset_is_found_whatever_the_level() {
+        // The note's first sample is 0, so its first sample over the
+        // threshold comes a little later.
+        let loud = detect_onset(&note(1000, 0.8));
+        let quiet = detect_onset(&note(1000, 0.05));
+        assert!((1000..1010).contains(&loud), "loud onset {loud}");
+        assert_eq!(quiet, loud);
+        assert_eq!(detect_onset(&[0.0; 512]), 0);
+        assert_eq!(detect_onset(&[]), 0);
+    }
+
+    #[test]
+    fn takes_line_up_on_their_first_notes() {
+        let player = TakePlayer::new(
+            vec![
+                take("a", note(1500, 0.5)),
+                take("b", note(300, 0.5)),
+                take("c", note(900, 0.2)),
+            ],
+            false,
+        );
+        assert_eq!(player.offsets(), [1200, 0, 600]);
+        assert_eq!(player.length(), LEN);
+    }
+
+    #[test]
+    fn switching_keeps_the_playhead() {
+        let mut player = TakePlayer::new(
+            vec![take("a", note(2000, 0.5)), take("b", note(1000, 0.5))],
+            false,
+        );
+        player.apply(TakeCommand::Play);
+        play(&mut player, 1500);
+        assert_eq!(player.playhead(), 1500);
+
+        player.apply(TakeCommand::Select(1));
+        assert_eq!(player.active(), 1);
+        assert_eq!(player.playhead(), 1500);
+
+        // Once the crossfade is over, it's take b at the same moment take a
+        // would have been: both notes line up.
+        let out = play(&mut player, 1000);
+        let expected = note(1000, 0.5);
+        for (i, sample) in out.iter().enumerate().skip(SWITCH_FADE_FRAMES) {
+            let frame = 1500 + i;
+            assert!((sample - expected[frame]).abs() < 1e-6, "frame {frame}");
+        }
+        assert_eq!(player.playhead(), 2500);
+        assert_eq!(player.transport().frame(), 2500);
+    }
+
+    #[test]
+    fn switching_crossfades_instead_of_jumping() {
+        let loud: Vec<f32> = vec![0.5; LEN];
+        let mut player = TakePlayer::new(vec![take("a", loud), take("b", vec![0.0; LEN])], false);
+        player.apply(TakeCommand::Play);
+        play(&mut player, 100);
+        player.apply(TakeCommand::Select(1));
+        let out = play(&mut player, SWITCH_FADE_FRAMES + 10);
+
+        assert!(out.windows(2).all(|w| (w[0] - w[1]).abs() < 0.01));
+        assert_eq!(out[SWITCH_FADE_FRAMES], 0.0);
+    }
+
+    #[test]
+    fn selecting_a_missing_take_does_nothing() {
+        let mut player = TakePlayer::new(vec![take("a", note(0, 0.5))], false);
+        player.apply(TakeCommand::Select(3));
+        assert_eq!(player.active(), 0);
+    }
+
+    #[test]
+    fn paused_player_leaves_the_output_alone() {
+        let mut player = TakePlayer::new(vec![take("a", note(0, 0.5))], false);
+        let mut left = vec![0.25; 64];
+        let mut right = vec![0.25; 64];
+        assert!(!player.render(&mut left, &mut right));
+        assert!(left.iter().chain(&right).all(|&s| s == 0.25));
+    }
+
+    #[test]
+    fn playing_past_the_end_stops_and_rewinds() {
+        let mut player = TakePlayer::new(vec![take("a", note(0, 0.5))], false);
+        player.apply(TakeCommand::Play);
+        let out = play(&mut player, LEN + 100);
+
+        assert!(out[LEN..].iter().all(|&s| s == 0.0));
+        assert!(!player.is_playing());
+        assert_eq!(player.playhead(), 0);
+        assert!(!player.transport().is_playing());
+    }
+
+    #[test]
+    fn normalising_evens_out_the_peaks() {
+        let mut player =
+            TakePlayer::new(vec![take("a", note(0, 0.8)), take("b", note(0, 0.1))], true);
+        let target = db_to_lin(NORMALIZED_PEAK_DB);
+        for index in 0..2 {
+            player.apply(TakeCommand::Rewind);
+            player.apply(TakeCommand::Select(index));
+            player.apply(TakeCommand::Play);
+            let out = play(&mut player, LEN);
+            let peak = out.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+            assert!((peak - target).abs() < 0.01, "take {index} peaks at {peak}");
+        }
+    }
+
+    #[test]
+    fn takes_group_by_name_newest_first() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let listing = vec![
+            (PathBuf::from("recording_20260101_120000.wav"), at(10)),
+            (PathBuf::from("solo 1.wav"), at(20)),
+            (PathBuf::from("recording_20260101_120500.wav"), at(30)),
+            (
+                PathBuf::from("recording_20260101_121000_frame96000.wav"),
+                at(40),
+            ),
+            (PathBuf::from("recording_20260101_121500.wav"), at(50)),
+            (PathBuf::from("recording_20260101_122000.wav"), at(60)),
+        ];
+
+        assert_eq!(
+            pick_takes(listing),
+            [
+                PathBuf::from("recording_20260101_120500.wav"),
+                PathBuf::from("recording_20260101_121000_frame96000.wav"),
+                PathBuf::from("recording_20260101_121500.wav"),
+                PathBuf::from("recording_20260101_122000.wav"),
+            ]
+        );
+        assert_eq!(take_prefix(Path::new("solo 2.wav")), "solo");
+        assert!(pick_takes(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::amp::stages::common::db_to_lin;
+use crate::audio::offline::read_mono_wav_at;
+
+/// Level a test source starts at, so switching one on is never full scale.
+pub const DEFAULT_TEST_LEVEL_DB: f32 = -20.0;
+pub const MIN_TEST_LEVEL_DB: f32 = -60.0;
+pub const MAX_TEST_LEVEL_DB: f32 = 0.0;
+
+/// Open A string, a useful default for profiling a guitar chain.
+pub const DEFAULT_TEST_FREQUENCY_HZ: f32 = 110.0;
+pub const MIN_TEST_FREQUENCY_HZ: f32 = 20.0;
+pub const MAX_TEST_FREQUENCY_HZ: f32 = 2000.0;
+
+const WAVETABLE_SIZE: usize = 2048;
+
+/// Brings the pink filter's output, fed uniform white noise, to unit RMS.
+const NOISE_GAIN: f32 = 0.56;
+/// Pink noise plays with its RMS this far below the level, leaving its peaks
+/// near it.
+const NOISE_CREST_DB: f32 = 12.0;
+
+/// What the engine plays in place of its input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TestSourceKind {
+    /// The real input.
+    #[default]
+    Off,
+    Sine,
+    PinkNoise,
+    /// A DI recording, looped.
+    DiSample,
+}
+
+impl TestSourceKind {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Sine, Self::PinkNoise, Self::DiSample];
+}
+
+impl std::fmt::Display for TestSourceKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "Off"),
+            Self::Sine => write!(f, "Sine"),
+            Self::PinkNoise => write!(f, "Pink Noise"),
+            Self::DiSample => write!(f, "DI Sample"),
+        }
+    }
+}
+
+/// Which test signal to generate and how loud.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TestSourceConfig {
+    pub kind: TestSourceKind,
+    /// Sine frequency; ignored by the other kinds.
+    pub frequency_hz: f32,
+    /// Peak level in dBFS for the sine and the DI sample. Pink noise has its
+    /// RMS 12 dB below it.
+    pub level_db: f32,
+}
+
+impl Default for TestSourceConfig {
+    fn default() -> Self {
+        Self {
+            kind: TestSourceKind::Off,
+            frequency_hz: DEFAULT_TEST_FREQUENCY_HZ,
+            level_db: DEFAULT_TEST_LEVEL_DB,
+        }
+    }
+}
+
+/// Pink noise from Paul Kellet's refined filter over xorshift white noise.
+#[derive(Debug, Clone)]
+struct PinkNoise {
+    state: u32,
+    b: [f32; 7],
+}
+
+impl PinkNoise {
+    const fn new() -> Self {
+        Self {
+            state: 0x9E37_79B9,
+            b: [0.0; 7],
+        }
+    }
+
+    fn white(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state as f32 / u32::MAX as f32).mul_add(2.0, -1.0)
+    }
+
+    fn next(&mut self) -> f32 {
+        let w = self.white();
+        let b = &mut self.b;
+        b[0] = 0.998_86f32.mul_add(b[0], w * 0.055_517_9);
+        b[1] = 0.993_32f32.mul_add(b[1], w * 0.075_075_9);
+        b[2] = 0.969f32.mul_add(b[2], w * 0.153_852);
+        b[3] = 0.8665f32.mul_add(b[3], w * 0.310_485_6);
+        b[4] = 0.55f32.mul_add(b[4], w * 0.532_952_2);
+        b[5] = (-0.7616f32).mul_add(b[5], -w * 0.016_898);
+        let pink = b[..6].iter().sum::<f32>() + w.mul_add(0.5362, b[6]);
+        b[6] = w * 0.115_926;
+        pink * NOISE_GAIN
+    }
+}
+
+/// Signal generator standing in for the engine's input. Everything it needs
+/// is allocated up front, so rendering a block never allocates.
+pub struct TestSource {
+    kind: TestSourceKind,
+    gain: f32,
+    wavetable: Vec<f32>,
+    phase: f32,
+    /// Wavetable positions advanced per sample.
+    phase_step: f32,
+    noise: PinkNoise,
+    /// Shared so changing the level or kind doesn't decode the file again.
+    di: Option<Arc<[f32]>>,
+    di_pos: usize,
+}
+
+impl TestSource {
+    /// `di` is the looped sample for [`TestSourceKind::DiSample`], already at
+    /// `sample_rate` and normalised to a peak of 1.0 (see [`load_di_sample`]).
+    pub fn new(config: TestSourceConfig, sample_rate: usize, di: Option<Arc<[f32]>>) -> Self {
+        let wavetable = (0..WAVETABLE_SIZE)
+            .map(|i| (std::f32::consts::TAU * i as f32 / WAVETABLE_SIZE as f32).sin())
+            .collect();
+        let frequency = config
+            .frequency_hz
+            .clamp(MIN_TEST_FREQUENCY_HZ, MAX_TEST_FREQUENCY_HZ);
+        let level_db = config.level_db.clamp(MIN_TEST_LEVEL_DB, MAX_TEST_LEVEL_DB);
+        let level_db = if config.kind == TestSourceKind::PinkNoise {
+            level_db - NOISE_CREST_DB
+        } else {
+            level_db
+        };
+
+        Self {
+            kind: config.kind,
+            gain: db_to_lin(level_db),
+            wavetable,
+            phase: 0.0,
+            phase_step: frequency * WAVETABLE_SIZE as f32 / sample_rate as f32,
+            noise: PinkNoise::new(),
+            di: di.filter(|samples| !samples.is_empty()),
+            di_pos: 0,
+        }
+    }
+
+    /// Fill `out` with the next block. Returns `false`, leaving `out` alone,
+    /// when there's nothing to play: the source is off, or it's the DI sample
+    /// and none is loaded.
+    pub fn render(&mut self, out: &mut [f32]) -> bool {
+        match self.kind {
+            TestSourceKind::Off => return false,
+            TestSourceKind::Sine => self.render_sine(out),
+            TestSourceKind::PinkNoise => {
+                for s in out.iter_mut() {
+                    *s = self.noise.next() * self.gain;
+                }
+            }
+            TestSourceKind::DiSample => {
+                let Some(di) = &self.di else {
+                    return false;
+                };
+                for s in out.iter_mut() {
+                    *s = di[self.di_pos] * self.gain;
+                    self.di_pos = (self.di_pos + 1) % di.len();
+                }
+            }
+        }
+        true
+    }
+
+    fn render_sine(&mut self, out: &mut [f32]) {
+        let size = WAVETABLE_SIZE as f32;
+        for s in out.iter_mut() {
+            let index = self.phase as usize;
+            let frac = self.phase - index as f32;
+            let a = self.wavetable[index];
+            let b = self.wavetable[(index + 1) % WAVETABLE_SIZE];
+            *s = frac.mul_add(b - a, a) * self.gain;
+
+            self.phase += self.phase_step;
+            if self.phase >= size {
+                self.phase -= size;
+            }
+        }
+    }
+}
+
+/// Read a DI recording for the test source: mixed to mono, resampled to
+/// `sample_rate` and normalised to a peak of 1.0 so the level applies as set.
+pub fn load_di_sample(path: &Path, sample_rate: usize) -> Result<Arc<[f32]>> {
+    let mut samples = read_mono_wav_at(path, sample_rate)?;
+
+    let peak = samples.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        for s in &mut samples {
+            *s /= peak;
+        }
+    }
+    Ok(samples.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn config(kind: TestSourceKind) -> TestSourceConfig {
+        TestSourceConfig {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    fn peak(buf: &[f32]) -> f32 {
+        buf.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()))
+    }
+
+    fn rms(buf: &[f32]) -> f32 {
+        (buf.iter().map(|s| s * s).sum::<f32>() / buf.len() as f32).sqrt()
+    }
+
+    fn to_db(lin: f32) -> f32 {
+        20.0 * lin.log10()
+    }
+
+    #[test]
+    fn defaults_to_off_at_minus_20() {
+        let config = TestSourceConfig::default();
+        assert_eq!(config.kind, TestSourceKind::Off);
+        assert_eq!(config.level_db, -20.0);
+
+        let mut source = TestSource::new(config, SAMPLE_RATE, None);
+        let mut buf = [0.5; 64];
+        assert!(!source.render(&mut buf));
+        assert_eq!(buf, [0.5; 64]);
+    }
+
+    #[test]
+    fn sine_has_the_set_level_and_frequency() {
+        let mut source = TestSource::new(config(TestSourceKind::Sine), SAMPLE_RATE, None);
+        let mut buf = vec![0.0; SAMPLE_RATE];
+        assert!(source.render(&mut buf));
+
+        assert!((to_db(peak(&buf)) - DEFAULT_TEST_LEVEL_DB).abs() < 0.05);
+        let crossings = buf.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        assert!(crossings.abs_diff(110) <= 1, "{crossings} cycles");
+    }
+
+    #[test]
+    fn sine_is_continuous_across_blocks() {
+        let mut source = TestSource::new(config(TestSourceKind::Sine), SAMPLE_RATE, None);
+        let mut whole = vec![0.0; 1024];
+        source.render(&mut whole);
+
+        let mut source = TestSource::new(config(TestSourceKind::Sine), SAMPLE_RATE, None);
+        let mut blocks = vec![0.0; 1024];
+        for block in blocks.chunks_mut(100) {
+            source.render(block);
+        }
+        assert_eq!(whole, blocks);
+    }
+
+    #[test]
+    fn pink_noise_sits_below_the_level_and_tilts_down() {
+        let mut source = TestSource::new(config(TestSourceKind::PinkNoise), SAMPLE_RATE, None);
+        let mut buf = vec![0.0; SAMPLE_RATE * 2];
+        source.render(&mut buf);
+
+        let rms_db = to_db(rms(&buf));
+        let target = DEFAULT_TEST_LEVEL_DB - NOISE_CREST_DB;
+        assert!((rms_db - target).abs() < 1.0, "RMS {rms_db} dB");
+        assert!(to_db(peak(&buf)) < 0.0);
+
+        // Pink noise has much less energy in its sample-to-sample differences
+        // than white noise at the same RMS, which would be about 3 dB above.
+        let diff: Vec<f32> = buf.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(to_db(rms(&diff)) < rms_db - 3.0);
+    }
+
+    #[test]
+    fn di_sample_loops_at_the_level() {
+        let di: Arc<[f32]> = vec![1.0, -0.5, 0.25].into();
+        let mut source = TestSource::new(config(TestSourceKind::DiSample), SAMPLE_RATE, Some(di));
+        let mut buf = [0.0; 7];
+        assert!(source.render(&mut buf));
+
+        let g = db_to_lin(DEFAULT_TEST_LEVEL_DB);
+        let expected = [1.0, -0.5, 0.25, 1.0, -0.5, 0.25, 1.0].map(|s| s * g);
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn di_sample_without_a_file_plays_nothing() {
+        let mut source = TestSource::new(config(TestSourceKind::DiSample), SAMPLE_RATE, None);
+        let mut buf = [0.5; 8];
+        assert!(!source.render(&mut buf));
+        assert_eq!(buf, [0.5; 8]);
+    }
+
+    #[test]
+    fn level_is_limited_to_full_scale() {
+        let loud = TestSourceConfig {
+            kind: TestSourceKind::Sine,
+            level_db: 12.0,
+            ..Default::default()
+        };
+        let mut source = TestSource::new(loud, SAMPLE_RATE, None);
+        let mut buf = vec![0.0; 4800];
+        source.render(&mut buf);
+        assert!(peak(&buf) <= 1.0);
+    }
+}
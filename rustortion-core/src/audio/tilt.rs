@@ -0,0 +1,332 @@
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// Frequency the tilt pivots around. It's left where it is; the lows go one
+/// way and the highs the other.
+pub const TILT_PIVOT_HZ: f64 = 650.0;
+/// Largest tilt either way: the highs rise by this much and the lows fall by
+/// as much, or the other way round.
+pub const MAX_TILT_DB: f32 = 6.0;
+
+/// Time a new setting takes to glide in, so dragging the slider doesn't
+/// zipper.
+const GLIDE_SECONDS: f32 = 0.03;
+/// Samples between coefficient updates while gliding.
+const UPDATE_INTERVAL: usize = 32;
+
+/// Normalised biquad coefficients.
+#[derive(Debug, Clone, Copy)]
+struct Coefficients {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+}
+
+impl Coefficients {
+    /// Audio EQ Cookbook low shelf at the pivot, with a shelf slope of 1.
+    fn low_shelf(gain_db: f64, sample_rate: f64) -> Self {
+        let (a, cos_w0, two_sqrt_a_alpha) = shelf_terms(gain_db, sample_rate);
+        Self::normalised(
+            a * ((a - 1.0).mul_add(-cos_w0, a + 1.0) + two_sqrt_a_alpha),
+            2.0 * a * (a + 1.0).mul_add(-cos_w0, a - 1.0),
+            a * ((a - 1.0).mul_add(-cos_w0, a + 1.0) - two_sqrt_a_alpha),
+            (a - 1.0).mul_add(cos_w0, a + 1.0) + two_sqrt_a_alpha,
+            -2.0 * (a + 1.0).mul_add(cos_w0, a - 1.0),
+            (a - 1.0).mul_add(cos_w0, a + 1.0) - two_sqrt_a_alpha,
+        )
+    }
+
+    /// Audio EQ Cookbook high shelf at the pivot, with a shelf slope of 1.
+    fn high_shelf(gain_db: f64, sample_rate: f64) -> Self {
+        let (a, cos_w0, two_sqrt_a_alpha) = shelf_terms(gain_db, sample_rate);
+        Self::normalised(
+            a * ((a - 1.0).mul_add(cos_w0, a + 1.0) + two_sqrt_a_alpha),
+            -2.0 * a * (a + 1.0).mul_add(cos_w0, a - 1.0),
+            a * ((a - 1.0).mul_add(cos_w0, a + 1.0) - two_sqrt_a_alpha),
+            (a - 1.0).mul_add(-cos_w0, a + 1.0) + two_sqrt_a_alpha,
+            2.0 * (a + 1.0).mul_add(-cos_w0, a - 1.0),
+            (a - 1.0).mul_add(-cos_w0, a + 1.0) - two_sqrt_a_alpha,
+        )
+    }
+
+    fn normalised(b0: f64, b1: f64, b2: f64, a0: f64, a1: f64, a2: f64) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    /// Magnitude response at `freq`, in dB.
+    fn magnitude_db(&self, freq: f64, sample_rate: f64) -> f64 {
+        let w = 2.0 * PI * freq / sample_rate;
+        let (sin1, cos1) = w.sin_cos();
+        let (sin2, cos2) = (2.0 * w).sin_cos();
+        let num_re = self.b2.mul_add(cos2, self.b1.mul_add(cos1, self.b0));
+        let num_im = -self.b2.mul_add(sin2, self.b1 * sin1);
+        let den_re = self.a2.mul_add(cos2, self.a1.mul_add(cos1, 1.0));
+        let den_im = -self.a2.mul_add(sin2, self.a1 * sin1);
+        let num = num_re.mul_add(num_re, num_im * num_im);
+        let den = den_re.mul_add(den_re, den_im * den_im);
+        10.0 * (num / den).log10()
+    }
+}
+
+/// `A`, `cos(w0)` and `2 * sqrt(A) * alpha` for a shelf at the pivot.
+fn shelf_terms(gain_db: f64, sample_rate: f64) -> (f64, f64, f64) {
+    let a = 10f64.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * TILT_PIVOT_HZ.min(sample_rate * 0.499) / sample_rate;
+    // A shelf slope of 1 makes alpha sin(w0) / sqrt(2) whatever the gain.
+    let alpha = w0.sin() * FRAC_1_SQRT_2;
+    (a, w0.cos(), 2.0 * a.sqrt() * alpha)
+}
+
+/// Direct Form 1 state for one biquad on one signal.
+#[derive(Debug, Clone, Copy, Default)]
+struct State {
+    x1: f64,
+    x2: f64,
+    y1: f64,
+    y2: f64,
+}
+
+impl State {
+    #[inline]
+    fn process(&mut self, c: &Coefficients, x: f64) -> f64 {
+        let y = c.b2.mul_add(
+            self.x2,
+            c.b1.mul_add(self.x1, c.b0 * x) - c.a1.mul_add(self.y1, c.a2 * self.y2),
+        );
+        self.x2 = self.x1;
+        self.x1 = x;
+        self.y2 = self.y1;
+        self.y1 = y;
+        y
+    }
+}
+
+/// Quick brightness correction for the room or headphones, run on the engine
+/// output after the IR: complementary low and high shelves at
+/// [`TILT_PIVOT_HZ`], so a positive tilt lifts the highs and cuts the lows by
+/// the same amount.
+pub struct Tilt {
+    sample_rate: f64,
+    target_db: f32,
+    /// Where the glide has got to; the coefficients are for this.
+    current_db: f32,
+    /// Change per coefficient update while gliding.
+    step_db: f32,
+    low: Coefficients,
+    high: Coefficients,
+    /// Low and high shelf state for the mid (or left) and side (or right)
+    /// signals.
+    state: [[State; 2]; 2],
+    /// Samples left until the next coefficient update.
+    countdown: usize,
+}
+
+impl Tilt {
+    pub fn new(tilt_db: f32, sample_rate: usize) -> Self {
+        let mut tilt = Self {
+            sample_rate: sample_rate as f64,
+            target_db: 0.0,
+            current_db: 0.0,
+            step_db: 0.0,
+            low: Coefficients::low_shelf(0.0, sample_rate as f64),
+            high: Coefficients::high_shelf(0.0, sample_rate as f64),
+            state: [[State::default(); 2]; 2],
+            countdown: 0,
+        };
+        tilt.target_db = tilt_db.clamp(-MAX_TILT_DB, MAX_TILT_DB);
+        tilt.jump_to_target();
+        tilt
+    }
+
+    /// Glide to `tilt_db`, clamped to [`MAX_TILT_DB`] either way.
+    pub fn set_tilt_db(&mut self, tilt_db: f32) {
+        self.target_db = tilt_db.clamp(-MAX_TILT_DB, MAX_TILT_DB);
+        let updates = (GLIDE_SECONDS * self.sample_rate as f32 / UPDATE_INTERVAL as f32).max(1.0);
+        self.step_db = (self.target_db - self.current_db).abs() / updates;
+    }
+
+    /// The setting being glided to.
+    pub const fn tilt_db(&self) -> f32 {
+        self.target_db
+    }
+
+    /// Retune for a new sample rate. Skips any glide in progress.
+    pub fn set_sample_rate(&mut self, sample_rate: usize) {
+        self.sample_rate = sample_rate as f64;
+        self.jump_to_target();
+    }
+
+    pub fn reset(&mut self) {
+        self.state = [[State::default(); 2]; 2];
+    }
+
+    /// Magnitude response at `freq` of a tilt of `tilt_db`, in dB.
+    pub fn response_db(tilt_db: f32, freq: f64, sample_rate: f64) -> f64 {
+        let tilt_db = f64::from(tilt_db.clamp(-MAX_TILT_DB, MAX_TILT_DB));
+        Coefficients::low_shelf(-tilt_db, sample_rate).magnitude_db(freq, sample_rate)
+            + Coefficients::high_shelf(tilt_db, sample_rate).magnitude_db(freq, sample_rate)
+    }
+
+    /// Filter `mid`, and `side` if there is one, in place.
+    pub fn process_block(&mut self, mid: &mut [f32], mut side: Option<&mut [f32]>) {
+        if self.current_db == 0.0 && self.target_db == 0.0 {
+            return;
+        }
+
+        let mut start = 0;
+        while start < mid.len() {
+            if self.countdown == 0 {
+                self.glide();
+                self.countdown = UPDATE_INTERVAL;
+            }
+            let end = (start + self.countdown).min(mid.len());
+            let (low, high) = (self.low, self.high);
+            let [mid_state, side_state] = &mut self.state;
+            filter(mid_state, &low, &high, &mut mid[start..end]);
+            if let Some(side) = side.as_deref_mut() {
+                let end = end.min(side.len());
+                if start < end {
+                    filter(side_state, &low, &high, &mut side[start..end]);
+                }
+            }
+            self.countdown -= end - start;
+            start = end;
+        }
+    }
+
+    /// Take one step towards the target, if not there yet.
+    fn glide(&mut self) {
+        if self.current_db == self.target_db {
+            return;
+        }
+        let remaining = self.target_db - self.current_db;
+        if remaining.abs() <= self.step_db {
+            self.current_db = self.target_db;
+        } else {
+            self.current_db += self.step_db.copysign(remaining);
+        }
+        self.update_coefficients();
+        if self.current_db == 0.0 && self.target_db == 0.0 {
+            // Back to flat and about to be skipped; start clean next time.
+            self.reset();
+        }
+    }
+
+    fn jump_to_target(&mut self) {
+        self.current_db = self.target_db;
+        self.step_db = 0.0;
+        self.update_coefficients();
+        self.reset();
+    }
+
+    fn update_coefficients(&mut self) {
+        let tilt_db = f64::from(self.current_db);
+        self.low = Coefficients::low_shelf(-tilt_db, self.sample_rate);
+        self.high = Coefficients::high_shelf(tilt_db, self.sample_rate);
+    }
+}
+
+fn filter(state: &mut [State; 2], low: &Coefficients, high: &Coefficients, samples: &mut [f32]) {
+    let [low_state, high_state] = state;
+    for sample in samples {
+        let shelved = low_state.process(low, f64::from(*sample));
+        *sample = high_state.process(high, shelved) as f32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: usize = 48000;
+
+    fn sine(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| 0.5 * (std::f32::consts::TAU * freq * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    fn rms(buf: &[f32]) -> f32 {
+        (buf.iter().map(|s| s * s).sum::<f32>() / buf.len() as f32).sqrt()
+    }
+
+    /// Level change in dB of a sine at `freq` through `tilts` in turn, once
+    /// settled.
+    fn gain_db(tilts: &mut [Tilt], freq: f32) -> f32 {
+        let input = sine(freq, SAMPLE_RATE);
+        let mut output = input.clone();
+        for block in output.chunks_mut(128) {
+            for tilt in tilts.iter_mut() {
+                tilt.process_block(block, None);
+            }
+        }
+        let settled = SAMPLE_RATE / 2;
+        20.0 * (rms(&output[settled..]) / rms(&input[settled..])).log10()
+    }
+
+    #[test]
+    fn tilt_pivots_around_650_hz() {
+        let sr = SAMPLE_RATE as f64;
+        assert!(Tilt::response_db(6.0, TILT_PIVOT_HZ, sr).abs() < 0.01);
+        assert!((Tilt::response_db(6.0, 20.0, sr) + 6.0).abs() < 0.1);
+        assert!((Tilt::response_db(6.0, 15_000.0, sr) - 6.0).abs() < 0.1);
+        assert!((Tilt::response_db(-6.0, 20.0, sr) - 6.0).abs() < 0.1);
+        // Clamped to the range.
+        assert!((Tilt::response_db(12.0, 15_000.0, sr) - 6.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn opposite_tilts_cancel() {
+        let sr = SAMPLE_RATE as f64;
+        for freq in [30.0, 100.0, 650.0, 2_000.0, 8_000.0, 18_000.0] {
+            let sum = Tilt::response_db(3.0, freq, sr) + Tilt::response_db(-3.0, freq, sr);
+            assert!(sum.abs() < 0.1, "{freq} Hz off by {sum} dB");
+        }
+
+        let mut tilts = [Tilt::new(3.0, SAMPLE_RATE), Tilt::new(-3.0, SAMPLE_RATE)];
+        for freq in [100.0, 650.0, 4_000.0] {
+            let gain = gain_db(&mut tilts, freq);
+            assert!(gain.abs() < 0.1, "{freq} Hz off by {gain} dB");
+        }
+    }
+
+    #[test]
+    fn flat_leaves_the_signal_alone() {
+        let input = sine(1_000.0, 512);
+        let mut output = input.clone();
+        Tilt::new(0.0, SAMPLE_RATE).process_block(&mut output, None);
+        assert_eq!(input, output);
+    }
+
+    #[test]
+    fn changes_glide_in() {
+        let mut tilt = Tilt::new(0.0, SAMPLE_RATE);
+        tilt.set_tilt_db(6.0);
+        assert_eq!(tilt.tilt_db(), 6.0);
+
+        let mut block = vec![0.0; UPDATE_INTERVAL];
+        tilt.process_block(&mut block, None);
+        assert!(tilt.current_db > 0.0 && tilt.current_db < 1.0);
+
+        let glide = (GLIDE_SECONDS * SAMPLE_RATE as f32) as usize;
+        let mut rest = vec![0.0; glide];
+        tilt.process_block(&mut rest, None);
+        assert_eq!(tilt.current_db, 6.0);
+    }
+
+    #[test]
+    fn side_gets_the_same_filter() {
+        let mut tilt = Tilt::new(4.0, SAMPLE_RATE);
+        let mut mid = sine(200.0, 1024);
+        let mut side = mid.clone();
+        tilt.process_block(&mut mid, Some(&mut side));
+        assert_eq!(mid, side);
+    }
+}
@@ -0,0 +1,451 @@
+//! Tone matching: how the chain's output differs in spectrum from a
+//! reference, and a graphic EQ setting that closes the gap.
+//!
+//! Both sides are reduced to a long-term average spectrum, averaged into
+//! third-octave bands. Their difference, with the overall level taken out,
+//! is the correction; an [`EqConfig`] is then fitted to it.
+
+use std::f32::consts::TAU;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use log::info;
+use realfft::RealFftPlanner;
+
+use crate::amp::chain::DEFAULT_CHAIN_CAPACITY;
+use crate::amp::stages::eq::{self, BAND_FREQS, EqConfig, MAX_GAIN_DB, MIN_GAIN_DB, NUM_BANDS};
+use crate::audio::offline::{preset_convolver, read_mono_wav, render_samples};
+use crate::audio::test_source::{TestSource, TestSourceConfig, TestSourceKind};
+use crate::ir::loader::{IrLoader, resample};
+use crate::preset::{Preset, StageConfig};
+
+/// Length of the pink noise fed through the chain.
+pub const PINK_NOISE_SECONDS: usize = 10;
+
+/// Largest boost or cut the correction asks for, the most the EQ can do.
+pub const MAX_CORRECTION_DB: f32 = MAX_GAIN_DB;
+
+const FFT_SIZE: usize = 16384;
+
+/// A band this far below the loudest band of either spectrum is left flat:
+/// boosting what the reference or the chain barely has only raises noise.
+const FLOOR_DB: f64 = 60.0;
+
+/// Refinements of the EQ gains against the response their overlapping
+/// bands give together, and how much of the remaining error each one takes.
+const FIT_PASSES: usize = 12;
+const FIT_STEP: f64 = 0.5;
+
+/// What's fed through the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MatchSource {
+    /// [`PINK_NOISE_SECONDS`] of pink noise at the test source's level.
+    PinkNoise,
+    /// A DI recording, resampled to the engine rate if need be.
+    Wav(PathBuf),
+}
+
+/// What the chain's output is matched to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchReference {
+    /// Another preset, fed the same source.
+    Preset(Box<Preset>),
+    /// A recording. Only its spectrum counts, not its level or length.
+    Wav(PathBuf),
+}
+
+/// The correction in one third-octave band.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CorrectionBand {
+    /// Centre frequency in Hz.
+    pub frequency: f32,
+    pub gain_db: f32,
+}
+
+/// The result of a match: the correction curve and an EQ that follows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToneMatch {
+    /// Third-octave bands below both spectra's Nyquist frequency, limited to
+    /// ±[`MAX_CORRECTION_DB`].
+    pub bands: Vec<CorrectionBand>,
+    /// Gains for the graphic EQ whose combined response follows `bands`.
+    pub eq_gains: [f32; NUM_BANDS],
+}
+
+impl ToneMatch {
+    /// Largest boost or cut anywhere on the curve.
+    pub fn max_deviation_db(&self) -> f32 {
+        self.bands
+            .iter()
+            .map(|band| band.gain_db.abs())
+            .fold(0.0, f32::max)
+    }
+
+    /// The correction as an EQ stage.
+    pub fn to_stage(&self) -> StageConfig {
+        StageConfig::Eq(EqConfig {
+            gains: self.eq_gains,
+            ..EqConfig::default()
+        })
+    }
+
+    /// `stages` with the correction appended, or `None` if the chain is full.
+    pub fn append_to(&self, stages: &[StageConfig]) -> Option<Vec<StageConfig>> {
+        if stages.len() >= DEFAULT_CHAIN_CAPACITY {
+            return None;
+        }
+        let mut appended = stages.to_vec();
+        appended.push(self.to_stage());
+        Some(appended)
+    }
+}
+
+/// Match `preset` to `reference`: render `source` through it offline, and
+/// through the reference preset too if that's what it is, then compare their
+/// spectra. `progress` is called with the completed fraction (0.0..=1.0).
+pub fn match_tone(
+    preset: &Preset,
+    source: &MatchSource,
+    reference: &MatchReference,
+    sample_rate: usize,
+    oversampling_factor: u32,
+    ir_loader: Option<&IrLoader>,
+    mut progress: impl FnMut(f32),
+) -> Result<ToneMatch> {
+    let input = match source {
+        MatchSource::PinkNoise => pink_noise(PINK_NOISE_SECONDS * sample_rate, sample_rate),
+        MatchSource::Wav(path) => read_source(path, sample_rate)?,
+    };
+    if input.is_empty() {
+        bail!("The source has no audio to analyse");
+    }
+
+    // Rendering the reference preset takes as long as the chain does.
+    let share = if matches!(reference, MatchReference::Preset(_)) {
+        0.5
+    } else {
+        1.0
+    };
+    let render = |preset: &Preset, progress: &mut dyn FnMut(f32)| {
+        render_samples(
+            preset,
+            &input,
+            sample_rate,
+            oversampling_factor,
+            preset_convolver(preset, sample_rate, ir_loader),
+            progress,
+        )
+    };
+
+    let output = render(preset, &mut |p| progress(p * share))?;
+    let (reference, reference_rate) = match reference {
+        MatchReference::Preset(other) => (
+            render(other, &mut |p| progress(p.mul_add(share, share)))?,
+            sample_rate,
+        ),
+        MatchReference::Wav(path) => {
+            let (samples, rate) = read_mono_wav(path)?;
+            if samples.is_empty() {
+                bail!("{} has no audio to match to", path.display());
+            }
+            (samples, rate as usize)
+        }
+    };
+    progress(1.0);
+
+    let eq_rate = (sample_rate * oversampling_factor as usize) as f64;
+    let matched = match_spectra(&output, sample_rate, &reference, reference_rate, eq_rate);
+    info!(
+        "Tone match for '{}': up to {:.1} dB of correction",
+        preset.name,
+        matched.max_deviation_db()
+    );
+    Ok(matched)
+}
+
+/// The correction that makes `output`'s spectrum follow `reference`'s, and
+/// the EQ gains for it at `eq_rate` (the chain's rate, oversampling
+/// included).
+pub fn match_spectra(
+    output: &[f32],
+    output_rate: usize,
+    reference: &[f32],
+    reference_rate: usize,
+    eq_rate: f64,
+) -> ToneMatch {
+    let output = band_levels(&long_term_spectrum(output), output_rate);
+    let reference = band_levels(&long_term_spectrum(reference), reference_rate);
+    let output_peak = loudest(&output);
+    let reference_peak = loudest(&reference);
+
+    // Difference per band both spectra cover, `None` where either is too
+    // quiet to go by.
+    let differences: Vec<(f64, Option<f64>)> = third_octave_centres()
+        .zip(output.iter().zip(&reference))
+        .filter_map(|(centre, (&out, &refr))| {
+            let (out, refr) = (out?, refr?);
+            let audible = out > output_peak - FLOOR_DB && refr > reference_peak - FLOOR_DB;
+            Some((centre, audible.then_some(refr - out)))
+        })
+        .collect();
+
+    // The level difference between the two isn't tone; take it out.
+    let audible: Vec<f64> = differences.iter().filter_map(|&(_, d)| d).collect();
+    let offset = if audible.is_empty() {
+        0.0
+    } else {
+        audible.iter().sum::<f64>() / audible.len() as f64
+    };
+
+    let bands: Vec<CorrectionBand> = differences
+        .into_iter()
+        .map(|(centre, difference)| CorrectionBand {
+            frequency: centre as f32,
+            gain_db: difference.map_or(0.0, |d| {
+                ((d - offset) as f32).clamp(-MAX_CORRECTION_DB, MAX_CORRECTION_DB)
+            }),
+        })
+        .collect();
+    let eq_gains = fit_eq(&bands, eq_rate);
+
+    ToneMatch { bands, eq_gains }
+}
+
+/// Pink noise at the test source's default level, the same every time.
+fn pink_noise(frames: usize, sample_rate: usize) -> Vec<f32> {
+    let config = TestSourceConfig {
+        kind: TestSourceKind::PinkNoise,
+        ..TestSourceConfig::default()
+    };
+    let mut source = TestSource::new(config, sample_rate, None);
+    let mut noise = vec![0.0; frames];
+    source.render(&mut noise);
+    noise
+}
+
+/// A DI recording as the engine would be fed it: mono, at `sample_rate`, at
+/// the level it was recorded.
+fn read_source(path: &Path, sample_rate: usize) -> Result<Vec<f32>> {
+    let (samples, file_rate) = read_mono_wav(path)?;
+    if file_rate as usize == sample_rate {
+        return Ok(samples);
+    }
+    resample(&samples, file_rate, sample_rate as u32)
+        .with_context(|| format!("Failed to resample {}", path.display()))
+}
+
+/// Centre frequencies of the third-octave bands from 25 Hz to 20 kHz.
+fn third_octave_centres() -> impl Iterator<Item = f64> {
+    (-16..=13).map(|n: i32| 1000.0 * 2f64.powf(f64::from(n) / 3.0))
+}
+
+/// Mean power per FFT bin over Hann-windowed frames overlapping by half.
+/// A signal shorter than a frame is zero-padded to one.
+fn long_term_spectrum(samples: &[f32]) -> Vec<f64> {
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(FFT_SIZE);
+    let window: Vec<f32> = (0..FFT_SIZE)
+        .map(|i| (-0.5f32).mul_add((TAU * i as f32 / FFT_SIZE as f32).cos(), 0.5))
+        .collect();
+    let mut frame = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let mut power = vec![0.0f64; spectrum.len()];
+
+    let hop = FFT_SIZE / 2;
+    let starts = (0..)
+        .map(|k| k * hop)
+        .take_while(|&start| start == 0 || start + FFT_SIZE <= samples.len());
+    let mut frames = 0;
+    for start in starts {
+        let chunk = &samples[start.min(samples.len())..(start + FFT_SIZE).min(samples.len())];
+        for (i, x) in frame.iter_mut().enumerate() {
+            *x = chunk.get(i).copied().unwrap_or(0.0) * window[i];
+        }
+        if fft.process(&mut frame, &mut spectrum).is_err() {
+            continue;
+        }
+        for (p, bin) in power.iter_mut().zip(&spectrum) {
+            *p += f64::from(bin.norm_sqr());
+        }
+        frames += 1;
+    }
+
+    if frames > 0 {
+        for p in &mut power {
+            *p /= f64::from(frames);
+        }
+    }
+    power
+}
+
+/// Level in dB of each third-octave band, averaged over its bins. `None` for
+/// bands reaching past Nyquist.
+fn band_levels(power: &[f64], sample_rate: usize) -> Vec<Option<f64>> {
+    let bin_hz = sample_rate as f64 / FFT_SIZE as f64;
+    let nyquist = sample_rate as f64 / 2.0;
+    let half_band = 2f64.powf(1.0 / 6.0);
+
+    third_octave_centres()
+        .map(|centre| {
+            let (low, high) = (centre / half_band, centre * half_band);
+            if high > nyquist {
+                return None;
+            }
+            let first = (low / bin_hz).ceil() as usize;
+            // A band narrower than a bin still gets the one it falls in.
+            let last = ((high / bin_hz).floor() as usize)
+                .max(first)
+                .min(power.len() - 1);
+            let bins = &power[first.min(last)..=last];
+            let mean = bins.iter().sum::<f64>() / bins.len() as f64;
+            Some(10.0 * mean.max(f64::MIN_POSITIVE).log10())
+        })
+        .collect()
+}
+
+fn loudest(levels: &[Option<f64>]) -> f64 {
+    levels.iter().flatten().copied().fold(f64::MIN, f64::max)
+}
+
+/// Graphic EQ gains following `bands`. Each EQ band starts at the curve's
+/// value at its frequency, then is nudged until the bands' combined
+/// response, overlap included, lands on the curve.
+fn fit_eq(bands: &[CorrectionBand], eq_rate: f64) -> [f32; NUM_BANDS] {
+    if bands.is_empty() {
+        return [0.0; NUM_BANDS];
+    }
+    let targets = BAND_FREQS.map(|freq| curve_at(bands, freq));
+    let mut gains = targets.map(|t| (t as f32).clamp(MIN_GAIN_DB, MAX_GAIN_DB));
+
+    for _ in 0..FIT_PASSES {
+        let errors: [f64; NUM_BANDS] =
+            std::array::from_fn(|i| targets[i] - eq::response_db(&gains, BAND_FREQS[i], eq_rate));
+        for (gain, error) in gains.iter_mut().zip(errors) {
+            *gain = (*gain + (error * FIT_STEP) as f32).clamp(MIN_GAIN_DB, MAX_GAIN_DB);
+        }
+    }
+    gains
+}
+
+/// The correction at `freq`, interpolated in log frequency and held flat
+/// past either end.
+fn curve_at(bands: &[CorrectionBand], freq: f64) -> f64 {
+    let upper = bands.partition_point(|band| f64::from(band.frequency) < freq);
+    match (upper.checked_sub(1).map(|i| bands[i]), bands.get(upper)) {
+        (Some(low), Some(high)) => {
+            let (low_f, high_f) = (f64::from(low.frequency), f64::from(high.frequency));
+            let t = (freq / low_f).log2() / (high_f / low_f).log2();
+            t.mul_add(
+                f64::from(high.gain_db - low.gain_db),
+                f64::from(low.gain_db),
+            )
+        }
+        (None, Some(band)) | (Some(band), None) => f64::from(band.gain_db),
+        (None, None) => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::Stage;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn noise() -> Vec<f32> {
+        pink_noise(4 * SAMPLE_RATE, SAMPLE_RATE)
+    }
+
+    fn gain_at(matched: &ToneMatch, freq: f32) -> f32 {
+        matched
+            .bands
+            .iter()
+            .find(|band| (band.frequency - freq).abs() < freq * 0.05)
+            .map(|band| band.gain_db)
+            .unwrap()
+    }
+
+    #[test]
+    fn a_level_difference_alone_needs_no_correction() {
+        let output = noise();
+        let quieter: Vec<f32> = output.iter().map(|s| s * 0.25).collect();
+
+        let matched = match_spectra(&output, SAMPLE_RATE, &quieter, SAMPLE_RATE, 96_000.0);
+        assert_eq!(matched.bands.len(), 30);
+        assert!(matched.max_deviation_db() < 0.01, "{matched:?}");
+        assert!(matched.eq_gains.iter().all(|g| g.abs() < 0.01));
+    }
+
+    #[test]
+    fn an_eq_boost_on_the_reference_is_recovered() {
+        let output = noise();
+        let mut gains = [0.0; NUM_BANDS];
+        gains[8] = 6.0;
+        let mut boost = EqConfig {
+            gains,
+            ..EqConfig::default()
+        }
+        .to_stage(SAMPLE_RATE as f32);
+        let reference: Vec<f32> = output.iter().map(|&s| boost.process(s)).collect();
+
+        let matched = match_spectra(
+            &output,
+            SAMPLE_RATE,
+            &reference,
+            SAMPLE_RATE,
+            SAMPLE_RATE as f64,
+        );
+        assert!(gain_at(&matched, 1000.0) > 4.0, "{matched:?}");
+        assert!(gain_at(&matched, 100.0).abs() < 1.5, "{matched:?}");
+        assert!(matched.eq_gains[8] > 4.0, "{:?}", matched.eq_gains);
+        assert!(matched.eq_gains[3].abs() < 1.5, "{:?}", matched.eq_gains);
+    }
+
+    #[test]
+    fn the_fitted_eq_follows_the_curve() {
+        // A gentle tilt, 2 dB per octave around 1 kHz, which neighbouring
+        // bands overlapping would overshoot if the gains were just sampled.
+        let bands: Vec<CorrectionBand> = third_octave_centres()
+            .map(|centre| CorrectionBand {
+                frequency: centre as f32,
+                gain_db: (2.0 * (centre / 1000.0).log2()) as f32,
+            })
+            .collect();
+        let gains = fit_eq(&bands, 96_000.0);
+
+        for (i, freq) in BAND_FREQS.iter().enumerate().take(NUM_BANDS - 1) {
+            let response = eq::response_db(&gains, *freq, 96_000.0);
+            assert!(
+                (response - curve_at(&bands, *freq)).abs() < 0.5,
+                "band {i}: {response} dB"
+            );
+        }
+    }
+
+    #[test]
+    fn the_correction_is_limited() {
+        let output = noise();
+        // Everything above 1 kHz gone from the reference but for a trace.
+        let mut cut = EqConfig {
+            gains: std::array::from_fn(|i| if i >= 9 { MIN_GAIN_DB } else { 0.0 }),
+            ..EqConfig::default()
+        }
+        .to_stage(SAMPLE_RATE as f32);
+        let reference: Vec<f32> = output.iter().map(|&s| cut.process(s)).collect();
+
+        let matched = match_spectra(
+            &output,
+            SAMPLE_RATE,
+            &reference,
+            SAMPLE_RATE,
+            SAMPLE_RATE as f64,
+        );
+        assert!(matched.max_deviation_db() <= MAX_CORRECTION_DB);
+        assert!(
+            matched
+                .eq_gains
+                .iter()
+                .all(|g| (MIN_GAIN_DB..=MAX_GAIN_DB).contains(g))
+        );
+    }
+}
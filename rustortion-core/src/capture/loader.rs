@@ -0,0 +1,147 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+
+use super::profile::CaptureProfile;
+
+/// A scanned capture file: the parsed profile, or why it can't be used.
+pub type CaptureEntry = Result<Arc<CaptureProfile>, String>;
+
+/// Recursively scans a directory for capture files (`*.json`, plus `*.nam` so
+/// NAM files dropped here get a clear "use the NAM stage" message) and parses
+/// each one at construction, off the real-time thread. Entries are keyed by
+/// path relative to the directory, with `/` separators, like IR names.
+pub struct CaptureLoader {
+    entries: BTreeMap<String, CaptureEntry>,
+}
+
+impl CaptureLoader {
+    /// Scan `directory`. A missing directory yields an empty loader (warn, not
+    /// error) so the app can run without a captures folder present.
+    pub fn new(directory: &Path) -> Result<Self> {
+        let mut loader = Self {
+            entries: BTreeMap::new(),
+        };
+
+        if !directory.is_dir() {
+            warn!(
+                "Captures directory '{}' does not exist; no captures loaded",
+                directory.display()
+            );
+            return Ok(loader);
+        }
+
+        loader.scan(directory, directory)?;
+        info!(
+            "Found {} capture file(s), {} usable",
+            loader.entries.len(),
+            loader.entries.values().flatten().count()
+        );
+        Ok(loader)
+    }
+
+    fn scan(&mut self, current: &Path, base: &Path) -> Result<()> {
+        let entries = std::fs::read_dir(current).with_context(|| {
+            format!("Failed to read captures directory '{}'", current.display())
+        })?;
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Skipping unreadable entry in captures directory: {e}");
+                    continue;
+                }
+            };
+            let path = entry.path();
+            if path.is_dir() {
+                self.scan(&path, base)?;
+                continue;
+            }
+            if !matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("json" | "nam")
+            ) {
+                continue;
+            }
+
+            let name = path
+                .strip_prefix(base)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|json| CaptureProfile::from_json_str(&json))
+                .map(Arc::new)
+                .map_err(|e| {
+                    warn!("Capture '{name}' can't be used: {e:#}");
+                    format!("{e:#}")
+                });
+            self.entries.insert(name, parsed);
+        }
+        Ok(())
+    }
+
+    /// Sorted list of every capture file found, usable or not.
+    #[must_use]
+    pub fn available_names(&self) -> Vec<String> {
+        self.entries.keys().cloned().collect()
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<CaptureEntry> {
+        self.entries.get(name).cloned()
+    }
+
+    /// All scanned entries, for populating the global registry.
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &CaptureEntry)> {
+        self.entries.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PROFILE: &str = r#"{
+        "architecture": "Wiener",
+        "waveshaper": { "table": [-1.0, 1.0] },
+        "kernel": [1.0]
+    }"#;
+
+    #[test]
+    fn scans_nested_files_and_keeps_unusable_ones() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let dir = tmp.path().join("captures");
+        std::fs::create_dir_all(dir.join("Mesa"))?;
+        std::fs::write(dir.join("clean.json"), PROFILE)?;
+        std::fs::write(
+            dir.join("Mesa").join("lead.nam"),
+            r#"{ "architecture": "WaveNet", "config": {}, "weights": [] }"#,
+        )?;
+        std::fs::write(dir.join("notes.txt"), "ignored")?;
+
+        let loader = CaptureLoader::new(&dir)?;
+        assert_eq!(
+            loader.available_names(),
+            vec!["Mesa/lead.nam", "clean.json"]
+        );
+        assert!(loader.get("clean.json").unwrap().is_ok());
+        let reason = loader.get("Mesa/lead.nam").unwrap().unwrap_err();
+        assert!(reason.contains("WaveNet"), "{reason}");
+        Ok(())
+    }
+
+    #[test]
+    fn missing_directory_is_empty() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let loader = CaptureLoader::new(&tmp.path().join("nope"))?;
+        assert!(loader.available_names().is_empty());
+        Ok(())
+    }
+}
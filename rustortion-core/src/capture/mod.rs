@@ -0,0 +1,15 @@
+//! Capture profiles: "linear + static nonlinearity" amp captures, run by the
+//! Capture stage through the convolver and a waveshaper lookup table.
+//!
+//! Profiles are parsed off the real-time thread. The [`loader`] scans a
+//! directory (recursively, like IRs) at startup; the [`registry`] makes the
+//! results reachable from `StageConfig::to_runtime`, which has no other handle
+//! to the loader. Files that can't be used are kept with the reason, so the
+//! stage card can say why instead of just listing fewer files.
+
+pub mod loader;
+pub mod profile;
+pub mod registry;
+
+pub use loader::{CaptureEntry, CaptureLoader};
+pub use profile::{CaptureArchitecture, CaptureProfile, Waveshaper};
@@ -0,0 +1,242 @@
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::ir::loader::resample;
+
+/// Longest linear kernel accepted, in seconds at the profile's sample rate.
+const MAX_KERNEL_SECONDS: f32 = 1.0;
+
+/// NAM architectures the capture stage recognises but can't run. Files using
+/// them are reported by name so the user knows to load them in the NAM stage.
+const NAM_ARCHITECTURES: [&str; 6] = [
+    "WaveNet",
+    "LSTM",
+    "ConvNet",
+    "Linear",
+    "CatWaveNet",
+    "CatLSTM",
+];
+
+/// Where the static nonlinearity sits relative to the linear kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureArchitecture {
+    /// Waveshaper, then kernel.
+    Hammerstein,
+    /// Kernel, then waveshaper.
+    Wiener,
+}
+
+impl std::fmt::Display for CaptureArchitecture {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hammerstein => write!(f, "Hammerstein"),
+            Self::Wiener => write!(f, "Wiener"),
+        }
+    }
+}
+
+/// A static transfer curve sampled at evenly spaced inputs from
+/// `-input_range` to `+input_range`. Inputs outside that span hold the end
+/// values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Waveshaper {
+    table: Vec<f32>,
+    input_range: f32,
+}
+
+impl Waveshaper {
+    pub fn new(table: Vec<f32>, input_range: f32) -> Result<Self> {
+        if table.len() < 2 {
+            bail!("waveshaper table needs at least 2 points");
+        }
+        if table.iter().any(|v| !v.is_finite()) {
+            bail!("waveshaper table contains non-finite values");
+        }
+        if !(input_range.is_finite() && input_range > 0.0) {
+            bail!("waveshaper input_range must be positive");
+        }
+        Ok(Self { table, input_range })
+    }
+
+    /// Linearly interpolated lookup. Real-time safe.
+    #[inline]
+    pub fn process(&self, input: f32) -> f32 {
+        let last = self.table.len() - 1;
+        let pos = ((input / self.input_range + 1.0) * 0.5 * last as f32).clamp(0.0, last as f32);
+        let idx = (pos as usize).min(last - 1);
+        let frac = pos - idx as f32;
+        let (a, b) = (self.table[idx], self.table[idx + 1]);
+        frac.mul_add(b - a, a)
+    }
+}
+
+/// A parsed "linear + static nonlinearity" capture profile.
+///
+/// The file is JSON:
+///
+/// ```json
+/// {
+///   "architecture": "Hammerstein",
+///   "sample_rate": 48000,
+///   "waveshaper": { "input_range": 1.0, "table": [-0.8, 0.0, 0.8] },
+///   "kernel": [0.6, 0.3, 0.1]
+/// }
+/// ```
+///
+/// `sample_rate` is the rate the kernel was captured at; without it the
+/// kernel is used as-is at any engine rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureProfile {
+    pub architecture: CaptureArchitecture,
+    pub sample_rate: Option<u32>,
+    pub waveshaper: Waveshaper,
+    pub kernel: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct WaveshaperJson {
+    #[serde(default = "default_input_range")]
+    input_range: f32,
+    table: Vec<f32>,
+}
+
+const fn default_input_range() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+struct ProfileJson {
+    sample_rate: Option<u32>,
+    waveshaper: WaveshaperJson,
+    kernel: Vec<f32>,
+}
+
+impl CaptureProfile {
+    /// Parse a profile. Files for architectures the stage can't run (such as
+    /// NAM's WaveNet) fail with a message naming the architecture.
+    pub fn from_json_str(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        let architecture = value
+            .get("architecture")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("missing \"architecture\" field"))?;
+
+        let architecture = match architecture {
+            "Hammerstein" => CaptureArchitecture::Hammerstein,
+            "Wiener" => CaptureArchitecture::Wiener,
+            nam if NAM_ARCHITECTURES.contains(&nam) => bail!(
+                "this capture requires {nam}, not supported yet by the Capture stage; \
+                 load it in the NAM stage instead"
+            ),
+            other => bail!("unknown capture architecture \"{other}\""),
+        };
+
+        let parsed: ProfileJson = serde_json::from_value(value)?;
+        let waveshaper = Waveshaper::new(parsed.waveshaper.table, parsed.waveshaper.input_range)?;
+
+        if parsed.sample_rate == Some(0) {
+            bail!("sample_rate must be positive");
+        }
+        if parsed.kernel.is_empty() {
+            bail!("kernel is empty");
+        }
+        if parsed.kernel.iter().any(|v| !v.is_finite()) {
+            bail!("kernel contains non-finite values");
+        }
+        let max_len = (parsed.sample_rate.unwrap_or(48_000) as f32 * MAX_KERNEL_SECONDS) as usize;
+        if parsed.kernel.len() > max_len {
+            bail!(
+                "kernel is too long: {} samples (max {max_len})",
+                parsed.kernel.len()
+            );
+        }
+
+        Ok(Self {
+            architecture,
+            sample_rate: parsed.sample_rate,
+            waveshaper,
+            kernel: parsed.kernel,
+        })
+    }
+
+    /// The kernel resampled to `sample_rate`, if the profile's rate differs.
+    pub fn kernel_at(&self, sample_rate: f32) -> Result<Vec<f32>> {
+        let target = sample_rate.round() as u32;
+        match self.sample_rate {
+            Some(rate) if rate != target => resample(&self.kernel, rate, target),
+            _ => Ok(self.kernel.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAMMERSTEIN: &str = r#"{
+        "architecture": "Hammerstein",
+        "sample_rate": 48000,
+        "waveshaper": { "input_range": 0.5, "table": [-0.5, 0.5] },
+        "kernel": [0.5, 0.25]
+    }"#;
+
+    #[test]
+    fn parses_hammerstein_profile() {
+        let profile = CaptureProfile::from_json_str(HAMMERSTEIN).unwrap();
+        assert_eq!(profile.architecture, CaptureArchitecture::Hammerstein);
+        assert_eq!(profile.sample_rate, Some(48_000));
+        assert_eq!(profile.kernel, vec![0.5, 0.25]);
+    }
+
+    #[test]
+    fn nam_architectures_are_reported_by_name() {
+        let json =
+            r#"{ "version": "0.5.2", "architecture": "WaveNet", "config": {}, "weights": [] }"#;
+        let err = CaptureProfile::from_json_str(json).unwrap_err().to_string();
+        assert!(err.contains("requires WaveNet"), "{err}");
+        assert!(err.contains("NAM stage"), "{err}");
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_architecture() {
+        let err = CaptureProfile::from_json_str(r#"{ "architecture": "Volterra" }"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Volterra"), "{err}");
+        assert!(CaptureProfile::from_json_str("{}").is_err());
+        assert!(CaptureProfile::from_json_str("not json").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_tables_and_kernels() {
+        let short_table = HAMMERSTEIN.replace("[-0.5, 0.5]", "[0.5]");
+        assert!(CaptureProfile::from_json_str(&short_table).is_err());
+        let empty_kernel = HAMMERSTEIN.replace("[0.5, 0.25]", "[]");
+        assert!(CaptureProfile::from_json_str(&empty_kernel).is_err());
+        let zero_range = HAMMERSTEIN.replace("0.5, \"table\"", "0.0, \"table\"");
+        assert!(CaptureProfile::from_json_str(&zero_range).is_err());
+    }
+
+    #[test]
+    fn waveshaper_interpolates_and_holds_ends() {
+        let shaper = Waveshaper::new(vec![-1.0, 0.0, 0.5], 2.0).unwrap();
+        assert!((shaper.process(-2.0) + 1.0).abs() < 1e-6);
+        assert!((shaper.process(-1.0) + 0.5).abs() < 1e-6);
+        assert!(shaper.process(0.0).abs() < 1e-6);
+        assert!((shaper.process(1.0) - 0.25).abs() < 1e-6);
+        assert!((shaper.process(10.0) - 0.5).abs() < 1e-6);
+        assert!((shaper.process(-10.0) + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn kernel_is_resampled_to_engine_rate() {
+        let mut profile = CaptureProfile::from_json_str(HAMMERSTEIN).unwrap();
+        profile.kernel = (0..4_800).map(|i| (i as f32 * 0.01).sin()).collect();
+        assert_eq!(profile.kernel_at(48_000.0).unwrap().len(), 4_800);
+
+        // Not exactly double, but close.
+        let resampled = profile.kernel_at(96_000.0).unwrap().len();
+        assert!(resampled > 9_000 && resampled < 10_000, "{resampled}");
+    }
+}
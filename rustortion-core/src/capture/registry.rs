@@ -0,0 +1,50 @@
+//! Process-global registry of scanned capture files.
+//!
+//! Same reasoning as the NAM registry: `CaptureConfig::to_stage` runs from
+//! `StageConfig::to_runtime(sample_rate)`, which has no handle to the
+//! [`CaptureLoader`](super::loader::CaptureLoader), and the captures folder is
+//! a singleton resource.
+
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use super::loader::{CaptureEntry, CaptureLoader};
+
+type Store = RwLock<HashMap<String, CaptureEntry>>;
+
+static CAPTURE_REGISTRY: OnceLock<Store> = OnceLock::new();
+
+fn store() -> &'static Store {
+    CAPTURE_REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Populate (or replace) the global registry from a loader's entries.
+pub fn init_from_loader(loader: &CaptureLoader) {
+    let mut map = store()
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    map.clear();
+    for (name, entry) in loader.entries() {
+        map.insert(name.clone(), entry.clone());
+    }
+}
+
+/// Look up a capture by its relative path.
+#[must_use]
+pub fn get(name: &str) -> Option<CaptureEntry> {
+    let map = store()
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    map.get(name).cloned()
+}
+
+/// Sorted list of every capture file found, usable or not.
+#[must_use]
+pub fn available_names() -> Vec<String> {
+    let map = store()
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let mut names: Vec<String> = map.keys().cloned().collect();
+    names.sort();
+    names
+}
@@ -0,0 +1,62 @@
+//! App events a user command can be hooked to. The standalone app runs the
+//! commands; the kinds live here so the settings UI can name them.
+
+use serde::{Deserialize, Serialize};
+
+/// Something that happened that a hook command can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A take started; the command gets the WAV's path.
+    RecordingStarted,
+    /// A take finished; the command gets the WAV's path.
+    RecordingStopped,
+    /// Another preset was selected; the command gets its name.
+    PresetChanged,
+    /// The audio stream stopped processing, e.g. the JACK server went away;
+    /// the command gets what was seen.
+    AudioDisconnected,
+}
+
+impl EventKind {
+    pub const ALL: [Self; 4] = [
+        Self::RecordingStarted,
+        Self::RecordingStopped,
+        Self::PresetChanged,
+        Self::AudioDisconnected,
+    ];
+
+    /// The name hooks see in `RUSTORTION_EVENT`, as in the settings file.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::RecordingStarted => "recording_started",
+            Self::RecordingStopped => "recording_stopped",
+            Self::PresetChanged => "preset_changed",
+            Self::AudioDisconnected => "audio_disconnected",
+        }
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RecordingStarted => write!(f, "Recording started"),
+            Self::RecordingStopped => write!(f, "Recording stopped"),
+            Self::PresetChanged => write!(f, "Preset changed"),
+            Self::AudioDisconnected => write!(f, "Audio disconnected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_match_the_settings_file() {
+        for kind in EventKind::ALL {
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.name()));
+        }
+    }
+}
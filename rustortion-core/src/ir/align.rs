@@ -0,0 +1,183 @@
+use serde::{Deserialize, Serialize};
+
+/// Largest shift, either way, in samples at the engine rate.
+pub const MAX_IR_OFFSET: i32 = 128;
+
+/// An IR's onset is its first sample within this many dB of its peak.
+pub const ONSET_THRESHOLD_DB: f32 = -40.0;
+
+/// Time and polarity adjustment applied to an IR before it's convolved,
+/// saved with each preset so blended IRs stay in phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IrAlignment {
+    /// Samples to shift the IR by: positive delays it, negative trims its head.
+    pub offset: i32,
+    /// Flip the IR's polarity.
+    pub inverted: bool,
+}
+
+impl IrAlignment {
+    pub const fn is_neutral(&self) -> bool {
+        self.offset == 0 && !self.inverted
+    }
+
+    pub fn clamped(self) -> Self {
+        Self {
+            offset: self.offset.clamp(-MAX_IR_OFFSET, MAX_IR_OFFSET),
+            inverted: self.inverted,
+        }
+    }
+}
+
+/// Index of the first sample whose level is within `threshold_db` of the
+/// IR's peak. `None` for a silent IR.
+pub fn onset(ir: &[f32], threshold_db: f32) -> Option<usize> {
+    let peak = ir.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+    if peak <= 0.0 {
+        return None;
+    }
+    let threshold = peak * 10.0_f32.powf(threshold_db / 20.0);
+    ir.iter().position(|s| s.abs() >= threshold)
+}
+
+/// The offset that moves an IR with its onset at `onset` onto `reference`,
+/// within [`MAX_IR_OFFSET`]. Aligning to a reference of 0 lines the IR up
+/// with the dry signal.
+pub fn offset_to(reference: usize, onset: usize) -> i32 {
+    let offset = reference as i64 - onset as i64;
+    offset.clamp(-i64::from(MAX_IR_OFFSET), i64::from(MAX_IR_OFFSET)) as i32
+}
+
+/// Shift `ir` by the alignment's offset and flip its polarity if asked.
+///
+/// The head is padded with silence or loses samples, and the tail moves with
+/// it, so every partition of the convolver sees the shift.
+pub fn apply(ir: &[f32], alignment: IrAlignment) -> Vec<f32> {
+    let alignment = alignment.clamped();
+    let shift = alignment.offset.unsigned_abs() as usize;
+    let sign = if alignment.inverted { -1.0 } else { 1.0 };
+
+    if alignment.offset >= 0 {
+        std::iter::repeat_n(0.0, shift)
+            .chain(ir.iter().map(|s| s * sign))
+            .collect()
+    } else {
+        ir.iter().skip(shift).map(|s| s * sign).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A decaying, ringing IR with a soft pre-onset rise, like a close-miked cab.
+    fn synthetic_ir(len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| {
+                let t = n as f32;
+                if n < 4 {
+                    0.001 * t
+                } else {
+                    let t = t - 4.0;
+                    (-t / 60.0).exp() * (t * 0.37).sin().mul_add(0.8, 0.2)
+                }
+            })
+            .collect()
+    }
+
+    fn delayed(ir: &[f32], samples: usize) -> Vec<f32> {
+        std::iter::repeat_n(0.0, samples)
+            .chain(ir.iter().copied())
+            .collect()
+    }
+
+    fn rms(buf: &[f32]) -> f32 {
+        (buf.iter().map(|s| s * s).sum::<f32>() / buf.len() as f32).sqrt()
+    }
+
+    #[test]
+    fn onset_is_the_first_sample_near_the_peak() {
+        let ir = synthetic_ir(512);
+        let a = onset(&ir, ONSET_THRESHOLD_DB).unwrap();
+        assert!(a <= 4, "onset {a}");
+        assert_eq!(onset(&delayed(&ir, 37), ONSET_THRESHOLD_DB), Some(a + 37));
+        assert_eq!(onset(&[0.0; 16], ONSET_THRESHOLD_DB), None);
+    }
+
+    #[test]
+    fn offset_shifts_and_inverts() {
+        let ir = [1.0, 0.5, 0.25];
+        let late = IrAlignment {
+            offset: 2,
+            inverted: false,
+        };
+        assert_eq!(apply(&ir, late), [0.0, 0.0, 1.0, 0.5, 0.25]);
+
+        let early_flipped = IrAlignment {
+            offset: -1,
+            inverted: true,
+        };
+        assert_eq!(apply(&ir, early_flipped), [-0.5, -0.25]);
+        assert_eq!(apply(&ir, IrAlignment::default()), ir);
+    }
+
+    #[test]
+    fn offsets_are_limited() {
+        assert_eq!(offset_to(0, 500), -MAX_IR_OFFSET);
+        assert_eq!(offset_to(500, 0), MAX_IR_OFFSET);
+        let wild = IrAlignment {
+            offset: 1000,
+            inverted: false,
+        };
+        assert_eq!(apply(&[1.0], wild).len(), MAX_IR_OFFSET as usize + 1);
+    }
+
+    #[test]
+    fn auto_aligned_and_flipped_copy_nulls() {
+        let a = synthetic_ir(1024);
+        let b = delayed(&a, 37);
+
+        let offset = offset_to(
+            onset(&a, ONSET_THRESHOLD_DB).unwrap(),
+            onset(&b, ONSET_THRESHOLD_DB).unwrap(),
+        );
+        assert_eq!(offset, -37);
+
+        let b = apply(
+            &b,
+            IrAlignment {
+                offset,
+                inverted: true,
+            },
+        );
+        let sum: Vec<f32> = a.iter().zip(&b).map(|(x, y)| x + y).collect();
+        let residual_db = 20.0 * (rms(&sum) / rms(&a)).max(1e-12).log10();
+        assert!(residual_db < -60.0, "residual {residual_db} dB");
+
+        // Without the flip the two add instead.
+        let unflipped = apply(
+            &delayed(&a, 37),
+            IrAlignment {
+                offset,
+                ..Default::default()
+            },
+        );
+        let sum: Vec<f32> = a.iter().zip(&unflipped).map(|(x, y)| x + y).collect();
+        assert!(rms(&sum) > 1.9 * rms(&a));
+    }
+
+    #[test]
+    fn misaligned_copies_do_not_null() {
+        let a = synthetic_ir(1024);
+        let b = apply(
+            &delayed(&a, 37),
+            IrAlignment {
+                offset: 0,
+                inverted: true,
+            },
+        );
+        let sum: Vec<f32> = a.iter().zip(&b).map(|(x, y)| x + y).collect();
+        assert!(rms(&sum) > 0.5 * rms(&a));
+    }
+}
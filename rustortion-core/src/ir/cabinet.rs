@@ -6,7 +6,10 @@ use crate::ir::convolver::Convolver;
 /// Configuration for convolver type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum ConvolverType {
+    /// Whichever of the two suits each IR's length; see
+    /// [`Convolver::new_auto`].
     #[default]
+    Auto,
     Fir,
     TwoStage,
 }
@@ -14,37 +17,69 @@ pub enum ConvolverType {
 /// Default maximum IR length in milliseconds for truncation
 pub const DEFAULT_MAX_IR_MS: usize = 50;
 
+/// Length of the crossfade from a playing IR to one swapped in, 10 ms at
+/// 48 kHz, so cycling through IRs doesn't click.
+pub const IR_CROSSFADE_SAMPLES: usize = 480;
+
 pub struct IrCabinet {
     /// Boxed so the convolver can be swapped in/out on the RT thread by
     /// exchanging pointers (`swap_convolver`) without moving the heavy
     /// convolver struct or allocating to type-erase it for `rt_drop`.
     convolver: Box<Convolver>,
+    /// The IR being faded out after a swap, fed the same input until the
+    /// fade ends. Between fades it's an idle spare, so a swap never
+    /// allocates.
+    outgoing: Box<Convolver>,
+    /// Samples left of the current crossfade; zero when not fading.
+    fade_remaining: usize,
 
     bypassed: bool,
     output_gain: f32,
+    /// Level-matching trim while comparing IRs; 1.0 otherwise.
+    trim: f32,
+    /// Play IRs head-only, set while the engine sheds load.
+    head_only: bool,
 }
 
 impl IrCabinet {
     pub fn new(convolver_type: ConvolverType, max_ir_samples: usize) -> Self {
-        let convolver = Box::new(match convolver_type {
-            ConvolverType::Fir => Convolver::new_fir(max_ir_samples),
-            ConvolverType::TwoStage => Convolver::new_two_stage(),
-        });
+        let new_convolver = || {
+            Box::new(match convolver_type {
+                ConvolverType::Auto => Convolver::new_auto(max_ir_samples),
+                ConvolverType::Fir => Convolver::new_fir(max_ir_samples),
+                ConvolverType::TwoStage => Convolver::new_two_stage(),
+            })
+        };
+        let convolver = new_convolver();
 
         debug!("IrCabinet created: {convolver_type:?} convolver, max {max_ir_samples} samples");
 
         Self {
             convolver,
+            outgoing: new_convolver(),
+            fade_remaining: 0,
             bypassed: false,
             output_gain: 0.1,
+            trim: 1.0,
+            head_only: false,
         }
     }
 
     /// RT-safe convolver swap: exchanges the cabinet's convolver with `other`
     /// in place. Neither side allocates or deallocates — the caller is left
-    /// holding the previous convolver (e.g. to retire it off the RT thread).
-    pub const fn swap_convolver(&mut self, other: &mut Box<Convolver>) {
+    /// holding a convolver to retire off the RT thread.
+    ///
+    /// If an IR was playing, it crossfades into the new one over
+    /// [`IR_CROSSFADE_SAMPLES`] rather than cutting off; the caller then gets
+    /// the spare (or the IR still fading from an earlier swap) back instead.
+    pub fn swap_convolver(&mut self, other: &mut Box<Convolver>) {
+        let crossfade = !self.bypassed && self.convolver.ir_length() > 0;
         std::mem::swap(&mut self.convolver, other);
+        self.convolver.set_head_only(self.head_only, 0);
+        if crossfade {
+            std::mem::swap(&mut self.outgoing, other);
+            self.fade_remaining = IR_CROSSFADE_SAMPLES;
+        }
     }
 
     /// Install a convolver by value, reusing the existing heap allocation.
@@ -55,6 +90,7 @@ impl IrCabinet {
 
     pub fn clear_convolver(&mut self) {
         self.convolver.reset();
+        self.fade_remaining = 0;
     }
 
     pub fn process_block(&mut self, samples: &mut [f32]) {
@@ -62,11 +98,18 @@ impl IrCabinet {
             return;
         }
 
-        self.convolver.process_block(samples);
+        if self.fade_remaining > 0 {
+            for sample in samples.iter_mut() {
+                *sample = self.process_convolvers(*sample);
+            }
+        } else {
+            self.convolver.process_block(samples);
+        }
 
         // Apply gain
+        let gain = self.output_gain * self.trim;
         for sample in samples.iter_mut() {
-            *sample *= self.output_gain;
+            *sample *= gain;
         }
     }
 
@@ -76,15 +119,37 @@ impl IrCabinet {
             return input;
         }
 
-        let conv_out = self.convolver.process_sample(input);
+        let conv_out = self.process_convolvers(input);
+
+        conv_out * self.output_gain * self.trim
+    }
+
+    /// One sample through the IR, mixed with the outgoing one while fading.
+    #[inline]
+    fn process_convolvers(&mut self, input: f32) -> f32 {
+        let current = self.convolver.process_sample(input);
+        if self.fade_remaining == 0 {
+            return current;
+        }
+        let outgoing = self.outgoing.process_sample(input);
+        let t = self.fade_remaining as f32 / IR_CROSSFADE_SAMPLES as f32;
+        self.fade_remaining -= 1;
+        t.mul_add(outgoing - current, current)
+    }
 
-        conv_out * self.output_gain
+    /// Cap IRs to their head, fading the tail over `fade_samples`, or bring
+    /// the tail back. IRs swapped in meanwhile start out the same way.
+    pub fn set_head_only(&mut self, head_only: bool, fade_samples: usize) {
+        self.head_only = head_only;
+        self.convolver.set_head_only(head_only, fade_samples);
+        self.outgoing.set_head_only(head_only, fade_samples);
     }
 
     pub fn set_bypass(&mut self, bypass: bool) {
         self.bypassed = bypass;
         if bypass {
             self.convolver.reset();
+            self.fade_remaining = 0;
         }
     }
 
@@ -99,4 +164,62 @@ impl IrCabinet {
     pub const fn gain(&self) -> f32 {
         self.output_gain
     }
+
+    /// Linear trim on top of the gain. Only ever cuts.
+    pub const fn set_trim(&mut self, trim: f32) {
+        self.trim = trim.clamp(0.0, 1.0);
+    }
+
+    /// Delay the cabinet adds; see [`Convolver::latency_samples`].
+    pub const fn latency_samples(&self) -> usize {
+        self.convolver.latency_samples()
+    }
+
+    pub const fn convolver(&self) -> &Convolver {
+        &self.convolver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fir(ir: &[f32]) -> Box<Convolver> {
+        let mut convolver = Convolver::new_fir(16);
+        convolver.set_ir(ir).unwrap();
+        Box::new(convolver)
+    }
+
+    #[test]
+    fn swapping_crossfades_from_the_playing_ir() {
+        let mut cabinet = IrCabinet::new(ConvolverType::Fir, 16);
+        cabinet.set_gain(1.0);
+        cabinet.swap_convolver(&mut fir(&[1.0]));
+
+        let mut incoming = fir(&[0.5]);
+        cabinet.swap_convolver(&mut incoming);
+        // Handed back: the idle spare, not the IR that is fading out.
+        assert_eq!(incoming.ir_length(), 0);
+
+        let mut block = vec![1.0; IR_CROSSFADE_SAMPLES + 4];
+        cabinet.process_block(&mut block);
+        assert!((block[0] - 1.0).abs() < 1e-6);
+        assert!(
+            block.windows(2).all(|w| w[1] <= w[0]),
+            "fade isn't monotonic"
+        );
+        assert!((block[IR_CROSSFADE_SAMPLES / 2] - 0.75).abs() < 0.01);
+        assert_eq!(block[IR_CROSSFADE_SAMPLES..], [0.5; 4]);
+    }
+
+    #[test]
+    fn the_first_ir_cuts_in_without_a_fade() {
+        let mut cabinet = IrCabinet::new(ConvolverType::Fir, 16);
+        cabinet.set_gain(1.0);
+        cabinet.swap_convolver(&mut fir(&[0.5]));
+
+        let mut block = vec![1.0; 4];
+        cabinet.process_block(&mut block);
+        assert_eq!(block, [0.5; 4]);
+    }
 }
@@ -0,0 +1,73 @@
+/// Most IRs that can be compared at once.
+pub const MAX_COMPARE_SLOTS: usize = 3;
+
+/// Broadband gain of an IR: the square root of its energy, which is the
+/// level it gives white noise relative to the input.
+pub fn ir_energy(ir: &[f32]) -> f32 {
+    ir.iter().map(|s| s * s).sum::<f32>().sqrt()
+}
+
+/// Trim in dB for each IR so all of them play as loud as the quietest.
+/// Nothing is boosted, so matching can't push a loud cab into clipping.
+///
+/// An IR not measured yet (`None`) or silent gets no trim, and doesn't count
+/// towards the quietest.
+pub fn level_match_trims_db(energies: &[Option<f32>]) -> Vec<f32> {
+    let quietest = energies
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|&e| e > 0.0)
+        .fold(f32::INFINITY, f32::min);
+
+    energies
+        .iter()
+        .map(|energy| match energy {
+            Some(e) if *e > 0.0 && quietest.is_finite() => 20.0 * (quietest / e).log10(),
+            _ => 0.0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db_to_lin(db: f32) -> f32 {
+        10.0_f32.powf(db / 20.0)
+    }
+
+    #[test]
+    fn energy_is_the_root_sum_of_squares() {
+        assert_eq!(ir_energy(&[0.6, 0.0, -0.8]), 1.0);
+        assert_eq!(ir_energy(&[]), 0.0);
+    }
+
+    #[test]
+    fn trims_bring_every_ir_to_the_quietest() {
+        let irs: [&[f32]; 3] = [&[1.0, 0.5], &[0.25, 0.125], &[2.0, -1.0, 0.5]];
+        let energies: Vec<Option<f32>> = irs.iter().map(|ir| Some(ir_energy(ir))).collect();
+        let trims = level_match_trims_db(&energies);
+
+        assert_eq!(trims[1], 0.0);
+        assert!((trims[0] - -12.041).abs() < 1e-2, "{}", trims[0]);
+        let matched: Vec<f32> = energies
+            .iter()
+            .zip(&trims)
+            .map(|(e, t)| e.unwrap() * db_to_lin(*t))
+            .collect();
+        for level in &matched {
+            assert!((level - matched[1]).abs() < 1e-5, "{matched:?}");
+        }
+        assert!(trims.iter().all(|&t| t <= 0.0));
+    }
+
+    #[test]
+    fn unmeasured_and_silent_irs_are_left_alone() {
+        let trims = level_match_trims_db(&[Some(2.0), None, Some(0.0), Some(1.0)]);
+        assert!((trims[0] - -6.0206).abs() < 1e-3, "{trims:?}");
+        assert_eq!(trims[1..], [0.0, 0.0, 0.0]);
+        assert_eq!(level_match_trims_db(&[None, Some(0.0)]), [0.0, 0.0]);
+        assert!(level_match_trims_db(&[]).is_empty());
+    }
+}
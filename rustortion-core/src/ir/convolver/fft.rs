@@ -3,13 +3,20 @@ use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
 use rustfft::num_complex::Complex;
 use std::sync::Arc;
 
-/// Default head length for zero-latency time-domain processing
-const HEAD_LEN: usize = 256;
+use super::TailFade;
+use super::cmac::{self, ComplexMac};
+use super::fir::{FirConvolver, MAX_FIR_TAPS};
 /// FFT block size
 const FFT_BLOCK_SIZE: usize = 1024;
 
+/// Taps played by the direct head. A partition's output is ready one
+/// partition after its input, so the head covers exactly that much and the
+/// tail lines up with no latency.
+const DIRECT_LEN: usize = FFT_BLOCK_SIZE / 2;
+const _: () = assert!(DIRECT_LEN <= MAX_FIR_TAPS);
+
 /// Two-stage convolver: time-domain FIR for the head (zero latency),
-/// partitioned FFT convolution for the tail.
+/// partitioned FFT convolution (uniform overlap-save) for the tail.
 ///
 /// This is the Gardner method for low-latency convolution.
 pub struct TwoStageConvolver {
@@ -20,14 +27,15 @@ pub struct TwoStageConvolver {
     /// Number of frequency bins
     num_bins: usize,
 
-    // Head (time-domain FIR)
-    head_coeffs: Vec<f32>,
-    head_ring: Vec<f32>,
-    head_write_pos: usize,
+    /// The first [`DIRECT_LEN`] taps, which also fades its own taps past
+    /// [`super::HEAD_LEN`] for head-only playback.
+    head: FirConvolver,
 
     // Tail (FFT partitioned convolution)
     tail_partitions: Vec<Vec<Complex<f32>>>,
     num_tail_partitions: usize,
+    /// Length of the loaded IR (head + tail), in samples
+    ir_length: usize,
 
     // FFT planners
     r2c: Arc<dyn RealToComplex<f32>>,
@@ -42,10 +50,9 @@ pub struct TwoStageConvolver {
     history: Vec<Vec<Complex<f32>>>,
     history_head: usize,
 
-    // Overlap-add buffer for tail output
-    ola_buffer: Vec<f32>,
-    ola_write: usize,
-    ola_read: usize,
+    /// Tail output for the partition being collected, computed from the
+    /// one before it.
+    tail_output: Vec<f32>,
 
     // Scratch buffers
     time_scratch: Vec<f32>,
@@ -53,6 +60,11 @@ pub struct TwoStageConvolver {
     freq_accumulator: Vec<Complex<f32>>,
     r2c_scratch: Vec<Complex<f32>>,
     c2r_scratch: Vec<Complex<f32>>,
+
+    /// Complex multiply-accumulate for the tail, picked for this CPU.
+    complex_mac: ComplexMac,
+    /// Level of the tail. Once faded out the tail isn't run at all.
+    tail: TailFade,
 }
 
 impl Default for TwoStageConvolver {
@@ -83,12 +95,11 @@ impl TwoStageConvolver {
             partition_size,
             num_bins,
 
-            head_coeffs: vec![0.0; HEAD_LEN],
-            head_ring: vec![0.0; HEAD_LEN],
-            head_write_pos: 0,
+            head: FirConvolver::new(DIRECT_LEN),
 
             tail_partitions: Vec::new(),
             num_tail_partitions: 0,
+            ir_length: 0,
 
             r2c,
             c2r,
@@ -100,21 +111,24 @@ impl TwoStageConvolver {
             history: Vec::new(),
             history_head: 0,
 
-            ola_buffer: vec![0.0; block_size],
-            ola_write: 0,
-            ola_read: 0,
+            tail_output: vec![0.0; partition_size],
 
             time_scratch: vec![0.0; block_size],
             freq_scratch: vec![Complex::new(0.0, 0.0); num_bins],
             freq_accumulator: vec![Complex::new(0.0, 0.0); num_bins],
             r2c_scratch,
             c2r_scratch,
+
+            complex_mac: cmac::detect(),
+            tail: TailFade::full(),
         }
     }
 
     pub fn set_ir(&mut self, ir: &[f32]) -> Result<()> {
+        self.ir_length = ir.len();
+
         if ir.is_empty() {
-            self.head_coeffs.fill(0.0);
+            self.head.set_ir(&[])?;
             self.tail_partitions.clear();
             self.num_tail_partitions = 0;
             self.history.clear();
@@ -122,13 +136,11 @@ impl TwoStageConvolver {
         }
 
         // Split IR into head and tail
-        let head_len = ir.len().min(HEAD_LEN);
-        self.head_coeffs = vec![0.0; HEAD_LEN];
-        self.head_coeffs[..head_len].copy_from_slice(&ir[..head_len]);
+        self.head.set_ir(&ir[..ir.len().min(DIRECT_LEN)])?;
 
         // Partition tail for FFT convolution
-        if ir.len() > HEAD_LEN {
-            let tail = &ir[HEAD_LEN..];
+        if ir.len() > DIRECT_LEN {
+            let tail = &ir[DIRECT_LEN..];
             self.partition_tail(tail)?;
         } else {
             self.tail_partitions.clear();
@@ -174,34 +186,23 @@ impl TwoStageConvolver {
 
     #[inline]
     pub fn process_sample(&mut self, input: f32) -> f32 {
-        // === Head processing (zero latency) ===
-        self.head_ring[self.head_write_pos] = input;
-
-        let mut head_out = 0.0f32;
-        let mut idx = self.head_write_pos;
-
-        for &coeff in &self.head_coeffs {
-            head_out = coeff.mul_add(self.head_ring[idx], head_out);
-            if idx == 0 {
-                idx = HEAD_LEN - 1;
-            } else {
-                idx -= 1;
-            }
+        // No IR yet: silence rather than the head's pass-through.
+        if self.ir_length == 0 {
+            return 0.0;
         }
 
-        self.head_write_pos = (self.head_write_pos + 1) % HEAD_LEN;
+        // === Head processing (zero latency) ===
+        let head_out = self.head.process_sample(input);
 
         // === Tail processing (FFT with latency) ===
-        let tail_out = if self.num_tail_partitions > 0 {
+        let tail_out = if self.num_tail_partitions > 0 && !self.tail.is_silent() {
             // Write input to buffer
             let write_idx =
                 (self.input_base + self.partition_size + self.input_pos) % self.block_size;
             self.input_buffer[write_idx] = input;
 
-            // Read from overlap-add buffer
-            let out = self.ola_buffer[self.ola_read];
-            self.ola_buffer[self.ola_read] = 0.0;
-            self.ola_read = (self.ola_read + 1) % self.block_size;
+            // The previous partition's tail lands where the head ends.
+            let out = self.tail_output[self.input_pos];
 
             self.input_pos += 1;
 
@@ -212,7 +213,7 @@ impl TwoStageConvolver {
                 self.input_base = (self.input_base + self.partition_size) % self.block_size;
             }
 
-            out
+            out * self.tail.next()
         } else {
             0.0
         };
@@ -243,8 +244,8 @@ impl TwoStageConvolver {
             )
             .is_err()
         {
-            // Advance ola_write to keep buffer alignment on error.
-            self.ola_write = (self.ola_write + self.partition_size) % self.block_size;
+            // Play silence for this partition rather than the last one again.
+            self.tail_output.fill(0.0);
             return;
         }
 
@@ -255,12 +256,12 @@ impl TwoStageConvolver {
         // Accumulate convolution
         self.freq_accumulator.fill(Complex::new(0.0, 0.0));
 
-        for j in 0..self.num_tail_partitions {
-            let hist_idx = (self.history_head + self.history.len() - 1 - j) % self.history.len();
-
-            for (k, acc) in self.freq_accumulator.iter_mut().enumerate() {
-                *acc += self.history[hist_idx][k] * self.tail_partitions[j][k];
-            }
+        // Newest spectrum meets the first partition: walk back from the slot
+        // just written, wrapping once, with no per-partition modulo.
+        let (older, newer) = self.history.split_at(self.history_head);
+        let newest_first = older.iter().rev().chain(newer.iter().rev());
+        for (spectrum, partition) in newest_first.zip(&self.tail_partitions) {
+            (self.complex_mac)(&mut self.freq_accumulator, spectrum, partition);
         }
 
         // Ensure DC and Nyquist are real
@@ -279,35 +280,35 @@ impl TwoStageConvolver {
             )
             .is_err()
         {
-            // Advance ola_write to keep buffer alignment on error.
-            self.ola_write = (self.ola_write + self.partition_size) % self.block_size;
+            self.tail_output.fill(0.0);
             return;
         }
 
-        // Overlap-add into output buffer
+        // Overlap-save: the first half wrapped around and is discarded, the
+        // second is the tail for the partition just collected.
         let scale = 1.0 / self.block_size as f32;
-        let tail_offset = HEAD_LEN % self.block_size;
-        let base = (self.ola_write + tail_offset) % self.block_size;
-
-        for i in 0..self.block_size {
-            let pos = (base + i) % self.block_size;
-            self.ola_buffer[pos] = self.time_scratch[i].mul_add(scale, self.ola_buffer[pos]);
+        for (out, &sample) in self
+            .tail_output
+            .iter_mut()
+            .zip(&self.time_scratch[self.partition_size..])
+        {
+            *out = sample * scale;
         }
-
-        self.ola_write = (self.ola_write + self.partition_size) % self.block_size;
     }
 
     pub fn reset(&mut self) {
-        self.head_ring.fill(0.0);
-        self.head_write_pos = 0;
+        self.head.reset();
+        self.reset_tail();
+    }
 
+    /// Clear the tail's history, e.g. before it fades back in after sitting
+    /// idle while the engine played IRs head-only.
+    fn reset_tail(&mut self) {
         self.input_buffer.fill(0.0);
         self.input_base = 0;
         self.input_pos = 0;
 
-        self.ola_buffer.fill(0.0);
-        self.ola_write = 0;
-        self.ola_read = 0;
+        self.tail_output.fill(0.0);
 
         self.history_head = 0;
         for hist in &mut self.history {
@@ -319,14 +320,52 @@ impl TwoStageConvolver {
         self.freq_accumulator.fill(Complex::new(0.0, 0.0));
     }
 
+    /// Play only the first [`super::HEAD_LEN`] taps, or the tail as well again. A
+    /// tail that stopped running restarts from silence as it fades in.
+    pub fn set_head_only(&mut self, head_only: bool, fade_samples: usize) {
+        if !head_only && self.tail.is_silent() {
+            self.reset_tail();
+        }
+        self.head.set_head_only(head_only, fade_samples);
+        self.tail.set(!head_only, fade_samples);
+    }
+
+    /// Bytes held in buffers, most of it the tail's partitions and history.
+    pub fn memory_bytes(&self) -> usize {
+        let complex = |v: &Vec<Complex<f32>>| v.capacity() * size_of::<Complex<f32>>();
+        let real = |v: &Vec<f32>| v.capacity() * size_of::<f32>();
+        self.tail_partitions
+            .iter()
+            .chain(&self.history)
+            .chain([
+                &self.freq_scratch,
+                &self.freq_accumulator,
+                &self.r2c_scratch,
+                &self.c2r_scratch,
+            ])
+            .map(complex)
+            .sum::<usize>()
+            + [&self.input_buffer, &self.tail_output, &self.time_scratch]
+                .into_iter()
+                .map(real)
+                .sum::<usize>()
+            + self.head.memory_bytes()
+    }
+
     pub const fn num_tail_partitions(&self) -> usize {
         self.num_tail_partitions
     }
+
+    /// Returns the current IR length
+    pub const fn ir_length(&self) -> usize {
+        self.ir_length
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ir::convolver::HEAD_LEN;
 
     #[test]
     fn test_two_stage_short_ir() {
@@ -357,4 +396,86 @@ mod tests {
         // Should have tail partitions
         assert!(conv.num_tail_partitions() > 0);
     }
+
+    #[test]
+    fn test_two_stage_head_only_skips_the_tail() {
+        let mut ir = vec![0.0; HEAD_LEN + 1];
+        ir[0] = 1.0;
+        ir[HEAD_LEN] = 0.5;
+        let mut conv = TwoStageConvolver::new();
+        conv.set_ir(&ir).unwrap();
+
+        let response = |conv: &mut TwoStageConvolver| {
+            let mut out = vec![conv.process_sample(1.0)];
+            out.extend((0..4 * FFT_BLOCK_SIZE).map(|_| conv.process_sample(0.0)));
+            out
+        };
+        let full = response(&mut conv);
+        assert!(full[1..].iter().any(|s| s.abs() > 0.1), "no tail");
+
+        conv.set_head_only(true, 0);
+        let head = response(&mut conv);
+        assert!((head[0] - 1.0).abs() < 1e-5);
+        assert!(head[1..].iter().all(|&s| s == 0.0));
+
+        // Restarted from silence, so nothing from the idle stretch leaks in.
+        conv.set_head_only(false, 0);
+        let restored = response(&mut conv);
+        assert!(
+            full.iter()
+                .zip(&restored)
+                .all(|(a, b)| (a - b).abs() < 1e-5)
+        );
+    }
+
+    #[test]
+    fn test_two_stage_tail_lands_on_its_taps() {
+        // One tap in the direct head, one in the first partition, one at
+        // the end of the second.
+        let mut ir = vec![0.0; DIRECT_LEN + 2 * FFT_BLOCK_SIZE / 2];
+        ir[0] = 1.0;
+        ir[DIRECT_LEN + 44] = 0.5;
+        let last = ir.len() - 1;
+        ir[last] = 0.25;
+        let mut conv = TwoStageConvolver::new();
+        conv.set_ir(&ir).unwrap();
+
+        let mut out = vec![conv.process_sample(1.0)];
+        out.extend((0..2 * ir.len()).map(|_| conv.process_sample(0.0)));
+
+        for (n, &sample) in out.iter().enumerate() {
+            let expected = ir.get(n).copied().unwrap_or(0.0);
+            assert!((sample - expected).abs() < 1e-5, "sample {n}: {sample}");
+        }
+    }
+
+    #[test]
+    fn test_two_stage_matches_direct_convolution() {
+        let ir: Vec<f32> = (0..1_700)
+            .map(|i| ((i * 7919 % 1000) as f32 / 1000.0 - 0.5) * (-(i as f32) / 400.0).exp())
+            .collect();
+        let input: Vec<f32> = (0..3_000)
+            .map(|i| ((i * 104_729 % 2000) as f32 / 1000.0) - 1.0)
+            .collect();
+
+        let mut conv = TwoStageConvolver::new();
+        conv.set_ir(&ir).unwrap();
+        let mut output = input.clone();
+        for block in output.chunks_mut(100) {
+            conv.process_block(block);
+        }
+
+        for (n, &sample) in output.iter().enumerate() {
+            let expected: f32 = ir
+                .iter()
+                .take(n + 1)
+                .enumerate()
+                .map(|(k, &h)| h * input[n - k])
+                .sum();
+            assert!(
+                (sample - expected).abs() < 1e-4,
+                "sample {n}: {sample} vs {expected}"
+            );
+        }
+    }
 }
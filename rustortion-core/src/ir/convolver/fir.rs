@@ -1,24 +1,45 @@
 use anyhow::Result;
 
-/// Simple time-domain FIR convolver using a ring buffer.
+use super::dot::{self, DotProduct};
+use super::{HEAD_LEN, TailFade};
+
+/// Longest IR the FIR plays. Past this the two-stage convolver's FFT tail
+/// costs less per sample than another 512 taps of dot product;
+/// `benches/impulse_responses.rs` compares the two at 256, 4096 and 48000
+/// taps.
+pub const MAX_FIR_TAPS: usize = 512;
+
+/// Time-domain FIR convolver for short IRs, with no latency and no FFT.
+///
+/// The input history is written twice, `len` apart, so the last `len` inputs
+/// are always one contiguous slice and each sample is a single SIMD dot
+/// product against the reversed coefficients.
 pub struct FirConvolver {
-    /// IR coefficients (stored in original order)
-    coefficients: Vec<f32>,
-    /// Ring buffer for input history
-    input_buffer: Vec<f32>,
-    /// Current write position in ring buffer
+    /// IR coefficients, last tap first, to line up with the history slice.
+    reversed: Vec<f32>,
+    /// Input history, `2 * len` long: each input at `pos` and `pos + len`.
+    history: Vec<f32>,
+    /// Where the next input goes, in `0..len`.
     write_pos: usize,
-    /// Maximum IR length this convolver supports
+    /// Maximum IR length this convolver supports, at most [`MAX_FIR_TAPS`].
     max_length: usize,
+    /// Level of the taps past [`HEAD_LEN`].
+    tail: TailFade,
+    dot: DotProduct,
 }
 
 impl FirConvolver {
+    /// A convolver for IRs of up to `max_length` taps, capped at
+    /// [`MAX_FIR_TAPS`]; longer IRs are truncated.
     pub fn new(max_length: usize) -> Self {
+        let max_length = max_length.min(MAX_FIR_TAPS);
         Self {
-            coefficients: Vec::new(),
-            input_buffer: vec![0.0; max_length],
+            reversed: Vec::new(),
+            history: vec![0.0; 2 * max_length],
             write_pos: 0,
             max_length,
+            tail: TailFade::full(),
+            dot: dot::detect(),
         }
     }
 
@@ -26,11 +47,10 @@ impl FirConvolver {
         // Truncate IR if longer than max
         let truncated_len = ir.len().min(self.max_length);
 
-        // Copy and truncate
-        self.coefficients = ir[..truncated_len].to_vec();
+        self.reversed = ir[..truncated_len].iter().rev().copied().collect();
 
-        // Resize input buffer to match IR length
-        self.input_buffer = vec![0.0; truncated_len];
+        // Resize the history to match the IR length
+        self.history = vec![0.0; 2 * truncated_len];
         self.write_pos = 0;
 
         Ok(())
@@ -38,31 +58,31 @@ impl FirConvolver {
 
     #[inline]
     pub fn process_sample(&mut self, input: f32) -> f32 {
-        if self.coefficients.is_empty() {
+        let len = self.reversed.len();
+        if len == 0 {
             return input;
         }
 
-        let len = self.coefficients.len();
+        let pos = self.write_pos;
+        self.history[pos] = input;
+        self.history[pos + len] = input;
+        self.write_pos = if pos + 1 == len { 0 } else { pos + 1 };
 
-        // Write current sample
-        self.input_buffer[self.write_pos] = input;
+        // Oldest input first, this one last: the first `tail_len` entries
+        // meet the taps past the head.
+        let window = &self.history[pos + 1..=pos + len];
+        let tail_len = len.saturating_sub(HEAD_LEN);
+        let (tail_x, head_x) = window.split_at(tail_len);
+        let (tail_h, head_h) = self.reversed.split_at(tail_len);
 
-        let mut output = 0.0;
+        let mut output = (self.dot)(head_x, head_h);
 
-        let mut idx = self.write_pos;
-
-        for &coeff in &self.coefficients {
-            output = self.input_buffer[idx].mul_add(coeff, output);
-            if idx == 0 {
-                idx = len - 1;
-            } else {
-                idx -= 1;
-            }
+        // The history keeps every input either way, so the tail can fade
+        // back in without catching up.
+        if tail_len > 0 && !self.tail.is_silent() {
+            output = (self.dot)(tail_x, tail_h).mul_add(self.tail.next(), output);
         }
 
-        // Advance write position
-        self.write_pos = (self.write_pos + 1) % len;
-
         output
     }
 
@@ -73,13 +93,23 @@ impl FirConvolver {
     }
 
     pub fn reset(&mut self) {
-        self.input_buffer.fill(0.0);
+        self.history.fill(0.0);
         self.write_pos = 0;
     }
 
+    /// Play only the first [`HEAD_LEN`] taps, or all of them again.
+    pub fn set_head_only(&mut self, head_only: bool, fade_samples: usize) {
+        self.tail.set(!head_only, fade_samples);
+    }
+
     /// Returns the current IR length
     pub const fn ir_length(&self) -> usize {
-        self.coefficients.len()
+        self.reversed.len()
+    }
+
+    /// Bytes held in the coefficients and the input history.
+    pub const fn memory_bytes(&self) -> usize {
+        (self.reversed.capacity() + self.history.capacity()) * size_of::<f32>()
     }
 }
 
@@ -116,6 +146,35 @@ mod tests {
         assert_eq!(conv.ir_length(), 100);
     }
 
+    #[test]
+    fn test_fir_is_capped_to_short_irs() {
+        let mut conv = FirConvolver::new(48_000);
+        conv.set_ir(&[0.1; 4_096]).unwrap();
+        assert_eq!(conv.ir_length(), MAX_FIR_TAPS);
+    }
+
+    #[test]
+    fn test_fir_history_wraps() {
+        // Several times round the history, with the IR's last tap hit.
+        let ir = [0.5, 0.0, 0.0, 0.25];
+        let mut conv = FirConvolver::new(16);
+        conv.set_ir(&ir).unwrap();
+
+        let input: Vec<f32> = (0..19).map(|i| i as f32).collect();
+        for (n, &x) in input.iter().enumerate() {
+            let expected = ir
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k <= n)
+                .map(|(k, &h)| h * input[n - k])
+                .sum::<f32>();
+            assert!(
+                (conv.process_sample(x) - expected).abs() < 1e-5,
+                "sample {n}"
+            );
+        }
+    }
+
     #[test]
     fn test_fir_reset() {
         let mut conv = FirConvolver::new(1024);
@@ -133,6 +192,33 @@ mod tests {
         assert!((y - 0.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_fir_head_only_fades_the_tail() {
+        let mut ir = vec![0.0; HEAD_LEN + 1];
+        ir[0] = 1.0;
+        ir[HEAD_LEN] = 0.5;
+        let mut conv = FirConvolver::new(1024);
+        conv.set_ir(&ir).unwrap();
+
+        let echo = |conv: &mut FirConvolver| {
+            conv.process_sample(1.0);
+            (0..HEAD_LEN)
+                .map(|_| conv.process_sample(0.0))
+                .last()
+                .unwrap()
+        };
+
+        conv.set_head_only(true, 4 * (HEAD_LEN + 1));
+        let fading = echo(&mut conv);
+        assert!(fading > 0.0 && fading < 0.5, "tail echo {fading}");
+
+        conv.set_head_only(true, 0);
+        assert_eq!(echo(&mut conv), 0.0);
+
+        conv.set_head_only(false, 0);
+        assert!((echo(&mut conv) - 0.5).abs() < 1e-6);
+    }
+
     #[test]
     fn test_fir_block_processing() {
         let mut conv = FirConvolver::new(1024);
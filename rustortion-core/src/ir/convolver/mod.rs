@@ -1,11 +1,62 @@
+pub mod cmac;
+pub mod dot;
 pub mod fft;
 pub mod fir;
 
 pub use fft::TwoStageConvolver;
-pub use fir::FirConvolver;
+pub use fir::{FirConvolver, MAX_FIR_TAPS};
 
 use anyhow::Result;
 
+/// Taps of an IR played with no latency, and all that's left of it while the
+/// engine plays IRs head-only.
+pub const HEAD_LEN: usize = 256;
+
+/// Level of an IR's tail, ramped rather than cut when the engine drops the
+/// tail to save CPU or brings it back.
+#[derive(Debug, Clone, Copy)]
+struct TailFade {
+    gain: f32,
+    target: f32,
+    step: f32,
+}
+
+impl TailFade {
+    const fn full() -> Self {
+        Self {
+            gain: 1.0,
+            target: 1.0,
+            step: 1.0,
+        }
+    }
+
+    /// Fade the tail in or out over `fade_samples`; at once if zero.
+    fn set(&mut self, on: bool, fade_samples: usize) {
+        self.target = if on { 1.0 } else { 0.0 };
+        if fade_samples == 0 {
+            self.gain = self.target;
+        } else {
+            self.step = 1.0 / fade_samples as f32;
+        }
+    }
+
+    /// Faded out and staying out, so the tail needn't run at all.
+    fn is_silent(&self) -> bool {
+        self.gain == 0.0 && self.target == 0.0
+    }
+
+    /// Gain for the next sample.
+    #[inline]
+    fn next(&mut self) -> f32 {
+        if self.gain < self.target {
+            self.gain = (self.gain + self.step).min(self.target);
+        } else if self.gain > self.target {
+            self.gain = (self.gain - self.step).max(self.target);
+        }
+        self.gain
+    }
+}
+
 /// Convolver implementation selector
 /// Ignore Clippy warning here, we are avoiding any dynamic dispatches using a box by using an enum, at the cost of some memory.
 #[allow(clippy::large_enum_variant)]
@@ -23,6 +74,17 @@ impl Convolver {
         Self::TwoStage(TwoStageConvolver::new())
     }
 
+    /// The cheaper convolver for an IR of `ir_len` taps: the FIR up to
+    /// [`MAX_FIR_TAPS`], the two-stage convolver past it. Neither adds
+    /// latency, so the choice is down to CPU alone.
+    pub fn new_auto(ir_len: usize) -> Self {
+        if ir_len <= MAX_FIR_TAPS {
+            Self::new_fir(ir_len)
+        } else {
+            Self::new_two_stage()
+        }
+    }
+
     pub fn set_ir(&mut self, ir: &[f32]) -> Result<()> {
         match self {
             Self::Fir(c) => c.set_ir(ir),
@@ -51,4 +113,133 @@ impl Convolver {
             Self::TwoStage(c) => c.reset(),
         }
     }
+
+    /// Play only the first [`HEAD_LEN`] taps, fading the rest out over
+    /// `fade_samples`, or fade the rest back in. Allocation-free.
+    pub fn set_head_only(&mut self, head_only: bool, fade_samples: usize) {
+        match self {
+            Self::Fir(c) => c.set_head_only(head_only, fade_samples),
+            Self::TwoStage(c) => c.set_head_only(head_only, fade_samples),
+        }
+    }
+
+    /// Length of the loaded IR in samples.
+    pub const fn ir_length(&self) -> usize {
+        match self {
+            Self::Fir(c) => c.ir_length(),
+            Self::TwoStage(c) => c.ir_length(),
+        }
+    }
+
+    /// Delay before the IR's first tap is heard. None for either: the FIR
+    /// plays every tap directly, and the two-stage convolver plays the head
+    /// directly while the tail's partition is still filling.
+    pub const fn latency_samples(&self) -> usize {
+        match self {
+            Self::Fir(_) | Self::TwoStage(_) => 0,
+        }
+    }
+
+    /// Bytes of coefficients, partitions and history the convolver holds,
+    /// for the IR memory budget.
+    pub fn memory_bytes(&self) -> usize {
+        match self {
+            Self::Fir(c) => c.memory_bytes(),
+            Self::TwoStage(c) => c.memory_bytes(),
+        }
+    }
+
+    /// Number of FFT partitions used for the IR tail (always 0 for FIR).
+    pub const fn num_partitions(&self) -> usize {
+        match self {
+            Self::Fir(_) => 0,
+            Self::TwoStage(c) => c.num_tail_partitions(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::noise;
+
+    /// A noise burst decaying over its whole length, like a cabinet IR, so
+    /// the taps each stage plays are all heard.
+    fn cabinet_ir(len: usize) -> Vec<f32> {
+        noise(7, len)
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| s * 0.05 * (-4.0 * i as f32 / len as f32).exp())
+            .collect()
+    }
+
+    fn rms_difference(a: &[f32], b: &[f32]) -> f32 {
+        #[allow(clippy::cast_precision_loss)]
+        let len = a.len() as f32;
+        (a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f32>() / len).sqrt()
+    }
+
+    fn render(mut convolver: Convolver, ir: &[f32], input: &[f32]) -> Vec<f32> {
+        convolver.set_ir(ir).unwrap();
+        let mut output = input.to_vec();
+        // Odd block sizes, so neither lines up with the FFT partitions.
+        for block in output.chunks_mut(100) {
+            convolver.process_block(block);
+        }
+        output
+    }
+
+    /// Straight convolution of `input` with `ir`, the reference both
+    /// convolvers are held to.
+    fn direct(ir: &[f32], input: &[f32]) -> Vec<f32> {
+        (0..input.len())
+            .map(|n| {
+                ir.iter()
+                    .take(n + 1)
+                    .enumerate()
+                    .map(|(k, tap)| tap * input[n - k])
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fir_and_two_stage_agree() {
+        let input = noise(1, 4_800);
+        // Head only, the whole direct head, and the head plus one FFT
+        // partition and part of the next; only the first two fit the FIR.
+        for ir_len in [HEAD_LEN, MAX_FIR_TAPS, 2 * MAX_FIR_TAPS + 100] {
+            let ir = cabinet_ir(ir_len);
+            let reference = direct(&ir, &input);
+            let two_stage = render(Convolver::new_two_stage(), &ir, &input);
+            let rms = rms_difference(&reference, &two_stage);
+            assert!(rms < 1e-5, "two-stage, {ir_len} taps: {rms:e} RMS apart");
+
+            if ir_len <= MAX_FIR_TAPS {
+                let fir = render(Convolver::new_fir(ir_len), &ir, &input);
+                let rms = rms_difference(&fir, &two_stage);
+                assert!(rms < 1e-5, "{ir_len} taps: {rms:e} RMS apart");
+            }
+        }
+    }
+
+    #[test]
+    fn auto_picks_fir_only_for_short_irs() {
+        assert!(matches!(Convolver::new_auto(HEAD_LEN), Convolver::Fir(_)));
+        assert!(matches!(
+            Convolver::new_auto(MAX_FIR_TAPS),
+            Convolver::Fir(_)
+        ));
+        assert!(matches!(
+            Convolver::new_auto(MAX_FIR_TAPS + 1),
+            Convolver::TwoStage(_)
+        ));
+    }
+
+    #[test]
+    fn auto_plays_long_irs_in_full() {
+        let mut convolver = Convolver::new_auto(4_096);
+        convolver.set_ir(&cabinet_ir(4_096)).unwrap();
+        assert_eq!(convolver.ir_length(), 4_096);
+    }
 }
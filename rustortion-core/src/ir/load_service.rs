@@ -1,37 +1,103 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread;
 
-use crossbeam::channel::{Sender, unbounded};
-use log::{debug, error, info};
+use crossbeam::channel::{Receiver, Sender, unbounded};
+use log::{debug, error, info, warn};
 
+use crate::audio::dual_mono::{ChainMode, RightMessage};
 use crate::audio::engine::{EngineHandle, PreparedIr};
+use crate::ir::align::{self, IrAlignment, ONSET_THRESHOLD_DB};
 use crate::ir::cabinet::ConvolverType;
-use crate::ir::convolver::Convolver;
-use crate::ir::loader::IrLoader;
+use crate::ir::compare::ir_energy;
+use crate::ir::convolver::{Convolver, MAX_FIR_TAPS};
+use crate::ir::loader::{IrError, IrLoader};
+use crate::ir::memory::IrMemoryBudget;
 
 enum IrRequest {
-    /// Load an IR and send the built convolver to the engine.
-    Load(String),
-    /// Load an IR into the cache only (no convolver sent).
+    /// Load an IR, shift it by the alignment, and send the built convolver
+    /// to the engine.
+    Load(String, IrAlignment),
+    /// Load an IR into the cache only (no convolver sent), if it fits the
+    /// memory budget.
     Preload(String),
     /// Shut down the background thread.
     Shutdown,
 }
 
+/// A requested IR that couldn't be loaded. The engine keeps the previous one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IrLoadFailure {
+    pub name: String,
+    pub error: IrError,
+}
+
+/// What the service measured of an IR when it loaded it, before alignment.
+#[derive(Debug, Clone, Copy)]
+struct IrMeasurement {
+    onset: Option<usize>,
+    energy: f32,
+}
+
+impl IrMeasurement {
+    fn of(coefficients: &[f32]) -> Self {
+        Self {
+            onset: align::onset(coefficients, ONSET_THRESHOLD_DB),
+            energy: ir_energy(coefficients),
+        }
+    }
+}
+
 /// Handle held by the `Manager` to send IR load requests.
 pub struct IrLoadHandle {
     request_tx: Sender<IrRequest>,
+    failure_rx: Receiver<IrLoadFailure>,
+    /// Every IR loaded or preloaded so far.
+    measurements: Arc<Mutex<HashMap<String, IrMeasurement>>>,
+    /// Loads requested that haven't reached the engine or failed yet.
+    pending: Arc<AtomicUsize>,
     thread: Option<thread::JoinHandle<()>>,
 }
 
 impl IrLoadHandle {
     /// Request loading an IR by name and sending the built convolver to the engine.
-    pub fn request_load(&self, name: &str) {
-        if let Err(e) = self.request_tx.send(IrRequest::Load(name.to_owned())) {
+    pub fn request_load(&self, name: &str, alignment: IrAlignment) {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        if let Err(e) = self
+            .request_tx
+            .send(IrRequest::Load(name.to_owned(), alignment))
+        {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
             error!("Failed to send IR load request: {e}");
         }
     }
 
+    /// Whether a requested IR hasn't been sent to the engine, or failed,
+    /// yet. Until then the engine plays the previous one.
+    pub fn is_loading(&self) -> bool {
+        self.pending.load(Ordering::Acquire) > 0
+    }
+
+    /// Onset of a loaded IR, in samples from the start of its trimmed
+    /// coefficients. `None` until it's loaded, or if it's silent.
+    pub fn onset(&self, name: &str) -> Option<usize> {
+        self.measurements.lock().ok()?.get(name)?.onset
+    }
+
+    /// Broadband gain of a loaded or preloaded IR, for level matching.
+    /// `None` until it's loaded.
+    pub fn energy(&self, name: &str) -> Option<f32> {
+        Some(self.measurements.lock().ok()?.get(name)?.energy)
+    }
+
+    /// The oldest load that failed since the last call. Failed preloads
+    /// aren't reported, nothing was waiting on them, except ones the memory
+    /// budget refused.
+    pub fn take_failure(&self) -> Option<IrLoadFailure> {
+        self.failure_rx.try_recv().ok()
+    }
+
     /// Preload IR coefficients into the cache without sending to the engine.
     pub fn preload(&self, name: &str) {
         if let Err(e) = self.request_tx.send(IrRequest::Preload(name.to_owned())) {
@@ -69,18 +135,32 @@ fn trim_silence(ir: &[f32]) -> &[f32] {
     &ir[start..end]
 }
 
-/// Build a `Convolver` from IR coefficients.
+/// Build a `Convolver` from IR coefficients, shifted and flipped by
+/// `alignment`. A delayed IR is cut back to `max_ir_samples`. An IR too long
+/// for the FIR gets the two-stage convolver even when the FIR was asked for.
 fn build_convolver(
     coefficients: &[f32],
+    alignment: IrAlignment,
     convolver_type: ConvolverType,
     max_ir_samples: usize,
 ) -> Convolver {
+    let mut aligned = align::apply(coefficients, alignment);
+    aligned.truncate(max_ir_samples);
+
     let mut convolver = match convolver_type {
+        ConvolverType::Auto => Convolver::new_auto(aligned.len()),
+        // The FIR would cut the IR to its first `MAX_FIR_TAPS` taps.
+        ConvolverType::Fir if aligned.len() > MAX_FIR_TAPS => {
+            warn!(
+                "{}-sample IR is longer than the FIR convolver's {MAX_FIR_TAPS} taps, using the two-stage one",
+                aligned.len()
+            );
+            Convolver::new_two_stage()
+        }
         ConvolverType::Fir => Convolver::new_fir(max_ir_samples),
         ConvolverType::TwoStage => Convolver::new_two_stage(),
     };
-
-    if let Err(e) = convolver.set_ir(coefficients) {
+    if let Err(e) = convolver.set_ir(&aligned) {
         error!("Failed to set IR on convolver: {e}");
     }
 
@@ -91,7 +171,8 @@ fn build_convolver(
 ///
 /// The service receives IR load requests, loads/resamples WAV files via `IrLoader`,
 /// caches the coefficients, builds a `Convolver`, and sends it to the engine as an
-/// `EngineMessage::SwapIrConvolver`.
+/// `EngineMessage::SwapIrConvolver`. The cache and the playing IR's convolvers
+/// are kept within `memory_mb`.
 ///
 pub fn spawn(
     ir_loader: IrLoader,
@@ -99,49 +180,101 @@ pub fn spawn(
     sample_rate: usize,
     max_ir_ms: usize,
     convolver_type: ConvolverType,
+    memory_mb: usize,
 ) -> IrLoadHandle {
     let (request_tx, request_rx) = unbounded::<IrRequest>();
+    let (failure_tx, failure_rx) = unbounded::<IrLoadFailure>();
+    let measurements = Arc::new(Mutex::new(HashMap::new()));
+    let service_measurements = Arc::clone(&measurements);
+    let pending = Arc::new(AtomicUsize::new(0));
+    let service_pending = Arc::clone(&pending);
     let max_ir_samples = (sample_rate * max_ir_ms) / 1000;
 
     let thread = thread::Builder::new()
         .name("ir-load-service".into())
         .spawn(move || {
-            let mut cache: HashMap<String, Vec<f32>> = HashMap::new();
+            let mut cache = IrMemoryBudget::with_megabytes(memory_mb);
+            let mut backlog: VecDeque<IrRequest> = VecDeque::new();
 
-            while let Ok(request) = request_rx.recv() {
+            while let Some(request) = backlog.pop_front().or_else(|| request_rx.recv().ok()) {
                 match request {
-                    IrRequest::Load(name) => {
-                        if !cache.contains_key(&name)
-                            && !load_and_cache(
-                                &ir_loader,
-                                &name,
-                                max_ir_samples,
-                                sample_rate,
-                                &mut cache,
-                            )
-                        {
-                            continue;
+                    IrRequest::Load(name, alignment) => {
+                        let (name, alignment, loads) =
+                            latest_load(name, alignment, &request_rx, &mut backlog);
+                        if !cache.contains(&name) {
+                            match load_coefficients(&ir_loader, &name, max_ir_samples, sample_rate)
+                            {
+                                Ok(coefficients) => cache.insert(&name, coefficients),
+                                Err(error) => {
+                                    let _ = failure_tx.send(IrLoadFailure { name, error });
+                                    service_pending.fetch_sub(loads, Ordering::AcqRel);
+                                    continue;
+                                }
+                            }
                         }
 
                         let coefficients = cache.get(&name).unwrap();
-                        let convolver =
-                            build_convolver(coefficients, convolver_type, max_ir_samples);
+                        if let Ok(mut measurements) = service_measurements.lock() {
+                            measurements.insert(name.clone(), IrMeasurement::of(coefficients));
+                        }
+                        let convolver = build_convolver(
+                            coefficients,
+                            alignment,
+                            convolver_type,
+                            max_ir_samples,
+                        );
+                        let mut convolver_bytes = convolver.memory_bytes();
                         let prepared = PreparedIr {
                             name: name.clone(),
                             convolver: Box::new(convolver),
                         };
 
                         engine_handle.swap_ir_convolver(prepared);
+                        if engine_handle.chain_mode() == ChainMode::DualMono {
+                            // The right channel's cabinet needs its own
+                            // convolver: they keep history.
+                            let convolver = build_convolver(
+                                coefficients,
+                                alignment,
+                                convolver_type,
+                                max_ir_samples,
+                            );
+                            convolver_bytes += convolver.memory_bytes();
+                            engine_handle.send_right(RightMessage::SwapIrConvolver(Box::new(
+                                PreparedIr {
+                                    name: name.clone(),
+                                    convolver: Box::new(convolver),
+                                },
+                            )));
+                        }
 
+                        cache.set_active(&name, convolver_bytes);
+                        service_pending.fetch_sub(loads, Ordering::AcqRel);
                         debug!("IR '{name}' loaded and sent to engine");
                     }
                     IrRequest::Preload(name) => {
-                        if cache.contains_key(&name) {
+                        if cache.contains(&name) {
                             debug!("IR '{name}' already cached, skipping preload");
                             continue;
                         }
-                        load_and_cache(&ir_loader, &name, max_ir_samples, sample_rate, &mut cache);
-                        debug!("IR '{name}' preloaded into cache");
+                        match preload(&ir_loader, &name, max_ir_samples, sample_rate, &mut cache) {
+                            Ok(()) => {
+                                // Measured now so a comparison can level-match
+                                // it before it's ever played.
+                                if let Some(coefficients) = cache.get(&name)
+                                    && let Ok(mut measurements) = service_measurements.lock()
+                                {
+                                    measurements
+                                        .insert(name.clone(), IrMeasurement::of(coefficients));
+                                }
+                                debug!("IR '{name}' preloaded into cache");
+                            }
+                            Err(error @ IrError::OverBudget { .. }) => {
+                                warn!("Not preloading IR '{name}': {error}");
+                                let _ = failure_tx.send(IrLoadFailure { name, error });
+                            }
+                            Err(_) => {}
+                        }
                     }
                     IrRequest::Shutdown => {
                         debug!("IR load service shutting down");
@@ -154,19 +287,61 @@ pub fn spawn(
 
     IrLoadHandle {
         request_tx,
+        failure_rx,
+        measurements,
+        pending,
         thread: Some(thread),
     }
 }
 
-/// Load an IR by name, process it (truncate, trim silence), and insert into the cache.
-/// Returns `true` on success.
-fn load_and_cache(
+/// Skip loads superseded by a newer one already queued, e.g. while stepping
+/// through IRs with a held key. Other requests are kept, in order, in `backlog`.
+/// Also returns how many load requests the result stands for.
+fn latest_load(
+    mut name: String,
+    mut alignment: IrAlignment,
+    request_rx: &Receiver<IrRequest>,
+    backlog: &mut VecDeque<IrRequest>,
+) -> (String, IrAlignment, usize) {
+    let mut loads = 1;
+    for request in request_rx.try_iter() {
+        match request {
+            IrRequest::Load(newer, newer_alignment) => {
+                debug!("Skipping stale IR load '{name}'");
+                name = newer;
+                alignment = newer_alignment;
+                loads += 1;
+            }
+            other => backlog.push_back(other),
+        }
+    }
+    (name, alignment, loads)
+}
+
+/// Load an IR into the cache if it fits the budget. Its header gives the
+/// size up front, so one that can't fit isn't decoded at all.
+fn preload(
+    loader: &IrLoader,
+    name: &str,
+    max_ir_samples: usize,
+    sample_rate: usize,
+    cache: &mut IrMemoryBudget,
+) -> Result<(), IrError> {
+    if let Some(header) = loader.header(name) {
+        let samples = header.samples_at(sample_rate).min(max_ir_samples);
+        cache.check(samples * size_of::<f32>())?;
+    }
+    let coefficients = load_coefficients(loader, name, max_ir_samples, sample_rate)?;
+    cache.try_insert(name, coefficients)
+}
+
+/// Load an IR by name and process it (truncate, trim silence).
+fn load_coefficients(
     loader: &IrLoader,
     name: &str,
     max_ir_samples: usize,
     sample_rate: usize,
-    cache: &mut HashMap<String, Vec<f32>>,
-) -> bool {
+) -> Result<Vec<f32>, IrError> {
     match loader.load_by_name(name) {
         Ok(mut samples) => {
             let original_len = samples.len();
@@ -189,20 +364,61 @@ fn load_and_cache(
                 trimmed.len() as f32 / sample_rate as f32 * 1000.0
             );
 
-            cache.insert(name.to_owned(), trimmed.to_vec());
-            true
+            Ok(trimmed.to_vec())
         }
         Err(e) => {
             error!("Failed to load IR '{name}': {e}");
-            false
+            Err(e)
         }
     }
 }
 
+/// Load an IR by name and build its convolver synchronously, exactly as the
+/// service would. For callers that need the cab immediately (offline rendering).
+pub fn prepare_convolver(
+    loader: &IrLoader,
+    name: &str,
+    alignment: IrAlignment,
+    sample_rate: usize,
+    max_ir_ms: usize,
+    convolver_type: ConvolverType,
+) -> Option<Convolver> {
+    let max_ir_samples = (sample_rate * max_ir_ms) / 1000;
+    let coefficients = load_coefficients(loader, name, max_ir_samples, sample_rate).ok()?;
+    Some(build_convolver(
+        &coefficients,
+        alignment,
+        convolver_type,
+        max_ir_samples,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_latest_load_skips_stale_requests() {
+        let (tx, rx) = unbounded();
+        let flipped = IrAlignment {
+            offset: -3,
+            inverted: true,
+        };
+        tx.send(IrRequest::Load("b".into(), IrAlignment::default()))
+            .unwrap();
+        tx.send(IrRequest::Preload("p".into())).unwrap();
+        tx.send(IrRequest::Load("c".into(), flipped)).unwrap();
+
+        let mut backlog = VecDeque::new();
+        assert_eq!(
+            latest_load("a".into(), IrAlignment::default(), &rx, &mut backlog),
+            ("c".to_string(), flipped, 3)
+        );
+        assert!(matches!(backlog.pop_front(), Some(IrRequest::Preload(name)) if name == "p"));
+        assert!(backlog.is_empty());
+        assert!(rx.is_empty());
+    }
+
     #[test]
     fn test_trim_silence_removes_leading_trailing() {
         let ir = vec![0.0, 0.0, 1.0, 0.5, 0.0, 0.0];
@@ -227,11 +443,64 @@ mod tests {
     #[test]
     fn test_build_convolver_fir() {
         let coefficients = vec![1.0, 0.5, 0.25];
-        let mut convolver = build_convolver(&coefficients, ConvolverType::Fir, 1024);
+        let mut convolver = build_convolver(
+            &coefficients,
+            IrAlignment::default(),
+            ConvolverType::Fir,
+            1024,
+        );
         // Verify it processes correctly (impulse response)
         let y0 = convolver.process_sample(1.0);
         let y1 = convolver.process_sample(0.0);
         assert!((y0 - 1.0).abs() < 1e-6);
         assert!((y1 - 0.5).abs() < 1e-6);
     }
+
+    #[test]
+    fn test_build_convolver_auto_sizes_to_the_ir() {
+        let short = build_convolver(
+            &[0.1; 64],
+            IrAlignment::default(),
+            ConvolverType::Auto,
+            2_400,
+        );
+        assert!(matches!(short, Convolver::Fir(_)));
+
+        let long = build_convolver(
+            &[0.1; 4_096],
+            IrAlignment::default(),
+            ConvolverType::Auto,
+            2_400,
+        );
+        assert!(matches!(long, Convolver::TwoStage(_)));
+        assert_eq!(long.ir_length(), 2_400);
+    }
+
+    #[test]
+    fn test_build_convolver_fir_falls_back_for_long_irs() {
+        let long = build_convolver(
+            &[0.1; 4_096],
+            IrAlignment::default(),
+            ConvolverType::Fir,
+            2_400,
+        );
+        assert!(matches!(long, Convolver::TwoStage(_)));
+        assert_eq!(long.ir_length(), 2_400);
+    }
+
+    #[test]
+    fn test_build_convolver_applies_alignment() {
+        let coefficients = vec![1.0, 0.5, 0.25];
+        let alignment = IrAlignment {
+            offset: 1,
+            inverted: true,
+        };
+        let mut convolver = build_convolver(&coefficients, alignment, ConvolverType::Fir, 3);
+        let out: Vec<f32> = [1.0, 0.0, 0.0, 0.0]
+            .iter()
+            .map(|&x| convolver.process_sample(x))
+            .collect();
+        // Delayed a sample, flipped, and cut back to the three-sample limit.
+        assert_eq!(out, [0.0, -1.0, -0.5, 0.0]);
+    }
 }
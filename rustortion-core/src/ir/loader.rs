@@ -1,8 +1,12 @@
 use anyhow::{Context, Result, anyhow};
-use hound::WavReader;
+use hound::{WavReader, WavSpec};
 use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use rubato::audioadapter_buffers::direct::SequentialSliceOfVecs;
 use rubato::{
@@ -10,20 +14,123 @@ use rubato::{
     WindowFunction,
 };
 
-const MAX_IR_LENGTH_SECONDS: u64 = 5;
+/// Default cap on IR length after resampling. Cab IRs are tens of
+/// milliseconds; a file much longer than this is a room or a stray song.
+pub const DEFAULT_MAX_IR_SECONDS: f32 = 1.0;
+/// Largest cap [`IrLoader::with_max_seconds`] accepts.
+pub const MAX_IR_SECONDS_LIMIT: f32 = 5.0;
+
+/// Why an IR file can't be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IrError {
+    NotFound(String),
+    /// Longer than the loader's cap, both counted at the target sample rate.
+    TooLong {
+        samples: usize,
+        max: usize,
+    },
+    /// Only mono and stereo IRs are mixed down.
+    UnsupportedChannels(u16),
+    /// Not a WAV file `hound` can read, or the samples couldn't be resampled.
+    Decode(String),
+    /// Caching it would take the IR memory budget over its cap, in bytes.
+    OverBudget {
+        needed: usize,
+        available: usize,
+    },
+}
+
+impl fmt::Display for IrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "IR '{name}' not found"),
+            Self::TooLong { samples, max } => {
+                write!(f, "IR is too long: {samples} samples (max {max})")
+            }
+            Self::UnsupportedChannels(channels) => {
+                write!(f, "IR has {channels} channels (mono or stereo only)")
+            }
+            Self::Decode(reason) => write!(f, "IR could not be read: {reason}"),
+            Self::OverBudget { needed, available } => write!(
+                f,
+                "IR memory budget is full: needs {:.1} MB, {:.1} MB left",
+                *needed as f64 / MEGABYTE,
+                *available as f64 / MEGABYTE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IrError {}
+
+const MEGABYTE: f64 = 1024.0 * 1024.0;
+
+/// What a WAV header says about an IR, read without decoding its samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IrHeader {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub frames: u32,
+}
+
+impl IrHeader {
+    fn of(spec: WavSpec, frames: u32) -> Self {
+        Self {
+            channels: spec.channels,
+            sample_rate: spec.sample_rate,
+            frames,
+        }
+    }
+
+    /// Playing time of the file. Zero for a file with no sample rate.
+    pub fn length(&self) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(f64::from(self.frames) / f64::from(self.sample_rate))
+    }
+
+    /// Mono samples once resampled to `sample_rate`, which is what decoding
+    /// it keeps in memory.
+    pub fn samples_at(&self, sample_rate: usize) -> usize {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        (u64::from(self.frames) * sample_rate as u64 / u64::from(self.sample_rate)) as usize
+    }
+}
 
 pub struct IrLoader {
     available_ir_paths: Vec<(String, PathBuf)>,
+    /// Files the last scan found unusable from their header alone.
+    flagged: HashMap<String, IrError>,
+    /// Headers the last scan read, for every file that has one.
+    headers: HashMap<String, IrHeader>,
     ir_directory: PathBuf,
     target_sample_rate: usize,
+    max_samples: usize,
 }
 
 impl IrLoader {
     pub fn new(directory: &Path, target_sample_rate: usize) -> Result<Self> {
+        Self::with_max_seconds(directory, target_sample_rate, DEFAULT_MAX_IR_SECONDS)
+    }
+
+    /// Like [`Self::new`], rejecting IRs longer than `max_seconds` (clamped to
+    /// [`MAX_IR_SECONDS_LIMIT`]) after resampling.
+    pub fn with_max_seconds(
+        directory: &Path,
+        target_sample_rate: usize,
+        max_seconds: f32,
+    ) -> Result<Self> {
+        let max_seconds = max_seconds.clamp(0.0, MAX_IR_SECONDS_LIMIT);
         let mut loader = Self {
             available_ir_paths: Vec::new(),
+            flagged: HashMap::new(),
+            headers: HashMap::new(),
             ir_directory: directory.to_path_buf(),
             target_sample_rate,
+            max_samples: (max_seconds * target_sample_rate as f32) as usize,
         };
 
         loader.scan_ir_directory()?;
@@ -36,17 +143,48 @@ impl IrLoader {
             return Err(anyhow!("available_ir_paths is empty"));
         }
 
-        self.load_ir(&self.available_ir_paths[0].1)
+        Ok(self.load_ir(&self.available_ir_paths[0].1)?)
     }
 
-    pub fn load_by_name(&self, name: &str) -> Result<Vec<f32>> {
+    pub fn load_by_name(&self, name: &str) -> Result<Vec<f32>, IrError> {
         for (ir_name, ir_path) in &self.available_ir_paths {
             if ir_name == name {
                 return self.load_ir(ir_path);
             }
         }
 
-        Err(anyhow!("ir name '{name}' not found"))
+        Err(IrError::NotFound(name.to_owned()))
+    }
+
+    /// Whether `name` is worth sending to the load service, judged from the
+    /// last scan without reading the file again.
+    pub fn check(&self, name: &str) -> Result<(), IrError> {
+        if !self
+            .available_ir_paths
+            .iter()
+            .any(|(ir_name, _)| ir_name == name)
+        {
+            return Err(IrError::NotFound(name.to_owned()));
+        }
+        self.flagged.get(name).map_or(Ok(()), |e| Err(e.clone()))
+    }
+
+    /// Where the file for `name` was found by the last scan.
+    pub fn path_of(&self, name: &str) -> Option<&Path> {
+        self.available_ir_paths
+            .iter()
+            .find(|(ir_name, _)| ir_name == name)
+            .map(|(_, path)| path.as_path())
+    }
+
+    /// The header the last scan read for `name`.
+    pub fn header(&self, name: &str) -> Option<IrHeader> {
+        self.headers.get(name).copied()
+    }
+
+    /// Names the last scan flagged, with the reason, for a warning in the picker.
+    pub fn flagged_irs(&self) -> &HashMap<String, IrError> {
+        &self.flagged
     }
 
     // available ir names returns a string list of impulse response names
@@ -57,57 +195,69 @@ impl IrLoader {
             .collect()
     }
 
-    pub fn load_ir(&self, path: &Path) -> Result<Vec<f32>> {
-        let reader = WavReader::open(path).context("Failed to open WAV file")?;
+    /// Every available IR with the tags its file name carries, and its
+    /// length and channels from the header.
+    pub fn available_irs_with_metadata(&self, vocabulary: &IrTagVocabulary) -> Vec<IrMetadata> {
+        let matcher = TagMatcher::new(vocabulary);
+        self.available_ir_paths
+            .iter()
+            .map(|(name, _)| {
+                let mut meta = IrMetadata::parse_with(name, &matcher);
+                if let Some(header) = self.headers.get(name) {
+                    meta.length = Some(header.length());
+                    meta.channels = Some(header.channels);
+                }
+                meta
+            })
+            .collect()
+    }
+
+    pub fn load_ir(&self, path: &Path) -> Result<Vec<f32>, IrError> {
+        let reader = WavReader::open(path).map_err(decode_error)?;
         self.decode_wav_reader(reader)
     }
 
-    pub fn load_ir_from_bytes(&self, bytes: &[u8]) -> Result<Vec<f32>> {
+    pub fn load_ir_from_bytes(&self, bytes: &[u8]) -> Result<Vec<f32>, IrError> {
         let cursor = std::io::Cursor::new(bytes);
-        let reader = WavReader::new(cursor).context("Failed to read WAV from bytes")?;
+        let reader = WavReader::new(cursor).map_err(decode_error)?;
         self.decode_wav_reader(reader)
     }
 
-    fn decode_wav_reader<R: std::io::Read>(&self, reader: WavReader<R>) -> Result<Vec<f32>> {
-        let spec = reader.spec();
+    /// Reject a file from its header alone, before anything is decoded.
+    fn check_header(&self, header: IrHeader) -> Result<(), IrError> {
+        if header.channels == 0 || header.channels > 2 {
+            return Err(IrError::UnsupportedChannels(header.channels));
+        }
+        if header.sample_rate == 0 {
+            return Err(IrError::Decode("sample rate is 0".to_owned()));
+        }
 
-        if reader.duration() as u64 > spec.sample_rate as u64 * MAX_IR_LENGTH_SECONDS {
-            return Err(anyhow::anyhow!(
-                "Failed to load IR as the IR is too long: {} seconds (max {}).",
-                reader.duration() as f64 / spec.sample_rate as f64,
-                MAX_IR_LENGTH_SECONDS
-            ));
+        let samples = header.samples_at(self.target_sample_rate);
+        if samples > self.max_samples {
+            return Err(IrError::TooLong {
+                samples,
+                max: self.max_samples,
+            });
         }
+        Ok(())
+    }
 
-        let samples: Vec<f32> = if spec.sample_format == hound::SampleFormat::Float {
-            reader
-                .into_samples::<f32>()
-                .collect::<Result<Vec<_>, _>>()
-                .context("Failed to read float samples")?
-        } else {
-            let max_val = (1 << (spec.bits_per_sample - 1)) as f32;
-            reader
-                .into_samples::<i32>()
-                .map(|s| s.map(|v| v as f32 / max_val))
-                .collect::<Result<Vec<_>, _>>()
-                .context("Failed to read integer samples")?
-        };
+    fn decode_wav_reader<R: std::io::Read>(
+        &self,
+        reader: WavReader<R>,
+    ) -> Result<Vec<f32>, IrError> {
+        let spec = reader.spec();
+        self.check_header(IrHeader::of(spec, reader.duration()))?;
 
-        let mono = if spec.channels > 1 {
-            samples
-                .chunks(spec.channels as usize)
-                .map(|c| c.iter().sum::<f32>() / spec.channels as f32)
-                .collect()
-        } else {
-            samples
-        };
+        let mono = read_ir_mono(reader, usize::MAX)?;
 
         let mut resampled = if spec.sample_rate != self.target_sample_rate as u32 {
             debug!(
                 "Resampling IR from {} Hz to {} Hz",
                 spec.sample_rate, self.target_sample_rate
             );
-            resample(&mono, spec.sample_rate, self.target_sample_rate as u32)?
+            resample(&mono, spec.sample_rate, self.target_sample_rate as u32)
+                .map_err(|e| IrError::Decode(format!("resampling failed: {e}")))?
         } else {
             mono
         };
@@ -133,6 +283,8 @@ impl IrLoader {
         }
 
         self.available_ir_paths.clear();
+        self.flagged.clear();
+        self.headers.clear();
         let base = self.ir_directory.clone();
         self.scan_recursive(&base, &base)?;
 
@@ -163,6 +315,19 @@ impl IrLoader {
                     .to_string_lossy()
                     .replace('\\', "/");
 
+                // Header only: a stray song is flagged without decoding it.
+                let header = WavReader::open(&path)
+                    .map(|reader| IrHeader::of(reader.spec(), reader.duration()))
+                    .map_err(decode_error);
+                if let Ok(header) = header {
+                    self.headers.insert(relative_path.clone(), header);
+                }
+                let header = header.and_then(|header| self.check_header(header));
+                if let Err(e) = header {
+                    warn!("IR '{relative_path}' will be rejected: {e}");
+                    self.flagged.insert(relative_path.clone(), e);
+                }
+
                 self.available_ir_paths.push((relative_path, path));
             }
         }
@@ -170,8 +335,357 @@ impl IrLoader {
     }
 }
 
-/// resample takes input samples at a given sample_rate and returns them in the target sample_rate
-fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
+pub(crate) fn decode_error(e: hound::Error) -> IrError {
+    IrError::Decode(e.to_string())
+}
+
+/// Up to `max_frames` frames of `reader`, scaled to ±1 and averaged down to
+/// mono. Every WAV the crate reads is decoded the same way, so IRs, DI
+/// tracks and metronome clicks agree on what full scale is.
+pub(crate) fn read_mono<R: std::io::Read>(
+    reader: WavReader<R>,
+    max_frames: usize,
+) -> Result<Vec<f32>, IrError> {
+    read_mono_scaled(reader, max_frames, false)
+}
+
+/// [`read_mono`] for an IR, which also takes a 32-bit file that never leaves
+/// the 24-bit range as padded 24-bit audio; see [`int_full_scale`].
+pub(crate) fn read_ir_mono<R: std::io::Read>(
+    reader: WavReader<R>,
+    max_frames: usize,
+) -> Result<Vec<f32>, IrError> {
+    read_mono_scaled(reader, max_frames, true)
+}
+
+fn read_mono_scaled<R: std::io::Read>(
+    reader: WavReader<R>,
+    max_frames: usize,
+    is_ir: bool,
+) -> Result<Vec<f32>, IrError> {
+    let channels = usize::from(reader.spec().channels.max(1));
+    let samples = read_samples(reader, max_frames, is_ir).map_err(decode_error)?;
+
+    Ok(if channels > 1 {
+        samples
+            .chunks(channels)
+            .map(|c| c.iter().sum::<f32>() / channels as f32)
+            .collect()
+    } else {
+        samples
+    })
+}
+
+/// `reader` as its left and right channels, scaled to ±1. A mono file is
+/// both; past two channels, the rest are dropped.
+pub(crate) fn read_stereo<R: std::io::Read>(
+    reader: WavReader<R>,
+) -> Result<[Vec<f32>; 2], IrError> {
+    let channels = usize::from(reader.spec().channels.max(1));
+    let samples = read_samples(reader, usize::MAX, false).map_err(decode_error)?;
+
+    if channels == 1 {
+        return Ok([samples.clone(), samples]);
+    }
+    let frames = samples.chunks_exact(channels);
+    Ok([
+        frames.clone().map(|frame| frame[0]).collect(),
+        frames.map(|frame| frame[1]).collect(),
+    ])
+}
+
+/// Up to `max_frames` interleaved frames of 16, 24 or 32-bit integer or
+/// 32-bit float samples, scaled to ±1. `is_ir` as for [`int_full_scale`].
+fn read_samples<R: std::io::Read>(
+    reader: WavReader<R>,
+    max_frames: usize,
+    is_ir: bool,
+) -> Result<Vec<f32>, hound::Error> {
+    let spec = reader.spec();
+    let max_samples = max_frames.saturating_mul(usize::from(spec.channels));
+
+    match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Float, 32) => {
+            reader.into_samples::<f32>().take(max_samples).collect()
+        }
+        (hound::SampleFormat::Int, 16 | 24 | 32) => {
+            let samples = reader
+                .into_samples::<i32>()
+                .take(max_samples)
+                .collect::<Result<Vec<_>, _>>()?;
+            let full_scale = int_full_scale(spec.bits_per_sample, &samples, is_ir);
+            Ok(samples.iter().map(|&s| s as f32 / full_scale).collect())
+        }
+        _ => Err(hound::Error::Unsupported),
+    }
+}
+
+/// What integer samples of `bits` are divided by to scale them to ±1.
+///
+/// `hound` hands 24-bit samples back as `i32`s in the 24-bit range, whether
+/// the file packs them in three bytes or pads them to four, going by the
+/// valid bits of a `WAVE_FORMAT_EXTENSIBLE` header. Some IR writers instead
+/// label padded 24-bit audio as plain 32-bit; with `is_ir`, a 32-bit file
+/// with nothing past the 24-bit range is taken as one of those, since IRs
+/// are normalized and one that peaks 48 dB down doesn't happen. A DI track
+/// or a take can be that quiet, so anything else is scaled as labelled.
+fn int_full_scale(bits: u16, samples: &[i32], is_ir: bool) -> f32 {
+    let bits = if is_ir
+        && bits == 32
+        && samples.iter().any(|&s| s != 0)
+        && samples.iter().all(|&s| s.unsigned_abs() <= 1 << 23)
+    {
+        debug!("32-bit IR only spans 24 bits, scaling it as 24-bit");
+        24
+    } else {
+        bits
+    };
+    (1_i64 << (bits - 1)) as f32
+}
+
+/// The one IR in `available` whose file stem matches `missing`'s, ignoring
+/// case and directories — e.g. `mesa_v30.wav` found again as
+/// `Cabs/mesa_v30.wav` after the IR folder was reorganized. `None` when there
+/// is no match or more than one, so an ambiguous guess is never made.
+pub fn find_by_stem<'a>(missing: &str, available: &'a [String]) -> Option<&'a str> {
+    let stem = |name: &str| {
+        Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_lowercase())
+    };
+    let wanted = stem(missing)?;
+
+    let mut matches = available
+        .iter()
+        .filter(|name| stem(name).as_deref() == Some(wanted.as_str()));
+    let found = matches.next()?;
+    matches.next().is_none().then_some(found.as_str())
+}
+
+/// Mic models recognized in IR file names unless the user configures others.
+pub const DEFAULT_MIC_TOKENS: &[&str] = &[
+    "SM57", "SM7B", "MD421", "MD441", "e906", "e609", "R121", "R92", "M160", "M201", "M88", "U87",
+    "U47", "TLM103", "C414", "KSM32", "PR30", "i5", "RE20", "Fathead",
+];
+/// Speaker models recognized in IR file names unless the user configures
+/// others. Cabinet sizes such as `4x12` are recognized on their own.
+pub const DEFAULT_SPEAKER_TOKENS: &[&str] = &[
+    "V30",
+    "Vintage 30",
+    "G12M",
+    "G12H",
+    "G12T75",
+    "G12-65",
+    "G12K100",
+    "Greenback",
+    "Creamback",
+    "Alnico Blue",
+    "EVM12L",
+    "P12Q",
+    "P12N",
+    "C12N",
+    "K100",
+    "H30",
+];
+
+/// Token lists the IR name parser looks for. Matching ignores case and
+/// punctuation, so `SM57` also finds `sm-57` and `SM 57`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IrTagVocabulary {
+    pub mics: Vec<String>,
+    pub speakers: Vec<String>,
+}
+
+impl Default for IrTagVocabulary {
+    fn default() -> Self {
+        let owned = |tokens: &[&str]| tokens.iter().map(ToString::to_string).collect();
+        Self {
+            mics: owned(DEFAULT_MIC_TOKENS),
+            speakers: owned(DEFAULT_SPEAKER_TOKENS),
+        }
+    }
+}
+
+/// Separator between several mics or speakers in one [`IrMetadata`] field,
+/// e.g. a blended `SM57 + R121`.
+pub const IR_TAG_SEPARATOR: &str = " + ";
+
+/// Mic, speaker and distance read from an IR's file name, for labelling and
+/// filtering the picker. Fields the name doesn't mention are `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IrMetadata {
+    /// Mic model(s), spelled as in the vocabulary.
+    pub mic: Option<String>,
+    /// Speaker model(s) and cabinet size, e.g. `4x12 + V30`.
+    pub speaker: Option<String>,
+    /// Mic distance, e.g. `1in` or `0.5in`.
+    pub distance: Option<String>,
+    /// Playing time, from the file's header.
+    pub length: Option<Duration>,
+    /// Channel count, from the file's header.
+    pub channels: Option<u16>,
+    /// The IR's name as listed, shown as-is when nothing was recognized.
+    pub raw_name: String,
+}
+
+impl IrMetadata {
+    /// Read tags from the file stem of `name`.
+    pub fn parse(name: &str, vocabulary: &IrTagVocabulary) -> Self {
+        Self::parse_with(name, &TagMatcher::new(vocabulary))
+    }
+
+    /// Runs on every IR in a scan, so it only splits and compares strings.
+    fn parse_with(name: &str, matcher: &TagMatcher<'_>) -> Self {
+        let stem = Path::new(name)
+            .file_stem()
+            .map_or_else(|| name.to_owned(), |s| s.to_string_lossy().into_owned());
+        let tokens: Vec<&str> = stem
+            .split(|c: char| !(c.is_alphanumeric() || c == '.' || c == ',' || c == '"'))
+            .map(|t| t.trim_matches(|c| c == '.' || c == ','))
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        let mut mics = Vec::new();
+        let mut speakers = Vec::new();
+        let mut distance = None;
+
+        let mut i = 0;
+        while i < tokens.len() {
+            // Two tokens first, so "SM 57" and "Vintage 30" win over "30".
+            let mut consumed = 0;
+            for len in [2, 1] {
+                let Some(window) = tokens.get(i..i + len) else {
+                    continue;
+                };
+                let key = normalize_tag(&window.concat());
+                if let Some(mic) = TagMatcher::find(&matcher.mics, &key) {
+                    push_unique(&mut mics, mic.to_owned());
+                } else if let Some(speaker) = TagMatcher::find(&matcher.speakers, &key) {
+                    push_unique(&mut speakers, speaker.to_owned());
+                } else if let Some(cab) = parse_cab_size(&key, i == 0 && len == 1) {
+                    push_unique(&mut speakers, cab);
+                } else if let Some(d) = parse_distance(&window.concat()) {
+                    distance.get_or_insert(d);
+                } else {
+                    continue;
+                }
+                consumed = len;
+                break;
+            }
+            i += consumed.max(1);
+        }
+
+        let join = |tags: Vec<String>| (!tags.is_empty()).then(|| tags.join(IR_TAG_SEPARATOR));
+        Self {
+            mic: join(mics),
+            speaker: join(speakers),
+            distance,
+            length: None,
+            channels: None,
+            raw_name: name.to_owned(),
+        }
+    }
+
+    pub const fn has_tags(&self) -> bool {
+        self.mic.is_some() || self.speaker.is_some() || self.distance.is_some()
+    }
+
+    /// Each mic and speaker on its own, for filter chips.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.mic
+            .iter()
+            .chain(&self.speaker)
+            .flat_map(|field| field.split(IR_TAG_SEPARATOR))
+    }
+
+    /// The recognized tags on one line, e.g. `SM57 · 4x12 + V30 · 1in`.
+    pub fn summary(&self) -> Option<String> {
+        let parts: Vec<&str> = [&self.mic, &self.speaker, &self.distance]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" · "))
+    }
+}
+
+/// Lowercase letters and digits only.
+fn normalize_tag(token: &str) -> String {
+    token
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// A vocabulary with its tokens normalized once, rather than per name.
+struct TagMatcher<'a> {
+    mics: Vec<(String, &'a str)>,
+    speakers: Vec<(String, &'a str)>,
+}
+
+impl<'a> TagMatcher<'a> {
+    fn new(vocabulary: &'a IrTagVocabulary) -> Self {
+        let keyed = |tokens: &'a [String]| {
+            tokens
+                .iter()
+                .map(|token| (normalize_tag(token), token.as_str()))
+                .collect()
+        };
+        Self {
+            mics: keyed(&vocabulary.mics),
+            speakers: keyed(&vocabulary.speakers),
+        }
+    }
+
+    fn find(tokens: &[(String, &'a str)], key: &str) -> Option<&'a str> {
+        tokens
+            .iter()
+            .find(|(normalized, _)| normalized == key)
+            .map(|&(_, token)| token)
+    }
+}
+
+fn push_unique(tags: &mut Vec<String>, tag: String) {
+    if !tags.contains(&tag) {
+        tags.push(tag);
+    }
+}
+
+/// `4x12`, `2X10`; or OwnHammer's `412` when it leads the name.
+fn parse_cab_size(key: &str, leading: bool) -> Option<String> {
+    if let Some((count, size)) = key.split_once('x') {
+        let valid =
+            |part: &str| (1..=2).contains(&part.len()) && part.chars().all(|c| c.is_ascii_digit());
+        return (valid(count) && valid(size)).then(|| format!("{count}x{size}"));
+    }
+    let owned_hammer = matches!(key.as_bytes(), [b'1'..=b'8', b'1', b'0' | b'2' | b'5']);
+    (leading && owned_hammer).then(|| format!("{}x{}", &key[..1], &key[1..]))
+}
+
+/// `1in`, `0.5in`, `2,5cm`, `6"`, `3ft`: a number followed by a unit.
+fn parse_distance(token: &str) -> Option<String> {
+    let split = token
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))
+        .filter(|&at| at > 0)?;
+    let (number, unit) = token.split_at(split);
+    let number = number.replace(',', ".");
+    number.parse::<f32>().ok()?;
+    let unit = match unit.to_ascii_lowercase().as_str() {
+        "in" | "inch" | "inches" | "\"" => "in",
+        "cm" => "cm",
+        "mm" => "mm",
+        "ft" | "feet" => "ft",
+        _ => return None,
+    };
+    Some(format!("{number}{unit}"))
+}
+
+/// `samples` taken from `from_rate` to `to_rate` through a windowed-sinc
+/// resampler, lined up with the input: the resampler's own delay is trimmed
+/// off the front, so an IR's onset stays where it was.
+pub(crate) fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
     if from_rate == to_rate {
         return Ok(samples.to_vec());
     }
@@ -186,11 +700,16 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
         window: WindowFunction::BlackmanHarris2,
     };
 
+    // Silence after the input flushes the tail out past the delay.
+    let mut input = samples.to_vec();
+    input.resize(samples.len() + params.sinc_len, 0.0);
+
     let mut resampler =
-        Async::<f32>::new_sinc(ratio, 1.0, &params, samples.len(), 1, FixedAsync::Input)?;
+        Async::<f32>::new_sinc(ratio, 1.0, &params, input.len(), 1, FixedAsync::Input)?;
+    let delay = resampler.output_delay();
 
-    let input = vec![samples.to_vec()];
-    let input_adapter = SequentialSliceOfVecs::new(&input, 1, samples.len())
+    let input = vec![input];
+    let input_adapter = SequentialSliceOfVecs::new(&input, 1, input[0].len())
         .map_err(|e| anyhow!("resampler input adapter: {e:?}"))?;
 
     let out_frames = resampler.output_frames_next();
@@ -206,6 +725,8 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
         .next()
         .ok_or_else(|| anyhow!("Resampling failed"))?;
     resampled.truncate(frames_written);
+    resampled.drain(..delay.min(frames_written));
+    resampled.truncate((samples.len() as f64 * ratio).round() as usize);
     Ok(resampled)
 }
 
@@ -236,6 +757,277 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_find_by_stem_needs_a_unique_match() {
+        let available = vec![
+            "Cabs/Mesa_V30.wav".to_string(),
+            "greenback.wav".to_string(),
+            "old/greenback.wav".to_string(),
+        ];
+
+        assert_eq!(
+            find_by_stem("mesa_v30.wav", &available),
+            Some("Cabs/Mesa_V30.wav")
+        );
+        assert_eq!(find_by_stem("greenback.wav", &available), None);
+        assert_eq!(find_by_stem("missing.wav", &available), None);
+    }
+
+    fn tags(name: &str) -> (Option<String>, Option<String>, Option<String>) {
+        let meta = IrMetadata::parse(name, &IrTagVocabulary::default());
+        assert_eq!(meta.raw_name, name);
+        (meta.mic, meta.speaker, meta.distance)
+    }
+
+    fn some(s: &str) -> Option<String> {
+        Some(s.to_owned())
+    }
+
+    #[test]
+    fn test_ir_metadata_from_common_naming_conventions() {
+        let cases = [
+            // Underscore-separated pack names.
+            (
+                "V30_SM57_CapEdge_1in.wav",
+                some("SM57"),
+                some("V30"),
+                some("1in"),
+            ),
+            (
+                "Mesa/OS_V30_MD421_2cm.wav",
+                some("MD421"),
+                some("V30"),
+                some("2cm"),
+            ),
+            // OwnHammer: cab size run together and leading, dashes everywhere.
+            (
+                "412-MRSH-1960AV-G12M-SM57-Cap-0.5in.wav",
+                some("SM57"),
+                some("4x12 + G12M"),
+                some("0.5in"),
+            ),
+            // York Audio / ML Sound Lab: spaces, dashes and multi-word speakers.
+            (
+                "York Audio - 4x12 Mesa OS V30 - SM57 1.5in.wav",
+                some("SM57"),
+                some("4x12 + V30"),
+                some("1.5in"),
+            ),
+            (
+                "ML Sound Lab - Mesa 4x12 Vintage 30 - R-121 - 2in.wav",
+                some("R121"),
+                some("4x12 + Vintage 30"),
+                some("2in"),
+            ),
+            // Blends, lowercase and a split "1 in".
+            (
+                "Marshall 1960A Greenback SM57+R121 Blend.wav",
+                some("SM57 + R121"),
+                some("Greenback"),
+                None,
+            ),
+            (
+                "cab 2X12 creamback sm-57 1 in.wav",
+                some("SM57"),
+                some("2x12 + Creamback"),
+                some("1in"),
+            ),
+            ("Tone 0,5cm e906.wav", some("e906"), None, some("0.5cm")),
+            // Helix-style names with a bare "57" aren't guessed at.
+            (
+                "HX 4x12 Cali V30 - 57 Dyn.wav",
+                None,
+                some("4x12 + V30"),
+                None,
+            ),
+            // A number that only looks like an OwnHammer size away from the front.
+            ("Take 412.wav", None, None, None),
+            ("My Favourite Cab.wav", None, None, None),
+        ];
+
+        for (name, mic, speaker, distance) in cases {
+            assert_eq!(tags(name), (mic, speaker, distance), "parsing {name}");
+        }
+    }
+
+    #[test]
+    fn test_ir_metadata_untagged_names_show_raw() {
+        let meta = IrMetadata::parse("Studio/My Favourite Cab.wav", &IrTagVocabulary::default());
+        assert!(!meta.has_tags());
+        assert_eq!(meta.summary(), None);
+        assert_eq!(meta.raw_name, "Studio/My Favourite Cab.wav");
+        assert_eq!(meta.tags().count(), 0);
+    }
+
+    #[test]
+    fn test_ir_metadata_tags_and_summary() {
+        let meta = IrMetadata::parse("4x12_V30_SM57+R121_1in.wav", &IrTagVocabulary::default());
+        assert_eq!(
+            meta.tags().collect::<Vec<_>>(),
+            vec!["SM57", "R121", "4x12", "V30"]
+        );
+        assert_eq!(
+            meta.summary().as_deref(),
+            Some("SM57 + R121 · 4x12 + V30 · 1in")
+        );
+    }
+
+    #[test]
+    fn test_ir_metadata_uses_the_configured_vocabulary() {
+        let vocabulary = IrTagVocabulary {
+            mics: vec!["Royer".into()],
+            speakers: Vec::new(),
+        };
+        let meta = IrMetadata::parse("royer_V30_SM57.wav", &vocabulary);
+        assert_eq!(meta.mic.as_deref(), Some("Royer"));
+        assert_eq!(meta.speaker, None);
+    }
+
+    /// A mono 48 kHz WAV of `samples`, stored in `bytes_per_sample` bytes.
+    fn int_wav(bits_per_sample: u16, bytes_per_sample: u16, samples: &[i32]) -> Vec<u8> {
+        let spec = hound::WavSpecEx {
+            spec: hound::WavSpec {
+                channels: 1,
+                sample_rate: 48000,
+                bits_per_sample,
+                sample_format: hound::SampleFormat::Int,
+            },
+            bytes_per_sample,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new_with_spec_ex(&mut cursor, spec).unwrap();
+        for &s in samples {
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+        cursor.into_inner()
+    }
+
+    fn decode(wav: &[u8]) -> Vec<f32> {
+        read_mono(WavReader::new(wav).unwrap(), usize::MAX).unwrap()
+    }
+
+    fn assert_full_scale(decoded: &[f32]) {
+        let expected = [1.0, 0.5, 0.0, -0.5, -1.0];
+        assert_eq!(decoded.len(), expected.len());
+        for (d, e) in decoded.iter().zip(expected) {
+            assert!(
+                (d - e).abs() < 1e-4,
+                "got {decoded:?}, expected {expected:?}"
+            );
+        }
+    }
+
+    /// Full scale down to its negative, at `bits`. The positive peak is one
+    /// step short of full scale, as integer audio's is.
+    fn full_scale_ramp(bits: u16) -> [i32; 5] {
+        let max = 1_i64 << (bits - 1);
+        [max - 1, max / 2, 0, -max / 2, -max].map(|s| s as i32)
+    }
+
+    #[test]
+    fn int_wavs_decode_to_full_scale_at_every_depth() {
+        for (bits, bytes) in [(16, 2), (24, 3), (24, 4), (32, 4)] {
+            let ramp = full_scale_ramp(bits);
+            assert_full_scale(&decode(&int_wav(bits, bytes, &ramp)));
+        }
+    }
+
+    #[test]
+    fn padded_24_bit_ir_labelled_32_bit_decodes_as_24_bit() {
+        let wav = int_wav(32, 4, &full_scale_ramp(24));
+        assert_full_scale(
+            &read_ir_mono(WavReader::new(wav.as_slice()).unwrap(), usize::MAX).unwrap(),
+        );
+    }
+
+    #[test]
+    fn quiet_32_bit_audio_is_not_boosted() {
+        let decoded = decode(&int_wav(32, 4, &full_scale_ramp(24)));
+        let peak = decoded.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 1.0 / 256.0).abs() < 1e-6, "peak {peak}");
+    }
+
+    #[test]
+    fn float_wav_decodes_as_is() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 48000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+        for s in [1.0_f32, 0.5, 0.0, -0.5, -1.0] {
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        assert_full_scale(&decode(&cursor.into_inner()));
+    }
+
+    #[test]
+    fn eight_bit_wav_is_rejected() {
+        let wav = int_wav(8, 1, &[64]);
+        assert!(matches!(
+            read_mono(WavReader::new(wav.as_slice()).unwrap(), usize::MAX),
+            Err(IrError::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn resampled_sine_keeps_its_amplitude_and_frequency() -> anyhow::Result<()> {
+        const FREQ: f64 = 1000.0;
+        let sine = |rate: f64, len: usize| -> Vec<f32> {
+            (0..len)
+                .map(|n| (std::f64::consts::TAU * FREQ * n as f64 / rate).sin() as f32)
+                .collect()
+        };
+
+        let output = resample(&sine(44_100.0, 44_100), 44_100, 48_000)?;
+        assert_eq!(output.len(), 48_000);
+
+        // Away from the edges, where the filter runs into the silence.
+        let middle = &output[1_000..47_000];
+
+        let rms = (middle.iter().map(|s| s * s).sum::<f32>() / middle.len() as f32).sqrt();
+        assert!(
+            (rms - std::f32::consts::FRAC_1_SQRT_2).abs() < 0.005,
+            "rms {rms}"
+        );
+
+        // Rising zero crossings, placed between samples by interpolation.
+        let crossings: Vec<f64> = middle
+            .windows(2)
+            .enumerate()
+            .filter(|(_, w)| w[0] < 0.0 && w[1] >= 0.0)
+            .map(|(i, w)| i as f64 + f64::from(w[0] / (w[0] - w[1])))
+            .collect();
+        let (first, last) = (crossings[0], crossings[crossings.len() - 1]);
+        let freq = (crossings.len() - 1) as f64 * 48_000.0 / (last - first);
+        assert!((freq - FREQ).abs() < 0.5, "frequency {freq}");
+        Ok(())
+    }
+
+    #[test]
+    fn resampling_keeps_an_impulse_in_place() -> anyhow::Result<()> {
+        let mut impulse = vec![0.0; 4410];
+        impulse[441] = 1.0;
+
+        let output = resample(&impulse, 44_100, 48_000)?;
+        let peak = output
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i);
+        // 10 ms in at either rate, give or take the sub-sample delay.
+        assert!(
+            peak.is_some_and(|i| i.abs_diff(480) <= 1),
+            "peak at {peak:?}"
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_resample_halves_length() -> anyhow::Result<()> {
         let input: Vec<f32> = (0..48000).map(|x| (x as f32).sin()).collect();
@@ -285,4 +1077,87 @@ mod tests {
         assert!(samples.len() >= 100);
         Ok(())
     }
+
+    fn write_wav(path: &Path, channels: u16, frames: usize) -> anyhow::Result<()> {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate: 48000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec)?;
+        for i in 0..frames * channels as usize {
+            writer.write_sample((i % 100) as i16)?;
+        }
+        writer.finalize()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_flags_long_and_multichannel_files() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        write_wav(&tmp.path().join("cab.wav"), 2, 2400)?;
+        write_wav(&tmp.path().join("song.wav"), 1, 96000)?;
+        write_wav(&tmp.path().join("surround.wav"), 6, 100)?;
+
+        let loader = IrLoader::new(tmp.path(), 48000)?;
+        assert_eq!(loader.check("cab.wav"), Ok(()));
+        assert_eq!(
+            loader.check("song.wav"),
+            Err(IrError::TooLong {
+                samples: 96000,
+                max: 48000
+            })
+        );
+        assert_eq!(
+            loader.check("surround.wav"),
+            Err(IrError::UnsupportedChannels(6))
+        );
+        assert_eq!(
+            loader.check("gone.wav"),
+            Err(IrError::NotFound("gone.wav".into()))
+        );
+        assert_eq!(loader.flagged_irs().len(), 2);
+
+        // Loading enforces the same limits as the scan.
+        assert!(matches!(
+            loader.load_by_name("song.wav"),
+            Err(IrError::TooLong { .. })
+        ));
+        assert_eq!(loader.load_by_name("cab.wav")?.len(), 2400);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_reads_length_and_channels_from_headers() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        write_wav(&tmp.path().join("cab.wav"), 2, 2400)?;
+        std::fs::write(tmp.path().join("broken.wav"), "")?;
+
+        let loader = IrLoader::new(tmp.path(), 96000)?;
+        let header = loader.header("cab.wav").unwrap();
+        assert_eq!(header.channels, 2);
+        assert_eq!(header.length(), Duration::from_millis(50));
+        assert_eq!(header.samples_at(96000), 4800);
+        assert_eq!(loader.header("broken.wav"), None);
+
+        let metadata = loader.available_irs_with_metadata(&IrTagVocabulary::default());
+        let cab = metadata.iter().find(|m| m.raw_name == "cab.wav").unwrap();
+        assert_eq!(cab.length, Some(Duration::from_millis(50)));
+        assert_eq!(cab.channels, Some(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_seconds_is_configurable_up_to_the_limit() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        write_wav(&tmp.path().join("room.wav"), 1, 96000)?;
+
+        let loader = IrLoader::with_max_seconds(tmp.path(), 48000, 3.0)?;
+        assert_eq!(loader.check("room.wav"), Ok(()));
+
+        let loader = IrLoader::with_max_seconds(tmp.path(), 48000, 60.0)?;
+        assert_eq!(loader.max_samples, 5 * 48000);
+        Ok(())
+    }
 }
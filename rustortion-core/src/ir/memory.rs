@@ -0,0 +1,243 @@
+//! Accounting for the memory IRs take: decoded coefficients cached by the
+//! load service, and the convolvers built for the IR that's playing.
+
+use std::collections::HashMap;
+
+use log::debug;
+
+use crate::ir::loader::IrError;
+
+/// Cap on IR memory unless the settings give another, in megabytes.
+pub const DEFAULT_IR_MEMORY_MB: usize = 256;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+struct CachedIr {
+    coefficients: Vec<f32>,
+    /// Value of the budget's clock when it was last used.
+    last_used: u64,
+}
+
+impl CachedIr {
+    const fn bytes(&self) -> usize {
+        self.coefficients.len() * size_of::<f32>()
+    }
+}
+
+/// The load service's cache of decoded IRs, kept under a cap. A load makes
+/// room by evicting the least recently used IRs; a preload that doesn't fit
+/// is refused instead, so preloading a whole library can't push out what's
+/// in use. The playing IR is never evicted.
+pub struct IrMemoryBudget {
+    cap: usize,
+    cache: HashMap<String, CachedIr>,
+    cached_bytes: usize,
+    /// The IR the engine is playing, with the bytes its convolvers hold.
+    active: Option<(String, usize)>,
+    clock: u64,
+}
+
+impl IrMemoryBudget {
+    pub fn new(cap_bytes: usize) -> Self {
+        Self {
+            cap: cap_bytes,
+            cache: HashMap::new(),
+            cached_bytes: 0,
+            active: None,
+            clock: 0,
+        }
+    }
+
+    /// A budget of `megabytes`.
+    pub fn with_megabytes(megabytes: usize) -> Self {
+        Self::new(megabytes.saturating_mul(BYTES_PER_MB))
+    }
+
+    pub const fn cap(&self) -> usize {
+        self.cap
+    }
+
+    /// Bytes held by cached coefficients and the playing IR's convolvers.
+    pub fn used(&self) -> usize {
+        self.cached_bytes + self.active.as_ref().map_or(0, |(_, bytes)| *bytes)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.cache.contains_key(name)
+    }
+
+    /// Cached coefficients for `name`, marking them as just used.
+    pub fn get(&mut self, name: &str) -> Option<&[f32]> {
+        self.clock += 1;
+        let cached = self.cache.get_mut(name)?;
+        cached.last_used = self.clock;
+        Some(&cached.coefficients)
+    }
+
+    /// Whether `bytes` more fit without evicting anything.
+    pub fn fits(&self, bytes: usize) -> bool {
+        self.used().saturating_add(bytes) <= self.cap
+    }
+
+    /// Like [`Self::fits`], with the shortfall as the error.
+    pub fn check(&self, needed: usize) -> Result<(), IrError> {
+        if self.fits(needed) {
+            Ok(())
+        } else {
+            Err(IrError::OverBudget {
+                needed,
+                available: self.cap.saturating_sub(self.used()),
+            })
+        }
+    }
+
+    /// Cache `coefficients` for a load, evicting the least recently used IRs
+    /// to make room. Kept even if the playing IR alone leaves no room: the
+    /// load has to happen.
+    pub fn insert(&mut self, name: &str, coefficients: Vec<f32>) {
+        let bytes = coefficients.len() * size_of::<f32>();
+        self.remove(name);
+        self.evict_until_fits(bytes);
+        self.store(name, coefficients);
+    }
+
+    /// Cache `coefficients` for a preload if they fit as things are.
+    pub fn try_insert(&mut self, name: &str, coefficients: Vec<f32>) -> Result<(), IrError> {
+        self.check(coefficients.len() * size_of::<f32>())?;
+        self.store(name, coefficients);
+        Ok(())
+    }
+
+    /// Mark `name` as the IR playing, whose convolvers hold `convolver_bytes`,
+    /// and evict others if that took the budget over.
+    pub fn set_active(&mut self, name: &str, convolver_bytes: usize) {
+        self.active = Some((name.to_owned(), convolver_bytes));
+        self.evict_until_fits(0);
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    fn store(&mut self, name: &str, coefficients: Vec<f32>) {
+        self.clock += 1;
+        let cached = CachedIr {
+            coefficients,
+            last_used: self.clock,
+        };
+        self.cached_bytes += cached.bytes();
+        if let Some(old) = self.cache.insert(name.to_owned(), cached) {
+            self.cached_bytes -= old.bytes();
+        }
+    }
+
+    fn remove(&mut self, name: &str) {
+        if let Some(old) = self.cache.remove(name) {
+            self.cached_bytes -= old.bytes();
+        }
+    }
+
+    fn evict_until_fits(&mut self, bytes: usize) {
+        while !self.fits(bytes) {
+            let active = self.active();
+            let Some(oldest) = self
+                .cache
+                .iter()
+                .filter(|(name, _)| Some(name.as_str()) != active)
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(name, _)| name.clone())
+            else {
+                break;
+            };
+            debug!("Evicting IR '{oldest}' from the cache to stay under budget");
+            self.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An IR taking exactly `megabytes` once cached.
+    fn ir(megabytes: usize) -> Vec<f32> {
+        vec![0.5; megabytes * BYTES_PER_MB / size_of::<f32>()]
+    }
+
+    #[test]
+    fn loads_evict_the_least_recently_used() {
+        let mut budget = IrMemoryBudget::with_megabytes(4);
+        budget.insert("a", ir(1));
+        budget.insert("b", ir(1));
+        budget.insert("c", ir(1));
+        // Using "a" again leaves "b" the oldest.
+        assert!(budget.get("a").is_some());
+
+        budget.insert("d", ir(2));
+        assert!(!budget.contains("b"));
+        assert!(budget.contains("a") && budget.contains("c") && budget.contains("d"));
+        assert_eq!(budget.used(), 4 * BYTES_PER_MB);
+
+        budget.insert("e", ir(1));
+        assert!(!budget.contains("c"));
+        assert!(budget.used() <= budget.cap());
+    }
+
+    #[test]
+    fn the_active_ir_is_never_evicted() {
+        let mut budget = IrMemoryBudget::with_megabytes(3);
+        budget.insert("playing", ir(1));
+        budget.set_active("playing", BYTES_PER_MB);
+        budget.insert("b", ir(1));
+
+        // "playing" is the oldest entry, but it's the one being heard.
+        budget.insert("c", ir(1));
+        assert!(budget.contains("playing"));
+        assert!(!budget.contains("b"));
+
+        // Even a load bigger than the budget leaves it in place.
+        budget.insert("huge", ir(5));
+        assert!(budget.contains("playing"));
+        assert!(budget.contains("huge"));
+        assert!(!budget.contains("c"));
+    }
+
+    #[test]
+    fn switching_the_active_ir_evicts_to_fit_its_convolvers() {
+        let mut budget = IrMemoryBudget::with_megabytes(4);
+        budget.insert("a", ir(1));
+        budget.insert("b", ir(1));
+        budget.insert("c", ir(1));
+        budget.set_active("c", 2 * BYTES_PER_MB);
+
+        assert_eq!(budget.active(), Some("c"));
+        assert!(!budget.contains("a"));
+        assert!(budget.contains("b") && budget.contains("c"));
+        assert_eq!(budget.used(), 4 * BYTES_PER_MB);
+    }
+
+    #[test]
+    fn preloads_that_do_not_fit_are_refused() {
+        let mut budget = IrMemoryBudget::with_megabytes(2);
+        budget.try_insert("a", ir(1)).unwrap();
+
+        assert_eq!(
+            budget.try_insert("b", ir(2)),
+            Err(IrError::OverBudget {
+                needed: 2 * BYTES_PER_MB,
+                available: BYTES_PER_MB,
+            })
+        );
+        assert!(budget.contains("a"));
+        assert!(!budget.contains("b"));
+        assert_eq!(budget.used(), BYTES_PER_MB);
+    }
+
+    #[test]
+    fn replacing_an_entry_keeps_the_count_right() {
+        let mut budget = IrMemoryBudget::with_megabytes(4);
+        budget.insert("a", ir(2));
+        budget.insert("a", ir(1));
+        assert_eq!(budget.used(), BYTES_PER_MB);
+    }
+}
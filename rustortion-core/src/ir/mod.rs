@@ -1,4 +1,8 @@
+pub mod align;
 pub mod cabinet;
+pub mod compare;
 pub mod convolver;
 pub mod load_service;
 pub mod loader;
+pub mod memory;
+pub mod thumbnail;
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crossbeam::channel::{Receiver, Sender, unbounded};
+use hound::WavReader;
+use log::debug;
+use realfft::RealFftPlanner;
+
+use crate::ir::loader::{IrError, decode_error, read_ir_mono};
+
+/// Columns in a waveform thumbnail.
+pub const WAVEFORM_BUCKETS: usize = 96;
+/// Bands in a spectrum thumbnail, log-spaced from [`SPECTRUM_LOW_HZ`] to
+/// Nyquist.
+pub const SPECTRUM_BANDS: usize = 48;
+pub const SPECTRUM_LOW_HZ: f32 = 20.0;
+/// Bands further below the loudest than this are drawn at it.
+pub const SPECTRUM_FLOOR_DB: f32 = -48.0;
+/// How much of the IR the waveform shows.
+const WAVEFORM_HEAD: Duration = Duration::from_millis(20);
+/// The head and early tail the spectrum is taken over.
+const SPECTRUM_FFT_SIZE: usize = 2048;
+
+/// A small picture of an IR for the picker: the start of its waveform and a
+/// coarse magnitude spectrum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IrThumbnail {
+    /// The largest sample of each of [`WAVEFORM_BUCKETS`] slices of the
+    /// first 20 ms, sign kept, scaled so the largest of all is ±1.
+    pub waveform: Vec<f32>,
+    /// Level of each of [`SPECTRUM_BANDS`] bands in dB below the loudest, no
+    /// lower than [`SPECTRUM_FLOOR_DB`].
+    pub spectrum: Vec<f32>,
+}
+
+impl IrThumbnail {
+    /// The thumbnail of mono IR `samples` at `sample_rate`.
+    pub fn of(samples: &[f32], sample_rate: usize) -> Self {
+        Self {
+            waveform: waveform(samples, sample_rate),
+            spectrum: spectrum(samples, sample_rate),
+        }
+    }
+
+    /// Decode just enough of the WAV at `path` for its thumbnail.
+    pub fn read(path: &Path) -> Result<Self, IrError> {
+        let reader = WavReader::open(path).map_err(decode_error)?;
+        let spec = reader.spec();
+        if spec.channels == 0 {
+            return Err(IrError::UnsupportedChannels(0));
+        }
+        if spec.sample_rate == 0 {
+            return Err(IrError::Decode("sample rate is 0".to_owned()));
+        }
+        let sample_rate = spec.sample_rate as usize;
+        let mono = read_ir_mono(reader, head_len(sample_rate).max(SPECTRUM_FFT_SIZE))?;
+        Ok(Self::of(&mono, sample_rate))
+    }
+}
+
+fn head_len(sample_rate: usize) -> usize {
+    (sample_rate as f64 * WAVEFORM_HEAD.as_secs_f64()).round() as usize
+}
+
+fn waveform(samples: &[f32], sample_rate: usize) -> Vec<f32> {
+    let head = &samples[..samples.len().min(head_len(sample_rate))];
+    let mut buckets: Vec<f32> = (0..WAVEFORM_BUCKETS)
+        .map(|bucket| {
+            let start = bucket * head.len() / WAVEFORM_BUCKETS;
+            let end = (bucket + 1) * head.len() / WAVEFORM_BUCKETS;
+            head[start..end].iter().copied().fold(0.0f32, |peak, x| {
+                if x.abs() > peak.abs() { x } else { peak }
+            })
+        })
+        .collect();
+
+    let peak = buckets.iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+    if peak > 0.0 {
+        for x in &mut buckets {
+            *x /= peak;
+        }
+    }
+    buckets
+}
+
+fn spectrum(samples: &[f32], sample_rate: usize) -> Vec<f32> {
+    let mut buffer = vec![0.0f32; SPECTRUM_FFT_SIZE];
+    let len = samples.len().min(SPECTRUM_FFT_SIZE);
+    buffer[..len].copy_from_slice(&samples[..len]);
+
+    let fft = RealFftPlanner::<f32>::new().plan_fft_forward(SPECTRUM_FFT_SIZE);
+    let mut bins = fft.make_output_vec();
+    if fft.process(&mut buffer, &mut bins).is_err() {
+        return vec![SPECTRUM_FLOOR_DB; SPECTRUM_BANDS];
+    }
+    let power: Vec<f32> = bins.iter().map(|bin| bin.norm_sqr()).collect();
+    if power.iter().all(|&p| p == 0.0) {
+        return vec![SPECTRUM_FLOOR_DB; SPECTRUM_BANDS];
+    }
+
+    let bin_hz = sample_rate as f32 / SPECTRUM_FFT_SIZE as f32;
+    let low = SPECTRUM_LOW_HZ;
+    let high = (sample_rate as f32 / 2.0).max(low * 2.0);
+    let edge = |band: usize| low * (high / low).powf(band as f32 / SPECTRUM_BANDS as f32);
+    let last_bin = power.len() - 1;
+
+    let levels: Vec<f32> = (0..SPECTRUM_BANDS)
+        .map(|band| {
+            let (from, to) = (edge(band), edge(band + 1));
+            let first = ((from / bin_hz).ceil() as usize).max(1);
+            let last = ((to / bin_hz).floor() as usize).min(last_bin);
+            // Low bands can fall between two bins: take the one nearest the
+            // band's centre.
+            let mean = if first <= last {
+                power[first..=last].iter().sum::<f32>() / (last - first + 1) as f32
+            } else {
+                let centre = ((from * to).sqrt() / bin_hz).round() as usize;
+                power[centre.clamp(1, last_bin)]
+            };
+            10.0 * mean.max(1e-20).log10()
+        })
+        .collect();
+
+    let loudest = levels.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    levels
+        .into_iter()
+        .map(|level| (level - loudest).max(SPECTRUM_FLOOR_DB))
+        .collect()
+}
+
+/// What [`IrThumbnails::get`] has for a file.
+#[derive(Debug, Clone)]
+pub enum ThumbnailLookup {
+    Ready(Arc<IrThumbnail>),
+    /// Being computed; ask again shortly.
+    Pending,
+    /// The file couldn't be read.
+    Unavailable,
+}
+
+#[derive(Debug, Clone)]
+enum CacheState {
+    Pending,
+    Done(Option<Arc<IrThumbnail>>),
+}
+
+/// A cached thumbnail and the modification time of the file it was made from.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    modified: SystemTime,
+    state: CacheState,
+}
+
+enum ThumbnailRequest {
+    Compute(PathBuf, SystemTime),
+    Shutdown,
+}
+
+type ThumbnailCache = Arc<Mutex<HashMap<PathBuf, CacheEntry>>>;
+
+/// Computes IR thumbnails on a background thread and caches them by file path
+/// and modification time, so browsing the picker never waits on decoding.
+pub struct IrThumbnails {
+    request_tx: Sender<ThumbnailRequest>,
+    cache: ThumbnailCache,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl IrThumbnails {
+    pub fn spawn() -> Self {
+        let (request_tx, request_rx) = unbounded::<ThumbnailRequest>();
+        let cache: ThumbnailCache = Arc::new(Mutex::new(HashMap::new()));
+        let service_cache = Arc::clone(&cache);
+
+        let thread = thread::Builder::new()
+            .name("ir-thumbnails".into())
+            .spawn(move || run(&request_rx, &service_cache))
+            .expect("Failed to spawn IR thumbnail thread");
+
+        Self {
+            request_tx,
+            cache,
+            thread: Some(thread),
+        }
+    }
+
+    /// The thumbnail of the IR at `path`. One that isn't cached, or was made
+    /// before the file last changed, is queued and comes back `Pending`.
+    pub fn get(&self, path: &Path) -> ThumbnailLookup {
+        let Ok(modified) = fs::metadata(path).and_then(|meta| meta.modified()) else {
+            return ThumbnailLookup::Unavailable;
+        };
+        let Ok(mut cache) = self.cache.lock() else {
+            return ThumbnailLookup::Unavailable;
+        };
+        if let Some(entry) = cache.get(path)
+            && entry.modified == modified
+        {
+            return match &entry.state {
+                CacheState::Pending => ThumbnailLookup::Pending,
+                CacheState::Done(Some(thumbnail)) => ThumbnailLookup::Ready(Arc::clone(thumbnail)),
+                CacheState::Done(None) => ThumbnailLookup::Unavailable,
+            };
+        }
+        if self
+            .request_tx
+            .send(ThumbnailRequest::Compute(path.to_path_buf(), modified))
+            .is_err()
+        {
+            return ThumbnailLookup::Unavailable;
+        }
+        cache.insert(
+            path.to_path_buf(),
+            CacheEntry {
+                modified,
+                state: CacheState::Pending,
+            },
+        );
+        ThumbnailLookup::Pending
+    }
+}
+
+impl Drop for IrThumbnails {
+    fn drop(&mut self) {
+        let _ = self.request_tx.send(ThumbnailRequest::Shutdown);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run(request_rx: &Receiver<ThumbnailRequest>, cache: &ThumbnailCache) {
+    while let Ok(request) = request_rx.recv() {
+        let ThumbnailRequest::Compute(mut path, mut modified) = request else {
+            break;
+        };
+        // Only the newest request matters while the highlight runs down the
+        // list; the ones it passed are forgotten, to be asked for again.
+        for newer in request_rx.try_iter() {
+            match newer {
+                ThumbnailRequest::Compute(newer_path, newer_modified) => {
+                    forget_pending(cache, &path, modified);
+                    path = newer_path;
+                    modified = newer_modified;
+                }
+                ThumbnailRequest::Shutdown => return,
+            }
+        }
+
+        let thumbnail = match IrThumbnail::read(&path) {
+            Ok(thumbnail) => Some(Arc::new(thumbnail)),
+            Err(e) => {
+                debug!("No thumbnail for IR '{}': {e}", path.display());
+                None
+            }
+        };
+        if let Ok(mut cache) = cache.lock() {
+            cache.insert(
+                path,
+                CacheEntry {
+                    modified,
+                    state: CacheState::Done(thumbnail),
+                },
+            );
+        }
+    }
+}
+
+fn forget_pending(cache: &ThumbnailCache, path: &Path, modified: SystemTime) {
+    if let Ok(mut cache) = cache.lock()
+        && cache.get(path).is_some_and(|entry| {
+            entry.modified == modified && matches!(entry.state, CacheState::Pending)
+        })
+    {
+        cache.remove(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use tempfile::TempDir;
+
+    const SAMPLE_RATE: usize = 48000;
+
+    /// An exponentially decaying IR that starts with a negative spike.
+    fn decaying_ir(len: usize, decay: f32) -> Vec<f32> {
+        (0..len)
+            .map(|n| {
+                let sign = if n == 0 { -1.0 } else { 1.0 };
+                sign * 0.5 * decay.powi(n as i32)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn waveform_is_peak_normalized_with_a_fixed_bucket_count() {
+        for len in [1, 10, 960, 48000] {
+            let thumbnail = IrThumbnail::of(&decaying_ir(len, 0.999), SAMPLE_RATE);
+            assert_eq!(thumbnail.waveform.len(), WAVEFORM_BUCKETS, "{len} samples");
+            assert_eq!(thumbnail.spectrum.len(), SPECTRUM_BANDS, "{len} samples");
+            let peak = thumbnail
+                .waveform
+                .iter()
+                .fold(0.0f32, |peak, x| peak.max(x.abs()));
+            assert!((peak - 1.0).abs() < 1e-6, "{len} samples: peak {peak}");
+        }
+
+        // The negative spike stays negative, and nothing past 20 ms is drawn.
+        let ir = decaying_ir(48000, 0.999);
+        let thumbnail = IrThumbnail::of(&ir, SAMPLE_RATE);
+        assert!((thumbnail.waveform[0] + 1.0).abs() < 1e-6);
+        let last = *thumbnail.waveform.last().unwrap();
+        let at_20ms = ir[head_len(SAMPLE_RATE) - 1] / 0.5;
+        assert!((last - at_20ms).abs() < 0.01, "{last} vs {at_20ms}");
+    }
+
+    #[test]
+    fn silence_draws_flat() {
+        let thumbnail = IrThumbnail::of(&[0.0; 4096], SAMPLE_RATE);
+        assert!(thumbnail.waveform.iter().all(|&x| x == 0.0));
+        assert!(
+            thumbnail
+                .spectrum
+                .iter()
+                .all(|&db| (db - SPECTRUM_FLOOR_DB).abs() < 1e-6)
+        );
+    }
+
+    #[test]
+    fn spectrum_follows_the_response() {
+        // A lone impulse passes everything alike.
+        let flat = IrThumbnail::of(&[1.0], SAMPLE_RATE);
+        assert!(flat.spectrum.iter().all(|&db| db.abs() < 0.01), "{flat:?}");
+
+        // A one-pole lowpass rolls off the top.
+        let ir: Vec<f32> = (0..4096).map(|n| 0.05 * 0.95f32.powi(n)).collect();
+        let lowpass = IrThumbnail::of(&ir, SAMPLE_RATE);
+        let spectrum = &lowpass.spectrum;
+        assert!(spectrum[0] > -1.0, "{spectrum:?}");
+        assert!(spectrum[SPECTRUM_BANDS - 1] < -20.0, "{spectrum:?}");
+        assert!(
+            spectrum.windows(2).all(|w| w[1] <= w[0] + 0.1),
+            "falls all the way: {spectrum:?}"
+        );
+    }
+
+    fn write_ir(path: &Path, ir: &[f32]) -> anyhow::Result<()> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: SAMPLE_RATE as u32,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = hound::WavWriter::create(path, spec)?;
+        for &x in ir {
+            writer.write_sample(x)?;
+        }
+        writer.finalize()?;
+        Ok(())
+    }
+
+    fn wait_for(thumbnails: &IrThumbnails, path: &Path) -> ThumbnailLookup {
+        let started = Instant::now();
+        loop {
+            let lookup = thumbnails.get(path);
+            if !matches!(lookup, ThumbnailLookup::Pending)
+                || started.elapsed() > Duration::from_secs(5)
+            {
+                return lookup;
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn thumbnails_are_cached_until_the_file_changes() -> anyhow::Result<()> {
+        let tmp = TempDir::new()?;
+        let path = tmp.path().join("cab.wav");
+        write_ir(&path, &decaying_ir(2400, 0.99))?;
+        let thumbnails = IrThumbnails::spawn();
+
+        assert!(matches!(thumbnails.get(&path), ThumbnailLookup::Pending));
+        let ThumbnailLookup::Ready(first) = wait_for(&thumbnails, &path) else {
+            panic!("no thumbnail");
+        };
+        assert_eq!(
+            *first,
+            IrThumbnail::of(&decaying_ir(2400, 0.99), SAMPLE_RATE)
+        );
+        let ThumbnailLookup::Ready(again) = thumbnails.get(&path) else {
+            panic!("not cached");
+        };
+        assert!(Arc::ptr_eq(&first, &again));
+
+        // Rewritten with a new modification time, it's made again.
+        write_ir(&path, &[1.0])?;
+        fs::File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(SystemTime::now() + Duration::from_secs(10))?;
+        assert!(matches!(thumbnails.get(&path), ThumbnailLookup::Pending));
+        let ThumbnailLookup::Ready(changed) = wait_for(&thumbnails, &path) else {
+            panic!("no thumbnail");
+        };
+        assert_eq!(*changed, IrThumbnail::of(&[1.0], SAMPLE_RATE));
+
+        let missing = tmp.path().join("gone.wav");
+        assert!(matches!(
+            thumbnails.get(&missing),
+            ThumbnailLookup::Unavailable
+        ));
+        fs::write(&missing, b"not a wav")?;
+        assert!(matches!(
+            wait_for(&thumbnails, &missing),
+            ThumbnailLookup::Unavailable
+        ));
+        Ok(())
+    }
+}
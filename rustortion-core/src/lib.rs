@@ -31,8 +31,15 @@
 
 pub mod amp;
 pub mod audio;
+pub mod capture;
+pub mod hooks;
 pub mod ir;
+pub mod mapping;
 pub mod metronome;
+pub mod migration;
 pub mod nam;
 pub mod preset;
 pub mod tuner;
+
+#[cfg(test)]
+mod test_util;
@@ -0,0 +1,51 @@
+//! What hotkey and MIDI mappings fire on, and how a MIDI mapping reads its
+//! input. The standalone app matches them with or without its GUI; the
+//! types live here so both builds share them.
+
+use serde::{Deserialize, Serialize};
+
+/// The input that fires a binding, in a form two bindings can be compared by.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Trigger {
+    /// A key with its modifiers, sorted so `Ctrl+Alt` and `Alt+Ctrl` are the
+    /// same trigger.
+    Key { key: String, modifiers: Vec<String> },
+    /// A MIDI channel and note or CC number. The message type isn't part of
+    /// it: mappings match a Note On, a Note Off (a Note On at velocity 0
+    /// included) and a CC on that number alike.
+    Midi { channel: u8, control: u8 },
+}
+
+impl Trigger {
+    pub fn key(key: &str, modifiers: &[String]) -> Self {
+        let mut modifiers = modifiers.to_vec();
+        modifiers.sort();
+        modifiers.dedup();
+        Self::Key {
+            key: key.to_owned(),
+            modifiers,
+        }
+    }
+
+    pub const fn midi(channel: u8, control: u8) -> Self {
+        Self::Midi { channel, control }
+    }
+}
+
+/// How a MIDI mapping on an on/off setting reads its input's presses and
+/// releases. A continuous mode, with a CC's value driving a parameter, waits
+/// on parameter mapping.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MappingMode {
+    /// The action's own behaviour, as mappings had before there were modes.
+    #[default]
+    Trigger,
+    /// On while held, then back to how it was before the press.
+    Momentary,
+    /// Flipped on each press; releases do nothing.
+    Toggle,
+}
+
+impl MappingMode {
+    pub const ALL: [Self; 3] = [Self::Trigger, Self::Momentary, Self::Toggle];
+}
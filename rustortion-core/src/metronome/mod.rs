@@ -1,29 +1,163 @@
-use core::f32;
+use anyhow::{Context, Result, bail};
 use hound::WavReader;
 use log::{debug, error};
-use std::fs::File;
-use std::io::BufReader;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
 
+use crate::ir::loader::read_mono;
+
+const ACCENT_WAV: &[u8] = include_bytes!("../../assets/metronome/accent.wav");
+const TICK_WAV: &[u8] = include_bytes!("../../assets/metronome/tick.wav");
+
+/// Beats per bar when none is chosen; the first one plays the accent.
+pub const DEFAULT_BEATS_PER_BAR: usize = 4;
+/// Time signatures offered, in beats per bar of quarter notes.
+pub const BEATS_PER_BAR_RANGE: std::ops::RangeInclusive<usize> = 1..=12;
+pub const MIN_BPM: f32 = 30.0;
+pub const MAX_BPM: f32 = 300.0;
+pub const DEFAULT_BPM: f32 = 120.0;
+
+/// What the metronome plays: whether it runs, and at what tempo and time
+/// signature.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetronomeConfig {
+    /// Never saved, so the metronome always starts stopped.
+    #[serde(skip)]
+    pub enabled: bool,
+    pub bpm: f32,
+    pub beats_per_bar: usize,
+    /// Mix the click into the main outputs as well as its own port. It is
+    /// never part of the recorded take either way.
+    pub in_monitor: bool,
+}
+
+impl Default for MetronomeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bpm: DEFAULT_BPM,
+            beats_per_bar: DEFAULT_BEATS_PER_BAR,
+            in_monitor: false,
+        }
+    }
+}
+
+impl MetronomeConfig {
+    /// Tempo and time signature pulled into range.
+    pub fn clamped(self) -> Self {
+        Self {
+            bpm: self.bpm.clamp(MIN_BPM, MAX_BPM),
+            beats_per_bar: self
+                .beats_per_bar
+                .clamp(*BEATS_PER_BAR_RANGE.start(), *BEATS_PER_BAR_RANGE.end()),
+            ..self
+        }
+    }
+}
+
+/// The clicks the metronome plays, decoded at the engine's sample rate.
+#[derive(Debug, Clone, Default)]
+pub struct MetronomeSounds {
+    /// Played on the first beat of each bar.
+    pub accent: Vec<f32>,
+    pub tick: Vec<f32>,
+}
+
+impl MetronomeSounds {
+    /// The ticks shipped with the app.
+    pub fn bundled(sample_rate: usize) -> Self {
+        let decode = |bytes: &[u8]| {
+            decode_wav_bytes(bytes, sample_rate).unwrap_or_else(|e| {
+                error!("Failed to decode bundled metronome tick: {e}");
+                Vec::new()
+            })
+        };
+        Self {
+            accent: decode(ACCENT_WAV),
+            tick: decode(TICK_WAV),
+        }
+    }
+
+    /// A custom click from `path`, played on every beat.
+    pub fn from_file(path: &Path, sample_rate: usize) -> Result<Self> {
+        let reader = WavReader::open(path)
+            .with_context(|| format!("Failed to open WAV file '{}'", path.display()))?;
+        let tick = decode_wav(reader, sample_rate)
+            .with_context(|| format!("Failed to decode WAV file '{}'", path.display()))?;
+        Ok(Self {
+            accent: tick.clone(),
+            tick,
+        })
+    }
+}
+
+/// A run of bars at one tempo and time signature.
+#[derive(Debug, Clone, Copy)]
+struct Schedule {
+    /// Engine frame of the downbeat it starts on.
+    start_frame: u64,
+    /// Samples per beat.
+    interval: usize,
+    beats_per_bar: usize,
+}
+
+impl Schedule {
+    /// Beat since the start, and offset into it, at engine frame `frame`;
+    /// `None` before the start.
+    fn position(&self, frame: u64) -> Option<(u64, usize)> {
+        if self.interval == 0 {
+            return None;
+        }
+        let elapsed = frame.checked_sub(self.start_frame)?;
+        let interval = self.interval as u64;
+        Some((elapsed / interval, (elapsed % interval) as usize))
+    }
+
+    /// Engine frame the first beat after `frame` starts on.
+    fn next_beat(&self, frame: u64) -> u64 {
+        match self.position(frame) {
+            Some((beat, _)) => self.start_frame + (beat + 1) * self.interval as u64,
+            None => self.start_frame,
+        }
+    }
+}
+
+/// Clicks scheduled from the engine's frame counter rather than counted
+/// block by block, so the click lines up with a recording to the sample
+/// whatever the block sizes were.
 pub struct Metronome {
     bpm: f32,
     sample_rate: usize,
     enabled: bool,
-    tick_buffer: Vec<f32>,
-    interval: usize,
-    samples_processed: usize,
-    buffer_index: usize,
+    sounds: Box<MetronomeSounds>,
+    schedule: Schedule,
+    /// The tempo and time signature before a change made while running,
+    /// played until `schedule` starts on the next beat.
+    lead_in: Option<Schedule>,
+    in_monitor: bool,
+    /// Engine frame of the beat during which the clicks were swapped; it
+    /// stays silent rather than start the new click from the middle.
+    muted_beat: Option<u64>,
 }
 
 impl Metronome {
+    /// A metronome playing the bundled ticks.
     pub fn new(bpm: f32, sample_rate: usize) -> Self {
         Self {
             bpm,
             sample_rate,
             enabled: false,
-            tick_buffer: Vec::new(),
-            interval: (sample_rate as f32 / (bpm / 60.0)) as usize,
-            samples_processed: 0,
-            buffer_index: 0,
+            sounds: Box::new(MetronomeSounds::bundled(sample_rate)),
+            schedule: Schedule {
+                start_frame: 0,
+                interval: beat_interval(bpm, sample_rate),
+                beats_per_bar: DEFAULT_BEATS_PER_BAR,
+            },
+            lead_in: None,
+            in_monitor: false,
+            muted_beat: None,
         }
     }
 
@@ -31,60 +165,146 @@ impl Metronome {
         self.bpm
     }
 
-    pub fn load_wav_file(&mut self, file_path: &str) {
-        let file = match File::open(file_path) {
-            Ok(f) => f,
-            Err(e) => {
-                error!("Failed to read file '{file_path}' : {e}");
-                return;
-            }
-        };
-        let mut reader = match WavReader::new(BufReader::new(file)) {
-            Ok(r) => r,
-            Err(e) => {
-                error!("Failed to parse WAV file '{file_path}': {e}");
-                return;
-            }
-        };
-        let spec = reader.spec();
-        debug!("Loaded WAV: {spec:?}");
-        let raw_samples: Vec<i16> = match reader.samples::<i16>().collect::<Result<Vec<_>, _>>() {
-            Ok(s) => s,
-            Err(e) => {
-                error!("Failed to decode samples from WAV file '{file_path}': {e}");
-                return;
-            }
+    pub const fn beats_per_bar(&self) -> usize {
+        self.schedule.beats_per_bar
+    }
+
+    pub const fn sample_rate(&self) -> usize {
+        self.sample_rate
+    }
+
+    pub const fn in_monitor(&self) -> bool {
+        self.in_monitor
+    }
+
+    pub const fn config(&self) -> MetronomeConfig {
+        MetronomeConfig {
+            enabled: self.enabled,
+            bpm: self.bpm,
+            beats_per_bar: self.schedule.beats_per_bar,
+            in_monitor: self.in_monitor,
+        }
+    }
+
+    /// Apply `config` at engine frame `frame`. Starting begins a bar there.
+    /// Changing the tempo or time signature while running lets the beat
+    /// that's playing finish at the old tempo and begins a new bar on the
+    /// next one, so no beat comes early or late.
+    pub fn set_config(&mut self, config: MetronomeConfig, frame: u64) {
+        let config = config.clamped();
+        let schedule = Schedule {
+            start_frame: frame,
+            interval: beat_interval(config.bpm, self.sample_rate),
+            beats_per_bar: config.beats_per_bar,
         };
-        let samples: Vec<f32> = raw_samples
-            .into_iter()
-            .map(|s| s as f32 / i16::MAX as f32)
-            .collect();
-        if spec.sample_rate != self.sample_rate as u32 {
-            self.tick_buffer =
-                Self::resample_tick_file(&samples, spec.sample_rate, self.sample_rate as u32);
-        } else {
-            self.tick_buffer = samples;
+        let retimed = config.bpm != self.bpm || config.beats_per_bar != self.schedule.beats_per_bar;
+
+        if config.enabled && !self.enabled {
+            self.schedule = schedule;
+            self.lead_in = None;
+            self.muted_beat = None;
+        } else if config.enabled && retimed {
+            // A change before the last one took over replaces it, still on
+            // the same beat.
+            let current = self.schedule_at(frame);
+            self.schedule = Schedule {
+                start_frame: current.next_beat(frame),
+                ..schedule
+            };
+            self.lead_in = Some(current);
+        } else if !config.enabled {
+            // Started again from scratch anyway.
+            self.schedule = schedule;
+            self.lead_in = None;
         }
+
+        self.bpm = config.bpm;
+        self.in_monitor = config.in_monitor;
+        self.enabled = config.enabled;
+    }
+
+    /// Play the WAV at `file_path` on every beat instead of the bundled ticks.
+    pub fn load_wav_file(&mut self, file_path: &str) -> Result<()> {
+        let sounds = MetronomeSounds::from_file(Path::new(file_path), self.sample_rate)?;
+        self.sounds = Box::new(sounds);
+        Ok(())
+    }
+
+    /// Swap in new clicks, decoded at this metronome's sample rate, at
+    /// engine frame `frame`, and return the old ones so the caller can drop
+    /// them off the RT thread.
+    pub fn set_sounds(&mut self, sounds: Box<MetronomeSounds>, frame: u64) -> Box<MetronomeSounds> {
+        // Don't finish the old click from the middle of a longer new one.
+        self.muted_beat = self
+            .position(frame)
+            .map(|(_, offset, _)| frame - offset as u64);
+        std::mem::replace(&mut self.sounds, sounds)
     }
 
     pub const fn is_enabled(&self) -> bool {
         self.enabled
     }
 
-    pub fn process_block(&mut self, output: &mut [f32]) {
-        //handle metronome logic
-        for i in output.iter_mut() {
-            if self.buffer_index < self.tick_buffer.len() {
-                *i = self.tick_buffer[self.buffer_index];
-                self.buffer_index += 1;
-            } else {
-                *i = 0.0;
-            }
-            self.samples_processed += 1;
-            if self.samples_processed >= self.interval {
-                self.samples_processed = 0;
-                self.buffer_index = 0;
-            }
+    /// The schedule playing at engine frame `frame`: the lead-in until the
+    /// last change takes over.
+    fn schedule_at(&self, frame: u64) -> Schedule {
+        match self.lead_in {
+            Some(lead_in) if frame < self.schedule.start_frame => lead_in,
+            _ => self.schedule,
+        }
+    }
+
+    /// Beat since the schedule playing started, offset into it, and beats
+    /// in its bar, at engine frame `frame`; `None` before the run or while
+    /// stopped.
+    fn position(&self, frame: u64) -> Option<(u64, usize, usize)> {
+        if !self.enabled {
+            return None;
+        }
+        let schedule = self.schedule_at(frame);
+        let (beat, offset) = schedule.position(frame)?;
+        Some((beat, offset, schedule.beats_per_bar))
+    }
+
+    /// Engine frame of the first downbeat at or after `frame`, from the
+    /// same schedule the clicks play from; `None` while stopped.
+    pub fn next_bar(&self, frame: u64) -> Option<u64> {
+        let (beat, offset, beats_per_bar) = self.position(frame)?;
+        let into_bar = beat % beats_per_bar as u64;
+        if into_bar == 0 && offset == 0 {
+            return Some(frame);
+        }
+        let schedule = self.schedule_at(frame);
+        let bar = schedule.start_frame
+            + (beat - into_bar + beats_per_bar as u64) * schedule.interval as u64;
+        // A tempo change waiting for the next beat starts a bar there.
+        if frame < self.schedule.start_frame {
+            return Some(bar.min(self.schedule.start_frame));
+        }
+        Some(bar)
+    }
+
+    /// The click at engine frame `frame`.
+    pub fn sample_at(&self, frame: u64) -> f32 {
+        let Some((beat, offset, beats_per_bar)) = self.position(frame) else {
+            return 0.0;
+        };
+        if self.muted_beat == Some(frame - offset as u64) {
+            return 0.0;
+        }
+        let click = if beat % beats_per_bar as u64 == 0 {
+            &self.sounds.accent
+        } else {
+            &self.sounds.tick
+        };
+        click.get(offset).copied().unwrap_or(0.0)
+    }
+
+    /// Fill `output` with the clicks of the block starting at engine frame
+    /// `frame`.
+    pub fn process_block(&self, output: &mut [f32], frame: u64) {
+        for (i, sample) in output.iter_mut().enumerate() {
+            *sample = self.sample_at(frame + i as u64);
         }
     }
 
@@ -109,8 +329,336 @@ impl Metronome {
         }
         out
     }
+}
+
+/// Samples per beat at `bpm`.
+fn beat_interval(bpm: f32, sample_rate: usize) -> usize {
+    (sample_rate as f32 / (bpm / 60.0)) as usize
+}
+
+fn decode_wav_bytes(bytes: &[u8], sample_rate: usize) -> Result<Vec<f32>> {
+    let reader = WavReader::new(std::io::Cursor::new(bytes))?;
+    decode_wav(reader, sample_rate)
+}
+
+/// Decode 16/24/32-bit integer or 32-bit float WAV samples, averaged down to
+/// mono and resampled to `sample_rate`.
+fn decode_wav<R: Read>(reader: WavReader<R>, sample_rate: usize) -> Result<Vec<f32>> {
+    let spec = reader.spec();
+    debug!("Decoding metronome tick: {spec:?}");
+    if spec.channels == 0 {
+        bail!("WAV has no channels");
+    }
+
+    let mono = read_mono(reader, usize::MAX)?;
+
+    Ok(if spec.sample_rate == sample_rate as u32 {
+        mono
+    } else {
+        Metronome::resample_tick_file(&mono, spec.sample_rate, sample_rate as u32)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+    use std::io::Cursor;
+
+    const SAMPLE_RATE: usize = 48_000;
+
+    fn spec(channels: u16, bits_per_sample: u16, sample_format: SampleFormat) -> WavSpec {
+        WavSpec {
+            channels,
+            sample_rate: SAMPLE_RATE as u32,
+            bits_per_sample,
+            sample_format,
+        }
+    }
+
+    /// A WAV of `frames`, each frame one value per channel.
+    fn int_fixture(spec: WavSpec, frames: &[&[i32]]) -> Vec<u8> {
+        let mut bytes = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut bytes, spec).unwrap();
+        for frame in frames {
+            for &s in *frame {
+                writer.write_sample(s).unwrap();
+            }
+        }
+        writer.finalize().unwrap();
+        bytes.into_inner()
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!(
+                (a - e).abs() < 1e-4,
+                "got {actual:?}, expected {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn decodes_16_bit() {
+        let wav = int_fixture(spec(1, 16, SampleFormat::Int), &[&[16384], &[-32768], &[0]]);
+        assert_close(
+            &decode_wav_bytes(&wav, SAMPLE_RATE).unwrap(),
+            &[0.5, -1.0, 0.0],
+        );
+    }
+
+    #[test]
+    fn decodes_24_bit() {
+        let wav = int_fixture(
+            spec(1, 24, SampleFormat::Int),
+            &[&[4_194_304], &[-8_388_608]],
+        );
+        assert_close(&decode_wav_bytes(&wav, SAMPLE_RATE).unwrap(), &[0.5, -1.0]);
+    }
+
+    #[test]
+    fn decodes_32_bit_int() {
+        let wav = int_fixture(spec(1, 32, SampleFormat::Int), &[&[1 << 30], &[i32::MIN]]);
+        assert_close(&decode_wav_bytes(&wav, SAMPLE_RATE).unwrap(), &[0.5, -1.0]);
+    }
+
+    #[test]
+    fn decodes_32_bit_float() {
+        let mut bytes = Cursor::new(Vec::new());
+        let mut writer = WavWriter::new(&mut bytes, spec(1, 32, SampleFormat::Float)).unwrap();
+        for s in [0.25_f32, -0.75] {
+            writer.write_sample(s).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        assert_close(
+            &decode_wav_bytes(&bytes.into_inner(), SAMPLE_RATE).unwrap(),
+            &[0.25, -0.75],
+        );
+    }
+
+    #[test]
+    fn downmixes_to_mono() {
+        let wav = int_fixture(
+            spec(2, 16, SampleFormat::Int),
+            &[&[16384, 0], &[-16384, -16384]],
+        );
+        assert_close(&decode_wav_bytes(&wav, SAMPLE_RATE).unwrap(), &[0.25, -0.5]);
+    }
+
+    #[test]
+    fn resamples_to_the_engine_rate() {
+        let wav = int_fixture(
+            spec(1, 16, SampleFormat::Int),
+            &[&[8192_i32] as &[i32]; 480],
+        );
+        let tick = decode_wav_bytes(&wav, SAMPLE_RATE * 2).unwrap();
+        assert_eq!(tick.len(), 960);
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        let wav = int_fixture(spec(1, 8, SampleFormat::Int), &[&[64]]);
+        assert!(decode_wav_bytes(&wav, SAMPLE_RATE).is_err());
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let mut metronome = Metronome::new(120.0, SAMPLE_RATE);
+        assert!(metronome.load_wav_file("does/not/exist.wav").is_err());
+    }
+
+    #[test]
+    fn bundled_ticks_decode() {
+        let sounds = MetronomeSounds::bundled(SAMPLE_RATE);
+        assert!(!sounds.accent.is_empty());
+        assert!(!sounds.tick.is_empty());
+        assert_ne!(sounds.accent, sounds.tick);
+    }
+
+    fn running(config: MetronomeConfig) -> Metronome {
+        let mut metronome = Metronome::new(config.bpm, SAMPLE_RATE);
+        metronome.set_sounds(
+            Box::new(MetronomeSounds {
+                accent: vec![1.0],
+                tick: vec![0.5],
+            }),
+            0,
+        );
+        metronome.set_config(
+            MetronomeConfig {
+                enabled: true,
+                ..config
+            },
+            0,
+        );
+        metronome
+    }
+
+    #[test]
+    fn accents_the_first_beat_of_each_bar() {
+        let metronome = running(MetronomeConfig::default());
+
+        let beat = SAMPLE_RATE / 2;
+        let mut output = vec![0.0; beat * (DEFAULT_BEATS_PER_BAR + 1)];
+        metronome.process_block(&mut output, 0);
+
+        let clicks: Vec<f32> = output.iter().step_by(beat).copied().collect();
+        assert_eq!(clicks, [1.0, 0.5, 0.5, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn follows_the_time_signature() {
+        let metronome = running(MetronomeConfig {
+            beats_per_bar: 3,
+            ..MetronomeConfig::default()
+        });
+
+        let beat = SAMPLE_RATE / 2;
+        let clicks: Vec<f32> = (0..5)
+            .map(|n| metronome.sample_at((n * beat) as u64))
+            .collect();
+        assert_eq!(clicks, [1.0, 0.5, 0.5, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn clicks_land_on_the_same_frames_whatever_the_block_size() {
+        let metronome = running(MetronomeConfig::default());
+        let total = SAMPLE_RATE * 3;
+
+        let render = |block: usize| {
+            let mut output = vec![0.0; total];
+            let mut frame = 0;
+            for chunk in output.chunks_mut(block) {
+                metronome.process_block(chunk, frame);
+                frame += chunk.len() as u64;
+            }
+            output
+        };
+        assert_eq!(render(64), render(1000));
+    }
+
+    #[test]
+    fn swapping_the_clicks_silences_the_rest_of_the_beat() {
+        let mut metronome = running(MetronomeConfig::default());
+        metronome.set_sounds(
+            Box::new(MetronomeSounds {
+                accent: vec![1.0; 10],
+                tick: vec![0.5; 10],
+            }),
+            5,
+        );
+
+        assert_eq!(metronome.sample_at(6), 0.0);
+        assert_eq!(metronome.sample_at((SAMPLE_RATE / 2) as u64), 0.5);
+    }
+
+    /// Frames with a click, and the click's first sample, over `frames`.
+    fn clicks(metronome: &Metronome, frames: u64) -> Vec<(u64, f32)> {
+        (0..frames)
+            .filter_map(|frame| {
+                let sample = metronome.sample_at(frame);
+                (sample != 0.0).then_some((frame, sample))
+            })
+            .collect()
+    }
+
+    fn retime(metronome: &mut Metronome, config: MetronomeConfig, frame: u64) {
+        metronome.set_config(
+            MetronomeConfig {
+                enabled: true,
+                ..config
+            },
+            frame,
+        );
+    }
+
+    /// Frames per beat at the default 120 BPM.
+    const BEAT: u64 = (SAMPLE_RATE / 2) as u64;
+
+    #[test]
+    fn tempo_change_waits_for_the_next_beat() {
+        let mut metronome = running(MetronomeConfig::default());
+        let slower = MetronomeConfig {
+            bpm: 60.0,
+            ..MetronomeConfig::default()
+        };
+        retime(&mut metronome, slower, BEAT + BEAT / 2);
+
+        // The second beat plays out; 60 BPM starts a bar on the next one.
+        assert_eq!(
+            clicks(&metronome, 7 * BEAT),
+            [
+                (0, 1.0),
+                (BEAT, 0.5),
+                (2 * BEAT, 1.0),
+                (4 * BEAT, 0.5),
+                (6 * BEAT, 0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn second_change_before_the_beat_replaces_the_first() {
+        let mut metronome = running(MetronomeConfig::default());
+        for (bpm, frame) in [(60.0, BEAT + BEAT / 4), (240.0, BEAT + BEAT / 2)] {
+            let config = MetronomeConfig {
+                bpm,
+                ..MetronomeConfig::default()
+            };
+            retime(&mut metronome, config, frame);
+        }
+
+        assert_eq!(
+            clicks(&metronome, 3 * BEAT + 1),
+            [
+                (0, 1.0),
+                (BEAT, 0.5),
+                (2 * BEAT, 1.0),
+                (2 * BEAT + BEAT / 2, 0.5),
+                (3 * BEAT, 0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn time_signature_change_starts_a_bar_on_the_next_beat() {
+        let mut metronome = running(MetronomeConfig::default());
+        let three_four = MetronomeConfig {
+            beats_per_bar: 3,
+            ..MetronomeConfig::default()
+        };
+        retime(&mut metronome, three_four, 2 * BEAT + BEAT / 2);
+
+        let accents: Vec<u64> = clicks(&metronome, 8 * BEAT)
+            .into_iter()
+            .filter(|&(_, sample)| sample == 1.0)
+            .map(|(frame, _)| frame)
+            .collect();
+        assert_eq!(accents, [0, 3 * BEAT, 6 * BEAT]);
+    }
+
+    #[test]
+    fn next_bar_is_the_next_accent() {
+        let mut metronome = running(MetronomeConfig::default());
+        let bar = 4 * BEAT;
+        assert_eq!(metronome.next_bar(0), Some(0));
+        assert_eq!(metronome.next_bar(1), Some(bar));
+        assert_eq!(metronome.next_bar(bar - 1), Some(bar));
+        assert_eq!(metronome.next_bar(bar), Some(bar));
+        assert_eq!(metronome.next_bar(bar + BEAT), Some(2 * bar));
+
+        // A tempo change starts a bar on the next beat.
+        let slower = MetronomeConfig {
+            bpm: 60.0,
+            ..MetronomeConfig::default()
+        };
+        retime(&mut metronome, slower, bar + BEAT / 2);
+        assert_eq!(metronome.next_bar(bar + BEAT / 2), Some(bar + BEAT));
+        assert_eq!(metronome.next_bar(bar + BEAT + 1), Some(bar + 9 * BEAT));
 
-    pub const fn toggle_metronome(&mut self) {
-        self.enabled = !self.enabled;
+        metronome.set_config(MetronomeConfig::default(), 2 * bar);
+        assert_eq!(metronome.next_bar(2 * bar), None);
     }
 }
@@ -0,0 +1,118 @@
+//! Versioned JSON files, brought up to date before they're deserialized.
+//!
+//! A file's `version` says which schema it was written with; files from
+//! before versioning have none and count as version 0. Each migration takes a
+//! file from one version to the next, so a file of version `n` runs through
+//! `migrations[n..]` in order and comes out at `migrations.len()`.
+
+use log::warn;
+use serde_json::Value;
+
+/// Key holding a file's schema version.
+pub const VERSION_KEY: &str = "version";
+
+/// Rewrites a file from one version to the next.
+pub type Migration = fn(Value) -> Value;
+
+/// A file after migration.
+#[derive(Debug)]
+pub struct Migrated {
+    pub value: Value,
+    /// Version the file was written with.
+    pub from: u32,
+    /// Written by a newer version than this one knows. Left as it was, to
+    /// load as well as it can, but it must not be saved back over.
+    pub read_only: bool,
+}
+
+/// Schema version of `value`; 0 when it has none.
+pub fn version_of(value: &Value) -> u32 {
+    value
+        .get(VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map_or(0, |v| u32::try_from(v).unwrap_or(u32::MAX))
+}
+
+/// Run `value` through the `migrations` it hasn't had, and stamp it with the
+/// version they bring it to. `what` names the file in the warning logged for
+/// one from a newer version.
+pub fn migrate(mut value: Value, migrations: &[Migration], what: &str) -> Migrated {
+    let current = migrations.len() as u32;
+    let from = version_of(&value);
+
+    if from > current {
+        warn!("{what} is version {from}, newer than {current}; loading it read-only");
+        return Migrated {
+            value,
+            from,
+            read_only: true,
+        };
+    }
+
+    for migration in &migrations[from as usize..] {
+        value = migration(value);
+    }
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert(VERSION_KEY.to_string(), Value::from(current));
+    }
+
+    Migrated {
+        value,
+        from,
+        read_only: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn push(mut value: Value, step: &str) -> Value {
+        value["steps"]
+            .as_array_mut()
+            .unwrap()
+            .push(Value::from(step));
+        value
+    }
+
+    fn v0_to_v1(value: Value) -> Value {
+        push(value, "v0->v1")
+    }
+
+    fn v1_to_v2(value: Value) -> Value {
+        push(value, "v1->v2")
+    }
+
+    const MIGRATIONS: [Migration; 2] = [v0_to_v1, v1_to_v2];
+
+    #[test]
+    fn unversioned_files_run_every_migration_in_order() {
+        let migrated = migrate(json!({ "steps": [] }), &MIGRATIONS, "test");
+        assert_eq!(migrated.from, 0);
+        assert!(!migrated.read_only);
+        assert_eq!(
+            migrated.value,
+            json!({ "steps": ["v0->v1", "v1->v2"], "version": 2 })
+        );
+    }
+
+    #[test]
+    fn runs_only_the_migrations_a_file_is_missing() {
+        let migrated = migrate(json!({ "steps": [], "version": 1 }), &MIGRATIONS, "test");
+        assert_eq!(migrated.from, 1);
+        assert_eq!(migrated.value["steps"], json!(["v1->v2"]));
+
+        let current = migrate(json!({ "steps": [], "version": 2 }), &MIGRATIONS, "test");
+        assert_eq!(current.value["steps"], json!([]));
+    }
+
+    #[test]
+    fn newer_files_are_left_alone_and_read_only() {
+        let file = json!({ "steps": [], "version": 7 });
+        let migrated = migrate(file.clone(), &MIGRATIONS, "test");
+        assert_eq!(migrated.from, 7);
+        assert!(migrated.read_only);
+        assert_eq!(migrated.value, file);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::amp::chain::DEFAULT_CHAIN_CAPACITY;
+use crate::preset::StageConfig;
+
+/// Above this many edits a full rebuild is cheaper, and the edits alone
+/// could overrun the engine's message queue before the audio thread drains it.
+pub const MAX_INCREMENTAL_EDITS: usize = 32;
+
+/// One step of an incremental chain update. Indices refer to the chain as it
+/// stands when the step runs, which for `Replace`/`Insert` is also the index
+/// of the stage's config in the new list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainEdit {
+    /// Rebuild this stage from its new config.
+    Replace(usize),
+    /// Build the new config at this index and insert it.
+    Insert(usize),
+    Remove(usize),
+    SetBypassed(usize, bool),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainUpdate {
+    /// Stages were reordered (or too much changed); rebuild the whole chain.
+    Rebuild,
+    /// Apply these edits in order. Stages not named keep their state.
+    Edits(Vec<ChainEdit>),
+}
+
+/// Work out how to turn a running chain built from `old` into one built from
+/// `new` while rebuilding as few stages as possible.
+///
+/// Stages are matched by position and variant: a common prefix and suffix of
+/// same-typed stages is kept, and only those whose config differs are
+/// replaced (or just re-bypassed). What's left in the middle must be a pure
+/// insertion, a pure removal, or a same-length run of in-place changes;
+/// anything else means stages moved, and the whole chain is rebuilt.
+pub fn plan_chain_update(old: &[StageConfig], new: &[StageConfig]) -> ChainUpdate {
+    if new.len() > DEFAULT_CHAIN_CAPACITY {
+        return ChainUpdate::Rebuild;
+    }
+
+    let same_type = |a: &StageConfig, b: &StageConfig| a.stage_type() == b.stage_type();
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(a, b)| same_type(a, b))
+        .count();
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| same_type(a, b))
+        .count();
+
+    let old_middle = prefix..old.len() - suffix;
+    let new_middle = prefix..new.len() - suffix;
+
+    let mut edits = Vec::new();
+    for idx in 0..prefix {
+        update_in_place(idx, &old[idx], &new[idx], &mut edits);
+    }
+
+    if old_middle.len() == new_middle.len() {
+        for idx in new_middle {
+            edits.push(ChainEdit::Replace(idx));
+            if new[idx].bypassed() != old[idx].bypassed() {
+                edits.push(ChainEdit::SetBypassed(idx, new[idx].bypassed()));
+            }
+        }
+    } else if new_middle.is_empty() {
+        edits.extend(old_middle.map(|_| ChainEdit::Remove(prefix)));
+    } else if old_middle.is_empty() {
+        for idx in new_middle {
+            edits.push(ChainEdit::Insert(idx));
+            if new[idx].bypassed() {
+                edits.push(ChainEdit::SetBypassed(idx, true));
+            }
+        }
+    } else {
+        return ChainUpdate::Rebuild;
+    }
+
+    for offset in 1..=suffix {
+        let (old_idx, new_idx) = (old.len() - offset, new.len() - offset);
+        update_in_place(new_idx, &old[old_idx], &new[new_idx], &mut edits);
+    }
+
+    if edits.len() > MAX_INCREMENTAL_EDITS {
+        ChainUpdate::Rebuild
+    } else {
+        ChainUpdate::Edits(edits)
+    }
+}
+
+/// Edits for a slot whose stage type didn't change.
+fn update_in_place(idx: usize, old: &StageConfig, new: &StageConfig, edits: &mut Vec<ChainEdit>) {
+    if old == new {
+        return;
+    }
+
+    let mut unbypassed_old = old.clone();
+    unbypassed_old.set_bypassed(new.bypassed());
+    // Locking a stage doesn't change its sound.
+    unbypassed_old.set_locked(new.locked());
+    if unbypassed_old != *new {
+        edits.push(ChainEdit::Replace(idx));
+    }
+    if old.bypassed() != new.bypassed() {
+        edits.push(ChainEdit::SetBypassed(idx, new.bypassed()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::chain::AmplifierChain;
+    use crate::amp::stages::delay::DelayConfig;
+    use crate::amp::stages::level::LevelConfig;
+    use crate::amp::stages::reverb::ReverbConfig;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+
+    fn level(gain: f32) -> StageConfig {
+        StageConfig::Level(LevelConfig {
+            gain,
+            ..LevelConfig::default()
+        })
+    }
+
+    fn delay() -> StageConfig {
+        StageConfig::Delay(DelayConfig {
+            delay_ms: 100.0,
+            feedback: 0.5,
+            mix: 0.5,
+            ..DelayConfig::default()
+        })
+    }
+
+    fn reverb() -> StageConfig {
+        StageConfig::Reverb(ReverbConfig::default())
+    }
+
+    fn bypassed(mut cfg: StageConfig) -> StageConfig {
+        cfg.set_bypassed(true);
+        cfg
+    }
+
+    /// What the GUI backends do with a plan, minus the engine queue.
+    fn apply(chain: &mut AmplifierChain, new: &[StageConfig], update: &ChainUpdate) {
+        let ChainUpdate::Edits(edits) = update else {
+            panic!("expected an incremental update, got {update:?}");
+        };
+        for edit in edits {
+            match *edit {
+                ChainEdit::Replace(idx) => {
+                    chain.replace_stage(idx, new[idx].to_runtime(SAMPLE_RATE));
+                }
+                ChainEdit::Insert(idx) => {
+                    assert!(
+                        chain
+                            .insert_stage(idx, new[idx].to_runtime(SAMPLE_RATE))
+                            .is_none()
+                    );
+                }
+                ChainEdit::Remove(idx) => {
+                    chain.remove_stage(idx);
+                }
+                ChainEdit::SetBypassed(idx, bypassed) => {
+                    chain.set_bypassed(idx, bypassed);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn identical_chains_need_no_edits() {
+        let stages = vec![level(1.0), delay(), reverb()];
+        assert_eq!(
+            plan_chain_update(&stages, &stages),
+            ChainUpdate::Edits(Vec::new())
+        );
+    }
+
+    #[test]
+    fn only_changed_stage_is_replaced() {
+        let old = vec![level(1.0), delay(), reverb()];
+        let new = vec![level(0.5), delay(), reverb()];
+        assert_eq!(
+            plan_chain_update(&old, &new),
+            ChainUpdate::Edits(vec![ChainEdit::Replace(0)])
+        );
+    }
+
+    #[test]
+    fn bypass_change_alone_does_not_rebuild() {
+        let old = vec![level(1.0), delay()];
+        let new = vec![level(1.0), bypassed(delay())];
+        assert_eq!(
+            plan_chain_update(&old, &new),
+            ChainUpdate::Edits(vec![ChainEdit::SetBypassed(1, true)])
+        );
+    }
+
+    #[test]
+    fn lock_change_alone_needs_no_edits() {
+        let old = vec![level(1.0), delay()];
+        let mut new = old.clone();
+        new[0].set_locked(true);
+        assert_eq!(
+            plan_chain_update(&old, &new),
+            ChainUpdate::Edits(Vec::new())
+        );
+    }
+
+    #[test]
+    fn insertion_and_removal_leave_neighbours_alone() {
+        let old = vec![level(1.0), reverb()];
+        let new = vec![level(1.0), bypassed(delay()), reverb()];
+        assert_eq!(
+            plan_chain_update(&old, &new),
+            ChainUpdate::Edits(vec![ChainEdit::Insert(1), ChainEdit::SetBypassed(1, true)])
+        );
+        assert_eq!(
+            plan_chain_update(&new, &old),
+            ChainUpdate::Edits(vec![ChainEdit::Remove(1)])
+        );
+    }
+
+    #[test]
+    fn suffix_edits_use_new_indices() {
+        let old = vec![level(1.0), reverb()];
+        let new = vec![level(1.0), delay(), delay(), bypassed(reverb())];
+        assert_eq!(
+            plan_chain_update(&old, &new),
+            ChainUpdate::Edits(vec![
+                ChainEdit::Insert(1),
+                ChainEdit::Insert(2),
+                ChainEdit::SetBypassed(3, true),
+            ])
+        );
+    }
+
+    #[test]
+    fn reordering_rebuilds() {
+        let old = vec![delay(), reverb(), level(1.0)];
+        let new = vec![reverb(), level(1.0), delay(), level(0.5)];
+        assert_eq!(plan_chain_update(&old, &new), ChainUpdate::Rebuild);
+    }
+
+    #[test]
+    fn large_changes_rebuild() {
+        let old: Vec<_> = (0..40).map(|_| level(1.0)).collect();
+        let new: Vec<_> = (0..40).map(|_| level(0.5)).collect();
+        assert_eq!(plan_chain_update(&old, &new), ChainUpdate::Rebuild);
+    }
+
+    #[test]
+    fn delay_tail_survives_edit_to_another_stage() {
+        let old = vec![level(1.0), delay()];
+        let mut chain = AmplifierChain::new();
+        for cfg in &old {
+            chain.add_stage(cfg.to_runtime(SAMPLE_RATE));
+        }
+
+        // Impulse, then stop before the first echo (100 ms = 4800 samples).
+        let mut block = vec![0.0; 2_400];
+        block[0] = 1.0;
+        chain.process_block(&mut block);
+
+        let new = vec![level(0.5), delay()];
+        apply(&mut chain, &new, &plan_chain_update(&old, &new));
+
+        let mut block = vec![0.0; 4_800];
+        chain.process_block(&mut block);
+        let echo = block.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(echo > 0.2, "echo tail was lost, peak {echo}");
+    }
+}
@@ -0,0 +1,399 @@
+use serde_json::Value;
+
+use super::{Preset, StageConfig, StageType};
+
+/// A single difference between a saved preset and the current in-memory state.
+///
+/// Stage positions are zero-based chain indices: `current` positions refer to
+/// the in-memory chain, `saved` positions to the preset on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PresetChange {
+    StageParam {
+        stage: StageType,
+        index: usize,
+        param: String,
+        saved: String,
+        current: String,
+    },
+    StageAdded {
+        stage: StageType,
+        index: usize,
+    },
+    StageRemoved {
+        stage: StageType,
+        index: usize,
+    },
+    StageMoved {
+        stage: StageType,
+        from: usize,
+        to: usize,
+    },
+    Ir {
+        saved: Option<String>,
+        current: Option<String>,
+    },
+    /// A scalar preset-level setting (IR gain, pitch shift, input filters).
+    Setting {
+        name: &'static str,
+        saved: String,
+        current: String,
+    },
+}
+
+/// Compare `current` against `saved` and list every difference, structural
+/// changes first, then per-stage parameters, then preset-level settings.
+///
+/// Stages are paired by type in order of appearance (the second Preamp in one
+/// chain pairs with the second Preamp in the other). Paired stages that are
+/// out of order relative to the longest in-order run are reported as moved.
+pub fn diff_presets(saved: &Preset, current: &Preset) -> Vec<PresetChange> {
+    let mut changes = Vec::new();
+
+    let pairs = pair_stages(&saved.stages, &current.stages);
+
+    let mut saved_matched = vec![false; saved.stages.len()];
+    let mut current_matched = vec![false; current.stages.len()];
+    for &(s, c) in &pairs {
+        saved_matched[s] = true;
+        current_matched[c] = true;
+    }
+
+    for (index, cfg) in saved.stages.iter().enumerate() {
+        if !saved_matched[index] {
+            changes.push(PresetChange::StageRemoved {
+                stage: cfg.stage_type(),
+                index,
+            });
+        }
+    }
+    for (index, cfg) in current.stages.iter().enumerate() {
+        if !current_matched[index] {
+            changes.push(PresetChange::StageAdded {
+                stage: cfg.stage_type(),
+                index,
+            });
+        }
+    }
+
+    let in_order = longest_increasing_run(&pairs);
+    for (i, &(s, c)) in pairs.iter().enumerate() {
+        if !in_order[i] {
+            changes.push(PresetChange::StageMoved {
+                stage: current.stages[c].stage_type(),
+                from: s,
+                to: c,
+            });
+        }
+    }
+
+    for &(s, c) in &pairs {
+        let saved_fields = stage_fields(&saved.stages[s]);
+        let current_fields = stage_fields(&current.stages[c]);
+        let stage = current.stages[c].stage_type();
+
+        for (param, current_value) in &current_fields {
+            let saved_value = saved_fields
+                .iter()
+                .find(|(name, _)| name == param)
+                .map(|(_, v)| v.as_str());
+            if saved_value != Some(current_value.as_str()) {
+                changes.push(PresetChange::StageParam {
+                    stage,
+                    index: c,
+                    param: param.clone(),
+                    saved: saved_value.unwrap_or("-").to_string(),
+                    current: current_value.clone(),
+                });
+            }
+        }
+    }
+
+    if saved.ir_name != current.ir_name {
+        changes.push(PresetChange::Ir {
+            saved: saved.ir_name.clone(),
+            current: current.ir_name.clone(),
+        });
+    }
+
+    let saved_settings = preset_settings(saved);
+    for (name, current_value) in preset_settings(current) {
+        let saved_value = &saved_settings
+            .iter()
+            .find(|(n, _)| *n == name)
+            .expect("both presets have the same settings")
+            .1;
+        if *saved_value != current_value {
+            changes.push(PresetChange::Setting {
+                name,
+                saved: saved_value.clone(),
+                current: current_value,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Pair stages of the same type by order of appearance. Returned pairs are
+/// `(saved_index, current_index)`, sorted by saved index.
+fn pair_stages(saved: &[StageConfig], current: &[StageConfig]) -> Vec<(usize, usize)> {
+    let mut taken = vec![false; current.len()];
+    let mut pairs = Vec::new();
+
+    for (s, cfg) in saved.iter().enumerate() {
+        let kind = cfg.stage_type();
+        if let Some(c) = (0..current.len()).find(|&c| !taken[c] && current[c].stage_type() == kind)
+        {
+            taken[c] = true;
+            pairs.push((s, c));
+        }
+    }
+
+    pairs
+}
+
+/// Mark the pairs that form the longest run with increasing current index.
+/// Everything else has been moved. Chains are short, so O(n²) is fine.
+fn longest_increasing_run(pairs: &[(usize, usize)]) -> Vec<bool> {
+    let n = pairs.len();
+    let mut length = vec![1usize; n];
+    let mut prev = vec![None; n];
+
+    for i in 0..n {
+        let best = (0..i)
+            .filter(|&j| pairs[j].1 < pairs[i].1)
+            .max_by_key(|&j| length[j]);
+        if let Some(j) = best {
+            length[i] = length[j] + 1;
+            prev[i] = Some(j);
+        }
+    }
+
+    let mut in_run = vec![false; n];
+    let mut cursor = (0..n).max_by_key(|&i| length[i]);
+    while let Some(i) = cursor {
+        in_run[i] = true;
+        cursor = prev[i];
+    }
+    in_run
+}
+
+/// Flatten a stage config into `(path, display value)` pairs. Reflection goes
+/// through serde so every config is covered without per-stage code; nested
+/// values get dotted paths (`bands.2.gain`).
+fn stage_fields(cfg: &StageConfig) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+
+    // Externally tagged: `{ "Preamp": { ...fields } }`.
+    if let Ok(Value::Object(tagged)) = serde_json::to_value(cfg)
+        && let Some(inner) = tagged.into_iter().next().map(|(_, v)| v)
+    {
+        flatten("", &inner, &mut fields);
+    }
+
+    fields
+}
+
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                flatten(&join(key), v, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), v, out);
+            }
+        }
+        scalar => out.push((prefix.to_string(), format_value(scalar))),
+    }
+}
+
+/// Format a scalar for display. Floats are rounded so `f32` → `f64` noise
+/// doesn't show up as a change.
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Number(n) if n.is_f64() => n.as_f64().map_or_else(|| n.to_string(), format_float),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn format_float(value: f64) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    format!("{rounded:?}")
+}
+
+fn preset_settings(preset: &Preset) -> Vec<(&'static str, String)> {
+    let filters = &preset.input_filters;
+    vec![
+        ("ir_gain", format_float(f64::from(preset.ir_gain))),
+        ("ir_offset", preset.ir_alignment.offset.to_string()),
+        ("ir_inverted", preset.ir_alignment.inverted.to_string()),
+        ("pitch_shift", preset.pitch_shift_semitones.to_string()),
+        ("hp_enabled", filters.hp_enabled.to_string()),
+        ("hp_cutoff", format_float(f64::from(filters.hp_cutoff))),
+        ("lp_enabled", filters.lp_enabled.to_string()),
+        ("lp_cutoff", format_float(f64::from(filters.lp_cutoff))),
+        ("denoise_bypassed", preset.denoise.bypassed.to_string()),
+        (
+            "denoise_reduction_db",
+            format_float(f64::from(preset.denoise.reduction_db)),
+        ),
+        (
+            "denoise_profile",
+            preset.denoise.profile.as_ref().map_or_else(
+                || "none".to_string(),
+                |profile| format!("{:.1} dB", profile.level_db()),
+            ),
+        ),
+        (
+            "ambience_amount",
+            format_float(f64::from(preset.ambience.amount)),
+        ),
+        (
+            "ambience_decay",
+            format_float(f64::from(preset.ambience.decay)),
+        ),
+        (
+            "ambience_tone",
+            format_float(f64::from(preset.ambience.tone)),
+        ),
+        (
+            "double_tracker_mix",
+            format_float(f64::from(preset.double_tracker.mix)),
+        ),
+        (
+            "double_tracker_width",
+            format_float(f64::from(preset.double_tracker.width)),
+        ),
+        (
+            "double_tracker_delay_ms",
+            format_float(f64::from(preset.double_tracker.delay_ms)),
+        ),
+        (
+            "double_tracker_detune_cents",
+            format_float(f64::from(preset.double_tracker.detune_cents)),
+        ),
+        (
+            "tilt_offset_db",
+            format_float(f64::from(preset.tilt_offset_db)),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::level::LevelConfig;
+    use crate::amp::stages::preamp::PreampConfig;
+
+    fn preset(stages: Vec<StageConfig>) -> Preset {
+        Preset {
+            stages,
+            ..Preset::default()
+        }
+    }
+
+    #[test]
+    fn test_identical_presets_have_no_changes() {
+        let saved = preset(vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::Level),
+        ]);
+        assert!(diff_presets(&saved, &saved.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_param_change_is_reported_with_both_values() {
+        let saved = preset(vec![StageConfig::Level(LevelConfig::default())]);
+        let mut current = saved.clone();
+        let StageConfig::Level(cfg) = &mut current.stages[0] else {
+            unreachable!()
+        };
+        let before = cfg.gain;
+        cfg.gain = before + 0.5;
+
+        let changes = diff_presets(&saved, &current);
+        assert_eq!(
+            changes,
+            vec![PresetChange::StageParam {
+                stage: StageType::Level,
+                index: 0,
+                param: "gain".to_string(),
+                saved: format_float(f64::from(before)),
+                current: format_float(f64::from(before + 0.5)),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_structural_changes() {
+        let saved = preset(vec![
+            StageConfig::Preamp(PreampConfig::default()),
+            StageConfig::from(StageType::ToneStack),
+            StageConfig::from(StageType::Level),
+        ]);
+        // Level moved to the front, ToneStack removed, Compressor added.
+        let current = preset(vec![
+            StageConfig::from(StageType::Level),
+            StageConfig::Preamp(PreampConfig::default()),
+            StageConfig::from(StageType::Compressor),
+        ]);
+
+        let changes = diff_presets(&saved, &current);
+        assert!(changes.contains(&PresetChange::StageRemoved {
+            stage: StageType::ToneStack,
+            index: 1,
+        }));
+        assert!(changes.contains(&PresetChange::StageAdded {
+            stage: StageType::Compressor,
+            index: 2,
+        }));
+
+        let moved: Vec<_> = changes
+            .iter()
+            .filter(|c| matches!(c, PresetChange::StageMoved { .. }))
+            .collect();
+        assert_eq!(moved.len(), 1, "only one stage should be reported moved");
+        assert_eq!(changes.len(), 3);
+    }
+
+    #[test]
+    fn test_ir_and_settings_changes() {
+        let saved = Preset {
+            ir_name: Some("mesa.wav".to_string()),
+            ..Preset::default()
+        };
+        let current = Preset {
+            ir_name: Some("friedman.wav".to_string()),
+            pitch_shift_semitones: -2,
+            ..Preset::default()
+        };
+
+        let changes = diff_presets(&saved, &current);
+        assert_eq!(
+            changes,
+            vec![
+                PresetChange::Ir {
+                    saved: Some("mesa.wav".to_string()),
+                    current: Some("friedman.wav".to_string()),
+                },
+                PresetChange::Setting {
+                    name: "pitch_shift",
+                    saved: "0".to_string(),
+                    current: "-2".to_string(),
+                },
+            ]
+        );
+    }
+}
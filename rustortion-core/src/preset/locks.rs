@@ -0,0 +1,181 @@
+//! Stages the user has locked, put back into a chain that replaces theirs:
+//! the next preset's, or a template's arrangement of it.
+
+use crate::amp::chain::DEFAULT_CHAIN_CAPACITY;
+use crate::preset::{StageCategory, StageConfig};
+
+/// `incoming` with the locked stages of `current` carried into it; see
+/// [`insert_locked_stages`].
+///
+/// A chain that has locked stages of its own keeps them and gets nothing
+/// carried over, so switching between presets saved with the lock doesn't
+/// stack copies. Nor does a chain that would grow past the chain's capacity.
+pub fn keep_locked_stages(current: &[StageConfig], incoming: Vec<StageConfig>) -> Vec<StageConfig> {
+    let locked = current.iter().filter(|s| s.locked()).count();
+    if locked == 0
+        || incoming.iter().any(StageConfig::locked)
+        || incoming.len() + locked > DEFAULT_CHAIN_CAPACITY
+    {
+        return incoming;
+    }
+    insert_locked_stages(current, incoming)
+}
+
+/// `stages` with the locked stages of `current` put into it, each at the
+/// same relative position within its section (amp or effects) that it had
+/// in `current`: the last stage stays last, the first stays first.
+pub fn insert_locked_stages(current: &[StageConfig], stages: Vec<StageConfig>) -> Vec<StageConfig> {
+    let (mut amp, mut effects): (Vec<_>, Vec<_>) = stages
+        .into_iter()
+        .partition(|s| s.category() == StageCategory::Amp);
+    carry_section(current, StageCategory::Amp, &mut amp);
+    carry_section(current, StageCategory::Effect, &mut effects);
+    amp.append(&mut effects);
+    amp
+}
+
+/// Put the locked `category` stages of `current` into `section`, which holds
+/// the new chain's stages of that category.
+fn carry_section(current: &[StageConfig], category: StageCategory, section: &mut Vec<StageConfig>) {
+    let old: Vec<&StageConfig> = current
+        .iter()
+        .filter(|s| s.category() == category)
+        .collect();
+    let last = old.len().saturating_sub(1);
+    let len = section.len();
+
+    // Positions are worked out among the new stages, then moved past
+    // the locked stages already put in ahead of them.
+    let locked = old.iter().enumerate().filter(|(_, s)| s.locked());
+    for (carried, (i, stage)) in locked.enumerate() {
+        let at = if last == 0 {
+            len
+        } else {
+            (i * len + last / 2) / last
+        };
+        section.insert(at + carried, (*stage).clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preset::StageType;
+
+    fn stage(stage_type: StageType) -> StageConfig {
+        StageConfig::from(stage_type)
+    }
+
+    fn locked(stage_type: StageType) -> StageConfig {
+        let mut cfg = stage(stage_type);
+        cfg.set_locked(true);
+        cfg
+    }
+
+    fn types(stages: &[StageConfig]) -> Vec<StageType> {
+        stages.iter().map(StageConfig::stage_type).collect()
+    }
+
+    #[test]
+    fn nothing_locked_takes_the_new_chain_as_is() {
+        let current = vec![stage(StageType::Preamp), stage(StageType::Level)];
+        let incoming = vec![stage(StageType::Compressor), stage(StageType::Delay)];
+        assert_eq!(keep_locked_stages(&current, incoming.clone()), incoming);
+    }
+
+    #[test]
+    fn a_locked_last_stage_stays_last_in_its_section() {
+        let current = vec![
+            stage(StageType::Preamp),
+            stage(StageType::PowerAmp),
+            locked(StageType::Level),
+            stage(StageType::Reverb),
+        ];
+        let incoming = vec![
+            stage(StageType::NoiseGate),
+            stage(StageType::Preamp),
+            stage(StageType::ToneStack),
+            stage(StageType::PowerAmp),
+            stage(StageType::Delay),
+        ];
+
+        let kept = keep_locked_stages(&current, incoming);
+        assert_eq!(
+            types(&kept),
+            [
+                StageType::NoiseGate,
+                StageType::Preamp,
+                StageType::ToneStack,
+                StageType::PowerAmp,
+                StageType::Level,
+                StageType::Delay,
+            ]
+        );
+        assert!(kept[4].locked());
+    }
+
+    #[test]
+    fn locked_stages_keep_their_relative_positions() {
+        let current = vec![
+            locked(StageType::NoiseGate),
+            stage(StageType::Preamp),
+            stage(StageType::ToneStack),
+            stage(StageType::PowerAmp),
+            locked(StageType::Level),
+            stage(StageType::Delay),
+            locked(StageType::Reverb),
+        ];
+        let incoming = vec![stage(StageType::Preamp), stage(StageType::Tremolo)];
+
+        assert_eq!(
+            types(&keep_locked_stages(&current, incoming)),
+            [
+                StageType::NoiseGate,
+                StageType::Preamp,
+                StageType::Level,
+                StageType::Tremolo,
+                StageType::Reverb,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_middle_stage_lands_in_the_middle() {
+        let current = vec![
+            stage(StageType::Preamp),
+            locked(StageType::Compressor),
+            stage(StageType::PowerAmp),
+        ];
+        let incoming = vec![
+            stage(StageType::Preamp),
+            stage(StageType::ToneStack),
+            stage(StageType::PowerAmp),
+            stage(StageType::Level),
+        ];
+
+        assert_eq!(
+            types(&keep_locked_stages(&current, incoming)),
+            [
+                StageType::Preamp,
+                StageType::ToneStack,
+                StageType::Compressor,
+                StageType::PowerAmp,
+                StageType::Level,
+            ]
+        );
+    }
+
+    #[test]
+    fn a_chain_with_its_own_locks_is_left_alone() {
+        let current = vec![stage(StageType::Preamp), locked(StageType::Level)];
+        let incoming = vec![stage(StageType::Preamp), locked(StageType::Level)];
+        assert_eq!(keep_locked_stages(&current, incoming.clone()), incoming);
+    }
+
+    #[test]
+    fn a_full_chain_is_not_overfilled() {
+        let current = vec![locked(StageType::Level)];
+        let incoming = vec![stage(StageType::Eq); DEFAULT_CHAIN_CAPACITY];
+        assert_eq!(keep_locked_stages(&current, incoming.clone()), incoming);
+    }
+}
@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+
+use super::StageConfig;
+
+/// Macros a preset can hold, as many as the macros strip shows.
+pub const MAX_MACROS: usize = 4;
+
+/// Rate stages are built at to check a target's range; the limits don't
+/// depend on it.
+const VALIDATION_SAMPLE_RATE: f32 = 48_000.0;
+
+/// How a target follows its macro between `from` and `to`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MacroCurve {
+    #[default]
+    Linear,
+    /// Slow at first, most of the travel near the top.
+    Exponential,
+    /// Most of the travel near the bottom, slow at the top.
+    Logarithmic,
+}
+
+impl MacroCurve {
+    pub const ALL: [Self; 3] = [Self::Linear, Self::Exponential, Self::Logarithmic];
+
+    /// How far along its range a target is with the macro at `position`,
+    /// both in `0.0..=1.0`.
+    pub fn shape(self, position: f32) -> f32 {
+        let x = position.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => x,
+            Self::Exponential => x * x,
+            Self::Logarithmic => {
+                let rest = 1.0 - x;
+                rest.mul_add(-rest, 1.0)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for MacroCurve {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Linear => write!(f, "Linear"),
+            Self::Exponential => write!(f, "Exponential"),
+            Self::Logarithmic => write!(f, "Logarithmic"),
+        }
+    }
+}
+
+/// One stage parameter a macro moves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MacroTarget {
+    /// Chain index of the stage.
+    pub stage_idx: usize,
+    /// Parameter name, one of the stage's [`StageConfig::parameter_names`].
+    pub param: String,
+    /// Value with the macro at its minimum.
+    pub from: f32,
+    /// Value with the macro at its maximum; may be below `from`.
+    pub to: f32,
+    #[serde(default)]
+    pub curve: MacroCurve,
+}
+
+impl MacroTarget {
+    /// The parameter's value with the macro at `position`.
+    pub fn value_at(&self, position: f32) -> f32 {
+        (self.to - self.from).mul_add(self.curve.shape(position), self.from)
+    }
+
+    /// Whether the target names a parameter of a stage in `stages`, and the
+    /// stage takes both ends of its range. The curves never leave the range,
+    /// so every value in between is taken too.
+    pub fn is_valid(&self, stages: &[StageConfig]) -> bool {
+        let Some(cfg) = stages.get(self.stage_idx) else {
+            return false;
+        };
+        if !cfg.parameter_names().contains(&self.param.as_str()) {
+            return false;
+        }
+        let mut stage = cfg.to_runtime(VALIDATION_SAMPLE_RATE);
+        stage.set_parameter(&self.param, self.from).is_ok()
+            && stage.set_parameter(&self.param, self.to).is_ok()
+    }
+}
+
+/// One control moving several stage parameters at once, each over its own
+/// range. Saved with the preset, at the position it was left at.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    /// Position in `0.0..=1.0`.
+    #[serde(default)]
+    pub value: f32,
+    #[serde(default)]
+    pub targets: Vec<MacroTarget>,
+}
+
+impl Macro {
+    pub const fn new(name: String) -> Self {
+        Self {
+            name,
+            value: 0.0,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Move the macro to `value` and write each target's new value into
+    /// `stages`. Returns the parameters that were set, by stage index and
+    /// name, to send on to the engine. Targets on a stage or parameter the
+    /// chain doesn't have are skipped.
+    pub fn apply(
+        &mut self,
+        value: f32,
+        stages: &mut [StageConfig],
+    ) -> Vec<(usize, &'static str, f32)> {
+        self.value = value.clamp(0.0, 1.0);
+        let mut changed = Vec::new();
+        for target in &self.targets {
+            let Some(cfg) = stages.get_mut(target.stage_idx) else {
+                continue;
+            };
+            if let Some((name, field)) = cfg
+                .float_params_mut()
+                .into_iter()
+                .find(|(name, _)| *name == target.param)
+            {
+                *field = target.value_at(self.value);
+                changed.push((target.stage_idx, name, *field));
+            }
+        }
+        changed
+    }
+
+    /// Follow the stages after the chain changed: `new_index` gives each
+    /// old index's new one, or `None` for a stage that's gone, whose
+    /// targets are dropped.
+    pub fn remap_stages(&mut self, new_index: impl Fn(usize) -> Option<usize>) {
+        self.targets.retain_mut(|target| {
+            new_index(target.stage_idx).is_some_and(|idx| {
+                target.stage_idx = idx;
+                true
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp::stages::noise_gate::NoiseGateConfig;
+    use crate::amp::stages::preamp::PreampConfig;
+    use crate::preset::{Preset, StageType};
+
+    fn target(stage_idx: usize, param: &str, from: f32, to: f32, curve: MacroCurve) -> MacroTarget {
+        MacroTarget {
+            stage_idx,
+            param: param.to_string(),
+            from,
+            to,
+            curve,
+        }
+    }
+
+    fn tightness() -> Macro {
+        Macro {
+            targets: vec![
+                target(0, "threshold", -60.0, -40.0, MacroCurve::Linear),
+                target(1, "bias", 0.2, -0.2, MacroCurve::Exponential),
+            ],
+            ..Macro::new("Tightness".to_string())
+        }
+    }
+
+    fn chain() -> Vec<StageConfig> {
+        vec![
+            StageConfig::NoiseGate(NoiseGateConfig::default()),
+            StageConfig::Preamp(PreampConfig::default()),
+        ]
+    }
+
+    #[test]
+    fn curves_meet_at_the_ends_and_keep_their_order_between() {
+        for curve in MacroCurve::ALL {
+            assert_eq!(curve.shape(0.0), 0.0);
+            assert_eq!(curve.shape(1.0), 1.0);
+            assert_eq!(curve.shape(-1.0), 0.0);
+            assert_eq!(curve.shape(2.0), 1.0);
+        }
+        assert_eq!(MacroCurve::Linear.shape(0.5), 0.5);
+        assert_eq!(MacroCurve::Exponential.shape(0.5), 0.25);
+        assert_eq!(MacroCurve::Logarithmic.shape(0.5), 0.75);
+    }
+
+    #[test]
+    fn fan_out_sets_every_target_on_its_curve() {
+        let mut stages = chain();
+        let mut tightness = tightness();
+        let changed = tightness.apply(0.5, &mut stages);
+
+        assert_eq!(changed, vec![(0, "threshold", -50.0), (1, "bias", 0.1)]);
+        let StageConfig::NoiseGate(gate) = &stages[0] else {
+            unreachable!()
+        };
+        assert_eq!(gate.threshold_db, -50.0);
+        let StageConfig::Preamp(preamp) = &stages[1] else {
+            unreachable!()
+        };
+        assert_eq!(preamp.bias, 0.1);
+        assert_eq!(tightness.value, 0.5);
+    }
+
+    #[test]
+    fn fan_out_clamps_the_position() {
+        let mut stages = chain();
+        let mut tightness = tightness();
+        let changed = tightness.apply(3.0, &mut stages);
+        assert_eq!(changed, vec![(0, "threshold", -40.0), (1, "bias", -0.2)]);
+        assert_eq!(tightness.value, 1.0);
+    }
+
+    #[test]
+    fn fan_out_skips_targets_the_chain_lacks() {
+        let mut stages = vec![StageConfig::from(StageType::Level)];
+        let mut tightness = tightness();
+        tightness
+            .targets
+            .push(target(0, "gain", 1.0, 2.0, MacroCurve::Linear));
+
+        assert_eq!(tightness.apply(1.0, &mut stages), vec![(0, "gain", 2.0)]);
+    }
+
+    #[test]
+    fn targets_follow_their_stages() {
+        let mut tightness = tightness();
+        // Stage 0 removed, stage 1 moved to 0.
+        tightness.remap_stages(|idx| idx.checked_sub(1));
+        assert_eq!(tightness.targets.len(), 1);
+        assert_eq!(tightness.targets[0].stage_idx, 0);
+        assert_eq!(tightness.targets[0].param, "bias");
+    }
+
+    #[test]
+    fn validity_checks_the_stage_limits() {
+        let stages = chain();
+        let tightness = tightness();
+        assert!(tightness.targets.iter().all(|t| t.is_valid(&stages)));
+
+        let too_far = target(1, "bias", 0.0, 100.0, MacroCurve::Linear);
+        assert!(!too_far.is_valid(&stages));
+        let unknown = target(1, "threshold", 0.0, 0.0, MacroCurve::Linear);
+        assert!(!unknown.is_valid(&stages));
+        let missing = target(2, "bias", 0.0, 0.0, MacroCurve::Linear);
+        assert!(!missing.is_valid(&stages));
+    }
+
+    #[test]
+    fn macros_round_trip_through_a_preset() {
+        let preset = Preset {
+            macros: vec![tightness()],
+            ..Preset::default()
+        };
+        let json = serde_json::to_string(&preset).unwrap();
+        let loaded: Preset = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.macros, preset.macros);
+    }
+
+    #[test]
+    fn presets_without_macros_load_and_save_without_them() {
+        let json = serde_json::to_string(&Preset::default()).unwrap();
+        assert!(!json.contains("macros"));
+        let loaded: Preset = serde_json::from_str(&json).unwrap();
+        assert!(loaded.macros.is_empty());
+    }
+
+    #[test]
+    fn curve_defaults_to_linear() {
+        let json = r#"{"stage_idx":0,"param":"gain","from":0.0,"to":1.0}"#;
+        let target: MacroTarget = serde_json::from_str(json).unwrap();
+        assert_eq!(target.curve, MacroCurve::Linear);
+    }
+}
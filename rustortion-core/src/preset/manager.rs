@@ -1,22 +1,172 @@
-use super::{InputFilterConfig, Preset, StageCategory};
-use anyhow::{Context, Result};
+use super::tags::{matches_tags, normalize_tags};
+use super::trash::{self, TRASH_DIR, TRASH_RETENTION, TrashedPreset};
+use super::{InputFilterConfig, Preset, StageCategory, StageConfig};
+use crate::migration::{Migration, migrate};
+use anyhow::{Context, Result, bail};
 use log::warn;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Longest preset name accepted, in characters.
+pub const MAX_PRESET_NAME_CHARS: usize = 64;
+
+/// Longest file stem written, in bytes, leaving room for a `-N` suffix and
+/// `.json` under the usual 255-byte filename limit.
+const MAX_FILE_STEM_BYTES: usize = 200;
+
+/// How many quick slots there are, numbered from 1.
+pub const QUICK_SLOTS: u8 = 8;
+
+/// Subfolder of the preset directory quick-slot presets are saved in.
+const SLOTS_DIR: &str = "slots";
+
+/// Subfolder of the preset directory for presets received from others. What
+/// lands here is listed apart from the library until it's kept or discarded.
+pub const INBOX_DIR: &str = "inbox";
+
+/// Migrations from each preset version to the next; see [`crate::migration`].
+pub(super) const PRESET_MIGRATIONS: [Migration; 1] = [migrate_v0_to_v1];
+
+/// Name of the preset quick slot `slot` saves to: "Slot 1" … "Slot 8".
+pub fn slot_preset_name(slot: u8) -> String {
+    format!("Slot {slot}")
+}
+
+fn is_slot_preset_name(name: &str) -> bool {
+    name.strip_prefix("Slot ")
+        .and_then(|n| n.parse::<u8>().ok())
+        .is_some_and(|n| (1..=QUICK_SLOTS).contains(&n))
+}
+
+/// Why a name can't be saved as a preset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PresetNameError {
+    /// Empty or only whitespace.
+    Empty,
+    TooLong {
+        chars: usize,
+        max: usize,
+    },
+    /// Tabs, newlines and other control characters.
+    ControlCharacter,
+}
+
+impl fmt::Display for PresetNameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Preset name is empty"),
+            Self::TooLong { chars, max } => {
+                write!(f, "Preset name is too long: {chars} characters (max {max})")
+            }
+            Self::ControlCharacter => write!(f, "Preset name contains a control character"),
+        }
+    }
+}
+
+impl std::error::Error for PresetNameError {}
+
+/// Why the preset directory couldn't be opened.
+#[derive(Debug)]
+pub enum PresetError {
+    CreateDir { path: PathBuf, source: io::Error },
+    ReadDir { path: PathBuf, source: io::Error },
+}
+
+impl fmt::Display for PresetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateDir { path, source } => write!(
+                f,
+                "Failed to create presets directory {}: {source}",
+                path.display()
+            ),
+            Self::ReadDir { path, source } => write!(
+                f,
+                "Failed to read presets directory {}: {source}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::CreateDir { source, .. } | Self::ReadDir { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Check a preset's display name. Any other character is allowed, `/`
+/// included: the file it's saved to gets a sanitized name of its own.
+pub fn validate_preset_name(name: &str) -> Result<(), PresetNameError> {
+    if name.trim().is_empty() {
+        return Err(PresetNameError::Empty);
+    }
+    let chars = name.chars().count();
+    if chars > MAX_PRESET_NAME_CHARS {
+        return Err(PresetNameError::TooLong {
+            chars,
+            max: MAX_PRESET_NAME_CHARS,
+        });
+    }
+    if name.chars().any(char::is_control) {
+        return Err(PresetNameError::ControlCharacter);
+    }
+    Ok(())
+}
+
+/// Where a listed preset lives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetGroup {
+    /// The presets that have been saved, imported or kept.
+    Library,
+    /// Unsorted presets in [`INBOX_DIR`], waiting to be kept or discarded.
+    Inbox,
+}
 
 pub struct Manager {
     presets_dir: PathBuf,
     presets: Vec<Preset>,
+    /// The file each loaded preset came from, by display name.
+    files: HashMap<String, PathBuf>,
+    /// Presets in the inbox, kept out of `presets` so nothing treats them as
+    /// part of the library until they're kept.
+    inbox: Vec<Preset>,
+    inbox_files: HashMap<String, PathBuf>,
+    /// Every tag on a library preset, sorted, rebuilt with each load.
+    tags: BTreeSet<String>,
 }
 
 impl Manager {
-    pub fn new(preset_dir: impl AsRef<Path>) -> Result<Self> {
+    pub fn new(preset_dir: impl AsRef<Path>) -> Result<Self, PresetError> {
         let presets_dir = preset_dir.as_ref().to_path_buf();
-        fs::create_dir_all(&presets_dir).context("Failed to create presets directory")?;
+        if let Err(source) = fs::create_dir_all(&presets_dir) {
+            return Err(PresetError::CreateDir {
+                path: presets_dir,
+                source,
+            });
+        }
+        let inbox_dir = presets_dir.join(INBOX_DIR);
+        if let Err(source) = fs::create_dir_all(&inbox_dir) {
+            return Err(PresetError::CreateDir {
+                path: inbox_dir,
+                source,
+            });
+        }
 
         let mut manager = Self {
             presets_dir,
             presets: Vec::new(),
+            files: HashMap::new(),
+            inbox: Vec::new(),
+            inbox_files: HashMap::new(),
+            tags: BTreeSet::new(),
         };
 
         manager.load_presets()?;
@@ -28,53 +178,113 @@ impl Manager {
     /// Save/delete operations will return errors.
     #[allow(clippy::missing_const_for_fn)] // Vec::new() is not const-stable
     pub fn new_from_presets(presets: Vec<Preset>) -> Self {
-        Self {
+        let mut manager = Self {
             presets_dir: PathBuf::new(),
             presets,
-        }
+            files: HashMap::new(),
+            inbox: Vec::new(),
+            inbox_files: HashMap::new(),
+            tags: BTreeSet::new(),
+        };
+        manager.index_tags();
+        manager
     }
 
-    pub fn load_presets(&mut self) -> Result<()> {
+    pub fn load_presets(&mut self) -> Result<(), PresetError> {
         self.presets.clear();
+        self.files.clear();
+        self.inbox.clear();
+        self.inbox_files.clear();
+        self.tags.clear();
 
         if !self.presets_dir.exists() {
             return Ok(());
         }
 
-        for entry in fs::read_dir(&self.presets_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        let dir = self.presets_dir.clone();
+        let mut library = self.load_dir(&dir)?;
+        let slots_dir = dir.join(SLOTS_DIR);
+        if slots_dir.is_dir() {
+            library.extend(self.load_dir(&slots_dir)?);
+        }
+        for (preset, path) in library {
+            self.files.insert(preset.name.clone(), path);
+            self.presets.push(preset);
+        }
+
+        let inbox_dir = dir.join(INBOX_DIR);
+        if inbox_dir.is_dir() {
+            for (preset, path) in self.load_dir(&inbox_dir)? {
+                self.inbox_files.insert(preset.name.clone(), path);
+                self.inbox.push(preset);
+            }
+        }
+
+        // Sort presets by name
+        self.presets.sort_by(|a, b| a.name.cmp(&b.name));
+        self.inbox.sort_by(|a, b| a.name.cmp(&b.name));
+        self.index_tags();
+
+        Ok(())
+    }
+
+    fn index_tags(&mut self) {
+        self.tags = self
+            .presets
+            .iter()
+            .flat_map(|preset| normalize_tags(preset.tags.iter().map(String::as_str)))
+            .collect();
+    }
+
+    /// Every tag on a library preset, sorted.
+    pub fn known_tags(&self) -> Vec<String> {
+        self.tags.iter().cloned().collect()
+    }
+
+    /// Library presets carrying every tag in `filter`; all of them for an
+    /// empty one.
+    pub fn presets_tagged(&self, filter: &[String]) -> Vec<&Preset> {
+        self.presets
+            .iter()
+            .filter(|preset| matches_tags(&preset.tags, filter))
+            .collect()
+    }
+
+    /// Every preset file in `dir` that loads, with the path it came from.
+    fn load_dir(&self, dir: &Path) -> Result<Vec<(Preset, PathBuf)>, PresetError> {
+        let read_dir_error = |source| PresetError::ReadDir {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let mut loaded = Vec::new();
+        for entry in fs::read_dir(dir).map_err(read_dir_error)? {
+            let path = entry.map_err(read_dir_error)?.path();
 
             if path.extension().and_then(|s| s.to_str()) == Some("json") {
                 match self.load_preset_file(&path) {
-                    Ok(preset) => self.presets.push(preset),
+                    Ok(preset) => loaded.push((preset, path)),
                     Err(e) => {
                         warn!("Failed to load preset {}: {e}", path.display());
                     }
                 }
             }
         }
+        Ok(loaded)
+    }
 
-        // Sort presets by name
-        self.presets.sort_by(|a, b| a.name.cmp(&b.name));
-
-        Ok(())
+    /// Re-read presets from disk so edits made outside the app are picked up.
+    /// No-op for in-memory managers, which have nothing to reload from.
+    pub fn refresh(&mut self) -> Result<(), PresetError> {
+        if self.presets_dir.as_os_str().is_empty() {
+            return Ok(());
+        }
+        self.load_presets()
     }
 
     #[allow(clippy::unused_self)]
     fn load_preset_file<P: AsRef<Path>>(&self, path: P) -> Result<Preset> {
         let content = fs::read_to_string(path.as_ref()).context("Failed to read preset file")?;
-
-        let mut preset: Preset = if let Ok(preset) = serde_json::from_str(&content) {
-            preset
-        } else {
-            // Try migration: parse as Value, strip Filter entries, extract input filters
-            let mut value: serde_json::Value =
-                serde_json::from_str(&content).context("Failed to parse preset JSON")?;
-            migrate_preset(&mut value);
-            serde_json::from_value(value).context("Failed to parse migrated preset")?
-        };
-
+        let mut preset = parse_preset(&content)?;
         enforce_stage_ordering(&mut preset);
         Ok(preset)
     }
@@ -83,11 +293,40 @@ impl Manager {
         if self.presets_dir.as_os_str().is_empty() {
             return Err(anyhow::anyhow!("Cannot save presets in read-only mode"));
         }
-        let filename = format!("{}.json", sanitize_filename(&preset.name));
-        let path = self.presets_dir.join(filename);
+        validate_preset_name(&preset.name)?;
+        if preset.is_read_only()
+            || self
+                .get_preset_by_name(&preset.name)
+                .is_some_and(Preset::is_read_only)
+        {
+            bail!(
+                "Preset '{}' was saved by a newer version; not overwriting it",
+                preset.name
+            );
+        }
+        let path = self.path_for(&preset.name);
+
+        // Solo is never serialized; say so rather than silently dropping it.
+        if preset
+            .stages
+            .iter()
+            .any(|stage| matches!(stage, StageConfig::MultibandSaturator(cfg) if cfg.any_soloed()))
+        {
+            warn!(
+                "Preset '{}' has a soloed multiband band; solo is not saved",
+                preset.name
+            );
+        }
 
         let json = serde_json::to_string_pretty(preset).context("Failed to serialize preset")?;
 
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).context("Failed to create preset directory")?;
+        }
+        // What's overwritten can be restored from the trash.
+        if path.exists() {
+            trash::copy_to_trash(&self.trash_dir(), &path)?;
+        }
         fs::write(&path, json).context("Failed to write preset file")?;
 
         // Reload presets to include the new/updated one
@@ -96,25 +335,186 @@ impl Manager {
         Ok(())
     }
 
-    pub fn delete_preset(&mut self, preset_name: &str) -> Result<()> {
+    /// Move a preset into the trash, from where [`Self::restore_preset`]
+    /// brings it back.
+    pub fn delete_preset(&mut self, preset_name: &str) -> Result<TrashedPreset> {
         if self.presets_dir.as_os_str().is_empty() {
             return Err(anyhow::anyhow!("Cannot delete presets in read-only mode"));
         }
-        let filename = format!("{}.json", sanitize_filename(preset_name));
-        let path = self.presets_dir.join(filename);
+        let path = self.path_for(preset_name);
 
         if path.exists() {
-            fs::remove_file(&path).context("Failed to delete preset file")?;
+            let trashed = trash::move_to_trash(&self.trash_dir(), &path)?;
 
             // Reload presets to reflect the deletion
             self.load_presets()?;
 
-            Ok(())
+            Ok(TrashedPreset {
+                name: preset_name.to_owned(),
+                trashed_at: fs::metadata(&trashed)?.modified()?,
+                path: trashed,
+            })
         } else {
             Err(anyhow::anyhow!("Preset file not found: {preset_name}"))
         }
     }
 
+    /// Deleted and overwritten presets, most recent first.
+    pub fn trashed_presets(&self) -> Result<Vec<TrashedPreset>> {
+        if self.presets_dir.as_os_str().is_empty() {
+            return Ok(Vec::new());
+        }
+        trash::list(&self.trash_dir())
+    }
+
+    /// Bring the preset at `trashed` back out of the trash. If its name has
+    /// since been taken it comes back numbered, as an import would. Returns
+    /// the name it was restored as.
+    pub fn restore_preset(&mut self, trashed: &Path) -> Result<String> {
+        self.check_in_trash(trashed)?;
+        let mut preset = self.load_preset_file(trashed)?;
+
+        if self.preset_exists(&preset.name) {
+            preset.name = self.unique_name(&preset.name);
+            self.save_preset(&preset)?;
+            fs::remove_file(trashed).context("Failed to remove restored preset from the trash")?;
+        } else {
+            let path = self.path_for(&preset.name);
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).context("Failed to create preset directory")?;
+            }
+            fs::rename(trashed, &path).context("Failed to restore preset")?;
+            self.load_presets()?;
+        }
+        Ok(preset.name)
+    }
+
+    /// Delete a trashed preset for good.
+    pub fn purge_trashed(&self, trashed: &Path) -> Result<()> {
+        self.check_in_trash(trashed)?;
+        fs::remove_file(trashed).context("Failed to purge trashed preset")
+    }
+
+    /// Purge what was trashed more than [`TRASH_RETENTION`] ago. Returns how
+    /// many presets went.
+    pub fn purge_expired_trash(&self) -> Result<usize> {
+        self.purge_trash_older_than(TRASH_RETENTION)
+    }
+
+    pub fn purge_trash_older_than(&self, age: Duration) -> Result<usize> {
+        if self.presets_dir.as_os_str().is_empty() {
+            return Ok(0);
+        }
+        trash::purge_older_than(&self.trash_dir(), age, SystemTime::now())
+    }
+
+    fn trash_dir(&self) -> PathBuf {
+        self.presets_dir.join(TRASH_DIR)
+    }
+
+    /// Refuse paths outside the trash, so restore and purge can't be pointed
+    /// at any other file.
+    fn check_in_trash(&self, path: &Path) -> Result<()> {
+        if self.presets_dir.as_os_str().is_empty() {
+            bail!("Cannot restore or purge presets in read-only mode");
+        }
+        if path.parent() != Some(self.trash_dir().as_path()) {
+            bail!("Not in the preset trash: {}", path.display());
+        }
+        Ok(())
+    }
+
+    /// Copy a preset file from elsewhere into the directory, read the way a
+    /// preset already here would be (old formats migrated, stage order
+    /// enforced) and saved under a valid name, with its stages unlocked. A
+    /// name already taken gets a numbered suffix: "Lead (2)", "Lead (3)".
+    /// Returns the name it was saved as.
+    pub fn import_preset(&mut self, path: &Path) -> Result<String> {
+        let mut preset = self.load_preset_file(path)?.without_locks();
+        validate_preset_name(&preset.name)?;
+        preset.name = self.unique_name(&preset.name);
+        self.save_preset(&preset)?;
+        Ok(preset.name)
+    }
+
+    /// Move a preset out of the inbox into the library. It goes through the
+    /// same checks as an import, so an invalid name is refused and a taken
+    /// one gets numbered. Returns the name it was kept as.
+    pub fn keep_inbox_preset(&mut self, name: &str) -> Result<String> {
+        let path = self.inbox_path(name)?;
+        let kept = self.import_preset(&path)?;
+        fs::remove_file(&path).context("Failed to remove kept preset from the inbox")?;
+        self.load_presets()?;
+        Ok(kept)
+    }
+
+    /// Move a preset from the inbox into the trash, from where
+    /// [`Self::restore_preset`] brings it back into the library.
+    pub fn discard_inbox_preset(&mut self, name: &str) -> Result<TrashedPreset> {
+        let path = self.inbox_path(name)?;
+        let trashed = trash::move_to_trash(&self.trash_dir(), &path)?;
+        self.load_presets()?;
+        Ok(TrashedPreset {
+            name: name.to_owned(),
+            trashed_at: fs::metadata(&trashed)?.modified()?,
+            path: trashed,
+        })
+    }
+
+    fn inbox_path(&self, name: &str) -> Result<PathBuf> {
+        if self.presets_dir.as_os_str().is_empty() {
+            bail!("Cannot sort the inbox in read-only mode");
+        }
+        self.inbox_files
+            .get(name)
+            .cloned()
+            .with_context(|| format!("Not in the preset inbox: {name}"))
+    }
+
+    /// `name`, or the first of "name (2)", "name (3)", … that no preset uses.
+    fn unique_name(&self, name: &str) -> String {
+        let mut unique = name.to_owned();
+        let mut n = 2;
+        while self.preset_exists(&unique) {
+            unique = format!("{name} ({n})");
+            n += 1;
+        }
+        unique
+    }
+
+    /// Point a saved preset at a different IR (or none) and write it back,
+    /// leaving the rest of the file as it was on disk.
+    pub fn set_ir_name(&mut self, preset_name: &str, ir_name: Option<String>) -> Result<()> {
+        let mut preset = self
+            .get_preset_by_name(preset_name)
+            .cloned()
+            .with_context(|| format!("Preset not found: {preset_name}"))?;
+        preset.ir_name = ir_name;
+        self.save_preset(&preset)
+    }
+
+    /// The file `name` is saved to: the one it was loaded from, or else a new
+    /// one named after it that no other preset is using. New quick-slot
+    /// presets go in their own subfolder.
+    fn path_for(&self, name: &str) -> PathBuf {
+        if let Some(path) = self.files.get(name) {
+            return path.clone();
+        }
+        let dir = if is_slot_preset_name(name) {
+            self.presets_dir.join(SLOTS_DIR)
+        } else {
+            self.presets_dir.clone()
+        };
+        let stem = preset_file_stem(name);
+        let mut path = dir.join(format!("{stem}.json"));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{stem}-{n}.json"));
+            n += 1;
+        }
+        path
+    }
+
     pub fn preset_exists(&self, name: &str) -> bool {
         self.presets.iter().any(|p| p.name == name)
     }
@@ -126,23 +526,48 @@ impl Manager {
     pub fn get_preset_by_name(&self, name: &str) -> Option<&Preset> {
         self.presets.iter().find(|p| p.name == name)
     }
+
+    /// The presets in `group`, sorted by name.
+    pub fn presets_in(&self, group: PresetGroup) -> &[Preset] {
+        match group {
+            PresetGroup::Library => &self.presets,
+            PresetGroup::Inbox => &self.inbox,
+        }
+    }
+
+    pub fn get_inbox_preset(&self, name: &str) -> Option<&Preset> {
+        self.inbox.iter().find(|p| p.name == name)
+    }
+}
+
+/// Parse a preset file's contents, migrating older versions first. One
+/// from a newer version loads as well as it can and keeps its version, which
+/// marks it read-only.
+pub fn parse_preset(json: &str) -> Result<Preset> {
+    let value: Value = serde_json::from_str(json).context("Failed to parse preset JSON")?;
+    let migrated = migrate(value, &PRESET_MIGRATIONS, "Preset");
+    serde_json::from_value(migrated.value).context("Failed to parse migrated preset")
 }
 
-/// Migrate old preset format: strip `"Filter"` entries from stages and extract
-/// highpass/lowpass cutoffs into an `input_filters` field.
-fn migrate_preset(value: &mut serde_json::Value) {
+/// Migrate the old preset format: strip `"Filter"` entries from stages and
+/// extract highpass/lowpass cutoffs into an `input_filters` field.
+fn migrate_v0_to_v1(mut value: Value) -> Value {
     let Some(obj) = value.as_object_mut() else {
-        return;
+        return value;
     };
 
     // Already migrated?
     if obj.contains_key("input_filters") {
-        return;
+        return value;
     }
 
     let Some(stages) = obj.get("stages").and_then(|s| s.as_array()).cloned() else {
-        return;
+        return value;
     };
+    // Presets without filter stages keep the default input filters.
+    if !stages.iter().any(|stage| stage.get("Filter").is_some()) {
+        return value;
+    }
 
     let mut hp_found = false;
     let mut lp_found = false;
@@ -200,16 +625,27 @@ fn migrate_preset(value: &mut serde_json::Value) {
     if let Ok(filters_value) = serde_json::to_value(input_filters) {
         obj.insert("input_filters".to_string(), filters_value);
     }
+    value
 }
 
 /// Enforce stage ordering: Amp stages first, then Effect stages.
-/// Preserves relative order within each category.
+/// Preserves relative order within each category. Groups may hold either,
+/// so each stays with the stage before it. Groups are flattened to one level
+/// of nesting.
 fn enforce_stage_ordering(preset: &mut Preset) {
     let mut amp_stages = Vec::new();
     let mut effect_stages = Vec::new();
+    let mut last = StageCategory::Amp;
 
-    for stage in preset.stages.drain(..) {
-        match stage.category() {
+    for mut stage in preset.stages.drain(..) {
+        let category = if let StageConfig::Group(group) = &mut stage {
+            group.flatten_nested();
+            last
+        } else {
+            stage.category()
+        };
+        last = category;
+        match category {
             StageCategory::Amp => amp_stages.push(stage),
             StageCategory::Effect => effect_stages.push(stage),
         }
@@ -219,22 +655,760 @@ fn enforce_stage_ordering(preset: &mut Preset) {
     preset.stages.append(&mut effect_stages);
 }
 
-fn sanitize_filename(name: &str) -> String {
-    name.chars()
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
-            _ => '_',
-        })
-        .collect()
+/// File stem for a preset name: letters and digits in any script, `-` and
+/// `_` are kept, and everything else (path separators and dots included)
+/// becomes `_`. Names Windows reserves for devices get a trailing `_`.
+pub(super) fn preset_file_stem(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.chars() {
+        let c = if c.is_alphanumeric() || c == '-' || c == '_' {
+            c
+        } else {
+            '_'
+        };
+        if stem.len() + c.len_utf8() > MAX_FILE_STEM_BYTES {
+            break;
+        }
+        stem.push(c);
+    }
+    if is_reserved_on_windows(&stem) {
+        stem.push('_');
+    }
+    stem
+}
+
+fn is_reserved_on_windows(stem: &str) -> bool {
+    let upper = stem.to_ascii_uppercase();
+    matches!(upper.as_str(), "CON" | "PRN" | "AUX" | "NUL")
+        || ((upper.starts_with("COM") || upper.starts_with("LPT"))
+            && upper.len() == 4
+            && upper.as_bytes()[3].is_ascii_digit())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::audio::denoiser::{DenoiseConfig, NoiseProfile};
+    use crate::preset::{PRESET_VERSION, StageType};
+
+    #[test]
+    fn test_set_ir_name_rewrites_preset_file() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let preset = Preset {
+            name: "Lead".to_string(),
+            ir_name: Some("mesa_v30.wav".to_string()),
+            ..Preset::default()
+        };
+        manager.save_preset(&preset)?;
+
+        manager.set_ir_name("Lead", Some("Cabs/mesa_v30.wav".to_string()))?;
+
+        let reloaded = Manager::new(tmp.path())?;
+        assert_eq!(
+            reloaded
+                .get_preset_by_name("Lead")
+                .unwrap()
+                .ir_name
+                .as_deref(),
+            Some("Cabs/mesa_v30.wav")
+        );
+        assert!(manager.set_ir_name("Missing", None).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_ordering_leaves_groups_with_the_stage_before_them() {
+        let group = |types: &[StageType]| {
+            let mut stage = StageConfig::from(StageType::Group);
+            if let StageConfig::Group(group) = &mut stage {
+                group.stages = types.iter().map(|&t| StageConfig::from(t)).collect();
+            }
+            stage
+        };
+        let mut preset = Preset {
+            stages: vec![
+                StageConfig::from(StageType::Preamp),
+                StageConfig::from(StageType::Delay),
+                group(&[StageType::Reverb, StageType::Tremolo]),
+                StageConfig::from(StageType::PowerAmp),
+                group(&[StageType::Compressor]),
+            ],
+            ..Preset::default()
+        };
+
+        enforce_stage_ordering(&mut preset);
+
+        let order: Vec<_> = preset.stages.iter().map(StageConfig::stage_type).collect();
+        assert_eq!(
+            order,
+            [
+                StageType::Preamp,
+                StageType::PowerAmp,
+                StageType::Group,
+                StageType::Delay,
+                StageType::Group,
+            ]
+        );
+        assert!(preset.stages[2].uses(StageType::Compressor));
+        assert!(preset.stages[4].uses(StageType::Reverb));
+    }
+
+    #[test]
+    fn test_import_numbers_a_taken_name() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let presets_dir = tmp.path().join("presets");
+        let mut manager = Manager::new(&presets_dir)?;
+        manager.save_preset(&named("Lead"))?;
+
+        let outside = tmp.path().join("lead.json");
+        fs::write(&outside, serde_json::to_string(&named("Lead"))?)?;
+        assert_eq!(manager.import_preset(&outside)?, "Lead (2)");
+        assert_eq!(manager.import_preset(&outside)?, "Lead (3)");
+        assert!(manager.preset_exists("Lead"));
+        assert_eq!(
+            preset_files(&presets_dir)?,
+            ["Lead.json", "Lead__2_.json", "Lead__3_.json"]
+        );
+
+        let broken = tmp.path().join("broken.json");
+        fs::write(&broken, "{ not json")?;
+        assert!(manager.import_preset(&broken).is_err());
+        fs::write(&broken, serde_json::to_string(&named(" "))?)?;
+        assert!(manager.import_preset(&broken).is_err());
+        assert_eq!(manager.get_presets().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_unlocks_stages() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path().join("presets"))?;
+
+        let mut shared = named("Shared");
+        let mut level = StageConfig::from(StageType::Level);
+        level.set_locked(true);
+        shared.stages.push(level);
+        let outside = tmp.path().join("shared.json");
+        fs::write(&outside, serde_json::to_string(&shared)?)?;
+
+        let name = manager.import_preset(&outside)?;
+        let imported = manager.get_preset_by_name(&name).unwrap();
+        assert!(!imported.stages.iter().any(StageConfig::locked));
+        Ok(())
+    }
+
+    #[test]
+    fn test_slot_presets_are_saved_in_their_own_folder() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        manager.save_preset(&named("Lead"))?;
+        manager.save_preset(&named(&slot_preset_name(1)))?;
+        manager.save_preset(&named(&slot_preset_name(QUICK_SLOTS + 1)))?;
+
+        assert_eq!(
+            preset_files(tmp.path())?,
+            ["Lead.json", "Slot_9.json", "slots"]
+        );
+        assert_eq!(preset_files(&tmp.path().join("slots"))?, ["Slot_1.json"]);
+
+        // Saving a slot again overwrites it, and it loads like any preset.
+        let mut slot = named(&slot_preset_name(1));
+        slot.ir_gain = 0.5;
+        manager.save_preset(&slot)?;
+        let reloaded = Manager::new(tmp.path())?;
+        assert_eq!(reloaded.get_presets().len(), 3);
+        assert_eq!(
+            reloaded.get_preset_by_name("Slot 1").map(|p| p.ir_gain),
+            Some(0.5)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_inbox_is_created_and_listed_apart_from_the_library() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let inbox = tmp.path().join(INBOX_DIR);
+        assert!(inbox.is_dir());
+
+        manager.save_preset(&named("Lead"))?;
+        fs::write(
+            inbox.join("crunch.json"),
+            serde_json::to_string(&named("Crunch"))?,
+        )?;
+        manager.refresh()?;
+
+        assert!(!manager.preset_exists("Crunch"));
+        let names = |group| -> Vec<String> {
+            manager
+                .presets_in(group)
+                .iter()
+                .map(|p| p.name.clone())
+                .collect()
+        };
+        assert_eq!(names(PresetGroup::Library), ["Lead"]);
+        assert_eq!(names(PresetGroup::Inbox), ["Crunch"]);
+        assert!(manager.get_inbox_preset("Crunch").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_keeping_an_inbox_preset_moves_it_into_the_library() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let inbox = tmp.path().join(INBOX_DIR);
+        manager.save_preset(&named("Lead"))?;
+        fs::write(
+            inbox.join("lead.json"),
+            serde_json::to_string(&named("Lead"))?,
+        )?;
+        fs::write(
+            inbox.join("clean.json"),
+            serde_json::to_string(&named("Clean"))?,
+        )?;
+        fs::write(
+            inbox.join("blank.json"),
+            serde_json::to_string(&named(" "))?,
+        )?;
+        manager.refresh()?;
+
+        assert_eq!(manager.keep_inbox_preset("Clean")?, "Clean");
+        // A taken name is numbered rather than overwriting the library's.
+        assert_eq!(manager.keep_inbox_preset("Lead")?, "Lead (2)");
+        assert_eq!(
+            preset_files(tmp.path())?,
+            ["Clean.json", "Lead.json", "Lead__2_.json"]
+        );
+        assert_eq!(preset_files(&inbox)?, ["blank.json"]);
+        assert_eq!(manager.presets_in(PresetGroup::Inbox).len(), 1);
+
+        // An invalid name stays in the inbox.
+        assert!(manager.keep_inbox_preset(" ").is_err());
+        assert_eq!(preset_files(&inbox)?, ["blank.json"]);
+        assert!(manager.keep_inbox_preset("Missing").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_discarding_an_inbox_preset_moves_it_to_the_trash() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let inbox = tmp.path().join(INBOX_DIR);
+        fs::write(
+            inbox.join("crunch.json"),
+            serde_json::to_string(&named("Crunch"))?,
+        )?;
+        manager.refresh()?;
+
+        let trashed = manager.discard_inbox_preset("Crunch")?;
+        assert_eq!(trashed.name, "Crunch");
+        assert!(manager.presets_in(PresetGroup::Inbox).is_empty());
+        assert_eq!(preset_files(&inbox)?, Vec::<String>::new());
+        assert_eq!(manager.trashed_presets()?, [trashed.clone()]);
+
+        // Restoring it brings it into the library, not back to the inbox.
+        assert_eq!(manager.restore_preset(&trashed.path)?, "Crunch");
+        assert!(manager.preset_exists("Crunch"));
+        assert!(manager.discard_inbox_preset("Crunch").is_err());
+        Ok(())
+    }
+
+    fn named(name: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            ..Preset::default()
+        }
+    }
+
+    /// What's in `dir`, leaving out the trash and the inbox.
+    fn preset_files(dir: &Path) -> Result<Vec<String>> {
+        let mut names = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<Result<Vec<_>>>()?;
+        names.retain(|name| name != TRASH_DIR && name != INBOX_DIR);
+        names.sort();
+        Ok(names)
+    }
+
+    fn trash_files(presets_dir: &Path) -> Result<Vec<String>> {
+        preset_files(&presets_dir.join(TRASH_DIR))
+    }
+
+    #[test]
+    fn test_delete_moves_to_the_trash_and_restore_brings_it_back() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        manager.save_preset(&Preset {
+            ir_gain: 0.5,
+            ..named("Lead")
+        })?;
+
+        let trashed = manager.delete_preset("Lead")?;
+        assert_eq!(trashed.name, "Lead");
+        assert!(!manager.preset_exists("Lead"));
+        assert_eq!(preset_files(tmp.path())?, Vec::<String>::new());
+        assert_eq!(manager.trashed_presets()?, [trashed.clone()]);
+        // The trash isn't loaded as presets.
+        assert!(Manager::new(tmp.path())?.get_presets().is_empty());
+
+        assert_eq!(manager.restore_preset(&trashed.path)?, "Lead");
+        assert_eq!(preset_files(tmp.path())?, ["Lead.json"]);
+        assert_eq!(
+            manager.get_preset_by_name("Lead").map(|p| p.ir_gain),
+            Some(0.5)
+        );
+        assert!(manager.trashed_presets()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trash_names_never_collide() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+
+        // The same preset deleted twice within a second.
+        manager.save_preset(&named("Lead"))?;
+        let first = manager.delete_preset("Lead")?;
+        manager.save_preset(&named("Lead"))?;
+        let second = manager.delete_preset("Lead")?;
+
+        assert_ne!(first.path, second.path);
+        assert_eq!(trash_files(tmp.path())?.len(), 2);
+        assert_eq!(manager.trashed_presets()?.len(), 2);
+
+        // Restoring both: the second finds its name taken and is numbered.
+        assert_eq!(manager.restore_preset(&first.path)?, "Lead");
+        assert_eq!(manager.restore_preset(&second.path)?, "Lead (2)");
+        assert!(manager.preset_exists("Lead"));
+        assert!(trash_files(tmp.path())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overwriting_keeps_the_previous_file_in_the_trash() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        manager.save_preset(&Preset {
+            ir_gain: 0.5,
+            ..named("Lead")
+        })?;
+        assert!(manager.trashed_presets()?.is_empty());
+
+        manager.save_preset(&Preset {
+            ir_gain: 0.7,
+            ..named("Lead")
+        })?;
+        let trashed = manager.trashed_presets()?;
+        assert_eq!(trashed.len(), 1);
+        let previous = parse_preset(&fs::read_to_string(&trashed[0].path)?)?;
+        assert_eq!(previous.ir_gain, 0.5);
+        assert_eq!(
+            manager.get_preset_by_name("Lead").map(|p| p.ir_gain),
+            Some(0.7)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_purge_removes_trashed_presets_for_good() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        for name in ["Old", "New", "Gone"] {
+            manager.save_preset(&named(name))?;
+        }
+        let old = manager.delete_preset("Old")?;
+        manager.delete_preset("New")?;
+        let gone = manager.delete_preset("Gone")?;
+
+        manager.purge_trashed(&gone.path)?;
+        assert!(!gone.path.exists());
+        assert!(manager.restore_preset(&gone.path).is_err());
+
+        // Trashed 31 days ago: purged on the next startup, the rest kept.
+        let month_ago = SystemTime::now() - Duration::from_secs(31 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(&old.path)?
+            .set_modified(month_ago)?;
+        assert_eq!(manager.purge_expired_trash()?, 1);
+        let left: Vec<_> = manager
+            .trashed_presets()?
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(left, ["New"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_and_purge_refuse_files_outside_the_trash() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        manager.save_preset(&named("Lead"))?;
+
+        let preset_file = tmp.path().join("Lead.json");
+        assert!(manager.restore_preset(&preset_file).is_err());
+        assert!(manager.purge_trashed(&preset_file).is_err());
+        assert!(preset_file.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_path_traversal_name_stays_in_preset_dir() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let presets_dir = tmp.path().join("presets");
+        let mut manager = Manager::new(&presets_dir)?;
+
+        manager.save_preset(&named("../../../etc/cron.d/pwn"))?;
+
+        assert_eq!(
+            preset_files(&presets_dir)?,
+            ["_________etc_cron_d_pwn.json"]
+        );
+        assert_eq!(preset_files(tmp.path())?, ["presets"]);
+        assert!(manager.preset_exists("../../../etc/cron.d/pwn"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_windows_reserved_names_get_a_suffix() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+
+        manager.save_preset(&named("con"))?;
+        manager.save_preset(&named("LPT1"))?;
+        manager.save_preset(&named("Console"))?;
+
+        assert_eq!(
+            preset_files(tmp.path())?,
+            ["Console.json", "LPT1_.json", "con_.json"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_names_are_rejected_with_a_typed_error() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+
+        let too_long = "x".repeat(MAX_PRESET_NAME_CHARS + 1);
+        let cases = [
+            ("   ", PresetNameError::Empty),
+            ("Lead\nTone", PresetNameError::ControlCharacter),
+            (
+                too_long.as_str(),
+                PresetNameError::TooLong {
+                    chars: MAX_PRESET_NAME_CHARS + 1,
+                    max: MAX_PRESET_NAME_CHARS,
+                },
+            ),
+        ];
+        for (name, expected) in cases {
+            let err = manager.save_preset(&named(name)).unwrap_err();
+            assert_eq!(err.downcast_ref::<PresetNameError>(), Some(&expected));
+        }
+        assert!(preset_files(tmp.path())?.is_empty());
+
+        // The limit counts characters, not bytes.
+        manager.save_preset(&named(&"音".repeat(MAX_PRESET_NAME_CHARS)))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_names_sharing_a_file_stem_stay_distinct() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+
+        manager.save_preset(&Preset {
+            description: Some("slash".to_string()),
+            ..named("a/b")
+        })?;
+        manager.save_preset(&Preset {
+            description: Some("underscore".to_string()),
+            ..named("a_b")
+        })?;
+        // Saving again rewrites the preset's own file, not its neighbour's.
+        manager.save_preset(&Preset {
+            description: Some("slash again".to_string()),
+            ..named("a/b")
+        })?;
+
+        assert_eq!(preset_files(tmp.path())?, ["a_b-2.json", "a_b.json"]);
+        let reloaded = Manager::new(tmp.path())?;
+        let description = |name| {
+            reloaded
+                .get_preset_by_name(name)
+                .and_then(|p| p.description.clone())
+        };
+        assert_eq!(description("a/b").as_deref(), Some("slash again"));
+        assert_eq!(description("a_b").as_deref(), Some("underscore"));
+
+        manager.delete_preset("a_b")?;
+        assert_eq!(preset_files(tmp.path())?, ["a_b.json"]);
+        assert!(manager.preset_exists("a/b"));
+        assert!(manager.delete_preset("a_b").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unicode_names_round_trip() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+
+        manager.save_preset(&named("清音"))?;
+        manager.save_preset(&named("失真"))?;
+
+        let reloaded = Manager::new(tmp.path())?;
+        let names: Vec<_> = reloaded.get_presets().iter().map(|p| &p.name).collect();
+        assert_eq!(names, ["失真", "清音"]);
+        assert_eq!(preset_files(tmp.path())?, ["失真.json", "清音.json"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_preset_dir_that_is_a_file_is_a_create_dir_error() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let file = tmp.path().join("presets");
+        fs::write(&file, "not a directory")?;
+
+        let err = Manager::new(&file)
+            .err()
+            .expect("a file is not a preset dir");
+        assert!(matches!(err, PresetError::CreateDir { ref path, .. } if *path == file));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unreadable_preset_is_skipped() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let group = StageConfig::Group(crate::amp::stages::group::GroupConfig {
+            stages: vec![StageConfig::from(crate::preset::StageType::Level)],
+            ..Default::default()
+        });
+        manager.save_preset(&Preset {
+            name: "Grouped".to_string(),
+            stages: vec![group.clone()],
+            ..Preset::default()
+        })?;
+        // A stage type this version doesn't know, as written by a newer one.
+        fs::write(
+            tmp.path().join("Future.json"),
+            r#"{"name": "Future", "stages": [{"Looper": {"bars": 4}}]}"#,
+        )?;
+
+        let reloaded = Manager::new(tmp.path())?;
+        assert!(!reloaded.preset_exists("Future"));
+        assert_eq!(
+            reloaded.get_preset_by_name("Grouped").unwrap().stages,
+            vec![group]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_collapse_state_round_trips() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let level = StageConfig::from(crate::preset::StageType::Level);
+        manager.save_preset(&Preset {
+            name: "Folded".to_string(),
+            stages: vec![level.clone(), level.clone(), level],
+            collapsed: vec![true, false, true],
+            ..Preset::default()
+        })?;
+        manager.save_preset(&named("Open"))?;
+
+        let reloaded = Manager::new(tmp.path())?;
+        assert_eq!(
+            reloaded.get_preset_by_name("Folded").unwrap().collapsed,
+            [true, false, true]
+        );
+        // Nothing collapsed is left out of the file.
+        assert!(!fs::read_to_string(tmp.path().join("Open.json"))?.contains("collapsed"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tag_index_is_rebuilt_on_scan() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let mut manager = Manager::new(tmp.path())?;
+        let tagged = |name: &str, tags: &[&str]| {
+            named(name).with_tags(tags.iter().map(|&t| t.to_owned()).collect())
+        };
+        manager.save_preset(&tagged("Djent", &["metal", "7-string"]))?;
+        manager.save_preset(&tagged("Chug", &["metal", "gig"]))?;
+        manager.save_preset(&named("Clean"))?;
+        assert_eq!(manager.known_tags(), ["7-string", "gig", "metal"]);
+
+        let names = |filter: &[&str]| -> Vec<String> {
+            let filter: Vec<String> = filter.iter().map(|&t| t.to_owned()).collect();
+            manager
+                .presets_tagged(&filter)
+                .iter()
+                .map(|p| p.name.clone())
+                .collect()
+        };
+        assert_eq!(names(&[]), ["Chug", "Clean", "Djent"]);
+        assert_eq!(names(&["metal"]), ["Chug", "Djent"]);
+        assert_eq!(names(&["metal", "gig"]), ["Chug"]);
+
+        // A file edited by hand joins the index in normal form.
+        fs::write(
+            tmp.path().join("Bass.json"),
+            r#"{"name": "Bass", "stages": [], "ir_name": null, "tags": [" Bass ", "GIG"]}"#,
+        )?;
+        manager.refresh()?;
+        assert_eq!(manager.known_tags(), ["7-string", "bass", "gig", "metal"]);
+        assert!(!fs::read_to_string(tmp.path().join("Clean.json"))?.contains("tags"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_preset_without_collapse_state_loads_expanded() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        fs::write(
+            tmp.path().join("Old.json"),
+            r#"{"name": "Old", "stages": [{"Level": {"gain": 1.0}}], "ir_name": null}"#,
+        )?;
+
+        let manager = Manager::new(tmp.path())?;
+        let preset = manager.get_preset_by_name("Old").unwrap();
+        assert_eq!(preset.stages.len(), 1);
+        assert!(preset.collapsed.is_empty());
+        assert!(preset.ir_alignment.is_neutral());
+        Ok(())
+    }
+
+    #[test]
+    fn test_preset_without_tilt_offset_loads_flat() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        fs::write(
+            tmp.path().join("Old.json"),
+            r#"{"name": "Old", "stages": [], "ir_name": null}"#,
+        )?;
+
+        let manager = Manager::new(tmp.path())?;
+        let preset = manager.get_preset_by_name("Old").unwrap();
+        assert_eq!(preset.tilt_offset_db, 0.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_noise_print_round_trips() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        fs::write(
+            tmp.path().join("Old.json"),
+            r#"{"name": "Old", "stages": [], "ir_name": null}"#,
+        )?;
+        let mut manager = Manager::new(tmp.path())?;
+        let old = manager.get_preset_by_name("Old").unwrap();
+        assert!(!old.denoise.is_active());
+
+        let denoise = DenoiseConfig {
+            reduction_db: 18.0,
+            profile: Some(NoiseProfile {
+                sample_rate: 48_000,
+                magnitudes: vec![0.25, 0.5, 0.125],
+            }),
+            ..DenoiseConfig::default()
+        };
+        let preset = Preset::default().with_denoise(denoise.clone());
+        manager.save_preset(&preset)?;
+
+        let manager = Manager::new(tmp.path())?;
+        let loaded = manager.get_preset_by_name(&preset.name).unwrap();
+        assert_eq!(loaded.denoise, denoise);
+        Ok(())
+    }
+
+    /// The presets shipped in the repo were written by earlier versions; every
+    /// one of them must still load into the current `StageConfig`.
+    #[test]
+    fn test_shipped_presets_still_load() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../presets");
+        let manager = Manager::new_from_presets(Vec::new());
+        let mut loaded = 0;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                let preset = manager
+                    .load_preset_file(&path)
+                    .with_context(|| format!("{} no longer loads", path.display()))?;
+                assert!(
+                    !preset.stages.is_empty(),
+                    "{} lost its stages",
+                    path.display()
+                );
+                loaded += 1;
+            }
+        }
+        assert!(loaded > 0, "no presets found in {}", dir.display());
+        Ok(())
+    }
+
+    #[test]
+    fn test_v0_preset_file_is_migrated_on_load() -> Result<()> {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/preset_v0.json");
+        let manager = Manager::new_from_presets(Vec::new());
+        let preset = manager.load_preset_file(&fixture)?;
+
+        assert_eq!(preset.version, PRESET_VERSION);
+        assert!(!preset.is_read_only());
+        assert_eq!(preset.name, "Old Lead");
+        assert_eq!(preset.stages.len(), 2);
+        assert!(preset.input_filters.hp_enabled);
+        assert!((preset.input_filters.hp_cutoff - 120.0).abs() < f32::EPSILON);
+        assert!(!preset.input_filters.lp_enabled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_v0_preset_without_filter_stages_keeps_default_filters() -> Result<()> {
+        let preset = parse_preset(
+            r#"{
+                "name": "Plain",
+                "stages": [{"Level": {"gain": 1.0}}],
+                "ir_name": null
+            }"#,
+        )?;
+        assert_eq!(preset.version, PRESET_VERSION);
+        assert!(preset.input_filters.hp_enabled);
+        assert!(preset.input_filters.lp_enabled);
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_preset_loads_read_only_and_is_not_overwritten() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let path = tmp.path().join("Future.json");
+        let json = format!(
+            r#"{{
+                "version": {},
+                "name": "Future",
+                "stages": [{{"Level": {{"gain": 1.0}}}}],
+                "ir_name": null,
+                "hologram": true
+            }}"#,
+            PRESET_VERSION + 1
+        );
+        fs::write(&path, &json)?;
+
+        let mut manager = Manager::new(tmp.path())?;
+        let preset = manager.get_preset_by_name("Future").unwrap();
+        assert!(preset.is_read_only());
+        assert_eq!(preset.stages.len(), 1);
+
+        assert!(manager.save_preset(&named("Future")).is_err());
+        assert!(manager.set_ir_name("Future", None).is_err());
+        assert_eq!(fs::read_to_string(&path)?, json);
+        Ok(())
+    }
 
     #[test]
     fn test_migrate_preset_extracts_filters() {
-        let mut value: serde_json::Value = serde_json::from_str(
+        let value: serde_json::Value = serde_json::from_str(
             r#"{
                 "name": "Test",
                 "stages": [
@@ -250,7 +1424,7 @@ mod tests {
         )
         .unwrap();
 
-        migrate_preset(&mut value);
+        let value = migrate_v0_to_v1(value);
 
         let obj = value.as_object().unwrap();
         assert!(obj.contains_key("input_filters"));
@@ -270,7 +1444,7 @@ mod tests {
 
     #[test]
     fn test_migrate_preset_skips_if_already_migrated() {
-        let mut value: serde_json::Value = serde_json::from_str(
+        let value: serde_json::Value = serde_json::from_str(
             r#"{
                 "name": "Test",
                 "stages": [{"Preamp": {"gain": 1.0, "bias": 0.0, "clipper_type": "ClassA"}}],
@@ -282,7 +1456,7 @@ mod tests {
         )
         .unwrap();
 
-        migrate_preset(&mut value);
+        let value = migrate_v0_to_v1(value);
 
         let stages = value["stages"].as_array().unwrap();
         assert_eq!(stages.len(), 1);
@@ -290,7 +1464,7 @@ mod tests {
 
     #[test]
     fn test_migrate_preset_filter_without_cutoff() {
-        let mut value: serde_json::Value = serde_json::from_str(
+        let value: serde_json::Value = serde_json::from_str(
             r#"{
                 "name": "Test",
                 "stages": [
@@ -304,7 +1478,7 @@ mod tests {
         )
         .unwrap();
 
-        migrate_preset(&mut value);
+        let value = migrate_v0_to_v1(value);
 
         let filters: InputFilterConfig =
             serde_json::from_value(value["input_filters"].clone()).unwrap();
@@ -1,10 +1,29 @@
 use serde::{Deserialize, Serialize};
 
+use crate::audio::ambience::AmbienceConfig;
+use crate::audio::denoiser::DenoiseConfig;
+use crate::audio::double_tracker::DoubleTrackerConfig;
+use crate::ir::align::IrAlignment;
+
+pub mod chain_update;
+pub mod diff;
+pub mod locks;
+pub mod macros;
 pub mod manager;
+pub mod setlist;
 pub mod stage_config;
+pub mod tags;
+pub mod tempo;
+pub mod trash;
 
-pub use manager::Manager;
+pub use macros::{MAX_MACROS, Macro, MacroCurve, MacroTarget};
+pub use manager::{
+    INBOX_DIR, Manager, PresetError, PresetGroup, PresetNameError, QUICK_SLOTS, parse_preset,
+    slot_preset_name,
+};
+pub use setlist::{Setlist, SetlistEntry, SetlistStore};
 pub use stage_config::{StageCategory, StageConfig, StageType};
+pub use trash::{TRASH_RETENTION, TrashedPreset};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct InputFilterConfig {
@@ -25,8 +44,14 @@ impl Default for InputFilterConfig {
     }
 }
 
+/// Schema version presets are saved with: one past the last migration.
+pub const PRESET_VERSION: u32 = manager::PRESET_MIGRATIONS.len() as u32;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
+    /// Schema version the preset was written with; 0 before versioning.
+    #[serde(default)]
+    pub version: u32,
     pub name: String,
     pub description: Option<String>,
     pub author: Option<String>,
@@ -34,10 +59,46 @@ pub struct Preset {
     pub ir_name: Option<String>,
     #[serde(default = "default_ir_gain")]
     pub ir_gain: f32,
+    /// Sample offset and polarity of the IR; neutral in older presets.
+    #[serde(default)]
+    pub ir_alignment: IrAlignment,
     #[serde(default)]
     pub pitch_shift_semitones: i32,
     #[serde(default)]
     pub input_filters: InputFilterConfig,
+    /// Input denoiser and the noise print it removes; none learned in
+    /// presets saved before it existed.
+    #[serde(default)]
+    pub denoise: DenoiseConfig,
+    /// Post-IR room and spring; off in presets saved before it existed.
+    #[serde(default)]
+    pub ambience: AmbienceConfig,
+    /// Post-IR stereo double; off in presets saved before it existed.
+    #[serde(default)]
+    pub double_tracker: DoubleTrackerConfig,
+    /// Added to the global output tilt while this preset is loaded, for
+    /// presets that need their own correction. 0 in presets without one.
+    #[serde(default)]
+    pub tilt_offset_db: f32,
+    /// Which stage cards were collapsed when the preset was saved, by chain
+    /// index. Empty in presets saved before it existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collapsed: Vec<bool>,
+    /// Controls each moving several stage parameters, at most
+    /// [`MAX_MACROS`]. Empty in presets saved before they existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub macros: Vec<Macro>,
+    /// Free-form labels to filter the preset list by, in the normal form of
+    /// [`tags::normalize_tag`]. Empty in presets saved before they existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Tempo of the song the preset is for, set on the metronome when the
+    /// preset is selected; see [`tempo::apply_tempo`]. Left alone when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bpm: Option<f32>,
+    /// Beats per bar and the note they count, as `(7, 8)` for 7/8.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_signature: Option<(u8, u8)>,
 }
 
 const fn default_ir_gain() -> f32 {
@@ -47,14 +108,25 @@ const fn default_ir_gain() -> f32 {
 impl Default for Preset {
     fn default() -> Self {
         Self {
+            version: PRESET_VERSION,
             name: "New Preset".to_string(),
             author: None,
             description: None,
             stages: Vec::new(),
             ir_name: None,
             ir_gain: 0.1,
+            ir_alignment: IrAlignment::default(),
             pitch_shift_semitones: 0,
             input_filters: InputFilterConfig::default(),
+            denoise: DenoiseConfig::UNLEARNED,
+            ambience: AmbienceConfig::default(),
+            double_tracker: DoubleTrackerConfig::default(),
+            tilt_offset_db: 0.0,
+            collapsed: Vec::new(),
+            macros: Vec::new(),
+            tags: Vec::new(),
+            bpm: None,
+            time_signature: None,
         }
     }
 }
@@ -69,17 +141,92 @@ impl Preset {
         input_filters: InputFilterConfig,
     ) -> Self {
         Self {
+            version: PRESET_VERSION,
             name,
             description: None,
             author: None,
             stages,
             ir_name,
             ir_gain,
+            ir_alignment: IrAlignment {
+                offset: 0,
+                inverted: false,
+            },
             pitch_shift_semitones,
             input_filters,
+            denoise: DenoiseConfig::UNLEARNED,
+            ambience: AmbienceConfig::OFF,
+            double_tracker: DoubleTrackerConfig::OFF,
+            tilt_offset_db: 0.0,
+            collapsed: Vec::new(),
+            macros: Vec::new(),
+            tags: Vec::new(),
+            bpm: None,
+            time_signature: None,
         }
     }
 
+    /// Written by a newer version than this one, so it mustn't be saved
+    /// back over: fields this version doesn't know about would be lost.
+    pub const fn is_read_only(&self) -> bool {
+        self.version > PRESET_VERSION
+    }
+
+    pub const fn with_ambience(mut self, ambience: AmbienceConfig) -> Self {
+        self.ambience = ambience;
+        self
+    }
+
+    pub const fn with_double_tracker(mut self, double_tracker: DoubleTrackerConfig) -> Self {
+        self.double_tracker = double_tracker;
+        self
+    }
+
+    pub const fn with_tilt_offset_db(mut self, tilt_offset_db: f32) -> Self {
+        self.tilt_offset_db = tilt_offset_db;
+        self
+    }
+
+    pub const fn with_ir_alignment(mut self, ir_alignment: IrAlignment) -> Self {
+        self.ir_alignment = ir_alignment;
+        self
+    }
+
+    pub fn with_collapsed(mut self, collapsed: Vec<bool>) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    pub fn with_denoise(mut self, denoise: DenoiseConfig) -> Self {
+        self.denoise = denoise;
+        self
+    }
+
+    pub fn with_macros(mut self, macros: Vec<Macro>) -> Self {
+        self.macros = macros;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub const fn with_tempo(mut self, bpm: Option<f32>, time_signature: Option<(u8, u8)>) -> Self {
+        self.bpm = bpm;
+        self.time_signature = time_signature;
+        self
+    }
+
+    /// The preset with its stages unlocked. Locks are the user's own
+    /// working setup, so a preset from someone else arrives without them.
+    pub fn without_locks(mut self) -> Self {
+        for stage in &mut self.stages {
+            stage.set_locked(false);
+        }
+        self
+    }
+
     pub fn with_description(mut self, description: &str) -> Self {
         self.description = Some(description.to_string());
         self
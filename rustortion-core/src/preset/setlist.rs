@@ -0,0 +1,229 @@
+use anyhow::{Context, Result, bail};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::manager::preset_file_stem;
+
+/// One song in a setlist: the preset it's played with and a reminder for
+/// the stage ("capo 2", "intro clean").
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetlistEntry {
+    pub preset: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl SetlistEntry {
+    pub const fn new(preset: String) -> Self {
+        Self {
+            preset,
+            notes: String::new(),
+        }
+    }
+}
+
+/// An ordered list of songs for a gig.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Setlist {
+    pub name: String,
+    #[serde(default)]
+    pub entries: Vec<SetlistEntry>,
+}
+
+impl Setlist {
+    pub const fn new(name: String) -> Self {
+        Self {
+            name,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Swap entry `idx` with the one before it. Returns whether it moved.
+    pub fn move_up(&mut self, idx: usize) -> bool {
+        if idx == 0 || idx >= self.entries.len() {
+            return false;
+        }
+        self.entries.swap(idx - 1, idx);
+        true
+    }
+
+    /// Swap entry `idx` with the one after it. Returns whether it moved.
+    pub fn move_down(&mut self, idx: usize) -> bool {
+        if idx + 1 >= self.entries.len() {
+            return false;
+        }
+        self.entries.swap(idx, idx + 1);
+        true
+    }
+
+    /// Indices of the entries naming a preset that `exists` doesn't know,
+    /// e.g. because it was renamed or deleted since the setlist was made.
+    pub fn missing_entries(&self, exists: impl Fn(&str) -> bool) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !exists(&entry.preset))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// The setlists saved as JSON files in their own directory, one per file,
+/// sorted by name.
+pub struct SetlistStore {
+    dir: PathBuf,
+    setlists: Vec<Setlist>,
+    /// The file each loaded setlist came from, by name.
+    files: HashMap<String, PathBuf>,
+}
+
+impl SetlistStore {
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create setlists directory {}", dir.display()))?;
+
+        let mut store = Self {
+            dir,
+            setlists: Vec::new(),
+            files: HashMap::new(),
+        };
+        store.load()?;
+        Ok(store)
+    }
+
+    /// Re-read every setlist from disk.
+    pub fn load(&mut self) -> Result<()> {
+        self.setlists.clear();
+        self.files.clear();
+
+        let entries = fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read setlists directory {}", self.dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            match load_setlist_file(&path) {
+                Ok(setlist) => {
+                    self.files.insert(setlist.name.clone(), path);
+                    self.setlists.push(setlist);
+                }
+                Err(e) => warn!("Failed to load setlist {}: {e}", path.display()),
+            }
+        }
+
+        self.setlists.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
+    pub fn save(&mut self, setlist: &Setlist) -> Result<()> {
+        if setlist.name.trim().is_empty() {
+            bail!("Setlist name is empty");
+        }
+        let path = self.path_for(&setlist.name);
+        let json = serde_json::to_string_pretty(setlist).context("Failed to serialize setlist")?;
+        fs::write(&path, json).context("Failed to write setlist file")?;
+        self.load()
+    }
+
+    pub fn delete(&mut self, name: &str) -> Result<()> {
+        let Some(path) = self.files.get(name) else {
+            bail!("Setlist not found: {name}");
+        };
+        fs::remove_file(path).context("Failed to delete setlist file")?;
+        self.load()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Setlist> {
+        self.setlists.iter().find(|s| s.name == name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.setlists.iter().map(|s| s.name.clone()).collect()
+    }
+
+    /// The file `name` is saved to: the one it was loaded from, or else a new
+    /// one named after it that no other setlist is using.
+    fn path_for(&self, name: &str) -> PathBuf {
+        if let Some(path) = self.files.get(name) {
+            return path.clone();
+        }
+        let stem = preset_file_stem(name);
+        let mut path = self.dir.join(format!("{stem}.json"));
+        let mut n = 2;
+        while path.exists() {
+            path = self.dir.join(format!("{stem}-{n}.json"));
+            n += 1;
+        }
+        path
+    }
+}
+
+fn load_setlist_file(path: &Path) -> Result<Setlist> {
+    let content = fs::read_to_string(path).context("Failed to read setlist file")?;
+    serde_json::from_str(&content).context("Failed to parse setlist JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setlist(name: &str, presets: &[&str]) -> Setlist {
+        Setlist {
+            name: name.to_string(),
+            entries: presets
+                .iter()
+                .map(|p| SetlistEntry::new((*p).to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_entries_move_within_bounds() {
+        let mut list = setlist("Gig", &["A", "B", "C"]);
+        assert!(list.move_up(2));
+        assert!(!list.move_up(0));
+        assert!(list.move_down(0));
+        assert!(!list.move_down(2));
+        let order: Vec<&str> = list.entries.iter().map(|e| e.preset.as_str()).collect();
+        assert_eq!(order, ["C", "A", "B"]);
+    }
+
+    #[test]
+    fn test_renamed_presets_are_reported_missing() {
+        let list = setlist("Gig", &["Clean", "Lead", "Crunch"]);
+        let presets = ["Clean", "Crunch (v2)"];
+        assert_eq!(list.missing_entries(|name| presets.contains(&name)), [1, 2]);
+    }
+
+    #[test]
+    fn test_setlists_round_trip_through_their_directory() -> Result<()> {
+        let tmp = tempfile::TempDir::new()?;
+        let dir = tmp.path().join("setlists");
+        let mut store = SetlistStore::new(&dir)?;
+
+        let mut friday = setlist("Friday/late", &["Clean", "Lead"]);
+        friday.entries[0].notes = "capo 2".to_string();
+        store.save(&friday)?;
+        store.save(&setlist("Acoustic", &["Clean"]))?;
+        assert!(store.save(&setlist("  ", &[])).is_err());
+
+        let reloaded = SetlistStore::new(&dir)?;
+        assert_eq!(reloaded.names(), ["Acoustic", "Friday/late"]);
+        assert_eq!(reloaded.get("Friday/late"), Some(&friday));
+
+        // Saving again rewrites the same file.
+        friday.entries.pop();
+        store.save(&friday)?;
+        assert_eq!(fs::read_dir(&dir)?.count(), 2);
+
+        store.delete("Acoustic")?;
+        assert_eq!(store.names(), ["Friday/late"]);
+        assert!(store.delete("Acoustic").is_err());
+        Ok(())
+    }
+}
@@ -3,9 +3,11 @@ use std::fmt::Display;
 use serde::{Deserialize, Serialize};
 
 use crate::amp::stages::Stage;
+use crate::amp::stages::capture::CaptureConfig;
 use crate::amp::stages::compressor::CompressorConfig;
 use crate::amp::stages::delay::DelayConfig;
-use crate::amp::stages::eq::EqConfig;
+use crate::amp::stages::eq::{BAND_NAMES, EqConfig};
+use crate::amp::stages::group::GroupConfig;
 use crate::amp::stages::level::LevelConfig;
 use crate::amp::stages::multiband_saturator::MultibandSaturatorConfig;
 use crate::amp::stages::nam::NamConfig;
@@ -15,6 +17,8 @@ use crate::amp::stages::preamp::PreampConfig;
 use crate::amp::stages::reverb::ReverbConfig;
 use crate::amp::stages::tonestack::ToneStackConfig;
 use crate::amp::stages::tremolo::TremoloConfig;
+use crate::amp::stages::trim::{CommonStageParams, TrimmedStage};
+use crate::amp::stages::wah::WahConfig;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StageCategory {
@@ -33,10 +37,13 @@ pub enum StageType {
     NoiseGate,
     MultibandSaturator,
     Nam,
+    Capture,
     Delay,
     Reverb,
     Eq,
     Tremolo,
+    Wah,
+    Group,
 }
 
 impl StageType {
@@ -49,10 +56,13 @@ impl StageType {
         Self::NoiseGate,
         Self::MultibandSaturator,
         Self::Nam,
+        Self::Capture,
         Self::Delay,
         Self::Reverb,
         Self::Eq,
         Self::Tremolo,
+        Self::Wah,
+        Self::Group,
     ];
 
     pub const fn category(self) -> StageCategory {
@@ -64,8 +74,33 @@ impl StageType {
             | Self::Level
             | Self::NoiseGate
             | Self::MultibandSaturator
-            | Self::Nam => StageCategory::Amp,
-            Self::Delay | Self::Reverb | Self::Eq | Self::Tremolo => StageCategory::Effect,
+            | Self::Nam
+            | Self::Capture
+            | Self::Group => StageCategory::Amp,
+            Self::Delay | Self::Reverb | Self::Eq | Self::Tremolo | Self::Wah => {
+                StageCategory::Effect
+            }
+        }
+    }
+
+    /// Abbreviation for one-line chain summaries, e.g. in recordings.
+    pub const fn short_name(self) -> &'static str {
+        match self {
+            Self::Preamp => "Preamp",
+            Self::Compressor => "Comp",
+            Self::ToneStack => "TS",
+            Self::PowerAmp => "PA",
+            Self::Level => "Level",
+            Self::NoiseGate => "Gate",
+            Self::MultibandSaturator => "MBSat",
+            Self::Nam => "NAM",
+            Self::Capture => "Capture",
+            Self::Delay => "Delay",
+            Self::Reverb => "Reverb",
+            Self::Eq => "EQ",
+            Self::Tremolo => "Trem",
+            Self::Wah => "Wah",
+            Self::Group => "Group",
         }
     }
 
@@ -89,15 +124,18 @@ impl Display for StageType {
             Self::NoiseGate => write!(f, "Noise Gate"),
             Self::MultibandSaturator => write!(f, "Multiband Saturator"),
             Self::Nam => write!(f, "NAM"),
+            Self::Capture => write!(f, "Capture"),
             Self::Delay => write!(f, "Delay"),
             Self::Reverb => write!(f, "Reverb"),
             Self::Eq => write!(f, "EQ"),
             Self::Tremolo => write!(f, "Tremolo"),
+            Self::Wah => write!(f, "Wah"),
+            Self::Group => write!(f, "Group"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum StageConfig {
     Preamp(PreampConfig),
     Compressor(CompressorConfig),
@@ -107,10 +145,13 @@ pub enum StageConfig {
     NoiseGate(NoiseGateConfig),
     MultibandSaturator(MultibandSaturatorConfig),
     Nam(NamConfig),
+    Capture(CaptureConfig),
     Delay(DelayConfig),
     Reverb(ReverbConfig),
     Eq(EqConfig),
     Tremolo(TremoloConfig),
+    Wah(WahConfig),
+    Group(GroupConfig),
 }
 
 impl From<StageType> for StageConfig {
@@ -126,17 +167,21 @@ impl From<StageType> for StageConfig {
                 Self::MultibandSaturator(MultibandSaturatorConfig::default())
             }
             StageType::Nam => Self::Nam(NamConfig::default()),
+            StageType::Capture => Self::Capture(CaptureConfig::default()),
             StageType::Delay => Self::Delay(DelayConfig::default()),
             StageType::Reverb => Self::Reverb(ReverbConfig::default()),
             StageType::Eq => Self::Eq(EqConfig::default()),
             StageType::Tremolo => Self::Tremolo(TremoloConfig::default()),
+            StageType::Wah => Self::Wah(WahConfig::default()),
+            StageType::Group => Self::Group(GroupConfig::default()),
         }
     }
 }
 
 impl StageConfig {
+    /// Build the runtime stage, wrapped in its trims so they can change live.
     pub fn to_runtime(&self, sample_rate: f32) -> Box<dyn Stage> {
-        match self {
+        let stage: Box<dyn Stage> = match self {
             Self::Preamp(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Compressor(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::ToneStack(cfg) => Box::new(cfg.to_stage(sample_rate)),
@@ -145,11 +190,15 @@ impl StageConfig {
             Self::NoiseGate(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::MultibandSaturator(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Nam(cfg) => Box::new(cfg.to_stage(sample_rate)),
+            Self::Capture(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Delay(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Reverb(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Eq(cfg) => Box::new(cfg.to_stage(sample_rate)),
             Self::Tremolo(cfg) => Box::new(cfg.to_stage(sample_rate)),
-        }
+            Self::Wah(cfg) => Box::new(cfg.to_stage(sample_rate)),
+            Self::Group(cfg) => Box::new(cfg.to_stage(sample_rate)),
+        };
+        Box::new(TrimmedStage::new(stage, self.common()))
     }
 
     pub const fn stage_type(&self) -> StageType {
@@ -162,10 +211,13 @@ impl StageConfig {
             Self::NoiseGate(_) => StageType::NoiseGate,
             Self::MultibandSaturator(_) => StageType::MultibandSaturator,
             Self::Nam(_) => StageType::Nam,
+            Self::Capture(_) => StageType::Capture,
             Self::Delay(_) => StageType::Delay,
             Self::Reverb(_) => StageType::Reverb,
             Self::Eq(_) => StageType::Eq,
             Self::Tremolo(_) => StageType::Tremolo,
+            Self::Wah(_) => StageType::Wah,
+            Self::Group(_) => StageType::Group,
         }
     }
 
@@ -173,6 +225,14 @@ impl StageConfig {
         self.stage_type().category()
     }
 
+    /// Whether this is a `stage_type` stage, or a group holding one.
+    pub fn uses(&self, stage_type: StageType) -> bool {
+        match self {
+            Self::Group(cfg) => cfg.stages.iter().any(|s| s.uses(stage_type)),
+            _ => self.stage_type() == stage_type,
+        }
+    }
+
     pub const fn bypassed(&self) -> bool {
         match self {
             Self::Preamp(cfg) => cfg.bypassed,
@@ -183,10 +243,13 @@ impl StageConfig {
             Self::NoiseGate(cfg) => cfg.bypassed,
             Self::MultibandSaturator(cfg) => cfg.bypassed,
             Self::Nam(cfg) => cfg.bypassed,
+            Self::Capture(cfg) => cfg.bypassed,
             Self::Delay(cfg) => cfg.bypassed,
             Self::Reverb(cfg) => cfg.bypassed,
             Self::Eq(cfg) => cfg.bypassed,
             Self::Tremolo(cfg) => cfg.bypassed,
+            Self::Wah(cfg) => cfg.bypassed,
+            Self::Group(cfg) => cfg.bypassed,
         }
     }
 
@@ -200,10 +263,157 @@ impl StageConfig {
             Self::NoiseGate(cfg) => cfg.bypassed = bypassed,
             Self::MultibandSaturator(cfg) => cfg.bypassed = bypassed,
             Self::Nam(cfg) => cfg.bypassed = bypassed,
+            Self::Capture(cfg) => cfg.bypassed = bypassed,
             Self::Delay(cfg) => cfg.bypassed = bypassed,
             Self::Reverb(cfg) => cfg.bypassed = bypassed,
             Self::Eq(cfg) => cfg.bypassed = bypassed,
             Self::Tremolo(cfg) => cfg.bypassed = bypassed,
+            Self::Wah(cfg) => cfg.bypassed = bypassed,
+            Self::Group(cfg) => cfg.bypassed = bypassed,
+        }
+    }
+
+    pub const fn common(&self) -> CommonStageParams {
+        match self {
+            Self::Preamp(cfg) => cfg.common,
+            Self::Compressor(cfg) => cfg.common,
+            Self::ToneStack(cfg) => cfg.common,
+            Self::PowerAmp(cfg) => cfg.common,
+            Self::Level(cfg) => cfg.common,
+            Self::NoiseGate(cfg) => cfg.common,
+            Self::MultibandSaturator(cfg) => cfg.common,
+            Self::Nam(cfg) => cfg.common,
+            Self::Capture(cfg) => cfg.common,
+            Self::Delay(cfg) => cfg.common,
+            Self::Reverb(cfg) => cfg.common,
+            Self::Eq(cfg) => cfg.common,
+            Self::Tremolo(cfg) => cfg.common,
+            Self::Wah(cfg) => cfg.common,
+            Self::Group(cfg) => cfg.common,
+        }
+    }
+
+    pub const fn common_mut(&mut self) -> &mut CommonStageParams {
+        match self {
+            Self::Preamp(cfg) => &mut cfg.common,
+            Self::Compressor(cfg) => &mut cfg.common,
+            Self::ToneStack(cfg) => &mut cfg.common,
+            Self::PowerAmp(cfg) => &mut cfg.common,
+            Self::Level(cfg) => &mut cfg.common,
+            Self::NoiseGate(cfg) => &mut cfg.common,
+            Self::MultibandSaturator(cfg) => &mut cfg.common,
+            Self::Nam(cfg) => &mut cfg.common,
+            Self::Capture(cfg) => &mut cfg.common,
+            Self::Delay(cfg) => &mut cfg.common,
+            Self::Reverb(cfg) => &mut cfg.common,
+            Self::Eq(cfg) => &mut cfg.common,
+            Self::Tremolo(cfg) => &mut cfg.common,
+            Self::Wah(cfg) => &mut cfg.common,
+            Self::Group(cfg) => &mut cfg.common,
+        }
+    }
+
+    /// Locked by the user; see [`CommonStageParams::locked`].
+    pub const fn locked(&self) -> bool {
+        self.common().locked
+    }
+
+    pub const fn set_locked(&mut self, locked: bool) {
+        self.common_mut().locked = locked;
+    }
+
+    /// Names of the continuous parameters, as `set_parameter` knows them.
+    /// Model stages and groups have none.
+    pub fn parameter_names(&self) -> Vec<&'static str> {
+        self.clone()
+            .float_params_mut()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Current value of the continuous parameter `name`.
+    pub fn parameter(&self, name: &str) -> Option<f32> {
+        self.clone()
+            .float_params_mut()
+            .into_iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Continuous parameters by the name the stage's `set_parameter` knows
+    /// them by, each with the config field holding it.
+    pub fn float_params_mut(&mut self) -> Vec<(&'static str, &mut f32)> {
+        match self {
+            Self::Preamp(c) => vec![("gain", &mut c.gain), ("bias", &mut c.bias)],
+            Self::Compressor(c) => vec![
+                ("threshold", &mut c.threshold_db),
+                ("ratio", &mut c.ratio),
+                ("attack", &mut c.attack_ms),
+                ("release", &mut c.release_ms),
+                ("makeup", &mut c.makeup_db),
+            ],
+            Self::ToneStack(c) => vec![
+                ("bass", &mut c.bass),
+                ("mid", &mut c.mid),
+                ("treble", &mut c.treble),
+                ("presence", &mut c.presence),
+            ],
+            Self::PowerAmp(c) => vec![
+                ("drive", &mut c.drive),
+                ("sag", &mut c.sag),
+                ("sag_release", &mut c.sag_release),
+            ],
+            Self::Level(c) => vec![("gain", &mut c.gain)],
+            Self::NoiseGate(c) => vec![
+                ("threshold", &mut c.threshold_db),
+                ("ratio", &mut c.ratio),
+                ("attack", &mut c.attack_ms),
+                ("hold", &mut c.hold_ms),
+                ("release", &mut c.release_ms),
+            ],
+            Self::MultibandSaturator(c) => vec![
+                ("low_drive", &mut c.low_drive),
+                ("mid_drive", &mut c.mid_drive),
+                ("high_drive", &mut c.high_drive),
+                ("low_level", &mut c.low_level),
+                ("mid_level", &mut c.mid_level),
+                ("high_level", &mut c.high_level),
+                ("low_freq", &mut c.low_freq),
+                ("high_freq", &mut c.high_freq),
+            ],
+            Self::Delay(c) => {
+                let mut params = vec![
+                    ("feedback", &mut c.feedback),
+                    ("mix", &mut c.mix),
+                    ("duck_depth", &mut c.duck_depth_db),
+                    ("duck_release", &mut c.duck_release_ms),
+                ];
+                // A synced delay's time follows the tempo.
+                if !c.sync {
+                    params.push(("delay_time", &mut c.delay_ms));
+                }
+                params
+            }
+            Self::Reverb(c) => vec![
+                ("room_size", &mut c.room_size),
+                ("damping", &mut c.damping),
+                ("mix", &mut c.mix),
+            ],
+            Self::Eq(c) => BAND_NAMES.into_iter().zip(c.gains.iter_mut()).collect(),
+            Self::Tremolo(c) => vec![
+                ("rate", &mut c.rate_hz),
+                ("depth", &mut c.depth),
+                ("shape", &mut c.shape),
+            ],
+            Self::Wah(c) => vec![
+                ("position", &mut c.position),
+                ("q", &mut c.q),
+                ("sensitivity", &mut c.sensitivity),
+                ("attack", &mut c.attack_ms),
+                ("release", &mut c.release_ms),
+            ],
+            Self::Nam(_) | Self::Capture(_) | Self::Group(_) => Vec::new(),
         }
     }
 }
@@ -226,6 +436,7 @@ mod tests {
                 output_gain_db: -2.0,
                 mix: 0.75,
                 bypassed: true,
+                ..NamConfig::default()
             }),
             // A passthrough NAM stage (no model) must round-trip as `None`, not "".
             StageConfig::Nam(NamConfig::default()),
@@ -253,4 +464,25 @@ mod tests {
         };
         assert_eq!(cfg.model_name, None);
     }
+
+    #[test]
+    fn stage_without_trims_loads_neutral_and_saves_unchanged() {
+        let json = r#"{"Level":{"gain":0.5,"bypassed":false}}"#;
+        let cfg: StageConfig = serde_json::from_str(json).expect("deserialize stage");
+        assert!(cfg.common().is_neutral());
+        assert_eq!(serde_json::to_string(&cfg).expect("serialize stage"), json);
+    }
+
+    #[test]
+    fn trims_apply_to_the_runtime_stage() {
+        let mut cfg = StageConfig::from(StageType::Level);
+        cfg.common_mut().invert = true;
+        let mut stage = cfg.to_runtime(48_000.0);
+        assert_eq!(stage.process(0.5), -0.5);
+
+        stage
+            .set_parameter("invert", 0.0)
+            .expect("invert is a parameter");
+        assert_eq!(stage.process(0.5), 0.5);
+    }
 }
@@ -0,0 +1,116 @@
+//! Free-form preset tags ("metal", "bass", "gig"), typed as a comma-separated
+//! list and compared in a normal form: trimmed and lowercase.
+
+/// The form tags are stored and compared in, or `None` for a blank one.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
+/// Tags typed as `"Metal, gig , metal,,"`: normalized, blanks dropped, and
+/// each kept once, in the order first typed.
+pub fn parse_tags(input: &str) -> Vec<String> {
+    normalize_tags(input.split(','))
+}
+
+/// `tags` in normal form, blanks dropped and each kept once.
+pub fn normalize_tags<'a>(tags: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.into_iter().filter_map(normalize_tag) {
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Tags as typed into the editor: `"metal, gig"`.
+pub fn format_tags(tags: &[String]) -> String {
+    tags.join(", ")
+}
+
+/// Whether a preset tagged `tags` passes a filter on `filter`: it has every
+/// one of them. An empty filter passes everything.
+pub fn matches_tags(tags: &[String], filter: &[String]) -> bool {
+    filter.iter().all(|wanted| {
+        tags.iter()
+            .any(|tag| normalize_tag(tag).as_deref() == Some(wanted.as_str()))
+    })
+}
+
+/// Known tags that complete what's being typed after the last comma of
+/// `input`, leaving out those already in it.
+pub fn suggest_tags<'a>(input: &str, known: &'a [String]) -> Vec<&'a str> {
+    let (typed, partial) = split_partial(input);
+    let typed = parse_tags(typed);
+    let partial = normalize_tag(partial).unwrap_or_default();
+    known
+        .iter()
+        .filter(|tag| tag.starts_with(&partial) && !typed.contains(tag))
+        .map(String::as_str)
+        .collect()
+}
+
+/// `input` with the tag being typed replaced by `tag`, ready for the next.
+pub fn complete_tag(input: &str, tag: &str) -> String {
+    let (typed, _) = split_partial(input);
+    let mut tags = parse_tags(typed);
+    tags.extend(normalize_tag(tag));
+    format!(
+        "{}, ",
+        format_tags(&normalize_tags(tags.iter().map(String::as_str)))
+    )
+}
+
+/// The finished tags before the last comma, and what's after it.
+fn split_partial(input: &str) -> (&str, &str) {
+    input.rsplit_once(',').unwrap_or(("", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|&t| t.to_owned()).collect()
+    }
+
+    #[test]
+    fn parsing_trims_lowercases_and_dedupes() {
+        assert_eq!(
+            parse_tags(" Metal, gig ,METAL,, low   Gain ,"),
+            tags(&["metal", "gig", "low gain"])
+        );
+        assert!(parse_tags("").is_empty());
+        assert!(parse_tags(" , ,").is_empty());
+        assert_eq!(normalize_tag("  Bass "), Some("bass".to_owned()));
+        assert_eq!(normalize_tag("   "), None);
+        assert_eq!(format_tags(&tags(&["metal", "gig"])), "metal, gig");
+    }
+
+    #[test]
+    fn filter_needs_every_tag() {
+        let preset = tags(&["metal", "gig", "7-string"]);
+        assert!(matches_tags(&preset, &[]));
+        assert!(matches_tags(&preset, &tags(&["metal"])));
+        assert!(matches_tags(&preset, &tags(&["gig", "metal"])));
+        assert!(!matches_tags(&preset, &tags(&["metal", "bass"])));
+        assert!(!matches_tags(&[], &tags(&["metal"])));
+
+        // Tags edited by hand in the file still match in normal form.
+        assert!(matches_tags(&tags(&[" Metal "]), &tags(&["metal"])));
+    }
+
+    #[test]
+    fn suggestions_complete_the_last_tag() {
+        let known = tags(&["bass", "blues", "gig", "metal"]);
+        assert_eq!(suggest_tags("", &known), ["bass", "blues", "gig", "metal"]);
+        assert_eq!(suggest_tags("B", &known), ["bass", "blues"]);
+        assert_eq!(suggest_tags("metal, b", &known), ["bass", "blues"]);
+        assert_eq!(suggest_tags("gig, ", &known), ["bass", "blues", "metal"]);
+
+        assert_eq!(complete_tag("metal, bl", "blues"), "metal, blues, ");
+        assert_eq!(complete_tag("", "gig"), "gig, ");
+        assert_eq!(complete_tag("gig, g", "gig"), "gig, ");
+    }
+}
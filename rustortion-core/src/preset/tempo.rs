@@ -0,0 +1,110 @@
+//! The tempo of the song a preset is for, typed as a BPM and a time
+//! signature like `"6/8"`, and handed to the metronome when the preset is
+//! selected.
+
+use crate::metronome::{BEATS_PER_BAR_RANGE, MAX_BPM, MIN_BPM, MetronomeConfig};
+
+/// Note values a time signature's lower number may be.
+const NOTE_VALUES: [u8; 6] = [1, 2, 4, 8, 16, 32];
+
+/// A typed BPM, pulled into the metronome's range. `None` for a blank or
+/// something that isn't a number.
+pub fn parse_bpm(input: &str) -> Option<f32> {
+    let bpm: f32 = input.trim().parse().ok()?;
+    bpm.is_finite().then(|| bpm.clamp(MIN_BPM, MAX_BPM))
+}
+
+/// A typed time signature: `"7/8"`, or just `"3"` for 3/4. `None` for a
+/// blank, or beats per bar the metronome can't count.
+pub fn parse_time_signature(input: &str) -> Option<(u8, u8)> {
+    let (beats, note) = input.split_once('/').unwrap_or((input, "4"));
+    let beats: u8 = beats.trim().parse().ok()?;
+    let note: u8 = note.trim().parse().ok()?;
+    (BEATS_PER_BAR_RANGE.contains(&usize::from(beats)) && NOTE_VALUES.contains(&note))
+        .then_some((beats, note))
+}
+
+/// A BPM as typed into the editor: `"92"`, `"92.5"`, or blank.
+pub fn format_bpm(bpm: Option<f32>) -> String {
+    bpm.map(|bpm| bpm.to_string()).unwrap_or_default()
+}
+
+/// A time signature as typed into the editor: `"6/8"`, or blank.
+pub fn format_time_signature(time_signature: Option<(u8, u8)>) -> String {
+    time_signature
+        .map(|(beats, note)| format!("{beats}/{note}"))
+        .unwrap_or_default()
+}
+
+/// `metronome` at a preset's tempo. Each of `bpm` and `time_signature` that
+/// is set replaces what the metronome had; one that isn't leaves it be. The
+/// metronome counts beats of whatever note the signature's lower number
+/// says, so the BPM of a 6/8 song is in eighth notes.
+pub fn apply_tempo(
+    metronome: MetronomeConfig,
+    bpm: Option<f32>,
+    time_signature: Option<(u8, u8)>,
+) -> MetronomeConfig {
+    MetronomeConfig {
+        bpm: bpm.unwrap_or(metronome.bpm),
+        beats_per_bar: time_signature
+            .map_or(metronome.beats_per_bar, |(beats, _)| usize::from(beats)),
+        ..metronome
+    }
+    .clamped()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bpm_parses_and_clamps() {
+        assert_eq!(parse_bpm(" 92.5 "), Some(92.5));
+        assert_eq!(parse_bpm("1000"), Some(MAX_BPM));
+        assert_eq!(parse_bpm(""), None);
+        assert_eq!(parse_bpm("fast"), None);
+        assert_eq!(parse_bpm("NaN"), None);
+    }
+
+    #[test]
+    fn time_signature_parses_with_quarter_notes_by_default() {
+        assert_eq!(parse_time_signature("7/8"), Some((7, 8)));
+        assert_eq!(parse_time_signature(" 3 "), Some((3, 4)));
+        assert_eq!(parse_time_signature("4/3"), None);
+        assert_eq!(parse_time_signature("13/4"), None);
+        assert_eq!(parse_time_signature(""), None);
+    }
+
+    #[test]
+    fn formatting_round_trips() {
+        assert_eq!(parse_bpm(&format_bpm(Some(140.0))), Some(140.0));
+        assert_eq!(format_bpm(None), "");
+        assert_eq!(
+            parse_time_signature(&format_time_signature(Some((6, 8)))),
+            Some((6, 8))
+        );
+        assert_eq!(format_time_signature(None), "");
+    }
+
+    #[test]
+    fn unset_fields_keep_the_metronome_setting() {
+        let metronome = MetronomeConfig {
+            enabled: true,
+            bpm: 100.0,
+            beats_per_bar: 4,
+            in_monitor: true,
+        };
+
+        assert_eq!(apply_tempo(metronome, None, None), metronome);
+
+        let tempo_only = apply_tempo(metronome, Some(150.0), None);
+        assert_eq!(tempo_only.bpm, 150.0);
+        assert_eq!(tempo_only.beats_per_bar, 4);
+
+        let signature_only = apply_tempo(metronome, None, Some((6, 8)));
+        assert_eq!(signature_only.bpm, 100.0);
+        assert_eq!(signature_only.beats_per_bar, 6);
+        assert!(signature_only.enabled && signature_only.in_monitor);
+    }
+}
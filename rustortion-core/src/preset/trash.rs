@@ -0,0 +1,120 @@
+//! Deleted and overwritten presets, kept in a `.trash` folder of the preset
+//! directory so a misclick can be undone. Each file is stamped with the time
+//! it was trashed, which is what expiry goes by.
+
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use log::warn;
+
+use super::manager::parse_preset;
+
+/// Subfolder of the preset directory trashed presets are kept in. Its files
+/// aren't loaded as presets.
+pub const TRASH_DIR: &str = ".trash";
+
+/// How long a trashed preset is kept before it is purged on startup.
+pub const TRASH_RETENTION: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A preset file in the trash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedPreset {
+    /// The preset's display name.
+    pub name: String,
+    pub path: PathBuf,
+    pub trashed_at: SystemTime,
+}
+
+/// Move `file` into `trash_dir`.
+pub(super) fn move_to_trash(trash_dir: &Path, file: &Path) -> Result<PathBuf> {
+    let dest = trash_path(trash_dir, file)?;
+    fs::rename(file, &dest).context("Failed to move preset to the trash")?;
+    stamp(&dest)?;
+    Ok(dest)
+}
+
+/// Copy `file` into `trash_dir`, before it is overwritten.
+pub(super) fn copy_to_trash(trash_dir: &Path, file: &Path) -> Result<PathBuf> {
+    let dest = trash_path(trash_dir, file)?;
+    fs::copy(file, &dest).context("Failed to copy preset to the trash")?;
+    stamp(&dest)?;
+    Ok(dest)
+}
+
+/// What's in `trash_dir`, most recently trashed first. Files that don't
+/// parse as presets are skipped.
+pub(super) fn list(trash_dir: &Path) -> Result<Vec<TrashedPreset>> {
+    if !trash_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut trashed = Vec::new();
+    for entry in fs::read_dir(trash_dir).context("Failed to read the preset trash")? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let preset = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| parse_preset(&json));
+        match preset {
+            Ok(preset) => trashed.push(TrashedPreset {
+                name: preset.name,
+                trashed_at: fs::metadata(&path)?.modified()?,
+                path,
+            }),
+            Err(e) => warn!("Skipping unreadable trashed preset {}: {e}", path.display()),
+        }
+    }
+    trashed.sort_by(|a, b| b.trashed_at.cmp(&a.trashed_at));
+    Ok(trashed)
+}
+
+/// Delete everything in `trash_dir` trashed more than `age` before `now`.
+/// Returns how many files went.
+pub(super) fn purge_older_than(trash_dir: &Path, age: Duration, now: SystemTime) -> Result<usize> {
+    let mut purged = 0;
+    for trashed in list(trash_dir)? {
+        if now
+            .duration_since(trashed.trashed_at)
+            .is_ok_and(|elapsed| elapsed > age)
+        {
+            fs::remove_file(&trashed.path).with_context(|| {
+                format!("Failed to purge trashed preset {}", trashed.path.display())
+            })?;
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+/// `<stem>_<YYYYmmdd_HHMMSS>.json` in `trash_dir`, numbered when a preset
+/// with the same file name was trashed in the same second.
+fn trash_path(trash_dir: &Path, file: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(trash_dir).context("Failed to create the preset trash")?;
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+
+    let mut path = trash_dir.join(format!("{stem}_{timestamp}.json"));
+    let mut n = 2;
+    while path.exists() {
+        path = trash_dir.join(format!("{stem}_{timestamp}-{n}.json"));
+        n += 1;
+    }
+    Ok(path)
+}
+
+/// Mark `path` as trashed now. A move keeps the preset's own modification
+/// time, which would otherwise make an old preset look long expired.
+fn stamp(path: &Path) -> Result<()> {
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::now())
+        .context("Failed to stamp trashed preset")
+}
@@ -0,0 +1,15 @@
+//! Helpers shared by the unit tests.
+
+/// Deterministic noise in `-1.0..1.0` (xorshift32).
+pub fn noise(seed: u32, len: usize) -> Vec<f32> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let unit = state as f32 / u32::MAX as f32;
+            unit.mul_add(2.0, -1.0)
+        })
+        .collect()
+}
@@ -1,78 +1,299 @@
-use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
+/// Analysis window at 48 kHz; scaled up with the sample rate so the window
+/// always spans the same time (~85 ms, over two periods of B0).
 const BUFFER_SIZE: usize = 4096;
+const BUFFER_REFERENCE_RATE: usize = 48_000;
+/// Samples between analyses. Windows overlap by the rest, so readings keep
+/// coming this often however long the window is.
+const HOP_SIZE: usize = 512;
+/// Readings the display takes the median of.
+const SMOOTHING_READINGS: usize = 3;
 const A1_HZ: f32 = 50.1;
 const E6_HZ: f32 = 1245.0;
+/// Lowest note YIN searches for: A0, a semitone under a 5-string's low B.
+const A0_HZ: f32 = 27.5;
+/// YIN takes the first dip of the normalised difference below this.
+const YIN_THRESHOLD: f32 = 0.15;
+/// Readings under this confidence are shown but not trusted.
+pub const MIN_CONFIDENCE: f32 = 0.8;
+/// Notes the tuner can be locked to, as MIDI numbers: B0 to E6.
+const LOWEST_TARGET: u8 = 23;
+const HIGHEST_TARGET: u8 = 88;
+/// Highest harmonic of a locked note that is folded back onto it.
+const MAX_HARMONIC: u32 = 8;
+/// How far from a harmonic of the locked note a reading may sit and still be
+/// taken as that harmonic.
+const HARMONIC_TOLERANCE_CENTS: f32 = 50.0;
+const NOTES: [&str; 12] = [
+    "A", "A#", "B", "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#",
+];
+
+/// Pitch detection method.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TunerAlgorithm {
+    /// Average magnitude difference. Cheap, fine from A1 upward.
+    #[default]
+    Amdf,
+    /// YIN with parabolic interpolation. Holds lock down to A0 without
+    /// octave jumps, for 5-string bass and drop tunings.
+    Yin,
+}
+
+impl TunerAlgorithm {
+    pub const ALL: [Self; 2] = [Self::Amdf, Self::Yin];
+}
+
+impl std::fmt::Display for TunerAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Amdf => write!(f, "AMDF"),
+            Self::Yin => write!(f, "YIN (low B)"),
+        }
+    }
+}
+
+/// How the tuner dialog shows the offset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TunerMode {
+    /// A needle over ±50 cents.
+    #[default]
+    Needle,
+    /// Rotating bands that drift as fast as the note is off, for setting
+    /// intonation to a fraction of a cent.
+    Strobe,
+}
+
+impl std::fmt::Display for TunerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Needle => write!(f, "Needle"),
+            Self::Strobe => write!(f, "Strobe"),
+        }
+    }
+}
+
+/// A note the tuner can be locked to, by MIDI number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetNote(u8);
+
+impl TargetNote {
+    pub const ALL: [Self; (HIGHEST_TARGET - LOWEST_TARGET + 1) as usize] = {
+        let mut all = [Self(LOWEST_TARGET); (HIGHEST_TARGET - LOWEST_TARGET + 1) as usize];
+        let mut i = 0;
+        while i < all.len() {
+            all[i] = Self(LOWEST_TARGET + i as u8);
+            i += 1;
+        }
+        all
+    };
+
+    pub fn frequency(self) -> f32 {
+        440.0 * ((f32::from(self.0) - 69.0) / 12.0).exp2()
+    }
+
+    /// Take `detection` as the nearest harmonic of this note that it sits
+    /// close to, and bring it down to the fundamental. Returns the folded
+    /// detection and the harmonic, 1 if it wasn't folded.
+    fn fold(self, detection: Detection) -> (Detection, u32) {
+        let target = self.frequency();
+        let harmonic = (detection.frequency / target).round() as u32;
+        if (2..=MAX_HARMONIC).contains(&harmonic) {
+            let folded = detection.frequency / harmonic as f32;
+            if cents_between(folded, target).abs() <= HARMONIC_TOLERANCE_CENTS {
+                let folded = Detection {
+                    frequency: folded,
+                    ..detection
+                };
+                return (folded, harmonic);
+            }
+        }
+        (detection, 1)
+    }
+}
+
+impl std::fmt::Display for TargetNote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (note, octave) = note_name(i32::from(self.0) - 69);
+        write!(f, "{note}{octave}")
+    }
+}
 
 pub struct Tuner {
+    /// The analysis window, oldest sample first.
     buffer: Vec<f32>,
+    /// YIN difference function, one entry per lag up to the A0 period.
+    difference: Vec<f32>,
     sample_rate: usize,
-    info: Arc<ArcSwap<TunerInfo>>,
-    enabled: bool,
+    algorithm: TunerAlgorithm,
+    shared: Arc<Shared>,
+    /// Whether the last block saw the tuner on, to drop the window when it
+    /// is switched off from the handle.
+    running: bool,
 }
 
 pub struct TunerHandle {
-    info: Arc<ArcSwap<TunerInfo>>,
+    shared: Arc<Shared>,
+}
+
+/// Between the audio thread and the GUI: the on/off switch, and the latest
+/// reading packed into one word so it's handed over without a lock or an
+/// allocation.
+struct Shared {
+    enabled: AtomicBool,
+    reading: AtomicU64,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct TunerInfo {
     pub frequency: Option<f32>,
     pub note: Option<String>,
+    /// Offset from `note`, to a fraction of a cent.
     pub cents_off: Option<f32>,
     pub in_tune: bool,
+    /// How periodic the analysed window was, 0–1. See [`MIN_CONFIDENCE`].
+    pub confidence: f32,
+    /// With a note lock, which harmonic of the locked note the reading was
+    /// taken as; 1 for the fundamental.
+    pub harmonic: Option<u32>,
+    /// How surely the reading is that harmonic, 0–1: its confidence, less
+    /// the further it sits from the harmonic. The plain confidence without
+    /// a lock.
+    pub harmonic_confidence: f32,
+}
+
+/// A detected pitch before it's turned into a note for display.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Detection {
+    frequency: f32,
+    confidence: f32,
+}
+
+impl Detection {
+    /// Frequency bits high, confidence bits low; zero for no pitch.
+    fn pack(detection: Option<Self>) -> u64 {
+        detection.map_or(0, |d| {
+            (u64::from(d.frequency.to_bits()) << 32) | u64::from(d.confidence.to_bits())
+        })
+    }
+
+    fn unpack(bits: u64) -> Option<Self> {
+        let frequency = f32::from_bits((bits >> 32) as u32);
+        (frequency > 0.0).then(|| Self {
+            frequency,
+            confidence: f32::from_bits(bits as u32),
+        })
+    }
 }
 
 impl Tuner {
     pub fn new(sample_rate: usize) -> (Self, TunerHandle) {
-        let info = Arc::new(ArcSwap::from_pointee(TunerInfo::default()));
+        let shared = Arc::new(Shared {
+            enabled: AtomicBool::new(false),
+            reading: AtomicU64::new(0),
+        });
+        let buffer_size = BUFFER_SIZE.max(BUFFER_SIZE * sample_rate / BUFFER_REFERENCE_RATE);
+        let max_lag = (sample_rate as f32 / A0_HZ) as usize;
 
         (
             Self {
-                buffer: Vec::with_capacity(BUFFER_SIZE),
+                buffer: Vec::with_capacity(buffer_size),
+                difference: vec![0.0; max_lag + 2],
                 sample_rate,
-                info: Arc::clone(&info),
-                enabled: false,
+                algorithm: TunerAlgorithm::default(),
+                shared: Arc::clone(&shared),
+                running: false,
             },
-            TunerHandle { info },
+            TunerHandle { shared },
         )
     }
 
-    pub fn process(&mut self, samples: &[f32]) {
-        if !self.enabled {
-            return;
+    /// A tuner at `sample_rate` that reports to an existing handle, to
+    /// replace the running one when the sample rate changes.
+    pub fn for_handle(sample_rate: usize, handle: &TunerHandle) -> Self {
+        let (mut tuner, _) = Self::new(sample_rate);
+        tuner.shared = Arc::clone(&handle.shared);
+        tuner
+    }
+
+    /// Analyse a block if the tuner is on. Returns whether it is, read once
+    /// per block so the caller mutes exactly the blocks the tuner heard.
+    pub fn process(&mut self, mut samples: &[f32]) -> bool {
+        let enabled = self.is_enabled();
+        if enabled != self.running {
+            self.running = enabled;
+            self.buffer.clear();
+        }
+        if !enabled {
+            return false;
         }
 
-        self.buffer.extend_from_slice(samples);
-        if self.buffer.len() >= BUFFER_SIZE {
-            let detected_frequency = self.simple_amdf();
-            self.info.store(Arc::new(detected_frequency.into()));
+        // Never grow past the preallocated window: fill it, analyse, slide
+        // it on by a hop, repeat.
+        let hop = HOP_SIZE.min(self.buffer.capacity());
+        while !samples.is_empty() {
+            if self.buffer.len() == self.buffer.capacity() {
+                self.buffer.copy_within(hop.., 0);
+                self.buffer.truncate(self.buffer.len() - hop);
+            }
 
-            self.buffer.clear();
+            let room = self.buffer.capacity() - self.buffer.len();
+            let (head, rest) = samples.split_at(room.min(samples.len()));
+            self.buffer.extend_from_slice(head);
+            samples = rest;
+
+            if self.buffer.len() == self.buffer.capacity() {
+                let detection = match self.algorithm {
+                    TunerAlgorithm::Amdf => self.simple_amdf(),
+                    TunerAlgorithm::Yin => self.yin(),
+                };
+                self.shared
+                    .reading
+                    .store(Detection::pack(detection), Ordering::Release);
+            }
         }
+        true
     }
 
-    pub const fn is_enabled(&self) -> bool {
-        self.enabled
+    pub fn is_enabled(&self) -> bool {
+        self.shared.enabled.load(Ordering::Acquire)
     }
 
+    /// Start or stop pitch detection, the same switch as
+    /// [`TunerHandle::set_enabled`]. The window is kept, only emptied, so
+    /// toggling never allocates.
     pub fn set_enabled(&mut self, enabled: bool) {
-        self.enabled = enabled;
-        if !enabled {
+        self.shared.enabled.store(enabled, Ordering::Release);
+        self.running = enabled;
+        self.buffer.clear();
+    }
+
+    pub const fn algorithm(&self) -> TunerAlgorithm {
+        self.algorithm
+    }
+
+    /// Switch detection method. Both share the preallocated buffers, so this
+    /// is safe on the audio thread; the partly filled window is dropped.
+    pub fn set_algorithm(&mut self, algorithm: TunerAlgorithm) {
+        if self.algorithm != algorithm {
+            self.algorithm = algorithm;
             self.buffer.clear();
-            self.info.store(Arc::new(TunerInfo::default()));
         }
     }
 
-    fn simple_amdf(&self) -> Option<f32> {
+    fn is_silent(&self) -> bool {
         if self.buffer.is_empty() {
-            return None;
+            return true;
         }
         let rms =
             (self.buffer.iter().map(|x| x * x).sum::<f32>() / self.buffer.len() as f32).sqrt();
+        rms < 0.01
+    }
 
-        if rms < 0.01 {
+    fn simple_amdf(&self) -> Option<Detection> {
+        if self.is_silent() {
             return None;
         }
 
@@ -81,12 +302,16 @@ impl Tuner {
 
         let mut best_period = 0;
         let mut min_diff = f32::MAX;
+        let mut total_diff = 0.0;
+        let mut lags = 0;
 
         for lag in min_period..max_period.min(self.buffer.len() / 2) {
             let mut diff = 0.0;
             for i in 0..(self.buffer.len() - lag) {
                 diff += (self.buffer[i] - self.buffer[i + lag]).abs();
             }
+            total_diff += diff;
+            lags += 1;
 
             if diff < min_diff {
                 min_diff = diff;
@@ -95,30 +320,218 @@ impl Tuner {
         }
 
         if best_period > 0 {
-            Some(self.sample_rate as f32 / best_period as f32)
+            let mean_diff = total_diff / lags as f32;
+            Some(Detection {
+                frequency: self.sample_rate as f32 / best_period as f32,
+                confidence: (1.0 - min_diff / mean_diff).clamp(0.0, 1.0),
+            })
         } else {
             None
         }
     }
+
+    /// De Cheveigné & Kawahara's YIN: squared difference over a window of
+    /// `buffer - max_lag` samples, cumulative-mean normalised, first dip under
+    /// the threshold, refined with a parabola through the raw difference.
+    fn yin(&mut self) -> Option<Detection> {
+        if self.is_silent() {
+            return None;
+        }
+
+        let max_lag = self.difference.len() - 2;
+        let min_lag = ((self.sample_rate as f32 / E6_HZ) as usize).max(2);
+        let window = self.buffer.len().checked_sub(max_lag + 1)?;
+
+        let buffer = &self.buffer;
+        let difference = &mut self.difference;
+        difference[0] = 0.0;
+        for (lag, value) in difference.iter_mut().enumerate().skip(1) {
+            *value = buffer[..window]
+                .iter()
+                .zip(&buffer[lag..])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+        }
+
+        // Cumulative-mean normalisation, computed on the fly so the raw
+        // values stay available for interpolation. The search is one lag
+        // behind so a sub-threshold value is only taken at its local minimum.
+        let mut running_sum = 0.0;
+        let mut previous = f32::MAX;
+        let mut lowest: Option<(usize, f32)> = None;
+        let mut first_dip = None;
+        for (lag, &value) in difference.iter().enumerate().take(max_lag + 1).skip(1) {
+            running_sum += value;
+            let normalised = if running_sum > 0.0 {
+                value * lag as f32 / running_sum
+            } else {
+                1.0
+            };
+
+            if lag > min_lag && previous < YIN_THRESHOLD && previous <= normalised {
+                first_dip = Some((lag - 1, previous));
+                break;
+            }
+            if lag >= min_lag && lowest.is_none_or(|(_, value)| normalised < value) {
+                lowest = Some((lag, normalised));
+            }
+            previous = normalised;
+        }
+
+        let (period, normalised) = first_dip.or(lowest)?;
+        let (before, at, after) = (
+            difference[period - 1],
+            difference[period],
+            difference[period + 1],
+        );
+        let curvature = 2.0f32.mul_add(-at, before) + after;
+        let offset = if curvature > 0.0 {
+            (0.5 * (before - after) / curvature).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+
+        Some(Detection {
+            frequency: self.sample_rate as f32 / (period as f32 + offset),
+            confidence: (1.0 - normalised).clamp(0.0, 1.0),
+        })
+    }
 }
 
 impl TunerHandle {
     pub fn get_tuner_info(&self) -> TunerInfo {
-        self.info.load().as_ref().clone()
+        self.detection().into()
+    }
+
+    fn detection(&self) -> Option<Detection> {
+        Detection::unpack(self.shared.reading.load(Ordering::Acquire))
+    }
+
+    /// Forget the last reading, so a freshly opened tuner doesn't show a
+    /// stale note.
+    pub fn reset(&self) {
+        self.shared.reading.store(0, Ordering::Release);
+    }
+
+    /// Start or stop pitch detection without going through the engine's
+    /// message queue: the audio thread picks the switch up on its next block.
+    /// Hold-to-tune flips this on every press and release.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.shared.enabled.store(enabled, Ordering::Release);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.shared.enabled.load(Ordering::Acquire)
     }
 }
 
-impl From<Option<f32>> for TunerInfo {
-    fn from(freq: Option<f32>) -> Self {
-        match freq {
+/// Steadies the tuner display: shows the median of the last few readings,
+/// so one stray reading doesn't flick the needle.
+#[derive(Debug, Default)]
+pub struct TunerSmoother {
+    /// Newest last, folded onto the locked note if there is one.
+    recent: Vec<Detection>,
+    lock: Option<TargetNote>,
+    /// Harmonic of the locked note the newest reading was taken as.
+    harmonic: u32,
+}
+
+impl TunerSmoother {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read `handle` and return what to display. Losing the pitch clears
+    /// the history, so the next note isn't dragged toward the last one.
+    pub fn update(&mut self, handle: &TunerHandle) -> TunerInfo {
+        let detection = self.push(handle.detection());
+        match self.lock {
+            Some(target) => TunerInfo::locked(detection, target, self.harmonic),
+            None => detection.into(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.recent.clear();
+    }
+
+    pub const fn lock(&self) -> Option<TargetNote> {
+        self.lock
+    }
+
+    /// Show the offset from `lock` only, whatever note is heard, with its
+    /// harmonics folded back onto it; or follow the nearest note again.
+    pub fn set_lock(&mut self, lock: Option<TargetNote>) {
+        self.lock = lock;
+        self.recent.clear();
+    }
+
+    fn push(&mut self, detection: Option<Detection>) -> Option<Detection> {
+        let Some(mut detection) = detection else {
+            self.recent.clear();
+            return None;
+        };
+        if let Some(target) = self.lock {
+            (detection, self.harmonic) = target.fold(detection);
+        }
+        if self.recent.len() == SMOOTHING_READINGS {
+            self.recent.remove(0);
+        }
+        self.recent.push(detection);
+
+        let mut sorted = self.recent.clone();
+        sorted.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        Some(sorted[sorted.len() / 2])
+    }
+}
+
+impl TunerInfo {
+    /// Whether the reading is stable enough to tune by.
+    pub fn is_confident(&self) -> bool {
+        self.frequency.is_some() && self.confidence >= MIN_CONFIDENCE
+    }
+
+    /// The offset of `detection`, already folded onto `target`'s
+    /// fundamental from `harmonic`, from `target`.
+    fn locked(detection: Option<Detection>, target: TargetNote, harmonic: u32) -> Self {
+        let Some(Detection {
+            frequency,
+            confidence,
+        }) = detection
+        else {
+            return Self::default();
+        };
+        let cents = cents_between(frequency, target.frequency());
+        Self {
+            frequency: Some(frequency),
+            note: Some(target.to_string()),
+            cents_off: Some(cents),
+            in_tune: cents.abs() < 5.0 && confidence >= MIN_CONFIDENCE,
+            confidence,
+            harmonic: Some(harmonic),
+            harmonic_confidence: confidence
+                * (1.0 - cents.abs() / HARMONIC_TOLERANCE_CENTS).clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl From<Option<Detection>> for TunerInfo {
+    fn from(detection: Option<Detection>) -> Self {
+        match detection {
             None => Self::default(),
-            Some(f) => {
-                let (note, octave, cents) = freq_to_note(f);
+            Some(Detection {
+                frequency,
+                confidence,
+            }) => {
+                let (note, octave, cents) = freq_to_note(frequency);
                 Self {
-                    frequency: Some(f),
+                    frequency: Some(frequency),
                     note: Some(format!("{note}{octave}")),
                     cents_off: Some(cents),
-                    in_tune: cents.abs() < 5.0,
+                    in_tune: cents.abs() < 5.0 && confidence >= MIN_CONFIDENCE,
+                    confidence,
+                    harmonic: None,
+                    harmonic_confidence: confidence,
                 }
             }
         }
@@ -131,20 +544,183 @@ fn freq_to_note(freq: f32) -> (&'static str, i32, f32) {
     let semitones_from_a4 = 12.0 * (freq / a4).log2();
     let note_number = semitones_from_a4.round() as i32;
     let cents = (semitones_from_a4 - note_number as f32) * 100.0;
+    let (note, octave) = note_name(note_number);
 
-    const NOTES: [&str; 12] = [
-        "A", "A#", "B", "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#",
-    ];
+    (note, octave, cents)
+}
+
+/// Name and octave of the note `note_number` semitones from A4.
+fn note_name(note_number: i32) -> (&'static str, i32) {
     let note_index = note_number.rem_euclid(12) as usize;
-    let octave = 4 + (note_number + 9) / 12; // +9 because A is 9 semitones before C
+    // +9 because A is 9 semitones before C; floor so notes under C4 don't
+    // round toward octave 4.
+    let octave = 4 + (note_number + 9).div_euclid(12);
 
-    (NOTES[note_index], octave, cents)
+    (NOTES[note_index], octave)
+}
+
+fn cents_between(frequency: f32, reference: f32) -> f32 {
+    1200.0 * (frequency / reference).log2()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SAMPLE_RATE: usize = 48_000;
+    const B0_HZ: f32 = 30.87;
+    const E1_HZ: f32 = 41.2;
+
+    /// A bass-like tone: weak fundamental under strong 2nd and 3rd
+    /// harmonics, the shape that pulls naive detectors up an octave.
+    fn bass_tone(frequency: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|n| {
+                let phase = std::f32::consts::TAU * frequency * n as f32 / SAMPLE_RATE as f32;
+                0.15f32.mul_add(
+                    (3.0 * phase).sin(),
+                    0.2f32.mul_add(phase.sin(), 0.35 * (2.0 * phase).sin()),
+                )
+            })
+            .collect()
+    }
+
+    fn detect(algorithm: TunerAlgorithm, frequency: f32) -> TunerInfo {
+        let (mut tuner, handle) = Tuner::new(SAMPLE_RATE);
+        tuner.set_algorithm(algorithm);
+        tuner.set_enabled(true);
+        for block in bass_tone(frequency, 2 * BUFFER_SIZE).chunks(256) {
+            tuner.process(block);
+        }
+        handle.get_tuner_info()
+    }
+
+    fn assert_locks(frequency: f32) {
+        let info = detect(TunerAlgorithm::Yin, frequency);
+        let detected = info.frequency.expect("no pitch detected");
+        let error = cents_between(detected, frequency);
+        assert!(
+            error.abs() <= 1.0,
+            "{frequency} Hz read as {detected} Hz ({error:+.2} cents)"
+        );
+        assert!(info.is_confident(), "confidence {}", info.confidence);
+    }
+
+    #[test]
+    fn yin_locks_on_low_b() {
+        assert_locks(B0_HZ);
+        assert_eq!(
+            detect(TunerAlgorithm::Yin, B0_HZ).note.as_deref(),
+            Some("B0")
+        );
+    }
+
+    #[test]
+    fn yin_locks_on_low_e() {
+        assert_locks(E1_HZ);
+        assert_eq!(
+            detect(TunerAlgorithm::Yin, E1_HZ).note.as_deref(),
+            Some("E1")
+        );
+    }
+
+    #[test]
+    fn yin_tracks_bent_notes() {
+        for (base, cents) in [(B0_HZ, -30.0), (B0_HZ, 45.0), (E1_HZ, 20.0), (E1_HZ, 100.0)] {
+            assert_locks(base * (cents / 1200.0).exp2());
+        }
+    }
+
+    #[test]
+    fn yin_covers_the_guitar_range() {
+        for frequency in [82.41, 110.0, 329.63, 987.77] {
+            assert_locks(frequency);
+        }
+    }
+
+    #[test]
+    fn amdf_cannot_reach_low_b() {
+        let detected = detect(TunerAlgorithm::Amdf, B0_HZ).frequency;
+        assert!(detected.is_none_or(|f| cents_between(f, B0_HZ).abs() > 1.0));
+    }
+
+    #[test]
+    fn noise_is_not_confident() {
+        let (mut tuner, handle) = Tuner::new(SAMPLE_RATE);
+        tuner.set_algorithm(TunerAlgorithm::Yin);
+        tuner.set_enabled(true);
+        let mut state = 0x1234_5678_u32;
+        let noise: Vec<f32> = (0..BUFFER_SIZE)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as f32 / u32::MAX as f32 - 0.5
+            })
+            .collect();
+        tuner.process(&noise);
+        let info = handle.get_tuner_info();
+        assert!(!info.is_confident());
+        assert!(!info.in_tune);
+    }
+
+    #[test]
+    fn readings_arrive_every_hop() {
+        let (mut tuner, handle) = Tuner::new(SAMPLE_RATE);
+        handle.set_enabled(true);
+        let tone = bass_tone(110.0, BUFFER_SIZE + 2 * HOP_SIZE);
+        let (window, rest) = tone.split_at(BUFFER_SIZE);
+
+        tuner.process(window);
+        assert!(handle.get_tuner_info().frequency.is_some());
+
+        for hop in rest.chunks(HOP_SIZE) {
+            handle.reset();
+            tuner.process(&hop[..HOP_SIZE - 1]);
+            assert!(handle.get_tuner_info().frequency.is_none());
+            tuner.process(&hop[HOP_SIZE - 1..]);
+            assert!(handle.get_tuner_info().frequency.is_some());
+        }
+    }
+
+    #[test]
+    fn switching_off_from_the_handle_drops_the_window() {
+        let (mut tuner, handle) = Tuner::new(SAMPLE_RATE);
+        let tone = bass_tone(110.0, BUFFER_SIZE);
+        handle.set_enabled(true);
+        tuner.process(&tone[..BUFFER_SIZE - 1]);
+
+        handle.set_enabled(false);
+        tuner.process(&tone[..1]);
+        assert!(!tuner.is_enabled());
+        handle.set_enabled(true);
+        tuner.process(&tone[..1]);
+        assert!(handle.get_tuner_info().frequency.is_none());
+    }
+
+    #[test]
+    fn smoother_ignores_a_stray_reading() {
+        let reading = |frequency| {
+            Some(Detection {
+                frequency,
+                confidence: 0.95,
+            })
+        };
+        for detection in [reading(110.0), None, reading(440.0)] {
+            assert_eq!(Detection::unpack(Detection::pack(detection)), detection);
+        }
+
+        let mut smoother = TunerSmoother::new();
+        smoother.push(reading(110.0));
+        smoother.push(reading(110.5));
+        let shown = smoother.push(reading(220.0)).unwrap();
+        assert!((shown.frequency - 110.5).abs() < f32::EPSILON);
+
+        assert!(smoother.push(None).is_none());
+        let shown = smoother.push(reading(220.0)).unwrap();
+        assert!((shown.frequency - 220.0).abs() < f32::EPSILON);
+    }
+
     #[test]
     fn test_freq_to_note() {
         let (note, octave, cents) = freq_to_note(440.0);
@@ -152,4 +728,80 @@ mod tests {
         assert_eq!(octave, 4);
         assert!(cents.abs() < 0.1);
     }
+
+    #[test]
+    fn octaves_below_middle_c() {
+        let note_and_octave = |freq| {
+            let (note, octave, _) = freq_to_note(freq);
+            (note, octave)
+        };
+        assert_eq!(note_and_octave(261.63), ("C", 4));
+        assert_eq!(note_and_octave(246.94), ("B", 3));
+        assert_eq!(note_and_octave(82.41), ("E", 2));
+        assert_eq!(note_and_octave(B0_HZ), ("B", 0));
+    }
+
+    /// Hand `frequency` to the GUI side as if the audio thread had heard it.
+    fn hear(handle: &TunerHandle, frequency: f32) {
+        let detection = Detection {
+            frequency,
+            confidence: 0.95,
+        };
+        handle
+            .shared
+            .reading
+            .store(Detection::pack(Some(detection)), Ordering::Release);
+    }
+
+    fn e2() -> TargetNote {
+        *TargetNote::ALL
+            .iter()
+            .find(|note| note.to_string() == "E2")
+            .unwrap()
+    }
+
+    #[test]
+    fn target_notes_span_low_b_to_high_e() {
+        assert_eq!(TargetNote::ALL[0].to_string(), "B0");
+        assert_eq!(TargetNote::ALL[TargetNote::ALL.len() - 1].to_string(), "E6");
+        assert!((e2().frequency() - 82.41).abs() < 0.01);
+    }
+
+    #[test]
+    fn note_lock_folds_harmonics_onto_the_target() {
+        let (_tuner, handle) = Tuner::new(SAMPLE_RATE);
+        let mut smoother = TunerSmoother::new();
+        smoother.set_lock(Some(e2()));
+        let played = e2().frequency() * (1.5f32 / 1200.0).exp2();
+
+        for (heard, harmonic) in [(played, 1), (2.0 * played, 2), (3.0 * played, 3)] {
+            hear(&handle, heard);
+            let info = smoother.update(&handle);
+            assert_eq!(info.note.as_deref(), Some("E2"));
+            assert_eq!(info.harmonic, Some(harmonic));
+            let cents = info.cents_off.unwrap();
+            assert!((cents - 1.5).abs() < 0.01, "{cents} cents");
+            assert!(info.harmonic_confidence > 0.9);
+        }
+    }
+
+    #[test]
+    fn note_lock_shows_the_offset_from_the_target_only() {
+        let (_tuner, handle) = Tuner::new(SAMPLE_RATE);
+        let mut smoother = TunerSmoother::new();
+        smoother.set_lock(Some(e2()));
+
+        // F2, a semitone up: still read against E2, and not as a harmonic.
+        hear(&handle, e2().frequency() * (1.0f32 / 12.0).exp2());
+        let info = smoother.update(&handle);
+        assert_eq!(info.note.as_deref(), Some("E2"));
+        assert!((info.cents_off.unwrap() - 100.0).abs() < 0.01);
+        assert_eq!(info.harmonic, Some(1));
+        assert_eq!(info.harmonic_confidence, 0.0);
+        assert!(!info.in_tune);
+
+        smoother.set_lock(None);
+        hear(&handle, e2().frequency() * (1.0f32 / 12.0).exp2());
+        assert_eq!(smoother.update(&handle).note.as_deref(), Some("F2"));
+    }
 }
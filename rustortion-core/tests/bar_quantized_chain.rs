@@ -0,0 +1,155 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! A chain queued while the metronome runs lands on the next bar.
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::level::LevelStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::peak_meter::PeakMeter;
+use rustortion_core::audio::rt_drop::RtDropHandle;
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::metronome::{Metronome, MetronomeConfig};
+use rustortion_core::tuner::Tuner;
+
+const SAMPLE_RATE: usize = 48_000;
+/// Doesn't divide a bar, so the downbeat falls inside a block.
+const BLOCK: usize = 441;
+/// Frames per 4/4 bar at 120 BPM.
+const BAR: u64 = 2 * SAMPLE_RATE as u64;
+const INPUT: f32 = 0.1;
+
+fn engine() -> (Engine, EngineHandle) {
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BLOCK, 1.0, SAMPLE_RATE).unwrap();
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    let (engine, handle) = Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )
+    .unwrap();
+    handle.set_amp_chain(level_chain(1.0));
+    (engine, handle)
+}
+
+fn level_chain(gain: f32) -> AmplifierChain {
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(gain)));
+    chain
+}
+
+/// Run one block; returns the gain the chain applied to it.
+fn block(engine: &mut Engine) -> f32 {
+    let input = [INPUT; BLOCK];
+    let mut output = [0.0; BLOCK];
+    engine.process(&input, &mut output).unwrap();
+    output[BLOCK - 1] / INPUT
+}
+
+fn assert_gain(applied: f32, expected: f32) {
+    assert!(
+        (applied - expected).abs() < 1e-5,
+        "gain {applied}, expected {expected}"
+    );
+}
+
+/// Run blocks until the next one would start at or after `frame`.
+fn run_to(engine: &mut Engine, handle: &EngineHandle, frame: u64) {
+    while handle.frames_processed() + (BLOCK as u64) <= frame {
+        block(engine);
+    }
+}
+
+fn start_metronome(handle: &EngineHandle) {
+    handle.set_metronome(MetronomeConfig {
+        enabled: true,
+        ..MetronomeConfig::default()
+    });
+}
+
+#[test]
+fn a_queued_chain_lands_on_the_next_bar() {
+    let (mut engine, handle) = engine();
+    start_metronome(&handle);
+
+    // Scripted: switch partway into the first bar, and again, to a
+    // different chain, partway into the third.
+    for (queued_at, gain, bar) in [(BAR / 3, 0.5, BAR), (2 * BAR + BAR / 2, 0.25, 3 * BAR)] {
+        run_to(&mut engine, &handle, queued_at);
+        handle.queue_amp_chain(level_chain(gain));
+
+        let mut landed = None;
+        while landed.is_none() {
+            let start = handle.frames_processed();
+            let applied = block(&mut engine);
+            if handle.chain_queued() {
+                assert!(
+                    (applied - gain).abs() > 1e-3,
+                    "landed early, at frame {start}"
+                );
+            } else {
+                assert_gain(applied, gain);
+                landed = Some(start);
+            }
+        }
+
+        let landed = landed.unwrap();
+        assert_eq!(handle.queued_chain_landed_at(), Some(landed));
+        assert!(
+            landed <= bar && bar - landed < BLOCK as u64,
+            "bar at {bar}, landed at {landed}"
+        );
+    }
+}
+
+#[test]
+fn a_second_choice_before_the_bar_replaces_the_first() {
+    let (mut engine, handle) = engine();
+    start_metronome(&handle);
+    run_to(&mut engine, &handle, BAR / 4);
+
+    handle.queue_amp_chain(level_chain(0.5));
+    block(&mut engine);
+    handle.queue_amp_chain(level_chain(0.25));
+    // Edits made meanwhile are to the chain that's waiting.
+    handle.set_parameter(0, "gain", 0.75);
+    run_to(&mut engine, &handle, BAR);
+
+    // The next block holds the downbeat.
+    assert!(handle.chain_queued());
+    assert_gain(block(&mut engine), 0.75);
+    assert!(!handle.chain_queued());
+}
+
+#[test]
+fn without_the_metronome_a_queued_chain_lands_at_once() {
+    let (mut engine, handle) = engine();
+    run_to(&mut engine, &handle, BAR / 3);
+
+    handle.queue_amp_chain(level_chain(0.5));
+    let start = handle.frames_processed();
+    assert_gain(block(&mut engine), 0.5);
+    assert!(!handle.chain_queued());
+    assert_eq!(handle.queued_chain_landed_at(), Some(start));
+}
+
+#[test]
+fn a_chain_set_directly_cancels_the_queued_one() {
+    let (mut engine, handle) = engine();
+    start_metronome(&handle);
+    run_to(&mut engine, &handle, BAR / 2);
+
+    handle.queue_amp_chain(level_chain(0.5));
+    block(&mut engine);
+    handle.set_amp_chain(level_chain(0.25));
+    assert_gain(block(&mut engine), 0.25);
+    assert!(!handle.chain_queued());
+
+    run_to(&mut engine, &handle, 2 * BAR);
+    assert_gain(block(&mut engine), 0.25);
+    assert_eq!(handle.queued_chain_landed_at(), None);
+}
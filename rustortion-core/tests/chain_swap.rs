@@ -0,0 +1,70 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::time::Instant;
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::peak_meter::PeakMeter;
+use rustortion_core::audio::rt_drop::RtDropHandle;
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::metronome::Metronome;
+use rustortion_core::tuner::Tuner;
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+
+fn engine() -> (Engine, EngineHandle) {
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BUFFER_SIZE, 1.0, SAMPLE_RATE).unwrap();
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )
+    .unwrap()
+}
+
+#[test]
+fn chain_swap_is_stamped_in_the_next_block() {
+    let (mut engine, handle) = engine();
+    let input = [0.1; BUFFER_SIZE];
+    let mut output = [0.0; BUFFER_SIZE];
+
+    engine.process(&input, &mut output).unwrap();
+    assert_eq!(handle.last_chain_change(), None);
+
+    let sent = Instant::now();
+    handle.set_amp_chain(AmplifierChain::new());
+    assert_eq!(handle.last_chain_change(), None);
+    engine.process(&input, &mut output).unwrap();
+
+    let applied = handle.last_chain_change().expect("chain swap stamped");
+    assert!(applied >= sent);
+    assert!(applied <= Instant::now());
+}
+
+#[test]
+fn parameter_changes_leave_the_stamp_alone() {
+    let (mut engine, handle) = engine();
+    let input = [0.1; BUFFER_SIZE];
+    let mut output = [0.0; BUFFER_SIZE];
+
+    handle.set_input_trim_db(-6.0);
+    engine.process(&input, &mut output).unwrap();
+    assert_eq!(handle.last_chain_change(), None);
+
+    handle.set_stage_bypassed(0, true);
+    engine.process(&input, &mut output).unwrap();
+    let first = handle
+        .last_chain_change()
+        .expect("bypass changes the chain");
+
+    handle.set_input_trim_db(0.0);
+    engine.process(&input, &mut output).unwrap();
+    assert_eq!(handle.last_chain_change(), Some(first));
+}
@@ -0,0 +1,179 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::path::Path;
+
+use hound::WavReader;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::peak_meter::PeakMeter;
+use rustortion_core::audio::recorder::{Recorder, TransportStart};
+use rustortion_core::audio::recording_info::{RecordingMetadata, TakeMetronome};
+use rustortion_core::audio::rt_drop::RtDropHandle;
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::metronome::{Metronome, MetronomeConfig, MetronomeSounds};
+use rustortion_core::preset::Preset;
+use rustortion_core::tuner::Tuner;
+
+const SAMPLE_RATE: usize = 48_000;
+const MAX_BLOCK: usize = 512;
+/// Samples per beat at 120 BPM.
+const BEAT: usize = SAMPLE_RATE / 2;
+
+fn engine() -> (Engine, EngineHandle) {
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let samplers = Samplers::new(MAX_BLOCK, 1.0, SAMPLE_RATE).unwrap();
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )
+    .unwrap()
+}
+
+/// Run `frames` of silence through `engine` in blocks of `block`, the way
+/// JACK would after a buffer size change.
+fn run(engine: &mut Engine, frames: usize, block: usize) {
+    engine.update_buffer_size(block).unwrap();
+    let input = vec![0.0; block];
+    let mut output = vec![0.0; block];
+    for _ in 0..frames / block {
+        engine.process(&input, &mut output).unwrap();
+    }
+}
+
+fn read_left(path: &Path) -> Vec<i16> {
+    WavReader::open(path)
+        .unwrap()
+        .samples::<i16>()
+        .step_by(2)
+        .map(Result::unwrap)
+        .collect()
+}
+
+#[test]
+fn click_track_lines_up_with_the_take_across_buffer_size_changes() {
+    let (mut engine, handle) = engine();
+    handle.set_metronome_sounds(MetronomeSounds {
+        accent: vec![1.0],
+        tick: vec![0.5],
+    });
+    handle.set_metronome(MetronomeConfig {
+        enabled: true,
+        ..MetronomeConfig::default()
+    });
+    // Start the take partway into a beat.
+    run(&mut engine, 64 * 100, 64);
+
+    let dir = tempfile::tempdir().unwrap();
+    let metadata = RecordingMetadata::new(Preset::default()).with_metronome(TakeMetronome {
+        bpm: 120.0,
+        beats_per_bar: 4,
+        click_track: true,
+    });
+    let take = handle
+        .start_recording(
+            SAMPLE_RATE,
+            dir.path().to_str().unwrap(),
+            MAX_BLOCK,
+            None,
+            Some(metadata),
+            false,
+        )
+        .unwrap();
+    run(&mut engine, 128 * 200, 128);
+    run(&mut engine, 500 * 60, 500);
+    run(&mut engine, 256 * 100, 256);
+    handle.stop_recording();
+    run(&mut engine, 64, 64);
+
+    let recorded = read_left(&take);
+    let click = read_left(&Recorder::click_track_path(&take));
+    assert_eq!(click.len(), recorded.len());
+    assert!(
+        recorded.iter().all(|&s| s == 0),
+        "the click got into the take"
+    );
+
+    // Beats fall on multiples of `BEAT` from the frame the metronome started.
+    let start = handle.recording_start_frame() as usize;
+    let expected: Vec<usize> = (1..)
+        .map(|beat| beat * BEAT - start)
+        .take_while(|&frame| frame < click.len())
+        .collect();
+    let clicks: Vec<usize> = (0..click.len()).filter(|&i| click[i] != 0).collect();
+    assert_eq!(clicks, expected);
+}
+
+#[test]
+fn a_take_started_after_the_transport_rolled_starts_at_the_roll() {
+    let (mut engine, handle) = engine();
+    handle.set_metronome(MetronomeConfig {
+        enabled: true,
+        ..MetronomeConfig::default()
+    });
+    run(&mut engine, 64 * 100, 64);
+    let rolled_at = handle.frames_processed();
+    // The GUI only hears about the roll a few blocks later.
+    run(&mut engine, 64 * 20, 64);
+
+    let dir = tempfile::tempdir().unwrap();
+    let metadata = RecordingMetadata::new(Preset::default()).with_metronome(TakeMetronome {
+        bpm: 120.0,
+        beats_per_bar: 4,
+        click_track: true,
+    });
+    let take = handle
+        .start_recording(
+            SAMPLE_RATE,
+            dir.path().to_str().unwrap(),
+            MAX_BLOCK,
+            Some(TransportStart {
+                frame: 96_000,
+                engine_frame: rolled_at,
+            }),
+            Some(metadata),
+            false,
+        )
+        .unwrap();
+    run(&mut engine, 128 * 10, 128);
+    handle.stop_recording();
+    run(&mut engine, 64, 64);
+
+    assert_eq!(handle.recording_start_frame(), rolled_at);
+    assert!(
+        take.to_string_lossy().ends_with("_frame96000.wav"),
+        "unexpected name {}",
+        take.display()
+    );
+    assert_eq!(read_left(&take).len(), 64 * 20 + 128 * 10);
+    assert_eq!(
+        read_left(&Recorder::click_track_path(&take)).len(),
+        64 * 20 + 128 * 10
+    );
+}
+
+#[test]
+fn no_click_track_unless_asked_for() {
+    let (mut engine, handle) = engine();
+    let dir = tempfile::tempdir().unwrap();
+    let take = handle
+        .start_recording(
+            SAMPLE_RATE,
+            dir.path().to_str().unwrap(),
+            MAX_BLOCK,
+            None,
+            Some(RecordingMetadata::new(Preset::default())),
+            false,
+        )
+        .unwrap();
+    run(&mut engine, 1024, 128);
+    handle.stop_recording();
+    run(&mut engine, 128, 128);
+
+    assert!(take.exists());
+    assert!(!Recorder::click_track_path(&take).exists());
+}
@@ -0,0 +1,115 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! In dual-mono each input has a chain and cabinet of its own: nothing played
+//! into one side reaches the other.
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::audio::dual_mono::{ChainMode, RightChannel, RightMessage};
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK_SIZE: usize = 128;
+const BLOCKS: usize = 40;
+const IR_LEN: usize = 256;
+
+fn chain() -> AmplifierChain {
+    let mut chain = AmplifierChain::new();
+    for kind in [StageType::Preamp, StageType::ToneStack, StageType::PowerAmp] {
+        chain.add_stage(StageConfig::from(kind).to_runtime(SAMPLE_RATE as f32));
+    }
+    chain
+}
+
+/// A short decaying IR, so the cabinet has a tail to leak through.
+fn cabinet() -> IrCabinet {
+    let ir: Vec<f32> = (0..IR_LEN).map(|i| 0.97f32.powi(i as i32)).collect();
+    let mut convolver = Convolver::new_fir(IR_LEN);
+    convolver.set_ir(&ir).unwrap();
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, IR_LEN);
+    cabinet.set_convolver(convolver);
+    cabinet
+}
+
+fn engine() -> (Engine, EngineHandle) {
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, Some(cabinet()), 1.0).unwrap();
+    handle.set_amp_chain(chain());
+
+    let samplers = Samplers::new(BLOCK_SIZE, 1.0, SAMPLE_RATE).unwrap();
+    handle.set_chain_mode(Some(RightChannel::new(samplers, Some(cabinet()), None)));
+    handle.send_right(RightMessage::SetAmpChain(Box::new(chain())));
+    assert_eq!(handle.chain_mode(), ChainMode::DualMono);
+    (engine, handle)
+}
+
+/// Both outputs for an impulse into the left and/or right input.
+fn render(left_impulse: bool, right_impulse: bool) -> (Vec<f32>, Vec<f32>) {
+    let (mut engine, _handle) = engine();
+    let (mut left_out, mut right_out) = (Vec::new(), Vec::new());
+    for block in 0..BLOCKS {
+        let mut left_in = vec![0.0f32; BLOCK_SIZE];
+        let mut right_in = vec![0.0f32; BLOCK_SIZE];
+        if block == 0 {
+            left_in[0] = if left_impulse { 0.5 } else { 0.0 };
+            right_in[0] = if right_impulse { 0.5 } else { 0.0 };
+        }
+        let mut left = vec![0.0f32; BLOCK_SIZE];
+        let mut right = vec![0.0f32; BLOCK_SIZE];
+        engine
+            .process_stereo_input(&left_in, &right_in, &mut left, &mut right)
+            .unwrap();
+        left_out.extend(left);
+        right_out.extend(right);
+    }
+    (left_out, right_out)
+}
+
+fn energy(samples: &[f32]) -> f32 {
+    samples.iter().map(|s| s * s).sum()
+}
+
+/// Energy of what changed between `with` and `without`, in dB against the
+/// energy of `signal`.
+fn leak_db(with: &[f32], without: &[f32], signal: &[f32]) -> f32 {
+    let diff: Vec<f32> = with.iter().zip(without).map(|(a, b)| a - b).collect();
+    10.0 * (energy(&diff).max(1e-30) / energy(signal)).log10()
+}
+
+#[test]
+fn left_impulse_stays_on_the_left() {
+    // Against silence rather than zero: the chain may idle at a DC offset.
+    let (silent_left, silent_right) = render(false, false);
+    let (left, right) = render(true, false);
+
+    assert!(energy(&left) > energy(&silent_left) * 10.0);
+    let leak = leak_db(&right, &silent_right, &left);
+    assert!(leak < -80.0, "left leaked into the right at {leak} dB");
+}
+
+#[test]
+fn right_impulse_stays_on_the_right() {
+    let (silent_left, silent_right) = render(false, false);
+    let (left, right) = render(false, true);
+
+    assert!(energy(&right) > energy(&silent_right) * 10.0);
+    let leak = leak_db(&left, &silent_left, &right);
+    assert!(leak < -80.0, "right leaked into the left at {leak} dB");
+}
+
+#[test]
+fn a_stereo_pair_keeps_both_sides() {
+    let (left_only, _) = render(true, false);
+    let (_, right_only) = render(false, true);
+    let (left, right) = render(true, true);
+
+    // Each side is the same as it would be alone.
+    let (silent_left, silent_right) = render(false, false);
+    assert!(leak_db(&left, &left_only, &left) < -80.0);
+    assert!(leak_db(&right, &right_only, &right) < -80.0);
+    assert!(energy(&left) > energy(&silent_left) * 10.0);
+    assert!(energy(&right) > energy(&silent_right) * 10.0);
+}
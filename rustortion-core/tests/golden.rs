@@ -0,0 +1,184 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Golden-audio regression suite: a fixed DI through fixture presets, from
+//! the WAV file through the engine's messages, chain, oversampling and
+//! cabinet, compared with a stored reference within an RMS tolerance.
+//!
+//! When a DSP change is meant to alter the sound, re-record the references
+//! with `RUSTORTION_UPDATE_GOLDEN=1 cargo test -p rustortion-core --test golden`
+//! and commit them with the change; see `tests/fixtures/README.md`.
+
+use std::path::{Path, PathBuf};
+
+use rustortion_core::audio::offline::{read_mono_wav, render_file};
+use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::preset::Preset;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+/// Set to re-record every reference instead of comparing against it.
+const UPDATE_ENV: &str = "RUSTORTION_UPDATE_GOLDEN";
+/// RMS of the difference allowed, about -60 dBFS: far above float noise
+/// across platforms, far below any change that can be heard.
+const RMS_TOLERANCE: f32 = 1e-3;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden")
+}
+
+struct Case {
+    name: &'static str,
+    preset: Preset,
+    oversampling: u32,
+}
+
+fn preset(name: &str, stages: &[StageType]) -> Preset {
+    Preset {
+        name: name.to_string(),
+        stages: stages.iter().copied().map(StageConfig::from).collect(),
+        ..Preset::default()
+    }
+}
+
+fn cases() -> Vec<Case> {
+    let mut lead = preset(
+        "lead",
+        &[
+            StageType::NoiseGate,
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+        ],
+    );
+    if let StageConfig::Preamp(preamp) = &mut lead.stages[1] {
+        preamp.gain = 8.0;
+    }
+    lead.ir_name = Some("cab.wav".to_string());
+    lead.ir_gain = 0.5;
+
+    vec![
+        Case {
+            name: "clean",
+            preset: preset("clean", &[StageType::ToneStack, StageType::Level]),
+            oversampling: 1,
+        },
+        Case {
+            name: "crunch",
+            preset: preset(
+                "crunch",
+                &[StageType::Preamp, StageType::ToneStack, StageType::PowerAmp],
+            ),
+            oversampling: 2,
+        },
+        Case {
+            name: "lead",
+            preset: lead,
+            oversampling: 4,
+        },
+    ]
+}
+
+fn rms_difference(a: &[f32], b: &[f32]) -> f32 {
+    let sum: f64 = a.iter().zip(b).map(|(a, b)| f64::from(a - b).powi(2)).sum();
+    (sum / a.len().max(1) as f64).sqrt() as f32
+}
+
+#[test]
+fn fixture_presets_match_their_references() {
+    let dir = golden_dir();
+    let update = std::env::var_os(UPDATE_ENV).is_some();
+    let loader = IrLoader::new(&dir.join("irs"), SAMPLE_RATE).unwrap();
+    let scratch = tempfile::tempdir().unwrap();
+    if update {
+        std::fs::create_dir_all(dir.join("reference")).unwrap();
+    }
+
+    let mut failures = Vec::new();
+    for case in cases() {
+        let rendered_path = scratch.path().join(format!("{}.wav", case.name));
+        render_file(
+            &case.preset,
+            &dir.join("di.wav"),
+            &rendered_path,
+            SAMPLE_RATE,
+            case.oversampling,
+            Some(&loader),
+            |_| {},
+        )
+        .unwrap();
+
+        let reference_path = dir.join("reference").join(format!("{}.wav", case.name));
+        if update {
+            std::fs::copy(&rendered_path, &reference_path).unwrap();
+            eprintln!(
+                "Recorded golden reference {}; review it and commit it",
+                reference_path.display()
+            );
+            continue;
+        }
+        if !reference_path.exists() {
+            failures.push(format!(
+                "{}: no reference at {}",
+                case.name,
+                reference_path.display()
+            ));
+            continue;
+        }
+
+        let (rendered, _) = read_mono_wav(&rendered_path).unwrap();
+        let (reference, _) = read_mono_wav(&reference_path).unwrap();
+        assert!(
+            rendered.iter().all(|s| s.is_finite()),
+            "{}: non-finite output",
+            case.name
+        );
+        if rendered.len() != reference.len() {
+            failures.push(format!(
+                "{}: {} samples, reference has {}",
+                case.name,
+                rendered.len(),
+                reference.len()
+            ));
+            continue;
+        }
+        let rms = rms_difference(&rendered, &reference);
+        if rms > RMS_TOLERANCE {
+            failures.push(format!(
+                "{}: RMS difference {rms:.2e} exceeds {RMS_TOLERANCE:.0e}",
+                case.name
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "output no longer matches the golden references:\n{}\n\
+         If the change is intended, re-record with {UPDATE_ENV}=1",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn the_di_fixture_reaches_the_output() {
+    // Guards the suite itself: a render that came out silent would match a
+    // silent reference forever.
+    let dir = golden_dir();
+    let scratch = tempfile::tempdir().unwrap();
+    let output = scratch.path().join("clean.wav");
+    let case = cases().remove(0);
+    render_file(
+        &case.preset,
+        &dir.join("di.wav"),
+        &output,
+        SAMPLE_RATE,
+        case.oversampling,
+        None,
+        |_| {},
+    )
+    .unwrap();
+
+    let (rendered, rate) = read_mono_wav(&output).unwrap();
+    assert_eq!(rate as usize, SAMPLE_RATE);
+    let peak = rendered.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!(peak > 0.01, "clean render peaked at {peak}");
+}
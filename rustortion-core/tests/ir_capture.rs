@@ -0,0 +1,136 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use rustortion_core::audio::ir_capture::{capture_ir, export_ir};
+use rustortion_core::audio::offline::{read_mono_wav, render_samples};
+use rustortion_core::ir::convolver::Convolver;
+use rustortion_core::preset::Preset;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+const CAPTURE_LEN: usize = 4096;
+const CABINET_LEN: usize = 256;
+const SETTLE: usize = 4096;
+
+/// A short decaying IR standing in for a cabinet.
+fn cabinet() -> Convolver {
+    let ir: Vec<f32> = (0..CABINET_LEN)
+        .map(|i| {
+            let t = i as f32 / CABINET_LEN as f32;
+            (-6.0 * t).exp() * (i as f32 * 0.7).sin()
+        })
+        .collect();
+    let mut convolver = Convolver::new_fir(CABINET_LEN);
+    convolver.set_ir(&ir).unwrap();
+    convolver
+}
+
+fn linear_stages() -> Vec<StageConfig> {
+    let mut eq = StageConfig::from(StageType::Eq);
+    if let StageConfig::Eq(cfg) = &mut eq {
+        cfg.gains[2] = 6.0;
+        cfg.gains[6] = -4.0;
+    }
+    let mut level = StageConfig::from(StageType::Level);
+    if let StageConfig::Level(cfg) = &mut level {
+        cfg.gain = 0.5;
+    }
+    vec![StageConfig::from(StageType::ToneStack), eq, level]
+}
+
+fn preset(stages: Vec<StageConfig>) -> Preset {
+    Preset {
+        name: "Capture".to_string(),
+        stages,
+        ..Preset::default()
+    }
+}
+
+/// A decaying two-tone burst.
+fn signal() -> Vec<f32> {
+    (0..2048)
+        .map(|i| {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            let tone = (std::f32::consts::TAU * 220.0 * t).sin()
+                + 0.5 * (std::f32::consts::TAU * 3_100.0 * t).sin();
+            0.2 * (-40.0 * t).exp() * tone
+        })
+        .collect()
+}
+
+fn convolve(signal: &[f32], ir: &[f32]) -> Vec<f32> {
+    (0..signal.len())
+        .map(|n| {
+            ir.iter()
+                .take(n + 1)
+                .enumerate()
+                .map(|(k, h)| h * signal[n - k])
+                .sum()
+        })
+        .collect()
+}
+
+fn rms(samples: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = samples.fold((0.0, 0), |(sum, count), s| (sum + s * s, count + 1));
+    (sum / count as f32).sqrt()
+}
+
+#[test]
+fn convolving_with_the_capture_sounds_like_the_linear_chain() {
+    let preset = preset(linear_stages());
+    let ir = capture_ir(&preset, SAMPLE_RATE, 1, Some(cabinet()), CAPTURE_LEN).unwrap();
+    assert_eq!(ir.len(), CAPTURE_LEN);
+
+    // Rendered after some silence, as the capture's impulse is, so smoothed
+    // parameters have settled.
+    let signal = signal();
+    let mut padded = vec![0.0; SETTLE];
+    padded.extend_from_slice(&signal);
+    let rendered =
+        render_samples(&preset, &padded, SAMPLE_RATE, 1, Some(cabinet()), |_| {}).unwrap();
+    let direct = &rendered[SETTLE..];
+    let convolved = convolve(&signal, &ir);
+
+    let error = rms(direct.iter().zip(&convolved).map(|(a, b)| a - b));
+    let level = rms(direct.iter().copied());
+    assert!(level > 0.01, "the chain passed too little: {level}");
+    assert!(
+        error < 0.01 * level,
+        "RMS error {error} against a signal of {level}"
+    );
+}
+
+#[test]
+fn non_linear_stages_are_left_out_of_the_capture() {
+    let linear = capture_ir(&preset(linear_stages()), SAMPLE_RATE, 1, None, CAPTURE_LEN).unwrap();
+
+    let mut stages = linear_stages();
+    stages.insert(0, StageConfig::from(StageType::Preamp));
+    stages.push(StageConfig::from(StageType::PowerAmp));
+    let with_amp = capture_ir(&preset(stages), SAMPLE_RATE, 1, None, CAPTURE_LEN).unwrap();
+
+    let worst = linear
+        .iter()
+        .zip(&with_amp)
+        .fold(0.0f32, |m, (a, b)| m.max((a - b).abs()));
+    assert!(worst < 1e-5, "captures differ by up to {worst}");
+}
+
+#[test]
+fn capture_length_is_bounded() {
+    let preset = preset(linear_stages());
+    assert!(capture_ir(&preset, SAMPLE_RATE, 1, None, 512).is_err());
+    assert!(capture_ir(&preset, SAMPLE_RATE, 1, None, 16_384).is_err());
+}
+
+#[test]
+fn export_writes_a_normalized_mono_wav() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("capture.wav");
+    export_ir(&preset(linear_stages()), SAMPLE_RATE, 1, None, 2048, &path).unwrap();
+
+    let (ir, rate) = read_mono_wav(&path).unwrap();
+    assert_eq!(rate as usize, SAMPLE_RATE);
+    assert_eq!(ir.len(), 2048);
+    let peak = ir.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    assert!((peak - 0.9).abs() < 1e-4, "peak {peak}");
+}
@@ -0,0 +1,155 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! The latency the engine reports must match the delay an impulse actually
+//! takes through it, whatever adds to it.
+
+use rustortion_core::audio::denoiser::{DenoiseConfig, NUM_BINS, NoiseProfile};
+use rustortion_core::audio::engine::{Engine, EngineHandle, EngineMessage};
+use rustortion_core::audio::pitch_shifter::PitchShifter;
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK_SIZE: usize = 128;
+/// Far enough in that the resamplers have settled.
+const IMPULSE_AT: usize = 1_000;
+/// Long enough for every delay here to come out.
+const BLOCKS: usize = 80;
+
+fn engine(factor: f64, cabinet: Option<IrCabinet>) -> (Engine, EngineHandle) {
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, cabinet, factor).unwrap();
+    (engine, handle)
+}
+
+/// A cabinet holding a unit impulse followed by silence, long enough for the
+/// two-stage convolver to have a tail.
+fn unit_cabinet(convolver_type: ConvolverType) -> IrCabinet {
+    let mut ir = vec![0.0f32; 2_048];
+    ir[0] = 1.0;
+    let mut convolver = match convolver_type {
+        ConvolverType::Auto => Convolver::new_auto(ir.len()),
+        ConvolverType::Fir => Convolver::new_fir(ir.len()),
+        ConvolverType::TwoStage => Convolver::new_two_stage(),
+    };
+    convolver.set_ir(&ir).unwrap();
+    let mut cabinet = IrCabinet::new(convolver_type, ir.len());
+    cabinet.set_convolver(convolver);
+    cabinet
+}
+
+/// A shifter at unity ratio: it delays like any other but leaves an impulse
+/// an impulse, so the delay can be read off the peak.
+fn send_unity_pitch_shifter(handle: &EngineHandle) {
+    handle.send(EngineMessage::SetPitchShift(Some(Box::new(
+        PitchShifter::new(0.0),
+    ))));
+}
+
+/// Samples between an impulse going in and the peak coming out.
+fn measured_delay(engine: &mut Engine) -> usize {
+    let mut input = vec![0.0f32; BLOCKS * BLOCK_SIZE];
+    input[IMPULSE_AT] = 1.0;
+    let mut output = vec![0.0f32; input.len()];
+    for (block_in, block_out) in input.chunks(BLOCK_SIZE).zip(output.chunks_mut(BLOCK_SIZE)) {
+        engine.process(block_in, block_out).unwrap();
+    }
+
+    let (peak, _) = output
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+        .unwrap();
+    assert!(
+        peak >= IMPULSE_AT,
+        "output peaked before the impulse went in"
+    );
+    peak - IMPULSE_AT
+}
+
+fn assert_reported_matches_measured(engine: &mut Engine, handle: &EngineHandle) -> usize {
+    let measured = measured_delay(engine);
+    let reported = engine.latency().total();
+    assert!(
+        reported.abs_diff(measured) <= 1,
+        "reported {reported} samples ({:?}), measured {measured}",
+        engine.latency()
+    );
+    assert_eq!(handle.latency(), engine.latency());
+    reported
+}
+
+#[test]
+fn base_path_adds_no_latency() {
+    for convolver_type in [
+        ConvolverType::Auto,
+        ConvolverType::Fir,
+        ConvolverType::TwoStage,
+    ] {
+        let (mut engine, handle) = engine(1.0, Some(unit_cabinet(convolver_type)));
+        assert_eq!(engine.latency().total(), 0);
+        assert_eq!(assert_reported_matches_measured(&mut engine, &handle), 0);
+    }
+}
+
+#[test]
+fn oversampling_latency_matches_the_resamplers() {
+    for factor in [2.0, 4.0, 8.0, 16.0] {
+        let (mut engine, handle) = engine(factor, None);
+        assert!(engine.latency().oversampling > 0, "{factor}x reports none");
+        assert_reported_matches_measured(&mut engine, &handle);
+    }
+}
+
+#[test]
+fn pitch_shifter_latency_matches_and_follows_toggling() {
+    let (mut engine, handle) = engine(1.0, None);
+    send_unity_pitch_shifter(&handle);
+    let reported = assert_reported_matches_measured(&mut engine, &handle);
+    assert_eq!(reported, engine.latency().pitch_shifter);
+    assert!(reported > 0);
+
+    handle.set_pitch_shift(0);
+    engine.handle_messages();
+    assert_eq!(handle.latency().pitch_shifter, 0);
+    assert_eq!(assert_reported_matches_measured(&mut engine, &handle), 0);
+}
+
+#[test]
+fn denoiser_latency_matches_and_follows_bypass() {
+    let (mut engine, handle) = engine(1.0, None);
+    // A silent noise print: every bin passes, so the impulse stays one.
+    let mut config = DenoiseConfig {
+        profile: Some(NoiseProfile {
+            sample_rate: SAMPLE_RATE as u32,
+            magnitudes: vec![0.0; NUM_BINS],
+        }),
+        ..DenoiseConfig::default()
+    };
+    handle.set_denoiser(&config, SAMPLE_RATE);
+    let reported = assert_reported_matches_measured(&mut engine, &handle);
+    assert_eq!(reported, engine.latency().denoiser);
+    assert!(reported > 0);
+
+    config.bypassed = true;
+    handle.set_denoiser(&config, SAMPLE_RATE);
+    engine.handle_messages();
+    assert_eq!(handle.latency().denoiser, 0);
+    assert_eq!(assert_reported_matches_measured(&mut engine, &handle), 0);
+}
+
+#[test]
+fn full_path_latency_is_the_sum_of_its_parts() {
+    let (mut engine, handle) = engine(4.0, Some(unit_cabinet(ConvolverType::TwoStage)));
+    send_unity_pitch_shifter(&handle);
+    engine.handle_messages();
+
+    let latency = engine.latency();
+    assert!(latency.oversampling > 0);
+    assert!(latency.pitch_shifter > 0);
+    assert_eq!(
+        latency.total(),
+        latency.oversampling + latency.denoiser + latency.pitch_shifter + latency.cabinet
+    );
+    assert_reported_matches_measured(&mut engine, &handle);
+}
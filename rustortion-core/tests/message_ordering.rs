@@ -0,0 +1,181 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Messages sent together land in the same block, so a preset switch is
+//! never heard with the new chain through the old cabinet settings.
+
+use std::thread;
+use std::time::Duration;
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::level::LevelStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle, IrChange, PreparedIr, PresetSwap};
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK_SIZE: usize = 128;
+const IR_LEN: usize = 256;
+/// Enough blocks for gain changes and filters to settle.
+const SETTLE_BLOCKS: usize = 40;
+/// Peak output for a 0.25 sine through either configuration.
+const EXPECTED_PEAK: f32 = 0.125;
+
+/// An impulse, so the cabinet only applies its gain.
+fn impulse() -> Convolver {
+    let mut ir = vec![0.0f32; IR_LEN];
+    ir[0] = 1.0;
+    let mut convolver = Convolver::new_fir(IR_LEN);
+    convolver.set_ir(&ir).unwrap();
+    convolver
+}
+
+fn level_chain(gain: f32) -> AmplifierChain {
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(gain)));
+    chain
+}
+
+/// Unity through the chain into a cabinet at x0.5. The other configuration
+/// used below, x2 into x0.25, sounds the same; mixing the two doesn't.
+fn engine() -> (Engine, EngineHandle) {
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, IR_LEN);
+    cabinet.set_convolver(impulse());
+    cabinet.set_gain(0.5);
+
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, Some(cabinet), 1.0).unwrap();
+    handle.set_amp_chain(level_chain(1.0));
+    (engine, handle)
+}
+
+fn preset(chain_gain: f32, ir_gain: f32) -> PresetSwap {
+    PresetSwap {
+        chain: Box::new(level_chain(chain_gain)),
+        ir: IrChange::Swap(Box::new(PreparedIr {
+            name: "impulse".to_string(),
+            convolver: Box::new(impulse()),
+        })),
+        ir_gain,
+        ir_bypass: Some(false),
+    }
+}
+
+/// Peak output of one block of a 1 kHz sine of amplitude 0.25.
+fn block_peak(engine: &mut Engine, block: usize) -> f32 {
+    let input: Vec<f32> = (0..BLOCK_SIZE)
+        .map(|i| {
+            let t = (block * BLOCK_SIZE + i) as f32 / SAMPLE_RATE as f32;
+            0.25 * (std::f32::consts::TAU * 1_000.0 * t).sin()
+        })
+        .collect();
+    let mut output = vec![0.0f32; BLOCK_SIZE];
+    engine.process(&input, &mut output).unwrap();
+    output.iter().fold(0.0, |m, s| m.max(s.abs()))
+}
+
+fn assert_consistent(peak: f32, block: usize) {
+    assert!(
+        (peak - EXPECTED_PEAK).abs() < 0.01,
+        "block {block} played a mixed configuration: peak {peak}"
+    );
+}
+
+#[test]
+fn a_burst_sent_between_blocks_lands_in_one_block() {
+    let (mut engine, handle) = engine();
+    for block in 0..SETTLE_BLOCKS {
+        block_peak(&mut engine, block);
+    }
+
+    handle.set_amp_chain(level_chain(2.0));
+    handle.set_ir_bypass(false);
+    handle.set_ir_gain(0.25);
+
+    for block in SETTLE_BLOCKS..2 * SETTLE_BLOCKS {
+        assert_consistent(block_peak(&mut engine, block), block);
+    }
+}
+
+#[test]
+fn presets_applied_while_the_engine_runs_never_mix() {
+    let (mut engine, handle) = engine();
+    for block in 0..SETTLE_BLOCKS {
+        block_peak(&mut engine, block);
+    }
+
+    let sender = {
+        let handle = handle.clone();
+        thread::spawn(move || {
+            for i in 0..200 {
+                let swap = if i % 2 == 0 {
+                    preset(2.0, 0.25)
+                } else {
+                    preset(1.0, 0.5)
+                };
+                handle.apply_preset(swap);
+                thread::sleep(Duration::from_micros(200));
+            }
+        })
+    };
+
+    let mut block = SETTLE_BLOCKS;
+    while !sender.is_finished() {
+        assert_consistent(block_peak(&mut engine, block), block);
+        block += 1;
+    }
+    sender.join().unwrap();
+    for _ in 0..SETTLE_BLOCKS {
+        assert_consistent(block_peak(&mut engine, block), block);
+        block += 1;
+    }
+}
+
+#[test]
+fn a_held_chain_waits_for_its_ir_and_lands_with_it() {
+    let (mut engine, handle) = engine();
+    let mut block = 0;
+    while block < SETTLE_BLOCKS {
+        block_peak(&mut engine, block);
+        block += 1;
+    }
+
+    // The chain arrives ahead of its IR, as a standalone preset load sends them.
+    handle.hold_amp_chain(level_chain(2.0));
+    for _ in 0..SETTLE_BLOCKS {
+        assert_consistent(block_peak(&mut engine, block), block);
+        block += 1;
+    }
+    assert!(handle.chain_queued());
+
+    let ir_block = block;
+    handle.swap_ir_convolver(PreparedIr {
+        name: "impulse".to_string(),
+        convolver: Box::new(impulse()),
+    });
+    handle.set_ir_gain(0.25);
+    for _ in 0..SETTLE_BLOCKS {
+        assert_consistent(block_peak(&mut engine, block), block);
+        block += 1;
+    }
+
+    assert!(!handle.chain_queued());
+    assert_eq!(
+        handle.queued_chain_landed_at(),
+        Some((ir_block * BLOCK_SIZE) as u64)
+    );
+}
+
+#[test]
+fn a_held_chain_lands_without_its_ir_after_a_while() {
+    let (mut engine, handle) = engine();
+    handle.hold_amp_chain(level_chain(2.0));
+
+    // Half a second, as when the IR failed to load.
+    let blocks = SAMPLE_RATE / 2 / BLOCK_SIZE + 2;
+    for block in 0..blocks {
+        block_peak(&mut engine, block);
+    }
+
+    assert!(!handle.chain_queued());
+    assert!(handle.queued_chain_landed_at().is_some());
+}
@@ -15,6 +15,7 @@ use assert_no_alloc::{AllocDisabler, assert_no_alloc, reset_violation_count, vio
 use hound::{WavSpec, WavWriter};
 
 use rustortion_core::amp::chain::{AmplifierChain, DEFAULT_CHAIN_CAPACITY};
+use rustortion_core::amp::crossfade::StageCrossfade;
 use rustortion_core::amp::stages::Stage;
 use rustortion_core::amp::stages::clipper::ClipperType;
 use rustortion_core::amp::stages::compressor::CompressorStage;
@@ -33,10 +34,12 @@ use rustortion_core::audio::engine::{Engine, EngineHandle, PreparedIr};
 use rustortion_core::audio::peak_meter::PeakMeter;
 use rustortion_core::audio::rt_drop::{RtDropHandle, RtDropReceiver};
 use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::audio::take_player::{MAX_TAKES, Take, TakeCommand, TakePlayer};
+use rustortion_core::audio::test_source::{TestSourceConfig, TestSourceKind};
 use rustortion_core::ir::cabinet::{ConvolverType, DEFAULT_MAX_IR_MS, IrCabinet};
 use rustortion_core::ir::convolver::Convolver;
 use rustortion_core::ir::loader::IrLoader;
-use rustortion_core::metronome::Metronome;
+use rustortion_core::metronome::{Metronome, MetronomeConfig};
 use rustortion_core::tuner::Tuner;
 
 #[global_allocator]
@@ -206,6 +209,38 @@ fn engine_process_does_not_allocate_with_oversampling() {
     assert_engine_alloc_free(&mut engine, &input, &mut output, 32);
 }
 
+#[test]
+fn switching_quality_does_not_allocate() {
+    // Covers: what the load guard does on the RT thread — the light
+    // resampling path and head-only IRs switched on and off, through the
+    // warm-ups and crossfades that follow. Both paths' buffers are built in
+    // `Samplers::new` and the convolver constructors (init-only, fine).
+    for convolver in [make_fir_convolver(), make_two_stage_convolver()] {
+        let mut samplers = Samplers::new(BUFFER_SIZE, 4.0, SAMPLE_RATE).unwrap();
+        let max_ir_samples = (SAMPLE_RATE * DEFAULT_MAX_IR_MS) / 1000;
+        let mut cabinet = IrCabinet::new(ConvolverType::Fir, max_ir_samples);
+        cabinet.set_convolver(convolver);
+        let (input, mut output) = buffers();
+
+        let violations = check_no_alloc(|| {
+            for reduced in [true, false, true, false] {
+                samplers.set_light_path(reduced);
+                cabinet.set_head_only(reduced, 960);
+                for _ in 0..64 {
+                    samplers.copy_input(&input).unwrap();
+                    samplers.upsample().unwrap();
+                    output.copy_from_slice(samplers.downsample().unwrap());
+                    cabinet.process_block(&mut output);
+                }
+            }
+        });
+        assert_eq!(
+            violations, 0,
+            "switching quality allocated {violations} time(s) on the RT path"
+        );
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Per-stage tests
 // ---------------------------------------------------------------------------
@@ -474,7 +509,14 @@ mod extras {
         let (mut engine, handle) = full_engine(1.0, None);
         let tmp = tempfile::tempdir().unwrap();
         handle
-            .start_recording(SAMPLE_RATE, tmp.path().to_str().unwrap(), BUFFER_SIZE)
+            .start_recording(
+                SAMPLE_RATE,
+                tmp.path().to_str().unwrap(),
+                BUFFER_SIZE,
+                None,
+                None,
+                false,
+            )
             .unwrap();
 
         let (input, mut output) = buffers();
@@ -576,6 +618,25 @@ mod message_arms {
         );
     }
 
+    #[test]
+    fn crossfade_stage_drain_does_not_allocate() {
+        // CrossfadeStage swaps in the stage the fade was built around and
+        // keeps the old one in that same fade box; nothing is built on RT.
+        let (mut engine, handle, _rx) = plugin_engine(1.0);
+        let (input, mut output) = buffers();
+        handle.add_stage(0, Box::new(LevelStage::new(0.5)));
+        let violations = assert_drain_alloc_free(&mut engine, &input, &mut output, || {
+            handle.crossfade_stage(
+                0,
+                StageCrossfade::new(Box::new(LevelStage::new(0.25)), SAMPLE_RATE_F32),
+            );
+        });
+        assert_eq!(
+            violations, 0,
+            "CrossfadeStage drain allocated {violations} time(s)"
+        );
+    }
+
     #[test]
     fn remove_stage_drain_does_not_allocate() {
         // RemoveStage removes from the Vec and retires the old stage via
@@ -672,10 +733,10 @@ mod message_arms {
     #[test]
     fn set_tuner_enabled_drain_does_not_allocate() {
         // SetTunerEnabled(true) flips a bool; the subsequent tuner.process
-        // appends into a pre-allocated 4096-sample buffer. Stay well under the
-        // detection threshold so no AMDF run (which allocates a readout) fires.
-        // This one drives process() (not just the drain) to also cover the
-        // tuner buffer-growth path.
+        // appends into a pre-allocated 4096-sample buffer. This one drives
+        // process() (not just the drain) to also cover the tuner
+        // buffer-growth path; detection is covered by
+        // `tuner_detection_does_not_allocate`.
         let (mut engine, handle) = full_engine(1.0, None);
         let (input, mut output) = buffers();
         engine.process(&input, &mut output).unwrap();
@@ -693,6 +754,34 @@ mod message_arms {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Tuner
+// ---------------------------------------------------------------------------
+
+#[test]
+fn tuner_detection_does_not_allocate() {
+    // Covers: Tuner::process sliding its window and publishing a reading
+    // every hop, switched on and off from the handle as hold-to-tune does.
+    let (mut tuner, handle) = Tuner::new(SAMPLE_RATE);
+    let block: Vec<f32> = (0..BUFFER_SIZE)
+        .map(|n| 0.5 * (std::f32::consts::TAU * 110.0 * n as f32 / SAMPLE_RATE_F32).sin())
+        .collect();
+
+    let violations = check_no_alloc(|| {
+        for round in 0..4 {
+            handle.set_enabled(round % 2 == 0);
+            for _ in 0..64 {
+                tuner.process(&block);
+            }
+        }
+    });
+    assert_eq!(
+        violations, 0,
+        "tuner detection allocated {violations} time(s)"
+    );
+    assert!(handle.get_tuner_info().frequency.is_some());
+}
+
 // ---------------------------------------------------------------------------
 // Metronome
 // ---------------------------------------------------------------------------
@@ -703,12 +792,18 @@ fn metronome_processing_does_not_allocate() {
     // Engine::process_metronome (driven on the RT thread for the standalone
     // metronome output port).
     let mut metronome = Metronome::new(120.0, SAMPLE_RATE);
-    metronome.toggle_metronome(); // enable
+    metronome.set_config(
+        MetronomeConfig {
+            enabled: true,
+            ..MetronomeConfig::default()
+        },
+        0,
+    );
     let mut output = vec![0.0_f32; BUFFER_SIZE];
 
     let violations = check_no_alloc(|| {
-        for _ in 0..32 {
-            metronome.process_block(&mut output);
+        for block in 0..32 {
+            metronome.process_block(&mut output, (block * BUFFER_SIZE) as u64);
         }
     });
     assert_eq!(
@@ -716,3 +811,102 @@ fn metronome_processing_does_not_allocate() {
         "metronome processing allocated {violations} time(s)"
     );
 }
+
+// ---------------------------------------------------------------------------
+// Test source
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_sources_do_not_allocate() {
+    // Covers: TestSource::render for every kind, fed through the full engine
+    // so the input meter and tuner see the generated block. The generator and
+    // its wavetable are built in `EngineHandle::set_test_source`, off the RT
+    // thread.
+    let di: std::sync::Arc<[f32]> = (0..1000).map(|i| (i as f32 * 0.01).sin()).collect();
+    for kind in [
+        TestSourceKind::Sine,
+        TestSourceKind::PinkNoise,
+        TestSourceKind::DiSample,
+    ] {
+        let (mut engine, handle) = full_engine(1.0, None);
+        handle.set_tuner_enabled(true);
+        let config = TestSourceConfig {
+            kind,
+            ..Default::default()
+        };
+        handle.set_test_source(config, SAMPLE_RATE, Some(std::sync::Arc::clone(&di)));
+
+        let (input, mut output) = buffers();
+        assert_engine_alloc_free(&mut engine, &input, &mut output, 32);
+    }
+}
+
+#[test]
+fn take_player_switching_does_not_allocate() {
+    // Covers: TakePlayer::render and TakePlayer::apply on the stereo path.
+    // The takes are decoded and aligned in `TakePlayer::new`, off the RT
+    // thread.
+    let takes = (0..MAX_TAKES)
+        .map(|n| {
+            let samples: Vec<f32> = (0..SAMPLE_RATE)
+                .map(|i| ((i + n * 100) as f32 * 0.01).sin())
+                .collect();
+            Take::new(format!("take {n}"), samples.clone(), samples)
+        })
+        .collect();
+    let (mut engine, handle) = full_engine(1.0, None);
+    handle.set_take_player(Some(TakePlayer::new(takes, true)));
+    handle.take_command(TakeCommand::Play);
+
+    let (input, _) = buffers();
+    let mut left = vec![0.0_f32; BUFFER_SIZE];
+    let mut right = vec![0.0_f32; BUFFER_SIZE];
+    engine
+        .process_stereo_input(&input, &input, &mut left, &mut right)
+        .unwrap();
+
+    let violations = check_no_alloc(|| {
+        for block in 0..32 {
+            handle.take_command(TakeCommand::Select(block % MAX_TAKES));
+            engine
+                .process_stereo_input(&input, &input, &mut left, &mut right)
+                .unwrap();
+        }
+    });
+    assert_eq!(
+        violations, 0,
+        "take switching allocated {violations} time(s) on the RT path"
+    );
+}
+
+#[test]
+fn queued_chain_landing_on_the_bar_does_not_allocate() {
+    // Covers: Engine::land_queued_chain and the edits routed to a chain
+    // waiting for its bar. The chain is built and boxed before the scope,
+    // like the GUI does off the RT thread.
+    let (mut engine, handle) = full_engine(1.0, None);
+    handle.set_metronome(MetronomeConfig {
+        enabled: true,
+        ..MetronomeConfig::default()
+    });
+    let (input, mut output) = buffers();
+    engine.process(&input, &mut output).unwrap();
+
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(0.5)));
+    handle.queue_amp_chain(chain);
+
+    // Two seconds is a bar at the default 120 BPM in 4/4.
+    let blocks = 2 * SAMPLE_RATE / BUFFER_SIZE + 1;
+    let violations = check_no_alloc(|| {
+        for _ in 0..blocks {
+            handle.set_parameter(0, "gain", 0.25);
+            engine.process(&input, &mut output).unwrap();
+        }
+    });
+    assert_eq!(
+        violations, 0,
+        "landing a queued chain allocated {violations} time(s) on the RT path"
+    );
+    assert!(!handle.chain_queued());
+}
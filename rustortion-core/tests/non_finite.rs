@@ -0,0 +1,73 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::amp::stages::delay::DelayStage;
+use rustortion_core::audio::engine::Engine;
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+
+/// Emits a single NaN on the first sample it sees, then passes through.
+struct NanOnce {
+    armed: bool,
+}
+
+impl Stage for NanOnce {
+    fn process(&mut self, input: f32) -> f32 {
+        if std::mem::take(&mut self.armed) {
+            f32::NAN
+        } else {
+            input
+        }
+    }
+
+    fn set_parameter(&mut self, _name: &str, _value: f32) -> Result<(), &'static str> {
+        Err("Unknown parameter")
+    }
+
+    fn get_parameter(&self, _name: &str) -> Result<f32, &'static str> {
+        Err("Unknown parameter")
+    }
+}
+
+#[test]
+fn nan_in_chain_is_flushed_and_state_recovers_within_one_block() {
+    let mut convolver = Convolver::new_fir(64);
+    convolver.set_ir(&[1.0, 0.5, 0.25]).unwrap();
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, 64);
+    cabinet.set_convolver(convolver);
+    cabinet.set_gain(1.0);
+
+    let (mut engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, Some(cabinet), 1.0).unwrap();
+    let diagnostics = engine.diagnostics();
+
+    // 1 ms delay (48 samples) with feedback: without a reset, the NaN would be
+    // read back out of the delay line during the very next block.
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(NanOnce { armed: true }));
+    chain.add_stage(Box::new(DelayStage::new(1.0, 0.5, 0.5, SAMPLE_RATE as f32)));
+    handle.set_amp_chain(chain);
+
+    let input = [0.25f32; BUFFER_SIZE];
+    let mut output = [0.0f32; BUFFER_SIZE];
+
+    engine.process(&input, &mut output).unwrap();
+    assert!(output.iter().all(|s| s.is_finite()));
+    assert_eq!(diagnostics.snapshot().non_finite_resets, 1);
+
+    engine.process(&input, &mut output).unwrap();
+    assert!(output.iter().all(|s| s.is_finite()));
+    assert!(
+        output.iter().any(|&s| s != 0.0),
+        "expected signal after recovery"
+    );
+    assert_eq!(
+        diagnostics.snapshot().non_finite_resets,
+        1,
+        "NaN survived the reset"
+    );
+}
@@ -0,0 +1,205 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::f32::consts::TAU;
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::filter::{FilterStage, FilterType};
+use rustortion_core::audio::automation::{Automation, AutomationEvent};
+use rustortion_core::audio::engine::Engine;
+use rustortion_core::audio::offline::{
+    OFFLINE_BLOCK_SIZE, read_mono_wav, render_automation, render_file,
+};
+use rustortion_core::ir::cabinet::{ConvolverType, DEFAULT_MAX_IR_MS, IrCabinet};
+use rustortion_core::preset::Preset;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+const OVERSAMPLING: u32 = 2;
+
+fn test_preset() -> Preset {
+    Preset {
+        stages: vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::ToneStack),
+            StageConfig::from(StageType::Delay),
+        ],
+        ..Preset::default()
+    }
+}
+
+/// A DI-like test signal that doesn't end on a block boundary.
+fn test_signal() -> Vec<f32> {
+    (0..OFFLINE_BLOCK_SIZE * 3 + 100)
+        .map(|i| 0.4 * (TAU * 220.0 * i as f32 / SAMPLE_RATE as f32).sin())
+        .collect()
+}
+
+/// Feed `samples` through an engine set up by hand the way the live app does,
+/// at the same block size, zero-padding the final block.
+fn render_realtime(preset: &Preset, samples: &[f32]) -> Vec<f32> {
+    let max_ir_samples = (SAMPLE_RATE * DEFAULT_MAX_IR_MS) / 1000;
+    let mut cabinet = IrCabinet::new(ConvolverType::default(), max_ir_samples);
+    cabinet.set_gain(preset.ir_gain);
+
+    let (mut engine, handle, _rt_drop_rx) = Engine::new_for_plugin(
+        SAMPLE_RATE,
+        OFFLINE_BLOCK_SIZE,
+        Some(cabinet),
+        f64::from(OVERSAMPLING),
+    )
+    .unwrap();
+
+    let filters = &preset.input_filters;
+    handle.set_input_filters(
+        Some(Box::new(FilterStage::new(
+            FilterType::Highpass,
+            filters.hp_cutoff,
+            SAMPLE_RATE as f32,
+        ))),
+        Some(Box::new(FilterStage::new(
+            FilterType::Lowpass,
+            filters.lp_cutoff,
+            SAMPLE_RATE as f32,
+        ))),
+    );
+
+    let mut chain = AmplifierChain::new();
+    for cfg in &preset.stages {
+        chain.add_stage(cfg.to_runtime((SAMPLE_RATE * OVERSAMPLING as usize) as f32));
+    }
+    handle.set_amp_chain(chain);
+    handle.set_pitch_shift(preset.pitch_shift_semitones);
+
+    let mut output = Vec::new();
+    for chunk in samples.chunks(OFFLINE_BLOCK_SIZE) {
+        let mut input = [0.0f32; OFFLINE_BLOCK_SIZE];
+        input[..chunk.len()].copy_from_slice(chunk);
+        let mut block = [0.0f32; OFFLINE_BLOCK_SIZE];
+        engine.process(&input, &mut block).unwrap();
+        output.extend_from_slice(&block[..chunk.len()]);
+    }
+    output
+}
+
+#[test]
+fn offline_render_matches_realtime_engine() {
+    let dir = tempfile::tempdir().unwrap();
+    let input_path = dir.path().join("dry.wav");
+    let output_path = dir.path().join("wet.wav");
+
+    let signal = test_signal();
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(&input_path, spec).unwrap();
+    for &s in &signal {
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let preset = test_preset();
+    let mut last_progress = 0.0;
+    render_file(
+        &preset,
+        &input_path,
+        &output_path,
+        SAMPLE_RATE,
+        OVERSAMPLING,
+        None,
+        |p| last_progress = p,
+    )
+    .unwrap();
+
+    let (rendered, rate) = read_mono_wav(&output_path).unwrap();
+    assert_eq!(rate, SAMPLE_RATE as u32);
+    assert_eq!(rendered.len(), signal.len());
+    assert!((last_progress - 1.0).abs() < f32::EPSILON);
+
+    let expected = render_realtime(&preset, &signal);
+    assert!(rendered.iter().any(|&s| s != 0.0), "render is silent");
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn offline_render_rejects_sample_rate_mismatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let input_path = dir.path().join("dry.wav");
+
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 44_100,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&input_path, spec).unwrap();
+    writer.write_sample(0i16).unwrap();
+    writer.finalize().unwrap();
+
+    let result = render_file(
+        &test_preset(),
+        &input_path,
+        &dir.path().join("wet.wav"),
+        SAMPLE_RATE,
+        1,
+        None,
+        |_| {},
+    );
+    assert!(result.is_err());
+}
+
+/// Replay a mute of a level stage at `muted_at` and check it lands there.
+fn assert_mute_replays_at(muted_at: usize) {
+    let dir = tempfile::tempdir().unwrap();
+    let input_path = dir.path().join("dry.wav");
+    let output_path = dir.path().join("wet.wav");
+
+    let signal = test_signal();
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(&input_path, spec).unwrap();
+    for &s in &signal {
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let automation = Automation {
+        sample_rate: SAMPLE_RATE,
+        preset: Preset {
+            stages: vec![StageConfig::from(StageType::Level)],
+            ..Preset::default()
+        },
+        events: vec![AutomationEvent {
+            frame: muted_at as u64,
+            stage: 0,
+            param: "gain".to_string(),
+            value: 0.0,
+        }],
+    };
+
+    render_automation(&automation, &input_path, &output_path, 1, None, |_| {}).unwrap();
+
+    let (rendered, _) = read_mono_wav(&output_path).unwrap();
+    assert_eq!(rendered.len(), signal.len());
+    assert!(rendered[..muted_at].iter().any(|&s| s != 0.0));
+    assert!(rendered[muted_at..].iter().all(|&s| s == 0.0));
+}
+
+#[test]
+fn automation_replays_on_the_recorded_frame() {
+    // A 128-frame live buffer: the change landed at the start of its third block.
+    assert_mute_replays_at(256);
+}
+
+#[test]
+fn automation_replays_on_the_frame_of_a_buffer_size_64_does_not_divide() {
+    // A 48-frame live buffer, e.g. 1 ms at 48 kHz.
+    assert_mute_replays_at(144);
+}
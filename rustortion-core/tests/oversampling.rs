@@ -0,0 +1,136 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Every stage, built the way the engine builds it at each oversampling
+//! factor, must process cleanly and report the same parameters whatever rate
+//! it runs at.
+
+use std::f32::consts::PI;
+
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::preset::{StageConfig, StageType};
+
+const BASE_RATES: [usize; 2] = [44_100, 96_000];
+const FACTORS: [usize; 3] = [1, 4, 16];
+/// Long enough for the default delay's first echo to come back.
+const SECONDS: f32 = 0.4;
+
+/// Parameters each stage reports, besides the trims every stage has.
+fn parameters(kind: StageType) -> &'static [&'static str] {
+    match kind {
+        StageType::Preamp => &["gain", "bias", "auto_level"],
+        StageType::Compressor => &["threshold", "ratio", "attack", "release", "makeup"],
+        StageType::ToneStack => &["bass", "mid", "treble", "presence"],
+        StageType::PowerAmp => &["drive", "sag", "sag_release", "auto_level"],
+        StageType::Level => &["gain"],
+        StageType::NoiseGate => &["threshold", "ratio", "attack", "hold", "release"],
+        StageType::MultibandSaturator => &[
+            "low_drive",
+            "mid_drive",
+            "high_drive",
+            "low_level",
+            "mid_level",
+            "high_level",
+            "low_freq",
+            "high_freq",
+            "low_mute",
+            "low_solo",
+        ],
+        StageType::Nam | StageType::Capture => &["input_gain_db", "output_gain_db", "mix"],
+        StageType::Delay => &[
+            "delay_time",
+            "feedback",
+            "mix",
+            "duck_depth",
+            "duck_release",
+            "sync",
+            "division",
+        ],
+        StageType::Reverb => &["room_size", "damping", "mix"],
+        StageType::Eq => &["band_0", "band_8", "band_15"],
+        StageType::Tremolo => &["rate", "depth", "shape"],
+        StageType::Wah => &["position", "q", "sensitivity", "attack", "release"],
+        StageType::Group => &[],
+    }
+}
+
+fn names(kind: StageType) -> impl Iterator<Item = &'static str> {
+    parameters(kind)
+        .iter()
+        .copied()
+        .chain(["in_trim_db", "out_trim_db", "invert"])
+}
+
+/// dB parameters go through a linear gain and back.
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-4 * a.abs().max(1.0)
+}
+
+fn build(kind: StageType, base_rate: usize, factor: usize) -> Box<dyn Stage> {
+    StageConfig::from(kind).to_runtime((base_rate * factor) as f32)
+}
+
+/// A 220 Hz tone with a loud burst in the middle, at the oversampled rate.
+fn input(base_rate: usize, factor: usize) -> Vec<f32> {
+    let rate = (base_rate * factor) as f32;
+    let len = (SECONDS * rate) as usize;
+    (0..len)
+        .map(|i| {
+            let t = i as f32 / rate;
+            let level = if (0.1..0.15).contains(&t) { 1.0 } else { 0.3 };
+            level * (2.0 * PI * 220.0 * t).sin()
+        })
+        .collect()
+}
+
+#[test]
+fn every_stage_stays_finite_at_every_rate() {
+    for base_rate in BASE_RATES {
+        for factor in FACTORS {
+            let mut block = input(base_rate, factor);
+            for &kind in StageType::ALL {
+                let mut stage = build(kind, base_rate, factor);
+                let mut samples = block.clone();
+                stage.process_block(&mut samples);
+                if let Some(i) = samples.iter().position(|s| !s.is_finite()) {
+                    panic!(
+                        "{kind} at {base_rate} Hz x{factor}: sample {i} is {}",
+                        samples[i]
+                    );
+                }
+                // Feed the next stage, like a chain would.
+                block = samples.iter().map(|s| s.clamp(-1.0, 1.0)).collect();
+            }
+        }
+    }
+}
+
+#[test]
+fn parameters_round_trip_at_every_rate() {
+    for &kind in StageType::ALL {
+        let reference = build(kind, BASE_RATES[0], 1);
+        for base_rate in BASE_RATES {
+            for factor in FACTORS {
+                let mut stage = build(kind, base_rate, factor);
+                for name in names(kind) {
+                    let expected = reference
+                        .get_parameter(name)
+                        .unwrap_or_else(|e| panic!("{kind} has no {name}: {e}"));
+                    let value = stage.get_parameter(name).unwrap();
+                    assert!(
+                        close(value, expected),
+                        "{kind} {name} at {base_rate} Hz x{factor} is {value}, {expected} at the base rate"
+                    );
+
+                    stage
+                        .set_parameter(name, value)
+                        .unwrap_or_else(|e| panic!("{kind} rejects its own {name}: {e}"));
+                    let restored = stage.get_parameter(name).unwrap();
+                    assert!(
+                        close(restored, value),
+                        "{kind} {name} at {base_rate} Hz x{factor} came back as {restored}, set {value}"
+                    );
+                }
+            }
+        }
+    }
+}
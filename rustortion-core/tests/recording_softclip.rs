@@ -0,0 +1,93 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use hound::WavReader;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::peak_meter::PeakMeter;
+use rustortion_core::audio::rt_drop::RtDropHandle;
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::metronome::Metronome;
+use rustortion_core::tuner::Tuner;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK: usize = 256;
+const BLOCKS: usize = 100;
+
+fn engine() -> (Engine, EngineHandle) {
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BLOCK, 1.0, SAMPLE_RATE).unwrap();
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )
+    .unwrap()
+}
+
+/// Record a full-scale sine through a fresh engine. Returns what the engine
+/// put out, the take's left channel and whether it was flagged as
+/// soft-clipped.
+fn record(softclip: bool) -> (Vec<f32>, Vec<i16>, bool) {
+    let (mut engine, handle) = engine();
+    let dir = tempfile::tempdir().unwrap();
+    let take = handle
+        .start_recording(
+            SAMPLE_RATE,
+            dir.path().to_str().unwrap(),
+            BLOCK,
+            None,
+            None,
+            softclip,
+        )
+        .unwrap();
+
+    let mut monitored = Vec::with_capacity(BLOCK * BLOCKS);
+    let mut output = vec![0.0; BLOCK];
+    for block in 0..BLOCKS {
+        let input: Vec<f32> = (0..BLOCK)
+            .map(|i| {
+                let t = (block * BLOCK + i) as f32 / SAMPLE_RATE as f32;
+                (std::f32::consts::TAU * 220.0 * t).sin()
+            })
+            .collect();
+        engine.process(&input, &mut output).unwrap();
+        monitored.extend_from_slice(&output);
+    }
+    let softclipped = handle.stop_recording();
+    engine.process(&[0.0; BLOCK], &mut output).unwrap();
+
+    let left = WavReader::open(&take)
+        .unwrap()
+        .samples::<i16>()
+        .step_by(2)
+        .map(Result::unwrap)
+        .collect();
+    (monitored, left, softclipped)
+}
+
+#[test]
+fn soft_clip_shapes_the_take_but_not_what_is_monitored() {
+    let (plain_out, plain_take, plain_flag) = record(false);
+    let (clipped_out, clipped_take, clipped_flag) = record(true);
+
+    assert!(!plain_flag);
+    assert!(clipped_flag);
+
+    // Bit for bit the same output, soft clip or not.
+    assert_eq!(
+        plain_out.iter().map(|s| s.to_bits()).collect::<Vec<_>>(),
+        clipped_out.iter().map(|s| s.to_bits()).collect::<Vec<_>>()
+    );
+
+    assert_eq!(plain_take.len(), clipped_take.len());
+    let peak = |take: &[i16]| take.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0);
+    assert!(
+        peak(&clipped_take) < peak(&plain_take),
+        "soft clip left the take's peak at {}",
+        peak(&clipped_take)
+    );
+}
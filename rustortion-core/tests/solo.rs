@@ -0,0 +1,108 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Soloing a stage plays the chain only up to it, with or without what comes
+//! after the chain, and never outlives a change to the stages.
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::level::LevelStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle, MonitorPoint};
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK_SIZE: usize = 128;
+/// Enough blocks for gain changes and filters to settle.
+const SETTLE_BLOCKS: usize = 40;
+
+/// Stages at x2 and x0.5 into a cabinet at x0.5: unity through the chain,
+/// half through the whole engine.
+fn engine() -> (Engine, EngineHandle) {
+    let mut ir = vec![0.0f32; 256];
+    ir[0] = 1.0;
+    let mut convolver = Convolver::new_fir(ir.len());
+    convolver.set_ir(&ir).unwrap();
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, ir.len());
+    cabinet.set_convolver(convolver);
+    cabinet.set_gain(0.5);
+
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, Some(cabinet), 1.0).unwrap();
+
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(2.0)));
+    chain.add_stage(Box::new(LevelStage::new(0.5)));
+    handle.set_amp_chain(chain);
+    (engine, handle)
+}
+
+/// Peak output for a 1 kHz sine of amplitude 0.25, once settled.
+fn output_peak(engine: &mut Engine) -> f32 {
+    let mut peak = 0.0f32;
+    for block in 0..SETTLE_BLOCKS {
+        let input: Vec<f32> = (0..BLOCK_SIZE)
+            .map(|i| {
+                let t = (block * BLOCK_SIZE + i) as f32 / SAMPLE_RATE as f32;
+                0.25 * (std::f32::consts::TAU * 1_000.0 * t).sin()
+            })
+            .collect();
+        let mut output = vec![0.0f32; BLOCK_SIZE];
+        engine.process(&input, &mut output).unwrap();
+        if block + 1 == SETTLE_BLOCKS {
+            peak = output.iter().fold(0.0, |m, s| m.max(s.abs()));
+        }
+    }
+    peak
+}
+
+fn assert_near(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() < 0.01,
+        "expected peak {expected}, got {actual}"
+    );
+}
+
+#[test]
+fn solo_taps_the_chain_at_the_stage() {
+    let (mut engine, handle) = engine();
+    assert_near(output_peak(&mut engine), 0.125);
+
+    handle.set_monitor_point(Some(MonitorPoint {
+        stage: 0,
+        keep_post: false,
+    }));
+    assert_near(output_peak(&mut engine), 0.5);
+
+    // Keeping the post chain runs the soloed signal through the cabinet.
+    handle.set_monitor_point(Some(MonitorPoint {
+        stage: 0,
+        keep_post: true,
+    }));
+    assert_near(output_peak(&mut engine), 0.25);
+
+    handle.set_monitor_point(None);
+    assert_near(output_peak(&mut engine), 0.125);
+}
+
+#[test]
+fn chain_changes_end_the_solo() {
+    let (mut engine, handle) = engine();
+    handle.set_monitor_point(Some(MonitorPoint {
+        stage: 0,
+        keep_post: false,
+    }));
+    assert_near(output_peak(&mut engine), 0.5);
+
+    handle.add_stage(2, Box::new(LevelStage::new(1.0)));
+    assert_near(output_peak(&mut engine), 0.125);
+
+    handle.set_monitor_point(Some(MonitorPoint {
+        stage: 0,
+        keep_post: false,
+    }));
+    assert_near(output_peak(&mut engine), 0.5);
+
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(1.0)));
+    handle.set_amp_chain(chain);
+    assert_near(output_peak(&mut engine), 0.125);
+}
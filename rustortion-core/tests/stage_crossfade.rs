@@ -0,0 +1,98 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::f32::consts::TAU;
+
+use rustortion_core::amp::crossfade::{STAGE_CROSSFADE_MS, StageCrossfade};
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::amp::stages::clipper::ClipperType;
+use rustortion_core::amp::stages::preamp::PreampStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+const BLOCKS: usize = 80;
+/// The clipper changes at the start of this block.
+const SWAP_BLOCK: usize = 40;
+const FREQUENCY: f32 = 110.0;
+/// Largest step allowed beyond the sine's own, through either clipper.
+const EPSILON: f32 = 0.005;
+
+fn preamp(clipper: ClipperType) -> Box<dyn Stage> {
+    Box::new(PreampStage::new(1.0, 0.0, clipper, SAMPLE_RATE as f32))
+}
+
+/// Run a sine through a soft-clipping preamp, calling `swap` to change it
+/// to hard clipping just before [`SWAP_BLOCK`]. The sine peaks at the swap,
+/// where the clippers differ most.
+fn render(swap: impl FnOnce(&EngineHandle)) -> Vec<f32> {
+    let (mut engine, handle, _rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    handle.add_stage(0, preamp(ClipperType::Soft));
+
+    let swap_at = (SWAP_BLOCK * BUFFER_SIZE) as f32;
+    let mut swap = Some(swap);
+    let mut output = Vec::with_capacity(BLOCKS * BUFFER_SIZE);
+    for block in 0..BLOCKS {
+        if block == SWAP_BLOCK
+            && let Some(swap) = swap.take()
+        {
+            swap(&handle);
+        }
+        let input: Vec<f32> = (0..BUFFER_SIZE)
+            .map(|i| {
+                let n = (block * BUFFER_SIZE + i) as f32;
+                0.5 * (TAU * FREQUENCY * (n - swap_at) / SAMPLE_RATE as f32).cos()
+            })
+            .collect();
+        let mut block_out = vec![0.0; BUFFER_SIZE];
+        engine.process(&input, &mut block_out).unwrap();
+        output.extend(block_out);
+    }
+    output
+}
+
+fn max_step(samples: &[f32]) -> f32 {
+    samples
+        .windows(2)
+        .fold(0.0_f32, |max, w| max.max((w[1] - w[0]).abs()))
+}
+
+/// The largest step in the sine through the old clipper before the swap
+/// and through the new one after the fade, and the largest across the swap.
+fn steps(output: &[f32]) -> (f32, f32) {
+    let swap = SWAP_BLOCK * BUFFER_SIZE;
+    let fade = (STAGE_CROSSFADE_MS / 1000.0 * SAMPLE_RATE as f32) as usize;
+    // Skip the start, where the DC blocker settles.
+    let before = max_step(&output[swap / 2..swap]);
+    let after = max_step(&output[swap + 2 * fade..]);
+    let across = max_step(&output[swap - 1..swap + 2 * fade]);
+    (before.max(after), across)
+}
+
+#[test]
+fn clipper_change_crossfades_without_a_click() {
+    let output = render(|handle| {
+        handle.crossfade_stage(
+            0,
+            StageCrossfade::new(preamp(ClipperType::Hard), SAMPLE_RATE as f32),
+        );
+    });
+
+    let (steady, across) = steps(&output);
+    assert!(
+        across <= steady + EPSILON,
+        "step of {across} across the swap, {steady} steady"
+    );
+}
+
+#[test]
+fn replacing_outright_clicks() {
+    // What the fade is for: the cold new stage jumps away from the old.
+    let output = render(|handle| handle.replace_stage(0, preamp(ClipperType::Hard)));
+
+    let (steady, across) = steps(&output);
+    assert!(
+        across > steady * 2.0,
+        "step of {across} across the swap, {steady} steady"
+    );
+}
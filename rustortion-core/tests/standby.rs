@@ -0,0 +1,165 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Standby mutes the power amp's output with a fade, while every stage keeps
+//! running, and survives the chain being swapped.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::standby::STANDBY_FADE_SECONDS;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK_SIZE: usize = 128;
+const LEVEL: f32 = 0.5;
+
+/// Passes its input through, counting samples and keeping the last one seen
+/// where the test can read them.
+#[derive(Clone, Default)]
+struct Probe {
+    power_amp: bool,
+    processed: Arc<AtomicUsize>,
+    last_input: Arc<AtomicU32>,
+}
+
+impl Probe {
+    fn power_amp() -> Self {
+        Self {
+            power_amp: true,
+            ..Self::default()
+        }
+    }
+
+    fn processed(&self) -> usize {
+        self.processed.load(Ordering::Relaxed)
+    }
+
+    fn last_input(&self) -> f32 {
+        f32::from_bits(self.last_input.load(Ordering::Relaxed))
+    }
+}
+
+impl Stage for Probe {
+    fn process(&mut self, input: f32) -> f32 {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.last_input.store(input.to_bits(), Ordering::Relaxed);
+        input
+    }
+
+    fn set_parameter(&mut self, _name: &str, _value: f32) -> Result<(), &'static str> {
+        Err("no parameters")
+    }
+
+    fn get_parameter(&self, _name: &str) -> Result<f32, &'static str> {
+        Err("no parameters")
+    }
+
+    fn is_power_amp(&self) -> bool {
+        self.power_amp
+    }
+}
+
+/// An engine running ahead -> power amp -> after, with no cabinet.
+fn engine() -> (Engine, EngineHandle, [Probe; 3]) {
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, None, 1.0).unwrap();
+    let probes = [Probe::default(), Probe::power_amp(), Probe::default()];
+    let mut chain = AmplifierChain::new();
+    for probe in &probes {
+        chain.add_stage(Box::new(probe.clone()));
+    }
+    handle.set_amp_chain(chain);
+    (engine, handle, probes)
+}
+
+/// Run `frames` of a constant input, returning the last block's output.
+fn run(engine: &mut Engine, frames: usize) -> Vec<f32> {
+    let input = vec![LEVEL; BLOCK_SIZE];
+    let mut output = vec![0.0f32; BLOCK_SIZE];
+    for _ in 0..frames / BLOCK_SIZE {
+        engine.process(&input, &mut output).unwrap();
+    }
+    output
+}
+
+const FADE_FRAMES: usize = (STANDBY_FADE_SECONDS * SAMPLE_RATE as f32) as usize;
+
+#[test]
+fn standby_fades_the_output_out_and_back_in() {
+    let (mut engine, handle, _probes) = engine();
+    let output = run(&mut engine, BLOCK_SIZE);
+    assert!((output[BLOCK_SIZE - 1] - LEVEL).abs() < 1e-6);
+
+    handle.set_standby(true);
+    // Half way through the fade, half the level.
+    let output = run(&mut engine, FADE_FRAMES / 2);
+    assert!(
+        (output[BLOCK_SIZE - 1] - LEVEL / 2.0).abs() < 0.01,
+        "got {}",
+        output[BLOCK_SIZE - 1]
+    );
+    // No step anywhere along it.
+    assert!(
+        output
+            .windows(2)
+            .all(|w| w[1] <= w[0] && w[0] - w[1] < 1e-3)
+    );
+
+    let output = run(&mut engine, FADE_FRAMES);
+    assert!(output.iter().all(|s| *s == 0.0));
+
+    handle.set_standby(false);
+    let output = run(&mut engine, BLOCK_SIZE);
+    assert!(output[0] > 0.0 && output[BLOCK_SIZE - 1] < 0.01);
+    let output = run(&mut engine, FADE_FRAMES);
+    assert!((output[BLOCK_SIZE - 1] - LEVEL).abs() < 1e-6);
+}
+
+#[test]
+fn stages_keep_running_in_standby() {
+    let (mut engine, handle, [ahead, power_amp, after]) = engine();
+    handle.set_standby(true);
+    run(&mut engine, 2 * FADE_FRAMES);
+    let processed = [ahead.processed(), power_amp.processed(), after.processed()];
+
+    run(&mut engine, 10 * BLOCK_SIZE);
+    assert_eq!(ahead.processed(), processed[0] + 10 * BLOCK_SIZE);
+    assert_eq!(power_amp.processed(), processed[1] + 10 * BLOCK_SIZE);
+    assert_eq!(after.processed(), processed[2] + 10 * BLOCK_SIZE);
+
+    // The stages up to the power amp still hear the input; the ones after
+    // it run on the muted output.
+    assert_eq!(ahead.last_input(), LEVEL);
+    assert_eq!(power_amp.last_input(), LEVEL);
+    assert_eq!(after.last_input(), 0.0);
+}
+
+#[test]
+fn standby_outlasts_a_chain_swap() {
+    let (mut engine, handle, _probes) = engine();
+    handle.set_standby(true);
+    run(&mut engine, 2 * FADE_FRAMES);
+
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(Probe::power_amp()));
+    handle.set_amp_chain(chain);
+    let output = run(&mut engine, BLOCK_SIZE);
+    assert!(output.iter().all(|s| *s == 0.0));
+}
+
+#[test]
+fn a_chain_without_a_power_amp_is_muted_at_its_end() {
+    let (mut engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK_SIZE, None, 1.0).unwrap();
+    let probe = Probe::default();
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(probe.clone()));
+    handle.set_amp_chain(chain);
+    handle.set_standby(true);
+
+    let output = run(&mut engine, 2 * FADE_FRAMES);
+    assert!(output.iter().all(|s| *s == 0.0));
+    assert_eq!(probe.last_input(), LEVEL);
+}
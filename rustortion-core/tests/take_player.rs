@@ -0,0 +1,155 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+
+use rustortion_core::audio::engine::Engine;
+use rustortion_core::audio::take_player::{TakeCommand, TakePlayer, recent_takes};
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+const TAKE_FRAMES: usize = 24_000;
+
+/// A 330 Hz note starting at `onset`, decaying from `level`.
+fn note(onset: usize, level: f32) -> Vec<f32> {
+    (0..TAKE_FRAMES)
+        .map(|i| {
+            if i < onset {
+                return 0.0;
+            }
+            let t = (i - onset) as f32 / SAMPLE_RATE as f32;
+            level * (-t * 8.0).exp() * (std::f32::consts::TAU * 330.0 * t).sin()
+        })
+        .collect()
+}
+
+/// Write `samples` to both channels of a 16-bit take, as the recorder
+/// would, dated `age` seconds ago.
+fn write_take(dir: &Path, name: &str, samples: &[f32], age: u64) -> PathBuf {
+    let path = dir.join(name);
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate: SAMPLE_RATE as u32,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&path, spec).unwrap();
+    for &s in samples {
+        let s = (s * f32::from(i16::MAX)) as i16;
+        writer.write_sample(s).unwrap();
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let modified = SystemTime::now() - Duration::from_secs(age);
+    File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    path
+}
+
+#[test]
+fn recent_takes_sharing_a_name_are_picked_oldest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let silence = [0.0; 64];
+    write_take(dir.path(), "recording_20260101_110000.wav", &silence, 600);
+    let first = write_take(dir.path(), "recording_20260101_120000.wav", &silence, 500);
+    let second = write_take(dir.path(), "recording_20260101_120500.wav", &silence, 400);
+    write_take(
+        dir.path(),
+        "recording_20260101_120500.click.wav",
+        &silence,
+        400,
+    );
+    write_take(dir.path(), "bass 1.wav", &silence, 300);
+    let third = write_take(dir.path(), "recording_20260101_121000.wav", &silence, 200);
+    let fourth = write_take(dir.path(), "recording_20260101_121500.wav", &silence, 100);
+
+    assert_eq!(
+        recent_takes(dir.path()).unwrap(),
+        [first, second, third, fourth]
+    );
+}
+
+#[test]
+fn loaded_takes_line_up_on_their_first_notes() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = [
+        write_take(dir.path(), "take 1.wav", &note(6000, 0.3), 30),
+        write_take(dir.path(), "take 2.wav", &note(2000, 0.9), 20),
+        write_take(dir.path(), "take 3.wav", &note(4500, 0.1), 10),
+    ];
+
+    let player = TakePlayer::load(&paths, SAMPLE_RATE, false).unwrap();
+    let offsets = player.offsets();
+    assert_eq!(offsets[1], 0);
+    // Quantised to 16 bits the quiet take's note crosses its threshold a
+    // sample or two apart from the others.
+    assert!(offsets[0].abs_diff(4000) <= 2, "offsets {offsets:?}");
+    assert!(offsets[2].abs_diff(2500) <= 2, "offsets {offsets:?}");
+    assert_eq!(player.take_names(), ["take 1", "take 2", "take 3"]);
+}
+
+#[test]
+fn switching_takes_in_the_engine_keeps_the_place() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = [
+        write_take(dir.path(), "take 1.wav", &note(3000, 0.5), 20),
+        write_take(dir.path(), "take 2.wav", &note(1000, 0.25), 10),
+    ];
+    let player = TakePlayer::load(&paths, SAMPLE_RATE, false).unwrap();
+    let transport = player.transport();
+
+    let (mut engine, handle, _rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    handle.set_take_player(Some(player));
+    handle.take_command(TakeCommand::Play);
+
+    let input = vec![0.0_f32; BUFFER_SIZE];
+    let mut left = vec![0.0_f32; BUFFER_SIZE];
+    let mut right = vec![0.0_f32; BUFFER_SIZE];
+    let mut block = |left: &mut [f32], right: &mut [f32]| {
+        engine
+            .process_stereo_input(&input, &input, left, right)
+            .unwrap();
+    };
+
+    // Take 1 into its note, then take 2 from the same place.
+    let mut heard = Vec::new();
+    for _ in 0..16 {
+        block(&mut left, &mut right);
+        heard.extend_from_slice(&left);
+    }
+    assert_eq!(transport.frame(), 16 * BUFFER_SIZE);
+    handle.take_command(TakeCommand::Select(1));
+    for _ in 0..16 {
+        block(&mut left, &mut right);
+        heard.extend_from_slice(&left);
+    }
+    assert_eq!(transport.frame(), 32 * BUFFER_SIZE);
+    assert!(transport.is_playing());
+
+    // The takes are the same note 2000 frames apart at different levels.
+    // Before the switch it's take 1, cut to line up with take 2; once the
+    // switch's short crossfade is over, take 2 carries on from the same
+    // place in the note.
+    let take_1 = note(1000, 0.5);
+    let take_2 = note(1000, 0.25);
+    for (frame, sample) in heard.iter().enumerate().take(16 * BUFFER_SIZE) {
+        assert!((sample - take_1[frame]).abs() < 1e-3, "frame {frame}");
+    }
+    let after_fade = 16 * BUFFER_SIZE + 512;
+    for (frame, sample) in heard.iter().enumerate().skip(after_fade) {
+        assert!(
+            (sample - take_2[frame]).abs() < 1e-3,
+            "frame {frame}: {sample} vs {}",
+            take_2[frame]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::sync::Arc;
+
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::audio::peak_meter::PeakMeter;
+use rustortion_core::audio::rt_drop::RtDropHandle;
+use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::audio::test_source::{TestSourceConfig, TestSourceKind};
+use rustortion_core::metronome::Metronome;
+use rustortion_core::tuner::{Tuner, TunerHandle};
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+
+fn sine(frequency_hz: f32) -> TestSourceConfig {
+    TestSourceConfig {
+        kind: TestSourceKind::Sine,
+        frequency_hz,
+        ..Default::default()
+    }
+}
+
+fn peak(buf: &[f32]) -> f32 {
+    buf.iter().fold(0.0_f32, |peak, s| peak.max(s.abs()))
+}
+
+/// Standalone-style engine, so the tuner is present.
+fn engine_with_tuner() -> (Engine, EngineHandle, TunerHandle) {
+    let (tuner, tuner_handle) = Tuner::new(SAMPLE_RATE);
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BUFFER_SIZE, 1.0, SAMPLE_RATE).unwrap();
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    let (engine, handle) = Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )
+    .unwrap();
+    (engine, handle, tuner_handle)
+}
+
+#[test]
+fn generated_signal_replaces_the_input_until_turned_off() {
+    let (mut engine, handle, _rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    let input = vec![0.5_f32; BUFFER_SIZE];
+    let mut output = vec![0.0_f32; BUFFER_SIZE];
+
+    handle.set_test_source(sine(440.0), SAMPLE_RATE, None);
+    let mut generated = Vec::new();
+    for _ in 0..8 {
+        engine.process(&input, &mut output).unwrap();
+        generated.extend_from_slice(&output);
+    }
+    // The empty chain passes the sine through at -20 dBFS; the input's DC is gone.
+    let level_db = 20.0 * peak(&generated).log10();
+    assert!((level_db + 20.0).abs() < 0.1, "level {level_db} dB");
+    let mean = generated.iter().sum::<f32>() / generated.len() as f32;
+    assert!(mean.abs() < 0.01, "mean {mean}");
+
+    handle.set_test_source(TestSourceConfig::default(), SAMPLE_RATE, None);
+    engine.process(&input, &mut output).unwrap();
+    assert_eq!(output, input);
+}
+
+#[test]
+fn di_sample_plays_through_the_engine() {
+    let (mut engine, handle, _rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    let di: Arc<[f32]> = (0..100).map(|i| if i == 0 { 1.0 } else { 0.0 }).collect();
+    let config = TestSourceConfig {
+        kind: TestSourceKind::DiSample,
+        level_db: 0.0,
+        ..Default::default()
+    };
+    handle.set_test_source(config, SAMPLE_RATE, Some(di));
+
+    let input = vec![0.0_f32; BUFFER_SIZE];
+    let mut output = vec![0.0_f32; BUFFER_SIZE];
+    engine.process(&input, &mut output).unwrap();
+
+    let clicks: Vec<usize> = (0..BUFFER_SIZE).filter(|&i| output[i] != 0.0).collect();
+    assert_eq!(clicks, [0, 100]);
+}
+
+#[test]
+fn tuner_hears_the_generated_signal() {
+    let (mut engine, handle, tuner) = engine_with_tuner();
+    handle.set_tuner_enabled(true);
+    handle.set_test_source(sine(110.0), SAMPLE_RATE, None);
+
+    // Silence on the real input; the tuner can only be hearing the sine.
+    let input = vec![0.0_f32; BUFFER_SIZE];
+    let mut output = vec![0.0_f32; BUFFER_SIZE];
+    for _ in 0..(SAMPLE_RATE / BUFFER_SIZE) {
+        engine.process(&input, &mut output).unwrap();
+    }
+
+    let frequency = tuner.get_tuner_info().frequency.expect("a pitch");
+    assert!((frequency - 110.0).abs() < 1.0, "detected {frequency} Hz");
+}
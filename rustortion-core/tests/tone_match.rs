@@ -0,0 +1,110 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::path::Path;
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rustortion_core::audio::offline::render_file;
+use rustortion_core::audio::test_source::{TestSource, TestSourceConfig, TestSourceKind};
+use rustortion_core::audio::tone_match::{MatchReference, MatchSource, ToneMatch, match_tone};
+use rustortion_core::preset::Preset;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+const OVERSAMPLING: u32 = 2;
+
+fn test_preset() -> Preset {
+    Preset {
+        name: "Crunch".to_string(),
+        stages: vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::ToneStack),
+        ],
+        ..Preset::default()
+    }
+}
+
+fn write_wav(path: &Path, samples: &[f32]) {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(path, spec).unwrap();
+    for &s in samples {
+        writer.write_sample(s).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+fn pink_noise(seconds: usize) -> Vec<f32> {
+    let config = TestSourceConfig {
+        kind: TestSourceKind::PinkNoise,
+        ..TestSourceConfig::default()
+    };
+    let mut source = TestSource::new(config, SAMPLE_RATE, None);
+    let mut noise = vec![0.0; seconds * SAMPLE_RATE];
+    source.render(&mut noise);
+    noise
+}
+
+fn assert_flat(matched: &ToneMatch) {
+    assert!(!matched.bands.is_empty());
+    for band in &matched.bands {
+        assert!(
+            band.gain_db.abs() <= 0.5,
+            "{} Hz corrected by {} dB",
+            band.frequency,
+            band.gain_db
+        );
+    }
+    for (i, gain) in matched.eq_gains.iter().enumerate() {
+        assert!(gain.abs() <= 0.5, "EQ band {i} set to {gain} dB");
+    }
+}
+
+#[test]
+fn matching_a_chain_to_its_own_recorded_output_is_flat() {
+    let dir = tempfile::tempdir().unwrap();
+    let dry = dir.path().join("dry.wav");
+    let wet = dir.path().join("wet.wav");
+    write_wav(&dry, &pink_noise(3));
+
+    let preset = test_preset();
+    render_file(&preset, &dry, &wet, SAMPLE_RATE, OVERSAMPLING, None, |_| {}).unwrap();
+
+    let mut last_progress = 0.0;
+    let matched = match_tone(
+        &preset,
+        &MatchSource::Wav(dry),
+        &MatchReference::Wav(wet),
+        SAMPLE_RATE,
+        OVERSAMPLING,
+        None,
+        |p| last_progress = p,
+    )
+    .unwrap();
+
+    assert_flat(&matched);
+    assert!((last_progress - 1.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn matching_a_preset_to_itself_on_pink_noise_is_flat() {
+    let preset = test_preset();
+    let mut progress = Vec::new();
+    let matched = match_tone(
+        &preset,
+        &MatchSource::PinkNoise,
+        &MatchReference::Preset(Box::new(preset.clone())),
+        SAMPLE_RATE,
+        OVERSAMPLING,
+        None,
+        |p| progress.push(p),
+    )
+    .unwrap();
+
+    assert_flat(&matched);
+    assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    assert!((progress.last().unwrap() - 1.0).abs() < f32::EPSILON);
+}
@@ -2,21 +2,31 @@ use std::sync::Arc;
 
 use nih_plug::prelude::{GuiContext, Param};
 use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::crossfade::StageCrossfade;
 use rustortion_core::amp::stages::Stage;
 use rustortion_core::amp::stages::filter::{FilterStage, FilterType};
-use rustortion_core::audio::engine::EngineHandle;
-use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::denoiser::{DenoiseConfig, NoiseLearn};
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::audio::engine::{EngineHandle, EngineState, MonitorPoint};
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::ir::loader::{IrError, IrLoader};
+use rustortion_core::ir::thumbnail::{IrThumbnails, ThumbnailLookup};
 use rustortion_core::preset::InputFilterConfig;
 use rustortion_core::preset::stage_config::StageConfig;
 use rustortion_ui::backend::{Capabilities, ExternalEvent, ParamBackend};
 
 use crate::SharedState;
+use crate::bank;
 use crate::params::RustortionParams;
+
 pub struct PluginBackend {
     engine_handle: EngineHandle,
     params: Arc<RustortionParams>,
     context: Arc<dyn GuiContext>,
     ir_loader: Option<Arc<IrLoader>>,
+    /// Picker previews of the user's IR files; factory IRs have none.
+    ir_thumbnails: IrThumbnails,
     shared_state: Arc<SharedState>,
     capabilities: Capabilities,
     sample_rate: f32,
@@ -36,6 +46,7 @@ impl PluginBackend {
             params,
             context,
             ir_loader,
+            ir_thumbnails: IrThumbnails::spawn(),
             shared_state,
             capabilities: Capabilities::plugin(),
             sample_rate,
@@ -70,17 +81,56 @@ impl PluginBackend {
             self.context.raw_end_set_parameter(ptr);
         }
     }
+
+    /// The chain the slot parameters are currently bound to.
+    fn bound_stages(&self) -> Vec<StageConfig> {
+        self.shared_state.take_gui_stages().unwrap_or_default()
+    }
+
+    /// Bind the slot parameters to a rebuilt stage's new config.
+    fn record_stage(&self, stage_idx: usize, config: &StageConfig) {
+        // Record the new config first, so the audio thread sees the choice
+        // slot change below as already applied.
+        let mut stages = self.bound_stages();
+        if let Some(slot) = stages.get_mut(stage_idx) {
+            *slot = config.clone();
+            self.shared_state.store_gui_stages(&stages);
+        }
+        for (ptr, normalized) in bank::host_values(&self.params, &stages) {
+            self.notify_host_param_changed(ptr, normalized);
+        }
+    }
 }
 
 impl ParamBackend for PluginBackend {
     fn set_parameter(&self, stage_idx: usize, name: &'static str, value: f32) {
-        self.engine_handle.set_parameter(stage_idx, name, value);
+        // Bound parameters go through the host, so the move can be recorded as
+        // automation; the audio thread applies it (smoothed) from the slot.
+        let stages = self.bound_stages();
+        if let Some(param) = bank::float_param(&self.params, &stages, stage_idx, name) {
+            self.notify_host_param_changed(param.as_ptr(), param.preview_normalized(value));
+        } else {
+            self.engine_handle.set_parameter(stage_idx, name, value);
+        }
+    }
+
+    fn set_child_parameter(&self, stage_idx: usize, child: usize, name: &'static str, value: f32) {
+        self.engine_handle
+            .set_child_parameter(stage_idx, child, name, value);
     }
 
     fn rebuild_stage(&self, stage_idx: usize, config: &StageConfig) {
         let sr = self.effective_sample_rate();
         let runtime_stage = config.to_runtime(sr);
         self.engine_handle.replace_stage(stage_idx, runtime_stage);
+        self.record_stage(stage_idx, config);
+    }
+
+    fn swap_stage(&self, stage_idx: usize, config: &StageConfig) {
+        let sr = self.effective_sample_rate();
+        self.engine_handle
+            .crossfade_stage(stage_idx, StageCrossfade::new(config.to_runtime(sr), sr));
+        self.record_stage(stage_idx, config);
     }
 
     fn set_amp_chain(&self, stages: &[StageConfig]) {
@@ -97,8 +147,19 @@ impl ParamBackend for PluginBackend {
         self.engine_handle.set_amp_chain(chain);
     }
 
+    /// Always a full rebuild: the audio thread also reloads the chain on its
+    /// own (host preset changes, oversampling), so the engine's chain can't be
+    /// assumed to match what the editor last sent.
+    fn sync_amp_chain(&self, _previous: &[StageConfig], stages: &[StageConfig]) {
+        self.set_amp_chain(stages);
+    }
+
     fn set_bypass(&self, stage_idx: usize, bypassed: bool) {
         self.engine_handle.set_stage_bypassed(stage_idx, bypassed);
+        let stages = self.bound_stages();
+        if let Some(param) = bank::bypass_param_for(&self.params, &stages, stage_idx) {
+            self.notify_host_param_changed(param.as_ptr(), param.preview_normalized(bypassed));
+        }
     }
 
     fn add_stage(&self, idx: usize, config: &StageConfig) {
@@ -115,22 +176,44 @@ impl ParamBackend for PluginBackend {
         self.engine_handle.swap_stages(a, b);
     }
 
-    fn set_ir(&self, name: &str) {
+    fn set_monitor_point(&self, point: Option<MonitorPoint>) {
+        self.engine_handle.set_monitor_point(point);
+    }
+
+    fn set_standby(&self, on: bool) {
+        self.engine_handle.set_standby(on);
+    }
+
+    fn set_ir(&self, name: &str, alignment: IrAlignment) -> Result<(), IrError> {
         let Some(loader) = &self.ir_loader else {
-            return;
+            return Ok(());
         };
-        // Try embedded factory IR first
-        if let Some(bytes) = crate::factory::get_factory_ir(name) {
-            crate::ir_helper::load_and_set_ir_from_bytes(
-                &self.engine_handle,
-                loader,
-                name,
-                &bytes,
-                self.sample_rate,
-            );
-        } else {
-            // Fall back to filesystem (user-added IRs)
-            crate::ir_helper::load_and_set_ir(&self.engine_handle, loader, name, self.sample_rate);
+        // Loaded synchronously, so a bad file is reported right here.
+        crate::ir_helper::load_and_set_named_ir(
+            &self.engine_handle,
+            loader,
+            name,
+            alignment,
+            self.sample_rate,
+        )
+    }
+
+    fn ir_onset(&self, name: &str) -> Option<usize> {
+        crate::ir_helper::named_ir_onset(self.ir_loader.as_deref()?, name)
+    }
+
+    fn ir_energy(&self, name: &str) -> Option<f32> {
+        crate::ir_helper::named_ir_energy(self.ir_loader.as_deref()?, name, self.sample_rate)
+    }
+
+    fn ir_thumbnail(&self, name: &str) -> ThumbnailLookup {
+        match self
+            .ir_loader
+            .as_deref()
+            .and_then(|loader| loader.path_of(name))
+        {
+            Some(path) => self.ir_thumbnails.get(path),
+            None => ThumbnailLookup::Unavailable,
         }
     }
 
@@ -146,6 +229,11 @@ impl ParamBackend for PluginBackend {
         self.notify_host_param_changed(param.as_ptr(), param.preview_normalized(gain));
     }
 
+    fn set_ir_trim_db(&self, trim_db: f32) {
+        // Not the `ir_gain` parameter, so comparing doesn't touch automation.
+        self.engine_handle.set_ir_trim_db(trim_db);
+    }
+
     fn set_input_filter(&self, filter: &InputFilterConfig) {
         let hp: Option<Box<dyn Stage>> = if filter.hp_enabled {
             Some(Box::new(FilterStage::new(
@@ -184,6 +272,27 @@ impl ParamBackend for PluginBackend {
         self.notify_host_param_changed(param.as_ptr(), param.preview_normalized(semitones));
     }
 
+    fn set_denoiser(&self, config: &DenoiseConfig) {
+        self.engine_handle
+            .set_denoiser(config, self.sample_rate() as usize);
+    }
+
+    fn learn_noise(&self) -> NoiseLearn {
+        self.engine_handle.learn_noise(self.sample_rate() as usize)
+    }
+
+    fn set_ambience(&self, config: AmbienceConfig) {
+        self.engine_handle.set_ambience(config);
+    }
+
+    fn set_double_tracker(&self, config: DoubleTrackerConfig) {
+        self.engine_handle.set_double_tracker(config);
+    }
+
+    fn set_tilt_db(&self, tilt_db: f32) {
+        self.engine_handle.set_tilt_db(tilt_db);
+    }
+
     fn set_preset_index(&self, index: usize) {
         let param = &self.params.preset_idx;
         #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
@@ -239,6 +348,22 @@ impl ParamBackend for PluginBackend {
         names
     }
 
+    fn get_flagged_irs(&self) -> Vec<String> {
+        self.ir_loader
+            .as_ref()
+            .map(|loader| loader.flagged_irs().keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn engine_state(&self) -> Option<EngineState> {
+        // The audio thread glides the IR gain towards the host value a block
+        // at a time; mid-glide the engine lags the GUI without being wrong.
+        if self.params.ir_gain.smoothed.is_smoothing() {
+            return None;
+        }
+        self.engine_handle.state()
+    }
+
     fn get_peak_meter_info(&self) -> Option<ExternalEvent> {
         // Plugin mode does not poll peak meters from the GUI; the DAW
         // provides its own metering.
@@ -256,6 +381,33 @@ impl ParamBackend for PluginBackend {
         Ok(loader.available_names().len())
     }
 
+    fn captures_dir(&self) -> Option<std::path::PathBuf> {
+        Some(crate::user_captures_dir())
+    }
+
+    fn rescan_captures(&self) -> Result<usize, String> {
+        let dir = crate::user_captures_dir();
+        let loader =
+            rustortion_core::capture::CaptureLoader::new(&dir).map_err(|e| e.to_string())?;
+        rustortion_core::capture::registry::init_from_loader(&loader);
+        Ok(loader.available_names().len())
+    }
+
+    fn sync_host_params(&self, stages: &mut [StageConfig]) -> bool {
+        if !self
+            .shared_state
+            .host_changed
+            .swap(false, std::sync::atomic::Ordering::Acquire)
+        {
+            return false;
+        }
+        let changed = bank::apply_host_values(&self.params, stages);
+        if changed {
+            self.shared_state.store_gui_stages(stages);
+        }
+        changed
+    }
+
     fn persist_chain_state(&self, stages: &[StageConfig]) {
         // Store in SharedState for editor close/reopen within same session
         self.shared_state.store_gui_stages(stages);
@@ -263,6 +415,14 @@ impl ParamBackend for PluginBackend {
         if let Ok(mut cs) = self.params.chain_state.lock() {
             *cs = Some(stages.to_vec());
         }
+        // Bring the slot params in line with the chain; this is also where a
+        // freshly loaded preset fills the bank.
+        for (ptr, normalized) in bank::host_values(&self.params, stages) {
+            self.notify_host_param_changed(ptr, normalized);
+        }
+        self.params
+            .slot_bank_synced
+            .store(true, std::sync::atomic::Ordering::Relaxed);
         // Touch preset_idx with its current value to notify the host that
         // state changed. #[persist] fields are serialized passively and
         // don't mark the session dirty on their own.
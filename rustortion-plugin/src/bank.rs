@@ -0,0 +1,726 @@
+//! Maps the live stage chain onto the fixed per-type slot parameters in
+//! [`RustortionParams`], so hosts can automate stage parameters.
+//!
+//! The n-th stage of a given type in the chain is bound to slot n of that
+//! type's array (e.g. the second preamp drives `preamp[1]`); stages past
+//! [`SLOTS`] of one type stay editable in the GUI but aren't exposed. Slot
+//! values flow both ways:
+//!
+//! - the editor pushes the chain's values into the bound slots whenever the
+//!   chain is persisted ([`host_values`]), which is also how a preset saved by
+//!   the standalone app lands in the bank;
+//! - the audio thread polls the bound slots ([`Binding::poll`]) and forwards
+//!   host-side changes to the running stages, and the editor pulls them back
+//!   into its stage configs ([`apply_host_values`]).
+
+use nih_plug::prelude::{BoolParam, FloatParam, IntParam, Param, ParamPtr};
+use rustortion_core::amp::stages::clipper::ClipperType;
+use rustortion_core::amp::stages::poweramp::PowerAmpType;
+use rustortion_core::amp::stages::tonestack::ToneStackModel;
+use rustortion_core::amp::stages::wah::WahMode;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+use crate::params::RustortionParams;
+
+/// Slots per stage type; the length of each array in [`RustortionParams`].
+pub const SLOTS: usize = 8;
+
+/// Normalized values closer than this are the same host value; plain values
+/// don't survive the trip through the host's normalized range bit-exact.
+const NORMALIZED_TOLERANCE: f32 = 1e-5;
+
+/// A continuous stage parameter and where it lives in the bank and config.
+pub struct FloatSlot {
+    /// Name the runtime stage knows it by ([`Stage::set_parameter`]).
+    ///
+    /// [`Stage::set_parameter`]: rustortion_core::amp::stages::Stage::set_parameter
+    pub name: &'static str,
+    param: fn(&RustortionParams, usize) -> &FloatParam,
+    get: fn(&StageConfig) -> Option<f32>,
+    set: fn(&mut StageConfig, f32),
+}
+
+/// A stage's choice parameter (clipper, tone stack model, ...). Changing it
+/// means rebuilding the stage, so it can't be applied on the audio thread.
+pub struct EnumSlot {
+    param: fn(&RustortionParams, usize) -> &IntParam,
+    get: fn(&StageConfig) -> Option<i32>,
+    set: fn(&mut StageConfig, i32),
+}
+
+macro_rules! float_slots {
+    ($variant:ident, $array:ident, [$($name:literal => $param:ident: $field:ident $([$i:literal])?),+ $(,)?]) => {
+        &[$(FloatSlot {
+            name: $name,
+            param: |params, slot| &params.$array[slot].$param,
+            get: |cfg| match cfg {
+                StageConfig::$variant(c) => Some(c.$field $([$i])?),
+                _ => None,
+            },
+            set: |cfg, value| {
+                if let StageConfig::$variant(c) = cfg {
+                    c.$field $([$i])? = value;
+                }
+            },
+        }),+]
+    };
+}
+
+macro_rules! enum_slot {
+    ($variant:ident, $array:ident, $param:ident, $field:ident, $choices:expr) => {
+        EnumSlot {
+            param: |params, slot| &params.$array[slot].$param,
+            get: |cfg| match cfg {
+                StageConfig::$variant(c) => choice_index(&$choices, c.$field),
+                _ => None,
+            },
+            set: |cfg, index| {
+                if let StageConfig::$variant(c) = cfg
+                    && let Some(choice) = usize::try_from(index).ok().and_then(|i| $choices.get(i))
+                {
+                    c.$field = *choice;
+                }
+            },
+        }
+    };
+}
+
+// Choice order matches the value-to-string tables in `params.rs`.
+const CLIPPER_TYPES: [ClipperType; 6] = [
+    ClipperType::Soft,
+    ClipperType::Medium,
+    ClipperType::Hard,
+    ClipperType::Asymmetric,
+    ClipperType::ClassA,
+    ClipperType::Triode,
+];
+const TONESTACK_MODELS: [ToneStackModel; 4] = [
+    ToneStackModel::Modern,
+    ToneStackModel::British,
+    ToneStackModel::American,
+    ToneStackModel::Flat,
+];
+const POWERAMP_TYPES: [PowerAmpType; 3] = [
+    PowerAmpType::ClassA,
+    PowerAmpType::ClassAB,
+    PowerAmpType::ClassB,
+];
+const WAH_MODES: [WahMode; 3] = [WahMode::Manual, WahMode::Auto, WahMode::Fixed];
+
+fn choice_index<T: PartialEq>(choices: &[T], value: T) -> Option<i32> {
+    choices
+        .iter()
+        .position(|c| *c == value)
+        .and_then(|i| i32::try_from(i).ok())
+}
+
+const PREAMP: &[FloatSlot] = float_slots!(Preamp, preamp, [
+    "gain" => gain: gain,
+    "bias" => bias: bias,
+]);
+const COMPRESSOR: &[FloatSlot] = float_slots!(Compressor, compressor, [
+    "threshold" => threshold_db: threshold_db,
+    "ratio" => ratio: ratio,
+    "attack" => attack_ms: attack_ms,
+    "release" => release_ms: release_ms,
+    "makeup" => makeup_db: makeup_db,
+]);
+const TONESTACK: &[FloatSlot] = float_slots!(ToneStack, tonestack, [
+    "bass" => bass: bass,
+    "mid" => mid: mid,
+    "treble" => treble: treble,
+    "presence" => presence: presence,
+]);
+const POWERAMP: &[FloatSlot] = float_slots!(PowerAmp, poweramp, [
+    "drive" => drive: drive,
+    "sag" => sag: sag,
+    "sag_release" => sag_release: sag_release,
+]);
+const LEVEL: &[FloatSlot] = float_slots!(Level, level, [
+    "gain" => gain: gain,
+]);
+const NOISE_GATE: &[FloatSlot] = float_slots!(NoiseGate, noise_gate, [
+    "threshold" => threshold_db: threshold_db,
+    "ratio" => ratio: ratio,
+    "attack" => attack_ms: attack_ms,
+    "hold" => hold_ms: hold_ms,
+    "release" => release_ms: release_ms,
+]);
+const MULTIBAND_SATURATOR: &[FloatSlot] = float_slots!(MultibandSaturator, multiband_saturator, [
+    "low_drive" => low_drive: low_drive,
+    "mid_drive" => mid_drive: mid_drive,
+    "high_drive" => high_drive: high_drive,
+    "low_level" => low_level: low_level,
+    "mid_level" => mid_level: mid_level,
+    "high_level" => high_level: high_level,
+    "low_freq" => low_freq: low_freq,
+    "high_freq" => high_freq: high_freq,
+]);
+const NAM: &[FloatSlot] = float_slots!(Nam, nam, [
+    "input_gain_db" => input_gain_db: input_gain_db,
+    "output_gain_db" => output_gain_db: output_gain_db,
+    "mix" => mix: mix,
+]);
+const CAPTURE: &[FloatSlot] = float_slots!(Capture, capture, [
+    "input_gain_db" => input_gain_db: input_gain_db,
+    "output_gain_db" => output_gain_db: output_gain_db,
+    "mix" => mix: mix,
+]);
+const DELAY: &[FloatSlot] = float_slots!(Delay, delay, [
+    "delay_time" => delay_ms: delay_ms,
+    "feedback" => feedback: feedback,
+    "mix" => mix: mix,
+]);
+const REVERB: &[FloatSlot] = float_slots!(Reverb, reverb, [
+    "room_size" => room_size: room_size,
+    "damping" => damping: damping,
+    "mix" => mix: mix,
+]);
+const EQ: &[FloatSlot] = float_slots!(Eq, eq, [
+    "band_0" => band_0: gains[0],
+    "band_1" => band_1: gains[1],
+    "band_2" => band_2: gains[2],
+    "band_3" => band_3: gains[3],
+    "band_4" => band_4: gains[4],
+    "band_5" => band_5: gains[5],
+    "band_6" => band_6: gains[6],
+    "band_7" => band_7: gains[7],
+    "band_8" => band_8: gains[8],
+    "band_9" => band_9: gains[9],
+    "band_10" => band_10: gains[10],
+    "band_11" => band_11: gains[11],
+    "band_12" => band_12: gains[12],
+    "band_13" => band_13: gains[13],
+    "band_14" => band_14: gains[14],
+    "band_15" => band_15: gains[15],
+]);
+const TREMOLO: &[FloatSlot] = float_slots!(Tremolo, tremolo, [
+    "rate" => rate: rate_hz,
+    "depth" => depth: depth,
+    "shape" => shape: shape,
+]);
+const WAH: &[FloatSlot] = float_slots!(Wah, wah, [
+    "position" => position: position,
+    "q" => q: q,
+    "sensitivity" => sensitivity: sensitivity,
+    "attack" => attack_ms: attack_ms,
+    "release" => release_ms: release_ms,
+]);
+
+const PREAMP_CLIPPER: EnumSlot =
+    enum_slot!(Preamp, preamp, clipper_type, clipper_type, CLIPPER_TYPES);
+const TONESTACK_MODEL: EnumSlot = enum_slot!(ToneStack, tonestack, model, model, TONESTACK_MODELS);
+const POWERAMP_TYPE: EnumSlot = enum_slot!(PowerAmp, poweramp, amp_type, amp_type, POWERAMP_TYPES);
+const WAH_MODE: EnumSlot = enum_slot!(Wah, wah, mode, mode, WAH_MODES);
+
+/// The automatable continuous parameters of a stage type.
+pub const fn float_slots(stage_type: StageType) -> &'static [FloatSlot] {
+    match stage_type {
+        StageType::Preamp => PREAMP,
+        StageType::Compressor => COMPRESSOR,
+        StageType::ToneStack => TONESTACK,
+        StageType::PowerAmp => POWERAMP,
+        StageType::Level => LEVEL,
+        StageType::NoiseGate => NOISE_GATE,
+        StageType::MultibandSaturator => MULTIBAND_SATURATOR,
+        StageType::Nam => NAM,
+        StageType::Capture => CAPTURE,
+        StageType::Delay => DELAY,
+        StageType::Reverb => REVERB,
+        StageType::Eq => EQ,
+        StageType::Tremolo => TREMOLO,
+        StageType::Wah => WAH,
+        StageType::Group => &[],
+    }
+}
+
+/// The choice parameter of a stage type, if it has one.
+pub const fn enum_slot(stage_type: StageType) -> Option<&'static EnumSlot> {
+    match stage_type {
+        StageType::Preamp => Some(&PREAMP_CLIPPER),
+        StageType::ToneStack => Some(&TONESTACK_MODEL),
+        StageType::PowerAmp => Some(&POWERAMP_TYPE),
+        StageType::Wah => Some(&WAH_MODE),
+        _ => None,
+    }
+}
+
+/// Whether stages of this type are bound to slots. A group's children are
+/// only reachable through the group, so groups get none.
+const fn has_slots(stage_type: StageType) -> bool {
+    !matches!(stage_type, StageType::Group)
+}
+
+fn bypass_param(
+    params: &RustortionParams,
+    stage_type: StageType,
+    slot: usize,
+) -> Option<&BoolParam> {
+    Some(match stage_type {
+        StageType::Preamp => &params.preamp[slot].bypassed,
+        StageType::Compressor => &params.compressor[slot].bypassed,
+        StageType::ToneStack => &params.tonestack[slot].bypassed,
+        StageType::PowerAmp => &params.poweramp[slot].bypassed,
+        StageType::Level => &params.level[slot].bypassed,
+        StageType::NoiseGate => &params.noise_gate[slot].bypassed,
+        StageType::MultibandSaturator => &params.multiband_saturator[slot].bypassed,
+        StageType::Nam => &params.nam[slot].bypassed,
+        StageType::Capture => &params.capture[slot].bypassed,
+        StageType::Delay => &params.delay[slot].bypassed,
+        StageType::Reverb => &params.reverb[slot].bypassed,
+        StageType::Eq => &params.eq[slot].bypassed,
+        StageType::Tremolo => &params.tremolo[slot].bypassed,
+        StageType::Wah => &params.wah[slot].bypassed,
+        StageType::Group => return None,
+    })
+}
+
+/// Set a stage's choice from its slot index. Returns whether it changed.
+pub fn set_enum(cfg: &mut StageConfig, index: i32) -> bool {
+    let Some(slot) = enum_slot(cfg.stage_type()) else {
+        return false;
+    };
+    if (slot.get)(cfg) == Some(index) {
+        return false;
+    }
+    (slot.set)(cfg, index);
+    true
+}
+
+/// The slot each stage is bound to: its position among the stages of the
+/// same type, or `None` once that type's slots run out.
+pub fn slot_assignments(stages: &[StageConfig]) -> Vec<Option<usize>> {
+    let mut seen = [0usize; StageType::ALL.len()];
+    stages
+        .iter()
+        .map(|cfg| {
+            let stage_type = cfg.stage_type();
+            let count = &mut seen[type_index(stage_type)];
+            let slot = *count;
+            *count += 1;
+            (has_slots(stage_type) && slot < SLOTS).then_some(slot)
+        })
+        .collect()
+}
+
+fn type_index(stage_type: StageType) -> usize {
+    StageType::ALL
+        .iter()
+        .position(|t| *t == stage_type)
+        .unwrap_or_default()
+}
+
+/// The slot parameter bound to `name` on stage `stage_idx`, if any.
+pub fn float_param<'a>(
+    params: &'a RustortionParams,
+    stages: &[StageConfig],
+    stage_idx: usize,
+    name: &str,
+) -> Option<&'a FloatParam> {
+    let cfg = stages.get(stage_idx)?;
+    let slot = slot_assignments(stages).get(stage_idx).copied()??;
+    float_slots(cfg.stage_type())
+        .iter()
+        .find(|f| f.name == name)
+        .map(|f| (f.param)(params, slot))
+}
+
+/// The bypass parameter bound to stage `stage_idx`, if any.
+pub fn bypass_param_for<'a>(
+    params: &'a RustortionParams,
+    stages: &[StageConfig],
+    stage_idx: usize,
+) -> Option<&'a BoolParam> {
+    let cfg = stages.get(stage_idx)?;
+    let slot = slot_assignments(stages).get(stage_idx).copied()??;
+    bypass_param(params, cfg.stage_type(), slot)
+}
+
+/// `plain` as a normalized value to send to the host, unless the parameter
+/// already holds it.
+fn pending<P: Param>(param: &P, plain: P::Plain) -> Option<(ParamPtr, f32)> {
+    let normalized = param.preview_normalized(plain);
+    ((normalized - param.unmodulated_normalized_value()).abs() > NORMALIZED_TOLERANCE)
+        .then(|| (param.as_ptr(), normalized))
+}
+
+/// A bound slot parameter with the value a stage config gives it.
+enum Bound<'a> {
+    Float(&'a FloatParam, f32),
+    Choice(&'a IntParam, i32),
+    Bypass(&'a BoolParam, bool),
+}
+
+/// Walk every bound slot parameter with the value `stages` gives it.
+fn for_each_bound(
+    params: &RustortionParams,
+    stages: &[StageConfig],
+    mut visit: impl FnMut(Bound<'_>),
+) {
+    for (cfg, slot) in stages.iter().zip(slot_assignments(stages)) {
+        let Some(slot) = slot else { continue };
+        let stage_type = cfg.stage_type();
+        for f in float_slots(stage_type) {
+            if let Some(value) = (f.get)(cfg) {
+                visit(Bound::Float((f.param)(params, slot), value));
+            }
+        }
+        if let Some(e) = enum_slot(stage_type)
+            && let Some(index) = (e.get)(cfg)
+        {
+            visit(Bound::Choice((e.param)(params, slot), index));
+        }
+        if let Some(param) = bypass_param(params, stage_type, slot) {
+            visit(Bound::Bypass(param, cfg.bypassed()));
+        }
+    }
+}
+
+/// The slot values `stages` needs the host to hold, as normalized values for
+/// the parameters that don't already hold them.
+pub fn host_values(params: &RustortionParams, stages: &[StageConfig]) -> Vec<(ParamPtr, f32)> {
+    let mut out = Vec::new();
+    for_each_bound(params, stages, |bound| {
+        out.extend(match bound {
+            Bound::Float(p, v) => pending(p, v),
+            Bound::Choice(p, v) => pending(p, v),
+            Bound::Bypass(p, v) => pending(p, v),
+        });
+    });
+    out
+}
+
+/// Copy the host's slot values into `stages`. Returns whether any changed.
+pub fn apply_host_values(params: &RustortionParams, stages: &mut [StageConfig]) -> bool {
+    let mut changed = false;
+    let slots = slot_assignments(stages);
+    for (cfg, slot) in stages.iter_mut().zip(slots) {
+        let Some(slot) = slot else { continue };
+        let stage_type = cfg.stage_type();
+        for f in float_slots(stage_type) {
+            let param = (f.param)(params, slot);
+            if (f.get)(cfg).is_some_and(|v| pending(param, v).is_some()) {
+                (f.set)(cfg, param.value());
+                changed = true;
+            }
+        }
+        if let Some(e) = enum_slot(stage_type) {
+            changed |= set_enum(cfg, (e.param)(params, slot).value());
+        }
+        if let Some(param) = bypass_param(params, stage_type, slot)
+            && cfg.bypassed() != param.value()
+        {
+            cfg.set_bypassed(param.value());
+            changed = true;
+        }
+    }
+    changed
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Float(&'static FloatSlot),
+    Enum(&'static EnumSlot),
+    Bypass,
+}
+
+/// A change the host made to a bound slot, for the audio thread to apply.
+pub enum HostChange {
+    Parameter {
+        stage_idx: usize,
+        name: &'static str,
+        value: f32,
+    },
+    Bypass {
+        stage_idx: usize,
+        bypassed: bool,
+    },
+    /// Needs a stage rebuild, which has to happen off the audio thread.
+    Choice {
+        stage_idx: usize,
+        index: i32,
+    },
+}
+
+/// One bound slot parameter, polled by the audio thread.
+pub struct Binding {
+    stage_idx: usize,
+    stage_type: StageType,
+    slot: usize,
+    target: Target,
+    /// Last value seen, as a plain value (choices and switches as numbers).
+    last: f32,
+}
+
+impl Binding {
+    fn current(&self, params: &RustortionParams) -> f32 {
+        match self.target {
+            Target::Float(f) => (f.param)(params, self.slot).value(),
+            #[allow(clippy::cast_precision_loss)]
+            Target::Enum(e) => (e.param)(params, self.slot).value() as f32,
+            Target::Bypass => f32::from(u8::from(
+                bypass_param(params, self.stage_type, self.slot).is_some_and(BoolParam::value),
+            )),
+        }
+    }
+
+    /// Take the current value as the baseline, so only later host changes are
+    /// applied. The chain was just built from configs that already hold it,
+    /// or from a preset the host hasn't been told about yet.
+    pub fn reset(&mut self, params: &RustortionParams) {
+        self.last = self.current(params);
+        if let Target::Float(f) = self.target {
+            (f.param)(params, self.slot).smoothed.reset(self.last);
+        }
+    }
+
+    /// Advance by one block of `samples` and report a change since the last
+    /// poll. Continuous parameters report every step of their ramp.
+    pub fn poll(&mut self, params: &RustortionParams, samples: u32) -> Option<HostChange> {
+        let value = match self.target {
+            Target::Float(f) => (f.param)(params, self.slot).smoothed.next_step(samples),
+            _ => self.current(params),
+        };
+        if (value - self.last).abs() <= f32::EPSILON {
+            return None;
+        }
+        self.last = value;
+        let stage_idx = self.stage_idx;
+        Some(match self.target {
+            Target::Float(f) => HostChange::Parameter {
+                stage_idx,
+                name: f.name,
+                value,
+            },
+            #[allow(clippy::cast_possible_truncation)]
+            Target::Enum(_) => HostChange::Choice {
+                stage_idx,
+                index: value as i32,
+            },
+            Target::Bypass => HostChange::Bypass {
+                stage_idx,
+                bypassed: value > 0.5,
+            },
+        })
+    }
+}
+
+/// Every slot parameter the chain `stages` binds.
+pub fn bind(stages: &[StageConfig]) -> Vec<Binding> {
+    let mut bindings = Vec::new();
+    for (stage_idx, (cfg, slot)) in stages.iter().zip(slot_assignments(stages)).enumerate() {
+        let Some(slot) = slot else { continue };
+        let stage_type = cfg.stage_type();
+        let targets = float_slots(stage_type)
+            .iter()
+            .map(Target::Float)
+            .chain(enum_slot(stage_type).map(Target::Enum))
+            .chain(std::iter::once(Target::Bypass));
+        bindings.extend(targets.map(|target| Binding {
+            stage_idx,
+            stage_type,
+            slot,
+            target,
+            last: 0.0,
+        }));
+    }
+    bindings
+}
+
+/// Hands new bindings to the audio thread without it allocating or freeing:
+/// the replaced list is parked here and dropped by the next publisher.
+#[derive(Default)]
+pub struct BindingQueue {
+    next: Option<Vec<Binding>>,
+    retired: Vec<Binding>,
+}
+
+impl BindingQueue {
+    pub fn publish(&mut self, bindings: Vec<Binding>) {
+        self.retired = Vec::new();
+        self.next = Some(bindings);
+    }
+
+    /// Swap pending bindings into `current`. Returns whether there were any.
+    pub fn take_into(&mut self, current: &mut Vec<Binding>) -> bool {
+        let Some(mut bindings) = self.next.take() else {
+            return false;
+        };
+        std::mem::swap(current, &mut bindings);
+        // `bindings` is now the old list; park it and drop the empty one.
+        std::mem::swap(&mut self.retired, &mut bindings);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nih_plug::prelude::Params;
+    use rustortion_core::amp::stages::Stage;
+    use std::collections::HashSet;
+
+    const SAMPLE_RATE: f32 = 48_000.0;
+    const TOL: f32 = 1e-3;
+
+    fn all_stages() -> Vec<StageConfig> {
+        StageType::ALL
+            .iter()
+            .map(|&t| StageConfig::from(t))
+            .collect()
+    }
+
+    #[test]
+    fn nth_stage_of_a_type_takes_the_nth_slot() {
+        let mut stages = vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::ToneStack),
+            StageConfig::from(StageType::Preamp),
+        ];
+        assert_eq!(slot_assignments(&stages), vec![Some(0), Some(0), Some(1)]);
+
+        stages.extend((0..SLOTS).map(|_| StageConfig::from(StageType::Level)));
+        stages.push(StageConfig::from(StageType::Level));
+        let slots = slot_assignments(&stages);
+        assert_eq!(slots[3 + SLOTS - 1], Some(SLOTS - 1));
+        assert_eq!(slots[3 + SLOTS], None);
+    }
+
+    #[test]
+    fn every_stage_type_round_trips_through_its_slots() {
+        let params = RustortionParams::default();
+        for &stage_type in StageType::ALL.iter().filter(|&&t| has_slots(t)) {
+            let default = StageConfig::from(stage_type);
+            let slots = float_slots(stage_type);
+            assert!(!slots.is_empty(), "{stage_type} has no slot parameters");
+
+            for f in slots {
+                let param = (f.param)(&params, 0);
+                // Move every value off its default so a swapped field shows up.
+                let value = param.preview_plain(0.37);
+                let mut cfg = default.clone();
+                (f.set)(&mut cfg, value);
+                assert!(
+                    (f.get)(&cfg).is_some_and(|v| (v - value).abs() < TOL),
+                    "{stage_type} {}",
+                    f.name
+                );
+
+                // Config -> host -> config.
+                let back = param.preview_plain(param.preview_normalized(value));
+                assert!((back - value).abs() < TOL, "{stage_type} {}", f.name);
+
+                // The runtime stage knows the parameter by this name.
+                let mut stage: Box<dyn Stage> = cfg.to_runtime(SAMPLE_RATE);
+                stage
+                    .set_parameter(f.name, value)
+                    .unwrap_or_else(|e| panic!("{stage_type} {}: {e}", f.name));
+                let got = stage.get_parameter(f.name).unwrap();
+                assert!((got - value).abs() < TOL, "{stage_type} {}", f.name);
+
+                // Default config values fit the host range.
+                let default_value = (f.get)(&default).unwrap();
+                let clamped = param.preview_plain(param.preview_normalized(default_value));
+                assert!(
+                    (clamped - default_value).abs() < TOL,
+                    "{stage_type} {} default {default_value} outside host range",
+                    f.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn choices_round_trip() {
+        let params = RustortionParams::default();
+        for &stage_type in StageType::ALL {
+            let Some(e) = enum_slot(stage_type) else {
+                continue;
+            };
+            let param = (e.param)(&params, 0);
+            let max = param.preview_plain(1.0);
+            let mut cfg = StageConfig::from(stage_type);
+            for index in 0..=max {
+                (e.set)(&mut cfg, index);
+                assert_eq!((e.get)(&cfg), Some(index), "{stage_type} choice {index}");
+            }
+            assert!(!set_enum(&mut cfg, max));
+            assert!(set_enum(&mut cfg, 0));
+        }
+    }
+
+    #[test]
+    fn bindings_cover_each_bound_stage() {
+        let stages = all_stages();
+        let bindings = bind(&stages);
+        for (idx, cfg) in stages.iter().enumerate() {
+            let expected = if has_slots(cfg.stage_type()) {
+                float_slots(cfg.stage_type()).len()
+                    + usize::from(enum_slot(cfg.stage_type()).is_some())
+                    + 1
+            } else {
+                0
+            };
+            let count = bindings.iter().filter(|b| b.stage_idx == idx).count();
+            assert_eq!(count, expected, "{}", cfg.stage_type());
+        }
+    }
+
+    #[test]
+    fn fresh_bindings_report_nothing() {
+        let params = RustortionParams::default();
+        let stages = all_stages();
+        let mut bindings = bind(&stages);
+        for binding in &mut bindings {
+            binding.reset(&params);
+            assert!(binding.poll(&params, 64).is_none());
+        }
+    }
+
+    #[test]
+    fn slot_lookups_follow_the_chain() {
+        let params = RustortionParams::default();
+        let stages = vec![
+            StageConfig::from(StageType::Level),
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::Preamp),
+        ];
+        let gain = float_param(&params, &stages, 2, "gain").unwrap();
+        assert!(std::ptr::eq(gain, &params.preamp[1].gain));
+        assert!(float_param(&params, &stages, 2, "nonexistent").is_none());
+        assert!(std::ptr::eq(
+            bypass_param_for(&params, &stages, 0).unwrap(),
+            &params.level[0].bypassed
+        ));
+        assert!(float_param(&params, &stages, 3, "gain").is_none());
+
+        let grouped = vec![StageConfig::from(StageType::Group)];
+        assert_eq!(slot_assignments(&grouped), vec![None]);
+        assert!(bypass_param_for(&params, &grouped, 0).is_none());
+    }
+
+    #[test]
+    fn host_names_are_unique() {
+        let params = RustortionParams::default();
+        let mut names = HashSet::new();
+        for (id, ptr, _group) in params.param_map() {
+            // SAFETY: `ptr` points into `params`, which outlives this loop.
+            let name = unsafe { ptr.name() };
+            assert!(names.insert(name.to_owned()), "{id}: duplicate name {name}");
+        }
+    }
+
+    #[test]
+    fn queue_hands_over_the_latest_bindings() {
+        let stages = all_stages();
+        let mut queue = BindingQueue::default();
+        let mut current = Vec::new();
+        assert!(!queue.take_into(&mut current));
+
+        queue.publish(bind(&stages[..1]));
+        queue.publish(bind(&stages));
+        assert!(queue.take_into(&mut current));
+        assert_eq!(current.len(), bind(&stages).len());
+        assert!(!queue.take_into(&mut current));
+    }
+}
@@ -9,13 +9,21 @@ use crate::params::RustortionParams;
 
 use rustortion_ui::app::{SharedApp, UpdateResult};
 use rustortion_ui::backend::ParamBackend;
+use rustortion_ui::components::ambience_control::AmbienceControl;
+use rustortion_ui::components::denoiser_control::DenoiserControl;
+use rustortion_ui::components::dialogs::macro_editor::MacroEditorDialog;
+use rustortion_ui::components::double_tracker_control::DoubleTrackerControl;
 use rustortion_ui::components::ir_cabinet_control::IrCabinetControl;
 use rustortion_ui::components::peak_meter::PeakMeterDisplay;
 use rustortion_ui::components::pitch_shift_control::PitchShiftControl;
+use rustortion_ui::components::recording_status::RecordingStatusDisplay;
+use rustortion_ui::components::stage_levels::StageLevels;
+use rustortion_ui::components::tilt_control::TiltControl;
 use rustortion_ui::handlers::hotkey::HotkeyHandler;
 use rustortion_ui::handlers::preset::PresetHandler;
 use rustortion_ui::hotkey::HotkeySettings;
 use rustortion_ui::messages::Message;
+use rustortion_ui::randomize::{RandomizeIntensity, XorShift};
 use rustortion_ui::stages::StageType;
 use rustortion_ui::tabs::Tab;
 
@@ -168,11 +176,12 @@ impl iced_baseview::Application for PluginApp {
 
         let available_irs = backend.get_available_irs();
 
-        let factory_presets = crate::factory::load_factory_presets();
-        let mut preset_handler = PresetHandler::new_from_presets(factory_presets);
+        let mut preset_handler = PresetHandler::new_from_presets(crate::factory::load_presets());
 
         let mut ir_cabinet = IrCabinetControl::default();
         ir_cabinet.set_available_irs(available_irs);
+        ir_cabinet.set_flagged_irs(backend.get_flagged_irs());
+        ir_cabinet.set_ir_metadata(backend.get_available_irs_with_metadata());
 
         // Check if we have previously stored stages (from a prior editor session
         // or from DAW-persisted chain state). If so, restore them directly instead
@@ -209,13 +218,38 @@ impl iced_baseview::Application for PluginApp {
             active_tab: Tab::Amp,
             selected_stage_type: StageType::ALL.first().copied().unwrap_or(StageType::Preamp),
             ir_cabinet_control: ir_cabinet,
+            ambience_control: AmbienceControl::default(),
+            double_tracker_control: DoubleTrackerControl::default(),
             pitch_shift_control: PitchShiftControl::new(0),
+            tilt_control: TiltControl::default(),
             preset_handler,
             peak_meter_display: PeakMeterDisplay::default(),
             hotkey_handler: HotkeyHandler::new(HotkeySettings::default()),
             input_filter_config: rustortion_core::preset::InputFilterConfig::default(),
+            denoiser_control: DenoiserControl::default(),
             oversampling_factor,
             is_recording: false,
+            recording_status: RecordingStatusDisplay::default(),
+            stage_drag: None,
+            focused_stage: None,
+            keyboard_focus: None,
+            missing_ir: None,
+            ir_error: None,
+            lint_enabled: true,
+            keep_locked_stages: false,
+            quantize_chain_changes: false,
+            pending_preset: None,
+            pending_preset_shown: false,
+            lint_warnings: Vec::new(),
+            randomize_intensity: RandomizeIntensity::default(),
+            rng: XorShift::from_clock(),
+            undo_stack: Vec::new(),
+            solo: None,
+            shift_held: false,
+            standby: false,
+            macros: Vec::new(),
+            macro_editor: MacroEditorDialog::new(),
+            stage_levels: StageLevels::new(),
         };
 
         // If we have stored stages, restore them directly.
@@ -248,7 +282,9 @@ impl iced_baseview::Application for PluginApp {
     fn view(
         &self,
     ) -> iced_baseview::Element<'_, Self::Message, Self::Theme, iced_baseview::Renderer> {
-        self.shared.view()
+        self.shared
+            .macro_editor_view()
+            .unwrap_or_else(|| self.shared.view())
     }
 
     fn theme(&self) -> Self::Theme {
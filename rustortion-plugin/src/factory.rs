@@ -1,5 +1,5 @@
 use rust_embed::RustEmbed;
-use rustortion_core::preset::Preset;
+use rustortion_core::preset::{Preset, parse_preset};
 
 #[derive(RustEmbed)]
 #[folder = "../presets/"]
@@ -17,7 +17,7 @@ pub fn load_factory_presets() -> Vec<Preset> {
         .filter_map(|filename| {
             let file = FactoryPresets::get(&filename)?;
             let json = std::str::from_utf8(file.data.as_ref()).ok()?;
-            let preset: Preset = serde_json::from_str(json)
+            let preset = parse_preset(json)
                 .inspect_err(|e| log::warn!("Failed to parse factory preset {filename}: {e}"))
                 .ok()?;
             Some(preset)
@@ -27,6 +27,39 @@ pub fn load_factory_presets() -> Vec<Preset> {
     presets
 }
 
+/// Factory presets followed by the user's own from [`crate::user_presets_dir`],
+/// the directory the standalone app can share.
+///
+/// User presets go last, so the host's stored preset index keeps pointing at
+/// the same factory preset when user presets come and go.
+pub fn load_presets() -> Vec<Preset> {
+    let dir = crate::user_presets_dir();
+    let user = if dir.is_dir() {
+        rustortion_core::preset::Manager::new(&dir)
+            .map(|m| m.get_presets().to_vec())
+            .inspect_err(|e| log::warn!("Failed to load user presets from {}: {e}", dir.display()))
+            .unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    merge_user_presets(load_factory_presets(), user)
+}
+
+/// Append `user` presets to `factory`, skipping any that reuse a factory
+/// preset's name.
+fn merge_user_presets(mut presets: Vec<Preset>, mut user: Vec<Preset>) -> Vec<Preset> {
+    user.retain(|u| {
+        let clash = presets.iter().any(|p| p.name == u.name);
+        if clash {
+            log::warn!("User preset '{}' shadows a factory preset; skipped", u.name);
+        }
+        !clash
+    });
+    user.sort_by(|a, b| a.name.cmp(&b.name));
+    presets.extend(user);
+    presets
+}
+
 /// List all embedded IR names (relative paths with `/` separators).
 pub fn factory_ir_names() -> Vec<String> {
     let mut names: Vec<String> = FactoryIrs::iter().map(|f| f.to_string()).collect();
@@ -38,3 +71,25 @@ pub fn factory_ir_names() -> Vec<String> {
 pub fn get_factory_ir(name: &str) -> Option<Vec<u8>> {
     FactoryIrs::get(name).map(|f| f.data.to_vec())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            ..Preset::default()
+        }
+    }
+
+    #[test]
+    fn user_presets_follow_factory_ones_without_shadowing() {
+        let merged = merge_user_presets(
+            vec![preset("Clean"), preset("Lead")],
+            vec![preset("Zed"), preset("Lead"), preset("Mine")],
+        );
+        let names: Vec<&str> = merged.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Clean", "Lead", "Mine", "Zed"]);
+    }
+}
@@ -1,41 +1,81 @@
 use rustortion_core::audio::engine::{EngineHandle, PreparedIr};
+use rustortion_core::ir::align::{self, IrAlignment, ONSET_THRESHOLD_DB};
+use rustortion_core::ir::compare::ir_energy;
 use rustortion_core::ir::convolver::Convolver;
-use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::ir::loader::{IrError, IrLoader};
 
-/// Load an IR by name from the filesystem, truncate to 35ms, and swap into engine.
-pub fn load_and_set_ir(handle: &EngineHandle, loader: &IrLoader, name: &str, sample_rate: f32) {
-    match loader.load_by_name(name) {
-        Ok(ir_samples) => set_ir_samples(handle, name, &ir_samples, sample_rate),
-        Err(e) => log::error!("Failed to load IR '{name}': {e}"),
-    }
-}
+use crate::factory;
 
-/// Load an IR from raw WAV bytes, truncate to 35ms, and swap into engine.
-pub fn load_and_set_ir_from_bytes(
+/// Load an IR by name, from the embedded factory IRs first and then the
+/// filesystem, align it, truncate to 35ms, and swap into engine.
+pub fn load_and_set_named_ir(
     handle: &EngineHandle,
     loader: &IrLoader,
     name: &str,
-    bytes: &[u8],
+    alignment: IrAlignment,
     sample_rate: f32,
-) {
-    match loader.load_ir_from_bytes(bytes) {
-        Ok(ir_samples) => set_ir_samples(handle, name, &ir_samples, sample_rate),
-        Err(e) => log::error!("Failed to load embedded IR '{name}': {e}"),
-    }
+) -> Result<(), IrError> {
+    handle.swap_ir_convolver(prepare_named_ir(loader, name, alignment, sample_rate)?);
+    Ok(())
+}
+
+/// Load an IR by name like [`load_and_set_named_ir`], but hand it back
+/// ready to swap in rather than sending it to the engine.
+pub fn prepare_named_ir(
+    loader: &IrLoader,
+    name: &str,
+    alignment: IrAlignment,
+    sample_rate: f32,
+) -> Result<PreparedIr, IrError> {
+    let ir_samples = load_named_samples(loader, name)?;
+    prepare_ir_samples(name, &ir_samples, alignment, sample_rate)
+}
+
+/// Onset of the named IR before alignment, for auto-align.
+pub fn named_ir_onset(loader: &IrLoader, name: &str) -> Option<usize> {
+    let ir_samples = load_named_samples(loader, name).ok()?;
+    align::onset(&ir_samples, ONSET_THRESHOLD_DB)
+}
+
+/// Broadband gain of the named IR over the part that's played, for level
+/// matching.
+pub fn named_ir_energy(loader: &IrLoader, name: &str, sample_rate: f32) -> Option<f32> {
+    let ir_samples = load_named_samples(loader, name).ok()?;
+    let played = ir_samples.len().min(max_ir_len(sample_rate));
+    Some(ir_energy(&ir_samples[..played]))
 }
 
-/// Truncate IR to 35ms (cab sim only, no room tail) and swap into engine.
-fn set_ir_samples(handle: &EngineHandle, name: &str, ir_samples: &[f32], sample_rate: f32) {
+/// Samples kept of an IR: 35ms, cab sim only, no room tail.
+fn max_ir_len(sample_rate: f32) -> usize {
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    let max_ir_len = (sample_rate * 35.0 / 1000.0) as usize;
-    let truncated_len = ir_samples.len().min(max_ir_len);
-    let mut convolver = Convolver::new_fir(truncated_len);
-    if let Err(e) = convolver.set_ir(&ir_samples[..truncated_len]) {
-        log::error!("Failed to set IR: {e}");
-    } else {
-        handle.swap_ir_convolver(PreparedIr {
-            name: name.to_string(),
-            convolver: Box::new(convolver),
-        });
+    let len = (sample_rate * 35.0 / 1000.0) as usize;
+    len
+}
+
+/// Decode a factory IR or, failing that, a file from the IR directory.
+fn load_named_samples(loader: &IrLoader, name: &str) -> Result<Vec<f32>, IrError> {
+    match factory::get_factory_ir(name) {
+        Some(bytes) => loader.load_ir_from_bytes(&bytes),
+        None => loader.load_by_name(name),
     }
 }
+
+/// Align the IR, truncate it to 35ms (cab sim only, no room tail) and build
+/// its convolver.
+fn prepare_ir_samples(
+    name: &str,
+    ir_samples: &[f32],
+    alignment: IrAlignment,
+    sample_rate: f32,
+) -> Result<(), IrError> {
+    let mut aligned = align::apply(ir_samples, alignment);
+    aligned.truncate(max_ir_len(sample_rate));
+    let mut convolver = Convolver::new_auto(aligned.len());
+    convolver
+        .set_ir(&aligned)
+        .map_err(|e| IrError::Decode(e.to_string()))?;
+    Ok(PreparedIr {
+        name: name.to_string(),
+        convolver: Box::new(convolver),
+    })
+}
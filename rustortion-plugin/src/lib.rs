@@ -1,11 +1,13 @@
 use nih_plug::prelude::*;
-use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::amp::crossfade::StageCrossfade;
+use rustortion_core::audio::engine::{Engine, EngineHandle, IrChange, PresetSwap};
 use rustortion_core::ir::loader::IrLoader;
 use rustortion_core::preset::stage_config::StageConfig;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 
 mod backend;
+mod bank;
 mod editor;
 mod factory;
 mod ir_helper;
@@ -24,6 +26,27 @@ pub fn user_nam_dir() -> std::path::PathBuf {
         .join("nam")
 }
 
+/// Directory the plugin scans for capture profiles:
+/// `~/.config/rustortion/captures`.
+#[must_use]
+pub fn user_captures_dir() -> std::path::PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("rustortion")
+        .join("captures")
+}
+
+/// Directory the plugin loads user presets from, after its factory ones:
+/// `~/.config/rustortion/presets`. Point the standalone app's preset
+/// directory here to share presets between the two.
+#[must_use]
+pub fn user_presets_dir() -> std::path::PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("rustortion")
+        .join("presets")
+}
+
 enum PluginTask {
     LoadPreset(String),
     /// Combined task: create new samplers at the given factor, then reload the
@@ -32,6 +55,12 @@ enum PluginTask {
         factor: u32,
         preset_name: Option<String>,
     },
+    /// The host changed a stage's choice slot (clipper, model, ...); the stage
+    /// has to be rebuilt with it.
+    SetStageChoice {
+        stage_idx: usize,
+        index: i32,
+    },
 }
 
 pub(crate) struct SharedState {
@@ -50,6 +79,11 @@ pub(crate) struct SharedState {
     active_oversampling: AtomicU32,
     /// GUI stage chain — survives editor close/reopen within the same session.
     gui_stages: Mutex<Option<Vec<StageConfig>>>,
+    /// Slot bindings for the current chain, waiting for the audio thread.
+    bindings: Mutex<bank::BindingQueue>,
+    /// Set by the audio thread when it applied a host change to a slot, so
+    /// the editor knows to pull the slot values into its stages.
+    host_changed: AtomicBool,
 }
 
 impl SharedState {
@@ -57,6 +91,15 @@ impl SharedState {
         if let Ok(mut g) = self.gui_stages.lock() {
             *g = Some(stages.to_vec());
         }
+        self.publish_bindings(stages);
+    }
+
+    /// Rebind the slot parameters to `stages`, the chain the engine now runs.
+    pub(crate) fn publish_bindings(&self, stages: &[StageConfig]) {
+        let bindings = bank::bind(stages);
+        if let Ok(mut queue) = self.bindings.lock() {
+            queue.publish(bindings);
+        }
     }
 
     pub(crate) fn take_gui_stages(&self) -> Option<Vec<StageConfig>> {
@@ -76,8 +119,14 @@ struct RustortionPlugin {
     last_preset_idx: i32,
     last_ir_gain: f32,
     active_oversampling: u32,
+    /// Latency last reported to the host, in samples.
+    reported_latency: u32,
+    /// Slot parameters bound to the running chain, polled every block.
+    bindings: Vec<bank::Binding>,
     input_buf: Vec<f32>,
     output_buf: Vec<f32>,
+    /// Right channel on a stereo bus; `output_buf` is then the left.
+    output_right_buf: Vec<f32>,
 }
 
 impl Default for RustortionPlugin {
@@ -97,20 +146,26 @@ impl Default for RustortionPlugin {
                 requested_oversampling: AtomicU32::new(1),
                 active_oversampling: AtomicU32::new(1),
                 gui_stages: Mutex::new(None),
+                bindings: Mutex::new(bank::BindingQueue::default()),
+                host_changed: AtomicBool::new(false),
             }),
             preset_names: Vec::new(),
             editor_preset_names: Arc::new(Mutex::new(Vec::new())),
             last_preset_idx: -1,
             last_ir_gain: util::db_to_gain(-20.0),
             active_oversampling: 1, // 1x (no oversampling)
+            reported_latency: 0,
+            bindings: Vec::new(),
             input_buf: Vec::new(),
             output_buf: Vec::new(),
+            output_right_buf: Vec::new(),
         }
     }
 }
 
 fn do_load_preset(
     handle: &EngineHandle,
+    shared: &SharedState,
     manager: Option<&rustortion_core::preset::Manager>,
     ir_loader: Option<&IrLoader>,
     sample_rate: f32,
@@ -138,26 +193,40 @@ fn do_load_preset(
             chain.set_bypassed(i, true);
         }
     }
-    handle.set_amp_chain(chain);
 
-    // Set pitch shift
-    handle.set_pitch_shift(preset.pitch_shift_semitones);
-
-    // Load IR if specified
-    if let Some(ir_name) = &preset.ir_name {
-        if let Some(loader) = ir_loader {
-            if let Some(bytes) = factory::get_factory_ir(ir_name) {
-                ir_helper::load_and_set_ir_from_bytes(handle, loader, ir_name, &bytes, sample_rate);
-            } else {
-                ir_helper::load_and_set_ir(handle, loader, ir_name, sample_rate);
+    // The chain and IR land in the same block, so the new chain is never
+    // heard through the old IR.
+    let ir = match (&preset.ir_name, ir_loader) {
+        (Some(ir_name), Some(loader)) => {
+            match ir_helper::prepare_named_ir(loader, ir_name, preset.ir_alignment, sample_rate) {
+                Ok(prepared) => IrChange::Swap(Box::new(prepared)),
+                Err(e) => {
+                    log::error!("Failed to load IR '{ir_name}': {e}");
+                    IrChange::Keep
+                }
             }
         }
-    } else {
-        handle.clear_ir();
-    }
+        (Some(_), None) => IrChange::Keep,
+        (None, _) => IrChange::Clear,
+    };
+    handle.apply_preset(PresetSwap {
+        chain: Box::new(chain),
+        ir,
+        ir_gain: preset.ir_gain,
+        ir_bypass: None,
+    });
+    // Keep the slot bindings and the editor's chain in step with the engine.
+    shared.store_gui_stages(&preset.stages);
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let sample_rate_usize = sample_rate as usize;
+    handle.set_denoiser(&preset.denoise, sample_rate_usize);
 
-    // Set IR gain
-    handle.set_ir_gain(preset.ir_gain);
+    // Set pitch shift
+    handle.set_pitch_shift(preset.pitch_shift_semitones);
+    handle.set_ambience(preset.ambience);
+    handle.set_double_tracker(preset.double_tracker);
+    handle.set_tilt_db(preset.tilt_offset_db);
 
     // Set input filters
     let filters = &preset.input_filters;
@@ -237,6 +306,7 @@ impl Plugin for RustortionPlugin {
                     let os_factor = shared.active_oversampling.load(Ordering::Relaxed);
                     do_load_preset(
                         &handle,
+                        &shared,
                         mgr.as_deref(),
                         loader.as_deref(),
                         sample_rate,
@@ -295,6 +365,28 @@ impl Plugin for RustortionPlugin {
                         Err(e) => nih_log!("Failed to create samplers: {e}"),
                     }
                 }
+                PluginTask::SetStageChoice { stage_idx, index } => {
+                    let Some(mut stages) = shared.take_gui_stages() else {
+                        return;
+                    };
+                    let Some(cfg) = stages.get_mut(stage_idx) else {
+                        return;
+                    };
+                    // Already applied when the change came from the editor.
+                    if !bank::set_enum(cfg, index) {
+                        return;
+                    }
+                    let sample_rate = f32::from_bits(shared.sample_rate.load(Ordering::Relaxed));
+                    let os_factor = shared.active_oversampling.load(Ordering::Relaxed);
+                    #[allow(clippy::cast_precision_loss)]
+                    let effective_sr = sample_rate * os_factor as f32;
+                    handle.crossfade_stage(
+                        stage_idx,
+                        StageCrossfade::new(cfg.to_runtime(effective_sr), effective_sr),
+                    );
+                    shared.store_gui_stages(&stages);
+                    shared.host_changed.store(true, Ordering::Release);
+                }
             }
         })
     }
@@ -311,7 +403,7 @@ impl Plugin for RustortionPlugin {
         &mut self,
         _audio_io_layout: &AudioIOLayout,
         buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
+        context: &mut impl InitContext<Self>,
     ) -> bool {
         self.sample_rate = buffer_config.sample_rate;
         self.shared
@@ -327,7 +419,7 @@ impl Plugin for RustortionPlugin {
 
         let max_ir_samples = sample_rate * 35 / 1000; // 35ms max IR (cab sim only, no room tail)
         let ir_cabinet = rustortion_core::ir::cabinet::IrCabinet::new(
-            rustortion_core::ir::cabinet::ConvolverType::Fir,
+            rustortion_core::ir::cabinet::ConvolverType::Auto,
             max_ir_samples,
         );
 
@@ -358,6 +450,12 @@ impl Plugin for RustortionPlugin {
             oversample_factor,
         ) {
             Ok((engine, handle, rt_drop_rx)) => {
+                // The pitch shifter and a changed oversampling factor are
+                // reported from `process` once the engine picks them up.
+                #[allow(clippy::cast_possible_truncation)]
+                let latency = engine.latency().total() as u32;
+                context.set_latency_samples(latency);
+                self.reported_latency = latency;
                 self.engine = Some(engine);
                 self.engine_handle = Some(handle.clone());
                 self.rt_drop_thread = Some(std::thread::spawn(move || {
@@ -396,16 +494,22 @@ impl Plugin for RustortionPlugin {
                     Err(e) => nih_log!("Failed to init NAM loader: {e}"),
                 }
 
-                // Load factory presets (embedded in binary)
-                let factory_presets = factory::load_factory_presets();
-                let names: Vec<String> = factory_presets.iter().map(|p| p.name.clone()).collect();
+                match rustortion_core::capture::CaptureLoader::new(&user_captures_dir()) {
+                    Ok(loader) => {
+                        nih_log!("Found {} capture file(s)", loader.available_names().len());
+                        rustortion_core::capture::registry::init_from_loader(&loader);
+                    }
+                    Err(e) => nih_log!("Failed to init capture loader: {e}"),
+                }
+
+                // Factory presets (embedded in binary), then the user's own
+                let presets = factory::load_presets();
+                let names: Vec<String> = presets.iter().map(|p| p.name.clone()).collect();
                 self.preset_names.clone_from(&names);
                 if let Ok(mut editor_names) = self.editor_preset_names.lock() {
                     editor_names.clone_from(&names);
                 }
-                let manager = Arc::new(rustortion_core::preset::Manager::new_from_presets(
-                    factory_presets,
-                ));
+                let manager = Arc::new(rustortion_core::preset::Manager::new_from_presets(presets));
                 if let Ok(mut m) = self.shared.preset_manager.lock() {
                     *m = Some(manager);
                 }
@@ -413,6 +517,7 @@ impl Plugin for RustortionPlugin {
                 // Pre-allocate audio buffers
                 self.input_buf.resize(max_buffer_size, 0.0);
                 self.output_buf.resize(max_buffer_size, 0.0);
+                self.output_right_buf.resize(max_buffer_size, 0.0);
 
                 // Re-load chain state: prefer DAW-persisted chain (user may have
                 // added/removed stages), fall back to preset from disk.
@@ -421,7 +526,7 @@ impl Plugin for RustortionPlugin {
 
                 // Prefer gui_stages (editor's in-session state) over chain_state
                 // (DAW persist, may be stale due to nih-plug re-deserialization).
-                let persisted_stages = self
+                let mut persisted_stages = self
                     .shared
                     .gui_stages
                     .lock()
@@ -429,6 +534,13 @@ impl Plugin for RustortionPlugin {
                     .and_then(|g| g.clone())
                     .or_else(|| self.params.chain_state.lock().ok().and_then(|g| g.clone()));
 
+                // The host restored the slot params too, and they win: automation
+                // may have moved them since the chain was last persisted.
+                let applied_host_values = self.params.slot_bank_synced.load(Ordering::Relaxed)
+                    && persisted_stages
+                        .as_mut()
+                        .is_some_and(|stages| bank::apply_host_values(&self.params, stages));
+
                 if let Some(handle) = &self.engine_handle {
                     if let Some(stages) = &persisted_stages {
                         // Restore from DAW-persisted chain state
@@ -444,6 +556,7 @@ impl Plugin for RustortionPlugin {
                             }
                         }
                         handle.set_amp_chain(chain);
+                        self.shared.publish_bindings(stages);
 
                         // Also load IR/filters/pitch from preset (those are
                         // persisted via nih-plug params and applied separately)
@@ -460,27 +573,26 @@ impl Plugin for RustortionPlugin {
                             if let Some(ir_name) = &preset.ir_name {
                                 let loader =
                                     self.shared.ir_loader.lock().ok().and_then(|g| g.clone());
-                                if let Some(loader) = &loader {
-                                    if let Some(bytes) = factory::get_factory_ir(ir_name) {
-                                        ir_helper::load_and_set_ir_from_bytes(
-                                            handle,
-                                            loader,
-                                            ir_name,
-                                            &bytes,
-                                            self.sample_rate,
-                                        );
-                                    } else {
-                                        ir_helper::load_and_set_ir(
-                                            handle,
-                                            loader,
-                                            ir_name,
-                                            self.sample_rate,
-                                        );
-                                    }
+                                if let Some(loader) = &loader
+                                    && let Err(e) = ir_helper::load_and_set_named_ir(
+                                        handle,
+                                        loader,
+                                        ir_name,
+                                        preset.ir_alignment,
+                                        self.sample_rate,
+                                    )
+                                {
+                                    log::error!("Failed to load IR '{ir_name}': {e}");
                                 }
                             }
                             handle.set_ir_gain(preset.ir_gain);
+                            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                            let sample_rate = self.sample_rate as usize;
+                            handle.set_denoiser(&preset.denoise, sample_rate);
                             handle.set_pitch_shift(preset.pitch_shift_semitones);
+                            handle.set_ambience(preset.ambience);
+                            handle.set_double_tracker(preset.double_tracker);
+                            handle.set_tilt_db(preset.tilt_offset_db);
                         }
                     } else {
                         // No persisted chain — fall back to loading preset from disk
@@ -495,6 +607,7 @@ impl Plugin for RustortionPlugin {
                             let loader = self.shared.ir_loader.lock().ok().and_then(|g| g.clone());
                             do_load_preset(
                                 handle,
+                                &self.shared,
                                 mgr.as_deref(),
                                 loader.as_deref(),
                                 self.sample_rate,
@@ -509,8 +622,11 @@ impl Plugin for RustortionPlugin {
                     // nih-plug can re-deserialize chain_state at any time,
                     // reverting our in-memory writes, so gui_stages is the
                     // authoritative in-session source of truth.
+                    // Host values applied above are newer than either source.
                     let gui_already_set = self.shared.gui_stages.lock().is_ok_and(|g| g.is_some());
-                    if !gui_already_set && let Some(stages) = persisted_stages {
+                    if (!gui_already_set || applied_host_values)
+                        && let Some(stages) = persisted_stages
+                    {
                         self.shared.store_gui_stages(&stages);
                     }
                 }
@@ -560,6 +676,43 @@ impl Plugin for RustortionPlugin {
             self.active_oversampling = requested_os;
         }
 
+        // Pick up the slot bindings for a rebuilt chain
+        if let Ok(mut queue) = self.shared.bindings.try_lock()
+            && queue.take_into(&mut self.bindings)
+        {
+            for binding in &mut self.bindings {
+                binding.reset(&self.params);
+            }
+        }
+
+        // Forward host changes to bound slot params onto the running stages
+        if let Some(handle) = &self.engine_handle {
+            #[allow(clippy::cast_possible_truncation)]
+            let samples = buffer.samples() as u32;
+            let mut changed = false;
+            for binding in &mut self.bindings {
+                match binding.poll(&self.params, samples) {
+                    Some(bank::HostChange::Parameter {
+                        stage_idx,
+                        name,
+                        value,
+                    }) => handle.set_parameter(stage_idx, name, value),
+                    Some(bank::HostChange::Bypass {
+                        stage_idx,
+                        bypassed,
+                    }) => handle.set_stage_bypassed(stage_idx, bypassed),
+                    Some(bank::HostChange::Choice { stage_idx, index }) => {
+                        context.execute_background(PluginTask::SetStageChoice { stage_idx, index });
+                    }
+                    None => continue,
+                }
+                changed = true;
+            }
+            if changed {
+                self.shared.host_changed.store(true, Ordering::Release);
+            }
+        }
+
         // Apply IR gain from DAW parameter
         if let Some(handle) = &self.engine_handle {
             #[allow(clippy::cast_possible_truncation)]
@@ -578,6 +731,8 @@ impl Plugin for RustortionPlugin {
             let num_samples = buffer.samples();
             let input_buf = &mut self.input_buf[..num_samples];
             let output_buf = &mut self.output_buf[..num_samples];
+            let output_right_buf = &mut self.output_right_buf[..num_samples];
+            let stereo = buffer.channels() > 1;
 
             // Sum all input channels to mono
             {
@@ -595,17 +750,41 @@ impl Plugin for RustortionPlugin {
                 }
             }
 
-            if let Err(e) = engine.process(input_buf, output_buf) {
+            // Tempo-synced stages follow the host transport.
+            #[allow(clippy::cast_possible_truncation)]
+            engine.set_host_tempo(context.transport().tempo.map(|bpm| bpm as f32));
+
+            let processed = if stereo {
+                engine.process_stereo(input_buf, output_buf, output_right_buf)
+            } else {
+                engine.process(input_buf, output_buf)
+            };
+            if let Err(e) = processed {
                 nih_log!("Engine process error: {e}");
                 return ProcessStatus::Normal;
             }
 
-            // Write mono output to all channels with output level applied
+            // Toggling the pitch shifter or changing the oversampling factor
+            // moves the latency; let the host re-align its compensation.
+            #[allow(clippy::cast_possible_truncation)]
+            let latency = engine.latency().total() as u32;
+            if latency != self.reported_latency {
+                context.set_latency_samples(latency);
+                self.reported_latency = latency;
+            }
+
+            // Write the output with the output level applied: left and right
+            // on a stereo bus, the mono signal otherwise.
             let output_slices = buffer.as_slice();
             for i in 0..num_samples {
                 let gain = self.params.output_level.smoothed.next();
-                for ch in output_slices.iter_mut() {
-                    ch[i] = output_buf[i] * gain;
+                for (ch_idx, ch) in output_slices.iter_mut().enumerate() {
+                    let sample = if stereo && ch_idx == 1 {
+                        output_right_buf[i]
+                    } else {
+                        output_buf[i]
+                    };
+                    ch[i] = sample * gain;
                 }
             }
         }
@@ -2,12 +2,22 @@ use nih_plug::prelude::*;
 use rustortion_core::preset::stage_config::StageConfig;
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::sync::atomic::AtomicU32;
+use std::sync::atomic::{AtomicBool, AtomicU32};
 
 // ---------------------------------------------------------------------------
 // Per-slot parameter structs
 // ---------------------------------------------------------------------------
 
+/// Ramp time for slot parameters, so host automation steps don't click.
+const SLOT_SMOOTHING_MS: f32 = 20.0;
+
+/// Host-facing name of a slot parameter, e.g. "Preamp 2 Gain". The nested
+/// array groups are numbered too, but hosts that list parameters flat would
+/// otherwise show eight indistinguishable "Gain"s.
+fn slot_name(group: &str, slot: usize, name: &str) -> String {
+    format!("{group} {} {name}", slot + 1)
+}
+
 #[derive(Params)]
 pub struct PreampSlotParams {
     #[id = "gain"]
@@ -20,39 +30,46 @@ pub struct PreampSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for PreampSlotParams {
-    fn default() -> Self {
+impl PreampSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Preamp";
         Self {
             gain: FloatParam::new(
-                "Gain",
+                slot_name(group, slot, "Gain"),
                 5.0,
                 FloatRange::Linear {
                     min: 0.0,
                     max: 10.0,
                 },
-            ),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             bias: FloatParam::new(
-                "Bias",
+                slot_name(group, slot, "Bias"),
                 0.0,
                 FloatRange::Linear {
                     min: -1.0,
                     max: 1.0,
                 },
-            ),
-            clipper_type: IntParam::new("Clipper Type", 0, IntRange::Linear { min: 0, max: 5 })
-                .with_value_to_string(Arc::new(|v| {
-                    match v {
-                        0 => "Soft",
-                        1 => "Medium",
-                        2 => "Hard",
-                        3 => "Asymmetric",
-                        4 => "ClassA",
-                        5 => "Triode",
-                        _ => "Unknown",
-                    }
-                    .to_string()
-                })),
-            bypassed: BoolParam::new("Bypassed", false),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            clipper_type: IntParam::new(
+                slot_name(group, slot, "Clipper Type"),
+                0,
+                IntRange::Linear { min: 0, max: 5 },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                match v {
+                    0 => "Soft",
+                    1 => "Medium",
+                    2 => "Hard",
+                    3 => "Asymmetric",
+                    4 => "ClassA",
+                    5 => "Triode",
+                    _ => "Unknown",
+                }
+                .to_string()
+            })),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -73,54 +90,60 @@ pub struct CompressorSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for CompressorSlotParams {
-    fn default() -> Self {
+impl CompressorSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Compressor";
         Self {
             attack_ms: FloatParam::new(
-                "Attack",
+                slot_name(group, slot, "Attack"),
                 1.0,
                 FloatRange::Linear {
                     min: 0.1,
                     max: 100.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
             release_ms: FloatParam::new(
-                "Release",
+                slot_name(group, slot, "Release"),
                 100.0,
                 FloatRange::Linear {
                     min: 10.0,
                     max: 1000.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
             threshold_db: FloatParam::new(
-                "Threshold",
+                slot_name(group, slot, "Threshold"),
                 -20.0,
                 FloatRange::Linear {
                     min: -60.0,
                     max: 0.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" dB"),
             ratio: FloatParam::new(
-                "Ratio",
+                slot_name(group, slot, "Ratio"),
                 4.0,
                 FloatRange::Linear {
                     min: 1.0,
                     max: 20.0,
                 },
-            ),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             makeup_db: FloatParam::new(
-                "Makeup",
+                slot_name(group, slot, "Makeup"),
                 0.0,
                 FloatRange::Linear {
                     min: -12.0,
                     max: 24.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" dB"),
-            bypassed: BoolParam::new("Bypassed", false),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -141,25 +164,50 @@ pub struct ToneStackSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for ToneStackSlotParams {
-    fn default() -> Self {
+impl ToneStackSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Tone Stack";
         Self {
-            model: IntParam::new("Model", 0, IntRange::Linear { min: 0, max: 3 })
-                .with_value_to_string(Arc::new(|v| {
-                    match v {
-                        0 => "Modern",
-                        1 => "British",
-                        2 => "American",
-                        3 => "Flat",
-                        _ => "Unknown",
-                    }
-                    .to_string()
-                })),
-            bass: FloatParam::new("Bass", 0.5, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            mid: FloatParam::new("Mid", 0.5, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            treble: FloatParam::new("Treble", 0.5, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            presence: FloatParam::new("Presence", 0.5, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            model: IntParam::new(
+                slot_name(group, slot, "Model"),
+                0,
+                IntRange::Linear { min: 0, max: 3 },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                match v {
+                    0 => "Modern",
+                    1 => "British",
+                    2 => "American",
+                    3 => "Flat",
+                    _ => "Unknown",
+                }
+                .to_string()
+            })),
+            bass: FloatParam::new(
+                slot_name(group, slot, "Bass"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            mid: FloatParam::new(
+                slot_name(group, slot, "Mid"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            treble: FloatParam::new(
+                slot_name(group, slot, "Treble"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            presence: FloatParam::new(
+                slot_name(group, slot, "Presence"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -178,31 +226,47 @@ pub struct PowerAmpSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for PowerAmpSlotParams {
-    fn default() -> Self {
+impl PowerAmpSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Power Amp";
         Self {
-            drive: FloatParam::new("Drive", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            amp_type: IntParam::new("Amp Type", 1, IntRange::Linear { min: 0, max: 2 })
-                .with_value_to_string(Arc::new(|v| {
-                    match v {
-                        0 => "Class A",
-                        1 => "Class AB",
-                        2 => "Class B",
-                        _ => "Unknown",
-                    }
-                    .to_string()
-                })),
-            sag: FloatParam::new("Sag", 0.3, FloatRange::Linear { min: 0.0, max: 1.0 }),
+            drive: FloatParam::new(
+                slot_name(group, slot, "Drive"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            amp_type: IntParam::new(
+                slot_name(group, slot, "Amp Type"),
+                1,
+                IntRange::Linear { min: 0, max: 2 },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                match v {
+                    0 => "Class A",
+                    1 => "Class AB",
+                    2 => "Class B",
+                    _ => "Unknown",
+                }
+                .to_string()
+            })),
+            sag: FloatParam::new(
+                slot_name(group, slot, "Sag"),
+                0.3,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             sag_release: FloatParam::new(
-                "Sag Release",
+                slot_name(group, slot, "Sag Release"),
                 120.0,
                 FloatRange::Linear {
                     min: 40.0,
                     max: 200.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
-            bypassed: BoolParam::new("Bypassed", false),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -215,11 +279,17 @@ pub struct LevelSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for LevelSlotParams {
-    fn default() -> Self {
+impl LevelSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Level";
         Self {
-            gain: FloatParam::new("Gain", 1.0, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            gain: FloatParam::new(
+                slot_name(group, slot, "Gain"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -240,54 +310,60 @@ pub struct NoiseGateSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for NoiseGateSlotParams {
-    fn default() -> Self {
+impl NoiseGateSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Noise Gate";
         Self {
             threshold_db: FloatParam::new(
-                "Threshold",
+                slot_name(group, slot, "Threshold"),
                 -40.0,
                 FloatRange::Linear {
                     min: -80.0,
                     max: 0.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" dB"),
             ratio: FloatParam::new(
-                "Ratio",
+                slot_name(group, slot, "Ratio"),
                 10.0,
                 FloatRange::Linear {
                     min: 1.0,
                     max: 100.0,
                 },
-            ),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             attack_ms: FloatParam::new(
-                "Attack",
+                slot_name(group, slot, "Attack"),
                 1.0,
                 FloatRange::Linear {
                     min: 0.1,
                     max: 100.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
             hold_ms: FloatParam::new(
-                "Hold",
+                slot_name(group, slot, "Hold"),
                 10.0,
                 FloatRange::Linear {
                     min: 0.0,
                     max: 500.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
             release_ms: FloatParam::new(
-                "Release",
+                slot_name(group, slot, "Release"),
                 100.0,
                 FloatRange::Linear {
                     min: 1.0,
                     max: 1000.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
-            bypassed: BoolParam::new("Bypassed", false),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -314,42 +390,67 @@ pub struct MultibandSaturatorSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for MultibandSaturatorSlotParams {
-    fn default() -> Self {
+impl MultibandSaturatorSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Multiband";
         Self {
-            low_drive: FloatParam::new("Low Drive", 0.3, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            mid_drive: FloatParam::new("Mid Drive", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
+            low_drive: FloatParam::new(
+                slot_name(group, slot, "Low Drive"),
+                0.3,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            mid_drive: FloatParam::new(
+                slot_name(group, slot, "Mid Drive"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             high_drive: FloatParam::new(
-                "High Drive",
+                slot_name(group, slot, "High Drive"),
                 0.4,
                 FloatRange::Linear { min: 0.0, max: 1.0 },
-            ),
-            low_level: FloatParam::new("Low Level", 1.0, FloatRange::Linear { min: 0.0, max: 2.0 }),
-            mid_level: FloatParam::new("Mid Level", 1.0, FloatRange::Linear { min: 0.0, max: 2.0 }),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            low_level: FloatParam::new(
+                slot_name(group, slot, "Low Level"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            mid_level: FloatParam::new(
+                slot_name(group, slot, "Mid Level"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 2.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             high_level: FloatParam::new(
-                "High Level",
+                slot_name(group, slot, "High Level"),
                 1.0,
                 FloatRange::Linear { min: 0.0, max: 2.0 },
-            ),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
             low_freq: FloatParam::new(
-                "Low Freq",
+                slot_name(group, slot, "Low Freq"),
                 200.0,
                 FloatRange::Linear {
                     min: 50.0,
                     max: 500.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" Hz"),
             high_freq: FloatParam::new(
-                "High Freq",
+                slot_name(group, slot, "High Freq"),
                 2500.0,
                 FloatRange::Linear {
                     min: 1000.0,
                     max: 6000.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" Hz"),
-            bypassed: BoolParam::new("Bypassed", false),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -366,28 +467,36 @@ pub struct DelaySlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for DelaySlotParams {
-    fn default() -> Self {
+impl DelaySlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Delay";
         Self {
             delay_ms: FloatParam::new(
-                "Delay",
+                slot_name(group, slot, "Delay"),
                 300.0,
                 FloatRange::Linear {
                     min: 0.0,
                     max: 2000.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" ms"),
             feedback: FloatParam::new(
-                "Feedback",
+                slot_name(group, slot, "Feedback"),
                 0.3,
                 FloatRange::Linear {
                     min: 0.0,
                     max: 0.95,
                 },
-            ),
-            mix: FloatParam::new("Mix", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            mix: FloatParam::new(
+                slot_name(group, slot, "Mix"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -404,13 +513,29 @@ pub struct ReverbSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for ReverbSlotParams {
-    fn default() -> Self {
+impl ReverbSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Reverb";
         Self {
-            room_size: FloatParam::new("Room Size", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            damping: FloatParam::new("Damping", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            mix: FloatParam::new("Mix", 0.2, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            room_size: FloatParam::new(
+                slot_name(group, slot, "Room Size"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            damping: FloatParam::new(
+                slot_name(group, slot, "Damping"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            mix: FloatParam::new(
+                slot_name(group, slot, "Mix"),
+                0.2,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -453,30 +578,63 @@ pub struct EqSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for EqSlotParams {
-    fn default() -> Self {
+impl EqSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "EQ";
         let eq_range = FloatRange::Linear {
             min: -12.0,
             max: 12.0,
         };
         Self {
-            band_0: FloatParam::new("Band 0", 0.0, eq_range).with_unit(" dB"),
-            band_1: FloatParam::new("Band 1", 0.0, eq_range).with_unit(" dB"),
-            band_2: FloatParam::new("Band 2", 0.0, eq_range).with_unit(" dB"),
-            band_3: FloatParam::new("Band 3", 0.0, eq_range).with_unit(" dB"),
-            band_4: FloatParam::new("Band 4", 0.0, eq_range).with_unit(" dB"),
-            band_5: FloatParam::new("Band 5", 0.0, eq_range).with_unit(" dB"),
-            band_6: FloatParam::new("Band 6", 0.0, eq_range).with_unit(" dB"),
-            band_7: FloatParam::new("Band 7", 0.0, eq_range).with_unit(" dB"),
-            band_8: FloatParam::new("Band 8", 0.0, eq_range).with_unit(" dB"),
-            band_9: FloatParam::new("Band 9", 0.0, eq_range).with_unit(" dB"),
-            band_10: FloatParam::new("Band 10", 0.0, eq_range).with_unit(" dB"),
-            band_11: FloatParam::new("Band 11", 0.0, eq_range).with_unit(" dB"),
-            band_12: FloatParam::new("Band 12", 0.0, eq_range).with_unit(" dB"),
-            band_13: FloatParam::new("Band 13", 0.0, eq_range).with_unit(" dB"),
-            band_14: FloatParam::new("Band 14", 0.0, eq_range).with_unit(" dB"),
-            band_15: FloatParam::new("Band 15", 0.0, eq_range).with_unit(" dB"),
-            bypassed: BoolParam::new("Bypassed", false),
+            band_0: FloatParam::new(slot_name(group, slot, "Band 0"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_1: FloatParam::new(slot_name(group, slot, "Band 1"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_2: FloatParam::new(slot_name(group, slot, "Band 2"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_3: FloatParam::new(slot_name(group, slot, "Band 3"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_4: FloatParam::new(slot_name(group, slot, "Band 4"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_5: FloatParam::new(slot_name(group, slot, "Band 5"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_6: FloatParam::new(slot_name(group, slot, "Band 6"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_7: FloatParam::new(slot_name(group, slot, "Band 7"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_8: FloatParam::new(slot_name(group, slot, "Band 8"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_9: FloatParam::new(slot_name(group, slot, "Band 9"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_10: FloatParam::new(slot_name(group, slot, "Band 10"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_11: FloatParam::new(slot_name(group, slot, "Band 11"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_12: FloatParam::new(slot_name(group, slot, "Band 12"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_13: FloatParam::new(slot_name(group, slot, "Band 13"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_14: FloatParam::new(slot_name(group, slot, "Band 14"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            band_15: FloatParam::new(slot_name(group, slot, "Band 15"), 0.0, eq_range)
+                .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+                .with_unit(" dB"),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -493,21 +651,115 @@ pub struct TremoloSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for TremoloSlotParams {
-    fn default() -> Self {
+impl TremoloSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Tremolo";
         Self {
             rate: FloatParam::new(
-                "Rate",
+                slot_name(group, slot, "Rate"),
                 5.0,
                 FloatRange::Linear {
                     min: 0.1,
                     max: 20.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" Hz"),
-            depth: FloatParam::new("Depth", 0.5, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            shape: FloatParam::new("Shape", 0.0, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            depth: FloatParam::new(
+                slot_name(group, slot, "Depth"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            shape: FloatParam::new(
+                slot_name(group, slot, "Shape"),
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
+        }
+    }
+}
+
+#[derive(Params)]
+pub struct WahSlotParams {
+    #[id = "mode"]
+    pub mode: IntParam,
+    #[id = "position"]
+    pub position: FloatParam,
+    #[id = "q"]
+    pub q: FloatParam,
+    #[id = "sensitivity"]
+    pub sensitivity: FloatParam,
+    #[id = "attack_ms"]
+    pub attack_ms: FloatParam,
+    #[id = "release_ms"]
+    pub release_ms: FloatParam,
+    #[id = "bypassed"]
+    pub bypassed: BoolParam,
+}
+
+impl WahSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Wah";
+        Self {
+            mode: IntParam::new(
+                slot_name(group, slot, "Mode"),
+                0,
+                IntRange::Linear { min: 0, max: 2 },
+            )
+            .with_value_to_string(Arc::new(|v| {
+                match v {
+                    0 => "Manual",
+                    1 => "Auto",
+                    2 => "Fixed",
+                    _ => "Unknown",
+                }
+                .to_string()
+            })),
+            position: FloatParam::new(
+                slot_name(group, slot, "Position"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            q: FloatParam::new(
+                slot_name(group, slot, "Q"),
+                4.0,
+                FloatRange::Linear {
+                    min: 0.5,
+                    max: 10.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            sensitivity: FloatParam::new(
+                slot_name(group, slot, "Sensitivity"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            attack_ms: FloatParam::new(
+                slot_name(group, slot, "Attack"),
+                10.0,
+                FloatRange::Linear {
+                    min: 1.0,
+                    max: 100.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+            .with_unit(" ms"),
+            release_ms: FloatParam::new(
+                slot_name(group, slot, "Release"),
+                200.0,
+                FloatRange::Linear {
+                    min: 10.0,
+                    max: 1000.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+            .with_unit(" ms"),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -518,9 +770,9 @@ impl Default for TremoloSlotParams {
 /// serialized `chain_state`, so it persists with and recalls from the DAW project
 /// (the chain is rebuilt and each NAM stage resolves its model by name on load).
 /// Name-based recall is robust against the model list reordering. Exposing model
-/// choice as a host-automatable param is deferred — it needs a general slot<->stage
-/// param pump (and a background reload, since switching models loads a neural net
-/// off the audio thread), with no clean NAM-only slice.
+/// choice as a host-automatable param is deferred — switching models loads a
+/// neural net, and the slot bank (`crate::bank`) only rebuilds stages from
+/// their configs, not from a model list the host could index into.
 #[derive(Params)]
 pub struct NamSlotParams {
     #[id = "input_gain_db"]
@@ -533,29 +785,86 @@ pub struct NamSlotParams {
     pub bypassed: BoolParam,
 }
 
-impl Default for NamSlotParams {
-    fn default() -> Self {
+impl NamSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "NAM";
         Self {
             input_gain_db: FloatParam::new(
-                "Input",
+                slot_name(group, slot, "Input"),
                 0.0,
                 FloatRange::Linear {
                     min: -24.0,
                     max: 24.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" dB"),
             output_gain_db: FloatParam::new(
-                "Output",
+                slot_name(group, slot, "Output"),
                 0.0,
                 FloatRange::Linear {
                     min: -24.0,
                     max: 24.0,
                 },
             )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
             .with_unit(" dB"),
-            mix: FloatParam::new("Mix", 1.0, FloatRange::Linear { min: 0.0, max: 1.0 }),
-            bypassed: BoolParam::new("Bypassed", false),
+            mix: FloatParam::new(
+                slot_name(group, slot, "Mix"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
+        }
+    }
+}
+
+/// Per-slot capture params. Like NAM, the selected capture is stored by its
+/// relative path in `CaptureConfig.capture` inside `chain_state`, not as a param.
+#[derive(Params)]
+pub struct CaptureSlotParams {
+    #[id = "input_gain_db"]
+    pub input_gain_db: FloatParam,
+    #[id = "output_gain_db"]
+    pub output_gain_db: FloatParam,
+    #[id = "mix"]
+    pub mix: FloatParam,
+    #[id = "bypassed"]
+    pub bypassed: BoolParam,
+}
+
+impl CaptureSlotParams {
+    fn new(slot: usize) -> Self {
+        let group = "Capture";
+        Self {
+            input_gain_db: FloatParam::new(
+                slot_name(group, slot, "Input"),
+                0.0,
+                FloatRange::Linear {
+                    min: -24.0,
+                    max: 24.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+            .with_unit(" dB"),
+            output_gain_db: FloatParam::new(
+                slot_name(group, slot, "Output"),
+                0.0,
+                FloatRange::Linear {
+                    min: -24.0,
+                    max: 24.0,
+                },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS))
+            .with_unit(" dB"),
+            mix: FloatParam::new(
+                slot_name(group, slot, "Mix"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_smoother(SmoothingStyle::Linear(SLOT_SMOOTHING_MS)),
+            bypassed: BoolParam::new(slot_name(group, slot, "Bypassed"), false),
         }
     }
 }
@@ -602,7 +911,13 @@ pub struct RustortionParams {
     #[persist = "chain_state"]
     pub chain_state: Arc<Mutex<Option<Vec<StageConfig>>>>,
 
-    // Per-stage slot arrays
+    /// Set once the editor has pushed the chain's values into the slot params
+    /// below. Projects saved before that hold default slot values, which must
+    /// not be applied over their `chain_state`.
+    #[persist = "slot_bank_synced"]
+    pub slot_bank_synced: Arc<AtomicBool>,
+
+    // Per-stage slot arrays, bound to the chain by `crate::bank`
     #[nested(array, group = "Preamp")]
     pub preamp: [PreampSlotParams; 8],
 
@@ -627,6 +942,9 @@ pub struct RustortionParams {
     #[nested(array, group = "NAM")]
     pub nam: [NamSlotParams; 8],
 
+    #[nested(array, group = "Capture")]
+    pub capture: [CaptureSlotParams; 8],
+
     #[nested(array, group = "Delay")]
     pub delay: [DelaySlotParams; 8],
 
@@ -638,6 +956,9 @@ pub struct RustortionParams {
 
     #[nested(array, group = "Tremolo")]
     pub tremolo: [TremoloSlotParams; 8],
+
+    #[nested(array, group = "Wah")]
+    pub wah: [WahSlotParams; 8],
 }
 
 impl Default for RustortionParams {
@@ -705,19 +1026,22 @@ impl Default for RustortionParams {
 
             oversampling_factor: Arc::new(AtomicU32::new(1)), // 1 = 1x (no oversampling)
             chain_state: Arc::new(Mutex::new(None)),
+            slot_bank_synced: Arc::new(AtomicBool::new(false)),
 
-            preamp: Default::default(),
-            compressor: Default::default(),
-            tonestack: Default::default(),
-            poweramp: Default::default(),
-            level: Default::default(),
-            noise_gate: Default::default(),
-            multiband_saturator: Default::default(),
-            nam: Default::default(),
-            delay: Default::default(),
-            reverb: Default::default(),
-            eq: Default::default(),
-            tremolo: Default::default(),
+            preamp: std::array::from_fn(PreampSlotParams::new),
+            compressor: std::array::from_fn(CompressorSlotParams::new),
+            tonestack: std::array::from_fn(ToneStackSlotParams::new),
+            poweramp: std::array::from_fn(PowerAmpSlotParams::new),
+            level: std::array::from_fn(LevelSlotParams::new),
+            noise_gate: std::array::from_fn(NoiseGateSlotParams::new),
+            multiband_saturator: std::array::from_fn(MultibandSaturatorSlotParams::new),
+            nam: std::array::from_fn(NamSlotParams::new),
+            capture: std::array::from_fn(CaptureSlotParams::new),
+            delay: std::array::from_fn(DelaySlotParams::new),
+            reverb: std::array::from_fn(ReverbSlotParams::new),
+            eq: std::array::from_fn(EqSlotParams::new),
+            tremolo: std::array::from_fn(TremoloSlotParams::new),
+            wah: std::array::from_fn(WahSlotParams::new),
         }
     }
 }
@@ -0,0 +1,103 @@
+//! Run the engine without the GUI: load the saved presets, start JACK and
+//! step through the presets on a timer until Ctrl+C.
+//!
+//! Builds without iced:
+//!
+//! ```sh
+//! cargo run -p rustortion-standalone --no-default-features --example headless_engine -- 10
+//! ```
+//!
+//! The optional argument is the number of seconds per preset (default 8).
+//! Ports, directories and oversampling come from the usual settings file.
+
+#![allow(clippy::cast_precision_loss)]
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use log::{info, warn};
+use rustortion::audio::bootstrap::{build_amp_chain, build_input_filters};
+use rustortion::audio::manager::Manager;
+use rustortion::settings::Settings;
+use rustortion_core::preset::{Manager as PresetManager, Preset};
+
+const DEFAULT_SECONDS_PER_PRESET: u64 = 8;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let seconds = std::env::args()
+        .nth(1)
+        .map(|arg| arg.parse::<u64>())
+        .transpose()
+        .context("seconds per preset must be a whole number")?
+        .unwrap_or(DEFAULT_SECONDS_PER_PRESET);
+
+    let settings = Settings::load().unwrap_or_default();
+    settings.apply_to_environment();
+
+    let presets = PresetManager::new(&settings.paths.preset_dir)?;
+    if presets.get_presets().is_empty() {
+        bail!("no presets found in {}", settings.paths.preset_dir);
+    }
+
+    let manager = Manager::new(settings)?;
+    info!(
+        "Engine running at {} Hz, {} frames per buffer",
+        manager.sample_rate(),
+        manager.buffer_size()
+    );
+
+    let running = Arc::new(AtomicBool::new(true));
+    let flag = Arc::clone(&running);
+    ctrlc::set_handler(move || flag.store(false, Ordering::SeqCst))?;
+
+    let mut cycle = presets.get_presets().iter().cycle();
+    let mut switched_at = Instant::now();
+    if let Some(preset) = cycle.next() {
+        apply_preset(&manager, preset);
+    }
+
+    while running.load(Ordering::SeqCst) {
+        std::thread::sleep(POLL_INTERVAL);
+        if switched_at.elapsed() >= Duration::from_secs(seconds)
+            && let Some(preset) = cycle.next()
+        {
+            apply_preset(&manager, preset);
+            switched_at = Instant::now();
+        }
+    }
+
+    info!("Stopping");
+    Ok(())
+}
+
+/// Send everything a preset describes to the running engine. Stages are built
+/// here, off the audio thread, at the oversampled rate they run at.
+fn apply_preset(manager: &Manager, preset: &Preset) {
+    info!("Preset: {}", preset.name);
+
+    let engine = manager.engine();
+    let sample_rate = manager.sample_rate() as f32;
+    let stage_rate = sample_rate * manager.current_oversampling_factor() as f32;
+
+    engine.set_amp_chain(build_amp_chain(&preset.stages, stage_rate));
+
+    let (hp, lp) = build_input_filters(&preset.input_filters, sample_rate);
+    engine.set_input_filters(hp, lp);
+    engine.set_pitch_shift(preset.pitch_shift_semitones);
+    engine.set_ambience(preset.ambience);
+
+    match preset.ir_name.as_deref() {
+        Some(name) => {
+            if let Err(e) = manager.request_ir_load(name, preset.ir_alignment) {
+                warn!("Preset IR '{name}' can't be used: {e}");
+            }
+        }
+        None => manager.clear_ir(),
+    }
+    engine.set_ir_gain(preset.ir_gain);
+}
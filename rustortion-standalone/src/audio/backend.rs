@@ -0,0 +1,86 @@
+//! The audio systems the engine can run on, behind one trait so the manager
+//! doesn't care which it got.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+
+use crossbeam::channel::Sender;
+use log::warn;
+
+use crate::audio::bootstrap::AudioHost;
+use crate::audio::cpal::CpalHost;
+use crate::audio::error::AudioError;
+use crate::audio::freewheel::InputFeed;
+use crate::audio::jack::JackHost;
+use crate::audio::transport::TransportEvent;
+use crate::settings::AudioSettings;
+use rustortion_core::audio::host::HostApi;
+
+/// The audio system a running stream is on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackendKind {
+    #[default]
+    Jack,
+    /// The fallback: no port graph to route, no transport, and a latency
+    /// fixed when the stream opens.
+    Cpal,
+}
+
+/// A stream the engine runs in, on top of the warm-start steps of
+/// [`AudioHost`]. Where JACK has ports, CPAL has devices: the input and
+/// output lists name whichever the backend connects to.
+pub trait AudioBackend: AudioHost + Send {
+    fn kind(&self) -> BackendKind;
+    /// Whether the engine was handed over and the stream should be running.
+    fn is_active(&self) -> bool;
+    /// DSP load, in percent of the time the period allows.
+    fn cpu_load(&self) -> f32;
+    /// Ask for a new period size before [`AudioHost::activate`]. A no-op
+    /// where the period is chosen when the stream opens.
+    fn request_buffer_size(&self, frames: u32);
+    /// Stop the output reaching anything, e.g. before reconnecting.
+    fn disconnect_all(&self);
+    /// What the input can be taken from: ports or devices.
+    fn available_inputs(&self) -> Vec<String>;
+    /// What the outputs can be sent to: ports or devices.
+    fn available_outputs(&self) -> Vec<String>;
+    /// Run the callback as fast as the chain allows rather than once per
+    /// period, or back in step with the hardware.
+    fn set_freewheel(&self, enabled: bool) -> Result<(), AudioError>;
+    /// Where a bounce feeds its file in, on backends that can freewheel.
+    fn input_feed(&self) -> Option<Arc<InputFeed>>;
+    /// Stop the stream and free its engine, keeping the client, and under
+    /// JACK its name, for [`AudioHost::activate`] to start again. The output
+    /// comes back disconnected. A no-op if the stream isn't running.
+    fn deactivate(&mut self) -> Result<(), AudioError>;
+    /// Whether the server shut the stream down since the last call, e.g.
+    /// the JACK server went away. Reported once.
+    fn take_shutdown(&self) -> bool;
+}
+
+/// Open the backend `settings` ask for. [`HostApi::Auto`] tries JACK first
+/// and falls back to CPAL when there's no server to connect to; if neither
+/// opens, the JACK error is the one returned, as the more likely fix.
+pub fn open_backend(
+    settings: &AudioSettings,
+    xrun_count: Arc<AtomicU64>,
+    transport_events: Sender<TransportEvent>,
+) -> Result<Box<dyn AudioBackend>, AudioError> {
+    match settings.host_api {
+        HostApi::Jack => Ok(Box::new(JackHost::open(xrun_count, transport_events)?)),
+        HostApi::Cpal => Ok(Box::new(CpalHost::open(settings, xrun_count)?)),
+        HostApi::Auto => match JackHost::open(Arc::clone(&xrun_count), transport_events) {
+            Ok(host) => Ok(Box::new(host)),
+            Err(jack_error) => {
+                warn!("{jack_error}; falling back to the system's audio devices");
+                match CpalHost::open(settings, xrun_count) {
+                    Ok(host) => Ok(Box::new(host)),
+                    Err(cpal_error) => {
+                        warn!("{cpal_error}");
+                        Err(jack_error)
+                    }
+                }
+            }
+        },
+    }
+}
@@ -0,0 +1,271 @@
+use std::time::{Duration, Instant};
+
+use log::{error, warn};
+
+use crate::audio::error::AudioError;
+use crate::audio::processor::Processor;
+use crate::settings::AudioSettings;
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::amp::stages::filter::{FilterStage, FilterType};
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::denoiser::DenoiseConfig;
+use rustortion_core::audio::diagnostics::DiagnosticsHandle;
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::audio::dual_mono::{ChainMode, RightMessage};
+use rustortion_core::audio::engine::{Engine, EngineHandle, MonitorPoint};
+use rustortion_core::audio::recording_info::{RecordingMetadata, TakeMetronome};
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::ir::load_service::IrLoadHandle;
+use rustortion_core::metronome::MetronomeConfig;
+use rustortion_core::preset::stage_config::StageConfig;
+use rustortion_core::preset::{InputFilterConfig, Preset};
+
+/// How long a warm start waits for the selected IR to reach the engine before
+/// connecting ports anyway.
+pub const IR_WARM_START_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The parts of an audio stream the warm-start sequence touches. Abstracted so
+/// the ordering can be exercised without a running JACK server, and so the
+/// CPAL fallback goes through the same steps; see
+/// [`crate::audio::backend::AudioBackend`].
+pub trait AudioHost {
+    fn sample_rate(&self) -> usize;
+    fn buffer_size(&self) -> usize;
+    /// Hand the engine to the audio thread and start processing.
+    fn activate(&mut self, engine: Engine) -> Result<(), AudioError>;
+    fn connect_ports(&mut self, settings: &AudioSettings);
+}
+
+/// GUI-side state that a freshly built engine must receive before any of its
+/// audio is allowed to reach the speakers. Assembled by the app, which stays
+/// the source of truth, and consumed by the audio manager on restart.
+#[derive(Debug, Clone)]
+pub struct EngineBootstrap {
+    pub stages: Vec<StageConfig>,
+    /// The soloed stage, so a restart keeps the solo the stage card shows.
+    pub monitor_point: Option<MonitorPoint>,
+    pub input_filters: InputFilterConfig,
+    pub denoise: DenoiseConfig,
+    pub ir_name: Option<String>,
+    pub ir_alignment: IrAlignment,
+    pub ir_gain: f32,
+    /// Level-matching trim of the IR playing in an A/B/C comparison, in dB.
+    pub ir_trim_db: f32,
+    pub ir_bypassed: bool,
+    pub pitch_shift: i32,
+    pub ambience: AmbienceConfig,
+    pub double_tracker: DoubleTrackerConfig,
+    /// Global output tilt plus the preset's offset, in dB.
+    pub tilt_db: f32,
+    /// The preset's share of `tilt_db`.
+    pub tilt_offset_db: f32,
+    pub oversampling_factor: u32,
+    pub tuner_enabled: bool,
+    /// Whether the amp is on standby, so a restart doesn't switch it on.
+    pub standby: bool,
+    pub metronome: MetronomeConfig,
+    /// Whether a resumed recording gets a click track, see
+    /// [`TakeMetronome::click_track`].
+    pub record_click_track: bool,
+    /// Whether a resumed recording is soft-clipped, see
+    /// [`Recorder::with_softclip`](rustortion_core::audio::recorder::Recorder::with_softclip).
+    pub recording_softclip: bool,
+    /// Name of the selected preset, for a resumed recording's metadata.
+    pub preset_name: Option<String>,
+    /// Directory to resume recording into, if a recording was running.
+    pub recording_dir: Option<String>,
+}
+
+impl EngineBootstrap {
+    /// Queue the full engine state on `engine`. The IR is excluded: it is loaded
+    /// asynchronously by the IR load service, see [`warm_start`].
+    pub fn send(&self, engine: &EngineHandle, sample_rate: usize, buffer_size: usize) {
+        let (hp, lp) = build_input_filters(&self.input_filters, sample_rate as f32);
+        engine.set_input_filters(hp, lp);
+        engine.set_denoiser(&self.denoise, sample_rate);
+
+        let effective_rate = sample_rate * self.oversampling_factor as usize;
+        engine.set_amp_chain(build_amp_chain(&self.stages, effective_rate as f32));
+        engine.set_monitor_point(self.monitor_point);
+
+        engine.set_ir_bypass(self.ir_bypassed);
+        engine.set_ir_gain(self.ir_gain);
+        engine.set_ir_trim_db(self.ir_trim_db);
+        engine.set_pitch_shift(self.pitch_shift);
+        engine.set_ambience(self.ambience);
+        engine.set_double_tracker(self.double_tracker);
+        engine.set_tilt_db(self.tilt_db);
+        engine.set_tuner_enabled(self.tuner_enabled);
+        engine.set_standby(self.standby);
+        engine.set_metronome(self.metronome);
+        self.send_right(engine, sample_rate);
+
+        if let Some(dir) = &self.recording_dir {
+            let max_block_samples = buffer_size.max(Processor::MAX_BUFFER_FRAMES);
+            let metadata = self.recording_metadata();
+            if let Err(e) = engine.start_recording(
+                sample_rate,
+                dir,
+                max_block_samples,
+                None,
+                Some(metadata),
+                self.recording_softclip,
+            ) {
+                error!("Failed to resume recording after restart: {e}");
+            }
+        }
+    }
+
+    /// What a resumed recording is tagged with.
+    fn recording_metadata(&self) -> RecordingMetadata {
+        let metadata = RecordingMetadata::new(self.preset());
+        if !self.metronome.enabled {
+            return metadata;
+        }
+        metadata.with_metronome(TakeMetronome {
+            bpm: self.metronome.bpm,
+            beats_per_bar: self.metronome.beats_per_bar,
+            click_track: self.record_click_track,
+        })
+    }
+
+    /// The state as a preset, as `AmplifierApp` would save it.
+    fn preset(&self) -> Preset {
+        Preset {
+            name: self.preset_name.clone().unwrap_or_default(),
+            stages: self.stages.clone(),
+            ir_name: self.ir_name.clone().filter(|_| !self.ir_bypassed),
+            ir_gain: self.ir_gain,
+            ir_alignment: self.ir_alignment,
+            pitch_shift_semitones: self.pitch_shift,
+            input_filters: self.input_filters,
+            denoise: self.denoise.clone(),
+            ambience: self.ambience,
+            double_tracker: self.double_tracker,
+            tilt_offset_db: self.tilt_offset_db,
+            ..Preset::default()
+        }
+    }
+
+    /// Queue the right channel's own chain and input filters, in dual-mono.
+    /// Its IR comes from the IR load service with the left's.
+    pub fn send_right(&self, engine: &EngineHandle, sample_rate: usize) {
+        if engine.chain_mode() == ChainMode::Mono {
+            return;
+        }
+        let (hp, lp) = build_input_filters(&self.input_filters, sample_rate as f32);
+        engine.send_right(RightMessage::SetInputFilters(hp, lp));
+
+        let effective_rate = sample_rate * self.oversampling_factor as usize;
+        engine.send_right(RightMessage::SetAmpChain(Box::new(build_amp_chain(
+            &self.stages,
+            effective_rate as f32,
+        ))));
+    }
+}
+
+/// Bring a new engine online behind `host` without ever letting unprocessed
+/// audio out: activate and re-send the GUI state. The ports are left for the
+/// returned [`PendingPorts`] to connect once the IR is in, so the caller
+/// doesn't block on the load.
+///
+/// The engine drains its message queue at the top of every cycle, so anything
+/// queued before the ports are connected is applied before the first audible
+/// block.
+pub fn warm_start<H: AudioHost + ?Sized>(
+    host: &mut H,
+    engine: Engine,
+    engine_handle: &EngineHandle,
+    ir_load: Option<&IrLoadHandle>,
+    bootstrap: &EngineBootstrap,
+) -> Result<PendingPorts, AudioError> {
+    let diagnostics = engine.diagnostics();
+    let sample_rate = host.sample_rate();
+    let buffer_size = host.buffer_size();
+
+    host.activate(engine)?;
+    bootstrap.send(engine_handle, sample_rate, buffer_size);
+
+    let ir_name = match (&bootstrap.ir_name, ir_load) {
+        (Some(name), Some(ir_load)) => {
+            ir_load.request_load(name, bootstrap.ir_alignment);
+            Some(name.clone())
+        }
+        _ => None,
+    };
+
+    Ok(PendingPorts {
+        diagnostics,
+        ir_name,
+        deadline: Instant::now() + IR_WARM_START_TIMEOUT,
+    })
+}
+
+/// The ports of a warm-started stream, held back until the selected IR has
+/// reached the engine or [`IR_WARM_START_TIMEOUT`] has passed.
+pub struct PendingPorts {
+    diagnostics: DiagnosticsHandle,
+    /// The IR being waited for; `None` connects straight away.
+    ir_name: Option<String>,
+    deadline: Instant,
+}
+
+impl PendingPorts {
+    fn ir_loaded(&self) -> bool {
+        self.ir_name.is_none() || self.diagnostics.snapshot().ir_length > 0
+    }
+
+    /// Whether the ports can be connected at `now`.
+    pub fn ready(&self, now: Instant) -> bool {
+        now >= self.deadline || self.ir_loaded()
+    }
+
+    /// Connect the ports, whether or not the IR made it in time.
+    pub fn connect<H: AudioHost + ?Sized>(self, host: &mut H, ports: &AudioSettings) {
+        if let Some(name) = &self.ir_name
+            && !self.ir_loaded()
+        {
+            warn!("IR '{name}' not loaded after {IR_WARM_START_TIMEOUT:?}, connecting anyway");
+        }
+        host.connect_ports(ports);
+    }
+}
+
+pub fn build_amp_chain(stages: &[StageConfig], sample_rate: f32) -> AmplifierChain {
+    let mut chain = AmplifierChain::new();
+    for cfg in stages {
+        chain.add_stage(cfg.to_runtime(sample_rate));
+    }
+    for (i, cfg) in stages.iter().enumerate() {
+        if cfg.bypassed() {
+            chain.set_bypassed(i, true);
+        }
+    }
+    chain
+}
+
+pub fn build_input_filters(
+    filter: &InputFilterConfig,
+    sample_rate: f32,
+) -> (Option<Box<dyn Stage>>, Option<Box<dyn Stage>>) {
+    let hp: Option<Box<dyn Stage>> = if filter.hp_enabled {
+        Some(Box::new(FilterStage::new(
+            FilterType::Highpass,
+            filter.hp_cutoff,
+            sample_rate,
+        )))
+    } else {
+        None
+    };
+    let lp: Option<Box<dyn Stage>> = if filter.lp_enabled {
+        Some(Box::new(FilterStage::new(
+            FilterType::Lowpass,
+            filter.lp_cutoff,
+            sample_rate,
+        )))
+    } else {
+        None
+    };
+    (hp, lp)
+}
@@ -0,0 +1,784 @@
+//! Fallback audio through CPAL (ALSA, CoreAudio, WASAPI) for machines with
+//! no JACK server. Where JACK routes ports, this opens a capture and a
+//! playback device: the first two capture channels are the inputs and the
+//! first two playback channels the outputs. There's no port graph, no
+//! transport and no separate metronome output, and the latency is fixed when
+//! the streams open.
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{
+    BufferSize, Device, Host, SampleFormat, SampleRate, Stream, StreamConfig, SupportedBufferSize,
+    SupportedStreamConfigRange,
+};
+use crossbeam::channel::{Sender, bounded};
+use crossbeam::queue::ArrayQueue;
+use log::{error, info, warn};
+
+use crate::audio::backend::{AudioBackend, BackendKind};
+use crate::audio::bootstrap::AudioHost;
+use crate::audio::error::AudioError;
+use crate::audio::freewheel::InputFeed;
+use crate::audio::processor::Processor;
+use crate::settings::AudioSettings;
+use rustortion_core::audio::engine::Engine;
+
+/// Smallest engine block; the settings' buffer size is clamped to at least
+/// this.
+const MIN_BLOCK_FRAMES: usize = 16;
+
+/// Capture frames that can wait for the playback side before the oldest are
+/// dropped.
+const INPUT_QUEUE_FRAMES: usize = 4 * Processor::MAX_BUFFER_FRAMES;
+
+/// Weight of the newest callback in the smoothed DSP load.
+const LOAD_SMOOTHING: f32 = 0.1;
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Input,
+    Output,
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Input => write!(f, "input"),
+            Self::Output => write!(f, "output"),
+        }
+    }
+}
+
+/// A capture and a playback device running the engine between them.
+pub struct CpalHost {
+    /// As in the settings: empty for the system default. Looked up again on
+    /// the stream thread, which owns the devices.
+    input_device: String,
+    output_device: String,
+    input_config: StreamConfig,
+    output_config: StreamConfig,
+    /// Frames per engine block: the buffer size asked for in the settings,
+    /// whatever period the devices run at.
+    block_frames: usize,
+    shared: Arc<Shared>,
+    running: Option<RunningStreams>,
+}
+
+/// What the callbacks share with the host.
+struct Shared {
+    xrun_count: Arc<AtomicU64>,
+    /// Smoothed DSP load in percent, as `f32` bits.
+    cpu_load: AtomicU32,
+    /// Cleared by `disconnect_all`; the output plays silence until the warm
+    /// start connects it.
+    connected: AtomicBool,
+    /// Most frames one capture callback has delivered, which the playback
+    /// side keeps queued on top of a block so it doesn't run dry between
+    /// them.
+    input_chunk: AtomicUsize,
+}
+
+/// The thread that owns the streams, which CPAL doesn't let cross threads.
+/// Dropping it stops the streams and frees the engine.
+struct RunningStreams {
+    stop: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for RunningStreams {
+    fn drop(&mut self) {
+        // Already gone if the thread exited on its own.
+        let _ = self.stop.send(());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            error!("CPAL stream thread panicked");
+        }
+    }
+}
+
+impl CpalHost {
+    /// Find the devices and settle on a format both run: 32-bit float at the
+    /// settings' sample rate if they can, else at the playback device's
+    /// default. The streams open on [`AudioHost::activate`].
+    pub fn open(settings: &AudioSettings, xrun_count: Arc<AtomicU64>) -> Result<Self, AudioError> {
+        let host = cpal::default_host();
+        let input = find_device(&host, &settings.input_device, Direction::Input)?;
+        let output = find_device(&host, &settings.output_device, Direction::Output)?;
+
+        let input_formats = float_formats(input.supported_input_configs(), Direction::Input)?;
+        let output_formats = float_formats(output.supported_output_configs(), Direction::Output)?;
+        let default_rate = output
+            .default_output_config()
+            .map_err(|e| AudioError::Device(e.to_string()))?
+            .sample_rate()
+            .0;
+
+        let block_frames =
+            (settings.buffer_size as usize).clamp(MIN_BLOCK_FRAMES, Processor::MAX_BUFFER_FRAMES);
+        let (rate, input_format, output_format) = pick_formats(
+            settings.sample_rate,
+            default_rate,
+            &input_formats,
+            &output_formats,
+        )
+        .ok_or_else(|| {
+            AudioError::Device(format!(
+                "'{}' and '{}' share no 32-bit float sample rate",
+                device_name(&input),
+                device_name(&output)
+            ))
+        })?;
+        let input_config = input_format.stream_config(rate, block_frames);
+        let output_config = output_format.stream_config(rate, block_frames);
+
+        info!(
+            "CPAL: '{}' ({} ch) -> '{}' ({} ch) at {rate} Hz, {block_frames}-frame blocks",
+            device_name(&input),
+            input_config.channels,
+            device_name(&output),
+            output_config.channels
+        );
+        if rate != settings.sample_rate {
+            warn!(
+                "CPAL devices can't run at {} Hz; using {rate} Hz",
+                settings.sample_rate
+            );
+        }
+
+        Ok(Self {
+            input_device: settings.input_device.clone(),
+            output_device: settings.output_device.clone(),
+            input_config,
+            output_config,
+            block_frames,
+            shared: Arc::new(Shared {
+                xrun_count,
+                cpu_load: AtomicU32::new(0.0f32.to_bits()),
+                connected: AtomicBool::new(false),
+                input_chunk: AtomicUsize::new(0),
+            }),
+            running: None,
+        })
+    }
+
+    /// Build and start both streams. Runs on the stream thread, which keeps
+    /// them alive.
+    fn start_streams(
+        input_name: &str,
+        output_name: &str,
+        input_config: &StreamConfig,
+        output_config: &StreamConfig,
+        mut duplex: Duplex,
+    ) -> Result<(Stream, Stream), AudioError> {
+        let host = cpal::default_host();
+        let input = find_device(&host, input_name, Direction::Input)?;
+        let output = find_device(&host, output_name, Direction::Output)?;
+
+        let queue = Arc::clone(&duplex.input.queue);
+        let shared = Arc::clone(&duplex.shared);
+        let input_channels = usize::from(input_config.channels);
+        let input_stream = input
+            .build_input_stream(
+                input_config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    capture(data, input_channels, &queue, &shared);
+                },
+                |e| error!("CPAL input stream error: {e}"),
+                None,
+            )
+            .map_err(|e| AudioError::Device(format!("input stream: {e}")))?;
+
+        let output_channels = usize::from(output_config.channels);
+        let output_stream = output
+            .build_output_stream(
+                output_config,
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    duplex.fill(data, output_channels);
+                },
+                |e| error!("CPAL output stream error: {e}"),
+                None,
+            )
+            .map_err(|e| AudioError::Device(format!("output stream: {e}")))?;
+
+        input_stream
+            .play()
+            .map_err(|e| AudioError::Device(format!("input stream: {e}")))?;
+        output_stream
+            .play()
+            .map_err(|e| AudioError::Device(format!("output stream: {e}")))?;
+
+        Ok((input_stream, output_stream))
+    }
+}
+
+impl AudioHost for CpalHost {
+    fn sample_rate(&self) -> usize {
+        self.output_config.sample_rate.0 as usize
+    }
+
+    fn buffer_size(&self) -> usize {
+        self.block_frames
+    }
+
+    fn activate(&mut self, engine: Engine) -> Result<(), AudioError> {
+        if self.running.is_some() {
+            return Err(AudioError::AlreadyActive);
+        }
+
+        let duplex = Duplex::new(
+            engine,
+            self.block_frames,
+            self.sample_rate(),
+            Arc::clone(&self.shared),
+        );
+        let input_name = self.input_device.clone();
+        let output_name = self.output_device.clone();
+        let input_config = self.input_config.clone();
+        let output_config = self.output_config.clone();
+        let (ready_tx, ready_rx) = bounded(1);
+        let (stop_tx, stop_rx) = bounded::<()>(1);
+
+        let thread = std::thread::Builder::new()
+            .name("cpal-audio".into())
+            .spawn(move || {
+                match Self::start_streams(
+                    &input_name,
+                    &output_name,
+                    &input_config,
+                    &output_config,
+                    duplex,
+                ) {
+                    Ok(streams) => {
+                        let _ = ready_tx.send(Ok(()));
+                        // Either a stop or the host going away ends it.
+                        let _ = stop_rx.recv();
+                        drop(streams);
+                    }
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                    }
+                }
+            })
+            .map_err(|e| AudioError::Device(format!("stream thread: {e}")))?;
+
+        let started = ready_rx
+            .recv()
+            .unwrap_or_else(|_| Err(AudioError::Device("stream thread exited".into())));
+        let running = RunningStreams {
+            stop: stop_tx,
+            thread: Some(thread),
+        };
+        started?;
+        self.running = Some(running);
+        Ok(())
+    }
+
+    // The devices were picked when the host opened; connecting only lets the
+    // output through.
+    fn connect_ports(&mut self, _settings: &AudioSettings) {
+        self.shared.connected.store(true, Ordering::Relaxed);
+    }
+}
+
+impl AudioBackend for CpalHost {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Cpal
+    }
+
+    fn is_active(&self) -> bool {
+        self.running.is_some()
+    }
+
+    fn cpu_load(&self) -> f32 {
+        f32::from_bits(self.shared.cpu_load.load(Ordering::Relaxed))
+    }
+
+    // The period is fixed when the streams open.
+    fn request_buffer_size(&self, _frames: u32) {}
+
+    fn disconnect_all(&self) {
+        self.shared.connected.store(false, Ordering::Relaxed);
+    }
+
+    fn available_inputs(&self) -> Vec<String> {
+        input_devices()
+    }
+
+    fn available_outputs(&self) -> Vec<String> {
+        output_devices()
+    }
+
+    // The device clocks the stream; there's no running ahead of it.
+    fn set_freewheel(&self, _enabled: bool) -> Result<(), AudioError> {
+        Err(AudioError::Freewheel("not available on CPAL".into()))
+    }
+
+    fn input_feed(&self) -> Option<Arc<InputFeed>> {
+        None
+    }
+
+    // The devices and format stay picked; the next activation reopens them.
+    fn deactivate(&mut self) -> Result<(), AudioError> {
+        self.shared.connected.store(false, Ordering::Relaxed);
+        self.running = None;
+        Ok(())
+    }
+
+    // No server to lose: a device that goes away shows up as a stall.
+    fn take_shutdown(&self) -> bool {
+        false
+    }
+}
+
+/// Runs on the capture callback: queue each frame's first two channels for
+/// the playback side. A full queue drops the frame and counts an xrun.
+fn capture(data: &[f32], channels: usize, queue: &ArrayQueue<(f32, f32)>, shared: &Shared) {
+    shared
+        .input_chunk
+        .fetch_max(data.len() / channels, Ordering::Relaxed);
+    let mut overrun = false;
+    for frame in data.chunks(channels) {
+        let right = frame.get(1).copied().unwrap_or(0.0);
+        overrun |= queue.push((frame[0], right)).is_err();
+    }
+    if overrun {
+        shared.xrun_count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The playback callback's state: the engine, run in fixed blocks whatever
+/// size of buffer the device asks to have filled.
+struct Duplex {
+    engine: Engine,
+    input: InputBlocks,
+    shared: Arc<Shared>,
+    in_left: Vec<f32>,
+    in_right: Vec<f32>,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    metronome: Vec<f32>,
+    /// Next frame of the processed block to play.
+    pos: usize,
+    sample_rate: f32,
+}
+
+impl Duplex {
+    fn new(engine: Engine, block_frames: usize, sample_rate: usize, shared: Arc<Shared>) -> Self {
+        Self {
+            engine,
+            input: InputBlocks::new(Arc::clone(&shared)),
+            shared,
+            in_left: vec![0.0; block_frames],
+            in_right: vec![0.0; block_frames],
+            left: vec![0.0; block_frames],
+            right: vec![0.0; block_frames],
+            metronome: vec![0.0; block_frames],
+            pos: block_frames,
+            sample_rate: sample_rate as f32,
+        }
+    }
+
+    /// Fill the device's interleaved buffer, processing a new block whenever
+    /// the last one has been played.
+    fn fill(&mut self, data: &mut [f32], channels: usize) {
+        let started = Instant::now();
+        for frame in data.chunks_mut(channels) {
+            if self.pos == self.left.len() {
+                self.process_block();
+                self.pos = 0;
+            }
+            write_frame(frame, self.left[self.pos], self.right[self.pos]);
+            self.pos += 1;
+        }
+
+        let frames = data.len() / channels;
+        if frames > 0 {
+            let load = started.elapsed().as_secs_f32() * self.sample_rate / frames as f32 * 100.0;
+            let previous = f32::from_bits(self.shared.cpu_load.load(Ordering::Relaxed));
+            let smoothed = (load - previous).mul_add(LOAD_SMOOTHING, previous);
+            self.shared
+                .cpu_load
+                .store(smoothed.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    fn process_block(&mut self) {
+        // Runs on silence until the queue is primed, so the engine keeps
+        // taking its messages and the warm start sees the IR arrive.
+        self.input.read(&mut self.in_left, &mut self.in_right);
+
+        if let Err(e) = self.engine.process_stereo_input(
+            &self.in_left,
+            &self.in_right,
+            &mut self.left,
+            &mut self.right,
+        ) {
+            error!("Audio processing error: {e}");
+            self.left.fill(0.0);
+            self.right.fill(0.0);
+            return;
+        }
+        // No port of its own to go to: the click is always in the outputs.
+        // The take was recorded inside `process`, so it stays clean.
+        if self.engine.process_metronome(&mut self.metronome) {
+            for ((left, right), click) in self
+                .left
+                .iter_mut()
+                .zip(self.right.iter_mut())
+                .zip(&self.metronome)
+            {
+                *left += click;
+                *right += click;
+            }
+        }
+
+        if !self.shared.connected.load(Ordering::Relaxed) {
+            self.left.fill(0.0);
+            self.right.fill(0.0);
+        }
+    }
+}
+
+/// One interleaved output frame: left and right on the first two channels,
+/// left alone on a mono device, and the rest silent.
+fn write_frame(frame: &mut [f32], left: f32, right: f32) {
+    if let Some((first, rest)) = frame.split_first_mut() {
+        *first = left;
+        if let Some((second, rest)) = rest.split_first_mut() {
+            *second = right;
+            rest.fill(0.0);
+        }
+    }
+}
+
+/// Captured frames, cut into engine blocks. Reading waits until a block
+/// plus the largest capture chunk is queued, so the playback side doesn't
+/// run dry between captures; that's the fixed latency the fallback adds. A
+/// backlog grown past twice that, from the two devices' clocks drifting, is
+/// dropped back to it.
+struct InputBlocks {
+    queue: Arc<ArrayQueue<(f32, f32)>>,
+    shared: Arc<Shared>,
+    primed: bool,
+}
+
+impl InputBlocks {
+    fn new(shared: Arc<Shared>) -> Self {
+        Self {
+            queue: Arc::new(ArrayQueue::new(INPUT_QUEUE_FRAMES)),
+            shared,
+            primed: false,
+        }
+    }
+
+    /// Fill `left` and `right` with the next block, or silence while the
+    /// queue primes. Running dry or dropping a backlog counts an xrun.
+    fn read(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let block = left.len();
+        let target = block + self.shared.input_chunk.load(Ordering::Relaxed);
+        let queued = self.queue.len();
+
+        if self.primed && queued < block {
+            self.primed = false;
+            self.shared.xrun_count.fetch_add(1, Ordering::Relaxed);
+        } else if !self.primed && queued >= target {
+            self.primed = true;
+        }
+        if !self.primed {
+            left.fill(0.0);
+            right.fill(0.0);
+            return;
+        }
+
+        if queued > 2 * target {
+            for _ in target..queued {
+                self.queue.pop();
+            }
+            self.shared.xrun_count.fetch_add(1, Ordering::Relaxed);
+        }
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            (*l, *r) = self.queue.pop().unwrap_or((0.0, 0.0));
+        }
+    }
+}
+
+/// A stream format a device offers in 32-bit float: a channel count over a
+/// range of rates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Format {
+    channels: u16,
+    min_rate: u32,
+    max_rate: u32,
+    buffer_size: SupportedBufferSize,
+}
+
+impl Format {
+    fn from_range(range: &SupportedStreamConfigRange) -> Option<Self> {
+        (range.sample_format() == SampleFormat::F32).then(|| Self {
+            channels: range.channels(),
+            min_rate: range.min_sample_rate().0,
+            max_rate: range.max_sample_rate().0,
+            buffer_size: *range.buffer_size(),
+        })
+    }
+
+    const fn supports(&self, rate: u32) -> bool {
+        self.min_rate <= rate && rate <= self.max_rate
+    }
+
+    /// The period is the engine block when the device allows it, and the
+    /// device's own otherwise.
+    fn stream_config(&self, rate: u32, block_frames: usize) -> StreamConfig {
+        let frames = block_frames as u32;
+        let buffer_size = match self.buffer_size {
+            SupportedBufferSize::Range { min, max } if (min..=max).contains(&frames) => {
+                BufferSize::Fixed(frames)
+            }
+            _ => BufferSize::Default,
+        };
+        StreamConfig {
+            channels: self.channels,
+            sample_rate: SampleRate(rate),
+            buffer_size,
+        }
+    }
+}
+
+/// The format to run at `rate`: stereo or more if there is one, with as few
+/// channels as will do.
+fn pick_format(formats: &[Format], rate: u32) -> Option<Format> {
+    formats
+        .iter()
+        .filter(|format| format.supports(rate))
+        .min_by_key(|format| (format.channels < 2, format.channels))
+        .copied()
+}
+
+/// The first of `wanted` and `fallback` both devices run at, with the
+/// formats to run it in.
+fn pick_formats(
+    wanted: u32,
+    fallback: u32,
+    input: &[Format],
+    output: &[Format],
+) -> Option<(u32, Format, Format)> {
+    [wanted, fallback]
+        .into_iter()
+        .find_map(|rate| Some((rate, pick_format(input, rate)?, pick_format(output, rate)?)))
+}
+
+fn float_formats<I>(
+    ranges: Result<I, cpal::SupportedStreamConfigsError>,
+    direction: Direction,
+) -> Result<Vec<Format>, AudioError>
+where
+    I: Iterator<Item = SupportedStreamConfigRange>,
+{
+    let formats: Vec<Format> = ranges
+        .map_err(|e| AudioError::Device(format!("{direction} formats: {e}")))?
+        .filter_map(|range| Format::from_range(&range))
+        .collect();
+    if formats.is_empty() {
+        return Err(AudioError::Device(format!(
+            "the {direction} device has no 32-bit float format"
+        )));
+    }
+    Ok(formats)
+}
+
+/// The device called `name`, or the system default when it's empty.
+fn find_device(host: &Host, name: &str, direction: Direction) -> Result<Device, AudioError> {
+    if name.is_empty() {
+        let device = match direction {
+            Direction::Input => host.default_input_device(),
+            Direction::Output => host.default_output_device(),
+        };
+        return device.ok_or_else(|| AudioError::Device(format!("no default {direction} device")));
+    }
+    devices(host, direction)?
+        .find(|device| device.name().is_ok_and(|n| n == name))
+        .ok_or_else(|| AudioError::Device(format!("{direction} device '{name}' not found")))
+}
+
+fn devices(
+    host: &Host,
+    direction: Direction,
+) -> Result<Box<dyn Iterator<Item = Device>>, AudioError> {
+    let devices: Box<dyn Iterator<Item = Device>> = match direction {
+        Direction::Input => Box::new(host.input_devices().map_err(device_error)?),
+        Direction::Output => Box::new(host.output_devices().map_err(device_error)?),
+    };
+    Ok(devices)
+}
+
+/// Names of the devices that can capture, for the settings' device pickers.
+/// Listed whichever backend is running, so CPAL can be picked from JACK.
+pub fn input_devices() -> Vec<String> {
+    device_names(Direction::Input)
+}
+
+/// Names of the devices that can play; see [`input_devices`].
+pub fn output_devices() -> Vec<String> {
+    device_names(Direction::Output)
+}
+
+fn device_names(direction: Direction) -> Vec<String> {
+    match devices(&cpal::default_host(), direction) {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(e) => {
+            warn!("Failed to list {direction} devices: {e}");
+            Vec::new()
+        }
+    }
+}
+
+fn device_name(device: &Device) -> String {
+    device.name().unwrap_or_else(|_| "unknown device".into())
+}
+
+fn device_error(error: cpal::DevicesError) -> AudioError {
+    AudioError::Device(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shared() -> Arc<Shared> {
+        Arc::new(Shared {
+            xrun_count: Arc::new(AtomicU64::new(0)),
+            cpu_load: AtomicU32::new(0),
+            connected: AtomicBool::new(true),
+            input_chunk: AtomicUsize::new(0),
+        })
+    }
+
+    fn format(channels: u16, min_rate: u32, max_rate: u32) -> Format {
+        Format {
+            channels,
+            min_rate,
+            max_rate,
+            buffer_size: SupportedBufferSize::Range { min: 32, max: 1024 },
+        }
+    }
+
+    #[test]
+    fn picks_the_wanted_rate_both_devices_run() {
+        let input = [format(1, 44100, 96000), format(2, 44100, 96000)];
+        let output = [format(8, 44100, 48000), format(2, 44100, 48000)];
+        let (rate, input, output) = pick_formats(48000, 44100, &input, &output).unwrap();
+        assert_eq!(rate, 48000);
+        assert_eq!(input.channels, 2);
+        assert_eq!(output.channels, 2);
+
+        // The output can't do 96 kHz, so the default rate is used.
+        let (rate, ..) = pick_formats(
+            96000,
+            44100,
+            &[format(1, 44100, 96000)],
+            &[format(2, 44100, 48000)],
+        )
+        .unwrap();
+        assert_eq!(rate, 44100);
+
+        assert!(
+            pick_formats(
+                96000,
+                96000,
+                &[format(1, 96000, 96000)],
+                &[format(2, 44100, 48000)]
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn period_is_the_block_when_the_device_allows_it() {
+        assert_eq!(
+            format(2, 48000, 48000)
+                .stream_config(48000, 128)
+                .buffer_size,
+            BufferSize::Fixed(128)
+        );
+        assert_eq!(
+            format(2, 48000, 48000)
+                .stream_config(48000, 4096)
+                .buffer_size,
+            BufferSize::Default
+        );
+    }
+
+    #[test]
+    fn frames_go_to_the_first_two_channels() {
+        let mut frame = [1.0; 4];
+        write_frame(&mut frame, 0.25, 0.5);
+        assert_eq!(frame, [0.25, 0.5, 0.0, 0.0]);
+
+        let mut mono = [1.0];
+        write_frame(&mut mono, 0.25, 0.5);
+        assert_eq!(mono, [0.25]);
+    }
+
+    #[test]
+    fn input_blocks_prime_before_reading() {
+        let shared = shared();
+        let mut blocks = InputBlocks::new(Arc::clone(&shared));
+        let (mut left, mut right) = (vec![1.0; 4], vec![1.0; 4]);
+
+        // Captures come four frames at a time: a block plus one capture is
+        // queued before anything is read.
+        let mut next = 0.0;
+        let mut deliver = |blocks: &InputBlocks, frames: usize| {
+            let data: Vec<f32> = (0..frames * 2)
+                .map(|i| {
+                    if i % 2 == 0 {
+                        next += 1.0;
+                        next
+                    } else {
+                        -next
+                    }
+                })
+                .collect();
+            capture(&data, 2, &blocks.queue, &shared);
+        };
+        deliver(&blocks, 4);
+        blocks.read(&mut left, &mut right);
+        assert_eq!(left, [0.0; 4]);
+
+        deliver(&blocks, 4);
+        blocks.read(&mut left, &mut right);
+        assert_eq!(left, [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(right, [-1.0, -2.0, -3.0, -4.0]);
+        assert_eq!(shared.xrun_count.load(Ordering::Relaxed), 0);
+
+        // Running dry is an xrun, and reading waits to prime again.
+        blocks.read(&mut left, &mut right);
+        blocks.read(&mut left, &mut right);
+        assert_eq!(left, [0.0; 4]);
+        assert_eq!(shared.xrun_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn input_blocks_drop_a_drifted_backlog() {
+        let shared = shared();
+        let mut blocks = InputBlocks::new(Arc::clone(&shared));
+        let (mut left, mut right) = (vec![0.0; 4], vec![0.0; 4]);
+
+        // Ten captures of four frames pile up unread.
+        let data: Vec<f32> = (0..40).map(|i| i as f32).collect();
+        for chunk in data.chunks(4) {
+            capture(chunk, 1, &blocks.queue, &shared);
+        }
+        blocks.read(&mut left, &mut right);
+        // 40 queued against a target of 8: the oldest 32 go.
+        assert_eq!(left, [32.0, 33.0, 34.0, 35.0]);
+        assert_eq!(blocks.queue.len(), 4);
+        assert_eq!(shared.xrun_count.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+
+use jack::ClientStatus;
+
+/// Why the audio stream couldn't be opened, started or rebuilt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioError {
+    /// No JACK (or PipeWire-JACK) server to connect to.
+    ServerNotFound,
+    /// The server was reachable but refused the client.
+    Client(String),
+    /// One of our ports couldn't be registered.
+    Port { name: &'static str, reason: String },
+    /// The client was opened but the server wouldn't start calling it.
+    Activation(String),
+    /// `activate` was called on a client that is already running.
+    AlreadyActive,
+    /// The engine couldn't be built for the server's sample rate or period,
+    /// e.g. the resampler rejected the oversampling factor.
+    Engine(String),
+    /// A CPAL device couldn't be found or opened, or has no stream format
+    /// the engine can run on.
+    Device(String),
+    /// Freewheel mode couldn't be switched, or the backend has none.
+    Freewheel(String),
+}
+
+impl AudioError {
+    /// Classify a failure to open a client. A missing server is reported as
+    /// such so the GUI can tell the user to start one.
+    pub fn client(error: &jack::Error) -> Self {
+        match error {
+            jack::Error::ClientError(status)
+                if status.intersects(ClientStatus::SERVER_FAILED | ClientStatus::SERVER_ERROR) =>
+            {
+                Self::ServerNotFound
+            }
+            other => Self::Client(other.to_string()),
+        }
+    }
+
+    pub fn port(name: &'static str, error: &jack::Error) -> Self {
+        Self::Port {
+            name,
+            reason: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ServerNotFound => write!(f, "JACK server not found"),
+            Self::Client(reason) => write!(f, "Failed to create JACK client: {reason}"),
+            Self::Port { name, reason } => write!(f, "Failed to register port {name}: {reason}"),
+            Self::Activation(reason) => write!(f, "Failed to activate JACK client: {reason}"),
+            Self::AlreadyActive => write!(f, "JACK client already activated"),
+            Self::Engine(reason) => write!(f, "Failed to build audio engine: {reason}"),
+            Self::Device(reason) => write!(f, "Failed to open audio device: {reason}"),
+            Self::Freewheel(reason) => write!(f, "Failed to switch freewheel mode: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
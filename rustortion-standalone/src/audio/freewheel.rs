@@ -0,0 +1,238 @@
+//! Bouncing a file through the live chain faster than realtime. The file is
+//! fed to the processor in place of the input (or on top of it), the
+//! recorder takes the output, and JACK's freewheel mode runs the callback as
+//! fast as the chain allows instead of once per period.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use log::{error, info};
+
+/// Silence run through the chain after the file, so reverb and delay tails
+/// make it into the take.
+pub const BOUNCE_TAIL_SECONDS: f32 = 2.0;
+
+/// A file played into the processor's input. Loaded and unloaded from the
+/// GUI; the callback only reads it and never waits on the lock, which the
+/// GUI takes only while the take isn't rolling.
+#[derive(Default)]
+pub struct InputFeed {
+    samples: Mutex<Option<Arc<[f32]>>>,
+    loaded: AtomicBool,
+    /// Play the live input under the file rather than replacing it.
+    mix: AtomicBool,
+    /// The file's length plus the tail, in frames.
+    total: AtomicUsize,
+    /// Frames fed so far.
+    position: AtomicUsize,
+}
+
+impl InputFeed {
+    /// Queue `samples` followed by `tail` frames of silence. The feed holds
+    /// at the start until the take is rolling; see [`Self::fill`].
+    pub fn load(&self, samples: Arc<[f32]>, tail: usize, mix: bool) {
+        let total = samples.len() + tail;
+        *self.lock_samples() = Some(samples);
+        self.mix.store(mix, Ordering::Relaxed);
+        self.total.store(total, Ordering::Relaxed);
+        self.position.store(0, Ordering::Relaxed);
+        self.loaded.store(true, Ordering::Release);
+    }
+
+    /// Hand the input back to the ports. The file is dropped here, on the
+    /// caller's thread.
+    pub fn unload(&self) {
+        self.loaded.store(false, Ordering::Release);
+        let samples = self.lock_samples().take();
+        drop(samples);
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded.load(Ordering::Acquire)
+    }
+
+    /// Frames fed so far, and the file's length plus the tail.
+    pub fn progress(&self) -> (usize, usize) {
+        (
+            self.position.load(Ordering::Relaxed),
+            self.total.load(Ordering::Relaxed),
+        )
+    }
+
+    /// The file and its tail have all been fed.
+    pub fn is_finished(&self) -> bool {
+        let (position, total) = self.progress();
+        self.is_loaded() && position >= total
+    }
+
+    /// Fill `out` with this cycle's input: the file where it's playing, on
+    /// top of `live` when mixing. The file only advances while `rolling`,
+    /// so its first frame lands on the take's first frame.
+    pub fn fill(&self, live: &[f32], out: &mut [f32], rolling: bool) {
+        out.fill(0.0);
+        if self.mix.load(Ordering::Relaxed) {
+            for (out, live) in out.iter_mut().zip(live) {
+                *out = *live;
+            }
+        }
+        if !rolling {
+            return;
+        }
+        let Ok(samples) = self.samples.try_lock() else {
+            return;
+        };
+        let Some(samples) = samples.as_deref() else {
+            return;
+        };
+
+        let position = self.position.load(Ordering::Relaxed);
+        let file = samples.get(position..).unwrap_or_default();
+        for (out, sample) in out.iter_mut().zip(file) {
+            *out += sample;
+        }
+        let total = self.total.load(Ordering::Relaxed);
+        self.position
+            .store((position + out.len()).min(total), Ordering::Relaxed);
+    }
+
+    fn lock_samples(&self) -> MutexGuard<'_, Option<Arc<[f32]>>> {
+        self.samples.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// What a bounce needs from around the stream. The app's audio manager in
+/// the GUI, a stand-in in tests.
+pub trait BounceHost {
+    /// The processor's feed, or `None` where the backend can't freewheel.
+    fn input_feed(&self) -> Option<Arc<InputFeed>>;
+    fn set_freewheel(&self, enabled: bool) -> Result<(), String>;
+    /// Start the take the bounce is captured into.
+    fn start_take(&mut self) -> Result<PathBuf, String>;
+    fn stop_take(&mut self);
+    /// Blocks the running take has lost so far.
+    fn take_overruns(&self) -> u64;
+    /// Silence the metronome, tuner and test source for the bounce, or put
+    /// them back as they were.
+    fn hold_monitoring(&mut self, held: bool);
+}
+
+/// Why a bounce didn't start, or came out incomplete. Whatever had been set
+/// up is undone first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BounceError {
+    /// A take is already being recorded; the bounce would need the recorder.
+    Recording,
+    /// The backend has no freewheel mode, e.g. CPAL.
+    Unsupported,
+    /// The take the bounce records into couldn't be started.
+    Take(String),
+    /// The server refused to freewheel.
+    Freewheel(String),
+    /// Blocks were lost on the way to the file, which was kept anyway.
+    Gapped { take: PathBuf, dropped: u64 },
+}
+
+impl fmt::Display for BounceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Recording => write!(f, "Stop the recording before bouncing"),
+            Self::Unsupported => write!(f, "Bouncing needs the JACK backend"),
+            Self::Take(reason) => write!(f, "Failed to start the take: {reason}"),
+            Self::Freewheel(reason) => write!(f, "Failed to enter freewheel mode: {reason}"),
+            Self::Gapped { take, dropped } => write!(
+                f,
+                "The bounce lost {dropped} blocks on the way to {}",
+                take.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BounceError {}
+
+/// A file being bounced. Started and ended from the GUI, which polls it in
+/// between; the callback does the rest.
+pub struct Bounce {
+    feed: Arc<InputFeed>,
+    take: PathBuf,
+    /// The take's lost blocks when the bounce started.
+    overruns: u64,
+}
+
+impl Bounce {
+    /// Feed `samples` and `tail` frames of silence through the chain into a
+    /// new take, in freewheel. Refused while `recording`, since the bounce
+    /// needs the recorder to itself.
+    pub fn start(
+        host: &mut impl BounceHost,
+        samples: Arc<[f32]>,
+        tail: usize,
+        mix: bool,
+        recording: bool,
+    ) -> Result<Self, BounceError> {
+        if recording {
+            return Err(BounceError::Recording);
+        }
+        let feed = host.input_feed().ok_or(BounceError::Unsupported)?;
+
+        // Held before the take starts, so not even its first block has a
+        // click in it.
+        feed.load(samples, tail, mix);
+        host.hold_monitoring(true);
+        let take = match host.start_take() {
+            Ok(take) => take,
+            Err(e) => {
+                host.hold_monitoring(false);
+                feed.unload();
+                return Err(BounceError::Take(e));
+            }
+        };
+        if let Err(e) = host.set_freewheel(true) {
+            host.stop_take();
+            host.hold_monitoring(false);
+            feed.unload();
+            return Err(BounceError::Freewheel(e));
+        }
+
+        info!("Bouncing into {}", take.display());
+        let overruns = host.take_overruns();
+        Ok(Self {
+            feed,
+            take,
+            overruns,
+        })
+    }
+
+    /// Frames processed so far, out of the file plus the tail.
+    pub fn progress(&self) -> (usize, usize) {
+        self.feed.progress()
+    }
+
+    /// Everything has been fed; the callback is holding on silence until
+    /// [`Self::end`].
+    pub fn is_finished(&self) -> bool {
+        self.feed.is_finished()
+    }
+
+    /// Leave freewheel, close the take and put the input and monitoring
+    /// back. Ending early cancels, keeping what was taken so far. Returns
+    /// the take's path, or [`BounceError::Gapped`] if blocks were lost.
+    pub fn end(self, host: &mut impl BounceHost) -> Result<PathBuf, BounceError> {
+        if let Err(e) = host.set_freewheel(false) {
+            error!("Failed to leave freewheel mode: {e}");
+        }
+        let dropped = host.take_overruns().saturating_sub(self.overruns);
+        host.stop_take();
+        self.feed.unload();
+        host.hold_monitoring(false);
+        if dropped > 0 {
+            return Err(BounceError::Gapped {
+                take: self.take,
+                dropped,
+            });
+        }
+        Ok(self.take)
+    }
+}
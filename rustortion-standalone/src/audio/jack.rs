@@ -1,34 +1,310 @@
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 
-use anyhow::{Context, Result};
-use jack::Client;
-use log::{error, warn};
+use crossbeam::channel::Sender;
+use jack::{AsyncClient, Client, ClientOptions, TransportState};
+use log::{error, info, warn};
 
+use crate::audio::backend::{AudioBackend, BackendKind};
+use crate::audio::bootstrap::AudioHost;
+use crate::audio::error::AudioError;
+use crate::audio::freewheel::InputFeed;
 use crate::audio::ports::Ports;
+use crate::audio::processor::{ProcessContext, Processor};
+use crate::audio::transport::{TransportEvent, TransportFollower};
+use crate::settings::AudioSettings;
 use rustortion_core::audio::engine::Engine;
 
+const CLIENT_NAME: &str = "rustortion";
+const PORT_NAMES: [&str; 5] = [
+    "in_port",
+    "in_port_right",
+    "out_port_left",
+    "out_port_right",
+    "metronome_out_port",
+];
+
+/// A JACK client that takes ownership of the engine when activated.
+pub struct JackHost {
+    client: Option<Client>,
+    active: Option<AsyncClient<NotificationHandler, ProcessHandler>>,
+    /// Our ports while the client is inactive, kept from the last
+    /// activation so a restart doesn't register them again.
+    ports: Option<Ports>,
+    xrun_count: Arc<AtomicU64>,
+    /// Set by the server's shutdown notification.
+    shut_down: Arc<AtomicBool>,
+    transport_events: Sender<TransportEvent>,
+    /// Where a bounce feeds its file in. Outlives the process handler's
+    /// engine, so it's made once with the client.
+    input_feed: Arc<InputFeed>,
+}
+
 pub struct NotificationHandler {
     xrun_count: Arc<AtomicU64>,
+    /// The server's rate, as last announced. Shared with the process handler.
+    sample_rate: Arc<AtomicUsize>,
+    shut_down: Arc<AtomicBool>,
 }
 
 pub struct ProcessHandler {
     ports: Ports,
-    audio_engine: Engine,
-    buffer: Vec<f32>,
-    metronome_buffer: Vec<f32>,
-    max_buffer_capacity: usize,
+    processor: Processor,
+    transport: TransportFollower,
+}
+
+/// The ports as they are for one JACK cycle.
+struct JackCycle<'a> {
+    ports: &'a mut Ports,
+    ps: &'a jack::ProcessScope,
+}
+
+impl ProcessContext for JackCycle<'_> {
+    fn input(&self) -> &[f32] {
+        self.ports.get_input(self.ps)
+    }
+
+    fn input_right(&self) -> &[f32] {
+        self.ports.get_input_right(self.ps)
+    }
+
+    fn write_output(&mut self, left: &[f32], right: &[f32]) {
+        self.ports.write_output(self.ps, left, right);
+    }
+
+    fn write_metronome_output(&mut self, samples: &[f32]) {
+        self.ports.write_metronome_output(self.ps, samples);
+    }
+
+    fn silence_output(&mut self) {
+        self.ports.silence_output(self.ps);
+    }
 }
 
 impl NotificationHandler {
-    pub const fn new(xrun_count: Arc<AtomicU64>) -> Self {
-        Self { xrun_count }
+    pub const fn new(
+        xrun_count: Arc<AtomicU64>,
+        sample_rate: Arc<AtomicUsize>,
+        shut_down: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            xrun_count,
+            sample_rate,
+            shut_down,
+        }
+    }
+}
+
+impl JackHost {
+    pub fn open(
+        xrun_count: Arc<AtomicU64>,
+        transport_events: Sender<TransportEvent>,
+    ) -> Result<Self, AudioError> {
+        let (client, _) = Client::new(CLIENT_NAME, ClientOptions::NO_START_SERVER)
+            .map_err(|e| AudioError::client(&e))?;
+
+        Ok(Self {
+            client: Some(client),
+            active: None,
+            ports: None,
+            xrun_count,
+            shut_down: Arc::default(),
+            transport_events,
+            input_feed: Arc::default(),
+        })
+    }
+
+    /// The client, active or not. `None` only after a failed activation or
+    /// deactivation took it, e.g. with the server gone; the host then reads
+    /// as stopped until it's replaced.
+    pub fn client(&self) -> Option<&Client> {
+        self.active
+            .as_ref()
+            .map(AsyncClient::as_client)
+            .or(self.client.as_ref())
+    }
+
+    /// Full name of one of our own ports. The server may have renamed the
+    /// client (e.g. `rustortion-01`) if another instance holds the name.
+    pub fn port_name(client: &Client, port: &str) -> String {
+        format!("{}:{port}", client.name())
+    }
+
+    /// Audio ports of other clients with `flags`, leaving out our own.
+    fn foreign_ports(&self, flags: jack::PortFlags) -> Vec<String> {
+        let Some(client) = self.client() else {
+            return Vec::new();
+        };
+        let own_prefix = Self::port_name(client, "");
+        client
+            .ports(None, Some("audio"), flags)
+            .into_iter()
+            .filter(|p| !p.starts_with(&own_prefix))
+            .collect()
+    }
+}
+
+impl AudioBackend for JackHost {
+    fn kind(&self) -> BackendKind {
+        BackendKind::Jack
+    }
+
+    fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    fn cpu_load(&self) -> f32 {
+        self.client().map_or(0.0, Client::cpu_load)
+    }
+
+    // Affects every client on the server, so only asked for when the user
+    // has explicitly changed it.
+    fn request_buffer_size(&self, frames: u32) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        if client.buffer_size() == frames {
+            return;
+        }
+        if let Err(e) = client.set_buffer_size(frames) {
+            warn!("Failed to set JACK buffer size to {frames}: {e}");
+        }
+    }
+
+    fn disconnect_all(&self) {
+        let Some(client) = self.client() else {
+            return;
+        };
+
+        for port in PORT_NAMES {
+            try_disconnect(client, &Self::port_name(client, port));
+        }
+    }
+
+    fn available_inputs(&self) -> Vec<String> {
+        self.foreign_ports(jack::PortFlags::IS_OUTPUT)
+    }
+
+    fn available_outputs(&self) -> Vec<String> {
+        self.foreign_ports(jack::PortFlags::IS_INPUT)
+    }
+
+    // Like the buffer size, this is the whole server's: every client runs
+    // unsynced from the hardware until it's turned off again.
+    fn set_freewheel(&self, enabled: bool) -> Result<(), AudioError> {
+        self.client()
+            .ok_or_else(|| AudioError::Freewheel("the JACK client is gone".into()))?
+            .set_freewheel(enabled)
+            .map_err(|e| AudioError::Freewheel(e.to_string()))
+    }
+
+    fn input_feed(&self) -> Option<Arc<InputFeed>> {
+        Some(Arc::clone(&self.input_feed))
+    }
+
+    // The server drops our connections on deactivation; the name and ports
+    // stay for the next activation.
+    fn deactivate(&mut self) -> Result<(), AudioError> {
+        let Some(active) = self.active.take() else {
+            return Ok(());
+        };
+        let (client, _, process_handler) = active
+            .deactivate()
+            .map_err(|e| AudioError::Activation(e.to_string()))?;
+        self.ports = Some(process_handler.ports);
+        self.client = Some(client);
+        Ok(())
+    }
+
+    fn take_shutdown(&self) -> bool {
+        self.shut_down.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl AudioHost for JackHost {
+    fn sample_rate(&self) -> usize {
+        self.client()
+            .map_or(0, |client| client.sample_rate() as usize)
+    }
+
+    fn buffer_size(&self) -> usize {
+        self.client()
+            .map_or(0, |client| client.buffer_size() as usize)
+    }
+
+    fn activate(&mut self, engine: Engine) -> Result<(), AudioError> {
+        let ports = match self.ports.take() {
+            Some(ports) => ports,
+            None => Ports::new(self.client.as_ref().ok_or(AudioError::AlreadyActive)?)?,
+        };
+        let client = self.client.take().ok_or(AudioError::AlreadyActive)?;
+
+        let sample_rate = Arc::new(AtomicUsize::new(client.sample_rate()));
+        let process_handler = ProcessHandler::new(
+            &client,
+            ports,
+            engine,
+            self.transport_events.clone(),
+            Arc::clone(&sample_rate),
+            Arc::clone(&self.input_feed),
+        );
+        let notification_handler = NotificationHandler::new(
+            Arc::clone(&self.xrun_count),
+            sample_rate,
+            Arc::clone(&self.shut_down),
+        );
+
+        let active = client
+            .activate_async(notification_handler, process_handler)
+            .map_err(|e| AudioError::Activation(e.to_string()))?;
+        self.active = Some(active);
+
+        Ok(())
+    }
+
+    fn connect_ports(&mut self, settings: &AudioSettings) {
+        let Some(client) = self.client() else {
+            return;
+        };
+        let own = |port: &str| Self::port_name(client, port);
+
+        try_connect(client, &settings.input_port, &own("in_port"));
+        if !settings.input_right_port.is_empty() {
+            try_connect(client, &settings.input_right_port, &own("in_port_right"));
+        }
+        try_connect(client, &own("out_port_left"), &settings.output_left_port);
+        try_connect(client, &own("out_port_right"), &settings.output_right_port);
+        try_connect(
+            client,
+            &own("metronome_out_port"),
+            &settings.metronome_out_port,
+        );
+    }
+}
+
+fn try_connect(client: &Client, src: &str, dst: &str) {
+    if let Err(e) = client.connect_ports_by_name(src, dst) {
+        warn!("Failed to connect '{src}' -> '{dst}': {e}");
+    } else {
+        info!("Connected: {src} -> {dst}");
+    }
+}
+
+fn try_disconnect(client: &Client, port_name: &str) {
+    if let Some(port) = client.port_by_name(port_name) {
+        client.disconnect(&port).unwrap_or_else(|e| {
+            error!("Failed to disconnect {port_name}: {e}");
+        });
     }
 }
 
 impl jack::NotificationHandler for NotificationHandler {
     fn sample_rate(&mut self, _: &Client, sample_rate: jack::Frames) -> jack::Control {
         warn!("JACK sample_rate changed to {sample_rate}");
+        // The manager notices the new rate on its next poll and retunes the
+        // engine; see `Manager::follow_sample_rate`.
+        self.sample_rate
+            .store(sample_rate as usize, Ordering::Relaxed);
 
         jack::Control::Continue
     }
@@ -37,84 +313,67 @@ impl jack::NotificationHandler for NotificationHandler {
         self.xrun_count.fetch_add(1, Ordering::Relaxed);
         jack::Control::Continue
     }
-}
 
-impl ProcessHandler {
-    /// Largest JACK period (in frames) sized for without reallocating. Also
-    /// used to size the recorder's buffer pool so a mid-recording buffer-size
-    /// increase up to this bound doesn't start dropping blocks.
-    pub const MAX_BUFFER_FRAMES: usize = 8192;
+    // Runs like a signal handler, so it only raises the flag; the GUI picks
+    // it up on its next watchdog poll.
+    unsafe fn shutdown(&mut self, _status: jack::ClientStatus, _reason: &str) {
+        self.shut_down.store(true, Ordering::Relaxed);
+    }
 
-    pub fn new(client: &Client, audio_engine: Engine) -> Result<Self> {
-        let ports = Ports::new(client).context("failed to create audio ports")?;
-        let buffer_size = client.buffer_size() as usize;
-        let max_capacity = Self::MAX_BUFFER_FRAMES.max(buffer_size);
+    fn freewheel(&mut self, _: &Client, is_freewheel_enabled: bool) {
+        info!(
+            "JACK freewheel mode {}",
+            if is_freewheel_enabled { "on" } else { "off" }
+        );
+    }
+}
 
-        let mut buffer = Vec::with_capacity(max_capacity);
-        buffer.resize(buffer_size, 0.0);
-        let mut metronome_buffer = Vec::with_capacity(max_capacity);
-        metronome_buffer.resize(buffer_size, 0.0);
+impl ProcessHandler {
+    pub fn new(
+        client: &Client,
+        ports: Ports,
+        audio_engine: Engine,
+        transport_events: Sender<TransportEvent>,
+        sample_rate: Arc<AtomicUsize>,
+        input_feed: Arc<InputFeed>,
+    ) -> Self {
+        let (rolling, _) = transport_state(client);
 
-        Ok(Self {
+        Self {
             ports,
-            audio_engine,
-            buffer,
-            metronome_buffer,
-            max_buffer_capacity: max_capacity,
-        })
+            processor: Processor::new(audio_engine, client.buffer_size() as usize, sample_rate)
+                .with_input_feed(input_feed),
+            transport: TransportFollower::new(rolling, transport_events),
+        }
     }
 }
 
-impl jack::ProcessHandler for ProcessHandler {
-    fn process(&mut self, _client: &jack::Client, ps: &jack::ProcessScope) -> jack::Control {
-        let input = self.ports.get_input(ps);
+/// Whether the JACK transport is rolling, and its current frame. A failed
+/// query reads as stopped. `jack_transport_query` is real-time safe.
+fn transport_state(client: &Client) -> (bool, u32) {
+    client.transport().query().map_or((false, 0), |status| {
+        (status.state == TransportState::Rolling, status.pos.frame())
+    })
+}
 
-        if let Err(e) = self.audio_engine.process(input, self.buffer.as_mut_slice()) {
-            error!("Audio processing error: {e}");
-            self.ports.silence_output(ps);
-            return jack::Control::Continue;
-        }
-        if self
-            .audio_engine
-            .process_metronome(self.metronome_buffer.as_mut_slice())
-        {
-            self.ports
-                .write_metronome_output(ps, &self.metronome_buffer);
-        }
+impl jack::ProcessHandler for ProcessHandler {
+    fn process(&mut self, client: &jack::Client, ps: &jack::ProcessScope) -> jack::Control {
+        let (rolling, frame) = transport_state(client);
+        self.transport
+            .update(rolling, frame, self.processor.frames_processed());
 
-        self.ports.write_output(ps, &self.buffer);
+        self.processor.process(&mut JackCycle {
+            ports: &mut self.ports,
+            ps,
+        });
         jack::Control::Continue
     }
 
     fn buffer_size(&mut self, _client: &jack::Client, frames: jack::Frames) -> jack::Control {
-        let new_size = frames as usize;
-
-        if new_size > self.max_buffer_capacity {
-            if let Err(e) = self
-                .buffer
-                .try_reserve(new_size.saturating_sub(self.buffer.capacity()))
-            {
-                error!("Failed to grow audio buffer for JACK buffer_size {new_size}: {e}");
-                return jack::Control::Quit;
-            }
-
-            if let Err(e) = self
-                .metronome_buffer
-                .try_reserve(new_size.saturating_sub(self.metronome_buffer.capacity()))
-            {
-                error!("Failed to grow metronome buffer for JACK buffer_size {new_size}: {e}");
-                return jack::Control::Quit;
-            }
-
-            self.max_buffer_capacity = new_size;
-        }
-
         warn!("JACK buffer_size changed to {frames} frames");
-        self.buffer.resize(new_size, 0.0);
-        self.metronome_buffer.resize(new_size, 0.0);
-
-        if let Err(e) = self.audio_engine.update_buffer_size(new_size) {
-            error!("Failed to update buffer size: {e}");
+        if let Err(e) = self.processor.set_buffer_size(frames as usize) {
+            error!("Failed to grow audio buffers for JACK buffer_size {frames}: {e}");
+            return jack::Control::Quit;
         }
 
         jack::Control::Continue
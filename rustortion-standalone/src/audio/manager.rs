@@ -1,158 +1,172 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Instant;
 
-use anyhow::{Context, Result};
-use jack::{AsyncClient, Client, ClientOptions};
+use anyhow::Result;
+use crossbeam::channel::{Receiver, Sender, TryRecvError, bounded};
 use log::{error, info, warn};
 
-use crate::audio::jack::{NotificationHandler, ProcessHandler};
-use crate::settings::{AudioSettings, Settings};
+use crate::audio::backend::{AudioBackend, BackendKind, open_backend};
+use crate::audio::bootstrap::{EngineBootstrap, PendingPorts, warm_start};
+use crate::audio::error::AudioError;
+use crate::audio::freewheel::InputFeed;
+use crate::audio::transport::{TRANSPORT_EVENT_CAPACITY, TransportEvent};
+use crate::settings::{AudioSettings, PathSettings, Settings};
+use rustortion_core::amp::stage_peaks::StagePeaks;
 use rustortion_core::amp::stages::clipper;
+use rustortion_core::audio::ambience::Ambience;
+use rustortion_core::audio::diagnostics::{Diagnostics, DiagnosticsHandle};
+use rustortion_core::audio::double_tracker::DoubleTracker;
+use rustortion_core::audio::dual_mono::RightChannel;
 use rustortion_core::audio::engine::Engine;
-use rustortion_core::audio::engine::EngineHandle;
+use rustortion_core::audio::engine::{EngineHandle, SampleRateParts};
 use rustortion_core::audio::peak_meter::{PeakMeter, PeakMeterHandle};
 use rustortion_core::audio::rt_drop::RtDropHandle;
-use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::audio::samplers::{ResamplerQuality, Samplers};
+use rustortion_core::audio::test_source::{self, TestSourceConfig};
+use rustortion_core::capture::{CaptureLoader, registry as capture_registry};
+use rustortion_core::ir::align::IrAlignment;
 use rustortion_core::ir::cabinet::{ConvolverType, DEFAULT_MAX_IR_MS, IrCabinet};
-use rustortion_core::ir::load_service::{self, IrLoadHandle};
-use rustortion_core::ir::loader::IrLoader;
-use rustortion_core::metronome::Metronome;
+use rustortion_core::ir::load_service::{self, IrLoadFailure, IrLoadHandle};
+use rustortion_core::ir::loader::{IrError, IrLoader, IrMetadata};
+use rustortion_core::ir::thumbnail::{IrThumbnails, ThumbnailLookup};
+use rustortion_core::metronome::{Metronome, MetronomeConfig, MetronomeSounds};
 use rustortion_core::nam::{NamLoader, registry as nam_registry};
 use rustortion_core::tuner::{Tuner, TunerHandle};
 
 pub struct Manager {
-    active_client: AsyncClient<NotificationHandler, ProcessHandler>,
+    host: Box<dyn AudioBackend>,
     current_settings: Settings,
     tuner_handle: TunerHandle,
     engine_handle: EngineHandle,
     peak_meter_handle: PeakMeterHandle,
+    /// The right channel's meter, in dual-mono.
+    right_meter_handle: Option<PeakMeterHandle>,
+    input_meter_handle: PeakMeterHandle,
+    diagnostics_handle: DiagnosticsHandle,
+    stage_peaks: Arc<StagePeaks>,
     xrun_count: Arc<AtomicU64>,
+    /// Rate the engine is built or retuned for. Differs from the server's
+    /// after the server changed rate, until [`Self::follow_sample_rate`].
+    engine_sample_rate: usize,
+    /// Transport start/stop events from the process callback. Outlives engine
+    /// restarts: each new JACK client gets a clone of the sender. Nothing
+    /// comes through under CPAL, which has no transport.
+    transport_tx: Sender<TransportEvent>,
+    transport_rx: Receiver<TransportEvent>,
     available_irs: Vec<String>,
+    /// Tags read from the IR file names, parsed once per scan.
+    ir_metadata: Vec<IrMetadata>,
+    /// IRs the scan found unusable; requests for them are refused up front.
+    flagged_irs: HashMap<String, IrError>,
     ir_load_handle: Option<IrLoadHandle>,
+    /// Picker previews of the IR files. Outlives rescans: its cache goes by
+    /// file modification time.
+    ir_thumbnails: IrThumbnails,
     /// Live NAM models directory — the single source of truth the NAM stage
     /// card displays and rescans. Updated whenever a rescan succeeds (from the
     /// settings dialog or the stage card) so the displayed path never drifts.
     nam_dir: Mutex<String>,
+    /// What the engine plays in place of its input. Not saved: every session
+    /// starts on the real input.
+    test_source: TestSourceConfig,
+    /// Decoded DI for the test source and the file it came from, at the
+    /// current sample rate.
+    test_di: Option<(String, Arc<[f32]>)>,
+    /// The metronome and tuner as they were before a bounce silenced them;
+    /// see [`Self::hold_monitoring`].
+    held_monitoring: Option<(MetronomeConfig, bool)>,
+    /// Ports of a restarted stream, waiting for its IR; see
+    /// [`Self::poll_warm_start`].
+    pending_ports: Option<PendingPorts>,
+    /// IR scan for a new sample rate, running in the background; see
+    /// [`Self::poll_ir_rescan`].
+    ir_rescan: Option<IrRescan>,
+}
+
+/// Everything built per stream: the engine plus the GUI-side handles that
+/// talk to it.
+struct EngineParts {
+    engine: Engine,
+    engine_handle: EngineHandle,
+    tuner_handle: TunerHandle,
+    peak_meter_handle: PeakMeterHandle,
+    right_meter_handle: Option<PeakMeterHandle>,
+    input_meter_handle: PeakMeterHandle,
+    diagnostics_handle: DiagnosticsHandle,
+    stage_peaks: Arc<StagePeaks>,
+    available_irs: Vec<String>,
+    ir_metadata: Vec<IrMetadata>,
+    flagged_irs: HashMap<String, IrError>,
+    ir_load_handle: Option<IrLoadHandle>,
 }
 
 impl Manager {
-    pub fn new(settings: Settings) -> Result<Self> {
+    pub fn new(settings: Settings) -> Result<Self, AudioError> {
         clipper::init();
 
-        let (client, _) = Client::new("rustortion", ClientOptions::NO_START_SERVER)
-            .context("failed to create JACK client")?;
-
-        let sample_rate = client.sample_rate() as usize;
-        let buffer_size = client.buffer_size() as usize;
-
-        let (tuner, tuner_handle) = Tuner::new(sample_rate);
-        let (peak_meter, peak_meter_handle) = PeakMeter::new(sample_rate);
-        let samplers = Samplers::new(
-            buffer_size,
-            settings.audio.oversampling_factor.into(),
-            sample_rate,
-        )?;
-        let mut metronome = Metronome::new(120.0, sample_rate);
-        metronome.load_wav_file("click.wav");
-
-        let convolver_type = ConvolverType::default();
-        let max_ir_samples = (sample_rate * DEFAULT_MAX_IR_MS) / 1000;
-
-        let (ir_loader, available_irs) =
-            match IrLoader::new(std::path::Path::new(&settings.ir_dir), sample_rate) {
-                Ok(loader) => {
-                    let names = loader.available_ir_names();
-                    (Some(loader), names)
-                }
-                Err(e) => {
-                    warn!("Failed to load IR directory: {e}");
-                    (None, Vec::new())
-                }
-            };
-
-        match load_nam_models(&settings.nam_dir) {
+        match load_nam_models(&settings.paths.nam_dir) {
             Ok(count) => info!("Loaded {count} NAM model(s)"),
             Err(e) => warn!("Failed to load NAM directory: {e}"),
         }
+        match load_captures(&settings.paths.captures_dir) {
+            Ok(count) => info!("Found {count} capture file(s)"),
+            Err(e) => warn!("Failed to load captures directory: {e}"),
+        }
 
-        let ir_cabinet = Some(IrCabinet::new(convolver_type, max_ir_samples));
-
-        let (rt_drop_handle, rt_drop_rx) = RtDropHandle::new();
-
-        let (engine, engine_handle) = Engine::new(
-            tuner,
-            samplers,
-            ir_cabinet,
-            peak_meter,
-            metronome,
-            rt_drop_handle,
+        let xrun_count = Arc::new(AtomicU64::new(0));
+        let (transport_tx, transport_rx) = bounded(TRANSPORT_EVENT_CAPACITY);
+        let mut host = open_backend(
+            &settings.audio,
+            Arc::clone(&xrun_count),
+            transport_tx.clone(),
         )?;
 
-        let _rt_drop_thread = std::thread::Builder::new()
-            .name("rt-drop-service".into())
-            .spawn(move || rt_drop_rx.run())
-            .expect("Failed to spawn RT drop service thread");
-
-        let ir_load_handle = ir_loader.map(|loader| {
-            load_service::spawn(
-                loader,
-                engine_handle.clone(),
-                sample_rate,
-                DEFAULT_MAX_IR_MS,
-                convolver_type,
-            )
-        });
-
-        let jack_handler =
-            ProcessHandler::new(&client, engine).context("failed to create process handler")?;
-
-        let xrun_count = Arc::new(AtomicU64::new(0));
-        let notification_handler = NotificationHandler::new(xrun_count.clone());
+        let engine_sample_rate = host.sample_rate();
+        let parts = build_engine(
+            &settings,
+            settings.audio.oversampling_factor,
+            engine_sample_rate,
+            host.buffer_size(),
+        )?;
 
-        let active_client = client
-            .activate_async(notification_handler, jack_handler)
-            .context("failed to activate async client")?;
+        host.activate(parts.engine)?;
 
-        let manager = Self {
-            active_client,
-            nam_dir: Mutex::new(settings.nam_dir.clone()),
+        let mut manager = Self {
+            host,
+            nam_dir: Mutex::new(settings.paths.nam_dir.clone()),
             current_settings: settings.clone(),
-            tuner_handle,
-            engine_handle,
-            peak_meter_handle,
+            tuner_handle: parts.tuner_handle,
+            engine_handle: parts.engine_handle,
+            peak_meter_handle: parts.peak_meter_handle,
+            right_meter_handle: parts.right_meter_handle,
+            input_meter_handle: parts.input_meter_handle,
+            diagnostics_handle: parts.diagnostics_handle,
+            stage_peaks: parts.stage_peaks,
             xrun_count,
-            available_irs,
-            ir_load_handle,
+            engine_sample_rate,
+            transport_tx,
+            transport_rx,
+            available_irs: parts.available_irs,
+            ir_metadata: parts.ir_metadata,
+            flagged_irs: parts.flagged_irs,
+            ir_load_handle: parts.ir_load_handle,
+            ir_thumbnails: IrThumbnails::spawn(),
+            test_source: TestSourceConfig::default(),
+            test_di: None,
+            held_monitoring: None,
+            pending_ports: None,
+            ir_rescan: None,
         };
 
-        manager.connect_ports(&settings.audio);
+        manager.host.connect_ports(&settings.audio);
 
         Ok(manager)
     }
 
-    /// Connect audio ports based on settings
-    fn connect_ports(&self, settings: &AudioSettings) {
-        let client = self.active_client.as_client();
-
-        try_connect(client, &settings.input_port, "rustortion:in_port");
-        try_connect(
-            client,
-            "rustortion:out_port_left",
-            &settings.output_left_port,
-        );
-        try_connect(
-            client,
-            "rustortion:out_port_right",
-            &settings.output_right_port,
-        );
-        try_connect(
-            client,
-            "rustortion:metronome_out_port",
-            &settings.metronome_out_port,
-        );
-    }
-
     pub const fn engine(&self) -> &EngineHandle {
         &self.engine_handle
     }
@@ -165,57 +179,423 @@ impl Manager {
         &self.peak_meter_handle
     }
 
+    /// The right channel's meter, in dual-mono.
+    pub const fn right_meter(&self) -> Option<&PeakMeterHandle> {
+        self.right_meter_handle.as_ref()
+    }
+
+    /// Level of the raw input, ahead of the input trim.
+    pub const fn input_meter(&self) -> &PeakMeterHandle {
+        &self.input_meter_handle
+    }
+
+    /// The audio settings the stream runs with, which a failed
+    /// [`Self::apply_settings`] leaves as they were.
+    pub const fn audio_settings(&self) -> &AudioSettings {
+        &self.current_settings.audio
+    }
+
+    /// The input port currently in use.
+    pub fn input_port(&self) -> &str {
+        &self.current_settings.audio.input_port
+    }
+
+    /// Store the calibrated trim for `port`, applying it if that port is the
+    /// one in use.
+    pub fn set_input_calibration(&mut self, port: String, trim_db: f32) {
+        self.current_settings
+            .input_calibrations
+            .insert(port, trim_db);
+        self.engine_handle
+            .set_input_trim_db(self.current_settings.input_trim_db());
+    }
+
     pub fn xrun_count(&self) -> u64 {
         self.xrun_count.load(Ordering::Relaxed)
     }
 
+    /// Transport start/stop events received since the last call.
+    pub fn transport_events(&self) -> impl Iterator<Item = TransportEvent> + '_ {
+        self.transport_rx.try_iter()
+    }
+
     pub fn cpu_load(&self) -> f32 {
-        self.active_client.as_client().cpu_load()
+        self.host.cpu_load()
+    }
+
+    /// The audio system the stream runs on. Under [`BackendKind::Cpal`] the
+    /// inputs and outputs are devices, not ports.
+    pub fn backend_kind(&self) -> BackendKind {
+        self.host.kind()
+    }
+
+    /// Whether the engine is shedding load at reduced quality.
+    pub fn reduced_quality(&self) -> bool {
+        self.diagnostics_handle.reduced_quality()
     }
 
-    /// Reconnect with new settings
-    pub fn apply_settings(&mut self, new_settings: AudioSettings) -> Result<()> {
+    /// Per-stage output peaks of the running chain, for gain staging and
+    /// the level bars.
+    pub fn stage_peaks(&self) -> &StagePeaks {
+        &self.stage_peaks
+    }
+
+    /// Engine diagnostics plus JACK host stats. Stage list and IR name are
+    /// GUI-side state and must be added by the caller.
+    pub fn diagnostics(&self) -> Diagnostics {
+        self.diagnostics_handle
+            .snapshot()
+            .with_host_stats(self.cpu_load(), self.xrun_count())
+    }
+
+    /// Apply new audio settings. Port changes only reconnect; a changed buffer
+    /// size, sample rate, audio API or CPAL device rebuilds the engine, and
+    /// the stream if it must, warm-started from `bootstrap` so the new engine
+    /// never plays at defaults. On an error the settings in use stay as they
+    /// were.
+    pub fn apply_settings(
+        &mut self,
+        new_settings: AudioSettings,
+        bootstrap: &EngineBootstrap,
+    ) -> Result<(), AudioError> {
         info!("Applying new audio settings");
 
-        // Disconnect existing connections
+        let current = &self.current_settings.audio;
+        // CPAL opens its devices with the stream, so picking others means a
+        // new one.
+        let devices_changed = self.host.kind() == BackendKind::Cpal
+            && (new_settings.input_device != current.input_device
+                || new_settings.output_device != current.output_device);
+        let needs_restart = new_settings.buffer_size != current.buffer_size
+            || new_settings.sample_rate != current.sample_rate
+            || new_settings.resampler_quality != current.resampler_quality
+            || new_settings.host_api != current.host_api
+            || devices_changed;
+        let chain_mode_changed = new_settings.dual_mono() != current.dual_mono();
+
+        if needs_restart {
+            return self.restart(new_settings, bootstrap);
+        }
+
+        let previous = std::mem::replace(&mut self.current_settings.audio, new_settings.clone());
+        if chain_mode_changed && let Err(e) = self.set_chain_mode(bootstrap) {
+            self.current_settings.audio = previous;
+            return Err(e);
+        }
+
+        // A new input port brings its own calibration with it.
+        self.engine_handle
+            .set_input_trim_db(self.current_settings.input_trim_db());
+        self.engine_handle
+            .set_load_guard(new_settings.adaptive_quality);
         self.disconnect_all();
+        self.pending_ports = None;
+        self.host.connect_ports(&new_settings);
 
-        // Update settings
-        self.current_settings.audio = new_settings.clone();
+        Ok(())
+    }
 
-        self.connect_ports(&new_settings);
+    /// Give the running engine a right channel, or take it away, to match the
+    /// settings, and send a new one the chain, filters and IR.
+    fn set_chain_mode(&mut self, bootstrap: &EngineBootstrap) -> Result<(), AudioError> {
+        let sample_rate = self.engine_sample_rate;
+        let (right, right_meter_handle) = build_right_channel(
+            &self.current_settings.audio,
+            bootstrap.oversampling_factor,
+            sample_rate,
+            self.host.buffer_size(),
+        )?;
+        let dual = right.is_some();
+        self.engine_handle.set_chain_mode(right);
+        self.right_meter_handle = right_meter_handle;
+
+        if dual {
+            bootstrap.send_right(&self.engine_handle, sample_rate);
+            // Sent to both, for the new cabinet to pick up.
+            self.engine_handle.set_ir_bypass(bootstrap.ir_bypassed);
+            self.engine_handle.set_ir_gain(bootstrap.ir_gain);
+            if let (Some(name), Some(ir_load)) = (&bootstrap.ir_name, &self.ir_load_handle) {
+                ir_load.request_load(name, bootstrap.ir_alignment);
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the audio stream should be running, for the watchdog.
+    pub fn is_running(&self) -> bool {
+        self.host.is_active()
+    }
+
+    /// Whether the server shut the stream down since last asked.
+    pub fn take_server_shutdown(&self) -> bool {
+        self.host.take_shutdown()
+    }
+
+    /// Restart the stream with a fresh engine after it stalled, warm-started
+    /// from `bootstrap` and reconnected to the configured ports.
+    pub fn restart_stream(&mut self, bootstrap: &EngineBootstrap) -> Result<(), AudioError> {
+        self.restart(self.current_settings.audio.clone(), bootstrap)
+    }
+
+    /// Whether a running stream can be stopped and started again for `audio`
+    /// rather than opened afresh. A JACK client keeps its name, and with it
+    /// saved connections and session scripts; CPAL fixes its devices, rate
+    /// and period when it opens.
+    fn keeps_host(&self, audio: &AudioSettings) -> bool {
+        let current = &self.current_settings.audio;
+        if !self.host.is_active() || audio.host_api != current.host_api {
+            return false;
+        }
+        match self.host.kind() {
+            BackendKind::Jack => true,
+            BackendKind::Cpal => {
+                audio.input_device == current.input_device
+                    && audio.output_device == current.output_device
+                    && audio.sample_rate == current.sample_rate
+                    && audio.buffer_size == current.buffer_size
+            }
+        }
+    }
+
+    /// Replace the engine with one built for `audio`, restarting the stream,
+    /// or opening a new one where it must. The running engine keeps playing
+    /// until the new one is built, so a backend that won't open or an engine
+    /// that won't build leaves everything as it was. Past that, a stream that
+    /// fails to start is an error with nothing running, for the watchdog's
+    /// restart to retry. The ports connect from [`Self::poll_warm_start`]
+    /// once the IR is in.
+    fn restart(
+        &mut self,
+        audio: AudioSettings,
+        bootstrap: &EngineBootstrap,
+    ) -> Result<(), AudioError> {
+        info!("Restarting audio engine");
+
+        let mut settings = self.current_settings.clone();
+        settings.audio = audio;
+        settings.audio.oversampling_factor = bootstrap.oversampling_factor;
+
+        let new_host = if self.keeps_host(&settings.audio) {
+            None
+        } else {
+            Some(open_backend(
+                &settings.audio,
+                Arc::clone(&self.xrun_count),
+                self.transport_tx.clone(),
+            )?)
+        };
+        let host = new_host.as_deref().unwrap_or(self.host.as_ref());
+        host.request_buffer_size(settings.audio.buffer_size);
+        let parts = match build_engine(
+            &settings,
+            bootstrap.oversampling_factor,
+            host.sample_rate(),
+            host.buffer_size(),
+        ) {
+            Ok(parts) => parts,
+            Err(e) => {
+                // The period is the whole server's; put it back too.
+                host.request_buffer_size(self.current_settings.audio.buffer_size);
+                return Err(e);
+            }
+        };
+
+        // From here the running engine goes.
+        self.pending_ports = None;
+        match new_host {
+            // Dropping the old host stops its stream and frees the old engine.
+            Some(host) => self.host = host,
+            None => {
+                if let Err(e) = self.host.deactivate() {
+                    warn!("Failed to stop the audio stream, opening a new one: {e}");
+                    self.host = open_backend(
+                        &settings.audio,
+                        Arc::clone(&self.xrun_count),
+                        self.transport_tx.clone(),
+                    )?;
+                }
+            }
+        }
+
+        self.current_settings = settings;
+        self.engine_sample_rate = self.host.sample_rate();
+        self.engine_handle = parts.engine_handle;
+        self.tuner_handle = parts.tuner_handle;
+        self.peak_meter_handle = parts.peak_meter_handle;
+        self.right_meter_handle = parts.right_meter_handle;
+        self.input_meter_handle = parts.input_meter_handle;
+        self.diagnostics_handle = parts.diagnostics_handle;
+        // The level bars keep running on the new engine.
+        parts.stage_peaks.set_live(self.stage_peaks.is_live());
+        self.stage_peaks = parts.stage_peaks;
+        self.available_irs = parts.available_irs;
+        self.ir_metadata = parts.ir_metadata;
+        self.flagged_irs = parts.flagged_irs;
+        self.ir_load_handle = parts.ir_load_handle;
+        // The new stream scanned its own IRs.
+        self.ir_rescan = None;
+        // The new engine starts on the real input, and the DI was decoded
+        // for the old sample rate.
+        self.test_source = TestSourceConfig::default();
+        self.test_di = None;
+
+        let pending = warm_start(
+            self.host.as_mut(),
+            parts.engine,
+            &self.engine_handle,
+            self.ir_load_handle.as_ref(),
+            bootstrap,
+        )?;
+        self.pending_ports = Some(pending);
+        self.poll_warm_start();
+        Ok(())
+    }
+
+    /// Connect a restarted stream's ports once its IR has reached the engine,
+    /// or has taken too long to. Polled by the GUI while
+    /// [`Self::warm_start_pending`].
+    pub fn poll_warm_start(&mut self) {
+        if self
+            .pending_ports
+            .as_ref()
+            .is_some_and(|pending| pending.ready(Instant::now()))
+            && let Some(pending) = self.pending_ports.take()
+        {
+            pending.connect(self.host.as_mut(), &self.current_settings.audio);
+        }
+    }
+
+    /// Whether a restarted stream's ports are still waiting on its IR.
+    pub const fn warm_start_pending(&self) -> bool {
+        self.pending_ports.is_some()
+    }
+
+    /// Put in the IRs scanned for a new sample rate once the scan is done,
+    /// and load the one the engine is waiting for. Polled by the GUI while
+    /// [`Self::ir_rescan_pending`]. Returns whether the IR list was replaced.
+    pub fn poll_ir_rescan(&mut self) -> bool {
+        let Some(rescan) = &self.ir_rescan else {
+            return false;
+        };
+        let scan = match rescan.result.try_recv() {
+            Err(TryRecvError::Empty) => return false,
+            Ok(scan) => scan,
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("the scan thread exited")),
+        };
+        let Some(rescan) = self.ir_rescan.take() else {
+            return false;
+        };
+
+        match scan {
+            Ok(scan) => {
+                self.install_irs(scan, rescan.sample_rate);
+                if let (Some((name, alignment)), Some(ir_load)) =
+                    (&rescan.load, &self.ir_load_handle)
+                {
+                    ir_load.request_load(name, *alignment);
+                }
+                true
+            }
+            // The engine gives up waiting for the IR and plays on without.
+            Err(e) => {
+                error!(
+                    "Failed to rescan IR directory '{}': {e}",
+                    self.current_settings.paths.ir_dir
+                );
+                false
+            }
+        }
+    }
+
+    /// Whether the IRs are being scanned for a new sample rate.
+    pub const fn ir_rescan_pending(&self) -> bool {
+        self.ir_rescan.is_some()
+    }
+
+    /// Whether the server changed its sample rate under the running engine,
+    /// e.g. PipeWire switching the graph rate for another stream.
+    pub fn sample_rate_changed(&self) -> bool {
+        self.host.is_active() && self.host.sample_rate() != self.engine_sample_rate
+    }
+
+    /// Retune the running engine to the server's new sample rate, keeping
+    /// the JACK client and its connections. The rate-dependent engine parts
+    /// are rebuilt here, the chain is rebuilt from `bootstrap` and the IR is
+    /// rescanned and resampled in the background; see
+    /// [`Self::poll_ir_rescan`]. The output is silent until the new chain is
+    /// in, then fades back in.
+    pub fn follow_sample_rate(&mut self, bootstrap: &EngineBootstrap) -> Result<(), AudioError> {
+        let sample_rate = self.host.sample_rate();
+        let buffer_size = self.host.buffer_size();
+        info!(
+            "Following JACK sample rate change: {} -> {sample_rate} Hz",
+            self.engine_sample_rate
+        );
+
+        let samplers = Samplers::with_quality(
+            buffer_size,
+            bootstrap.oversampling_factor.into(),
+            sample_rate,
+            self.current_settings.audio.resampler_quality,
+        )
+        .map_err(|e| AudioError::Engine(e.to_string()))?;
+
+        // The take so far was written at the old rate; `bootstrap` resumes
+        // it in a new file.
+        if bootstrap.recording_dir.is_some() {
+            self.engine_handle.stop_recording();
+        }
+        // A new load service resamples the IRs for the new rate once the
+        // directory is rescanned; the output stays muted until the current
+        // one is ready, rather than playing it at the old rate.
+        let ir_follows = bootstrap.ir_name.is_some() && self.ir_load_handle.is_some();
+        let load = bootstrap
+            .ir_name
+            .clone()
+            .filter(|_| ir_follows)
+            .map(|name| (name, bootstrap.ir_alignment));
+        self.spawn_ir_rescan(sample_rate, load);
+        self.engine_handle.set_sample_rate(SampleRateParts {
+            samplers,
+            tuner: Tuner::for_handle(sample_rate, &self.tuner_handle),
+            metronome: build_metronome(sample_rate, &self.current_settings.metronome_tick),
+            ambience: Ambience::new(bootstrap.ambience, sample_rate),
+            double_tracker: DoubleTracker::new(bootstrap.double_tracker, sample_rate),
+            ir_follows,
+        });
+        self.engine_sample_rate = sample_rate;
+        self.current_settings.audio.oversampling_factor = bootstrap.oversampling_factor;
+        // A right channel for the new rate, left empty for `bootstrap` to fill.
+        let (right, right_meter_handle) = build_right_channel(
+            &self.current_settings.audio,
+            bootstrap.oversampling_factor,
+            sample_rate,
+            buffer_size,
+        )?;
+        self.engine_handle.set_chain_mode(right);
+        self.right_meter_handle = right_meter_handle;
+        // The DI was decoded for the old rate.
+        self.test_di = None;
+        self.set_test_source(TestSourceConfig::default());
+
+        bootstrap.send(&self.engine_handle, sample_rate, buffer_size);
 
         Ok(())
     }
 
     /// Disconnect all audio connections
     pub fn disconnect_all(&self) {
-        let client = self.active_client.as_client();
-
-        try_disconnect(client, "rustortion:in_port");
-        try_disconnect(client, "rustortion:out_port_left");
-        try_disconnect(client, "rustortion:out_port_right");
-        try_disconnect(client, "rustortion:metronome_out_port");
+        self.host.disconnect_all();
     }
 
-    /// Get available input ports
+    /// Get available input ports, or capture devices under CPAL
     pub fn get_available_inputs(&self) -> Vec<String> {
-        self.active_client
-            .as_client()
-            .ports(None, Some("audio"), jack::PortFlags::IS_OUTPUT)
-            .into_iter()
-            .filter(|p| !p.starts_with("rustortion:"))
-            .collect()
+        self.host.available_inputs()
     }
 
-    /// Get available output ports
+    /// Get available output ports, or playback devices under CPAL
     pub fn get_available_outputs(&self) -> Vec<String> {
-        self.active_client
-            .as_client()
-            .ports(None, Some("audio"), jack::PortFlags::IS_INPUT)
-            .into_iter()
-            .filter(|p| !p.starts_with("rustortion:"))
-            .collect()
+        self.host.available_outputs()
     }
 
     // Get available IR paths
@@ -223,10 +603,57 @@ impl Manager {
         self.available_irs.clone()
     }
 
-    pub fn request_ir_load(&self, name: &str) {
+    /// Mic, speaker and distance tags of every available IR.
+    pub fn get_available_irs_with_metadata(&self) -> Vec<IrMetadata> {
+        self.ir_metadata.clone()
+    }
+
+    /// IRs the scan flagged, to mark in the picker.
+    pub fn get_flagged_irs(&self) -> Vec<String> {
+        self.flagged_irs.keys().cloned().collect()
+    }
+
+    /// Queue `name` for loading, or refuse it straight away if the scan
+    /// flagged it. Failures found while decoding come from
+    /// [`Self::take_ir_load_failure`].
+    pub fn request_ir_load(&self, name: &str, alignment: IrAlignment) -> Result<(), IrError> {
+        if let Some(e) = self.flagged_irs.get(name) {
+            return Err(e.clone());
+        }
         if let Some(ref handle) = self.ir_load_handle {
-            handle.request_load(name);
+            handle.request_load(name, alignment);
         }
+        Ok(())
+    }
+
+    /// Onset of a loaded IR before alignment, for auto-align.
+    pub fn ir_onset(&self, name: &str) -> Option<usize> {
+        self.ir_load_handle.as_ref()?.onset(name)
+    }
+
+    /// Broadband gain of a loaded or preloaded IR, for level matching.
+    pub fn ir_energy(&self, name: &str) -> Option<f32> {
+        self.ir_load_handle.as_ref()?.energy(name)
+    }
+
+    /// Waveform and spectrum preview of an available IR, decoded off the
+    /// GUI thread.
+    pub fn ir_thumbnail(&self, name: &str) -> ThumbnailLookup {
+        if !self.available_irs.iter().any(|ir| ir == name) {
+            return ThumbnailLookup::Unavailable;
+        }
+        let path = std::path::Path::new(&self.current_settings.paths.ir_dir).join(name);
+        self.ir_thumbnails.get(&path)
+    }
+
+    pub fn take_ir_load_failure(&self) -> Option<IrLoadFailure> {
+        self.ir_load_handle.as_ref()?.take_failure()
+    }
+
+    pub fn ir_load_pending(&self) -> bool {
+        self.ir_load_handle
+            .as_ref()
+            .is_some_and(IrLoadHandle::is_loading)
     }
 
     pub fn clear_ir(&self) {
@@ -241,10 +668,90 @@ impl Manager {
         }
     }
 
+    pub const fn test_source(&self) -> TestSourceConfig {
+        self.test_source
+    }
+
+    /// Play `config`'s test signal in place of the input, or go back to the
+    /// input when it's off.
+    pub fn set_test_source(&mut self, config: TestSourceConfig) {
+        self.test_source = config;
+        let di = self.test_di.as_ref().map(|(_, di)| Arc::clone(di));
+        self.engine_handle
+            .set_test_source(config, self.sample_rate(), di);
+    }
+
+    /// Where a bounce feeds its file in, if the backend can freewheel.
+    pub fn input_feed(&self) -> Option<Arc<InputFeed>> {
+        self.host.input_feed()
+    }
+
+    pub fn set_freewheel(&self, enabled: bool) -> Result<(), AudioError> {
+        self.host.set_freewheel(enabled)
+    }
+
+    /// Silence the metronome, tuner and test source for a bounce, so the take
+    /// is the file through the chain and nothing else. `metronome` and
+    /// `tuner_enabled` are what [`Self::release_monitoring`] puts back.
+    pub fn hold_monitoring(&mut self, metronome: MetronomeConfig, tuner_enabled: bool) {
+        self.held_monitoring = Some((metronome, tuner_enabled));
+        self.engine_handle.set_metronome(MetronomeConfig {
+            enabled: false,
+            ..metronome
+        });
+        self.tuner_handle.set_enabled(false);
+        self.engine_handle
+            .set_test_source(TestSourceConfig::default(), self.sample_rate(), None);
+    }
+
+    /// Undo [`Self::hold_monitoring`]. Does nothing if nothing is held.
+    pub fn release_monitoring(&mut self) {
+        let Some((metronome, tuner_enabled)) = self.held_monitoring.take() else {
+            return;
+        };
+        self.engine_handle.set_metronome(metronome);
+        self.tuner_handle.set_enabled(tuner_enabled);
+        self.set_test_source(self.test_source);
+    }
+
+    /// Decode `path` as the DI the test source loops and restart the test
+    /// source with it. Does nothing if it's the one already loaded.
+    pub fn load_test_di(&mut self, path: &str) -> Result<()> {
+        if self
+            .test_di
+            .as_ref()
+            .is_some_and(|(loaded, _)| loaded == path)
+        {
+            return Ok(());
+        }
+        let di = test_source::load_di_sample(std::path::Path::new(path), self.sample_rate())?;
+        info!("Loaded test DI '{path}': {} samples", di.len());
+        self.test_di = Some((path.to_owned(), di));
+        self.set_test_source(self.test_source);
+        Ok(())
+    }
+
+    /// Play the WAV at `path` as the metronome click, or the bundled ticks
+    /// when `path` is empty. Kept for engine rebuilds.
+    pub fn load_metronome_tick(&mut self, path: &str) -> Result<()> {
+        let sounds = if path.is_empty() {
+            MetronomeSounds::bundled(self.engine_sample_rate)
+        } else {
+            MetronomeSounds::from_file(std::path::Path::new(path), self.engine_sample_rate)?
+        };
+        self.engine_handle.set_metronome_sounds(sounds);
+        path.clone_into(&mut self.current_settings.metronome_tick);
+        Ok(())
+    }
+
     pub const fn current_oversampling_factor(&self) -> u32 {
         self.current_settings.audio.oversampling_factor
     }
 
+    pub const fn resampler_quality(&self) -> ResamplerQuality {
+        self.current_settings.audio.resampler_quality
+    }
+
     /// Re-scan `dir` for `*.nam` files and re-register them in the global NAM
     /// registry, replacing any previously loaded models. Runs off the real-time
     /// thread (settings dialog action), so scanning/parsing here is fine.
@@ -270,24 +777,282 @@ impl Manager {
         }
     }
 
+    /// Re-scan the configured captures directory and re-register the global
+    /// capture registry. Returns the number of capture files found.
+    pub fn rescan_captures(&self) -> Result<usize, String> {
+        let dir = &self.current_settings.paths.captures_dir;
+        load_captures(dir).map_err(|e| {
+            let msg = format!("Failed to rescan captures directory '{dir}': {e}");
+            error!("{msg}");
+            msg
+        })
+    }
+
+    /// Re-scan the IR directory after files were added, renamed or removed.
+    /// The load service is replaced with one on the new scan, which also
+    /// drops coefficients cached from files that may have changed on disk.
+    /// Returns the number of IRs now available.
+    pub fn rescan_irs(&mut self) -> Result<usize, String> {
+        let sample_rate = self.sample_rate();
+        let (loader, names, metadata, flagged) = scan_irs(&self.current_settings, sample_rate)
+            .map_err(|e| {
+                let msg = format!(
+                    "Failed to rescan IR directory '{}': {e}",
+                    self.current_settings.paths.ir_dir
+                );
+                error!("{msg}");
+                msg
+            })?;
+
+        self.install_irs((loader, names, metadata, flagged), sample_rate);
+        Ok(self.available_irs.len())
+    }
+
+    /// Scan the IR directory for `sample_rate` on a thread of its own, so a
+    /// large directory doesn't hold up the GUI; `load` is the IR to load
+    /// once it's in. The old load service is dropped now rather than left to
+    /// load IRs at the old rate.
+    fn spawn_ir_rescan(&mut self, sample_rate: usize, load: Option<(String, IrAlignment)>) {
+        // Dropping the service joins its thread.
+        self.ir_load_handle = None;
+        self.ir_rescan = None;
+
+        let (result_tx, result_rx) = bounded(1);
+        let settings = self.current_settings.clone();
+        let spawned = thread::Builder::new()
+            .name("ir-rescan".into())
+            .spawn(move || {
+                let _ = result_tx.send(scan_irs(&settings, sample_rate));
+            });
+        match spawned {
+            Ok(_) => {
+                self.ir_rescan = Some(IrRescan {
+                    result: result_rx,
+                    sample_rate,
+                    load,
+                });
+            }
+            Err(e) => error!("Failed to start rescanning the IR directory: {e}"),
+        }
+    }
+
+    /// Replace the load service and the IR lists with a new scan's.
+    fn install_irs(&mut self, scan: IrScan, sample_rate: usize) {
+        let (loader, names, metadata, flagged) = scan;
+        // Drop the old service first: it joins its thread.
+        self.ir_load_handle = None;
+        self.ir_load_handle = Some(load_service::spawn(
+            loader,
+            self.engine_handle.clone(),
+            sample_rate,
+            DEFAULT_MAX_IR_MS,
+            ConvolverType::default(),
+            self.current_settings.ir_memory_mb,
+        ));
+        self.available_irs = names;
+        self.ir_metadata = metadata;
+        self.flagged_irs = flagged;
+
+        info!("Rescanned IR directory: {} IR(s)", self.available_irs.len());
+    }
+
+    /// Point the recording, IR and preset directories somewhere else. The
+    /// IR list still comes from the old directory until [`Self::rescan_irs`].
+    pub fn set_paths(&mut self, paths: PathSettings) {
+        self.current_settings.paths = paths;
+    }
+
+    pub fn captures_dir(&self) -> &str {
+        &self.current_settings.paths.captures_dir
+    }
+
     /// The NAM models directory currently in use — the same source of truth the
     /// settings dialog edits and rescans against. Returns the last successfully
     /// scanned directory (or the configured default if none has been scanned).
     pub fn nam_dir(&self) -> String {
-        self.nam_dir
-            .lock()
-            .map_or_else(|_| self.current_settings.nam_dir.clone(), |d| d.clone())
+        self.nam_dir.lock().map_or_else(
+            |_| self.current_settings.paths.nam_dir.clone(),
+            |d| d.clone(),
+        )
     }
 
     pub fn sample_rate(&self) -> usize {
-        self.active_client.as_client().sample_rate() as usize
+        self.host.sample_rate()
     }
 
     pub fn buffer_size(&self) -> usize {
-        self.active_client.as_client().buffer_size() as usize
+        self.host.buffer_size()
     }
 }
 
+/// Build an engine for a client running at `sample_rate` / `buffer_size`, and
+/// start the services that feed it (RT drop thread, IR load service).
+fn build_engine(
+    settings: &Settings,
+    oversampling_factor: u32,
+    sample_rate: usize,
+    buffer_size: usize,
+) -> Result<EngineParts, AudioError> {
+    let (mut tuner, tuner_handle) = Tuner::new(sample_rate);
+    tuner.set_algorithm(settings.tuner_algorithm);
+    let (peak_meter, peak_meter_handle) = PeakMeter::new(sample_rate);
+    let (input_meter, input_meter_handle) = PeakMeter::new(sample_rate);
+    let samplers = Samplers::with_quality(
+        buffer_size,
+        oversampling_factor.into(),
+        sample_rate,
+        settings.audio.resampler_quality,
+    )
+    .map_err(|e| AudioError::Engine(e.to_string()))?;
+    let metronome = build_metronome(sample_rate, &settings.metronome_tick);
+
+    let convolver_type = ConvolverType::default();
+    let max_ir_samples = (sample_rate * DEFAULT_MAX_IR_MS) / 1000;
+
+    let (ir_loader, available_irs, ir_metadata, flagged_irs) = match scan_irs(settings, sample_rate)
+    {
+        Ok((loader, names, metadata, flagged)) => (Some(loader), names, metadata, flagged),
+        Err(e) => {
+            warn!("Failed to load IR directory: {e}");
+            (None, Vec::new(), Vec::new(), HashMap::new())
+        }
+    };
+
+    let ir_cabinet = Some(IrCabinet::new(convolver_type, max_ir_samples));
+
+    let (rt_drop_handle, rt_drop_rx) = RtDropHandle::new();
+
+    let (mut engine, engine_handle) = Engine::new(
+        tuner,
+        samplers,
+        ir_cabinet,
+        peak_meter,
+        metronome,
+        rt_drop_handle,
+    )
+    .map_err(|e| AudioError::Engine(e.to_string()))?;
+    engine.set_input_meter(input_meter);
+    // Start muted, as an amp comes up on standby, until the player switches
+    // it on.
+    engine.start_in_standby();
+    engine_handle.set_input_trim_db(settings.input_trim_db());
+    engine_handle.set_load_guard(settings.audio.adaptive_quality);
+    let (right, right_meter_handle) = build_right_channel(
+        &settings.audio,
+        oversampling_factor,
+        sample_rate,
+        buffer_size,
+    )?;
+    engine_handle.set_chain_mode(right);
+
+    let diagnostics_handle = engine.diagnostics();
+    let stage_peaks = engine.stage_peaks();
+
+    let _rt_drop_thread = std::thread::Builder::new()
+        .name("rt-drop-service".into())
+        .spawn(move || rt_drop_rx.run())
+        .expect("Failed to spawn RT drop service thread");
+
+    let ir_load_handle = ir_loader.map(|loader| {
+        load_service::spawn(
+            loader,
+            engine_handle.clone(),
+            sample_rate,
+            DEFAULT_MAX_IR_MS,
+            convolver_type,
+            settings.ir_memory_mb,
+        )
+    });
+
+    Ok(EngineParts {
+        engine,
+        engine_handle,
+        tuner_handle,
+        peak_meter_handle,
+        right_meter_handle,
+        input_meter_handle,
+        diagnostics_handle,
+        stage_peaks,
+        available_irs,
+        ir_metadata,
+        flagged_irs,
+        ir_load_handle,
+    })
+}
+
+/// The right channel for dual-mono and its meter's handle, or neither when
+/// `settings` ask for mono or have no right input.
+fn build_right_channel(
+    settings: &AudioSettings,
+    oversampling_factor: u32,
+    sample_rate: usize,
+    buffer_size: usize,
+) -> Result<(Option<RightChannel>, Option<PeakMeterHandle>), AudioError> {
+    if !settings.dual_mono() {
+        return Ok((None, None));
+    }
+    let samplers = Samplers::with_quality(
+        buffer_size,
+        oversampling_factor.into(),
+        sample_rate,
+        settings.resampler_quality,
+    )
+    .map_err(|e| AudioError::Engine(e.to_string()))?;
+    let max_ir_samples = (sample_rate * DEFAULT_MAX_IR_MS) / 1000;
+    let ir_cabinet = IrCabinet::new(ConvolverType::default(), max_ir_samples);
+    let (peak_meter, peak_meter_handle) = PeakMeter::new(sample_rate);
+
+    Ok((
+        Some(RightChannel::new(
+            samplers,
+            Some(ir_cabinet),
+            Some(peak_meter),
+        )),
+        Some(peak_meter_handle),
+    ))
+}
+
+/// A metronome playing the custom tick at `tick_path`, or the bundled ticks
+/// when it's empty or fails to load.
+fn build_metronome(sample_rate: usize, tick_path: &str) -> Metronome {
+    let mut metronome = Metronome::new(120.0, sample_rate);
+    if !tick_path.is_empty()
+        && let Err(e) = metronome.load_wav_file(tick_path)
+    {
+        error!("Failed to load metronome tick, using the bundled one: {e:#}");
+    }
+    metronome
+}
+
+type IrScan = (
+    IrLoader,
+    Vec<String>,
+    Vec<IrMetadata>,
+    HashMap<String, IrError>,
+);
+
+/// An IR scan running on its own thread; see [`Manager::poll_ir_rescan`].
+struct IrRescan {
+    result: Receiver<Result<IrScan>>,
+    sample_rate: usize,
+    /// The IR the engine is waiting for, and how to align it.
+    load: Option<(String, IrAlignment)>,
+}
+
+/// Scan the IR directory for a client at `sample_rate`: the loader for the
+/// load service, plus the names, tags and flags the GUI lists.
+fn scan_irs(settings: &Settings, sample_rate: usize) -> Result<IrScan> {
+    let loader = IrLoader::with_max_seconds(
+        std::path::Path::new(&settings.paths.ir_dir),
+        sample_rate,
+        settings.ir_max_seconds,
+    )?;
+    let names = loader.available_ir_names();
+    let metadata = loader.available_irs_with_metadata(&settings.ir_tags);
+    let flagged = loader.flagged_irs().clone();
+    Ok((loader, names, metadata, flagged))
+}
+
 /// Build a fresh [`NamLoader`] from `dir` and re-populate the global NAM
 /// registry. Rebuilding the loader is the rescan idiom: `init_from_loader`
 /// clears and repopulates the registry. Returns the number of models loaded.
@@ -298,18 +1063,11 @@ fn load_nam_models(dir: &str) -> Result<usize> {
     Ok(count)
 }
 
-fn try_connect(client: &Client, src: &str, dst: &str) {
-    if let Err(e) = client.connect_ports_by_name(src, dst) {
-        warn!("Failed to connect '{src}' -> '{dst}': {e}");
-    } else {
-        info!("Connected: {src} -> {dst}");
-    }
-}
-
-fn try_disconnect(client: &Client, port_name: &str) {
-    if let Some(port) = client.port_by_name(port_name) {
-        client.disconnect(&port).unwrap_or_else(|e| {
-            error!("Failed to disconnect {port_name}: {e}");
-        });
-    }
+/// Build a fresh [`CaptureLoader`] from `dir` and re-populate the global
+/// capture registry, like [`load_nam_models`]. Returns the number of files found.
+fn load_captures(dir: &str) -> Result<usize> {
+    let loader = CaptureLoader::new(std::path::Path::new(dir))?;
+    let count = loader.available_names().len();
+    capture_registry::init_from_loader(&loader);
+    Ok(count)
 }
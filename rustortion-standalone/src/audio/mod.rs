@@ -1,3 +1,11 @@
+pub mod backend;
+pub mod bootstrap;
+pub mod cpal;
+pub mod error;
+pub mod freewheel;
 pub mod jack;
 pub mod manager;
 pub mod ports;
+pub mod processor;
+pub mod transport;
+pub mod watchdog;
@@ -1,8 +1,11 @@
-use anyhow::{Context, Result};
 use jack::{AudioIn, AudioOut, Client, Port, ProcessScope};
 
+use crate::audio::error::AudioError;
+
 pub struct Ports {
     input: Port<AudioIn>,
+    /// Only read in dual-mono.
+    input_right: Port<AudioIn>,
     output_left: Port<AudioOut>,
     output_right: Port<AudioOut>,
     //need separate ports for guitar output and metronome output
@@ -10,20 +13,23 @@ pub struct Ports {
 }
 
 impl Ports {
-    pub fn new(client: &Client) -> Result<Self> {
+    pub fn new(client: &Client) -> Result<Self, AudioError> {
         Ok(Self {
             input: client
                 .register_port("in_port", AudioIn::default())
-                .context("failed to register in port")?,
+                .map_err(|e| AudioError::port("in_port", &e))?,
+            input_right: client
+                .register_port("in_port_right", AudioIn::default())
+                .map_err(|e| AudioError::port("in_port_right", &e))?,
             output_left: client
                 .register_port("out_port_left", AudioOut::default())
-                .context("failed to register out port left")?,
+                .map_err(|e| AudioError::port("out_port_left", &e))?,
             output_right: client
                 .register_port("out_port_right", AudioOut::default())
-                .context("failed to register out port right")?,
+                .map_err(|e| AudioError::port("out_port_right", &e))?,
             metronome_output: client
                 .register_port("metronome_out_port", AudioOut::default())
-                .context("failed to register metronome out port")?,
+                .map_err(|e| AudioError::port("metronome_out_port", &e))?,
         })
     }
 
@@ -31,14 +37,18 @@ impl Ports {
         self.input.as_slice(ps)
     }
 
-    pub fn write_output(&mut self, ps: &ProcessScope, samples: &[f32]) {
+    pub fn get_input_right<'a>(&'a self, ps: &'a ProcessScope) -> &'a [f32] {
+        self.input_right.as_slice(ps)
+    }
+
+    pub fn write_output(&mut self, ps: &ProcessScope, left: &[f32], right: &[f32]) {
         let output_size = ps.n_frames() as usize;
-        let frame_count = samples.len().min(output_size);
+        let frame_count = left.len().min(right.len()).min(output_size);
         let out_left = self.output_left.as_mut_slice(ps);
         let out_right = self.output_right.as_mut_slice(ps);
 
-        out_left[..frame_count].copy_from_slice(&samples[..frame_count]);
-        out_right[..frame_count].copy_from_slice(&samples[..frame_count]);
+        out_left[..frame_count].copy_from_slice(&left[..frame_count]);
+        out_right[..frame_count].copy_from_slice(&right[..frame_count]);
 
         for i in frame_count..output_size {
             out_left[i] = 0.0;
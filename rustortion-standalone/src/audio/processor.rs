@@ -0,0 +1,173 @@
+//! The body of the JACK process callback, kept apart from JACK itself so the
+//! full path from engine messages to the output ports can be driven from
+//! tests with synthetic blocks.
+
+use std::collections::TryReserveError;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use log::error;
+
+use crate::audio::freewheel::InputFeed;
+use rustortion_core::audio::engine::Engine;
+
+/// One cycle's worth of ports: what a [`Processor`] reads its input from and
+/// writes its output to. JACK's process scope in the app, a plain set of
+/// buffers in tests.
+pub trait ProcessContext {
+    /// The left input, or the only one in mono.
+    fn input(&self) -> &[f32];
+    /// The right input. Only read in dual-mono.
+    fn input_right(&self) -> &[f32];
+    /// Write both outputs, silencing whatever of the cycle they don't cover.
+    fn write_output(&mut self, left: &[f32], right: &[f32]);
+    /// Write the metronome's own output.
+    fn write_metronome_output(&mut self, samples: &[f32]);
+    /// Silence both outputs for this cycle.
+    fn silence_output(&mut self);
+}
+
+/// Runs the engine once per cycle, mixes in the click where it's monitored,
+/// and follows the server's buffer size. While a bounce has a file loaded
+/// into its [`InputFeed`], the engine hears that instead of the ports.
+pub struct Processor {
+    engine: Engine,
+    /// Left output; also the mono signal the engine meters and records.
+    left: Vec<f32>,
+    right: Vec<f32>,
+    metronome: Vec<f32>,
+    max_buffer_capacity: usize,
+    /// The server's rate. Until the engine has been retuned to it, the
+    /// output is silenced rather than played at the wrong rate.
+    sample_rate: Arc<AtomicUsize>,
+    input_feed: Arc<InputFeed>,
+    /// The input as the engine gets it while a file is fed.
+    fed: Vec<f32>,
+}
+
+impl Processor {
+    /// Largest period (in frames) sized for without reallocating. Also used
+    /// to size the recorder's buffer pool so a mid-recording buffer-size
+    /// increase up to this bound doesn't start dropping blocks.
+    pub const MAX_BUFFER_FRAMES: usize = 8192;
+
+    pub fn new(engine: Engine, buffer_size: usize, sample_rate: Arc<AtomicUsize>) -> Self {
+        let max_capacity = Self::MAX_BUFFER_FRAMES.max(buffer_size);
+        let buffer = || {
+            let mut buffer = Vec::with_capacity(max_capacity);
+            buffer.resize(buffer_size, 0.0);
+            buffer
+        };
+
+        Self {
+            engine,
+            left: buffer(),
+            right: buffer(),
+            metronome: buffer(),
+            max_buffer_capacity: max_capacity,
+            sample_rate,
+            input_feed: Arc::default(),
+            fed: buffer(),
+        }
+    }
+
+    /// Take the input from `feed` whenever it has a file loaded.
+    pub fn with_input_feed(mut self, feed: Arc<InputFeed>) -> Self {
+        self.input_feed = feed;
+        self
+    }
+
+    /// The engine frame the next cycle starts at.
+    pub fn frames_processed(&self) -> u64 {
+        self.engine.frames_processed()
+    }
+
+    /// Process one cycle from `context`'s inputs to its outputs.
+    pub fn process(&mut self, context: &mut impl ProcessContext) {
+        let fed = self.input_feed.is_loaded();
+        if fed {
+            // The take starting and stopping are messages: apply them before
+            // deciding whether the file rolls this cycle.
+            self.engine.handle_messages();
+            // Fed to the end; hold on silence until the bounce is ended.
+            if self.input_feed.is_finished() {
+                context.silence_output();
+                return;
+            }
+            // Freewheel comes straight back for the next cycle, so outrunning
+            // the disk would drop blocks from the take: wait for the writer
+            // instead, without advancing the file.
+            if self.engine.is_recording() && !self.engine.recorder_has_room() {
+                context.silence_output();
+                return;
+            }
+            self.input_feed
+                .fill(context.input(), &mut self.fed, self.engine.is_recording());
+        }
+
+        let (input, input_right) = if fed {
+            (self.fed.as_slice(), self.fed.as_slice())
+        } else {
+            (context.input(), context.input_right())
+        };
+        if let Err(e) =
+            self.engine
+                .process_stereo_input(input, input_right, &mut self.left, &mut self.right)
+        {
+            error!("Audio processing error: {e}");
+            context.silence_output();
+            return;
+        }
+        if self.engine.process_metronome(&mut self.metronome) {
+            context.write_metronome_output(&self.metronome);
+            // The take was recorded inside `process`, so it stays clean.
+            if self.engine.metronome_in_monitor() {
+                for ((left, right), click) in self
+                    .left
+                    .iter_mut()
+                    .zip(self.right.iter_mut())
+                    .zip(&self.metronome)
+                {
+                    *left += click;
+                    *right += click;
+                }
+            }
+        }
+
+        // Still tuned for the old rate: the messages retuning it are on the way.
+        if self.sample_rate.load(Ordering::Relaxed) != self.engine.sample_rate() {
+            context.silence_output();
+            return;
+        }
+
+        context.write_output(&self.left, &self.right);
+    }
+
+    /// Resize for a new period of `frames`. Within [`Self::MAX_BUFFER_FRAMES`]
+    /// this never allocates; past it, a failed allocation is returned and
+    /// nothing is resized.
+    pub fn set_buffer_size(&mut self, frames: usize) -> Result<(), TryReserveError> {
+        if frames > self.max_buffer_capacity {
+            for buffer in [
+                &mut self.left,
+                &mut self.right,
+                &mut self.metronome,
+                &mut self.fed,
+            ] {
+                buffer.try_reserve(frames.saturating_sub(buffer.capacity()))?;
+            }
+            self.max_buffer_capacity = frames;
+        }
+
+        self.left.resize(frames, 0.0);
+        self.right.resize(frames, 0.0);
+        self.metronome.resize(frames, 0.0);
+        self.fed.resize(frames, 0.0);
+
+        if let Err(e) = self.engine.update_buffer_size(frames) {
+            error!("Failed to update buffer size: {e}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use crossbeam::channel::Sender;
+
+/// Queue depth for transport notifications. The GUI drains it every few tens
+/// of milliseconds; a full queue only drops events nobody is listening for.
+pub const TRANSPORT_EVENT_CAPACITY: usize = 16;
+
+/// A JACK transport state change, reported from the process callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportEvent {
+    /// Transport started rolling at this frame position, in the engine's
+    /// block starting at `engine_frame`.
+    Started {
+        frame: u32,
+        engine_frame: u64,
+    },
+    Stopped,
+}
+
+/// Turns the per-cycle transport state into start/stop events. Only the
+/// rolling state is tracked, so a relocation while rolling is not an event:
+/// a recording in progress simply keeps going.
+pub struct TransportFollower {
+    rolling: bool,
+    events: Sender<TransportEvent>,
+}
+
+impl TransportFollower {
+    /// `rolling` is the transport state when the client starts, so attaching
+    /// to an already-rolling transport doesn't report a spurious start.
+    pub const fn new(rolling: bool, events: Sender<TransportEvent>) -> Self {
+        Self { rolling, events }
+    }
+
+    /// Called once per process cycle, before the engine runs the block
+    /// starting at `engine_frame`. Real-time safe: never allocates or
+    /// blocks; if the GUI has fallen behind, the event is dropped.
+    pub fn update(&mut self, rolling: bool, frame: u32, engine_frame: u64) {
+        if rolling == self.rolling {
+            return;
+        }
+        self.rolling = rolling;
+
+        let event = if rolling {
+            TransportEvent::Started {
+                frame,
+                engine_frame,
+            }
+        } else {
+            TransportEvent::Stopped
+        };
+        let _ = self.events.try_send(event);
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::{Duration, Instant};
+
+use rustortion_core::audio::engine::EngineHandle;
+
+/// Stall threshold used when the settings don't name one.
+pub const DEFAULT_STALL_SECONDS: u32 = 3;
+
+/// Anything with a frame counter that advances while audio is flowing.
+/// Abstracted so the detection logic can be driven without a JACK server.
+pub trait FrameSource {
+    fn frames_processed(&self) -> u64;
+}
+
+impl FrameSource for EngineHandle {
+    fn frames_processed(&self) -> u64 {
+        Self::frames_processed(self)
+    }
+}
+
+/// What a poll found, reported once per transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchdogEvent {
+    /// Nothing new: running normally, or still stalled.
+    None,
+    /// The counter hasn't moved for the threshold while audio should be running.
+    Stalled,
+    /// The counter moved again after a stall.
+    Recovered,
+}
+
+/// Detects a stalled audio callback from the engine's frame counter. Polled
+/// from the GUI about once a second; the callback itself does nothing but
+/// bump the counter.
+pub struct Watchdog {
+    threshold: Duration,
+    last_frames: Option<u64>,
+    last_advance: Instant,
+    stalled: bool,
+}
+
+impl Watchdog {
+    pub const fn new(threshold: Duration, now: Instant) -> Self {
+        Self {
+            threshold,
+            last_frames: None,
+            last_advance: now,
+            stalled: false,
+        }
+    }
+
+    pub const fn set_threshold(&mut self, threshold: Duration) {
+        self.threshold = threshold;
+    }
+
+    pub const fn is_stalled(&self) -> bool {
+        self.stalled
+    }
+
+    /// Forget the counter, e.g. after the engine was replaced and its counter
+    /// started again from zero. The threshold runs from `now`.
+    pub const fn reset(&mut self, now: Instant) {
+        self.last_frames = None;
+        self.last_advance = now;
+        self.stalled = false;
+    }
+
+    /// Check `source` against the last poll. `audio_expected` is whether the
+    /// app believes the stream is running; while it isn't, nothing is a stall.
+    pub fn poll(
+        &mut self,
+        now: Instant,
+        source: &impl FrameSource,
+        audio_expected: bool,
+    ) -> WatchdogEvent {
+        if !audio_expected {
+            self.reset(now);
+            return WatchdogEvent::None;
+        }
+
+        let frames = source.frames_processed();
+        if self.last_frames != Some(frames) {
+            self.last_frames = Some(frames);
+            self.last_advance = now;
+            if self.stalled {
+                self.stalled = false;
+                return WatchdogEvent::Recovered;
+            }
+            return WatchdogEvent::None;
+        }
+
+        if !self.stalled && now.duration_since(self.last_advance) >= self.threshold {
+            self.stalled = true;
+            return WatchdogEvent::Stalled;
+        }
+        WatchdogEvent::None
+    }
+
+    /// How long the counter has been still, as of `now`.
+    pub fn stalled_for(&self, now: Instant) -> Duration {
+        now.duration_since(self.last_advance)
+    }
+}
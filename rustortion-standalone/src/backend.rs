@@ -1,20 +1,35 @@
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicU32, Ordering};
 
-use log::error;
-use rustortion_core::amp::chain::AmplifierChain;
-use rustortion_core::amp::stages::Stage;
-use rustortion_core::amp::stages::filter::{FilterStage, FilterType};
+use log::{error, info};
+use rustortion_core::amp::crossfade::StageCrossfade;
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::automation::AutomationLog;
+use rustortion_core::audio::denoiser::{DenoiseConfig, NoiseLearn};
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::audio::dual_mono::{ChainMode, RightMessage};
+use rustortion_core::audio::engine::{EngineState, MonitorPoint};
+use rustortion_core::audio::peak_meter::PeakMeterHandle;
+use rustortion_core::audio::recorder::RecordingStatus;
 use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::ir::load_service::IrLoadFailure;
+use rustortion_core::ir::loader::{IrError, IrMetadata};
+use rustortion_core::ir::thumbnail::ThumbnailLookup;
 use rustortion_core::preset::InputFilterConfig;
 use rustortion_core::preset::stage_config::StageConfig;
 use rustortion_ui::backend::{Capabilities, ExternalEvent, ParamBackend};
 
+use crate::audio::bootstrap::{build_amp_chain, build_input_filters};
 use crate::audio::manager::Manager;
 
 pub struct StandaloneBackend {
     manager: Manager,
     capabilities: Capabilities,
     oversampling_factor: AtomicU32,
+    /// Parameter changes of the take being recorded, when automation
+    /// recording is on. Only touched from the GUI thread.
+    automation: Mutex<Option<AutomationLog>>,
 }
 
 impl StandaloneBackend {
@@ -24,6 +39,7 @@ impl StandaloneBackend {
             manager,
             capabilities: Capabilities::standalone(),
             oversampling_factor: AtomicU32::new(factor),
+            automation: Mutex::new(None),
         }
     }
 
@@ -35,6 +51,40 @@ impl StandaloneBackend {
         &mut self.manager
     }
 
+    /// Offer tempo sync while the metronome is on, since its tempo is what
+    /// the delays follow.
+    pub const fn set_tempo_sync(&mut self, metronome_enabled: bool) {
+        self.capabilities.has_tempo_sync = metronome_enabled;
+    }
+
+    /// Log every parameter change sent from now on into `log`.
+    pub fn start_automation(&self, log: AutomationLog) {
+        *self.lock_automation() = Some(log);
+    }
+
+    /// Write the take's automation sidecar, if one was being logged.
+    pub fn finish_automation(&self) {
+        let Some(log) = self.lock_automation().take() else {
+            return;
+        };
+        let take_start = self.manager.engine().recording_start_frame();
+        let (path, automation) = log.finish(take_start);
+        match automation.save(&path) {
+            Ok(()) => info!(
+                "Saved {} automation events to {}",
+                automation.events.len(),
+                path.display()
+            ),
+            Err(e) => error!("Failed to save automation: {e:#}"),
+        }
+    }
+
+    fn lock_automation(&self) -> std::sync::MutexGuard<'_, Option<AutomationLog>> {
+        self.automation
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
     fn effective_sample_rate(&self) -> usize {
         self.manager.sample_rate() * self.oversampling_factor() as usize
     }
@@ -42,29 +92,76 @@ impl StandaloneBackend {
 
 impl ParamBackend for StandaloneBackend {
     fn set_parameter(&self, stage_idx: usize, name: &'static str, value: f32) {
-        self.manager.engine().set_parameter(stage_idx, name, value);
+        let engine = self.manager.engine();
+        if let Some(log) = self.lock_automation().as_mut() {
+            log.push(engine.frames_processed(), stage_idx, name, value);
+        }
+        engine.set_parameter(stage_idx, name, value);
+    }
+
+    fn set_child_parameter(&self, stage_idx: usize, child: usize, name: &'static str, value: f32) {
+        self.manager
+            .engine()
+            .set_child_parameter(stage_idx, child, name, value);
     }
 
     fn rebuild_stage(&self, stage_idx: usize, config: &StageConfig) {
         let sr = self.effective_sample_rate() as f32;
         let runtime_stage = config.to_runtime(sr);
-        self.manager
-            .engine()
-            .replace_stage(stage_idx, runtime_stage);
+        let engine = self.manager.engine();
+        engine.replace_stage(stage_idx, runtime_stage);
+        if engine.chain_mode() == ChainMode::DualMono {
+            engine.send_right(RightMessage::ReplaceStage(stage_idx, config.to_runtime(sr)));
+        }
+    }
+
+    fn swap_stage(&self, stage_idx: usize, config: &StageConfig) {
+        let sr = self.effective_sample_rate() as f32;
+        let engine = self.manager.engine();
+        engine.crossfade_stage(stage_idx, StageCrossfade::new(config.to_runtime(sr), sr));
+        if engine.chain_mode() == ChainMode::DualMono {
+            engine.send_right(RightMessage::CrossfadeStage(
+                stage_idx,
+                Box::new(StageCrossfade::new(config.to_runtime(sr), sr)),
+            ));
+        }
     }
 
     fn set_amp_chain(&self, stages: &[StageConfig]) {
-        let sr = self.effective_sample_rate();
-        let mut chain = AmplifierChain::new();
-        for cfg in stages {
-            chain.add_stage(cfg.to_runtime(sr as f32));
+        let sr = self.effective_sample_rate() as f32;
+        let engine = self.manager.engine();
+        engine.set_amp_chain(build_amp_chain(stages, sr));
+        if engine.chain_mode() == ChainMode::DualMono {
+            engine.send_right(RightMessage::SetAmpChain(Box::new(build_amp_chain(
+                stages, sr,
+            ))));
         }
-        for (i, cfg) in stages.iter().enumerate() {
-            if cfg.bypassed() {
-                chain.set_bypassed(i, true);
-            }
+    }
+
+    fn queue_amp_chain(&self, stages: &[StageConfig]) {
+        let engine = self.manager.engine();
+        // Each side of a dual mono chain swaps on its own; don't split them.
+        if engine.chain_mode() == ChainMode::DualMono {
+            self.set_amp_chain(stages);
+            return;
         }
-        self.manager.engine().set_amp_chain(chain);
+        let sr = self.effective_sample_rate() as f32;
+        engine.queue_amp_chain(build_amp_chain(stages, sr));
+    }
+
+    fn hold_amp_chain(&self, stages: &[StageConfig]) {
+        let engine = self.manager.engine();
+        // Each side of a dual mono chain swaps on its own; don't split them.
+        if engine.chain_mode() == ChainMode::DualMono {
+            self.set_amp_chain(stages);
+            return;
+        }
+        let sr = self.effective_sample_rate() as f32;
+        engine.hold_amp_chain(build_amp_chain(stages, sr));
+    }
+
+    fn chain_queued(&self) -> bool {
+        self.manager.engine().chain_queued()
     }
 
     fn set_bypass(&self, stage_idx: usize, bypassed: bool) {
@@ -76,7 +173,11 @@ impl ParamBackend for StandaloneBackend {
     fn add_stage(&self, idx: usize, config: &StageConfig) {
         let sr = self.effective_sample_rate() as f32;
         let runtime_stage = config.to_runtime(sr);
-        self.manager.engine().add_stage(idx, runtime_stage);
+        let engine = self.manager.engine();
+        engine.add_stage(idx, runtime_stage);
+        if engine.chain_mode() == ChainMode::DualMono {
+            engine.send_right(RightMessage::AddStage(idx, config.to_runtime(sr)));
+        }
     }
 
     fn remove_stage(&self, idx: usize) {
@@ -87,8 +188,16 @@ impl ParamBackend for StandaloneBackend {
         self.manager.engine().swap_stages(a, b);
     }
 
-    fn set_ir(&self, name: &str) {
-        self.manager.request_ir_load(name);
+    fn set_monitor_point(&self, point: Option<MonitorPoint>) {
+        self.manager.engine().set_monitor_point(point);
+    }
+
+    fn set_standby(&self, on: bool) {
+        self.manager.engine().set_standby(on);
+    }
+
+    fn set_ir(&self, name: &str, alignment: IrAlignment) -> Result<(), IrError> {
+        self.manager.request_ir_load(name, alignment)
     }
 
     fn set_ir_bypass(&self, bypassed: bool) {
@@ -99,42 +208,70 @@ impl ParamBackend for StandaloneBackend {
         self.manager.engine().set_ir_gain(gain);
     }
 
+    fn set_ir_trim_db(&self, trim_db: f32) {
+        self.manager.engine().set_ir_trim_db(trim_db);
+    }
+
     fn set_input_filter(&self, filter: &InputFilterConfig) {
         let sample_rate = self.manager.sample_rate() as f32;
-        let hp: Option<Box<dyn Stage>> = if filter.hp_enabled {
-            Some(Box::new(FilterStage::new(
-                FilterType::Highpass,
-                filter.hp_cutoff,
-                sample_rate,
-            )))
-        } else {
-            None
-        };
-        let lp: Option<Box<dyn Stage>> = if filter.lp_enabled {
-            Some(Box::new(FilterStage::new(
-                FilterType::Lowpass,
-                filter.lp_cutoff,
-                sample_rate,
-            )))
-        } else {
-            None
-        };
-        self.manager.engine().set_input_filters(hp, lp);
+        let (hp, lp) = build_input_filters(filter, sample_rate);
+        let engine = self.manager.engine();
+        engine.set_input_filters(hp, lp);
+        if engine.chain_mode() == ChainMode::DualMono {
+            let (hp, lp) = build_input_filters(filter, sample_rate);
+            engine.send_right(RightMessage::SetInputFilters(hp, lp));
+        }
+    }
+
+    fn set_denoiser(&self, config: &DenoiseConfig) {
+        self.manager
+            .engine()
+            .set_denoiser(config, self.manager.sample_rate());
+    }
+
+    fn learn_noise(&self) -> NoiseLearn {
+        self.manager
+            .engine()
+            .learn_noise(self.manager.sample_rate())
     }
 
     fn set_pitch_shift(&self, semitones: i32) {
         self.manager.engine().set_pitch_shift(semitones);
     }
 
+    fn set_ambience(&self, config: AmbienceConfig) {
+        self.manager.engine().set_ambience(config);
+    }
+
+    fn set_double_tracker(&self, config: DoubleTrackerConfig) {
+        self.manager.engine().set_double_tracker(config);
+    }
+
+    fn set_tilt_db(&self, tilt_db: f32) {
+        self.manager.engine().set_tilt_db(tilt_db);
+    }
+
     fn set_oversampling(&self, factor: u32) {
         let sample_rate = self.manager.sample_rate();
         let buffer_size = self.manager.buffer_size();
-        match Samplers::new(buffer_size, f64::from(factor), sample_rate) {
+        let quality = self.manager.resampler_quality();
+        let engine = self.manager.engine();
+        let build = || Samplers::with_quality(buffer_size, f64::from(factor), sample_rate, quality);
+        match build() {
             Ok(samplers) => {
-                self.manager.engine().set_samplers(samplers);
+                engine.set_samplers(samplers);
                 self.oversampling_factor.store(factor, Ordering::Relaxed);
             }
-            Err(e) => error!("Failed to create samplers for {factor}x oversampling: {e}"),
+            Err(e) => {
+                error!("Failed to create samplers for {factor}x oversampling: {e}");
+                return;
+            }
+        }
+        if engine.chain_mode() == ChainMode::DualMono {
+            match build() {
+                Ok(samplers) => engine.send_right(RightMessage::SetSamplers(Box::new(samplers))),
+                Err(e) => error!("Failed to create right samplers for {factor}x oversampling: {e}"),
+            }
         }
     }
 
@@ -154,6 +291,10 @@ impl ParamBackend for StandaloneBackend {
         self.manager.get_available_irs()
     }
 
+    fn get_available_irs_with_metadata(&self) -> Vec<IrMetadata> {
+        self.manager.get_available_irs_with_metadata()
+    }
+
     fn nam_models_dir(&self) -> Option<std::path::PathBuf> {
         Some(std::path::PathBuf::from(self.manager.nam_dir()))
     }
@@ -163,6 +304,50 @@ impl ParamBackend for StandaloneBackend {
         self.manager.rescan_nam_models(&dir)
     }
 
+    fn captures_dir(&self) -> Option<std::path::PathBuf> {
+        Some(std::path::PathBuf::from(self.manager.captures_dir()))
+    }
+
+    fn rescan_captures(&self) -> Result<usize, String> {
+        self.manager.rescan_captures()
+    }
+
+    fn get_flagged_irs(&self) -> Vec<String> {
+        self.manager.get_flagged_irs()
+    }
+
+    fn ir_onset(&self, name: &str) -> Option<usize> {
+        self.manager.ir_onset(name)
+    }
+
+    fn ir_energy(&self, name: &str) -> Option<f32> {
+        self.manager.ir_energy(name)
+    }
+
+    fn preload_ir(&self, name: &str) {
+        self.manager.preload_irs(&[name.to_owned()]);
+    }
+
+    fn ir_thumbnail(&self, name: &str) -> ThumbnailLookup {
+        self.manager.ir_thumbnail(name)
+    }
+
+    fn take_ir_load_failure(&self) -> Option<IrLoadFailure> {
+        self.manager.take_ir_load_failure()
+    }
+
+    fn ir_load_pending(&self) -> bool {
+        self.manager.ir_load_pending()
+    }
+
+    fn engine_state(&self) -> Option<EngineState> {
+        self.manager.engine().state()
+    }
+
+    fn recording_status(&self) -> Option<RecordingStatus> {
+        self.manager.engine().recording_status()
+    }
+
     fn get_peak_meter_info(&self) -> Option<ExternalEvent> {
         let info = self.manager.peak_meter().get_info();
         let xrun_count = self.manager.xrun_count();
@@ -171,6 +356,16 @@ impl ParamBackend for StandaloneBackend {
             info,
             xrun_count,
             cpu_load,
+            reduced_quality: self.manager.reduced_quality(),
+            right: self.manager.right_meter().map(PeakMeterHandle::get_info),
         })
     }
+
+    fn set_stage_levels_enabled(&self, enabled: bool) {
+        self.manager.stage_peaks().set_live(enabled);
+    }
+
+    fn take_stage_levels(&self, len: usize) -> Vec<f32> {
+        self.manager.stage_peaks().take_live(len)
+    }
 }
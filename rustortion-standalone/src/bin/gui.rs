@@ -1,13 +1,49 @@
 #![allow(clippy::missing_errors_doc)]
 
-use anyhow::Result;
-use log::info;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use log::{info, warn};
 use rustortion::gui::start;
 use rustortion::settings::Settings;
+use rustortion_core::audio::offline::{read_mono_wav, render_file};
+use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::nam::{NamLoader, registry as nam_registry};
+use rustortion_core::preset::Manager as PresetManager;
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render a WAV file through a saved preset without starting JACK.
+    Render {
+        /// Name of the preset to render with.
+        #[arg(long)]
+        preset: String,
+        /// Dry input WAV file (multi-channel input is mixed to mono).
+        input: PathBuf,
+        /// Output WAV file, written as 32-bit float mono.
+        output: PathBuf,
+        /// Oversampling factor; defaults to the one in settings.
+        #[arg(long)]
+        oversampling: Option<u32>,
+        /// Render sample rate; defaults to the input file's rate.
+        #[arg(long)]
+        sample_rate: Option<usize>,
+    },
+}
 
 pub fn main() -> Result<()> {
+    let cli = Cli::parse();
     dotenv::dotenv().ok();
 
+    let first_run = Settings::is_first_run();
     let settings = Settings::load().unwrap_or_else(|e| {
         info!("Could not load settings, using defaults: {e}");
         Settings::default()
@@ -17,6 +53,25 @@ pub fn main() -> Result<()> {
 
     env_logger::init();
 
+    if let Some(Command::Render {
+        preset,
+        input,
+        output,
+        oversampling,
+        sample_rate,
+    }) = cli.command
+    {
+        let oversampling = oversampling.unwrap_or(settings.audio.oversampling_factor);
+        return render(
+            &settings,
+            &preset,
+            &input,
+            &output,
+            oversampling,
+            sample_rate,
+        );
+    }
+
     info!(
         r"
 __________                __                 __  .__
@@ -30,7 +85,58 @@ __________                __                 __  .__
     info!("v{}", env!("CARGO_PKG_VERSION"));
     info!("{settings}");
 
-    start(settings).map_err(|e| anyhow::anyhow!("GUI error: {e}"))?;
+    start(settings, first_run).map_err(|e| anyhow::anyhow!("GUI error: {e}"))?;
 
     Ok(())
 }
+
+fn render(
+    settings: &Settings,
+    preset_name: &str,
+    input: &Path,
+    output: &Path,
+    oversampling: u32,
+    sample_rate: Option<usize>,
+) -> Result<()> {
+    let presets = PresetManager::new(&settings.paths.preset_dir)?;
+    let preset = presets.get_preset_by_name(preset_name).with_context(|| {
+        format!(
+            "Preset '{preset_name}' not found in {}",
+            settings.paths.preset_dir
+        )
+    })?;
+
+    let sample_rate = match sample_rate {
+        Some(rate) => rate,
+        None => read_mono_wav(input)?.1 as usize,
+    };
+
+    match NamLoader::new(Path::new(&settings.paths.nam_dir)) {
+        Ok(loader) => nam_registry::init_from_loader(&loader),
+        Err(e) => warn!("Failed to load NAM models: {e}"),
+    }
+
+    let ir_loader = IrLoader::with_max_seconds(
+        Path::new(&settings.paths.ir_dir),
+        sample_rate,
+        settings.ir_max_seconds,
+    )
+    .map_err(|e| warn!("Failed to open IR directory: {e}"))
+    .ok();
+
+    let mut next_report = 0.1;
+    render_file(
+        preset,
+        input,
+        output,
+        sample_rate,
+        oversampling,
+        ir_loader.as_ref(),
+        |progress| {
+            if progress >= next_report {
+                info!("Rendering: {:.0}%", progress * 100.0);
+                next_report += 0.1;
+            }
+        },
+    )
+}
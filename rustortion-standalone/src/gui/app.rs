@@ -1,43 +1,142 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Instant;
 
-use iced::widget::container;
-use iced::{Element, Length, Subscription, Task, Theme, time, time::Duration};
-use log::{debug, error};
+use iced::widget::{column, container};
+use iced::{
+    Element, Length, Subscription, Task, Theme, event, system, theme, time, time::Duration, window,
+};
+use log::{debug, error, warn};
 
+use crate::audio::bootstrap::EngineBootstrap;
 use crate::audio::manager::Manager;
+use crate::audio::transport::TransportEvent;
 use crate::backend::StandaloneBackend;
-use crate::gui::handlers::midi::MidiHandler;
+use crate::gui::components::startup_error::StartupError;
+use crate::gui::handlers::calibration::CalibrationHandler;
+use crate::gui::handlers::gain_staging::GainStagingHandler;
+use crate::gui::handlers::hooks::HooksHandler;
+use crate::gui::handlers::import::ImportHandler;
+use crate::gui::handlers::mappings::MappingsHandler;
+use crate::gui::handlers::midi::{
+    MidiHandler, PresetSwitch, SwitchStates, SwitchTarget, wakeup_stream,
+};
+use crate::gui::handlers::remote::RemoteHandler;
+use crate::gui::handlers::render::{ManagerBounceHost, RenderHandler, RenderRequest};
+use crate::gui::handlers::setlist::SetlistHandler;
 use crate::gui::handlers::settings::SettingsHandler;
+use crate::gui::handlers::setup::SetupHandler;
+use crate::gui::handlers::takes::TakesHandler;
+use crate::gui::handlers::tone_match::ToneMatchHandler;
 use crate::gui::handlers::tuner::TunerHandler;
+use crate::gui::handlers::watchdog::WatchdogHandler;
+use crate::gui::handlers::window::WindowHandler;
+use crate::import::{ImportReport, ImportRequest};
 use crate::midi::start_midi_manager;
-use crate::settings::Settings;
+use crate::settings::{LayoutSettings, PathSettings, Settings};
+use crate::watcher::{DirWatcher, WatchedDir, WatchedDirs};
+use rustortion_core::audio::automation::AutomationLog;
+use rustortion_core::audio::diagnostics::Diagnostics;
+use rustortion_core::audio::recorder::TransportStart;
+use rustortion_core::audio::recording_info::{RecordingMetadata, TakeMetronome};
+use rustortion_core::audio::take_player::MAX_TAKES;
+use rustortion_core::ir::load_service::IrLoadFailure;
+use rustortion_core::preset::Preset;
+use rustortion_core::preset::tempo::apply_tempo;
 use rustortion_ui::app::{SharedApp, UpdateResult};
 use rustortion_ui::backend::ParamBackend;
+use rustortion_ui::components::ambience_control::AmbienceControl;
+use rustortion_ui::components::denoiser_control::DenoiserControl;
+use rustortion_ui::components::dialogs::macro_editor::MacroEditorDialog;
+use rustortion_ui::components::double_tracker_control::DoubleTrackerControl;
 use rustortion_ui::components::ir_cabinet_control::IrCabinetControl;
+use rustortion_ui::components::missing_ir_banner::MissingIr;
 use rustortion_ui::components::peak_meter::PeakMeterDisplay;
 use rustortion_ui::components::pitch_shift_control::PitchShiftControl;
+use rustortion_ui::components::recording_status::RecordingStatusDisplay;
+use rustortion_ui::components::stage_levels::StageLevels;
+use rustortion_ui::components::tilt_control::TiltControl;
+use rustortion_ui::components::widgets::search_select;
 use rustortion_ui::handlers::hotkey::HotkeyHandler;
 use rustortion_ui::handlers::preset::PresetHandler;
 use rustortion_ui::i18n;
-use rustortion_ui::messages::{HotkeyMessage, Message, MidiMessage, PresetMessage, TunerMessage};
+use rustortion_ui::mappings::Binding;
+use rustortion_ui::messages::{
+    CalibrationMessage, GainStagingMessage, HotkeyMessage, ImportMessage, MappingsMessage, Message,
+    MidiMessage, PresetMessage, RenderMessage, SetlistMessage, SettingsMessage, SetupMessage,
+    TakesMessage, ToneMatchMessage, TunerMessage, WindowMessage,
+};
+use rustortion_ui::randomize::{RandomizeIntensity, XorShift};
 use rustortion_ui::stages::StageType;
 use rustortion_ui::tabs::Tab;
+use rustortion_ui::theme::ThemeChoice;
 
 const TUNER_POLL_INTERVAL: Duration = Duration::from_millis(20);
 const MIDI_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const RENDER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const TONE_MATCH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const TAKES_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const IMPORT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const TRANSPORT_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const WATCHDOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const WARM_START_POLL_INTERVAL: Duration = Duration::from_millis(10);
+const IR_RESCAN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const DIR_WATCH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+const REMOTE_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const WINDOW_SAVE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The audio stream and presets, opened by [`AmplifierApp::open`].
+pub struct Startup {
+    audio_manager: Manager,
+    preset_handler: PresetHandler,
+}
 
 pub struct AmplifierApp {
     shared: SharedApp<StandaloneBackend>,
     settings: Settings,
     settings_handler: SettingsHandler,
+    setup_handler: SetupHandler,
+    calibration_handler: CalibrationHandler,
+    gain_staging_handler: GainStagingHandler,
     tuner_handler: TunerHandler,
     midi_handler: MidiHandler,
+    mappings_handler: MappingsHandler,
+    render_handler: RenderHandler,
+    tone_match_handler: ToneMatchHandler,
+    takes_handler: TakesHandler,
+    setlist_handler: SetlistHandler,
+    import_handler: ImportHandler,
+    watchdog_handler: WatchdogHandler,
+    remote_handler: RemoteHandler,
+    hooks_handler: HooksHandler,
+    window_handler: WindowHandler,
+    /// Rescans the IR and preset lists when their directories change.
+    dir_watcher: Option<DirWatcher>,
+    /// Last light/dark mode the platform reported, for "follow system".
+    system_theme: theme::Mode,
 }
 
 impl AmplifierApp {
-    pub fn boot(settings: Settings) -> (Self, Task<Message>) {
-        let audio_manager = Manager::new(settings.clone()).unwrap();
-        let mut preset_handler = PresetHandler::new(&settings.preset_dir).unwrap();
+    /// Open what the app can't run without, before any window exists, so a
+    /// failure can be shown instead of panicking in [`Self::boot`].
+    pub fn open(settings: &Settings) -> Result<Startup, StartupError> {
+        let audio_manager = Manager::new(settings.clone())?;
+        let preset_handler = PresetHandler::new(&settings.paths.preset_dir)?;
+        Ok(Startup {
+            audio_manager,
+            preset_handler,
+        })
+    }
+
+    pub fn boot(settings: Settings, startup: Startup) -> (Self, Task<Message>) {
+        let Startup {
+            audio_manager,
+            mut preset_handler,
+        } = startup;
+        preset_handler.set_recent(
+            settings.recent_presets.clone(),
+            settings.recent_presets_limit,
+        );
 
         // Try and load the last opened preset
         if let Some(last_opened_preset) = settings.selected_preset.as_deref() {
@@ -47,11 +146,27 @@ impl AmplifierApp {
         let preset = preset_handler.get_selected_preset().unwrap_or_default();
 
         let settings_handler = SettingsHandler::new(&settings.audio);
+        let tuner_handler = TunerHandler::new(settings.tuner_mode);
+        let watchdog_handler = WatchdogHandler::new(settings.watchdog.stall_seconds);
+        let remote_handler = RemoteHandler::new(&settings.remote);
+        let hooks_handler = HooksHandler::new(&settings.hooks, preset_handler.selected_name());
+        let setlist_handler = SetlistHandler::new(
+            &settings.paths.setlist_dir,
+            settings.active_setlist.as_deref(),
+            settings.setlist_position,
+        );
 
         let mut ir_cabinet_control = IrCabinetControl::new(settings.ir_bypassed, preset.ir_gain);
+        ir_cabinet_control.set_alignment(preset.ir_alignment);
         ir_cabinet_control.set_available_irs(audio_manager.get_available_irs());
+        ir_cabinet_control.set_ir_metadata(audio_manager.get_available_irs_with_metadata());
+        ir_cabinet_control.set_flagged_irs(audio_manager.get_flagged_irs());
 
         let pitch_shift_control = PitchShiftControl::new(preset.pitch_shift_semitones);
+        let ambience_control = AmbienceControl::new(preset.ambience);
+        let double_tracker_control = DoubleTrackerControl::new(preset.double_tracker);
+        let denoiser_control = DenoiserControl::new(preset.denoise.clone());
+        let tilt_control = TiltControl::new(settings.tilt_db, preset.tilt_offset_db);
 
         if settings.ir_bypassed {
             audio_manager.engine().set_ir_bypass(true);
@@ -62,13 +177,38 @@ impl AmplifierApp {
         audio_manager
             .engine()
             .set_pitch_shift(preset.pitch_shift_semitones);
+        audio_manager
+            .engine()
+            .set_denoiser(&preset.denoise, audio_manager.sample_rate());
+        audio_manager.engine().set_ambience(preset.ambience);
+        audio_manager
+            .engine()
+            .set_double_tracker(preset.double_tracker);
+        audio_manager
+            .engine()
+            .set_tilt_db(tilt_control.effective_db());
 
-        if let Some(ir_name) = preset.ir_name {
-            ir_cabinet_control.set_selected_ir(Some(ir_name.clone()));
-            audio_manager.request_ir_load(&ir_name);
-        } else if let Some(first_ir) = ir_cabinet_control.get_selected_ir() {
-            ir_cabinet_control.set_selected_ir(Some(first_ir.clone()));
-            audio_manager.request_ir_load(&first_ir);
+        // A missing IR falls through to the first one, with the banner up.
+        let missing_ir = preset.ir_name.as_deref().and_then(|ir_name| {
+            MissingIr::detect(&preset.name, ir_name, ir_cabinet_control.available_irs())
+        });
+        let boot_ir = match &preset.ir_name {
+            Some(ir_name) if missing_ir.is_none() => Some(ir_name.clone()),
+            _ => ir_cabinet_control.get_selected_ir(),
+        };
+        let mut ir_error = None;
+        if let Some(ir_name) = boot_ir {
+            match audio_manager.request_ir_load(&ir_name, preset.ir_alignment) {
+                Ok(()) => ir_cabinet_control.set_selected_ir(Some(ir_name)),
+                Err(error) => {
+                    warn!("IR '{ir_name}' refused at startup: {error}");
+                    ir_cabinet_control.set_selected_ir(None);
+                    ir_error = Some(IrLoadFailure {
+                        name: ir_name,
+                        error,
+                    });
+                }
+            }
         }
 
         // Preload IRs referenced by presets
@@ -109,16 +249,25 @@ impl AmplifierApp {
         let mut settings = settings;
         settings.selected_preset = Some(preset.name.clone());
 
-        let collapsed_stages = Self::restore_collapsed(
-            &settings.collapsed_stages,
-            &preset.name,
-            preset.stages.len(),
-        );
+        // A preset saved with collapse state opens with it; otherwise use the
+        // state last seen for it.
+        let collapsed_stages = if preset.collapsed.is_empty() {
+            Self::restore_collapsed(
+                &settings.collapsed_stages,
+                &preset.name,
+                preset.stages.len(),
+            )
+        } else {
+            let mut collapsed = preset.collapsed.clone();
+            collapsed.resize(preset.stages.len(), false);
+            collapsed
+        };
 
         let input_filter_config = preset.input_filters;
 
         // Build the standalone backend
-        let backend = StandaloneBackend::new(audio_manager);
+        let mut backend = StandaloneBackend::new(audio_manager);
+        backend.set_tempo_sync(settings.metronome.enabled);
 
         // Send initial input filters to engine
         backend.set_input_filter(&input_filter_config);
@@ -127,7 +276,7 @@ impl AmplifierApp {
         backend.set_amp_chain(&preset.stages);
 
         let oversampling_factor = backend.oversampling_factor();
-        let shared = SharedApp {
+        let mut shared = SharedApp {
             backend,
             stages: preset.stages,
             collapsed_stages,
@@ -135,35 +284,115 @@ impl AmplifierApp {
             active_tab: Tab::default(),
             selected_stage_type: StageType::default(),
             ir_cabinet_control,
+            ambience_control,
+            double_tracker_control,
             pitch_shift_control,
+            tilt_control,
             preset_handler,
             peak_meter_display: PeakMeterDisplay::new(),
             hotkey_handler,
             input_filter_config,
+            denoiser_control,
             oversampling_factor,
             is_recording: false,
+            recording_status: RecordingStatusDisplay::default(),
+            stage_drag: None,
+            focused_stage: None,
+            keyboard_focus: None,
+            missing_ir,
+            ir_error,
+            lint_enabled: settings.chain_lint,
+            keep_locked_stages: settings.keep_locked_stages,
+            quantize_chain_changes: settings.quantize_chain_changes && settings.metronome.enabled,
+            pending_preset: None,
+            pending_preset_shown: false,
+            lint_warnings: Vec::new(),
+            randomize_intensity: RandomizeIntensity::default(),
+            rng: XorShift::from_clock(),
+            undo_stack: Vec::new(),
+            solo: None,
+            shift_held: false,
+            // The engine starts in standby, so nothing sounds until asked.
+            standby: true,
+            macros: preset.macros,
+            macro_editor: MacroEditorDialog::new(),
+            stage_levels: StageLevels::new(),
         };
+        shared.relint();
+        shared.select_tab(settings.layout.tab);
+        if settings.layout.stage_levels {
+            shared.stage_levels.set_enabled(true);
+            shared.backend.set_stage_levels_enabled(true);
+        }
+
+        let mut app = Self {
+            shared,
+            settings,
+            settings_handler,
+            setup_handler: SetupHandler::new(),
+            calibration_handler: CalibrationHandler::new(),
+            gain_staging_handler: GainStagingHandler::new(),
+            tuner_handler,
+            midi_handler,
+            mappings_handler: MappingsHandler::new(),
+            render_handler: RenderHandler::new(),
+            tone_match_handler: ToneMatchHandler::new(),
+            takes_handler: TakesHandler::new(),
+            setlist_handler,
+            import_handler: ImportHandler::new(),
+            watchdog_handler,
+            remote_handler,
+            hooks_handler,
+            window_handler: WindowHandler::new(),
+            dir_watcher: None,
+            system_theme: theme::Mode::None,
+        };
+        app.watch_dirs();
 
         (
-            Self {
-                shared,
-                settings,
-                settings_handler,
-                tuner_handler: TunerHandler::new(),
-                midi_handler,
-            },
-            Task::none(),
+            app,
+            Task::batch([
+                system::theme()
+                    .map(|mode| Message::Settings(SettingsMessage::SystemThemeChanged(mode))),
+                window::oldest()
+                    .and_then(|id| Task::done(Message::Window(WindowMessage::Opened(id)))),
+            ]),
         )
     }
 
+    /// Walk a new install through the first-run setup.
+    pub fn open_setup(&mut self) {
+        self.setup_handler
+            .open(&self.settings, self.shared.backend.manager());
+    }
+
     pub fn view(&self) -> Element<'_, Message> {
-        let main_content = self.shared.view();
+        let presets = self.shared.preset_handler.get_available_presets();
+        let banners = [
+            self.watchdog_handler.view(),
+            self.import_handler.view(),
+            self.setlist_handler.view_now_playing(presets),
+        ];
+        let main_content: Element<'_, Message> = column(banners.into_iter().flatten())
+            .push(self.shared.view())
+            .into();
 
         let dialogs = [
+            self.setup_handler.view(),
             self.settings_handler.view(),
+            self.calibration_handler.view(),
+            self.gain_staging_handler.view(),
             self.tuner_handler.view(),
             self.midi_handler.view(),
             self.shared.hotkey_handler.view(),
+            self.shared.macro_editor_view(),
+            self.mappings_handler.view(self.bindings()),
+            self.shared.preset_handler.diff_view(),
+            self.shared.preset_handler.manage_view(),
+            self.render_handler.view(),
+            self.tone_match_handler.view(),
+            self.takes_handler.view(),
+            self.setlist_handler.view(presets),
         ];
 
         if let Some(dialog) = dialogs.into_iter().flatten().next() {
@@ -176,8 +405,8 @@ impl AmplifierApp {
         }
     }
 
-    pub const fn theme(&self) -> Theme {
-        Theme::TokyoNight
+    pub fn theme(&self) -> Theme {
+        self.settings.theme.resolve(self.system_theme)
     }
 
     pub fn subscription(&self) -> Subscription<Message> {
@@ -189,15 +418,123 @@ impl AmplifierApp {
             Subscription::none()
         };
 
+        // The tick keeps the dialog and connection state current; input
+        // wakes the handler up straight away.
         let midi_sub = if self.midi_handler.is_visible()
             || self.midi_handler.get_selected_controller().is_some()
         {
-            time::every(MIDI_POLL_INTERVAL).map(|_| Message::Midi(MidiMessage::Update))
+            Subscription::batch([
+                time::every(MIDI_POLL_INTERVAL).map(|_| Message::Midi(MidiMessage::Update)),
+                Subscription::run_with(self.midi_handler.wakeups(), wakeup_stream),
+            ])
+        } else {
+            Subscription::none()
+        };
+
+        let render_sub = if self.render_handler.is_running() {
+            time::every(RENDER_POLL_INTERVAL).map(|_| Message::Render(RenderMessage::Update))
+        } else {
+            Subscription::none()
+        };
+
+        let tone_match_sub = if self.tone_match_handler.is_running() {
+            time::every(TONE_MATCH_POLL_INTERVAL)
+                .map(|_| Message::ToneMatch(ToneMatchMessage::Update))
+        } else {
+            Subscription::none()
+        };
+
+        let takes_sub = if self.takes_handler.is_playing() {
+            time::every(TAKES_POLL_INTERVAL).map(|_| Message::Takes(TakesMessage::Update))
+        } else {
+            Subscription::none()
+        };
+
+        let window_sub = event::listen_with(|event, _status, id| match event {
+            iced::Event::Window(window::Event::FileDropped(path)) => {
+                Some(Message::Import(ImportMessage::FileDropped(path)))
+            }
+            iced::Event::Window(window::Event::Moved(position)) => {
+                Some(Message::Window(WindowMessage::Moved(id, position)))
+            }
+            iced::Event::Window(window::Event::Resized(size)) => {
+                Some(Message::Window(WindowMessage::Resized(id, size)))
+            }
+            iced::Event::Window(window::Event::CloseRequested) => {
+                Some(Message::Window(WindowMessage::CloseRequested(id)))
+            }
+            _ => None,
+        });
+
+        let window_save_sub = if self.window_handler.is_pending() {
+            time::every(WINDOW_SAVE_POLL_INTERVAL).map(|_| Message::Window(WindowMessage::SaveTick))
+        } else {
+            Subscription::none()
+        };
+
+        let import_sub = if self.import_handler.is_active() {
+            time::every(IMPORT_POLL_INTERVAL).map(|_| Message::Import(ImportMessage::Update))
+        } else {
+            Subscription::none()
+        };
+
+        let transport_sub = if self.settings.record_follow_transport {
+            time::every(TRANSPORT_POLL_INTERVAL).map(|_| Message::TransportTick)
         } else {
             Subscription::none()
         };
 
-        Subscription::batch(vec![shared_sub, tuner_sub, midi_sub])
+        let watchdog_sub = time::every(WATCHDOG_POLL_INTERVAL).map(|_| Message::WatchdogTick);
+
+        let warm_start_sub = if self.shared.backend.manager().warm_start_pending() {
+            time::every(WARM_START_POLL_INTERVAL).map(|_| Message::WarmStartTick)
+        } else {
+            Subscription::none()
+        };
+
+        let ir_rescan_sub = if self.shared.backend.manager().ir_rescan_pending() {
+            time::every(IR_RESCAN_POLL_INTERVAL).map(|_| Message::IrRescanTick)
+        } else {
+            Subscription::none()
+        };
+
+        let dir_watch_sub = if self.dir_watcher.is_some() {
+            time::every(DIR_WATCH_POLL_INTERVAL).map(|_| Message::DirWatchTick)
+        } else {
+            Subscription::none()
+        };
+
+        let remote_sub = if self.remote_handler.is_running() {
+            time::every(REMOTE_POLL_INTERVAL).map(|_| Message::RemoteTick)
+        } else {
+            Subscription::none()
+        };
+
+        let system_theme_sub = if self.settings.theme == ThemeChoice::System {
+            system::theme_changes()
+                .map(|mode| Message::Settings(SettingsMessage::SystemThemeChanged(mode)))
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(vec![
+            shared_sub,
+            tuner_sub,
+            midi_sub,
+            render_sub,
+            tone_match_sub,
+            takes_sub,
+            window_sub,
+            window_save_sub,
+            import_sub,
+            transport_sub,
+            watchdog_sub,
+            warm_start_sub,
+            ir_rescan_sub,
+            dir_watch_sub,
+            remote_sub,
+            system_theme_sub,
+        ])
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
@@ -209,8 +546,11 @@ impl AmplifierApp {
                 | Message::RemoveStage(_)
                 | Message::MoveStageUp(_)
                 | Message::MoveStageDown(_)
+                | Message::MoveStageTo { .. }
                 | Message::ToggleStageCollapse(_)
                 | Message::ToggleAllStagesCollapse
+                | Message::CollapseAllExcept(_)
+                | Message::SetStageCollapse(_)
         );
 
         let needs_ir_bypass_persist = matches!(message, Message::IrBypassed(_));
@@ -243,13 +583,36 @@ impl AmplifierApp {
             _ => None,
         };
 
-        // Block key events when standalone dialogs are open
-        if matches!(message, Message::KeyPressed(..)) && self.any_dialog_visible() {
-            return Task::none();
+        // Standalone dialogs own the keyboard while open, so hotkeys can't
+        // fire behind them. The hotkey, macro and preset diff and manage
+        // dialogs belong to the shared app, which handles their keys itself.
+        if let Message::KeyPressed(key, modifiers) = &message
+            && let Some(task) = self.handle_dialog_key(key, *modifiers)
+        {
+            return task;
+        }
+
+        // The shared app consumes the meter tick, so a running input
+        // calibration or gain staging analysis, or the setup's input level,
+        // samples before it does.
+        if matches!(message, Message::PeakMeterUpdate) && self.setup_handler.is_visible() {
+            self.setup_handler.tick(self.shared.backend.manager());
+        }
+        if matches!(message, Message::PeakMeterUpdate) && self.calibration_handler.is_capturing() {
+            self.calibration_handler.tick(self.shared.backend.manager());
+        }
+        if matches!(message, Message::PeakMeterUpdate) && self.gain_staging_handler.is_capturing() {
+            self.gain_staging_handler
+                .tick(&self.shared.stages, self.shared.backend.manager());
+        }
+        if matches!(message, Message::PeakMeterUpdate)
+            && let Some(state) = self.shared.backend.engine_state()
+        {
+            self.tuner_handler.sync_with_engine(state.tuner_enabled);
         }
 
         // Handle SetStages with collapse state restoration from settings
-        if let Message::SetStages(ref stages) = message
+        if let Message::SetStages(ref stages) | Message::SetPresetStages(ref stages) = message
             && let Some(preset_name) = self.settings.selected_preset.as_deref()
         {
             self.shared.collapsed_stages =
@@ -283,11 +646,27 @@ impl AmplifierApp {
             self.save_settings();
         }
 
+        let layout = self.layout();
+        if layout != self.settings.layout {
+            self.settings.layout = layout;
+            self.save_settings();
+        }
+
+        if self.shared.tilt_control.global_db() != self.settings.tilt_db {
+            self.settings.tilt_db = self.shared.tilt_control.global_db();
+            self.save_settings();
+        }
+
         if is_preset_select_or_save && let Some(name) = preset_name_for_persist {
             self.settings.selected_preset = Some(name);
             self.save_settings();
         }
 
+        if self.shared.preset_handler.recent() != self.settings.recent_presets.as_slice() {
+            self.settings.recent_presets = self.shared.preset_handler.recent().to_vec();
+            self.save_settings();
+        }
+
         if is_preset_delete && let Some(deleted_name) = deleted_preset_name {
             self.settings.collapsed_stages.remove(&deleted_name);
             if self.settings.selected_preset == Some(deleted_name) {
@@ -296,40 +675,180 @@ impl AmplifierApp {
             self.save_settings();
         }
 
+        self.remote_handler.sync(
+            self.shared.preset_handler.selected_name(),
+            self.shared.is_recording,
+        );
+        self.hooks_handler
+            .sync(self.shared.preset_handler.selected_name());
+
         task
     }
 
     /// Handle standalone-only messages.
     fn handle_standalone(&mut self, message: Message) -> Task<Message> {
         match message {
-            Message::StartRecording => {
-                let sample_rate = self.shared.backend.manager().sample_rate();
-                // Size the recorder pool for the worst-case JACK period, not the
-                // current one: JACK can raise the buffer size mid-recording, and
-                // a pool sized to the smaller period would then drop every block
-                // as an overrun. See `ProcessHandler::MAX_BUFFER_FRAMES`.
-                let max_block_samples = self
-                    .shared
-                    .backend
-                    .manager()
-                    .buffer_size()
-                    .max(crate::audio::jack::ProcessHandler::MAX_BUFFER_FRAMES);
-                let recording_dir = &self.settings.recording_dir;
-                if let Err(e) = self.shared.backend.manager().engine().start_recording(
-                    sample_rate,
-                    recording_dir,
-                    max_block_samples,
-                ) {
-                    error!("Failed to start recording: {e}");
-                } else {
-                    self.shared.is_recording = true;
-                    debug!("Recording started");
+            Message::StartRecording => self.start_recording(None),
+            Message::StopRecording => self.stop_recording(),
+            Message::TransportTick => self.follow_transport(),
+            Message::WatchdogTick => {
+                self.follow_sample_rate();
+                self.check_watchdog();
+            }
+            Message::WarmStartTick => self.shared.backend.manager_mut().poll_warm_start(),
+            Message::IrRescanTick => {
+                if self.shared.backend.manager_mut().poll_ir_rescan() {
+                    return Task::done(Message::IrListChanged);
+                }
+            }
+            Message::DirWatchTick => return self.collect_dir_changes(),
+            Message::RemoteTick => {
+                return self.remote_handler.tick(
+                    self.shared.preset_handler.get_available_presets(),
+                    self.shared.preset_handler.selected_name(),
+                    self.shared.is_recording,
+                );
+            }
+            Message::RestartAudio => {
+                let bootstrap = self.engine_bootstrap();
+                self.watchdog_handler
+                    .restart(self.shared.backend.manager_mut(), &bootstrap);
+            }
+            Message::DismissAudioStall => self.watchdog_handler.dismiss(),
+            Message::PresetTempo {
+                bpm,
+                time_signature,
+            } => {
+                // Retimes the metronome on its next beat if it's running.
+                let metronome = apply_tempo(self.settings.metronome, bpm, time_signature);
+                if metronome != self.settings.metronome {
+                    return self.settings_handler.handle(
+                        SettingsMessage::MetronomeChanged(metronome),
+                        &mut self.settings,
+                        self.shared.backend.manager_mut(),
+                    );
                 }
             }
-            Message::StopRecording => {
-                self.shared.backend.manager().engine().stop_recording();
-                self.shared.is_recording = false;
-                debug!("Recording stopped");
+            Message::Window(msg) => return self.window_handler.handle(msg, &mut self.settings),
+            Message::Settings(SettingsMessage::SystemThemeChanged(mode)) => {
+                self.system_theme = mode;
+            }
+            Message::Settings(SettingsMessage::CopyDiagnostics) => {
+                return self.copy_diagnostics();
+            }
+            Message::Settings(SettingsMessage::CalibrateInput) => {
+                self.settings_handler.handle(
+                    SettingsMessage::Close,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                return self.calibration_handler.handle(
+                    CalibrationMessage::Open,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+            }
+            Message::Settings(SettingsMessage::RunSetup) => {
+                self.settings_handler.handle(
+                    SettingsMessage::Close,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                self.open_setup();
+            }
+            Message::Settings(SettingsMessage::AnalyzeGainStaging) => {
+                self.settings_handler.handle(
+                    SettingsMessage::Close,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                return self.gain_staging_handler.handle(
+                    GainStagingMessage::Open,
+                    &self.shared.stages,
+                    self.shared.backend.manager(),
+                );
+            }
+            Message::Settings(SettingsMessage::MatchTone) => {
+                self.settings_handler.handle(
+                    SettingsMessage::Close,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                self.tone_match_handler
+                    .open(self.shared.preset_handler.get_available_presets().to_vec());
+            }
+            Message::Settings(SettingsMessage::Apply) => {
+                let bootstrap = self.engine_bootstrap();
+                self.settings_handler.apply(
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                    &bootstrap,
+                );
+                self.watch_dirs();
+            }
+            Message::Settings(SettingsMessage::ChainLintToggled(enabled)) => {
+                self.shared.set_lint_enabled(enabled);
+                return self.settings_handler.handle(
+                    SettingsMessage::ChainLintToggled(enabled),
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+            }
+            Message::Settings(SettingsMessage::KeepLockedStagesToggled(enabled)) => {
+                self.shared.keep_locked_stages = enabled;
+                return self.settings_handler.handle(
+                    SettingsMessage::KeepLockedStagesToggled(enabled),
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+            }
+            // Preset chains wait for the bar, and delays sync, only while
+            // the metronome gives them a bar and a tempo.
+            Message::Settings(
+                msg @ (SettingsMessage::QuantizeChainChangesToggled(_)
+                | SettingsMessage::MetronomeChanged(_)),
+            ) => {
+                let task = self.settings_handler.handle(
+                    msg,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                self.shared.quantize_chain_changes =
+                    self.settings.quantize_chain_changes && self.settings.metronome.enabled;
+                self.shared
+                    .backend
+                    .set_tempo_sync(self.settings.metronome.enabled);
+                return task;
+            }
+            Message::Settings(SettingsMessage::WatchdogStallSecondsChanged(seconds)) => {
+                self.watchdog_handler.set_stall_seconds(seconds);
+                return self.settings_handler.handle(
+                    SettingsMessage::WatchdogStallSecondsChanged(seconds),
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+            }
+            Message::Settings(
+                msg @ (SettingsMessage::RemoteToggled(_) | SettingsMessage::RemotePortSubmitted),
+            ) => {
+                let task = self.settings_handler.handle(
+                    msg,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                self.remote_handler.configure(&self.settings.remote);
+                return task;
+            }
+            Message::Settings(
+                msg @ (SettingsMessage::HooksToggled(_) | SettingsMessage::HookCommandSubmitted(_)),
+            ) => {
+                let task = self.settings_handler.handle(
+                    msg,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+                self.hooks_handler.configure(&self.settings.hooks);
+                return task;
             }
             Message::Settings(msg) => {
                 return self.settings_handler.handle(
@@ -338,12 +857,76 @@ impl AmplifierApp {
                     self.shared.backend.manager_mut(),
                 );
             }
+            Message::Setup(msg) => return self.handle_setup(msg),
+            Message::Calibration(msg) => {
+                return self.calibration_handler.handle(
+                    msg,
+                    &mut self.settings,
+                    self.shared.backend.manager_mut(),
+                );
+            }
+            Message::GainStaging(msg) => {
+                return self.gain_staging_handler.handle(
+                    msg,
+                    &self.shared.stages,
+                    self.shared.backend.manager(),
+                );
+            }
             Message::Tuner(msg) => {
-                return self
-                    .tuner_handler
-                    .handle(msg, self.shared.backend.manager());
+                return self.tuner_handler.handle(
+                    msg,
+                    &mut self.settings,
+                    self.shared.backend.manager(),
+                );
             }
             Message::Midi(msg) => return self.handle_midi(msg),
+            Message::Mappings(msg) => return self.mappings_handler.handle(msg),
+            Message::Render(RenderMessage::Bounce) => {
+                let sample_rate = self.shared.backend.manager().sample_rate();
+                let recording = self.shared.is_recording;
+                self.with_bounce_host(|render, host| {
+                    render.start_bounce(host, sample_rate, recording);
+                });
+            }
+            Message::Render(RenderMessage::CancelBounce) => {
+                self.with_bounce_host(|render, host| render.cancel_bounce(host));
+            }
+            Message::Render(RenderMessage::Update) if self.render_handler.is_bouncing() => {
+                self.with_bounce_host(|render, host| render.poll_bounce(host));
+            }
+            Message::Render(msg) => {
+                let request = matches!(msg, RenderMessage::Start | RenderMessage::CaptureIr)
+                    .then(|| self.render_request());
+                let task = self.render_handler.handle(msg, request);
+                // A captured IR shows up in the list right away.
+                if self.render_handler.take_captured_ir().is_some()
+                    && self.shared.backend.manager_mut().rescan_irs().is_ok()
+                {
+                    return Task::batch([task, Task::done(Message::IrListChanged)]);
+                }
+                return task;
+            }
+            Message::ToneMatch(ToneMatchMessage::Start) => {
+                let reference = self
+                    .tone_match_handler
+                    .reference_preset()
+                    .and_then(|name| self.shared.preset_handler.get_preset_by_name(name))
+                    .cloned();
+                self.tone_match_handler
+                    .start(self.render_request(), reference);
+            }
+            Message::ToneMatch(msg) => {
+                return self.tone_match_handler.handle(msg, &self.shared.stages);
+            }
+            Message::Takes(msg) => {
+                return self.takes_handler.handle(
+                    msg,
+                    &self.settings.paths.recording_dir,
+                    self.shared.backend.manager(),
+                );
+            }
+            Message::Setlist(msg) => return self.handle_setlist(msg),
+            Message::Import(msg) => return self.handle_import(msg),
             other => {
                 debug!("Unhandled message: {other:?}");
             }
@@ -370,7 +953,20 @@ impl AmplifierApp {
             MidiMessage::ConfirmMapping | MidiMessage::RemoveMapping(_)
         );
 
-        let task = self.midi_handler.handle(msg);
+        if matches!(msg, MidiMessage::Update) {
+            self.midi_handler
+                .report_switch_latency(self.shared.backend.manager().engine());
+        }
+
+        let switches = SwitchStates {
+            tuner: self.tuner_handler.is_enabled(),
+            standby: self.shared.standby,
+            ir_bypassed: self.shared.ir_cabinet_control.is_bypassed(),
+        };
+        let mut task = self.midi_handler.handle(msg, switches);
+        if let Some(switch) = self.midi_handler.take_switch() {
+            task = Task::batch([task, self.switch_preset(switch)]);
+        }
 
         if let Some(name) = controller_update {
             self.settings.midi.controller_name = name;
@@ -383,11 +979,524 @@ impl AmplifierApp {
         task
     }
 
-    const fn any_dialog_visible(&self) -> bool {
-        self.settings_handler.is_visible()
-            || self.tuner_handler.is_visible()
-            || self.midi_handler.is_visible()
-            || self.shared.hotkey_handler.is_visible()
+    fn handle_setup(&mut self, msg: SetupMessage) -> Task<Message> {
+        let previous = self.settings.paths.clone();
+        let bootstrap = self.engine_bootstrap();
+        let task = self.setup_handler.handle(
+            msg,
+            &mut self.settings,
+            self.shared.backend.manager_mut(),
+            &bootstrap,
+        );
+        if self.setup_handler.take_paths_changed() {
+            return Task::batch([task, self.apply_paths(&previous)]);
+        }
+        task
+    }
+
+    /// Follow directories the setup moved: the manager records to and scans
+    /// the new ones, a new preset directory gets its own handler, and both
+    /// lists are refreshed.
+    fn apply_paths(&mut self, previous: &PathSettings) -> Task<Message> {
+        self.shared
+            .backend
+            .manager_mut()
+            .set_paths(self.settings.paths.clone());
+
+        if self.settings.paths.preset_dir != previous.preset_dir {
+            match PresetHandler::new(&self.settings.paths.preset_dir) {
+                Ok(mut preset_handler) => {
+                    preset_handler.set_recent(
+                        self.settings.recent_presets.clone(),
+                        self.settings.recent_presets_limit,
+                    );
+                    self.shared.preset_handler = preset_handler;
+                }
+                Err(e) => error!(
+                    "Failed to open the preset directory '{}': {e}",
+                    self.settings.paths.preset_dir
+                ),
+            }
+        }
+        self.watch_dirs();
+
+        let mut tasks = vec![Task::done(Message::PresetListChanged)];
+        if self.shared.backend.manager_mut().rescan_irs().is_ok() {
+            tasks.push(Task::done(Message::IrListChanged));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Every hotkey and MIDI mapping, for the mappings overview.
+    fn bindings(&self) -> Vec<Binding> {
+        let hotkeys = self.shared.hotkey_handler.settings().mappings.iter();
+        let midi = self.settings.midi.mappings.iter();
+        hotkeys
+            .enumerate()
+            .map(|(i, m)| m.binding(i))
+            .chain(midi.enumerate().map(|(i, m)| m.binding(i)))
+            .collect()
+    }
+
+    /// Song changes load their preset the way picking it in the preset bar
+    /// does. Where the setlist is at is saved so a restart picks up there.
+    fn handle_setlist(&mut self, msg: SetlistMessage) -> Task<Message> {
+        let presets = self.shared.preset_handler.get_available_presets();
+        let load = self.setlist_handler.handle(msg, presets);
+
+        let active = self.setlist_handler.active_name().map(str::to_owned);
+        let position = self.setlist_handler.position();
+        if active != self.settings.active_setlist || position != self.settings.setlist_position {
+            self.settings.active_setlist = active;
+            self.settings.setlist_position = position;
+            self.save_settings();
+        }
+
+        load.map_or_else(Task::none, |name| {
+            Task::done(Message::Preset(PresetMessage::Select(name)))
+        })
+    }
+
+    fn handle_import(&mut self, msg: ImportMessage) -> Task<Message> {
+        match msg {
+            ImportMessage::FileDropped(path) => self.import_handler.file_dropped(path),
+            ImportMessage::Update => {
+                if let Some(paths) = self.import_handler.take_pending() {
+                    let request = self.import_request(paths);
+                    self.import_handler.start(request);
+                }
+                if let Some(report) = self.import_handler.poll() {
+                    return self.finish_import(&report);
+                }
+            }
+            ImportMessage::DismissToast => self.import_handler.dismiss_toast(),
+        }
+        Task::none()
+    }
+
+    fn import_request(&self, paths: Vec<PathBuf>) -> ImportRequest {
+        ImportRequest {
+            paths,
+            ir_dir: PathBuf::from(&self.settings.paths.ir_dir),
+            preset_dir: PathBuf::from(&self.settings.paths.preset_dir),
+            sample_rate: self.shared.backend.manager().sample_rate(),
+            ir_max_seconds: self.settings.ir_max_seconds,
+        }
+    }
+
+    /// Show what was imported: the IR list is rescanned and the first new IR
+    /// selected, and the preset list picks up new presets.
+    fn finish_import(&mut self, report: &ImportReport) -> Task<Message> {
+        let mut tasks = Vec::new();
+        if let Some(first_ir) = report.irs.first()
+            && self.shared.backend.manager_mut().rescan_irs().is_ok()
+        {
+            tasks.push(
+                Task::done(Message::IrListChanged)
+                    .chain(Task::done(Message::IrSelected(first_ir.clone()))),
+            );
+        }
+        if !report.presets.is_empty() {
+            tasks.push(Task::done(Message::PresetListChanged));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Apply a MIDI preset switch on the spot: the new chain goes to the
+    /// engine now instead of after a round of tasks, so it plays from the
+    /// next callback. The rest of the preset follows in the returned task.
+    fn switch_preset(&mut self, switch: PresetSwitch) -> Task<Message> {
+        let handled = Instant::now();
+        let name = match switch.target {
+            SwitchTarget::Preset(name) => name,
+            SwitchTarget::Previous => match self.shared.preset_handler.previous_preset() {
+                Some(name) => name.to_owned(),
+                None => return Task::none(),
+            },
+        };
+
+        let Some(task) = self.shared.switch_preset_now(&name) else {
+            return Task::none();
+        };
+        self.midi_handler
+            .time_switch(name.clone(), switch.received, handled);
+
+        self.shared.collapsed_stages = Self::restore_collapsed(
+            &self.settings.collapsed_stages,
+            &name,
+            self.shared.stages.len(),
+        );
+        self.settings.selected_preset = Some(name);
+        self.save_settings();
+        task
+    }
+
+    /// Snapshot of the GUI state a restarted engine must be brought up with.
+    fn engine_bootstrap(&self) -> EngineBootstrap {
+        let ir_control = &self.shared.ir_cabinet_control;
+
+        EngineBootstrap {
+            stages: self.shared.stages.clone(),
+            monitor_point: self.shared.solo,
+            input_filters: self.shared.input_filter_config,
+            denoise: self.shared.denoiser_control.config().clone(),
+            ir_name: ir_control.get_selected_ir(),
+            ir_alignment: ir_control.alignment(),
+            ir_gain: ir_control.get_gain(),
+            ir_trim_db: ir_control.applied_trim_db(),
+            ir_bypassed: ir_control.is_bypassed(),
+            pitch_shift: self.shared.pitch_shift_control.get_semitones(),
+            ambience: self.shared.ambience_control.get_config(),
+            double_tracker: self.shared.double_tracker_control.get_config(),
+            tilt_db: self.shared.tilt_control.effective_db(),
+            tilt_offset_db: self.shared.tilt_control.preset_offset_db(),
+            oversampling_factor: self.shared.oversampling_factor,
+            tuner_enabled: self.tuner_handler.is_enabled(),
+            standby: self.shared.standby,
+            metronome: self.settings.metronome,
+            record_click_track: self.settings.record_click_track,
+            recording_softclip: self.settings.recording_softclip,
+            preset_name: self.settings.selected_preset.clone(),
+            recording_dir: self
+                .shared
+                .is_recording
+                .then(|| self.settings.paths.recording_dir.clone()),
+        }
+    }
+
+    fn start_recording(&mut self, transport: Option<TransportStart>) {
+        // A bounce has the recorder to itself until it ends.
+        if self.render_handler.is_bouncing() {
+            warn!("Not recording: a bounce is running");
+            return;
+        }
+        let sample_rate = self.shared.backend.manager().sample_rate();
+        // Size the recorder pool for the worst-case JACK period, not the
+        // current one: JACK can raise the buffer size mid-recording, and
+        // a pool sized to the smaller period would then drop every block
+        // as an overrun. See `Processor::MAX_BUFFER_FRAMES`.
+        let max_block_samples = self
+            .shared
+            .backend
+            .manager()
+            .buffer_size()
+            .max(crate::audio::processor::Processor::MAX_BUFFER_FRAMES);
+        let recording_dir = &self.settings.paths.recording_dir;
+        match self.shared.backend.manager().engine().start_recording(
+            sample_rate,
+            recording_dir,
+            max_block_samples,
+            transport,
+            Some(self.recording_metadata()),
+            self.settings.recording_softclip,
+        ) {
+            Ok(path) => {
+                self.hooks_handler.recording_started(path.clone());
+                if self.settings.record_automation {
+                    // Sliders edited before this point are flushed now so the
+                    // snapshot and the engine agree when the take starts.
+                    self.shared.flush_dirty_params();
+                    self.shared.backend.start_automation(AutomationLog::new(
+                        path,
+                        sample_rate,
+                        self.current_preset(),
+                    ));
+                }
+                self.shared.is_recording = true;
+                debug!("Recording started");
+            }
+            Err(e) => error!("Failed to start recording: {e}"),
+        }
+    }
+
+    /// Run `f` on the render handler with the audio manager as the host of
+    /// its bounce.
+    fn with_bounce_host(&mut self, f: impl FnOnce(&mut RenderHandler, &mut ManagerBounceHost<'_>)) {
+        let metadata = RecordingMetadata::new(self.current_preset());
+        let mut host = ManagerBounceHost {
+            manager: self.shared.backend.manager_mut(),
+            recording_dir: &self.settings.paths.recording_dir,
+            metadata,
+            metronome: self.settings.metronome,
+            tuner_enabled: self.tuner_handler.is_enabled(),
+        };
+        f(&mut self.render_handler, &mut host);
+    }
+
+    /// The preset a take starts with, and the metronome it's played to.
+    fn recording_metadata(&self) -> RecordingMetadata {
+        let metadata = RecordingMetadata::new(self.current_preset());
+        let metronome = self.settings.metronome;
+        if !metronome.enabled {
+            return metadata;
+        }
+        metadata.with_metronome(TakeMetronome {
+            bpm: metronome.bpm,
+            beats_per_bar: metronome.beats_per_bar,
+            click_track: self.settings.record_click_track,
+        })
+    }
+
+    fn stop_recording(&mut self) {
+        // Changes still waiting for the next rebuild tick belong to this take.
+        self.shared.flush_dirty_params();
+        let softclipped = self.shared.backend.manager().engine().stop_recording();
+        self.shared.backend.finish_automation();
+        self.shared.is_recording = false;
+        self.shared.recording_status.finish(softclipped);
+        self.hooks_handler.recording_stopped();
+        debug!("Recording stopped");
+    }
+
+    /// Start/stop recording as the JACK transport rolls and halts. Manual
+    /// control keeps working: a start while already recording is ignored.
+    fn follow_transport(&mut self) {
+        let events: Vec<_> = self.shared.backend.manager().transport_events().collect();
+        for event in events {
+            match event {
+                TransportEvent::Started {
+                    frame,
+                    engine_frame,
+                } if !self.shared.is_recording => {
+                    debug!("Transport rolling at frame {frame}");
+                    // Polled a few tens of milliseconds late: the take is
+                    // lined up with the block the transport rolled in.
+                    self.start_recording(Some(TransportStart {
+                        frame: u64::from(frame),
+                        engine_frame,
+                    }));
+                }
+                TransportEvent::Stopped if self.shared.is_recording => {
+                    debug!("Transport stopped");
+                    self.stop_recording();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Poll the audio watchdog. A stall is logged with the diagnostics
+    /// attached and, if the settings allow, the stream is restarted the way
+    /// applying new audio settings would. A server shutdown runs the
+    /// disconnect hook straight away, ahead of the stall it leads to.
+    fn check_watchdog(&mut self) {
+        if self.shared.backend.manager().take_server_shutdown() {
+            error!("The audio server shut the stream down");
+            self.hooks_handler
+                .audio_disconnected("the audio server shut down".to_owned());
+        }
+
+        let Some(stalled_for) = self.watchdog_handler.tick(self.shared.backend.manager()) else {
+            return;
+        };
+
+        let report = self
+            .diagnostics()
+            .to_json()
+            .unwrap_or_else(|e| format!("<diagnostics unavailable: {e}>"));
+        error!(
+            "Audio stalled: no frames processed for {:.1} s\n{report}",
+            stalled_for.as_secs_f32()
+        );
+        self.hooks_handler.audio_disconnected(format!(
+            "no audio processed for {:.1} s",
+            stalled_for.as_secs_f32()
+        ));
+
+        if self.settings.watchdog.auto_restart {
+            let bootstrap = self.engine_bootstrap();
+            self.watchdog_handler
+                .restart(self.shared.backend.manager_mut(), &bootstrap);
+        }
+    }
+
+    /// Retune the engine if the server changed its sample rate, e.g.
+    /// PipeWire following another stream, without restarting the client.
+    fn follow_sample_rate(&mut self) {
+        if !self.shared.backend.manager().sample_rate_changed() {
+            return;
+        }
+        let bootstrap = self.engine_bootstrap();
+        let manager = self.shared.backend.manager_mut();
+        if let Err(e) = manager.follow_sample_rate(&bootstrap) {
+            error!("Failed to follow the JACK sample rate change: {e}");
+        }
+        self.settings_handler
+            .refresh_jack_status(self.shared.backend.manager());
+    }
+
+    /// Watch the configured IR and preset directories, replacing the watcher
+    /// if either path changed. The old watcher's thread is stopped first.
+    fn watch_dirs(&mut self) {
+        let dirs = WatchedDirs::new(&self.settings.paths.ir_dir, &self.settings.paths.preset_dir);
+        if self
+            .dir_watcher
+            .as_ref()
+            .is_some_and(|watcher| watcher.watches(&dirs))
+        {
+            return;
+        }
+        self.dir_watcher = None;
+        self.dir_watcher = match DirWatcher::spawn(dirs) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Failed to watch the IR and preset directories: {e}");
+                None
+            }
+        };
+    }
+
+    /// Rescan whichever directories the watcher saw change, and refresh
+    /// their lists.
+    fn collect_dir_changes(&mut self) -> Task<Message> {
+        let Some(watcher) = &self.dir_watcher else {
+            return Task::none();
+        };
+        let mut tasks = Vec::new();
+        for dir in watcher.changes() {
+            match dir {
+                WatchedDir::Irs => {
+                    if self.shared.backend.manager_mut().rescan_irs().is_ok() {
+                        tasks.push(Task::done(Message::IrListChanged));
+                    }
+                }
+                WatchedDir::Presets => tasks.push(Task::done(Message::PresetListChanged)),
+            }
+        }
+        Task::batch(tasks)
+    }
+
+    /// The current chain as a preset, for rendering a file offline with the
+    /// same sound the user is hearing.
+    fn render_request(&self) -> RenderRequest {
+        RenderRequest {
+            preset: self.current_preset(),
+            sample_rate: self.shared.backend.manager().sample_rate(),
+            oversampling_factor: self.shared.oversampling_factor,
+            ir_dir: self.settings.paths.ir_dir.clone(),
+            ir_max_seconds: self.settings.ir_max_seconds,
+        }
+    }
+
+    /// What the user is hearing, as a preset.
+    fn current_preset(&self) -> Preset {
+        let ir_control = &self.shared.ir_cabinet_control;
+        Preset {
+            name: self.settings.selected_preset.clone().unwrap_or_default(),
+            stages: self.shared.stages.clone(),
+            ir_name: ir_control
+                .get_selected_ir()
+                .filter(|_| !ir_control.is_bypassed()),
+            ir_gain: ir_control.get_gain(),
+            ir_alignment: ir_control.alignment(),
+            pitch_shift_semitones: self.shared.pitch_shift_control.get_semitones(),
+            input_filters: self.shared.input_filter_config,
+            denoise: self.shared.denoiser_control.config().clone(),
+            ambience: self.shared.ambience_control.get_config(),
+            double_tracker: self.shared.double_tracker_control.get_config(),
+            tilt_offset_db: self.shared.tilt_control.preset_offset_db(),
+            macros: self.shared.macros.clone(),
+            ..Preset::default()
+        }
+    }
+
+    /// Engine and host diagnostics, with the chain and IR the GUI holds.
+    fn diagnostics(&self) -> Diagnostics {
+        let stages = self
+            .shared
+            .stages
+            .iter()
+            .map(|stage| stage.stage_type().to_string())
+            .collect();
+
+        self.shared
+            .backend
+            .manager()
+            .diagnostics()
+            .with_stages(stages)
+            .with_ir_name(self.shared.ir_cabinet_control.get_selected_ir())
+    }
+
+    fn copy_diagnostics(&self) -> Task<Message> {
+        match self.diagnostics().to_json() {
+            Ok(json) => iced::clipboard::write(json),
+            Err(e) => {
+                error!("Failed to serialize diagnostics: {e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Keys for the topmost standalone dialog, checked in the order `view`
+    /// picks them: Escape closes it, the MIDI dialog also takes Enter while
+    /// learning and the arrows for its preset search, and anything else is
+    /// swallowed. `None` when no standalone dialog is on top.
+    fn handle_dialog_key(
+        &mut self,
+        key: &iced::keyboard::Key,
+        modifiers: iced::keyboard::Modifiers,
+    ) -> Option<Task<Message>> {
+        use iced::keyboard::{Key, key::Named};
+
+        let escape = matches!(key, Key::Named(Named::Escape));
+        let enter = matches!(key, Key::Named(Named::Enter));
+
+        let message = if self.setup_handler.is_visible() {
+            escape.then_some(Message::Setup(SetupMessage::Skip))
+        } else if self.settings_handler.is_visible() {
+            escape.then_some(Message::Settings(SettingsMessage::Close))
+        } else if self.calibration_handler.is_visible() {
+            escape.then_some(Message::Calibration(CalibrationMessage::Close))
+        } else if self.gain_staging_handler.is_visible() {
+            escape.then_some(Message::GainStaging(GainStagingMessage::Close))
+        } else if self.tuner_handler.is_visible() {
+            escape.then_some(Message::Tuner(TunerMessage::Toggle))
+        } else if self.midi_handler.is_visible() {
+            if self.midi_handler.is_search_open()
+                && modifiers.is_empty()
+                && let Some(search) = search_select::key_message(key)
+            {
+                return Some(self.handle_midi(MidiMessage::PresetSearch(search)));
+            }
+            let learning = self.midi_handler.is_learning();
+            let midi = match (escape, enter) {
+                (true, _) if learning => Some(MidiMessage::CancelLearning),
+                (true, _) => Some(MidiMessage::Close),
+                (_, true) if learning => Some(MidiMessage::ConfirmMapping),
+                _ => None,
+            };
+            midi.map(Message::Midi)
+        } else if self.shared.hotkey_handler.is_visible() || self.shared.macro_editor.is_visible() {
+            return None;
+        } else if self.mappings_handler.is_visible() {
+            escape.then_some(Message::Mappings(MappingsMessage::Close))
+        } else if self.shared.preset_handler.is_diff_visible()
+            || self.shared.preset_handler.is_manage_visible()
+        {
+            return None;
+        } else if self.render_handler.is_visible() {
+            escape.then_some(Message::Render(RenderMessage::Close))
+        } else if self.tone_match_handler.is_visible() {
+            escape.then_some(Message::ToneMatch(ToneMatchMessage::Close))
+        } else if self.takes_handler.is_visible() {
+            // 1-4 switch takes, keeping the playhead where it is.
+            let takes = match key {
+                Key::Named(Named::Space) => Some(TakesMessage::PlayPause),
+                Key::Character(c) if modifiers.is_empty() => c
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| (1..=MAX_TAKES).contains(n))
+                    .map(|n| TakesMessage::Select(n - 1)),
+                _ => escape.then_some(TakesMessage::Close),
+            };
+            takes.map(Message::Takes)
+        } else if self.setlist_handler.is_visible() {
+            escape.then_some(Message::Setlist(SetlistMessage::Close))
+        } else {
+            return None;
+        };
+
+        Some(message.map_or_else(Task::none, Task::done))
     }
 
     fn persist_collapse_state(&mut self) {
@@ -403,6 +1512,14 @@ impl AmplifierApp {
         }
     }
 
+    /// The main window's display choices, as saved in settings.
+    const fn layout(&self) -> LayoutSettings {
+        LayoutSettings {
+            tab: self.shared.active_tab,
+            stage_levels: self.shared.stage_levels.is_enabled(),
+        }
+    }
+
     fn restore_collapsed(
         saved: &std::collections::HashMap<String, Vec<bool>>,
         preset_name: &str,
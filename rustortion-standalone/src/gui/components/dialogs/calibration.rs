@@ -0,0 +1,170 @@
+use iced::widget::{button, column, progress_bar, row, rule, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_core::audio::calibration::CalibrationState;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_MUTED, COLOR_SUCCESS, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT,
+    TEXT_SIZE_INFO, TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::CalibrationMessage;
+
+/// "Calibrate input" dialog: measures how hot the interface runs and offers a
+/// trim that brings it to the reference level.
+pub struct CalibrationDialog {
+    port: String,
+    /// Trim already stored for `port`, if it has been calibrated.
+    current_trim_db: Option<f32>,
+    state: CalibrationState,
+    /// Fraction of the capture window elapsed.
+    progress: f32,
+    show_dialog: bool,
+}
+
+impl Default for CalibrationDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalibrationDialog {
+    pub const fn new() -> Self {
+        Self {
+            port: String::new(),
+            current_trim_db: None,
+            state: CalibrationState::Idle,
+            progress: 0.0,
+            show_dialog: false,
+        }
+    }
+
+    pub fn show(&mut self, port: String, current_trim_db: Option<f32>) {
+        self.port = port;
+        self.current_trim_db = current_trim_db;
+        self.state = CalibrationState::Idle;
+        self.progress = 0.0;
+        self.show_dialog = true;
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub const fn update(&mut self, state: CalibrationState, progress: f32) {
+        self.state = state;
+        self.progress = progress;
+    }
+
+    pub const fn set_current_trim(&mut self, trim_db: f32) {
+        self.current_trim_db = Some(trim_db);
+    }
+
+    pub fn view(&self) -> Option<Element<'_, CalibrationMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(calibration_title), CalibrationMessage::Close);
+
+        let current_trim = self.current_trim_db.map_or_else(
+            || tr!(calibration_not_calibrated).to_string(),
+            |trim_db| format!("{trim_db:+.1} dB"),
+        );
+        let header = column![
+            text(format!("{} {}", tr!(input_port), self.port)).size(TEXT_SIZE_LABEL),
+            muted_line(format!("{} {current_trim}", tr!(calibration_current_trim))),
+            text(tr!(calibration_instructions)).size(TEXT_SIZE_INFO),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let status: Element<'_, CalibrationMessage> = match self.state {
+            CalibrationState::Idle => button(tr!(calibration_start))
+                .on_press(CalibrationMessage::Start)
+                .into(),
+            CalibrationState::Capturing { peak_db, .. } => column![
+                text(tr!(calibration_listening)).size(TEXT_SIZE_INFO),
+                progress_bar(0.0..=1.0, self.progress),
+                muted_line(format!(
+                    "{} {}",
+                    tr!(calibration_peak),
+                    format_dbfs(peak_db)
+                )),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            CalibrationState::Done { peak_db, trim_db } => column![
+                muted_line(format!(
+                    "{} {}",
+                    tr!(calibration_peak),
+                    format_dbfs(peak_db)
+                )),
+                text(format!("{} {trim_db:+.1} dB", tr!(calibration_recommended)))
+                    .size(TEXT_SIZE_LABEL)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(COLOR_SUCCESS),
+                    }),
+                row![
+                    button(tr!(calibration_retry)).on_press(CalibrationMessage::Start),
+                    space::horizontal(),
+                    button(tr!(save))
+                        .on_press(CalibrationMessage::Save)
+                        .style(iced::widget::button::success),
+                ]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            CalibrationState::NoSignal { .. } => column![
+                text(tr!(calibration_no_signal))
+                    .size(TEXT_SIZE_INFO)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(COLOR_ERROR),
+                    }),
+                button(tr!(calibration_retry)).on_press(CalibrationMessage::Start),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+        };
+
+        let section = dialog_section_container(
+            column![header, status]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+/// Like `muted_text`, but owning its label.
+fn muted_line(label: String) -> iced::widget::Text<'static> {
+    text(label)
+        .size(TEXT_SIZE_INFO)
+        .style(|_: &iced::Theme| iced::widget::text::Style {
+            color: Some(COLOR_MUTED),
+        })
+}
+
+fn format_dbfs(db: f32) -> String {
+    if db.is_finite() {
+        format!("{db:.1} dBFS")
+    } else {
+        "-- dBFS".to_string()
+    }
+}
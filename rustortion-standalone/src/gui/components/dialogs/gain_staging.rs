@@ -0,0 +1,213 @@
+use iced::widget::{button, column, progress_bar, row, rule, scrollable, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_core::audio::gain_staging::HOT_STAGE_DBFS;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_MUTED, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO,
+    TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::GainStagingMessage;
+
+/// Range the per-stage bars cover, in dBFS.
+const BAR_RANGE_DB: std::ops::RangeInclusive<f32> = -48.0..=12.0;
+
+/// One stage's measured output.
+pub struct StageRow {
+    /// "Preamp 2", as on the stage card.
+    pub label: String,
+    /// `None` for a bypassed stage.
+    pub peak_db: Option<f32>,
+}
+
+enum View {
+    Idle,
+    Capturing {
+        progress: f32,
+    },
+    Results {
+        rows: Vec<StageRow>,
+        suggestions: Vec<String>,
+        can_apply: bool,
+    },
+}
+
+/// "Gain staging" dialog: measures every stage's output while the player
+/// plays, and suggests level changes for the ones that run hot.
+pub struct GainStagingDialog {
+    view: View,
+    show_dialog: bool,
+}
+
+impl Default for GainStagingDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GainStagingDialog {
+    pub const fn new() -> Self {
+        Self {
+            view: View::Idle,
+            show_dialog: false,
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.view = View::Idle;
+        self.show_dialog = true;
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub fn set_progress(&mut self, progress: f32) {
+        self.view = View::Capturing { progress };
+    }
+
+    pub fn set_results(&mut self, rows: Vec<StageRow>, suggestions: Vec<String>, can_apply: bool) {
+        self.view = View::Results {
+            rows,
+            suggestions,
+            can_apply,
+        };
+    }
+
+    pub fn view(&self) -> Option<Element<'_, GainStagingMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(gain_staging_title), GainStagingMessage::Close);
+        let instructions = text(tr!(gain_staging_instructions)).size(TEXT_SIZE_INFO);
+
+        let body: Element<'_, GainStagingMessage> = match &self.view {
+            View::Idle => button(tr!(gain_staging_start))
+                .on_press(GainStagingMessage::Start)
+                .into(),
+            View::Capturing { progress } => column![
+                text(tr!(gain_staging_listening)).size(TEXT_SIZE_INFO),
+                progress_bar(0.0..=1.0, *progress),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            View::Results {
+                rows,
+                suggestions,
+                can_apply,
+            } => view_results(rows, suggestions, *can_apply),
+        };
+
+        let section = dialog_section_container(
+            column![instructions, body]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+fn view_results<'a>(
+    rows: &'a [StageRow],
+    suggestions: &'a [String],
+    can_apply: bool,
+) -> Element<'a, GainStagingMessage> {
+    let mut content = column![].spacing(SPACING_TIGHT);
+
+    if rows.is_empty() {
+        content = content.push(text(tr!(gain_staging_empty_chain)).size(TEXT_SIZE_INFO));
+    }
+    for stage in rows {
+        content = content.push(stage_bar(stage));
+    }
+
+    content = content.push(rule::horizontal(1));
+    if suggestions.is_empty() && !rows.is_empty() {
+        content = content.push(text(tr!(gain_staging_all_good)).size(TEXT_SIZE_INFO));
+    }
+    for line in suggestions {
+        content = content.push(text(line).size(TEXT_SIZE_INFO));
+    }
+
+    let controls = row![
+        button(tr!(gain_staging_retry)).on_press(GainStagingMessage::Start),
+        space::horizontal(),
+        button(tr!(gain_staging_apply))
+            .on_press_maybe(can_apply.then_some(GainStagingMessage::Apply))
+            .style(iced::widget::button::success),
+    ]
+    .spacing(SPACING_NORMAL)
+    .align_y(Alignment::Center);
+
+    column![scrollable(content).height(Length::Fill), controls]
+        .spacing(SPACING_NORMAL)
+        .into()
+}
+
+fn stage_bar(stage: &StageRow) -> Element<'_, GainStagingMessage> {
+    let label = text(&stage.label)
+        .size(TEXT_SIZE_LABEL)
+        .width(Length::FillPortion(3));
+
+    let Some(peak_db) = stage.peak_db else {
+        return row![
+            label,
+            text(tr!(gain_staging_bypassed))
+                .size(TEXT_SIZE_INFO)
+                .width(Length::FillPortion(7))
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_MUTED),
+                }),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center)
+        .into();
+    };
+
+    let hot = peak_db > HOT_STAGE_DBFS;
+    let bar = progress_bar(
+        BAR_RANGE_DB,
+        peak_db.clamp(*BAR_RANGE_DB.start(), *BAR_RANGE_DB.end()),
+    )
+    .style(if hot {
+        iced::widget::progress_bar::danger
+    } else {
+        iced::widget::progress_bar::primary
+    });
+    let reading = if peak_db.is_finite() {
+        format!("{peak_db:+.1} dBFS")
+    } else {
+        "-- dBFS".to_string()
+    };
+
+    row![
+        label,
+        iced::widget::container(bar).width(Length::FillPortion(5)),
+        text(reading)
+            .size(TEXT_SIZE_INFO)
+            .width(Length::FillPortion(2))
+            .style(move |_| iced::widget::text::Style {
+                color: hot.then_some(COLOR_ERROR),
+            }),
+    ]
+    .spacing(SPACING_NORMAL)
+    .align_y(Alignment::Center)
+    .into()
+}
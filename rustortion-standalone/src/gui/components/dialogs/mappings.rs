@@ -0,0 +1,151 @@
+use iced::widget::{button, column, row, rule, scrollable, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_ui::components::dialogs::common::{
+    conflict_warning, dialog_container, dialog_section_container, dialog_title_row, muted_text,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_MUTED, ICON_BUTTON_WIDTH, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO, TEXT_SIZE_LABEL,
+};
+use rustortion_ui::mappings::{Binding, validate};
+use rustortion_ui::messages::MappingsMessage;
+
+const TRIGGER_WIDTH: f32 = 160.0;
+const SOURCE_WIDTH: f32 = 80.0;
+
+/// Every hotkey and MIDI mapping in one table, with the ones that collide
+/// flagged. The mappings themselves live with their own dialogs, so they're
+/// passed in at view time.
+pub struct MappingsDialog {
+    show_dialog: bool,
+    filter: String,
+}
+
+impl Default for MappingsDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MappingsDialog {
+    pub const fn new() -> Self {
+        Self {
+            show_dialog: false,
+            filter: String::new(),
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.show_dialog = true;
+        self.filter.clear();
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+    }
+
+    /// `bindings` are the hotkeys followed by the MIDI mappings. Conflicts are
+    /// found across all of them before the filter hides any.
+    pub fn view(&self, bindings: Vec<Binding>) -> Option<Element<'_, MappingsMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(mappings), MappingsMessage::Close);
+
+        let filter = text_input(tr!(filter_mappings), &self.filter)
+            .on_input(MappingsMessage::Filter)
+            .size(TEXT_SIZE_LABEL);
+
+        let header = row![
+            column_title(tr!(trigger)).width(Length::Fixed(TRIGGER_WIDTH)),
+            column_title(tr!(target)).width(Length::Fill),
+            column_title(tr!(source)).width(Length::Fixed(SOURCE_WIDTH)),
+            column_title("").width(Length::Fixed(ICON_BUTTON_WIDTH)),
+        ]
+        .spacing(SPACING_NORMAL);
+
+        let conflicts = validate::conflicts(&bindings);
+        let mut rows = column![].spacing(SPACING_TIGHT);
+        let mut shown = 0;
+        for (binding, conflict) in bindings.into_iter().zip(conflicts) {
+            if !binding.matches(&self.filter) {
+                continue;
+            }
+            shown += 1;
+
+            let binding_row = row![
+                text(binding.description)
+                    .size(TEXT_SIZE_INFO)
+                    .width(Length::Fixed(TRIGGER_WIDTH)),
+                text(binding.target)
+                    .size(TEXT_SIZE_INFO)
+                    .width(Length::Fill),
+                text(binding.source.to_string())
+                    .size(TEXT_SIZE_INFO)
+                    .width(Length::Fixed(SOURCE_WIDTH)),
+                button("\u{00d7}")
+                    .on_press(MappingsMessage::Remove(binding.source, binding.index))
+                    .style(iced::widget::button::danger)
+                    .width(Length::Fixed(ICON_BUTTON_WIDTH)),
+            ]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+
+            rows = rows.push(binding_row);
+            if let Some(conflict) = conflict {
+                rows = rows.push(conflict_warning(&conflict, false));
+            }
+        }
+
+        let table: Element<'_, MappingsMessage> = if shown > 0 {
+            column![
+                header,
+                rule::horizontal(1),
+                scrollable(rows).height(Length::Fill)
+            ]
+            .spacing(SPACING_TIGHT)
+            .into()
+        } else if self.filter.trim().is_empty() {
+            muted_text(tr!(no_mappings_configured)).into()
+        } else {
+            muted_text(tr!(no_matching_mappings)).into()
+        };
+
+        let dialog_content = column![
+            title_row,
+            rule::horizontal(1),
+            filter,
+            dialog_section_container(
+                column![table]
+                    .padding(SPACING_NORMAL)
+                    .height(Length::Fill)
+                    .into()
+            ),
+        ]
+        .spacing(DIALOG_CONTENT_SPACING)
+        .padding(DIALOG_CONTENT_PADDING)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+fn column_title(label: &str) -> iced::widget::Text<'_> {
+    text(label)
+        .size(TEXT_SIZE_INFO)
+        .style(|_: &iced::Theme| iced::widget::text::Style {
+            color: Some(COLOR_MUTED),
+        })
+}
@@ -1,7 +1,7 @@
-use iced::widget::{button, column, pick_list, row, rule, scrollable, space, text};
+use iced::widget::{button, checkbox, column, pick_list, row, rule, scrollable, space, text};
 use iced::{Alignment, Color, Element, Length};
 
-use crate::midi::{MidiInputEvent, MidiManager, MidiMapping};
+use crate::midi::{MidiAction, MidiInputEvent, MidiManager, MidiMapping};
 use crate::tr;
 use rustortion_ui::components::dialogs::common::{
     dialog_container, dialog_section_container, dialog_title_row, input_captured_view,
@@ -9,9 +9,12 @@ use rustortion_ui::components::dialogs::common::{
 };
 use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
 use rustortion_ui::components::widgets::common::{
-    COLOR_SUBTLE, COLOR_SUCCESS, SPACING_NORMAL, TEXT_SIZE_INFO, TEXT_SIZE_SECTION_TITLE,
-    TEXT_SIZE_SMALL,
+    COLOR_SUBTLE, COLOR_SUCCESS, COLOR_WARNING, SPACING_NORMAL, TEXT_SIZE_INFO,
+    TEXT_SIZE_SECTION_TITLE, TEXT_SIZE_SMALL,
 };
+use rustortion_ui::components::widgets::search_select::{SearchSelect, SearchSelectMessage};
+use rustortion_ui::mappings::validate::{self, Conflict};
+use rustortion_ui::mappings::{MappingMode, ModeLabel, Trigger};
 use rustortion_ui::messages::MidiMessage;
 
 const MAX_DEBUG_MESSAGES: usize = 20;
@@ -35,12 +38,20 @@ pub struct MidiDialog {
     show_dialog: bool,
     available_controllers: Vec<String>,
     selected_controller: Option<String>,
+    /// The selected controller is plugged in and open. When it isn't, the
+    /// manager is waiting for it to come back.
+    connected: bool,
     mappings: Vec<MidiMapping>,
     available_presets: Vec<String>,
     learning_state: LearningState,
     debug_messages: Vec<String>,
     /// Preset selected for new mapping
     selected_preset_for_mapping: Option<String>,
+    /// What the new mapping does
+    action_for_mapping: MidiAction,
+    /// How the new mapping switches, where its action switches something
+    mode_for_mapping: MappingMode,
+    preset_search: SearchSelect,
 }
 
 impl Default for MidiDialog {
@@ -55,11 +66,15 @@ impl MidiDialog {
             show_dialog: false,
             available_controllers: Vec::new(),
             selected_controller: None,
+            connected: false,
             mappings: Vec::new(),
             available_presets: Vec::new(),
             learning_state: LearningState::Idle,
             debug_messages: Vec::new(),
             selected_preset_for_mapping: None,
+            action_for_mapping: MidiAction::LoadPreset,
+            mode_for_mapping: MappingMode::Trigger,
+            preset_search: SearchSelect::new(),
         }
     }
 
@@ -85,9 +100,15 @@ impl MidiDialog {
     }
 
     pub fn set_selected_controller(&mut self, controller: Option<String>) {
+        self.connected = controller.is_some();
         self.selected_controller = controller;
     }
 
+    /// The selected controller went away or came back.
+    pub const fn set_connected(&mut self, connected: bool) {
+        self.connected = connected;
+    }
+
     pub fn get_selected_controller(&self) -> Option<String> {
         self.selected_controller.clone()
     }
@@ -103,11 +124,29 @@ impl MidiDialog {
     pub fn start_learning(&mut self) {
         self.learning_state = LearningState::WaitingForInput;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = MidiAction::LoadPreset;
+        self.mode_for_mapping = MappingMode::Trigger;
+        self.preset_search.update(SearchSelectMessage::Close, &[]);
     }
 
     pub fn cancel_learning(&mut self) {
         self.learning_state = LearningState::Idle;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = MidiAction::LoadPreset;
+        self.mode_for_mapping = MappingMode::Trigger;
+        self.preset_search.update(SearchSelectMessage::Close, &[]);
+    }
+
+    /// Apply a message from the preset search box; a pick becomes the
+    /// mapping's preset.
+    pub fn search_preset(&mut self, message: SearchSelectMessage) {
+        if let Some(preset) = self.preset_search.update(message, &self.available_presets) {
+            self.selected_preset_for_mapping = Some(preset);
+        }
+    }
+
+    pub const fn is_search_open(&self) -> bool {
+        self.show_dialog && self.preset_search.is_open()
     }
 
     pub const fn is_learning(&self) -> bool {
@@ -141,6 +180,38 @@ impl MidiDialog {
         self.selected_preset_for_mapping = Some(preset);
     }
 
+    /// Tick or untick one of the "instead of loading a preset" boxes.
+    pub const fn toggle_action_for_mapping(&mut self, action: MidiAction, on: bool) {
+        self.action_for_mapping = if on { action } else { MidiAction::LoadPreset };
+    }
+
+    pub const fn set_mode_for_mapping(&mut self, mode: MappingMode) {
+        self.mode_for_mapping = mode;
+    }
+
+    const fn can_confirm(&self) -> bool {
+        !matches!(self.action_for_mapping, MidiAction::LoadPreset)
+            || self.selected_preset_for_mapping.is_some()
+    }
+
+    /// What the captured input would collide with, warned about before it's
+    /// confirmed.
+    fn captured_conflict(&self) -> Option<Conflict> {
+        let LearningState::InputCaptured {
+            channel, control, ..
+        } = self.learning_state
+        else {
+            return None;
+        };
+        let existing: Vec<_> = self
+            .mappings
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.binding(i))
+            .collect();
+        validate::check_new(&Trigger::midi(channel, control), &existing)
+    }
+
     /// Complete adding a new mapping
     pub fn complete_mapping(&mut self) -> Option<MidiMapping> {
         let LearningState::InputCaptured {
@@ -150,9 +221,19 @@ impl MidiDialog {
             return None;
         };
 
-        let preset_name = self.selected_preset_for_mapping.as_ref()?;
-
-        let mapping = MidiMapping::new(channel, control, preset_name.clone());
+        let mapping = match self.action_for_mapping {
+            MidiAction::LoadPreset => {
+                let preset_name = self.selected_preset_for_mapping.as_ref()?;
+                MidiMapping::new(channel, control, preset_name.clone())
+            }
+            MidiAction::HoldTuner => MidiMapping::hold_tuner(channel, control),
+            MidiAction::PreviousPreset => MidiMapping::previous_preset(channel, control),
+            MidiAction::NextSong => MidiMapping::next_song(channel, control),
+            MidiAction::PreviousSong => MidiMapping::previous_song(channel, control),
+            MidiAction::ToggleStandby => MidiMapping::toggle_standby(channel, control),
+            MidiAction::ToggleIrBypass => MidiMapping::toggle_ir_bypass(channel, control),
+        }
+        .with_mode(self.mode_for_mapping);
 
         // Remove any existing mapping for the same input
         self.mappings
@@ -161,6 +242,8 @@ impl MidiDialog {
         self.mappings.push(mapping.clone());
         self.learning_state = LearningState::Idle;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = MidiAction::LoadPreset;
+        self.mode_for_mapping = MappingMode::Trigger;
 
         Some(mapping)
     }
@@ -213,12 +296,18 @@ impl MidiDialog {
                     color: Some(theme.palette().text),
                 });
 
-        let status_text = if self.selected_controller.is_some() {
+        let status_text = if self.selected_controller.is_some() && self.connected {
             text(tr!(connected))
                 .size(TEXT_SIZE_INFO)
                 .style(|_: &iced::Theme| iced::widget::text::Style {
                     color: Some(COLOR_SUCCESS),
                 })
+        } else if self.selected_controller.is_some() {
+            text(tr!(midi_waiting_for_device))
+                .size(TEXT_SIZE_INFO)
+                .style(|_: &iced::Theme| iced::widget::text::Style {
+                    color: Some(COLOR_WARNING),
+                })
         } else {
             text(tr!(not_connected))
                 .size(TEXT_SIZE_INFO)
@@ -280,20 +369,73 @@ impl MidiDialog {
         let learning_content: Element<'_, MidiMessage> = match &self.learning_state {
             LearningState::Idle => column![].into(),
             LearningState::WaitingForInput => waiting_for_input_view(tr!(press_midi_device)),
-            LearningState::InputCaptured { description, .. } => input_captured_view(
-                description,
-                &self.available_presets,
-                self.selected_preset_for_mapping.clone(),
-                MidiMessage::PresetForMappingSelected,
-                MidiMessage::ConfirmMapping,
-            ),
+            LearningState::InputCaptured { description, .. } => {
+                let hold_tuner = checkbox(self.action_for_mapping == MidiAction::HoldTuner)
+                    .label(tr!(hold_tuner_instead))
+                    .on_toggle(MidiMessage::HoldTunerToggled);
+                let previous = checkbox(self.action_for_mapping == MidiAction::PreviousPreset)
+                    .label(tr!(previous_preset_instead))
+                    .on_toggle(MidiMessage::PreviousPresetToggled);
+                let next_song = checkbox(self.action_for_mapping == MidiAction::NextSong)
+                    .label(tr!(next_song_instead))
+                    .on_toggle(MidiMessage::NextSongToggled);
+                let previous_song = checkbox(self.action_for_mapping == MidiAction::PreviousSong)
+                    .label(tr!(previous_song_instead))
+                    .on_toggle(MidiMessage::PreviousSongToggled);
+                let standby = checkbox(self.action_for_mapping == MidiAction::ToggleStandby)
+                    .label(tr!(toggle_standby_instead))
+                    .on_toggle(MidiMessage::ToggleStandbyToggled);
+                let ir_bypass = checkbox(self.action_for_mapping == MidiAction::ToggleIrBypass)
+                    .label(tr!(toggle_ir_bypass_instead))
+                    .on_toggle(MidiMessage::ToggleIrBypassToggled);
+                let mut target = column![
+                    hold_tuner,
+                    previous,
+                    next_song,
+                    previous_song,
+                    standby,
+                    ir_bypass
+                ]
+                .spacing(SPACING_NORMAL);
+                if self.action_for_mapping.switch().is_some() {
+                    target = target.push(
+                        row![
+                            text(tr!(mapping_mode)).size(TEXT_SIZE_INFO),
+                            pick_list(
+                                ModeLabel::ALL,
+                                Some(ModeLabel(self.mode_for_mapping)),
+                                |label| MidiMessage::MappingModeSelected(label.0),
+                            ),
+                        ]
+                        .spacing(SPACING_NORMAL)
+                        .align_y(Alignment::Center),
+                    );
+                }
+                if self.action_for_mapping == MidiAction::LoadPreset {
+                    target = target.push(
+                        self.preset_search
+                            .view(
+                                &self.available_presets,
+                                self.selected_preset_for_mapping.as_deref(),
+                            )
+                            .map(MidiMessage::PresetSearch),
+                    );
+                }
+                input_captured_view(
+                    description,
+                    self.captured_conflict().as_ref(),
+                    target.into(),
+                    self.can_confirm(),
+                    MidiMessage::ConfirmMapping,
+                )
+            }
         };
 
         // Existing mappings list
         let mappings_list = mapping_list_view(
             self.mappings
                 .iter()
-                .map(|m| (m.description.clone(), m.preset_name.clone()))
+                .map(|m| (m.description.clone(), m.target_label()))
                 .collect(),
             tr!(no_mappings_configured),
             MidiMessage::RemoveMapping,
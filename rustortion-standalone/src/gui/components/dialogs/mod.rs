@@ -1,3 +1,11 @@
+pub mod calibration;
+pub mod gain_staging;
+pub mod mappings;
 pub mod midi;
+pub mod render;
+pub mod setlist;
 pub mod settings;
+pub mod setup;
+pub mod takes;
+pub mod tone_match;
 pub mod tuner;
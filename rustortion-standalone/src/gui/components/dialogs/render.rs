@@ -0,0 +1,265 @@
+use iced::widget::{
+    button, checkbox, column, pick_list, progress_bar, row, rule, text, text_input,
+};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_core::audio::ir_capture::DEFAULT_CAPTURE_LEN;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row, muted_text,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_SUCCESS, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO,
+    TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::RenderMessage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderStatus {
+    Idle,
+    /// Fraction of the input processed so far.
+    Running(f32),
+    Done,
+    /// An IR capture was saved under this name.
+    Captured(String),
+    /// Frames of the file and its tail bounced so far, out of all of them.
+    Bouncing {
+        done: usize,
+        total: usize,
+    },
+    /// A bounce was recorded into this take.
+    Bounced(String),
+    Failed(String),
+}
+
+/// Capture lengths offered, in samples.
+const CAPTURE_LENGTHS: [usize; 4] = [1024, 2048, 4096, 8192];
+
+/// "Render file through current preset" dialog.
+pub struct RenderDialog {
+    input_path: String,
+    output_path: String,
+    automation_path: String,
+    capture_len: usize,
+    bounce_mix: bool,
+    status: RenderStatus,
+    show_dialog: bool,
+}
+
+impl Default for RenderDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderDialog {
+    pub const fn new() -> Self {
+        Self {
+            input_path: String::new(),
+            output_path: String::new(),
+            automation_path: String::new(),
+            capture_len: DEFAULT_CAPTURE_LEN,
+            bounce_mix: false,
+            status: RenderStatus::Idle,
+            show_dialog: false,
+        }
+    }
+
+    pub fn show(&mut self) {
+        self.show_dialog = true;
+        if !self.is_running() {
+            self.status = RenderStatus::Idle;
+        }
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub const fn is_running(&self) -> bool {
+        matches!(
+            self.status,
+            RenderStatus::Running(_) | RenderStatus::Bouncing { .. }
+        )
+    }
+
+    pub fn set_status(&mut self, status: RenderStatus) {
+        self.status = status;
+    }
+
+    pub fn set_input_path(&mut self, path: String) {
+        self.input_path = path;
+    }
+
+    pub fn set_output_path(&mut self, path: String) {
+        self.output_path = path;
+    }
+
+    pub fn set_automation_path(&mut self, path: String) {
+        self.automation_path = path;
+    }
+
+    pub const fn set_capture_len(&mut self, len: usize) {
+        self.capture_len = len;
+    }
+
+    pub const fn capture_len(&self) -> usize {
+        self.capture_len
+    }
+
+    pub const fn set_bounce_mix(&mut self, mix: bool) {
+        self.bounce_mix = mix;
+    }
+
+    pub const fn bounce_mix(&self) -> bool {
+        self.bounce_mix
+    }
+
+    pub fn input_path(&self) -> &str {
+        &self.input_path
+    }
+
+    pub fn output_path(&self) -> &str {
+        &self.output_path
+    }
+
+    pub fn automation_path(&self) -> &str {
+        &self.automation_path
+    }
+
+    pub fn view(&self) -> Option<Element<'_, RenderMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(render_title), RenderMessage::Close);
+
+        let running = self.is_running();
+        let paths = column![
+            text(tr!(input_file)).size(TEXT_SIZE_LABEL),
+            text_input("dry.wav", &self.input_path)
+                .on_input_maybe((!running).then_some(RenderMessage::InputPathChanged)),
+            text(tr!(output_file)).size(TEXT_SIZE_LABEL),
+            text_input("wet.wav", &self.output_path)
+                .on_input_maybe((!running).then_some(RenderMessage::OutputPathChanged)),
+            text(tr!(automation_file)).size(TEXT_SIZE_LABEL),
+            text_input("take.automation.json", &self.automation_path)
+                .on_input_maybe((!running).then_some(RenderMessage::AutomationPathChanged)),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let can_start =
+            !running && !self.input_path.trim().is_empty() && !self.output_path.trim().is_empty();
+        let render_button = button(tr!(render))
+            .on_press_maybe(can_start.then_some(RenderMessage::Start))
+            .style(iced::widget::button::success);
+
+        let status: Element<'_, RenderMessage> = match &self.status {
+            RenderStatus::Idle => muted_text(tr!(render_hint)).into(),
+            RenderStatus::Running(progress) => column![
+                text(tr!(rendering)).size(TEXT_SIZE_INFO),
+                progress_bar(0.0..=1.0, *progress),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            RenderStatus::Bouncing { done, total } => column![
+                text(format!("{} {done} / {total}", tr!(bouncing))).size(TEXT_SIZE_INFO),
+                progress_bar(0.0..=1.0, *done as f32 / (*total).max(1) as f32),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            RenderStatus::Bounced(take) => text(format!("{} {take}", tr!(bounce_done)))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_SUCCESS),
+                })
+                .into(),
+            RenderStatus::Done => text(tr!(render_done))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_SUCCESS),
+                })
+                .into(),
+            RenderStatus::Captured(name) => text(format!("{} {name}", tr!(ir_capture_done)))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_SUCCESS),
+                })
+                .into(),
+            RenderStatus::Failed(error) => text(format!("{} {error}", tr!(render_failed)))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_ERROR),
+                })
+                .into(),
+        };
+
+        let section = dialog_section_container(
+            column![paths, render_button, status]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let capture = dialog_section_container(
+            column![
+                text(tr!(ir_capture)).size(TEXT_SIZE_LABEL),
+                muted_text(tr!(ir_capture_hint)),
+                row![
+                    text(tr!(ir_capture_length)).size(TEXT_SIZE_LABEL),
+                    pick_list(
+                        CAPTURE_LENGTHS,
+                        Some(self.capture_len),
+                        RenderMessage::CaptureLengthChanged,
+                    ),
+                    button(tr!(capture_ir))
+                        .on_press_maybe((!running).then_some(RenderMessage::CaptureIr)),
+                ]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(SPACING_TIGHT)
+            .padding(PADDING_NORMAL)
+            .into(),
+        );
+
+        let bouncing = matches!(self.status, RenderStatus::Bouncing { .. });
+        let bounce_button = if bouncing {
+            button(tr!(cancel)).on_press(RenderMessage::CancelBounce)
+        } else {
+            button(tr!(bounce)).on_press_maybe(
+                (!running && !self.input_path.trim().is_empty()).then_some(RenderMessage::Bounce),
+            )
+        };
+        let bounce = dialog_section_container(
+            column![
+                text(tr!(bounce_title)).size(TEXT_SIZE_LABEL),
+                muted_text(tr!(bounce_hint)),
+                row![
+                    checkbox(self.bounce_mix)
+                        .label(tr!(bounce_mix))
+                        .on_toggle_maybe((!running).then_some(RenderMessage::BounceMixToggled)),
+                    bounce_button,
+                ]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(SPACING_TIGHT)
+            .padding(PADDING_NORMAL)
+            .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section, capture, bounce]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
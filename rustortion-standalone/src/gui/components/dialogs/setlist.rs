@@ -0,0 +1,299 @@
+use iced::widget::{
+    button, column, pick_list, row, rule, scrollable, space, text, text_input, tooltip,
+};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_core::preset::{Setlist, SetlistEntry};
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row, muted_text,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_WARNING, ICON_BUTTON_WIDTH, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT,
+    TEXT_SIZE_INFO, TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::SetlistMessage;
+
+/// Editor for the saved setlists: pick one, reorder its songs and note what
+/// each needs, then save it or start playing it.
+pub struct SetlistDialog {
+    show_dialog: bool,
+    saved: Vec<String>,
+    /// Name the setlist in the editor was saved under, `None` until it is.
+    editing: Option<String>,
+    draft: Setlist,
+    preset_to_add: Option<String>,
+    error: Option<String>,
+}
+
+impl Default for SetlistDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetlistDialog {
+    pub const fn new() -> Self {
+        Self {
+            show_dialog: false,
+            saved: Vec::new(),
+            editing: None,
+            draft: Setlist::new(String::new()),
+            preset_to_add: None,
+            error: None,
+        }
+    }
+
+    pub fn show(&mut self, saved: Vec<String>) {
+        self.saved = saved;
+        self.error = None;
+        self.show_dialog = true;
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    /// Load a saved setlist into the editor.
+    pub fn edit(&mut self, setlist: Setlist) {
+        self.editing = Some(setlist.name.clone());
+        self.draft = setlist;
+        self.error = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.editing = None;
+        self.draft = Setlist::new(String::new());
+        self.error = None;
+    }
+
+    pub fn editing(&self) -> Option<&str> {
+        self.editing.as_deref()
+    }
+
+    pub const fn draft(&self) -> &Setlist {
+        &self.draft
+    }
+
+    /// The draft was saved as `name`; `saved` is the refreshed list.
+    pub fn set_saved(&mut self, saved: Vec<String>, name: Option<String>) {
+        self.saved = saved;
+        self.editing = name;
+        self.error = None;
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.draft.name = name;
+    }
+
+    pub fn set_preset_to_add(&mut self, preset: String) {
+        self.preset_to_add = Some(preset);
+    }
+
+    pub fn add_entry(&mut self) {
+        if let Some(preset) = self.preset_to_add.clone() {
+            self.draft.entries.push(SetlistEntry::new(preset));
+        }
+    }
+
+    pub fn remove_entry(&mut self, idx: usize) {
+        if idx < self.draft.entries.len() {
+            self.draft.entries.remove(idx);
+        }
+    }
+
+    pub fn move_entry_up(&mut self, idx: usize) {
+        self.draft.move_up(idx);
+    }
+
+    pub fn move_entry_down(&mut self, idx: usize) {
+        self.draft.move_down(idx);
+    }
+
+    pub fn set_entry_notes(&mut self, idx: usize, notes: String) {
+        if let Some(entry) = self.draft.entries.get_mut(idx) {
+            entry.notes = notes;
+        }
+    }
+
+    /// `presets` are the presets on disk, to flag songs whose preset is gone;
+    /// `playing` shows the stop button.
+    pub fn view<'a>(
+        &'a self,
+        presets: &'a [String],
+        playing: bool,
+    ) -> Option<Element<'a, SetlistMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(setlist_title), SetlistMessage::Close);
+
+        let picker = row![
+            pick_list(
+                self.saved.as_slice(),
+                self.editing.clone(),
+                SetlistMessage::Edit
+            )
+            .placeholder(tr!(setlist_select))
+            .width(Length::Fill),
+            button(tr!(setlist_new))
+                .on_press(SetlistMessage::New)
+                .style(iced::widget::button::secondary),
+            button(tr!(delete))
+                .on_press_maybe(self.editing.is_some().then_some(SetlistMessage::Delete))
+                .style(iced::widget::button::danger),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let name = row![
+            text(tr!(setlist_name)).size(TEXT_SIZE_LABEL),
+            text_input("", &self.draft.name).on_input(SetlistMessage::NameChanged),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let missing = self
+            .draft
+            .missing_entries(|preset| presets.iter().any(|p| p == preset));
+        let songs: Element<'_, SetlistMessage> = if self.draft.entries.is_empty() {
+            muted_text(tr!(setlist_empty)).into()
+        } else {
+            let mut list = column![].spacing(SPACING_TIGHT);
+            for (idx, entry) in self.draft.entries.iter().enumerate() {
+                list = list.push(entry_row(
+                    idx,
+                    entry,
+                    self.draft.entries.len(),
+                    missing.contains(&idx),
+                ));
+            }
+            scrollable(list).height(Length::Fill).into()
+        };
+
+        let add_row = row![
+            pick_list(
+                presets,
+                self.preset_to_add.clone(),
+                SetlistMessage::PresetSelected
+            )
+            .width(Length::Fill),
+            button(tr!(setlist_add_song))
+                .on_press_maybe(
+                    self.preset_to_add
+                        .is_some()
+                        .then_some(SetlistMessage::AddEntry)
+                )
+                .style(iced::widget::button::primary),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let named = !self.draft.name.trim().is_empty();
+        let mut footer = row![
+            button(tr!(save))
+                .on_press_maybe(named.then_some(SetlistMessage::Save))
+                .style(iced::widget::button::primary),
+            button(tr!(setlist_start))
+                .on_press_maybe(
+                    (named && !self.draft.entries.is_empty()).then_some(SetlistMessage::Start)
+                )
+                .style(iced::widget::button::success),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+        if playing {
+            footer = footer.push(
+                button(tr!(setlist_stop))
+                    .on_press(SetlistMessage::Stop)
+                    .style(iced::widget::button::secondary),
+            );
+        }
+        if let Some(error) = &self.error {
+            footer = footer
+                .push(space::horizontal())
+                .push(
+                    text(error)
+                        .size(TEXT_SIZE_INFO)
+                        .style(|_| iced::widget::text::Style {
+                            color: Some(COLOR_ERROR),
+                        }),
+                );
+        }
+
+        let section = dialog_section_container(
+            column![picker, name, rule::horizontal(1), songs, add_row, footer]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+fn entry_row(
+    idx: usize,
+    entry: &SetlistEntry,
+    count: usize,
+    missing: bool,
+) -> Element<'_, SetlistMessage> {
+    let icon = |label: &'static str, message: Option<SetlistMessage>, primary: bool| {
+        button(text(label))
+            .width(Length::Fixed(ICON_BUTTON_WIDTH))
+            .on_press_maybe(message)
+            .style(if primary {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::danger
+            })
+    };
+
+    let mut preset = row![text(format!("{}. {}", idx + 1, entry.preset))].spacing(SPACING_TIGHT);
+    if missing {
+        preset = preset.push(tooltip(
+            text("⚠").style(|_| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            }),
+            tr!(setlist_missing_preset),
+            iced::widget::tooltip::Position::Bottom,
+        ));
+    }
+
+    row![
+        icon(
+            "↑",
+            (idx > 0).then_some(SetlistMessage::MoveEntryUp(idx)),
+            true
+        ),
+        icon(
+            "↓",
+            (idx + 1 < count).then_some(SetlistMessage::MoveEntryDown(idx)),
+            true
+        ),
+        icon("×", Some(SetlistMessage::RemoveEntry(idx)), false),
+        preset.width(Length::FillPortion(1)),
+        text_input(tr!(setlist_notes), &entry.notes)
+            .on_input(move |notes| SetlistMessage::EntryNotesChanged(idx, notes))
+            .width(Length::FillPortion(2)),
+    ]
+    .spacing(SPACING_TIGHT)
+    .align_y(Alignment::Center)
+    .into()
+}
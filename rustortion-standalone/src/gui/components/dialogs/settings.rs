@@ -1,9 +1,25 @@
-use iced::widget::{button, column, pick_list, row, rule, space, text, text_input};
+use std::collections::HashMap;
+
+use iced::widget::{
+    button, checkbox, column, pick_list, row, rule, slider, space, text, text_input,
+};
 use iced::{Alignment, Element, Length};
 
+use crate::audio::backend::BackendKind;
 use crate::i18n::{self, LANGUAGES};
-use crate::settings::AudioSettings;
+use crate::settings::{AudioSettings, HookSettings, RemoteSettings, WatchdogSettings};
 use crate::tr;
+use rustortion_core::audio::dual_mono::ChainMode;
+use rustortion_core::audio::engine::Latency;
+use rustortion_core::audio::host::HostApi;
+use rustortion_core::audio::samplers::ResamplerQuality;
+use rustortion_core::audio::test_source::{
+    MAX_TEST_FREQUENCY_HZ, MAX_TEST_LEVEL_DB, MIN_TEST_FREQUENCY_HZ, MIN_TEST_LEVEL_DB,
+    TestSourceConfig, TestSourceKind,
+};
+use rustortion_core::hooks::EventKind;
+use rustortion_core::metronome::{BEATS_PER_BAR_RANGE, MAX_BPM, MIN_BPM, MetronomeConfig};
+use rustortion_core::tuner::TunerAlgorithm;
 use rustortion_ui::components::dialogs::common::{
     dialog_container, dialog_section_container, dialog_title_row,
 };
@@ -13,12 +29,81 @@ use rustortion_ui::components::widgets::common::{
     TEXT_SIZE_INFO, TEXT_SIZE_LABEL, TEXT_SIZE_SECTION_TITLE, TEXT_SIZE_SMALL,
 };
 use rustortion_ui::messages::SettingsMessage;
+use rustortion_ui::theme::ThemeChoice;
+
+/// A pick-list entry for the right input: a port, or none for mono.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RightInput {
+    None,
+    Port(String),
+}
+
+impl RightInput {
+    fn into_port(self) -> String {
+        match self {
+            Self::None => String::new(),
+            Self::Port(port) => port,
+        }
+    }
+}
+
+impl std::fmt::Display for RightInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str(tr!(input_right_none)),
+            Self::Port(port) => f.write_str(port),
+        }
+    }
+}
+
+/// A pick-list entry for a CPAL device: a named one, or the system default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DeviceChoice {
+    Default,
+    Named(String),
+}
+
+impl DeviceChoice {
+    pub(crate) fn new(device: &str) -> Self {
+        match device {
+            "" => Self::Default,
+            name => Self::Named(name.to_string()),
+        }
+    }
+
+    pub(crate) fn into_device(self) -> String {
+        match self {
+            Self::Default => String::new(),
+            Self::Named(name) => name,
+        }
+    }
+
+    pub(crate) fn list(devices: &[String]) -> Vec<Self> {
+        std::iter::once(Self::Default)
+            .chain(devices.iter().cloned().map(Self::Named))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for DeviceChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => f.write_str(tr!(system_default_device)),
+            Self::Named(name) => f.write_str(name),
+        }
+    }
+}
 
 /// Actual JACK settings as reported by the server
 #[derive(Debug, Clone, Default)]
 pub struct JackStatus {
     pub sample_rate: usize,
     pub buffer_size: usize,
+    /// What the engine adds on top of the buffer.
+    pub engine_latency: Latency,
+    /// Which audio system is running; the rate and period are the CPAL
+    /// stream's under [`BackendKind::Cpal`].
+    pub backend: BackendKind,
 }
 
 /// User Settings
@@ -26,8 +111,33 @@ pub struct SettingsDialog {
     temp_settings: AudioSettings,
     /// Working copy of the NAM models directory, staged until Apply/Rescan.
     temp_nam_dir: String,
+    record_follow_transport: bool,
+    record_automation: bool,
+    recording_softclip: bool,
+    tuner_algorithm: TunerAlgorithm,
+    chain_lint: bool,
+    keep_locked_stages: bool,
+    quantize_chain_changes: bool,
+    watchdog: WatchdogSettings,
+    remote_enabled: bool,
+    /// Working copy of the remote-control port, staged until submitted.
+    remote_port: String,
+    hooks_enabled: bool,
+    /// Working copies of the hook commands, each staged until submitted.
+    hook_commands: HashMap<EventKind, String>,
+    test_source: TestSourceConfig,
+    /// Working copy of the test DI path, staged until Load.
+    test_di_file: String,
+    /// Working copy of the metronome tick path, staged until Load.
+    metronome_tick: String,
+    metronome: MetronomeConfig,
+    record_click_track: bool,
+    theme: ThemeChoice,
     available_inputs: Vec<String>,
     available_outputs: Vec<String>,
+    /// CPAL devices, listed whichever backend runs so it can be switched to.
+    input_devices: Vec<String>,
+    output_devices: Vec<String>,
     show_dialog: bool,
     jack_status: JackStatus,
 }
@@ -37,8 +147,28 @@ impl SettingsDialog {
         Self {
             temp_settings: settings.clone(),
             temp_nam_dir: String::new(),
+            record_follow_transport: false,
+            record_automation: false,
+            recording_softclip: false,
+            tuner_algorithm: TunerAlgorithm::default(),
+            chain_lint: true,
+            keep_locked_stages: false,
+            quantize_chain_changes: false,
+            watchdog: WatchdogSettings::default(),
+            remote_enabled: false,
+            remote_port: String::new(),
+            hooks_enabled: false,
+            hook_commands: HashMap::new(),
+            test_source: TestSourceConfig::default(),
+            test_di_file: String::new(),
+            metronome_tick: String::new(),
+            metronome: MetronomeConfig::default(),
+            record_click_track: false,
+            theme: ThemeChoice::default(),
             available_inputs: Vec::new(),
             available_outputs: Vec::new(),
+            input_devices: Vec::new(),
+            output_devices: Vec::new(),
             show_dialog: false,
             jack_status: JackStatus::default(),
         }
@@ -48,12 +178,14 @@ impl SettingsDialog {
         &mut self,
         current_settings: &AudioSettings,
         nam_dir: String,
+        record_follow_transport: bool,
         inputs: Vec<String>,
         outputs: Vec<String>,
         jack_status: JackStatus,
     ) {
         self.temp_settings = current_settings.clone();
         self.temp_nam_dir = nam_dir;
+        self.record_follow_transport = record_follow_transport;
         self.available_inputs = inputs;
         self.available_outputs = outputs;
         self.jack_status = jack_status;
@@ -66,6 +198,10 @@ impl SettingsDialog {
             self.available_inputs
                 .push(self.temp_settings.input_port.clone());
         }
+        let right = &self.temp_settings.input_right_port;
+        if !right.is_empty() && !self.available_inputs.contains(right) {
+            self.available_inputs.push(right.clone());
+        }
         if !self
             .available_outputs
             .contains(&self.temp_settings.output_left_port)
@@ -84,6 +220,27 @@ impl SettingsDialog {
         self.show_dialog = true;
     }
 
+    pub fn set_devices(&mut self, inputs: Vec<String>, outputs: Vec<String>) {
+        self.input_devices = inputs;
+        self.output_devices = outputs;
+    }
+
+    /// Whether Apply would run on CPAL: picked outright, or what Auto fell
+    /// back to. Devices are picked instead of ports then.
+    fn uses_cpal(&self) -> bool {
+        match self.temp_settings.host_api {
+            HostApi::Auto => self.jack_status.backend == BackendKind::Cpal,
+            HostApi::Jack => false,
+            HostApi::Cpal => true,
+        }
+    }
+
+    /// Show the rate and period the server is running at now, e.g. after
+    /// it changed them while the dialog is open.
+    pub const fn set_jack_status(&mut self, jack_status: JackStatus) {
+        self.jack_status = jack_status;
+    }
+
     pub const fn hide(&mut self) {
         self.show_dialog = false;
     }
@@ -108,6 +265,102 @@ impl SettingsDialog {
         self.temp_nam_dir = dir;
     }
 
+    pub const fn set_record_follow_transport(&mut self, enabled: bool) {
+        self.record_follow_transport = enabled;
+    }
+
+    pub const fn set_record_automation(&mut self, enabled: bool) {
+        self.record_automation = enabled;
+    }
+
+    pub const fn set_recording_softclip(&mut self, enabled: bool) {
+        self.recording_softclip = enabled;
+    }
+
+    pub const fn set_tuner_algorithm(&mut self, algorithm: TunerAlgorithm) {
+        self.tuner_algorithm = algorithm;
+    }
+
+    pub const fn set_chain_lint(&mut self, enabled: bool) {
+        self.chain_lint = enabled;
+    }
+
+    pub const fn set_keep_locked_stages(&mut self, enabled: bool) {
+        self.keep_locked_stages = enabled;
+    }
+
+    pub const fn set_quantize_chain_changes(&mut self, enabled: bool) {
+        self.quantize_chain_changes = enabled;
+    }
+
+    pub fn set_watchdog(&mut self, watchdog: WatchdogSettings) {
+        self.watchdog = watchdog;
+    }
+
+    pub fn set_remote(&mut self, remote: &RemoteSettings) {
+        self.remote_enabled = remote.enabled;
+        self.remote_port = remote.port.to_string();
+    }
+
+    pub fn set_remote_port(&mut self, port: String) {
+        self.remote_port = port;
+    }
+
+    pub fn remote_port(&self) -> String {
+        self.remote_port.clone()
+    }
+
+    pub fn set_hooks(&mut self, hooks: &HookSettings) {
+        self.hooks_enabled = hooks.enabled;
+        self.hook_commands.clone_from(&hooks.commands);
+    }
+
+    pub const fn set_hooks_enabled(&mut self, enabled: bool) {
+        self.hooks_enabled = enabled;
+    }
+
+    pub fn set_hook_command(&mut self, kind: EventKind, command: String) {
+        self.hook_commands.insert(kind, command);
+    }
+
+    pub fn hook_command(&self, kind: EventKind) -> String {
+        self.hook_commands.get(&kind).cloned().unwrap_or_default()
+    }
+
+    pub fn set_test_source(&mut self, config: TestSourceConfig, di_file: String) {
+        self.test_source = config;
+        self.test_di_file = di_file;
+    }
+
+    pub const fn set_test_source_config(&mut self, config: TestSourceConfig) {
+        self.test_source = config;
+    }
+
+    pub fn set_test_di_file(&mut self, path: String) {
+        self.test_di_file = path;
+    }
+
+    pub fn test_di_file(&self) -> String {
+        self.test_di_file.clone()
+    }
+
+    pub fn set_metronome_tick(&mut self, path: String) {
+        self.metronome_tick = path;
+    }
+
+    pub fn metronome_tick(&self) -> String {
+        self.metronome_tick.clone()
+    }
+
+    pub const fn set_metronome(&mut self, config: MetronomeConfig, record_click_track: bool) {
+        self.metronome = config;
+        self.record_click_track = record_click_track;
+    }
+
+    pub fn set_theme(&mut self, theme: ThemeChoice) {
+        self.theme = theme;
+    }
+
     pub fn view(&self) -> Option<Element<'static, SettingsMessage>> {
         if !self.show_dialog {
             return None;
@@ -130,6 +383,30 @@ impl SettingsDialog {
         ]
         .spacing(SPACING_TIGHT);
 
+        // Theme selection, applied immediately like the language
+        let theme_section = column![
+            text(tr!(theme)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                ThemeChoice::all(),
+                Some(self.theme.clone()),
+                SettingsMessage::ThemeChanged
+            )
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        // Audio system; a change restarts the stream
+        let host_api_section = column![
+            text(tr!(audio_api)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                HostApi::ALL,
+                Some(self.temp_settings.host_api),
+                SettingsMessage::HostApiChanged
+            )
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
         // Input port selection
         let input_section = column![
             text(tr!(input_port)).size(TEXT_SIZE_LABEL),
@@ -142,6 +419,22 @@ impl SettingsDialog {
         ]
         .spacing(SPACING_TIGHT);
 
+        // Second input for dual-mono
+        let mut right_inputs = vec![RightInput::None];
+        right_inputs.extend(self.available_inputs.iter().cloned().map(RightInput::Port));
+        let right_selected = match self.temp_settings.input_right_port.as_str() {
+            "" => RightInput::None,
+            port => RightInput::Port(port.to_string()),
+        };
+        let input_right_section = column![
+            text(tr!(input_right_port)).size(TEXT_SIZE_LABEL),
+            pick_list(right_inputs, Some(right_selected), |choice| {
+                SettingsMessage::InputRightPortChanged(choice.into_port())
+            })
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
         // Output port selections
         let output_left_section = column![
             text(tr!(output_left_port)).size(TEXT_SIZE_LABEL),
@@ -165,6 +458,20 @@ impl SettingsDialog {
         ]
         .spacing(SPACING_TIGHT);
 
+        let mut routing_column = column![language_section, theme_section, host_api_section];
+        routing_column = if self.uses_cpal() {
+            routing_column.push(self.devices_view())
+        } else {
+            routing_column
+                .push(input_section)
+                .push(input_right_section)
+                .push(output_left_section)
+                .push(output_right_section)
+        };
+        let routing_column = routing_column
+            .spacing(SPACING_NORMAL)
+            .padding(SPACING_TIGHT);
+
         // Buffer size selection
         let buffer_sizes = vec![64u32, 128, 256, 512, 1024, 2048, 4096];
         let buffer_section = column![
@@ -187,19 +494,41 @@ impl SettingsDialog {
         ]
         .spacing(SPACING_TIGHT);
 
-        // Latency display (based on actual JACK values)
-        let latency =
-            (self.jack_status.buffer_size as f32 / self.jack_status.sample_rate as f32) * 1000.0;
-        let latency_text = text(format!(
-            "{} {:.2} {}",
-            tr!(actual_latency),
-            latency,
-            tr!(ms)
-        ))
-        .size(TEXT_SIZE_INFO)
-        .style(|_theme: &iced::Theme| iced::widget::text::Style {
-            color: Some(COLOR_SUBTLE),
-        });
+        // Resampler profile used when oversampling
+        let resampler_quality_section = column![
+            text(tr!(resampler_quality)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                ResamplerQuality::ALL,
+                Some(self.temp_settings.resampler_quality),
+                SettingsMessage::ResamplerQualityChanged
+            )
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        // Shedding load instead of crackling; applied without a restart
+        let adaptive_quality_section = checkbox(self.temp_settings.adaptive_quality)
+            .label(tr!(adaptive_quality))
+            .on_toggle(SettingsMessage::AdaptiveQualityToggled);
+
+        // A chain per input; needs a right input to run the second one on
+        let has_right_input = !self.temp_settings.input_right_port.is_empty();
+        let dual_mono_label = if has_right_input {
+            tr!(dual_mono)
+        } else {
+            tr!(dual_mono_needs_right)
+        };
+        let dual_mono_section = checkbox(self.temp_settings.dual_mono())
+            .label(dual_mono_label)
+            .on_toggle_maybe(has_right_input.then_some(SettingsMessage::ChainModeToggled));
+
+        // Latency display: the JACK buffer plus what the engine adds
+        let latency_text =
+            text(self.latency_text())
+                .size(TEXT_SIZE_INFO)
+                .style(|_theme: &iced::Theme| iced::widget::text::Style {
+                    color: Some(COLOR_SUBTLE),
+                });
 
         // NAM models directory + rescan (no restart required)
         let nam_section = column![
@@ -215,9 +544,92 @@ impl SettingsDialog {
         ]
         .spacing(SPACING_TIGHT);
 
+        // Custom metronome click; empty goes back to the bundled ticks
+        let metronome_section = row![
+            text(tr!(metronome_tick)).size(TEXT_SIZE_LABEL),
+            text_input(tr!(metronome_tick_bundled), &self.metronome_tick)
+                .on_input(SettingsMessage::MetronomeTickChanged)
+                .on_submit(SettingsMessage::LoadMetronomeTick)
+                .width(Length::Fill),
+            button(tr!(load_metronome_tick)).on_press(SettingsMessage::LoadMetronomeTick),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        // Applied immediately, like the language
+        let transport_section = checkbox(self.record_follow_transport)
+            .label(tr!(record_follow_transport))
+            .on_toggle(SettingsMessage::RecordFollowTransportToggled);
+        let automation_section = checkbox(self.record_automation)
+            .label(tr!(record_automation))
+            .on_toggle(SettingsMessage::RecordAutomationToggled);
+        let softclip_section = checkbox(self.recording_softclip)
+            .label(tr!(recording_softclip))
+            .on_toggle(SettingsMessage::RecordingSoftclipToggled);
+        let chain_lint_section = checkbox(self.chain_lint)
+            .label(tr!(chain_lint))
+            .on_toggle(SettingsMessage::ChainLintToggled);
+        let locked_stages_section = checkbox(self.keep_locked_stages)
+            .label(tr!(keep_locked_stages))
+            .on_toggle(SettingsMessage::KeepLockedStagesToggled);
+        let quantize_chain_section = checkbox(self.quantize_chain_changes)
+            .label(tr!(quantize_chain_changes))
+            .on_toggle(SettingsMessage::QuantizeChainChangesToggled);
+        let tuner_section = row![
+            text(tr!(tuner_algorithm)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                TunerAlgorithm::ALL,
+                Some(self.tuner_algorithm),
+                SettingsMessage::TunerAlgorithmChanged
+            ),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        // Stall thresholds offered for the audio watchdog, in seconds
+        let mut stall_seconds = vec![2u32, 3, 5, 10, 30];
+        if !stall_seconds.contains(&self.watchdog.stall_seconds) {
+            stall_seconds.push(self.watchdog.stall_seconds);
+            stall_seconds.sort_unstable();
+        }
+        let watchdog_section = row![
+            checkbox(self.watchdog.auto_restart)
+                .label(tr!(watchdog_auto_restart))
+                .on_toggle(SettingsMessage::WatchdogAutoRestartToggled),
+            space::horizontal(),
+            text(tr!(watchdog_stall_seconds)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                stall_seconds,
+                Some(self.watchdog.stall_seconds),
+                SettingsMessage::WatchdogStallSecondsChanged
+            ),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        // Applied on toggle, or when the port is submitted
+        let remote_section = row![
+            checkbox(self.remote_enabled)
+                .label(tr!(remote_control))
+                .on_toggle(SettingsMessage::RemoteToggled),
+            space::horizontal(),
+            text(tr!(remote_port)).size(TEXT_SIZE_LABEL),
+            text_input("", &self.remote_port)
+                .on_input(SettingsMessage::RemotePortChanged)
+                .on_submit(SettingsMessage::RemotePortSubmitted)
+                .width(Length::Fixed(80.0)),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
         // Control buttons
         let controls = row![
             button(tr!(refresh_ports)).on_press(SettingsMessage::RefreshPorts),
+            button(tr!(copy_diagnostics)).on_press(SettingsMessage::CopyDiagnostics),
+            button(tr!(calibrate_input)).on_press(SettingsMessage::CalibrateInput),
+            button(tr!(run_setup)).on_press(SettingsMessage::RunSetup),
+            button(tr!(gain_staging)).on_press(SettingsMessage::AnalyzeGainStaging),
+            button(tr!(tone_match)).on_press(SettingsMessage::MatchTone),
             space::horizontal(),
             button(tr!(apply))
                 .on_press(SettingsMessage::Apply)
@@ -232,17 +644,13 @@ impl SettingsDialog {
             jack_status_section,
             rule::horizontal(1),
             row![
-                column![
-                    language_section,
-                    input_section,
-                    output_left_section,
-                    output_right_section,
-                ]
-                .spacing(SPACING_NORMAL)
-                .padding(SPACING_TIGHT),
+                routing_column,
                 column![
                     buffer_section,
                     sample_rate_section,
+                    resampler_quality_section,
+                    adaptive_quality_section,
+                    dual_mono_section,
                     latency_text,
                     text(tr!(changes_require_restart))
                         .size(TEXT_SIZE_SMALL)
@@ -257,6 +665,19 @@ impl SettingsDialog {
             .padding(SPACING_TIGHT),
             rule::horizontal(1),
             nam_section,
+            transport_section,
+            automation_section,
+            softclip_section,
+            chain_lint_section,
+            locked_stages_section,
+            quantize_chain_section,
+            tuner_section,
+            watchdog_section,
+            remote_section,
+            self.hooks_view(),
+            metronome_section,
+            self.metronome_view(),
+            self.test_source_view(),
             controls,
         ]
         .spacing(DIALOG_CONTENT_SPACING)
@@ -267,9 +688,230 @@ impl SettingsDialog {
         Some(dialog_container(dialog_content.into()))
     }
 
+    /// Capture and playback device pickers, in place of the port pickers
+    /// under CPAL, with what the fallback can't do.
+    fn devices_view(&self) -> Element<'static, SettingsMessage> {
+        let input = column![
+            text(tr!(input_device)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                DeviceChoice::list(&self.input_devices),
+                Some(DeviceChoice::new(&self.temp_settings.input_device)),
+                |choice| SettingsMessage::InputDeviceChanged(choice.into_device())
+            )
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let output = column![
+            text(tr!(output_device)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                DeviceChoice::list(&self.output_devices),
+                Some(DeviceChoice::new(&self.temp_settings.output_device)),
+                |choice| SettingsMessage::OutputDeviceChanged(choice.into_device())
+            )
+            .width(Length::Fill),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let note = text(tr!(cpal_limitations))
+            .size(TEXT_SIZE_SMALL)
+            .style(|_: &iced::Theme| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            });
+
+        column![input, output, note].spacing(SPACING_NORMAL).into()
+    }
+
+    /// Hook commands per event, each saved when submitted, behind a switch
+    /// applied immediately.
+    fn hooks_view(&self) -> Element<'static, SettingsMessage> {
+        let toggle = row![
+            checkbox(self.hooks_enabled)
+                .label(tr!(hooks_enabled))
+                .on_toggle(SettingsMessage::HooksToggled),
+            text(tr!(hooks_security_note))
+                .size(TEXT_SIZE_SMALL)
+                .style(|_: &iced::Theme| iced::widget::text::Style {
+                    color: Some(COLOR_WARNING),
+                }),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let mut section = column![toggle].spacing(SPACING_TIGHT);
+        if !self.hooks_enabled {
+            return section.into();
+        }
+        for kind in EventKind::ALL {
+            section = section.push(
+                row![
+                    text(hook_label(kind))
+                        .size(TEXT_SIZE_LABEL)
+                        .width(Length::Fixed(160.0)),
+                    text_input(tr!(hook_command_placeholder), &self.hook_command(kind))
+                        .on_input(move |command| SettingsMessage::HookCommandChanged(kind, command))
+                        .on_submit(SettingsMessage::HookCommandSubmitted(kind))
+                        .width(Length::Fill),
+                ]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            );
+        }
+        section.into()
+    }
+
+    /// Metronome on/off, tempo and where the click goes, applied immediately.
+    fn metronome_view(&self) -> Element<'static, SettingsMessage> {
+        let config = self.metronome;
+        let beats: Vec<usize> = BEATS_PER_BAR_RANGE.collect();
+        let tempo = row![
+            checkbox(config.enabled)
+                .label(tr!(metronome))
+                .on_toggle(move |enabled| {
+                    SettingsMessage::MetronomeChanged(MetronomeConfig { enabled, ..config })
+                }),
+            text(tr!(metronome_bpm)).size(TEXT_SIZE_LABEL),
+            slider(MIN_BPM..=MAX_BPM, config.bpm, move |bpm| {
+                SettingsMessage::MetronomeChanged(MetronomeConfig { bpm, ..config })
+            })
+            .step(1.0)
+            .width(Length::Fixed(160.0)),
+            text(format!("{:.0}", config.bpm)).size(TEXT_SIZE_INFO),
+            text(tr!(metronome_time_signature)).size(TEXT_SIZE_LABEL),
+            pick_list(beats, Some(config.beats_per_bar), move |beats_per_bar| {
+                SettingsMessage::MetronomeChanged(MetronomeConfig {
+                    beats_per_bar,
+                    ..config
+                })
+            }),
+            text("/4").size(TEXT_SIZE_INFO),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let routing = row![
+            checkbox(config.in_monitor)
+                .label(tr!(click_in_monitor))
+                .on_toggle(move |in_monitor| {
+                    SettingsMessage::MetronomeChanged(MetronomeConfig {
+                        in_monitor,
+                        ..config
+                    })
+                }),
+            checkbox(self.record_click_track)
+                .label(tr!(record_click_track))
+                .on_toggle(SettingsMessage::RecordClickTrackToggled),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        column![tempo, routing].spacing(SPACING_TIGHT).into()
+    }
+
+    /// Signal generator played in place of the input, applied immediately.
+    fn test_source_view(&self) -> Element<'static, SettingsMessage> {
+        let config = self.test_source;
+        let mut section = row![
+            text(tr!(test_source)).size(TEXT_SIZE_LABEL),
+            pick_list(TestSourceKind::ALL, Some(config.kind), move |kind| {
+                SettingsMessage::TestSourceChanged(TestSourceConfig { kind, ..config })
+            }),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        match config.kind {
+            TestSourceKind::Off => return section.into(),
+            TestSourceKind::Sine => {
+                section = section.push(text(tr!(test_source_frequency)).size(TEXT_SIZE_LABEL));
+                section = section.push(
+                    slider(
+                        MIN_TEST_FREQUENCY_HZ..=MAX_TEST_FREQUENCY_HZ,
+                        config.frequency_hz,
+                        move |frequency_hz| {
+                            SettingsMessage::TestSourceChanged(TestSourceConfig {
+                                frequency_hz,
+                                ..config
+                            })
+                        },
+                    )
+                    .step(1.0)
+                    .width(Length::Fixed(160.0)),
+                );
+                section = section.push(
+                    text(format!("{:.0} {}", config.frequency_hz, tr!(hz))).size(TEXT_SIZE_INFO),
+                );
+            }
+            TestSourceKind::PinkNoise => {}
+            TestSourceKind::DiSample => {
+                section = section.push(
+                    text_input(tr!(test_di_file), &self.test_di_file)
+                        .on_input(SettingsMessage::TestDiFileChanged)
+                        .on_submit(SettingsMessage::LoadTestDi)
+                        .width(Length::Fill),
+                );
+                section =
+                    section.push(button(tr!(load_test_di)).on_press(SettingsMessage::LoadTestDi));
+            }
+        }
+
+        section = section.push(text(tr!(level)).size(TEXT_SIZE_LABEL));
+        section = section.push(
+            slider(
+                MIN_TEST_LEVEL_DB..=MAX_TEST_LEVEL_DB,
+                config.level_db,
+                move |level_db| {
+                    SettingsMessage::TestSourceChanged(TestSourceConfig { level_db, ..config })
+                },
+            )
+            .step(1.0)
+            .width(Length::Fixed(120.0)),
+        );
+        section
+            .push(text(format!("{:.0} {}", config.level_db, tr!(db))).size(TEXT_SIZE_INFO))
+            .into()
+    }
+
+    /// Total latency with its breakdown, e.g. "Base 2.7 ms + oversampling
+    /// 1.3 ms + pitch shifter 42.7 ms". Parts adding nothing are left out.
+    fn latency_text(&self) -> String {
+        let status = &self.jack_status;
+        let ms = |samples: usize| samples as f32 / status.sample_rate as f32 * 1000.0;
+        let latency = status.engine_latency;
+
+        let mut parts = vec![format!(
+            "{} {:.1} {}",
+            tr!(latency_base),
+            ms(status.buffer_size),
+            tr!(ms)
+        )];
+        for (label, samples) in [
+            (tr!(latency_oversampling), latency.oversampling),
+            (tr!(latency_denoiser), latency.denoiser),
+            (tr!(latency_pitch_shifter), latency.pitch_shifter),
+            (tr!(latency_cabinet), latency.cabinet),
+        ] {
+            if samples > 0 {
+                parts.push(format!("{label} {:.1} {}", ms(samples), tr!(ms)));
+            }
+        }
+
+        format!(
+            "{} {:.2} {} ({})",
+            tr!(actual_latency),
+            ms(status.buffer_size + latency.total()),
+            tr!(ms),
+            parts.join(" + ")
+        )
+    }
+
     /// The view containing JACK server status information
     fn jack_status_view(&self) -> Element<'static, SettingsMessage> {
-        let header = text(tr!(jack_server_status))
+        let title = match self.jack_status.backend {
+            BackendKind::Jack => tr!(jack_server_status),
+            BackendKind::Cpal => tr!(cpal_stream_status),
+        };
+        let header = text(title)
             .size(TEXT_SIZE_SECTION_TITLE)
             .style(|theme: &iced::Theme| iced::widget::text::Style {
                 color: Some(theme.palette().text),
@@ -336,11 +978,14 @@ impl SettingsDialog {
         .align_y(Alignment::Center);
 
         let warning = if !sample_rate_match || !buffer_size_match {
-            text(tr!(jack_different_settings))
-                .size(TEXT_SIZE_SMALL)
-                .style(|_: &iced::Theme| iced::widget::text::Style {
-                    color: Some(COLOR_WARNING),
-                })
+            text(match self.jack_status.backend {
+                BackendKind::Jack => tr!(jack_different_settings),
+                BackendKind::Cpal => tr!(cpal_different_settings),
+            })
+            .size(TEXT_SIZE_SMALL)
+            .style(|_: &iced::Theme| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            })
         } else {
             text("")
         };
@@ -353,3 +998,12 @@ impl SettingsDialog {
         )
     }
 }
+
+fn hook_label(kind: EventKind) -> &'static str {
+    match kind {
+        EventKind::RecordingStarted => tr!(hook_recording_started),
+        EventKind::RecordingStopped => tr!(hook_recording_stopped),
+        EventKind::PresetChanged => tr!(hook_preset_changed),
+        EventKind::AudioDisconnected => tr!(hook_audio_disconnected),
+    }
+}
@@ -0,0 +1,468 @@
+use iced::widget::{
+    button, checkbox, column, container, pick_list, progress_bar, row, rule, space, text,
+    text_input,
+};
+use iced::{Alignment, Color, Element, Length};
+
+use crate::audio::backend::BackendKind;
+use crate::gui::components::dialogs::settings::DeviceChoice;
+use crate::settings::{AudioSettings, PathSettings};
+use crate::setup::AudioServer;
+use crate::tr;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_MUTED, COLOR_SUCCESS, COLOR_WARNING, PADDING_NORMAL, SPACING_NORMAL,
+    SPACING_TIGHT, TEXT_SIZE_INFO, TEXT_SIZE_LABEL, TEXT_SIZE_SECTION_TITLE,
+};
+use rustortion_ui::messages::SetupMessage;
+
+/// Range of the input meter on the ports step.
+const METER_RANGE_DB: std::ops::RangeInclusive<f32> = -60.0..=0.0;
+
+/// Where the setup is up to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SetupStep {
+    /// Find a JACK or PipeWire server, or explain how to get one.
+    #[default]
+    Audio,
+    /// Pick the input and outputs, with a meter to confirm the signal.
+    Ports,
+    /// Recording, IR and preset directories, and the factory presets.
+    Folders,
+    /// Optionally a MIDI controller, through the MIDI dialog's learn flow.
+    Midi,
+}
+
+impl SetupStep {
+    pub const COUNT: usize = 4;
+
+    /// 1-based, for the step counter.
+    pub const fn number(self) -> usize {
+        self as usize + 1
+    }
+
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Audio => Self::Ports,
+            Self::Ports => Self::Folders,
+            Self::Folders | Self::Midi => Self::Midi,
+        }
+    }
+
+    pub const fn previous(self) -> Self {
+        match self {
+            Self::Audio | Self::Ports => Self::Audio,
+            Self::Folders => Self::Ports,
+            Self::Midi => Self::Folders,
+        }
+    }
+
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Audio => tr!(setup_audio_heading),
+            Self::Ports => tr!(setup_ports_heading),
+            Self::Folders => tr!(setup_folders_heading),
+            Self::Midi => tr!(setup_midi_heading),
+        }
+    }
+}
+
+/// First-run setup wizard. On a first run without audio it's the only thing
+/// shown, held on the first step; see [`Self::hold`].
+pub struct SetupDialog {
+    step: SetupStep,
+    show_dialog: bool,
+    server: AudioServer,
+    /// The running stream's backend, or `None` while audio can't start.
+    backend: Option<BackendKind>,
+    /// Why audio couldn't start, translated, and the untranslated detail.
+    audio_error: Option<(String, String)>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// The ports or devices picked so far, applied as they're picked.
+    audio: AudioSettings,
+    input_peak_db: f32,
+    paths: PathSettings,
+    install_presets: bool,
+    folders_error: Option<String>,
+}
+
+impl Default for SetupDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetupDialog {
+    pub fn new() -> Self {
+        Self {
+            step: SetupStep::Audio,
+            show_dialog: false,
+            server: AudioServer::None,
+            backend: None,
+            audio_error: None,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            audio: AudioSettings::default(),
+            input_peak_db: f32::NEG_INFINITY,
+            paths: PathSettings::default(),
+            install_presets: true,
+            folders_error: None,
+        }
+    }
+
+    /// Open at the first step with audio running on `backend`.
+    pub fn show(
+        &mut self,
+        server: AudioServer,
+        backend: BackendKind,
+        audio: &AudioSettings,
+        paths: &PathSettings,
+    ) {
+        self.step = SetupStep::Audio;
+        self.server = server;
+        self.backend = Some(backend);
+        self.audio_error = None;
+        self.audio = audio.clone();
+        self.input_peak_db = f32::NEG_INFINITY;
+        self.paths = paths.clone();
+        self.install_presets = true;
+        self.folders_error = None;
+        self.show_dialog = true;
+    }
+
+    /// Hold on the first step because audio can't start: `message` says why
+    /// in the user's language, `detail` is the untranslated error.
+    pub fn hold(&mut self, server: AudioServer, message: String, detail: String) {
+        self.step = SetupStep::Audio;
+        self.server = server;
+        self.backend = None;
+        self.audio_error = Some((message, detail));
+        self.show_dialog = true;
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub const fn step(&self) -> SetupStep {
+        self.step
+    }
+
+    pub const fn set_step(&mut self, step: SetupStep) {
+        self.step = step;
+    }
+
+    pub const fn set_server(&mut self, server: AudioServer) {
+        self.server = server;
+    }
+
+    pub fn set_ports(&mut self, inputs: Vec<String>, outputs: Vec<String>) {
+        self.inputs = inputs;
+        self.outputs = outputs;
+    }
+
+    pub fn set_audio(&mut self, audio: &AudioSettings) {
+        self.audio = audio.clone();
+    }
+
+    pub const fn set_input_peak(&mut self, peak_db: f32) {
+        self.input_peak_db = peak_db;
+    }
+
+    pub const fn paths(&self) -> &PathSettings {
+        &self.paths
+    }
+
+    pub const fn paths_mut(&mut self) -> &mut PathSettings {
+        &mut self.paths
+    }
+
+    pub const fn install_presets(&self) -> bool {
+        self.install_presets
+    }
+
+    pub const fn set_install_presets(&mut self, install: bool) {
+        self.install_presets = install;
+    }
+
+    pub fn set_folders_error(&mut self, error: Option<String>) {
+        self.folders_error = error;
+    }
+
+    const fn uses_cpal(&self) -> bool {
+        matches!(self.backend, Some(BackendKind::Cpal))
+    }
+
+    pub fn view(&self) -> Option<Element<'_, SetupMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        // Without audio there's no app behind the setup to skip to.
+        let holding = self.backend.is_none();
+        let title_row = if holding {
+            dialog_title_row(tr!(setup_title), SetupMessage::Quit)
+        } else {
+            dialog_title_row(tr!(setup_title), SetupMessage::Skip)
+        };
+
+        let header = column![
+            muted_line(format!(
+                "{} {}/{}",
+                tr!(setup_step),
+                self.step.number(),
+                SetupStep::COUNT
+            )),
+            text(self.step.heading()).size(TEXT_SIZE_SECTION_TITLE),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let body = match self.step {
+            SetupStep::Audio => self.audio_view(),
+            SetupStep::Ports => self.ports_view(),
+            SetupStep::Folders => self.folders_view(),
+            SetupStep::Midi => column![
+                text(tr!(setup_midi_hint)).size(TEXT_SIZE_INFO),
+                button(tr!(setup_midi_open)).on_press(SetupMessage::SetUpMidi),
+            ]
+            .spacing(SPACING_NORMAL)
+            .into(),
+        };
+
+        let section = dialog_section_container(
+            column![header, body]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let mut controls = row![].spacing(SPACING_NORMAL).width(Length::Fill);
+        if holding {
+            controls = controls.push(button(tr!(quit)).on_press(SetupMessage::Quit));
+        } else {
+            controls = controls.push(button(tr!(setup_skip)).on_press(SetupMessage::Skip));
+        }
+        controls = controls.push(space::horizontal());
+        if self.step != SetupStep::Audio {
+            controls = controls.push(button(tr!(setup_back)).on_press(SetupMessage::Back));
+        }
+        controls = if self.step == SetupStep::Midi {
+            controls.push(
+                button(tr!(setup_finish))
+                    .on_press(SetupMessage::Finish)
+                    .style(iced::widget::button::success),
+            )
+        } else {
+            controls.push(
+                button(tr!(setup_next))
+                    .on_press_maybe((!holding).then_some(SetupMessage::Next))
+                    .style(iced::widget::button::success),
+            )
+        };
+
+        let dialog_content = column![
+            title_row,
+            rule::horizontal(1),
+            section,
+            space::vertical(),
+            controls
+        ]
+        .spacing(DIALOG_CONTENT_SPACING)
+        .padding(DIALOG_CONTENT_PADDING)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+
+    fn audio_view(&self) -> Element<'_, SetupMessage> {
+        let (server, color) = match self.server {
+            AudioServer::Jack => (tr!(setup_server_jack), COLOR_SUCCESS),
+            AudioServer::PipeWireJack => (tr!(setup_server_pipewire_jack), COLOR_SUCCESS),
+            AudioServer::PipeWireWithoutJack => (tr!(setup_server_pipewire_no_jack), COLOR_WARNING),
+            AudioServer::None => (tr!(setup_server_none), COLOR_WARNING),
+        };
+        let mut content = column![colored_line(server, color)].spacing(SPACING_TIGHT);
+
+        if self.uses_cpal() {
+            content = content.push(text(tr!(setup_running_cpal)).size(TEXT_SIZE_INFO));
+        }
+        if let Some((message, detail)) = &self.audio_error {
+            content = content
+                .push(text(tr!(setup_waiting_audio)).size(TEXT_SIZE_INFO))
+                .push(colored_line(message, COLOR_ERROR))
+                .push(muted_line(detail.clone()));
+        }
+
+        column![
+            content,
+            button(tr!(setup_retry)).on_press(SetupMessage::Retry)
+        ]
+        .spacing(SPACING_NORMAL)
+        .into()
+    }
+
+    fn ports_view(&self) -> Element<'_, SetupMessage> {
+        let routing: Element<'_, SetupMessage> = if self.uses_cpal() {
+            column![
+                labelled(
+                    tr!(input_device),
+                    pick_list(
+                        DeviceChoice::list(&self.inputs),
+                        Some(DeviceChoice::new(&self.audio.input_device)),
+                        |choice| SetupMessage::InputChanged(choice.into_device()),
+                    )
+                    .width(Length::Fill)
+                    .into(),
+                ),
+                labelled(
+                    tr!(output_device),
+                    pick_list(
+                        DeviceChoice::list(&self.outputs),
+                        Some(DeviceChoice::new(&self.audio.output_device)),
+                        |choice| SetupMessage::OutputLeftChanged(choice.into_device()),
+                    )
+                    .width(Length::Fill)
+                    .into(),
+                ),
+            ]
+            .spacing(SPACING_NORMAL)
+            .into()
+        } else {
+            column![
+                labelled(
+                    tr!(input_port),
+                    pick_list(
+                        self.inputs.as_slice(),
+                        Some(&self.audio.input_port),
+                        SetupMessage::InputChanged,
+                    )
+                    .width(Length::Fill)
+                    .into(),
+                ),
+                labelled(
+                    tr!(output_left_port),
+                    pick_list(
+                        self.outputs.as_slice(),
+                        Some(&self.audio.output_left_port),
+                        SetupMessage::OutputLeftChanged,
+                    )
+                    .width(Length::Fill)
+                    .into(),
+                ),
+                labelled(
+                    tr!(output_right_port),
+                    pick_list(
+                        self.outputs.as_slice(),
+                        Some(&self.audio.output_right_port),
+                        SetupMessage::OutputRightChanged,
+                    )
+                    .width(Length::Fill)
+                    .into(),
+                ),
+            ]
+            .spacing(SPACING_NORMAL)
+            .into()
+        };
+
+        let peak_db = self.input_peak_db;
+        let reading = if peak_db.is_finite() {
+            format!("{peak_db:+.1} dBFS")
+        } else {
+            "-- dBFS".to_string()
+        };
+        let meter = row![
+            text(tr!(setup_input_level)).size(TEXT_SIZE_LABEL),
+            container(progress_bar(
+                METER_RANGE_DB,
+                peak_db.clamp(*METER_RANGE_DB.start(), *METER_RANGE_DB.end()),
+            ))
+            .width(Length::FillPortion(5)),
+            text(reading)
+                .size(TEXT_SIZE_INFO)
+                .width(Length::FillPortion(2)),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        column![
+            routing,
+            text(tr!(setup_ports_hint)).size(TEXT_SIZE_INFO),
+            meter,
+            button(tr!(refresh_ports)).on_press(SetupMessage::RefreshPorts),
+        ]
+        .spacing(SPACING_NORMAL)
+        .into()
+    }
+
+    fn folders_view(&self) -> Element<'_, SetupMessage> {
+        let mut content = column![
+            text(tr!(setup_folders_hint)).size(TEXT_SIZE_INFO),
+            labelled(
+                tr!(setup_recording_dir),
+                text_input(tr!(setup_recording_dir), &self.paths.recording_dir)
+                    .on_input(SetupMessage::RecordingDirChanged)
+                    .width(Length::Fill)
+                    .into(),
+            ),
+            labelled(
+                tr!(setup_ir_dir),
+                text_input(tr!(setup_ir_dir), &self.paths.ir_dir)
+                    .on_input(SetupMessage::IrDirChanged)
+                    .width(Length::Fill)
+                    .into(),
+            ),
+            labelled(
+                tr!(setup_preset_dir),
+                text_input(tr!(setup_preset_dir), &self.paths.preset_dir)
+                    .on_input(SetupMessage::PresetDirChanged)
+                    .width(Length::Fill)
+                    .into(),
+            ),
+            checkbox(self.install_presets)
+                .label(tr!(setup_install_presets))
+                .on_toggle(SetupMessage::InstallPresetsToggled),
+        ]
+        .spacing(SPACING_NORMAL);
+
+        if let Some(error) = &self.folders_error {
+            content = content.push(colored_line(
+                &format!("{} {error}", tr!(setup_folders_failed)),
+                COLOR_ERROR,
+            ));
+        }
+        content.into()
+    }
+}
+
+fn labelled<'a>(
+    label: &'static str,
+    control: Element<'a, SetupMessage>,
+) -> Element<'a, SetupMessage> {
+    column![text(label).size(TEXT_SIZE_LABEL), control]
+        .spacing(SPACING_TIGHT)
+        .into()
+}
+
+fn colored_line(label: &str, color: Color) -> iced::widget::Text<'static> {
+    text(label.to_string())
+        .size(TEXT_SIZE_INFO)
+        .style(move |_: &iced::Theme| iced::widget::text::Style { color: Some(color) })
+}
+
+/// Like `muted_text`, but owning its label.
+fn muted_line(label: String) -> iced::widget::Text<'static> {
+    text(label)
+        .size(TEXT_SIZE_INFO)
+        .style(|_: &iced::Theme| iced::widget::text::Style {
+            color: Some(COLOR_MUTED),
+        })
+}
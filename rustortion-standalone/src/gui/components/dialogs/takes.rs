@@ -0,0 +1,212 @@
+use iced::widget::{button, checkbox, column, progress_bar, row, rule, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row, muted_text,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO, TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::TakesMessage;
+
+/// "Compare takes" dialog: the latest takes, one per number key, and a
+/// transport for the playhead they share.
+pub struct TakesDialog {
+    names: Vec<String>,
+    active: usize,
+    playing: bool,
+    /// Playhead and length of the aligned takes, in frames.
+    frame: usize,
+    length: usize,
+    sample_rate: usize,
+    normalize: bool,
+    error: Option<String>,
+    show_dialog: bool,
+}
+
+impl Default for TakesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TakesDialog {
+    pub const fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            active: 0,
+            playing: false,
+            frame: 0,
+            length: 0,
+            sample_rate: 48_000,
+            normalize: false,
+            error: None,
+            show_dialog: false,
+        }
+    }
+
+    pub const fn show(&mut self) {
+        self.show_dialog = true;
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+        self.playing = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    /// Freshly loaded takes, stopped at the start of the first.
+    pub fn set_takes(&mut self, names: Vec<String>, length: usize, sample_rate: usize) {
+        self.names = names;
+        self.length = length;
+        self.sample_rate = sample_rate.max(1);
+        self.active = 0;
+        self.playing = false;
+        self.frame = 0;
+        self.error = None;
+    }
+
+    /// Loading failed; nothing is left to play.
+    pub fn set_error(&mut self, error: String) {
+        self.set_takes(Vec::new(), 0, self.sample_rate);
+        self.error = Some(error);
+    }
+
+    pub const fn take_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub const fn set_active(&mut self, take: usize) {
+        self.active = take;
+    }
+
+    pub const fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub const fn set_transport(&mut self, frame: usize, playing: bool) {
+        self.frame = frame;
+        self.playing = playing;
+    }
+
+    pub const fn normalize(&self) -> bool {
+        self.normalize
+    }
+
+    pub const fn set_normalize(&mut self, normalize: bool) {
+        self.normalize = normalize;
+    }
+
+    pub fn view(&self) -> Option<Element<'_, TakesMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(takes_title), TakesMessage::Close);
+
+        let options = row![
+            checkbox(self.normalize)
+                .label(tr!(takes_normalize))
+                .on_toggle(TakesMessage::NormalizeToggled),
+            space::horizontal(),
+            button(tr!(takes_reload))
+                .on_press(TakesMessage::Reload)
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let mut content = column![muted_text(tr!(takes_hint)), options].spacing(SPACING_NORMAL);
+        if let Some(error) = &self.error {
+            content = content.push(
+                text(format!("{} {error}", tr!(takes_failed)))
+                    .size(TEXT_SIZE_INFO)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(COLOR_ERROR),
+                    }),
+            );
+        } else if self.names.is_empty() {
+            content = content.push(muted_text(tr!(takes_none)));
+        } else {
+            content = content.push(self.view_takes()).push(self.view_transport());
+        }
+
+        let dialog_content = column![
+            title_row,
+            rule::horizontal(1),
+            dialog_section_container(content.padding(PADDING_NORMAL).into()),
+        ]
+        .spacing(DIALOG_CONTENT_SPACING)
+        .padding(DIALOG_CONTENT_PADDING)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+
+    fn view_takes(&self) -> Element<'_, TakesMessage> {
+        let mut takes = column![].spacing(SPACING_TIGHT);
+        for (idx, name) in self.names.iter().enumerate() {
+            let style = if idx == self.active {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            };
+            takes = takes.push(
+                button(
+                    row![
+                        text((idx + 1).to_string()).size(TEXT_SIZE_LABEL),
+                        text(name).size(TEXT_SIZE_LABEL),
+                    ]
+                    .spacing(SPACING_NORMAL),
+                )
+                .on_press(TakesMessage::Select(idx))
+                .style(style)
+                .width(Length::Fill),
+            );
+        }
+        takes.into()
+    }
+
+    fn view_transport(&self) -> Element<'_, TakesMessage> {
+        let play = if self.playing {
+            tr!(takes_pause)
+        } else {
+            tr!(takes_play)
+        };
+        let position = format!(
+            "{} / {}",
+            clock(self.frame, self.sample_rate),
+            clock(self.length, self.sample_rate)
+        );
+
+        column![
+            progress_bar(0.0..=self.length.max(1) as f32, self.frame as f32),
+            row![
+                button(tr!(takes_rewind))
+                    .on_press(TakesMessage::Rewind)
+                    .style(iced::widget::button::secondary),
+                button(play)
+                    .on_press(TakesMessage::PlayPause)
+                    .style(iced::widget::button::success),
+                space::horizontal(),
+                text(position).size(TEXT_SIZE_INFO),
+            ]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center),
+        ]
+        .spacing(SPACING_TIGHT)
+        .into()
+    }
+}
+
+/// `frames` as minutes and seconds, like the recording timer.
+fn clock(frames: usize, sample_rate: usize) -> String {
+    let secs = frames / sample_rate;
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,239 @@
+use iced::widget::{
+    button, column, container, pick_list, progress_bar, row, rule, scrollable, space, text,
+    text_input,
+};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_core::amp::stages::eq::{BAND_FREQS, MAX_GAIN_DB, MIN_GAIN_DB};
+use rustortion_core::audio::tone_match::ToneMatch;
+use rustortion_ui::components::dialogs::common::{
+    dialog_container, dialog_section_container, dialog_title_row,
+};
+use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use rustortion_ui::components::widgets::common::{
+    COLOR_ERROR, COLOR_WARNING, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO,
+    TEXT_SIZE_LABEL,
+};
+use rustortion_ui::messages::ToneMatchMessage;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToneMatchStatus {
+    Idle,
+    /// Fraction of the renders done so far.
+    Running(f32),
+    /// The correction, waiting to be appended or discarded. `can_apply` is
+    /// false when the chain has no room for another stage.
+    Done {
+        matched: ToneMatch,
+        can_apply: bool,
+    },
+    Failed(String),
+}
+
+/// "Tone match" dialog: pick a source and a reference, run the analysis, and
+/// append the correction EQ if it looks right.
+pub struct ToneMatchDialog {
+    source_path: String,
+    reference_preset: Option<String>,
+    reference_path: String,
+    presets: Vec<String>,
+    status: ToneMatchStatus,
+    show_dialog: bool,
+}
+
+impl Default for ToneMatchDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToneMatchDialog {
+    pub const fn new() -> Self {
+        Self {
+            source_path: String::new(),
+            reference_preset: None,
+            reference_path: String::new(),
+            presets: Vec::new(),
+            status: ToneMatchStatus::Idle,
+            show_dialog: false,
+        }
+    }
+
+    pub fn show(&mut self, presets: Vec<String>) {
+        self.show_dialog = true;
+        self.presets = presets;
+        if !self.is_running() {
+            self.status = ToneMatchStatus::Idle;
+        }
+    }
+
+    pub const fn hide(&mut self) {
+        self.show_dialog = false;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub const fn is_running(&self) -> bool {
+        matches!(self.status, ToneMatchStatus::Running(_))
+    }
+
+    pub fn set_status(&mut self, status: ToneMatchStatus) {
+        self.status = status;
+    }
+
+    pub const fn status(&self) -> &ToneMatchStatus {
+        &self.status
+    }
+
+    pub fn set_source_path(&mut self, path: String) {
+        self.source_path = path;
+    }
+
+    /// Picking a preset replaces a recording as the reference, and the other
+    /// way round.
+    pub fn set_reference_preset(&mut self, name: String) {
+        self.reference_preset = Some(name);
+        self.reference_path.clear();
+    }
+
+    pub fn set_reference_path(&mut self, path: String) {
+        self.reference_path = path;
+        self.reference_preset = None;
+    }
+
+    pub fn source_path(&self) -> &str {
+        self.source_path.trim()
+    }
+
+    pub fn reference_preset(&self) -> Option<&str> {
+        self.reference_preset.as_deref()
+    }
+
+    pub fn reference_path(&self) -> &str {
+        self.reference_path.trim()
+    }
+
+    pub fn view(&self) -> Option<Element<'_, ToneMatchMessage>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(tr!(tone_match_title), ToneMatchMessage::Close);
+        let instructions = text(tr!(tone_match_instructions)).size(TEXT_SIZE_INFO);
+
+        let running = self.is_running();
+        let inputs = column![
+            text(tr!(tone_match_source)).size(TEXT_SIZE_LABEL),
+            text_input("di.wav", &self.source_path)
+                .on_input_maybe((!running).then_some(ToneMatchMessage::SourcePathChanged)),
+            text(tr!(tone_match_reference_preset)).size(TEXT_SIZE_LABEL),
+            pick_list(
+                self.presets.clone(),
+                self.reference_preset.clone(),
+                ToneMatchMessage::ReferencePresetSelected,
+            )
+            .width(Length::Fill),
+            text(tr!(tone_match_reference_file)).size(TEXT_SIZE_LABEL),
+            text_input("reference.wav", &self.reference_path)
+                .on_input_maybe((!running).then_some(ToneMatchMessage::ReferencePathChanged)),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        let has_reference = self.reference_preset.is_some() || !self.reference_path().is_empty();
+        let start_button = button(tr!(tone_match_start))
+            .on_press_maybe((!running && has_reference).then_some(ToneMatchMessage::Start));
+
+        let status: Element<'_, ToneMatchMessage> = match &self.status {
+            ToneMatchStatus::Idle => column![].into(),
+            ToneMatchStatus::Running(progress) => column![
+                text(tr!(tone_match_running)).size(TEXT_SIZE_INFO),
+                progress_bar(0.0..=1.0, *progress),
+            ]
+            .spacing(SPACING_TIGHT)
+            .into(),
+            ToneMatchStatus::Done { matched, can_apply } => view_result(matched, *can_apply),
+            ToneMatchStatus::Failed(error) => text(format!("{} {error}", tr!(tone_match_failed)))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_ERROR),
+                })
+                .into(),
+        };
+
+        let section = dialog_section_container(
+            column![instructions, inputs, start_button, status]
+                .spacing(SPACING_NORMAL)
+                .padding(PADDING_NORMAL)
+                .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+fn view_result(matched: &ToneMatch, can_apply: bool) -> Element<'_, ToneMatchMessage> {
+    let mut bands = column![].spacing(SPACING_TIGHT);
+    for (freq, gain) in BAND_FREQS.iter().zip(matched.eq_gains) {
+        bands = bands.push(
+            row![
+                text(band_label(*freq))
+                    .size(TEXT_SIZE_LABEL)
+                    .width(Length::FillPortion(2)),
+                container(progress_bar(MIN_GAIN_DB..=MAX_GAIN_DB, gain))
+                    .width(Length::FillPortion(7)),
+                text(format!("{gain:+.1} dB"))
+                    .size(TEXT_SIZE_INFO)
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let summary = text(format!(
+        "{} {:.1} dB",
+        tr!(tone_match_largest),
+        matched.max_deviation_db()
+    ))
+    .size(TEXT_SIZE_INFO);
+
+    let apply: Element<'_, ToneMatchMessage> = if can_apply {
+        button(tr!(tone_match_apply))
+            .on_press(ToneMatchMessage::Apply)
+            .style(iced::widget::button::success)
+            .into()
+    } else {
+        text(tr!(tone_match_chain_full))
+            .size(TEXT_SIZE_INFO)
+            .style(|_| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            })
+            .into()
+    };
+
+    column![
+        summary,
+        scrollable(bands).height(Length::Fill),
+        row![space::horizontal(), apply].align_y(Alignment::Center),
+    ]
+    .spacing(SPACING_NORMAL)
+    .into()
+}
+
+/// "25 Hz", "1.6 kHz" and so on, for an EQ band.
+fn band_label(freq: f64) -> String {
+    if freq >= 1000.0 {
+        format!("{} kHz", freq / 1000.0)
+    } else {
+        format!("{freq} Hz")
+    }
+}
@@ -1,8 +1,13 @@
-use iced::widget::{column, container, row, rule, space, text};
-use iced::{Alignment, Color, Element, Length};
+use std::f32::consts::TAU;
+use std::time::Instant;
+
+use iced::widget::{
+    button, canvas, checkbox, column, container, pick_list, row, rule, space, text,
+};
+use iced::{Alignment, Color, Element, Length, Radians, Rectangle, mouse};
 
 use crate::tr;
-use rustortion_core::tuner::TunerInfo;
+use rustortion_core::tuner::{TargetNote, TunerInfo, TunerMode};
 use rustortion_ui::components::dialogs::common::{dialog_container, dialog_title_row};
 use rustortion_ui::components::dialogs::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
 use rustortion_ui::components::widgets::common::{
@@ -11,28 +16,45 @@ use rustortion_ui::components::widgets::common::{
 };
 use rustortion_ui::messages::TunerMessage;
 
+/// Segments a strobe band drifts per second for each cent off.
+const STROBE_SEGMENTS_PER_CENT: f32 = 0.5;
+/// How quickly the strobe's speed follows a new reading, so it glides
+/// between polls rather than jumping.
+const STROBE_EASE_SECONDS: f32 = 0.08;
+/// Past this the bands spin too fast to read anyway.
+const STROBE_MAX_CENTS: f32 = 50.0;
+const STROBE_RINGS: u32 = 4;
+const STROBE_SIZE: f32 = 240.0;
+
 pub struct TunerDisplay {
     info: TunerInfo,
     show_dialog: bool,
+    mode: TunerMode,
+    lock: Option<TargetNote>,
+    strobe: StrobeMotion,
 }
 
 impl Default for TunerDisplay {
     fn default() -> Self {
-        Self::new()
+        Self::new(TunerMode::default())
     }
 }
 
 impl TunerDisplay {
-    pub fn new() -> Self {
+    pub fn new(mode: TunerMode) -> Self {
         Self {
             info: TunerInfo::default(),
             show_dialog: false,
+            mode,
+            lock: None,
+            strobe: StrobeMotion::default(),
         }
     }
 
     pub fn show(&mut self) {
         self.show_dialog = true;
         self.info = TunerInfo::default();
+        self.strobe = StrobeMotion::default();
     }
 
     pub const fn hide(&mut self) {
@@ -44,9 +66,20 @@ impl TunerDisplay {
     }
 
     pub fn update(&mut self, info: TunerInfo) {
+        let cents = info.cents_off.filter(|_| info.is_confident());
+        self.strobe.advance(cents, Instant::now());
         self.info = info;
     }
 
+    pub const fn set_mode(&mut self, mode: TunerMode) {
+        self.mode = mode;
+    }
+
+    pub fn set_lock(&mut self, lock: Option<TargetNote>) {
+        self.lock = lock;
+        self.info = TunerInfo::default();
+    }
+
     pub fn view(&self) -> Option<Element<'_, TunerMessage>> {
         if !self.show_dialog {
             return None;
@@ -54,14 +87,18 @@ impl TunerDisplay {
 
         let title_row = dialog_title_row(tr!(tuner_title), TunerMessage::Toggle);
 
+        let confident = self.info.is_confident();
+
         let note_display = if let Some(ref note) = self.info.note {
             text(note)
                 .size(96)
                 .style(move |_: &iced::Theme| iced::widget::text::Style {
                     color: Some(if self.info.in_tune {
                         COLOR_SUCCESS
-                    } else {
+                    } else if confident {
                         Color::from_rgb(0.9, 0.9, 0.9)
+                    } else {
+                        COLOR_INACTIVE
                     }),
                 })
         } else {
@@ -85,7 +122,10 @@ impl TunerDisplay {
                     color: Some(COLOR_SUBTLE),
                 });
 
-        let cents_indicator = self.cents_display();
+        let cents_indicator = match self.mode {
+            TunerMode::Needle => self.cents_display(),
+            TunerMode::Strobe => self.strobe_display(),
+        };
 
         let status_text = if self.info.in_tune {
             text(format!("{} \u{2713}", tr!(in_tune)))
@@ -93,6 +133,12 @@ impl TunerDisplay {
                 .style(|_: &iced::Theme| iced::widget::text::Style {
                     color: Some(COLOR_SUCCESS),
                 })
+        } else if self.info.cents_off.is_some() && !confident {
+            text(tr!(tuner_unsteady))
+                .size(20)
+                .style(|_: &iced::Theme| iced::widget::text::Style {
+                    color: Some(COLOR_MUTED),
+                })
         } else if self.info.cents_off.is_some() {
             text(tr!(adjust))
                 .size(20)
@@ -107,25 +153,101 @@ impl TunerDisplay {
                 })
         };
 
-        let tuner_display = column![note_display, freq_display, cents_indicator, status_text,]
+        let mut tuner_display = column![note_display, freq_display, cents_indicator, status_text,]
             .spacing(SPACING_NORMAL)
             .align_x(Alignment::Center);
 
+        // Heard as an overtone of the locked note, and read against it.
+        if let Some(harmonic) = self.info.harmonic.filter(|&h| h > 1) {
+            tuner_display = tuner_display.push(
+                text(format!("{} {harmonic}", tr!(tuner_harmonic)))
+                    .size(TEXT_SIZE_INFO)
+                    .style(|_: &iced::Theme| iced::widget::text::Style {
+                        color: Some(COLOR_MUTED),
+                    }),
+            );
+        }
+
         let tuner_centered = container(tuner_display)
             .width(Length::Fill)
             .height(Length::Fill)
             .center_x(Length::Fill)
             .center_y(Length::Fill);
 
-        let dialog_content = column![title_row, rule::horizontal(1), tuner_centered,]
-            .spacing(DIALOG_CONTENT_SPACING)
-            .padding(DIALOG_CONTENT_PADDING)
-            .width(Length::Fill)
-            .height(Length::Fill);
+        let dialog_content = column![
+            title_row,
+            rule::horizontal(1),
+            tuner_centered,
+            self.controls()
+        ]
+        .spacing(DIALOG_CONTENT_SPACING)
+        .padding(DIALOG_CONTENT_PADDING)
+        .width(Length::Fill)
+        .height(Length::Fill);
 
         Some(dialog_container(dialog_content.into()))
     }
 
+    /// The strobe/needle switch and the note lock.
+    fn controls(&self) -> Element<'_, TunerMessage> {
+        let strobe = checkbox(self.mode == TunerMode::Strobe)
+            .label(tr!(tuner_strobe))
+            .on_toggle(|strobe| {
+                TunerMessage::ModeChanged(if strobe {
+                    TunerMode::Strobe
+                } else {
+                    TunerMode::Needle
+                })
+            });
+
+        let lock = pick_list(TargetNote::ALL, self.lock, |note| {
+            TunerMessage::LockChanged(Some(note))
+        })
+        .placeholder(tr!(tuner_lock_note));
+
+        let mut controls = row![strobe, space::horizontal(), lock]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+        if self.lock.is_some() {
+            controls =
+                controls.push(button(tr!(tuner_unlock)).on_press(TunerMessage::LockChanged(None)));
+        }
+        controls.into()
+    }
+
+    /// Concentric bands that turn as fast as the note is off: sharp
+    /// clockwise, flat the other way, still when in tune.
+    fn strobe_display(&self) -> Element<'static, TunerMessage> {
+        let confident = self.info.is_confident();
+        let color = if self.info.in_tune {
+            COLOR_SUCCESS
+        } else if confident {
+            Color::from_rgb(0.9, 0.9, 0.9)
+        } else {
+            COLOR_INACTIVE
+        };
+
+        let cents_text = match self.info.cents_off {
+            Some(cents) => format!("{cents:+.1}\u{00a2}"),
+            None => "--\u{00a2}".to_string(),
+        };
+
+        column![
+            canvas(StrobeBands {
+                phase: self.strobe.phase,
+                color,
+            })
+            .width(Length::Fixed(STROBE_SIZE))
+            .height(Length::Fixed(STROBE_SIZE)),
+            text(cents_text)
+                .size(22)
+                .style(move |_: &iced::Theme| iced::widget::text::Style { color: Some(color) }),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_x(Alignment::Center)
+        .into()
+    }
+
     fn cents_display(&self) -> Element<'static, TunerMessage> {
         if let Some(cents) = self.info.cents_off {
             let width: usize = 50;
@@ -146,7 +268,11 @@ impl TunerDisplay {
                 format!("{cents:.0}\u{00a2}")
             };
 
-            let color = if cents.abs() < 5.0 {
+            // A shaky reading still shows where it is, but greyed out so it
+            // isn't mistaken for one to tune by.
+            let color = if !self.info.is_confident() {
+                COLOR_INACTIVE
+            } else if cents.abs() < 5.0 {
                 COLOR_SUCCESS
             } else if cents.abs() < 20.0 {
                 Color::from_rgb(1.0, 0.8, 0.2) // Yellow
@@ -203,3 +329,85 @@ impl TunerDisplay {
         }
     }
 }
+
+/// Where the strobe bands are, advanced at every poll by the time since the
+/// last one.
+#[derive(Debug, Default)]
+struct StrobeMotion {
+    /// Cents the bands are drifting for, easing toward the latest reading.
+    cents: f32,
+    /// In segments; the pattern repeats every two, one lit and one dark.
+    phase: f32,
+    last: Option<Instant>,
+}
+
+impl StrobeMotion {
+    /// Drift for the time since the last call, and ease toward `cents`, or
+    /// to a stop without a steady reading.
+    fn advance(&mut self, cents: Option<f32>, now: Instant) {
+        let dt = self
+            .last
+            .map_or(0.0, |last| now.duration_since(last).as_secs_f32());
+        self.last = Some(now);
+
+        let target = cents
+            .unwrap_or(0.0)
+            .clamp(-STROBE_MAX_CENTS, STROBE_MAX_CENTS);
+        let ease = 1.0 - (-dt / STROBE_EASE_SECONDS).exp();
+        self.cents = (target - self.cents).mul_add(ease, self.cents);
+        self.phase = (self.cents * STROBE_SEGMENTS_PER_CENT)
+            .mul_add(dt, self.phase)
+            .rem_euclid(2.0);
+    }
+}
+
+/// The strobe's rings of alternating lit and dark segments, turned by
+/// `phase` segments. Outer rings have more, smaller segments, so every ring
+/// drifts by the same number of segments.
+struct StrobeBands {
+    phase: f32,
+    color: Color,
+}
+
+impl<Message> canvas::Program<Message> for StrobeBands {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        _theme: &iced::Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let center = frame.center();
+        let band = frame.width().min(frame.height()) / 2.0 / (STROBE_RINGS as f32 + 1.0);
+
+        for ring in 0..STROBE_RINGS {
+            let segments = 8 << ring;
+            let segment = TAU / segments as f32;
+            let radius = band * (ring as f32 + 1.5);
+            let rotation = self.phase * segment;
+            for lit in (0..segments).step_by(2) {
+                let start = (lit as f32).mul_add(segment, rotation);
+                let arc = canvas::Path::new(|b| {
+                    b.arc(canvas::path::Arc {
+                        center,
+                        radius,
+                        start_angle: Radians(start),
+                        end_angle: Radians(start + segment),
+                    });
+                });
+                frame.stroke(
+                    &arc,
+                    canvas::Stroke::default()
+                        .with_width(band * 0.8)
+                        .with_color(self.color),
+                );
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -1 +1,4 @@
 pub mod dialogs;
+pub mod stall_banner;
+pub mod startup_error;
+pub mod toast;
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use iced::widget::{button, container, row, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_ui::components::widgets::common::{
+    BORDER_RADIUS_CARD, PADDING_NORMAL, SPACING_NORMAL, TEXT_SIZE_INFO,
+};
+use rustortion_ui::messages::Message;
+
+/// Outcome of restarting the stream after a stall, if one was attempted.
+#[derive(Debug, Clone)]
+pub enum RestartOutcome {
+    NotAttempted,
+    Restarted,
+    Failed(String),
+}
+
+/// The audio callback stopped running. Stays up until dismissed, or until the
+/// stream resumes by itself.
+#[derive(Debug, Clone)]
+pub struct AudioStall {
+    pub stalled_for: Duration,
+    pub restart: RestartOutcome,
+}
+
+impl AudioStall {
+    pub fn view(&self) -> Element<'_, Message> {
+        let mut message = format!("{} {} s", tr!(audio_stalled), self.stalled_for.as_secs());
+        match &self.restart {
+            RestartOutcome::NotAttempted => {}
+            RestartOutcome::Restarted => {
+                message = format!("{message} — {}", tr!(audio_stall_restarted));
+            }
+            RestartOutcome::Failed(error) => {
+                message = format!("{message} — {} {error}", tr!(audio_stall_restart_failed));
+            }
+        }
+
+        let mut banner = row![text(message), space::horizontal()]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+        if !matches!(self.restart, RestartOutcome::Restarted) {
+            banner = banner.push(
+                button(text(tr!(restart_audio)).size(TEXT_SIZE_INFO))
+                    .on_press(Message::RestartAudio)
+                    .style(iced::widget::button::primary),
+            );
+        }
+        banner = banner.push(
+            button(text(tr!(close)).size(TEXT_SIZE_INFO))
+                .on_press(Message::DismissAudioStall)
+                .style(iced::widget::button::secondary),
+        );
+
+        container(banner)
+            .width(Length::Fill)
+            .padding(PADDING_NORMAL)
+            .style(|theme: &iced::Theme| {
+                let pair = theme.extended_palette().danger.strong;
+                container::Style::default()
+                    .background(pair.color)
+                    .color(pair.text)
+                    .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+            })
+            .into()
+    }
+}
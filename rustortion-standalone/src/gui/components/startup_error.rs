@@ -0,0 +1,106 @@
+use std::fmt;
+
+use iced::widget::{button, column, container, text};
+use iced::{Alignment, Element, Length};
+
+use crate::audio::error::AudioError;
+use crate::tr;
+use rustortion_core::preset::PresetError;
+use rustortion_ui::components::widgets::common::{
+    PADDING_NORMAL, SPACING_NORMAL, TEXT_SIZE_INFO, TEXT_SIZE_SECTION_TITLE,
+};
+
+/// Something the amp can't run without failed before the main window opened.
+#[derive(Debug)]
+pub enum StartupError {
+    Audio(AudioError),
+    Presets(PresetError),
+}
+
+impl StartupError {
+    /// What to tell the user, in their language. The untranslated error
+    /// goes to the log and the window's detail line.
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::Audio(error) => audio_error_message(error),
+            Self::Presets(_) => tr!(preset_error_dir).to_string(),
+        }
+    }
+}
+
+impl fmt::Display for StartupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Audio(error) => error.fmt(f),
+            Self::Presets(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StartupError {}
+
+impl From<AudioError> for StartupError {
+    fn from(error: AudioError) -> Self {
+        Self::Audio(error)
+    }
+}
+
+impl From<PresetError> for StartupError {
+    fn from(error: PresetError) -> Self {
+        Self::Presets(error)
+    }
+}
+
+/// Translated one-liner for an audio failure, shared by the startup window
+/// and the stall banner.
+pub fn audio_error_message(error: &AudioError) -> String {
+    match error {
+        AudioError::ServerNotFound => tr!(audio_error_server_not_found).to_string(),
+        AudioError::Client(_) | AudioError::Activation(_) | AudioError::AlreadyActive => {
+            tr!(audio_error_client).to_string()
+        }
+        AudioError::Port { .. } => tr!(audio_error_ports).to_string(),
+        AudioError::Engine(_) => tr!(audio_error_engine).to_string(),
+        AudioError::Device(_) => tr!(audio_error_device).to_string(),
+        AudioError::Freewheel(_) => tr!(audio_error_freewheel).to_string(),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum StartupErrorMessage {
+    Quit,
+}
+
+/// The only window shown when startup failed: the error and a way out.
+#[derive(Debug, Clone)]
+pub struct StartupErrorWindow {
+    message: String,
+    detail: String,
+}
+
+impl StartupErrorWindow {
+    pub fn new(error: &StartupError) -> Self {
+        Self {
+            message: error.user_message(),
+            detail: error.to_string(),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, StartupErrorMessage> {
+        let content = column![
+            text(tr!(startup_error_title)).size(TEXT_SIZE_SECTION_TITLE),
+            text(&self.message),
+            text(&self.detail).size(TEXT_SIZE_INFO),
+            button(text(tr!(quit)))
+                .on_press(StartupErrorMessage::Quit)
+                .style(iced::widget::button::danger),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_x(Alignment::Center);
+
+        container(content)
+            .padding(PADDING_NORMAL)
+            .center(Length::Fill)
+            .into()
+    }
+}
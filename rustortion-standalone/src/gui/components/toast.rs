@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+use iced::widget::{button, container, row, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::tr;
+use rustortion_ui::components::widgets::common::{
+    BORDER_RADIUS_CARD, PADDING_NORMAL, SPACING_NORMAL, TEXT_SIZE_INFO,
+};
+use rustortion_ui::messages::Message;
+
+/// How long a toast stays up unless it's closed first.
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// A short confirmation above the chain that goes away by itself.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    message: String,
+    error: bool,
+    shown_at: Instant,
+}
+
+impl Toast {
+    pub fn new(message: String, error: bool) -> Self {
+        Self {
+            message,
+            error,
+            shown_at: Instant::now(),
+        }
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.shown_at) >= TOAST_DURATION
+    }
+
+    pub fn view(&self, close: Message) -> Element<'_, Message> {
+        let banner = row![
+            text(&self.message).size(TEXT_SIZE_INFO),
+            space::horizontal(),
+            button(text(tr!(close)).size(TEXT_SIZE_INFO))
+                .on_press(close)
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let error = self.error;
+        container(banner)
+            .width(Length::Fill)
+            .padding(PADDING_NORMAL)
+            .style(move |theme: &iced::Theme| {
+                let palette = theme.extended_palette();
+                let pair = if error {
+                    palette.danger.weak
+                } else {
+                    palette.success.weak
+                };
+                container::Style::default()
+                    .background(pair.color)
+                    .color(pair.text)
+                    .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+            })
+            .into()
+    }
+}
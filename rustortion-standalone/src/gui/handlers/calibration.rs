@@ -0,0 +1,97 @@
+use std::time::Instant;
+
+use iced::{Element, Task};
+use log::{debug, error};
+
+use crate::audio::manager::Manager;
+use crate::gui::components::dialogs::calibration::CalibrationDialog;
+use crate::settings::Settings;
+use rustortion_core::audio::calibration::{CalibrationCapture, CalibrationState};
+use rustortion_ui::messages::{CalibrationMessage, Message};
+
+pub struct CalibrationHandler {
+    dialog: CalibrationDialog,
+    capture: CalibrationCapture,
+    /// Input port being calibrated, fixed when the dialog opens.
+    port: String,
+}
+
+impl Default for CalibrationHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CalibrationHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: CalibrationDialog::new(),
+            capture: CalibrationCapture::new(),
+            port: String::new(),
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        message: CalibrationMessage,
+        settings: &mut Settings,
+        audio_manager: &mut Manager,
+    ) -> Task<Message> {
+        match message {
+            CalibrationMessage::Open => {
+                self.port = audio_manager.input_port().to_string();
+                self.capture.reset();
+                let current = settings.input_calibrations.get(&self.port).copied();
+                self.dialog.show(self.port.clone(), current);
+            }
+            CalibrationMessage::Close => {
+                self.capture.reset();
+                self.dialog.hide();
+            }
+            CalibrationMessage::Start => {
+                self.capture.start(Instant::now());
+                self.dialog.update(self.capture.state(), 0.0);
+            }
+            CalibrationMessage::Save => {
+                if let CalibrationState::Done { trim_db, .. } = self.capture.state() {
+                    settings
+                        .input_calibrations
+                        .insert(self.port.clone(), trim_db);
+                    if let Err(e) = settings.save() {
+                        error!("Failed to save input calibration: {e}");
+                    }
+                    audio_manager.set_input_calibration(self.port.clone(), trim_db);
+                    debug!("Calibrated '{}' with {trim_db:+.1} dB trim", self.port);
+
+                    self.capture.reset();
+                    self.dialog.set_current_trim(trim_db);
+                    self.dialog.hide();
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Feed the capture the latest input meter reading. Called on the peak
+    /// meter tick while a capture is running.
+    pub fn tick(&mut self, audio_manager: &Manager) {
+        let now = Instant::now();
+        self.capture
+            .observe(now, audio_manager.input_meter().get_info().peak_db);
+        self.dialog
+            .update(self.capture.state(), self.capture.progress(now));
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::Calibration))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    pub const fn is_capturing(&self) -> bool {
+        self.capture.is_capturing()
+    }
+}
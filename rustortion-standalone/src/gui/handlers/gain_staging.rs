@@ -0,0 +1,148 @@
+use std::time::Instant;
+
+use iced::{Element, Task};
+use log::debug;
+
+use crate::audio::manager::Manager;
+use crate::gui::components::dialogs::gain_staging::{GainStagingDialog, StageRow};
+use crate::tr;
+use rustortion_core::audio::gain_staging::{
+    Fix, GainStagingCapture, Suggestion, apply_suggestions, suggest,
+};
+use rustortion_core::preset::StageConfig;
+use rustortion_ui::messages::{GainStagingMessage, Message};
+use rustortion_ui::stages::stage_type_label;
+
+pub struct GainStagingHandler {
+    dialog: GainStagingDialog,
+    capture: GainStagingCapture,
+    suggestions: Vec<Suggestion>,
+}
+
+impl Default for GainStagingHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GainStagingHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: GainStagingDialog::new(),
+            capture: GainStagingCapture::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        message: GainStagingMessage,
+        stages: &[StageConfig],
+        audio_manager: &Manager,
+    ) -> Task<Message> {
+        match message {
+            GainStagingMessage::Open => {
+                self.capture.reset();
+                self.suggestions.clear();
+                self.dialog.show();
+            }
+            GainStagingMessage::Close => {
+                audio_manager.stage_peaks().set_enabled(false);
+                self.capture.reset();
+                self.dialog.hide();
+            }
+            GainStagingMessage::Start => {
+                audio_manager.stage_peaks().set_enabled(true);
+                self.capture.start(Instant::now(), stages.len());
+                self.suggestions.clear();
+                self.dialog.set_progress(0.0);
+            }
+            GainStagingMessage::Apply => {
+                let applied = apply_suggestions(stages, &self.suggestions);
+                debug!(
+                    "Applied {} gain staging suggestion(s)",
+                    self.suggestions.len()
+                );
+                self.capture.reset();
+                self.suggestions.clear();
+                self.dialog.hide();
+                return Task::done(Message::SetStages(applied));
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Feed the capture the engine's per-stage peaks. Called on the peak
+    /// meter tick while a capture is running.
+    pub fn tick(&mut self, stages: &[StageConfig], audio_manager: &Manager) {
+        let now = Instant::now();
+        let peaks = audio_manager.stage_peaks();
+        self.capture.observe(now, &peaks.take(stages.len()));
+
+        if !self.capture.is_done() {
+            self.dialog.set_progress(self.capture.progress(now));
+            return;
+        }
+
+        peaks.set_enabled(false);
+        let peaks_db = self.capture.peaks_db();
+        self.suggestions = suggest(stages, &peaks_db);
+
+        let rows = stages
+            .iter()
+            .zip(&peaks_db)
+            .enumerate()
+            .map(|(idx, (stage, &peak_db))| StageRow {
+                label: stage_label(stages, idx),
+                peak_db: (!stage.bypassed()).then_some(peak_db),
+            })
+            .collect();
+        let lines = self
+            .suggestions
+            .iter()
+            .map(|s| suggestion_line(stages, s))
+            .collect();
+        let can_apply = self.suggestions.iter().any(|s| s.fix != Fix::Manual);
+        self.dialog.set_results(rows, lines, can_apply);
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::GainStaging))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    pub const fn is_capturing(&self) -> bool {
+        self.capture.is_capturing()
+    }
+}
+
+/// "Preamp 2": the stage's type and its 1-based position in the chain.
+fn stage_label(stages: &[StageConfig], idx: usize) -> String {
+    stages.get(idx).map_or_else(String::new, |stage| {
+        format!("{} {}", stage_type_label(&stage.stage_type()), idx + 1)
+    })
+}
+
+fn suggestion_line(stages: &[StageConfig], s: &Suggestion) -> String {
+    let fix = match s.fix {
+        Fix::SetLevel { stage_idx, gain } => format!(
+            "{} {} → {gain:.2}",
+            tr!(gain_staging_fix_set_level),
+            stage_idx + 1
+        ),
+        Fix::InsertLevel { gain, .. } => {
+            format!("{} → {gain:.2}", tr!(gain_staging_fix_insert_level))
+        }
+        Fix::Manual => tr!(gain_staging_fix_manual).to_string(),
+    };
+    format!(
+        "{} {} {:+.1} dBFS — {fix}",
+        stage_label(stages, s.stage_idx),
+        tr!(gain_staging_peaks_at),
+        s.peak_db
+    )
+}
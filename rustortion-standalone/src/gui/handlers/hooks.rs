@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use crate::hooks::{HookDispatcher, HookEvent};
+use crate::settings::HookSettings;
+
+/// Hands app events to the user's hook commands. Preset changes are noticed
+/// by comparing the selection after each update, whatever caused them.
+pub struct HooksHandler {
+    dispatcher: HookDispatcher,
+    /// Preset selected when last looked; moving off it is a change.
+    preset: Option<String>,
+    /// The take being recorded, for the stop hook.
+    take: Option<PathBuf>,
+}
+
+impl HooksHandler {
+    /// The preset selected at startup isn't a change.
+    pub fn new(settings: &HookSettings, selected: Option<&str>) -> Self {
+        Self {
+            dispatcher: HookDispatcher::new(settings),
+            preset: selected.map(str::to_owned),
+            take: None,
+        }
+    }
+
+    pub fn configure(&mut self, settings: &HookSettings) {
+        self.dispatcher.configure(settings);
+    }
+
+    pub fn recording_started(&mut self, path: PathBuf) {
+        self.dispatcher
+            .dispatch(&HookEvent::RecordingStarted { path: path.clone() });
+        self.take = Some(path);
+    }
+
+    pub fn recording_stopped(&mut self) {
+        if let Some(path) = self.take.take() {
+            self.dispatcher
+                .dispatch(&HookEvent::RecordingStopped { path });
+        }
+    }
+
+    pub fn sync(&mut self, selected: Option<&str>) {
+        if let Some(name) = selected
+            && self.preset.as_deref() != Some(name)
+        {
+            self.dispatcher.dispatch(&HookEvent::PresetChanged {
+                name: name.to_owned(),
+            });
+            self.preset = Some(name.to_owned());
+        }
+    }
+
+    pub fn audio_disconnected(&self, reason: String) {
+        self.dispatcher
+            .dispatch(&HookEvent::AudioDisconnected { reason });
+    }
+}
@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+use std::thread;
+use std::time::Instant;
+
+use crossbeam::channel::{Receiver, TryRecvError, bounded};
+use iced::Element;
+use log::error;
+
+use crate::gui::components::toast::Toast;
+use crate::import::{ImportReport, ImportRequest, import_dropped};
+use crate::tr;
+use rustortion_ui::messages::{ImportMessage, Message};
+
+/// Collects files dropped onto the window, imports them on a background
+/// thread, and confirms what happened with a toast.
+pub struct ImportHandler {
+    /// Dropped since the last import started; a multi-file drop arrives as
+    /// one event per file, so they're gathered until the next poll.
+    pending: Vec<PathBuf>,
+    job: Option<Receiver<ImportReport>>,
+    toast: Option<Toast>,
+}
+
+impl Default for ImportHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImportHandler {
+    pub const fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            job: None,
+            toast: None,
+        }
+    }
+
+    pub fn file_dropped(&mut self, path: PathBuf) {
+        self.pending.push(path);
+    }
+
+    /// The files to import now, if any were dropped and no import is running.
+    pub fn take_pending(&mut self) -> Option<Vec<PathBuf>> {
+        (self.job.is_none() && !self.pending.is_empty()).then(|| std::mem::take(&mut self.pending))
+    }
+
+    pub fn start(&mut self, request: ImportRequest) {
+        let (result_tx, result_rx) = bounded(1);
+        let spawned = thread::Builder::new()
+            .name("file-import".into())
+            .spawn(move || {
+                let _ = result_tx.send(import_dropped(&request));
+            });
+
+        match spawned {
+            Ok(_) => self.job = Some(result_rx),
+            Err(e) => {
+                error!("Failed to start importing dropped files: {e}");
+                self.toast = Some(Toast::new(format!("{} {e}", tr!(import_failed)), true));
+            }
+        }
+    }
+
+    /// The report of a finished import, once. Also expires the toast.
+    pub fn poll(&mut self) -> Option<ImportReport> {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|t| t.is_expired(Instant::now()))
+        {
+            self.toast = None;
+        }
+
+        let job = self.job.as_ref()?;
+        let report = match job.try_recv() {
+            Err(TryRecvError::Empty) => return None,
+            Ok(report) => report,
+            Err(TryRecvError::Disconnected) => ImportReport {
+                failures: vec![(String::new(), "import thread exited".to_string())],
+                ..ImportReport::default()
+            },
+        };
+        self.job = None;
+
+        if !report.is_empty() {
+            self.toast = Some(Toast::new(summary(&report), !report.failures.is_empty()));
+        }
+        Some(report)
+    }
+
+    pub fn dismiss_toast(&mut self) {
+        self.toast = None;
+    }
+
+    /// Whether there's anything to poll for: files waiting, an import
+    /// running, or a toast to expire.
+    pub const fn is_active(&self) -> bool {
+        !self.pending.is_empty() || self.job.is_some() || self.toast.is_some()
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.toast
+            .as_ref()
+            .map(|toast| toast.view(Message::Import(ImportMessage::DismissToast)))
+    }
+}
+
+/// "Imported: a.wav, Lead — Not imported: notes.txt (reason)".
+fn summary(report: &ImportReport) -> String {
+    let mut parts = Vec::new();
+    let imported: Vec<&str> = report
+        .irs
+        .iter()
+        .chain(&report.presets)
+        .map(String::as_str)
+        .collect();
+    if !imported.is_empty() {
+        parts.push(format!("{} {}", tr!(import_done), imported.join(", ")));
+    }
+    if !report.failures.is_empty() {
+        let failed: Vec<String> = report
+            .failures
+            .iter()
+            .map(|(file, reason)| format!("{file} ({reason})"))
+            .collect();
+        parts.push(format!("{} {}", tr!(import_failed), failed.join(", ")));
+    }
+    parts.join(" — ")
+}
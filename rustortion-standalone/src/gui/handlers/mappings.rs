@@ -0,0 +1,48 @@
+use iced::{Element, Task};
+
+use crate::gui::components::dialogs::mappings::MappingsDialog;
+use rustortion_ui::mappings::{Binding, BindingSource};
+use rustortion_ui::messages::{HotkeyMessage, MappingsMessage, Message, MidiMessage};
+
+/// The overview of hotkey and MIDI mappings. It holds none of its own:
+/// removals go to the hotkey or MIDI handler, which save them as usual.
+pub struct MappingsHandler {
+    dialog: MappingsDialog,
+}
+
+impl Default for MappingsHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MappingsHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: MappingsDialog::new(),
+        }
+    }
+
+    pub fn handle(&mut self, message: MappingsMessage) -> Task<Message> {
+        match message {
+            MappingsMessage::Open => self.dialog.show(),
+            MappingsMessage::Close => self.dialog.hide(),
+            MappingsMessage::Filter(filter) => self.dialog.set_filter(filter),
+            MappingsMessage::Remove(BindingSource::Hotkey, index) => {
+                return Task::done(Message::Hotkey(HotkeyMessage::RemoveMapping(index)));
+            }
+            MappingsMessage::Remove(BindingSource::Midi, index) => {
+                return Task::done(Message::Midi(MidiMessage::RemoveMapping(index)));
+            }
+        }
+        Task::none()
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    pub fn view(&self, bindings: Vec<Binding>) -> Option<Element<'_, Message>> {
+        self.dialog.view(bindings).map(|e| e.map(Message::Mappings))
+    }
+}
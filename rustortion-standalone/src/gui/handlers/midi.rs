@@ -1,20 +1,130 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use iced::futures::Stream;
+use iced::futures::channel::mpsc;
 use iced::{Element, Task};
-use log::debug;
+use log::{debug, error, info};
 
 use crate::gui::components::dialogs::midi::MidiDialog;
-use crate::midi::{MidiEvent, MidiHandle, MidiMapping};
-use rustortion_ui::messages::{Message, MidiMessage, PresetMessage};
+use crate::midi::{
+    EdgeTracker, MidiAction, MidiEvent, MidiHandle, MidiMapping, MidiSwitch, MidiTrigger,
+    MidiWakeups, SwitchLatch,
+};
+use rustortion_core::audio::engine::EngineHandle;
+use rustortion_ui::messages::{Message, MidiMessage, SetlistMessage, TunerMessage};
+
+/// How long to wait for the engine to play a switched chain before giving up
+/// on timing it, e.g. because the new preset's chain is the same.
+const SWITCH_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Which preset a MIDI switch asks for.
+#[derive(Debug, Clone)]
+pub enum SwitchTarget {
+    Preset(String),
+    Previous,
+}
+
+/// A preset switch triggered over MIDI, for the app to apply on the spot.
+#[derive(Debug, Clone)]
+pub struct PresetSwitch {
+    pub target: SwitchTarget,
+    /// When the MIDI message was parsed.
+    pub received: Instant,
+}
+
+/// Where the settings MIDI mappings can switch stand, as the app has them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SwitchStates {
+    pub tuner: bool,
+    pub standby: bool,
+    pub ir_bypassed: bool,
+}
+
+impl SwitchStates {
+    const fn get(self, switch: MidiSwitch) -> bool {
+        match switch {
+            MidiSwitch::Tuner => self.tuner,
+            MidiSwitch::Standby => self.standby,
+            MidiSwitch::IrBypass => self.ir_bypassed,
+        }
+    }
+}
+
+/// A switch pushed to the engine but not yet seen playing there.
+struct PendingSwitch {
+    preset: String,
+    received: Instant,
+    handled: Instant,
+}
 
 pub struct MidiHandler {
     dialog: MidiDialog,
     handle: MidiHandle,
+    switch: Option<PresetSwitch>,
+    pending: Option<PendingSwitch>,
+    edges: EdgeTracker,
+    latch: SwitchLatch,
 }
 
 impl MidiHandler {
-    pub const fn new(handle: MidiHandle) -> Self {
+    pub fn new(handle: MidiHandle) -> Self {
         Self {
             dialog: MidiDialog::new(),
             handle,
+            switch: None,
+            pending: None,
+            edges: EdgeTracker::default(),
+            latch: SwitchLatch::default(),
+        }
+    }
+
+    /// Wakeups for the app to poll on as soon as MIDI input arrives; see
+    /// [`wakeup_stream`].
+    pub fn wakeups(&self) -> MidiWakeups {
+        self.handle.wakeups()
+    }
+
+    /// The preset switch the last poll picked up, if any.
+    pub const fn take_switch(&mut self) -> Option<PresetSwitch> {
+        self.switch.take()
+    }
+
+    /// Time a switch to `preset` from its MIDI message to the engine block
+    /// that first plays its chain. `handled` is when the GUI started on it.
+    pub fn time_switch(&mut self, preset: String, received: Instant, handled: Instant) {
+        self.pending = Some(PendingSwitch {
+            preset,
+            received,
+            handled,
+        });
+    }
+
+    /// Log how long the pending switch took once the engine has applied it.
+    pub fn report_switch_latency(&mut self, engine: &EngineHandle) {
+        let Some(pending) = &self.pending else {
+            return;
+        };
+
+        match engine.last_chain_change() {
+            Some(applied) if applied >= pending.handled => {
+                info!(
+                    "MIDI switch to '{}' took {:.1} ms ({:.1} ms to the GUI, {:.1} ms to the engine)",
+                    pending.preset,
+                    ms(applied - pending.received),
+                    ms(pending.handled - pending.received),
+                    ms(applied - pending.handled),
+                );
+                self.pending = None;
+            }
+            _ if pending.handled.elapsed() > SWITCH_TIMEOUT => {
+                debug!(
+                    "MIDI switch to '{}' left the chain as it was; not timed",
+                    pending.preset
+                );
+                self.pending = None;
+            }
+            _ => {}
         }
     }
 
@@ -22,7 +132,8 @@ impl MidiHandler {
         self.dialog.show(presets, mappings);
     }
 
-    pub fn handle(&mut self, message: MidiMessage) -> Task<Message> {
+    /// `switches` is only read for `Update`, where mappings may switch them.
+    pub fn handle(&mut self, message: MidiMessage, switches: SwitchStates) -> Task<Message> {
         match message {
             MidiMessage::Open => {}
             MidiMessage::Close => {
@@ -48,6 +159,36 @@ impl MidiHandler {
             MidiMessage::PresetForMappingSelected(preset) => {
                 self.dialog.set_preset_for_mapping(preset);
             }
+            MidiMessage::HoldTunerToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::HoldTuner, on);
+            }
+            MidiMessage::PreviousPresetToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::PreviousPreset, on);
+            }
+            MidiMessage::NextSongToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::NextSong, on);
+            }
+            MidiMessage::PreviousSongToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::PreviousSong, on);
+            }
+            MidiMessage::ToggleStandbyToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::ToggleStandby, on);
+            }
+            MidiMessage::ToggleIrBypassToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(MidiAction::ToggleIrBypass, on);
+            }
+            MidiMessage::MappingModeSelected(mode) => {
+                self.dialog.set_mode_for_mapping(mode);
+            }
+            MidiMessage::PresetSearch(msg) => {
+                self.dialog.search_preset(msg);
+            }
             MidiMessage::ConfirmMapping => {
                 if self.dialog.complete_mapping().is_some() {
                     let mappings = self.dialog.get_mappings();
@@ -64,14 +205,14 @@ impl MidiHandler {
                 return Task::none();
             }
             MidiMessage::Update => {
-                return self.poll_events();
+                return self.poll_events(switches);
             }
         }
 
         Task::none()
     }
 
-    fn poll_events(&mut self) -> Task<Message> {
+    fn poll_events(&mut self, switches: SwitchStates) -> Task<Message> {
         while let Some(event) = self.handle.try_recv() {
             match event {
                 MidiEvent::Input(input) => {
@@ -79,19 +220,66 @@ impl MidiHandler {
                         self.dialog.on_midi_input(&input);
                     }
 
+                    // Followed while learning too, or a release during it
+                    // would leave the input looking held.
+                    let edge = self.edges.edge(&input);
                     if self.dialog.is_learning() {
                         continue;
                     }
+                    let Some(edge) = edge else {
+                        continue;
+                    };
 
-                    if let Some(preset_name) = self.handle.check_mapping(&input) {
-                        debug!("MIDI triggered preset: {preset_name}");
-                        return Task::done(Message::Preset(PresetMessage::Select(preset_name)));
+                    match self.handle.check_mapping(&input, edge) {
+                        Some(MidiTrigger::LoadPreset(preset_name)) => {
+                            debug!("MIDI triggered preset: {preset_name}");
+                            self.switch = Some(PresetSwitch {
+                                target: SwitchTarget::Preset(preset_name),
+                                received: input.received,
+                            });
+                            return Task::none();
+                        }
+                        Some(MidiTrigger::HoldTuner { pressed }) => {
+                            let message = if pressed {
+                                TunerMessage::Hold
+                            } else {
+                                TunerMessage::Release
+                            };
+                            return Task::done(Message::Tuner(message));
+                        }
+                        Some(MidiTrigger::PreviousPreset) => {
+                            debug!("MIDI triggered previous preset");
+                            self.switch = Some(PresetSwitch {
+                                target: SwitchTarget::Previous,
+                                received: input.received,
+                            });
+                            return Task::none();
+                        }
+                        Some(MidiTrigger::NextSong) => {
+                            debug!("MIDI triggered next song");
+                            return Task::done(Message::Setlist(SetlistMessage::Next));
+                        }
+                        Some(MidiTrigger::PreviousSong) => {
+                            debug!("MIDI triggered previous song");
+                            return Task::done(Message::Setlist(SetlistMessage::Previous));
+                        }
+                        Some(trigger) => {
+                            if let Some(message) = self.resolve_switch(&trigger, switches) {
+                                return Task::done(message);
+                            }
+                        }
+                        None => {}
                     }
                 }
                 MidiEvent::Disconnected => {
-                    self.dialog.set_selected_controller(None);
+                    // Still selected: the manager reconnects when it's back.
+                    self.dialog.set_connected(false);
                     debug!("MIDI device disconnected");
                 }
+                MidiEvent::Reconnected(port) => {
+                    debug!("MIDI device reconnected as {port}");
+                    self.dialog.set_selected_controller(Some(port));
+                }
                 MidiEvent::Error(e) => {
                     log::error!("MIDI error: {e}");
                 }
@@ -100,14 +288,34 @@ impl MidiHandler {
         Task::none()
     }
 
+    /// The message a switch's trigger comes to, if it changes anything.
+    fn resolve_switch(&mut self, trigger: &MidiTrigger, switches: SwitchStates) -> Option<Message> {
+        let switch = trigger.switch()?;
+        let current = switches.get(switch);
+        let on = self
+            .latch
+            .resolve(trigger, current)
+            .filter(|on| *on != current)?;
+        debug!("MIDI switched {switch:?} {}", if on { "on" } else { "off" });
+        Some(switch_message(switch, on))
+    }
+
     pub fn view(&self) -> Option<Element<'_, Message>> {
         self.dialog.view().map(|e| e.map(Message::Midi))
     }
 
+    pub const fn is_search_open(&self) -> bool {
+        self.dialog.is_search_open()
+    }
+
     pub const fn is_visible(&self) -> bool {
         self.dialog.is_visible()
     }
 
+    pub const fn is_learning(&self) -> bool {
+        self.dialog.is_learning()
+    }
+
     pub fn get_selected_controller(&self) -> Option<String> {
         self.dialog.get_selected_controller()
     }
@@ -131,3 +339,43 @@ impl MidiHandler {
         self.dialog.set_selected_controller(controller);
     }
 }
+
+/// The message that turns `switch` over to `on`, from the other state.
+const fn switch_message(switch: MidiSwitch, on: bool) -> Message {
+    match switch {
+        MidiSwitch::Tuner => Message::Tuner(TunerMessage::Toggle),
+        MidiSwitch::Standby => Message::ToggleStandby,
+        MidiSwitch::IrBypass => Message::IrBypassed(on),
+    }
+}
+
+/// An `Update` for every MIDI wakeup, so a preset switch is handled as its
+/// message arrives rather than on the next poll tick.
+pub fn wakeup_stream(wakeups: &MidiWakeups) -> impl Stream<Item = Message> + use<> {
+    let wakeups = wakeups.clone();
+    iced::stream::channel(1, |mut output: mpsc::Sender<Message>| async move {
+        // Waiting blocks, so it gets a thread of its own. It ends with the
+        // first wakeup after the subscription is dropped.
+        let waiter = thread::Builder::new()
+            .name("midi-wakeups".to_string())
+            .spawn(move || {
+                while wakeups.wait() {
+                    // A full channel already has an update queued.
+                    if output
+                        .try_send(Message::Midi(MidiMessage::Update))
+                        .is_err_and(|e| e.is_disconnected())
+                    {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = waiter {
+            error!("Failed to spawn the MIDI wakeup thread: {e}");
+        }
+        std::future::pending::<()>().await;
+    })
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
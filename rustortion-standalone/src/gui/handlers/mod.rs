@@ -1,3 +1,16 @@
+pub mod calibration;
+pub mod gain_staging;
+pub mod hooks;
+pub mod import;
+pub mod mappings;
 pub mod midi;
+pub mod remote;
+pub mod render;
+pub mod setlist;
 pub mod settings;
+pub mod setup;
+pub mod takes;
+pub mod tone_match;
 pub mod tuner;
+pub mod watchdog;
+pub mod window;
@@ -0,0 +1,131 @@
+use iced::Task;
+use log::{error, info, warn};
+
+use crate::remote::{RemoteCommand, RemoteServer, RemoteState, next_preset};
+use crate::settings::RemoteSettings;
+use rustortion_ui::messages::{Message, PresetMessage};
+
+/// Runs the OSC remote-control server while it's enabled, turns what remotes
+/// send into messages, and tells them about preset and recording changes.
+pub struct RemoteHandler {
+    server: Option<RemoteServer>,
+    /// Last state sent to remotes; `None` until sent.
+    sent_preset: Option<String>,
+    sent_recording: Option<bool>,
+}
+
+impl RemoteHandler {
+    pub fn new(settings: &RemoteSettings) -> Self {
+        let mut handler = Self {
+            server: None,
+            sent_preset: None,
+            sent_recording: None,
+        };
+        handler.configure(settings);
+        handler
+    }
+
+    /// Start, stop or move the server to match `settings`.
+    pub fn configure(&mut self, settings: &RemoteSettings) {
+        if !settings.enabled {
+            if self.server.take().is_some() {
+                info!("OSC remote control stopped");
+            }
+            return;
+        }
+        if self
+            .server
+            .as_ref()
+            .is_some_and(|server| server.port() == settings.port)
+        {
+            return;
+        }
+
+        // Stopped first, so moving back to a port just left can bind it.
+        self.server = None;
+        match RemoteServer::start(settings.port) {
+            Ok(server) => {
+                self.server = Some(server);
+                self.sent_preset = None;
+                self.sent_recording = None;
+            }
+            Err(e) => error!(
+                "Failed to start OSC remote control on port {}: {e}",
+                settings.port
+            ),
+        }
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.server.is_some()
+    }
+
+    /// Messages for the commands received since the last tick, run in the
+    /// order they arrived.
+    pub fn tick(
+        &self,
+        presets: &[String],
+        selected: Option<&str>,
+        is_recording: bool,
+    ) -> Task<Message> {
+        let Some(server) = &self.server else {
+            return Task::none();
+        };
+        server
+            .commands()
+            .into_iter()
+            .filter_map(|command| to_message(command, presets, selected, is_recording))
+            .fold(Task::none(), |task, message| {
+                task.chain(Task::done(message))
+            })
+    }
+
+    /// Send remotes whatever changed since they were last told, and
+    /// everything to one heard from for the first time.
+    pub fn sync(&mut self, selected: Option<&str>, is_recording: bool) {
+        let Some(server) = &self.server else {
+            return;
+        };
+        if server.take_joined() {
+            self.sent_preset = None;
+            self.sent_recording = None;
+        }
+
+        if let Some(name) = selected
+            && self.sent_preset.as_deref() != Some(name)
+        {
+            server.broadcast(&RemoteState::Preset(name.to_owned()));
+            self.sent_preset = Some(name.to_owned());
+        }
+        if self.sent_recording != Some(is_recording) {
+            server.broadcast(&RemoteState::Recording(is_recording));
+            self.sent_recording = Some(is_recording);
+        }
+    }
+}
+
+/// The message carrying out `command`, if there's anything to do. Preset
+/// names are checked against the presets on disk.
+fn to_message(
+    command: RemoteCommand,
+    presets: &[String],
+    selected: Option<&str>,
+    is_recording: bool,
+) -> Option<Message> {
+    match command {
+        RemoteCommand::SelectPreset(name) => {
+            if presets.contains(&name) {
+                Some(Message::Preset(PresetMessage::Select(name)))
+            } else {
+                warn!("Remote asked for unknown preset '{name}'");
+                None
+            }
+        }
+        RemoteCommand::NextPreset => next_preset(presets, selected)
+            .map(|name| Message::Preset(PresetMessage::Select(name.to_owned()))),
+        RemoteCommand::SetIrBypass(bypassed) => Some(Message::IrBypassed(bypassed)),
+        RemoteCommand::SetRecording(record) if record == is_recording => None,
+        RemoteCommand::SetRecording(true) => Some(Message::StartRecording),
+        RemoteCommand::SetRecording(false) => Some(Message::StopRecording),
+    }
+}
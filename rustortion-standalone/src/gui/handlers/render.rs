@@ -0,0 +1,408 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use crossbeam::channel::{Receiver, TryRecvError, bounded};
+use iced::{Element, Task};
+use log::{error, info};
+
+use crate::audio::freewheel::{BOUNCE_TAIL_SECONDS, Bounce, BounceHost, InputFeed};
+use crate::audio::manager::Manager;
+use crate::audio::processor::Processor;
+use crate::gui::components::dialogs::render::{RenderDialog, RenderStatus};
+use crate::import::unique_path;
+use rustortion_core::audio::automation::Automation;
+use rustortion_core::audio::ir_capture::export_ir;
+use rustortion_core::audio::offline::{read_mono_wav_at, render_automation, render_file};
+use rustortion_core::audio::recording_info::RecordingMetadata;
+use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::metronome::MetronomeConfig;
+use rustortion_core::preset::Preset;
+use rustortion_ui::messages::{Message, RenderMessage};
+
+/// Everything a render job needs, captured from the GUI when it starts.
+pub struct RenderRequest {
+    pub preset: Preset,
+    pub sample_rate: usize,
+    pub oversampling_factor: u32,
+    pub ir_dir: String,
+    pub ir_max_seconds: f32,
+}
+
+/// A render running on a background thread.
+struct RenderJob {
+    /// `f32` fraction complete, stored as bits.
+    progress: Arc<AtomicU32>,
+    /// The captured IR's name for a capture, `None` for a render.
+    result_rx: Receiver<Result<Option<String>, String>>,
+}
+
+/// The app's side of a bounce: the audio manager, with the take started the
+/// way a manual one would be, minus the recording hooks.
+pub struct ManagerBounceHost<'a> {
+    pub manager: &'a mut Manager,
+    pub recording_dir: &'a str,
+    /// Without a metronome: it's held off for the bounce, so there's no
+    /// click track to record.
+    pub metadata: RecordingMetadata,
+    /// What the metronome and tuner go back to afterwards.
+    pub metronome: MetronomeConfig,
+    pub tuner_enabled: bool,
+}
+
+impl BounceHost for ManagerBounceHost<'_> {
+    fn input_feed(&self) -> Option<Arc<InputFeed>> {
+        self.manager.input_feed()
+    }
+
+    fn set_freewheel(&self, enabled: bool) -> Result<(), String> {
+        self.manager
+            .set_freewheel(enabled)
+            .map_err(|e| e.to_string())
+    }
+
+    fn start_take(&mut self) -> Result<PathBuf, String> {
+        // Sized like a manual take's pool; see `Processor::MAX_BUFFER_FRAMES`.
+        let max_block_samples = self.manager.buffer_size().max(Processor::MAX_BUFFER_FRAMES);
+        self.manager
+            .engine()
+            .start_recording(
+                self.manager.sample_rate(),
+                self.recording_dir,
+                max_block_samples,
+                None,
+                Some(self.metadata.clone()),
+                false,
+            )
+            .map_err(|e| format!("{e:#}"))
+    }
+
+    fn stop_take(&mut self) {
+        self.manager.engine().stop_recording();
+    }
+
+    fn take_overruns(&self) -> u64 {
+        self.manager.engine().recording_overruns()
+    }
+
+    fn hold_monitoring(&mut self, held: bool) {
+        if held {
+            self.manager
+                .hold_monitoring(self.metronome, self.tuner_enabled);
+        } else {
+            self.manager.release_monitoring();
+        }
+    }
+}
+
+pub struct RenderHandler {
+    dialog: RenderDialog,
+    job: Option<RenderJob>,
+    /// A file being bounced through the live chain.
+    bounce: Option<Bounce>,
+    /// A finished capture the IR list hasn't been rescanned for yet.
+    captured_ir: Option<String>,
+}
+
+impl Default for RenderHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: RenderDialog::new(),
+            job: None,
+            bounce: None,
+            captured_ir: None,
+        }
+    }
+
+    /// `request` carries the current GUI state and is only needed for `Start`
+    /// and `CaptureIr`.
+    pub fn handle(
+        &mut self,
+        message: RenderMessage,
+        request: Option<RenderRequest>,
+    ) -> Task<Message> {
+        match message {
+            RenderMessage::Open => self.dialog.show(),
+            RenderMessage::Close => self.dialog.hide(),
+            RenderMessage::InputPathChanged(path) => self.dialog.set_input_path(path),
+            RenderMessage::OutputPathChanged(path) => self.dialog.set_output_path(path),
+            RenderMessage::AutomationPathChanged(path) => self.dialog.set_automation_path(path),
+            RenderMessage::Start => {
+                if let Some(request) = request
+                    && !self.is_running()
+                {
+                    self.start(request);
+                }
+            }
+            RenderMessage::CaptureLengthChanged(len) => self.dialog.set_capture_len(len),
+            RenderMessage::CaptureIr => {
+                if let Some(request) = request
+                    && !self.is_running()
+                {
+                    self.start_capture(request);
+                }
+            }
+            RenderMessage::BounceMixToggled(mix) => self.dialog.set_bounce_mix(mix),
+            // These need the audio manager; the app calls `start_bounce` and
+            // `cancel_bounce` for them.
+            RenderMessage::Bounce | RenderMessage::CancelBounce => {}
+            RenderMessage::Update => self.poll(),
+        }
+
+        Task::none()
+    }
+
+    fn start(&mut self, request: RenderRequest) {
+        let input = self.dialog.input_path().trim().to_string();
+        let output = self.dialog.output_path().trim().to_string();
+        let automation = Some(self.dialog.automation_path().trim())
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let (result_tx, result_rx) = bounded(1);
+
+        let thread_progress = Arc::clone(&progress);
+        let report = move |p: f32| thread_progress.store(p.to_bits(), Ordering::Relaxed);
+        let spawned = thread::Builder::new()
+            .name("offline-render".into())
+            .spawn(move || {
+                let result = match automation {
+                    Some(path) => Automation::load(&path).and_then(|automation| {
+                        render_automation(
+                            &automation,
+                            Path::new(&input),
+                            Path::new(&output),
+                            request.oversampling_factor,
+                            open_ir_loader(&request, automation.sample_rate).as_ref(),
+                            report,
+                        )
+                    }),
+                    None => render_file(
+                        &request.preset,
+                        Path::new(&input),
+                        Path::new(&output),
+                        request.sample_rate,
+                        request.oversampling_factor,
+                        open_ir_loader(&request, request.sample_rate).as_ref(),
+                        report,
+                    ),
+                }
+                .map(|()| None)
+                .map_err(|e| format!("{e:#}"));
+
+                let _ = result_tx.send(result);
+            });
+
+        self.track(spawned, progress, result_rx);
+    }
+
+    /// Export the chain's linear response into the IR directory, under a
+    /// name no other IR has.
+    fn start_capture(&mut self, request: RenderRequest) {
+        let length = self.dialog.capture_len();
+        let stem = if request.preset.name.is_empty() {
+            "Chain capture".to_string()
+        } else {
+            format!("{} capture", request.preset.name)
+        };
+        let ir_dir = PathBuf::from(&request.ir_dir);
+        let output = unique_path(&ir_dir, &stem, "wav");
+        let name = output
+            .strip_prefix(&ir_dir)
+            .unwrap_or(&output)
+            .to_string_lossy()
+            .into_owned();
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let (result_tx, result_rx) = bounded(1);
+
+        let spawned = thread::Builder::new()
+            .name("ir-capture".into())
+            .spawn(move || {
+                let result = export_ir(
+                    &request.preset,
+                    request.sample_rate,
+                    request.oversampling_factor,
+                    open_ir_loader(&request, request.sample_rate).as_ref(),
+                    length,
+                    &output,
+                )
+                .map(|()| Some(name))
+                .map_err(|e| format!("{e:#}"));
+
+                let _ = result_tx.send(result);
+            });
+
+        self.track(spawned, progress, result_rx);
+    }
+
+    /// Bounce the input file through the live chain into a new take. Refused
+    /// while `recording` or while a render is running.
+    pub fn start_bounce(
+        &mut self,
+        host: &mut impl BounceHost,
+        sample_rate: usize,
+        recording: bool,
+    ) {
+        if self.is_running() {
+            return;
+        }
+        let input = self.dialog.input_path().trim().to_string();
+        let samples = match read_mono_wav_at(Path::new(&input), sample_rate) {
+            Ok(samples) => samples,
+            Err(e) => {
+                error!("Failed to read {input} for bouncing: {e:#}");
+                self.dialog
+                    .set_status(RenderStatus::Failed(format!("{e:#}")));
+                return;
+            }
+        };
+        let tail = (BOUNCE_TAIL_SECONDS * sample_rate as f32) as usize;
+
+        match Bounce::start(
+            host,
+            samples.into(),
+            tail,
+            self.dialog.bounce_mix(),
+            recording,
+        ) {
+            Ok(bounce) => {
+                let (done, total) = bounce.progress();
+                self.dialog
+                    .set_status(RenderStatus::Bouncing { done, total });
+                self.bounce = Some(bounce);
+            }
+            Err(e) => {
+                error!("Failed to start bounce: {e}");
+                self.dialog.set_status(RenderStatus::Failed(e.to_string()));
+            }
+        }
+    }
+
+    /// Follow a running bounce, ending it once the file and its tail are
+    /// through the chain.
+    pub fn poll_bounce(&mut self, host: &mut impl BounceHost) {
+        let Some(bounce) = &self.bounce else {
+            return;
+        };
+        if bounce.is_finished() {
+            self.end_bounce(host);
+        } else {
+            let (done, total) = bounce.progress();
+            self.dialog
+                .set_status(RenderStatus::Bouncing { done, total });
+        }
+    }
+
+    /// Stop a running bounce early, keeping what was taken so far.
+    pub fn cancel_bounce(&mut self, host: &mut impl BounceHost) {
+        self.end_bounce(host);
+    }
+
+    fn end_bounce(&mut self, host: &mut impl BounceHost) {
+        let Some(bounce) = self.bounce.take() else {
+            return;
+        };
+        match bounce.end(host) {
+            Ok(take) => {
+                info!("Bounced into {}", take.display());
+                self.dialog
+                    .set_status(RenderStatus::Bounced(take.display().to_string()));
+            }
+            Err(e) => {
+                error!("Bounce failed: {e}");
+                self.dialog.set_status(RenderStatus::Failed(e.to_string()));
+            }
+        }
+    }
+
+    fn track(
+        &mut self,
+        spawned: std::io::Result<thread::JoinHandle<()>>,
+        progress: Arc<AtomicU32>,
+        result_rx: Receiver<Result<Option<String>, String>>,
+    ) {
+        match spawned {
+            Ok(_) => {
+                self.dialog.set_status(RenderStatus::Running(0.0));
+                self.job = Some(RenderJob {
+                    progress,
+                    result_rx,
+                });
+            }
+            Err(e) => self.dialog.set_status(RenderStatus::Failed(format!("{e}"))),
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some(job) = &self.job else {
+            return;
+        };
+
+        match job.result_rx.try_recv() {
+            Err(TryRecvError::Empty) => {
+                let progress = f32::from_bits(job.progress.load(Ordering::Relaxed));
+                self.dialog.set_status(RenderStatus::Running(progress));
+            }
+            Ok(Ok(None)) => {
+                info!("Offline render complete");
+                self.dialog.set_status(RenderStatus::Done);
+                self.job = None;
+            }
+            Ok(Ok(Some(name))) => {
+                self.dialog.set_status(RenderStatus::Captured(name.clone()));
+                self.captured_ir = Some(name);
+                self.job = None;
+            }
+            Ok(Err(e)) => {
+                error!("Offline render failed: {e}");
+                self.dialog.set_status(RenderStatus::Failed(e));
+                self.job = None;
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.dialog
+                    .set_status(RenderStatus::Failed("render thread exited".to_string()));
+                self.job = None;
+            }
+        }
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::Render))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some() || self.bounce.is_some()
+    }
+
+    pub const fn is_bouncing(&self) -> bool {
+        self.bounce.is_some()
+    }
+
+    /// Name of an IR captured since the last call, for the IR list rescan.
+    pub const fn take_captured_ir(&mut self) -> Option<String> {
+        self.captured_ir.take()
+    }
+}
+
+/// The IR directory for an offline job at `sample_rate`, or `None` if it
+/// can't be opened, in which case the job runs without the IR.
+pub fn open_ir_loader(request: &RenderRequest, sample_rate: usize) -> Option<IrLoader> {
+    IrLoader::with_max_seconds(
+        Path::new(&request.ir_dir),
+        sample_rate,
+        request.ir_max_seconds,
+    )
+    .map_err(|e| error!("Failed to open IR directory for render: {e}"))
+    .ok()
+}
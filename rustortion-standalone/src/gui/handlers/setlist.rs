@@ -0,0 +1,301 @@
+use iced::widget::{button, column, container, row, space, text};
+use iced::{Alignment, Element, Length};
+use log::{debug, error, warn};
+
+use crate::gui::components::dialogs::setlist::SetlistDialog;
+use crate::tr;
+use rustortion_core::preset::{Setlist, SetlistStore};
+use rustortion_ui::components::widgets::common::{
+    BORDER_RADIUS_CARD, COLOR_WARNING, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT,
+    TEXT_SIZE_INFO, TEXT_SIZE_LABEL, TEXT_SIZE_SECTION_TITLE,
+};
+use rustortion_ui::messages::{Message, SetlistMessage};
+
+const TEXT_SIZE_CURRENT_SONG: f32 = 36.0;
+
+/// Owns the saved setlists, their editor, and the one being played through.
+pub struct SetlistHandler {
+    /// `None` if the setlists directory couldn't be opened.
+    store: Option<SetlistStore>,
+    dialog: SetlistDialog,
+    active: Option<Setlist>,
+    position: usize,
+}
+
+impl SetlistHandler {
+    /// Open the setlists in `dir` and pick up the one that was playing.
+    pub fn new(dir: &str, active: Option<&str>, position: usize) -> Self {
+        let store = SetlistStore::new(dir)
+            .map_err(|e| warn!("Failed to open setlists directory {dir}: {e:#}"))
+            .ok();
+        let active = active.and_then(|name| store.as_ref()?.get(name).cloned());
+        let position = active
+            .as_ref()
+            .map_or(0, |list| position.min(list.entries.len().saturating_sub(1)));
+
+        Self {
+            store,
+            dialog: SetlistDialog::new(),
+            active,
+            position,
+        }
+    }
+
+    /// Returns the preset to load when the current song changes. `presets`
+    /// are the presets on disk; a song whose preset is missing isn't loaded.
+    pub fn handle(&mut self, message: SetlistMessage, presets: &[String]) -> Option<String> {
+        match message {
+            SetlistMessage::Open => {
+                if self.dialog.editing().is_none()
+                    && let Some(active) = &self.active
+                {
+                    self.dialog.edit(active.clone());
+                }
+                self.dialog.show(self.names());
+            }
+            SetlistMessage::Close => self.dialog.hide(),
+            SetlistMessage::Edit(name) => {
+                if let Some(setlist) = self.store.as_ref().and_then(|s| s.get(&name)) {
+                    self.dialog.edit(setlist.clone());
+                }
+            }
+            SetlistMessage::New => self.dialog.clear(),
+            SetlistMessage::NameChanged(name) => self.dialog.set_name(name),
+            SetlistMessage::PresetSelected(preset) => self.dialog.set_preset_to_add(preset),
+            SetlistMessage::AddEntry => self.dialog.add_entry(),
+            SetlistMessage::RemoveEntry(idx) => self.dialog.remove_entry(idx),
+            SetlistMessage::MoveEntryUp(idx) => self.dialog.move_entry_up(idx),
+            SetlistMessage::MoveEntryDown(idx) => self.dialog.move_entry_down(idx),
+            SetlistMessage::EntryNotesChanged(idx, notes) => {
+                self.dialog.set_entry_notes(idx, notes);
+            }
+            SetlistMessage::Save => {
+                self.save();
+            }
+            SetlistMessage::Delete => self.delete(),
+            SetlistMessage::Start => {
+                if self.save() {
+                    self.active = Some(self.dialog.draft().clone());
+                    self.position = 0;
+                    self.dialog.hide();
+                    return self.current_preset(presets);
+                }
+            }
+            SetlistMessage::Stop => {
+                self.active = None;
+                self.position = 0;
+            }
+            SetlistMessage::Next => return self.step(1, presets),
+            SetlistMessage::Previous => return self.step(-1, presets),
+        }
+
+        None
+    }
+
+    /// Save the editor's setlist. Renaming a saved setlist replaces its old
+    /// file, and edits to the one playing take effect straight away.
+    fn save(&mut self) -> bool {
+        let Some(store) = &mut self.store else {
+            self.dialog
+                .set_error("setlists directory unavailable".to_string());
+            return false;
+        };
+
+        let mut setlist = self.dialog.draft().clone();
+        setlist.name = setlist.name.trim().to_string();
+        let previous = self.dialog.editing().map(str::to_owned);
+        let result = store.save(&setlist).and_then(|()| match &previous {
+            Some(old) if *old != setlist.name => store.delete(old),
+            _ => Ok(()),
+        });
+
+        match result {
+            Ok(()) => {
+                let was_active = self.active.as_ref().is_some_and(|active| {
+                    active.name == setlist.name || Some(&active.name) == previous.as_ref()
+                });
+                if was_active {
+                    self.position = self.position.min(setlist.entries.len().saturating_sub(1));
+                    self.active = Some(setlist.clone());
+                }
+                self.dialog.set_name(setlist.name.clone());
+                self.dialog.set_saved(store.names(), Some(setlist.name));
+                true
+            }
+            Err(e) => {
+                error!("Failed to save setlist: {e:#}");
+                self.dialog.set_error(format!("{e:#}"));
+                false
+            }
+        }
+    }
+
+    fn delete(&mut self) {
+        let (Some(store), Some(name)) = (&mut self.store, self.dialog.editing()) else {
+            return;
+        };
+        let name = name.to_owned();
+
+        match store.delete(&name) {
+            Ok(()) => {
+                if self.active.as_ref().is_some_and(|a| a.name == name) {
+                    self.active = None;
+                    self.position = 0;
+                }
+                self.dialog.clear();
+                self.dialog.set_saved(store.names(), None);
+            }
+            Err(e) => {
+                error!("Failed to delete setlist {name}: {e:#}");
+                self.dialog.set_error(format!("{e:#}"));
+            }
+        }
+    }
+
+    /// Move `delta` songs through the playing setlist, stopping at either end.
+    fn step(&mut self, delta: isize, presets: &[String]) -> Option<String> {
+        let Some(active) = &self.active else {
+            debug!("No setlist playing, ignoring song step");
+            return None;
+        };
+        let position = self.position.checked_add_signed(delta)?;
+        if position >= active.entries.len() {
+            return None;
+        }
+        self.position = position;
+        self.current_preset(presets)
+    }
+
+    fn current_preset(&self, presets: &[String]) -> Option<String> {
+        let entry = self.active.as_ref()?.entries.get(self.position)?;
+        if presets.contains(&entry.preset) {
+            Some(entry.preset.clone())
+        } else {
+            warn!(
+                "Song {} uses preset {}, which no longer exists",
+                self.position + 1,
+                entry.preset
+            );
+            None
+        }
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.store
+            .as_ref()
+            .map(SetlistStore::names)
+            .unwrap_or_default()
+    }
+
+    /// Name of the setlist playing, for the settings.
+    pub fn active_name(&self) -> Option<&str> {
+        self.active.as_ref().map(|list| list.name.as_str())
+    }
+
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn view<'a>(&'a self, presets: &'a [String]) -> Option<Element<'a, Message>> {
+        self.dialog
+            .view(presets, self.active.is_some())
+            .map(|e| e.map(Message::Setlist))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    /// The current song in large text above the chain, with its notes and
+    /// what comes next.
+    pub fn view_now_playing<'a>(&'a self, presets: &[String]) -> Option<Element<'a, Message>> {
+        let active = self.active.as_ref()?;
+        let current = active.entries.get(self.position)?;
+        let next = active.entries.get(self.position + 1);
+        let exists = |preset: &str| presets.iter().any(|p| p == preset);
+
+        let mut title = row![text(&current.preset).size(TEXT_SIZE_CURRENT_SONG)]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+        if !exists(&current.preset) {
+            title = title.push(
+                text(tr!(setlist_missing_preset))
+                    .size(TEXT_SIZE_INFO)
+                    .style(|_| iced::widget::text::Style {
+                        color: Some(COLOR_WARNING),
+                    }),
+            );
+        }
+
+        let mut song = column![
+            text(format!(
+                "{} · {}/{}",
+                active.name,
+                self.position + 1,
+                active.entries.len()
+            ))
+            .size(TEXT_SIZE_INFO),
+            title,
+        ]
+        .spacing(SPACING_TIGHT);
+        if !current.notes.is_empty() {
+            song = song.push(text(&current.notes).size(TEXT_SIZE_LABEL));
+        }
+
+        let up_next: Element<'_, Message> = match next {
+            Some(entry) => {
+                let label = if exists(&entry.preset) {
+                    entry.preset.clone()
+                } else {
+                    format!("⚠ {}", entry.preset)
+                };
+                column![
+                    text(tr!(setlist_next_up)).size(TEXT_SIZE_INFO),
+                    text(label).size(TEXT_SIZE_SECTION_TITLE),
+                ]
+                .spacing(SPACING_TIGHT)
+                .into()
+            }
+            None => text(tr!(setlist_last_song))
+                .size(TEXT_SIZE_SECTION_TITLE)
+                .into(),
+        };
+
+        let controls = row![
+            button(text("◀").size(TEXT_SIZE_LABEL))
+                .on_press_maybe(
+                    (self.position > 0).then_some(Message::Setlist(SetlistMessage::Previous))
+                )
+                .style(iced::widget::button::secondary),
+            button(text("▶").size(TEXT_SIZE_LABEL))
+                .on_press_maybe(
+                    next.is_some()
+                        .then_some(Message::Setlist(SetlistMessage::Next))
+                )
+                .style(iced::widget::button::primary),
+            button(text(tr!(setlist_stop)).size(TEXT_SIZE_INFO))
+                .on_press(Message::Setlist(SetlistMessage::Stop))
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center);
+
+        let banner = row![song, space::horizontal(), up_next, controls]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+
+        Some(
+            container(banner)
+                .width(Length::Fill)
+                .padding(PADDING_NORMAL)
+                .style(|theme: &iced::Theme| {
+                    let pair = theme.extended_palette().background.strong;
+                    container::Style::default()
+                        .background(pair.color)
+                        .color(pair.text)
+                        .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+                })
+                .into(),
+        )
+    }
+}
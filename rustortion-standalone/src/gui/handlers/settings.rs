@@ -1,10 +1,14 @@
 use iced::{Element, Task};
 use log::{debug, error};
 
+use crate::audio::bootstrap::EngineBootstrap;
+use crate::audio::cpal;
 use crate::audio::manager::Manager;
 use crate::gui::components::dialogs::settings::{JackStatus, SettingsDialog};
 use crate::i18n;
 use crate::settings::{AudioSettings, Settings};
+use rustortion_core::audio::dual_mono::ChainMode;
+use rustortion_core::audio::test_source::TestSourceKind;
 use rustortion_ui::messages::{Message, SettingsMessage};
 
 pub struct SettingsHandler {
@@ -26,15 +30,35 @@ impl SettingsHandler {
     ) -> Task<Message> {
         match message {
             SettingsMessage::Open | SettingsMessage::RefreshPorts => {
+                self.dialog.set_theme(settings.theme.clone());
+                self.dialog
+                    .set_record_automation(settings.record_automation);
+                self.dialog
+                    .set_recording_softclip(settings.recording_softclip);
+                self.dialog.set_tuner_algorithm(settings.tuner_algorithm);
+                self.dialog.set_chain_lint(settings.chain_lint);
+                self.dialog
+                    .set_keep_locked_stages(settings.keep_locked_stages);
+                self.dialog
+                    .set_quantize_chain_changes(settings.quantize_chain_changes);
+                self.dialog.set_watchdog(settings.watchdog.clone());
+                self.dialog.set_remote(&settings.remote);
+                self.dialog.set_hooks(&settings.hooks);
+                self.dialog
+                    .set_test_source(audio_manager.test_source(), settings.test_di_file.clone());
+                self.dialog
+                    .set_metronome_tick(settings.metronome_tick.clone());
+                self.dialog
+                    .set_metronome(settings.metronome, settings.record_click_track);
                 let inputs = audio_manager.get_available_inputs();
                 let outputs = audio_manager.get_available_outputs();
-                let jack_status = JackStatus {
-                    sample_rate: audio_manager.sample_rate(),
-                    buffer_size: audio_manager.buffer_size(),
-                };
+                let jack_status = jack_status(audio_manager);
+                self.dialog
+                    .set_devices(cpal::input_devices(), cpal::output_devices());
                 self.dialog.show(
                     &settings.audio,
-                    settings.nam_dir.clone(),
+                    settings.paths.nam_dir.clone(),
+                    settings.record_follow_transport,
                     inputs,
                     outputs,
                     jack_status,
@@ -43,25 +67,24 @@ impl SettingsHandler {
             SettingsMessage::Close => {
                 self.dialog.hide();
             }
-            SettingsMessage::Apply => {
-                let new_audio_settings = self.dialog.get_settings();
-                settings.audio = new_audio_settings.clone();
-                settings.nam_dir = self.dialog.get_nam_dir();
-
-                if let Err(e) = audio_manager.apply_settings(new_audio_settings) {
-                    error!("Failed to apply audio settings: {e}");
-                }
-
-                if let Err(e) = settings.save() {
-                    error!("Failed to save settings: {e}");
-                }
-
-                self.dialog.hide();
-                debug!("Audio settings applied successfully");
+            // Needs the GUI state to warm-start the engine, so `AmplifierApp`
+            // calls `apply` directly.
+            SettingsMessage::Apply => {}
+            SettingsMessage::HostApiChanged(api) => {
+                self.with_temp_settings(|s| s.host_api = api);
+            }
+            SettingsMessage::InputDeviceChanged(d) => {
+                self.with_temp_settings(|s| s.input_device = d);
+            }
+            SettingsMessage::OutputDeviceChanged(d) => {
+                self.with_temp_settings(|s| s.output_device = d);
             }
             SettingsMessage::InputPortChanged(p) => {
                 self.with_temp_settings(|s| s.input_port = p);
             }
+            SettingsMessage::InputRightPortChanged(p) => {
+                self.with_temp_settings(|s| s.input_right_port = p);
+            }
             SettingsMessage::OutputLeftPortChanged(p) => {
                 self.with_temp_settings(|s| s.output_left_port = p);
             }
@@ -74,6 +97,21 @@ impl SettingsHandler {
             SettingsMessage::SampleRateChanged(x) => {
                 self.with_temp_settings(|s| s.sample_rate = x);
             }
+            SettingsMessage::ResamplerQualityChanged(q) => {
+                self.with_temp_settings(|s| s.resampler_quality = q);
+            }
+            SettingsMessage::AdaptiveQualityToggled(enabled) => {
+                self.with_temp_settings(|s| s.adaptive_quality = enabled);
+            }
+            SettingsMessage::ChainModeToggled(enabled) => {
+                self.with_temp_settings(|s| {
+                    s.chain_mode = if enabled {
+                        ChainMode::DualMono
+                    } else {
+                        ChainMode::Mono
+                    };
+                });
+            }
             SettingsMessage::NamDirChanged(dir) => {
                 self.dialog.set_nam_dir(dir);
             }
@@ -82,7 +120,7 @@ impl SettingsHandler {
                 match audio_manager.rescan_nam_models(&nam_dir) {
                     Ok(count) => {
                         // Persist the directory so the new path survives a restart.
-                        settings.nam_dir = nam_dir;
+                        settings.paths.nam_dir = nam_dir;
                         if let Err(e) = settings.save() {
                             error!("Failed to save settings after NAM rescan: {e}");
                         }
@@ -91,6 +129,189 @@ impl SettingsHandler {
                     Err(e) => error!("{e}"),
                 }
             }
+            SettingsMessage::RecordFollowTransportToggled(enabled) => {
+                // Events queued while nobody was following are stale.
+                audio_manager.transport_events().for_each(drop);
+                self.dialog.set_record_follow_transport(enabled);
+                settings.record_follow_transport = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save transport follow setting: {e}");
+                }
+            }
+            SettingsMessage::RecordAutomationToggled(enabled) => {
+                self.dialog.set_record_automation(enabled);
+                settings.record_automation = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save automation recording setting: {e}");
+                }
+            }
+            SettingsMessage::RecordingSoftclipToggled(enabled) => {
+                self.dialog.set_recording_softclip(enabled);
+                settings.recording_softclip = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save recording soft clip setting: {e}");
+                }
+            }
+            SettingsMessage::TunerAlgorithmChanged(algorithm) => {
+                audio_manager.engine().set_tuner_algorithm(algorithm);
+                self.dialog.set_tuner_algorithm(algorithm);
+                settings.tuner_algorithm = algorithm;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save tuner algorithm setting: {e}");
+                }
+            }
+            // `AmplifierApp` also turns the lint on or off in the stage view.
+            SettingsMessage::ChainLintToggled(enabled) => {
+                self.dialog.set_chain_lint(enabled);
+                settings.chain_lint = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save chain lint setting: {e}");
+                }
+            }
+            // `AmplifierApp` also hands it to the stage view.
+            SettingsMessage::KeepLockedStagesToggled(enabled) => {
+                self.dialog.set_keep_locked_stages(enabled);
+                settings.keep_locked_stages = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save locked stages setting: {e}");
+                }
+            }
+            // `AmplifierApp` also hands it to the stage view.
+            SettingsMessage::QuantizeChainChangesToggled(enabled) => {
+                self.dialog.set_quantize_chain_changes(enabled);
+                settings.quantize_chain_changes = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save chain quantize setting: {e}");
+                }
+            }
+            SettingsMessage::WatchdogAutoRestartToggled(enabled) => {
+                settings.watchdog.auto_restart = enabled;
+                self.dialog.set_watchdog(settings.watchdog.clone());
+                if let Err(e) = settings.save() {
+                    error!("Failed to save watchdog setting: {e}");
+                }
+            }
+            // `AmplifierApp` also hands the new threshold to the watchdog.
+            SettingsMessage::WatchdogStallSecondsChanged(seconds) => {
+                settings.watchdog.stall_seconds = seconds;
+                self.dialog.set_watchdog(settings.watchdog.clone());
+                if let Err(e) = settings.save() {
+                    error!("Failed to save watchdog setting: {e}");
+                }
+            }
+            // `AmplifierApp` starts or stops the server to match.
+            SettingsMessage::RemoteToggled(enabled) => {
+                settings.remote.enabled = enabled;
+                self.dialog.set_remote(&settings.remote);
+                if let Err(e) = settings.save() {
+                    error!("Failed to save remote control setting: {e}");
+                }
+            }
+            SettingsMessage::RemotePortChanged(port) => {
+                self.dialog.set_remote_port(port);
+            }
+            // `AmplifierApp` moves a running server to the new port.
+            SettingsMessage::RemotePortSubmitted => {
+                match self.dialog.remote_port().trim().parse::<u16>() {
+                    Ok(port) if port != 0 => {
+                        settings.remote.port = port;
+                        if let Err(e) = settings.save() {
+                            error!("Failed to save remote control port: {e}");
+                        }
+                    }
+                    _ => error!(
+                        "Invalid remote control port '{}'",
+                        self.dialog.remote_port()
+                    ),
+                }
+                self.dialog.set_remote(&settings.remote);
+            }
+            // `AmplifierApp` hands the hooks to the dispatcher.
+            SettingsMessage::HooksToggled(enabled) => {
+                settings.hooks.enabled = enabled;
+                self.dialog.set_hooks_enabled(enabled);
+                if let Err(e) = settings.save() {
+                    error!("Failed to save hook setting: {e}");
+                }
+            }
+            SettingsMessage::HookCommandChanged(kind, command) => {
+                self.dialog.set_hook_command(kind, command);
+            }
+            SettingsMessage::HookCommandSubmitted(kind) => {
+                let command = self.dialog.hook_command(kind).trim().to_owned();
+                if command.is_empty() {
+                    settings.hooks.commands.remove(&kind);
+                } else {
+                    settings.hooks.commands.insert(kind, command);
+                }
+                if let Err(e) = settings.save() {
+                    error!("Failed to save {kind} hook: {e}");
+                }
+            }
+            SettingsMessage::TestSourceChanged(config) => {
+                self.dialog.set_test_source_config(config);
+                audio_manager.set_test_source(config);
+                // Decoded on first use, and again after a sample rate change.
+                if config.kind == TestSourceKind::DiSample
+                    && !settings.test_di_file.is_empty()
+                    && let Err(e) = audio_manager.load_test_di(&settings.test_di_file)
+                {
+                    error!("Failed to load test DI: {e}");
+                }
+            }
+            SettingsMessage::MetronomeChanged(config) => {
+                let config = config.clamped();
+                audio_manager.engine().set_metronome(config);
+                self.dialog
+                    .set_metronome(config, settings.record_click_track);
+                settings.metronome = config;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save metronome settings: {e}");
+                }
+            }
+            SettingsMessage::RecordClickTrackToggled(enabled) => {
+                self.dialog.set_metronome(settings.metronome, enabled);
+                settings.record_click_track = enabled;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save click track setting: {e}");
+                }
+            }
+            SettingsMessage::TestDiFileChanged(path) => {
+                self.dialog.set_test_di_file(path);
+            }
+            SettingsMessage::LoadTestDi => {
+                let path = self.dialog.test_di_file();
+                match audio_manager.load_test_di(&path) {
+                    Ok(()) => {
+                        settings.test_di_file = path;
+                        if let Err(e) = settings.save() {
+                            error!("Failed to save test DI setting: {e}");
+                        }
+                    }
+                    Err(e) => error!("Failed to load test DI '{path}': {e}"),
+                }
+            }
+            SettingsMessage::MetronomeTickChanged(path) => {
+                self.dialog.set_metronome_tick(path);
+            }
+            SettingsMessage::LoadMetronomeTick => {
+                let path = self.dialog.metronome_tick();
+                match audio_manager.load_metronome_tick(&path) {
+                    Ok(()) => {
+                        settings.metronome_tick = path;
+                        if let Err(e) = settings.save() {
+                            error!("Failed to save metronome tick setting: {e}");
+                        }
+                    }
+                    Err(e) => error!("Failed to load metronome tick '{path}': {e:#}"),
+                }
+            }
+            // Needs the GUI's stage list, so `AmplifierApp` handles it directly.
+            SettingsMessage::CopyDiagnostics => {}
+            // Open their dialogs, so `AmplifierApp` handles them.
+            SettingsMessage::CalibrateInput
+            | SettingsMessage::AnalyzeGainStaging
+            | SettingsMessage::MatchTone => {}
             SettingsMessage::LanguageChanged(lang) => {
                 i18n::set_language(lang);
                 settings.language = lang;
@@ -98,11 +319,48 @@ impl SettingsHandler {
                     error!("Failed to save language settings: {e}");
                 }
             }
+            SettingsMessage::ThemeChanged(theme) => {
+                self.dialog.set_theme(theme.clone());
+                settings.theme = theme;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save theme settings: {e}");
+                }
+            }
+            // Only the app's theme depends on it, so `AmplifierApp` handles it.
+            SettingsMessage::SystemThemeChanged(_) => {}
         }
 
         Task::none()
     }
 
+    pub fn apply(
+        &mut self,
+        settings: &mut Settings,
+        audio_manager: &mut Manager,
+        bootstrap: &EngineBootstrap,
+    ) {
+        let new_audio_settings = self.dialog.get_settings();
+        settings.audio = new_audio_settings.clone();
+        settings.paths.nam_dir = self.dialog.get_nam_dir();
+
+        if let Err(e) = audio_manager.apply_settings(new_audio_settings, bootstrap) {
+            error!("Failed to apply audio settings: {e}");
+            settings.audio.clone_from(audio_manager.audio_settings());
+        }
+
+        if let Err(e) = settings.save() {
+            error!("Failed to save settings: {e}");
+        }
+
+        self.dialog.hide();
+        debug!("Audio settings applied successfully");
+    }
+
+    /// Re-read the server's rate and period for the dialog's JACK status.
+    pub fn refresh_jack_status(&mut self, audio_manager: &Manager) {
+        self.dialog.set_jack_status(jack_status(audio_manager));
+    }
+
     fn with_temp_settings<F: FnOnce(&mut AudioSettings)>(&mut self, f: F) {
         let mut tmp = self.dialog.get_settings();
         f(&mut tmp);
@@ -121,3 +379,12 @@ impl SettingsHandler {
         self.dialog.get_settings()
     }
 }
+
+fn jack_status(audio_manager: &Manager) -> JackStatus {
+    JackStatus {
+        sample_rate: audio_manager.sample_rate(),
+        buffer_size: audio_manager.buffer_size(),
+        engine_latency: audio_manager.engine().latency(),
+        backend: audio_manager.backend_kind(),
+    }
+}
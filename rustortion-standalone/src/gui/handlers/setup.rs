@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use iced::{Element, Task};
+use log::{debug, error};
+
+use crate::audio::backend::BackendKind;
+use crate::audio::bootstrap::EngineBootstrap;
+use crate::audio::manager::Manager;
+use crate::gui::components::dialogs::setup::{SetupDialog, SetupStep};
+use crate::settings::{AudioSettings, Settings};
+use crate::setup::{AudioServer, create_dirs, install_factory_presets};
+use rustortion_ui::messages::{Message, MidiMessage, SetupMessage};
+
+/// The first-run setup, once audio is running. Holding on the first step
+/// until it is happens before the app boots; see
+/// [`crate::gui::shell::Shell`].
+pub struct SetupHandler {
+    dialog: SetupDialog,
+    /// The folders step moved or filled a directory the app reads from.
+    paths_changed: bool,
+}
+
+impl Default for SetupHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SetupHandler {
+    pub fn new() -> Self {
+        Self {
+            dialog: SetupDialog::new(),
+            paths_changed: false,
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        message: SetupMessage,
+        settings: &mut Settings,
+        audio_manager: &mut Manager,
+        bootstrap: &EngineBootstrap,
+    ) -> Task<Message> {
+        match message {
+            SetupMessage::Open => self.open(settings, audio_manager),
+            SetupMessage::Retry => self.dialog.set_server(AudioServer::detect()),
+            SetupMessage::Back => self.dialog.set_step(self.dialog.step().previous()),
+            SetupMessage::Next => match self.dialog.step() {
+                SetupStep::Audio => {
+                    self.refresh_ports(audio_manager);
+                    self.dialog.set_step(SetupStep::Ports);
+                }
+                SetupStep::Ports => self.dialog.set_step(SetupStep::Folders),
+                SetupStep::Folders => {
+                    if self.apply_folders(settings) {
+                        self.dialog.set_step(SetupStep::Midi);
+                    }
+                }
+                SetupStep::Midi => {}
+            },
+            SetupMessage::RefreshPorts => self.refresh_ports(audio_manager),
+            SetupMessage::InputChanged(port) => {
+                let cpal = audio_manager.backend_kind() == BackendKind::Cpal;
+                self.apply_audio(settings, audio_manager, bootstrap, |audio| {
+                    if cpal {
+                        audio.input_device = port;
+                    } else {
+                        audio.input_port = port;
+                    }
+                });
+            }
+            SetupMessage::OutputLeftChanged(port) => {
+                let cpal = audio_manager.backend_kind() == BackendKind::Cpal;
+                self.apply_audio(settings, audio_manager, bootstrap, |audio| {
+                    if cpal {
+                        audio.output_device = port;
+                    } else {
+                        audio.output_left_port = port;
+                    }
+                });
+            }
+            SetupMessage::OutputRightChanged(port) => {
+                self.apply_audio(settings, audio_manager, bootstrap, |audio| {
+                    audio.output_right_port = port;
+                });
+            }
+            SetupMessage::RecordingDirChanged(dir) => self.dialog.paths_mut().recording_dir = dir,
+            SetupMessage::IrDirChanged(dir) => self.dialog.paths_mut().ir_dir = dir,
+            SetupMessage::PresetDirChanged(dir) => self.dialog.paths_mut().preset_dir = dir,
+            SetupMessage::InstallPresetsToggled(install) => {
+                self.dialog.set_install_presets(install);
+            }
+            SetupMessage::Skip | SetupMessage::Finish => self.close(settings),
+            SetupMessage::SetUpMidi => {
+                self.close(settings);
+                return Task::done(Message::Midi(MidiMessage::Open));
+            }
+            SetupMessage::Quit => return iced::exit(),
+        }
+
+        Task::none()
+    }
+
+    /// Start at the first step, with what's running and what's configured.
+    pub fn open(&mut self, settings: &Settings, audio_manager: &Manager) {
+        self.dialog.show(
+            AudioServer::detect(),
+            audio_manager.backend_kind(),
+            &settings.audio,
+            &settings.paths,
+        );
+    }
+
+    /// Show the input level on the ports step. Called on the peak meter
+    /// tick while the setup is open.
+    pub fn tick(&mut self, audio_manager: &Manager) {
+        if self.dialog.step() == SetupStep::Ports {
+            self.dialog
+                .set_input_peak(audio_manager.input_meter().get_info().peak_db);
+        }
+    }
+
+    /// Whether the folders step changed the directories since last asked,
+    /// so the app can rescan them.
+    pub const fn take_paths_changed(&mut self) -> bool {
+        std::mem::replace(&mut self.paths_changed, false)
+    }
+
+    fn refresh_ports(&mut self, audio_manager: &Manager) {
+        self.dialog.set_ports(
+            audio_manager.get_available_inputs(),
+            audio_manager.get_available_outputs(),
+        );
+    }
+
+    /// Picked ports are connected straight away, so the meter follows them.
+    fn apply_audio(
+        &mut self,
+        settings: &mut Settings,
+        audio_manager: &mut Manager,
+        bootstrap: &EngineBootstrap,
+        change: impl FnOnce(&mut AudioSettings),
+    ) {
+        change(&mut settings.audio);
+        if let Err(e) = audio_manager.apply_settings(settings.audio.clone(), bootstrap) {
+            error!("Failed to apply the picked ports: {e}");
+            settings.audio.clone_from(audio_manager.audio_settings());
+        }
+        self.dialog.set_audio(&settings.audio);
+        save(settings);
+    }
+
+    /// Create the folders and install the presets. Stays on the step,
+    /// showing why, if that fails.
+    fn apply_folders(&mut self, settings: &mut Settings) -> bool {
+        let mut paths = settings.paths.clone();
+        let chosen = self.dialog.paths();
+        paths.recording_dir.clone_from(&chosen.recording_dir);
+        paths.ir_dir.clone_from(&chosen.ir_dir);
+        paths.preset_dir.clone_from(&chosen.preset_dir);
+
+        if let Err(e) = create_dirs(&paths) {
+            error!("Failed to create the setup folders: {e}");
+            self.dialog.set_folders_error(Some(e.to_string()));
+            return false;
+        }
+        if self.dialog.install_presets() {
+            match install_factory_presets(Path::new(&paths.preset_dir)) {
+                Ok(0) => debug!("Factory presets already installed"),
+                Ok(_) => self.paths_changed = true,
+                Err(e) => {
+                    error!("Failed to install the factory presets: {e}");
+                    self.dialog.set_folders_error(Some(e.to_string()));
+                    return false;
+                }
+            }
+        }
+        self.dialog.set_folders_error(None);
+
+        if paths.recording_dir != settings.paths.recording_dir
+            || paths.ir_dir != settings.paths.ir_dir
+            || paths.preset_dir != settings.paths.preset_dir
+        {
+            self.paths_changed = true;
+        }
+        settings.paths = paths;
+        save(settings);
+        true
+    }
+
+    /// Done or skipped: saving marks the first run as over.
+    fn close(&mut self, settings: &Settings) {
+        self.dialog.hide();
+        save(settings);
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::Setup))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+}
+
+fn save(settings: &Settings) {
+    if let Err(e) = settings.save() {
+        error!("Failed to save settings from setup: {e}");
+    }
+}
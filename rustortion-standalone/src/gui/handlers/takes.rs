@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use iced::{Element, Task};
+use log::{error, info};
+
+use crate::audio::manager::Manager;
+use crate::gui::components::dialogs::takes::TakesDialog;
+use rustortion_core::audio::take_player::{TakeCommand, TakePlayer, TakeTransport, recent_takes};
+use rustortion_ui::messages::{Message, TakesMessage};
+
+/// Loads the latest takes into the engine's take player and drives it from
+/// the "Compare takes" dialog.
+pub struct TakesHandler {
+    dialog: TakesDialog,
+    /// Follows the player on the RT thread while one is loaded.
+    transport: Option<Arc<TakeTransport>>,
+}
+
+impl Default for TakesHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TakesHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: TakesDialog::new(),
+            transport: None,
+        }
+    }
+
+    pub fn handle(
+        &mut self,
+        message: TakesMessage,
+        recording_dir: &str,
+        manager: &Manager,
+    ) -> Task<Message> {
+        match message {
+            TakesMessage::Open => {
+                self.dialog.show();
+                self.load(recording_dir, manager);
+            }
+            TakesMessage::Close => {
+                self.dialog.hide();
+                if self.transport.take().is_some() {
+                    manager.engine().set_take_player(None);
+                }
+            }
+            TakesMessage::Reload => self.load(recording_dir, manager),
+            TakesMessage::NormalizeToggled(normalize) => {
+                self.dialog.set_normalize(normalize);
+                self.load(recording_dir, manager);
+            }
+            TakesMessage::Select(take) => {
+                if take < self.dialog.take_count() {
+                    manager.engine().take_command(TakeCommand::Select(take));
+                    self.dialog.set_active(take);
+                }
+            }
+            TakesMessage::PlayPause => {
+                if let Some(transport) = &self.transport {
+                    let playing = !self.dialog.is_playing();
+                    let command = if playing {
+                        TakeCommand::Play
+                    } else {
+                        TakeCommand::Pause
+                    };
+                    manager.engine().take_command(command);
+                    self.dialog.set_transport(transport.frame(), playing);
+                }
+            }
+            TakesMessage::Rewind => {
+                if let Some(transport) = &self.transport {
+                    manager.engine().take_command(TakeCommand::Rewind);
+                    self.dialog.set_transport(0, transport.is_playing());
+                }
+            }
+            TakesMessage::Update => {
+                if let Some(transport) = &self.transport {
+                    self.dialog
+                        .set_transport(transport.frame(), transport.is_playing());
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Decode the latest takes in `recording_dir` and hand them to the
+    /// engine, replacing any being compared.
+    fn load(&mut self, recording_dir: &str, manager: &Manager) {
+        let sample_rate = manager.sample_rate();
+        let loaded = recent_takes(Path::new(recording_dir))
+            .and_then(|paths| TakePlayer::load(&paths, sample_rate, self.dialog.normalize()));
+
+        match loaded {
+            Ok(player) if player.take_names().is_empty() => {
+                self.dialog.set_takes(Vec::new(), 0, sample_rate);
+                if self.transport.take().is_some() {
+                    manager.engine().set_take_player(None);
+                }
+            }
+            Ok(player) => {
+                info!("Comparing takes {:?}", player.take_names());
+                self.dialog
+                    .set_takes(player.take_names(), player.length(), sample_rate);
+                self.transport = Some(player.transport());
+                manager.engine().set_take_player(Some(player));
+            }
+            Err(e) => {
+                error!("Failed to load takes: {e:#}");
+                self.dialog.set_error(format!("{e:#}"));
+                if self.transport.take().is_some() {
+                    manager.engine().set_take_player(None);
+                }
+            }
+        }
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::Takes))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    /// Whether the playhead needs polling.
+    pub const fn is_playing(&self) -> bool {
+        self.dialog.is_playing()
+    }
+}
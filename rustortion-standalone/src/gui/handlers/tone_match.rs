@@ -0,0 +1,171 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+use crossbeam::channel::{Receiver, TryRecvError, bounded};
+use iced::{Element, Task};
+use log::{error, info};
+
+use crate::gui::components::dialogs::tone_match::{ToneMatchDialog, ToneMatchStatus};
+use crate::gui::handlers::render::{RenderRequest, open_ir_loader};
+use rustortion_core::audio::tone_match::{MatchReference, MatchSource, ToneMatch, match_tone};
+use rustortion_core::preset::{Preset, StageConfig};
+use rustortion_ui::messages::{Message, ToneMatchMessage};
+
+/// An analysis running on a background thread.
+struct ToneMatchJob {
+    /// `f32` fraction complete, stored as bits.
+    progress: Arc<AtomicU32>,
+    result_rx: Receiver<Result<ToneMatch, String>>,
+}
+
+pub struct ToneMatchHandler {
+    dialog: ToneMatchDialog,
+    job: Option<ToneMatchJob>,
+}
+
+impl Default for ToneMatchHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToneMatchHandler {
+    pub const fn new() -> Self {
+        Self {
+            dialog: ToneMatchDialog::new(),
+            job: None,
+        }
+    }
+
+    pub fn open(&mut self, presets: Vec<String>) {
+        self.dialog.show(presets);
+    }
+
+    /// `Open` and `Start` need the app's presets and are handled by
+    /// [`Self::open`] and [`Self::start`]. `stages` is the chain the
+    /// correction is appended to.
+    pub fn handle(&mut self, message: ToneMatchMessage, stages: &[StageConfig]) -> Task<Message> {
+        match message {
+            ToneMatchMessage::Open | ToneMatchMessage::Start => {}
+            ToneMatchMessage::Close => self.dialog.hide(),
+            ToneMatchMessage::SourcePathChanged(path) => self.dialog.set_source_path(path),
+            ToneMatchMessage::ReferencePresetSelected(name) => {
+                self.dialog.set_reference_preset(name);
+            }
+            ToneMatchMessage::ReferencePathChanged(path) => self.dialog.set_reference_path(path),
+            ToneMatchMessage::Update => self.poll(stages),
+            ToneMatchMessage::Apply => {
+                if let ToneMatchStatus::Done { matched, .. } = self.dialog.status()
+                    && let Some(appended) = matched.append_to(stages)
+                {
+                    info!("Appended the tone match correction to the chain");
+                    self.dialog.set_status(ToneMatchStatus::Idle);
+                    self.dialog.hide();
+                    return Task::done(Message::SetStages(appended));
+                }
+            }
+        }
+
+        Task::none()
+    }
+
+    /// The preset picked as the reference, for the app to look up.
+    pub fn reference_preset(&self) -> Option<&str> {
+        self.dialog.reference_preset()
+    }
+
+    /// Start matching `request.preset` to the reference. `reference_preset`
+    /// is the picked preset, looked up by the app; without one the reference
+    /// recording is used.
+    pub fn start(&mut self, request: RenderRequest, reference_preset: Option<Preset>) {
+        if self.job.is_some() {
+            return;
+        }
+        let source = match self.dialog.source_path() {
+            "" => MatchSource::PinkNoise,
+            path => MatchSource::Wav(PathBuf::from(path)),
+        };
+        let reference = match reference_preset {
+            Some(preset) => MatchReference::Preset(Box::new(preset)),
+            None => MatchReference::Wav(PathBuf::from(self.dialog.reference_path())),
+        };
+        let progress = Arc::new(AtomicU32::new(0.0f32.to_bits()));
+        let (result_tx, result_rx) = bounded(1);
+
+        let thread_progress = Arc::clone(&progress);
+        let report = move |p: f32| thread_progress.store(p.to_bits(), Ordering::Relaxed);
+        let spawned = thread::Builder::new()
+            .name("tone-match".into())
+            .spawn(move || {
+                let result = match_tone(
+                    &request.preset,
+                    &source,
+                    &reference,
+                    request.sample_rate,
+                    request.oversampling_factor,
+                    open_ir_loader(&request, request.sample_rate).as_ref(),
+                    report,
+                )
+                .map_err(|e| format!("{e:#}"));
+
+                let _ = result_tx.send(result);
+            });
+
+        match spawned {
+            Ok(_) => {
+                self.dialog.set_status(ToneMatchStatus::Running(0.0));
+                self.job = Some(ToneMatchJob {
+                    progress,
+                    result_rx,
+                });
+            }
+            Err(e) => self
+                .dialog
+                .set_status(ToneMatchStatus::Failed(format!("{e}"))),
+        }
+    }
+
+    fn poll(&mut self, stages: &[StageConfig]) {
+        let Some(job) = &self.job else {
+            return;
+        };
+
+        match job.result_rx.try_recv() {
+            Err(TryRecvError::Empty) => {
+                let progress = f32::from_bits(job.progress.load(Ordering::Relaxed));
+                self.dialog.set_status(ToneMatchStatus::Running(progress));
+            }
+            Ok(Ok(matched)) => {
+                let can_apply = matched.append_to(stages).is_some();
+                self.dialog
+                    .set_status(ToneMatchStatus::Done { matched, can_apply });
+                self.job = None;
+            }
+            Ok(Err(e)) => {
+                error!("Tone match failed: {e}");
+                self.dialog.set_status(ToneMatchStatus::Failed(e));
+                self.job = None;
+            }
+            Err(TryRecvError::Disconnected) => {
+                self.dialog.set_status(ToneMatchStatus::Failed(
+                    "tone match thread exited".to_string(),
+                ));
+                self.job = None;
+            }
+        }
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.dialog.view().map(|e| e.map(Message::ToneMatch))
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.dialog.is_visible()
+    }
+
+    pub const fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+}
@@ -1,51 +1,122 @@
 use iced::{Element, Task};
+use log::{error, warn};
 
 use crate::audio::manager::Manager;
 use crate::gui::components::dialogs::tuner::TunerDisplay;
+use crate::settings::Settings;
+use rustortion_core::tuner::{TunerMode, TunerSmoother};
 use rustortion_ui::messages::{Message, TunerMessage};
 
 pub struct TunerHandler {
     dialog: TunerDisplay,
+    smoother: TunerSmoother,
     enabled: bool,
+    /// While a hold-to-tune input is down: whether the tuner was already on
+    /// before it, so the release knows what to go back to.
+    enabled_before_hold: Option<bool>,
 }
 
 impl Default for TunerHandler {
     fn default() -> Self {
-        Self::new()
+        Self::new(TunerMode::default())
     }
 }
 
 impl TunerHandler {
-    pub fn new() -> Self {
+    pub fn new(mode: TunerMode) -> Self {
         Self {
-            dialog: TunerDisplay::new(),
+            dialog: TunerDisplay::new(mode),
+            smoother: TunerSmoother::new(),
             enabled: false,
+            enabled_before_hold: None,
         }
     }
 
-    pub fn handle(&mut self, message: TunerMessage, audio_manager: &Manager) -> Task<Message> {
+    pub fn handle(
+        &mut self,
+        message: TunerMessage,
+        settings: &mut Settings,
+        audio_manager: &Manager,
+    ) -> Task<Message> {
         match message {
             TunerMessage::Toggle => {
-                self.enabled = !self.enabled;
-
-                if self.enabled {
-                    self.dialog.show();
-                    audio_manager.engine().set_tuner_enabled(true);
-                } else {
-                    self.dialog.hide();
-                    audio_manager.engine().set_tuner_enabled(false);
+                // Toggling by hand mid-hold makes that the state to keep.
+                self.enabled_before_hold = None;
+                self.set_enabled(!self.enabled, audio_manager);
+            }
+            TunerMessage::Hold => {
+                if self.enabled_before_hold.is_none() {
+                    self.enabled_before_hold = Some(self.enabled);
+                    self.set_enabled(true, audio_manager);
+                }
+            }
+            TunerMessage::Release => {
+                if let Some(was_enabled) = self.enabled_before_hold.take() {
+                    self.set_enabled(was_enabled, audio_manager);
                 }
             }
             TunerMessage::Update => {
                 if self.enabled {
-                    self.dialog.update(audio_manager.tuner().get_tuner_info());
+                    let info = self.smoother.update(audio_manager.tuner());
+                    self.dialog.update(info);
                 }
             }
+            TunerMessage::ModeChanged(mode) => {
+                self.dialog.set_mode(mode);
+                settings.tuner_mode = mode;
+                if let Err(e) = settings.save() {
+                    error!("Failed to save tuner mode setting: {e}");
+                }
+            }
+            TunerMessage::LockChanged(lock) => {
+                self.smoother.set_lock(lock);
+                self.dialog.set_lock(lock);
+            }
         }
 
         Task::none()
     }
 
+    /// Mute and show the tuner, or hide it and unmute. The switch is an
+    /// atomic the engine reads every block and it fades the output either
+    /// way, so this is safe to flip as often as needed.
+    fn set_enabled(&mut self, enabled: bool, audio_manager: &Manager) {
+        if enabled == self.enabled {
+            return;
+        }
+        self.enabled = enabled;
+
+        if enabled {
+            audio_manager.tuner().reset();
+            self.smoother.reset();
+            self.dialog.show();
+        } else {
+            self.dialog.hide();
+        }
+        audio_manager.tuner().set_enabled(enabled);
+    }
+
+    /// Bring the dialog in line with what the engine reports, for when the
+    /// switch was flipped somewhere the dialog didn't hear about.
+    pub fn sync_with_engine(&mut self, enabled: bool) {
+        if enabled == self.enabled {
+            return;
+        }
+        warn!(
+            "Tuner shown as {}, but the engine has it {}",
+            if self.enabled { "on" } else { "off" },
+            if enabled { "on" } else { "off" }
+        );
+        self.enabled = enabled;
+        self.enabled_before_hold = None;
+        if enabled {
+            self.smoother.reset();
+            self.dialog.show();
+        } else {
+            self.dialog.hide();
+        }
+    }
+
     pub fn view(&self) -> Option<Element<'_, Message>> {
         self.dialog.view().map(|e| e.map(Message::Tuner))
     }
@@ -0,0 +1,89 @@
+use std::time::{Duration, Instant};
+
+use iced::Element;
+use log::{error, info};
+
+use crate::audio::bootstrap::EngineBootstrap;
+use crate::audio::manager::Manager;
+use crate::audio::watchdog::{Watchdog, WatchdogEvent};
+use crate::gui::components::stall_banner::{AudioStall, RestartOutcome};
+use crate::gui::components::startup_error::audio_error_message;
+use rustortion_ui::messages::Message;
+
+/// Watches the engine's frame counter and owns the banner shown when the
+/// audio callback stops running.
+pub struct WatchdogHandler {
+    watchdog: Watchdog,
+    stall: Option<AudioStall>,
+}
+
+impl WatchdogHandler {
+    pub fn new(stall_seconds: u32) -> Self {
+        Self {
+            watchdog: Watchdog::new(threshold(stall_seconds), Instant::now()),
+            stall: None,
+        }
+    }
+
+    pub const fn set_stall_seconds(&mut self, stall_seconds: u32) {
+        self.watchdog.set_threshold(threshold(stall_seconds));
+    }
+
+    /// Poll the engine. Returns how long it has been still when a stall is
+    /// first detected, for the caller to log and act on; the banner is up by
+    /// then.
+    pub fn tick(&mut self, audio_manager: &Manager) -> Option<Duration> {
+        let now = Instant::now();
+        let event = self
+            .watchdog
+            .poll(now, audio_manager.engine(), audio_manager.is_running());
+
+        match event {
+            WatchdogEvent::None => None,
+            WatchdogEvent::Stalled => {
+                let stalled_for = self.watchdog.stalled_for(now);
+                self.stall = Some(AudioStall {
+                    stalled_for,
+                    restart: RestartOutcome::NotAttempted,
+                });
+                Some(stalled_for)
+            }
+            WatchdogEvent::Recovered => {
+                info!("Audio resumed after a stall");
+                self.stall = None;
+                None
+            }
+        }
+    }
+
+    /// Restart the stream, from the banner or automatically on a stall.
+    pub fn restart(&mut self, audio_manager: &mut Manager, bootstrap: &EngineBootstrap) {
+        let outcome = match audio_manager.restart_stream(bootstrap) {
+            Ok(()) => {
+                info!("Audio stream restarted");
+                RestartOutcome::Restarted
+            }
+            Err(e) => {
+                error!("Failed to restart the audio stream: {e}");
+                RestartOutcome::Failed(audio_error_message(&e))
+            }
+        };
+        // The new engine counts from zero; give it a full threshold to start.
+        self.watchdog.reset(Instant::now());
+        if let Some(stall) = &mut self.stall {
+            stall.restart = outcome;
+        }
+    }
+
+    pub fn dismiss(&mut self) {
+        self.stall = None;
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        self.stall.as_ref().map(AudioStall::view)
+    }
+}
+
+const fn threshold(stall_seconds: u32) -> Duration {
+    Duration::from_secs(stall_seconds.max(1) as u64)
+}
@@ -0,0 +1,177 @@
+use std::time::Instant;
+
+use iced::{Point, Size, Task, time::Duration, window};
+use log::{debug, error, info};
+
+use crate::settings::{Settings, WindowSettings};
+use rustortion_ui::messages::{Message, WindowMessage};
+
+/// How long the window has to stay put before its geometry is saved, so
+/// dragging it doesn't write the settings file on every step.
+pub const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Keeps [`Settings::window`] following the main window, saving it once the
+/// window settles and again on close.
+#[derive(Default)]
+pub struct WindowHandler {
+    /// When the geometry last changed without being saved.
+    changed_at: Option<Instant>,
+}
+
+impl WindowHandler {
+    pub const fn new() -> Self {
+        Self { changed_at: None }
+    }
+
+    pub fn handle(&mut self, message: WindowMessage, settings: &mut Settings) -> Task<Message> {
+        match message {
+            WindowMessage::Opened(id) => {
+                return window::monitor_size(id)
+                    .map(move |size| Message::Window(WindowMessage::MonitorSize(id, size)));
+            }
+            WindowMessage::MonitorSize(id, Some(monitor)) => {
+                return fit_to_monitor(id, &settings.window, monitor);
+            }
+            WindowMessage::MonitorSize(_, None) => {
+                debug!("Monitor size unknown, leaving the window where it opened");
+            }
+            WindowMessage::Moved(id, position) => {
+                return window::is_maximized(id).map(move |maximized| {
+                    Message::Window(WindowMessage::Changed {
+                        position: Some(position),
+                        size: None,
+                        maximized,
+                    })
+                });
+            }
+            // Minimizing reports an empty size on some platforms.
+            WindowMessage::Resized(_, size) if size.width < 1.0 || size.height < 1.0 => {}
+            WindowMessage::Resized(id, size) => {
+                return window::is_maximized(id).map(move |maximized| {
+                    Message::Window(WindowMessage::Changed {
+                        position: None,
+                        size: Some(size),
+                        maximized,
+                    })
+                });
+            }
+            WindowMessage::Changed {
+                position,
+                size,
+                maximized,
+            } => self.record(
+                &mut settings.window,
+                position,
+                size,
+                maximized,
+                Instant::now(),
+            ),
+            WindowMessage::SaveTick => {
+                if self.is_due(Instant::now()) {
+                    self.save(settings);
+                }
+            }
+            WindowMessage::CloseRequested(_) => {
+                if self.is_pending() {
+                    self.save(settings);
+                }
+                return iced::exit();
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Take in a move or resize that happened at `now`. A maximized
+    /// window's size and position aren't kept, so it un-maximizes to where
+    /// it was.
+    pub fn record(
+        &mut self,
+        saved: &mut WindowSettings,
+        position: Option<Point>,
+        size: Option<Size>,
+        maximized: bool,
+        now: Instant,
+    ) {
+        let mut changed = saved.clone();
+        changed.maximized = maximized;
+        if !maximized {
+            if let Some(position) = position {
+                changed.position = Some((position.x, position.y));
+            }
+            if let Some(size) = size {
+                changed.width = size.width;
+                changed.height = size.height;
+            }
+        }
+        if changed != *saved {
+            *saved = changed;
+            self.changed_at = Some(now);
+        }
+    }
+
+    /// There's a change not saved yet.
+    pub const fn is_pending(&self) -> bool {
+        self.changed_at.is_some()
+    }
+
+    /// The window has been still for [`SAVE_DELAY`] since its last change.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.changed_at
+            .is_some_and(|changed| now.duration_since(changed) >= SAVE_DELAY)
+    }
+
+    /// Nothing is written on a first run: that would mark it over before the
+    /// setup is done. The setup saves the geometry with everything else.
+    fn save(&mut self, settings: &Settings) {
+        self.changed_at = None;
+        if Settings::is_first_run() {
+            return;
+        }
+        if let Err(e) = settings.save() {
+            error!("Failed to save the window geometry: {e}");
+        }
+    }
+}
+
+/// How to open the main window: as it was left, or maximized if it hasn't
+/// been yet. Off-screen positions are caught once it's open; see
+/// [`WindowSettings::fitted_to`].
+pub fn open_settings(saved: &WindowSettings) -> window::Settings {
+    window::Settings {
+        size: Size::new(saved.width, saved.height),
+        position: saved.position.map_or(window::Position::Default, |(x, y)| {
+            window::Position::Specific(Point::new(x, y))
+        }),
+        maximized: saved.maximized,
+        min_size: Some(Size::new(
+            WindowSettings::MIN_WIDTH,
+            WindowSettings::MIN_HEIGHT,
+        )),
+        // Closing saves the geometry first; see `WindowMessage::CloseRequested`.
+        exit_on_close_request: false,
+        ..window::Settings::default()
+    }
+}
+
+/// Bring a window restored onto a monitor that's gone back onto the one it
+/// opened on. The move and resize come back as events and are saved.
+fn fit_to_monitor(id: window::Id, saved: &WindowSettings, monitor: Size) -> Task<Message> {
+    if saved.maximized {
+        return Task::none();
+    }
+    let fitted = saved.fitted_to(monitor.width, monitor.height);
+    if fitted == *saved {
+        return Task::none();
+    }
+
+    info!(
+        "Window doesn't fit the {}x{} monitor it opened on, moving it",
+        monitor.width, monitor.height
+    );
+    let resize = window::resize(id, Size::new(fitted.width, fitted.height));
+    match fitted.position {
+        Some((x, y)) => resize.chain(window::move_to(id, Point::new(x, y))),
+        None => resize,
+    }
+}
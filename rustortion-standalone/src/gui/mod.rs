@@ -1,28 +1,92 @@
 pub mod app;
 pub mod components;
 pub mod handlers;
+pub mod shell;
+
+use std::cell::Cell;
+
+use iced::Task;
+use log::error;
 
 pub use app::AmplifierApp;
 pub use rustortion_ui::messages::Message;
+pub use shell::Shell;
 
+use crate::gui::components::startup_error::{
+    StartupError, StartupErrorMessage, StartupErrorWindow,
+};
+use crate::gui::handlers::window::open_settings;
+use crate::gui::shell::waits_for_audio;
 use crate::settings::Settings;
 use rustortion_ui::font::{EMBEDDED_FONT, EMBEDDED_FONT_BYTES};
+use rustortion_ui::i18n;
+
+/// Open the app, or the error window if it can't run. A first run whose
+/// audio didn't open waits in the setup instead, retrying. The window opens
+/// where it was left.
+pub fn start(settings: Settings, first_run: bool) -> iced::Result {
+    // The startup error window is translated too.
+    i18n::set_language(settings.language);
+
+    let startup = match AmplifierApp::open(&settings) {
+        Ok(startup) => Some(startup),
+        Err(e) if waits_for_audio(&e, first_run) => {
+            error!("Audio didn't start, waiting in setup: {e}");
+            let window = open_settings(&settings.window);
+            let shell = Cell::new(Some(Shell::wait(settings, &e)));
+            return run(
+                move || (shell.take().expect("the app boots once"), Task::none()),
+                window,
+            );
+        }
+        Err(e) => {
+            error!("Startup failed: {e}");
+            return show_startup_error(&e);
+        }
+    };
+    // iced wants a boot function it could call again; it only calls it once.
+    let startup = Cell::new(startup);
+
+    let window = open_settings(&settings.window);
+    run(
+        move || {
+            let startup = startup.take().expect("the app boots once");
+            Shell::boot(settings.clone(), startup, first_run)
+        },
+        window,
+    )
+}
+
+fn run(
+    boot: impl Fn() -> (Shell, Task<Message>) + 'static,
+    window: iced::window::Settings,
+) -> iced::Result {
+    iced::application(boot, Shell::update, Shell::view)
+        .subscription(Shell::subscription)
+        .window(window)
+        .font(EMBEDDED_FONT_BYTES)
+        .default_font(EMBEDDED_FONT)
+        .theme(Shell::theme)
+        .title("Rustortion")
+        .run()
+}
+
+fn show_startup_error(error: &StartupError) -> iced::Result {
+    let window = StartupErrorWindow::new(error);
 
-pub fn start(settings: Settings) -> iced::Result {
     iced::application(
-        move || AmplifierApp::boot(settings.clone()),
-        AmplifierApp::update,
-        AmplifierApp::view,
+        move || window.clone(),
+        |_: &mut StartupErrorWindow, message| match message {
+            StartupErrorMessage::Quit => iced::exit(),
+        },
+        StartupErrorWindow::view,
     )
-    .subscription(AmplifierApp::subscription)
     .window(iced::window::Settings {
-        maximized: true,
-        min_size: Some(iced::Size::new(800.0, 600.0)),
+        size: iced::Size::new(520.0, 240.0),
         ..iced::window::Settings::default()
     })
     .font(EMBEDDED_FONT_BYTES)
     .default_font(EMBEDDED_FONT)
-    .theme(AmplifierApp::theme)
     .title("Rustortion")
     .run()
 }
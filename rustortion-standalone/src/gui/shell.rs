@@ -0,0 +1,107 @@
+use iced::widget::space;
+use iced::{Element, Subscription, Task, Theme, event, theme, time, time::Duration, window};
+use log::info;
+
+use crate::gui::app::{AmplifierApp, Startup};
+use crate::gui::components::dialogs::setup::SetupDialog;
+use crate::gui::components::startup_error::StartupError;
+use crate::settings::Settings;
+use crate::setup::AudioServer;
+use rustortion_ui::messages::{Message, SetupMessage, WindowMessage};
+
+/// How often a first run waiting for audio tries to open it again.
+const AUDIO_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The window's contents: the app, or on a first run whose audio couldn't
+/// open, the setup's first step until it can.
+pub enum Shell {
+    Waiting {
+        settings: Settings,
+        setup: SetupDialog,
+    },
+    Running(Box<AmplifierApp>),
+}
+
+impl Shell {
+    /// Boot the app if audio opened, opening the setup over it on a first
+    /// run.
+    pub fn boot(settings: Settings, startup: Startup, first_run: bool) -> (Self, Task<Message>) {
+        let (mut app, task) = AmplifierApp::boot(settings, startup);
+        if first_run {
+            app.open_setup();
+        }
+        (Self::Running(Box::new(app)), task)
+    }
+
+    /// Hold on the setup's audio step, showing why audio didn't open.
+    pub fn wait(settings: Settings, error: &StartupError) -> Self {
+        let mut setup = SetupDialog::new();
+        setup.hold(
+            AudioServer::detect(),
+            error.user_message(),
+            error.to_string(),
+        );
+        Self::Waiting { settings, setup }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match self {
+            Self::Running(app) => app.update(message),
+            Self::Waiting { settings, setup } => match message {
+                Message::Setup(SetupMessage::Retry) => match AmplifierApp::open(settings) {
+                    Ok(startup) => {
+                        info!("Audio is up, starting");
+                        let (shell, task) = Self::boot(settings.clone(), startup, true);
+                        *self = shell;
+                        task
+                    }
+                    Err(e) => {
+                        setup.hold(AudioServer::detect(), e.user_message(), e.to_string());
+                        Task::none()
+                    }
+                },
+                Message::Setup(SetupMessage::Quit)
+                | Message::Window(WindowMessage::CloseRequested(_)) => iced::exit(),
+                _ => Task::none(),
+            },
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        match self {
+            Self::Running(app) => app.view(),
+            Self::Waiting { setup, .. } => setup
+                .view()
+                .map_or_else(|| space().into(), |dialog| dialog.map(Message::Setup)),
+        }
+    }
+
+    pub fn theme(&self) -> Theme {
+        match self {
+            Self::Running(app) => app.theme(),
+            Self::Waiting { settings, .. } => settings.theme.resolve(theme::Mode::None),
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        match self {
+            Self::Running(app) => app.subscription(),
+            Self::Waiting { .. } => Subscription::batch([
+                time::every(AUDIO_RETRY_INTERVAL).map(|_| Message::Setup(SetupMessage::Retry)),
+                // The window doesn't close by itself; see `open_settings`.
+                event::listen_with(|event, _status, id| match event {
+                    iced::Event::Window(window::Event::CloseRequested) => {
+                        Some(Message::Window(WindowMessage::CloseRequested(id)))
+                    }
+                    _ => None,
+                }),
+            ]),
+        }
+    }
+}
+
+/// Whether a failed startup should wait in the setup instead of showing the
+/// error window: only audio, and only on a first run.
+pub const fn waits_for_audio(error: &StartupError, first_run: bool) -> bool {
+    first_run && matches!(error, StartupError::Audio(_))
+}
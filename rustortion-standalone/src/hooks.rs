@@ -0,0 +1,432 @@
+//! User commands run on app events, for scripting the studio around the amp:
+//! start a DAW take when recording starts, tag a file when it stops, and so
+//! on. Commands are started in the order of their events on a worker
+//! thread, each then waited on by a thread of its own, so a slow or
+//! long-running one never holds up the GUI, the audio or the next hook.
+//!
+//! Each command runs through the shell (`sh -c`, or `cmd /C` on Windows)
+//! with the event's detail as its first argument, and these variables set:
+//!
+//! - `RUSTORTION_EVENT`: the event, e.g. `recording_started`
+//! - `RUSTORTION_RECORDING`: the take's WAV path, for recording events
+//! - `RUSTORTION_PRESET`: the preset's name, for preset changes
+//! - `RUSTORTION_REASON`: what was seen, for audio disconnects
+
+use crossbeam::channel::{Sender, unbounded};
+use log::{debug, info, warn};
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+
+use crate::settings::HookSettings;
+use rustortion_core::hooks::EventKind;
+
+/// An event, with the detail its hook is given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookEvent {
+    RecordingStarted { path: PathBuf },
+    RecordingStopped { path: PathBuf },
+    PresetChanged { name: String },
+    AudioDisconnected { reason: String },
+}
+
+impl HookEvent {
+    pub const fn kind(&self) -> EventKind {
+        match self {
+            Self::RecordingStarted { .. } => EventKind::RecordingStarted,
+            Self::RecordingStopped { .. } => EventKind::RecordingStopped,
+            Self::PresetChanged { .. } => EventKind::PresetChanged,
+            Self::AudioDisconnected { .. } => EventKind::AudioDisconnected,
+        }
+    }
+
+    /// The command's first argument.
+    pub fn argument(&self) -> String {
+        match self {
+            Self::RecordingStarted { path } | Self::RecordingStopped { path } => {
+                path.display().to_string()
+            }
+            Self::PresetChanged { name } => name.clone(),
+            Self::AudioDisconnected { reason } => reason.clone(),
+        }
+    }
+
+    /// Variables set for the command, on top of the app's environment.
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let detail = match self {
+            Self::RecordingStarted { .. } | Self::RecordingStopped { .. } => "RUSTORTION_RECORDING",
+            Self::PresetChanged { .. } => "RUSTORTION_PRESET",
+            Self::AudioDisconnected { .. } => "RUSTORTION_REASON",
+        };
+        vec![
+            ("RUSTORTION_EVENT", self.kind().name().to_owned()),
+            (detail, self.argument()),
+        ]
+    }
+}
+
+/// A hook to run: the configured command line, and what the event hands it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookCommand {
+    pub kind: EventKind,
+    pub command: String,
+    pub argument: String,
+    pub env: Vec<(&'static str, String)>,
+}
+
+/// Starts hook commands, on the dispatcher's worker thread. Abstracted so
+/// the dispatcher can be tested without spawning processes.
+pub trait HookExecutor: Send + 'static {
+    /// Start `command` without waiting for it to finish.
+    fn spawn(&mut self, command: &HookCommand) -> io::Result<()>;
+}
+
+/// Runs hooks through the system shell. Each is reaped by a thread of its
+/// own, which logs its output once it exits.
+pub struct ShellExecutor;
+
+impl HookExecutor for ShellExecutor {
+    fn spawn(&mut self, command: &HookCommand) -> io::Result<()> {
+        let child = shell(command)
+            .envs(command.env.iter().map(|(key, value)| (*key, value)))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let kind = command.kind;
+        thread::Builder::new()
+            .name(format!("hook-{}", kind.name()))
+            .spawn(move || log_exit(kind, child.wait_with_output()))?;
+        Ok(())
+    }
+}
+
+// The argument goes in as `$1` rather than being pasted into the command
+// line, so a preset name or path is never parsed by the shell.
+#[cfg(not(windows))]
+fn shell(command: &HookCommand) -> Command {
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(format!("{} \"$1\"", command.command))
+        .arg("rustortion-hook")
+        .arg(&command.argument);
+    shell
+}
+
+// `cmd` parses its own command line rather than taking an argument list,
+// so the line is built here, with the argument escaped for it.
+#[cfg(windows)]
+fn shell(command: &HookCommand) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    let mut shell = Command::new("cmd");
+    shell.raw_arg(format!(
+        "/S /C \"{} {}\"",
+        command.command,
+        cmd_quote(&command.argument)
+    ));
+    shell
+}
+
+/// `arg` as one argument for a program started by `cmd /C`: quoted for the
+/// program's C runtime, then with every `cmd` metacharacter, the quotes
+/// included, escaped with `^` so nothing in it is expanded or redirected.
+#[cfg(windows)]
+fn cmd_quote(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+        } else {
+            if c == '"' {
+                quoted.extend(std::iter::repeat_n('\\', backslashes + 1));
+            }
+            backslashes = 0;
+        }
+        quoted.push(c);
+    }
+    quoted.extend(std::iter::repeat_n('\\', backslashes));
+    quoted.push('"');
+
+    let mut escaped = String::with_capacity(quoted.len() * 2);
+    for c in quoted.chars() {
+        if matches!(c, '(' | ')' | '%' | '!' | '^' | '"' | '<' | '>' | '&' | '|') {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Queues the configured hook for each event it's told about and starts
+/// them in order on a worker thread. The worker ends once the dispatcher is
+/// dropped and the queue is empty; commands still running are left to
+/// finish.
+pub struct HookDispatcher {
+    enabled: bool,
+    commands: HashMap<EventKind, String>,
+    queue: Sender<HookCommand>,
+}
+
+impl HookDispatcher {
+    pub fn new(settings: &HookSettings) -> Self {
+        Self::with_executor(settings, ShellExecutor)
+    }
+
+    pub fn with_executor<E: HookExecutor>(settings: &HookSettings, mut executor: E) -> Self {
+        let (queue, commands) = unbounded::<HookCommand>();
+        let spawned = thread::Builder::new().name("hooks".into()).spawn(move || {
+            for command in commands {
+                run(&mut executor, &command);
+            }
+        });
+        if let Err(e) = spawned {
+            warn!("Failed to start the hook thread; hooks won't run: {e}");
+        }
+
+        let mut dispatcher = Self {
+            enabled: false,
+            commands: HashMap::new(),
+            queue,
+        };
+        dispatcher.configure(settings);
+        dispatcher
+    }
+
+    /// Pick up hooks edited or switched on or off in the settings.
+    pub fn configure(&mut self, settings: &HookSettings) {
+        self.enabled = settings.enabled;
+        self.commands = settings
+            .commands
+            .iter()
+            .filter(|(_, command)| !command.trim().is_empty())
+            .map(|(kind, command)| (*kind, command.trim().to_owned()))
+            .collect();
+    }
+
+    /// Queue the hook for `event`, if hooks are on and one is set for it.
+    /// Returns whether one was queued.
+    pub fn dispatch(&self, event: &HookEvent) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let kind = event.kind();
+        let Some(command) = self.commands.get(&kind) else {
+            return false;
+        };
+        let command = HookCommand {
+            kind,
+            command: command.clone(),
+            argument: event.argument(),
+            env: event.env(),
+        };
+        if self.queue.send(command).is_err() {
+            warn!("Hook thread isn't running; skipped the {kind} hook");
+            return false;
+        }
+        true
+    }
+}
+
+fn run(executor: &mut impl HookExecutor, command: &HookCommand) {
+    let kind = command.kind;
+    debug!("Running {kind} hook: {}", command.command);
+    if let Err(e) = executor.spawn(command) {
+        warn!("Failed to run {kind} hook '{}': {e}", command.command);
+    }
+}
+
+/// Log how a hook's command finished, from its reaper thread.
+fn log_exit(kind: EventKind, output: io::Result<Output>) {
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Lost track of the {kind} hook: {e}");
+            return;
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let (stdout, stderr) = (stdout.trim(), stderr.trim());
+    if !stdout.is_empty() {
+        debug!("{kind} hook output: {stdout}");
+    }
+    match output.status.code() {
+        Some(0) => info!("{kind} hook finished"),
+        Some(code) => warn!("{kind} hook exited with status {code}: {stderr}"),
+        None => warn!("{kind} hook was killed: {stderr}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam::channel::Receiver;
+    use std::time::Duration;
+
+    /// Hands every command it's asked to start back to the test.
+    struct StubExecutor {
+        ran: Sender<HookCommand>,
+        result: io::Result<()>,
+    }
+
+    impl HookExecutor for StubExecutor {
+        fn spawn(&mut self, command: &HookCommand) -> io::Result<()> {
+            self.ran.send(command.clone()).unwrap();
+            match &self.result {
+                Ok(()) => Ok(()),
+                Err(e) => Err(io::Error::new(e.kind(), e.to_string())),
+            }
+        }
+    }
+
+    fn dispatcher(
+        enabled: bool,
+        commands: &[(EventKind, &str)],
+    ) -> (HookDispatcher, Receiver<HookCommand>) {
+        let (ran, ran_rx) = unbounded();
+        let settings = HookSettings {
+            enabled,
+            commands: commands
+                .iter()
+                .map(|(kind, command)| (*kind, (*command).to_owned()))
+                .collect(),
+        };
+        let executor = StubExecutor {
+            ran,
+            result: Ok(()),
+        };
+        (HookDispatcher::with_executor(&settings, executor), ran_rx)
+    }
+
+    fn next(ran: &Receiver<HookCommand>) -> HookCommand {
+        ran.recv_timeout(Duration::from_secs(5))
+            .expect("hook didn't run")
+    }
+
+    #[test]
+    fn runs_the_hook_with_the_event_detail() {
+        let (hooks, ran) = dispatcher(true, &[(EventKind::RecordingStarted, " notify.sh ")]);
+        let event = HookEvent::RecordingStarted {
+            path: PathBuf::from("/takes/take 1.wav"),
+        };
+        assert!(hooks.dispatch(&event));
+
+        let command = next(&ran);
+        assert_eq!(command.kind, EventKind::RecordingStarted);
+        assert_eq!(command.command, "notify.sh");
+        assert_eq!(command.argument, "/takes/take 1.wav");
+        assert_eq!(
+            command.env,
+            vec![
+                ("RUSTORTION_EVENT", "recording_started".to_owned()),
+                ("RUSTORTION_RECORDING", "/takes/take 1.wav".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_runs_while_disabled_or_unset() {
+        let preset = HookEvent::PresetChanged {
+            name: "Lead".into(),
+        };
+        let (mut hooks, ran) = dispatcher(false, &[(EventKind::PresetChanged, "echo")]);
+        assert!(!hooks.dispatch(&preset));
+
+        hooks.configure(&HookSettings {
+            enabled: true,
+            commands: HashMap::from([
+                (EventKind::PresetChanged, "echo".to_owned()),
+                (EventKind::RecordingStopped, "   ".to_owned()),
+            ]),
+        });
+        let stopped = HookEvent::RecordingStopped {
+            path: PathBuf::from("take.wav"),
+        };
+        assert!(!hooks.dispatch(&stopped));
+        assert!(!hooks.dispatch(&HookEvent::AudioDisconnected {
+            reason: "stalled".into()
+        }));
+        assert!(hooks.dispatch(&preset));
+        assert_eq!(next(&ran).argument, "Lead");
+        assert!(ran.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+
+    #[test]
+    fn hooks_run_in_the_order_of_their_events() {
+        let (hooks, ran) = dispatcher(
+            true,
+            &[
+                (EventKind::RecordingStarted, "start"),
+                (EventKind::RecordingStopped, "stop"),
+            ],
+        );
+        let path = PathBuf::from("take.wav");
+        hooks.dispatch(&HookEvent::RecordingStarted { path: path.clone() });
+        hooks.dispatch(&HookEvent::RecordingStopped { path });
+
+        assert_eq!(next(&ran).command, "start");
+        assert_eq!(next(&ran).command, "stop");
+    }
+
+    #[test]
+    fn a_failing_hook_does_not_stop_the_next() {
+        let (ran, ran_rx) = unbounded();
+        let executor = StubExecutor {
+            ran,
+            result: Err(io::Error::new(io::ErrorKind::NotFound, "no such command")),
+        };
+        let settings = HookSettings {
+            enabled: true,
+            commands: HashMap::from([(EventKind::PresetChanged, "missing".to_owned())]),
+        };
+        let hooks = HookDispatcher::with_executor(&settings, executor);
+        for name in ["A", "B"] {
+            hooks.dispatch(&HookEvent::PresetChanged { name: name.into() });
+        }
+        assert_eq!(next(&ran_rx).argument, "A");
+        assert_eq!(next(&ran_rx).argument, "B");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_long_running_hook_does_not_hold_up_the_next() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("stopped");
+        let settings = HookSettings {
+            enabled: true,
+            commands: HashMap::from([
+                (EventKind::RecordingStarted, "sleep 3; :".to_owned()),
+                (EventKind::RecordingStopped, "touch".to_owned()),
+            ]),
+        };
+        let hooks = HookDispatcher::new(&settings);
+        hooks.dispatch(&HookEvent::RecordingStarted {
+            path: PathBuf::from("take.wav"),
+        });
+        hooks.dispatch(&HookEvent::RecordingStopped {
+            path: marker.clone(),
+        });
+
+        let started = std::time::Instant::now();
+        while !marker.exists() {
+            assert!(
+                started.elapsed() < Duration::from_secs(2),
+                "the stop hook waited for the start hook"
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn cmd_arguments_are_quoted_and_escaped() {
+        assert_eq!(cmd_quote("take 1.wav"), "^\"take 1.wav^\"");
+        assert_eq!(cmd_quote("a&b %PATH%"), "^\"a^&b ^%PATH^%^\"");
+        assert_eq!(cmd_quote(r#"say "hi"\"#), r#"^"say \^"hi\^"\\^""#);
+    }
+}
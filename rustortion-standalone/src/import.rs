@@ -0,0 +1,145 @@
+//! Files dropped onto the window: `.wav` impulse responses are copied into
+//! the IR directory and `.json` presets imported into the preset directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use log::{info, warn};
+
+use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::preset::Manager as PresetManager;
+
+/// Subfolder of the IR directory dropped IRs are copied into.
+pub const DROPPED_IR_DIR: &str = "Dropped";
+
+/// What to import and where to, captured from the GUI when files are dropped.
+#[derive(Debug, Clone)]
+pub struct ImportRequest {
+    pub paths: Vec<PathBuf>,
+    pub ir_dir: PathBuf,
+    pub preset_dir: PathBuf,
+    /// The engine's rate, to check IRs the way loading them would.
+    pub sample_rate: usize,
+    pub ir_max_seconds: f32,
+}
+
+/// What came of an import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Imported IRs by name in the IR list, e.g. `Dropped/cab.wav`, in drop
+    /// order.
+    pub irs: Vec<String>,
+    /// Imported presets by the name they were saved as.
+    pub presets: Vec<String>,
+    /// Files that weren't imported, with the reason.
+    pub failures: Vec<(String, String)>,
+}
+
+impl ImportReport {
+    pub const fn is_empty(&self) -> bool {
+        self.irs.is_empty() && self.presets.is_empty() && self.failures.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DroppedKind {
+    Ir,
+    Preset,
+}
+
+impl DroppedKind {
+    fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "wav" => Some(Self::Ir),
+            "json" => Some(Self::Preset),
+            _ => None,
+        }
+    }
+}
+
+/// Import every file in `request`. IRs are decoded before they're copied, so
+/// a file that can't be used never lands in the IR directory. Blocking; run
+/// it off the UI thread.
+pub fn import_dropped(request: &ImportRequest) -> ImportReport {
+    let mut report = ImportReport::default();
+    let mut irs = None;
+    let mut presets = None;
+
+    for path in &request.paths {
+        let file = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        let result = match DroppedKind::of(path) {
+            Some(DroppedKind::Ir) => {
+                let loader = irs.get_or_insert_with(|| open_dropped_dir(request));
+                import_ir(loader, path, &request.ir_dir).map(|name| report.irs.push(name))
+            }
+            Some(DroppedKind::Preset) => {
+                let manager = presets.get_or_insert_with(|| {
+                    PresetManager::new(&request.preset_dir).map_err(|e| e.to_string())
+                });
+                match manager {
+                    Ok(manager) => manager
+                        .import_preset(path)
+                        .map(|name| report.presets.push(name)),
+                    Err(e) => Err(anyhow::anyhow!("{e}")),
+                }
+            }
+            None => Err(anyhow::anyhow!("not a .wav IR or a .json preset")),
+        };
+
+        match result {
+            Ok(()) => info!("Imported {}", path.display()),
+            Err(e) => {
+                warn!("Failed to import {}: {e:#}", path.display());
+                report.failures.push((file, format!("{e:#}")));
+            }
+        }
+    }
+
+    report
+}
+
+/// A loader over the dropped-IR folder, created if needed, to decode each
+/// file with the same checks as loading it.
+fn open_dropped_dir(request: &ImportRequest) -> Result<IrLoader, String> {
+    IrLoader::with_max_seconds(
+        &request.ir_dir.join(DROPPED_IR_DIR),
+        request.sample_rate,
+        request.ir_max_seconds,
+    )
+    .map_err(|e| format!("{e:#}"))
+}
+
+fn import_ir(loader: &Result<IrLoader, String>, path: &Path, ir_dir: &Path) -> Result<String> {
+    let loader = loader.as_ref().map_err(|e| anyhow::anyhow!("{e}"))?;
+    loader.load_ir(path)?;
+
+    let stem = path
+        .file_stem()
+        .map_or_else(|| "ir".into(), |stem| stem.to_string_lossy());
+    // The IR scan only picks up a lowercase extension.
+    let target = unique_path(&ir_dir.join(DROPPED_IR_DIR), &stem, "wav");
+    fs::copy(path, &target).with_context(|| format!("Failed to copy to {}", target.display()))?;
+
+    let name = target
+        .strip_prefix(ir_dir)
+        .unwrap_or(&target)
+        .to_string_lossy()
+        .replace('\\', "/");
+    Ok(name)
+}
+
+/// `dir/stem.extension`, or the first of `stem-2`, `stem-3`, … not taken.
+pub fn unique_path(dir: &Path, stem: &str, extension: &str) -> PathBuf {
+    let mut path = dir.join(format!("{stem}.{extension}"));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{stem}-{n}.{extension}"));
+        n += 1;
+    }
+    path
+}
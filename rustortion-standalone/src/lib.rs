@@ -38,10 +38,20 @@
 #![allow(clippy::redundant_pub_crate, clippy::significant_drop_tightening)]
 
 pub mod audio;
+#[cfg(feature = "gui")]
 pub mod backend;
+#[cfg(feature = "gui")]
 pub mod gui;
+#[cfg(feature = "gui")]
 pub use rustortion_ui::hotkey;
+#[cfg(feature = "gui")]
 pub use rustortion_ui::i18n;
+#[cfg(feature = "gui")]
 pub use rustortion_ui::tr;
+pub mod hooks;
+pub mod import;
 pub mod midi;
+pub mod remote;
 pub mod settings;
+pub mod setup;
+pub mod watcher;
@@ -1,10 +1,100 @@
 use arc_swap::ArcSwap;
-use crossbeam::channel::{Receiver, Sender, bounded};
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender, bounded};
 use log::{debug, error, info, warn};
 use midir::{MidiInput, MidiInputConnection};
+use rustortion_core::mapping::{MappingMode, Trigger};
+#[cfg(feature = "gui")]
+use rustortion_ui::mappings::{Binding, BindingSource, ModeLabel};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the manager thread checks the connected device is still there.
+const LIVENESS_INTERVAL: Duration = Duration::from_secs(1);
+/// Wait after a first failed reconnection attempt, doubled after each one.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// What a mapped MIDI input does.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MidiAction {
+    /// Load `preset_name`.
+    #[default]
+    LoadPreset,
+    /// Mute and show the tuner between a press and its release (Note On/Off,
+    /// or a CC crossing 64).
+    HoldTuner,
+    /// Switch back to the previously selected preset, on press only.
+    PreviousPreset,
+    /// Step to the next song of the running setlist, on press only.
+    NextSong,
+    /// Step back to the previous song of the running setlist, on press only.
+    PreviousSong,
+    /// Put the amp into standby, or take it out, on press only.
+    ToggleStandby,
+    /// Bypass the IR cabinet, or bring it back, on press only.
+    ToggleIrBypass,
+}
+
+impl MidiAction {
+    /// The on/off setting this action switches, for the actions a
+    /// [`MappingMode`] applies to.
+    pub const fn switch(self) -> Option<MidiSwitch> {
+        match self {
+            Self::HoldTuner => Some(MidiSwitch::Tuner),
+            Self::ToggleStandby => Some(MidiSwitch::Standby),
+            Self::ToggleIrBypass => Some(MidiSwitch::IrBypass),
+            Self::LoadPreset | Self::PreviousPreset | Self::NextSong | Self::PreviousSong => None,
+        }
+    }
+}
+
+/// An on/off setting a mapping can switch. "On" is the tuner showing, the
+/// amp in standby, or the IR bypassed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MidiSwitch {
+    Tuner,
+    Standby,
+    IrBypass,
+}
+
+/// Which way a mapped input just went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Pressed,
+    Released,
+}
+
+/// What the app should do in response to a mapped MIDI input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MidiTrigger {
+    LoadPreset(String),
+    HoldTuner {
+        pressed: bool,
+    },
+    PreviousPreset,
+    NextSong,
+    PreviousSong,
+    /// Turn the switch on, remembering how it was; see [`SwitchLatch`].
+    Engage(MidiSwitch),
+    /// Put the switch back to how it was before it was engaged.
+    Disengage(MidiSwitch),
+    /// Turn the switch over.
+    Flip(MidiSwitch),
+}
+
+impl MidiTrigger {
+    /// The setting this switches, for the triggers [`SwitchLatch`] resolves.
+    pub const fn switch(&self) -> Option<MidiSwitch> {
+        match self {
+            Self::Engage(switch) | Self::Disengage(switch) | Self::Flip(switch) => Some(*switch),
+            _ => None,
+        }
+    }
+}
 
 /// A MIDI input mapping that associates a MIDI message with a preset
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -17,6 +107,12 @@ pub struct MidiMapping {
     pub preset_name: String,
     /// Human-readable description of this mapping
     pub description: String,
+    #[serde(default)]
+    pub action: MidiAction,
+    /// How presses and releases switch the action's setting. Only read for
+    /// actions with one; see [`MidiAction::switch`].
+    #[serde(default)]
+    pub mode: MappingMode,
 }
 
 impl MidiMapping {
@@ -26,6 +122,91 @@ impl MidiMapping {
             control,
             preset_name,
             description: format!("Ch{} CC/Note {}", channel + 1, control),
+            action: MidiAction::LoadPreset,
+            mode: MappingMode::Trigger,
+        }
+    }
+
+    /// A mapping that holds the tuner on while the input is pressed.
+    pub fn hold_tuner(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::HoldTuner,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    /// A mapping that switches back to the previous preset.
+    pub fn previous_preset(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::PreviousPreset,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    /// A mapping that steps to the next song of the setlist.
+    pub fn next_song(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::NextSong,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    /// A mapping that steps back to the previous song of the setlist.
+    pub fn previous_song(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::PreviousSong,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    /// A mapping that toggles standby.
+    pub fn toggle_standby(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::ToggleStandby,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    /// A mapping that toggles the IR bypass.
+    pub fn toggle_ir_bypass(channel: u8, control: u8) -> Self {
+        Self {
+            action: MidiAction::ToggleIrBypass,
+            ..Self::new(channel, control, String::new())
+        }
+    }
+
+    pub const fn with_mode(mut self, mode: MappingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// What this mapping asks for at `edge`, if anything. In trigger mode a
+    /// footswitch's release would swap straight back, so everything but
+    /// hold-to-tune ignores it.
+    pub fn trigger(&self, edge: Edge) -> Option<MidiTrigger> {
+        let pressed = edge == Edge::Pressed;
+        if let Some(switch) = self.action.switch() {
+            return match (self.mode, edge) {
+                (MappingMode::Trigger, _) if self.action == MidiAction::HoldTuner => {
+                    Some(MidiTrigger::HoldTuner { pressed })
+                }
+                (MappingMode::Trigger | MappingMode::Toggle, Edge::Pressed) => {
+                    Some(MidiTrigger::Flip(switch))
+                }
+                (MappingMode::Trigger | MappingMode::Toggle, Edge::Released) => None,
+                (MappingMode::Momentary, Edge::Pressed) => Some(MidiTrigger::Engage(switch)),
+                (MappingMode::Momentary, Edge::Released) => Some(MidiTrigger::Disengage(switch)),
+            };
+        }
+
+        match self.action {
+            // Both edges, as preset mappings always have: a controller that
+            // only sends low CC values still switches presets.
+            MidiAction::LoadPreset => Some(MidiTrigger::LoadPreset(self.preset_name.clone())),
+            MidiAction::PreviousPreset => pressed.then_some(MidiTrigger::PreviousPreset),
+            MidiAction::NextSong => pressed.then_some(MidiTrigger::NextSong),
+            MidiAction::PreviousSong => pressed.then_some(MidiTrigger::PreviousSong),
+            MidiAction::HoldTuner | MidiAction::ToggleStandby | MidiAction::ToggleIrBypass => None,
         }
     }
 
@@ -33,6 +214,43 @@ impl MidiMapping {
     pub const fn matches(&self, channel: u8, control: u8) -> bool {
         self.channel == channel && self.control == control
     }
+
+    /// The input this mapping listens to, for comparing with other mappings.
+    pub const fn input_trigger(&self) -> Trigger {
+        Trigger::midi(self.channel, self.control)
+    }
+
+    /// What the mapping does, for display: the preset name or the action,
+    /// with the mode where it isn't the action's own.
+    #[cfg(feature = "gui")]
+    pub fn target_label(&self) -> String {
+        let label = match self.action {
+            MidiAction::LoadPreset => return self.preset_name.clone(),
+            MidiAction::HoldTuner => crate::tr!(hold_tuner),
+            MidiAction::PreviousPreset => crate::tr!(previous_preset),
+            MidiAction::NextSong => crate::tr!(next_song),
+            MidiAction::PreviousSong => crate::tr!(previous_song),
+            MidiAction::ToggleStandby => crate::tr!(toggle_standby),
+            MidiAction::ToggleIrBypass => crate::tr!(toggle_ir_bypass),
+        };
+        if self.action.switch().is_some() && self.mode != MappingMode::Trigger {
+            format!("{label} ({})", ModeLabel(self.mode))
+        } else {
+            label.to_owned()
+        }
+    }
+
+    /// This mapping as the `index`th MIDI mapping in the mappings overview.
+    #[cfg(feature = "gui")]
+    pub fn binding(&self, index: usize) -> Binding {
+        Binding {
+            source: BindingSource::Midi,
+            index,
+            trigger: self.input_trigger(),
+            description: self.description.clone(),
+            target: self.target_label(),
+        }
+    }
 }
 
 /// Represents a detected MIDI input
@@ -43,6 +261,84 @@ pub struct MidiInputEvent {
     pub control: u8,
     pub value: u8,
     pub raw_bytes: Vec<u8>,
+    /// When the message was parsed on the MIDI thread, the start of a preset
+    /// switch's latency.
+    pub received: Instant,
+}
+
+impl MidiInputEvent {
+    /// The "let go" half of a press: a Note Off (including Note On with
+    /// velocity 0), or a CC below 64 as sustain-style footswitches send it.
+    pub const fn is_release(&self) -> bool {
+        match self.message_type {
+            MidiMessageType::NoteOff => true,
+            MidiMessageType::ControlChange => self.value < 64,
+            _ => false,
+        }
+    }
+
+    /// Whether the input has a release half at all. A program change is
+    /// only ever a press.
+    pub const fn has_release(&self) -> bool {
+        matches!(
+            self.message_type,
+            MidiMessageType::NoteOn | MidiMessageType::NoteOff | MidiMessageType::ControlChange
+        )
+    }
+
+    /// The input this event would be learned as. Presses and releases of a
+    /// note or CC all give the same one.
+    pub const fn input_trigger(&self) -> Trigger {
+        Trigger::midi(self.channel, self.control)
+    }
+}
+
+/// Turns MIDI messages into press and release edges per input, so a
+/// controller resending the same value doesn't press again.
+#[derive(Debug, Default)]
+pub struct EdgeTracker {
+    /// Whether each note or CC was last seen pressed.
+    pressed: HashMap<(u8, u8), bool>,
+}
+
+impl EdgeTracker {
+    /// The edge `event` makes, or `None` if its input was already that way.
+    pub fn edge(&mut self, event: &MidiInputEvent) -> Option<Edge> {
+        if !event.has_release() {
+            return Some(Edge::Pressed);
+        }
+        let pressed = !event.is_release();
+        if self.pressed.insert((event.channel, event.control), pressed) == Some(pressed) {
+            return None;
+        }
+        Some(if pressed {
+            Edge::Pressed
+        } else {
+            Edge::Released
+        })
+    }
+}
+
+/// What momentary mappings switched from, so their release can put it back.
+#[derive(Debug, Default)]
+pub struct SwitchLatch {
+    before: HashMap<MidiSwitch, bool>,
+}
+
+impl SwitchLatch {
+    /// Where `switch` should go for `trigger`, from `current`. `None` leaves
+    /// it as it is. Engaged twice before a release, the first state is kept.
+    pub fn resolve(&mut self, trigger: &MidiTrigger, current: bool) -> Option<bool> {
+        match *trigger {
+            MidiTrigger::Engage(switch) => {
+                self.before.entry(switch).or_insert(current);
+                Some(true)
+            }
+            MidiTrigger::Disengage(switch) => self.before.remove(&switch),
+            MidiTrigger::Flip(_) => Some(!current),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -84,8 +380,11 @@ impl std::fmt::Display for MidiInputEvent {
 pub enum MidiEvent {
     /// A MIDI input was received
     Input(MidiInputEvent),
-    /// Connection was lost
+    /// The device went away or couldn't be opened. The manager reconnects
+    /// on its own once it shows up again.
     Disconnected,
+    /// The device came back and was reconnected, under this port name.
+    Reconnected(String),
     /// Error occurred
     Error(String),
 }
@@ -100,10 +399,28 @@ pub enum MidiCommand {
     Shutdown,
 }
 
+/// Signalled by the MIDI thread whenever it queues input, so the GUI can
+/// poll for it straight away rather than on its next tick.
+#[derive(Clone)]
+pub struct MidiWakeups(Receiver<()>);
+
+impl MidiWakeups {
+    /// Block until input arrives. `false` once the MIDI manager has stopped.
+    pub fn wait(&self) -> bool {
+        self.0.recv().is_ok()
+    }
+}
+
+/// Every copy waits on the same channel, so they all identify as one.
+impl Hash for MidiWakeups {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
+
 /// Handle for communicating with the MIDI manager from the main thread
 pub struct MidiHandle {
     command_sender: Sender<MidiCommand>,
     event_receiver: Receiver<MidiEvent>,
+    wake_receiver: Receiver<()>,
     mappings: Arc<ArcSwap<Vec<MidiMapping>>>,
 }
 
@@ -127,6 +444,11 @@ impl MidiHandle {
         self.event_receiver.try_recv().ok()
     }
 
+    /// Wakeups for each MIDI message queued for [`Self::try_recv`].
+    pub fn wakeups(&self) -> MidiWakeups {
+        MidiWakeups(self.wake_receiver.clone())
+    }
+
     pub fn set_mappings(&self, mappings: Vec<MidiMapping>) {
         self.mappings.store(Arc::new(mappings));
     }
@@ -135,15 +457,14 @@ impl MidiHandle {
         self.mappings.load().as_ref().clone()
     }
 
-    /// Check if a MIDI input matches any mapping and return the preset name
-    pub fn check_mapping(&self, event: &MidiInputEvent) -> Option<String> {
+    /// Check if a MIDI input matches any mapping and return what it
+    /// triggers at `edge`; see [`EdgeTracker`].
+    pub fn check_mapping(&self, event: &MidiInputEvent, edge: Edge) -> Option<MidiTrigger> {
         let mappings = self.mappings.load();
-        for mapping in mappings.iter() {
-            if mapping.matches(event.channel, event.control) {
-                return Some(mapping.preset_name.clone());
-            }
-        }
-        None
+        mappings
+            .iter()
+            .find(|m| m.matches(event.channel, event.control))
+            .and_then(|m| m.trigger(edge))
     }
 }
 
@@ -153,12 +474,146 @@ impl Drop for MidiHandle {
     }
 }
 
+/// Source of MIDI input port names, so hot-plug handling can be tested
+/// without devices.
+pub trait PortLister {
+    fn port_names(&self) -> Vec<String>;
+}
+
+/// The system's MIDI input ports, through midir.
+pub struct MidirPorts;
+
+impl PortLister for MidirPorts {
+    fn port_names(&self) -> Vec<String> {
+        match MidiInput::new("rustortion-scan") {
+            Ok(midi_in) => midi_in
+                .ports()
+                .iter()
+                .filter_map(|p| midi_in.port_name(p).ok())
+                .collect(),
+            Err(e) => {
+                error!("Failed to create MIDI input for scanning: {e}");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// What the manager thread should do after a liveness check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WatchAction {
+    /// The connected port is gone; drop the connection.
+    Lost,
+    /// The wanted device is back under this port name; try to open it.
+    Reconnect(String),
+}
+
+/// Follows the controller the user picked across hot-plugs: notices when
+/// its port disappears and says when to try it again once it's back.
+#[derive(Debug)]
+struct ConnectionWatch {
+    /// Controller the user asked for, as saved in the settings.
+    wanted: Option<String>,
+    /// Port open now, if any.
+    connected: Option<String>,
+    /// No reconnection attempt before this.
+    next_attempt: Instant,
+    /// Wait after the next failed attempt.
+    backoff: Duration,
+}
+
+impl ConnectionWatch {
+    const fn new(now: Instant) -> Self {
+        Self {
+            wanted: None,
+            connected: None,
+            next_attempt: now,
+            backoff: INITIAL_BACKOFF,
+        }
+    }
+
+    /// The user picked a controller, or `None` to stay disconnected.
+    fn want(&mut self, device: Option<String>, now: Instant) {
+        self.wanted = device;
+        self.connected = None;
+        self.reset_backoff(now);
+    }
+
+    fn connected(&mut self, port: String, now: Instant) {
+        self.connected = Some(port);
+        self.reset_backoff(now);
+    }
+
+    fn attempt_failed(&mut self, now: Instant) {
+        self.next_attempt = now + self.backoff;
+        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+    }
+
+    const fn reset_backoff(&mut self, now: Instant) {
+        self.next_attempt = now;
+        self.backoff = INITIAL_BACKOFF;
+    }
+
+    /// Compare the connection with the ports there are now.
+    fn check(&mut self, ports: &impl PortLister, now: Instant) -> Option<WatchAction> {
+        if self.connected.is_none() && (self.wanted.is_none() || now < self.next_attempt) {
+            return None;
+        }
+        let names = ports.port_names();
+
+        if let Some(port) = &self.connected {
+            if names.contains(port) {
+                return None;
+            }
+            self.connected = None;
+            // Try again as soon as it's back.
+            self.reset_backoff(now);
+            return Some(WatchAction::Lost);
+        }
+
+        let wanted = self.wanted.as_deref()?;
+        find_port(&names, wanted).map(|port| WatchAction::Reconnect(port.clone()))
+    }
+}
+
+/// ALSA ends port names with the client and port numbers (`Foot
+/// Controller:Foot Controller MIDI 1 20:0`), and the client number can change
+/// when the device is plugged back in. This is the name without them.
+fn port_prefix(name: &str) -> &str {
+    match name.rsplit_once(' ') {
+        Some((prefix, suffix))
+            if suffix
+                .split_once(':')
+                .is_some_and(|(client, port)| is_number(client) && is_number(port)) =>
+        {
+            prefix
+        }
+        _ => name,
+    }
+}
+
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// The port for `device`: one with exactly that name, or failing that one
+/// that differs from it only in the numbers ALSA appends.
+fn find_port<'a>(ports: &'a [String], device: &str) -> Option<&'a String> {
+    ports.iter().find(|p| *p == device).or_else(|| {
+        let prefix = port_prefix(device);
+        ports.iter().find(|p| port_prefix(p) == prefix)
+    })
+}
+
 /// The MIDI manager runs in a separate thread and handles device connections
 pub struct MidiManager {
     command_receiver: Receiver<MidiCommand>,
     event_sender: Sender<MidiEvent>,
     connection: Option<MidiInputConnection<()>>,
     midi_event_sender: Sender<MidiEvent>,
+    wake_sender: Sender<()>,
+    watch: ConnectionWatch,
+    last_check: Instant,
 }
 
 impl MidiManager {
@@ -166,6 +621,8 @@ impl MidiManager {
     pub fn new() -> (Self, MidiHandle) {
         let (command_sender, command_receiver) = bounded(10);
         let (event_sender, event_receiver) = bounded(100);
+        // One pending wakeup covers any number of queued events.
+        let (wake_sender, wake_receiver) = bounded(1);
         let mappings = Arc::new(ArcSwap::from_pointee(Vec::new()));
 
         (
@@ -174,10 +631,14 @@ impl MidiManager {
                 event_sender: event_sender.clone(),
                 connection: None,
                 midi_event_sender: event_sender,
+                wake_sender,
+                watch: ConnectionWatch::new(Instant::now()),
+                last_check: Instant::now(),
             },
             MidiHandle {
                 command_sender,
                 event_receiver,
+                wake_receiver,
                 mappings,
             },
         )
@@ -185,17 +646,7 @@ impl MidiManager {
 
     /// Get a list of available MIDI input devices
     pub fn list_devices() -> Vec<String> {
-        match MidiInput::new("rustortion-scan") {
-            Ok(midi_in) => midi_in
-                .ports()
-                .iter()
-                .filter_map(|p| midi_in.port_name(p).ok())
-                .collect(),
-            Err(e) => {
-                error!("Failed to create MIDI input for scanning: {e}");
-                Vec::new()
-            }
-        }
+        MidirPorts.port_names()
     }
 
     /// Run the MIDI manager (blocking, should be called from a dedicated thread)
@@ -203,80 +654,124 @@ impl MidiManager {
         debug!("MIDI manager started");
 
         loop {
-            match self.command_receiver.recv() {
+            let until_check = LIVENESS_INTERVAL.saturating_sub(self.last_check.elapsed());
+            match self.command_receiver.recv_timeout(until_check) {
                 Ok(MidiCommand::Connect(device_name)) => {
                     self.handle_connect(&device_name);
                 }
                 Ok(MidiCommand::Disconnect) => {
                     self.handle_disconnect();
+                    self.watch.want(None, Instant::now());
                 }
                 Ok(MidiCommand::Shutdown) => {
                     debug!("MIDI manager shutting down");
                     self.handle_disconnect();
                     break;
                 }
-                Err(_) => {
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
                     // Channel closed, shutdown
                     break;
                 }
             }
+
+            if self.last_check.elapsed() >= LIVENESS_INTERVAL {
+                self.check_liveness();
+            }
+        }
+    }
+
+    /// Drop a connection whose device has gone, and reconnect once it's back.
+    fn check_liveness(&mut self) {
+        let now = Instant::now();
+        self.last_check = now;
+        match self.watch.check(&MidirPorts, now) {
+            Some(WatchAction::Lost) => {
+                self.handle_disconnect();
+                warn!("MIDI device unplugged; waiting for it to come back");
+                let _ = self.event_sender.try_send(MidiEvent::Disconnected);
+            }
+            Some(WatchAction::Reconnect(port)) => match self.open(&port) {
+                Ok(()) => {
+                    info!("Reconnected to MIDI device: {port}");
+                    self.watch.connected(port.clone(), now);
+                    let _ = self.event_sender.try_send(MidiEvent::Reconnected(port));
+                }
+                Err(e) => {
+                    warn!("Failed to reconnect to MIDI device {port}: {e}");
+                    self.watch.attempt_failed(now);
+                }
+            },
+            None => {}
         }
     }
 
     fn handle_connect(&mut self, device_name: &str) {
         // Disconnect existing connection first
         self.handle_disconnect();
+        let now = Instant::now();
+        self.watch.want(Some(device_name.to_owned()), now);
 
-        let midi_in = match MidiInput::new("rustortion") {
-            Ok(m) => m,
-            Err(e) => {
-                error!("Failed to create MIDI input: {e}");
-                let _ = self.event_sender.try_send(MidiEvent::Error(format!(
-                    "Failed to create MIDI input: {e}"
-                )));
-                return;
-            }
-        };
-
-        // Find the port by name
-        let port = midi_in
-            .ports()
-            .into_iter()
-            .find(|p| midi_in.port_name(p).is_ok_and(|n| n == device_name));
-
-        let Some(port) = port else {
+        // Matched like a reconnection, so a saved name still finds a device
+        // that came back under different numbers.
+        let ports = MidirPorts.port_names();
+        let Some(port) = find_port(&ports, device_name) else {
             error!("MIDI device not found: {device_name}");
             let _ = self
                 .event_sender
                 .try_send(MidiEvent::Error(format!("Device not found: {device_name}")));
+            let _ = self.event_sender.try_send(MidiEvent::Disconnected);
             return;
         };
 
+        match self.open(port) {
+            Ok(()) => {
+                info!("Connected to MIDI device: {port}");
+                self.watch.connected(port.clone(), now);
+            }
+            Err(e) => {
+                error!("{e}");
+                let _ = self.event_sender.try_send(MidiEvent::Error(e));
+                let _ = self.event_sender.try_send(MidiEvent::Disconnected);
+                self.watch.attempt_failed(now);
+            }
+        }
+    }
+
+    /// Open the input port named `port_name`.
+    fn open(&mut self, port_name: &str) -> Result<(), String> {
+        let midi_in = MidiInput::new("rustortion")
+            .map_err(|e| format!("Failed to create MIDI input: {e}"))?;
+
+        let port = midi_in
+            .ports()
+            .into_iter()
+            .find(|p| midi_in.port_name(p).is_ok_and(|n| n == port_name))
+            .ok_or_else(|| format!("Device not found: {port_name}"))?;
+
         let sender = self.midi_event_sender.clone();
+        let wake = self.wake_sender.clone();
 
-        let connection = match midi_in.connect(
-            &port,
-            "rustortion-input",
-            move |_timestamp, message, ()| {
-                let Some(event) = parse_midi_message(message) else {
-                    return;
-                };
+        let connection = midi_in
+            .connect(
+                &port,
+                "rustortion-input",
+                move |_timestamp, message, ()| {
+                    let Some(event) = parse_midi_message(message) else {
+                        return;
+                    };
 
-                if let Err(e) = sender.try_send(MidiEvent::Input(event)) {
-                    warn!("Failed to send MIDI event: {e}");
-                }
-            },
-            (),
-        ) {
-            Ok(c) => c,
-            Err(e) => {
-                error!("Failed to connect to MIDI device: {e}");
-                return;
-            }
-        };
+                    if let Err(e) = sender.try_send(MidiEvent::Input(event)) {
+                        warn!("Failed to send MIDI event: {e}");
+                    }
+                    let _ = wake.try_send(());
+                },
+                (),
+            )
+            .map_err(|e| format!("Failed to connect to MIDI device: {e}"))?;
 
-        info!("Connected to MIDI device: {device_name}");
         self.connection = Some(connection);
+        Ok(())
     }
 
     fn handle_disconnect(&mut self) {
@@ -349,6 +844,7 @@ fn parse_midi_message(message: &[u8]) -> Option<MidiInputEvent> {
         control,
         value,
         raw_bytes: message.to_vec(),
+        received: Instant::now(),
     })
 }
 
@@ -369,6 +865,32 @@ pub fn start_midi_manager() -> MidiHandle {
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "gui")]
+    use rustortion_ui::mappings::validate::{self, Conflict};
+
+    /// The edge `event` makes on its own, with no earlier state.
+    fn edge(event: &MidiInputEvent) -> Edge {
+        if event.is_release() {
+            Edge::Released
+        } else {
+            Edge::Pressed
+        }
+    }
+
+    fn cc(control: u8, value: u8) -> MidiInputEvent {
+        parse_midi_message(&[0xB0, control, value]).unwrap()
+    }
+
+    /// Run `values` of CC 64 through a tracker and `mapping`, collecting
+    /// what fired.
+    fn fire(mapping: &MidiMapping, values: &[u8]) -> Vec<MidiTrigger> {
+        let mut edges = EdgeTracker::default();
+        values
+            .iter()
+            .filter_map(|&value| edges.edge(&cc(64, value)))
+            .filter_map(|edge| mapping.trigger(edge))
+            .collect()
+    }
 
     #[test]
     fn test_parse_note_on() {
@@ -397,6 +919,221 @@ mod tests {
         assert_eq!(event.value, 64);
     }
 
+    #[test]
+    fn test_hold_tuner_follows_press_and_release() {
+        let mapping = MidiMapping::hold_tuner(0, 64);
+        let note_on = parse_midi_message(&[0x90, 64, 100]).unwrap();
+        let note_off = parse_midi_message(&[0x80, 64, 0]).unwrap();
+        let cc_down = parse_midi_message(&[0xB0, 64, 127]).unwrap();
+        let cc_up = parse_midi_message(&[0xB0, 64, 0]).unwrap();
+
+        assert_eq!(
+            mapping.trigger(edge(&note_on)),
+            Some(MidiTrigger::HoldTuner { pressed: true })
+        );
+        assert_eq!(
+            mapping.trigger(edge(&note_off)),
+            Some(MidiTrigger::HoldTuner { pressed: false })
+        );
+        assert_eq!(
+            mapping.trigger(edge(&cc_down)),
+            Some(MidiTrigger::HoldTuner { pressed: true })
+        );
+        assert_eq!(
+            mapping.trigger(edge(&cc_up)),
+            Some(MidiTrigger::HoldTuner { pressed: false })
+        );
+    }
+
+    #[test]
+    fn test_previous_preset_fires_on_press_only() {
+        let mapping = MidiMapping::previous_preset(0, 64);
+        let cc_down = parse_midi_message(&[0xB0, 64, 127]).unwrap();
+        let cc_up = parse_midi_message(&[0xB0, 64, 0]).unwrap();
+
+        assert_eq!(
+            mapping.trigger(edge(&cc_down)),
+            Some(MidiTrigger::PreviousPreset)
+        );
+        assert_eq!(mapping.trigger(edge(&cc_up)), None);
+    }
+
+    #[test]
+    fn test_song_steps_fire_on_press_only() {
+        let next = MidiMapping::next_song(0, 80);
+        let previous = MidiMapping::previous_song(0, 81);
+        let note_on = parse_midi_message(&[0x90, 80, 100]).unwrap();
+        let note_off = parse_midi_message(&[0x80, 80, 0]).unwrap();
+
+        assert_eq!(next.trigger(edge(&note_on)), Some(MidiTrigger::NextSong));
+        assert_eq!(next.trigger(edge(&note_off)), None);
+        assert_eq!(
+            previous.trigger(edge(&note_on)),
+            Some(MidiTrigger::PreviousSong)
+        );
+        assert_eq!(previous.trigger(edge(&note_off)), None);
+    }
+
+    #[test]
+    fn test_toggle_standby_fires_on_press_only() {
+        let mapping = MidiMapping::toggle_standby(0, 65);
+        let cc_down = parse_midi_message(&[0xB0, 65, 127]).unwrap();
+        let cc_up = parse_midi_message(&[0xB0, 65, 0]).unwrap();
+
+        assert_eq!(
+            mapping.trigger(edge(&cc_down)),
+            Some(MidiTrigger::Flip(MidiSwitch::Standby))
+        );
+        assert_eq!(mapping.trigger(edge(&cc_up)), None);
+    }
+
+    #[test]
+    fn test_repeated_values_are_one_press() {
+        let mut edges = EdgeTracker::default();
+        assert_eq!(edges.edge(&cc(64, 127)), Some(Edge::Pressed));
+        assert_eq!(edges.edge(&cc(64, 127)), None);
+        assert_eq!(edges.edge(&cc(64, 100)), None);
+        assert_eq!(edges.edge(&cc(64, 0)), Some(Edge::Released));
+        assert_eq!(edges.edge(&cc(64, 10)), None);
+        assert_eq!(edges.edge(&cc(64, 64)), Some(Edge::Pressed));
+        // Another CC has its own state.
+        assert_eq!(edges.edge(&cc(65, 127)), Some(Edge::Pressed));
+    }
+
+    #[test]
+    fn test_program_changes_always_press() {
+        let mut edges = EdgeTracker::default();
+        let program = parse_midi_message(&[0xC0, 3]).unwrap();
+        assert_eq!(edges.edge(&program), Some(Edge::Pressed));
+        assert_eq!(edges.edge(&program), Some(Edge::Pressed));
+    }
+
+    #[test]
+    fn test_trigger_mode_keeps_each_actions_behaviour() {
+        let standby = MidiMapping::toggle_standby(0, 64);
+        assert_eq!(
+            fire(&standby, &[127, 127, 0, 0, 127]),
+            vec![
+                MidiTrigger::Flip(MidiSwitch::Standby),
+                MidiTrigger::Flip(MidiSwitch::Standby),
+            ]
+        );
+
+        let tuner = MidiMapping::hold_tuner(0, 64);
+        assert_eq!(
+            fire(&tuner, &[127, 120, 0]),
+            vec![
+                MidiTrigger::HoldTuner { pressed: true },
+                MidiTrigger::HoldTuner { pressed: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_momentary_engages_until_released() {
+        let mapping = MidiMapping::toggle_ir_bypass(0, 64).with_mode(MappingMode::Momentary);
+        assert_eq!(
+            fire(&mapping, &[127, 127, 90, 0, 0]),
+            vec![
+                MidiTrigger::Engage(MidiSwitch::IrBypass),
+                MidiTrigger::Disengage(MidiSwitch::IrBypass),
+            ]
+        );
+
+        let tuner = MidiMapping::hold_tuner(0, 64).with_mode(MappingMode::Momentary);
+        assert_eq!(
+            fire(&tuner, &[127, 0]),
+            vec![
+                MidiTrigger::Engage(MidiSwitch::Tuner),
+                MidiTrigger::Disengage(MidiSwitch::Tuner),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toggle_flips_on_presses_only() {
+        let mapping = MidiMapping::hold_tuner(0, 64).with_mode(MappingMode::Toggle);
+        assert_eq!(
+            fire(&mapping, &[127, 127, 0, 127, 0]),
+            vec![
+                MidiTrigger::Flip(MidiSwitch::Tuner),
+                MidiTrigger::Flip(MidiSwitch::Tuner),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_modes_leave_non_switches_alone() {
+        let mapping = MidiMapping::next_song(0, 64).with_mode(MappingMode::Momentary);
+        assert_eq!(fire(&mapping, &[127, 0]), vec![MidiTrigger::NextSong]);
+
+        // Preset mappings still load on any edge.
+        let preset = MidiMapping::new(0, 64, "Lead".to_string());
+        let load = MidiTrigger::LoadPreset("Lead".to_string());
+        assert_eq!(fire(&preset, &[127, 127, 0]), vec![load.clone(), load]);
+    }
+
+    #[test]
+    fn test_latch_restores_the_state_before_the_press() {
+        let mut latch = SwitchLatch::default();
+        let engage = MidiTrigger::Engage(MidiSwitch::IrBypass);
+        let disengage = MidiTrigger::Disengage(MidiSwitch::IrBypass);
+
+        // Off before: on while held, off again after.
+        assert_eq!(latch.resolve(&engage, false), Some(true));
+        assert_eq!(latch.resolve(&disengage, true), Some(false));
+
+        // Already on: stays on after the release.
+        assert_eq!(latch.resolve(&engage, true), Some(true));
+        assert_eq!(latch.resolve(&disengage, true), Some(true));
+
+        // A release with nothing engaged leaves it.
+        assert_eq!(latch.resolve(&disengage, true), None);
+
+        // Engaged twice, the first state is the one restored.
+        assert_eq!(latch.resolve(&engage, false), Some(true));
+        assert_eq!(latch.resolve(&engage, true), Some(true));
+        assert_eq!(latch.resolve(&disengage, true), Some(false));
+
+        let flip = MidiTrigger::Flip(MidiSwitch::Standby);
+        assert_eq!(latch.resolve(&flip, true), Some(false));
+        assert_eq!(latch.resolve(&flip, false), Some(true));
+    }
+
+    #[test]
+    fn test_mapping_without_mode_is_trigger() {
+        let json = r#"{"channel":0,"control":60,"preset_name":"","description":"x","action":"ToggleStandby"}"#;
+        let mapping: MidiMapping = serde_json::from_str(json).unwrap();
+        assert_eq!(mapping.mode, MappingMode::Trigger);
+    }
+
+    #[test]
+    fn test_mapping_without_action_loads_preset() {
+        let json = r#"{"channel":0,"control":60,"preset_name":"Lead","description":"x"}"#;
+        let mapping: MidiMapping = serde_json::from_str(json).unwrap();
+        assert_eq!(mapping.action, MidiAction::LoadPreset);
+    }
+
+    #[test]
+    #[cfg(feature = "gui")]
+    fn test_note_on_and_off_learn_as_the_same_input() {
+        let note_on = parse_midi_message(&[0x90, 60, 100]).unwrap();
+        let zero_velocity = parse_midi_message(&[0x90, 60, 0]).unwrap();
+        let note_off = parse_midi_message(&[0x80, 60, 0]).unwrap();
+        assert_eq!(note_on.input_trigger(), zero_velocity.input_trigger());
+        assert_eq!(note_on.input_trigger(), note_off.input_trigger());
+
+        let existing = [MidiMapping::new(0, 60, "Lead".to_string()).binding(0)];
+        for event in [&note_on, &zero_velocity, &note_off] {
+            assert_eq!(
+                validate::check_new(&event.input_trigger(), &existing),
+                Some(Conflict::Duplicate {
+                    target: "Lead".to_string()
+                })
+            );
+        }
+    }
+
     #[test]
     fn test_midi_mapping_matches() {
         let mapping = MidiMapping::new(0, 60, "Test Preset".to_string());
@@ -404,4 +1141,119 @@ mod tests {
         assert!(!mapping.matches(1, 60));
         assert!(!mapping.matches(0, 61));
     }
+
+    /// A fixed set of ports standing in for the system's.
+    struct Ports(Vec<String>);
+
+    impl Ports {
+        fn of(names: &[&str]) -> Self {
+            Self(names.iter().map(|n| (*n).to_owned()).collect())
+        }
+    }
+
+    impl PortLister for Ports {
+        fn port_names(&self) -> Vec<String> {
+            self.0.clone()
+        }
+    }
+
+    const PEDAL: &str = "FootCtl:FootCtl MIDI 1 20:0";
+    const PEDAL_REPLUGGED: &str = "FootCtl:FootCtl MIDI 1 24:0";
+
+    fn connected_watch(now: Instant) -> ConnectionWatch {
+        let mut watch = ConnectionWatch::new(now);
+        watch.want(Some(PEDAL.to_owned()), now);
+        watch.connected(PEDAL.to_owned(), now);
+        watch
+    }
+
+    #[test]
+    fn connected_device_that_stays_is_left_alone() {
+        let now = Instant::now();
+        let mut watch = connected_watch(now);
+        assert_eq!(watch.check(&Ports::of(&[PEDAL, "Other 14:0"]), now), None);
+    }
+
+    #[test]
+    fn unplugging_reports_lost_then_reconnects_when_back() {
+        let now = Instant::now();
+        let mut watch = connected_watch(now);
+
+        assert_eq!(watch.check(&Ports::of(&[]), now), Some(WatchAction::Lost));
+        // Lost is reported once; nothing to do until the device is back.
+        assert_eq!(watch.check(&Ports::of(&[]), now), None);
+
+        assert_eq!(
+            watch.check(&Ports::of(&[PEDAL]), now),
+            Some(WatchAction::Reconnect(PEDAL.to_owned()))
+        );
+        watch.connected(PEDAL.to_owned(), now);
+        assert_eq!(watch.check(&Ports::of(&[PEDAL]), now), None);
+    }
+
+    #[test]
+    fn reconnects_under_a_changed_port_suffix() {
+        let now = Instant::now();
+        let mut watch = connected_watch(now);
+        watch.check(&Ports::of(&[]), now);
+
+        assert_eq!(
+            watch.check(&Ports::of(&["Other 14:0", PEDAL_REPLUGGED]), now),
+            Some(WatchAction::Reconnect(PEDAL_REPLUGGED.to_owned()))
+        );
+    }
+
+    #[test]
+    fn failed_attempts_back_off() {
+        let start = Instant::now();
+        let mut watch = ConnectionWatch::new(start);
+        watch.want(Some(PEDAL.to_owned()), start);
+        let ports = Ports::of(&[PEDAL]);
+
+        let mut now = start;
+        let mut waits = Vec::new();
+        for _ in 0..8 {
+            assert!(watch.check(&ports, now).is_some());
+            watch.attempt_failed(now);
+            assert_eq!(watch.check(&ports, now), None);
+            let wait = watch.next_attempt - now;
+            waits.push(wait);
+            now += wait;
+        }
+
+        assert_eq!(waits[0], INITIAL_BACKOFF);
+        assert_eq!(waits[1], INITIAL_BACKOFF * 2);
+        assert!(waits.windows(2).all(|w| w[1] >= w[0]));
+        assert_eq!(*waits.last().unwrap(), MAX_BACKOFF);
+
+        // A success starts the next round of attempts from the shortest wait.
+        watch.connected(PEDAL.to_owned(), now);
+        watch.check(&Ports::of(&[]), now);
+        watch.check(&ports, now);
+        watch.attempt_failed(now);
+        assert_eq!(watch.next_attempt - now, INITIAL_BACKOFF);
+    }
+
+    #[test]
+    fn disconnecting_stops_reconnection() {
+        let now = Instant::now();
+        let mut watch = connected_watch(now);
+        watch.want(None, now);
+        assert_eq!(watch.check(&Ports::of(&[PEDAL]), now), None);
+    }
+
+    #[test]
+    fn find_port_prefers_the_exact_name() {
+        let ports = vec![PEDAL_REPLUGGED.to_owned(), PEDAL.to_owned()];
+        assert_eq!(find_port(&ports, PEDAL), Some(&ports[1]));
+        assert_eq!(find_port(&ports[..1], PEDAL), Some(&ports[0]));
+        assert_eq!(find_port(&ports, "FootCtl:FootCtl MIDI 2 20:1"), None);
+    }
+
+    #[test]
+    fn port_prefix_strips_only_alsa_numbers() {
+        assert_eq!(port_prefix(PEDAL), "FootCtl:FootCtl MIDI 1");
+        assert_eq!(port_prefix("Virtual Port"), "Virtual Port");
+        assert_eq!(port_prefix("Mixer 1:A"), "Mixer 1:A");
+    }
 }
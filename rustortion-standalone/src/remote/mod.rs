@@ -0,0 +1,454 @@
+//! OSC remote control over UDP, so a tablet on the network can switch
+//! presets, bypass the IR and start recording.
+//!
+//! Accepted messages:
+//!
+//! - `/preset/select s`: load the preset with this name
+//! - `/preset/next`: load the preset after the selected one
+//! - `/ir/bypass i`: bypass the IR (non-zero) or play it (zero)
+//! - `/record i`: start (non-zero) or stop (zero) recording
+//!
+//! Anything else is ignored. Every address a valid packet came from is
+//! sent `/preset/current s` and `/record/state i` when they change.
+
+use crossbeam::channel::{Receiver, Sender, unbounded};
+use log::{debug, info, warn};
+use rosc::{OscMessage, OscPacket, OscType, decoder, encoder};
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Port the server listens on unless the settings say otherwise.
+pub const DEFAULT_REMOTE_PORT: u16 = 9000;
+/// How long the listener waits for a packet before checking it should stop.
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+/// Remotes state changes are sent to; the oldest is forgotten past this.
+const MAX_PEERS: usize = 8;
+
+/// What a remote asked for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteCommand {
+    SelectPreset(String),
+    NextPreset,
+    SetIrBypass(bool),
+    SetRecording(bool),
+}
+
+/// App state sent to remotes so they can show it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteState {
+    Preset(String),
+    Recording(bool),
+}
+
+/// The commands in a raw OSC packet, bundles included, in order. A packet
+/// that doesn't decode gives none.
+pub fn parse_packet(bytes: &[u8]) -> Vec<RemoteCommand> {
+    let mut commands = Vec::new();
+    match decoder::decode_udp(bytes) {
+        Ok((_, packet)) => collect(packet, &mut commands),
+        Err(e) => debug!("Ignoring malformed OSC packet: {e:?}"),
+    }
+    commands
+}
+
+fn collect(packet: OscPacket, commands: &mut Vec<RemoteCommand>) {
+    match packet {
+        OscPacket::Message(message) => commands.extend(parse_message(&message)),
+        OscPacket::Bundle(bundle) => {
+            for packet in bundle.content {
+                collect(packet, commands);
+            }
+        }
+    }
+}
+
+fn parse_message(message: &OscMessage) -> Option<RemoteCommand> {
+    let command = match (message.addr.as_str(), message.args.as_slice()) {
+        ("/preset/select", [OscType::String(name)]) => {
+            Some(RemoteCommand::SelectPreset(name.clone()))
+        }
+        ("/preset/next", []) => Some(RemoteCommand::NextPreset),
+        ("/ir/bypass", [arg]) => flag(arg).map(RemoteCommand::SetIrBypass),
+        ("/record", [arg]) => flag(arg).map(RemoteCommand::SetRecording),
+        _ => None,
+    };
+    if command.is_none() {
+        debug!("Ignoring OSC message {} {:?}", message.addr, message.args);
+    }
+    command
+}
+
+/// On/off from an int, as specified, or the bool and float arguments touch
+/// controllers tend to send instead.
+fn flag(arg: &OscType) -> Option<bool> {
+    match *arg {
+        OscType::Int(i) => Some(i != 0),
+        OscType::Float(f) => Some(f != 0.0),
+        OscType::Bool(b) => Some(b),
+        _ => None,
+    }
+}
+
+/// The preset after `selected` in `presets`, wrapping round to the first.
+/// The first when none is selected or it's no longer there.
+pub fn next_preset<'a>(presets: &'a [String], selected: Option<&str>) -> Option<&'a str> {
+    let next = selected
+        .and_then(|name| presets.iter().position(|p| p == name))
+        .map_or(0, |i| (i + 1) % presets.len());
+    presets.get(next).map(String::as_str)
+}
+
+/// The OSC packet announcing `state`.
+pub fn encode_state(state: &RemoteState) -> Vec<u8> {
+    let (addr, arg) = match state {
+        RemoteState::Preset(name) => ("/preset/current", OscType::String(name.clone())),
+        RemoteState::Recording(on) => ("/record/state", OscType::Int(i32::from(*on))),
+    };
+    let packet = OscPacket::Message(OscMessage {
+        addr: addr.to_string(),
+        args: vec![arg],
+    });
+    encoder::encode(&packet).unwrap_or_default()
+}
+
+/// Listens for OSC on a UDP port on its own thread. Dropping it stops the
+/// thread.
+pub struct RemoteServer {
+    port: u16,
+    socket: UdpSocket,
+    peers: Arc<Mutex<Peers>>,
+    command_rx: Receiver<RemoteCommand>,
+    running: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+/// Addresses valid packets came from, newest last.
+#[derive(Default)]
+struct Peers {
+    addrs: Vec<SocketAddr>,
+    /// A remote was heard from for the first time since the last check.
+    joined: bool,
+}
+
+impl Peers {
+    fn heard_from(&mut self, addr: SocketAddr) {
+        if self.addrs.contains(&addr) {
+            return;
+        }
+        if self.addrs.len() == MAX_PEERS {
+            self.addrs.remove(0);
+        }
+        self.addrs.push(addr);
+        self.joined = true;
+    }
+}
+
+impl RemoteServer {
+    /// Listen on `port` on every interface.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", port))?;
+        socket.set_read_timeout(Some(RECV_TIMEOUT))?;
+        let listener = socket.try_clone()?;
+        let port = socket.local_addr()?.port();
+
+        let peers = Arc::new(Mutex::new(Peers::default()));
+        let running = Arc::new(AtomicBool::new(true));
+        let (command_tx, command_rx) = unbounded();
+
+        let thread_peers = Arc::clone(&peers);
+        let thread_running = Arc::clone(&running);
+        let thread = thread::Builder::new()
+            .name("osc-remote".into())
+            .spawn(move || run(&listener, &thread_peers, &thread_running, &command_tx))?;
+
+        info!("OSC remote control listening on UDP port {port}");
+        Ok(Self {
+            port,
+            socket,
+            peers,
+            command_rx,
+            running,
+            thread: Some(thread),
+        })
+    }
+
+    pub const fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Commands received since the last call, in order.
+    pub fn commands(&self) -> Vec<RemoteCommand> {
+        self.command_rx.try_iter().collect()
+    }
+
+    /// Whether a remote was heard from for the first time since the last
+    /// call, and so hasn't been sent the current state yet.
+    pub fn take_joined(&self) -> bool {
+        self.peers
+            .lock()
+            .is_ok_and(|mut peers| std::mem::take(&mut peers.joined))
+    }
+
+    /// Send `state` to every remote heard from.
+    pub fn broadcast(&self, state: &RemoteState) {
+        let packet = encode_state(state);
+        let addrs = self
+            .peers
+            .lock()
+            .map(|peers| peers.addrs.clone())
+            .unwrap_or_default();
+        for addr in addrs {
+            if let Err(e) = self.socket.send_to(&packet, addr) {
+                debug!("Failed to send OSC state to {addr}: {e}");
+            }
+        }
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+        debug!("OSC remote control on port {} stopped", self.port);
+    }
+}
+
+/// The listener thread. Wakes at least every [`RECV_TIMEOUT`] to see whether
+/// it should stop.
+fn run(
+    socket: &UdpSocket,
+    peers: &Mutex<Peers>,
+    running: &AtomicBool,
+    command_tx: &Sender<RemoteCommand>,
+) {
+    let mut buf = vec![0u8; decoder::MTU];
+    while running.load(Ordering::Relaxed) {
+        let (len, addr) = match socket.recv_from(&mut buf) {
+            Ok(received) => received,
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(e) => {
+                warn!("OSC remote control receive failed: {e}");
+                continue;
+            }
+        };
+
+        let commands = parse_packet(&buf[..len]);
+        if commands.is_empty() {
+            continue;
+        }
+        if let Ok(mut peers) = peers.lock() {
+            peers.heard_from(addr);
+        }
+        for command in commands {
+            if command_tx.send(command).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosc::{OscBundle, OscTime};
+
+    /// An OSC string: the bytes, a NUL, padded to four bytes.
+    fn osc_string(s: &str) -> Vec<u8> {
+        let mut bytes = s.as_bytes().to_vec();
+        bytes.push(0);
+        while bytes.len() % 4 != 0 {
+            bytes.push(0);
+        }
+        bytes
+    }
+
+    /// A raw OSC message with int arguments only.
+    fn raw_message(addr: &str, tags: &str, ints: &[i32]) -> Vec<u8> {
+        let mut bytes = osc_string(addr);
+        bytes.extend(osc_string(&format!(",{tags}")));
+        for i in ints {
+            bytes.extend(i.to_be_bytes());
+        }
+        bytes
+    }
+
+    fn encode(addr: &str, args: Vec<OscType>) -> Vec<u8> {
+        encoder::encode(&OscPacket::Message(OscMessage {
+            addr: addr.to_string(),
+            args,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_hand_built_packets() {
+        assert_eq!(
+            parse_packet(&raw_message("/preset/next", "", &[])),
+            [RemoteCommand::NextPreset]
+        );
+        assert_eq!(
+            parse_packet(&raw_message("/ir/bypass", "i", &[1])),
+            [RemoteCommand::SetIrBypass(true)]
+        );
+        assert_eq!(
+            parse_packet(&raw_message("/record", "i", &[0])),
+            [RemoteCommand::SetRecording(false)]
+        );
+
+        let mut select = osc_string("/preset/select");
+        select.extend(osc_string(",s"));
+        select.extend(osc_string("Lead"));
+        assert_eq!(
+            parse_packet(&select),
+            [RemoteCommand::SelectPreset("Lead".to_string())]
+        );
+    }
+
+    #[test]
+    fn accepts_float_and_bool_flags() {
+        assert_eq!(
+            parse_packet(&encode("/ir/bypass", vec![OscType::Float(1.0)])),
+            [RemoteCommand::SetIrBypass(true)]
+        );
+        assert_eq!(
+            parse_packet(&encode("/record", vec![OscType::Bool(true)])),
+            [RemoteCommand::SetRecording(true)]
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_addresses_and_wrong_arguments() {
+        for packet in [
+            raw_message("/amp/explode", "i", &[1]),
+            raw_message("/preset/select", "i", &[3]),
+            raw_message("/preset/next", "i", &[1]),
+            raw_message("/ir/bypass", "", &[]),
+            raw_message("/record", "ii", &[1, 1]),
+            encode("/record", vec![OscType::String("on".into())]),
+        ] {
+            assert!(parse_packet(&packet).is_empty());
+        }
+    }
+
+    #[test]
+    fn ignores_malformed_packets() {
+        assert!(parse_packet(&[]).is_empty());
+        assert!(parse_packet(b"/preset/next").is_empty());
+        assert!(parse_packet(&[0xff; 16]).is_empty());
+    }
+
+    #[test]
+    fn unpacks_bundles_in_order() {
+        let bundle = OscPacket::Bundle(OscBundle {
+            timetag: OscTime::from((0, 1)),
+            content: vec![
+                OscPacket::Message(OscMessage {
+                    addr: "/ir/bypass".into(),
+                    args: vec![OscType::Int(0)],
+                }),
+                OscPacket::Message(OscMessage {
+                    addr: "/unknown".into(),
+                    args: vec![],
+                }),
+                OscPacket::Message(OscMessage {
+                    addr: "/preset/next".into(),
+                    args: vec![],
+                }),
+            ],
+        });
+        assert_eq!(
+            parse_packet(&encoder::encode(&bundle).unwrap()),
+            [RemoteCommand::SetIrBypass(false), RemoteCommand::NextPreset]
+        );
+    }
+
+    #[test]
+    fn encodes_state_for_remotes() {
+        let (_, packet) =
+            decoder::decode_udp(&encode_state(&RemoteState::Recording(true))).unwrap();
+        let OscPacket::Message(message) = packet else {
+            panic!("expected a message");
+        };
+        assert_eq!(message.addr, "/record/state");
+        assert_eq!(message.args, [OscType::Int(1)]);
+
+        let (_, packet) =
+            decoder::decode_udp(&encode_state(&RemoteState::Preset("Lead".into()))).unwrap();
+        let OscPacket::Message(message) = packet else {
+            panic!("expected a message");
+        };
+        assert_eq!(message.addr, "/preset/current");
+        assert_eq!(message.args, [OscType::String("Lead".into())]);
+    }
+
+    #[test]
+    fn next_preset_wraps_round() {
+        let presets = ["Clean", "Crunch", "Lead"].map(String::from);
+        assert_eq!(next_preset(&presets, Some("Clean")), Some("Crunch"));
+        assert_eq!(next_preset(&presets, Some("Lead")), Some("Clean"));
+        assert_eq!(next_preset(&presets, Some("Deleted")), Some("Clean"));
+        assert_eq!(next_preset(&presets, None), Some("Clean"));
+        assert_eq!(next_preset(&[], Some("Clean")), None);
+    }
+
+    #[test]
+    fn forgets_the_oldest_remote_past_the_limit() {
+        let mut peers = Peers::default();
+        for port in 0..=MAX_PEERS as u16 {
+            peers.heard_from(SocketAddr::from(([10, 0, 0, 1], 9000 + port)));
+        }
+        assert_eq!(peers.addrs.len(), MAX_PEERS);
+        assert_eq!(peers.addrs[0].port(), 9001);
+        assert!(peers.joined);
+    }
+
+    #[test]
+    fn server_receives_commands_and_stops_on_drop() {
+        let server = RemoteServer::start(0).unwrap();
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        client
+            .send_to(
+                &raw_message("/ir/bypass", "i", &[1]),
+                ("127.0.0.1", server.port()),
+            )
+            .unwrap();
+
+        let mut commands = Vec::new();
+        for _ in 0..200 {
+            commands = server.commands();
+            if !commands.is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(commands, [RemoteCommand::SetIrBypass(true)]);
+        assert!(server.take_joined());
+        assert!(!server.take_joined());
+
+        server.broadcast(&RemoteState::Recording(true));
+        let mut buf = [0u8; 64];
+        let (len, _) = client.recv_from(&mut buf).unwrap();
+        assert_eq!(&buf[..len], encode_state(&RemoteState::Recording(true)));
+
+        let port = server.port();
+        drop(server);
+        // The port is free again once the thread has stopped.
+        UdpSocket::bind(("0.0.0.0", port)).unwrap();
+    }
+}
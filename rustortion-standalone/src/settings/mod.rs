@@ -1,53 +1,124 @@
 // src/gui/settings.rs
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::audio::watchdog::DEFAULT_STALL_SECONDS;
+#[cfg(feature = "gui")]
 use crate::i18n::Language;
 use crate::midi::MidiMapping;
+use crate::remote::DEFAULT_REMOTE_PORT;
+use rustortion_core::audio::dual_mono::ChainMode;
+use rustortion_core::audio::host::HostApi;
+use rustortion_core::audio::samplers::ResamplerQuality;
+use rustortion_core::hooks::EventKind;
+use rustortion_core::ir::loader::{DEFAULT_MAX_IR_SECONDS, IrTagVocabulary};
+use rustortion_core::ir::memory::DEFAULT_IR_MEMORY_MB;
+use rustortion_core::metronome::MetronomeConfig;
+use rustortion_core::migration::{Migration, migrate};
+use rustortion_core::tuner::{TunerAlgorithm, TunerMode};
+#[cfg(feature = "gui")]
+use rustortion_ui::handlers::preset::DEFAULT_RECENT_PRESETS;
+#[cfg(feature = "gui")]
 use rustortion_ui::hotkey::HotkeySettings;
+#[cfg(feature = "gui")]
+use rustortion_ui::tabs::Tab;
+#[cfg(feature = "gui")]
+use rustortion_ui::theme::ThemeChoice;
 
 impl std::fmt::Display for AudioSettings {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Audio API: {}", self.host_api)?;
         writeln!(f, "Input Port: {}", self.input_port)?;
+        writeln!(f, "Right Input Port: {}", self.input_right_port)?;
         writeln!(f, "Output Left Port: {}", self.output_left_port)?;
         writeln!(f, "Output Right Port: {}", self.output_right_port)?;
         writeln!(f, "Metronome Output Port: {}", self.metronome_out_port)?;
+        writeln!(f, "Input Device: {}", self.input_device)?;
+        writeln!(f, "Output Device: {}", self.output_device)?;
         writeln!(f, "Buffer Size: {}", self.buffer_size)?;
         writeln!(f, "Sample Rate: {}", self.sample_rate)?;
         writeln!(f, "Oversampling Factor: {}", self.oversampling_factor)?;
+        writeln!(f, "Resampler Quality: {}", self.resampler_quality)?;
+        writeln!(f, "Adaptive Quality: {}", self.adaptive_quality)?;
+        writeln!(f, "Chain Mode: {}", self.chain_mode)?;
         Ok(())
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSettings {
+    /// JACK, the system's own audio through CPAL, or JACK falling back to
+    /// CPAL.
+    #[serde(default)]
+    pub host_api: HostApi,
     pub input_port: String,
+    /// Second input for [`ChainMode::DualMono`]; empty when there's none.
+    #[serde(default)]
+    pub input_right_port: String,
     pub output_left_port: String,
     pub output_right_port: String,
     pub metronome_out_port: String,
+    /// Capture device under CPAL, which has devices where JACK has ports;
+    /// empty for the system default. Its first two channels are the inputs.
+    #[serde(default)]
+    pub input_device: String,
+    /// Playback device under CPAL; empty for the system default. The left and
+    /// right outputs are its first two channels.
+    #[serde(default)]
+    pub output_device: String,
     pub buffer_size: u32,
     pub sample_rate: u32,
     pub oversampling_factor: u32,
+    #[serde(default)]
+    pub resampler_quality: ResamplerQuality,
+    /// Drop to cheap resampling and head-only IRs while the DSP load is too
+    /// high to keep up, instead of crackling.
+    #[serde(default = "default_adaptive_quality")]
+    pub adaptive_quality: bool,
+    /// Run a second chain on the right input. Off by default: it roughly
+    /// doubles the CPU.
+    #[serde(default)]
+    pub chain_mode: ChainMode,
+}
+
+impl AudioSettings {
+    /// Whether to run a chain per input: asked for, and there's a right
+    /// input to run it on.
+    pub const fn dual_mono(&self) -> bool {
+        matches!(self.chain_mode, ChainMode::DualMono) && !self.input_right_port.is_empty()
+    }
 }
 
 impl Default for AudioSettings {
     fn default() -> Self {
         Self {
+            host_api: HostApi::default(),
             input_port: "system:capture_1".to_string(),
+            input_right_port: String::new(),
             output_left_port: "system:playback_1".to_string(),
             output_right_port: "system:playback_2".to_string(),
             metronome_out_port: "system:playback_1".to_string(),
+            input_device: String::new(),
+            output_device: String::new(),
             buffer_size: 128,
             sample_rate: 48000,
             oversampling_factor: 1,
+            resampler_quality: ResamplerQuality::default(),
+            adaptive_quality: default_adaptive_quality(),
+            chain_mode: ChainMode::default(),
         }
     }
 }
 
+const fn default_adaptive_quality() -> bool {
+    true
+}
+
 impl std::fmt::Display for MidiSettings {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
@@ -71,28 +142,353 @@ pub struct MidiSettings {
     pub mappings: Vec<MidiMapping>,
 }
 
-fn default_nam_dir() -> String {
-    "./nam".to_string()
+impl std::fmt::Display for WatchdogSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Stall Threshold: {} s", self.stall_seconds)?;
+        writeln!(f, "Auto Restart: {}", self.auto_restart)?;
+        Ok(())
+    }
 }
 
-#[allow(clippy::unsafe_derive_deserialize)] // unsafe is only for set_var, unrelated to Deserialize
+/// The audio watchdog, which watches for a callback that stopped running.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Settings {
-    pub audio: AudioSettings,
-    pub midi: MidiSettings,
+#[serde(default)]
+pub struct WatchdogSettings {
+    /// Seconds without a processed frame before the stream counts as stalled.
+    pub stall_seconds: u32,
+    /// Restart the JACK client on a stall instead of only reporting it.
+    pub auto_restart: bool,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        Self {
+            stall_seconds: DEFAULT_STALL_SECONDS,
+            auto_restart: true,
+        }
+    }
+}
+
+impl std::fmt::Display for RemoteSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Enabled: {}", self.enabled)?;
+        writeln!(f, "Port: {}", self.port)?;
+        Ok(())
+    }
+}
+
+/// The OSC remote-control server; see [`crate::remote`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteSettings {
+    pub enabled: bool,
+    /// UDP port to listen on, on every interface.
+    pub port: u16,
+}
+
+impl Default for RemoteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_REMOTE_PORT,
+        }
+    }
+}
+
+/// Commands run on app events; see [`crate::hooks`]. Off until switched on,
+/// since they run whatever is typed in with the user's permissions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HookSettings {
+    pub enabled: bool,
+    pub commands: HashMap<EventKind, String>,
+}
+
+impl std::fmt::Display for HookSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Enabled: {}", self.enabled)?;
+        for kind in EventKind::ALL {
+            if let Some(command) = self.commands.get(&kind) {
+                writeln!(f, "{kind}: {command}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for PathSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Recording Directory: {}", self.recording_dir)?;
+        writeln!(f, "Impulse Response Directory: {}", self.ir_dir)?;
+        writeln!(f, "NAM Directory: {}", self.nam_dir)?;
+        writeln!(f, "Captures Directory: {}", self.captures_dir)?;
+        writeln!(f, "Preset Directory: {}", self.preset_dir)?;
+        writeln!(f, "Setlist Directory: {}", self.setlist_dir)?;
+        Ok(())
+    }
+}
+
+/// Directories the app reads from and writes to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathSettings {
     pub recording_dir: String,
     pub ir_dir: String,
-    #[serde(default = "default_nam_dir")]
     pub nam_dir: String,
+    pub captures_dir: String,
     pub preset_dir: String,
+    pub setlist_dir: String,
+}
+
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            recording_dir: "./recordings".to_string(),
+            ir_dir: "./impulse_responses".to_string(),
+            nam_dir: "./nam".to_string(),
+            captures_dir: "./captures".to_string(),
+            preset_dir: "./presets".to_string(),
+            setlist_dir: "./setlists".to_string(),
+        }
+    }
+}
+
+/// Where the main window was left. Sizes and positions are in logical
+/// pixels.
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// Size when not maximized.
+    pub width: f32,
+    pub height: f32,
+    /// Top-left corner when not maximized; `None` lets the platform place
+    /// it, as Wayland always does.
+    pub position: Option<(f32, f32)>,
+    pub maximized: bool,
+}
+
+#[cfg(feature = "gui")]
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280.0,
+            height: 800.0,
+            position: None,
+            maximized: true,
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+impl WindowSettings {
+    /// Smallest size the window can be made.
+    pub const MIN_WIDTH: f32 = 800.0;
+    pub const MIN_HEIGHT: f32 = 600.0;
+
+    /// These settings with the window made to fit a monitor of the given
+    /// size, and put back in the middle of it if it lies wholly outside.
+    ///
+    /// The monitor's size is all there is to go on, not where it sits on
+    /// the desktop, so a window overhanging it is left alone: that is
+    /// usually one on a second display. One that misses it completely is
+    /// usually on a display that has been unplugged.
+    pub fn fitted_to(&self, monitor_width: f32, monitor_height: f32) -> Self {
+        let width = self.width.min(monitor_width).max(Self::MIN_WIDTH);
+        let height = self.height.min(monitor_height).max(Self::MIN_HEIGHT);
+        let position = self.position.map(|(x, y)| {
+            let on_monitor =
+                x < monitor_width && y < monitor_height && x + width > 0.0 && y + height > 0.0;
+            if on_monitor {
+                (x, y)
+            } else {
+                (
+                    ((monitor_width - width) / 2.0).max(0.0),
+                    ((monitor_height - height) / 2.0).max(0.0),
+                )
+            }
+        });
+        Self {
+            width,
+            height,
+            position,
+            maximized: self.maximized,
+        }
+    }
+}
+
+/// Display choices on the main window. Which stage cards are collapsed is
+/// kept per preset in [`Settings::collapsed_stages`], and the theme in
+/// [`Settings::theme`].
+#[cfg(feature = "gui")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutSettings {
+    pub tab: Tab,
+    /// Live output level bars on the stage cards.
+    pub stage_levels: bool,
+}
+
+/// Migrations from each settings version to the next; see
+/// [`rustortion_core::migration`].
+const SETTINGS_MIGRATIONS: [Migration; 1] = [migrate_v0_to_v1];
+
+/// Schema version settings are saved with: one past the last migration.
+pub const SETTINGS_VERSION: u32 = SETTINGS_MIGRATIONS.len() as u32;
+
+/// Move the directories, which used to sit at the top level, into `paths`.
+fn migrate_v0_to_v1(mut value: Value) -> Value {
+    const PATH_KEYS: [&str; 6] = [
+        "recording_dir",
+        "ir_dir",
+        "nam_dir",
+        "captures_dir",
+        "preset_dir",
+        "setlist_dir",
+    ];
+
+    let Some(obj) = value.as_object_mut() else {
+        return value;
+    };
+    let paths: serde_json::Map<String, Value> = PATH_KEYS
+        .iter()
+        .filter_map(|&key| Some((key.to_string(), obj.remove(key)?)))
+        .collect();
+    obj.insert("paths".to_string(), Value::Object(paths));
+    value
+}
+
+#[cfg(feature = "gui")]
+fn default_hotkeys() -> HotkeySettings {
+    HotkeySettings::with_quick_slots()
+}
+
+#[cfg(feature = "gui")]
+const fn default_recent_presets_limit() -> usize {
+    DEFAULT_RECENT_PRESETS
+}
+
+const fn default_chain_lint() -> bool {
+    true
+}
+
+const fn default_ir_max_seconds() -> f32 {
+    DEFAULT_MAX_IR_SECONDS
+}
+
+const fn default_ir_memory_mb() -> usize {
+    DEFAULT_IR_MEMORY_MB
+}
+
+#[allow(clippy::unsafe_derive_deserialize)] // unsafe is only for set_var, unrelated to Deserialize
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    /// Schema version the file was written with; 0 before versioning.
+    #[serde(default)]
+    pub version: u32,
+    pub audio: AudioSettings,
+    pub midi: MidiSettings,
+    #[serde(default)]
+    pub watchdog: WatchdogSettings,
+    #[serde(default)]
+    pub remote: RemoteSettings,
+    #[serde(default)]
+    pub hooks: HookSettings,
+    #[serde(default)]
+    pub paths: PathSettings,
+    /// Start and stop recording with the JACK transport.
+    #[serde(default)]
+    pub record_follow_transport: bool,
+    /// Log parameter changes during a take to a sidecar next to the WAV.
+    #[serde(default)]
+    pub record_automation: bool,
+    #[serde(default)]
+    pub tuner_algorithm: TunerAlgorithm,
+    #[serde(default)]
+    pub tuner_mode: TunerMode,
+    /// Flag questionable stage orderings on the stage cards.
+    #[serde(default = "default_chain_lint")]
+    pub chain_lint: bool,
+    /// Carry locked stages into the chain of each preset switched to.
+    #[serde(default)]
+    pub keep_locked_stages: bool,
+    /// While the metronome runs, swap a switched-to preset's amp chain in
+    /// on its next bar. The rest of the preset applies at once.
+    #[serde(default)]
+    pub quantize_chain_changes: bool,
+    /// IRs longer than this after resampling are refused (at most 5 s).
+    #[serde(default = "default_ir_max_seconds")]
+    pub ir_max_seconds: f32,
+    /// Cap on memory for cached IRs and the playing IR's convolvers, in MB.
+    /// Past it the least recently used IRs are dropped from the cache.
+    #[serde(default = "default_ir_memory_mb")]
+    pub ir_memory_mb: usize,
+    /// Mic and speaker names picked out of IR file names for the picker's tags.
+    #[serde(default)]
+    pub ir_tags: IrTagVocabulary,
+    /// Setlist being played through, and the song it is on.
+    #[serde(default)]
+    pub active_setlist: Option<String>,
+    #[serde(default)]
+    pub setlist_position: usize,
     pub ir_bypassed: bool,
     pub selected_preset: Option<String>,
+    #[cfg(feature = "gui")]
     #[serde(default)]
     pub language: Language,
+    #[cfg(feature = "gui")]
     #[serde(default)]
+    pub theme: ThemeChoice,
+    /// Quick-slot hotkeys are seeded only when no hotkeys were saved.
+    #[cfg(feature = "gui")]
+    #[serde(default = "default_hotkeys")]
     pub hotkeys: HotkeySettings,
+    /// Recently selected presets, newest first, for the preset bar's chips
+    /// and the "previous preset" action.
+    #[cfg(feature = "gui")]
+    #[serde(default)]
+    pub recent_presets: Vec<String>,
+    /// How many recent presets to remember.
+    #[cfg(feature = "gui")]
+    #[serde(default = "default_recent_presets_limit")]
+    pub recent_presets_limit: usize,
     #[serde(default)]
     pub collapsed_stages: HashMap<String, Vec<bool>>,
+    /// Input trim in dB per JACK input port, measured by input calibration.
+    #[serde(default)]
+    pub input_calibrations: HashMap<String, f32>,
+    /// DI recording the test source loops, for profiling without a guitar.
+    #[serde(default)]
+    pub test_di_file: String,
+    /// WAV played as the metronome click; empty plays the bundled ticks.
+    #[serde(default)]
+    pub metronome_tick: String,
+    /// Tempo, time signature and monitoring of the metronome.
+    #[serde(default)]
+    pub metronome: MetronomeConfig,
+    /// Write the metronome to a WAV of its own next to each take it plays
+    /// along with.
+    #[serde(default)]
+    pub record_click_track: bool,
+    /// Run takes through a gentle soft clip from about −6 dBFS as they're
+    /// written, so peaks saturate instead of clipping. The monitored output
+    /// is left alone.
+    #[serde(default)]
+    pub recording_softclip: bool,
+    /// Output tilt in dB around 650 Hz, applied on top of every preset.
+    #[serde(default)]
+    pub tilt_db: f32,
+    #[cfg(feature = "gui")]
+    #[serde(default)]
+    pub window: WindowSettings,
+    #[cfg(feature = "gui")]
+    #[serde(default)]
+    pub layout: LayoutSettings,
+    /// Without the GUI, its settings (language, theme, window, hotkeys) are kept here as
+    /// raw JSON so a headless build saving the file doesn't drop them.
+    #[cfg(not(feature = "gui"))]
+    #[serde(flatten)]
+    pub gui: HashMap<String, serde_json::Value>,
 }
 
 impl std::fmt::Display for Settings {
@@ -105,21 +501,57 @@ impl std::fmt::Display for Settings {
         writeln!(f, "MIDI Settings:")?;
         writeln!(f, "{}", self.midi)?;
 
-        writeln!(f, "Hotkey Settings:")?;
-        writeln!(f, "{}", self.hotkeys)?;
+        writeln!(f, "Watchdog Settings:")?;
+        writeln!(f, "{}", self.watchdog)?;
+
+        writeln!(f, "Remote Control Settings:")?;
+        writeln!(f, "{}", self.remote)?;
+
+        writeln!(f, "Hook Settings:")?;
+        writeln!(f, "{}", self.hooks)?;
+
+        #[cfg(feature = "gui")]
+        {
+            writeln!(f, "Hotkey Settings:")?;
+            writeln!(f, "{}", self.hotkeys)?;
+        }
+
+        writeln!(f, "Paths:")?;
+        writeln!(f, "{}", self.paths)?;
 
         writeln!(f, "Settings:")?;
-        writeln!(f, "Recording Directory: {}", self.recording_dir)?;
-        writeln!(f, "Impulse Response Directory: {}", self.ir_dir)?;
-        writeln!(f, "NAM Directory: {}", self.nam_dir)?;
-        writeln!(f, "Preset Directory: {}", self.preset_dir)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(
+            f,
+            "Record Follows Transport: {}",
+            self.record_follow_transport
+        )?;
+        writeln!(f, "Tuner Algorithm: {}", self.tuner_algorithm)?;
+        writeln!(f, "Tuner Mode: {}", self.tuner_mode)?;
+        writeln!(
+            f,
+            "Metronome: {} BPM, {}/4, click in monitor: {}",
+            self.metronome.bpm, self.metronome.beats_per_bar, self.metronome.in_monitor
+        )?;
+        writeln!(f, "Record Click Track: {}", self.record_click_track)?;
+        writeln!(f, "Recording Soft Clip: {}", self.recording_softclip)?;
+        writeln!(f, "Chain Lint: {}", self.chain_lint)?;
+        writeln!(f, "Keep Locked Stages: {}", self.keep_locked_stages)?;
+        writeln!(f, "Quantize Chain Changes: {}", self.quantize_chain_changes)?;
+        writeln!(f, "Max IR Length: {} s", self.ir_max_seconds)?;
+        writeln!(f, "IR Memory Budget: {} MB", self.ir_memory_mb)?;
         writeln!(f, "IR Bypassed: {}", self.ir_bypassed)?;
+        writeln!(f, "Input Trim: {:+.1} dB", self.input_trim_db())?;
         writeln!(
             f,
             "Selected Preset: {}",
             self.selected_preset.as_deref().unwrap_or("None")
         )?;
-        writeln!(f, "Language: {}", self.language)?;
+        #[cfg(feature = "gui")]
+        {
+            writeln!(f, "Language: {}", self.language)?;
+            writeln!(f, "Theme: {}", String::from(self.theme.clone()))?;
+        }
         Ok(())
     }
 }
@@ -127,42 +559,105 @@ impl std::fmt::Display for Settings {
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             audio: AudioSettings::default(),
             midi: MidiSettings::default(),
-            recording_dir: "./recordings".to_string(),
-            ir_dir: "./impulse_responses".to_string(),
-            nam_dir: default_nam_dir(),
-            preset_dir: "./presets".to_string(),
+            watchdog: WatchdogSettings::default(),
+            remote: RemoteSettings::default(),
+            hooks: HookSettings::default(),
+            paths: PathSettings::default(),
+            record_follow_transport: false,
+            record_automation: false,
+            tuner_algorithm: TunerAlgorithm::default(),
+            tuner_mode: TunerMode::default(),
+            chain_lint: true,
+            keep_locked_stages: false,
+            quantize_chain_changes: false,
+            ir_max_seconds: DEFAULT_MAX_IR_SECONDS,
+            ir_memory_mb: DEFAULT_IR_MEMORY_MB,
+            ir_tags: IrTagVocabulary::default(),
+            active_setlist: None,
+            setlist_position: 0,
             ir_bypassed: false,
             selected_preset: None,
+            #[cfg(feature = "gui")]
             language: Language::default(),
-            hotkeys: HotkeySettings::default(),
+            #[cfg(feature = "gui")]
+            theme: ThemeChoice::default(),
+            #[cfg(feature = "gui")]
+            hotkeys: default_hotkeys(),
+            #[cfg(feature = "gui")]
+            recent_presets: Vec::new(),
+            #[cfg(feature = "gui")]
+            recent_presets_limit: DEFAULT_RECENT_PRESETS,
             collapsed_stages: HashMap::new(),
+            input_calibrations: HashMap::new(),
+            test_di_file: String::new(),
+            metronome_tick: String::new(),
+            metronome: MetronomeConfig::default(),
+            record_click_track: false,
+            recording_softclip: false,
+            tilt_db: 0.0,
+            #[cfg(feature = "gui")]
+            window: WindowSettings::default(),
+            #[cfg(feature = "gui")]
+            layout: LayoutSettings::default(),
+            #[cfg(not(feature = "gui"))]
+            gui: HashMap::new(),
         }
     }
 }
 
 impl Settings {
+    /// Calibrated trim for the selected input port, `0.0` if uncalibrated.
+    pub fn input_trim_db(&self) -> f32 {
+        self.input_calibrations
+            .get(&self.audio.input_port)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
     pub fn load() -> Result<Self> {
         let settings_path = Self::get_settings_path();
 
         if settings_path.exists() {
             let contents =
                 fs::read_to_string(&settings_path).context("Failed to read settings file")?;
-            let settings: Self =
-                serde_json::from_str(&contents).context("Failed to parse settings")?;
+            let settings = Self::from_json(&contents)?;
             debug!("Loaded settings from {}", settings_path.display());
             Ok(settings)
         } else {
+            // Not saved yet: the file appears once first-run setup is done
+            // or skipped, so quitting part way through shows it again.
             info!("No settings file found, using defaults");
-            let settings = Self::default();
-            // Try to save defaults, but don't fail if we can't
-            let _ = settings.save();
-            Ok(settings)
+            Ok(Self::default())
         }
     }
 
+    /// No settings have been saved yet, so the app runs its first-run setup.
+    pub fn is_first_run() -> bool {
+        !Self::get_settings_path().exists()
+    }
+
+    /// Parse the contents of a settings file, migrating older versions
+    /// first. One from a newer version loads as well as it can and keeps its
+    /// version, which makes it read-only.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(json).context("Failed to parse settings")?;
+        let migrated = migrate(value, &SETTINGS_MIGRATIONS, "settings.json");
+        serde_json::from_value(migrated.value).context("Failed to parse settings")
+    }
+
+    /// Written by a newer version than this one, so it mustn't be saved
+    /// back over: settings this version doesn't know about would be lost.
+    pub const fn is_read_only(&self) -> bool {
+        self.version > SETTINGS_VERSION
+    }
+
     pub fn save(&self) -> Result<()> {
+        if self.is_read_only() {
+            bail!("Settings were written by a newer version; not overwriting them");
+        }
         let settings_path = Self::get_settings_path();
 
         // Ensure the config directory exists
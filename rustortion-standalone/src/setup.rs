@@ -0,0 +1,110 @@
+//! The parts of first-run setup that don't need the GUI: finding an audio
+//! server to run on, and giving a new install its folders and the factory
+//! presets.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use jack::{Client, ClientOptions};
+use log::{debug, info};
+use rust_embed::RustEmbed;
+
+use crate::settings::PathSettings;
+
+/// Name of the throwaway client [`AudioServer::detect`] opens.
+const PROBE_CLIENT_NAME: &str = "rustortion_probe";
+
+#[derive(RustEmbed)]
+#[folder = "../presets/"]
+#[include = "*.json"]
+struct FactoryPresets;
+
+/// What there is to run on, as far as setup can tell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AudioServer {
+    /// A JACK server accepted a client.
+    Jack,
+    /// PipeWire is running and its JACK support accepted a client.
+    PipeWireJack,
+    /// PipeWire is running, but JACK clients can't reach it: its JACK
+    /// support isn't installed, or the app wasn't started through `pw-jack`.
+    PipeWireWithoutJack,
+    /// Neither JACK nor PipeWire.
+    #[default]
+    None,
+}
+
+impl AudioServer {
+    /// Probe with a client that never starts a server, closed straight away.
+    pub fn detect() -> Self {
+        let jack = match Client::new(PROBE_CLIENT_NAME, ClientOptions::NO_START_SERVER) {
+            Ok(_) => true,
+            Err(e) => {
+                debug!("No JACK server to probe: {e}");
+                false
+            }
+        };
+        Self::classify(jack, pipewire_running())
+    }
+
+    /// Whether a JACK client could connect, and whether PipeWire is running.
+    pub const fn classify(jack: bool, pipewire: bool) -> Self {
+        match (jack, pipewire) {
+            (true, false) => Self::Jack,
+            (true, true) => Self::PipeWireJack,
+            (false, true) => Self::PipeWireWithoutJack,
+            (false, false) => Self::None,
+        }
+    }
+
+    /// A JACK client can connect, through either server.
+    pub const fn has_jack(self) -> bool {
+        matches!(self, Self::Jack | Self::PipeWireJack)
+    }
+}
+
+/// PipeWire listens on a socket in the runtime directory.
+fn pipewire_running() -> bool {
+    std::env::var_os("XDG_RUNTIME_DIR")
+        .is_some_and(|dir| Path::new(&dir).join("pipewire-0").exists())
+}
+
+/// Create the recording, IR and preset directories where they're missing.
+/// Nothing is downloaded or copied into them here.
+pub fn create_dirs(paths: &PathSettings) -> io::Result<()> {
+    for dir in [&paths.recording_dir, &paths.ir_dir, &paths.preset_dir] {
+        fs::create_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Copy the presets that ship with the app into `dir`. A file already there
+/// under the same name is left alone, so running setup again never undoes
+/// edits to a factory preset. Returns how many were written.
+pub fn install_factory_presets(dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    let mut installed = 0;
+    for filename in FactoryPresets::iter() {
+        let path = dir.join(filename.as_ref());
+        if path.exists() {
+            continue;
+        }
+        if let Some(file) = FactoryPresets::get(&filename) {
+            fs::write(&path, file.data.as_ref())?;
+            installed += 1;
+        }
+    }
+    info!(
+        "Installed {installed} factory preset(s) into {}",
+        dir.display()
+    );
+    Ok(installed)
+}
+
+/// File names of the presets that ship with the app, sorted.
+pub fn factory_preset_files() -> Vec<String> {
+    let mut files: Vec<String> = FactoryPresets::iter().map(|f| f.to_string()).collect();
+    files.sort();
+    files
+}
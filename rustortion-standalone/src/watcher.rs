@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam::channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+use log::{debug, warn};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How long a directory has to stay quiet before it's rescanned, so copying
+/// a folder of IRs triggers one rescan rather than one per file.
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A directory the app lists files from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchedDir {
+    Irs,
+    Presets,
+}
+
+/// The watched directories, resolved so they match the paths in events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchedDirs {
+    pub ir_dir: PathBuf,
+    pub preset_dir: PathBuf,
+}
+
+impl WatchedDirs {
+    pub fn new(ir_dir: impl AsRef<Path>, preset_dir: impl AsRef<Path>) -> Self {
+        Self {
+            ir_dir: resolve(ir_dir.as_ref()),
+            preset_dir: resolve(preset_dir.as_ref()),
+        }
+    }
+
+    /// Which directories `paths` lie in. Both, if one is inside the other.
+    pub fn classify(&self, paths: &[PathBuf]) -> Vec<WatchedDir> {
+        let mut dirs = Vec::new();
+        for (dir, root) in [
+            (WatchedDir::Irs, &self.ir_dir),
+            (WatchedDir::Presets, &self.preset_dir),
+        ] {
+            if paths.iter().any(|p| p.starts_with(root)) {
+                dirs.push(dir);
+            }
+        }
+        dirs
+    }
+}
+
+/// Absolute form of `dir`, or `dir` itself if it doesn't exist (yet).
+fn resolve(dir: &Path) -> PathBuf {
+    dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf())
+}
+
+/// Holds back changes until their directory has been quiet for [`DEBOUNCE`].
+#[derive(Debug, Default)]
+pub struct Debouncer {
+    /// Each directory with changes pending, and when it last changed.
+    pending: Vec<(WatchedDir, Instant)>,
+}
+
+impl Debouncer {
+    pub fn touch(&mut self, dir: WatchedDir, now: Instant) {
+        match self.pending.iter_mut().find(|(d, _)| *d == dir) {
+            Some((_, last)) => *last = now,
+            None => self.pending.push((dir, now)),
+        }
+    }
+
+    /// When the next pending directory will be due, if any is pending.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.iter().map(|(_, last)| *last + DEBOUNCE).min()
+    }
+
+    /// Directories quiet for long enough, removed from the pending set.
+    pub fn take_due(&mut self, now: Instant) -> Vec<WatchedDir> {
+        let mut due = Vec::new();
+        self.pending.retain(|&(dir, last)| {
+            let ready = now.duration_since(last) >= DEBOUNCE;
+            if ready {
+                due.push(dir);
+            }
+            !ready
+        });
+        due
+    }
+}
+
+enum WatchEvent {
+    Fs(notify::Result<notify::Event>),
+    Shutdown,
+}
+
+/// Watches the IR and preset directories on its own thread and reports which
+/// one changed once it settles. Dropping it stops the thread.
+pub struct DirWatcher {
+    dirs: WatchedDirs,
+    event_tx: Sender<WatchEvent>,
+    change_rx: Receiver<WatchedDir>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl DirWatcher {
+    /// Start watching. A directory that can't be watched is logged and
+    /// skipped; the other is still watched.
+    pub fn spawn(dirs: WatchedDirs) -> notify::Result<Self> {
+        let (event_tx, event_rx) = unbounded();
+        let (change_tx, change_rx) = unbounded();
+
+        let fs_tx = event_tx.clone();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = fs_tx.send(WatchEvent::Fs(event));
+        })?;
+        for dir in [&dirs.ir_dir, &dirs.preset_dir] {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::Recursive) {
+                warn!("Not watching '{}': {e}", dir.display());
+            }
+        }
+
+        let thread_dirs = dirs.clone();
+        let thread = thread::Builder::new()
+            .name("dir-watcher".into())
+            .spawn(move || run(&watcher, &thread_dirs, &event_rx, &change_tx))
+            .expect("Failed to spawn directory watcher thread");
+
+        Ok(Self {
+            dirs,
+            event_tx,
+            change_rx,
+            thread: Some(thread),
+        })
+    }
+
+    /// Whether this watcher covers `dirs`; if not, replace it.
+    pub fn watches(&self, dirs: &WatchedDirs) -> bool {
+        self.dirs == *dirs
+    }
+
+    /// Directories that changed since the last call, each reported once.
+    pub fn changes(&self) -> Vec<WatchedDir> {
+        let mut changes: Vec<WatchedDir> = Vec::new();
+        for dir in self.change_rx.try_iter() {
+            if !changes.contains(&dir) {
+                changes.push(dir);
+            }
+        }
+        changes
+    }
+}
+
+impl Drop for DirWatcher {
+    fn drop(&mut self) {
+        let _ = self.event_tx.send(WatchEvent::Shutdown);
+        if let Some(handle) = self.thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// The watcher thread. `_watcher` is only held so it lives as long as the
+/// thread does.
+fn run(
+    _watcher: &RecommendedWatcher,
+    dirs: &WatchedDirs,
+    event_rx: &Receiver<WatchEvent>,
+    change_tx: &Sender<WatchedDir>,
+) {
+    let mut debouncer = Debouncer::default();
+    loop {
+        let event = match debouncer.next_deadline() {
+            Some(deadline) => match event_rx.recv_deadline(deadline) {
+                Ok(event) => Some(event),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match event_rx.recv() {
+                Ok(event) => Some(event),
+                Err(_) => break,
+            },
+        };
+
+        match event {
+            // Our own reads of IRs and presets show up as access events.
+            Some(WatchEvent::Fs(Ok(event))) if !event.kind.is_access() => {
+                let now = Instant::now();
+                for dir in dirs.classify(&event.paths) {
+                    debouncer.touch(dir, now);
+                }
+            }
+            Some(WatchEvent::Fs(Err(e))) => warn!("Directory watcher error: {e}"),
+            Some(WatchEvent::Shutdown) => break,
+            Some(WatchEvent::Fs(Ok(_))) | None => {}
+        }
+
+        for dir in debouncer.take_due(Instant::now()) {
+            debug!("{dir:?} directory changed");
+            if change_tx.send(dir).is_err() {
+                return;
+            }
+        }
+    }
+    debug!("Directory watcher shutting down");
+}
@@ -0,0 +1,38 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use jack::ClientStatus;
+use rustortion::audio::error::AudioError;
+
+#[test]
+fn missing_server_is_reported_as_server_not_found() {
+    for status in [
+        ClientStatus::FAILURE | ClientStatus::SERVER_FAILED,
+        ClientStatus::FAILURE | ClientStatus::SERVER_ERROR,
+    ] {
+        let error = AudioError::client(&jack::Error::ClientError(status));
+        assert_eq!(error, AudioError::ServerNotFound);
+    }
+}
+
+#[test]
+fn other_client_failures_keep_their_reason() {
+    let error = AudioError::client(&jack::Error::ClientError(
+        ClientStatus::FAILURE | ClientStatus::NAME_NOT_UNIQUE,
+    ));
+    assert!(matches!(error, AudioError::Client(_)));
+}
+
+#[test]
+fn port_failures_name_the_port() {
+    let error = AudioError::port(
+        "in_port",
+        &jack::Error::PortRegistrationError("in_port".to_string()),
+    );
+    assert!(matches!(
+        error,
+        AudioError::Port {
+            name: "in_port",
+            ..
+        }
+    ));
+}
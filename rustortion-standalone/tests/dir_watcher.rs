@@ -0,0 +1,70 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rustortion::watcher::{DEBOUNCE, Debouncer, DirWatcher, WatchedDir, WatchedDirs};
+
+#[test]
+fn a_burst_of_changes_is_reported_once_it_settles() {
+    let start = Instant::now();
+    let mut debouncer = Debouncer::default();
+    debouncer.touch(WatchedDir::Irs, start);
+    debouncer.touch(WatchedDir::Irs, start + DEBOUNCE / 2);
+
+    assert_eq!(
+        debouncer.next_deadline(),
+        Some(start + DEBOUNCE / 2 + DEBOUNCE)
+    );
+    assert!(debouncer.take_due(start + DEBOUNCE).is_empty());
+    assert_eq!(
+        debouncer.take_due(start + DEBOUNCE / 2 + DEBOUNCE),
+        [WatchedDir::Irs]
+    );
+    assert_eq!(debouncer.next_deadline(), None);
+}
+
+#[test]
+fn each_directory_settles_on_its_own() {
+    let start = Instant::now();
+    let mut debouncer = Debouncer::default();
+    debouncer.touch(WatchedDir::Presets, start);
+    debouncer.touch(WatchedDir::Irs, start + DEBOUNCE);
+
+    assert_eq!(debouncer.take_due(start + DEBOUNCE), [WatchedDir::Presets]);
+    assert_eq!(debouncer.take_due(start + DEBOUNCE * 2), [WatchedDir::Irs]);
+}
+
+#[test]
+fn paths_are_classified_by_directory() {
+    let dirs = WatchedDirs {
+        ir_dir: PathBuf::from("/amp/irs"),
+        preset_dir: PathBuf::from("/amp/presets"),
+    };
+    assert_eq!(
+        dirs.classify(&[PathBuf::from("/amp/irs/Cabs/v30.wav")]),
+        [WatchedDir::Irs]
+    );
+    assert_eq!(
+        dirs.classify(&[PathBuf::from("/amp/presets/Lead.json")]),
+        [WatchedDir::Presets]
+    );
+    assert!(dirs.classify(&[PathBuf::from("/amp/irs.bak")]).is_empty());
+}
+
+#[test]
+fn a_new_file_is_reported_for_its_directory() {
+    let irs = tempfile::TempDir::new().unwrap();
+    let presets = tempfile::TempDir::new().unwrap();
+    let watcher = DirWatcher::spawn(WatchedDirs::new(irs.path(), presets.path())).unwrap();
+
+    std::fs::write(presets.path().join("Lead.json"), "{}").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut changes = Vec::new();
+    while changes.is_empty() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(50));
+        changes = watcher.changes();
+    }
+    assert_eq!(changes, [WatchedDir::Presets]);
+}
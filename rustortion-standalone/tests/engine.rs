@@ -4,10 +4,14 @@ use anyhow::Result;
 use assert_no_alloc::{AllocDisabler, assert_no_alloc, reset_violation_count, violation_count};
 use rustortion_core::amp::chain::AmplifierChain;
 use rustortion_core::amp::stages::level::LevelStage;
-use rustortion_core::audio::engine::Engine;
+use rustortion_core::audio::ambience::{Ambience, AmbienceConfig};
+use rustortion_core::audio::double_tracker::{DoubleTracker, DoubleTrackerConfig};
+use rustortion_core::audio::engine::{Engine, EngineHandle, PreparedIr, SampleRateParts};
 use rustortion_core::audio::peak_meter::PeakMeter;
 use rustortion_core::audio::rt_drop::RtDropHandle;
 use rustortion_core::audio::samplers::Samplers;
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
 use rustortion_core::metronome::Metronome;
 use rustortion_core::tuner::Tuner;
 
@@ -313,6 +317,61 @@ fn engine_tuner_enabled_no_output() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn engine_tuner_toggle_fades_without_allocating() -> Result<()> {
+    const SAMPLE_RATE: usize = 48_000;
+    const BUFFER_SIZE: usize = 128;
+
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BUFFER_SIZE, 1.0, SAMPLE_RATE)?;
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    let (mut engine, handle) = Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )?;
+
+    let input = vec![0.5f32; BUFFER_SIZE];
+    let mut output = vec![0.0f32; BUFFER_SIZE];
+    engine.process(&input, &mut output)?;
+    let level = output[BUFFER_SIZE - 1];
+    assert!(level > 0.0, "expected signal before the tuner is on");
+
+    let mut blocks = Vec::with_capacity(16);
+    let violations = check_no_alloc(|| {
+        for enabled in [true, false] {
+            handle.set_tuner_enabled(enabled);
+            for _ in 0..8 {
+                engine.process(&input, &mut output).unwrap();
+                blocks.push((output[0], output[BUFFER_SIZE - 1]));
+            }
+        }
+    });
+    assert_eq!(violations, 0, "tuner toggling allocated on RT path");
+
+    // Muting ramps down rather than cutting to zero mid-waveform...
+    let (first, last) = blocks[0];
+    assert!(
+        first > 0.0 && last < first,
+        "expected a fade out: {first} -> {last}"
+    );
+    assert!(blocks[7].0 == 0.0 && blocks[7].1 == 0.0, "expected silence");
+
+    // ...and unmuting ramps back up to the original level.
+    let (first, last) = blocks[8];
+    assert!(
+        first < level && last > first,
+        "expected a fade in: {first} -> {last}"
+    );
+    assert!((blocks[15].1 - level).abs() < 1e-6, "expected full level");
+
+    Ok(())
+}
+
 #[test]
 fn engine_set_parameter_updates_live_stage() -> Result<()> {
     const SAMPLE_RATE: usize = 48_000;
@@ -369,3 +428,259 @@ fn engine_set_parameter_updates_live_stage() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn engine_applies_input_trim_and_meters_raw_input() -> Result<()> {
+    const SAMPLE_RATE: usize = 48000;
+    const BUFFER_SIZE: usize = 128;
+    const OVERSAMPLE_FACTOR: f64 = 1.0;
+
+    let (tuner, _) = Tuner::new(SAMPLE_RATE);
+    let samplers = Samplers::new(BUFFER_SIZE, OVERSAMPLE_FACTOR, SAMPLE_RATE)?;
+    let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+    let (input_meter, input_meter_handle) = PeakMeter::new(SAMPLE_RATE);
+    let metronome = Metronome::new(120.0, SAMPLE_RATE);
+    let (mut engine, handle) = Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )?;
+    engine.set_input_meter(input_meter);
+
+    let input = vec![0.5f32; BUFFER_SIZE];
+    let mut untrimmed = vec![0.0f32; BUFFER_SIZE];
+    let mut trimmed = vec![0.0f32; BUFFER_SIZE];
+
+    engine.process(&input, &mut untrimmed)?;
+    handle.set_input_trim_db(-20.0);
+    engine.process(&input, &mut trimmed)?;
+
+    for (u, t) in untrimmed.iter().zip(&trimmed) {
+        assert!((u * 0.1 - t).abs() < 1e-5, "expected -20 dB: {u} -> {t}");
+    }
+
+    // The calibration meter sees the input as it arrives, before the trim.
+    let info = input_meter_handle.get_info();
+    assert!((info.peak_linear - 0.5).abs() < 1e-6);
+
+    Ok(())
+}
+
+#[test]
+fn engine_ambience_splits_stereo_around_the_mono_signal() -> Result<()> {
+    const SAMPLE_RATE: usize = 48_000;
+    const BUFFER_SIZE: usize = 128;
+
+    let new_engine = || {
+        let (tuner, _) = Tuner::new(SAMPLE_RATE);
+        let samplers = Samplers::new(BUFFER_SIZE, 1.0, SAMPLE_RATE).unwrap();
+        let (peak_meter, _) = PeakMeter::new(SAMPLE_RATE);
+        let metronome = Metronome::new(120.0, SAMPLE_RATE);
+        Engine::new(
+            tuner,
+            samplers,
+            None,
+            peak_meter,
+            metronome,
+            RtDropHandle::new().0,
+        )
+        .unwrap()
+    };
+    let (mut mono_engine, mono_handle) = new_engine();
+    let (mut stereo_engine, stereo_handle) = new_engine();
+
+    let input: Vec<f32> = (0..BUFFER_SIZE)
+        .map(|i| (2.0 * std::f32::consts::PI * 220.0 * i as f32 / SAMPLE_RATE as f32).sin() * 0.3)
+        .collect();
+    let mut mono = vec![0.0f32; BUFFER_SIZE];
+    let mut left = vec![0.0f32; BUFFER_SIZE];
+    let mut right = vec![0.0f32; BUFFER_SIZE];
+
+    // Off, both channels carry the mono signal.
+    mono_engine.process(&input, &mut mono)?;
+    stereo_engine.process_stereo(&input, &mut left, &mut right)?;
+    assert_eq!(left, right);
+    assert_eq!(left, mono);
+
+    let config = AmbienceConfig {
+        amount: 0.8,
+        decay: 0.7,
+        tone: 0.5,
+    };
+    mono_handle.set_ambience(config);
+    stereo_handle.set_ambience(config);
+
+    let violations = check_no_alloc(|| {
+        for _ in 0..64 {
+            mono_engine.process(&input, &mut mono).unwrap();
+            stereo_engine
+                .process_stereo(&input, &mut left, &mut right)
+                .unwrap();
+        }
+    });
+    assert_eq!(violations, 0, "stereo ambience allocated on RT path");
+
+    assert!(
+        left.iter().zip(&right).any(|(l, r)| (l - r).abs() > 1e-4),
+        "expected the ambience to decorrelate left and right"
+    );
+    for ((l, r), m) in left.iter().zip(&right).zip(&mono) {
+        assert!(
+            (0.5 * (l + r) - m).abs() < 1e-5,
+            "mono sum {} should match the mono output {m}",
+            0.5 * (l + r)
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn engine_sample_rate_change_mutes_until_the_new_chain_arrives() -> Result<()> {
+    const OLD_RATE: usize = 48_000;
+    const NEW_RATE: usize = 96_000;
+    const BUFFER_SIZE: usize = 128;
+
+    let (tuner, tuner_handle) = Tuner::new(OLD_RATE);
+    let samplers = Samplers::new(BUFFER_SIZE, 1.0, OLD_RATE)?;
+    let (peak_meter, _) = PeakMeter::new(OLD_RATE);
+    let metronome = Metronome::new(120.0, OLD_RATE);
+    let (mut engine, handle) = Engine::new(
+        tuner,
+        samplers,
+        None,
+        peak_meter,
+        metronome,
+        RtDropHandle::new().0,
+    )?;
+
+    let input = vec![0.5f32; BUFFER_SIZE];
+    let mut output = vec![0.0f32; BUFFER_SIZE];
+    engine.process(&input, &mut output)?;
+    assert!(output.iter().any(|&x| x != 0.0));
+
+    handle.set_sample_rate(SampleRateParts {
+        samplers: Samplers::new(BUFFER_SIZE, 1.0, NEW_RATE)?,
+        tuner: Tuner::for_handle(NEW_RATE, &tuner_handle),
+        metronome: Metronome::new(120.0, NEW_RATE),
+        ambience: Ambience::new(AmbienceConfig::default(), NEW_RATE),
+        double_tracker: DoubleTracker::new(DoubleTrackerConfig::default(), NEW_RATE),
+        ir_follows: false,
+    });
+
+    let violations = check_no_alloc(|| {
+        for _ in 0..4 {
+            engine.process(&input, &mut output).unwrap();
+        }
+    });
+    assert_eq!(violations, 0, "sample rate swap allocated on RT path");
+    assert_eq!(engine.sample_rate(), NEW_RATE);
+    assert_eq!(engine.diagnostics().snapshot().sample_rate, NEW_RATE);
+    assert!(
+        output.iter().all(|&x| x == 0.0),
+        "expected silence until the chain is rebuilt"
+    );
+
+    handle.set_amp_chain(AmplifierChain::new());
+    for _ in 0..8 {
+        engine.process(&input, &mut output)?;
+    }
+    assert!(
+        output.iter().all(|&x| x != 0.0),
+        "expected the output back once the chain arrived"
+    );
+
+    Ok(())
+}
+
+const IR_LEN: usize = 256;
+
+/// An impulse, so the cabinet passes its input through.
+fn impulse() -> Convolver {
+    let mut ir = vec![0.0f32; IR_LEN];
+    ir[0] = 1.0;
+    let mut convolver = Convolver::new_fir(IR_LEN);
+    convolver.set_ir(&ir).unwrap();
+    convolver
+}
+
+/// An engine with a cabinet, retuned from 48 to 96 kHz with an IR to follow
+/// and the new chain already in.
+fn retuned_engine_awaiting_ir(buffer_size: usize) -> Result<(Engine, EngineHandle)> {
+    let (tuner, tuner_handle) = Tuner::new(48_000);
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, IR_LEN);
+    cabinet.set_convolver(impulse());
+    let (mut engine, handle) = Engine::new(
+        tuner,
+        Samplers::new(buffer_size, 1.0, 48_000)?,
+        Some(cabinet),
+        PeakMeter::new(48_000).0,
+        Metronome::new(120.0, 48_000),
+        RtDropHandle::new().0,
+    )?;
+
+    handle.set_sample_rate(SampleRateParts {
+        samplers: Samplers::new(buffer_size, 1.0, 96_000)?,
+        tuner: Tuner::for_handle(96_000, &tuner_handle),
+        metronome: Metronome::new(120.0, 96_000),
+        ambience: Ambience::new(AmbienceConfig::default(), 96_000),
+        double_tracker: DoubleTracker::new(DoubleTrackerConfig::default(), 96_000),
+        ir_follows: true,
+    });
+    handle.set_amp_chain(AmplifierChain::new());
+
+    let input = vec![0.5f32; buffer_size];
+    let mut output = vec![0.0f32; buffer_size];
+    for _ in 0..8 {
+        engine.process(&input, &mut output)?;
+    }
+    assert!(
+        output.iter().all(|&x| x == 0.0),
+        "expected silence through the old-rate IR"
+    );
+    Ok((engine, handle))
+}
+
+#[test]
+fn engine_sample_rate_change_mutes_until_the_resampled_ir_arrives() -> Result<()> {
+    const BUFFER_SIZE: usize = 128;
+    let (mut engine, handle) = retuned_engine_awaiting_ir(BUFFER_SIZE)?;
+
+    handle.swap_ir_convolver(PreparedIr {
+        name: "impulse".to_string(),
+        convolver: Box::new(impulse()),
+    });
+    let input = vec![0.5f32; BUFFER_SIZE];
+    let mut output = vec![0.0f32; BUFFER_SIZE];
+    for _ in 0..8 {
+        engine.process(&input, &mut output)?;
+    }
+    assert!(
+        output.iter().all(|&x| x != 0.0),
+        "expected the output back once the IR arrived"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn engine_sample_rate_change_stops_waiting_for_an_ir_that_never_arrives() -> Result<()> {
+    const BUFFER_SIZE: usize = 128;
+    let (mut engine, _handle) = retuned_engine_awaiting_ir(BUFFER_SIZE)?;
+
+    // Well past the two seconds it waits at 96 kHz.
+    let input = vec![0.5f32; BUFFER_SIZE];
+    let mut output = vec![0.0f32; BUFFER_SIZE];
+    for _ in 0..(3 * 96_000 / BUFFER_SIZE) {
+        engine.process(&input, &mut output)?;
+    }
+    assert!(
+        output.iter().all(|&x| x != 0.0),
+        "expected the output back after the IR timed out"
+    );
+
+    Ok(())
+}
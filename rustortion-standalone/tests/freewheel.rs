@@ -0,0 +1,384 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! The bounce state machine against a real processor, with a stand-in for
+//! the audio manager and synthetic blocks in place of a JACK server.
+
+use std::cell::Cell;
+use std::io::{self, Cursor, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rustortion::audio::freewheel::{Bounce, BounceError, BounceHost, InputFeed};
+use rustortion::audio::processor::{ProcessContext, Processor};
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::level::LevelStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle, EngineMessage};
+use rustortion_core::audio::recorder::{Recorder, RecordingProgress};
+use tempfile::TempDir;
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+const FILE_FRAMES: usize = 1_000;
+const TAIL_FRAMES: usize = 256;
+const AMPLITUDE: f32 = 0.25;
+
+/// Stands in for a JACK cycle: a live input of constant level, and whatever
+/// the processor wrote to the ports.
+struct MockCycle {
+    input: Vec<f32>,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    silenced: bool,
+}
+
+impl MockCycle {
+    fn live() -> Self {
+        Self {
+            input: vec![AMPLITUDE; BUFFER_SIZE],
+            left: vec![f32::NAN; BUFFER_SIZE],
+            right: vec![f32::NAN; BUFFER_SIZE],
+            silenced: false,
+        }
+    }
+
+    fn peak(&self) -> f32 {
+        self.left.iter().fold(0.0, |m, s| m.max(s.abs()))
+    }
+}
+
+impl ProcessContext for MockCycle {
+    fn input(&self) -> &[f32] {
+        &self.input
+    }
+
+    fn input_right(&self) -> &[f32] {
+        &self.input
+    }
+
+    fn write_output(&mut self, left: &[f32], right: &[f32]) {
+        self.left.copy_from_slice(left);
+        self.right.copy_from_slice(right);
+    }
+
+    fn write_metronome_output(&mut self, _samples: &[f32]) {}
+
+    fn silence_output(&mut self) {
+        self.silenced = true;
+        self.left.fill(0.0);
+        self.right.fill(0.0);
+    }
+}
+
+/// Stands in for the disk: holds the WAV in memory, stalls every write
+/// until opened, or fails them all when broken.
+#[derive(Clone, Default)]
+struct MockDisk {
+    bytes: Arc<Mutex<Cursor<Vec<u8>>>>,
+    open: Arc<AtomicBool>,
+    broken: bool,
+}
+
+impl MockDisk {
+    fn frames(&self) -> u32 {
+        let bytes = self.bytes.lock().unwrap().get_ref().clone();
+        let reader = hound::WavReader::new(Cursor::new(bytes)).unwrap();
+        reader.duration()
+    }
+}
+
+impl Write for MockDisk {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.broken {
+            return Err(io::Error::other("disk full"));
+        }
+        while !self.open.load(Ordering::Relaxed) {
+            thread::sleep(Duration::from_millis(1));
+        }
+        self.bytes.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MockDisk {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.bytes.lock().unwrap().seek(pos)
+    }
+}
+
+/// What the audio manager would do, recorded for the assertions.
+struct MockHost {
+    handle: EngineHandle,
+    feed: Option<Arc<InputFeed>>,
+    dir: TempDir,
+    /// Record into this rather than a file in `dir`.
+    disk: Option<MockDisk>,
+    take: Option<Arc<RecordingProgress>>,
+    freewheel: Cell<bool>,
+    refuse_freewheel: bool,
+    taking: bool,
+    held: bool,
+}
+
+impl BounceHost for MockHost {
+    fn input_feed(&self) -> Option<Arc<InputFeed>> {
+        self.feed.clone()
+    }
+
+    fn set_freewheel(&self, enabled: bool) -> Result<(), String> {
+        if self.refuse_freewheel && enabled {
+            return Err("refused".into());
+        }
+        self.freewheel.set(enabled);
+        Ok(())
+    }
+
+    fn start_take(&mut self) -> Result<PathBuf, String> {
+        if let Some(disk) = &self.disk {
+            // Sized like the app's recorder, so the pool only holds a few
+            // dozen of these small blocks.
+            let recorder = Recorder::with_writer(
+                self.dir.path().join("bounce.wav"),
+                disk.clone(),
+                SAMPLE_RATE as u32,
+                Processor::MAX_BUFFER_FRAMES,
+            );
+            let path = recorder.path().to_path_buf();
+            self.take = Some(recorder.progress());
+            self.handle
+                .send(EngineMessage::StartRecording(recorder, None));
+            self.taking = true;
+            return Ok(path);
+        }
+        let path = self
+            .handle
+            .start_recording(
+                SAMPLE_RATE,
+                self.dir.path().to_str().unwrap(),
+                BUFFER_SIZE,
+                None,
+                None,
+                false,
+            )
+            .map_err(|e| e.to_string())?;
+        self.taking = true;
+        Ok(path)
+    }
+
+    fn stop_take(&mut self) {
+        self.handle.stop_recording();
+        self.taking = false;
+    }
+
+    fn take_overruns(&self) -> u64 {
+        self.take
+            .as_ref()
+            .map_or_else(|| self.handle.recording_overruns(), |p| p.overruns())
+    }
+
+    fn hold_monitoring(&mut self, held: bool) {
+        self.held = held;
+    }
+}
+
+fn setup() -> (Processor, MockHost) {
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    let mut chain = AmplifierChain::new();
+    chain.add_stage(Box::new(LevelStage::new(1.0)));
+    handle.set_amp_chain(chain);
+
+    let feed = Arc::new(InputFeed::default());
+    let processor = Processor::new(engine, BUFFER_SIZE, Arc::new(AtomicUsize::new(SAMPLE_RATE)))
+        .with_input_feed(Arc::clone(&feed));
+    let host = MockHost {
+        handle,
+        feed: Some(feed),
+        dir: TempDir::new().unwrap(),
+        disk: None,
+        take: None,
+        freewheel: Cell::new(false),
+        refuse_freewheel: false,
+        taking: false,
+        held: false,
+    };
+    (processor, host)
+}
+
+fn file() -> Arc<[f32]> {
+    vec![0.5; FILE_FRAMES].into()
+}
+
+fn run_cycle(processor: &mut Processor) -> MockCycle {
+    let mut cycle = MockCycle::live();
+    processor.process(&mut cycle);
+    cycle
+}
+
+fn assert_untouched(host: &MockHost) {
+    assert!(!host.freewheel.get());
+    assert!(!host.taking);
+    assert!(!host.held);
+    assert!(!host.feed.as_ref().unwrap().is_loaded());
+}
+
+#[test]
+fn a_bounce_feeds_the_file_then_holds_until_ended() {
+    let (mut processor, mut host) = setup();
+    let bounce = Bounce::start(&mut host, file(), TAIL_FRAMES, false, false).unwrap();
+    assert!(host.freewheel.get());
+    assert!(host.taking);
+    assert!(host.held);
+
+    let total = FILE_FRAMES + TAIL_FRAMES;
+    for i in 1..=total.div_ceil(BUFFER_SIZE) {
+        assert!(!bounce.is_finished());
+        let cycle = run_cycle(&mut processor);
+        assert!(!cycle.silenced);
+        assert_eq!(bounce.progress(), ((i * BUFFER_SIZE).min(total), total));
+    }
+    assert!(bounce.is_finished());
+
+    // Held on silence while the GUI gets round to ending it.
+    for _ in 0..4 {
+        assert!(run_cycle(&mut processor).silenced);
+    }
+    assert_eq!(bounce.progress(), (total, total));
+
+    let take = bounce.end(&mut host).unwrap();
+    assert!(take.starts_with(host.dir.path()));
+    assert_untouched(&host);
+    let cycle = run_cycle(&mut processor);
+    assert!(!cycle.silenced);
+}
+
+#[test]
+fn the_file_waits_for_the_take_to_start() {
+    let (mut processor, host) = setup();
+    let feed = host.feed.as_ref().unwrap();
+    feed.load(file(), TAIL_FRAMES, false);
+
+    for _ in 0..8 {
+        let cycle = run_cycle(&mut processor);
+        assert!(!cycle.silenced);
+        // The live input is replaced, and the file isn't playing yet.
+        assert!(cycle.peak() < 1e-3, "got a peak of {}", cycle.peak());
+    }
+    assert_eq!(feed.progress(), (0, FILE_FRAMES + TAIL_FRAMES));
+}
+
+#[test]
+fn mixing_keeps_the_live_input_under_the_file() {
+    let feed = InputFeed::default();
+    feed.load(file(), TAIL_FRAMES, true);
+    let live = vec![AMPLITUDE; BUFFER_SIZE];
+    let mut out = vec![0.0; BUFFER_SIZE];
+
+    feed.fill(&live, &mut out, false);
+    assert!(out.iter().all(|s| *s == AMPLITUDE));
+    feed.fill(&live, &mut out, true);
+    assert!(out.iter().all(|s| (s - (AMPLITUDE + 0.5)).abs() < 1e-6));
+    assert_eq!(feed.progress().0, BUFFER_SIZE);
+}
+
+#[test]
+fn refused_while_recording() {
+    let (_processor, mut host) = setup();
+    let result = Bounce::start(&mut host, file(), TAIL_FRAMES, false, true);
+    assert_eq!(result.err(), Some(BounceError::Recording));
+    assert_untouched(&host);
+}
+
+#[test]
+fn refused_without_a_feed() {
+    let (_processor, mut host) = setup();
+    host.feed = None;
+    let result = Bounce::start(&mut host, file(), TAIL_FRAMES, false, false);
+    assert_eq!(result.err(), Some(BounceError::Unsupported));
+    assert!(!host.freewheel.get());
+    assert!(!host.taking);
+    assert!(!host.held);
+}
+
+#[test]
+fn a_refused_freewheel_undoes_the_start() {
+    let (_processor, mut host) = setup();
+    host.refuse_freewheel = true;
+    let result = Bounce::start(&mut host, file(), TAIL_FRAMES, false, false);
+    assert_eq!(result.err(), Some(BounceError::Freewheel("refused".into())));
+    assert_untouched(&host);
+}
+
+#[test]
+fn the_feed_waits_for_a_slow_disk_instead_of_dropping_blocks() {
+    let (mut processor, mut host) = setup();
+    let disk = MockDisk::default();
+    host.disk = Some(disk.clone());
+    let samples: Arc<[f32]> = vec![0.5; 100 * BUFFER_SIZE].into();
+    let total = samples.len() + TAIL_FRAMES;
+    let bounce = Bounce::start(&mut host, samples, TAIL_FRAMES, false, false).unwrap();
+
+    // The writer is stuck on the header: once the pool runs dry the file
+    // stops advancing rather than outrunning it.
+    for _ in 0..200 {
+        run_cycle(&mut processor);
+    }
+    let stalled = bounce.progress().0;
+    assert!(stalled > 0 && stalled < total, "stalled at {stalled}");
+    assert!(run_cycle(&mut processor).silenced);
+    assert_eq!(bounce.progress().0, stalled);
+    assert_eq!(host.take_overruns(), 0);
+
+    disk.open.store(true, Ordering::Relaxed);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !bounce.is_finished() {
+        assert!(Instant::now() < deadline, "the bounce never finished");
+        if run_cycle(&mut processor).silenced {
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    let take = bounce.end(&mut host).unwrap();
+    assert!(take.starts_with(host.dir.path()));
+    // Stopping the take finalizes it.
+    run_cycle(&mut processor);
+    assert_eq!(
+        disk.frames() as usize,
+        total.div_ceil(BUFFER_SIZE) * BUFFER_SIZE
+    );
+}
+
+#[test]
+fn a_failed_take_ends_the_bounce_with_the_lost_blocks() {
+    let (mut processor, mut host) = setup();
+    host.disk = Some(MockDisk {
+        broken: true,
+        ..MockDisk::default()
+    });
+    let bounce = Bounce::start(&mut host, file(), TAIL_FRAMES, false, false).unwrap();
+    let take = host.take.clone().unwrap();
+    while !take.status().failed {
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // A dead writer doesn't hold the feed up.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !bounce.is_finished() {
+        assert!(Instant::now() < deadline, "the bounce never finished");
+        run_cycle(&mut processor);
+    }
+
+    match bounce.end(&mut host) {
+        Err(BounceError::Gapped { take, dropped }) => {
+            assert!(take.starts_with(host.dir.path()));
+            assert!(dropped > 0);
+        }
+        other => panic!("expected a gapped bounce, got {other:?}"),
+    }
+    assert_untouched(&host);
+}
@@ -0,0 +1,118 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rustortion::import::{DROPPED_IR_DIR, ImportRequest, import_dropped, unique_path};
+use rustortion_core::preset::Preset;
+use tempfile::TempDir;
+
+const SAMPLE_RATE: u32 = 48_000;
+
+fn write_ir(path: &Path) {
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(path, spec).unwrap();
+    for n in 0..256 {
+        writer.write_sample((-(n as f32) / 32.0).exp()).unwrap();
+    }
+    writer.finalize().unwrap();
+}
+
+fn write_preset(path: &Path, name: &str) {
+    let preset = Preset {
+        name: name.to_string(),
+        ..Preset::default()
+    };
+    fs::write(path, serde_json::to_string(&preset).unwrap()).unwrap();
+}
+
+fn request(tmp: &TempDir, paths: Vec<PathBuf>) -> ImportRequest {
+    ImportRequest {
+        paths,
+        ir_dir: tmp.path().join("irs"),
+        preset_dir: tmp.path().join("presets"),
+        sample_rate: SAMPLE_RATE as usize,
+        ir_max_seconds: 1.0,
+    }
+}
+
+#[test]
+fn taken_file_names_get_a_numbered_suffix() {
+    let tmp = TempDir::new().unwrap();
+    assert_eq!(
+        unique_path(tmp.path(), "cab", "wav"),
+        tmp.path().join("cab.wav")
+    );
+
+    fs::write(tmp.path().join("cab.wav"), "").unwrap();
+    fs::write(tmp.path().join("cab-2.wav"), "").unwrap();
+    assert_eq!(
+        unique_path(tmp.path(), "cab", "wav"),
+        tmp.path().join("cab-3.wav")
+    );
+}
+
+#[test]
+fn dropped_irs_are_copied_into_the_dropped_folder() {
+    let tmp = TempDir::new().unwrap();
+    let source = tmp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    write_ir(&source.join("v30.wav"));
+    write_ir(&source.join("GREENBACK.WAV"));
+
+    let paths = vec![source.join("v30.wav"), source.join("GREENBACK.WAV")];
+    let first = import_dropped(&request(&tmp, paths.clone()));
+    assert_eq!(first.irs, ["Dropped/v30.wav", "Dropped/GREENBACK.wav"]);
+    assert!(first.failures.is_empty());
+
+    // Dropping the same files again keeps both copies.
+    let second = import_dropped(&request(&tmp, paths));
+    assert_eq!(second.irs, ["Dropped/v30-2.wav", "Dropped/GREENBACK-2.wav"]);
+    assert!(
+        tmp.path()
+            .join("irs")
+            .join(DROPPED_IR_DIR)
+            .join("v30-2.wav")
+            .exists()
+    );
+}
+
+#[test]
+fn files_that_fail_to_decode_are_reported_and_not_copied() {
+    let tmp = TempDir::new().unwrap();
+    let broken = tmp.path().join("broken.wav");
+    let notes = tmp.path().join("notes.txt");
+    fs::write(&broken, "not a wav").unwrap();
+    fs::write(&notes, "setlist").unwrap();
+
+    let report = import_dropped(&request(&tmp, vec![broken, notes]));
+    assert!(report.irs.is_empty());
+    let failed: Vec<&str> = report.failures.iter().map(|(f, _)| f.as_str()).collect();
+    assert_eq!(failed, ["broken.wav", "notes.txt"]);
+    let dropped = tmp.path().join("irs").join(DROPPED_IR_DIR);
+    assert_eq!(fs::read_dir(dropped).unwrap().count(), 0);
+}
+
+#[test]
+fn dropped_presets_are_imported_under_a_free_name() {
+    let tmp = TempDir::new().unwrap();
+    let lead = tmp.path().join("lead.json");
+    write_preset(&lead, "Lead");
+
+    let first = import_dropped(&request(&tmp, vec![lead.clone()]));
+    let second = import_dropped(&request(&tmp, vec![lead]));
+    assert_eq!(first.presets, ["Lead"]);
+    assert_eq!(second.presets, ["Lead (2)"]);
+
+    let unnamed = tmp.path().join("unnamed.json");
+    write_preset(&unnamed, "");
+    let report = import_dropped(&request(&tmp, vec![unnamed]));
+    assert!(report.presets.is_empty());
+    assert_eq!(report.failures.len(), 1);
+}
@@ -0,0 +1,212 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! Drives the audio callback the way JACK would, with synthetic blocks in
+//! place of a server, from engine messages through to the output ports.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
+
+use rustortion::audio::processor::{ProcessContext, Processor};
+use rustortion_core::amp::chain::AmplifierChain;
+use rustortion_core::amp::stages::level::LevelStage;
+use rustortion_core::audio::engine::{Engine, EngineHandle, PreparedIr};
+use rustortion_core::ir::cabinet::{ConvolverType, IR_CROSSFADE_SAMPLES, IrCabinet};
+use rustortion_core::ir::convolver::Convolver;
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+const IR_LEN: usize = 256;
+const AMPLITUDE: f32 = 0.25;
+
+/// Stands in for a JACK cycle: the inputs it was given and whatever the
+/// processor wrote to the ports.
+struct MockCycle {
+    input: Vec<f32>,
+    input_right: Vec<f32>,
+    left: Vec<f32>,
+    right: Vec<f32>,
+    metronome: Vec<f32>,
+    silenced: bool,
+}
+
+impl MockCycle {
+    /// A cycle of `frames` of a 1 kHz sine, starting at frame `start`.
+    fn sine(start: usize, frames: usize) -> Self {
+        let input: Vec<f32> = (start..start + frames)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                AMPLITUDE * (std::f32::consts::TAU * 1_000.0 * t).sin()
+            })
+            .collect();
+        Self {
+            input_right: input.clone(),
+            input,
+            left: vec![f32::NAN; frames],
+            right: vec![f32::NAN; frames],
+            metronome: vec![0.0; frames],
+            silenced: false,
+        }
+    }
+
+    fn peak(&self) -> f32 {
+        self.left.iter().fold(0.0, |m, s| m.max(s.abs()))
+    }
+}
+
+impl ProcessContext for MockCycle {
+    fn input(&self) -> &[f32] {
+        &self.input
+    }
+
+    fn input_right(&self) -> &[f32] {
+        &self.input_right
+    }
+
+    fn write_output(&mut self, left: &[f32], right: &[f32]) {
+        let frames = left.len().min(right.len()).min(self.left.len());
+        self.left.fill(0.0);
+        self.right.fill(0.0);
+        self.left[..frames].copy_from_slice(&left[..frames]);
+        self.right[..frames].copy_from_slice(&right[..frames]);
+    }
+
+    fn write_metronome_output(&mut self, samples: &[f32]) {
+        let frames = samples.len().min(self.metronome.len());
+        self.metronome[..frames].copy_from_slice(&samples[..frames]);
+    }
+
+    fn silence_output(&mut self) {
+        self.silenced = true;
+        self.left.fill(0.0);
+        self.right.fill(0.0);
+    }
+}
+
+/// Runs cycles through a processor, keeping the frame count for the input.
+struct Harness {
+    processor: Processor,
+    handle: EngineHandle,
+    frame: usize,
+    frames: usize,
+}
+
+impl Harness {
+    fn new(oversampling: f64, cabinet: Option<IrCabinet>) -> Self {
+        let (engine, handle, _rt_drop_rx) =
+            Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, cabinet, oversampling).unwrap();
+        let mut chain = AmplifierChain::new();
+        chain.add_stage(Box::new(LevelStage::new(1.0)));
+        handle.set_amp_chain(chain);
+        let sample_rate = Arc::new(AtomicUsize::new(SAMPLE_RATE));
+        Self {
+            processor: Processor::new(engine, BUFFER_SIZE, sample_rate),
+            handle,
+            frame: 0,
+            frames: BUFFER_SIZE,
+        }
+    }
+
+    fn cycle(&mut self) -> MockCycle {
+        let mut cycle = MockCycle::sine(self.frame, self.frames);
+        self.processor.process(&mut cycle);
+        self.frame += self.frames;
+        cycle
+    }
+
+    /// Run enough cycles for filters and fades to settle.
+    fn settle(&mut self) -> MockCycle {
+        let cycles = (4 * SAMPLE_RATE / 100).div_ceil(self.frames).max(4);
+        for _ in 1..cycles {
+            self.cycle();
+        }
+        self.cycle()
+    }
+
+    fn set_buffer_size(&mut self, frames: usize) {
+        self.processor.set_buffer_size(frames).unwrap();
+        self.frames = frames;
+    }
+}
+
+/// A convolver whose IR only scales, by `gain`.
+fn scaling_ir(gain: f32) -> PreparedIr {
+    let mut ir = vec![0.0f32; IR_LEN];
+    ir[0] = gain;
+    let mut convolver = Convolver::new_fir(IR_LEN);
+    convolver.set_ir(&ir).unwrap();
+    PreparedIr {
+        name: format!("x{gain}"),
+        convolver: Box::new(convolver),
+    }
+}
+
+fn unity_cabinet() -> IrCabinet {
+    let mut cabinet = IrCabinet::new(ConvolverType::Fir, IR_LEN);
+    cabinet.set_gain(1.0);
+    cabinet
+}
+
+fn assert_peak(cycle: &MockCycle, expected: f32) {
+    assert!(!cycle.silenced, "cycle was silenced");
+    assert!(
+        (cycle.peak() - expected).abs() < 0.01,
+        "expected a peak of {expected}, got {}",
+        cycle.peak()
+    );
+}
+
+#[test]
+fn a_cycle_reaches_both_outputs() {
+    let mut harness = Harness::new(1.0, None);
+    let cycle = harness.settle();
+    assert_peak(&cycle, AMPLITUDE);
+    assert_eq!(cycle.left, cycle.right);
+}
+
+#[test]
+fn output_is_silenced_until_the_engine_follows_the_server_rate() {
+    let (engine, _handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0).unwrap();
+    let server_rate = Arc::new(AtomicUsize::new(44_100));
+    let mut processor = Processor::new(engine, BUFFER_SIZE, server_rate);
+
+    let mut cycle = MockCycle::sine(0, BUFFER_SIZE);
+    processor.process(&mut cycle);
+    assert!(cycle.silenced);
+    assert!(cycle.left.iter().all(|s| *s == 0.0));
+}
+
+/// Regression: the oversampling buffers must follow a period change, beyond
+/// the preallocated size too, or the resamplers read past their input.
+#[test]
+fn buffer_size_changes_under_oversampling() {
+    let mut harness = Harness::new(4.0, None);
+    assert_peak(&harness.settle(), AMPLITUDE);
+
+    for frames in [256, Processor::MAX_BUFFER_FRAMES + 512, 64, BUFFER_SIZE] {
+        harness.set_buffer_size(frames);
+        let cycle = harness.settle();
+        assert_eq!(cycle.left.len(), frames);
+        assert!(cycle.left.iter().all(|s| s.is_finite()));
+        assert_peak(&cycle, AMPLITUDE);
+    }
+}
+
+/// Regression: IR switches sent between cycles are applied in the order they
+/// were sent, so the last one picked is the one heard.
+#[test]
+fn ir_switches_end_on_the_last_one_sent() {
+    let mut harness = Harness::new(2.0, Some(unity_cabinet()));
+    harness.handle.swap_ir_convolver(scaling_ir(1.0));
+    assert_peak(&harness.settle(), AMPLITUDE);
+
+    harness.handle.swap_ir_convolver(scaling_ir(0.5));
+    harness.handle.swap_ir_convolver(scaling_ir(0.25));
+    harness.handle.swap_ir_convolver(scaling_ir(0.75));
+    // Past the crossfade, only the last IR is playing.
+    for _ in 0..IR_CROSSFADE_SAMPLES.div_ceil(BUFFER_SIZE) {
+        harness.cycle();
+    }
+    assert_peak(&harness.cycle(), 0.75 * AMPLITUDE);
+    assert_peak(&harness.settle(), 0.75 * AMPLITUDE);
+}
@@ -0,0 +1,60 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+//! settings.json files from older versions must keep loading, and ones from
+//! newer versions must never be written over.
+
+use rustortion::settings::{PathSettings, SETTINGS_VERSION, Settings};
+
+const V0: &str = include_str!("fixtures/settings_v0.json");
+
+#[test]
+fn v0_settings_are_migrated_on_load() {
+    let settings = Settings::from_json(V0).unwrap();
+
+    assert_eq!(settings.version, SETTINGS_VERSION);
+    assert!(!settings.is_read_only());
+
+    let defaults = PathSettings::default();
+    assert_eq!(settings.paths.recording_dir, "/home/amp/recordings");
+    assert_eq!(settings.paths.ir_dir, "/home/amp/irs");
+    assert_eq!(settings.paths.nam_dir, "/home/amp/nam");
+    assert_eq!(settings.paths.preset_dir, "/home/amp/presets");
+    assert_eq!(settings.paths.captures_dir, defaults.captures_dir);
+    assert_eq!(settings.paths.setlist_dir, defaults.setlist_dir);
+
+    // Everything else comes through untouched.
+    assert_eq!(settings.audio.input_port, "system:capture_2");
+    assert_eq!(settings.audio.oversampling_factor, 4);
+    assert!(settings.ir_bypassed);
+    assert_eq!(settings.selected_preset.as_deref(), Some("Lead"));
+}
+
+#[test]
+fn migrated_settings_save_in_the_current_layout() {
+    let settings = Settings::from_json(V0).unwrap();
+    let saved: serde_json::Value = serde_json::to_value(&settings).unwrap();
+
+    assert_eq!(saved["version"], SETTINGS_VERSION);
+    assert_eq!(saved["paths"]["ir_dir"], "/home/amp/irs");
+    assert!(saved.get("ir_dir").is_none());
+
+    let reloaded = Settings::from_json(&saved.to_string()).unwrap();
+    assert_eq!(reloaded.paths.ir_dir, "/home/amp/irs");
+    assert_eq!(reloaded.paths.preset_dir, "/home/amp/presets");
+}
+
+#[test]
+fn newer_settings_load_read_only() {
+    let mut future: serde_json::Value =
+        serde_json::to_value(Settings::from_json(V0).unwrap()).unwrap();
+    future["version"] = (SETTINGS_VERSION + 1).into();
+    future["paths"]["ir_dir"] = "/srv/irs".into();
+
+    let settings = Settings::from_json(&future.to_string()).unwrap();
+    assert_eq!(settings.version, SETTINGS_VERSION + 1);
+    assert!(settings.is_read_only());
+    // Not migrated again: a newer file's layout is left as it is.
+    assert_eq!(settings.paths.ir_dir, "/srv/irs");
+    // Refused before the settings path is even looked at.
+    assert!(settings.save().is_err());
+}
@@ -0,0 +1,67 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::fs;
+
+use rustortion::settings::PathSettings;
+use rustortion::setup::{AudioServer, create_dirs, factory_preset_files, install_factory_presets};
+use rustortion_core::preset::parse_preset;
+
+#[test]
+fn servers_are_told_apart_by_what_answers() {
+    assert_eq!(AudioServer::classify(true, false), AudioServer::Jack);
+    assert_eq!(AudioServer::classify(true, true), AudioServer::PipeWireJack);
+    assert_eq!(
+        AudioServer::classify(false, true),
+        AudioServer::PipeWireWithoutJack
+    );
+    assert_eq!(AudioServer::classify(false, false), AudioServer::None);
+
+    assert!(AudioServer::Jack.has_jack());
+    assert!(AudioServer::PipeWireJack.has_jack());
+    assert!(!AudioServer::PipeWireWithoutJack.has_jack());
+    assert!(!AudioServer::None.has_jack());
+}
+
+#[test]
+fn factory_presets_install_and_parse() {
+    let dir = tempfile::tempdir().unwrap();
+    let preset_dir = dir.path().join("presets");
+
+    let files = factory_preset_files();
+    assert!(!files.is_empty());
+    assert_eq!(install_factory_presets(&preset_dir).unwrap(), files.len());
+
+    for file in &files {
+        let json = fs::read_to_string(preset_dir.join(file)).unwrap();
+        parse_preset(&json).unwrap_or_else(|e| panic!("{file} doesn't parse: {e}"));
+    }
+}
+
+#[test]
+fn installing_again_keeps_edited_presets() {
+    let dir = tempfile::tempdir().unwrap();
+    install_factory_presets(dir.path()).unwrap();
+
+    let edited = dir.path().join(&factory_preset_files()[0]);
+    fs::write(&edited, "edited").unwrap();
+
+    assert_eq!(install_factory_presets(dir.path()).unwrap(), 0);
+    assert_eq!(fs::read_to_string(&edited).unwrap(), "edited");
+}
+
+#[test]
+fn setup_creates_nested_folders() {
+    let dir = tempfile::tempdir().unwrap();
+    let paths = PathSettings {
+        recording_dir: dir.path().join("a/recordings").display().to_string(),
+        ir_dir: dir.path().join("b/irs").display().to_string(),
+        preset_dir: dir.path().join("c/presets").display().to_string(),
+        ..PathSettings::default()
+    };
+
+    create_dirs(&paths).unwrap();
+
+    for path in [&paths.recording_dir, &paths.ir_dir, &paths.preset_dir] {
+        assert!(fs::metadata(path).unwrap().is_dir());
+    }
+}
@@ -0,0 +1,75 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use crossbeam::channel::bounded;
+use rustortion::audio::transport::{TRANSPORT_EVENT_CAPACITY, TransportEvent, TransportFollower};
+
+#[test]
+fn reports_start_and_stop_once_per_transition() {
+    let (tx, rx) = bounded(TRANSPORT_EVENT_CAPACITY);
+    let mut follower = TransportFollower::new(false, tx);
+
+    follower.update(false, 0, 0);
+    follower.update(true, 48_000, 128);
+    follower.update(true, 48_128, 256);
+    follower.update(false, 48_256, 384);
+    follower.update(false, 48_256, 512);
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![
+            TransportEvent::Started {
+                frame: 48_000,
+                engine_frame: 128
+            },
+            TransportEvent::Stopped
+        ]
+    );
+}
+
+#[test]
+fn relocation_while_rolling_is_not_an_event() {
+    let (tx, rx) = bounded(TRANSPORT_EVENT_CAPACITY);
+    let mut follower = TransportFollower::new(false, tx);
+
+    follower.update(true, 1_000, 0);
+    follower.update(true, 500_000, 128);
+    follower.update(true, 0, 256);
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![TransportEvent::Started {
+            frame: 1_000,
+            engine_frame: 0
+        }]
+    );
+}
+
+#[test]
+fn already_rolling_transport_is_not_reported_as_a_start() {
+    let (tx, rx) = bounded(TRANSPORT_EVENT_CAPACITY);
+    let mut follower = TransportFollower::new(true, tx);
+
+    follower.update(true, 2_048, 0);
+
+    assert!(rx.try_recv().is_err());
+}
+
+#[test]
+fn full_queue_drops_events_without_blocking() {
+    let (tx, rx) = bounded(1);
+    let mut follower = TransportFollower::new(false, tx);
+
+    follower.update(true, 0, 0);
+    follower.update(false, 128, 128);
+
+    let events: Vec<_> = rx.try_iter().collect();
+    assert_eq!(
+        events,
+        vec![TransportEvent::Started {
+            frame: 0,
+            engine_frame: 0
+        }]
+    );
+}
@@ -0,0 +1,189 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::time::Instant;
+
+use anyhow::Result;
+use rustortion::audio::bootstrap::{AudioHost, EngineBootstrap, IR_WARM_START_TIMEOUT, warm_start};
+use rustortion::audio::error::AudioError;
+use rustortion::settings::AudioSettings;
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::denoiser::DenoiseConfig;
+use rustortion_core::audio::diagnostics::MessageKind;
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::audio::engine::{Engine, MonitorPoint};
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::ir::cabinet::ConvolverType;
+use rustortion_core::ir::load_service;
+use rustortion_core::ir::loader::IrLoader;
+use rustortion_core::metronome::MetronomeConfig;
+use rustortion_core::preset::InputFilterConfig;
+use rustortion_core::preset::stage_config::{StageConfig, StageType};
+
+const SAMPLE_RATE: usize = 48_000;
+const BUFFER_SIZE: usize = 128;
+
+#[derive(Debug, PartialEq)]
+enum HostEvent {
+    Activated,
+    /// Messages the engine had applied by the time the ports were connected.
+    Connected(Vec<MessageKind>),
+}
+
+/// Stands in for a JACK client: owns the engine once activated and runs a
+/// single cycle when the ports are connected, as the server would.
+#[derive(Default)]
+struct FakeHost {
+    engine: Option<Engine>,
+    events: Vec<HostEvent>,
+    fail_activation: bool,
+}
+
+impl AudioHost for FakeHost {
+    fn sample_rate(&self) -> usize {
+        SAMPLE_RATE
+    }
+
+    fn buffer_size(&self) -> usize {
+        BUFFER_SIZE
+    }
+
+    fn activate(&mut self, engine: Engine) -> Result<(), AudioError> {
+        if self.fail_activation {
+            return Err(AudioError::Activation("activation refused".to_string()));
+        }
+        self.engine = Some(engine);
+        self.events.push(HostEvent::Activated);
+        Ok(())
+    }
+
+    fn connect_ports(&mut self, _settings: &AudioSettings) {
+        let engine = self.engine.as_mut().expect("connected before activation");
+        engine
+            .process(&[0.0; BUFFER_SIZE], &mut [0.0; BUFFER_SIZE])
+            .unwrap();
+
+        let applied = engine.diagnostics().snapshot().recent_messages;
+        self.events.push(HostEvent::Connected(applied));
+    }
+}
+
+fn bootstrap() -> EngineBootstrap {
+    EngineBootstrap {
+        stages: vec![StageConfig::from(StageType::Level)],
+        monitor_point: Some(MonitorPoint {
+            stage: 0,
+            keep_post: true,
+        }),
+        input_filters: InputFilterConfig::default(),
+        denoise: DenoiseConfig::default(),
+        ir_name: None,
+        ir_alignment: IrAlignment::default(),
+        ir_gain: 0.7,
+        ir_trim_db: -3.0,
+        ir_bypassed: true,
+        pitch_shift: 0,
+        ambience: AmbienceConfig::default(),
+        double_tracker: DoubleTrackerConfig::default(),
+        tilt_db: 0.0,
+        tilt_offset_db: 0.0,
+        oversampling_factor: 1,
+        tuner_enabled: false,
+        standby: false,
+        metronome: MetronomeConfig::default(),
+        record_click_track: false,
+        recording_softclip: false,
+        preset_name: None,
+        recording_dir: None,
+    }
+}
+
+#[test]
+fn warm_start_connects_ports_after_state_is_applied() -> Result<()> {
+    let (engine, engine_handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0)?;
+    let mut host = FakeHost::default();
+
+    let pending = warm_start(&mut host, engine, &engine_handle, None, &bootstrap())?;
+    assert_eq!(host.events, [HostEvent::Activated]);
+    assert!(pending.ready(Instant::now()), "no IR to wait for");
+    pending.connect(&mut host, &AudioSettings::default());
+
+    assert_eq!(host.events.len(), 2);
+    assert_eq!(host.events[0], HostEvent::Activated);
+
+    let HostEvent::Connected(applied) = &host.events[1] else {
+        panic!("ports must be connected last, got {:?}", host.events);
+    };
+    for kind in [
+        MessageKind::SetInputFilters,
+        MessageKind::SetAmpChain,
+        MessageKind::SetMonitorPoint,
+        MessageKind::SetIrBypass,
+        MessageKind::SetIrGain,
+        MessageKind::SetIrTrim,
+        MessageKind::SetPitchShift,
+        MessageKind::SetAmbience,
+        MessageKind::SetDoubleTracker,
+        MessageKind::SetTunerEnabled,
+        MessageKind::SetMetronome,
+    ] {
+        assert!(
+            applied.contains(&kind),
+            "{kind:?} not applied before ports were connected: {applied:?}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn warm_start_leaves_ports_alone_when_activation_fails() -> Result<()> {
+    let (engine, engine_handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0)?;
+    let mut host = FakeHost {
+        fail_activation: true,
+        ..FakeHost::default()
+    };
+
+    let result = warm_start(&mut host, engine, &engine_handle, None, &bootstrap());
+
+    assert!(matches!(result, Err(AudioError::Activation(_))));
+    assert!(host.events.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn warm_start_holds_ports_until_the_ir_arrives_or_times_out() -> Result<()> {
+    let (engine, engine_handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BUFFER_SIZE, None, 1.0)?;
+    let ir_dir = tempfile::tempdir()?;
+    let ir_load = load_service::spawn(
+        IrLoader::new(ir_dir.path(), SAMPLE_RATE)?,
+        engine_handle.clone(),
+        SAMPLE_RATE,
+        500,
+        ConvolverType::default(),
+        64,
+    );
+    let mut host = FakeHost::default();
+    let bootstrap = EngineBootstrap {
+        ir_name: Some("missing.wav".to_string()),
+        ..bootstrap()
+    };
+
+    let started = Instant::now();
+    let pending = warm_start(
+        &mut host,
+        engine,
+        &engine_handle,
+        Some(&ir_load),
+        &bootstrap,
+    )?;
+
+    assert!(!pending.ready(started), "IR can't have loaded yet");
+    assert!(pending.ready(started + IR_WARM_START_TIMEOUT));
+    assert_eq!(host.events, [HostEvent::Activated]);
+
+    Ok(())
+}
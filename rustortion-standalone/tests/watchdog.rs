@@ -0,0 +1,187 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+use rustortion::audio::watchdog::{FrameSource, Watchdog, WatchdogEvent};
+
+const THRESHOLD: Duration = Duration::from_secs(3);
+const BLOCK: u64 = 128;
+
+/// Stands in for the engine's frame counter; the test decides when it moves.
+#[derive(Default)]
+struct MockCounter {
+    frames: Cell<u64>,
+}
+
+impl MockCounter {
+    fn advance(&self) {
+        self.frames.set(self.frames.get() + BLOCK);
+    }
+}
+
+impl FrameSource for MockCounter {
+    fn frames_processed(&self) -> u64 {
+        self.frames.get()
+    }
+}
+
+fn secs(n: u64) -> Duration {
+    Duration::from_secs(n)
+}
+
+#[test]
+fn running_audio_is_never_a_stall() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(THRESHOLD, start);
+
+    for n in 0..10 {
+        counter.advance();
+        assert_eq!(
+            watchdog.poll(start + secs(n), &counter, true),
+            WatchdogEvent::None
+        );
+    }
+    assert!(!watchdog.is_stalled());
+}
+
+#[test]
+fn reports_a_stall_once_after_the_threshold() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(THRESHOLD, start);
+
+    counter.advance();
+    assert_eq!(watchdog.poll(start, &counter, true), WatchdogEvent::None);
+
+    // The counter stops moving.
+    assert_eq!(
+        watchdog.poll(start + secs(1), &counter, true),
+        WatchdogEvent::None
+    );
+    assert_eq!(
+        watchdog.poll(start + secs(2), &counter, true),
+        WatchdogEvent::None
+    );
+    assert_eq!(
+        watchdog.poll(start + secs(3), &counter, true),
+        WatchdogEvent::Stalled
+    );
+    assert!(watchdog.is_stalled());
+    assert_eq!(watchdog.stalled_for(start + secs(3)), secs(3));
+
+    // Still stalled, but already reported.
+    assert_eq!(
+        watchdog.poll(start + secs(4), &counter, true),
+        WatchdogEvent::None
+    );
+    assert!(watchdog.is_stalled());
+}
+
+#[test]
+fn reports_recovery_when_the_counter_moves_again() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(THRESHOLD, start);
+
+    watchdog.poll(start, &counter, true);
+    assert_eq!(
+        watchdog.poll(start + secs(3), &counter, true),
+        WatchdogEvent::Stalled
+    );
+
+    counter.advance();
+    assert_eq!(
+        watchdog.poll(start + secs(4), &counter, true),
+        WatchdogEvent::Recovered
+    );
+    assert!(!watchdog.is_stalled());
+
+    // The threshold runs from the recovery, not the first stall.
+    assert_eq!(
+        watchdog.poll(start + secs(6), &counter, true),
+        WatchdogEvent::None
+    );
+    assert_eq!(
+        watchdog.poll(start + secs(7), &counter, true),
+        WatchdogEvent::Stalled
+    );
+}
+
+#[test]
+fn nothing_is_a_stall_while_audio_is_not_expected() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(THRESHOLD, start);
+
+    for n in 0..10 {
+        assert_eq!(
+            watchdog.poll(start + secs(n), &counter, false),
+            WatchdogEvent::None
+        );
+    }
+
+    // Once audio is expected again the threshold starts from there.
+    assert_eq!(
+        watchdog.poll(start + secs(10), &counter, true),
+        WatchdogEvent::None
+    );
+    assert_eq!(
+        watchdog.poll(start + secs(12), &counter, true),
+        WatchdogEvent::None
+    );
+    assert_eq!(
+        watchdog.poll(start + secs(13), &counter, true),
+        WatchdogEvent::Stalled
+    );
+}
+
+#[test]
+fn reset_after_a_restart_clears_the_stall_without_reporting_recovery() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(THRESHOLD, start);
+
+    counter.advance();
+    watchdog.poll(start, &counter, true);
+    assert_eq!(
+        watchdog.poll(start + secs(3), &counter, true),
+        WatchdogEvent::Stalled
+    );
+
+    // A new engine starts counting from zero.
+    let restarted = MockCounter::default();
+    watchdog.reset(start + secs(3));
+    assert!(!watchdog.is_stalled());
+    restarted.advance();
+    assert_eq!(
+        watchdog.poll(start + secs(4), &restarted, true),
+        WatchdogEvent::None
+    );
+
+    // And a restart that didn't help is caught again.
+    assert_eq!(
+        watchdog.poll(start + secs(7), &restarted, true),
+        WatchdogEvent::Stalled
+    );
+}
+
+#[test]
+fn a_lowered_threshold_applies_to_the_current_stall() {
+    let start = Instant::now();
+    let counter = MockCounter::default();
+    let mut watchdog = Watchdog::new(secs(10), start);
+
+    watchdog.poll(start, &counter, true);
+    assert_eq!(
+        watchdog.poll(start + secs(2), &counter, true),
+        WatchdogEvent::None
+    );
+
+    watchdog.set_threshold(secs(2));
+    assert_eq!(
+        watchdog.poll(start + secs(2), &counter, true),
+        WatchdogEvent::Stalled
+    );
+}
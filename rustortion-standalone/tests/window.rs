@@ -0,0 +1,105 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+#![cfg(feature = "gui")]
+
+//! The window opens where it was left, but never off every monitor, and
+//! dragging it saves once it settles rather than on every step.
+
+use std::time::{Duration, Instant};
+
+use iced::{Point, Size};
+use rustortion::gui::handlers::window::{SAVE_DELAY, WindowHandler};
+use rustortion::settings::{Settings, WindowSettings};
+
+const V0: &str = include_str!("fixtures/settings_v0.json");
+
+fn placed(x: f32, y: f32, width: f32, height: f32) -> WindowSettings {
+    WindowSettings {
+        width,
+        height,
+        position: Some((x, y)),
+        maximized: false,
+    }
+}
+
+#[test]
+fn settings_without_a_window_open_maximized() {
+    let settings = Settings::from_json(V0).unwrap();
+    assert_eq!(settings.window, WindowSettings::default());
+    assert!(settings.window.maximized);
+    assert_eq!(settings.window.position, None);
+}
+
+#[test]
+fn window_on_the_monitor_stays_put() {
+    let saved = placed(200.0, 100.0, 1024.0, 700.0);
+    assert_eq!(saved.fitted_to(1920.0, 1080.0), saved);
+
+    // Overhanging the edge, as a window on a second display does.
+    let overhanging = placed(1500.0, 100.0, 1024.0, 700.0);
+    assert_eq!(overhanging.fitted_to(1920.0, 1080.0), overhanging);
+}
+
+#[test]
+fn window_off_every_monitor_is_centred() {
+    // Left on a display to the right that has since been unplugged.
+    let stranded = placed(2200.0, 100.0, 1024.0, 700.0);
+    let fitted = stranded.fitted_to(1920.0, 1080.0);
+    assert_eq!(fitted.position, Some((448.0, 190.0)));
+    assert_eq!((fitted.width, fitted.height), (1024.0, 700.0));
+}
+
+#[test]
+fn window_bigger_than_the_monitor_is_shrunk() {
+    let fitted = placed(0.0, 0.0, 2560.0, 1440.0).fitted_to(1920.0, 1080.0);
+    assert_eq!((fitted.width, fitted.height), (1920.0, 1080.0));
+    assert_eq!(fitted.position, Some((0.0, 0.0)));
+}
+
+#[test]
+fn maximizing_keeps_the_size_to_restore_to() {
+    let mut handler = WindowHandler::new();
+    let mut saved = placed(200.0, 100.0, 1024.0, 700.0);
+    let now = Instant::now();
+
+    handler.record(
+        &mut saved,
+        Some(Point::ORIGIN),
+        Some(Size::new(1920.0, 1080.0)),
+        true,
+        now,
+    );
+
+    assert!(saved.maximized);
+    assert_eq!(saved.position, Some((200.0, 100.0)));
+    assert_eq!((saved.width, saved.height), (1024.0, 700.0));
+}
+
+#[test]
+fn geometry_saves_once_the_window_settles() {
+    let mut handler = WindowHandler::new();
+    let mut saved = placed(200.0, 100.0, 1024.0, 700.0);
+    let start = Instant::now();
+
+    // Nothing changed, nothing to save.
+    handler.record(
+        &mut saved,
+        Some(Point::new(200.0, 100.0)),
+        None,
+        false,
+        start,
+    );
+    assert!(!handler.is_pending());
+
+    // A drag: each step pushes the save back.
+    for step in 0..10u16 {
+        let at = start + Duration::from_millis(u64::from(step) * 50);
+        let x = 200.0 + f32::from(step) * 10.0;
+        handler.record(&mut saved, Some(Point::new(x, 100.0)), None, false, at);
+        assert!(!handler.is_due(at));
+    }
+    let last = start + Duration::from_millis(450);
+    assert_eq!(saved.position, Some((290.0, 100.0)));
+    assert!(handler.is_pending());
+    assert!(!handler.is_due(last + SAVE_DELAY / 2));
+    assert!(handler.is_due(last + SAVE_DELAY));
+}
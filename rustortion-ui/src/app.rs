@@ -1,32 +1,98 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::time::Instant;
 
+use iced::widget::operation::{self, AbsoluteOffset};
 use iced::widget::{
-    button, checkbox, column, container, pick_list, row, scrollable, slider, space, text,
+    button, checkbox, column, container, pick_list, row, scrollable, slider, space, stack, text,
+    tooltip,
+};
+use iced::{
+    Alignment, Element, Length, Subscription, Task, event, keyboard, mouse, time, time::Duration,
 };
-use iced::{Alignment, Element, Length, Subscription, Task, keyboard, time, time::Duration};
 
 use crate::backend::{ExternalEvent, ParamBackend};
+use crate::components::ambience_control::AmbienceControl;
+use crate::components::denoiser_control::DenoiserControl;
+use crate::components::dialogs::macro_editor::MacroEditorDialog;
+use crate::components::double_tracker_control::DoubleTrackerControl;
 use crate::components::ir_cabinet_control::IrCabinetControl;
+use crate::components::ir_error_banner;
+use crate::components::macro_strip;
 use crate::components::minimap;
+use crate::components::missing_ir_banner::MissingIr;
 use crate::components::peak_meter::PeakMeterDisplay;
 use crate::components::pitch_shift_control::PitchShiftControl;
+use crate::components::recording_status::RecordingStatusDisplay;
+use crate::components::stage_levels::StageLevels;
+use crate::components::stage_overview;
+use crate::components::tilt_control::TiltControl;
+use crate::components::view_menu::{self, ViewToggle};
 use crate::components::widgets::common::{
-    PADDING_LARGE, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, StageViewState,
-    TAB_BUTTON_PADDING, TEXT_SIZE_TAB, section_container, section_title,
+    COLOR_WARNING, PADDING_LARGE, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, StageViewState,
+    TAB_BUTTON_PADDING, TEXT_SIZE_INFO, TEXT_SIZE_TAB, drop_indicator, section_container,
+    section_title,
 };
+use crate::components::widgets::search_select;
+use crate::focus::{self, FocusTarget};
 use crate::handlers::hotkey::HotkeyHandler;
 use crate::handlers::preset::PresetHandler;
-use crate::messages::{HotkeyMessage, Message, PresetMessage};
+use crate::lint::{self, LintWarning};
+use crate::messages::{
+    DenoiseMessage, HotkeyMessage, IrCompareMessage, MacroMessage, Message, MissingIrMessage,
+    PresetGuiMessage, PresetMessage,
+};
+use crate::randomize::{RandomizeIntensity, XorShift, randomize_chain};
 use crate::stages::{
-    ParamUpdate, StageCategory, StageConfig, StageType, apply_stage_config, view_stage_config,
+    ParamUpdate, StageCategory, StageConfig, StageType, apply_stage_config, apply_trim,
+    view_stage_config,
 };
 use crate::tabs::Tab;
+use crate::templates::{CHAIN_TEMPLATES, ChainTemplate, apply_template};
 use crate::tr;
 use rustortion_core::amp::chain::DEFAULT_CHAIN_CAPACITY;
-use rustortion_core::preset::InputFilterConfig;
+use rustortion_core::audio::engine::MonitorPoint;
+use rustortion_core::ir::align::{self, IrAlignment};
+use rustortion_core::ir::load_service::IrLoadFailure;
+use rustortion_core::ir::loader::IrError;
+use rustortion_core::preset::locks::keep_locked_stages;
+use rustortion_core::preset::{InputFilterConfig, MAX_MACROS, Macro};
 
 const REBUILD_INTERVAL: Duration = Duration::from_millis(100);
 const PEAK_METER_POLL_INTERVAL: Duration = Duration::from_millis(20);
+const IR_FLASH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How often a thumbnail being made for the IR preview is checked on.
+const IR_PREVIEW_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How often a noise print being learned is checked on, for its countdown.
+const DENOISE_LEARN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How often an offered preset undo is checked for expiry.
+const PRESET_UNDO_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Blink rate of a preset waiting for the next bar; also how often it's
+/// checked for having landed.
+const PENDING_PRESET_BLINK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// The Amp and Effects stage lists share one id; only one is shown at a time.
+const STAGE_LIST_ID: &str = "stage-list";
+
+/// How far Page Up/Down scroll the stage list, in logical pixels.
+const STAGE_LIST_PAGE: f32 = 400.0;
+
+/// Randomized chains Ctrl+Z can step back through.
+const MAX_UNDO: usize = 20;
+
+/// Tabs in the order the tab bar shows them.
+const TAB_ORDER: [Tab; 4] = [Tab::Io, Tab::Amp, Tab::Effects, Tab::Cabinet];
+
+/// How a replaced chain reaches the engine.
+#[derive(Debug, Clone, Copy)]
+enum ChainSwap {
+    /// Diffed against the playing chain and applied at once.
+    Now,
+    /// Swapped in on the metronome's next bar.
+    OnBar,
+    /// Swapped in with the IR loading for it.
+    WithIr,
+}
 
 /// Result of `SharedApp::update()` — either handled (with a task) or unhandled
 /// (the message is returned so the outer shell can process it).
@@ -35,39 +101,141 @@ pub enum UpdateResult {
     Unhandled(Message),
 }
 
+/// An in-progress drag of a stage card.
+#[derive(Debug, Clone, Copy)]
+pub struct StageDrag {
+    /// Index of the stage being dragged.
+    pub from: usize,
+    /// Index of the card currently under the cursor, if any.
+    pub over: Option<usize>,
+}
+
+impl StageDrag {
+    /// The cursor entered card `idx`.
+    pub const fn hover(&mut self, idx: usize) {
+        self.over = Some(idx);
+    }
+
+    /// The cursor left card `idx`. It may already have entered the next
+    /// card, so `over` is only cleared if it's still `idx`.
+    pub fn leave(&mut self, idx: usize) {
+        if self.over == Some(idx) {
+            self.over = None;
+        }
+    }
+
+    /// The move a release ends the drag with: none unless over a card.
+    pub fn released(self) -> Option<Message> {
+        self.over.map(|to| Message::MoveStageTo {
+            from: self.from,
+            to,
+        })
+    }
+}
+
 /// Shared application state that is common across standalone and plugin GUIs.
 /// Generic over the audio backend (`B: ParamBackend`).
 pub struct SharedApp<B: ParamBackend> {
     pub backend: B,
     pub stages: Vec<StageConfig>,
     pub collapsed_stages: Vec<bool>,
-    pub dirty_params: HashMap<(usize, &'static str), f32>,
+    /// Slider moves not yet sent to the backend, keyed by stage, child within
+    /// a group (if any), and parameter name.
+    pub dirty_params: HashMap<(usize, Option<usize>, &'static str), f32>,
     pub active_tab: Tab,
     pub selected_stage_type: StageType,
     pub ir_cabinet_control: IrCabinetControl,
+    pub ambience_control: AmbienceControl,
+    pub double_tracker_control: DoubleTrackerControl,
     pub pitch_shift_control: PitchShiftControl,
+    pub tilt_control: TiltControl,
     pub preset_handler: PresetHandler,
     pub peak_meter_display: PeakMeterDisplay,
     pub hotkey_handler: HotkeyHandler,
     pub input_filter_config: InputFilterConfig,
+    pub denoiser_control: DenoiserControl,
     pub oversampling_factor: u32,
     /// Whether recording is active — set by standalone, displayed in header.
     pub is_recording: bool,
+    pub recording_status: RecordingStatusDisplay,
+    pub stage_drag: Option<StageDrag>,
+    /// Stage moved by Ctrl+Up/Down. Set by grabbing a stage or moving it.
+    pub focused_stage: Option<usize>,
+    /// Control reached with Tab/Shift+Tab; Enter activates it.
+    pub keyboard_focus: Option<FocusTarget>,
+    /// The loaded preset's IR wasn't found; drives the warning banner.
+    pub missing_ir: Option<MissingIr>,
+    /// The last IR the backend refused or failed to load; drives the error banner.
+    pub ir_error: Option<IrLoadFailure>,
+    /// Check the chain for questionable stage orderings.
+    pub lint_enabled: bool,
+    /// Carry locked stages into the chain of each preset switched to.
+    pub keep_locked_stages: bool,
+    /// Swap a switched-to preset's amp chain in on the metronome's next bar
+    /// instead of at once. Only the chain waits: the IR and the preset's
+    /// other settings apply as they load. Set by the standalone while its
+    /// metronome is on.
+    pub quantize_chain_changes: bool,
+    /// Preset picked but with its chain waiting for the next bar; shown
+    /// blinking until the chain lands.
+    pub pending_preset: Option<String>,
+    /// Which half of the blink the pending preset's name is in.
+    pub pending_preset_shown: bool,
+    /// What the chain lint found on the last edit; see [`lint::lint_chain`].
+    pub lint_warnings: Vec<LintWarning>,
+    pub randomize_intensity: RandomizeIntensity,
+    pub rng: XorShift,
+    /// Chains as they were before each randomize, newest last, for Ctrl+Z.
+    /// Cleared when a preset loads.
+    pub undo_stack: Vec<Vec<StageConfig>>,
+    /// Stage being auditioned on its own. Never saved, and ended by any
+    /// change to which stages are in the chain.
+    pub solo: Option<MonitorPoint>,
+    /// Shift is down, so a solo click keeps the post chain.
+    pub shift_held: bool,
+    /// The power amp is muted while the preamp keeps running. Survives
+    /// preset switches, but not a restart.
+    pub standby: bool,
+    /// The loaded preset's macros, saved with it.
+    pub macros: Vec<Macro>,
+    pub macro_editor: MacroEditorDialog,
+    /// Live level bars on the stage cards, off until picked from the View
+    /// menu.
+    pub stage_levels: StageLevels,
 }
 
 impl<B: ParamBackend> SharedApp<B> {
     pub fn update(&mut self, message: Message) -> UpdateResult {
         match message {
-            Message::TabSelected(tab) => {
-                self.active_tab = tab;
-                self.sync_stage_type_with_tab(tab);
+            Message::TabSelected(tab) => self.select_tab(tab),
+            Message::SetStages(stages) => self.set_stages(stages),
+            Message::SetPresetStages(stages) => self.set_preset_stages(stages),
+            Message::PendingPresetTick => {
+                if self.backend.chain_queued() {
+                    self.pending_preset_shown = !self.pending_preset_shown;
+                } else {
+                    self.pending_preset = None;
+                }
+            }
+            Message::RandomizeIntensityChanged(intensity) => {
+                self.randomize_intensity = intensity;
+            }
+            Message::RandomizeChain => {
+                let rolled = randomize_chain(&self.stages, self.randomize_intensity, &mut self.rng);
+                self.push_undo();
+                self.set_stages(rolled);
             }
-            Message::SetStages(stages) => {
-                self.collapsed_stages.resize(stages.len(), false);
-                self.stages = stages;
-                self.dirty_params.clear();
-                self.update_processor_chain();
-                self.backend.persist_chain_state(&self.stages);
+            Message::ApplyChainTemplate(template) => {
+                let templated = apply_template(&self.stages, &template);
+                if templated.len() <= DEFAULT_CHAIN_CAPACITY && templated != self.stages {
+                    self.push_undo();
+                    self.set_stages(templated);
+                }
+            }
+            Message::Undo => {
+                if let Some(stages) = self.undo_stack.pop() {
+                    self.set_stages(stages);
+                }
             }
             Message::SetInputFilters(config) => {
                 self.input_filter_config = config;
@@ -95,22 +263,32 @@ impl<B: ParamBackend> SharedApp<B> {
                 // the RT thread. See `DEFAULT_CHAIN_CAPACITY`.
                 if self.stages.len() < DEFAULT_CHAIN_CAPACITY {
                     self.flush_dirty_params();
+                    self.end_solo();
                     let new_stage = StageConfig::from(self.selected_stage_type);
                     let category = new_stage.category();
                     let insert_idx = self.category_end_index(category);
                     self.stages.insert(insert_idx, new_stage);
                     self.collapsed_stages.insert(insert_idx, false);
+                    self.remap_macro_stages(|i| Some(if i >= insert_idx { i + 1 } else { i }));
+                    self.focused_stage = None;
                     self.backend.add_stage(insert_idx, &self.stages[insert_idx]);
-                    self.backend.persist_chain_state(&self.stages);
+                    self.chain_changed();
                 }
             }
             Message::RemoveStage(idx) => {
                 if idx < self.stages.len() {
                     self.flush_dirty_params();
+                    self.end_solo();
                     self.stages.remove(idx);
                     self.collapsed_stages.remove(idx);
+                    self.remap_macro_stages(|i| match i.cmp(&idx) {
+                        Ordering::Less => Some(i),
+                        Ordering::Equal => None,
+                        Ordering::Greater => Some(i - 1),
+                    });
+                    self.focused_stage = None;
                     self.backend.remove_stage(idx);
-                    self.backend.persist_chain_state(&self.stages);
+                    self.chain_changed();
                 }
             }
             Message::MoveStageUp(idx) => {
@@ -121,10 +299,13 @@ impl<B: ParamBackend> SharedApp<B> {
                         .find(|&i| self.stages[i].category() == category)
                     {
                         self.flush_dirty_params();
+                        self.end_solo();
                         self.stages.swap(prev, idx);
                         self.collapsed_stages.swap(prev, idx);
+                        self.remap_macro_stages(|i| Some(swapped(i, prev, idx)));
                         self.backend.swap_stages(prev, idx);
-                        self.backend.persist_chain_state(&self.stages);
+                        self.focused_stage = Some(prev);
+                        self.chain_changed();
                     }
                 }
             }
@@ -135,13 +316,41 @@ impl<B: ParamBackend> SharedApp<B> {
                         .find(|&i| self.stages[i].category() == category)
                     {
                         self.flush_dirty_params();
+                        self.end_solo();
                         self.stages.swap(idx, next);
                         self.collapsed_stages.swap(idx, next);
+                        self.remap_macro_stages(|i| Some(swapped(i, idx, next)));
                         self.backend.swap_stages(idx, next);
-                        self.backend.persist_chain_state(&self.stages);
+                        self.focused_stage = Some(next);
+                        self.chain_changed();
                     }
                 }
             }
+            Message::MoveStageTo { from, to } => self.move_stage_to(from, to),
+            Message::StageDragStarted(idx) => {
+                if idx < self.stages.len() {
+                    self.stage_drag = Some(StageDrag {
+                        from: idx,
+                        over: None,
+                    });
+                    self.focused_stage = Some(idx);
+                }
+            }
+            Message::StageDragHovered(idx) => {
+                if let Some(drag) = self.stage_drag.as_mut() {
+                    drag.hover(idx);
+                }
+            }
+            Message::StageDragLeft(idx) => {
+                if let Some(drag) = self.stage_drag.as_mut() {
+                    drag.leave(idx);
+                }
+            }
+            Message::StageDragReleased => {
+                if let Some(move_stage) = self.stage_drag.take().and_then(StageDrag::released) {
+                    return UpdateResult::Handled(Task::done(move_stage));
+                }
+            }
             Message::ToggleStageCollapse(idx) => {
                 if let Some(collapsed) = self.collapsed_stages.get_mut(idx) {
                     *collapsed = !*collapsed;
@@ -168,22 +377,105 @@ impl<B: ParamBackend> SharedApp<B> {
                     }
                 }
             }
+            Message::CollapseAllExcept(idx) => {
+                stage_overview::collapse_all_except(&self.stages, &mut self.collapsed_stages, idx);
+            }
+            Message::SetStageCollapse(collapsed) => {
+                self.collapsed_stages = collapsed;
+                self.collapsed_stages.resize(self.stages.len(), false);
+            }
+            Message::ScrollToStage(idx) => {
+                if idx < self.stages.len() {
+                    self.focused_stage = Some(idx);
+                    let y =
+                        stage_overview::scroll_offset(&self.stages, &self.collapsed_stages, idx);
+                    return UpdateResult::Handled(operation::scroll_to(
+                        STAGE_LIST_ID,
+                        AbsoluteOffset { x: 0.0, y },
+                    ));
+                }
+            }
             Message::ToggleStageBypass(idx) => {
                 if let Some(stage) = self.stages.get_mut(idx) {
                     let new_state = !stage.bypassed();
                     stage.set_bypassed(new_state);
                     self.backend.set_bypass(idx, new_state);
-                    self.backend.persist_chain_state(&self.stages);
+                    self.chain_changed();
+                }
+            }
+            Message::ToggleStageSolo(idx) => {
+                if idx < self.stages.len() {
+                    let point = (self.solo.map(|p| p.stage) != Some(idx)).then_some(MonitorPoint {
+                        stage: idx,
+                        keep_post: self.shift_held,
+                    });
+                    self.solo = point;
+                    self.backend.set_monitor_point(point);
+                }
+            }
+            Message::ToggleStageLock(idx) => {
+                if let Some(stage) = self.stages.get_mut(idx) {
+                    stage.set_locked(!stage.locked());
+                    self.chain_changed();
+                }
+            }
+            Message::ToggleStandby => {
+                self.standby = !self.standby;
+                self.backend.set_standby(self.standby);
+            }
+            Message::StageTrim(idx, trim_msg) => {
+                if let Some(stage) = self.stages.get_mut(idx) {
+                    let (name, value) = apply_trim(stage.common_mut(), trim_msg);
+                    self.dirty_params.insert((idx, None, name), value);
+                    self.chain_changed();
                 }
             }
             Message::StageTypeSelected(stage_type) => {
                 self.selected_stage_type = stage_type;
             }
             Message::IrSelected(ir_name) => {
-                self.ir_cabinet_control
-                    .set_selected_ir(Some(ir_name.clone()));
-                self.backend.set_ir(&ir_name);
+                // A refused IR leaves the old one playing and selected.
+                let alignment = self.ir_cabinet_control.alignment();
+                if let Err(error) = self.backend.set_ir(&ir_name, alignment) {
+                    self.reject_ir(ir_name, error);
+                    return UpdateResult::Handled(Task::none());
+                }
+                self.ir_error = None;
+                // With the banner up, whatever IR is picked replaces the missing one.
+                if let Some(missing) = self.missing_ir.take()
+                    && missing.write_back
+                {
+                    self.preset_handler
+                        .set_ir_name(&missing.preset, Some(ir_name.clone()));
+                }
+                self.ir_cabinet_control.set_selected_ir(Some(ir_name));
+                self.apply_compare_trim();
+            }
+            Message::IrCompare(msg) => return self.handle_ir_compare(msg),
+            Message::MissingIr(msg) => return self.handle_missing_ir(msg),
+            Message::IrListChanged => return self.refresh_irs(),
+            Message::PresetListChanged => self.preset_handler.rescan(),
+            Message::DismissIrError => self.ir_error = None,
+            Message::IrSearch(msg) => {
+                let picked = self.ir_cabinet_control.search(msg);
+                self.refresh_ir_preview();
+                if let Some(ir_name) = picked {
+                    return UpdateResult::Handled(Task::done(Message::IrSelected(ir_name)));
+                }
+            }
+            Message::IrStep(offset) => {
+                if let Some(ir_name) = self.ir_cabinet_control.step_target(offset) {
+                    self.audition_ir(ir_name);
+                }
             }
+            Message::IrShuffle => {
+                if let Some(ir_name) = self.ir_cabinet_control.shuffle_target() {
+                    self.audition_ir(ir_name);
+                }
+            }
+            Message::IrTagFilter(tag) => self.ir_cabinet_control.set_tag_filter(tag),
+            Message::IrFlashTick => self.ir_cabinet_control.expire_flash(),
+            Message::IrPreviewTick => self.refresh_ir_preview(),
             Message::IrBypassed(bypassed) => {
                 self.ir_cabinet_control.set_bypassed(bypassed);
                 self.backend.set_ir_bypass(bypassed);
@@ -192,6 +484,49 @@ impl<B: ParamBackend> SharedApp<B> {
                 self.ir_cabinet_control.set_gain(gain);
                 self.backend.set_ir_gain(gain);
             }
+            Message::IrAlignmentChanged(alignment) => {
+                let alignment = alignment.clamped();
+                if alignment != self.ir_cabinet_control.alignment() {
+                    self.ir_cabinet_control.set_alignment(alignment);
+                    if let Some(ir_name) = self.ir_cabinet_control.get_selected_ir()
+                        && let Err(error) = self.backend.set_ir(&ir_name, alignment)
+                    {
+                        self.reject_ir(ir_name, error);
+                    }
+                }
+            }
+            Message::IrAutoAlign => {
+                if let Some(ir_name) = self.ir_cabinet_control.get_selected_ir() {
+                    let Some(onset) = self.backend.ir_onset(&ir_name) else {
+                        log::warn!("No onset known for IR '{ir_name}', not aligning");
+                        return UpdateResult::Handled(Task::none());
+                    };
+                    let alignment = IrAlignment {
+                        offset: align::offset_to(0, onset),
+                        ..self.ir_cabinet_control.alignment()
+                    };
+                    return UpdateResult::Handled(Task::done(Message::IrAlignmentChanged(
+                        alignment,
+                    )));
+                }
+            }
+            Message::Denoise(msg) => self.handle_denoise(msg),
+            Message::AmbienceChanged(config) => {
+                self.ambience_control.set_config(config);
+                self.backend.set_ambience(config);
+            }
+            Message::DoubleTrackerChanged(config) => {
+                self.double_tracker_control.set_config(config);
+                self.backend.set_double_tracker(config);
+            }
+            Message::TiltChanged(tilt_db) => {
+                self.tilt_control.set_global_db(tilt_db);
+                self.backend.set_tilt_db(self.tilt_control.effective_db());
+            }
+            Message::PresetTiltOffsetChanged(offset_db) => {
+                self.tilt_control.set_preset_offset_db(offset_db);
+                self.backend.set_tilt_db(self.tilt_control.effective_db());
+            }
             Message::PitchShiftChanged(semitones) => {
                 self.pitch_shift_control.set_semitones(semitones);
                 self.backend.set_pitch_shift(semitones);
@@ -200,19 +535,30 @@ impl<B: ParamBackend> SharedApp<B> {
                 self.oversampling_factor = factor;
                 self.backend.set_oversampling(factor);
                 self.flush_dirty_params();
+                // The rebuilt chain starts unsoloed.
+                self.solo = None;
                 self.backend.set_amp_chain(&self.stages);
             }
             Message::Stage(idx, stage_msg) => {
                 if let Some(stage) = self.stages.get_mut(idx) {
                     match apply_stage_config(stage, stage_msg) {
                         Some(ParamUpdate::Changed(name, value)) => {
-                            self.dirty_params.insert((idx, name), value);
-                            self.backend.persist_chain_state(&self.stages);
+                            self.dirty_params.insert((idx, None, name), value);
+                            self.chain_changed();
+                        }
+                        Some(ParamUpdate::ChildChanged(child, name, value)) => {
+                            self.dirty_params.insert((idx, Some(child), name), value);
+                            self.chain_changed();
                         }
                         Some(ParamUpdate::NeedsStageRebuild) => {
                             self.flush_dirty_params();
                             self.backend.rebuild_stage(idx, &self.stages[idx]);
-                            self.backend.persist_chain_state(&self.stages);
+                            self.chain_changed();
+                        }
+                        Some(ParamUpdate::NeedsStageSwap) => {
+                            self.flush_dirty_params();
+                            self.backend.swap_stage(idx, &self.stages[idx]);
+                            self.chain_changed();
                         }
                         Some(ParamUpdate::RescanNamModels) => {
                             // The pick-list refreshes automatically because the NAM
@@ -225,7 +571,7 @@ impl<B: ParamBackend> SharedApp<B> {
                                     // live NAM stages so an in-place `.nam` edit takes effect
                                     // immediately (and re-selecting the same name still works).
                                     for idx in 0..self.stages.len() {
-                                        if self.stages[idx].stage_type() == StageType::Nam {
+                                        if self.stages[idx].uses(StageType::Nam) {
                                             self.backend.rebuild_stage(idx, &self.stages[idx]);
                                         }
                                     }
@@ -233,33 +579,104 @@ impl<B: ParamBackend> SharedApp<B> {
                                 Err(e) => log::error!("Failed to rescan NAM models: {e}"),
                             }
                         }
+                        Some(ParamUpdate::RescanCaptures) => match self.backend.rescan_captures() {
+                            Ok(count) => {
+                                log::info!("Rescanned captures: {count} found");
+                                // Built stages own their kernel; rebuild them so an
+                                // edited capture file takes effect.
+                                for idx in 0..self.stages.len() {
+                                    if self.stages[idx].uses(StageType::Capture) {
+                                        self.backend.rebuild_stage(idx, &self.stages[idx]);
+                                    }
+                                }
+                            }
+                            Err(e) => log::error!("Failed to rescan captures: {e}"),
+                        },
                         None => {}
                     }
                 }
             }
+            Message::Macro(msg) => self.handle_macro(msg),
             Message::Hotkey(msg) => return self.handle_hotkey(msg),
             Message::KeyPressed(key, modifiers) => {
                 return self.handle_key_pressed(&key, modifiers);
             }
+            Message::KeyReleased(key) => {
+                if !self.hotkey_handler.is_learning()
+                    && let Some(message) = self.hotkey_handler.check_release(&key)
+                {
+                    return UpdateResult::Handled(Task::done(message));
+                }
+            }
+            Message::ModifiersChanged(modifiers) => self.shift_held = modifiers.shift(),
+            Message::ViewToggled(ViewToggle::StageLevels(on)) => {
+                self.stage_levels.set_enabled(!on);
+                self.backend.set_stage_levels_enabled(!on);
+            }
             Message::PeakMeterUpdate => {
                 if let Some(ExternalEvent::PeakMeterUpdate {
                     info,
                     xrun_count,
                     cpu_load,
+                    reduced_quality,
+                    right,
                 }) = self.backend.get_peak_meter_info()
                 {
-                    self.peak_meter_display.update(info, xrun_count, cpu_load);
+                    self.peak_meter_display.update(
+                        info,
+                        xrun_count,
+                        cpu_load,
+                        reduced_quality,
+                        right,
+                    );
+                }
+                if self.stage_levels.is_enabled() {
+                    let peaks = self.backend.take_stage_levels(self.stages.len());
+                    self.stage_levels.update(&peaks);
+                }
+                self.sync_engine_state();
+                if self.is_recording {
+                    self.recording_status
+                        .update(self.backend.recording_status());
+                }
+                // Leave pending slider moves alone; the host catches up once
+                // they're flushed.
+                if self.dirty_params.is_empty() {
+                    self.backend.sync_host_params(&mut self.stages);
+                }
+                // Preloaded comparison slots are measured once they land.
+                if self.ir_cabinet_control.is_comparing() {
+                    self.measure_compare_slots();
+                }
+                // Loads run in the background, so their failures arrive here.
+                while let Some(failure) = self.backend.take_ir_load_failure() {
+                    log::error!("IR '{}' failed to load: {}", failure.name, failure.error);
+                    self.ir_cabinet_control.revert_failed(&failure.name);
+                    self.ir_error = Some(failure);
                 }
             }
             Message::Preset(msg) => {
+                let previous = self.preset_handler.selected_preset_index();
                 let task = self.preset_handler.handle(
                     msg,
                     self.stages.clone(),
                     self.ir_cabinet_control.get_selected_ir(),
                     self.ir_cabinet_control.get_gain(),
+                    self.ir_cabinet_control.alignment(),
                     self.pitch_shift_control.get_semitones(),
                     self.input_filter_config,
+                    self.denoiser_control.config().clone(),
+                    self.ambience_control.get_config(),
+                    self.double_tracker_control.get_config(),
+                    self.tilt_control.preset_offset_db(),
+                    self.collapsed_stages.clone(),
+                    self.macros.clone(),
                 );
+                // A warning about the previous preset's IR no longer applies;
+                // the new preset's IR is checked by the load tasks.
+                if self.preset_handler.selected_preset_index() != previous {
+                    self.missing_ir = None;
+                }
                 // Notify backend of the new preset index for DAW state persistence
                 if let Some(idx) = self.preset_handler.selected_preset_index() {
                     self.backend.set_preset_index(idx);
@@ -272,6 +689,52 @@ impl<B: ParamBackend> SharedApp<B> {
         UpdateResult::Handled(Task::none())
     }
 
+    fn handle_macro(&mut self, msg: MacroMessage) {
+        match msg {
+            MacroMessage::ValueChanged(idx, value) => {
+                if let Some(macro_) = self.macros.get_mut(idx) {
+                    // Same path as the stage sliders, so the moves are sent
+                    // on the next rebuild tick.
+                    for (stage, name, value) in macro_.apply(value, &mut self.stages) {
+                        self.dirty_params.insert((stage, None, name), value);
+                    }
+                    self.chain_changed();
+                }
+            }
+            MacroMessage::Set(mut macros) => {
+                macros.truncate(MAX_MACROS);
+                self.macros = macros;
+                self.macro_editor.hide();
+            }
+            MacroMessage::Add => {
+                if self.macros.len() < MAX_MACROS {
+                    let name = format!("{} {}", tr!(new_macro_name), self.macros.len() + 1);
+                    self.macro_editor.show(None, &Macro::new(name));
+                }
+            }
+            MacroMessage::Edit(idx) => {
+                if let Some(macro_) = self.macros.get(idx) {
+                    self.macro_editor.show(Some(idx), macro_);
+                }
+            }
+            MacroMessage::Save => match self.macro_editor.take(&self.stages) {
+                Some((Some(idx), macro_)) if idx < self.macros.len() => self.macros[idx] = macro_,
+                Some((_, macro_)) if self.macros.len() < MAX_MACROS => self.macros.push(macro_),
+                _ => {}
+            },
+            MacroMessage::Remove => {
+                if let Some(idx) = self.macro_editor.editing()
+                    && idx < self.macros.len()
+                {
+                    self.macros.remove(idx);
+                }
+                self.macro_editor.hide();
+            }
+            MacroMessage::Close => self.macro_editor.hide(),
+            edit => self.macro_editor.handle(edit, &self.stages),
+        }
+    }
+
     fn handle_hotkey(&mut self, msg: HotkeyMessage) -> UpdateResult {
         if matches!(msg, HotkeyMessage::Open) {
             let presets = self.preset_handler.get_available_presets().to_vec();
@@ -288,23 +751,418 @@ impl<B: ParamBackend> SharedApp<B> {
         key: &iced::keyboard::Key,
         modifiers: iced::keyboard::Modifiers,
     ) -> UpdateResult {
+        // Dialog and text-field keys come first, so a hotkey bound to a bare
+        // letter can't fire while a dialog is open or a name is being typed.
+        if let Some(task) = self.handle_dialog_key(key, modifiers) {
+            return UpdateResult::Handled(task);
+        }
+
+        if let Some(message) = self.hotkey_handler.check_mapping(key, modifiers) {
+            return UpdateResult::Handled(Task::done(message));
+        }
+
+        if let Some(task) = self.handle_navigation_key(key, modifiers) {
+            return UpdateResult::Handled(task);
+        }
+
+        if modifiers.command() && matches!(key.as_ref(), keyboard::Key::Character("z")) {
+            return UpdateResult::Handled(Task::done(Message::Undo));
+        }
+
+        // Ctrl+Up/Down moves the focused stage — keyboard fallback for drag-and-drop.
+        if modifiers.control()
+            && let Some(idx) = self.focused_stage
+        {
+            let message = match key {
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                    Some(Message::MoveStageUp(idx))
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                    Some(Message::MoveStageDown(idx))
+                }
+                _ => None,
+            };
+            if let Some(message) = message {
+                return UpdateResult::Handled(Task::done(message));
+            }
+        }
+
+        // Left/Right auditions the previous/next IR while the Cabinet tab is
+        // shown, unless they're moving the cursor in the IR search box.
+        if self.active_tab == Tab::Cabinet
+            && modifiers.is_empty()
+            && !self.ir_cabinet_control.is_search_open()
+        {
+            let offset = match key {
+                keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => Some(-1),
+                keyboard::Key::Named(keyboard::key::Named::ArrowRight) => Some(1),
+                _ => None,
+            };
+            if let Some(offset) = offset {
+                return UpdateResult::Handled(Task::done(Message::IrStep(offset)));
+            }
+            // Space plays the next slot while comparing IRs.
+            if self.ir_cabinet_control.is_comparing()
+                && *key == keyboard::Key::Named(keyboard::key::Named::Space)
+            {
+                return UpdateResult::Handled(Task::done(IrCompareMessage::Cycle.into()));
+            }
+        }
+
+        UpdateResult::Handled(Task::none())
+    }
+
+    /// Keys owned by the hotkey dialog, the preset diff, an open search
+    /// dropdown or the preset name field. `None` when none of them is open.
+    fn handle_dialog_key(
+        &mut self,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> Option<Task<Message>> {
+        let named = match key {
+            keyboard::Key::Named(named) => Some(*named),
+            _ => None,
+        };
+
         if self.hotkey_handler.is_learning() {
-            self.hotkey_handler.on_key_input(key, modifiers);
-            return UpdateResult::Handled(Task::none());
+            let message = match named {
+                Some(keyboard::key::Named::Escape) => Some(HotkeyMessage::CancelLearning),
+                Some(keyboard::key::Named::Enter) => Some(HotkeyMessage::ConfirmMapping),
+                _ => {
+                    self.hotkey_handler.on_key_input(key, modifiers);
+                    None
+                }
+            };
+            return Some(message.map_or_else(Task::none, |m| Task::done(Message::Hotkey(m))));
+        }
+        if self.hotkey_handler.is_visible() {
+            return Some(if named == Some(keyboard::key::Named::Escape) {
+                Task::done(Message::Hotkey(HotkeyMessage::Close))
+            } else {
+                Task::none()
+            });
+        }
+        if self.preset_handler.is_diff_visible() {
+            return Some(if named == Some(keyboard::key::Named::Escape) {
+                Task::done(Message::Preset(PresetMessage::Gui(
+                    PresetGuiMessage::CloseDiff,
+                )))
+            } else {
+                Task::none()
+            });
+        }
+        if self.macro_editor.is_visible() {
+            return Some(if named == Some(keyboard::key::Named::Escape) {
+                Task::done(MacroMessage::Close.into())
+            } else {
+                Task::none()
+            });
+        }
+        if self.preset_handler.is_manage_visible() {
+            return Some(if named == Some(keyboard::key::Named::Escape) {
+                Task::done(Message::Preset(PresetMessage::Gui(
+                    PresetGuiMessage::CloseManage,
+                )))
+            } else {
+                Task::none()
+            });
         }
 
-        // If the outer shell has dialogs open, it should intercept KeyPressed
-        // before calling SharedApp::update(). But as a safety net, hotkey
-        // mapping check still runs here.
-        if let Some(preset_name) = self.hotkey_handler.check_mapping(key, modifiers) {
-            return UpdateResult::Handled(Task::done(Message::Preset(PresetMessage::Select(
-                preset_name,
-            ))));
+        // Up/Down/Enter/Escape drive whichever search dropdown is open.
+        if modifiers.is_empty()
+            && let Some(search) = search_select::key_message(key)
+        {
+            if self.ir_cabinet_control.is_search_open() {
+                return Some(Task::done(Message::IrSearch(search)));
+            }
+            if self.preset_handler.is_search_open() {
+                return Some(Task::done(Message::Preset(PresetMessage::Gui(
+                    PresetGuiMessage::Search(search),
+                ))));
+            }
         }
 
+        self.preset_handler.key_task(key)
+    }
+
+    /// Tab/Shift+Tab focus ring, Enter to activate, Escape to drop focus and
+    /// Page Up/Down to scroll the stage list.
+    fn handle_navigation_key(
+        &mut self,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> Option<Task<Message>> {
+        let keyboard::Key::Named(named) = key else {
+            return None;
+        };
+
+        match named {
+            keyboard::key::Named::Tab if !modifiers.control() && !modifiers.alt() => {
+                let target =
+                    focus::step(&self.focus_ring(), self.keyboard_focus, !modifiers.shift());
+                self.keyboard_focus = target;
+                // Landing on a stage also makes it the Ctrl+Up/Down target.
+                if let Some(FocusTarget::Stage(idx)) = target {
+                    self.focused_stage = Some(idx);
+                }
+                Some(Task::none())
+            }
+            keyboard::key::Named::Enter if modifiers.is_empty() => self
+                .keyboard_focus
+                .map(|target| Task::done(self.focus_message(target))),
+            keyboard::key::Named::Escape if self.keyboard_focus.is_some() => {
+                self.keyboard_focus = None;
+                Some(Task::none())
+            }
+            keyboard::key::Named::PageUp | keyboard::key::Named::PageDown
+                if self.active_tab.stage_category().is_some() =>
+            {
+                let y = if *named == keyboard::key::Named::PageUp {
+                    -STAGE_LIST_PAGE
+                } else {
+                    STAGE_LIST_PAGE
+                };
+                Some(operation::scroll_by(
+                    STAGE_LIST_ID,
+                    AbsoluteOffset { x: 0.0, y },
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Everything Tab can reach, in on-screen order: the header buttons this
+    /// backend shows, the tabs, then the stage headers on the active tab.
+    fn focus_ring(&self) -> Vec<FocusTarget> {
+        let caps = self.backend.capabilities();
+        let mut ring = vec![FocusTarget::Standby];
+
+        if caps.has_midi_config {
+            ring.extend([
+                FocusTarget::Hotkeys,
+                FocusTarget::Midi,
+                FocusTarget::Mappings,
+            ]);
+        }
+        if caps.has_setlists {
+            ring.push(FocusTarget::Setlist);
+        }
+        if caps.has_tuner {
+            ring.push(FocusTarget::Tuner);
+        }
+        if caps.has_settings_dialog {
+            ring.push(FocusTarget::Settings);
+        }
+        if caps.has_recorder {
+            ring.extend([FocusTarget::Record, FocusTarget::Render, FocusTarget::Takes]);
+        }
+
+        ring.extend(TAB_ORDER.map(FocusTarget::Tab));
+
+        if let Some(category) = self.active_tab.stage_category() {
+            ring.extend(
+                self.stages
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| s.category() == category)
+                    .map(|(i, _)| FocusTarget::Stage(i)),
+            );
+        }
+
+        ring
+    }
+
+    /// The message Enter sends for a focused control — the same one a click would.
+    const fn focus_message(&self, target: FocusTarget) -> Message {
+        match target {
+            FocusTarget::Standby => Message::ToggleStandby,
+            FocusTarget::Hotkeys => Message::Hotkey(HotkeyMessage::Open),
+            FocusTarget::Midi => Message::Midi(crate::messages::MidiMessage::Open),
+            FocusTarget::Mappings => Message::Mappings(crate::messages::MappingsMessage::Open),
+            FocusTarget::Setlist => Message::Setlist(crate::messages::SetlistMessage::Open),
+            FocusTarget::Tuner => Message::Tuner(crate::messages::TunerMessage::Toggle),
+            FocusTarget::Settings => Message::Settings(crate::messages::SettingsMessage::Open),
+            FocusTarget::Record if self.is_recording => Message::StopRecording,
+            FocusTarget::Record => Message::StartRecording,
+            FocusTarget::Render => Message::Render(crate::messages::RenderMessage::Open),
+            FocusTarget::Takes => Message::Takes(crate::messages::TakesMessage::Open),
+            FocusTarget::Tab(tab) => Message::TabSelected(tab),
+            FocusTarget::Stage(idx) => Message::ToggleStageCollapse(idx),
+        }
+    }
+
+    fn handle_missing_ir(&mut self, msg: MissingIrMessage) -> UpdateResult {
+        match msg {
+            MissingIrMessage::Check { preset, ir_name } => {
+                self.missing_ir =
+                    MissingIr::detect(&preset, &ir_name, self.ir_cabinet_control.available_irs());
+                if self.missing_ir.is_none() {
+                    return UpdateResult::Handled(Task::done(Message::IrSelected(ir_name)));
+                }
+                log::warn!("Preset '{preset}' uses IR '{ir_name}', which was not found");
+            }
+            MissingIrMessage::UseSuggestion => {
+                if let Some(suggestion) =
+                    self.missing_ir.as_ref().and_then(|m| m.suggestion.clone())
+                {
+                    return UpdateResult::Handled(Task::done(Message::IrSelected(suggestion)));
+                }
+            }
+            MissingIrMessage::ChooseReplacement => {
+                self.active_tab = Tab::Cabinet;
+                self.ir_cabinet_control.open_picker();
+            }
+            MissingIrMessage::WriteBackToggled(on) => {
+                if let Some(missing) = &mut self.missing_ir {
+                    missing.write_back = on;
+                }
+            }
+            MissingIrMessage::ContinueWithoutIr => {
+                self.missing_ir = None;
+                return UpdateResult::Handled(Task::done(Message::IrBypassed(true)));
+            }
+        }
         UpdateResult::Handled(Task::none())
     }
 
+    /// Pick up the backend's rescanned IR list. A selected IR that went
+    /// missing brings up the missing-IR banner; one that came back is loaded
+    /// again and takes the banner down.
+    fn refresh_irs(&mut self) -> UpdateResult {
+        let control = &mut self.ir_cabinet_control;
+        control.set_available_irs(self.backend.get_available_irs());
+        control.set_ir_metadata(self.backend.get_available_irs_with_metadata());
+        control.set_flagged_irs(self.backend.get_flagged_irs());
+
+        if let Some(missing) = self.missing_ir.take() {
+            match MissingIr::detect(&missing.preset, &missing.ir_name, control.available_irs()) {
+                Some(still_missing) => {
+                    self.missing_ir = Some(MissingIr {
+                        write_back: missing.write_back,
+                        ..still_missing
+                    });
+                }
+                None => {
+                    return UpdateResult::Handled(Task::done(Message::IrSelected(missing.ir_name)));
+                }
+            }
+        } else if let Some(ir_name) = control.get_selected_ir()
+            && !control.available_irs().contains(&ir_name)
+        {
+            let preset = self
+                .preset_handler
+                .selected_name()
+                .unwrap_or_default()
+                .to_owned();
+            return UpdateResult::Handled(Task::done(Message::MissingIr(
+                MissingIrMessage::Check { preset, ir_name },
+            )));
+        }
+        UpdateResult::Handled(Task::none())
+    }
+
+    /// Apply an IR picked by stepping or shuffling and flash its name.
+    fn audition_ir(&mut self, ir_name: String) {
+        // Loads are async and the load service drops superseded requests, so
+        // holding an arrow key doesn't queue a load per step.
+        if let Err(error) = self
+            .backend
+            .set_ir(&ir_name, self.ir_cabinet_control.alignment())
+        {
+            self.reject_ir(ir_name, error);
+            return;
+        }
+        self.ir_error = None;
+        self.ir_cabinet_control
+            .set_selected_ir(Some(ir_name.clone()));
+        self.ir_cabinet_control.flash(ir_name);
+        self.apply_compare_trim();
+    }
+
+    fn handle_ir_compare(&mut self, msg: IrCompareMessage) -> UpdateResult {
+        match msg {
+            IrCompareMessage::Start => {
+                self.ir_cabinet_control.start_compare();
+                self.measure_compare_slots();
+            }
+            IrCompareMessage::AddSelected => {
+                if let Some(ir_name) = self.ir_cabinet_control.get_selected_ir()
+                    && self.ir_cabinet_control.add_compare_slot(ir_name.clone())
+                {
+                    // Loaded in the background where the backend has to;
+                    // the meter tick measures it once it lands.
+                    self.backend.preload_ir(&ir_name);
+                    self.measure_compare_slots();
+                }
+            }
+            IrCompareMessage::Remove(idx) => {
+                self.ir_cabinet_control.remove_compare_slot(idx);
+                self.apply_compare_trim();
+            }
+            IrCompareMessage::Play(idx) => {
+                if let Some(ir_name) = self.ir_cabinet_control.compare_slot(idx) {
+                    self.audition_ir(ir_name.to_owned());
+                }
+            }
+            IrCompareMessage::Cycle => {
+                if let Some(ir_name) = self.ir_cabinet_control.next_compare_slot() {
+                    self.audition_ir(ir_name);
+                }
+            }
+            IrCompareMessage::Keep => {
+                if let Some(ir_name) = self.ir_cabinet_control.playing_compare_slot() {
+                    self.ir_cabinet_control.end_compare();
+                    self.backend.set_ir_trim_db(0.0);
+                    if let Some(preset) = self.preset_handler.selected_name().map(str::to_owned) {
+                        self.preset_handler.set_ir_name(&preset, Some(ir_name));
+                    }
+                }
+            }
+            IrCompareMessage::Exit => {
+                let original = self.ir_cabinet_control.end_compare();
+                self.backend.set_ir_trim_db(0.0);
+                if let Some(ir_name) = original
+                    && self.ir_cabinet_control.get_selected_ir().as_ref() != Some(&ir_name)
+                {
+                    return UpdateResult::Handled(Task::done(Message::IrSelected(ir_name)));
+                }
+            }
+        }
+        UpdateResult::Handled(Task::none())
+    }
+
+    /// Look up the thumbnail of the IR the picker highlights, or the
+    /// selected one, if the preview doesn't have it yet.
+    fn refresh_ir_preview(&mut self) {
+        if let Some(ir_name) = self.ir_cabinet_control.preview_wanted() {
+            let lookup = self.backend.ir_thumbnail(&ir_name);
+            self.ir_cabinet_control.set_preview(ir_name, lookup);
+        }
+    }
+
+    /// Measure the slots whose IR has loaded since the last call, then
+    /// re-trim the playing IR.
+    fn measure_compare_slots(&mut self) {
+        for ir_name in self.ir_cabinet_control.unmeasured_compare_slots() {
+            if let Some(energy) = self.backend.ir_energy(&ir_name) {
+                self.ir_cabinet_control.set_compare_energy(&ir_name, energy);
+            }
+        }
+        self.apply_compare_trim();
+    }
+
+    /// Level-match the playing IR against the other slots while comparing.
+    fn apply_compare_trim(&mut self) {
+        if let Some(trim_db) = self.ir_cabinet_control.take_trim_change() {
+            self.backend.set_ir_trim_db(trim_db);
+        }
+    }
+
+    fn reject_ir(&mut self, name: String, error: IrError) {
+        log::error!("IR '{name}' refused: {error}");
+        self.ir_error = Some(IrLoadFailure { name, error });
+    }
+
     // -- View methods --------------------------------------------------------
 
     /// Main content view (header, preset bar, tab bar, tab content, footer).
@@ -323,96 +1181,250 @@ impl<B: ParamBackend> SharedApp<B> {
         let footer =
             row![self.peak_meter_display.view_status(), signal_minimap,].align_y(Alignment::Center);
 
-        column![
+        let mut content = column![
             header,
             self.preset_handler
                 .view(!self.backend.capabilities().has_preset_management),
-            tab_bar,
-            tab_content,
-            footer,
+            macro_strip::view(&self.macros),
         ]
         .spacing(SPACING_NORMAL)
-        .padding(PADDING_LARGE)
-        .into()
+        .padding(PADDING_LARGE);
+        if let Some(missing) = &self.missing_ir {
+            content = content.push(missing.view(self.backend.capabilities().has_preset_management));
+        }
+        if let Some(failure) = &self.ir_error {
+            content = content.push(ir_error_banner::view(failure));
+        }
+        let content = content.push(tab_bar).push(tab_content).push(footer);
+
+        match self.ir_cabinet_control.view_flash() {
+            Some(flash) => stack![content, flash].into(),
+            None => content.into(),
+        }
+    }
+
+    /// The macro editor, while it's open. Like the shells' own dialogs it
+    /// replaces the main view rather than overlaying it.
+    pub fn macro_editor_view(&self) -> Option<Element<'_, Message>> {
+        self.macro_editor.view(&self.stages)
     }
 
     fn view_header(&self) -> Element<'_, Message> {
         let caps = self.backend.capabilities();
 
-        let mut header_row = row![self.peak_meter_display.view(), space::horizontal(),]
+        let mut header_row = row![self.peak_meter_display.view()]
             .spacing(SPACING_TIGHT)
             .align_y(Alignment::Center);
+        header_row = header_row.push(self.view_standby_switch());
+        if let Some(indicator) = self.view_solo_indicator() {
+            header_row = header_row.push(indicator);
+        }
+        if let Some(pending) = self.view_pending_preset() {
+            header_row = header_row.push(pending);
+        }
+        header_row = header_row
+            .push(space::horizontal())
+            .push(self.tilt_control.view());
 
         // Standalone-only buttons are guarded by capabilities
+        let focused = |target: FocusTarget| self.keyboard_focus == Some(target);
+
         if caps.has_midi_config {
             header_row = header_row
                 .push(
                     button(tr!(hotkeys))
                         .on_press(Message::Hotkey(HotkeyMessage::Open))
-                        .style(iced::widget::button::secondary),
+                        .style(with_focus_ring(
+                            focused(FocusTarget::Hotkeys),
+                            iced::widget::button::secondary,
+                        )),
                 )
                 .push(
                     button(tr!(midi))
                         .on_press(Message::Midi(crate::messages::MidiMessage::Open))
-                        .style(iced::widget::button::secondary),
+                        .style(with_focus_ring(
+                            focused(FocusTarget::Midi),
+                            iced::widget::button::secondary,
+                        )),
+                )
+                .push(
+                    button(tr!(mappings))
+                        .on_press(Message::Mappings(crate::messages::MappingsMessage::Open))
+                        .style(with_focus_ring(
+                            focused(FocusTarget::Mappings),
+                            iced::widget::button::secondary,
+                        )),
                 );
         }
+        if caps.has_setlists {
+            header_row = header_row.push(
+                button(tr!(setlist))
+                    .on_press(Message::Setlist(crate::messages::SetlistMessage::Open))
+                    .style(with_focus_ring(
+                        focused(FocusTarget::Setlist),
+                        iced::widget::button::secondary,
+                    )),
+            );
+        }
         if caps.has_tuner {
             header_row = header_row.push(
                 button(tr!(tuner))
                     .on_press(Message::Tuner(crate::messages::TunerMessage::Toggle))
-                    .style(iced::widget::button::secondary),
+                    .style(with_focus_ring(
+                        focused(FocusTarget::Tuner),
+                        iced::widget::button::secondary,
+                    )),
             );
         }
+        if caps.has_stage_levels {
+            header_row = header_row.push(view_menu::view(self.stage_levels.is_enabled()));
+        }
         if caps.has_settings_dialog {
             header_row = header_row.push(
                 button(tr!(settings))
-                    .on_press(Message::Settings(crate::messages::SettingsMessage::Open)),
+                    .on_press(Message::Settings(crate::messages::SettingsMessage::Open))
+                    .style(with_focus_ring(
+                        focused(FocusTarget::Settings),
+                        iced::widget::button::primary,
+                    )),
             );
         }
 
         if caps.has_recorder {
+            let record_focused = focused(FocusTarget::Record);
             let record_button = if self.is_recording {
                 button(text(tr!(stop_recording)))
                     .on_press(Message::StopRecording)
-                    .style(iced::widget::button::danger)
+                    .style(with_focus_ring(
+                        record_focused,
+                        iced::widget::button::danger,
+                    ))
             } else {
                 button(text(tr!(start_recording)))
                     .on_press(Message::StartRecording)
-                    .style(iced::widget::button::success)
+                    .style(with_focus_ring(
+                        record_focused,
+                        iced::widget::button::success,
+                    ))
             };
             header_row = header_row.push(record_button);
             if self.is_recording {
-                header_row =
-                    header_row.push(text(tr!(recording)).style(|_| iced::widget::text::Style {
-                        color: Some(crate::components::widgets::common::COLOR_ERROR),
-                    }));
+                header_row = header_row.push(self.recording_status.view());
+            } else if let Some(badge) = self.recording_status.softclip_badge() {
+                header_row = header_row.push(badge);
             }
+            header_row = header_row.push(
+                button(tr!(render_file))
+                    .on_press(Message::Render(crate::messages::RenderMessage::Open))
+                    .style(with_focus_ring(
+                        focused(FocusTarget::Render),
+                        iced::widget::button::secondary,
+                    )),
+            );
+            header_row = header_row.push(
+                button(tr!(compare_takes))
+                    .on_press(Message::Takes(crate::messages::TakesMessage::Open))
+                    .style(with_focus_ring(
+                        focused(FocusTarget::Takes),
+                        iced::widget::button::secondary,
+                    )),
+            );
         }
 
         header_row.into()
     }
 
-    fn view_tab_bar(&self) -> Element<'_, Message> {
-        let tabs = [
-            (Tab::Io, tr!(tab_io)),
-            (Tab::Amp, tr!(tab_amp)),
-            (Tab::Effects, tr!(tab_effects)),
-            (Tab::Cabinet, tr!(tab_cabinet)),
-        ];
+    /// The standby switch, lit up while the power amp is muted so it can't
+    /// be mistaken for a dead signal.
+    fn view_standby_switch(&self) -> Element<'_, Message> {
+        let focused = self.keyboard_focus == Some(FocusTarget::Standby);
+        let switch = if self.standby {
+            tooltip(
+                button(text(format!("⏻ {}", tr!(standby_muted))))
+                    .on_press(Message::ToggleStandby)
+                    .style(with_focus_ring(focused, iced::widget::button::warning)),
+                tr!(standby_tooltip_on),
+                iced::widget::tooltip::Position::Bottom,
+            )
+        } else {
+            tooltip(
+                button(text(format!("⏻ {}", tr!(standby))))
+                    .on_press(Message::ToggleStandby)
+                    .style(with_focus_ring(focused, iced::widget::button::secondary)),
+                tr!(standby_tooltip_off),
+                iced::widget::tooltip::Position::Bottom,
+            )
+        };
+        switch.into()
+    }
 
+    /// Shown whenever a stage is soloed, whichever tab is open, so a solo
+    /// can't be left on unnoticed. Clicking it ends the solo.
+    fn view_solo_indicator(&self) -> Option<Element<'_, Message>> {
+        let point = self.solo?;
+        let stage = self.stages.get(point.stage)?;
+        let mut label = format!(
+            "🎧 {}: {} {}",
+            tr!(solo_indicator),
+            stage.stage_type(),
+            point.stage + 1
+        );
+        if point.keep_post {
+            label = format!("{label} ({})", tr!(solo_keep_post));
+        }
+        Some(
+            tooltip(
+                button(text(label))
+                    .on_press(Message::ToggleStageSolo(point.stage))
+                    .style(iced::widget::button::warning),
+                tr!(solo_indicator_tooltip),
+                iced::widget::tooltip::Position::Bottom,
+            )
+            .into(),
+        )
+    }
+
+    /// The preset waiting for the next bar, blinking. Blanked rather than
+    /// removed on the off beat so the header doesn't shift.
+    fn view_pending_preset(&self) -> Option<Element<'_, Message>> {
+        let name = self.pending_preset.as_deref()?;
+        let shown = self.pending_preset_shown;
+        Some(
+            text(format!("{} {name}", tr!(preset_pending)))
+                .size(TEXT_SIZE_INFO)
+                .style(move |_| iced::widget::text::Style {
+                    color: Some(if shown {
+                        COLOR_WARNING
+                    } else {
+                        iced::Color::TRANSPARENT
+                    }),
+                })
+                .into(),
+        )
+    }
+
+    fn view_tab_bar(&self) -> Element<'_, Message> {
         let mut tab_row = row![].spacing(SPACING_TIGHT);
 
-        for (tab, label) in tabs {
+        for tab in TAB_ORDER {
+            let label = match tab {
+                Tab::Io => tr!(tab_io),
+                Tab::Amp => tr!(tab_amp),
+                Tab::Effects => tr!(tab_effects),
+                Tab::Cabinet => tr!(tab_cabinet),
+            };
             let is_active = self.active_tab == tab;
             let btn = button(text(label).size(TEXT_SIZE_TAB))
                 .on_press(Message::TabSelected(tab))
                 .padding(TAB_BUTTON_PADDING)
-                .style(if is_active {
-                    tab_button_active
-                } else {
-                    tab_button_inactive
-                });
+                .style(with_focus_ring(
+                    self.keyboard_focus == Some(FocusTarget::Tab(tab)),
+                    if is_active {
+                        tab_button_active
+                    } else {
+                        tab_button_inactive
+                    },
+                ));
             tab_row = tab_row.push(btn);
         }
 
@@ -443,12 +1455,25 @@ impl<B: ParamBackend> SharedApp<B> {
 
         let collapse_toggle = self.view_collapse_toggle(category);
 
+        // Drop target, if it differs from the dragged stage and is in this tab.
+        let drop_target = self.stage_drag.and_then(|drag| {
+            drag.over
+                .filter(|&to| to != drag.from && category_indices.contains(&to))
+                .map(|to| (drag.from, to))
+        });
+
         let mut stage_col = column![].width(Length::Fill).spacing(SPACING_TIGHT);
         for (pos, &abs_idx) in category_indices.iter().enumerate() {
             let is_collapsed = self.collapsed_stages.get(abs_idx).copied().unwrap_or(false);
             let can_move_up = pos > 0;
             let can_move_down = pos < total_in_category.saturating_sub(1);
             let bypassed = self.stages[abs_idx].bypassed();
+
+            // Moving up lands before the target, moving down lands after it.
+            if matches!(drop_target, Some((from, to)) if to == abs_idx && from > to) {
+                stage_col = stage_col.push(drop_indicator());
+            }
+
             stage_col = stage_col.push(view_stage_config(
                 &self.stages[abs_idx],
                 abs_idx,
@@ -457,21 +1482,46 @@ impl<B: ParamBackend> SharedApp<B> {
                     can_move_up,
                     can_move_down,
                     bypassed,
+                    common: self.stages[abs_idx].common(),
+                    is_focused: self.focused_stage == Some(abs_idx),
+                    drag_active: self.stage_drag.is_some(),
                     // Effective rate (device × oversampling) — the rate stages are
                     // built at, so NAM's mismatch check compares against the right value.
                     engine_sample_rate: self.backend.sample_rate()
                         * self.backend.oversampling_factor(),
                     // NAM-specific: where the NAM stage card shows users to drop models.
                     nam_models_dir: self.backend.nam_models_dir(),
+                    captures_dir: self.backend.captures_dir(),
+                    tempo_sync: self.backend.capabilities().has_tempo_sync,
+                    stage_type: self.stages[abs_idx].stage_type(),
+                    warnings: lint::messages_for(&self.lint_warnings, abs_idx),
+                    can_solo: true,
+                    can_lock: true,
+                    soloed: self.solo.is_some_and(|p| p.stage == abs_idx),
+                    level: self.stage_levels.level(abs_idx),
                 },
             ));
+
+            if matches!(drop_target, Some((from, to)) if to == abs_idx && from < to) {
+                stage_col = stage_col.push(drop_indicator());
+            }
         }
 
         let add_bar = self.view_add_stage_bar(category);
 
+        let overview = stage_overview::view(
+            &self.stages,
+            &self.collapsed_stages,
+            self.focused_stage,
+            category,
+        );
+
         let content = column![
             collapse_toggle,
-            scrollable(stage_col.padding(PADDING_NORMAL)).height(Length::Fill),
+            overview,
+            scrollable(stage_col.padding(PADDING_NORMAL))
+                .id(STAGE_LIST_ID)
+                .height(Length::Fill),
             add_bar,
         ]
         .spacing(SPACING_TIGHT);
@@ -495,11 +1545,20 @@ impl<B: ParamBackend> SharedApp<B> {
             format!("\u{25b6} {}", tr!(collapse_all))
         };
 
+        // Only offered while a stage in this tab is selected.
+        let keep = self
+            .focused_stage
+            .filter(|&i| self.stages.get(i).is_some_and(|s| s.category() == category));
+
         row![
             button(text(label))
                 .on_press(Message::ToggleAllStagesCollapse)
                 .style(iced::widget::button::secondary),
+            button(text(tr!(collapse_all_except_selected)))
+                .on_press_maybe(keep.map(Message::CollapseAllExcept))
+                .style(iced::widget::button::secondary),
         ]
+        .spacing(SPACING_NORMAL)
         .into()
     }
 
@@ -513,20 +1572,49 @@ impl<B: ParamBackend> SharedApp<B> {
 
         // Disable "Add Stage" once the chain hits its capacity cap.
         let add_msg = (self.stages.len() < DEFAULT_CHAIN_CAPACITY).then_some(Message::AddStage);
-        row![
+        let undo_msg = (!self.undo_stack.is_empty()).then_some(Message::Undo);
+        let mut bar = row![
             pick_list(available_types, selected, Message::StageTypeSelected),
             button(tr!(add_stage)).on_press_maybe(add_msg),
         ]
         .spacing(SPACING_NORMAL)
-        .align_y(Alignment::Center)
-        .into()
+        .align_y(Alignment::Center);
+        // Templates only order amp stages.
+        if category == StageCategory::Amp {
+            bar = bar.push(
+                pick_list(
+                    CHAIN_TEMPLATES,
+                    None::<ChainTemplate>,
+                    Message::ApplyChainTemplate,
+                )
+                .placeholder(tr!(chain_template)),
+            );
+        }
+        bar.push(space::horizontal())
+            .push(pick_list(
+                RandomizeIntensity::ALL,
+                Some(self.randomize_intensity),
+                Message::RandomizeIntensityChanged,
+            ))
+            .push(button(tr!(randomize)).on_press(Message::RandomizeChain))
+            .push(
+                button(tr!(undo))
+                    .on_press_maybe(undo_msg)
+                    .style(iced::widget::button::secondary),
+            )
+            .into()
     }
 
     fn view_cabinet_tab(&self) -> Element<'_, Message> {
         let content = scrollable(
-            column![self.ir_cabinet_control.view()]
-                .width(Length::Fill)
-                .padding(PADDING_NORMAL),
+            column![
+                self.ir_cabinet_control.view(),
+                self.ambience_control.view(),
+                self.double_tracker_control.view()
+            ]
+            .spacing(SPACING_NORMAL)
+            .width(Length::Fill)
+            .padding(PADDING_NORMAL),
         )
         .height(Length::Fill);
 
@@ -619,9 +1707,14 @@ impl<B: ParamBackend> SharedApp<B> {
         );
 
         let content = scrollable(
-            column![input_filters_section, pitch_section, oversampling_section,]
-                .spacing(SPACING_NORMAL)
-                .padding(PADDING_NORMAL),
+            column![
+                input_filters_section,
+                self.denoiser_control.view(),
+                pitch_section,
+                oversampling_section,
+            ]
+            .spacing(SPACING_NORMAL)
+            .padding(PADDING_NORMAL),
         )
         .height(Length::Fill);
 
@@ -640,21 +1733,120 @@ impl<B: ParamBackend> SharedApp<B> {
         let peak_meter_sub =
             time::every(PEAK_METER_POLL_INTERVAL).map(|_| Message::PeakMeterUpdate);
 
+        // Auto-repeat is only let through for Left/Right, so holding the key
+        // keeps stepping through IRs, and Page Up/Down, so it keeps scrolling.
         let keyboard_sub = keyboard::listen().filter_map(|event| match event {
             keyboard::Event::KeyPressed {
                 key,
                 modifiers,
-                repeat: false,
+                repeat,
                 ..
-            } => Some(Message::KeyPressed(key, modifiers)),
+            } if !repeat
+                || matches!(
+                    key,
+                    keyboard::Key::Named(
+                        keyboard::key::Named::ArrowLeft
+                            | keyboard::key::Named::ArrowRight
+                            | keyboard::key::Named::PageUp
+                            | keyboard::key::Named::PageDown
+                    )
+                ) =>
+            {
+                Some(Message::KeyPressed(key, modifiers))
+            }
+            keyboard::Event::KeyReleased { key, .. } => Some(Message::KeyReleased(key)),
+            keyboard::Event::ModifiersChanged(modifiers) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
             _ => None,
         });
 
-        Subscription::batch(vec![rebuild_sub, peak_meter_sub, keyboard_sub])
+        let ir_flash_sub = if self.ir_cabinet_control.is_flashing() {
+            time::every(IR_FLASH_POLL_INTERVAL).map(|_| Message::IrFlashTick)
+        } else {
+            Subscription::none()
+        };
+
+        let ir_preview_sub = if self.ir_cabinet_control.preview_wanted().is_some() {
+            time::every(IR_PREVIEW_POLL_INTERVAL).map(|_| Message::IrPreviewTick)
+        } else {
+            Subscription::none()
+        };
+
+        let denoise_learn_sub = if self.denoiser_control.is_learning() {
+            time::every(DENOISE_LEARN_POLL_INTERVAL)
+                .map(|_| Message::Denoise(DenoiseMessage::LearnTick))
+        } else {
+            Subscription::none()
+        };
+
+        let pending_preset_sub = if self.pending_preset.is_some() {
+            time::every(PENDING_PRESET_BLINK_INTERVAL).map(|_| Message::PendingPresetTick)
+        } else {
+            Subscription::none()
+        };
+
+        let preset_undo_sub = if self.preset_handler.has_pending_undo() {
+            time::every(PRESET_UNDO_POLL_INTERVAL)
+                .map(|_| Message::Preset(PresetMessage::Gui(PresetGuiMessage::UndoTick)))
+        } else {
+            Subscription::none()
+        };
+
+        // The drop can land anywhere in the window, so listen globally while dragging.
+        let drag_sub = if self.stage_drag.is_some() {
+            event::listen_with(|event, _status, _window| match event {
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::StageDragReleased)
+                }
+                _ => None,
+            })
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch(vec![
+            rebuild_sub,
+            peak_meter_sub,
+            keyboard_sub,
+            drag_sub,
+            ir_flash_sub,
+            ir_preview_sub,
+            denoise_learn_sub,
+            preset_undo_sub,
+            pending_preset_sub,
+        ])
     }
 
     // -- Helpers -------------------------------------------------------------
 
+    fn handle_denoise(&mut self, message: DenoiseMessage) {
+        match message {
+            DenoiseMessage::Set(config) => self.denoiser_control.set_config(config),
+            DenoiseMessage::ReductionChanged(db) => self.denoiser_control.set_reduction_db(db),
+            DenoiseMessage::BypassToggled(bypassed) => {
+                self.denoiser_control.set_bypassed(bypassed);
+            }
+            DenoiseMessage::Learn => {
+                let learn = self.backend.learn_noise();
+                self.denoiser_control.start_learn(learn, Instant::now());
+                return;
+            }
+            DenoiseMessage::LearnTick => {
+                if !self.denoiser_control.poll_learn(Instant::now()) {
+                    return;
+                }
+            }
+        }
+        self.backend.set_denoiser(self.denoiser_control.config());
+    }
+
+    /// Switch to a tab, and the stage picker to its category.
+    pub fn select_tab(&mut self, tab: Tab) {
+        self.active_tab = tab;
+        self.sync_stage_type_with_tab(tab);
+    }
+
     /// Reset `selected_stage_type` to the first stage of the new tab's category.
     fn sync_stage_type_with_tab(&mut self, tab: Tab) {
         let Some(category) = tab.stage_category() else {
@@ -689,16 +1881,221 @@ impl<B: ParamBackend> SharedApp<B> {
         }
     }
 
+    /// Move a stage to a new index within its category, keeping its collapse
+    /// state attached to it.
+    fn move_stage_to(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.stages.len() || to >= self.stages.len() {
+            return;
+        }
+        // Amp stages always precede effects; drops across the boundary are ignored.
+        if self.stages[from].category() != self.stages[to].category() {
+            return;
+        }
+
+        self.flush_dirty_params();
+        self.end_solo();
+
+        let stage = self.stages.remove(from);
+        self.stages.insert(to, stage);
+
+        self.collapsed_stages.resize(self.stages.len(), false);
+        let collapsed = self.collapsed_stages.remove(from);
+        self.collapsed_stages.insert(to, collapsed);
+        self.remap_macro_stages(|i| Some(moved(i, from, to)));
+
+        // The engine only knows adjacent swaps; replay the move as a series of
+        // them so the running chain is reordered without a rebuild.
+        if from < to {
+            for i in from..to {
+                self.backend.swap_stages(i, i + 1);
+            }
+        } else {
+            for i in (to..from).rev() {
+                self.backend.swap_stages(i, i + 1);
+            }
+        }
+
+        self.focused_stage = Some(to);
+        self.chain_changed();
+    }
+
+    /// Keep macro targets on their stages after the chain changed shape;
+    /// `new_index` maps each old chain index to its new one.
+    fn remap_macro_stages(&mut self, new_index: impl Fn(usize) -> Option<usize> + Copy) {
+        for macro_ in &mut self.macros {
+            macro_.remap_stages(new_index);
+        }
+    }
+
+    /// Check what's shown against what the engine has applied, and show the
+    /// engine's side where they differ. Runs on the meter tick, so a message
+    /// the engine never got is corrected within one tick of it settling.
+    fn sync_engine_state(&mut self) {
+        let Some(state) = self.backend.engine_state() else {
+            return;
+        };
+        self.ir_cabinet_control
+            .sync_with_engine(&state, self.backend.ir_load_pending());
+        if self.backend.capabilities().has_recorder && self.is_recording != state.recording {
+            log::warn!(
+                "Recording shown as {} but the engine has {}",
+                self.is_recording,
+                state.recording
+            );
+            self.is_recording = state.recording;
+        }
+    }
+
+    /// Remember the chain so `Undo` can bring it back.
+    fn push_undo(&mut self) {
+        if self.undo_stack.len() == MAX_UNDO {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(self.stages.clone());
+    }
+
+    fn set_stages(&mut self, stages: Vec<StageConfig>) {
+        self.replace_stages(stages, ChainSwap::Now);
+    }
+
+    /// Put `stages` in place of the chain, reaching the engine as `swap`
+    /// says.
+    fn replace_stages(&mut self, stages: Vec<StageConfig>, swap: ChainSwap) {
+        // Pending slider values are already in `self.stages`; send them
+        // so the engine matches it before diffing against the new chain.
+        self.flush_dirty_params();
+        // A new chain, often a new preset, never starts soloed.
+        self.end_solo();
+        self.collapsed_stages.resize(stages.len(), false);
+        let previous = std::mem::replace(&mut self.stages, stages);
+        self.stage_drag = None;
+        self.focused_stage = None;
+        self.keyboard_focus = None;
+        match swap {
+            ChainSwap::Now => self.backend.sync_amp_chain(&previous, &self.stages),
+            ChainSwap::OnBar => self.backend.queue_amp_chain(&self.stages),
+            ChainSwap::WithIr => self.backend.hold_amp_chain(&self.stages),
+        }
+        self.chain_changed();
+    }
+
+    /// Load a preset's chain, with the locked stages of the current one
+    /// carried into it if [`Self::keep_locked_stages`] is on. With
+    /// [`Self::quantize_chain_changes`] on, the chain waits for the next bar;
+    /// otherwise it waits for the preset's IR, if it has one to load.
+    /// Undo starts over, so it can't bring the last preset's chain back
+    /// into this one.
+    fn set_preset_stages(&mut self, stages: Vec<StageConfig>) {
+        self.undo_stack.clear();
+        let stages = if self.keep_locked_stages {
+            keep_locked_stages(&self.stages, stages)
+        } else {
+            stages
+        };
+        let on_bar = self.quantize_chain_changes;
+        let swap = if on_bar {
+            ChainSwap::OnBar
+        } else if self.preset_ir_follows() {
+            ChainSwap::WithIr
+        } else {
+            ChainSwap::Now
+        };
+        self.replace_stages(stages, swap);
+        if on_bar {
+            self.pending_preset = self.preset_handler.selected_name().map(str::to_owned);
+            self.pending_preset_shown = true;
+        }
+    }
+
+    /// Whether the selected preset has an IR that its load is about to
+    /// select. A missing one brings up the banner instead, and nothing
+    /// would arrive for the chain to wait for.
+    fn preset_ir_follows(&self) -> bool {
+        self.preset_handler
+            .selected_name()
+            .and_then(|name| self.preset_handler.get_preset_by_name(name))
+            .and_then(|preset| preset.ir_name.as_ref())
+            .is_some_and(|ir_name| self.ir_cabinet_control.available_irs().contains(ir_name))
+    }
+
+    /// Select the preset `name` and push its chain to the engine before
+    /// returning, rather than through the `SetStages` task, for MIDI switching
+    /// where every round trip is heard. Returns the task loading the rest of
+    /// the preset, or `None` if there is no such preset.
+    pub fn switch_preset_now(&mut self, name: &str) -> Option<Task<Message>> {
+        let (stages, task) = self.preset_handler.select_now(name)?;
+        self.set_preset_stages(stages);
+        self.missing_ir = None;
+        if let Some(idx) = self.preset_handler.selected_preset_index() {
+            self.backend.set_preset_index(idx);
+        }
+        Some(task)
+    }
+
+    /// Stop soloing, if a stage is soloed. Called before the stages change
+    /// so the solo can't land on whatever takes the stage's place.
+    fn end_solo(&mut self) {
+        if self.solo.take().is_some() {
+            self.backend.set_monitor_point(None);
+        }
+    }
+
+    /// Save the chain after an edit and run the lint over it again.
+    fn chain_changed(&mut self) {
+        self.backend.persist_chain_state(&self.stages);
+        self.relint();
+    }
+
+    /// Turn the chain lint on or off; off clears its warnings.
+    pub fn set_lint_enabled(&mut self, enabled: bool) {
+        self.lint_enabled = enabled;
+        self.relint();
+    }
+
+    /// Run the lint over the chain as it is, e.g. after building the app
+    /// with its stages already in place.
+    pub fn relint(&mut self) {
+        self.lint_warnings = if self.lint_enabled {
+            lint::lint_chain(&self.stages)
+        } else {
+            Vec::new()
+        };
+    }
+
     pub fn flush_dirty_params(&mut self) {
-        for ((idx, name), value) in self.dirty_params.drain() {
-            self.backend.begin_edit(idx, name);
-            self.backend.set_parameter(idx, name, value);
-            self.backend.end_edit(idx, name);
+        for ((idx, child, name), value) in self.dirty_params.drain() {
+            if let Some(child) = child {
+                self.backend.set_child_parameter(idx, child, name, value);
+            } else {
+                self.backend.begin_edit(idx, name);
+                self.backend.set_parameter(idx, name, value);
+                self.backend.end_edit(idx, name);
+            }
         }
     }
+}
 
-    fn update_processor_chain(&self) {
-        self.backend.set_amp_chain(&self.stages);
+/// Where the stage at `i` ends up after the stages at `a` and `b` swap.
+const fn swapped(i: usize, a: usize, b: usize) -> usize {
+    if i == a {
+        b
+    } else if i == b {
+        a
+    } else {
+        i
+    }
+}
+
+/// Where the stage at `i` ends up after the one at `from` moves to `to`.
+const fn moved(i: usize, from: usize, to: usize) -> usize {
+    if i == from {
+        to
+    } else if from < to && i > from && i <= to {
+        i - 1
+    } else if to < from && i >= to && i < from {
+        i + 1
+    } else {
+        i
     }
 }
 
@@ -734,6 +2131,27 @@ pub fn tab_button_active(
     }
 }
 
+/// Wrap a button style with a border marking keyboard focus.
+pub fn with_focus_ring(
+    focused: bool,
+    style: impl Fn(&iced::Theme, iced::widget::button::Status) -> iced::widget::button::Style,
+) -> impl Fn(&iced::Theme, iced::widget::button::Status) -> iced::widget::button::Style {
+    move |theme: &iced::Theme, status: iced::widget::button::Status| {
+        let base = style(theme, status);
+        if !focused {
+            return base;
+        }
+        iced::widget::button::Style {
+            border: iced::Border {
+                color: theme.palette().text,
+                width: 2.0,
+                radius: base.border.radius,
+            },
+            ..base
+        }
+    }
+}
+
 pub fn tab_button_inactive(
     theme: &iced::Theme,
     status: iced::widget::button::Status,
@@ -757,3 +2175,32 @@ pub fn tab_button_inactive(
         ..iced::widget::button::Style::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drag_from(from: usize) -> StageDrag {
+        StageDrag { from, over: None }
+    }
+
+    #[test]
+    fn releasing_outside_the_cards_cancels_the_drag() {
+        let mut drag = drag_from(0);
+        drag.hover(2);
+        drag.leave(2);
+        assert!(drag.released().is_none());
+    }
+
+    #[test]
+    fn leaving_a_card_after_entering_the_next_keeps_the_target() {
+        let mut drag = drag_from(0);
+        drag.hover(2);
+        drag.hover(1);
+        drag.leave(2);
+        assert!(matches!(
+            drag.released(),
+            Some(Message::MoveStageTo { from: 0, to: 1 })
+        ));
+    }
+}
@@ -1,5 +1,15 @@
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::denoiser::{DenoiseConfig, NoiseLearn};
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::audio::engine::{EngineState, MonitorPoint};
 use rustortion_core::audio::peak_meter::PeakMeterInfo;
+use rustortion_core::audio::recorder::RecordingStatus;
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::ir::load_service::IrLoadFailure;
+use rustortion_core::ir::loader::{IrError, IrMetadata, IrTagVocabulary};
+use rustortion_core::ir::thumbnail::ThumbnailLookup;
 use rustortion_core::preset::InputFilterConfig;
+use rustortion_core::preset::chain_update::{ChainEdit, ChainUpdate, plan_chain_update};
 use rustortion_core::preset::stage_config::StageConfig;
 
 /// Capabilities of the current backend — controls which UI sections render.
@@ -11,6 +21,12 @@ pub struct Capabilities {
     pub has_midi_config: bool,
     pub has_jack_settings: bool,
     pub has_preset_management: bool,
+    /// Setlists saved in their own directory, stepped through on stage.
+    pub has_setlists: bool,
+    /// Live per-stage level bars, from [`ParamBackend::take_stage_levels`].
+    pub has_stage_levels: bool,
+    /// The engine gets a tempo to sync delays to (the host transport).
+    pub has_tempo_sync: bool,
 }
 
 impl Capabilities {
@@ -22,6 +38,11 @@ impl Capabilities {
             has_midi_config: true,
             has_jack_settings: true,
             has_preset_management: true,
+            has_setlists: true,
+            has_stage_levels: true,
+            // The metronome provides one while it's on; the standalone sets
+            // this to follow it.
+            has_tempo_sync: false,
         }
     }
 
@@ -33,6 +54,9 @@ impl Capabilities {
             has_midi_config: false,
             has_jack_settings: false,
             has_preset_management: false,
+            has_setlists: false,
+            has_stage_levels: false,
+            has_tempo_sync: true,
         }
     }
 }
@@ -44,6 +68,10 @@ pub enum ExternalEvent {
         info: PeakMeterInfo,
         xrun_count: u64,
         cpu_load: f32,
+        /// The engine is shedding load at reduced quality.
+        reduced_quality: bool,
+        /// The right channel's meter, when it runs a chain of its own.
+        right: Option<PeakMeterInfo>,
     },
     ParamsChanged,
 }
@@ -55,20 +83,90 @@ pub trait ParamBackend: Send + Sync + 'static {
     fn set_parameter(&self, stage_idx: usize, name: &'static str, value: f32);
     fn begin_edit(&self, _stage_idx: usize, _name: &str) {}
     fn end_edit(&self, _stage_idx: usize, _name: &str) {}
+    /// Set a parameter on the stage at `child` inside the group at
+    /// `stage_idx`. Group children aren't host parameters, so there is no
+    /// edit gesture to bracket it with.
+    fn set_child_parameter(&self, stage_idx: usize, child: usize, name: &'static str, value: f32);
 
     fn rebuild_stage(&self, stage_idx: usize, config: &StageConfig);
+    /// Rebuild a stage after a change that can't be smoothed, such as a new
+    /// clipper type or tone stack model, fading over from the old build
+    /// instead of cutting to the new one. Defaults to a plain rebuild.
+    fn swap_stage(&self, stage_idx: usize, config: &StageConfig) {
+        self.rebuild_stage(stage_idx, config);
+    }
     fn set_amp_chain(&self, stages: &[StageConfig]);
     fn set_bypass(&self, stage_idx: usize, bypassed: bool);
     fn add_stage(&self, idx: usize, config: &StageConfig);
     fn remove_stage(&self, idx: usize);
     fn swap_stages(&self, a: usize, b: usize);
+    /// Solo a stage, or `None` to hear the whole chain. Never saved.
+    fn set_monitor_point(&self, point: Option<MonitorPoint>);
+    /// Mute the power amp's output while the chain keeps running, or bring
+    /// it back. Kept across preset switches; never saved.
+    fn set_standby(&self, on: bool);
 
-    fn set_ir(&self, path: &str);
+    /// Bring the engine from a chain built from `previous` to one built from
+    /// `stages`, rebuilding only the stages that changed so the rest keep
+    /// their delay lines and filter state. Assumes the engine's chain matches
+    /// `previous`; backends where something else can replace the chain
+    /// should override this with a full `set_amp_chain`.
+    fn sync_amp_chain(&self, previous: &[StageConfig], stages: &[StageConfig]) {
+        match plan_chain_update(previous, stages) {
+            ChainUpdate::Rebuild => self.set_amp_chain(stages),
+            ChainUpdate::Edits(edits) => {
+                for edit in edits {
+                    match edit {
+                        ChainEdit::Replace(idx) => self.rebuild_stage(idx, &stages[idx]),
+                        ChainEdit::Insert(idx) => self.add_stage(idx, &stages[idx]),
+                        ChainEdit::Remove(idx) => self.remove_stage(idx),
+                        ChainEdit::SetBypassed(idx, bypassed) => self.set_bypass(idx, bypassed),
+                    }
+                }
+            }
+        }
+    }
+    /// Swap in a chain built from `stages` on the metronome's next bar, or
+    /// at once while it's stopped. Edits made before then go to the waiting
+    /// chain. Defaults to a plain `set_amp_chain`.
+    fn queue_amp_chain(&self, stages: &[StageConfig]) {
+        self.set_amp_chain(stages);
+    }
+    /// Swap in a chain built from `stages` together with the next IR to
+    /// load, so a preset's chain isn't heard through the previous preset's
+    /// IR. Edits made before then go to the waiting chain. Defaults to a
+    /// plain `set_amp_chain`.
+    fn hold_amp_chain(&self, stages: &[StageConfig]) {
+        self.set_amp_chain(stages);
+    }
+    /// Whether a chain passed to `queue_amp_chain` is still waiting for its bar.
+    fn chain_queued(&self) -> bool {
+        false
+    }
+
+    /// Load the named IR, shifted and flipped by `alignment`. An error means
+    /// it was refused and the previous IR is still playing; loads that fail
+    /// later come from [`Self::take_ir_load_failure`].
+    fn set_ir(&self, name: &str, alignment: IrAlignment) -> Result<(), IrError>;
     fn set_ir_bypass(&self, bypassed: bool);
     fn set_ir_gain(&self, gain: f32);
+    /// Level-matching trim on top of the IR gain, for comparing IRs. Not a
+    /// host parameter and not saved.
+    fn set_ir_trim_db(&self, trim_db: f32);
 
     fn set_input_filter(&self, filter: &InputFilterConfig);
+    /// Denoise the input with `config`'s noise print, or stop when it has
+    /// none or is bypassed.
+    fn set_denoiser(&self, config: &DenoiseConfig);
+    /// Start learning the input's noise print; poll the result for the
+    /// countdown and the print.
+    fn learn_noise(&self) -> NoiseLearn;
     fn set_pitch_shift(&self, semitones: i32);
+    fn set_ambience(&self, config: AmbienceConfig);
+    fn set_double_tracker(&self, config: DoubleTrackerConfig);
+    /// Output tilt after the IR, in dB: the global setting plus the preset's
+    /// offset.
+    fn set_tilt_db(&self, tilt_db: f32);
     fn set_oversampling(&self, factor: u32);
     fn set_preset_index(&self, _index: usize) {}
 
@@ -78,7 +176,61 @@ pub trait ParamBackend: Send + Sync + 'static {
     fn capabilities(&self) -> &Capabilities;
 
     fn get_available_irs(&self) -> Vec<String>;
+    /// Mic, speaker and distance tags for each available IR, read from the
+    /// file names with the built-in token lists unless the backend has its own.
+    fn get_available_irs_with_metadata(&self) -> Vec<IrMetadata> {
+        let vocabulary = IrTagVocabulary::default();
+        self.get_available_irs()
+            .iter()
+            .map(|name| IrMetadata::parse(name, &vocabulary))
+            .collect()
+    }
+    /// IRs that will be refused (too long, too many channels, unreadable),
+    /// marked with a warning in the picker.
+    fn get_flagged_irs(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Onset of the named IR before any alignment, in samples, for
+    /// auto-align. `None` if it isn't loaded or is silent.
+    fn ir_onset(&self, name: &str) -> Option<usize>;
+    /// Broadband gain of the named IR as it's played, for level matching.
+    /// `None` if it isn't loaded yet.
+    fn ir_energy(&self, name: &str) -> Option<f32>;
+    /// Start loading an IR in the background so it can be measured and
+    /// swapped in without a wait.
+    fn preload_ir(&self, _name: &str) {}
+    /// Waveform and spectrum preview of the named IR for the picker. Made in
+    /// the background: `Pending` until then, so ask again.
+    fn ir_thumbnail(&self, _name: &str) -> ThumbnailLookup {
+        ThumbnailLookup::Unavailable
+    }
+    /// A background IR load that failed since the last call.
+    fn take_ir_load_failure(&self) -> Option<IrLoadFailure> {
+        None
+    }
+    /// Whether an IR passed to `set_ir` is still loading, so the engine
+    /// plays the previous one for now.
+    fn ir_load_pending(&self) -> bool {
+        false
+    }
+    /// What the engine has applied, for the GUI to check what it shows
+    /// against. `None` while messages are still on their way to it.
+    fn engine_state(&self) -> Option<EngineState> {
+        None
+    }
     fn get_peak_meter_info(&self) -> Option<ExternalEvent>;
+    /// Start or stop tapping each stage's output for the level bars. Off by
+    /// default, and free for the engine while off.
+    fn set_stage_levels_enabled(&self, _enabled: bool) {}
+    /// Linear output peak of each of the first `len` stages since the last
+    /// call. Empty if the backend doesn't tap stages.
+    fn take_stage_levels(&self, _len: usize) -> Vec<f32> {
+        Vec::new()
+    }
+    /// The take being recorded, if the backend records and one is running.
+    fn recording_status(&self) -> Option<RecordingStatus> {
+        None
+    }
 
     /// Directory the NAM stage loads `.nam` models from (for display), if any.
     fn nam_models_dir(&self) -> Option<std::path::PathBuf>;
@@ -86,8 +238,22 @@ pub trait ParamBackend: Send + Sync + 'static {
     /// Returns the number of models now available, or an error string.
     fn rescan_nam_models(&self) -> Result<usize, String>;
 
+    /// Directory the capture stage scans for capture files (for display), if any.
+    fn captures_dir(&self) -> Option<std::path::PathBuf>;
+    /// Re-scan the captures directory and re-register the global registry.
+    /// Returns the number of capture files found, or an error string.
+    fn rescan_captures(&self) -> Result<usize, String>;
+
     /// Called by the shared GUI after any stage mutation (add, remove, reorder,
     /// param change, preset load) so the backend can persist the chain state.
     /// Default is a no-op (standalone doesn't need this).
     fn persist_chain_state(&self, _stages: &[StageConfig]) {}
+
+    /// Pull stage parameter changes made on the host side (DAW automation,
+    /// the host's generic editor) into `stages`. Returns whether any changed;
+    /// the engine already runs the new values. Default: the GUI is the only
+    /// source of changes.
+    fn sync_host_params(&self, _stages: &mut [StageConfig]) -> bool {
+        false
+    }
 }
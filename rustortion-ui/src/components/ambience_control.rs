@@ -0,0 +1,76 @@
+use iced::widget::{column, row, slider, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::components::widgets::common::{
+    SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_SMALL, section_container, section_title,
+};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::audio::ambience::AmbienceConfig;
+
+/// Compact amount/decay/tone strip for the post-IR ambience, shown under
+/// the cabinet control.
+pub struct AmbienceControl {
+    config: AmbienceConfig,
+}
+
+impl Default for AmbienceControl {
+    fn default() -> Self {
+        Self::new(AmbienceConfig::default())
+    }
+}
+
+impl AmbienceControl {
+    pub const fn new(config: AmbienceConfig) -> Self {
+        Self { config }
+    }
+
+    pub const fn set_config(&mut self, config: AmbienceConfig) {
+        self.config = config;
+    }
+
+    pub const fn get_config(&self) -> AmbienceConfig {
+        self.config
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let config = self.config;
+        let knobs = row![
+            knob(tr!(ambience_amount), config.amount, move |amount| {
+                Message::AmbienceChanged(AmbienceConfig { amount, ..config })
+            }),
+            knob(tr!(ambience_decay), config.decay, move |decay| {
+                Message::AmbienceChanged(AmbienceConfig { decay, ..config })
+            }),
+            knob(tr!(ambience_tone), config.tone, move |tone| {
+                Message::AmbienceChanged(AmbienceConfig { tone, ..config })
+            }),
+        ]
+        .spacing(SPACING_NORMAL);
+
+        section_container(
+            column![section_title(tr!(ambience)), knobs]
+                .spacing(SPACING_NORMAL)
+                .into(),
+        )
+    }
+}
+
+fn knob<'a>(
+    label: &'a str,
+    value: f32,
+    on_change: impl Fn(f32) -> Message + 'a,
+) -> Element<'a, Message> {
+    column![
+        row![
+            text(label).size(TEXT_SIZE_SMALL),
+            space::horizontal(),
+            text(format!("{:.0}%", value * 100.0)).size(TEXT_SIZE_SMALL),
+        ]
+        .align_y(Alignment::Center),
+        slider(0.0..=1.0, value, on_change).step(0.01),
+    ]
+    .spacing(SPACING_TIGHT)
+    .width(Length::Fill)
+    .into()
+}
@@ -0,0 +1,134 @@
+use std::time::Instant;
+
+use iced::widget::{button, checkbox, column, row, text};
+use iced::{Alignment, Element};
+
+use crate::components::widgets::common::{
+    COLOR_ERROR, COLOR_MUTED, SPACING_NORMAL, TEXT_SIZE_SMALL, labeled_slider, section_container,
+    section_title,
+};
+use crate::messages::{DenoiseMessage, Message};
+use crate::tr;
+use rustortion_core::audio::denoiser::{DenoiseConfig, MAX_REDUCTION_DB, NoiseLearn};
+
+/// Learn button, reduction and bypass for the input denoiser, with the
+/// countdown while a noise print is being learned.
+pub struct DenoiserControl {
+    config: DenoiseConfig,
+    learn: Option<NoiseLearn>,
+    /// Whole seconds left on the running learn, as last polled.
+    remaining_secs: u64,
+    /// The last learn timed out without hearing anything.
+    learn_failed: bool,
+}
+
+impl Default for DenoiserControl {
+    fn default() -> Self {
+        Self::new(DenoiseConfig::default())
+    }
+}
+
+impl DenoiserControl {
+    pub const fn new(config: DenoiseConfig) -> Self {
+        Self {
+            config,
+            learn: None,
+            remaining_secs: 0,
+            learn_failed: false,
+        }
+    }
+
+    pub fn set_config(&mut self, config: DenoiseConfig) {
+        self.config = config;
+        self.learn_failed = false;
+    }
+
+    pub const fn config(&self) -> &DenoiseConfig {
+        &self.config
+    }
+
+    pub const fn set_reduction_db(&mut self, reduction_db: f32) {
+        self.config.reduction_db = reduction_db;
+    }
+
+    pub const fn set_bypassed(&mut self, bypassed: bool) {
+        self.config.bypassed = bypassed;
+    }
+
+    pub const fn is_learning(&self) -> bool {
+        self.learn.is_some()
+    }
+
+    pub fn start_learn(&mut self, learn: NoiseLearn, now: Instant) {
+        self.remaining_secs = learn.remaining(now).as_secs_f32().ceil() as u64;
+        self.learn = Some(learn);
+        self.learn_failed = false;
+    }
+
+    /// Check on the running learn at `now`. Returns true once it has
+    /// finished and its noise print is in the config.
+    pub fn poll_learn(&mut self, now: Instant) -> bool {
+        let Some(learn) = &self.learn else {
+            return false;
+        };
+        if let Some(profile) = learn.try_finish() {
+            self.config.profile = Some(profile);
+            self.learn = None;
+            return true;
+        }
+        if learn.timed_out(now) {
+            self.learn = None;
+            self.learn_failed = true;
+            return false;
+        }
+        self.remaining_secs = learn.remaining(now).as_secs_f32().ceil() as u64;
+        false
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let learn_button = if self.is_learning() {
+            button(text(format!(
+                "{} {}s",
+                tr!(denoise_learning),
+                self.remaining_secs
+            )))
+        } else {
+            button(tr!(denoise_learn)).on_press(Message::Denoise(DenoiseMessage::Learn))
+        };
+
+        let (status, color) = if self.learn_failed {
+            (tr!(denoise_learn_failed), Some(COLOR_ERROR))
+        } else if self.config.profile.is_some() {
+            (tr!(denoise_learned), None)
+        } else {
+            (tr!(denoise_no_profile), Some(COLOR_MUTED))
+        };
+        let status = text(status)
+            .size(TEXT_SIZE_SMALL)
+            .style(move |_: &iced::Theme| iced::widget::text::Style { color });
+
+        let controls = column![
+            row![learn_button, status]
+                .spacing(SPACING_NORMAL)
+                .align_y(Alignment::Center),
+            checkbox(self.config.bypassed)
+                .label(tr!(bypassed))
+                .on_toggle(|bypassed| Message::Denoise(DenoiseMessage::BypassToggled(bypassed))),
+            labeled_slider(
+                tr!(denoise_reduction),
+                0.0..=MAX_REDUCTION_DB,
+                self.config.reduction_db,
+                |db| Message::Denoise(DenoiseMessage::ReductionChanged(db)),
+                |db| format!("{db:.1} dB"),
+                0.5,
+            ),
+        ]
+        .spacing(SPACING_NORMAL);
+
+        section_container(
+            column![section_title(tr!(denoise)), controls]
+                .spacing(SPACING_NORMAL)
+                .into(),
+        )
+    }
+}
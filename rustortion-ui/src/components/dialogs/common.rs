@@ -1,10 +1,11 @@
-use iced::widget::{button, column, container, pick_list, row, scrollable, space, text};
+use iced::widget::{button, column, container, row, scrollable, space, text};
 use iced::{Alignment, Color, Element, Length};
 
 use crate::components::widgets::common::{
     BORDER_RADIUS_CARD, BORDER_RADIUS_DIALOG, COLOR_MUTED, COLOR_SUCCESS, COLOR_WARNING,
     PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO, TEXT_SIZE_LABEL,
 };
+use crate::mappings::validate::Conflict;
 use crate::tr;
 
 use super::{DIALOG_TITLE_ROW_SPACING, DIALOG_TITLE_SIZE};
@@ -72,32 +73,53 @@ pub fn waiting_for_input_view<'a, M: 'a>(prompt_text: &'a str) -> Element<'a, M>
     .into()
 }
 
+/// A mapping conflict in words. `new` is for an input still being learned,
+/// where confirming replaces the mapping it duplicates.
+pub fn conflict_text(conflict: &Conflict, new: bool) -> String {
+    match conflict {
+        Conflict::Duplicate { target } if new => format!("{} {target}", tr!(mapping_replaces)),
+        Conflict::Duplicate { target } => format!("{} {target}", tr!(mapping_duplicate)),
+        Conflict::ShadowsTyping => tr!(mapping_shadows_typing).to_owned(),
+    }
+}
+
+/// Amber warning line for a mapping conflict.
+pub fn conflict_warning<'a, M: 'a>(conflict: &Conflict, new: bool) -> Element<'a, M> {
+    text(format!("\u{26a0} {}", conflict_text(conflict, new)))
+        .size(TEXT_SIZE_INFO)
+        .style(|_: &iced::Theme| iced::widget::text::Style {
+            color: Some(COLOR_WARNING),
+        })
+        .into()
+}
+
 /// Green "captured: X" container with preset picker + confirm button.
+/// `picker` chooses what the input does; confirming is enabled once `can_confirm`.
+/// A `conflict` with the existing mappings is warned about above the picker.
 pub fn input_captured_view<'a, M: Clone + 'a>(
     description: &str,
-    presets: &[String],
-    selected_preset: Option<String>,
-    on_select: impl Fn(String) -> M + 'a,
+    conflict: Option<&Conflict>,
+    picker: Element<'a, M>,
+    can_confirm: bool,
     confirm_msg: M,
 ) -> Element<'a, M> {
-    let captured_text = text(format!("{} {}", tr!(captured), description))
-        .size(TEXT_SIZE_LABEL)
-        .style(|_: &iced::Theme| iced::widget::text::Style {
-            color: Some(COLOR_SUCCESS),
-        });
-
-    let has_preset = selected_preset.is_some();
-
-    let preset_picker = row![
-        text(tr!(assign_to)).width(Length::Fixed(80.0)),
-        pick_list(presets.to_vec(), selected_preset, on_select)
-            .width(Length::Fill)
-            .placeholder(tr!(select_preset)),
+    let mut captured_text = column![
+        text(format!("{} {}", tr!(captured), description))
+            .size(TEXT_SIZE_LABEL)
+            .style(|_: &iced::Theme| iced::widget::text::Style {
+                color: Some(COLOR_SUCCESS),
+            })
     ]
-    .spacing(SPACING_NORMAL)
-    .align_y(Alignment::Center);
+    .spacing(SPACING_TIGHT);
+    if let Some(conflict) = conflict {
+        captured_text = captured_text.push(conflict_warning(conflict, true));
+    }
+
+    let preset_picker = row![text(tr!(assign_to)).width(Length::Fixed(80.0)), picker,]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Start);
 
-    let confirm_button = if has_preset {
+    let confirm_button = if can_confirm {
         button(tr!(confirm_mapping))
             .on_press(confirm_msg)
             .style(iced::widget::button::success)
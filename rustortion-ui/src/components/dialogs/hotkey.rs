@@ -1,5 +1,5 @@
 use iced::keyboard::{Key, Modifiers};
-use iced::widget::{button, column, row, rule, space};
+use iced::widget::{button, checkbox, column, pick_list, row, rule, space, text};
 use iced::{Alignment, Element, Length};
 
 use super::common::{
@@ -8,9 +8,14 @@ use super::common::{
 };
 use super::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
 use crate::components::widgets::common::{SPACING_NORMAL, TEXT_SIZE_SECTION_TITLE};
-use crate::hotkey::{HotkeyMapping, is_uncapturable_key, serialize_key, serialize_modifiers};
+use crate::hotkey::{
+    HotkeyAction, HotkeyMapping, is_uncapturable_key, serialize_key, serialize_modifiers,
+};
+use crate::mappings::Trigger;
+use crate::mappings::validate::{self, Conflict};
 use crate::messages::HotkeyMessage;
 use crate::tr;
+use rustortion_core::preset::QUICK_SLOTS;
 
 /// State for the "learning" mode where we wait for a key press
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,6 +39,10 @@ pub struct HotkeyDialog {
     learning_state: LearningState,
     /// Preset selected for new mapping
     selected_preset_for_mapping: Option<String>,
+    /// What the new mapping does
+    action_for_mapping: HotkeyAction,
+    /// Quick slot for a new save or load slot mapping
+    slot_for_mapping: u8,
 }
 
 impl Default for HotkeyDialog {
@@ -50,6 +59,8 @@ impl HotkeyDialog {
             available_presets: Vec::new(),
             learning_state: LearningState::Idle,
             selected_preset_for_mapping: None,
+            action_for_mapping: HotkeyAction::LoadPreset,
+            slot_for_mapping: 1,
         }
     }
 
@@ -79,11 +90,15 @@ impl HotkeyDialog {
     pub fn start_learning(&mut self) {
         self.learning_state = LearningState::WaitingForInput;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = HotkeyAction::LoadPreset;
+        self.slot_for_mapping = 1;
     }
 
     pub fn cancel_learning(&mut self) {
         self.learning_state = LearningState::Idle;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = HotkeyAction::LoadPreset;
+        self.slot_for_mapping = 1;
     }
 
     /// Called when a key is pressed while in learning mode
@@ -119,10 +134,53 @@ impl HotkeyDialog {
         self.selected_preset_for_mapping = Some(preset);
     }
 
+    /// Tick or untick one of the "instead of loading a preset" boxes.
+    pub const fn toggle_action_for_mapping(&mut self, action: HotkeyAction, on: bool) {
+        self.action_for_mapping = if on { action } else { HotkeyAction::LoadPreset };
+    }
+
+    pub const fn slot_for_mapping(&self) -> u8 {
+        self.slot_for_mapping
+    }
+
+    /// Pick the quick slot, retargeting a slot action already ticked.
+    pub const fn set_slot_for_mapping(&mut self, slot: u8) {
+        self.slot_for_mapping = slot;
+        self.action_for_mapping = match self.action_for_mapping {
+            HotkeyAction::SaveSlot(_) => HotkeyAction::SaveSlot(slot),
+            HotkeyAction::LoadSlot(_) => HotkeyAction::LoadSlot(slot),
+            action => action,
+        };
+    }
+
+    const fn can_confirm(&self) -> bool {
+        !matches!(self.action_for_mapping, HotkeyAction::LoadPreset)
+            || self.selected_preset_for_mapping.is_some()
+    }
+
     pub fn get_mappings(&self) -> Vec<HotkeyMapping> {
         self.mappings.clone()
     }
 
+    pub fn set_mappings(&mut self, mappings: Vec<HotkeyMapping>) {
+        self.mappings = mappings;
+    }
+
+    /// What the captured key would collide with, warned about before it's
+    /// confirmed.
+    fn captured_conflict(&self) -> Option<Conflict> {
+        let LearningState::InputCaptured { key, modifiers, .. } = &self.learning_state else {
+            return None;
+        };
+        let existing: Vec<_> = self
+            .mappings
+            .iter()
+            .enumerate()
+            .map(|(i, m)| m.binding(i))
+            .collect();
+        validate::check_new(&Trigger::key(key, modifiers), &existing)
+    }
+
     /// Complete adding a new mapping
     pub fn complete_mapping(&mut self) -> Option<HotkeyMapping> {
         let LearningState::InputCaptured {
@@ -134,19 +192,40 @@ impl HotkeyDialog {
             return None;
         };
 
-        let preset_name = self.selected_preset_for_mapping.as_ref()?;
-
-        let mapping = HotkeyMapping::new(key.clone(), modifiers.clone(), preset_name.clone());
+        let mapping = match self.action_for_mapping {
+            HotkeyAction::LoadPreset => {
+                let preset_name = self.selected_preset_for_mapping.as_ref()?;
+                HotkeyMapping::new(key.clone(), modifiers.clone(), preset_name.clone())
+            }
+            HotkeyAction::HoldTuner => HotkeyMapping::hold_tuner(key.clone(), modifiers.clone()),
+            HotkeyAction::PreviousPreset => {
+                HotkeyMapping::previous_preset(key.clone(), modifiers.clone())
+            }
+            HotkeyAction::Randomize => HotkeyMapping::randomize(key.clone(), modifiers.clone()),
+            HotkeyAction::NextSong => HotkeyMapping::next_song(key.clone(), modifiers.clone()),
+            HotkeyAction::PreviousSong => {
+                HotkeyMapping::previous_song(key.clone(), modifiers.clone())
+            }
+            HotkeyAction::ToggleStandby => {
+                HotkeyMapping::toggle_standby(key.clone(), modifiers.clone())
+            }
+            HotkeyAction::SaveSlot(slot) => {
+                HotkeyMapping::save_slot(key.clone(), modifiers.clone(), slot)
+            }
+            HotkeyAction::LoadSlot(slot) => {
+                HotkeyMapping::load_slot(key.clone(), modifiers.clone(), slot)
+            }
+        };
 
-        // Remove any existing mapping for the same key+modifiers
-        let key_match = key.clone();
-        let mods_match = modifiers.clone();
-        self.mappings
-            .retain(|m| !(m.key == key_match && m.modifiers == mods_match));
+        // Replace any existing mapping for the same key and modifiers, in
+        // whatever order they were held.
+        let trigger = mapping.trigger();
+        self.mappings.retain(|m| m.trigger() != trigger);
 
         self.mappings.push(mapping.clone());
         self.learning_state = LearningState::Idle;
         self.selected_preset_for_mapping = None;
+        self.action_for_mapping = HotkeyAction::LoadPreset;
 
         Some(mapping)
     }
@@ -196,20 +275,87 @@ impl HotkeyDialog {
         let learning_content: Element<'_, HotkeyMessage> = match &self.learning_state {
             LearningState::Idle => column![].into(),
             LearningState::WaitingForInput => waiting_for_input_view(tr!(press_any_key)),
-            LearningState::InputCaptured { description, .. } => input_captured_view(
-                description,
-                &self.available_presets,
-                self.selected_preset_for_mapping.clone(),
-                HotkeyMessage::PresetSelected,
-                HotkeyMessage::ConfirmMapping,
-            ),
+            LearningState::InputCaptured { description, .. } => {
+                let hold_tuner = checkbox(self.action_for_mapping == HotkeyAction::HoldTuner)
+                    .label(tr!(hold_tuner_instead))
+                    .on_toggle(HotkeyMessage::HoldTunerToggled);
+                let previous = checkbox(self.action_for_mapping == HotkeyAction::PreviousPreset)
+                    .label(tr!(previous_preset_instead))
+                    .on_toggle(HotkeyMessage::PreviousPresetToggled);
+                let randomize = checkbox(self.action_for_mapping == HotkeyAction::Randomize)
+                    .label(tr!(randomize_instead))
+                    .on_toggle(HotkeyMessage::RandomizeToggled);
+                let next_song = checkbox(self.action_for_mapping == HotkeyAction::NextSong)
+                    .label(tr!(next_song_instead))
+                    .on_toggle(HotkeyMessage::NextSongToggled);
+                let previous_song = checkbox(self.action_for_mapping == HotkeyAction::PreviousSong)
+                    .label(tr!(previous_song_instead))
+                    .on_toggle(HotkeyMessage::PreviousSongToggled);
+                let standby = checkbox(self.action_for_mapping == HotkeyAction::ToggleStandby)
+                    .label(tr!(toggle_standby_instead))
+                    .on_toggle(HotkeyMessage::ToggleStandbyToggled);
+                let save_slot =
+                    checkbox(matches!(self.action_for_mapping, HotkeyAction::SaveSlot(_)))
+                        .label(tr!(save_slot_instead))
+                        .on_toggle(HotkeyMessage::SaveSlotToggled);
+                let load_slot =
+                    checkbox(matches!(self.action_for_mapping, HotkeyAction::LoadSlot(_)))
+                        .label(tr!(load_slot_instead))
+                        .on_toggle(HotkeyMessage::LoadSlotToggled);
+                let mut target = column![
+                    hold_tuner,
+                    previous,
+                    randomize,
+                    next_song,
+                    previous_song,
+                    standby,
+                    save_slot,
+                    load_slot
+                ]
+                .spacing(SPACING_NORMAL);
+                if matches!(
+                    self.action_for_mapping,
+                    HotkeyAction::SaveSlot(_) | HotkeyAction::LoadSlot(_)
+                ) {
+                    target = target.push(
+                        row![
+                            text(tr!(quick_slot)),
+                            pick_list(
+                                (1..=QUICK_SLOTS).collect::<Vec<_>>(),
+                                Some(self.slot_for_mapping),
+                                HotkeyMessage::SlotSelected,
+                            ),
+                        ]
+                        .spacing(SPACING_NORMAL)
+                        .align_y(Alignment::Center),
+                    );
+                }
+                if self.action_for_mapping == HotkeyAction::LoadPreset {
+                    target = target.push(
+                        pick_list(
+                            self.available_presets.clone(),
+                            self.selected_preset_for_mapping.clone(),
+                            HotkeyMessage::PresetSelected,
+                        )
+                        .width(Length::Fill)
+                        .placeholder(tr!(select_preset)),
+                    );
+                }
+                input_captured_view(
+                    description,
+                    self.captured_conflict().as_ref(),
+                    target.into(),
+                    self.can_confirm(),
+                    HotkeyMessage::ConfirmMapping,
+                )
+            }
         };
 
         // Existing mappings list
         let mappings_list = mapping_list_view(
             self.mappings
                 .iter()
-                .map(|m| (m.description.clone(), m.preset_name.clone()))
+                .map(|m| (m.description.clone(), m.target_label().into_owned()))
                 .collect(),
             tr!(no_mappings_configured),
             HotkeyMessage::RemoveMapping,
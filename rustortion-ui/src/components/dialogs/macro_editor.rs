@@ -0,0 +1,391 @@
+use std::fmt;
+
+use iced::widget::{button, column, pick_list, row, rule, scrollable, space, text, text_input};
+use iced::{Alignment, Element, Length};
+
+use super::common::{dialog_container, dialog_section_container, dialog_title_row, muted_text};
+use super::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use crate::components::widgets::common::{
+    COLOR_WARNING, ICON_BUTTON_WIDTH, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO,
+};
+use crate::messages::{MacroMessage, Message};
+use crate::stages::StageConfig;
+use crate::tr;
+use rustortion_core::preset::{Macro, MacroCurve, MacroTarget};
+
+const VALUE_INPUT_WIDTH: f32 = 80.0;
+
+/// A stage the picker offers, shown by its place in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StageChoice {
+    idx: usize,
+    label: String,
+}
+
+impl fmt::Display for StageChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}. {}", self.idx + 1, self.label)
+    }
+}
+
+/// A target as it's being edited, with its range as typed.
+#[derive(Debug, Clone)]
+struct DraftTarget {
+    stage_idx: usize,
+    param: String,
+    from: String,
+    to: String,
+    curve: MacroCurve,
+}
+
+impl DraftTarget {
+    fn new(target: &MacroTarget) -> Self {
+        Self {
+            stage_idx: target.stage_idx,
+            param: target.param.clone(),
+            from: target.from.to_string(),
+            to: target.to.to_string(),
+            curve: target.curve,
+        }
+    }
+
+    /// The target, if both ends of its range are numbers.
+    fn target(&self) -> Option<MacroTarget> {
+        Some(MacroTarget {
+            stage_idx: self.stage_idx,
+            param: self.param.clone(),
+            from: self.from.trim().parse().ok()?,
+            to: self.to.trim().parse().ok()?,
+            curve: self.curve,
+        })
+    }
+
+    /// Aim at `param` of `stage_idx`, with the range starting and ending at
+    /// its current value.
+    fn point_at(&mut self, stages: &[StageConfig], stage_idx: usize, param: &str) {
+        let value = stages
+            .get(stage_idx)
+            .and_then(|stage| stage.parameter(param))
+            .unwrap_or_default();
+        self.stage_idx = stage_idx;
+        self.param = param.to_owned();
+        self.from = value.to_string();
+        self.to = value.to_string();
+    }
+}
+
+struct Draft {
+    /// Which macro is being edited, or `None` for one not added yet.
+    index: Option<usize>,
+    name: String,
+    value: f32,
+    targets: Vec<DraftTarget>,
+}
+
+/// Editor for one macro: its name, and the stage parameters it moves with
+/// each one's range and curve.
+#[derive(Default)]
+pub struct MacroEditorDialog {
+    draft: Option<Draft>,
+}
+
+impl MacroEditorDialog {
+    pub const fn new() -> Self {
+        Self { draft: None }
+    }
+
+    /// Open on `macro_`, the macro at `index` or a new one for `None`.
+    pub fn show(&mut self, index: Option<usize>, macro_: &Macro) {
+        self.draft = Some(Draft {
+            index,
+            name: macro_.name.clone(),
+            value: macro_.value,
+            targets: macro_.targets.iter().map(DraftTarget::new).collect(),
+        });
+    }
+
+    pub fn hide(&mut self) {
+        self.draft = None;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    /// Index of the macro being edited, `None` for a new one or when closed.
+    pub fn editing(&self) -> Option<usize> {
+        self.draft.as_ref().and_then(|draft| draft.index)
+    }
+
+    /// Apply an edit to the draft. Messages that aren't edits are ignored.
+    pub fn handle(&mut self, msg: MacroMessage, stages: &[StageConfig]) {
+        let Some(draft) = self.draft.as_mut() else {
+            return;
+        };
+        match msg {
+            MacroMessage::NameChanged(name) => draft.name = name,
+            MacroMessage::AddTarget => {
+                if let Some((idx, param)) = stages.iter().enumerate().find_map(|(idx, stage)| {
+                    stage.parameter_names().first().map(|&param| (idx, param))
+                }) {
+                    let mut target = DraftTarget {
+                        stage_idx: idx,
+                        param: String::new(),
+                        from: String::new(),
+                        to: String::new(),
+                        curve: MacroCurve::default(),
+                    };
+                    target.point_at(stages, idx, param);
+                    draft.targets.push(target);
+                }
+            }
+            MacroMessage::RemoveTarget(i) => {
+                if i < draft.targets.len() {
+                    draft.targets.remove(i);
+                }
+            }
+            MacroMessage::TargetStage(i, stage_idx) => {
+                let param = stages
+                    .get(stage_idx)
+                    .and_then(|stage| stage.parameter_names().first().copied());
+                if let (Some(target), Some(param)) = (draft.targets.get_mut(i), param) {
+                    target.point_at(stages, stage_idx, param);
+                }
+            }
+            MacroMessage::TargetParam(i, param) => {
+                if let Some(target) = draft.targets.get_mut(i) {
+                    target.point_at(stages, target.stage_idx, param);
+                }
+            }
+            MacroMessage::TargetFrom(i, from) => {
+                if let Some(target) = draft.targets.get_mut(i) {
+                    target.from = from;
+                }
+            }
+            MacroMessage::TargetTo(i, to) => {
+                if let Some(target) = draft.targets.get_mut(i) {
+                    target.to = to;
+                }
+            }
+            MacroMessage::TargetCurve(i, curve) => {
+                if let Some(target) = draft.targets.get_mut(i) {
+                    target.curve = curve;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The edited macro and where it goes, if it has a name and every
+    /// target is one the chain takes.
+    fn result(&self, stages: &[StageConfig]) -> Option<(Option<usize>, Macro)> {
+        let draft = self.draft.as_ref()?;
+        let name = draft.name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let targets = draft
+            .targets
+            .iter()
+            .map(|target| target.target().filter(|t| t.is_valid(stages)))
+            .collect::<Option<Vec<_>>>()?;
+        Some((
+            draft.index,
+            Macro {
+                name: name.to_owned(),
+                value: draft.value,
+                targets,
+            },
+        ))
+    }
+
+    /// Close the editor, returning the edited macro and the index it
+    /// replaces (`None` for a new one). `None` if it can't be saved yet,
+    /// in which case the editor stays open.
+    pub fn take(&mut self, stages: &[StageConfig]) -> Option<(Option<usize>, Macro)> {
+        let result = self.result(stages)?;
+        self.draft = None;
+        Some(result)
+    }
+
+    pub fn view(&self, stages: &[StageConfig]) -> Option<Element<'_, Message>> {
+        let draft = self.draft.as_ref()?;
+
+        let title = if draft.index.is_some() {
+            tr!(edit_macro)
+        } else {
+            tr!(add_macro)
+        };
+        let title_row = dialog_title_row(title, Message::Macro(MacroMessage::Close));
+
+        let name_row = row![
+            text(tr!(macro_name)),
+            text_input(tr!(macro_name), &draft.name)
+                .on_input(|name| Message::Macro(MacroMessage::NameChanged(name))),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let choices: Vec<StageChoice> = stages
+            .iter()
+            .enumerate()
+            .filter(|(_, stage)| !stage.parameter_names().is_empty())
+            .map(|(idx, stage)| StageChoice {
+                idx,
+                label: stage.stage_type().to_string(),
+            })
+            .collect();
+
+        let targets: Element<'_, Message> = if draft.targets.is_empty() {
+            muted_text(tr!(no_macro_targets)).into()
+        } else {
+            let rows = draft
+                .targets
+                .iter()
+                .enumerate()
+                .fold(column![].spacing(SPACING_TIGHT), |col, (i, target)| {
+                    col.push(target_row(i, target, stages, &choices))
+                });
+            scrollable(rows).height(Length::Fill).into()
+        };
+
+        let section = dialog_section_container(
+            column![
+                text(tr!(macro_targets)),
+                targets,
+                button(tr!(add_macro_target))
+                    .on_press_maybe(
+                        (!choices.is_empty()).then_some(Message::Macro(MacroMessage::AddTarget))
+                    )
+                    .style(iced::widget::button::secondary),
+            ]
+            .spacing(SPACING_NORMAL)
+            .padding(SPACING_NORMAL)
+            .into(),
+        );
+
+        let mut actions = row![].spacing(SPACING_NORMAL);
+        if draft.index.is_some() {
+            actions = actions.push(
+                button(tr!(remove_macro))
+                    .on_press(Message::Macro(MacroMessage::Remove))
+                    .style(iced::widget::button::danger),
+            );
+        }
+        let save = self
+            .result(stages)
+            .is_some()
+            .then_some(Message::Macro(MacroMessage::Save));
+        let actions = actions
+            .push(space::horizontal())
+            .push(button(tr!(save)).on_press_maybe(save));
+
+        let dialog_content = column![title_row, rule::horizontal(1), name_row, section, actions]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+fn target_row<'a>(
+    i: usize,
+    target: &'a DraftTarget,
+    stages: &[StageConfig],
+    choices: &[StageChoice],
+) -> Element<'a, Message> {
+    let selected_stage = choices.iter().find(|c| c.idx == target.stage_idx).cloned();
+    let params = stages
+        .get(target.stage_idx)
+        .map(StageConfig::parameter_names)
+        .unwrap_or_default();
+    let selected_param = params.iter().copied().find(|&p| p == target.param);
+
+    let mut line = row![
+        pick_list(choices.to_vec(), selected_stage, move |choice| {
+            Message::Macro(MacroMessage::TargetStage(i, choice.idx))
+        })
+        .placeholder(tr!(macro_stage)),
+        pick_list(params, selected_param, move |param| {
+            Message::Macro(MacroMessage::TargetParam(i, param))
+        })
+        .placeholder(tr!(macro_parameter)),
+        text_input(tr!(macro_from), &target.from)
+            .on_input(move |from| Message::Macro(MacroMessage::TargetFrom(i, from)))
+            .width(VALUE_INPUT_WIDTH),
+        text_input(tr!(macro_to), &target.to)
+            .on_input(move |to| Message::Macro(MacroMessage::TargetTo(i, to)))
+            .width(VALUE_INPUT_WIDTH),
+        pick_list(MacroCurve::ALL, Some(target.curve), move |curve| {
+            Message::Macro(MacroMessage::TargetCurve(i, curve))
+        }),
+    ]
+    .spacing(SPACING_NORMAL)
+    .align_y(Alignment::Center);
+
+    if !target.target().is_some_and(|t| t.is_valid(stages)) {
+        line = line.push(text(tr!(macro_target_invalid)).size(TEXT_SIZE_INFO).style(
+            |_: &iced::Theme| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            },
+        ));
+    }
+
+    line.push(space::horizontal())
+        .push(
+            button("\u{00d7}")
+                .on_press(Message::Macro(MacroMessage::RemoveTarget(i)))
+                .style(iced::widget::button::danger)
+                .width(Length::Fixed(ICON_BUTTON_WIDTH)),
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::StageType;
+
+    fn stages() -> Vec<StageConfig> {
+        vec![
+            StageConfig::from(StageType::NoiseGate),
+            StageConfig::from(StageType::Preamp),
+        ]
+    }
+
+    #[test]
+    fn new_targets_start_at_the_current_value() {
+        let stages = stages();
+        let mut editor = MacroEditorDialog::new();
+        editor.show(None, &Macro::new("Tight".to_string()));
+        editor.handle(MacroMessage::AddTarget, &stages);
+        editor.handle(MacroMessage::TargetStage(0, 1), &stages);
+        editor.handle(MacroMessage::TargetParam(0, "bias"), &stages);
+
+        let (index, tight) = editor.take(&stages).unwrap();
+        assert_eq!(index, None);
+        let bias = stages[1].parameter("bias").unwrap();
+        assert_eq!(tight.targets[0].param, "bias");
+        assert_eq!(tight.targets[0].from, bias);
+        assert_eq!(tight.targets[0].to, bias);
+        assert!(!editor.is_visible());
+    }
+
+    #[test]
+    fn out_of_range_targets_keep_the_editor_open() {
+        let stages = stages();
+        let mut editor = MacroEditorDialog::new();
+        editor.show(Some(0), &Macro::new("Tight".to_string()));
+        editor.handle(MacroMessage::AddTarget, &stages);
+        editor.handle(MacroMessage::TargetTo(0, "1000".to_string()), &stages);
+        assert!(editor.take(&stages).is_none());
+        assert!(editor.is_visible());
+
+        editor.handle(MacroMessage::TargetTo(0, "-40".to_string()), &stages);
+        let (index, tight) = editor.take(&stages).unwrap();
+        assert_eq!(index, Some(0));
+        assert_eq!(tight.targets[0].to, -40.0);
+    }
+}
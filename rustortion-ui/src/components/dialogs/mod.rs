@@ -1,5 +1,8 @@
 pub mod common;
 pub mod hotkey;
+pub mod macro_editor;
+pub mod preset_diff;
+pub mod preset_manage;
 
 use super::widgets::common::{PADDING_LARGE, SPACING_NORMAL, SPACING_WIDE};
 
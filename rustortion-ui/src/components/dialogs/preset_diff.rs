@@ -0,0 +1,143 @@
+use iced::widget::{button, column, row, rule, scrollable, space, text};
+use iced::{Alignment, Element, Length};
+
+use super::common::{dialog_container, dialog_section_container, dialog_title_row, muted_text};
+use super::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use crate::components::widgets::common::{SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO};
+use crate::messages::{Message, PresetGuiMessage, PresetMessage};
+use crate::stages::stage_type_label;
+use crate::tr;
+use rustortion_core::preset::diff::PresetChange;
+
+/// "Compare to saved" view: lists what differs between the in-memory chain
+/// and the selected preset as stored on disk.
+pub struct PresetDiffDialog {
+    show_dialog: bool,
+    preset_name: String,
+    changes: Vec<PresetChange>,
+}
+
+impl Default for PresetDiffDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PresetDiffDialog {
+    pub const fn new() -> Self {
+        Self {
+            show_dialog: false,
+            preset_name: String::new(),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn show(&mut self, preset_name: String, changes: Vec<PresetChange>) {
+        self.show_dialog = true;
+        self.preset_name = preset_name;
+        self.changes = changes;
+    }
+
+    pub fn hide(&mut self) {
+        self.show_dialog = false;
+        self.changes.clear();
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(
+            tr!(preset_changes),
+            PresetMessage::Gui(PresetGuiMessage::CloseDiff).into(),
+        );
+
+        let changes: Element<'_, Message> = if self.changes.is_empty() {
+            muted_text(tr!(no_changes)).into()
+        } else {
+            let lines = self
+                .changes
+                .iter()
+                .fold(column![].spacing(SPACING_TIGHT), |col, change| {
+                    col.push(text(describe(change)).size(TEXT_SIZE_INFO))
+                });
+            scrollable(lines).height(Length::Fill).into()
+        };
+
+        let mut revert = button(tr!(revert)).style(iced::widget::button::danger);
+        if !self.changes.is_empty() {
+            revert = revert.on_press(PresetMessage::Revert.into());
+        }
+
+        let section = dialog_section_container(
+            column![
+                row![text(&self.preset_name), space::horizontal(), revert]
+                    .align_y(Alignment::Center),
+                changes,
+            ]
+            .spacing(SPACING_NORMAL)
+            .padding(SPACING_NORMAL)
+            .into(),
+        );
+
+        let dialog_content = column![title_row, rule::horizontal(1), section]
+            .spacing(DIALOG_CONTENT_SPACING)
+            .padding(DIALOG_CONTENT_PADDING)
+            .width(Length::Fill)
+            .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+}
+
+/// One human-readable line per change, e.g. "Preamp 2: gain 5.0 → 7.5".
+fn describe(change: &PresetChange) -> String {
+    match change {
+        PresetChange::StageParam {
+            stage,
+            index,
+            param,
+            saved,
+            current,
+        } => format!(
+            "{} {}: {param} {saved} \u{2192} {current}",
+            stage_type_label(stage),
+            index + 1
+        ),
+        PresetChange::StageAdded { stage, index } => format!(
+            "{} {}: {}",
+            stage_type_label(stage),
+            index + 1,
+            tr!(diff_added)
+        ),
+        PresetChange::StageRemoved { stage, index } => format!(
+            "{} {}: {}",
+            stage_type_label(stage),
+            index + 1,
+            tr!(diff_removed)
+        ),
+        PresetChange::StageMoved { stage, from, to } => format!(
+            "{}: {} {} \u{2192} {}",
+            stage_type_label(stage),
+            tr!(diff_moved),
+            from + 1,
+            to + 1
+        ),
+        PresetChange::Ir { saved, current } => format!(
+            "{}: {} \u{2192} {}",
+            tr!(ir),
+            saved.as_deref().unwrap_or(tr!(no_ir_loaded)),
+            current.as_deref().unwrap_or(tr!(no_ir_loaded))
+        ),
+        PresetChange::Setting {
+            name,
+            saved,
+            current,
+        } => format!("{name}: {saved} \u{2192} {current}"),
+    }
+}
@@ -0,0 +1,175 @@
+use std::time::SystemTime;
+
+use iced::widget::{button, column, row, rule, scrollable, space, text};
+use iced::{Alignment, Element, Length};
+
+use super::common::{dialog_container, dialog_section_container, dialog_title_row, muted_text};
+use super::{DIALOG_CONTENT_PADDING, DIALOG_CONTENT_SPACING};
+use crate::components::widgets::common::{
+    COLOR_MUTED, COLOR_WARNING, SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO,
+};
+use crate::messages::{Message, PresetGuiMessage, PresetMessage};
+use crate::tr;
+use rustortion_core::preset::TrashedPreset;
+
+/// "Manage presets" view: presets waiting in the inbox, to try, keep or
+/// discard, and the presets deleted or overwritten in the last 30 days, to
+/// restore or delete for good.
+pub struct PresetManageDialog {
+    show_dialog: bool,
+    inbox: Vec<String>,
+    trashed: Vec<TrashedPreset>,
+}
+
+impl Default for PresetManageDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PresetManageDialog {
+    pub const fn new() -> Self {
+        Self {
+            show_dialog: false,
+            inbox: Vec::new(),
+            trashed: Vec::new(),
+        }
+    }
+
+    pub fn show(&mut self, trashed: Vec<TrashedPreset>, inbox: Vec<String>) {
+        self.show_dialog = true;
+        self.trashed = trashed;
+        self.inbox = inbox;
+    }
+
+    pub fn hide(&mut self) {
+        self.show_dialog = false;
+        self.trashed.clear();
+        self.inbox.clear();
+    }
+
+    /// Replace the inbox list after a preset was kept or discarded.
+    pub fn set_inbox(&mut self, inbox: Vec<String>) {
+        self.inbox = inbox;
+    }
+
+    /// Replace the list after a restore or purge.
+    pub fn set_trashed(&mut self, trashed: Vec<TrashedPreset>) {
+        self.trashed = trashed;
+    }
+
+    pub const fn is_visible(&self) -> bool {
+        self.show_dialog
+    }
+
+    pub fn view(&self) -> Option<Element<'_, Message>> {
+        if !self.show_dialog {
+            return None;
+        }
+
+        let title_row = dialog_title_row(
+            tr!(manage_presets),
+            PresetMessage::Gui(PresetGuiMessage::CloseManage).into(),
+        );
+
+        let trashed: Element<'_, Message> = if self.trashed.is_empty() {
+            muted_text(tr!(trash_empty)).into()
+        } else {
+            let lines =
+                self.trashed
+                    .iter()
+                    .fold(column![].spacing(SPACING_TIGHT), |col, trashed| {
+                        col.push(
+                            row![
+                                text(&trashed.name),
+                                text(age(trashed.trashed_at)).size(TEXT_SIZE_INFO).style(
+                                    |_: &iced::Theme| iced::widget::text::Style {
+                                        color: Some(COLOR_MUTED),
+                                    }
+                                ),
+                                space::horizontal(),
+                                button(tr!(restore))
+                                    .on_press(PresetMessage::Restore(trashed.path.clone()).into()),
+                                button(tr!(delete_forever))
+                                    .on_press(PresetMessage::Purge(trashed.path.clone()).into())
+                                    .style(iced::widget::button::danger),
+                            ]
+                            .spacing(SPACING_NORMAL)
+                            .align_y(Alignment::Center),
+                        )
+                    });
+            scrollable(lines).height(Length::Fill).into()
+        };
+
+        let section = dialog_section_container(
+            column![text(tr!(recently_deleted)), trashed]
+                .spacing(SPACING_NORMAL)
+                .padding(SPACING_NORMAL)
+                .into(),
+        );
+
+        let dialog_content = column![
+            title_row,
+            rule::horizontal(1),
+            self.inbox_section(),
+            section
+        ]
+        .spacing(DIALOG_CONTENT_SPACING)
+        .padding(DIALOG_CONTENT_PADDING)
+        .width(Length::Fill)
+        .height(Length::Fill);
+
+        Some(dialog_container(dialog_content.into()))
+    }
+
+    /// Presets received from others, each flagged until it's sorted.
+    fn inbox_section(&self) -> Element<'_, Message> {
+        let inbox: Element<'_, Message> = if self.inbox.is_empty() {
+            muted_text(tr!(inbox_empty)).into()
+        } else {
+            self.inbox
+                .iter()
+                .fold(column![].spacing(SPACING_TIGHT), |col, name| {
+                    col.push(
+                        row![
+                            text("●").style(|_: &iced::Theme| iced::widget::text::Style {
+                                color: Some(COLOR_WARNING),
+                            }),
+                            text(name),
+                            space::horizontal(),
+                            button(tr!(try_preset))
+                                .on_press(PresetMessage::OpenInbox(name.clone()).into())
+                                .style(iced::widget::button::secondary),
+                            button(tr!(keep))
+                                .on_press(PresetMessage::KeepInbox(name.clone()).into()),
+                            button(tr!(discard))
+                                .on_press(PresetMessage::DiscardInbox(name.clone()).into())
+                                .style(iced::widget::button::danger),
+                        ]
+                        .spacing(SPACING_NORMAL)
+                        .align_y(Alignment::Center),
+                    )
+                })
+                .into()
+        };
+
+        dialog_section_container(
+            column![text(tr!(inbox)), inbox]
+                .spacing(SPACING_NORMAL)
+                .padding(SPACING_NORMAL)
+                .into(),
+        )
+    }
+}
+
+/// How long ago a preset was trashed, e.g. "3 h ago".
+fn age(trashed_at: SystemTime) -> String {
+    let minutes = trashed_at.elapsed().map_or(0, |d| d.as_secs() / 60);
+    if minutes < 60 {
+        format!("{minutes} {}", tr!(minutes_ago))
+    } else if minutes < 24 * 60 {
+        format!("{} {}", minutes / 60, tr!(hours_ago))
+    } else {
+        format!("{} {}", minutes / (24 * 60), tr!(days_ago))
+    }
+}
@@ -0,0 +1,111 @@
+use iced::widget::{column, row, slider, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::components::widgets::common::{
+    SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_SMALL, section_container, section_title,
+};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::audio::double_tracker::{
+    DoubleTrackerConfig, MAX_DELAY_MS, MAX_DETUNE_CENTS, MIN_DELAY_MS,
+};
+
+/// Compact mix/width/delay/detune strip for the post-IR double tracker,
+/// shown under the ambience.
+pub struct DoubleTrackerControl {
+    config: DoubleTrackerConfig,
+}
+
+impl Default for DoubleTrackerControl {
+    fn default() -> Self {
+        Self::new(DoubleTrackerConfig::default())
+    }
+}
+
+impl DoubleTrackerControl {
+    pub const fn new(config: DoubleTrackerConfig) -> Self {
+        Self { config }
+    }
+
+    pub const fn set_config(&mut self, config: DoubleTrackerConfig) {
+        self.config = config;
+    }
+
+    pub const fn get_config(&self) -> DoubleTrackerConfig {
+        self.config
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let config = self.config;
+        let knobs = row![
+            knob(
+                tr!(double_tracker_mix),
+                format!("{:.0}%", config.mix * 100.0),
+                0.0..=1.0,
+                0.01,
+                config.mix,
+                move |mix| Message::DoubleTrackerChanged(DoubleTrackerConfig { mix, ..config }),
+            ),
+            knob(
+                tr!(double_tracker_width),
+                format!("{:.0}%", config.width * 100.0),
+                0.0..=1.0,
+                0.01,
+                config.width,
+                move |width| Message::DoubleTrackerChanged(DoubleTrackerConfig { width, ..config }),
+            ),
+            knob(
+                tr!(double_tracker_delay),
+                format!("{:.1} ms", config.delay_ms),
+                MIN_DELAY_MS..=MAX_DELAY_MS,
+                0.1,
+                config.delay_ms,
+                move |delay_ms| {
+                    Message::DoubleTrackerChanged(DoubleTrackerConfig { delay_ms, ..config })
+                },
+            ),
+            knob(
+                tr!(double_tracker_detune),
+                format!("{:.1} ct", config.detune_cents),
+                0.0..=MAX_DETUNE_CENTS,
+                0.1,
+                config.detune_cents,
+                move |detune_cents| {
+                    Message::DoubleTrackerChanged(DoubleTrackerConfig {
+                        detune_cents,
+                        ..config
+                    })
+                },
+            ),
+        ]
+        .spacing(SPACING_NORMAL);
+
+        section_container(
+            column![section_title(tr!(double_tracker)), knobs]
+                .spacing(SPACING_NORMAL)
+                .into(),
+        )
+    }
+}
+
+fn knob<'a>(
+    label: &'a str,
+    value_text: String,
+    range: std::ops::RangeInclusive<f32>,
+    step: f32,
+    value: f32,
+    on_change: impl Fn(f32) -> Message + 'a,
+) -> Element<'a, Message> {
+    column![
+        row![
+            text(label).size(TEXT_SIZE_SMALL),
+            space::horizontal(),
+            text(value_text).size(TEXT_SIZE_SMALL),
+        ]
+        .align_y(Alignment::Center),
+        slider(range, value, on_change).step(step),
+    ]
+    .spacing(SPACING_TIGHT)
+    .width(Length::Fill)
+    .into()
+}
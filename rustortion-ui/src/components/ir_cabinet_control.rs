@@ -1,18 +1,74 @@
-use iced::widget::{checkbox, column, pick_list, row, slider, text};
-use iced::{Alignment, Element, Length};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use iced::widget::{Row, button, checkbox, column, container, row, slider, space, text};
+use iced::{Alignment, Color, Element, Length};
 
 use crate::components::widgets::common::{
-    COLOR_SUBTLE, COLOR_SUCCESS, COLOR_WARNING, SPACING_NORMAL, TEXT_SIZE_INFO, section_container,
+    BORDER_RADIUS_DIALOG, COLOR_SUBTLE, COLOR_SUCCESS, COLOR_WARNING, PADDING_LARGE,
+    SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO, TEXT_SIZE_SMALL, section_container,
     section_title,
 };
-use crate::messages::Message;
+use crate::components::widgets::ir_preview::ir_preview;
+use crate::components::widgets::search_select::{SearchSelect, SearchSelectMessage};
+use crate::messages::{IrCompareMessage, Message};
 use crate::tr;
+use rustortion_core::audio::engine::{EngineState, ir_name_id};
+use rustortion_core::ir::align::{IrAlignment, MAX_IR_OFFSET};
+use rustortion_core::ir::compare::{MAX_COMPARE_SLOTS, level_match_trims_db};
+use rustortion_core::ir::loader::{IR_TAG_SEPARATOR, IrMetadata};
+use rustortion_core::ir::thumbnail::{IrThumbnail, ThumbnailLookup};
+
+/// How long the IR name stays on screen after stepping or shuffling.
+pub const IR_FLASH_DURATION: Duration = Duration::from_secs(1);
+const IR_FLASH_TEXT_SIZE: f32 = 40.0;
+const COMPARE_NEXT_TEXT_SIZE: f32 = 20.0;
+/// Smaller differences between the shown and applied IR gain are rounding.
+const GAIN_SYNC_TOLERANCE: f32 = 1e-4;
+
+/// An IR picked into a comparison slot, and its gain once it's measured.
+#[derive(Debug, Clone)]
+struct CompareSlot {
+    name: String,
+    energy: Option<f32>,
+}
+
+/// IRs picked for a level-matched A/B/C comparison.
+#[derive(Debug, Clone)]
+struct IrCompare {
+    slots: Vec<CompareSlot>,
+    /// The IR that played before comparing, to go back to on exit.
+    original: Option<String>,
+    /// Trim last handed out by [`IrCabinetControl::take_trim_change`].
+    applied_trim_db: f32,
+}
 
 pub struct IrCabinetControl {
     available_irs: Vec<String>,
+    /// IRs the backend will refuse, marked in the picker.
+    flagged_irs: Vec<String>,
+    /// Tags read from each IR's file name, by IR name.
+    ir_metadata: HashMap<String, IrMetadata>,
+    /// Only IRs with this mic or speaker tag are offered.
+    tag_filter: Option<String>,
     selected_ir: Option<String>,
+    /// What was selected before `selected_ir`, to go back to if it fails to load.
+    previous_ir: Option<String>,
     bypassed: bool,
     gain: f32,
+    /// Sample offset and polarity the IR is loaded with.
+    alignment: IrAlignment,
+    /// IR name shown in the audition overlay, and when it was shown.
+    flash: Option<(String, Instant)>,
+    ir_search: SearchSelect,
+    compare: Option<IrCompare>,
+    /// The IR last looked up for the preview, and what came back.
+    preview: Option<(String, ThumbnailLookup)>,
+    /// The preview on screen; kept while the next one is still being made,
+    /// so it doesn't flicker while browsing.
+    shown_preview: Option<Arc<IrThumbnail>>,
 }
 
 impl Default for IrCabinetControl {
@@ -22,12 +78,22 @@ impl Default for IrCabinetControl {
 }
 
 impl IrCabinetControl {
-    pub const fn new(bypassed: bool, gain: f32) -> Self {
+    pub fn new(bypassed: bool, gain: f32) -> Self {
         Self {
             available_irs: Vec::new(),
+            flagged_irs: Vec::new(),
+            ir_metadata: HashMap::new(),
+            tag_filter: None,
             selected_ir: None,
+            previous_ir: None,
             bypassed,
             gain,
+            alignment: IrAlignment::default(),
+            flash: None,
+            ir_search: SearchSelect::new(),
+            compare: None,
+            preview: None,
+            shown_preview: None,
         }
     }
 
@@ -39,18 +105,283 @@ impl IrCabinetControl {
         }
     }
 
+    pub fn set_flagged_irs(&mut self, irs: Vec<String>) {
+        self.flagged_irs = irs;
+    }
+
+    pub fn set_ir_metadata(&mut self, metadata: Vec<IrMetadata>) {
+        self.ir_metadata = metadata
+            .into_iter()
+            .map(|meta| (meta.raw_name.clone(), meta))
+            .collect();
+        if self
+            .tag_filter
+            .as_ref()
+            .is_some_and(|tag| !self.all_tags().contains(tag))
+        {
+            self.tag_filter = None;
+        }
+    }
+
+    pub fn available_irs(&self) -> &[String] {
+        &self.available_irs
+    }
+
+    /// Offer only IRs tagged `tag`, or every IR for `None`. The picker,
+    /// stepping and shuffling all follow it.
+    pub fn set_tag_filter(&mut self, tag: Option<String>) {
+        self.tag_filter = tag;
+    }
+
+    pub fn tag_filter(&self) -> Option<&str> {
+        self.tag_filter.as_deref()
+    }
+
+    /// The IRs the tag filter lets through, in scan order.
+    pub fn visible_irs(&self) -> Vec<String> {
+        let Some(tag) = &self.tag_filter else {
+            return self.available_irs.clone();
+        };
+        self.available_irs
+            .iter()
+            .filter(|name| {
+                self.ir_metadata
+                    .get(*name)
+                    .is_some_and(|meta| meta.tags().any(|t| t == tag))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Every mic tag, then every speaker tag, each sorted and listed once.
+    fn all_tags(&self) -> Vec<String> {
+        let mut mics: Vec<&str> = Vec::new();
+        let mut speakers: Vec<&str> = Vec::new();
+        for meta in self.ir_metadata.values() {
+            let split = |field: &Option<String>| -> Vec<&str> {
+                field
+                    .as_deref()
+                    .map(|f| f.split(IR_TAG_SEPARATOR).collect())
+                    .unwrap_or_default()
+            };
+            mics.extend(split(&meta.mic));
+            speakers.extend(split(&meta.speaker));
+        }
+        for tags in [&mut mics, &mut speakers] {
+            tags.sort_unstable();
+            tags.dedup();
+        }
+        mics.into_iter()
+            .chain(speakers)
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Start comparing, with the playing IR in the first slot.
+    pub fn start_compare(&mut self) {
+        let slots = self
+            .selected_ir
+            .iter()
+            .map(|name| CompareSlot {
+                name: name.clone(),
+                energy: None,
+            })
+            .collect();
+        self.compare = Some(IrCompare {
+            slots,
+            original: self.selected_ir.clone(),
+            applied_trim_db: 0.0,
+        });
+    }
+
+    pub const fn is_comparing(&self) -> bool {
+        self.compare.is_some()
+    }
+
+    /// Stop comparing. Returns the IR that played before it started.
+    pub fn end_compare(&mut self) -> Option<String> {
+        self.compare.take()?.original
+    }
+
+    /// Put `name` in the next free slot. False if the slots are full or it's
+    /// already in one.
+    pub fn add_compare_slot(&mut self, name: String) -> bool {
+        let Some(compare) = &mut self.compare else {
+            return false;
+        };
+        if compare.slots.len() >= MAX_COMPARE_SLOTS || compare.slots.iter().any(|s| s.name == name)
+        {
+            return false;
+        }
+        compare.slots.push(CompareSlot { name, energy: None });
+        true
+    }
+
+    pub fn remove_compare_slot(&mut self, idx: usize) {
+        if let Some(compare) = &mut self.compare
+            && idx < compare.slots.len()
+        {
+            compare.slots.remove(idx);
+        }
+    }
+
+    pub fn compare_slot(&self, idx: usize) -> Option<&str> {
+        let slot = self.compare.as_ref()?.slots.get(idx)?;
+        Some(&slot.name)
+    }
+
+    /// Slots whose IR hasn't been measured yet.
+    pub fn unmeasured_compare_slots(&self) -> Vec<String> {
+        self.compare
+            .iter()
+            .flat_map(|compare| &compare.slots)
+            .filter(|slot| slot.energy.is_none())
+            .map(|slot| slot.name.clone())
+            .collect()
+    }
+
+    pub fn set_compare_energy(&mut self, name: &str, energy: f32) {
+        if let Some(slot) = self
+            .compare
+            .iter_mut()
+            .flat_map(|compare| &mut compare.slots)
+            .find(|slot| slot.name == name)
+        {
+            slot.energy = Some(energy);
+        }
+    }
+
+    /// Level-matching trim of each slot, in dB.
+    pub fn compare_trims_db(&self) -> Vec<f32> {
+        let energies: Vec<Option<f32>> = self
+            .compare
+            .iter()
+            .flat_map(|compare| &compare.slots)
+            .map(|slot| slot.energy)
+            .collect();
+        level_match_trims_db(&energies)
+    }
+
+    /// Slot of the IR that's playing, if it's one of them.
+    fn playing_slot(&self) -> Option<usize> {
+        let selected = self.selected_ir.as_ref()?;
+        self.compare
+            .as_ref()?
+            .slots
+            .iter()
+            .position(|slot| slot.name == *selected)
+    }
+
+    /// The slot after the one playing, wrapping; the first if none is.
+    pub fn next_compare_slot(&self) -> Option<String> {
+        let slots = &self.compare.as_ref()?.slots;
+        let next = self
+            .playing_slot()
+            .map_or(0, |playing| (playing + 1) % slots.len());
+        slots.get(next).map(|slot| slot.name.clone())
+    }
+
+    /// The IR of the slot that's playing, to keep.
+    pub fn playing_compare_slot(&self) -> Option<String> {
+        self.compare_slot(self.playing_slot()?).map(str::to_owned)
+    }
+
+    /// The trim the playing IR needs, if it isn't the one last handed out.
+    /// An IR outside the slots gets none.
+    pub fn take_trim_change(&mut self) -> Option<f32> {
+        let trim = self
+            .playing_slot()
+            .and_then(|slot| self.compare_trims_db().get(slot).copied())
+            .unwrap_or(0.0);
+        let compare = self.compare.as_mut()?;
+        if (trim - compare.applied_trim_db).abs() < f32::EPSILON {
+            return None;
+        }
+        compare.applied_trim_db = trim;
+        Some(trim)
+    }
+
+    /// The trim last handed out by [`Self::take_trim_change`], which the
+    /// engine is playing; 0 dB outside a comparison.
+    pub fn applied_trim_db(&self) -> f32 {
+        self.compare
+            .as_ref()
+            .map_or(0.0, |compare| compare.applied_trim_db)
+    }
+
+    /// Drop the IR list open, as if the user had clicked it.
+    pub fn open_picker(&mut self) {
+        if !self.ir_search.is_open() {
+            self.ir_search
+                .update(SearchSelectMessage::Toggle, &self.visible_irs());
+        }
+    }
+
     pub fn set_selected_ir(&mut self, ir: Option<String>) {
-        self.selected_ir = ir;
+        if ir != self.selected_ir {
+            self.previous_ir = std::mem::replace(&mut self.selected_ir, ir);
+        }
+    }
+
+    /// `name` failed to load after it was selected: go back to the IR that
+    /// is still playing. Does nothing if the selection has moved on.
+    pub fn revert_failed(&mut self, name: &str) {
+        if self.selected_ir.as_deref() == Some(name) {
+            self.selected_ir = self.previous_ir.take();
+        }
     }
 
     pub const fn set_bypassed(&mut self, bypassed: bool) {
         self.bypassed = bypassed;
     }
 
+    /// Show what the engine has where it differs from what's shown, e.g.
+    /// after a message to it was dropped. The selected IR is left alone
+    /// while `ir_loading`, as the engine plays the previous one until then.
+    pub fn sync_with_engine(&mut self, state: &EngineState, ir_loading: bool) {
+        if self.bypassed != state.ir_bypassed {
+            log::warn!(
+                "IR bypass shown as {} but the engine has {}",
+                self.bypassed,
+                state.ir_bypassed
+            );
+            self.bypassed = state.ir_bypassed;
+        }
+        if (self.gain - state.ir_gain).abs() > GAIN_SYNC_TOLERANCE {
+            log::warn!(
+                "IR gain shown as {} but the engine has {}",
+                self.gain,
+                state.ir_gain
+            );
+            self.gain = state.ir_gain;
+        }
+        let shown = self.selected_ir.as_deref().map_or(0, ir_name_id);
+        if !ir_loading && shown != state.ir_id {
+            let playing = self
+                .available_irs
+                .iter()
+                .find(|name| ir_name_id(name) == state.ir_id)
+                .cloned();
+            log::warn!(
+                "IR shown as {:?} but the engine plays {playing:?}",
+                self.selected_ir
+            );
+            self.set_selected_ir(playing);
+        }
+    }
+
     pub const fn set_gain(&mut self, gain: f32) {
         self.gain = gain;
     }
 
+    pub const fn set_alignment(&mut self, alignment: IrAlignment) {
+        self.alignment = alignment;
+    }
+
+    pub const fn alignment(&self) -> IrAlignment {
+        self.alignment
+    }
+
     pub fn get_selected_ir(&self) -> Option<String> {
         self.selected_ir.clone()
     }
@@ -63,18 +394,301 @@ impl IrCabinetControl {
         self.gain
     }
 
+    /// The IR `offset` places from the selected one, wrapping at both ends and
+    /// skipping flagged IRs. With nothing selected, steps in from the matching
+    /// end of the list.
+    pub fn step_target(&self, offset: i32) -> Option<String> {
+        let irs = self.visible_irs();
+        let len = irs.len();
+        if len == 0 {
+            return None;
+        }
+
+        let mut next = match self.selected_index(&irs) {
+            Some(current) => {
+                let steps = offset.unsigned_abs() as usize % len;
+                if offset >= 0 {
+                    (current + steps) % len
+                } else {
+                    (current + len - steps) % len
+                }
+            }
+            None if offset < 0 => len - 1,
+            None => 0,
+        };
+
+        // Walk past IRs that would be refused, so holding an arrow key
+        // doesn't stall on one.
+        for _ in 0..len {
+            if !self.flagged_irs.contains(&irs[next]) {
+                break;
+            }
+            next = if offset >= 0 {
+                (next + 1) % len
+            } else {
+                (next + len - 1) % len
+            };
+        }
+
+        Some(irs[next].clone())
+    }
+
+    /// A random IR other than the selected one, for blind comparison.
+    pub fn shuffle_target(&self) -> Option<String> {
+        let irs = self.visible_irs();
+        let len = irs.len();
+        let current = self.selected_index(&irs);
+        let candidates = if current.is_some() { len - 1 } else { len };
+        if candidates == 0 {
+            return None;
+        }
+
+        let seed = RandomState::new().hash_one(Instant::now());
+        let mut pick = usize::try_from(seed % candidates as u64).unwrap_or(0);
+        if current.is_some_and(|current| pick >= current) {
+            pick += 1;
+        }
+
+        Some(irs[pick].clone())
+    }
+
+    fn selected_index(&self, irs: &[String]) -> Option<usize> {
+        let selected = self.selected_ir.as_ref()?;
+        irs.iter().position(|ir| ir == selected)
+    }
+
+    /// Apply a message from the IR search box. Returns the IR the user picked.
+    pub fn search(&mut self, message: SearchSelectMessage) -> Option<String> {
+        let irs = self.visible_irs();
+        self.ir_search.update(message, &irs)
+    }
+
+    pub const fn is_search_open(&self) -> bool {
+        self.ir_search.is_open()
+    }
+
+    /// The IR to preview: the one highlighted in the open picker, or else
+    /// the selected one.
+    fn preview_target(&self) -> Option<String> {
+        if self.ir_search.is_open() {
+            let irs = self.visible_irs();
+            return self
+                .ir_search
+                .matches(&irs)
+                .get(self.ir_search.highlighted())
+                .map(|name| (*name).clone());
+        }
+        self.selected_ir.clone()
+    }
+
+    /// The IR whose thumbnail should be looked up, if the preview isn't
+    /// showing it yet.
+    pub fn preview_wanted(&self) -> Option<String> {
+        let target = self.preview_target()?;
+        match &self.preview {
+            Some((name, ThumbnailLookup::Ready(_) | ThumbnailLookup::Unavailable))
+                if *name == target =>
+            {
+                None
+            }
+            _ => Some(target),
+        }
+    }
+
+    pub fn set_preview(&mut self, name: String, lookup: ThumbnailLookup) {
+        match &lookup {
+            ThumbnailLookup::Ready(thumbnail) => self.shown_preview = Some(Arc::clone(thumbnail)),
+            ThumbnailLookup::Unavailable => self.shown_preview = None,
+            ThumbnailLookup::Pending => {}
+        }
+        self.preview = Some((name, lookup));
+    }
+
+    /// Show `name` in the audition overlay for [`IR_FLASH_DURATION`].
+    pub fn flash(&mut self, name: String) {
+        self.flash = Some((name, Instant::now()));
+    }
+
+    /// Drop the overlay once it has been up for [`IR_FLASH_DURATION`].
+    pub fn expire_flash(&mut self) {
+        if self
+            .flash
+            .as_ref()
+            .is_some_and(|(_, shown)| shown.elapsed() >= IR_FLASH_DURATION)
+        {
+            self.flash = None;
+        }
+    }
+
+    pub const fn is_flashing(&self) -> bool {
+        self.flash.is_some()
+    }
+
+    /// Large, centred IR name shown while auditioning, readable from a distance.
+    pub fn view_flash(&self) -> Option<Element<'_, Message>> {
+        let (name, _) = self.flash.as_ref()?;
+
+        let label = container(text(name).size(IR_FLASH_TEXT_SIZE))
+            .padding(PADDING_LARGE)
+            .style(|theme: &iced::Theme| {
+                container::Style::default()
+                    .background(Color {
+                        a: 0.9,
+                        ..theme.palette().background
+                    })
+                    .border(
+                        iced::Border::default()
+                            .rounded(BORDER_RADIUS_DIALOG)
+                            .width(2),
+                    )
+            });
+
+        Some(container(label).center(Length::Fill).into())
+    }
+
+    /// The comparison slots, each with its level-matching trim, and the
+    /// buttons to cycle through, keep or leave them.
+    fn view_compare(&self, compare: &IrCompare) -> Element<'static, Message> {
+        let trims = self.compare_trims_db();
+        let playing = self.playing_slot();
+
+        let mut slots = Row::new().spacing(SPACING_NORMAL);
+        for (idx, (slot, trim)) in compare.slots.iter().zip(trims).enumerate() {
+            let letter = char::from(b'A' + idx as u8);
+            let level = if slot.energy.is_some() {
+                format!("{trim:+.1} {}", tr!(db))
+            } else {
+                tr!(ir_compare_measuring).to_string()
+            };
+            let label = column![
+                text(format!("{letter}: {}", slot.name)),
+                text(level).size(TEXT_SIZE_SMALL),
+            ];
+            slots = slots.push(
+                row![
+                    button(label)
+                        .on_press(IrCompareMessage::Play(idx).into())
+                        .style(if playing == Some(idx) {
+                            iced::widget::button::primary
+                        } else {
+                            iced::widget::button::secondary
+                        }),
+                    button(text("×"))
+                        .on_press(IrCompareMessage::Remove(idx).into())
+                        .style(iced::widget::button::secondary),
+                ]
+                .spacing(SPACING_TIGHT)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        let can_add = compare.slots.len() < MAX_COMPARE_SLOTS
+            && self
+                .selected_ir
+                .as_ref()
+                .is_some_and(|name| compare.slots.iter().all(|slot| slot.name != *name));
+        let controls = row![
+            button(text(tr!(ir_compare_add)))
+                .on_press_maybe(can_add.then_some(IrCompareMessage::AddSelected.into()))
+                .style(iced::widget::button::secondary),
+            button(text(tr!(ir_compare_next)).size(COMPARE_NEXT_TEXT_SIZE))
+                .padding(PADDING_LARGE)
+                .on_press_maybe(
+                    (compare.slots.len() > 1).then_some(IrCompareMessage::Cycle.into())
+                ),
+            button(text(tr!(ir_compare_keep)))
+                .on_press_maybe(playing.map(|_| IrCompareMessage::Keep.into()))
+                .style(iced::widget::button::success),
+            button(text(tr!(ir_compare_exit)))
+                .on_press(IrCompareMessage::Exit.into())
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        let hint =
+            text(tr!(ir_compare_hint))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_SUBTLE),
+                });
+
+        column![slots.wrap().vertical_spacing(SPACING_TIGHT), controls, hint]
+            .spacing(SPACING_NORMAL)
+            .into()
+    }
+
+    /// Filter chips for every mic and speaker tag, or `None` when no IR name
+    /// carried any.
+    fn view_tag_chips(&self) -> Option<Element<'static, Message>> {
+        let tags = self.all_tags();
+        if tags.is_empty() {
+            return None;
+        }
+
+        let chip = |label: String, filter: Option<String>, active: bool| {
+            button(text(label).size(TEXT_SIZE_SMALL))
+                .on_press(Message::IrTagFilter(filter))
+                .style(if active {
+                    iced::widget::button::primary
+                } else {
+                    iced::widget::button::secondary
+                })
+        };
+
+        let mut chips = Row::new().spacing(SPACING_TIGHT).push(chip(
+            tr!(ir_tags_all).to_string(),
+            None,
+            self.tag_filter.is_none(),
+        ));
+        for tag in tags {
+            let active = self.tag_filter.as_ref() == Some(&tag);
+            chips = chips.push(chip(tag.clone(), Some(tag), active));
+        }
+
+        Some(
+            row![
+                text(tr!(ir_tags)).width(Length::Fixed(80.0)),
+                chips.wrap().vertical_spacing(SPACING_TIGHT),
+            ]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center)
+            .into(),
+        )
+    }
+
     pub fn view(&self) -> Element<'static, Message> {
+        let visible_irs = self.visible_irs();
+        let notes: HashMap<String, String> = visible_irs
+            .iter()
+            .filter_map(|name| {
+                let note = ir_note(self.ir_metadata.get(name)?)?;
+                Some((name.clone(), note))
+            })
+            .collect();
+
         let ir_selector = row![
             text(tr!(ir)).width(Length::Fixed(80.0)),
-            pick_list(
-                self.available_irs.clone(),
-                self.selected_ir.clone(),
-                Message::IrSelected
-            )
-            .width(Length::Fill),
+            self.ir_search
+                .view_annotated(
+                    &visible_irs,
+                    self.selected_ir.as_deref(),
+                    &self.flagged_irs,
+                    &notes
+                )
+                .map(Message::IrSearch),
+            button(tr!(shuffle))
+                .on_press_maybe((visible_irs.len() > 1).then_some(Message::IrShuffle))
+                .style(iced::widget::button::secondary),
+            button(tr!(ir_compare))
+                .on_press_maybe(
+                    (self.compare.is_none() && visible_irs.len() > 1)
+                        .then_some(IrCompareMessage::Start.into())
+                )
+                .style(iced::widget::button::secondary),
         ]
         .spacing(SPACING_NORMAL)
-        .align_y(Alignment::Center);
+        .align_y(Alignment::Start);
 
         let bypass_control = checkbox(self.bypassed)
             .label(tr!(bypassed))
@@ -91,6 +705,34 @@ impl IrCabinetControl {
         .spacing(SPACING_NORMAL)
         .align_y(Alignment::Center);
 
+        let alignment = self.alignment;
+        let max_offset = MAX_IR_OFFSET as f32;
+        let align_control = row![
+            text(format!("{}:", tr!(ir_offset))).width(Length::Fixed(80.0)),
+            slider(
+                -max_offset..=max_offset,
+                alignment.offset as f32,
+                move |offset| Message::IrAlignmentChanged(IrAlignment {
+                    offset: offset as i32,
+                    ..alignment
+                }),
+            )
+            .width(Length::FillPortion(5))
+            .step(1.0),
+            text(format!("{} {}", alignment.offset, tr!(samples))).width(Length::FillPortion(2)),
+            checkbox(alignment.inverted)
+                .label(tr!(ir_invert_polarity))
+                .on_toggle(move |inverted| Message::IrAlignmentChanged(IrAlignment {
+                    inverted,
+                    ..alignment
+                })),
+            button(text(tr!(ir_auto_align)))
+                .on_press_maybe(self.selected_ir.as_ref().map(|_| Message::IrAutoAlign))
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
         let status = if self.bypassed {
             let bypassed_status = format!("({})", tr!(bypassed));
             text(bypassed_status)
@@ -112,15 +754,211 @@ impl IrCabinetControl {
                 })
         };
 
-        let content = column![
-            section_title(tr!(cabinet_ir)),
-            ir_selector,
-            gain_control,
-            bypass_control,
-            status,
-        ]
-        .spacing(SPACING_NORMAL);
+        let hint =
+            text(tr!(ir_step_hint))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_SUBTLE),
+                });
+
+        let mut content = column![section_title(tr!(cabinet_ir))].spacing(SPACING_NORMAL);
+        if let Some(chips) = self.view_tag_chips() {
+            content = content.push(chips);
+        }
+        content = content.push(ir_selector);
+        if let Some(thumbnail) = &self.shown_preview {
+            content = content.push(
+                row![space().width(Length::Fixed(80.0)), ir_preview(thumbnail)]
+                    .spacing(SPACING_NORMAL),
+            );
+        }
+        if let Some(compare) = &self.compare {
+            content = content.push(self.view_compare(compare));
+        }
+        let content = content
+            .push(gain_control)
+            .push(align_control)
+            .push(bypass_control)
+            .push(status)
+            .push(hint);
 
         section_container(content.into())
     }
 }
+
+/// Tags, then length and channels, for an IR's line in the picker.
+fn ir_note(meta: &IrMetadata) -> Option<String> {
+    let channels = meta.channels.map(|channels| match channels {
+        1 => tr!(ir_mono).to_owned(),
+        2 => tr!(ir_stereo).to_owned(),
+        n => format!("{n} ch"),
+    });
+    let parts: Vec<String> = [
+        meta.summary(),
+        meta.length
+            .map(|length| format!("{} ms", length.as_millis())),
+        channels,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_load_reverts_to_previous_selection() {
+        let mut control = IrCabinetControl::default();
+        control.set_available_irs(vec!["a.wav".into(), "song.wav".into()]);
+        assert_eq!(control.get_selected_ir().as_deref(), Some("a.wav"));
+
+        control.set_selected_ir(Some("song.wav".into()));
+        control.revert_failed("song.wav");
+        assert_eq!(control.get_selected_ir().as_deref(), Some("a.wav"));
+
+        // A failure for an IR that's no longer selected changes nothing.
+        control.set_selected_ir(Some("song.wav".into()));
+        control.set_selected_ir(Some("a.wav".into()));
+        control.revert_failed("song.wav");
+        assert_eq!(control.get_selected_ir().as_deref(), Some("a.wav"));
+    }
+
+    #[test]
+    fn stepping_skips_flagged_irs() {
+        let mut control = IrCabinetControl::default();
+        control.set_available_irs(vec!["a.wav".into(), "song.wav".into(), "c.wav".into()]);
+        control.set_flagged_irs(vec!["song.wav".into()]);
+
+        assert_eq!(control.step_target(1).as_deref(), Some("c.wav"));
+        control.set_selected_ir(Some("c.wav".into()));
+        assert_eq!(control.step_target(-1).as_deref(), Some("a.wav"));
+    }
+
+    #[test]
+    fn comparing_cycles_the_slots_with_their_trims() {
+        let mut control = IrCabinetControl::default();
+        control.set_available_irs(vec!["a.wav".into(), "b.wav".into(), "c.wav".into()]);
+        control.start_compare();
+        assert_eq!(control.compare_slot(0), Some("a.wav"));
+
+        assert!(control.add_compare_slot("b.wav".into()));
+        assert!(
+            !control.add_compare_slot("b.wav".into()),
+            "already in a slot"
+        );
+        assert!(control.add_compare_slot("c.wav".into()));
+        assert!(!control.add_compare_slot("d.wav".into()), "slots are full");
+
+        assert_eq!(
+            control.unmeasured_compare_slots(),
+            ["a.wav", "b.wav", "c.wav"]
+        );
+        control.set_compare_energy("a.wav", 1.0);
+        control.set_compare_energy("b.wav", 0.5);
+        control.set_compare_energy("c.wav", 2.0);
+        assert!(control.unmeasured_compare_slots().is_empty());
+
+        // a.wav plays first, trimmed down to b.wav's level.
+        let trim = control.take_trim_change().unwrap();
+        assert!((trim - -6.02).abs() < 0.01, "{trim}");
+        assert_eq!(control.take_trim_change(), None, "already applied");
+        assert_eq!(control.applied_trim_db(), trim, "kept for a restart");
+
+        assert_eq!(control.next_compare_slot().as_deref(), Some("b.wav"));
+        control.set_selected_ir(Some("b.wav".into()));
+        assert_eq!(control.take_trim_change(), Some(0.0));
+        control.set_selected_ir(Some("c.wav".into()));
+        assert_eq!(control.next_compare_slot().as_deref(), Some("a.wav"));
+
+        control.remove_compare_slot(2);
+        assert_eq!(control.playing_compare_slot(), None);
+        assert_eq!(control.next_compare_slot().as_deref(), Some("a.wav"));
+        assert_eq!(control.end_compare().as_deref(), Some("a.wav"));
+        assert!(!control.is_comparing());
+    }
+
+    #[test]
+    fn preview_follows_the_highlight_and_keeps_the_last_thumbnail() {
+        let mut control = IrCabinetControl::default();
+        control.set_available_irs(vec!["a.wav".into(), "b.wav".into()]);
+        assert_eq!(control.preview_wanted().as_deref(), Some("a.wav"));
+
+        control.set_preview("a.wav".into(), ThumbnailLookup::Pending);
+        assert_eq!(control.preview_wanted().as_deref(), Some("a.wav"));
+        let thumbnail = Arc::new(IrThumbnail::of(&[1.0], 48000));
+        control.set_preview("a.wav".into(), ThumbnailLookup::Ready(thumbnail.clone()));
+        assert_eq!(control.preview_wanted(), None);
+
+        // Browsing the open picker previews the highlighted IR, keeping the
+        // last thumbnail up until the new one is ready.
+        control.open_picker();
+        control.search(SearchSelectMessage::Move(1));
+        assert_eq!(control.preview_wanted().as_deref(), Some("b.wav"));
+        control.set_preview("b.wav".into(), ThumbnailLookup::Pending);
+        assert!(
+            control
+                .shown_preview
+                .as_ref()
+                .is_some_and(|shown| Arc::ptr_eq(shown, &thumbnail))
+        );
+        control.set_preview("b.wav".into(), ThumbnailLookup::Unavailable);
+        assert_eq!(control.preview_wanted(), None);
+        assert!(control.shown_preview.is_none());
+
+        // Closing it goes back to the selected IR.
+        control.search(SearchSelectMessage::Close);
+        assert_eq!(control.preview_wanted().as_deref(), Some("a.wav"));
+    }
+
+    #[test]
+    fn tag_filter_limits_the_picker_and_stepping() {
+        use rustortion_core::ir::loader::IrTagVocabulary;
+
+        let irs: Vec<String> = vec![
+            "V30_SM57_1in.wav".into(),
+            "Greenback_R121.wav".into(),
+            "4x12_V30_R121.wav".into(),
+            "My Cab.wav".into(),
+        ];
+        let vocabulary = IrTagVocabulary::default();
+        let mut control = IrCabinetControl::default();
+        control.set_available_irs(irs.clone());
+        control.set_ir_metadata(
+            irs.iter()
+                .map(|name| IrMetadata::parse(name, &vocabulary))
+                .collect(),
+        );
+        assert_eq!(
+            control.all_tags(),
+            vec!["R121", "SM57", "4x12", "Greenback", "V30"]
+        );
+
+        control.set_tag_filter(Some("R121".into()));
+        assert_eq!(
+            control.visible_irs(),
+            vec!["Greenback_R121.wav", "4x12_V30_R121.wav"]
+        );
+        // The selection isn't tagged R121, so stepping starts at the ends.
+        assert_eq!(
+            control.step_target(1).as_deref(),
+            Some("Greenback_R121.wav")
+        );
+        control.set_selected_ir(Some("Greenback_R121.wav".into()));
+        assert_eq!(control.step_target(1).as_deref(), Some("4x12_V30_R121.wav"));
+        assert_eq!(
+            control.shuffle_target().as_deref(),
+            Some("4x12_V30_R121.wav")
+        );
+
+        control.set_tag_filter(None);
+        assert_eq!(control.visible_irs(), irs);
+
+        // A rescan without the tag drops the filter instead of hiding everything.
+        control.set_tag_filter(Some("R121".into()));
+        control.set_ir_metadata(Vec::new());
+        assert_eq!(control.tag_filter(), None);
+    }
+}
@@ -0,0 +1,40 @@
+use iced::widget::{button, container, row, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::components::widgets::common::{
+    BORDER_RADIUS_CARD, PADDING_NORMAL, SPACING_NORMAL, TEXT_SIZE_INFO,
+};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::ir::load_service::IrLoadFailure;
+
+/// An IR the user picked was refused or failed to load. The previous IR keeps
+/// playing; the banner says why until it's dismissed or another IR loads.
+pub fn view(failure: &IrLoadFailure) -> Element<'_, Message> {
+    let banner = row![
+        text(format!(
+            "{} {} ({})",
+            tr!(ir_rejected),
+            failure.name,
+            failure.error
+        )),
+        space::horizontal(),
+        button(text(tr!(close)).size(TEXT_SIZE_INFO))
+            .on_press(Message::DismissIrError)
+            .style(iced::widget::button::secondary),
+    ]
+    .spacing(SPACING_NORMAL)
+    .align_y(Alignment::Center);
+
+    container(banner)
+        .width(Length::Fill)
+        .padding(PADDING_NORMAL)
+        .style(|theme: &iced::Theme| {
+            let pair = theme.extended_palette().danger.weak;
+            container::Style::default()
+                .background(pair.color)
+                .color(pair.text)
+                .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+        })
+        .into()
+}
@@ -0,0 +1,45 @@
+use iced::widget::{button, row, slider, text};
+use iced::{Alignment, Element};
+
+use crate::components::widgets::common::{SPACING_NORMAL, SPACING_TIGHT, TEXT_SIZE_INFO};
+use crate::messages::{MacroMessage, Message};
+use crate::tr;
+use rustortion_core::preset::{MAX_MACROS, Macro};
+
+const MACRO_SLIDER_WIDTH: f32 = 100.0;
+const MACRO_STEP: f32 = 0.01;
+
+/// The preset's macros, one slider each. Clicking a name opens the macro in
+/// the editor.
+pub fn view(macros: &[Macro]) -> Element<'_, Message> {
+    let mut strip = row![text(tr!(macros)).size(TEXT_SIZE_INFO)]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+    for (idx, macro_) in macros.iter().enumerate() {
+        strip = strip.push(
+            row![
+                button(text(&macro_.name).size(TEXT_SIZE_INFO))
+                    .on_press(Message::Macro(MacroMessage::Edit(idx)))
+                    .style(iced::widget::button::text),
+                slider(0.0..=1.0, macro_.value, move |value| {
+                    Message::Macro(MacroMessage::ValueChanged(idx, value))
+                })
+                .step(MACRO_STEP)
+                .width(MACRO_SLIDER_WIDTH),
+                text(format!("{:.0}%", macro_.value * 100.0)).size(TEXT_SIZE_INFO),
+            ]
+            .spacing(SPACING_TIGHT)
+            .align_y(Alignment::Center),
+        );
+    }
+
+    let add = (macros.len() < MAX_MACROS).then_some(Message::Macro(MacroMessage::Add));
+    strip
+        .push(
+            button(tr!(add_macro))
+                .on_press_maybe(add)
+                .style(iced::widget::button::secondary),
+        )
+        .into()
+}
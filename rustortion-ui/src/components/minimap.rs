@@ -6,7 +6,7 @@ use crate::stages::{StageCategory, StageConfig};
 use crate::tabs::Tab;
 use rustortion_core::preset::InputFilterConfig;
 
-const fn stage_abbreviation(cfg: &StageConfig) -> &'static str {
+pub(crate) const fn stage_abbreviation(cfg: &StageConfig) -> &'static str {
     match cfg {
         StageConfig::Preamp(_) => "Pre",
         StageConfig::Compressor(_) => "Cmp",
@@ -16,10 +16,13 @@ const fn stage_abbreviation(cfg: &StageConfig) -> &'static str {
         StageConfig::NoiseGate(_) => "NG",
         StageConfig::MultibandSaturator(_) => "MBS",
         StageConfig::Nam(_) => "NAM",
+        StageConfig::Capture(_) => "Cap",
         StageConfig::Delay(_) => "Dly",
         StageConfig::Reverb(_) => "Rev",
         StageConfig::Eq(_) => "EQ",
         StageConfig::Tremolo(_) => "Trm",
+        StageConfig::Wah(_) => "Wah",
+        StageConfig::Group(_) => "Grp",
     }
 }
 
@@ -0,0 +1,103 @@
+use iced::widget::{button, checkbox, container, row, space, text};
+use iced::{Alignment, Element, Length};
+
+use crate::components::widgets::common::{
+    PADDING_NORMAL, SPACING_NORMAL, TEXT_SIZE_INFO, accent_style,
+};
+use crate::messages::{Message, MissingIrMessage};
+use crate::tr;
+use rustortion_core::ir::loader::find_by_stem;
+
+/// A loaded preset names an IR that isn't in the scanned list. Shown as a
+/// banner above the tabs until the user picks a replacement or bypasses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingIr {
+    pub preset: String,
+    pub ir_name: String,
+    /// The only scanned IR with the same file stem, if there is exactly one.
+    pub suggestion: Option<String>,
+    /// Write the replacement back into the preset file.
+    pub write_back: bool,
+}
+
+impl MissingIr {
+    /// `None` if `ir_name` is among the `available` IRs.
+    pub fn detect(preset: &str, ir_name: &str, available: &[String]) -> Option<Self> {
+        if available.iter().any(|ir| ir == ir_name) {
+            return None;
+        }
+        Some(Self {
+            preset: preset.to_owned(),
+            ir_name: ir_name.to_owned(),
+            suggestion: find_by_stem(ir_name, available).map(str::to_owned),
+            write_back: false,
+        })
+    }
+
+    /// `can_write_back` is false for read-only preset sources (the plugin's
+    /// factory presets), which hides the write-back option.
+    pub fn view(&self, can_write_back: bool) -> Element<'_, Message> {
+        let mut banner = row![text(format!("{} {}", tr!(missing_ir), self.ir_name)),]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center);
+
+        if let Some(suggestion) = &self.suggestion {
+            banner = banner.push(
+                button(text(format!("{} {suggestion}", tr!(missing_ir_use))).size(TEXT_SIZE_INFO))
+                    .on_press(MissingIrMessage::UseSuggestion.into())
+                    .style(iced::widget::button::success),
+            );
+        }
+
+        banner = banner
+            .push(
+                button(text(tr!(missing_ir_choose)).size(TEXT_SIZE_INFO))
+                    .on_press(MissingIrMessage::ChooseReplacement.into())
+                    .style(iced::widget::button::primary),
+            )
+            .push(
+                button(text(tr!(missing_ir_continue)).size(TEXT_SIZE_INFO))
+                    .on_press(MissingIrMessage::ContinueWithoutIr.into())
+                    .style(iced::widget::button::secondary),
+            )
+            .push(space::horizontal());
+
+        if can_write_back {
+            banner = banner.push(
+                checkbox(self.write_back)
+                    .label(tr!(missing_ir_write_back))
+                    .on_toggle(|on| MissingIrMessage::WriteBackToggled(on).into()),
+            );
+        }
+
+        container(banner)
+            .width(Length::Fill)
+            .padding(PADDING_NORMAL)
+            .style(|theme: &iced::Theme| accent_style(theme.extended_palette().warning.weak))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn irs() -> Vec<String> {
+        vec!["Cabs/mesa_v30.wav".to_string(), "greenback.wav".to_string()]
+    }
+
+    #[test]
+    fn present_ir_is_not_missing() {
+        assert_eq!(MissingIr::detect("Lead", "greenback.wav", &irs()), None);
+    }
+
+    #[test]
+    fn moved_ir_is_suggested() {
+        let missing = MissingIr::detect("Lead", "mesa_v30.wav", &irs()).unwrap();
+        assert_eq!(missing.suggestion.as_deref(), Some("Cabs/mesa_v30.wav"));
+        assert!(!missing.write_back);
+
+        let gone = MissingIr::detect("Lead", "deleted.wav", &irs()).unwrap();
+        assert_eq!(gone.suggestion, None);
+    }
+}
@@ -1,8 +1,19 @@
+pub mod ambience_control;
+pub mod denoiser_control;
 pub mod dialogs;
+pub mod double_tracker_control;
 pub mod input_filter_control;
 pub mod ir_cabinet_control;
+pub mod ir_error_banner;
+pub mod macro_strip;
 pub mod minimap;
+pub mod missing_ir_banner;
 pub mod peak_meter;
 pub mod pitch_shift_control;
 pub mod preset_bar;
+pub mod recording_status;
+pub mod stage_levels;
+pub mod stage_overview;
+pub mod tilt_control;
+pub mod view_menu;
 pub mod widgets;
@@ -1,8 +1,8 @@
-use iced::widget::{container, row, space, text};
+use iced::widget::{column, container, row, space, text};
 use iced::{Color, Element, Length};
 
 use crate::components::widgets::common::{
-    COLOR_ERROR, COLOR_INACTIVE, SPACING_NORMAL, TEXT_SIZE_INFO,
+    COLOR_ERROR, COLOR_INACTIVE, COLOR_WARNING, SPACING_NORMAL, TEXT_SIZE_INFO,
 };
 use crate::messages::Message;
 use crate::tr;
@@ -15,6 +15,9 @@ pub struct PeakMeterDisplay {
     info: PeakMeterInfo,
     xrun_count: u64,
     cpu_load: f32,
+    reduced_quality: bool,
+    /// The right channel's meter, in dual-mono.
+    right: Option<PeakMeterInfo>,
 }
 
 impl Default for PeakMeterDisplay {
@@ -29,73 +32,37 @@ impl PeakMeterDisplay {
             info: PeakMeterInfo::default(),
             xrun_count: 0,
             cpu_load: 0.0,
+            reduced_quality: false,
+            right: None,
         }
     }
 
-    pub const fn update(&mut self, info: PeakMeterInfo, xrun_count: u64, cpu_load: f32) {
+    pub const fn update(
+        &mut self,
+        info: PeakMeterInfo,
+        xrun_count: u64,
+        cpu_load: f32,
+        reduced_quality: bool,
+        right: Option<PeakMeterInfo>,
+    ) {
         self.info = info;
         self.xrun_count = xrun_count;
         self.cpu_load = cpu_load;
+        self.reduced_quality = reduced_quality;
+        self.right = right;
     }
 
+    /// One meter for the output, or one per side in dual-mono.
     pub fn view(&self) -> Element<'_, Message> {
-        let level_pct = ((self.info.peak_db + 60.0) / 60.0).clamp(0.0, 1.0);
-        let level_width = METER_WIDTH * level_pct;
-
-        let color = if self.info.is_clipping {
-            Color::from_rgb(1.0, 0.0, 0.0) // bright red clip
-        } else if self.info.peak_db > -6.0 {
-            Color::from_rgb(1.0, 0.7, 0.0) // orange-yellow warning
-        } else if self.info.peak_db > -20.0 {
-            Color::from_rgb(0.0, 1.0, 0.0) // bright green
-        } else {
-            Color::from_rgb(0.0, 0.5, 0.0) // dim green
-        };
-
-        let db_text = if self.info.peak_db > -100.0 {
-            format!("{:+.1} {}", self.info.peak_db, tr!(db))
-        } else {
-            format!("-∞ {}", tr!(db))
-        };
-
-        let status_text = if self.info.is_clipping {
-            text("CLIP!")
-                .size(TEXT_SIZE_INFO)
-                .style(move |_: &iced::Theme| iced::widget::text::Style {
-                    color: Some(Color::from_rgb(1.0, 0.0, 0.0)),
-                })
-        } else {
-            text("")
-                .size(TEXT_SIZE_INFO)
-                .style(|theme: &iced::Theme| iced::widget::text::Style {
-                    color: Some(theme.palette().text),
-                })
-        };
-
-        let meter = container(
-            container(space().width(level_width).height(METER_HEIGHT))
-                .style(move |_| container::Style::default().background(color)),
-        )
-        .width(Length::Fixed(METER_WIDTH))
-        .height(Length::Fixed(METER_HEIGHT))
-        .style(|_| {
-            container::Style::default()
-                .background(Color::from_rgb(0.2, 0.2, 0.2))
-                .border(iced::Border::default().width(1).rounded(3))
-        });
-
-        row![
-            text(tr!(output)).width(Length::Fixed(75.0)),
-            meter,
-            text(db_text)
-                .size(TEXT_SIZE_INFO)
-                .width(Length::Fixed(80.0))
-                .style(move |_: &iced::Theme| iced::widget::text::Style { color: Some(color) }),
-            status_text.width(Length::Fixed(50.0)),
-        ]
-        .spacing(SPACING_NORMAL)
-        .align_y(iced::Alignment::Center)
-        .into()
+        match &self.right {
+            Some(right) => column![
+                meter_row(tr!(output_left), &self.info),
+                meter_row(tr!(output_right), right),
+            ]
+            .spacing(SPACING_NORMAL)
+            .into(),
+            None => meter_row(tr!(output), &self.info),
+        }
     }
 
     pub fn view_status(&self) -> Element<'_, Message> {
@@ -116,7 +83,7 @@ impl PeakMeterDisplay {
         let xrun_count = self.xrun_count;
         let cpu_load = self.cpu_load;
 
-        row![
+        let mut status = row![
             text(format!("{} {xrun_count}", tr!(xruns)))
                 .size(11)
                 .style(move |_: &iced::Theme| iced::widget::text::Style {
@@ -129,7 +96,80 @@ impl PeakMeterDisplay {
                 }),
         ]
         .spacing(SPACING_NORMAL)
-        .align_y(iced::Alignment::Center)
-        .into()
+        .align_y(iced::Alignment::Center);
+
+        // The engine is shedding load rather than crackling.
+        if self.reduced_quality {
+            status = status.push(
+                text(tr!(reduced_quality))
+                    .size(11)
+                    .style(|_: &iced::Theme| iced::widget::text::Style {
+                        color: Some(COLOR_WARNING),
+                    }),
+            );
+        }
+
+        status.into()
     }
 }
+
+/// A labelled bar for one meter.
+fn meter_row(label: &'static str, info: &PeakMeterInfo) -> Element<'static, Message> {
+    let level_pct = ((info.peak_db + 60.0) / 60.0).clamp(0.0, 1.0);
+    let level_width = METER_WIDTH * level_pct;
+
+    let color = if info.is_clipping {
+        Color::from_rgb(1.0, 0.0, 0.0) // bright red clip
+    } else if info.peak_db > -6.0 {
+        Color::from_rgb(1.0, 0.7, 0.0) // orange-yellow warning
+    } else if info.peak_db > -20.0 {
+        Color::from_rgb(0.0, 1.0, 0.0) // bright green
+    } else {
+        Color::from_rgb(0.0, 0.5, 0.0) // dim green
+    };
+
+    let db_text = if info.peak_db > -100.0 {
+        format!("{:+.1} {}", info.peak_db, tr!(db))
+    } else {
+        format!("-∞ {}", tr!(db))
+    };
+
+    let status_text = if info.is_clipping {
+        text("CLIP!")
+            .size(TEXT_SIZE_INFO)
+            .style(move |_: &iced::Theme| iced::widget::text::Style {
+                color: Some(Color::from_rgb(1.0, 0.0, 0.0)),
+            })
+    } else {
+        text("")
+            .size(TEXT_SIZE_INFO)
+            .style(|theme: &iced::Theme| iced::widget::text::Style {
+                color: Some(theme.palette().text),
+            })
+    };
+
+    let meter = container(
+        container(space().width(level_width).height(METER_HEIGHT))
+            .style(move |_| container::Style::default().background(color)),
+    )
+    .width(Length::Fixed(METER_WIDTH))
+    .height(Length::Fixed(METER_HEIGHT))
+    .style(|_| {
+        container::Style::default()
+            .background(Color::from_rgb(0.2, 0.2, 0.2))
+            .border(iced::Border::default().width(1).rounded(3))
+    });
+
+    row![
+        text(label).width(Length::Fixed(75.0)),
+        meter,
+        text(db_text)
+            .size(TEXT_SIZE_INFO)
+            .width(Length::Fixed(80.0))
+            .style(move |_: &iced::Theme| iced::widget::text::Style { color: Some(color) }),
+        status_text.width(Length::Fixed(50.0)),
+    ]
+    .spacing(SPACING_NORMAL)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
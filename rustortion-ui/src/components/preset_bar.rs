@@ -1,17 +1,57 @@
-use iced::widget::{button, container, pick_list, row, space, text, text_input};
-use iced::{Alignment, Element, Length, Task};
+use iced::widget::{button, column, container, operation, row, space, text, text_input};
+use iced::{Alignment, Element, Length, Task, keyboard};
 
 use crate::components::widgets::common::{
-    BORDER_RADIUS_CARD, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT,
+    BORDER_RADIUS_CARD, COLOR_ERROR, COLOR_WARNING, PADDING_NORMAL, SPACING_NORMAL, SPACING_TIGHT,
+    TEXT_SIZE_SMALL,
 };
+use crate::components::widgets::search_select::{SearchSelect, SearchSelectMessage};
 use crate::messages::{Message, PresetGuiMessage, PresetMessage};
 use crate::tr;
+use rustortion_core::preset::tags::{complete_tag, suggest_tags};
+
+const NAME_INPUT_ID: &str = "preset-name-input";
+const TAGS_INPUT_ID: &str = "preset-tags-input";
+
+/// Most tag suggestions offered under the editor at once.
+const MAX_TAG_SUGGESTIONS: usize = 6;
+
+/// What the bar shows of the preset inbox.
+#[derive(Debug, Clone, Default)]
+pub struct InboxView {
+    /// How many presets are waiting in it.
+    pub unsorted: usize,
+    /// The inbox preset being tried, to offer keeping or discarding.
+    pub trying: Option<String>,
+}
+
+/// What the bar shows of preset tags.
+#[derive(Debug, Clone, Default)]
+pub struct TagView {
+    /// The selected preset's tags, shown by its name.
+    pub selected: Vec<String>,
+    /// Every tag in the library, to filter by and suggest.
+    pub known: Vec<String>,
+}
 
 pub struct PresetBar {
     preset_name_input: String,
     show_save_input: bool,
+    /// Why the last save was refused, shown next to the name field.
+    save_error: Option<String>,
     show_overwrite_confirmation: bool,
     overwrite_target: String,
+    preset_search: SearchSelect,
+    /// Tags typed for the preset being saved, comma-separated.
+    tag_input: String,
+    /// The song's BPM and time signature typed for the preset being saved.
+    bpm_input: String,
+    time_signature_input: String,
+    /// The tag and tempo editor is open for the selected preset, outside
+    /// Save As.
+    editing_tags: bool,
+    /// Tags a preset needs every one of to be listed.
+    tag_filter: Vec<String>,
 }
 
 impl Default for PresetBar {
@@ -25,8 +65,15 @@ impl PresetBar {
         Self {
             preset_name_input: String::new(),
             show_save_input: false,
+            save_error: None,
             show_overwrite_confirmation: false,
             overwrite_target: String::new(),
+            preset_search: SearchSelect::new(),
+            tag_input: String::new(),
+            bpm_input: String::new(),
+            time_signature_input: String::new(),
+            editing_tags: false,
+            tag_filter: Vec::new(),
         }
     }
 
@@ -34,6 +81,7 @@ impl PresetBar {
         match message {
             PresetGuiMessage::ShowSave => {
                 self.show_save_input(true);
+                return operation::focus(NAME_INPUT_ID);
             }
             PresetGuiMessage::CancelSave => {
                 self.show_save_input(false);
@@ -50,24 +98,138 @@ impl PresetBar {
             PresetGuiMessage::CancelOverwrite => {
                 self.hide_overwrite_confirmation();
             }
+            PresetGuiMessage::EditTags => {
+                self.editing_tags = true;
+                return operation::focus(TAGS_INPUT_ID);
+            }
+            PresetGuiMessage::CancelTags => self.show_save_input(false),
+            PresetGuiMessage::TagsChanged(tags) => self.tag_input = tags,
+            PresetGuiMessage::BpmChanged(bpm) => self.bpm_input = bpm,
+            PresetGuiMessage::TimeSignatureChanged(time_signature) => {
+                self.time_signature_input = time_signature;
+            }
+            PresetGuiMessage::CompleteTag(tag) => {
+                self.tag_input = complete_tag(&self.tag_input, &tag);
+                return operation::focus(TAGS_INPUT_ID);
+            }
+            PresetGuiMessage::ToggleTagFilter(tag) => {
+                if let Some(i) = self.tag_filter.iter().position(|t| *t == tag) {
+                    self.tag_filter.remove(i);
+                } else {
+                    self.tag_filter.push(tag);
+                }
+            }
+            PresetGuiMessage::ClearTagFilter => self.tag_filter.clear(),
+            // Owned by the dialogs and the handler; `PresetHandler` routes
+            // them there.
+            PresetGuiMessage::CloseDiff
+            | PresetGuiMessage::Search(_)
+            | PresetGuiMessage::ShowManage
+            | PresetGuiMessage::CloseManage
+            | PresetGuiMessage::UndoTick => {}
         }
 
         Task::none()
     }
 
+    /// Apply a message from the preset search box; a pick selects the preset.
+    pub fn search(&mut self, message: SearchSelectMessage, presets: &[String]) -> Task<Message> {
+        self.preset_search
+            .update(message, presets)
+            .map_or_else(Task::none, |name| {
+                Task::done(Message::Preset(PresetMessage::Select(name)))
+            })
+    }
+
+    pub const fn is_search_open(&self) -> bool {
+        self.preset_search.is_open()
+    }
+
+    /// Keys while the name field, the tag editor or the overwrite prompt is
+    /// showing: Enter saves or confirms, Escape cancels, and every other key
+    /// is swallowed so typing a name can't trigger hotkeys. `None` when none
+    /// of them is showing.
+    pub fn key_task(&self, key: &keyboard::Key) -> Option<Task<Message>> {
+        let (confirm, cancel) = if self.show_overwrite_confirmation {
+            (
+                PresetMessage::Gui(PresetGuiMessage::ConfirmOverwrite),
+                PresetMessage::Gui(PresetGuiMessage::CancelOverwrite),
+            )
+        } else if self.show_save_input {
+            (
+                PresetMessage::Save(self.preset_name_input.clone()),
+                PresetMessage::Gui(PresetGuiMessage::CancelSave),
+            )
+        } else if self.editing_tags {
+            (
+                PresetMessage::Update,
+                PresetMessage::Gui(PresetGuiMessage::CancelTags),
+            )
+        } else {
+            return None;
+        };
+
+        let message = match key {
+            keyboard::Key::Named(keyboard::key::Named::Enter) => Some(confirm),
+            keyboard::Key::Named(keyboard::key::Named::Escape) => Some(cancel),
+            _ => None,
+        };
+        Some(message.map_or_else(Task::none, |m| Task::done(Message::Preset(m))))
+    }
+
     pub fn set_new_preset_name(&mut self, name: String) {
         self.preset_name_input = name;
+        self.save_error = None;
+    }
+
+    pub fn set_save_error(&mut self, error: String) {
+        self.save_error = Some(error);
     }
 
     pub fn show_save_input(&mut self, show: bool) {
         self.show_save_input = show;
+        self.save_error = None;
         if !show {
             self.preset_name_input.clear();
             self.show_overwrite_confirmation = false;
             self.overwrite_target.clear();
+            self.tag_input.clear();
+            self.bpm_input.clear();
+            self.time_signature_input.clear();
+            self.editing_tags = false;
         }
     }
 
+    /// Start the tag editor off from a preset's tags.
+    pub fn set_tag_input(&mut self, tags: String) {
+        self.tag_input = tags;
+    }
+
+    pub fn tag_input(&self) -> &str {
+        &self.tag_input
+    }
+
+    /// Start the tempo fields off from a preset's tempo.
+    pub fn set_tempo_input(&mut self, bpm: String, time_signature: String) {
+        self.bpm_input = bpm;
+        self.time_signature_input = time_signature;
+    }
+
+    /// The BPM and time signature as typed.
+    pub fn tempo_input(&self) -> (&str, &str) {
+        (&self.bpm_input, &self.time_signature_input)
+    }
+
+    /// Whether a save takes its tags and tempo from the editor, rather than
+    /// keeping the preset's.
+    pub const fn is_editing_tags(&self) -> bool {
+        self.show_save_input || self.editing_tags
+    }
+
+    pub fn tag_filter(&self) -> &[String] {
+        &self.tag_filter
+    }
+
     pub fn show_overwrite_confirmation(&mut self, preset_name: String) {
         self.show_overwrite_confirmation = true;
         self.overwrite_target = preset_name;
@@ -78,21 +240,52 @@ impl PresetBar {
         self.overwrite_target.clear();
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn view(
         &self,
         selected_preset: Option<String>,
         available_presets: Vec<String>,
+        recent: Vec<String>,
+        missing: bool,
+        undo: Option<String>,
+        inbox: InboxView,
+        tags: TagView,
         read_only: bool,
     ) -> Element<'static, Message> {
-        let preset_selector = row![
+        let mut preset_selector = row![
             text(tr!(preset)).width(Length::Fixed(80.0)),
-            pick_list(available_presets, selected_preset.clone(), |p| {
-                PresetMessage::Select(p).into()
-            })
+            container(
+                self.preset_search
+                    .view(&available_presets, selected_preset.as_deref())
+                    .map(|m| PresetMessage::Gui(PresetGuiMessage::Search(m)).into())
+            )
             .width(Length::Fixed(200.0)),
         ]
         .spacing(SPACING_NORMAL)
-        .align_y(Alignment::Center);
+        .align_y(Alignment::Start);
+
+        for tag in &tags.selected {
+            preset_selector = preset_selector.push(tag_chip(tag));
+        }
+
+        // The selected preset's file was removed or renamed on disk.
+        if missing {
+            preset_selector =
+                preset_selector.push(text(tr!(preset_missing)).size(TEXT_SIZE_SMALL).style(|_| {
+                    iced::widget::text::Style {
+                        color: Some(COLOR_WARNING),
+                    }
+                }));
+        }
+
+        // One click back to a recently used preset.
+        for name in recent {
+            preset_selector = preset_selector.push(
+                button(text(name.clone()).size(TEXT_SIZE_SMALL))
+                    .on_press(PresetMessage::Select(name).into())
+                    .style(iced::widget::button::secondary),
+            );
+        }
 
         if read_only {
             return container(
@@ -110,6 +303,37 @@ impl PresetBar {
             .into();
         }
 
+        // An inbox preset is playing: keep it or throw it away.
+        if let Some(name) = inbox.trying {
+            let inbox_controls = row![
+                text(format!("'{name}' {}", tr!(inbox_trying))).style(|_| {
+                    iced::widget::text::Style {
+                        color: Some(COLOR_WARNING),
+                    }
+                }),
+                button(tr!(keep)).on_press(PresetMessage::KeepInbox(name.clone()).into()),
+                button(tr!(discard))
+                    .on_press(PresetMessage::DiscardInbox(name).into())
+                    .style(iced::widget::button::danger),
+            ]
+            .spacing(SPACING_TIGHT)
+            .align_y(Alignment::Center);
+
+            return container(
+                row![preset_selector, space::horizontal(), inbox_controls,]
+                    .spacing(SPACING_NORMAL)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .padding(PADDING_NORMAL)
+            .style(|theme: &iced::Theme| {
+                container::Style::default()
+                    .background(theme.palette().background)
+                    .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+            })
+            .into();
+        }
+
         if self.show_overwrite_confirmation {
             let confirmation_controls = row![
                 text(format!(
@@ -141,47 +365,215 @@ impl PresetBar {
         }
 
         let save_controls = if self.show_save_input {
-            row![
+            let mut name = column![
                 text_input(tr!(preset_name_placeholder), &self.preset_name_input)
+                    .id(NAME_INPUT_ID)
                     .on_input(|p| PresetMessage::Gui(PresetGuiMessage::NameChanged(p)).into())
                     .width(Length::Fixed(150.0)),
-                button(tr!(save))
-                    .on_press(PresetMessage::Save(self.preset_name_input.clone()).into()),
+            ];
+            if let Some(error) = &self.save_error {
+                // Wraps to the input's width rather than widening the row.
+                name = name.push(
+                    text(error)
+                        .size(TEXT_SIZE_SMALL)
+                        .width(Length::Fixed(150.0))
+                        .style(|_| iced::widget::text::Style {
+                            color: Some(COLOR_ERROR),
+                        }),
+                );
+            }
+            row![name]
+                .push(self.tag_editor(&tags.known))
+                .push(self.tempo_editor())
+                .push(
+                    button(tr!(save))
+                        .on_press(PresetMessage::Save(self.preset_name_input.clone()).into()),
+                )
+                .push(
+                    button(tr!(cancel))
+                        .on_press(PresetMessage::Gui(PresetGuiMessage::CancelSave).into()),
+                )
+                .spacing(SPACING_TIGHT)
+                .align_y(Alignment::Center)
+        } else if self.editing_tags {
+            row![
+                text(tr!(tags)).size(TEXT_SIZE_SMALL),
+                self.tag_editor(&tags.known),
+                self.tempo_editor(),
+                button(tr!(save)).on_press(PresetMessage::Update.into()),
                 button(tr!(cancel))
-                    .on_press(PresetMessage::Gui(PresetGuiMessage::CancelSave).into()),
+                    .on_press(PresetMessage::Gui(PresetGuiMessage::CancelTags).into()),
             ]
             .spacing(SPACING_TIGHT)
             .align_y(Alignment::Center)
         } else {
-            let mut controls = row![
+            let mut controls = row![];
+
+            // A delete can be taken back for a few seconds.
+            if let Some(deleted) = undo {
+                controls = controls
+                    .push(
+                        text(format!("{} '{deleted}'", tr!(preset_deleted))).size(TEXT_SIZE_SMALL),
+                    )
+                    .push(
+                        button(tr!(undo))
+                            .on_press(PresetMessage::UndoDelete.into())
+                            .style(iced::widget::button::secondary),
+                    );
+            }
+
+            controls = controls.push(
                 button(tr!(save_as))
                     .on_press(PresetMessage::Gui(PresetGuiMessage::ShowSave).into()),
-            ];
+            );
 
             if let Some(ref preset_name) = selected_preset {
-                controls = controls
-                    .push(button(tr!(update)).on_press(PresetMessage::Update.into()))
-                    .push(
+                // A missing preset has nothing on disk to compare or delete;
+                // updating saves it again.
+                if !missing {
+                    controls = controls
+                        .push(button(tr!(compare_saved)).on_press(PresetMessage::ShowDiff.into()));
+                }
+                controls =
+                    controls.push(button(tr!(update)).on_press(PresetMessage::Update.into()));
+                if !missing {
+                    controls = controls.push(
+                        button(tr!(tags_and_tempo))
+                            .on_press(PresetMessage::Gui(PresetGuiMessage::EditTags).into())
+                            .style(iced::widget::button::secondary),
+                    );
+                    controls = controls.push(
                         button(tr!(delete))
                             .on_press(PresetMessage::Delete(preset_name.clone()).into())
                             .style(iced::widget::button::danger),
                     );
+                }
             }
+            // Presets received from others wait in the inbox, sorted from
+            // the manage view.
+            if inbox.unsorted > 0 {
+                controls = controls.push(
+                    button(
+                        text(format!("{} ({})", tr!(inbox), inbox.unsorted))
+                            .size(TEXT_SIZE_SMALL)
+                            .style(|_| iced::widget::text::Style {
+                                color: Some(COLOR_WARNING),
+                            }),
+                    )
+                    .on_press(PresetMessage::Gui(PresetGuiMessage::ShowManage).into())
+                    .style(iced::widget::button::text),
+                );
+            }
+            controls = controls.push(
+                button(tr!(manage_presets))
+                    .on_press(PresetMessage::Gui(PresetGuiMessage::ShowManage).into())
+                    .style(iced::widget::button::secondary),
+            );
 
             controls.spacing(SPACING_TIGHT).align_y(Alignment::Center)
         };
 
-        container(
-            row![preset_selector, space::horizontal(), save_controls,]
-                .spacing(SPACING_NORMAL)
-                .align_y(Alignment::Center)
-                .width(Length::Fill),
-        )
+        let bar = row![preset_selector, space::horizontal(), save_controls,]
+            .spacing(SPACING_NORMAL)
+            .align_y(Alignment::Center)
+            .width(Length::Fill);
+        let content: Element<'static, Message> = if tags.known.is_empty() {
+            bar.into()
+        } else {
+            column![self.tag_filter_row(&tags.known), bar]
+                .spacing(SPACING_TIGHT)
+                .into()
+        };
+
+        container(content)
+            .style(|theme: &iced::Theme| {
+                container::Style::default()
+                    .background(theme.palette().background)
+                    .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+            })
+            .into()
+    }
+
+    /// The comma-separated tag field, with known tags that would complete
+    /// what's being typed.
+    fn tag_editor(&self, known: &[String]) -> Element<'static, Message> {
+        let mut editor = row![
+            text_input(tr!(tags_placeholder), &self.tag_input)
+                .id(TAGS_INPUT_ID)
+                .on_input(|t| PresetMessage::Gui(PresetGuiMessage::TagsChanged(t)).into())
+                .width(Length::Fixed(180.0)),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center);
+        for tag in suggest_tags(&self.tag_input, known)
+            .into_iter()
+            .take(MAX_TAG_SUGGESTIONS)
+        {
+            editor = editor.push(
+                button(text(tag.to_owned()).size(TEXT_SIZE_SMALL))
+                    .on_press(
+                        PresetMessage::Gui(PresetGuiMessage::CompleteTag(tag.to_owned())).into(),
+                    )
+                    .style(iced::widget::button::text),
+            );
+        }
+        editor.into()
+    }
+
+    /// The song's BPM and time signature, both optional.
+    fn tempo_editor(&self) -> Element<'static, Message> {
+        row![
+            text_input(tr!(bpm), &self.bpm_input)
+                .on_input(|b| PresetMessage::Gui(PresetGuiMessage::BpmChanged(b)).into())
+                .width(Length::Fixed(60.0)),
+            text_input(tr!(time_signature_placeholder), &self.time_signature_input)
+                .on_input(|t| PresetMessage::Gui(PresetGuiMessage::TimeSignatureChanged(t)).into())
+                .width(Length::Fixed(60.0)),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /// Every known tag as a toggle; the list shows presets with all of
+    /// those on.
+    fn tag_filter_row(&self, known: &[String]) -> Element<'static, Message> {
+        let mut filter = row![text(tr!(filter_by_tags)).size(TEXT_SIZE_SMALL)]
+            .spacing(SPACING_TIGHT)
+            .align_y(Alignment::Center);
+        for tag in known {
+            let style = if self.tag_filter.contains(tag) {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            };
+            filter = filter.push(
+                button(text(tag.clone()).size(TEXT_SIZE_SMALL))
+                    .on_press(
+                        PresetMessage::Gui(PresetGuiMessage::ToggleTagFilter(tag.clone())).into(),
+                    )
+                    .style(style),
+            );
+        }
+        if !self.tag_filter.is_empty() {
+            filter = filter.push(
+                button(text(tr!(clear_filter)).size(TEXT_SIZE_SMALL))
+                    .on_press(PresetMessage::Gui(PresetGuiMessage::ClearTagFilter).into())
+                    .style(iced::widget::button::text),
+            );
+        }
+        filter.into()
+    }
+}
+
+/// A tag shown by the preset's name.
+fn tag_chip(tag: &str) -> Element<'static, Message> {
+    container(text(tag.to_owned()).size(TEXT_SIZE_SMALL))
+        .padding([2, 6])
         .style(|theme: &iced::Theme| {
             container::Style::default()
-                .background(theme.palette().background)
+                .background(theme.extended_palette().background.weak.color)
                 .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
         })
         .into()
-    }
 }
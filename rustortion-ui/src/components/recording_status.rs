@@ -0,0 +1,114 @@
+use iced::widget::{container, row, space, text};
+use iced::{Color, Element, Length};
+
+use crate::components::widgets::common::{
+    COLOR_ERROR, COLOR_WARNING, SPACING_TIGHT, TEXT_SIZE_INFO,
+};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::audio::recorder::RecordingStatus;
+
+const METER_WIDTH: f32 = 60.0;
+const METER_HEIGHT: f32 = 8.0;
+/// Fall of the held level per poll, so short peaks stay readable.
+const LEVEL_DECAY: f32 = 0.9;
+
+/// Elapsed time, level and size of the take being written, shown next to
+/// the record button.
+#[derive(Default)]
+pub struct RecordingStatusDisplay {
+    status: Option<RecordingStatus>,
+    level: f32,
+    /// The soft clip saturated the last take, flagged once it's stopped.
+    softclipped: bool,
+}
+
+impl RecordingStatusDisplay {
+    /// Take the latest snapshot; `None` once recording stops.
+    pub fn update(&mut self, status: Option<RecordingStatus>) {
+        self.level = match &status {
+            Some(s) => s.peak.max(self.level * LEVEL_DECAY),
+            None => 0.0,
+        };
+        if let Some(s) = &status {
+            self.softclipped = s.softclipped;
+        }
+        self.status = status;
+    }
+
+    /// The take has stopped; `softclipped` if the soft clip saturated it.
+    pub fn finish(&mut self, softclipped: bool) {
+        self.update(None);
+        self.softclipped = softclipped;
+    }
+
+    /// Badge marking a stopped take the soft clip saturated.
+    pub fn softclip_badge(&self) -> Option<Element<'_, Message>> {
+        if !self.softclipped || self.status.is_some() {
+            return None;
+        }
+        let badge = container(
+            text(tr!(recording_softclipped))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_WARNING),
+                }),
+        )
+        .padding([0, 4])
+        .style(|_| {
+            container::Style::default().border(
+                iced::Border::default()
+                    .color(COLOR_WARNING)
+                    .width(1)
+                    .rounded(4),
+            )
+        });
+        Some(badge.into())
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let red = |_: &iced::Theme| iced::widget::text::Style {
+            color: Some(COLOR_ERROR),
+        };
+        let Some(status) = &self.status else {
+            return text(tr!(recording)).style(red).into();
+        };
+        if status.failed {
+            return text(tr!(recording_failed)).style(red).into();
+        }
+
+        let secs = status.elapsed.as_secs();
+        let elapsed = format!("{:02}:{:02}", secs / 60, secs % 60);
+        let size = format!("{:.1} MB", status.file_bytes as f64 / 1_000_000.0);
+
+        let level_db = 20.0 * self.level.max(1e-6).log10();
+        let level_pct = ((level_db + 60.0) / 60.0).clamp(0.0, 1.0);
+        let color = if self.level >= 1.0 {
+            Color::from_rgb(1.0, 0.0, 0.0)
+        } else if level_db > -6.0 {
+            Color::from_rgb(1.0, 0.7, 0.0)
+        } else {
+            Color::from_rgb(0.0, 1.0, 0.0)
+        };
+        let meter = container(
+            container(space().width(METER_WIDTH * level_pct).height(METER_HEIGHT))
+                .style(move |_| container::Style::default().background(color)),
+        )
+        .width(Length::Fixed(METER_WIDTH))
+        .height(Length::Fixed(METER_HEIGHT))
+        .style(|_| {
+            container::Style::default()
+                .background(Color::from_rgb(0.2, 0.2, 0.2))
+                .border(iced::Border::default().width(1).rounded(2))
+        });
+
+        row![
+            text(format!("● {elapsed}")).style(red),
+            meter,
+            text(size).size(TEXT_SIZE_INFO),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+}
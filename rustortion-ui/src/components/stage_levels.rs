@@ -0,0 +1,96 @@
+/// Fraction of a bar's level kept each meter tick (20 ms): a fall of about
+/// 20 dB a second once the stage goes quiet.
+const LEVEL_DECAY: f32 = 0.955;
+
+/// Live output level of every stage, drawn as a bar on its card so the
+/// signal can be watched growing through the chain. Each meter tick takes
+/// the peaks the engine held since the last one; a bar jumps up to a louder
+/// peak and falls back slowly.
+#[derive(Debug, Default)]
+pub struct StageLevels {
+    enabled: bool,
+    /// Linear, by chain index.
+    levels: Vec<f32>,
+}
+
+impl StageLevels {
+    pub const fn new() -> Self {
+        Self {
+            enabled: false,
+            levels: Vec::new(),
+        }
+    }
+
+    pub const fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Show or hide the bars. Either way they start again from silence.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.levels.clear();
+    }
+
+    /// Fold in the peaks held since the last tick, one per stage.
+    pub fn update(&mut self, peaks: &[f32]) {
+        self.levels.resize(peaks.len(), 0.0);
+        for (level, &peak) in self.levels.iter_mut().zip(peaks) {
+            *level = peak.max(*level * LEVEL_DECAY);
+        }
+    }
+
+    /// Level to draw on stage `idx`'s card, or `None` while the bars are
+    /// hidden.
+    pub fn level(&self, idx: usize) -> Option<f32> {
+        self.enabled
+            .then(|| self.levels.get(idx).copied().unwrap_or(0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_until_enabled() {
+        let mut levels = StageLevels::new();
+        levels.update(&[0.5]);
+        assert_eq!(levels.level(0), None);
+
+        levels.set_enabled(true);
+        assert_eq!(levels.level(0), Some(0.0));
+        levels.update(&[0.5]);
+        assert_eq!(levels.level(0), Some(0.5));
+        assert_eq!(levels.level(1), Some(0.0));
+    }
+
+    #[test]
+    fn rises_at_once_and_decays_slowly() {
+        let mut levels = StageLevels::new();
+        levels.set_enabled(true);
+        levels.update(&[0.8, 0.1]);
+        levels.update(&[0.0, 0.4]);
+        assert_eq!(levels.level(0), Some(0.8 * LEVEL_DECAY));
+        assert_eq!(levels.level(1), Some(0.4));
+
+        // A second of silence: about 20 dB down.
+        for _ in 0..49 {
+            levels.update(&[0.0, 0.0]);
+        }
+        let level = levels.level(0).unwrap();
+        assert!(level > 0.06 && level < 0.1, "{level}");
+    }
+
+    #[test]
+    fn follows_the_chain_length() {
+        let mut levels = StageLevels::new();
+        levels.set_enabled(true);
+        levels.update(&[0.5, 0.5, 0.5]);
+        levels.update(&[0.5]);
+        assert_eq!(levels.level(2), Some(0.0));
+
+        levels.set_enabled(false);
+        levels.set_enabled(true);
+        assert_eq!(levels.level(0), Some(0.0));
+    }
+}
@@ -0,0 +1,214 @@
+use iced::widget::{Row, column, container, mouse_area, text};
+use iced::{Element, Length, mouse};
+
+use super::minimap::stage_abbreviation;
+use super::widgets::common::{
+    BORDER_RADIUS_CARD, PADDING_NORMAL, PADDING_SMALL, SPACING_TIGHT, TEXT_SIZE_SMALL, stage_style,
+};
+use crate::messages::Message;
+use crate::stages::{StageCategory, StageConfig};
+
+/// Height of a collapsed card: the header row plus the card's own padding.
+const COLLAPSED_CARD_HEIGHT: f32 = 50.0;
+/// Height of one control row (slider, pick list) in an expanded card body.
+const CONTROL_ROW_HEIGHT: f32 = 36.0;
+
+/// The one parameter worth seeing at a glance for each stage type.
+fn key_parameter(cfg: &StageConfig) -> Option<String> {
+    match cfg {
+        StageConfig::Preamp(c) => Some(format!("{:.1}", c.gain)),
+        StageConfig::Compressor(c) => Some(format!("{:.0} dB", c.threshold_db)),
+        StageConfig::ToneStack(c) => Some(c.model.to_string()),
+        StageConfig::PowerAmp(c) => Some(format!("{:.2}", c.drive)),
+        StageConfig::Level(c) => Some(format!("{:.2}", c.gain)),
+        StageConfig::NoiseGate(c) => Some(format!("{:.0} dB", c.threshold_db)),
+        StageConfig::MultibandSaturator(c) => Some(format!("{:.2}", c.mid_drive)),
+        StageConfig::Nam(c) => Some(format!("{:+.1} dB", c.input_gain_db)),
+        StageConfig::Capture(c) => Some(format!("{:+.1} dB", c.input_gain_db)),
+        StageConfig::Delay(c) if c.sync => Some(c.division.to_string()),
+        StageConfig::Delay(c) => Some(format!("{:.0} ms", c.delay_ms)),
+        StageConfig::Reverb(c) => Some(format!("{:.0}%", c.mix * 100.0)),
+        StageConfig::Tremolo(c) => Some(format!("{:.1} Hz", c.rate_hz)),
+        StageConfig::Wah(c) => Some(format!("{:.2}", c.position)),
+        StageConfig::Group(c) => Some(format!("×{}", c.stages.len())),
+        StageConfig::Eq(_) => None,
+    }
+}
+
+/// Rough number of control rows in an expanded card body.
+fn body_rows(cfg: &StageConfig) -> f32 {
+    match cfg {
+        StageConfig::Level(_) => 1.0,
+        StageConfig::Preamp(_) | StageConfig::Reverb(_) | StageConfig::Tremolo(_) => 3.0,
+        StageConfig::PowerAmp(_) | StageConfig::Delay(_) => 4.0,
+        StageConfig::Compressor(_)
+        | StageConfig::NoiseGate(_)
+        | StageConfig::ToneStack(_)
+        | StageConfig::Nam(_)
+        | StageConfig::Capture(_)
+        | StageConfig::Eq(_) => 5.0,
+        StageConfig::Wah(_) => 6.0,
+        StageConfig::MultibandSaturator(_) => 8.0,
+        StageConfig::Group(c) => 2.0 + c.stages.iter().map(body_rows).sum::<f32>(),
+    }
+}
+
+fn estimated_card_height(cfg: &StageConfig, collapsed: bool) -> f32 {
+    if collapsed {
+        COLLAPSED_CARD_HEIGHT
+    } else {
+        body_rows(cfg).mul_add(CONTROL_ROW_HEIGHT, COLLAPSED_CARD_HEIGHT + PADDING_NORMAL)
+    }
+}
+
+/// Estimated scroll offset of `target`'s card in its tab's stage list. Card
+/// heights aren't known outside layout, so this sums an estimate per card;
+/// it lands the card near the top rather than exactly on it.
+pub fn scroll_offset(stages: &[StageConfig], collapsed: &[bool], target: usize) -> f32 {
+    let Some(category) = stages.get(target).map(StageConfig::category) else {
+        return 0.0;
+    };
+    stages
+        .iter()
+        .enumerate()
+        .take(target)
+        .filter(|(_, s)| s.category() == category)
+        .map(|(i, s)| {
+            estimated_card_height(s, collapsed.get(i).copied().unwrap_or(false)) + SPACING_TIGHT
+        })
+        .sum()
+}
+
+/// Collapse every card in `keep`'s tab except `keep`, which is expanded.
+/// Cards in the other tab are left alone.
+pub fn collapse_all_except(stages: &[StageConfig], collapsed: &mut [bool], keep: usize) {
+    let Some(category) = stages.get(keep).map(StageConfig::category) else {
+        return;
+    };
+    for (i, stage) in stages.iter().enumerate() {
+        if stage.category() == category
+            && let Some(c) = collapsed.get_mut(i)
+        {
+            *c = i != keep;
+        }
+    }
+}
+
+fn stage_box<'a>(
+    cfg: &StageConfig,
+    idx: usize,
+    collapsed: bool,
+    focused: bool,
+) -> Element<'a, Message> {
+    let marker = if collapsed { "▶" } else { "▼" };
+    let mut label =
+        column![text(format!("{marker} {}", stage_abbreviation(cfg))).size(TEXT_SIZE_SMALL)]
+            .spacing(2);
+    if let Some(value) = key_parameter(cfg) {
+        label = label.push(text(value).size(TEXT_SIZE_SMALL));
+    }
+
+    let stage_type = cfg.stage_type();
+    let opacity = if cfg.bypassed() { 0.5 } else { 1.0 };
+    let block = container(label)
+        .padding([2.0, PADDING_SMALL])
+        .style(move |theme: &iced::Theme| {
+            let mut style = stage_style(stage_type, theme);
+            if let Some(iced::Background::Color(color)) = style.background {
+                style.background = Some(iced::Background::Color(iced::Color {
+                    a: color.a * opacity,
+                    ..color
+                }));
+            }
+            if focused {
+                style.border = iced::Border::default()
+                    .rounded(BORDER_RADIUS_CARD)
+                    .color(theme.palette().primary)
+                    .width(2.0);
+            }
+            style
+        });
+
+    mouse_area(block)
+        .on_press(Message::ScrollToStage(idx))
+        .on_double_click(Message::ToggleStageCollapse(idx))
+        .interaction(mouse::Interaction::Pointer)
+        .into()
+}
+
+/// Strip of small boxes above a tab's stage list, one per stage in chain
+/// order. Click scrolls to the card; double-click collapses or expands it.
+pub fn view<'a>(
+    stages: &[StageConfig],
+    collapsed: &[bool],
+    focused: Option<usize>,
+    category: StageCategory,
+) -> Element<'a, Message> {
+    let boxes = stages
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.category() == category)
+        .map(|(i, s)| {
+            stage_box(
+                s,
+                i,
+                collapsed.get(i).copied().unwrap_or(false),
+                focused == Some(i),
+            )
+        });
+
+    container(Row::with_children(boxes).spacing(SPACING_TIGHT).wrap())
+        .width(Length::Fill)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::StageType;
+
+    fn chain() -> Vec<StageConfig> {
+        vec![
+            StageConfig::from(StageType::Preamp),
+            StageConfig::from(StageType::Delay),
+            StageConfig::from(StageType::ToneStack),
+            StageConfig::from(StageType::Level),
+        ]
+    }
+
+    #[test]
+    fn offset_counts_only_earlier_cards_in_the_same_tab() {
+        let stages = chain();
+        let expanded = vec![false; stages.len()];
+        assert_eq!(scroll_offset(&stages, &expanded, 0), 0.0);
+        // The delay sits in the Effects tab, so it's the first card there.
+        assert_eq!(scroll_offset(&stages, &expanded, 1), 0.0);
+
+        let after_preamp = scroll_offset(&stages, &expanded, 2);
+        assert!(after_preamp > 0.0);
+        assert!(scroll_offset(&stages, &expanded, 3) > after_preamp);
+    }
+
+    #[test]
+    fn collapsed_cards_take_less_room() {
+        let stages = chain();
+        let expanded = vec![false; stages.len()];
+        let collapsed = vec![true; stages.len()];
+        assert!(scroll_offset(&stages, &collapsed, 3) < scroll_offset(&stages, &expanded, 3));
+        assert_eq!(
+            scroll_offset(&stages, &collapsed, 3),
+            2.0 * (COLLAPSED_CARD_HEIGHT + SPACING_TIGHT)
+        );
+    }
+
+    #[test]
+    fn collapse_all_except_keeps_one_open_and_leaves_the_other_tab() {
+        let stages = chain();
+        let mut collapsed = vec![false, true, true, false];
+        collapse_all_except(&stages, &mut collapsed, 2);
+        assert_eq!(collapsed, [true, true, false, true]);
+
+        collapse_all_except(&stages, &mut collapsed, 9);
+        assert_eq!(collapsed, [true, true, false, true]);
+    }
+}
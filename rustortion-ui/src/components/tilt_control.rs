@@ -0,0 +1,109 @@
+use iced::widget::{mouse_area, row, slider, text};
+use iced::{Alignment, Element};
+
+use crate::components::widgets::common::{SPACING_TIGHT, TEXT_SIZE_INFO};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::audio::tilt::MAX_TILT_DB;
+
+const TILT_SLIDER_WIDTH: f32 = 100.0;
+const TILT_STEP_DB: f32 = 0.5;
+
+/// Global brightness tilt for the room or headphones, in the header so it's
+/// always at hand. Double-click the slider to flatten it.
+pub struct TiltControl {
+    /// Kept across preset switches; the shell persists it.
+    global_db: f32,
+    /// The loaded preset's own correction, on top of the global tilt.
+    preset_offset_db: f32,
+}
+
+impl Default for TiltControl {
+    fn default() -> Self {
+        Self::new(0.0, 0.0)
+    }
+}
+
+impl TiltControl {
+    pub const fn new(global_db: f32, preset_offset_db: f32) -> Self {
+        Self {
+            global_db: global_db.clamp(-MAX_TILT_DB, MAX_TILT_DB),
+            preset_offset_db,
+        }
+    }
+
+    pub const fn set_global_db(&mut self, tilt_db: f32) {
+        self.global_db = tilt_db.clamp(-MAX_TILT_DB, MAX_TILT_DB);
+    }
+
+    pub const fn global_db(&self) -> f32 {
+        self.global_db
+    }
+
+    pub const fn set_preset_offset_db(&mut self, offset_db: f32) {
+        self.preset_offset_db = offset_db;
+    }
+
+    pub const fn preset_offset_db(&self) -> f32 {
+        self.preset_offset_db
+    }
+
+    /// What the engine plays: the global tilt plus the preset's offset.
+    pub const fn effective_db(&self) -> f32 {
+        (self.global_db + self.preset_offset_db).clamp(-MAX_TILT_DB, MAX_TILT_DB)
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let value = if self.preset_offset_db == 0.0 {
+            format!("{:+.1} dB", self.global_db)
+        } else {
+            format!(
+                "{:+.1} dB ({} {:+.1})",
+                self.global_db,
+                tr!(tilt_preset_offset),
+                self.preset_offset_db
+            )
+        };
+
+        row![
+            text(tr!(tilt)).size(TEXT_SIZE_INFO),
+            mouse_area(
+                slider(
+                    -MAX_TILT_DB..=MAX_TILT_DB,
+                    self.global_db,
+                    Message::TiltChanged
+                )
+                .step(TILT_STEP_DB)
+                .width(TILT_SLIDER_WIDTH),
+            )
+            .on_double_click(Message::TiltChanged(0.0)),
+            text(value).size(TEXT_SIZE_INFO),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_offset_adds_to_the_global_tilt() {
+        let mut control = TiltControl::new(2.0, 0.0);
+        assert_eq!(control.effective_db(), 2.0);
+
+        control.set_preset_offset_db(-1.5);
+        assert_eq!(control.effective_db(), 0.5);
+        assert_eq!(control.global_db(), 2.0);
+
+        // A preset without an offset leaves the global tilt as it is.
+        control.set_preset_offset_db(0.0);
+        assert_eq!(control.effective_db(), 2.0);
+
+        control.set_global_db(5.0);
+        control.set_preset_offset_db(3.0);
+        assert_eq!(control.effective_db(), MAX_TILT_DB);
+    }
+}
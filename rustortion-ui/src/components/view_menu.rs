@@ -0,0 +1,34 @@
+use iced::Element;
+use iced::widget::pick_list;
+
+use crate::messages::Message;
+use crate::tr;
+
+/// An entry of the header's View menu: a display option, and whether it's
+/// on now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViewToggle {
+    /// Live output level bars on the stage cards.
+    StageLevels(bool),
+}
+
+impl std::fmt::Display for ViewToggle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (label, on) = match self {
+            Self::StageLevels(on) => (tr!(stage_levels), *on),
+        };
+        let mark = if on { "\u{2713}" } else { "\u{2003}" };
+        write!(f, "{mark} {label}")
+    }
+}
+
+/// Display options off the main view, off by default. Picking one flips it.
+pub fn view(stage_levels: bool) -> Element<'static, Message> {
+    pick_list(
+        [ViewToggle::StageLevels(stage_levels)],
+        None::<ViewToggle>,
+        Message::ViewToggled,
+    )
+    .placeholder(tr!(view_menu))
+    .into()
+}
@@ -1,9 +1,11 @@
 use crate::messages::Message;
+use crate::stages::{CommonStageParams, MAX_TRIM_DB, StageType, TrimMessage};
 use crate::tr;
 use iced::widget::{
-    button, column, container, pick_list, row, rule, slider, text, tooltip, vertical_slider,
+    button, column, container, mouse_area, pick_list, row, rule, slider, space, text, tooltip,
+    vertical_slider,
 };
-use iced::{Alignment, Color, Element, Length};
+use iced::{Alignment, Color, Element, Length, mouse};
 
 // ── Text sizes ──────────────────────────────────────────────────────────────
 pub const TEXT_SIZE_SECTION_TITLE: f32 = 18.0;
@@ -39,6 +41,17 @@ pub const COLOR_SUBTLE: Color = Color::from_rgb(0.7, 0.7, 0.7);
 pub const ICON_BUTTON_WIDTH: f32 = 30.0;
 pub const TAB_BUTTON_PADDING: [f32; 2] = [8.0, 24.0];
 
+// ── Drag and drop ───────────────────────────────────────────────────────────
+pub const DROP_INDICATOR_HEIGHT: f32 = 3.0;
+
+// ── Stage header trims ──────────────────────────────────────────────────────
+pub const TRIM_SLIDER_WIDTH: f32 = 60.0;
+
+// ── Stage level bars ────────────────────────────────────────────────────────
+pub const LEVEL_BAR_WIDTH: f32 = 6.0;
+/// Range a level bar spans below 0 dBFS.
+pub const LEVEL_BAR_RANGE_DB: f32 = 60.0;
+
 pub fn labeled_slider<'a, F: 'a + Fn(f32) -> Message>(
     label: &'a str,
     range: std::ops::RangeInclusive<f32>,
@@ -104,6 +117,13 @@ pub struct StageViewState {
     pub can_move_up: bool,
     pub can_move_down: bool,
     pub bypassed: bool,
+    /// Input/output trims and polarity, shown as micro-controls on the header.
+    pub common: CommonStageParams,
+    /// Target of Ctrl+Up/Down keyboard moves; drawn with a highlighted border.
+    pub is_focused: bool,
+    /// A stage is currently being dragged — cards report hover so the drop
+    /// target can be tracked.
+    pub drag_active: bool,
     /// Effective engine sample rate in Hz — the device rate times the oversampling
     /// factor, i.e. the rate stages are actually built and run at. Used by stages
     /// (e.g. NAM) to detect rate mismatches, so it must match what `to_stage` sees.
@@ -112,6 +132,55 @@ pub struct StageViewState {
     /// the NAM stage card so users know where to drop model files. `None` if the
     /// backend has no NAM directory. Ignored by all other stage views.
     pub nam_models_dir: Option<std::path::PathBuf>,
+    /// Capture-specific: directory the capture stage scans, shown on its card.
+    pub captures_dir: Option<std::path::PathBuf>,
+    /// Delay-specific: the backend has a tempo to sync to, so the delay card
+    /// offers its sync controls. Ignored by all other stage views.
+    pub tempo_sync: bool,
+    /// Picks the header accent, see [`stage_style`].
+    pub stage_type: StageType,
+    /// Chain lint warnings involving this stage, shown as an icon on its header.
+    pub warnings: Vec<&'static str>,
+    /// Offer the solo button. Off for stages inside a group, which the engine
+    /// can't tap on their own.
+    pub can_solo: bool,
+    /// This stage is the one being auditioned.
+    pub soloed: bool,
+    /// Offer the padlock. Off for stages inside a group, which are locked
+    /// with their group.
+    pub can_lock: bool,
+    /// Live output peak (linear) for the level bar at the card's right
+    /// edge; `None` hides the bar.
+    pub level: Option<f32>,
+}
+
+/// Header style for a stage card: one accent per stage type, taken from the
+/// theme's extended palette so it reads in light and dark themes alike.
+/// Related stages share a palette family (filters are primary, gain stages
+/// warning/danger, dynamics secondary) so a long chain can be scanned by color.
+pub fn stage_style(stage_type: StageType, theme: &iced::Theme) -> container::Style {
+    let palette = theme.extended_palette();
+    let pair = match stage_type {
+        StageType::ToneStack | StageType::Eq | StageType::Wah => palette.primary.weak,
+        StageType::Delay | StageType::Reverb => palette.primary.strong,
+        StageType::Preamp => palette.warning.weak,
+        StageType::MultibandSaturator => palette.warning.strong,
+        StageType::PowerAmp => palette.danger.weak,
+        StageType::Compressor | StageType::Level => palette.secondary.weak,
+        StageType::NoiseGate => palette.secondary.strong,
+        StageType::Nam | StageType::Capture => palette.success.weak,
+        StageType::Tremolo => palette.success.strong,
+        StageType::Group => palette.background.strong,
+    };
+    accent_style(pair)
+}
+
+/// A card filled with one of the theme's accent pairs, as in [`stage_style`].
+pub fn accent_style(pair: iced::theme::palette::Pair) -> container::Style {
+    container::Style::default()
+        .background(pair.color)
+        .color(pair.text)
+        .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
 }
 
 fn stage_header<'a>(
@@ -121,6 +190,14 @@ fn stage_header<'a>(
 ) -> Element<'a, Message> {
     let header_text = format!("{} {}", stage_name, idx + 1);
 
+    let drag_handle = mouse_area(
+        container(text("⠿"))
+            .width(Length::Fixed(ICON_BUTTON_WIDTH))
+            .center_x(Length::Fixed(ICON_BUTTON_WIDTH)),
+    )
+    .on_press(Message::StageDragStarted(idx))
+    .interaction(mouse::Interaction::Grab);
+
     let collapse_icon = if state.is_collapsed { "▶" } else { "▼" };
     let collapse_btn = icon_button(
         collapse_icon,
@@ -168,13 +245,115 @@ fn stage_header<'a>(
         iced::widget::tooltip::Position::Bottom,
     );
 
-    row![
+    let mut header = row![
+        drag_handle,
         collapse_btn,
         move_up_btn,
         move_down_btn,
         remove_btn,
         bypass_btn,
-        text(header_text)
+    ]
+    .spacing(SPACING_TIGHT)
+    .align_y(Alignment::Center);
+
+    if state.can_solo {
+        header = header.push(tooltip(
+            icon_button(
+                "🎧",
+                Some(Message::ToggleStageSolo(idx)),
+                if state.soloed {
+                    iced::widget::button::warning
+                } else {
+                    iced::widget::button::secondary
+                },
+            ),
+            tr!(stage_solo_tooltip),
+            iced::widget::tooltip::Position::Bottom,
+        ));
+    }
+
+    if state.can_lock {
+        header = header.push(tooltip(
+            icon_button(
+                if state.common.locked { "🔒" } else { "🔓" },
+                Some(Message::ToggleStageLock(idx)),
+                if state.common.locked {
+                    iced::widget::button::primary
+                } else {
+                    iced::widget::button::secondary
+                },
+            ),
+            tr!(stage_lock_tooltip),
+            iced::widget::tooltip::Position::Bottom,
+        ));
+    }
+
+    header = header
+        .push(text(header_text))
+        .push(space::horizontal())
+        .push(stage_trims(idx, state.common));
+
+    if !state.warnings.is_empty() {
+        header = header.push(tooltip(
+            text("⚠").style(|_: &iced::Theme| iced::widget::text::Style {
+                color: Some(COLOR_WARNING),
+            }),
+            container(text(state.warnings.join("\n")).size(TEXT_SIZE_SMALL))
+                .padding(PADDING_SMALL)
+                .style(container::rounded_box),
+            iced::widget::tooltip::Position::Bottom,
+        ));
+    }
+
+    let stage_type = state.stage_type;
+    container(header)
+        .width(Length::Fill)
+        .padding(PADDING_SMALL)
+        .style(move |theme: &iced::Theme| stage_style(stage_type, theme))
+        .into()
+}
+
+/// Input trim, output trim and polarity flip, small enough for the header.
+/// Exact values are in the tooltips.
+fn stage_trims<'a>(idx: usize, common: CommonStageParams) -> Element<'a, Message> {
+    let trim = |label: &str, db: f32, on_change: fn(f32) -> TrimMessage| {
+        tooltip(
+            slider(-MAX_TRIM_DB..=MAX_TRIM_DB, db, move |v| {
+                Message::StageTrim(idx, on_change(v))
+            })
+            .width(Length::Fixed(TRIM_SLIDER_WIDTH))
+            .step(0.5),
+            text(format!("{label}: {db:+.1} dB")).size(TEXT_SIZE_SMALL),
+            iced::widget::tooltip::Position::Bottom,
+        )
+    };
+
+    let invert_btn = tooltip(
+        icon_button(
+            "Ø",
+            Some(Message::StageTrim(idx, TrimMessage::InvertToggled)),
+            if common.invert {
+                iced::widget::button::primary
+            } else {
+                iced::widget::button::secondary
+            },
+        ),
+        tr!(stage_invert_tooltip),
+        iced::widget::tooltip::Position::Bottom,
+    );
+
+    row![
+        trim(
+            tr!(stage_in_trim),
+            common.in_trim_db,
+            TrimMessage::InTrimChanged
+        ),
+        trim(
+            tr!(stage_out_trim),
+            common.out_trim_db,
+            TrimMessage::OutTrimChanged
+        ),
+        invert_btn,
     ]
     .spacing(SPACING_TIGHT)
     .align_y(Alignment::Center)
@@ -202,14 +381,94 @@ pub fn stage_card<'a>(
     };
 
     let opacity = if state.bypassed { 0.5 } else { 1.0 };
+    let is_focused = state.is_focused;
+
+    let content: Element<'a, Message> = match state.level {
+        Some(level) => row![content.padding(padding), level_bar(level)]
+            .spacing(SPACING_TIGHT)
+            .into(),
+        None => content.padding(padding).into(),
+    };
 
-    container(content.padding(padding))
+    let card = container(content)
         .width(Length::Fill)
         .style(move |theme: &iced::Theme| {
-            let bg = theme.palette().background;
+            let palette = theme.palette();
+            let bg = palette.background;
+            let border = if is_focused {
+                iced::Border::default()
+                    .rounded(BORDER_RADIUS_CARD)
+                    .color(palette.primary)
+                    .width(1.0)
+            } else {
+                iced::Border::default().rounded(BORDER_RADIUS_CARD)
+            };
             container::Style::default()
                 .background(iced::Color::from_rgba(bg.r, bg.g, bg.b, opacity))
-                .border(iced::Border::default().rounded(BORDER_RADIUS_CARD))
+                .border(border)
+        });
+
+    if state.drag_active {
+        mouse_area(card)
+            .on_enter(Message::StageDragHovered(idx))
+            .on_exit(Message::StageDragLeft(idx))
+            .interaction(mouse::Interaction::Grabbing)
+            .into()
+    } else {
+        card.into()
+    }
+}
+
+/// Vertical bar of a stage's output level, `peak` linear, filled from the
+/// bottom over the top [`LEVEL_BAR_RANGE_DB`] below 0 dBFS. Colored like the
+/// output meter: orange within 6 dB of full scale, red past it.
+pub fn level_bar<'a>(peak: f32) -> Element<'a, Message> {
+    let peak_db = 20.0 * peak.max(f32::MIN_POSITIVE).log10();
+    let filled = ((peak_db + LEVEL_BAR_RANGE_DB) / LEVEL_BAR_RANGE_DB).clamp(0.0, 1.0);
+    let filled_portion = (filled * 100.0).round() as u16;
+
+    let color = if peak >= 1.0 {
+        COLOR_ERROR
+    } else if peak_db > -6.0 {
+        COLOR_WARNING
+    } else {
+        COLOR_SUCCESS
+    };
+
+    // A zero portion isn't a fill, so leave out an empty part.
+    let mut bar = column![];
+    if filled_portion < 100 {
+        bar = bar.push(space::vertical().height(Length::FillPortion(100 - filled_portion)));
+    }
+    if filled_portion > 0 {
+        bar = bar.push(
+            container(space::vertical())
+                .width(Length::Fill)
+                .height(Length::FillPortion(filled_portion))
+                .style(move |_| container::Style::default().background(color)),
+        );
+    }
+
+    container(bar)
+        .width(Length::Fixed(LEVEL_BAR_WIDTH))
+        .height(Length::Fill)
+        .style(|_| {
+            container::Style::default()
+                .background(Color::from_rgb(0.2, 0.2, 0.2))
+                .border(iced::Border::default().rounded(LEVEL_BAR_WIDTH / 2.0))
+        })
+        .into()
+}
+
+/// Horizontal bar marking where a dragged stage will be dropped.
+pub fn drop_indicator<'a>() -> Element<'a, Message> {
+    container(space::horizontal())
+        .width(Length::Fill)
+        .height(Length::Fixed(DROP_INDICATOR_HEIGHT))
+        .style(|theme: &iced::Theme| {
+            container::Style::default()
+                .background(theme.palette().primary)
+                .border(iced::Border::default().rounded(DROP_INDICATOR_HEIGHT / 2.0))
         })
         .into()
 }
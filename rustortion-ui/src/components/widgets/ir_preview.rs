@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use iced::widget::{canvas, column, row, text};
+use iced::{Color, Element, Length, Point, Rectangle, Size, mouse};
+
+use crate::components::widgets::common::{COLOR_MUTED, SPACING_NORMAL, SPACING_TIGHT};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::ir::thumbnail::{IrThumbnail, SPECTRUM_FLOOR_DB};
+
+const PREVIEW_WIDTH: f32 = 200.0;
+const PREVIEW_HEIGHT: f32 = 48.0;
+const CAPTION_SIZE: f32 = 11.0;
+
+/// The waveform and spectrum of an IR side by side, each captioned.
+pub fn ir_preview<'a>(thumbnail: &Arc<IrThumbnail>) -> Element<'a, Message> {
+    let plot = |program: Plot, caption: &str| {
+        column![
+            canvas(program)
+                .width(Length::Fixed(PREVIEW_WIDTH))
+                .height(Length::Fixed(PREVIEW_HEIGHT)),
+            text(caption.to_owned())
+                .size(CAPTION_SIZE)
+                .style(|_: &iced::Theme| iced::widget::text::Style {
+                    color: Some(COLOR_MUTED),
+                }),
+        ]
+        .spacing(SPACING_TIGHT)
+    };
+
+    row![
+        plot(
+            Plot::Waveform(Arc::clone(thumbnail)),
+            tr!(ir_preview_waveform)
+        ),
+        plot(
+            Plot::Spectrum(Arc::clone(thumbnail)),
+            tr!(ir_preview_spectrum)
+        ),
+    ]
+    .spacing(SPACING_NORMAL)
+    .into()
+}
+
+enum Plot {
+    /// Peak bars either side of a centre line.
+    Waveform(Arc<IrThumbnail>),
+    /// A line from the lowest band on the left to Nyquist on the right.
+    Spectrum(Arc<IrThumbnail>),
+}
+
+impl<M> canvas::Program<M> for Plot {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        theme: &iced::Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let palette = theme.extended_palette();
+        frame.fill_rectangle(Point::ORIGIN, frame.size(), palette.background.weak.color);
+        let color = palette.primary.strong.color;
+        let (width, height) = (frame.width(), frame.height());
+
+        match self {
+            Self::Waveform(thumbnail) => {
+                let middle = height / 2.0;
+                frame.fill_rectangle(
+                    Point::new(0.0, middle),
+                    Size::new(width, 1.0),
+                    Color { a: 0.4, ..color },
+                );
+                let bar = width / thumbnail.waveform.len().max(1) as f32;
+                for (i, &peak) in thumbnail.waveform.iter().enumerate() {
+                    let reach = peak * middle;
+                    frame.fill_rectangle(
+                        Point::new(i as f32 * bar, middle - reach.max(0.0)),
+                        Size::new(bar.max(1.0), reach.abs().max(1.0)),
+                        color,
+                    );
+                }
+            }
+            Self::Spectrum(thumbnail) => {
+                let last = thumbnail.spectrum.len().saturating_sub(1).max(1) as f32;
+                let line = canvas::Path::new(|b| {
+                    for (i, &db) in thumbnail.spectrum.iter().enumerate() {
+                        let point =
+                            Point::new(i as f32 / last * width, db / SPECTRUM_FLOOR_DB * height);
+                        if i == 0 {
+                            b.move_to(point);
+                        } else {
+                            b.line_to(point);
+                        }
+                    }
+                });
+                frame.stroke(
+                    &line,
+                    canvas::Stroke::default().with_width(1.5).with_color(color),
+                );
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
@@ -1 +1,3 @@
 pub mod common;
+pub mod ir_preview;
+pub mod search_select;
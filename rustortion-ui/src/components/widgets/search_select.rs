@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use iced::widget::{button, column, container, row, scrollable, space, text, text_input};
+use iced::{Alignment, Element, Length, keyboard};
+
+use crate::components::widgets::common::{
+    BORDER_RADIUS_CARD, COLOR_MUTED, SPACING_TIGHT, TEXT_SIZE_INFO,
+};
+use crate::tr;
+
+/// Height of the match list before it scrolls.
+const LIST_HEIGHT: f32 = 200.0;
+const ROW_HEIGHT: f32 = 28.0;
+
+#[derive(Debug, Clone)]
+pub enum SearchSelectMessage {
+    /// Show or hide the full list without typing.
+    Toggle,
+    QueryChanged(String),
+    /// Move the highlight by this many rows (Up/Down keys).
+    Move(i32),
+    /// Pick the highlighted match (Enter).
+    Submit,
+    /// Pick an option by clicking it.
+    Pick(String),
+    Close,
+}
+
+/// Dropdown with a filter box, for option lists too long for a plain
+/// `pick_list`. The parent owns the options and the selection; this only keeps
+/// the filter text, whether the list is open, and the highlighted row.
+#[derive(Debug, Default)]
+pub struct SearchSelect {
+    query: String,
+    open: bool,
+    highlighted: usize,
+}
+
+impl SearchSelect {
+    pub const fn new() -> Self {
+        Self {
+            query: String::new(),
+            open: false,
+            highlighted: 0,
+        }
+    }
+
+    pub const fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub const fn highlighted(&self) -> usize {
+        self.highlighted
+    }
+
+    /// Options matching the current filter, in their original order.
+    pub fn matches<'a>(&self, options: &'a [String]) -> Vec<&'a String> {
+        options
+            .iter()
+            .filter(|option| matches_query(option, &self.query))
+            .collect()
+    }
+
+    /// Apply `message`. Returns the option the user picked, if any.
+    pub fn update(&mut self, message: SearchSelectMessage, options: &[String]) -> Option<String> {
+        match message {
+            SearchSelectMessage::Toggle => {
+                if self.open {
+                    self.close();
+                } else {
+                    self.open = true;
+                    self.highlighted = 0;
+                }
+                None
+            }
+            SearchSelectMessage::QueryChanged(query) => {
+                self.query = query;
+                self.open = true;
+                self.highlighted = 0;
+                None
+            }
+            SearchSelectMessage::Move(delta) => {
+                if !self.open {
+                    self.open = true;
+                    self.highlighted = 0;
+                    return None;
+                }
+                let last = self.matches(options).len().saturating_sub(1);
+                self.highlighted = self
+                    .highlighted
+                    .saturating_add_signed(delta as isize)
+                    .min(last);
+                None
+            }
+            SearchSelectMessage::Submit => {
+                let picked = self
+                    .matches(options)
+                    .get(self.highlighted)
+                    .copied()
+                    .cloned();
+                if picked.is_some() {
+                    self.close();
+                }
+                picked
+            }
+            SearchSelectMessage::Pick(option) => {
+                self.close();
+                Some(option)
+            }
+            SearchSelectMessage::Close => {
+                self.close();
+                None
+            }
+        }
+    }
+
+    fn close(&mut self) {
+        self.open = false;
+        self.query.clear();
+        self.highlighted = 0;
+    }
+
+    /// The filter box (showing `selected` until the user types) and, when
+    /// open, the list of matches.
+    pub fn view(
+        &self,
+        options: &[String],
+        selected: Option<&str>,
+    ) -> Element<'static, SearchSelectMessage> {
+        self.view_flagged(options, selected, &[])
+    }
+
+    /// [`Self::view`] with a warning glyph before each of the `flagged` options.
+    pub fn view_flagged(
+        &self,
+        options: &[String],
+        selected: Option<&str>,
+        flagged: &[String],
+    ) -> Element<'static, SearchSelectMessage> {
+        self.view_annotated(options, selected, flagged, &HashMap::new())
+    }
+
+    /// [`Self::view_flagged`] with each option's entry in `notes`, if any,
+    /// right-aligned and muted in its row.
+    pub fn view_annotated(
+        &self,
+        options: &[String],
+        selected: Option<&str>,
+        flagged: &[String],
+        notes: &HashMap<String, String>,
+    ) -> Element<'static, SearchSelectMessage> {
+        let placeholder = selected.unwrap_or(tr!(search_placeholder));
+        let toggle_icon = if self.open { "▲" } else { "▼" };
+
+        let field = row![
+            text_input(placeholder, &self.query)
+                .on_input(SearchSelectMessage::QueryChanged)
+                .on_submit(SearchSelectMessage::Submit)
+                .width(Length::Fill),
+            button(text(toggle_icon))
+                .on_press(SearchSelectMessage::Toggle)
+                .style(iced::widget::button::secondary),
+        ]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center);
+
+        if !self.open {
+            return field.into();
+        }
+
+        let matches = self.matches(options);
+        let list: Element<'static, SearchSelectMessage> = if matches.is_empty() {
+            text(tr!(no_matches))
+                .size(TEXT_SIZE_INFO)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_MUTED),
+                })
+                .into()
+        } else {
+            let height = (matches.len() as f32 * ROW_HEIGHT).min(LIST_HEIGHT);
+            let rows = matches.into_iter().enumerate().map(|(i, option)| {
+                let style = if i == self.highlighted {
+                    iced::widget::button::primary
+                } else if Some(option.as_str()) == selected {
+                    iced::widget::button::secondary
+                } else {
+                    iced::widget::button::text
+                };
+                let label = if flagged.contains(option) {
+                    format!("\u{26a0} {option}")
+                } else {
+                    option.clone()
+                };
+                let content: Element<'static, SearchSelectMessage> = match notes.get(option) {
+                    Some(note) => row![
+                        text(label).size(TEXT_SIZE_INFO),
+                        space::horizontal(),
+                        text(note.clone()).size(TEXT_SIZE_INFO).style(|_| {
+                            iced::widget::text::Style {
+                                color: Some(COLOR_MUTED),
+                            }
+                        }),
+                    ]
+                    .spacing(SPACING_TIGHT)
+                    .into(),
+                    None => text(label).size(TEXT_SIZE_INFO).into(),
+                };
+                button(content)
+                    .on_press(SearchSelectMessage::Pick(option.clone()))
+                    .width(Length::Fill)
+                    .height(Length::Fixed(ROW_HEIGHT))
+                    .style(style)
+                    .into()
+            });
+            scrollable(column(rows))
+                .height(Length::Fixed(height))
+                .into()
+        };
+
+        let dropdown = container(list)
+            .width(Length::Fill)
+            .style(|theme: &iced::Theme| {
+                container::Style::default()
+                    .background(theme.palette().background)
+                    .border(iced::Border::default().rounded(BORDER_RADIUS_CARD).width(1))
+            });
+
+        column![field, dropdown].spacing(SPACING_TIGHT).into()
+    }
+}
+
+/// Up/Down/Escape drive an open dropdown. Enter needs no routing: it's the
+/// filter box's own submit.
+pub const fn key_message(key: &keyboard::Key) -> Option<SearchSelectMessage> {
+    match key {
+        keyboard::Key::Named(keyboard::key::Named::ArrowUp) => Some(SearchSelectMessage::Move(-1)),
+        keyboard::Key::Named(keyboard::key::Named::ArrowDown) => Some(SearchSelectMessage::Move(1)),
+        keyboard::Key::Named(keyboard::key::Named::Escape) => Some(SearchSelectMessage::Close),
+        _ => None,
+    }
+}
+
+/// Case-insensitive, folder-aware match: every word of `query` must appear in
+/// `option`, in any order, where path separators and `_`/`-`/`.` count as
+/// spaces. "4x12 v30" matches "Mesa/4x12_V30_SM57.wav".
+pub fn matches_query(option: &str, query: &str) -> bool {
+    let haystack = normalize(option);
+    normalize(query)
+        .split_whitespace()
+        .all(|word| haystack.contains(word))
+}
+
+fn normalize(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '/' | '\\' | '_' | '-' | '.' => ' ',
+            c => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options() -> Vec<String> {
+        [
+            "Mesa/4x12_V30_SM57.wav",
+            "Mesa/2x12_Greenback.wav",
+            "Marshall/4x12_G12M_SM57.wav",
+            "Orange/4x12_V30_MD421.wav",
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+
+    #[test]
+    fn matches_words_across_folders_and_separators() {
+        assert!(matches_query("Mesa/4x12_V30_SM57.wav", "4x12 v30"));
+        assert!(matches_query("Mesa/4x12_V30_SM57.wav", "mesa sm57"));
+        assert!(matches_query("Mesa/4x12_V30_SM57.wav", "4x12_v30"));
+        assert!(matches_query("Mesa/4x12_V30_SM57.wav", "SM57 MESA"));
+        assert!(!matches_query("Mesa/4x12_V30_SM57.wav", "greenback"));
+        assert!(matches_query("Anything", ""));
+    }
+
+    #[test]
+    fn filter_keeps_original_order() {
+        let options = options();
+        let mut select = SearchSelect::new();
+        select.update(
+            SearchSelectMessage::QueryChanged("4x12 sm57".into()),
+            &options,
+        );
+
+        assert_eq!(select.matches(&options), vec![&options[0], &options[2]]);
+    }
+
+    #[test]
+    fn move_is_clamped_to_matches() {
+        let options = options();
+        let mut select = SearchSelect::new();
+        select.update(SearchSelectMessage::QueryChanged("v30".into()), &options);
+
+        select.update(SearchSelectMessage::Move(-1), &options);
+        assert_eq!(select.highlighted(), 0);
+        select.update(SearchSelectMessage::Move(5), &options);
+        assert_eq!(select.highlighted(), 1);
+    }
+
+    #[test]
+    fn submit_picks_highlighted_match_and_closes() {
+        let options = options();
+        let mut select = SearchSelect::new();
+        select.update(SearchSelectMessage::QueryChanged("4x12".into()), &options);
+        select.update(SearchSelectMessage::Move(1), &options);
+
+        let picked = select.update(SearchSelectMessage::Submit, &options);
+        assert_eq!(picked.as_deref(), Some("Marshall/4x12_G12M_SM57.wav"));
+        assert!(!select.is_open());
+        assert!(select.query().is_empty());
+    }
+
+    #[test]
+    fn submit_without_matches_keeps_list_open() {
+        let options = options();
+        let mut select = SearchSelect::new();
+        select.update(SearchSelectMessage::QueryChanged("zzz".into()), &options);
+
+        assert_eq!(select.update(SearchSelectMessage::Submit, &options), None);
+        assert!(select.is_open());
+    }
+
+    #[test]
+    fn typing_resets_highlight() {
+        let options = options();
+        let mut select = SearchSelect::new();
+        select.update(SearchSelectMessage::Toggle, &options);
+        select.update(SearchSelectMessage::Move(3), &options);
+        assert_eq!(select.highlighted(), 3);
+
+        select.update(SearchSelectMessage::QueryChanged("o".into()), &options);
+        assert_eq!(select.highlighted(), 0);
+    }
+
+    #[test]
+    fn move_opens_closed_list() {
+        let options = options();
+        let mut select = SearchSelect::new();
+
+        select.update(SearchSelectMessage::Move(1), &options);
+        assert!(select.is_open());
+        assert_eq!(select.highlighted(), 0);
+    }
+}
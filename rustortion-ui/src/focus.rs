@@ -0,0 +1,89 @@
+use crate::tabs::Tab;
+
+/// A control the Tab-key focus ring can land on.
+///
+/// iced only tracks focus for text inputs, so buttons and stage headers are
+/// tracked by the app instead and drawn with a focus border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusTarget {
+    Standby,
+    Hotkeys,
+    Midi,
+    Mappings,
+    Setlist,
+    Tuner,
+    Settings,
+    Record,
+    Render,
+    Takes,
+    Tab(Tab),
+    /// A stage card header, by absolute chain index. Enter toggles collapse.
+    Stage(usize),
+}
+
+/// The entry after (or before) `current` in `ring`, wrapping at the ends.
+///
+/// With nothing focused, or with a target that has since left the ring (a
+/// removed stage, a stage on another tab), focus starts from the first entry
+/// going forward and the last going backward.
+pub fn step(
+    ring: &[FocusTarget],
+    current: Option<FocusTarget>,
+    forward: bool,
+) -> Option<FocusTarget> {
+    let len = ring.len();
+    if len == 0 {
+        return None;
+    }
+
+    let next = match current.and_then(|c| ring.iter().position(|&t| t == c)) {
+        Some(pos) if forward => (pos + 1) % len,
+        Some(pos) => (pos + len - 1) % len,
+        None if forward => 0,
+        None => len - 1,
+    };
+    Some(ring[next])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RING: [FocusTarget; 4] = [
+        FocusTarget::Settings,
+        FocusTarget::Tab(Tab::Amp),
+        FocusTarget::Stage(2),
+        FocusTarget::Stage(5),
+    ];
+
+    #[test]
+    fn starts_at_the_ends() {
+        assert_eq!(step(&RING, None, true), Some(FocusTarget::Settings));
+        assert_eq!(step(&RING, None, false), Some(FocusTarget::Stage(5)));
+        assert_eq!(step(&[], None, true), None);
+    }
+
+    #[test]
+    fn wraps_in_both_directions() {
+        assert_eq!(
+            step(&RING, Some(FocusTarget::Stage(5)), true),
+            Some(FocusTarget::Settings)
+        );
+        assert_eq!(
+            step(&RING, Some(FocusTarget::Settings), false),
+            Some(FocusTarget::Stage(5))
+        );
+        assert_eq!(
+            step(&RING, Some(FocusTarget::Tab(Tab::Amp)), true),
+            Some(FocusTarget::Stage(2))
+        );
+    }
+
+    #[test]
+    fn stale_target_restarts() {
+        assert_eq!(
+            step(&RING, Some(FocusTarget::Stage(9)), true),
+            Some(FocusTarget::Settings)
+        );
+    }
+}
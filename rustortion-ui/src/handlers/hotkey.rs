@@ -4,8 +4,9 @@ use iced::keyboard::{Key, Modifiers};
 use log::debug;
 
 use crate::components::dialogs::hotkey::HotkeyDialog;
-use crate::hotkey::HotkeySettings;
-use crate::messages::{HotkeyMessage, Message};
+use crate::hotkey::{HotkeyAction, HotkeySettings};
+use crate::messages::{HotkeyMessage, Message, PresetMessage, SetlistMessage, TunerMessage};
+use rustortion_core::preset::slot_preset_name;
 
 pub struct HotkeyHandler {
     dialog: HotkeyDialog,
@@ -39,6 +40,43 @@ impl HotkeyHandler {
             HotkeyMessage::PresetSelected(preset) => {
                 self.dialog.set_preset_for_mapping(preset);
             }
+            HotkeyMessage::HoldTunerToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::HoldTuner, on);
+            }
+            HotkeyMessage::PreviousPresetToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::PreviousPreset, on);
+            }
+            HotkeyMessage::RandomizeToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::Randomize, on);
+            }
+            HotkeyMessage::NextSongToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::NextSong, on);
+            }
+            HotkeyMessage::PreviousSongToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::PreviousSong, on);
+            }
+            HotkeyMessage::ToggleStandbyToggled(on) => {
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::ToggleStandby, on);
+            }
+            HotkeyMessage::SaveSlotToggled(on) => {
+                let slot = self.dialog.slot_for_mapping();
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::SaveSlot(slot), on);
+            }
+            HotkeyMessage::LoadSlotToggled(on) => {
+                let slot = self.dialog.slot_for_mapping();
+                self.dialog
+                    .toggle_action_for_mapping(HotkeyAction::LoadSlot(slot), on);
+            }
+            HotkeyMessage::SlotSelected(slot) => {
+                self.dialog.set_slot_for_mapping(slot);
+            }
             HotkeyMessage::ConfirmMapping => {
                 if self.dialog.complete_mapping().is_some() {
                     self.settings.mappings = self.dialog.get_mappings();
@@ -46,8 +84,12 @@ impl HotkeyHandler {
                 }
             }
             HotkeyMessage::RemoveMapping(idx) => {
-                self.dialog.remove_mapping(idx);
-                self.settings.mappings = self.dialog.get_mappings();
+                // Also sent from the mappings overview, with the dialog closed
+                // and its list stale, so the settings are what's edited.
+                if idx < self.settings.mappings.len() {
+                    self.settings.mappings.remove(idx);
+                }
+                self.dialog.set_mappings(self.settings.mappings.clone());
                 debug!("Hotkey mapping removed and saved");
             }
         }
@@ -67,13 +109,41 @@ impl HotkeyHandler {
         self.dialog.is_visible()
     }
 
-    /// Check if a key event matches any hotkey mapping, returning the preset name if so.
-    pub fn check_mapping(&self, key: &Key, modifiers: Modifiers) -> Option<String> {
+    /// Check if a key press matches any hotkey mapping, returning the message
+    /// it triggers: a preset load, or the start of a tuner hold. Saving a
+    /// quick slot is a plain save, so it overwrites without asking.
+    pub fn check_mapping(&self, key: &Key, modifiers: Modifiers) -> Option<Message> {
+        let mapping = self
+            .settings
+            .mappings
+            .iter()
+            .find(|m| m.matches(key, modifiers))?;
+        Some(match mapping.action {
+            HotkeyAction::LoadPreset => {
+                Message::Preset(PresetMessage::Select(mapping.preset_name.clone()))
+            }
+            HotkeyAction::HoldTuner => Message::Tuner(TunerMessage::Hold),
+            HotkeyAction::PreviousPreset => Message::Preset(PresetMessage::Previous),
+            HotkeyAction::Randomize => Message::RandomizeChain,
+            HotkeyAction::NextSong => Message::Setlist(SetlistMessage::Next),
+            HotkeyAction::PreviousSong => Message::Setlist(SetlistMessage::Previous),
+            HotkeyAction::ToggleStandby => Message::ToggleStandby,
+            HotkeyAction::SaveSlot(slot) => {
+                Message::Preset(PresetMessage::Save(slot_preset_name(slot)))
+            }
+            HotkeyAction::LoadSlot(slot) => {
+                Message::Preset(PresetMessage::Select(slot_preset_name(slot)))
+            }
+        })
+    }
+
+    /// Check if a key release ends a tuner hold.
+    pub fn check_release(&self, key: &Key) -> Option<Message> {
         self.settings
             .mappings
             .iter()
-            .find(|m| m.matches(key, modifiers))
-            .map(|m| m.preset_name.clone())
+            .any(|m| m.action == HotkeyAction::HoldTuner && m.matches_release(key))
+            .then_some(Message::Tuner(TunerMessage::Release))
     }
 
     pub const fn settings(&self) -> &HotkeySettings {
@@ -1,24 +1,64 @@
-use anyhow::Result;
 use iced::Element;
 use iced::Task;
-use log::{debug, error};
+use log::{debug, error, warn};
 use std::path::Path;
+use std::time::Duration;
 
-use crate::components::preset_bar::PresetBar;
-use crate::messages::Message;
+use crate::components::dialogs::preset_diff::PresetDiffDialog;
+use crate::components::dialogs::preset_manage::PresetManageDialog;
+use crate::components::preset_bar::{InboxView, PresetBar, TagView};
+use crate::messages::{DenoiseMessage, MacroMessage, Message, MissingIrMessage, PresetGuiMessage};
 use crate::stages::StageConfig;
-use rustortion_core::preset::{InputFilterConfig, Manager, Preset};
+use crate::tr;
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::denoiser::DenoiseConfig;
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::ir::align::IrAlignment;
+use rustortion_core::preset::diff::diff_presets;
+use rustortion_core::preset::tags::{format_tags, parse_tags};
+use rustortion_core::preset::tempo::{
+    format_bpm, format_time_signature, parse_bpm, parse_time_signature,
+};
+use rustortion_core::preset::{
+    InputFilterConfig, Macro, Manager, Preset, PresetError, PresetGroup, PresetNameError,
+    TrashedPreset,
+};
+
+/// How many recently used presets are remembered unless configured otherwise.
+pub const DEFAULT_RECENT_PRESETS: usize = 5;
+
+/// How long the preset bar offers to undo a delete.
+const UNDO_WINDOW: Duration = Duration::from_secs(10);
 
 pub struct PresetHandler {
     available_presets: Vec<String>,
+    /// Presets received from others and not yet sorted, listed apart from
+    /// `available_presets` and never selected.
+    inbox: Vec<String>,
+    /// The inbox preset loaded to try out, while it waits to be kept or
+    /// discarded. Nothing is selected meanwhile, so Update can't write it
+    /// over a library preset.
+    trying_inbox: Option<String>,
     preset_manager: Manager,
     selected_preset: Option<String>,
+    /// Recently selected presets, newest (normally the selected one) first.
+    recent: Vec<String>,
+    recent_limit: usize,
     preset_bar: PresetBar,
+    diff_dialog: PresetDiffDialog,
+    manage_dialog: PresetManageDialog,
+    /// The preset deleted last, while it can still be undone.
+    undo: Option<TrashedPreset>,
 }
 
 impl PresetHandler {
-    pub fn new(preset_dir: impl AsRef<Path>) -> Result<Self> {
+    pub fn new(preset_dir: impl AsRef<Path>) -> Result<Self, PresetError> {
         let preset_manager = Manager::new(preset_dir)?;
+        match preset_manager.purge_expired_trash() {
+            Ok(0) => {}
+            Ok(purged) => debug!("Purged {purged} presets from the trash"),
+            Err(e) => error!("Failed to purge the preset trash: {e}"),
+        }
 
         let presets = preset_names(&preset_manager);
         let selected_preset = presets.first().cloned();
@@ -26,9 +66,16 @@ impl PresetHandler {
 
         Ok(Self {
             available_presets: presets,
+            inbox: inbox_names(&preset_manager),
+            trying_inbox: None,
             preset_manager,
             selected_preset,
+            recent: Vec::new(),
+            recent_limit: DEFAULT_RECENT_PRESETS,
             preset_bar,
+            diff_dialog: PresetDiffDialog::new(),
+            manage_dialog: PresetManageDialog::new(),
+            undo: None,
         })
     }
 
@@ -39,24 +86,70 @@ impl PresetHandler {
         let selected_preset = available_presets.first().cloned();
         Self {
             available_presets,
+            inbox: Vec::new(),
+            trying_inbox: None,
             preset_manager: Manager::new_from_presets(presets),
             selected_preset,
+            recent: Vec::new(),
+            recent_limit: DEFAULT_RECENT_PRESETS,
             preset_bar: PresetBar::new(),
+            diff_dialog: PresetDiffDialog::new(),
+            manage_dialog: PresetManageDialog::new(),
+            undo: None,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn handle(
         &mut self,
         message: crate::messages::PresetMessage,
         stages: Vec<StageConfig>,
         ir_name: Option<String>,
         ir_gain: f32,
+        ir_alignment: IrAlignment,
         pitch_shift_semitones: i32,
         input_filters: InputFilterConfig,
+        denoise: DenoiseConfig,
+        ambience: AmbienceConfig,
+        double_tracker: DoubleTrackerConfig,
+        tilt_offset_db: f32,
+        collapsed: Vec<bool>,
+        macros: Vec<Macro>,
     ) -> Task<Message> {
         use crate::messages::PresetMessage;
 
         match message {
+            PresetMessage::Gui(PresetGuiMessage::CloseDiff) => self.diff_dialog.hide(),
+            PresetMessage::Gui(PresetGuiMessage::ShowManage) => {
+                self.refresh_presets();
+                let trashed = self.trashed_presets();
+                self.manage_dialog.show(trashed, self.inbox.clone());
+            }
+            PresetMessage::Gui(PresetGuiMessage::CloseManage) => self.manage_dialog.hide(),
+            PresetMessage::Gui(PresetGuiMessage::UndoTick) => {
+                if self
+                    .undo
+                    .as_ref()
+                    .is_some_and(|t| t.trashed_at.elapsed().unwrap_or_default() >= UNDO_WINDOW)
+                {
+                    self.undo = None;
+                }
+            }
+            PresetMessage::Gui(PresetGuiMessage::Search(msg)) => {
+                let listed = self.listed_presets();
+                return self.preset_bar.search(msg, &listed);
+            }
+            PresetMessage::Gui(msg @ (PresetGuiMessage::ShowSave | PresetGuiMessage::EditTags)) => {
+                // The editor starts from the selected preset's tags and tempo.
+                let selected = self.get_selected_preset().unwrap_or_default();
+                let task = self.preset_bar.handle(msg);
+                self.preset_bar.set_tag_input(format_tags(&selected.tags));
+                self.preset_bar.set_tempo_input(
+                    format_bpm(selected.bpm),
+                    format_time_signature(selected.time_signature),
+                );
+                return task;
+            }
             PresetMessage::Gui(msg) => return self.preset_bar.handle(msg),
             PresetMessage::Select(preset_name) => {
                 if self.selected_preset.as_deref() != Some(preset_name.as_str()) {
@@ -69,16 +162,21 @@ impl PresetHandler {
             }
             PresetMessage::Save(name) => {
                 debug!("Saving preset... {name}");
-                if !name.trim().is_empty() {
-                    self.save_preset_named(
-                        &name,
-                        stages,
-                        ir_name,
-                        ir_gain,
-                        pitch_shift_semitones,
-                        input_filters,
-                    );
-                }
+                self.save_preset_named(
+                    &name,
+                    stages,
+                    ir_name,
+                    ir_gain,
+                    ir_alignment,
+                    pitch_shift_semitones,
+                    input_filters,
+                    denoise,
+                    ambience,
+                    double_tracker,
+                    tilt_offset_db,
+                    collapsed,
+                    macros,
+                );
             }
             PresetMessage::Update => {
                 if let Some(name) = self.selected_preset.clone() {
@@ -87,11 +185,23 @@ impl PresetHandler {
                         stages,
                         ir_name,
                         ir_gain,
+                        ir_alignment,
                         pitch_shift_semitones,
                         input_filters,
+                        denoise,
+                        ambience,
+                        double_tracker,
+                        tilt_offset_db,
+                        collapsed,
+                        macros,
                     );
                 }
             }
+            PresetMessage::Previous => {
+                if let Some(name) = self.previous_preset() {
+                    return Task::done(Message::Preset(PresetMessage::Select(name.to_owned())));
+                }
+            }
             PresetMessage::Delete(preset_name) => {
                 self.delete_preset(&preset_name);
                 if let Some(preset) = self.get_selected_preset() {
@@ -100,19 +210,169 @@ impl PresetHandler {
 
                 return Task::done(Message::SetStages(Vec::new()));
             }
+            PresetMessage::UndoDelete => {
+                if let Some(trashed) = self.undo.take()
+                    && let Some(name) = self.restore_preset(&trashed.path)
+                {
+                    return Task::done(Message::Preset(PresetMessage::Select(name)));
+                }
+            }
+            PresetMessage::Restore(path) => {
+                self.restore_preset(&path);
+            }
+            PresetMessage::Purge(path) => {
+                match self.preset_manager.purge_trashed(&path) {
+                    Ok(()) => debug!("Purged trashed preset {}", path.display()),
+                    Err(e) => error!("Failed to purge trashed preset: {e}"),
+                }
+                if self.undo.as_ref().is_some_and(|t| t.path == path) {
+                    self.undo = None;
+                }
+                let trashed = self.trashed_presets();
+                self.manage_dialog.set_trashed(trashed);
+            }
+            PresetMessage::OpenInbox(name) => {
+                if let Some(preset) = self.preset_manager.get_inbox_preset(&name).cloned() {
+                    debug!("Trying inbox preset: {name}");
+                    self.selected_preset = None;
+                    self.trying_inbox = Some(name);
+                    self.manage_dialog.hide();
+                    return build_preset_load_tasks(preset);
+                }
+            }
+            PresetMessage::KeepInbox(name) => self.keep_inbox_preset(&name),
+            PresetMessage::DiscardInbox(name) => {
+                let was_trying = self.trying_inbox.as_deref() == Some(name.as_str());
+                self.discard_inbox_preset(&name);
+                // The discarded preset was playing; go back to the one before.
+                if was_trying && self.trying_inbox.is_none() {
+                    if let Some(previous) = self.previous_preset() {
+                        return Task::done(Message::Preset(PresetMessage::Select(
+                            previous.to_owned(),
+                        )));
+                    }
+                    return Task::done(Message::SetStages(Vec::new()));
+                }
+            }
+            PresetMessage::ShowDiff => {
+                if let Some(name) = self.selected_preset.clone() {
+                    let current = Preset::new(
+                        name.clone(),
+                        stages,
+                        ir_name,
+                        ir_gain,
+                        pitch_shift_semitones,
+                        input_filters,
+                    )
+                    .with_denoise(denoise)
+                    .with_ambience(ambience)
+                    .with_double_tracker(double_tracker)
+                    .with_tilt_offset_db(tilt_offset_db)
+                    .with_ir_alignment(ir_alignment);
+                    self.show_diff(name, &current);
+                }
+            }
+            PresetMessage::Revert => {
+                self.diff_dialog.hide();
+                if let Some(name) = self.selected_preset.take() {
+                    // Clearing the selection lets the normal select path reload
+                    // the (freshly read) preset even though it's already active.
+                    self.refresh_presets();
+                    return Task::done(Message::Preset(PresetMessage::Select(name)));
+                }
+            }
         }
 
         Task::none()
     }
 
+    /// Select `name` without a task round trip, as a MIDI switch needs: the
+    /// stages come back for the caller to apply straight away, with a task
+    /// loading the rest of the preset. Selecting the preset already selected
+    /// loads it again, dropping unsaved edits, the way pressing a footswitch
+    /// twice resets the sound. `None` if `name` doesn't exist.
+    pub fn select_now(&mut self, name: &str) -> Option<(Vec<StageConfig>, Task<Message>)> {
+        self.load_preset_by_name(name);
+        let mut preset = self
+            .get_selected_preset()
+            .filter(|preset| preset.name == name)?;
+
+        let stages = std::mem::take(&mut preset.stages);
+        let collapsed = std::mem::take(&mut preset.collapsed);
+        let collapse_task = if collapsed.is_empty() {
+            Task::none()
+        } else {
+            Task::done(Message::SetStageCollapse(collapsed))
+        };
+        let macros_task = Task::done(MacroMessage::Set(std::mem::take(&mut preset.macros)).into());
+        Some((
+            stages,
+            Task::batch(vec![
+                collapse_task,
+                macros_task,
+                build_preset_settings_tasks(preset),
+            ]),
+        ))
+    }
+
     pub fn view(&self, read_only: bool) -> Element<'static, Message> {
+        let recent = self
+            .recent
+            .iter()
+            .filter(|n| self.selected_preset.as_ref() != Some(*n))
+            .cloned()
+            .collect();
         self.preset_bar.view(
             self.selected_preset.clone(),
-            self.available_presets.clone(),
+            self.listed_presets(),
+            recent,
+            self.is_selected_missing(),
+            self.undo.as_ref().map(|t| t.name.clone()),
+            InboxView {
+                unsorted: self.inbox.len(),
+                trying: self.trying_inbox.clone(),
+            },
+            TagView {
+                selected: self
+                    .get_selected_preset()
+                    .map(|p| p.tags)
+                    .unwrap_or_default(),
+                known: self.preset_manager.known_tags(),
+            },
             read_only,
         )
     }
 
+    pub fn diff_view(&self) -> Option<Element<'_, Message>> {
+        self.diff_dialog.view()
+    }
+
+    pub fn manage_view(&self) -> Option<Element<'_, Message>> {
+        self.manage_dialog.view()
+    }
+
+    pub const fn is_manage_visible(&self) -> bool {
+        self.manage_dialog.is_visible()
+    }
+
+    /// A delete can still be undone from the preset bar.
+    pub const fn has_pending_undo(&self) -> bool {
+        self.undo.is_some()
+    }
+
+    pub const fn is_search_open(&self) -> bool {
+        self.preset_bar.is_search_open()
+    }
+
+    pub const fn is_diff_visible(&self) -> bool {
+        self.diff_dialog.is_visible()
+    }
+
+    /// Keys for the save-name field or overwrite prompt, if one is showing.
+    pub fn key_task(&self, key: &iced::keyboard::Key) -> Option<Task<Message>> {
+        self.preset_bar.key_task(key)
+    }
+
     pub fn get_selected_preset(&self) -> Option<Preset> {
         self.selected_preset
             .as_ref()
@@ -120,35 +380,204 @@ impl PresetHandler {
             .cloned()
     }
 
+    pub fn selected_name(&self) -> Option<&str> {
+        self.selected_preset.as_deref()
+    }
+
+    /// The selected preset's file is gone from the preset directory. It stays
+    /// selected, and playing, until the user saves it again or picks another.
+    pub fn is_selected_missing(&self) -> bool {
+        self.selected_preset
+            .as_ref()
+            .is_some_and(|name| !self.available_presets.contains(name))
+    }
+
+    /// Re-read the preset directory after files in it changed.
+    pub fn rescan(&mut self) {
+        self.refresh_presets();
+        if self.is_selected_missing() {
+            warn!(
+                "Preset '{}' is no longer in the preset directory",
+                self.selected_preset.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
     pub fn get_available_presets(&self) -> &[String] {
         &self.available_presets
     }
 
+    /// Presets waiting in the inbox, by name.
+    pub fn inbox(&self) -> &[String] {
+        &self.inbox
+    }
+
+    /// The inbox preset loaded to try out, if one is.
+    pub fn trying_inbox(&self) -> Option<&str> {
+        self.trying_inbox.as_deref()
+    }
+
     pub fn selected_preset_index(&self) -> Option<usize> {
         let name = self.selected_preset.as_ref()?;
         self.available_presets.iter().position(|n| n == name)
     }
 
+    /// Recently selected presets, newest first.
+    pub fn recent(&self) -> &[String] {
+        &self.recent
+    }
+
+    /// Restore a saved history, keeping at most `limit` presets. At least two
+    /// are kept, so there's always one to go back to.
+    pub fn set_recent(&mut self, recent: Vec<String>, limit: usize) {
+        self.recent_limit = limit.max(2);
+        self.recent.clear();
+        for name in recent.into_iter().rev() {
+            if self.preset_manager.preset_exists(&name) {
+                self.remember(&name);
+            }
+        }
+    }
+
+    /// The most recent preset other than the selected one that still exists.
+    pub fn previous_preset(&self) -> Option<&str> {
+        self.recent
+            .iter()
+            .filter(|n| self.selected_preset.as_ref() != Some(*n))
+            .find(|n| self.preset_manager.preset_exists(n))
+            .map(String::as_str)
+    }
+
     pub fn get_preset_by_name(&self, name: &str) -> Option<&Preset> {
         self.preset_manager.get_preset_by_name(name)
     }
 
+    /// Rewrite the IR a saved preset uses, keeping the rest of the file.
+    pub fn set_ir_name(&mut self, preset_name: &str, ir_name: Option<String>) {
+        match self.preset_manager.set_ir_name(preset_name, ir_name) {
+            Ok(()) => debug!("Updated IR of preset: {preset_name}"),
+            Err(e) => error!("Failed to update IR of preset '{preset_name}': {e}"),
+        }
+    }
+
     pub fn load_preset_by_name(&mut self, name: &str) {
         if self.preset_manager.get_preset_by_name(name).is_some() {
             self.selected_preset = Some(name.to_owned());
+            self.trying_inbox = None;
+            self.remember(name);
             debug!("Loaded preset: {name}");
         }
     }
 
-    fn delete_preset(&mut self, preset_name: &str) {
-        if let Err(e) = self.preset_manager.delete_preset(preset_name) {
-            error!("Failed to delete preset: {e}");
+    /// Move `name` to the front of the history.
+    fn remember(&mut self, name: &str) {
+        self.recent.retain(|n| n != name);
+        self.recent.insert(0, name.to_owned());
+        self.recent.truncate(self.recent_limit);
+    }
+
+    /// Compare `current` against the saved preset `name`, re-read from disk so
+    /// edits made outside the app are taken into account.
+    fn show_diff(&mut self, name: String, current: &Preset) {
+        self.refresh_presets();
+
+        let Some(saved) = self.preset_manager.get_preset_by_name(&name) else {
+            error!("Preset '{name}' no longer exists on disk");
             return;
+        };
+
+        let changes = diff_presets(saved, current);
+        self.diff_dialog.show(name, changes);
+    }
+
+    /// The presets the bar lists: those with every tag filtered on. A
+    /// filtered tag no preset carries any more is ignored.
+    fn listed_presets(&self) -> Vec<String> {
+        let known = self.preset_manager.known_tags();
+        let filter: Vec<String> = self
+            .preset_bar
+            .tag_filter()
+            .iter()
+            .filter(|tag| known.contains(tag))
+            .cloned()
+            .collect();
+        if filter.is_empty() {
+            return self.available_presets.clone();
         }
+        self.preset_manager
+            .presets_tagged(&filter)
+            .into_iter()
+            .map(|p| p.name.clone())
+            .collect()
+    }
 
-        debug!("Deleted preset: {preset_name}");
+    fn refresh_presets(&mut self) {
+        if let Err(e) = self.preset_manager.refresh() {
+            error!("Failed to reload presets: {e}");
+        }
+        self.available_presets = preset_names(&self.preset_manager);
+        let available = &self.available_presets;
+        self.recent.retain(|n| available.contains(n));
+        self.refresh_inbox();
+    }
+
+    /// Re-list the inbox after the manager reloaded. An inbox preset being
+    /// tried that has gone from it keeps playing, but is no longer offered
+    /// to keep or discard.
+    fn refresh_inbox(&mut self) {
+        self.inbox = inbox_names(&self.preset_manager);
+        if self
+            .trying_inbox
+            .as_ref()
+            .is_some_and(|name| !self.inbox.contains(name))
+        {
+            self.trying_inbox = None;
+        }
+        if self.manage_dialog.is_visible() {
+            self.manage_dialog.set_inbox(self.inbox.clone());
+        }
+    }
+
+    /// Keep `name` from the inbox. If it was being tried it becomes the
+    /// selected preset, under whatever name it was kept as.
+    fn keep_inbox_preset(&mut self, name: &str) {
+        match self.preset_manager.keep_inbox_preset(name) {
+            Ok(kept) => {
+                debug!("Kept inbox preset '{name}' as '{kept}'");
+                self.available_presets = preset_names(&self.preset_manager);
+                if self.trying_inbox.as_deref() == Some(name) {
+                    self.selected_preset = Some(kept.clone());
+                    self.remember(&kept);
+                }
+            }
+            Err(e) => error!("Failed to keep inbox preset '{name}': {e}"),
+        }
+        self.refresh_inbox();
+    }
+
+    fn discard_inbox_preset(&mut self, name: &str) {
+        match self.preset_manager.discard_inbox_preset(name) {
+            Ok(_) => debug!("Moved inbox preset to the trash: {name}"),
+            Err(e) => error!("Failed to discard inbox preset '{name}': {e}"),
+        }
+        self.refresh_inbox();
+        let trashed = self.trashed_presets();
+        self.manage_dialog.set_trashed(trashed);
+    }
+
+    fn delete_preset(&mut self, preset_name: &str) {
+        match self.preset_manager.delete_preset(preset_name) {
+            Ok(trashed) => self.undo = Some(trashed),
+            Err(e) => {
+                error!("Failed to delete preset: {e}");
+                return;
+            }
+        }
+
+        debug!("Moved preset to the trash: {preset_name}");
 
         self.available_presets = preset_names(&self.preset_manager);
+        self.recent.retain(|n| n != preset_name);
 
         if self.selected_preset.as_deref() == Some(preset_name) {
             if let Some(first) = self.available_presets.first() {
@@ -159,15 +588,67 @@ impl PresetHandler {
         }
     }
 
+    /// Bring `path` back out of the trash. Returns the name it was restored
+    /// as.
+    fn restore_preset(&mut self, path: &Path) -> Option<String> {
+        let restored = match self.preset_manager.restore_preset(path) {
+            Ok(name) => {
+                debug!("Restored preset: {name}");
+                Some(name)
+            }
+            Err(e) => {
+                error!("Failed to restore preset: {e}");
+                None
+            }
+        };
+        if self.undo.as_ref().is_some_and(|t| t.path == path) {
+            self.undo = None;
+        }
+        self.available_presets = preset_names(&self.preset_manager);
+        let trashed = self.trashed_presets();
+        self.manage_dialog.set_trashed(trashed);
+        restored
+    }
+
+    fn trashed_presets(&self) -> Vec<TrashedPreset> {
+        self.preset_manager.trashed_presets().unwrap_or_else(|e| {
+            error!("Failed to list the preset trash: {e}");
+            Vec::new()
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn save_preset_named(
         &mut self,
         name: &str,
         stages: Vec<StageConfig>,
         ir_name: Option<String>,
         ir_gain: f32,
+        ir_alignment: IrAlignment,
         pitch_shift_semitones: i32,
         input_filters: InputFilterConfig,
+        denoise: DenoiseConfig,
+        ambience: AmbienceConfig,
+        double_tracker: DoubleTrackerConfig,
+        tilt_offset_db: f32,
+        collapsed: Vec<bool>,
+        macros: Vec<Macro>,
     ) {
+        // Tags and tempo come from the editor when it's open, and are
+        // otherwise kept.
+        let (tags, bpm, time_signature) = if self.preset_bar.is_editing_tags() {
+            let (bpm, time_signature) = self.preset_bar.tempo_input();
+            (
+                parse_tags(self.preset_bar.tag_input()),
+                parse_bpm(bpm),
+                parse_time_signature(time_signature),
+            )
+        } else {
+            self.preset_manager
+                .get_preset_by_name(name)
+                .map(|p| (p.tags.clone(), p.bpm, p.time_signature))
+                .unwrap_or_default()
+        };
         let preset = Preset::new(
             name.to_owned(),
             stages,
@@ -175,16 +656,42 @@ impl PresetHandler {
             ir_gain,
             pitch_shift_semitones,
             input_filters,
-        );
+        )
+        .with_denoise(denoise)
+        .with_ambience(ambience)
+        .with_double_tracker(double_tracker)
+        .with_tilt_offset_db(tilt_offset_db)
+        .with_ir_alignment(ir_alignment)
+        .with_collapsed(collapsed)
+        .with_macros(macros)
+        .with_tags(tags)
+        .with_tempo(bpm, time_signature);
         match self.preset_manager.save_preset(&preset) {
             Ok(()) => {
                 debug!("Saved preset: {name}");
                 self.selected_preset = Some(name.to_owned());
+                self.trying_inbox = None;
+                self.remember(name);
                 self.preset_bar.show_save_input(false);
 
                 self.available_presets = preset_names(&self.preset_manager);
             }
-            Err(e) => error!("Failed to save preset: {e}"),
+            Err(e) => {
+                error!("Failed to save preset: {e}");
+                if let Some(invalid) = e.downcast_ref::<PresetNameError>() {
+                    let reason = match invalid {
+                        PresetNameError::Empty => tr!(preset_name_empty).to_owned(),
+                        PresetNameError::TooLong { chars, max } => {
+                            format!("{} ({chars}/{max})", tr!(preset_name_too_long))
+                        }
+                        PresetNameError::ControlCharacter => {
+                            tr!(preset_name_control_character).to_owned()
+                        }
+                    };
+                    self.preset_bar
+                        .set_save_error(format!("{} {reason}", tr!(preset_name_invalid)));
+                }
+            }
         }
     }
 }
@@ -197,21 +704,373 @@ fn preset_names(manager: &Manager) -> Vec<String> {
         .collect()
 }
 
-fn build_preset_load_tasks(preset: Preset) -> Task<Message> {
-    let set_stage_task = Task::done(Message::SetStages(preset.stages));
+fn inbox_names(manager: &Manager) -> Vec<String> {
+    manager
+        .presets_in(PresetGroup::Inbox)
+        .iter()
+        .map(|p| p.name.clone())
+        .collect()
+}
+
+fn build_preset_load_tasks(mut preset: Preset) -> Task<Message> {
+    // Collapse state and macros must land after the stages they index.
+    // Presets saved without collapse state keep whatever the shell restores
+    // for them; presets without macros clear the previous preset's.
+    let mut set_stage_task =
+        Task::done(Message::SetPresetStages(std::mem::take(&mut preset.stages)));
+    let collapsed = std::mem::take(&mut preset.collapsed);
+    if !collapsed.is_empty() {
+        set_stage_task = set_stage_task.chain(Task::done(Message::SetStageCollapse(collapsed)));
+    }
+    set_stage_task = set_stage_task.chain(Task::done(
+        MacroMessage::Set(std::mem::take(&mut preset.macros)).into(),
+    ));
+
+    Task::batch(vec![set_stage_task, build_preset_settings_tasks(preset)])
+}
+
+/// Everything a preset loads besides its stages and their collapse state.
+fn build_preset_settings_tasks(preset: Preset) -> Task<Message> {
+    // Ahead of the IR itself, so it loads aligned the first time.
+    let set_ir_alignment_task = Task::done(Message::IrAlignmentChanged(preset.ir_alignment));
     let set_ir_task = match preset.ir_name {
-        Some(ir_name) => Task::done(Message::IrSelected(ir_name)),
+        Some(ir_name) => Task::done(Message::MissingIr(MissingIrMessage::Check {
+            preset: preset.name,
+            ir_name,
+        })),
         None => Task::none(),
     };
     let set_ir_gain_task = Task::done(Message::IrGainChanged(preset.ir_gain));
     let set_pitch_shift_task = Task::done(Message::PitchShiftChanged(preset.pitch_shift_semitones));
     let set_input_filters_task = Task::done(Message::SetInputFilters(preset.input_filters));
+    let set_denoise_task = Task::done(Message::Denoise(DenoiseMessage::Set(preset.denoise)));
+    let set_ambience_task = Task::done(Message::AmbienceChanged(preset.ambience));
+    let set_double_tracker_task = Task::done(Message::DoubleTrackerChanged(preset.double_tracker));
+    let set_tilt_offset_task = Task::done(Message::PresetTiltOffsetChanged(preset.tilt_offset_db));
+    // Without a tempo of its own, the metronome keeps the one it has.
+    let set_tempo_task = if preset.bpm.is_some() || preset.time_signature.is_some() {
+        Task::done(Message::PresetTempo {
+            bpm: preset.bpm,
+            time_signature: preset.time_signature,
+        })
+    } else {
+        Task::none()
+    };
 
     Task::batch(vec![
-        set_stage_task,
+        set_ir_alignment_task,
         set_ir_task,
         set_ir_gain_task,
         set_pitch_shift_task,
         set_input_filters_task,
+        set_denoise_task,
+        set_ambience_task,
+        set_double_tracker_task,
+        set_tilt_offset_task,
+        set_tempo_task,
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::PresetMessage;
+
+    fn handler_with(names: &[&str]) -> (tempfile::TempDir, PresetHandler) {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut manager = Manager::new(tmp.path()).unwrap();
+        for name in names {
+            manager
+                .save_preset(&Preset {
+                    name: (*name).to_string(),
+                    ..Preset::default()
+                })
+                .unwrap();
+        }
+        let handler = PresetHandler::new(tmp.path()).unwrap();
+        (tmp, handler)
+    }
+
+    /// Drop a preset into the inbox, as a file received from someone else.
+    fn receive(tmp: &tempfile::TempDir, name: &str) {
+        let preset = Preset {
+            name: name.to_string(),
+            ..Preset::default()
+        };
+        std::fs::write(
+            tmp.path()
+                .join(rustortion_core::preset::INBOX_DIR)
+                .join(format!("{name}.json")),
+            serde_json::to_string(&preset).unwrap(),
+        )
+        .unwrap();
+    }
+
+    fn send(handler: &mut PresetHandler, message: PresetMessage) {
+        let _ = handler.handle(
+            message,
+            Vec::new(),
+            None,
+            1.0,
+            IrAlignment::default(),
+            0,
+            InputFilterConfig::default(),
+            DenoiseConfig::default(),
+            AmbienceConfig::default(),
+            DoubleTrackerConfig::default(),
+            0.0,
+            Vec::new(),
+            Vec::new(),
+        );
+    }
+
+    fn select(handler: &mut PresetHandler, name: &str) {
+        send(handler, PresetMessage::Select(name.to_string()));
+    }
+
+    #[test]
+    fn recent_presets_are_newest_first_and_bounded() {
+        let (_tmp, mut handler) = handler_with(&["A", "B", "C", "D"]);
+        handler.set_recent(Vec::new(), 3);
+        for name in ["B", "C", "B", "D"] {
+            select(&mut handler, name);
+        }
+        assert_eq!(handler.recent(), ["D", "B", "C"]);
+    }
+
+    #[test]
+    fn previous_preset_toggles_between_the_last_two() {
+        let (_tmp, mut handler) = handler_with(&["A", "B", "C"]);
+        select(&mut handler, "B");
+        select(&mut handler, "C");
+        assert_eq!(handler.previous_preset(), Some("B"));
+
+        // What `PresetMessage::Previous` selects; pressed again, it returns.
+        select(&mut handler, "B");
+        assert_eq!(handler.previous_preset(), Some("C"));
+    }
+
+    #[test]
+    fn deleted_presets_drop_out_of_the_history() {
+        let (_tmp, mut handler) = handler_with(&["A", "B", "C"]);
+        for name in ["B", "A", "C"] {
+            select(&mut handler, name);
+        }
+        send(&mut handler, PresetMessage::Delete("A".to_string()));
+        assert_eq!(handler.recent(), ["C", "B"]);
+        assert_eq!(handler.previous_preset(), Some("B"));
+
+        send(&mut handler, PresetMessage::Delete("B".to_string()));
+        assert_eq!(handler.previous_preset(), None);
+    }
+
+    #[test]
+    fn undo_restores_the_deleted_preset_and_selects_it() {
+        let (_tmp, mut handler) = handler_with(&["A", "B"]);
+        select(&mut handler, "B");
+        send(&mut handler, PresetMessage::Delete("B".to_string()));
+        assert!(handler.has_pending_undo());
+        assert_eq!(handler.get_available_presets(), ["A"]);
+
+        send(&mut handler, PresetMessage::UndoDelete);
+        assert!(!handler.has_pending_undo());
+        assert_eq!(handler.get_available_presets(), ["A", "B"]);
+        select(&mut handler, "B");
+        assert_eq!(handler.selected_name(), Some("B"));
+    }
+
+    #[test]
+    fn restored_history_skips_missing_presets() {
+        let (_tmp, mut handler) = handler_with(&["A", "B"]);
+        handler.set_recent(vec!["Gone".into(), "B".into(), "A".into()], 5);
+        assert_eq!(handler.recent(), ["B", "A"]);
+    }
+
+    #[test]
+    fn rescan_marks_a_selected_preset_deleted_on_disk_as_missing() {
+        let (tmp, mut handler) = handler_with(&["A", "B"]);
+        select(&mut handler, "B");
+
+        std::fs::remove_file(tmp.path().join("B.json")).unwrap();
+        handler.rescan();
+        assert_eq!(handler.get_available_presets(), ["A"]);
+        assert_eq!(handler.selected_name(), Some("B"));
+        assert!(handler.is_selected_missing());
+
+        let mut manager = Manager::new(tmp.path()).unwrap();
+        manager
+            .save_preset(&Preset {
+                name: "B".to_string(),
+                ..Preset::default()
+            })
+            .unwrap();
+        handler.rescan();
+        assert!(!handler.is_selected_missing());
+    }
+
+    #[test]
+    fn inbox_presets_are_never_selected_on_startup() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        Manager::new(tmp.path()).unwrap();
+        receive(&tmp, "Aardvark");
+        let handler = PresetHandler::new(tmp.path()).unwrap();
+        assert_eq!(handler.selected_name(), None);
+        assert!(handler.get_available_presets().is_empty());
+        assert_eq!(handler.inbox(), ["Aardvark"]);
+
+        let (tmp, _) = handler_with(&["Lead"]);
+        receive(&tmp, "Aardvark");
+        let handler = PresetHandler::new(tmp.path()).unwrap();
+        assert_eq!(handler.selected_name(), Some("Lead"));
+        assert_eq!(handler.get_available_presets(), ["Lead"]);
+    }
+
+    #[test]
+    fn keeping_a_tried_inbox_preset_selects_it() {
+        let (tmp, mut handler) = handler_with(&["Lead"]);
+        receive(&tmp, "Lead");
+        receive(&tmp, "Crunch");
+        handler.rescan();
+        assert_eq!(handler.inbox(), ["Crunch", "Lead"]);
+
+        send(&mut handler, PresetMessage::OpenInbox("Lead".to_string()));
+        assert_eq!(handler.trying_inbox(), Some("Lead"));
+        assert_eq!(handler.selected_name(), None);
+
+        // The library already has a "Lead", so the kept one is numbered.
+        send(&mut handler, PresetMessage::KeepInbox("Lead".to_string()));
+        assert_eq!(handler.trying_inbox(), None);
+        assert_eq!(handler.selected_name(), Some("Lead (2)"));
+        assert_eq!(handler.get_available_presets(), ["Lead", "Lead (2)"]);
+        assert_eq!(handler.inbox(), ["Crunch"]);
+
+        // Kept from the list without trying it: the selection stays.
+        send(&mut handler, PresetMessage::KeepInbox("Crunch".to_string()));
+        assert_eq!(handler.selected_name(), Some("Lead (2)"));
+        assert!(handler.inbox().is_empty());
+    }
+
+    #[test]
+    fn discarding_an_inbox_preset_trashes_it() {
+        let (tmp, mut handler) = handler_with(&["A", "Lead"]);
+        receive(&tmp, "Crunch");
+        handler.rescan();
+        select(&mut handler, "Lead");
+
+        send(&mut handler, PresetMessage::OpenInbox("Crunch".to_string()));
+        send(
+            &mut handler,
+            PresetMessage::DiscardInbox("Crunch".to_string()),
+        );
+        assert_eq!(handler.trying_inbox(), None);
+        assert!(handler.inbox().is_empty());
+        assert_eq!(handler.get_available_presets(), ["A", "Lead"]);
+        // What the discard goes back to.
+        assert_eq!(handler.previous_preset(), Some("Lead"));
+        assert_eq!(
+            handler
+                .trashed_presets()
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>(),
+            ["Crunch"]
+        );
+    }
+
+    #[test]
+    fn tempo_is_saved_from_the_editor_and_kept_by_updates() {
+        let (_tmp, mut handler) = handler_with(&["Song"]);
+        select(&mut handler, "Song");
+        send(&mut handler, PresetMessage::Gui(PresetGuiMessage::EditTags));
+        send(
+            &mut handler,
+            PresetMessage::Gui(PresetGuiMessage::BpmChanged("96".to_owned())),
+        );
+        send(
+            &mut handler,
+            PresetMessage::Gui(PresetGuiMessage::TimeSignatureChanged("6/8".to_owned())),
+        );
+        send(&mut handler, PresetMessage::Update);
+
+        let saved = handler.get_preset_by_name("Song").unwrap();
+        assert_eq!(saved.bpm, Some(96.0));
+        assert_eq!(saved.time_signature, Some((6, 8)));
+
+        // An update outside the editor keeps it.
+        send(&mut handler, PresetMessage::Update);
+        assert_eq!(handler.get_preset_by_name("Song").unwrap().bpm, Some(96.0));
+
+        // The editor opens on it, and clearing it takes it off.
+        send(&mut handler, PresetMessage::Gui(PresetGuiMessage::EditTags));
+        assert_eq!(handler.preset_bar.tempo_input(), ("96", "6/8"));
+        send(
+            &mut handler,
+            PresetMessage::Gui(PresetGuiMessage::BpmChanged(String::new())),
+        );
+        send(&mut handler, PresetMessage::Update);
+        let cleared = handler.get_preset_by_name("Song").unwrap();
+        assert_eq!(cleared.bpm, None);
+        assert_eq!(cleared.time_signature, Some((6, 8)));
+    }
+
+    #[test]
+    fn tags_are_saved_from_the_editor_and_filter_the_list() {
+        let (_tmp, mut handler) = handler_with(&["Clean", "Lead", "Rhythm"]);
+        for (name, tags) in [("Lead", "Metal, gig"), ("Rhythm", "metal")] {
+            select(&mut handler, name);
+            send(&mut handler, PresetMessage::Gui(PresetGuiMessage::EditTags));
+            send(
+                &mut handler,
+                PresetMessage::Gui(PresetGuiMessage::TagsChanged(tags.to_owned())),
+            );
+            send(&mut handler, PresetMessage::Update);
+        }
+        assert_eq!(
+            handler.get_preset_by_name("Lead").unwrap().tags,
+            ["metal", "gig"]
+        );
+        assert!(!handler.preset_bar.is_editing_tags());
+
+        // An update outside the editor keeps the preset's tags.
+        send(&mut handler, PresetMessage::Update);
+        assert_eq!(
+            handler.get_preset_by_name("Rhythm").unwrap().tags,
+            ["metal"]
+        );
+
+        assert_eq!(handler.listed_presets(), ["Clean", "Lead", "Rhythm"]);
+        for tag in ["metal", "gig"] {
+            send(
+                &mut handler,
+                PresetMessage::Gui(PresetGuiMessage::ToggleTagFilter(tag.to_owned())),
+            );
+        }
+        assert_eq!(handler.listed_presets(), ["Lead"]);
+        send(
+            &mut handler,
+            PresetMessage::Gui(PresetGuiMessage::ToggleTagFilter("gig".to_owned())),
+        );
+        assert_eq!(handler.listed_presets(), ["Lead", "Rhythm"]);
+        send(
+            &mut handler,
+            PresetMessage::Gui(PresetGuiMessage::ClearTagFilter),
+        );
+        assert_eq!(handler.listed_presets(), ["Clean", "Lead", "Rhythm"]);
+    }
+
+    #[test]
+    fn select_now_reloads_the_selected_preset_and_ignores_unknown_ones() {
+        let (_tmp, mut handler) = handler_with(&["A", "B"]);
+        select(&mut handler, "A");
+
+        assert!(handler.select_now("B").is_some());
+        assert_eq!(handler.get_selected_preset().unwrap().name, "B");
+        assert_eq!(handler.previous_preset(), Some("A"));
+
+        // A second press loads it again, and "previous" still goes back.
+        assert!(handler.select_now("B").is_some());
+        assert_eq!(handler.previous_preset(), Some("A"));
+        assert!(handler.select_now("Gone").is_none());
+        assert_eq!(handler.get_selected_preset().unwrap().name, "B");
+    }
+}
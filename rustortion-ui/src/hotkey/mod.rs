@@ -1,6 +1,35 @@
+use std::borrow::Cow;
+
 use iced::keyboard::{Key, Modifiers};
+use rustortion_core::preset::QUICK_SLOTS;
 use serde::{Deserialize, Serialize};
 
+use crate::mappings::{Binding, BindingSource, Trigger};
+
+/// What a hotkey does when pressed.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Load `preset_name`.
+    #[default]
+    LoadPreset,
+    /// Mute and show the tuner while the key is held.
+    HoldTuner,
+    /// Switch back to the previously selected preset.
+    PreviousPreset,
+    /// Randomize the chain at the intensity picked next to the button.
+    Randomize,
+    /// Step to the next song of the running setlist.
+    NextSong,
+    /// Step back to the previous song of the running setlist.
+    PreviousSong,
+    /// Put the amp into standby, or take it out.
+    ToggleStandby,
+    /// Save the current chain to this quick slot, overwriting it.
+    SaveSlot(u8),
+    /// Load this quick slot.
+    LoadSlot(u8),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct HotkeyMapping {
     /// Serialized key name (e.g. "F1", "1", "a")
@@ -11,6 +40,8 @@ pub struct HotkeyMapping {
     pub preset_name: String,
     /// Human-readable description (e.g. "Ctrl+F1")
     pub description: String,
+    #[serde(default)]
+    pub action: HotkeyAction,
 }
 
 impl HotkeyMapping {
@@ -23,9 +54,113 @@ impl HotkeyMapping {
             modifiers,
             preset_name,
             description,
+            action: HotkeyAction::LoadPreset,
+        }
+    }
+
+    /// A mapping that holds the tuner on while the key is down.
+    pub fn hold_tuner(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::HoldTuner,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that switches back to the previous preset.
+    pub fn previous_preset(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::PreviousPreset,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that randomizes the chain.
+    pub fn randomize(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::Randomize,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that steps to the next song of the setlist.
+    pub fn next_song(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::NextSong,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that steps back to the previous song of the setlist.
+    pub fn previous_song(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::PreviousSong,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that toggles standby.
+    pub fn toggle_standby(key: String, modifiers: Vec<String>) -> Self {
+        Self {
+            action: HotkeyAction::ToggleStandby,
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that saves the chain to quick slot `slot`.
+    pub fn save_slot(key: String, modifiers: Vec<String>, slot: u8) -> Self {
+        Self {
+            action: HotkeyAction::SaveSlot(slot),
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// A mapping that loads quick slot `slot`.
+    pub fn load_slot(key: String, modifiers: Vec<String>, slot: u8) -> Self {
+        Self {
+            action: HotkeyAction::LoadSlot(slot),
+            ..Self::new(key, modifiers, String::new())
+        }
+    }
+
+    /// What the mapping does, for display: the preset name, "Hold to tune"
+    /// or "Previous preset".
+    pub fn target_label(&self) -> Cow<'_, str> {
+        match self.action {
+            HotkeyAction::LoadPreset => Cow::Borrowed(&self.preset_name),
+            HotkeyAction::HoldTuner => Cow::Borrowed(crate::tr!(hold_tuner)),
+            HotkeyAction::PreviousPreset => Cow::Borrowed(crate::tr!(previous_preset)),
+            HotkeyAction::Randomize => Cow::Borrowed(crate::tr!(randomize)),
+            HotkeyAction::NextSong => Cow::Borrowed(crate::tr!(next_song)),
+            HotkeyAction::PreviousSong => Cow::Borrowed(crate::tr!(previous_song)),
+            HotkeyAction::ToggleStandby => Cow::Borrowed(crate::tr!(toggle_standby)),
+            HotkeyAction::SaveSlot(slot) => {
+                Cow::Owned(format!("{} {slot}", crate::tr!(save_to_slot)))
+            }
+            HotkeyAction::LoadSlot(slot) => Cow::Owned(format!("{} {slot}", crate::tr!(load_slot))),
         }
     }
 
+    pub fn trigger(&self) -> Trigger {
+        Trigger::key(&self.key, &self.modifiers)
+    }
+
+    /// This mapping as the `index`th hotkey in the mappings overview.
+    pub fn binding(&self, index: usize) -> Binding {
+        Binding {
+            source: BindingSource::Hotkey,
+            index,
+            trigger: self.trigger(),
+            description: self.description.clone(),
+            target: self.target_label().into_owned(),
+        }
+    }
+
+    /// Check a key release against this mapping. Modifiers are ignored, since
+    /// they're often let go before the key itself.
+    pub fn matches_release(&self, key: &Key) -> bool {
+        serialize_key(key).is_some_and(|k| k == self.key)
+    }
+
     /// Check if a key event matches this mapping
     pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
         let key_str = serialize_key(key);
@@ -255,6 +390,50 @@ mod tests {
         let key = Key::Character("1".into());
         assert!(mapping.matches(&key, Modifiers::empty()));
     }
+
+    #[test]
+    fn test_mapping_without_action_loads_preset() {
+        let json = r#"{"key":"F1","modifiers":[],"preset_name":"Clean","description":"F1"}"#;
+        let mapping: HotkeyMapping = serde_json::from_str(json).unwrap();
+        assert_eq!(mapping.action, HotkeyAction::LoadPreset);
+    }
+
+    #[test]
+    fn test_quick_slot_defaults() {
+        let settings = HotkeySettings::with_quick_slots();
+        assert_eq!(settings.mappings.len(), 2 * usize::from(QUICK_SLOTS));
+
+        let key = Key::Character("3".into());
+        let save = settings
+            .mappings
+            .iter()
+            .find(|m| m.matches(&key, Modifiers::CTRL | Modifiers::SHIFT))
+            .unwrap();
+        assert_eq!(save.action, HotkeyAction::SaveSlot(3));
+        assert_eq!(save.description, "Ctrl+Shift+3");
+        let load = settings
+            .mappings
+            .iter()
+            .find(|m| m.matches(&key, Modifiers::CTRL))
+            .unwrap();
+        assert_eq!(load.action, HotkeyAction::LoadSlot(3));
+    }
+
+    #[test]
+    fn test_slot_action_round_trips() {
+        let mapping = HotkeyMapping::save_slot("1".to_string(), vec!["Ctrl".to_string()], 1);
+        let json = serde_json::to_string(&mapping).unwrap();
+        let restored: HotkeyMapping = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, mapping);
+    }
+
+    #[test]
+    fn test_hold_tuner_release_ignores_modifiers() {
+        let mapping = HotkeyMapping::hold_tuner("t".to_string(), vec!["Ctrl".to_string()]);
+        assert_eq!(mapping.action, HotkeyAction::HoldTuner);
+        assert!(mapping.matches_release(&Key::Character("t".into())));
+        assert!(!mapping.matches_release(&Key::Character("u".into())));
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -262,11 +441,32 @@ pub struct HotkeySettings {
     pub mappings: Vec<HotkeyMapping>,
 }
 
+impl HotkeySettings {
+    /// The mappings a fresh install starts with: Ctrl+Shift+1..8 save the
+    /// chain to a quick slot and Ctrl+1..8 load it back.
+    pub fn with_quick_slots() -> Self {
+        let mappings = (1..=QUICK_SLOTS)
+            .flat_map(|slot| {
+                let key = slot.to_string();
+                [
+                    HotkeyMapping::save_slot(
+                        key.clone(),
+                        vec!["Ctrl".to_string(), "Shift".to_string()],
+                        slot,
+                    ),
+                    HotkeyMapping::load_slot(key, vec!["Ctrl".to_string()], slot),
+                ]
+            })
+            .collect();
+        Self { mappings }
+    }
+}
+
 impl std::fmt::Display for HotkeySettings {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Mappings:")?;
         for mapping in &self.mappings {
-            writeln!(f, "  {} → {}", mapping.description, mapping.preset_name)?;
+            writeln!(f, "  {} → {}", mapping.description, mapping.target_label())?;
         }
         Ok(())
     }
@@ -74,31 +74,96 @@ pub struct Translations {
     // Top bar buttons
     pub midi: &'static str,
     pub tuner: &'static str,
+    pub setlist: &'static str,
     pub settings: &'static str,
+    pub view_menu: &'static str,
+    pub stage_levels: &'static str,
 
     // Audio Settings dialog
     pub audio_settings: &'static str,
     pub input_port: &'static str,
+    pub input_right_port: &'static str,
+    pub input_right_none: &'static str,
     pub output_left_port: &'static str,
     pub output_right_port: &'static str,
     pub buffer_size_requested: &'static str,
     pub sample_rate_requested: &'static str,
+    pub resampler_quality: &'static str,
+    pub adaptive_quality: &'static str,
+    pub dual_mono: &'static str,
+    pub dual_mono_needs_right: &'static str,
     pub oversampling_factor: &'static str,
     pub actual_latency: &'static str,
+    pub latency_base: &'static str,
+    pub latency_oversampling: &'static str,
+    pub latency_denoiser: &'static str,
+    pub latency_pitch_shifter: &'static str,
+    pub latency_cabinet: &'static str,
     pub changes_require_restart: &'static str,
     pub jack_server_status: &'static str,
     pub sample_rate: &'static str,
     pub buffer_size: &'static str,
     pub jack_different_settings: &'static str,
+    pub cpal_stream_status: &'static str,
+    pub cpal_different_settings: &'static str,
+    pub audio_api: &'static str,
+    pub input_device: &'static str,
+    pub output_device: &'static str,
+    pub system_default_device: &'static str,
+    pub cpal_limitations: &'static str,
     pub refresh_ports: &'static str,
     pub nam_models_dir: &'static str,
     pub nam_rescan_models: &'static str,
+    pub captures_dir: &'static str,
+    pub capture_rescan: &'static str,
+    pub record_follow_transport: &'static str,
+    pub record_automation: &'static str,
+    pub recording_softclip: &'static str,
+    pub tuner_algorithm: &'static str,
+    pub watchdog_auto_restart: &'static str,
+    pub watchdog_stall_seconds: &'static str,
+    pub remote_control: &'static str,
+    pub remote_port: &'static str,
+    pub hooks_enabled: &'static str,
+    pub hooks_security_note: &'static str,
+    pub hook_command_placeholder: &'static str,
+    pub hook_recording_started: &'static str,
+    pub hook_recording_stopped: &'static str,
+    pub hook_preset_changed: &'static str,
+    pub hook_audio_disconnected: &'static str,
+    pub test_source: &'static str,
+    pub chain_lint: &'static str,
+    pub keep_locked_stages: &'static str,
+    pub quantize_chain_changes: &'static str,
+    pub preset_pending: &'static str,
+    pub test_source_frequency: &'static str,
+    pub test_di_file: &'static str,
+    pub load_test_di: &'static str,
+    pub metronome_tick: &'static str,
+    pub metronome_tick_bundled: &'static str,
+    pub load_metronome_tick: &'static str,
+    pub metronome: &'static str,
+    pub metronome_bpm: &'static str,
+    pub metronome_time_signature: &'static str,
+    pub click_in_monitor: &'static str,
+    pub record_click_track: &'static str,
+    pub copy_diagnostics: &'static str,
+    pub calibrate_input: &'static str,
+    pub gain_staging: &'static str,
+    pub tone_match: &'static str,
     pub cancel: &'static str,
     pub apply: &'static str,
     pub language: &'static str,
+    pub theme: &'static str,
+    pub theme_follow_system: &'static str,
 
     // Tuner dialog
     pub tuner_title: &'static str,
+    pub tuner_unsteady: &'static str,
+    pub tuner_strobe: &'static str,
+    pub tuner_lock_note: &'static str,
+    pub tuner_unlock: &'static str,
+    pub tuner_harmonic: &'static str,
     pub in_tune: &'static str,
     pub adjust: &'static str,
     pub play_a_note: &'static str,
@@ -111,6 +176,7 @@ pub struct Translations {
     pub controller: &'static str,
     pub connected: &'static str,
     pub not_connected: &'static str,
+    pub midi_waiting_for_device: &'static str,
     pub device: &'static str,
     pub select_midi_controller: &'static str,
     pub disconnect: &'static str,
@@ -120,19 +186,91 @@ pub struct Translations {
     pub captured: &'static str,
     pub assign_to: &'static str,
     pub select_preset: &'static str,
+    pub hold_tuner: &'static str,
+    pub hold_tuner_instead: &'static str,
+    pub previous_preset: &'static str,
+    pub previous_preset_instead: &'static str,
+    pub next_song: &'static str,
+    pub previous_song: &'static str,
+    pub next_song_instead: &'static str,
+    pub previous_song_instead: &'static str,
+    pub toggle_standby: &'static str,
+    pub toggle_standby_instead: &'static str,
+    pub toggle_ir_bypass: &'static str,
+    pub toggle_ir_bypass_instead: &'static str,
+    pub mapping_mode: &'static str,
+    pub mapping_mode_trigger: &'static str,
+    pub mapping_mode_momentary: &'static str,
+    pub mapping_mode_toggle: &'static str,
+    pub randomize_instead: &'static str,
+    pub save_slot_instead: &'static str,
+    pub load_slot_instead: &'static str,
+    pub save_to_slot: &'static str,
+    pub load_slot: &'static str,
+    pub quick_slot: &'static str,
     pub confirm_mapping: &'static str,
     pub no_mappings_configured: &'static str,
+    pub hotkey: &'static str,
+    pub mappings: &'static str,
+    pub filter_mappings: &'static str,
+    pub trigger: &'static str,
+    pub target: &'static str,
+    pub source: &'static str,
+    pub mapping_replaces: &'static str,
+    pub mapping_duplicate: &'static str,
+    pub mapping_shadows_typing: &'static str,
+    pub no_matching_mappings: &'static str,
     pub debug_log: &'static str,
     pub no_midi_messages: &'static str,
     pub refresh_controllers: &'static str,
 
     // Control bar
     pub add_stage: &'static str,
+    pub randomize: &'static str,
+    pub chain_template: &'static str,
+    pub randomize_subtle: &'static str,
+    pub randomize_medium: &'static str,
+    pub randomize_wild: &'static str,
+    pub undo: &'static str,
     pub collapse_all: &'static str,
     pub expand_all: &'static str,
+    pub collapse_all_except_selected: &'static str,
     pub stop_recording: &'static str,
     pub start_recording: &'static str,
     pub recording: &'static str,
+    pub recording_failed: &'static str,
+    pub recording_softclipped: &'static str,
+    pub render_file: &'static str,
+    pub render_title: &'static str,
+    pub input_file: &'static str,
+    pub output_file: &'static str,
+    pub automation_file: &'static str,
+    pub render: &'static str,
+    pub rendering: &'static str,
+    pub render_done: &'static str,
+    pub render_failed: &'static str,
+    pub render_hint: &'static str,
+    pub ir_capture: &'static str,
+    pub ir_capture_hint: &'static str,
+    pub ir_capture_length: &'static str,
+    pub capture_ir: &'static str,
+    pub ir_capture_done: &'static str,
+    pub bounce: &'static str,
+    pub bounce_title: &'static str,
+    pub bounce_hint: &'static str,
+    pub bounce_mix: &'static str,
+    pub bouncing: &'static str,
+    pub bounce_done: &'static str,
+    pub compare_takes: &'static str,
+    pub takes_title: &'static str,
+    pub takes_hint: &'static str,
+    pub takes_none: &'static str,
+    pub takes_failed: &'static str,
+    pub takes_normalize: &'static str,
+    pub takes_reload: &'static str,
+    pub takes_play: &'static str,
+    pub takes_pause: &'static str,
+    pub takes_rewind: &'static str,
 
     // IR Cabinet control
     pub cabinet_ir: &'static str,
@@ -141,6 +279,44 @@ pub struct Translations {
     pub gain: &'static str,
     pub active: &'static str,
     pub no_ir_loaded: &'static str,
+    pub shuffle: &'static str,
+    pub ir_step_hint: &'static str,
+    pub ir_preview_waveform: &'static str,
+    pub ir_preview_spectrum: &'static str,
+    pub ir_compare: &'static str,
+    pub ir_compare_add: &'static str,
+    pub ir_compare_next: &'static str,
+    pub ir_compare_keep: &'static str,
+    pub ir_compare_exit: &'static str,
+    pub ir_compare_measuring: &'static str,
+    pub ir_compare_hint: &'static str,
+    pub ir_tags: &'static str,
+    pub ir_tags_all: &'static str,
+    pub ir_offset: &'static str,
+    pub ir_invert_polarity: &'static str,
+    pub ir_auto_align: &'static str,
+    pub missing_ir: &'static str,
+    pub missing_ir_use: &'static str,
+    pub missing_ir_choose: &'static str,
+    pub missing_ir_continue: &'static str,
+    pub missing_ir_write_back: &'static str,
+    pub preset_missing: &'static str,
+    pub ir_rejected: &'static str,
+    pub ir_mono: &'static str,
+    pub ir_stereo: &'static str,
+    pub audio_stalled: &'static str,
+    pub audio_stall_restarted: &'static str,
+    pub audio_stall_restart_failed: &'static str,
+    pub audio_error_server_not_found: &'static str,
+    pub audio_error_client: &'static str,
+    pub audio_error_ports: &'static str,
+    pub audio_error_engine: &'static str,
+    pub audio_error_device: &'static str,
+    pub audio_error_freewheel: &'static str,
+    pub preset_error_dir: &'static str,
+    pub startup_error_title: &'static str,
+    pub quit: &'static str,
+    pub restart_audio: &'static str,
 
     // Preset bar
     pub preset: &'static str,
@@ -148,10 +324,43 @@ pub struct Translations {
     pub yes: &'static str,
     pub no: &'static str,
     pub preset_name_placeholder: &'static str,
+    pub tags: &'static str,
+    pub tags_placeholder: &'static str,
+    pub bpm: &'static str,
+    pub time_signature_placeholder: &'static str,
+    pub tags_and_tempo: &'static str,
+    pub filter_by_tags: &'static str,
+    pub clear_filter: &'static str,
+    pub preset_name_invalid: &'static str,
+    pub preset_name_empty: &'static str,
+    pub preset_name_too_long: &'static str,
+    pub preset_name_control_character: &'static str,
     pub save: &'static str,
     pub save_as: &'static str,
     pub update: &'static str,
     pub delete: &'static str,
+    pub preset_deleted: &'static str,
+    pub manage_presets: &'static str,
+    pub recently_deleted: &'static str,
+    pub restore: &'static str,
+    pub delete_forever: &'static str,
+    pub trash_empty: &'static str,
+    pub inbox: &'static str,
+    pub inbox_empty: &'static str,
+    pub inbox_trying: &'static str,
+    pub try_preset: &'static str,
+    pub keep: &'static str,
+    pub discard: &'static str,
+    pub minutes_ago: &'static str,
+    pub hours_ago: &'static str,
+    pub days_ago: &'static str,
+    pub compare_saved: &'static str,
+    pub preset_changes: &'static str,
+    pub no_changes: &'static str,
+    pub revert: &'static str,
+    pub diff_added: &'static str,
+    pub diff_removed: &'static str,
+    pub diff_moved: &'static str,
 
     // Stage names
     pub stage_filter: &'static str,
@@ -166,6 +375,9 @@ pub struct Translations {
     pub stage_reverb: &'static str,
     pub stage_eq: &'static str,
     pub stage_tremolo: &'static str,
+    pub stage_wah: &'static str,
+    pub stage_group: &'static str,
+    pub group_add_stage: &'static str,
     pub stage_nam: &'static str,
     pub nam_model: &'static str,
     pub nam_no_model: &'static str,
@@ -175,8 +387,32 @@ pub struct Translations {
     pub nam_input_gain: &'static str,
     pub nam_output_gain: &'static str,
     pub nam_mix: &'static str,
+    pub stage_capture: &'static str,
+    pub capture_file: &'static str,
+    pub capture_none: &'static str,
+    pub capture_not_found: &'static str,
+    pub capture_taps: &'static str,
+    pub capture_input_gain: &'static str,
+    pub capture_output_gain: &'static str,
+    pub capture_mix: &'static str,
     pub stage_bypass: &'static str,
     pub stage_bypass_tooltip: &'static str,
+    pub stage_solo_tooltip: &'static str,
+    pub stage_lock_tooltip: &'static str,
+    pub solo_indicator: &'static str,
+    pub solo_keep_post: &'static str,
+    pub solo_indicator_tooltip: &'static str,
+    pub standby: &'static str,
+    pub standby_muted: &'static str,
+    pub standby_tooltip_on: &'static str,
+    pub standby_tooltip_off: &'static str,
+    pub stage_in_trim: &'static str,
+    pub stage_out_trim: &'static str,
+    pub stage_invert_tooltip: &'static str,
+    pub lint_gate_after_gain: &'static str,
+    pub lint_leading_level: &'static str,
+    pub lint_multiple_power_amps: &'static str,
+    pub lint_stacked_tone_stacks: &'static str,
 
     // Stage parameters
     pub clipper: &'static str,
@@ -195,23 +431,34 @@ pub struct Translations {
     pub drive: &'static str,
     pub sag: &'static str,
     pub sag_release: &'static str,
+    pub auto_level: &'static str,
     pub cutoff: &'static str,
     pub hold: &'static str,
     pub low_band: &'static str,
     pub mid_band: &'static str,
     pub high_band: &'static str,
+    pub band_mute_tooltip: &'static str,
+    pub band_solo_tooltip: &'static str,
     pub low_freq: &'static str,
     pub high_freq: &'static str,
     pub level: &'static str,
     pub crossover: &'static str,
     pub delay_time: &'static str,
+    pub tempo_sync: &'static str,
+    pub note_division: &'static str,
     pub feedback: &'static str,
     pub dry_wet: &'static str,
+    pub duck_depth: &'static str,
+    pub duck_release: &'static str,
     pub room_size: &'static str,
     pub damping: &'static str,
     pub rate: &'static str,
     pub depth: &'static str,
     pub shape: &'static str,
+    pub wah_position: &'static str,
+    pub wah_q: &'static str,
+    pub sensitivity: &'static str,
+    pub mode: &'static str,
 
     // Filter types
     pub filter_highpass: &'static str,
@@ -238,16 +485,38 @@ pub struct Translations {
 
     // Pitch shift control
     pub pitch_shift: &'static str,
+    pub ambience: &'static str,
+    pub ambience_amount: &'static str,
+    pub ambience_decay: &'static str,
+    pub ambience_tone: &'static str,
+    pub double_tracker: &'static str,
+    pub double_tracker_mix: &'static str,
+    pub double_tracker_width: &'static str,
+    pub double_tracker_delay: &'static str,
+    pub double_tracker_detune: &'static str,
+    pub denoise: &'static str,
+    pub denoise_learn: &'static str,
+    pub denoise_learning: &'static str,
+    pub denoise_no_profile: &'static str,
+    pub denoise_learned: &'static str,
+    pub denoise_learn_failed: &'static str,
+    pub denoise_reduction: &'static str,
+    pub tilt: &'static str,
+    pub tilt_preset_offset: &'static str,
     pub semitones: &'static str,
     pub pitch_bypass: &'static str,
 
     // Misc UI labels
     pub output: &'static str,
+    pub output_left: &'static str,
+    pub output_right: &'static str,
     pub samples: &'static str,
     pub requested: &'static str,
     pub hz: &'static str,
     pub db: &'static str,
     pub ms: &'static str,
+    pub search_placeholder: &'static str,
+    pub no_matches: &'static str,
 
     // Hotkey dialog
     pub hotkey_settings: &'static str,
@@ -268,6 +537,111 @@ pub struct Translations {
     // Peak meter / status
     pub xruns: &'static str,
     pub cpu: &'static str,
+    pub reduced_quality: &'static str,
+
+    // Input calibration dialog
+    pub calibration_title: &'static str,
+    pub calibration_instructions: &'static str,
+    pub calibration_current_trim: &'static str,
+    pub calibration_not_calibrated: &'static str,
+    pub calibration_start: &'static str,
+    pub calibration_listening: &'static str,
+    pub calibration_peak: &'static str,
+    pub calibration_recommended: &'static str,
+    pub calibration_no_signal: &'static str,
+    pub calibration_retry: &'static str,
+
+    // Gain staging dialog
+    pub gain_staging_title: &'static str,
+    pub gain_staging_instructions: &'static str,
+    pub gain_staging_start: &'static str,
+    pub gain_staging_listening: &'static str,
+    pub gain_staging_retry: &'static str,
+    pub gain_staging_empty_chain: &'static str,
+    pub gain_staging_bypassed: &'static str,
+    pub gain_staging_peaks_at: &'static str,
+    pub gain_staging_all_good: &'static str,
+    pub gain_staging_fix_set_level: &'static str,
+    pub gain_staging_fix_insert_level: &'static str,
+    pub gain_staging_fix_manual: &'static str,
+    pub gain_staging_apply: &'static str,
+    pub tone_match_title: &'static str,
+    pub tone_match_instructions: &'static str,
+    pub tone_match_source: &'static str,
+    pub tone_match_reference_preset: &'static str,
+    pub tone_match_reference_file: &'static str,
+    pub tone_match_start: &'static str,
+    pub tone_match_running: &'static str,
+    pub tone_match_failed: &'static str,
+    pub tone_match_largest: &'static str,
+    pub tone_match_apply: &'static str,
+    pub tone_match_chain_full: &'static str,
+
+    // Setlist dialog
+    pub setlist_title: &'static str,
+    pub setlist_new: &'static str,
+    pub setlist_name: &'static str,
+    pub setlist_empty: &'static str,
+    pub setlist_notes: &'static str,
+    pub setlist_add_song: &'static str,
+    pub setlist_start: &'static str,
+    pub setlist_stop: &'static str,
+    pub setlist_missing_preset: &'static str,
+    pub setlist_next_up: &'static str,
+    pub setlist_last_song: &'static str,
+    pub setlist_select: &'static str,
+
+    // Drag-and-drop import
+    pub import_done: &'static str,
+    pub import_failed: &'static str,
+
+    // Macros
+    pub macros: &'static str,
+    pub add_macro: &'static str,
+    pub new_macro_name: &'static str,
+    pub edit_macro: &'static str,
+    pub macro_name: &'static str,
+    pub macro_targets: &'static str,
+    pub add_macro_target: &'static str,
+    pub remove_macro: &'static str,
+    pub macro_stage: &'static str,
+    pub macro_parameter: &'static str,
+    pub macro_from: &'static str,
+    pub macro_to: &'static str,
+    pub macro_curve: &'static str,
+    pub macro_target_invalid: &'static str,
+    pub no_macro_targets: &'static str,
+    pub no_macros: &'static str,
+
+    // First-run setup
+    pub setup_title: &'static str,
+    pub setup_step: &'static str,
+    pub setup_skip: &'static str,
+    pub setup_back: &'static str,
+    pub setup_next: &'static str,
+    pub setup_finish: &'static str,
+    pub setup_retry: &'static str,
+    pub setup_audio_heading: &'static str,
+    pub setup_server_jack: &'static str,
+    pub setup_server_pipewire_jack: &'static str,
+    pub setup_server_pipewire_no_jack: &'static str,
+    pub setup_server_none: &'static str,
+    pub setup_running_cpal: &'static str,
+    pub setup_waiting_audio: &'static str,
+    pub setup_ports_heading: &'static str,
+    pub setup_ports_hint: &'static str,
+    pub setup_input_level: &'static str,
+    pub setup_folders_heading: &'static str,
+    pub setup_folders_hint: &'static str,
+    pub setup_recording_dir: &'static str,
+    pub setup_ir_dir: &'static str,
+    pub setup_preset_dir: &'static str,
+    pub setup_install_presets: &'static str,
+    pub setup_folders_failed: &'static str,
+    pub setup_midi_heading: &'static str,
+    pub setup_midi_hint: &'static str,
+    pub setup_midi_open: &'static str,
+    pub run_setup: &'static str,
 }
 
 impl Translations {
@@ -283,31 +657,96 @@ pub static EN: Translations = Translations {
     // Top bar buttons
     midi: "Midi",
     tuner: "Tuner",
+    setlist: "Setlist",
     settings: "Settings",
+    view_menu: "View",
+    stage_levels: "Stage levels",
 
     // Audio Settings dialog
     audio_settings: "Audio Settings",
     input_port: "Input Port:",
+    input_right_port: "Right Input Port:",
+    input_right_none: "None",
     output_left_port: "Output Left Port:",
     output_right_port: "Output Right Port:",
     buffer_size_requested: "Buffer Size* (requested):",
     sample_rate_requested: "Sample Rate* (requested):",
+    resampler_quality: "Resampler Quality*:",
+    adaptive_quality: "Reduce quality under heavy load instead of crackling",
+    dual_mono: "Process left and right inputs separately (dual-mono, roughly doubles CPU)",
+    dual_mono_needs_right: "Needs a right input port",
     oversampling_factor: "Oversampling Factor:",
     actual_latency: "Actual Latency:",
+    latency_base: "Base",
+    latency_oversampling: "oversampling",
+    latency_denoiser: "denoiser",
+    latency_pitch_shifter: "pitch shifter",
+    latency_cabinet: "cabinet",
     changes_require_restart: "* Changes require restart",
     jack_server_status: "JACK Server Status",
     sample_rate: "Sample Rate:",
     buffer_size: "Buffer Size:",
     jack_different_settings: "JACK is using different settings than requested. This may be controlled by PipeWire/JACK server configuration.",
+    cpal_stream_status: "System Audio Status (CPAL)",
+    cpal_different_settings: "The audio devices are running at different settings than requested, as close as they support.",
+    audio_api: "Audio API",
+    input_device: "Input Device",
+    output_device: "Output Device",
+    system_default_device: "System default",
+    cpal_limitations: "System audio is a fallback: no port routing or transport sync, the metronome plays through the main outputs, and latency is fixed when the devices open.",
     refresh_ports: "Refresh Ports",
     nam_models_dir: "NAM Models Directory",
     nam_rescan_models: "Rescan Models",
+    captures_dir: "Captures Directory",
+    capture_rescan: "Rescan Captures",
+    record_follow_transport: "Start/stop recording with JACK transport",
+    record_automation: "Save knob moves with recordings (automation)",
+    recording_softclip: "Soft-clip recordings above -6 dBFS (monitoring unaffected)",
+    tuner_algorithm: "Tuner detection:",
+    watchdog_auto_restart: "Restart audio automatically when it stalls",
+    watchdog_stall_seconds: "Stall threshold (s):",
+    remote_control: "OSC remote control",
+    remote_port: "Port:",
+    hooks_enabled: "Run hook commands on events",
+    hooks_security_note: "Hooks run any command typed here, with your permissions. Only use commands you trust.",
+    hook_command_placeholder: "Command, given the path or name as $1 (Enter to save)",
+    hook_recording_started: "Recording started",
+    hook_recording_stopped: "Recording stopped",
+    hook_preset_changed: "Preset changed",
+    hook_audio_disconnected: "Audio disconnected",
+    test_source: "Test signal:",
+    chain_lint: "Warn about unusual stage orderings",
+    keep_locked_stages: "Keep locked stages when switching presets",
+    quantize_chain_changes: "Switch a preset's amp chain on the next bar while the metronome runs",
+    preset_pending: "Chain on next bar:",
+    test_source_frequency: "Frequency:",
+    test_di_file: "DI file (WAV)",
+    load_test_di: "Load",
+    metronome_tick: "Metronome Click",
+    metronome_tick_bundled: "Built-in ticks",
+    load_metronome_tick: "Load",
+    metronome: "Metronome",
+    metronome_bpm: "BPM",
+    metronome_time_signature: "Time Signature",
+    click_in_monitor: "Click in main outputs",
+    record_click_track: "Save a click track with recordings",
+    copy_diagnostics: "Copy Diagnostics",
+    calibrate_input: "Calibrate Input",
+    gain_staging: "Gain Staging",
+    tone_match: "Tone Match",
     cancel: "Cancel",
     apply: "Apply",
     language: "Language:",
+    theme: "Theme:",
+    theme_follow_system: "Follow system",
 
     // Tuner dialog
     tuner_title: "Tuner",
+    tuner_unsteady: "Unsteady, let the note ring",
+    tuner_strobe: "Strobe",
+    tuner_lock_note: "Lock to note...",
+    tuner_unlock: "Unlock",
+    tuner_harmonic: "Harmonic",
     in_tune: "IN TUNE",
     adjust: "ADJUST",
     play_a_note: "PLAY A NOTE",
@@ -320,6 +759,7 @@ pub static EN: Translations = Translations {
     controller: "Controller",
     connected: "Connected",
     not_connected: "Not connected",
+    midi_waiting_for_device: "Unplugged, waiting for it to return",
     device: "Device:",
     select_midi_controller: "Select a MIDI controller...",
     disconnect: "Disconnect",
@@ -329,19 +769,91 @@ pub static EN: Translations = Translations {
     captured: "Captured:",
     assign_to: "Assign to:",
     select_preset: "Select a preset...",
+    hold_tuner: "Hold to tune",
+    hold_tuner_instead: "Hold to tune instead of loading a preset",
+    previous_preset: "Previous preset",
+    previous_preset_instead: "Switch to the previous preset instead of loading one",
+    next_song: "Next song",
+    previous_song: "Previous song",
+    next_song_instead: "Step to the next song in the setlist instead of loading a preset",
+    previous_song_instead: "Step to the previous song in the setlist instead of loading a preset",
+    toggle_standby: "Toggle standby",
+    toggle_standby_instead: "Toggle standby instead of loading a preset",
+    toggle_ir_bypass: "Toggle IR bypass",
+    toggle_ir_bypass_instead: "Toggle IR bypass instead of loading a preset",
+    mapping_mode: "Mode",
+    mapping_mode_trigger: "Trigger",
+    mapping_mode_momentary: "Momentary",
+    mapping_mode_toggle: "Toggle",
+    randomize_instead: "Randomize the chain instead of loading a preset",
+    save_slot_instead: "Save the chain to a quick slot instead of loading a preset",
+    load_slot_instead: "Load a quick slot instead of loading a preset",
+    save_to_slot: "Save to slot",
+    load_slot: "Load slot",
+    quick_slot: "Slot:",
     confirm_mapping: "Confirm Mapping",
     no_mappings_configured: "No mappings configured",
+    hotkey: "Hotkey",
+    mappings: "Mappings",
+    filter_mappings: "Filter by input, target or type…",
+    trigger: "Input",
+    target: "Does",
+    source: "Type",
+    mapping_replaces: "Replaces the mapping to",
+    mapping_duplicate: "Same input as",
+    mapping_shadows_typing: "Also typed into text boxes",
+    no_matching_mappings: "No mappings match the filter",
     debug_log: "Debug Log",
     no_midi_messages: "No MIDI messages received yet",
     refresh_controllers: "Refresh Controllers",
 
     // Control bar
     add_stage: "Add Stage",
+    randomize: "Randomize",
+    chain_template: "Template…",
+    randomize_subtle: "Subtle",
+    randomize_medium: "Medium",
+    randomize_wild: "Wild",
+    undo: "Undo",
     collapse_all: "Collapse All",
     expand_all: "Expand All",
+    collapse_all_except_selected: "Collapse All Except Selected",
     stop_recording: "Stop Recording",
     start_recording: "Start Recording",
     recording: "Recording...",
+    recording_failed: "Recording failed: disk write error",
+    recording_softclipped: "Soft-clipped",
+    render_file: "Render File...",
+    render_title: "Render File Through Current Preset",
+    input_file: "Input WAV:",
+    output_file: "Output WAV:",
+    automation_file: "Automation file (optional):",
+    render: "Render",
+    rendering: "Rendering...",
+    render_done: "Render complete",
+    render_failed: "Render failed:",
+    render_hint: "Processes a WAV file at the JACK sample rate with the current chain, IR and oversampling.",
+    ir_capture: "IR capture",
+    ir_capture_hint: "Saves the chain's linear response, cabinet included, to the IR folder. Drive, dynamics, modulation and time effects are left out.",
+    ir_capture_length: "Length (samples)",
+    capture_ir: "Capture IR",
+    ir_capture_done: "Saved as",
+    bounce: "Bounce",
+    bounce_title: "Bounce through the live chain",
+    bounce_hint: "Plays the input file through the live chain into a new take, as fast as JACK can freewheel. Needs the JACK backend.",
+    bounce_mix: "Mix with live input",
+    bouncing: "Bouncing…",
+    bounce_done: "Bounced into",
+    compare_takes: "Compare Takes...",
+    takes_title: "Compare Takes",
+    takes_hint: "The latest takes with the same name, lined up on their first note. Press 1-4 to switch takes without losing your place, Space to play or pause.",
+    takes_none: "No takes in the recording folder yet.",
+    takes_failed: "Could not load the takes:",
+    takes_normalize: "Match peak levels",
+    takes_reload: "Reload",
+    takes_play: "Play",
+    takes_pause: "Pause",
+    takes_rewind: "Rewind",
 
     // IR Cabinet control
     cabinet_ir: "Cabinet IR",
@@ -350,6 +862,44 @@ pub static EN: Translations = Translations {
     gain: "Gain",
     active: "Active:",
     no_ir_loaded: "No IR loaded",
+    shuffle: "Shuffle",
+    ir_step_hint: "Left/Right arrows step through IRs",
+    ir_preview_waveform: "First 20 ms",
+    ir_preview_spectrum: "Frequency response",
+    ir_compare: "Compare",
+    ir_compare_add: "Add selected",
+    ir_compare_next: "Next slot (Space)",
+    ir_compare_keep: "Keep",
+    ir_compare_exit: "Exit",
+    ir_compare_measuring: "Measuring…",
+    ir_compare_hint: "Levels are matched to the quietest slot; keeping saves the IR to the preset",
+    ir_tags: "Tags:",
+    ir_tags_all: "All",
+    ir_offset: "Offset",
+    ir_invert_polarity: "Invert polarity",
+    ir_auto_align: "Auto-align",
+    missing_ir: "IR not found:",
+    missing_ir_use: "Use",
+    missing_ir_choose: "Choose replacement",
+    missing_ir_continue: "Continue without IR",
+    missing_ir_write_back: "Save replacement to preset",
+    preset_missing: "Missing on disk",
+    ir_rejected: "IR not loaded:",
+    ir_mono: "mono",
+    ir_stereo: "stereo",
+    audio_stalled: "Audio stopped: no audio processed for",
+    audio_stall_restarted: "the audio stream was restarted",
+    audio_stall_restart_failed: "restart failed:",
+    audio_error_server_not_found: "JACK/PipeWire server not found — is it running?",
+    audio_error_client: "The audio server refused the connection.",
+    audio_error_ports: "Could not register the audio ports.",
+    audio_error_engine: "Could not start the audio engine at the server's sample rate and buffer size.",
+    audio_error_device: "Could not open the audio device. Check it is connected and not held by another application.",
+    audio_error_freewheel: "Could not switch JACK freewheel mode. Check the server is still running.",
+    preset_error_dir: "Could not open the presets directory.",
+    startup_error_title: "Rustortion couldn't start",
+    quit: "Quit",
+    restart_audio: "Restart audio",
 
     // Preset bar
     preset: "Preset:",
@@ -357,10 +907,43 @@ pub static EN: Translations = Translations {
     yes: "Yes",
     no: "No",
     preset_name_placeholder: "Preset name...",
+    tags: "Tags",
+    tags_placeholder: "metal, gig, ...",
+    bpm: "BPM",
+    time_signature_placeholder: "4/4",
+    tags_and_tempo: "Tags & Tempo",
+    filter_by_tags: "Filter:",
+    clear_filter: "Clear",
+    preset_name_invalid: "Can't save:",
+    preset_name_empty: "the name is empty",
+    preset_name_too_long: "the name is too long",
+    preset_name_control_character: "the name contains a tab, line break or other control character",
     save: "Save",
     save_as: "Save As...",
     update: "Update",
     delete: "Delete",
+    preset_deleted: "Deleted",
+    manage_presets: "Manage Presets",
+    recently_deleted: "Recently deleted",
+    restore: "Restore",
+    delete_forever: "Delete forever",
+    trash_empty: "Nothing deleted or overwritten in the last 30 days",
+    inbox: "Inbox",
+    inbox_empty: "No presets waiting. Presets dropped in the inbox folder show up here.",
+    inbox_trying: "from the inbox:",
+    try_preset: "Try",
+    keep: "Keep",
+    discard: "Discard",
+    minutes_ago: "min ago",
+    hours_ago: "h ago",
+    days_ago: "d ago",
+    compare_saved: "Compare",
+    preset_changes: "Changes vs Saved",
+    no_changes: "No changes",
+    revert: "Revert",
+    diff_added: "added",
+    diff_removed: "removed",
+    diff_moved: "moved",
 
     // Stage names
     stage_filter: "Filter",
@@ -375,6 +958,9 @@ pub static EN: Translations = Translations {
     stage_reverb: "Reverb",
     stage_eq: "Graphic EQ",
     stage_tremolo: "Tremolo",
+    stage_wah: "Wah",
+    stage_group: "Group",
+    group_add_stage: "Add stage to group…",
     stage_nam: "NAM",
     nam_model: "Model",
     nam_no_model: "Select a model…",
@@ -384,8 +970,32 @@ pub static EN: Translations = Translations {
     nam_input_gain: "Input",
     nam_output_gain: "Output",
     nam_mix: "Mix",
+    stage_capture: "Capture",
+    capture_file: "Capture",
+    capture_none: "Select a capture…",
+    capture_not_found: "Capture not found",
+    capture_taps: "taps",
+    capture_input_gain: "Input",
+    capture_output_gain: "Output",
+    capture_mix: "Mix",
     stage_bypass: "Bypass",
     stage_bypass_tooltip: "Toggle stage bypass",
+    stage_solo_tooltip: "Solo: hear the chain only up to this stage (Shift+click keeps the cabinet; not saved in presets)",
+    stage_lock_tooltip: "Lock: keep this stage as it is when randomizing or applying a template",
+    solo_indicator: "Solo",
+    solo_keep_post: "with cabinet",
+    solo_indicator_tooltip: "A stage is soloed. Click to hear the whole chain again.",
+    standby: "Standby",
+    standby_muted: "STANDBY · muted",
+    standby_tooltip_on: "The power amp is muted while the preamp keeps running. Click to play.",
+    standby_tooltip_off: "Mute the power amp, keeping the preamp warmed up",
+    stage_in_trim: "Input trim",
+    stage_out_trim: "Output trim",
+    stage_invert_tooltip: "Invert polarity",
+    lint_gate_after_gain: "A noise gate works best before a high-gain preamp",
+    lint_leading_level: "A Level stage at the very start does nothing a preamp's gain can't",
+    lint_multiple_power_amps: "Multiple power amp stages",
+    lint_stacked_tone_stacks: "Three or more tone stacks hollow out the mids",
 
     // Stage parameters
     clipper: "Clipper:",
@@ -404,23 +1014,34 @@ pub static EN: Translations = Translations {
     drive: "Drive",
     sag: "Sag",
     sag_release: "Sag Release",
+    auto_level: "Auto Level",
     cutoff: "Cutoff",
     hold: "Hold",
     low_band: "Low Band",
     mid_band: "Mid Band",
     high_band: "High Band",
+    band_mute_tooltip: "Mute band",
+    band_solo_tooltip: "Solo band (not saved in presets)",
     low_freq: "Low Crossover",
     high_freq: "High Crossover",
     level: "Level",
     crossover: "Crossover",
     delay_time: "Delay Time",
+    tempo_sync: "Sync to Tempo",
+    note_division: "Division",
     feedback: "Feedback",
     dry_wet: "Dry/Wet",
+    duck_depth: "Duck Depth",
+    duck_release: "Duck Release",
     room_size: "Room Size",
     damping: "Damping",
     rate: "Rate",
     depth: "Depth",
     shape: "Shape",
+    wah_position: "Position",
+    wah_q: "Q",
+    sensitivity: "Sensitivity",
+    mode: "Mode",
 
     // Filter types
     filter_highpass: "Highpass",
@@ -447,16 +1068,38 @@ pub static EN: Translations = Translations {
 
     // Pitch shift control
     pitch_shift: "Pitch Shift",
+    ambience: "Ambience",
+    ambience_amount: "Amount",
+    ambience_decay: "Decay",
+    ambience_tone: "Tone",
+    double_tracker: "Double Tracker",
+    double_tracker_mix: "Mix",
+    double_tracker_width: "Width",
+    double_tracker_delay: "Delay",
+    double_tracker_detune: "Detune",
+    denoise: "Noise Reduction",
+    denoise_learn: "Learn Noise",
+    denoise_learning: "Learning…",
+    denoise_no_profile: "Mute the strings and press Learn.",
+    denoise_learned: "Noise print learned",
+    denoise_learn_failed: "No input heard; nothing learned.",
+    denoise_reduction: "Reduction",
+    tilt: "Tilt",
+    tilt_preset_offset: "preset",
     semitones: "st",
     pitch_bypass: "Bypass (no shift)",
 
     // Misc UI labels
     output: "Output:",
+    output_left: "Left:",
+    output_right: "Right:",
     samples: "samples",
     requested: "requested:",
     hz: "Hz",
     db: "dB",
     ms: "ms",
+    search_placeholder: "Search...",
+    no_matches: "No matches",
 
     // Hotkey dialog
     hotkey_settings: "Hotkey Settings",
@@ -477,37 +1120,207 @@ pub static EN: Translations = Translations {
     // Peak meter / status
     xruns: "xruns",
     cpu: "CPU",
+    reduced_quality: "Reduced quality",
+
+    // Input calibration dialog
+    calibration_title: "Input Calibration",
+    calibration_instructions: "Play an open low E as hard as you will ever play.",
+    calibration_current_trim: "Current trim:",
+    calibration_not_calibrated: "Not calibrated",
+    calibration_start: "Start",
+    calibration_listening: "Listening…",
+    calibration_peak: "Peak:",
+    calibration_recommended: "Recommended trim:",
+    calibration_no_signal: "No signal detected. Check the input port and cable.",
+    calibration_retry: "Retry",
+
+    // Gain staging dialog
+    gain_staging_title: "Gain Staging",
+    gain_staging_instructions: "Play your loudest passage while each stage's output is measured.",
+    gain_staging_start: "Start",
+    gain_staging_listening: "Listening…",
+    gain_staging_retry: "Analyze Again",
+    gain_staging_empty_chain: "The chain has no stages.",
+    gain_staging_bypassed: "bypassed",
+    gain_staging_peaks_at: "output peaks at",
+    gain_staging_all_good: "Every stage has headroom. Nothing to change.",
+    gain_staging_fix_set_level: "turn down Level",
+    gain_staging_fix_insert_level: "add a Level stage after it",
+    gain_staging_fix_manual: "reduce its gain or output",
+    gain_staging_apply: "Apply Suggestions",
+    tone_match_title: "Match Tone to a Reference",
+    tone_match_instructions: "Feeds the same signal through the current chain and, for a preset reference, through that preset too, then compares their spectra and fits an EQ that closes the difference.",
+    tone_match_source: "Source DI recording (empty for pink noise)",
+    tone_match_reference_preset: "Reference preset",
+    tone_match_reference_file: "Or a reference recording",
+    tone_match_start: "Analyse",
+    tone_match_running: "Rendering and analysing…",
+    tone_match_failed: "Tone match failed:",
+    tone_match_largest: "Largest correction:",
+    tone_match_apply: "Append EQ Stage",
+    tone_match_chain_full: "The chain is full; remove a stage to append the EQ.",
+
+    // Setlist dialog
+    setlist_title: "Setlists",
+    setlist_new: "New",
+    setlist_name: "Name",
+    setlist_empty: "No songs yet. Pick a preset and add it.",
+    setlist_notes: "Notes",
+    setlist_add_song: "Add song",
+    setlist_start: "Start",
+    setlist_stop: "Stop",
+    setlist_missing_preset: "Preset not found — renamed or deleted?",
+    setlist_next_up: "Next:",
+    setlist_last_song: "Last song",
+    setlist_select: "Select setlist...",
+
+    // Drag-and-drop import
+    import_done: "Imported:",
+    import_failed: "Not imported:",
+
+    // Macros
+    macros: "Macros",
+    add_macro: "Add Macro",
+    new_macro_name: "Macro",
+    edit_macro: "Edit Macro",
+    macro_name: "Name",
+    macro_targets: "Targets",
+    add_macro_target: "Add Target",
+    remove_macro: "Remove Macro",
+    macro_stage: "Stage",
+    macro_parameter: "Parameter",
+    macro_from: "From",
+    macro_to: "To",
+    macro_curve: "Curve",
+    macro_target_invalid: "Out of range for this stage",
+    no_macro_targets: "No targets yet",
+    no_macros: "No macros in this preset",
+
+    // First-run setup
+    setup_title: "Welcome to Rustortion",
+    setup_step: "Step",
+    setup_skip: "Skip Setup",
+    setup_back: "Back",
+    setup_next: "Next",
+    setup_finish: "Finish",
+    setup_retry: "Check Again",
+    setup_audio_heading: "Audio server",
+    setup_server_jack: "A JACK server is running.",
+    setup_server_pipewire_jack: "PipeWire is running, with its JACK support.",
+    setup_server_pipewire_no_jack: "PipeWire is running, but JACK programs can't reach it. Install its JACK support (the pipewire-jack package), or start Rustortion with pw-jack, then check again.",
+    setup_server_none: "No JACK or PipeWire server was found. Start JACK (for example with QjackCtl), or install PipeWire with its JACK support, then check again.",
+    setup_running_cpal: "Running on the system's own audio for now. JACK gives lower latency and lets you route ports freely.",
+    setup_waiting_audio: "Audio can't start yet. Setup carries on as soon as it can, checking again every few seconds.",
+    setup_ports_heading: "Inputs and outputs",
+    setup_ports_hint: "Play your instrument: the meter moves when the right input is picked.",
+    setup_input_level: "Input level",
+    setup_folders_heading: "Folders",
+    setup_folders_hint: "Folders that don't exist yet are created. Nothing is downloaded.",
+    setup_recording_dir: "Recordings",
+    setup_ir_dir: "Impulse responses",
+    setup_preset_dir: "Presets",
+    setup_install_presets: "Install the factory presets",
+    setup_folders_failed: "Couldn't set up the folders:",
+    setup_midi_heading: "MIDI controller",
+    setup_midi_hint: "Optionally connect a MIDI controller and teach it presets, standby or the tuner. This can also be done later from the MIDI settings.",
+    setup_midi_open: "Set Up a Controller",
+    run_setup: "Run Setup Again",
 };
 
 pub static ZH_CN: Translations = Translations {
     // Top bar buttons
     midi: "MIDI",
     tuner: "调音器",
+    setlist: "歌单",
     settings: "设置",
+    view_menu: "视图",
+    stage_levels: "各级电平",
 
     // Audio Settings dialog
     audio_settings: "音频设置",
     input_port: "输入端口:",
+    input_right_port: "右输入端口:",
+    input_right_none: "无",
     output_left_port: "左输出端口:",
     output_right_port: "右输出端口:",
     buffer_size_requested: "缓冲区大小* (请求):",
     sample_rate_requested: "采样率* (请求):",
+    resampler_quality: "重采样质量*:",
+    adaptive_quality: "负载过高时降低音质以避免爆音",
+    dual_mono: "分别处理左右输入（双单声道，CPU 占用约翻倍）",
+    dual_mono_needs_right: "需要右输入端口",
     oversampling_factor: "过采样倍数:",
     actual_latency: "实际延迟:",
+    latency_base: "基础",
+    latency_oversampling: "过采样",
+    latency_denoiser: "降噪",
+    latency_pitch_shifter: "移调",
+    latency_cabinet: "箱体",
     changes_require_restart: "* 更改需要重启",
     jack_server_status: "JACK 服务器状态",
     sample_rate: "采样率:",
     buffer_size: "缓冲区大小:",
     jack_different_settings: "JACK 使用的设置与请求的不同。这可能由 PipeWire/JACK 服务器配置控制。",
+    cpal_stream_status: "系统音频状态 (CPAL)",
+    cpal_different_settings: "音频设备使用的设置与请求的不同，已采用其支持的最接近设置。",
+    audio_api: "音频接口",
+    input_device: "输入设备",
+    output_device: "输出设备",
+    system_default_device: "系统默认",
+    cpal_limitations: "系统音频为后备方案：不支持端口路由和走带同步，节拍器从主输出播放，延迟在设备打开时固定。",
     refresh_ports: "刷新端口",
     nam_models_dir: "NAM 模型目录",
     nam_rescan_models: "重新扫描模型",
+    captures_dir: "捕获文件目录",
+    capture_rescan: "重新扫描捕获文件",
+    record_follow_transport: "随 JACK 走带开始/停止录音",
+    record_automation: "录音时保存旋钮操作（自动化）",
+    recording_softclip: "录音时对 -6 dBFS 以上软削波（不影响监听）",
+    tuner_algorithm: "调音器检测算法:",
+    watchdog_auto_restart: "音频停顿时自动重启",
+    watchdog_stall_seconds: "停顿判定阈值（秒）:",
+    remote_control: "OSC 远程控制",
+    remote_port: "端口:",
+    hooks_enabled: "在事件发生时运行钩子命令",
+    hooks_security_note: "钩子会以你的权限运行此处输入的任何命令，请只使用你信任的命令。",
+    hook_command_placeholder: "命令，路径或名称作为 $1 传入（按回车保存）",
+    hook_recording_started: "开始录音",
+    hook_recording_stopped: "停止录音",
+    hook_preset_changed: "预设已切换",
+    hook_audio_disconnected: "音频已断开",
+    test_source: "测试信号:",
+    chain_lint: "提示不合理的效果器顺序",
+    keep_locked_stages: "切换预设时保留已锁定的模块",
+    quantize_chain_changes: "节拍器运行时在下一小节切换预设的放大器链",
+    preset_pending: "下一小节换链：",
+    test_source_frequency: "频率:",
+    test_di_file: "DI 文件（WAV）",
+    load_test_di: "加载",
+    metronome_tick: "节拍器音色",
+    metronome_tick_bundled: "内置音色",
+    load_metronome_tick: "加载",
+    metronome: "节拍器",
+    metronome_bpm: "BPM",
+    metronome_time_signature: "拍号",
+    click_in_monitor: "主输出中包含节拍",
+    record_click_track: "录音时另存节拍音轨",
+    copy_diagnostics: "复制诊断信息",
+    calibrate_input: "校准输入",
+    gain_staging: "增益分配",
+    tone_match: "音色匹配",
     cancel: "取消",
     apply: "应用",
     language: "语言:",
+    theme: "主题:",
+    theme_follow_system: "跟随系统",
 
     // Tuner dialog
     tuner_title: "调音器",
+    tuner_unsteady: "信号不稳定，请让音符延音",
+    tuner_strobe: "频闪",
+    tuner_lock_note: "锁定音符...",
+    tuner_unlock: "解锁",
+    tuner_harmonic: "泛音",
     in_tune: "已调准",
     adjust: "调整",
     play_a_note: "请弹奏",
@@ -520,6 +1333,7 @@ pub static ZH_CN: Translations = Translations {
     controller: "控制器",
     connected: "已连接",
     not_connected: "未连接",
+    midi_waiting_for_device: "已拔出，等待设备重新连接",
     device: "设备:",
     select_midi_controller: "选择 MIDI 控制器...",
     disconnect: "断开",
@@ -529,19 +1343,91 @@ pub static ZH_CN: Translations = Translations {
     captured: "已捕获:",
     assign_to: "分配到:",
     select_preset: "选择预设...",
+    hold_tuner: "按住调音",
+    hold_tuner_instead: "按住调音（不加载预设）",
+    previous_preset: "上一个预设",
+    previous_preset_instead: "切换到上一个预设（不加载预设）",
+    next_song: "下一首",
+    previous_song: "上一首",
+    next_song_instead: "切换到歌单中的下一首（不加载预设）",
+    previous_song_instead: "切换到歌单中的上一首（不加载预设）",
+    toggle_standby: "切换待机",
+    toggle_standby_instead: "切换待机（不加载预设）",
+    toggle_ir_bypass: "切换 IR 旁通",
+    toggle_ir_bypass_instead: "切换 IR 旁通（不加载预设）",
+    mapping_mode: "模式",
+    mapping_mode_trigger: "触发",
+    mapping_mode_momentary: "瞬时",
+    mapping_mode_toggle: "切换",
+    randomize_instead: "随机化效果链（不加载预设）",
+    save_slot_instead: "保存到快速槽位（不加载预设）",
+    load_slot_instead: "加载快速槽位（不加载预设）",
+    save_to_slot: "保存到槽位",
+    load_slot: "加载槽位",
+    quick_slot: "槽位：",
     confirm_mapping: "确认映射",
     no_mappings_configured: "未配置映射",
+    hotkey: "快捷键",
+    mappings: "映射",
+    filter_mappings: "按输入、目标或类型筛选…",
+    trigger: "输入",
+    target: "功能",
+    source: "类型",
+    mapping_replaces: "将替换映射：",
+    mapping_duplicate: "与以下映射输入相同：",
+    mapping_shadows_typing: "也会输入到文本框中",
+    no_matching_mappings: "没有符合筛选条件的映射",
     debug_log: "调试日志",
     no_midi_messages: "尚未收到 MIDI 消息",
     refresh_controllers: "刷新控制器",
 
     // Control bar
     add_stage: "添加级",
+    randomize: "随机化",
+    chain_template: "模板…",
+    randomize_subtle: "轻微",
+    randomize_medium: "中等",
+    randomize_wild: "狂野",
+    undo: "撤销",
     collapse_all: "全部折叠",
     expand_all: "全部展开",
+    collapse_all_except_selected: "仅展开所选",
     stop_recording: "停止录音",
     start_recording: "开始录音",
     recording: "录音中...",
+    recording_failed: "录音失败：磁盘写入错误",
+    recording_softclipped: "已软削波",
+    render_file: "渲染文件...",
+    render_title: "用当前预设渲染文件",
+    input_file: "输入 WAV:",
+    output_file: "输出 WAV:",
+    automation_file: "自动化文件（可选）:",
+    render: "渲染",
+    rendering: "渲染中...",
+    render_done: "渲染完成",
+    render_failed: "渲染失败:",
+    render_hint: "以 JACK 采样率用当前效果链、IR 和过采样处理 WAV 文件。",
+    ir_capture: "IR 捕获",
+    ir_capture_hint: "将效果链的线性响应（含箱体）保存到 IR 文件夹。失真、动态、调制和时间类效果不包含在内。",
+    ir_capture_length: "长度（采样）",
+    capture_ir: "捕获 IR",
+    ir_capture_done: "已保存为",
+    bounce: "并轨",
+    bounce_title: "通过实时链路并轨",
+    bounce_hint: "将输入文件通过实时链路录制为新的音轨，以 JACK 自由运行模式尽可能快地处理。需要 JACK 后端。",
+    bounce_mix: "与实时输入混合",
+    bouncing: "正在并轨…",
+    bounce_done: "已并轨到",
+    compare_takes: "比较录音...",
+    takes_title: "比较录音",
+    takes_hint: "同名的最新录音，按第一个音符对齐。按 1-4 切换录音而不丢失播放位置，按空格播放或暂停。",
+    takes_none: "录音文件夹中还没有录音。",
+    takes_failed: "无法加载录音：",
+    takes_normalize: "匹配峰值电平",
+    takes_reload: "重新加载",
+    takes_play: "播放",
+    takes_pause: "暂停",
+    takes_rewind: "回到开头",
 
     // IR Cabinet control
     cabinet_ir: "箱体脉冲响应",
@@ -550,6 +1436,44 @@ pub static ZH_CN: Translations = Translations {
     gain: "增益",
     active: "当前:",
     no_ir_loaded: "未加载 IR",
+    shuffle: "随机",
+    ir_step_hint: "左/右方向键切换 IR",
+    ir_preview_waveform: "前 20 毫秒",
+    ir_preview_spectrum: "频率响应",
+    ir_compare: "对比",
+    ir_compare_add: "添加所选",
+    ir_compare_next: "下一个 (空格)",
+    ir_compare_keep: "保留",
+    ir_compare_exit: "退出",
+    ir_compare_measuring: "测量中…",
+    ir_compare_hint: "音量已匹配到最安静的槽位；保留会将 IR 存入预设",
+    ir_tags: "标签:",
+    ir_tags_all: "全部",
+    ir_offset: "偏移",
+    ir_invert_polarity: "反相",
+    ir_auto_align: "自动对齐",
+    missing_ir: "未找到 IR:",
+    missing_ir_use: "使用",
+    missing_ir_choose: "选择替代 IR",
+    missing_ir_continue: "不使用 IR 继续",
+    missing_ir_write_back: "将替代 IR 保存到预设",
+    preset_missing: "磁盘上已不存在",
+    ir_rejected: "IR 未加载:",
+    ir_mono: "单声道",
+    ir_stereo: "立体声",
+    audio_stalled: "音频已停止：未处理音频已持续",
+    audio_stall_restarted: "音频流已重启",
+    audio_stall_restart_failed: "重启失败:",
+    audio_error_server_not_found: "未找到 JACK/PipeWire 服务器——它在运行吗？",
+    audio_error_client: "音频服务器拒绝了连接。",
+    audio_error_ports: "无法注册音频端口。",
+    audio_error_engine: "无法以服务器的采样率和缓冲区大小启动音频引擎。",
+    audio_error_device: "无法打开音频设备。请检查设备是否已连接且未被其他程序占用。",
+    audio_error_freewheel: "无法切换 JACK 自由运行模式。请检查服务器是否仍在运行。",
+    preset_error_dir: "无法打开预设目录。",
+    startup_error_title: "Rustortion 无法启动",
+    quit: "退出",
+    restart_audio: "重启音频",
 
     // Preset bar
     preset: "预设:",
@@ -557,10 +1481,43 @@ pub static ZH_CN: Translations = Translations {
     yes: "是",
     no: "否",
     preset_name_placeholder: "预设名称...",
+    tags: "标签",
+    tags_placeholder: "金属, 演出, ...",
+    bpm: "BPM",
+    time_signature_placeholder: "4/4",
+    tags_and_tempo: "标签和速度",
+    filter_by_tags: "筛选：",
+    clear_filter: "清除",
+    preset_name_invalid: "无法保存：",
+    preset_name_empty: "名称为空",
+    preset_name_too_long: "名称过长",
+    preset_name_control_character: "名称包含制表符、换行符或其他控制字符",
     save: "保存",
     save_as: "另存为...",
     update: "更新",
     delete: "删除",
+    preset_deleted: "已删除",
+    manage_presets: "管理预设",
+    recently_deleted: "最近删除",
+    restore: "恢复",
+    delete_forever: "永久删除",
+    trash_empty: "最近 30 天内没有删除或覆盖的预设",
+    inbox: "收件箱",
+    inbox_empty: "没有待整理的预设。放入 inbox 文件夹的预设会显示在这里。",
+    inbox_trying: "来自收件箱：",
+    try_preset: "试听",
+    keep: "保留",
+    discard: "丢弃",
+    minutes_ago: "分钟前",
+    hours_ago: "小时前",
+    days_ago: "天前",
+    compare_saved: "对比",
+    preset_changes: "与已保存版本的差异",
+    no_changes: "无变化",
+    revert: "还原",
+    diff_added: "已添加",
+    diff_removed: "已移除",
+    diff_moved: "已移动",
 
     // Stage names
     stage_filter: "滤波器",
@@ -575,6 +1532,9 @@ pub static ZH_CN: Translations = Translations {
     stage_reverb: "混响",
     stage_eq: "图形均衡器",
     stage_tremolo: "颤音",
+    stage_wah: "哇音",
+    stage_group: "编组",
+    group_add_stage: "向编组添加效果…",
     stage_nam: "NAM",
     nam_model: "模型",
     nam_no_model: "选择模型…",
@@ -584,8 +1544,32 @@ pub static ZH_CN: Translations = Translations {
     nam_input_gain: "输入",
     nam_output_gain: "输出",
     nam_mix: "混合",
+    stage_capture: "采样捕获",
+    capture_file: "捕获文件",
+    capture_none: "选择捕获文件…",
+    capture_not_found: "未找到捕获文件",
+    capture_taps: "抽头",
+    capture_input_gain: "输入",
+    capture_output_gain: "输出",
+    capture_mix: "混合",
     stage_bypass: "旁路",
     stage_bypass_tooltip: "切换旁路",
+    stage_solo_tooltip: "独奏：只听到此级为止的信号链（Shift+点击保留箱体；不保存到预设）",
+    stage_lock_tooltip: "锁定：随机化或套用模板时保持此模块不变",
+    solo_indicator: "独奏",
+    solo_keep_post: "含箱体",
+    solo_indicator_tooltip: "有一级正在独奏。点击恢复完整信号链。",
+    standby: "待机",
+    standby_muted: "待机 · 已静音",
+    standby_tooltip_on: "功放已静音，前级仍在运行。点击恢复出声。",
+    standby_tooltip_off: "静音功放，前级保持运行",
+    stage_in_trim: "输入微调",
+    stage_out_trim: "输出微调",
+    stage_invert_tooltip: "反转极性",
+    lint_gate_after_gain: "噪声门最好放在高增益前级之前",
+    lint_leading_level: "链首的电平模块作用与前级增益相同",
+    lint_multiple_power_amps: "存在多个功放模块",
+    lint_stacked_tone_stacks: "三个或更多音色堆栈会挖空中频",
 
     // Stage parameters
     clipper: "削波器:",
@@ -604,23 +1588,34 @@ pub static ZH_CN: Translations = Translations {
     drive: "驱动",
     sag: "下垂",
     sag_release: "下垂释放",
+    auto_level: "自动电平",
     cutoff: "截止",
     hold: "保持",
     low_band: "低频段",
     mid_band: "中频段",
     high_band: "高频段",
+    band_mute_tooltip: "静音频段",
+    band_solo_tooltip: "独奏频段（不保存到预设）",
     low_freq: "低频分频点",
     high_freq: "高频分频点",
     level: "电平",
     crossover: "分频",
     delay_time: "延迟时间",
+    tempo_sync: "同步速度",
+    note_division: "音符时值",
     feedback: "反馈",
     dry_wet: "干/湿",
+    duck_depth: "避让深度",
+    duck_release: "避让释放",
     room_size: "房间大小",
     damping: "阻尼",
     rate: "速率",
     depth: "深度",
     shape: "波形",
+    wah_position: "位置",
+    wah_q: "Q值",
+    sensitivity: "灵敏度",
+    mode: "模式",
 
     // Filter types
     filter_highpass: "高通",
@@ -647,16 +1642,38 @@ pub static ZH_CN: Translations = Translations {
 
     // Pitch shift control
     pitch_shift: "音高移位",
+    ambience: "氛围",
+    ambience_amount: "混响量",
+    ambience_decay: "衰减",
+    ambience_tone: "音色",
+    double_tracker: "双轨叠录",
+    double_tracker_mix: "混合",
+    double_tracker_width: "宽度",
+    double_tracker_delay: "延迟",
+    double_tracker_detune: "失谐",
+    denoise: "降噪",
+    denoise_learn: "学习噪声",
+    denoise_learning: "正在学习…",
+    denoise_no_profile: "请静音琴弦后按“学习噪声”。",
+    denoise_learned: "已学习噪声特征",
+    denoise_learn_failed: "未检测到输入，未能学习。",
+    denoise_reduction: "降噪量",
+    tilt: "倾斜",
+    tilt_preset_offset: "预设",
     semitones: "半音",
     pitch_bypass: "旁通（无移位）",
 
     // Misc UI labels
     output: "输出:",
+    output_left: "左:",
+    output_right: "右:",
     samples: "采样",
     requested: "请求:",
     hz: "赫兹",
     db: "分贝",
     ms: "毫秒",
+    search_placeholder: "搜索...",
+    no_matches: "无匹配项",
 
     // Hotkey dialog
     hotkey_settings: "快捷键设置",
@@ -677,4 +1694,109 @@ pub static ZH_CN: Translations = Translations {
     // Peak meter / status
     xruns: "欠载",
     cpu: "CPU",
+    reduced_quality: "已降低音质",
+
+    // Input calibration dialog
+    calibration_title: "输入校准",
+    calibration_instructions: "请用你最大的力度弹奏空弦低音 E。",
+    calibration_current_trim: "当前输入微调：",
+    calibration_not_calibrated: "未校准",
+    calibration_start: "开始",
+    calibration_listening: "正在监听…",
+    calibration_peak: "峰值：",
+    calibration_recommended: "建议微调：",
+    calibration_no_signal: "未检测到信号。请检查输入端口和线缆。",
+    calibration_retry: "重试",
+
+    // Gain staging dialog
+    gain_staging_title: "增益分配",
+    gain_staging_instructions: "请演奏最响的段落，同时测量每一级的输出。",
+    gain_staging_start: "开始",
+    gain_staging_listening: "正在聆听…",
+    gain_staging_retry: "重新分析",
+    gain_staging_empty_chain: "效果链中没有效果级。",
+    gain_staging_bypassed: "已旁通",
+    gain_staging_peaks_at: "输出峰值为",
+    gain_staging_all_good: "每一级都有足够余量，无需调整。",
+    gain_staging_fix_set_level: "调低电平",
+    gain_staging_fix_insert_level: "在其后添加电平级",
+    gain_staging_fix_manual: "请调低其增益或输出",
+    gain_staging_apply: "应用建议",
+    tone_match_title: "将音色匹配到参考",
+    tone_match_instructions: "将相同信号送入当前效果链（若参考为预设，也送入该预设），比较两者频谱，并拟合一个弥补差异的均衡器。",
+    tone_match_source: "源 DI 录音（留空则使用粉红噪声）",
+    tone_match_reference_preset: "参考预设",
+    tone_match_reference_file: "或参考录音",
+    tone_match_start: "分析",
+    tone_match_running: "正在渲染和分析…",
+    tone_match_failed: "音色匹配失败：",
+    tone_match_largest: "最大校正：",
+    tone_match_apply: "追加均衡器模块",
+    tone_match_chain_full: "效果链已满；请移除一个模块以追加均衡器。",
+
+    // Setlist dialog
+    setlist_title: "歌单",
+    setlist_new: "新建",
+    setlist_name: "名称",
+    setlist_empty: "还没有歌曲。选择一个预设并添加。",
+    setlist_notes: "备注",
+    setlist_add_song: "添加歌曲",
+    setlist_start: "开始",
+    setlist_stop: "停止",
+    setlist_missing_preset: "未找到预设——已重命名或删除？",
+    setlist_next_up: "下一首：",
+    setlist_last_song: "最后一首",
+    setlist_select: "选择歌单...",
+
+    // Drag-and-drop import
+    import_done: "已导入：",
+    import_failed: "未导入：",
+
+    // Macros
+    macros: "宏",
+    add_macro: "添加宏",
+    new_macro_name: "宏",
+    edit_macro: "编辑宏",
+    macro_name: "名称",
+    macro_targets: "目标",
+    add_macro_target: "添加目标",
+    remove_macro: "删除宏",
+    macro_stage: "模块",
+    macro_parameter: "参数",
+    macro_from: "起始",
+    macro_to: "结束",
+    macro_curve: "曲线",
+    macro_target_invalid: "超出该模块的范围",
+    no_macro_targets: "尚无目标",
+    no_macros: "此预设没有宏",
+
+    // First-run setup
+    setup_title: "欢迎使用 Rustortion",
+    setup_step: "步骤",
+    setup_skip: "跳过设置",
+    setup_back: "上一步",
+    setup_next: "下一步",
+    setup_finish: "完成",
+    setup_retry: "重新检测",
+    setup_audio_heading: "音频服务器",
+    setup_server_jack: "检测到正在运行的 JACK 服务器。",
+    setup_server_pipewire_jack: "检测到 PipeWire 及其 JACK 支持。",
+    setup_server_pipewire_no_jack: "PipeWire 正在运行，但 JACK 程序无法连接。请安装其 JACK 支持（pipewire-jack 软件包），或用 pw-jack 启动 Rustortion，然后重新检测。",
+    setup_server_none: "未找到 JACK 或 PipeWire 服务器。请启动 JACK（例如使用 QjackCtl），或安装 PipeWire 及其 JACK 支持，然后重新检测。",
+    setup_running_cpal: "当前使用系统自带的音频。JACK 延迟更低，并可自由连接端口。",
+    setup_waiting_audio: "音频暂时无法启动。设置会每隔几秒重新检测，一旦可以启动便继续。",
+    setup_ports_heading: "输入与输出",
+    setup_ports_hint: "弹奏乐器：选对输入后电平表会有反应。",
+    setup_input_level: "输入电平",
+    setup_folders_heading: "文件夹",
+    setup_folders_hint: "不存在的文件夹会被创建，不会下载任何内容。",
+    setup_recording_dir: "录音",
+    setup_ir_dir: "脉冲响应",
+    setup_preset_dir: "预设",
+    setup_install_presets: "安装出厂预设",
+    setup_folders_failed: "无法设置文件夹：",
+    setup_midi_heading: "MIDI 控制器",
+    setup_midi_hint: "可选：连接 MIDI 控制器并学习预设、待机或调音器控制。之后也可以在 MIDI 设置中完成。",
+    setup_midi_open: "设置控制器",
+    run_setup: "重新运行设置向导",
 };
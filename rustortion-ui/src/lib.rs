@@ -32,10 +32,16 @@
 pub mod app;
 pub mod backend;
 pub mod components;
+pub mod focus;
 pub mod font;
 pub mod handlers;
 pub mod hotkey;
 pub mod i18n;
+pub mod lint;
+pub mod mappings;
 pub mod messages;
+pub mod randomize;
 pub mod stages;
 pub mod tabs;
+pub mod templates;
+pub mod theme;
@@ -0,0 +1,227 @@
+use crate::stages::{StageConfig, StageType};
+use crate::tr;
+
+/// Preamp gain from which a noise gate after it is gating amplified hiss.
+const HIGH_GAIN: f32 = 5.0;
+/// Tone stacks it takes before stacking them is flagged.
+const TONE_STACK_LIMIT: usize = 3;
+
+/// A questionable stage ordering. Only advice: the chain runs as it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintWarning {
+    pub message: &'static str,
+    /// Chain indices of the stages involved.
+    pub stages: Vec<usize>,
+}
+
+/// A rule checked against the whole chain whenever it changes.
+pub trait ChainLint {
+    fn check(&self, stages: &[StageConfig]) -> Vec<LintWarning>;
+}
+
+/// Stages that process audio, with their chain indices.
+fn active(stages: &[StageConfig]) -> impl Iterator<Item = (usize, &StageConfig)> {
+    stages.iter().enumerate().filter(|(_, s)| !s.bypassed())
+}
+
+fn active_of_type(stages: &[StageConfig], stage_type: StageType) -> Vec<usize> {
+    active(stages)
+        .filter(|(_, s)| s.stage_type() == stage_type)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// A noise gate after a high-gain preamp gates the hiss the preamp has
+/// already amplified, so it has to clamp down hard on the playing too.
+pub struct GateAfterGain;
+
+impl ChainLint for GateAfterGain {
+    fn check(&self, stages: &[StageConfig]) -> Vec<LintWarning> {
+        let Some(preamp) = active(stages).find_map(|(i, s)| match s {
+            StageConfig::Preamp(c) if c.gain >= HIGH_GAIN => Some(i),
+            _ => None,
+        }) else {
+            return Vec::new();
+        };
+
+        active_of_type(stages, StageType::NoiseGate)
+            .into_iter()
+            .filter(|&gate| gate > preamp)
+            .map(|gate| LintWarning {
+                message: tr!(lint_gate_after_gain),
+                stages: vec![preamp, gate],
+            })
+            .collect()
+    }
+}
+
+/// A Level stage ahead of everything only scales the input, which the
+/// preamp's gain after it already does.
+pub struct LeadingLevel;
+
+impl ChainLint for LeadingLevel {
+    fn check(&self, stages: &[StageConfig]) -> Vec<LintWarning> {
+        let mut chain = active(stages);
+        match chain.next() {
+            Some((first, StageConfig::Level(_)))
+                if chain.any(|(_, s)| s.stage_type() == StageType::Preamp) =>
+            {
+                vec![LintWarning {
+                    message: tr!(lint_leading_level),
+                    stages: vec![first],
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Two power amps in series is rarely what anyone meant.
+pub struct MultiplePowerAmps;
+
+impl ChainLint for MultiplePowerAmps {
+    fn check(&self, stages: &[StageConfig]) -> Vec<LintWarning> {
+        let power_amps = active_of_type(stages, StageType::PowerAmp);
+        if power_amps.len() < 2 {
+            return Vec::new();
+        }
+        vec![LintWarning {
+            message: tr!(lint_multiple_power_amps),
+            stages: power_amps,
+        }]
+    }
+}
+
+/// Every tone stack cuts its mids again; a few in a row hollow the sound out.
+pub struct StackedToneStacks;
+
+impl ChainLint for StackedToneStacks {
+    fn check(&self, stages: &[StageConfig]) -> Vec<LintWarning> {
+        let tone_stacks = active_of_type(stages, StageType::ToneStack);
+        if tone_stacks.len() < TONE_STACK_LIMIT {
+            return Vec::new();
+        }
+        vec![LintWarning {
+            message: tr!(lint_stacked_tone_stacks),
+            stages: tone_stacks,
+        }]
+    }
+}
+
+const RULES: [&dyn ChainLint; 4] = [
+    &GateAfterGain,
+    &LeadingLevel,
+    &MultiplePowerAmps,
+    &StackedToneStacks,
+];
+
+/// Every rule's warnings for `stages`. Bypassed stages are ignored, and so
+/// are the stages inside a group.
+pub fn lint_chain(stages: &[StageConfig]) -> Vec<LintWarning> {
+    RULES.iter().flat_map(|rule| rule.check(stages)).collect()
+}
+
+/// Messages of the warnings that involve the stage at `idx`.
+pub fn messages_for(warnings: &[LintWarning], idx: usize) -> Vec<&'static str> {
+    warnings
+        .iter()
+        .filter(|w| w.stages.contains(&idx))
+        .map(|w| w.message)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(types: &[StageType]) -> Vec<StageConfig> {
+        types.iter().copied().map(StageConfig::from).collect()
+    }
+
+    fn set_preamp_gain(stages: &mut [StageConfig], idx: usize, gain: f32) {
+        if let StageConfig::Preamp(c) = &mut stages[idx] {
+            c.gain = gain;
+        }
+    }
+
+    #[test]
+    fn gate_after_high_gain_preamp_is_flagged() {
+        let mut stages = chain(&[StageType::Preamp, StageType::NoiseGate]);
+        set_preamp_gain(&mut stages, 0, 8.0);
+        let warnings = GateAfterGain.check(&stages);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].stages, [0, 1]);
+
+        // Gating first is the advice, and a clean preamp doesn't need it.
+        let mut gate_first = chain(&[StageType::NoiseGate, StageType::Preamp]);
+        set_preamp_gain(&mut gate_first, 1, 8.0);
+        assert!(GateAfterGain.check(&gate_first).is_empty());
+        set_preamp_gain(&mut stages, 0, 2.0);
+        assert!(GateAfterGain.check(&stages).is_empty());
+    }
+
+    #[test]
+    fn leading_level_before_a_preamp_is_flagged() {
+        let stages = chain(&[StageType::Level, StageType::Preamp]);
+        let warnings = LeadingLevel.check(&stages);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].stages, [0]);
+
+        assert!(LeadingLevel.check(&chain(&[StageType::Level])).is_empty());
+        let level_last = chain(&[StageType::Preamp, StageType::Level]);
+        assert!(LeadingLevel.check(&level_last).is_empty());
+    }
+
+    #[test]
+    fn multiple_power_amps_are_flagged_together() {
+        let stages = chain(&[
+            StageType::PowerAmp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+        ]);
+        let warnings = MultiplePowerAmps.check(&stages);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].stages, [0, 2]);
+
+        assert!(
+            MultiplePowerAmps
+                .check(&chain(&[StageType::PowerAmp]))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn three_tone_stacks_are_flagged() {
+        let two = chain(&[StageType::ToneStack, StageType::ToneStack]);
+        assert!(StackedToneStacks.check(&two).is_empty());
+
+        let three = chain(&[
+            StageType::ToneStack,
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::ToneStack,
+        ]);
+        assert_eq!(StackedToneStacks.check(&three)[0].stages, [0, 2, 3]);
+    }
+
+    #[test]
+    fn bypassed_stages_are_ignored() {
+        let mut stages = chain(&[StageType::PowerAmp, StageType::PowerAmp]);
+        stages[1].set_bypassed(true);
+        assert!(lint_chain(&stages).is_empty());
+    }
+
+    #[test]
+    fn messages_are_listed_per_stage() {
+        let stages = chain(&[
+            StageType::Level,
+            StageType::Preamp,
+            StageType::PowerAmp,
+            StageType::PowerAmp,
+        ]);
+        let warnings = lint_chain(&stages);
+        assert_eq!(messages_for(&warnings, 0), [tr!(lint_leading_level)]);
+        assert!(messages_for(&warnings, 1).is_empty());
+        assert_eq!(messages_for(&warnings, 3), [tr!(lint_multiple_power_amps)]);
+    }
+}
@@ -0,0 +1,98 @@
+//! Hotkey and MIDI bindings side by side, for the mappings overview and the
+//! conflict checks both learn dialogs run before confirming.
+
+pub mod validate;
+
+use std::fmt;
+
+pub use rustortion_core::mapping::{MappingMode, Trigger};
+
+/// Which dialog a binding belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindingSource {
+    Hotkey,
+    Midi,
+}
+
+impl fmt::Display for BindingSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hotkey => write!(f, "{}", crate::tr!(hotkey)),
+            Self::Midi => write!(f, "{}", crate::tr!(midi)),
+        }
+    }
+}
+
+/// One hotkey or MIDI mapping, as the overview lists it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub source: BindingSource,
+    /// Position in its source's mapping list, for removing it.
+    pub index: usize,
+    pub trigger: Trigger,
+    /// The trigger as the user reads it, e.g. "Ctrl+F1" or "Ch1 CC/Note 64".
+    pub description: String,
+    /// What it does: a preset name or an action.
+    pub target: String,
+}
+
+impl Binding {
+    /// Whether the input, target or type contains `filter`, ignoring case.
+    /// An empty filter matches everything.
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+        [
+            self.description.as_str(),
+            self.target.as_str(),
+            self.source.to_string().as_str(),
+        ]
+        .iter()
+        .any(|field| field.to_lowercase().contains(&filter))
+    }
+}
+
+/// A [`MappingMode`] under its translated name, for the MIDI dialog's
+/// picker and the mapping lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeLabel(pub MappingMode);
+
+impl ModeLabel {
+    pub const ALL: [Self; 3] = [
+        Self(MappingMode::Trigger),
+        Self(MappingMode::Momentary),
+        Self(MappingMode::Toggle),
+    ];
+}
+
+impl fmt::Display for ModeLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.0 {
+            MappingMode::Trigger => crate::tr!(mapping_mode_trigger),
+            MappingMode::Momentary => crate::tr!(mapping_mode_momentary),
+            MappingMode::Toggle => crate::tr!(mapping_mode_toggle),
+        };
+        write!(f, "{label}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_matches_input_target_and_type() {
+        let binding = Binding {
+            source: BindingSource::Midi,
+            index: 0,
+            trigger: Trigger::midi(0, 64),
+            description: "Ch1 CC/Note 64".to_owned(),
+            target: "Lead Tone".to_owned(),
+        };
+        assert!(binding.matches(""));
+        assert!(binding.matches("  "));
+        assert!(binding.matches("cc/note 64"));
+        assert!(binding.matches("LEAD"));
+        assert!(binding.matches(&BindingSource::Midi.to_string()));
+        assert!(!binding.matches("clean"));
+    }
+}
@@ -0,0 +1,186 @@
+//! Collision rules for hotkey and MIDI bindings. Kept free of dialog state so
+//! the overview and both learn dialogs flag the same things.
+
+use super::{Binding, Trigger};
+
+/// Why a binding won't do what its row says.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    /// Another binding has the same trigger; only the first one listed fires.
+    /// Confirming a new binding on a taken trigger replaces the old one.
+    Duplicate { target: String },
+    /// A character key with no modifier but Shift, which is also typed into
+    /// the name and search boxes.
+    ShadowsTyping,
+}
+
+/// Whether `trigger` is a printable character with at most Shift held.
+pub fn shadows_typing(trigger: &Trigger) -> bool {
+    match trigger {
+        Trigger::Key { key, modifiers } => {
+            key.chars().count() == 1 && modifiers.iter().all(|m| m == "Shift")
+        }
+        Trigger::Midi { .. } => false,
+    }
+}
+
+/// The conflict of each of `bindings`, in order. A duplicate is reported
+/// over shadowing typing, and names the other binding's target.
+pub fn conflicts(bindings: &[Binding]) -> Vec<Option<Conflict>> {
+    bindings
+        .iter()
+        .enumerate()
+        .map(|(i, binding)| {
+            bindings
+                .iter()
+                .enumerate()
+                .find(|&(j, other)| j != i && other.trigger == binding.trigger)
+                .map(|(_, other)| Conflict::Duplicate {
+                    target: other.target.clone(),
+                })
+                .or_else(|| shadows_typing(&binding.trigger).then_some(Conflict::ShadowsTyping))
+        })
+        .collect()
+}
+
+/// What a new binding on `trigger` runs into among `existing`, for the learn
+/// dialogs to warn about before it's confirmed.
+pub fn check_new(trigger: &Trigger, existing: &[Binding]) -> Option<Conflict> {
+    existing
+        .iter()
+        .find(|other| other.trigger == *trigger)
+        .map(|other| Conflict::Duplicate {
+            target: other.target.clone(),
+        })
+        .or_else(|| shadows_typing(trigger).then_some(Conflict::ShadowsTyping))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mappings::BindingSource;
+
+    fn binding(trigger: Trigger, target: &str) -> Binding {
+        let source = match trigger {
+            Trigger::Key { .. } => BindingSource::Hotkey,
+            Trigger::Midi { .. } => BindingSource::Midi,
+        };
+        Binding {
+            source,
+            index: 0,
+            trigger,
+            description: String::new(),
+            target: target.to_owned(),
+        }
+    }
+
+    fn key(key: &str, modifiers: &[&str]) -> Trigger {
+        let modifiers: Vec<String> = modifiers.iter().map(ToString::to_string).collect();
+        Trigger::key(key, &modifiers)
+    }
+
+    fn duplicate(target: &str) -> Option<Conflict> {
+        Some(Conflict::Duplicate {
+            target: target.to_owned(),
+        })
+    }
+
+    #[test]
+    fn modifier_order_does_not_make_a_different_hotkey() {
+        assert_eq!(key("F1", &["Shift", "Ctrl"]), key("F1", &["Ctrl", "Shift"]));
+        assert_eq!(key("F1", &["Ctrl", "Ctrl"]), key("F1", &["Ctrl"]));
+
+        let bindings = [
+            binding(key("F1", &["Ctrl", "Alt"]), "Clean"),
+            binding(key("F1", &["Alt", "Ctrl"]), "Lead"),
+        ];
+        assert_eq!(
+            conflicts(&bindings),
+            [duplicate("Lead"), duplicate("Clean")]
+        );
+    }
+
+    #[test]
+    fn different_modifiers_are_different_hotkeys() {
+        let bindings = [
+            binding(key("1", &["Ctrl"]), "Load slot 1"),
+            binding(key("1", &["Ctrl", "Shift"]), "Save to slot 1"),
+            binding(key("F1", &[]), "Clean"),
+        ];
+        assert_eq!(conflicts(&bindings), [None, None, None]);
+    }
+
+    #[test]
+    fn bare_characters_shadow_typing() {
+        assert!(shadows_typing(&key("a", &[])));
+        assert!(shadows_typing(&key("A", &["Shift"])));
+        assert!(!shadows_typing(&key("a", &["Ctrl"])));
+        assert!(!shadows_typing(&key("A", &["Ctrl", "Shift"])));
+        // Named keys serialize to their name, and aren't typed.
+        assert!(!shadows_typing(&key("F1", &[])));
+        assert!(!shadows_typing(&Trigger::midi(0, 1)));
+
+        let bindings = [binding(key("q", &[]), "Clean")];
+        assert_eq!(conflicts(&bindings), [Some(Conflict::ShadowsTyping)]);
+    }
+
+    #[test]
+    fn a_duplicate_wins_over_shadowing() {
+        let bindings = [
+            binding(key("q", &[]), "Clean"),
+            binding(key("q", &[]), "Lead"),
+        ];
+        assert_eq!(
+            conflicts(&bindings),
+            [duplicate("Lead"), duplicate("Clean")]
+        );
+    }
+
+    #[test]
+    fn midi_collides_on_channel_and_number() {
+        let bindings = [
+            binding(Trigger::midi(0, 64), "Clean"),
+            binding(Trigger::midi(0, 64), "Lead"),
+            binding(Trigger::midi(1, 64), "Crunch"),
+            binding(Trigger::midi(0, 65), "Solo"),
+        ];
+        assert_eq!(
+            conflicts(&bindings),
+            [duplicate("Lead"), duplicate("Clean"), None, None]
+        );
+    }
+
+    #[test]
+    fn hotkeys_and_midi_never_collide() {
+        let bindings = [
+            binding(key("1", &[]), "Clean"),
+            binding(Trigger::midi(0, 1), "Lead"),
+        ];
+        assert_eq!(conflicts(&bindings), [Some(Conflict::ShadowsTyping), None]);
+    }
+
+    #[test]
+    fn new_bindings_are_checked_against_the_existing_ones() {
+        let existing = [
+            binding(key("F1", &["Alt", "Ctrl"]), "Clean"),
+            binding(Trigger::midi(0, 60), "Lead"),
+        ];
+
+        assert_eq!(
+            check_new(&key("F1", &["Ctrl", "Alt"]), &existing),
+            duplicate("Clean")
+        );
+        // A note learned from its Note Off, or a Note On at velocity 0, is
+        // the same trigger as one learned from the Note On.
+        assert_eq!(
+            check_new(&Trigger::midi(0, 60), &existing),
+            duplicate("Lead")
+        );
+        assert_eq!(
+            check_new(&key("x", &[]), &existing),
+            Some(Conflict::ShadowsTyping)
+        );
+        assert_eq!(check_new(&key("F2", &[]), &existing), None);
+        assert_eq!(check_new(&Trigger::midi(0, 61), &existing), None);
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone)]
+pub enum CalibrationMessage {
+    Open,
+    Close,
+    /// Start (or redo) the capture.
+    Start,
+    /// Store the measured trim for the current input port.
+    Save,
+}
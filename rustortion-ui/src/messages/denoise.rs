@@ -0,0 +1,13 @@
+use rustortion_core::audio::denoiser::DenoiseConfig;
+
+#[derive(Debug, Clone)]
+pub enum DenoiseMessage {
+    /// A preset's settings and noise print, all at once.
+    Set(DenoiseConfig),
+    ReductionChanged(f32),
+    BypassToggled(bool),
+    /// Listen to the input for a new noise print.
+    Learn,
+    /// Poll the running learn for its countdown and result.
+    LearnTick,
+}
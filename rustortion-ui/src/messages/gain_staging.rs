@@ -0,0 +1,9 @@
+#[derive(Debug, Clone)]
+pub enum GainStagingMessage {
+    Open,
+    Close,
+    /// Start (or redo) the analysis.
+    Start,
+    /// Apply the suggested level changes to the chain.
+    Apply,
+}
@@ -5,6 +5,24 @@ pub enum HotkeyMessage {
     StartLearning,
     CancelLearning,
     PresetSelected(String),
+    /// Map the captured key to hold-to-tune instead of a preset.
+    HoldTunerToggled(bool),
+    /// Map the captured key to "previous preset" instead of a preset.
+    PreviousPresetToggled(bool),
+    /// Map the captured key to randomizing the chain instead of a preset.
+    RandomizeToggled(bool),
+    /// Map the captured key to the setlist's next song instead of a preset.
+    NextSongToggled(bool),
+    /// Map the captured key to the setlist's previous song instead of a preset.
+    PreviousSongToggled(bool),
+    /// Map the captured key to toggling standby instead of a preset.
+    ToggleStandbyToggled(bool),
+    /// Map the captured key to saving a quick slot instead of a preset.
+    SaveSlotToggled(bool),
+    /// Map the captured key to loading a quick slot instead of a preset.
+    LoadSlotToggled(bool),
+    /// Which quick slot the captured key saves or loads.
+    SlotSelected(u8),
     ConfirmMapping,
     RemoveMapping(usize),
 }
@@ -0,0 +1,12 @@
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub enum ImportMessage {
+    /// A file was dropped onto the window. Several dropped at once arrive one
+    /// message each and are imported together.
+    FileDropped(PathBuf),
+    /// Start importing what was dropped, poll a running import, and expire
+    /// the confirmation.
+    Update,
+    DismissToast,
+}
@@ -0,0 +1,16 @@
+#[derive(Debug, Clone)]
+pub enum IrCompareMessage {
+    /// Start comparing, with the playing IR in the first slot.
+    Start,
+    /// Put the selected IR in the next free slot.
+    AddSelected,
+    Remove(usize),
+    /// Play the IR in this slot.
+    Play(usize),
+    /// Play the next slot, wrapping around.
+    Cycle,
+    /// Keep the playing slot's IR, write it into the preset and stop comparing.
+    Keep,
+    /// Stop comparing and go back to the IR that played before.
+    Exit,
+}
@@ -0,0 +1,30 @@
+use rustortion_core::preset::{Macro, MacroCurve};
+
+#[derive(Debug, Clone)]
+pub enum MacroMessage {
+    /// Move a macro, setting every parameter it's assigned to.
+    ValueChanged(usize, f32),
+    /// The loaded preset's macros; sent after `SetStages`.
+    Set(Vec<Macro>),
+    /// Add a macro with no targets and open it in the editor.
+    Add,
+    /// Open the editor on a macro.
+    Edit(usize),
+    /// Keep the editor's changes and close it.
+    Save,
+    /// Delete the macro being edited and close the editor.
+    Remove,
+    /// Close the editor without keeping its changes.
+    Close,
+    NameChanged(String),
+    AddTarget,
+    RemoveTarget(usize),
+    /// Point a target at another stage, by chain index.
+    TargetStage(usize, usize),
+    TargetParam(usize, &'static str),
+    /// The target's value with the macro at its minimum, as typed.
+    TargetFrom(usize, String),
+    /// The target's value with the macro at its maximum, as typed.
+    TargetTo(usize, String),
+    TargetCurve(usize, MacroCurve),
+}
@@ -0,0 +1,11 @@
+use crate::mappings::BindingSource;
+
+#[derive(Debug, Clone)]
+pub enum MappingsMessage {
+    Open,
+    Close,
+    /// Show only the bindings whose input, target or type contains this.
+    Filter(String),
+    /// Remove a binding by its position in its source's mapping list.
+    Remove(BindingSource, usize),
+}
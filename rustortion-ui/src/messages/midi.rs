@@ -1,3 +1,6 @@
+use crate::components::widgets::search_select::SearchSelectMessage;
+use crate::mappings::MappingMode;
+
 #[derive(Debug, Clone)]
 pub enum MidiMessage {
     Open,
@@ -8,6 +11,22 @@ pub enum MidiMessage {
     StartLearning,
     CancelLearning,
     PresetForMappingSelected(String),
+    /// Map the captured input to hold-to-tune instead of a preset.
+    HoldTunerToggled(bool),
+    /// Map the captured input to "previous preset" instead of a preset.
+    PreviousPresetToggled(bool),
+    /// Map the captured input to the setlist's next song instead of a preset.
+    NextSongToggled(bool),
+    /// Map the captured input to the setlist's previous song instead of a preset.
+    PreviousSongToggled(bool),
+    /// Map the captured input to toggling standby instead of a preset.
+    ToggleStandbyToggled(bool),
+    /// Map the captured input to toggling the IR bypass instead of a preset.
+    ToggleIrBypassToggled(bool),
+    /// How the captured input switches its setting, for the actions that
+    /// switch one.
+    MappingModeSelected(MappingMode),
+    PresetSearch(SearchSelectMessage),
     ConfirmMapping,
     RemoveMapping(usize),
     Update,
@@ -0,0 +1,14 @@
+#[derive(Debug, Clone)]
+pub enum MissingIrMessage {
+    /// IR named by a preset that was just loaded. Loaded if it was scanned,
+    /// otherwise the missing-IR banner is shown.
+    Check { preset: String, ir_name: String },
+    /// Load the single file-stem match the banner suggested.
+    UseSuggestion,
+    /// Open the IR picker; the next IR picked replaces the missing one.
+    ChooseReplacement,
+    /// Also write the replacement into the preset file.
+    WriteBackToggled(bool),
+    /// Bypass the cabinet and dismiss the banner.
+    ContinueWithoutIr,
+}
@@ -1,18 +1,53 @@
-use crate::stages::{StageConfig, StageType};
+use crate::components::view_menu::ViewToggle;
+use crate::components::widgets::search_select::SearchSelectMessage;
+use crate::randomize::RandomizeIntensity;
+use crate::stages::{StageConfig, StageType, TrimMessage};
 use crate::tabs::Tab;
+use crate::templates::ChainTemplate;
+use rustortion_core::audio::ambience::AmbienceConfig;
+use rustortion_core::audio::double_tracker::DoubleTrackerConfig;
+use rustortion_core::ir::align::IrAlignment;
 use rustortion_core::preset::InputFilterConfig;
 
+pub mod calibration;
+pub mod denoise;
+pub mod gain_staging;
 pub mod hotkey;
+pub mod import;
+pub mod ir_compare;
+pub mod macros;
+pub mod mappings;
 pub mod midi;
+pub mod missing_ir;
 pub mod preset;
+pub mod render;
+pub mod setlist;
 pub mod settings;
+pub mod setup;
+pub mod takes;
+pub mod tone_match;
 pub mod tuner;
+pub mod window;
 
+pub use calibration::*;
+pub use denoise::*;
+pub use gain_staging::*;
 pub use hotkey::*;
+pub use import::*;
+pub use ir_compare::*;
+pub use macros::*;
+pub use mappings::*;
 pub use midi::*;
+pub use missing_ir::*;
 pub use preset::*;
+pub use render::*;
+pub use setlist::*;
 pub use settings::*;
+pub use setup::*;
+pub use takes::*;
+pub use tone_match::*;
 pub use tuner::*;
+pub use window::*;
 
 pub use crate::stages::{
     CompressorMessage, LevelMessage, MultibandSaturatorMessage, NoiseGateMessage, PowerAmpMessage,
@@ -29,12 +64,49 @@ pub enum Message {
     RemoveStage(usize),
     MoveStageUp(usize),
     MoveStageDown(usize),
+    MoveStageTo {
+        from: usize,
+        to: usize,
+    },
+    StageDragStarted(usize),
+    StageDragHovered(usize),
+    StageDragLeft(usize),
+    StageDragReleased,
     ToggleStageCollapse(usize),
     ToggleAllStagesCollapse,
+    /// Collapse every card in the stage's tab except that stage, which is expanded.
+    CollapseAllExcept(usize),
+    /// Collapse state saved with a preset, by chain index; sent after `SetStages`.
+    SetStageCollapse(Vec<bool>),
+    /// Scroll the stage list to a card and focus it, from the overview strip.
+    ScrollToStage(usize),
     ToggleStageBypass(usize),
+    /// Hear the chain only up to this stage, or stop if it's already soloed.
+    /// Shift keeps the cabinet and post effects on the soloed signal.
+    ToggleStageSolo(usize),
+    /// Keep the stage out of randomizing and templates, and across preset
+    /// switches if that's turned on.
+    ToggleStageLock(usize),
+    /// Switch the power amp into or out of standby.
+    ToggleStandby,
+    /// Input/output trim or polarity from a stage card's header.
+    StageTrim(usize, TrimMessage),
     StageTypeSelected(StageType),
     RebuildTick,
     SetStages(Vec<StageConfig>),
+    /// A preset's chain, replacing the current one. Locked stages are
+    /// carried into it if the user keeps them across presets.
+    SetPresetStages(Vec<StageConfig>),
+    /// Blink the name of a preset waiting for the next bar, and clear it
+    /// once it lands.
+    PendingPresetTick,
+    RandomizeIntensityChanged(RandomizeIntensity),
+    /// Replace the chain with a variation on it at the selected intensity.
+    RandomizeChain,
+    /// Rearrange the amp stages into a template's order, adding what's missing.
+    ApplyChainTemplate(ChainTemplate),
+    /// Put the chain back the way it was before the last randomize or template.
+    Undo,
 
     // Input filter messages
     InputFilterHighpassToggle(bool),
@@ -49,14 +121,102 @@ pub enum Message {
     // Recording messages
     StartRecording,
     StopRecording,
+    /// Poll for JACK transport start/stop while recording follows transport.
+    TransportTick,
+
+    // Audio watchdog messages
+    /// Check the engine's frame counter for a stalled stream.
+    WatchdogTick,
+    /// Connect a restarted stream's ports once its IR is in.
+    WarmStartTick,
+    /// Put in the IRs scanned for a new sample rate once they're ready.
+    IrRescanTick,
+    /// Restart the audio stream from the stall banner.
+    RestartAudio,
+    DismissAudioStall,
+
+    // Directory watcher messages
+    /// Collect IR and preset directory changes the watcher has settled on.
+    DirWatchTick,
+    /// The backend rescanned its IRs; refresh the picker.
+    IrListChanged,
+    /// Files in the preset directory changed; rescan it.
+    PresetListChanged,
+
+    // Remote control messages
+    /// Collect commands the OSC remote-control server received.
+    RemoteTick,
+
+    // Offline render messages
+    Render(RenderMessage),
+
+    // Take comparison messages
+    Takes(TakesMessage),
+
+    // Drag-and-drop import messages
+    Import(ImportMessage),
 
     // Settings messages
     Settings(SettingsMessage),
 
+    // First-run setup messages
+    Setup(SetupMessage),
+
+    // Window geometry messages
+    Window(WindowMessage),
+
+    // Setlist messages
+    Setlist(SetlistMessage),
+
+    // Input calibration messages
+    Calibration(CalibrationMessage),
+
+    // Gain staging assistant messages
+    GainStaging(GainStagingMessage),
+
+    // Tone match messages
+    ToneMatch(ToneMatchMessage),
+
     // IR Cabinet messages
     IrSelected(String),
+    IrSearch(SearchSelectMessage),
+    /// Step through the scanned IR list by this many entries, wrapping around.
+    IrStep(i32),
+    /// Jump to a random IR for blind comparison.
+    IrShuffle,
+    /// Limit the IR picker to IRs with this mic or speaker tag, or show all.
+    IrTagFilter(Option<String>),
+    IrFlashTick,
+    /// Check on the thumbnail of the IR being previewed.
+    IrPreviewTick,
     IrBypassed(bool),
     IrGainChanged(f32),
+    /// Reload the IR with a new sample offset or polarity.
+    IrAlignmentChanged(IrAlignment),
+    /// Shift the IR so its onset lands on the first sample.
+    IrAutoAlign,
+    /// Level-matched A/B/C comparison of up to three IRs.
+    IrCompare(IrCompareMessage),
+    MissingIr(MissingIrMessage),
+    DismissIrError,
+
+    /// Input denoiser settings and its noise print.
+    Denoise(DenoiseMessage),
+
+    /// Post-IR ambience settings, all three at once.
+    AmbienceChanged(AmbienceConfig),
+    /// Post-IR stereo double settings, all four at once.
+    DoubleTrackerChanged(DoubleTrackerConfig),
+    /// Global output tilt in dB, for the room rather than the preset.
+    TiltChanged(f32),
+    /// The loaded preset's own tilt, added to the global one.
+    PresetTiltOffsetChanged(f32),
+    /// The loaded preset's song tempo, for the metronome. Only sent when it
+    /// has one.
+    PresetTempo {
+        bpm: Option<f32>,
+        time_signature: Option<(u8, u8)>,
+    },
 
     // Pitch shift messages
     PitchShiftChanged(i32),
@@ -67,6 +227,9 @@ pub enum Message {
     // Stage-specific messages
     Stage(usize, StageMessage),
 
+    /// Controls each moving several stage parameters, and their editor.
+    Macro(MacroMessage),
+
     // Tuner messages
     Tuner(TunerMessage),
 
@@ -75,10 +238,18 @@ pub enum Message {
 
     // Hotkey messages
     Hotkey(HotkeyMessage),
+    /// The overview of hotkey and MIDI mappings together.
+    Mappings(MappingsMessage),
     KeyPressed(iced::keyboard::Key, iced::keyboard::Modifiers),
+    /// Only used to end hold-to-tune; press handling covers everything else.
+    KeyReleased(iced::keyboard::Key),
+    /// Held modifiers, for clicks that read them.
+    ModifiersChanged(iced::keyboard::Modifiers),
 
     // Peak meter messages
     PeakMeterUpdate,
+    /// An entry picked from the View menu, with its state before the pick.
+    ViewToggled(ViewToggle),
 }
 
 impl From<PresetMessage> for Message {
@@ -93,18 +264,84 @@ impl From<HotkeyMessage> for Message {
     }
 }
 
+impl From<MappingsMessage> for Message {
+    fn from(msg: MappingsMessage) -> Self {
+        Self::Mappings(msg)
+    }
+}
+
+impl From<SetlistMessage> for Message {
+    fn from(msg: SetlistMessage) -> Self {
+        Self::Setlist(msg)
+    }
+}
+
 impl From<MidiMessage> for Message {
     fn from(msg: MidiMessage) -> Self {
         Self::Midi(msg)
     }
 }
 
+impl From<MacroMessage> for Message {
+    fn from(msg: MacroMessage) -> Self {
+        Self::Macro(msg)
+    }
+}
+
+impl From<IrCompareMessage> for Message {
+    fn from(msg: IrCompareMessage) -> Self {
+        Self::IrCompare(msg)
+    }
+}
+
+impl From<TakesMessage> for Message {
+    fn from(msg: TakesMessage) -> Self {
+        Self::Takes(msg)
+    }
+}
+
+impl From<MissingIrMessage> for Message {
+    fn from(msg: MissingIrMessage) -> Self {
+        Self::MissingIr(msg)
+    }
+}
+
+impl From<CalibrationMessage> for Message {
+    fn from(msg: CalibrationMessage) -> Self {
+        Self::Calibration(msg)
+    }
+}
+
+impl From<GainStagingMessage> for Message {
+    fn from(msg: GainStagingMessage) -> Self {
+        Self::GainStaging(msg)
+    }
+}
+
+impl From<ToneMatchMessage> for Message {
+    fn from(msg: ToneMatchMessage) -> Self {
+        Self::ToneMatch(msg)
+    }
+}
+
+impl From<RenderMessage> for Message {
+    fn from(msg: RenderMessage) -> Self {
+        Self::Render(msg)
+    }
+}
+
 impl From<SettingsMessage> for Message {
     fn from(msg: SettingsMessage) -> Self {
         Self::Settings(msg)
     }
 }
 
+impl From<SetupMessage> for Message {
+    fn from(msg: SetupMessage) -> Self {
+        Self::Setup(msg)
+    }
+}
+
 impl From<TunerMessage> for Message {
     fn from(msg: TunerMessage) -> Self {
         Self::Tuner(msg)
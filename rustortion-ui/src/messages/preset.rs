@@ -1,9 +1,33 @@
+use std::path::PathBuf;
+
+use crate::components::widgets::search_select::SearchSelectMessage;
+
 #[derive(Debug, Clone)]
 pub enum PresetMessage {
     Select(String),
     Save(String),
     Update,
+    /// Move a preset to the trash.
     Delete(String),
+    /// Bring back the preset deleted last, while the bar still offers it.
+    UndoDelete,
+    /// Bring a preset back out of the trash.
+    Restore(PathBuf),
+    /// Delete a trashed preset for good.
+    Purge(PathBuf),
+    /// Load a preset from the inbox to try it, offering to keep or discard
+    /// it.
+    OpenInbox(String),
+    /// Move a preset from the inbox into the library.
+    KeepInbox(String),
+    /// Move a preset from the inbox to the trash.
+    DiscardInbox(String),
+    /// Go back to the previously selected preset; pressed again, return.
+    Previous,
+    /// Compare the current chain against the selected preset on disk.
+    ShowDiff,
+    /// Discard changes and reload the selected preset from disk.
+    Revert,
     Gui(PresetGuiMessage),
 }
 
@@ -14,4 +38,25 @@ pub enum PresetGuiMessage {
     NameChanged(String),
     ConfirmOverwrite,
     CancelOverwrite,
+    CloseDiff,
+    Search(SearchSelectMessage),
+    ShowManage,
+    CloseManage,
+    /// Ages the undo offered after a delete.
+    UndoTick,
+    /// Open the tag and tempo editor for the selected preset; Update saves
+    /// it.
+    EditTags,
+    CancelTags,
+    /// Comma-separated tags typed into the editor.
+    TagsChanged(String),
+    /// The song's BPM typed into the editor; blank for none.
+    BpmChanged(String),
+    /// The song's time signature typed into the editor, as `"6/8"`.
+    TimeSignatureChanged(String),
+    /// Finish the tag being typed with a suggested one.
+    CompleteTag(String),
+    /// Add a tag to the preset list's filter, or take it off.
+    ToggleTagFilter(String),
+    ClearTagFilter,
 }
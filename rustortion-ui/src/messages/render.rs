@@ -0,0 +1,25 @@
+#[derive(Debug, Clone)]
+pub enum RenderMessage {
+    Open,
+    Close,
+    InputPathChanged(String),
+    OutputPathChanged(String),
+    /// Sidecar of a recorded take; when set, its preset and knob moves are
+    /// replayed instead of rendering with the current chain.
+    AutomationPathChanged(String),
+    Start,
+    /// Length of an IR capture, in samples.
+    CaptureLengthChanged(usize),
+    /// Export the current chain's linear response as an IR.
+    CaptureIr,
+    /// Play the input file through the live chain into a new take, with
+    /// JACK freewheeling so it runs faster than realtime.
+    Bounce,
+    /// End a running bounce early, keeping what was taken so far.
+    CancelBounce,
+    /// Hear the live input under the file while bouncing, instead of the
+    /// file alone.
+    BounceMixToggled(bool),
+    /// Poll the running render or bounce for progress.
+    Update,
+}
@@ -0,0 +1,27 @@
+#[derive(Debug, Clone)]
+pub enum SetlistMessage {
+    Open,
+    Close,
+    /// Load a saved setlist into the editor.
+    Edit(String),
+    /// Start a new, empty setlist in the editor.
+    New,
+    NameChanged(String),
+    /// Preset picked for the next entry to add.
+    PresetSelected(String),
+    AddEntry,
+    RemoveEntry(usize),
+    MoveEntryUp(usize),
+    MoveEntryDown(usize),
+    EntryNotesChanged(usize, String),
+    Save,
+    Delete,
+    /// Play the setlist in the editor: load its first song and show the
+    /// current and next songs above the chain.
+    Start,
+    /// Stop following a setlist and hide the song display.
+    Stop,
+    /// Step to the next or previous song, from a footswitch or hotkey.
+    Next,
+    Previous,
+}
@@ -1,4 +1,11 @@
 use crate::i18n::Language;
+use crate::theme::ThemeChoice;
+use rustortion_core::audio::host::HostApi;
+use rustortion_core::audio::samplers::ResamplerQuality;
+use rustortion_core::audio::test_source::TestSourceConfig;
+use rustortion_core::hooks::EventKind;
+use rustortion_core::metronome::MetronomeConfig;
+use rustortion_core::tuner::TunerAlgorithm;
 
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
@@ -6,12 +13,60 @@ pub enum SettingsMessage {
     Close,
     Apply,
     RefreshPorts,
+    HostApiChanged(HostApi),
+    /// CPAL capture device; empty for the system default.
+    InputDeviceChanged(String),
+    /// CPAL playback device; empty for the system default.
+    OutputDeviceChanged(String),
     InputPortChanged(String),
+    /// Empty for no right input.
+    InputRightPortChanged(String),
     OutputLeftPortChanged(String),
     OutputRightPortChanged(String),
     BufferSizeChanged(u32),
     SampleRateChanged(u32),
+    ResamplerQualityChanged(ResamplerQuality),
+    AdaptiveQualityToggled(bool),
+    /// Dual-mono on or off.
+    ChainModeToggled(bool),
     LanguageChanged(Language),
+    ThemeChanged(ThemeChoice),
+    /// Light/dark mode reported by the platform, for "follow system".
+    SystemThemeChanged(iced::theme::Mode),
     NamDirChanged(String),
     RescanNamModels,
+    RecordFollowTransportToggled(bool),
+    RecordAutomationToggled(bool),
+    /// Soft-clip takes as they're written; takes the next one.
+    RecordingSoftclipToggled(bool),
+    TunerAlgorithmChanged(TunerAlgorithm),
+    ChainLintToggled(bool),
+    /// Carry locked stages into each preset switched to.
+    KeepLockedStagesToggled(bool),
+    /// Hold a switched-to preset's amp chain for the metronome's next bar
+    /// while it runs.
+    QuantizeChainChangesToggled(bool),
+    WatchdogAutoRestartToggled(bool),
+    WatchdogStallSecondsChanged(u32),
+    RemoteToggled(bool),
+    /// Port typed into the remote-control field, staged until submitted.
+    RemotePortChanged(String),
+    RemotePortSubmitted,
+    HooksToggled(bool),
+    /// Command typed in for an event's hook, staged until submitted.
+    HookCommandChanged(EventKind, String),
+    HookCommandSubmitted(EventKind),
+    TestSourceChanged(TestSourceConfig),
+    TestDiFileChanged(String),
+    LoadTestDi,
+    MetronomeTickChanged(String),
+    LoadMetronomeTick,
+    MetronomeChanged(MetronomeConfig),
+    RecordClickTrackToggled(bool),
+    CopyDiagnostics,
+    CalibrateInput,
+    /// Close the settings and run the first-run setup again.
+    RunSetup,
+    AnalyzeGainStaging,
+    MatchTone,
 }
@@ -0,0 +1,28 @@
+/// First-run setup, also run again from the settings dialog.
+#[derive(Debug, Clone)]
+pub enum SetupMessage {
+    /// Run the setup again, from its first step.
+    Open,
+    /// Leave the setup, keeping what was set so far.
+    Skip,
+    Back,
+    Next,
+    /// Look for an audio server again. While audio can't start, also try to
+    /// start it; sent on a timer then, too.
+    Retry,
+    /// Close the app, while it's held on the first step without audio.
+    Quit,
+    /// JACK input port, or capture device under CPAL.
+    InputChanged(String),
+    /// JACK left output port, or playback device under CPAL.
+    OutputLeftChanged(String),
+    OutputRightChanged(String),
+    RefreshPorts,
+    RecordingDirChanged(String),
+    IrDirChanged(String),
+    PresetDirChanged(String),
+    InstallPresetsToggled(bool),
+    /// Finish and open the MIDI dialog to pick and learn a controller.
+    SetUpMidi,
+    Finish,
+}
@@ -0,0 +1,17 @@
+#[derive(Debug, Clone)]
+pub enum TakesMessage {
+    /// Load the latest takes and start comparing them.
+    Open,
+    /// Stop comparing and go back to the live output.
+    Close,
+    /// Load the latest takes again, after recording another.
+    Reload,
+    /// Bring every take to the same peak, reloading them.
+    NormalizeToggled(bool),
+    /// Play this take from where the playhead is.
+    Select(usize),
+    PlayPause,
+    Rewind,
+    /// Poll the playhead while playing.
+    Update,
+}
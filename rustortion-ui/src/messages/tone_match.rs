@@ -0,0 +1,16 @@
+#[derive(Debug, Clone)]
+pub enum ToneMatchMessage {
+    Open,
+    Close,
+    /// DI recording to feed through both sides; empty for pink noise.
+    SourcePathChanged(String),
+    /// Match to another preset fed the same source.
+    ReferencePresetSelected(String),
+    /// Match to a recording instead of a preset.
+    ReferencePathChanged(String),
+    Start,
+    /// Poll the running analysis for progress.
+    Update,
+    /// Append the correction EQ to the chain.
+    Apply,
+}
@@ -1,5 +1,14 @@
+use rustortion_core::tuner::{TargetNote, TunerMode};
+
 #[derive(Debug, Clone)]
 pub enum TunerMessage {
     Toggle,
     Update,
+    /// A hold-to-tune key or footswitch went down: mute and show the tuner.
+    Hold,
+    /// The hold-to-tune input was released: go back to how things were.
+    Release,
+    ModeChanged(TunerMode),
+    /// Read against this note only, or `None` to follow the nearest note.
+    LockChanged(Option<TargetNote>),
 }
@@ -0,0 +1,23 @@
+use iced::{Point, Size, window};
+
+/// The main window's geometry, remembered across runs. Only the standalone
+/// app listens for it.
+#[derive(Debug, Clone)]
+pub enum WindowMessage {
+    /// The window is open; check it landed on a monitor.
+    Opened(window::Id),
+    /// Size of the monitor the window opened on, if the platform said.
+    MonitorSize(window::Id, Option<Size>),
+    Moved(window::Id, Point),
+    Resized(window::Id, Size),
+    /// A move or resize, with whether the window is maximized after it.
+    /// The size and position of a maximized window aren't kept.
+    Changed {
+        position: Option<Point>,
+        size: Option<Size>,
+        maximized: bool,
+    },
+    /// Save the geometry once the window has stopped moving.
+    SaveTick,
+    CloseRequested(window::Id),
+}
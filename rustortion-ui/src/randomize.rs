@@ -0,0 +1,397 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rustortion_core::amp::chain::DEFAULT_CHAIN_CAPACITY;
+use rustortion_core::amp::stages::Stage;
+use rustortion_core::amp::stages::common::db_to_lin;
+use rustortion_core::amp::stages::delay::NoteDivision;
+
+use crate::stages::poweramp::POWER_AMP_TYPES;
+use crate::stages::preamp::CLIPPER_TYPES;
+use crate::stages::tonestack::TONE_STACK_MODELS;
+use crate::stages::wah::WAH_MODES;
+use crate::stages::{StageConfig, StageType};
+use crate::tr;
+
+/// Rate stages are built at to check values against their limits; the limits
+/// don't depend on it.
+const VALIDATION_SAMPLE_RATE: f32 = 48_000.0;
+/// Halvings towards the original value when a rolled value is out of range.
+const BISECT_STEPS: usize = 16;
+/// Most a Level stage may be turned up, so a roll can't get much louder.
+const MAX_LEVEL_BOOST_DB: f32 = 3.0;
+/// Chance, per stage, that a medium or wild roll changes a choice like the
+/// clipper type.
+const CHOICE_CHANCE: f32 = 0.3;
+/// Stages a wild roll may add or take away. Gain stages and models are left
+/// out, so the chain keeps its character and its loudness.
+const WILD_STAGES: [StageType; 7] = [
+    StageType::NoiseGate,
+    StageType::Compressor,
+    StageType::ToneStack,
+    StageType::Eq,
+    StageType::Delay,
+    StageType::Reverb,
+    StageType::Tremolo,
+];
+
+/// How far a roll may take the chain from where it is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RandomizeIntensity {
+    /// Continuous parameters move by up to 10%.
+    #[default]
+    Subtle,
+    /// Up to 30%, and choices like the clipper type may change.
+    Medium,
+    /// Up to 50%, choices may change and a stage may be added or removed.
+    Wild,
+}
+
+impl RandomizeIntensity {
+    pub const ALL: [Self; 3] = [Self::Subtle, Self::Medium, Self::Wild];
+
+    /// Largest move of a parameter, relative to its value (or to 1 for
+    /// values smaller than that, so a parameter at zero still moves).
+    const fn amount(self) -> f32 {
+        match self {
+            Self::Subtle => 0.1,
+            Self::Medium => 0.3,
+            Self::Wild => 0.5,
+        }
+    }
+}
+
+impl std::fmt::Display for RandomizeIntensity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Subtle => write!(f, "{}", tr!(randomize_subtle)),
+            Self::Medium => write!(f, "{}", tr!(randomize_medium)),
+            Self::Wild => write!(f, "{}", tr!(randomize_wild)),
+        }
+    }
+}
+
+/// Source of randomness for [`randomize_chain`]; tests use a seeded one.
+pub trait Rng {
+    fn next_u32(&mut self) -> u32;
+
+    /// Uniform in `0.0..1.0`.
+    fn unit(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform in `-1.0..1.0`.
+    fn signed(&mut self) -> f32 {
+        self.unit().mul_add(2.0, -1.0)
+    }
+
+    /// Uniform in `0..n`; `n` must not be 0.
+    fn below(&mut self, n: usize) -> usize {
+        ((self.unit() * n as f32) as usize).min(n - 1)
+    }
+}
+
+/// Xorshift generator, plenty for picking sounds.
+#[derive(Debug, Clone)]
+pub struct XorShift(u32);
+
+impl XorShift {
+    pub const fn new(seed: u32) -> Self {
+        // Zero is xorshift's one fixed point.
+        Self(if seed == 0 { 0x9E37_79B9 } else { seed })
+    }
+
+    /// Seeded from the clock, for a different roll every session.
+    pub fn from_clock() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        Self::new(nanos)
+    }
+}
+
+impl Rng for XorShift {
+    fn next_u32(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0
+    }
+}
+
+/// A variation on `stages` for sound exploration.
+///
+/// Values stay inside the limits each stage's `set_parameter` enforces, and
+/// Level stages are never turned up by more than 3 dB. Model stages (NAM,
+/// captures) and locked stages are left as they are; groups have their
+/// children varied.
+pub fn randomize_chain(
+    stages: &[StageConfig],
+    intensity: RandomizeIntensity,
+    rng: &mut impl Rng,
+) -> Vec<StageConfig> {
+    let mut stages = stages.to_vec();
+    for stage in stages.iter_mut().filter(|s| !s.locked()) {
+        vary_stage(stage, intensity, rng);
+    }
+    if intensity == RandomizeIntensity::Wild {
+        add_or_remove_stage(&mut stages, rng);
+    }
+    stages
+}
+
+fn vary_stage(cfg: &mut StageConfig, intensity: RandomizeIntensity, rng: &mut impl Rng) {
+    match cfg {
+        StageConfig::Group(group) => {
+            for child in &mut group.stages {
+                vary_stage(child, intensity, rng);
+            }
+            return;
+        }
+        StageConfig::Nam(_) | StageConfig::Capture(_) => return,
+        _ => {}
+    }
+
+    let mut stage = cfg.to_runtime(VALIDATION_SAMPLE_RATE);
+    let is_level = cfg.stage_type() == StageType::Level;
+    for (name, value) in cfg.float_params_mut() {
+        let original = *value;
+        let target = rng
+            .signed()
+            .mul_add(intensity.amount() * original.abs().max(1.0), original);
+        *value = settle(&mut *stage, name, original, target);
+        if is_level {
+            *value = value.min(original * db_to_lin(MAX_LEVEL_BOOST_DB));
+        }
+    }
+
+    if intensity != RandomizeIntensity::Subtle && rng.unit() < CHOICE_CHANCE {
+        vary_choice(cfg, rng);
+    }
+}
+
+/// `target` if the stage takes it, otherwise the value closest to it on the
+/// way back to `original`. A value the stage already refuses is kept.
+fn settle(stage: &mut dyn Stage, name: &str, original: f32, target: f32) -> f32 {
+    if stage.set_parameter(name, target).is_ok() {
+        return target;
+    }
+    if stage.set_parameter(name, original).is_err() {
+        return original;
+    }
+
+    let (mut accepted, mut refused) = (original, target);
+    for _ in 0..BISECT_STEPS {
+        let mid = 0.5 * (accepted + refused);
+        if stage.set_parameter(name, mid).is_ok() {
+            accepted = mid;
+        } else {
+            refused = mid;
+        }
+    }
+    accepted
+}
+
+fn pick<T: Copy>(rng: &mut impl Rng, choices: &[T]) -> T {
+    choices[rng.below(choices.len())]
+}
+
+fn vary_choice(cfg: &mut StageConfig, rng: &mut impl Rng) {
+    match cfg {
+        StageConfig::Preamp(c) => c.clipper_type = pick(rng, &CLIPPER_TYPES),
+        StageConfig::PowerAmp(c) => c.amp_type = pick(rng, &POWER_AMP_TYPES),
+        StageConfig::ToneStack(c) => c.model = pick(rng, &TONE_STACK_MODELS),
+        StageConfig::Wah(c) => c.mode = pick(rng, &WAH_MODES),
+        StageConfig::Delay(c) if c.sync => c.division = pick(rng, &NoteDivision::ALL),
+        _ => {}
+    }
+}
+
+/// Add a stage from [`WILD_STAGES`] at the end of its tab, or take an
+/// unlocked one of them away; a coin decides, unless only one of the two is
+/// possible.
+fn add_or_remove_stage(stages: &mut Vec<StageConfig>, rng: &mut impl Rng) {
+    let removable: Vec<usize> = stages
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| !s.locked() && WILD_STAGES.contains(&s.stage_type()))
+        .map(|(i, _)| i)
+        .collect();
+    let can_add = stages.len() < DEFAULT_CHAIN_CAPACITY;
+
+    let add = match (can_add, removable.is_empty()) {
+        (false, true) => return,
+        (true, false) => rng.unit() < 0.5,
+        (can_add, _) => can_add,
+    };
+
+    if add {
+        let stage = StageConfig::from(pick(rng, &WILD_STAGES));
+        let category = stage.category();
+        let at = stages
+            .iter()
+            .rposition(|s| s.category() == category)
+            .map_or(stages.len(), |i| i + 1);
+        stages.insert(at, stage);
+    } else {
+        stages.remove(pick(rng, &removable));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Vec<StageConfig> {
+        [
+            StageType::NoiseGate,
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+            StageType::Eq,
+            StageType::Level,
+            StageType::Delay,
+        ]
+        .into_iter()
+        .map(StageConfig::from)
+        .collect()
+    }
+
+    /// Every float parameter is one its stage accepts.
+    fn assert_in_range(stages: &mut [StageConfig]) {
+        for cfg in stages {
+            if matches!(
+                cfg,
+                StageConfig::Nam(_) | StageConfig::Capture(_) | StageConfig::Group(_)
+            ) {
+                continue;
+            }
+            let mut stage = cfg.to_runtime(VALIDATION_SAMPLE_RATE);
+            for (name, value) in cfg.float_params_mut() {
+                assert!(
+                    stage.set_parameter(name, *value).is_ok(),
+                    "{name} = {value} is out of range"
+                );
+            }
+        }
+    }
+
+    fn level_gain(stages: &[StageConfig]) -> f32 {
+        stages
+            .iter()
+            .find_map(|s| match s {
+                StageConfig::Level(c) => Some(c.gain),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn rolls_stay_in_range() {
+        let mut rng = XorShift::new(7);
+        for intensity in RandomizeIntensity::ALL {
+            let mut stages = chain();
+            for _ in 0..50 {
+                stages = randomize_chain(&stages, intensity, &mut rng);
+                assert_in_range(&mut stages);
+            }
+        }
+    }
+
+    #[test]
+    fn subtle_moves_each_value_by_at_most_ten_percent() {
+        let mut original = chain();
+        let mut rolled =
+            randomize_chain(&original, RandomizeIntensity::Subtle, &mut XorShift::new(3));
+        assert_eq!(rolled.len(), original.len());
+
+        let mut moved = false;
+        for (before, after) in original.iter_mut().zip(&mut rolled) {
+            assert_eq!(before.stage_type(), after.stage_type());
+            for ((_, a), (_, b)) in before
+                .float_params_mut()
+                .into_iter()
+                .zip(after.float_params_mut())
+            {
+                assert!((*b - *a).abs() <= 0.1f32.mul_add(a.abs().max(1.0), 1e-4));
+                moved |= a != b;
+            }
+        }
+        assert!(moved);
+    }
+
+    #[test]
+    fn subtle_keeps_choices() {
+        let original = chain();
+        let rolled = randomize_chain(
+            &original,
+            RandomizeIntensity::Subtle,
+            &mut XorShift::new(11),
+        );
+        for (before, after) in original.iter().zip(&rolled) {
+            match (before, after) {
+                (StageConfig::Preamp(a), StageConfig::Preamp(b)) => {
+                    assert_eq!(a.clipper_type, b.clipper_type);
+                }
+                (StageConfig::ToneStack(a), StageConfig::ToneStack(b)) => {
+                    assert_eq!(a.model, b.model);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn level_never_rises_more_than_3_db() {
+        let limit = level_gain(&chain()) * db_to_lin(MAX_LEVEL_BOOST_DB);
+        let mut rng = XorShift::new(5);
+        for _ in 0..200 {
+            let rolled = randomize_chain(&chain(), RandomizeIntensity::Wild, &mut rng);
+            assert!(level_gain(&rolled) <= limit + 1e-6);
+        }
+    }
+
+    #[test]
+    fn wild_adds_or_removes_whitelisted_stages_only() {
+        let original = chain();
+        let count = |stages: &[StageConfig], t: StageType| {
+            stages.iter().filter(|s| s.stage_type() == t).count()
+        };
+
+        let mut rng = XorShift::new(9);
+        for _ in 0..50 {
+            let rolled = randomize_chain(&original, RandomizeIntensity::Wild, &mut rng);
+            assert_eq!(rolled.len().abs_diff(original.len()), 1);
+            for t in [StageType::Preamp, StageType::PowerAmp, StageType::Level] {
+                assert_eq!(count(&rolled, t), count(&original, t));
+            }
+        }
+
+        let medium = randomize_chain(&original, RandomizeIntensity::Medium, &mut rng);
+        assert_eq!(medium.len(), original.len());
+    }
+
+    #[test]
+    fn locked_stages_are_left_alone() {
+        let mut original = chain();
+        for stage in &mut original {
+            stage.set_locked(true);
+        }
+
+        let mut rng = XorShift::new(13);
+        for intensity in RandomizeIntensity::ALL {
+            for _ in 0..20 {
+                let rolled = randomize_chain(&original, intensity, &mut rng);
+                for stage in &original {
+                    assert!(rolled.contains(stage), "{stage:?} was changed");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_range_rolls_settle_at_the_limit() {
+        let mut stage = StageConfig::from(StageType::Preamp).to_runtime(VALIDATION_SAMPLE_RATE);
+        let settled = settle(&mut *stage, "gain", 9.5, 12.0);
+        assert!(settled <= 10.0 && settled > 9.99, "{settled}");
+    }
+}
@@ -0,0 +1,167 @@
+use iced::widget::{button, column, pick_list, row, text};
+use iced::{Alignment, Element, Length};
+
+use rustortion_core::amp::stages::capture::CaptureConfig;
+use rustortion_core::capture::registry;
+
+use crate::components::widgets::common::{
+    labeled_slider, stage_card, StageViewState, COLOR_WARNING, SPACING_NORMAL, SPACING_TIGHT,
+};
+use crate::messages::Message;
+use crate::tr;
+
+use super::{ParamUpdate, StageMessage};
+
+// --- Message ---
+
+#[derive(Debug, Clone)]
+pub enum CaptureMessage {
+    CaptureSelected(Option<String>),
+    InputGainChanged(f32),
+    OutputGainChanged(f32),
+    MixChanged(f32),
+    /// Re-scan the captures directory and refresh the pick-list.
+    Rescan,
+}
+
+/// A pick-list entry: a capture file, or "none" to go back to passthrough.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CaptureChoice {
+    None,
+    Capture(String),
+}
+
+impl CaptureChoice {
+    fn into_option(self) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::Capture(name) => Some(name),
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str(tr!(capture_none)),
+            Self::Capture(name) => f.write_str(name),
+        }
+    }
+}
+
+// --- Apply ---
+
+pub fn apply(cfg: &mut CaptureConfig, msg: CaptureMessage) -> Option<ParamUpdate> {
+    match msg {
+        CaptureMessage::CaptureSelected(name) => {
+            cfg.capture = name;
+            Some(ParamUpdate::NeedsStageRebuild)
+        }
+        CaptureMessage::InputGainChanged(v) => {
+            cfg.input_gain_db = v;
+            Some(ParamUpdate::Changed("input_gain_db", v))
+        }
+        CaptureMessage::OutputGainChanged(v) => {
+            cfg.output_gain_db = v;
+            Some(ParamUpdate::Changed("output_gain_db", v))
+        }
+        CaptureMessage::MixChanged(v) => {
+            cfg.mix = v;
+            Some(ParamUpdate::Changed("mix", v))
+        }
+        CaptureMessage::Rescan => Some(ParamUpdate::RescanCaptures),
+    }
+}
+
+// --- View ---
+
+pub fn view(idx: usize, cfg: &CaptureConfig, state: StageViewState) -> Element<'_, Message> {
+    let capture = cfg.capture.clone();
+    let input_gain_db = cfg.input_gain_db;
+    let output_gain_db = cfg.output_gain_db;
+    let mix = cfg.mix;
+    let captures_dir = state.captures_dir.as_ref().map(|p| p.display().to_string());
+
+    stage_card(tr!(stage_capture), idx, state, move || {
+        let mut choices = vec![CaptureChoice::None];
+        choices.extend(registry::available_names().into_iter().map(CaptureChoice::Capture));
+        let selected = capture.clone().map_or(CaptureChoice::None, CaptureChoice::Capture);
+
+        let selector = row![
+            text(tr!(capture_file)).width(Length::FillPortion(3)),
+            pick_list(choices, Some(selected), move |choice| {
+                Message::Stage(
+                    idx,
+                    StageMessage::Capture(CaptureMessage::CaptureSelected(choice.into_option())),
+                )
+            })
+            .placeholder(tr!(capture_none))
+            .width(Length::FillPortion(7)),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        // What the selected file turned out to be, or why it can't be used
+        // (e.g. a WaveNet `.nam` that belongs in the NAM stage).
+        let info_line: Element<'_, Message> = match capture.as_deref().map(registry::get) {
+            Some(Some(Ok(profile))) => text(format!(
+                "{} · {} {}",
+                profile.architecture,
+                profile.kernel.len(),
+                tr!(capture_taps)
+            ))
+            .into(),
+            Some(Some(Err(reason))) => text(reason)
+                .style(|_| iced::widget::text::Style {
+                    color: Some(COLOR_WARNING),
+                })
+                .into(),
+            Some(None) => text(tr!(capture_not_found)).into(),
+            None => text(String::new()).into(),
+        };
+
+        let dir_text = captures_dir.map_or_else(
+            || format!("{}: —", tr!(captures_dir)),
+            |dir| format!("{}: {dir}", tr!(captures_dir)),
+        );
+        let folder_row = row![
+            text(dir_text).width(Length::Fill),
+            button(text(tr!(capture_rescan)))
+                .on_press(Message::Stage(idx, StageMessage::Capture(CaptureMessage::Rescan))),
+        ]
+        .spacing(SPACING_NORMAL)
+        .align_y(Alignment::Center);
+
+        column![
+            selector,
+            folder_row,
+            info_line,
+            labeled_slider(
+                tr!(capture_input_gain),
+                -24.0..=24.0,
+                input_gain_db,
+                move |v| Message::Stage(idx, StageMessage::Capture(CaptureMessage::InputGainChanged(v))),
+                |v| format!("{v:+.1} dB"),
+                0.1,
+            ),
+            labeled_slider(
+                tr!(capture_output_gain),
+                -24.0..=24.0,
+                output_gain_db,
+                move |v| Message::Stage(idx, StageMessage::Capture(CaptureMessage::OutputGainChanged(v))),
+                |v| format!("{v:+.1} dB"),
+                0.1,
+            ),
+            labeled_slider(
+                tr!(capture_mix),
+                0.0..=1.0,
+                mix,
+                move |v| Message::Stage(idx, StageMessage::Capture(CaptureMessage::MixChanged(v))),
+                |v| format!("{:.0}%", v * 100.0),
+                0.01,
+            ),
+        ]
+        .spacing(SPACING_TIGHT)
+        .into()
+    })
+}
@@ -1,8 +1,10 @@
-use iced::widget::column;
+use iced::widget::{checkbox, column};
 use iced::Element;
 
-use rustortion_core::amp::stages::delay::DelayConfig;
-use crate::components::widgets::common::{labeled_slider, stage_card, StageViewState, SPACING_TIGHT};
+use rustortion_core::amp::stages::delay::{DelayConfig, NoteDivision};
+use crate::components::widgets::common::{
+    labeled_picker, labeled_slider, stage_card, StageViewState, SPACING_TIGHT,
+};
 use crate::messages::Message;
 use crate::tr;
 
@@ -15,6 +17,10 @@ pub enum DelayMessage {
     DelayTimeChanged(f32),
     FeedbackChanged(f32),
     MixChanged(f32),
+    DuckDepthChanged(f32),
+    DuckReleaseChanged(f32),
+    SyncToggled(bool),
+    DivisionChanged(NoteDivision),
 }
 
 // --- Apply ---
@@ -24,6 +30,10 @@ pub const fn apply(cfg: &mut DelayConfig, msg: DelayMessage) -> Option<ParamUpda
         DelayMessage::DelayTimeChanged(v) => { cfg.delay_ms = v; Some(ParamUpdate::Changed("delay_time", v)) }
         DelayMessage::FeedbackChanged(v) => { cfg.feedback = v; Some(ParamUpdate::Changed("feedback", v)) }
         DelayMessage::MixChanged(v) => { cfg.mix = v; Some(ParamUpdate::Changed("mix", v)) }
+        DelayMessage::DuckDepthChanged(v) => { cfg.duck_depth_db = v; Some(ParamUpdate::Changed("duck_depth", v)) }
+        DelayMessage::DuckReleaseChanged(v) => { cfg.duck_release_ms = v; Some(ParamUpdate::Changed("duck_release", v)) }
+        DelayMessage::SyncToggled(on) => { cfg.sync = on; Some(ParamUpdate::Changed("sync", if on { 1.0 } else { 0.0 })) }
+        DelayMessage::DivisionChanged(d) => { cfg.division = d; Some(ParamUpdate::Changed("division", d.index() as f32)) }
     }
 }
 
@@ -34,12 +44,32 @@ pub fn view(
     cfg: &DelayConfig,
     state: StageViewState,
 ) -> Element<'_, Message> {
+    // Without a tempo source the sync controls are hidden and the delay runs
+    // at its time in ms, which is also what the engine does.
+    let tempo_sync = state.tempo_sync;
     stage_card(
         tr!(stage_delay),
         idx,
         state,
         || {
-            column![
+            let mut content = column![].spacing(SPACING_TIGHT);
+
+            if tempo_sync {
+                content = content.push(
+                    checkbox(cfg.sync)
+                        .label(tr!(tempo_sync))
+                        .on_toggle(move |on| Message::Stage(
+                            idx,
+                            StageMessage::Delay(DelayMessage::SyncToggled(on))
+                        )),
+                );
+            }
+
+            let time = if tempo_sync && cfg.sync {
+                labeled_picker(tr!(note_division), NoteDivision::ALL, Some(cfg.division), move |d| {
+                    Message::Stage(idx, StageMessage::Delay(DelayMessage::DivisionChanged(d)))
+                })
+            } else {
                 labeled_slider(
                     tr!(delay_time),
                     0.0..=2000.0,
@@ -50,8 +80,12 @@ pub fn view(
                     ),
                     |v| format!("{v:.0} {}", tr!(ms)),
                     1.0
-                ),
-                labeled_slider(
+                )
+            };
+
+            content
+                .push(time)
+                .push(labeled_slider(
                     tr!(feedback),
                     0.0..=0.95,
                     cfg.feedback,
@@ -61,8 +95,8 @@ pub fn view(
                     ),
                     |v| format!("{v:.2}"),
                     0.01
-                ),
-                labeled_slider(
+                ))
+                .push(labeled_slider(
                     tr!(dry_wet),
                     0.0..=1.0,
                     cfg.mix,
@@ -72,10 +106,30 @@ pub fn view(
                     ),
                     |v| format!("{:.0}%", v * 100.0),
                     0.01
-                ),
-            ]
-            .spacing(SPACING_TIGHT)
-            .into()
+                ))
+                .push(labeled_slider(
+                    tr!(duck_depth),
+                    0.0..=24.0,
+                    cfg.duck_depth_db,
+                    move |v| Message::Stage(
+                        idx,
+                        StageMessage::Delay(DelayMessage::DuckDepthChanged(v))
+                    ),
+                    |v| format!("{v:.1} {}", tr!(db)),
+                    0.5
+                ))
+                .push(labeled_slider(
+                    tr!(duck_release),
+                    50.0..=1000.0,
+                    cfg.duck_release_ms,
+                    move |v| Message::Stage(
+                        idx,
+                        StageMessage::Delay(DelayMessage::DuckReleaseChanged(v))
+                    ),
+                    |v| format!("{v:.0} {}", tr!(ms)),
+                    1.0
+                ))
+                .into()
         },
     )
 }
@@ -1,7 +1,7 @@
 use iced::widget::row;
 use iced::{Element, Length};
 
-use rustortion_core::amp::stages::eq::{BAND_FREQS, EqConfig, MAX_GAIN_DB, MIN_GAIN_DB, NUM_BANDS};
+use rustortion_core::amp::stages::eq::{BAND_FREQS, BAND_NAMES, EqConfig, MAX_GAIN_DB, MIN_GAIN_DB, NUM_BANDS};
 use crate::components::widgets::common::{
     labeled_vertical_slider, stage_card, StageViewState, SPACING_WIDE,
 };
@@ -10,13 +10,6 @@ use crate::tr;
 
 use super::{ParamUpdate, StageMessage};
 
-const BAND_NAMES: [&str; 16] = [
-    "band_0", "band_1", "band_2", "band_3",
-    "band_4", "band_5", "band_6", "band_7",
-    "band_8", "band_9", "band_10", "band_11",
-    "band_12", "band_13", "band_14", "band_15",
-];
-
 // --- Message ---
 
 #[derive(Debug, Clone, Copy)]
@@ -0,0 +1,220 @@
+use iced::widget::{column, container, pick_list};
+use iced::{Element, Length};
+
+use crate::components::widgets::common::{
+    BORDER_RADIUS_CARD, PADDING_SMALL, SPACING_TIGHT, StageViewState, stage_card,
+};
+use crate::messages::Message;
+use crate::tr;
+use rustortion_core::amp::stages::group::{GroupConfig, MAX_GROUP_STAGES};
+
+use super::{
+    ParamUpdate, StageMessage, StageType, TrimMessage, apply_stage_config, apply_trim,
+    view_stage_config,
+};
+
+// --- Message ---
+
+#[derive(Debug, Clone)]
+pub enum GroupMessage {
+    /// A message from the child stage at this index.
+    Child(usize, Box<StageMessage>),
+    AddChild(StageType),
+    RemoveChild(usize),
+    MoveChildUp(usize),
+    MoveChildDown(usize),
+    ToggleChildBypass(usize),
+    ToggleChildCollapse(usize),
+    ChildTrim(usize, TrimMessage),
+}
+
+// --- Apply ---
+
+/// Slider moves go to the live group; anything structural rebuilds it.
+pub fn apply(cfg: &mut GroupConfig, msg: GroupMessage) -> Option<ParamUpdate> {
+    match msg {
+        GroupMessage::Child(child, msg) => {
+            match apply_stage_config(cfg.stages.get_mut(child)?, *msg)? {
+                ParamUpdate::Changed(name, value) => {
+                    Some(ParamUpdate::ChildChanged(child, name, value))
+                }
+                other => Some(other),
+            }
+        }
+        GroupMessage::AddChild(stage_type) => {
+            if stage_type == StageType::Group || cfg.stages.len() >= MAX_GROUP_STAGES {
+                return None;
+            }
+            cfg.collapsed.resize(cfg.stages.len(), false);
+            cfg.stages.push(stage_type.into());
+            cfg.collapsed.push(false);
+            Some(ParamUpdate::NeedsStageRebuild)
+        }
+        GroupMessage::RemoveChild(child) => {
+            if child >= cfg.stages.len() {
+                return None;
+            }
+            cfg.stages.remove(child);
+            if child < cfg.collapsed.len() {
+                cfg.collapsed.remove(child);
+            }
+            Some(ParamUpdate::NeedsStageRebuild)
+        }
+        GroupMessage::MoveChildUp(child) => swap_children(cfg, child.checked_sub(1)?, child),
+        GroupMessage::MoveChildDown(child) => swap_children(cfg, child, child + 1),
+        GroupMessage::ToggleChildBypass(child) => {
+            let stage = cfg.stages.get_mut(child)?;
+            stage.set_bypassed(!stage.bypassed());
+            Some(ParamUpdate::NeedsStageRebuild)
+        }
+        GroupMessage::ToggleChildCollapse(child) => {
+            cfg.toggle_child_collapsed(child);
+            None
+        }
+        GroupMessage::ChildTrim(child, msg) => {
+            let (name, value) = apply_trim(cfg.stages.get_mut(child)?.common_mut(), msg);
+            Some(ParamUpdate::ChildChanged(child, name, value))
+        }
+    }
+}
+
+fn swap_children(cfg: &mut GroupConfig, a: usize, b: usize) -> Option<ParamUpdate> {
+    if b >= cfg.stages.len() {
+        return None;
+    }
+    cfg.stages.swap(a, b);
+    cfg.collapsed.resize(cfg.stages.len(), false);
+    cfg.collapsed.swap(a, b);
+    Some(ParamUpdate::NeedsStageRebuild)
+}
+
+/// Re-address a message from a child card to the group at `idx`. Dragging
+/// a child drags the whole group.
+fn child_to_group(idx: usize, msg: Message) -> Message {
+    let child = |m| Message::Stage(idx, StageMessage::Group(m));
+    match msg {
+        Message::Stage(c, m) => child(GroupMessage::Child(c, Box::new(m))),
+        Message::ToggleStageCollapse(c) => child(GroupMessage::ToggleChildCollapse(c)),
+        Message::ToggleStageBypass(c) => child(GroupMessage::ToggleChildBypass(c)),
+        Message::StageTrim(c, m) => child(GroupMessage::ChildTrim(c, m)),
+        Message::RemoveStage(c) => child(GroupMessage::RemoveChild(c)),
+        Message::MoveStageUp(c) => child(GroupMessage::MoveChildUp(c)),
+        Message::MoveStageDown(c) => child(GroupMessage::MoveChildDown(c)),
+        Message::StageDragStarted(_) => Message::StageDragStarted(idx),
+        other => other,
+    }
+}
+
+// --- View ---
+
+pub fn view(idx: usize, cfg: &GroupConfig, state: StageViewState) -> Element<'_, Message> {
+    let child_state = state.clone();
+    stage_card(tr!(stage_group), idx, state, move || {
+        let last = cfg.stages.len().saturating_sub(1);
+        let mut children = column![].spacing(SPACING_TIGHT);
+        for (child, stage) in cfg.stages.iter().enumerate() {
+            let view = view_stage_config(
+                stage,
+                child,
+                StageViewState {
+                    is_collapsed: cfg.is_child_collapsed(child),
+                    can_move_up: child > 0,
+                    can_move_down: child < last,
+                    bypassed: stage.bypassed(),
+                    common: stage.common(),
+                    is_focused: false,
+                    drag_active: false,
+                    stage_type: stage.stage_type(),
+                    warnings: Vec::new(),
+                    can_solo: false,
+                    can_lock: false,
+                    soloed: false,
+                    // The engine taps the group as a whole.
+                    level: None,
+                    ..child_state.clone()
+                },
+            );
+            children = children.push(view.map(move |m| child_to_group(idx, m)));
+        }
+
+        if cfg.stages.len() < MAX_GROUP_STAGES {
+            let types: Vec<StageType> = StageType::ALL
+                .iter()
+                .copied()
+                .filter(|t| *t != StageType::Group)
+                .collect();
+            children = children.push(
+                pick_list(types, None::<StageType>, move |t| {
+                    Message::Stage(idx, StageMessage::Group(GroupMessage::AddChild(t)))
+                })
+                .placeholder(tr!(group_add_stage)),
+            );
+        }
+
+        container(children.padding(PADDING_SMALL))
+            .width(Length::Fill)
+            .style(|theme: &iced::Theme| {
+                container::Style::default().border(
+                    iced::Border::default()
+                        .rounded(BORDER_RADIUS_CARD)
+                        .color(theme.extended_palette().background.strong.color)
+                        .width(1.0),
+                )
+            })
+            .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::LevelMessage;
+    use rustortion_core::preset::StageConfig;
+
+    fn group_of(types: &[StageType]) -> GroupConfig {
+        GroupConfig {
+            stages: types.iter().map(|&t| t.into()).collect(),
+            ..GroupConfig::default()
+        }
+    }
+
+    #[test]
+    fn child_slider_becomes_child_change() {
+        let mut cfg = group_of(&[StageType::Preamp, StageType::Level]);
+        let msg = GroupMessage::Child(
+            1,
+            Box::new(StageMessage::Level(LevelMessage::GainChanged(0.5))),
+        );
+        assert!(matches!(
+            apply(&mut cfg, msg),
+            Some(ParamUpdate::ChildChanged(1, "gain", v)) if (v - 0.5).abs() < 1e-6
+        ));
+        assert!(matches!(&cfg.stages[1], StageConfig::Level(l) if (l.gain - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn child_trim_becomes_child_change() {
+        let mut cfg = group_of(&[StageType::Preamp, StageType::Level]);
+        let msg = GroupMessage::ChildTrim(0, TrimMessage::InvertToggled);
+        assert!(matches!(apply(&mut cfg, msg), Some(ParamUpdate::ChildChanged(0, "invert", 1.0))));
+        assert!(cfg.stages[0].common().invert);
+    }
+
+    #[test]
+    fn groups_cannot_be_added_to_groups() {
+        let mut cfg = group_of(&[]);
+        assert!(apply(&mut cfg, GroupMessage::AddChild(StageType::Group)).is_none());
+        assert!(cfg.stages.is_empty());
+    }
+
+    #[test]
+    fn moving_a_child_keeps_its_collapse_state() {
+        let mut cfg = group_of(&[StageType::Preamp, StageType::Eq]);
+        cfg.toggle_child_collapsed(0);
+        assert!(apply(&mut cfg, GroupMessage::MoveChildDown(0)).is_some());
+        assert_eq!(cfg.stages[1].stage_type(), StageType::Preamp);
+        assert!(cfg.is_child_collapsed(1));
+        assert!(!cfg.is_child_collapsed(0));
+        assert!(apply(&mut cfg, GroupMessage::MoveChildDown(1)).is_none());
+    }
+}
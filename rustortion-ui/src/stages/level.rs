@@ -64,7 +64,7 @@ mod tests {
 
     #[test]
     fn serialize_includes_bypassed() {
-        let cfg = LevelConfig { gain: 1.0, bypassed: true };
+        let cfg = LevelConfig { gain: 1.0, bypassed: true, ..LevelConfig::default() };
         let json = serde_json::to_string(&cfg).unwrap();
         assert!(json.contains("\"bypassed\":true"));
     }
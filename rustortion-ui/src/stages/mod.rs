@@ -1,3 +1,4 @@
+pub use rustortion_core::amp::stages::trim::{CommonStageParams, MAX_TRIM_DB};
 pub use rustortion_core::preset::stage_config::{StageCategory, StageConfig, StageType};
 
 use crate::messages::Message;
@@ -8,10 +9,45 @@ use iced::Element;
 pub enum ParamUpdate {
     /// A float parameter changed -- forward via `SetParameter` message.
     Changed(&'static str, f32),
+    /// A float parameter of the child at this index of a group changed --
+    /// forward via `SetChildParameter`.
+    ChildChanged(usize, &'static str, f32),
     /// A non-float parameter changed -- rebuild this stage only.
     NeedsStageRebuild,
+    /// A type or model was picked -- rebuild this stage, crossfading from
+    /// the old build so the switch doesn't click.
+    NeedsStageSwap,
     /// Re-scan the NAM models directory and re-register the global registry.
     RescanNamModels,
+    /// Re-scan the captures directory and re-register the global registry.
+    RescanCaptures,
+}
+
+/// A change to the trims in a stage card's header, the same for every type.
+#[derive(Debug, Clone)]
+pub enum TrimMessage {
+    InTrimChanged(f32),
+    OutTrimChanged(f32),
+    InvertToggled,
+}
+
+/// Apply a header trim change. Trims are live parameters of every runtime
+/// stage, so the result is always a name/value to forward.
+pub fn apply_trim(common: &mut CommonStageParams, msg: TrimMessage) -> (&'static str, f32) {
+    match msg {
+        TrimMessage::InTrimChanged(db) => {
+            common.in_trim_db = db;
+            ("in_trim_db", db)
+        }
+        TrimMessage::OutTrimChanged(db) => {
+            common.out_trim_db = db;
+            ("out_trim_db", db)
+        }
+        TrimMessage::InvertToggled => {
+            common.invert = !common.invert;
+            ("invert", if common.invert { 1.0 } else { 0.0 })
+        }
+    }
 }
 
 macro_rules! gui_stage_registry {
@@ -64,8 +100,11 @@ gui_stage_registry! {
     NoiseGate          => noise_gate,           NoiseGateMessage,          stage_noise_gate;
     MultibandSaturator => multiband_saturator,  MultibandSaturatorMessage, stage_multiband_saturator;
     Nam                => nam,                  NamMessage,                stage_nam;
+    Capture            => capture,              CaptureMessage,            stage_capture;
     Delay              => delay,                DelayMessage,              stage_delay;
     Reverb             => reverb,               ReverbMessage,             stage_reverb;
     Eq                 => eq,                   EqMessage,                 stage_eq;
     Tremolo            => tremolo,              TremoloMessage,            stage_tremolo;
+    Wah                => wah,                  WahMessage,                stage_wah;
+    Group              => group,                GroupMessage,              stage_group;
 }
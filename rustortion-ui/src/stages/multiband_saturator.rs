@@ -1,10 +1,10 @@
-use iced::widget::{column, row, text};
-use iced::{Element, Length};
+use iced::widget::{column, row, space, text, tooltip};
+use iced::{Alignment, Element, Length};
 
 use rustortion_core::amp::stages::multiband_saturator::MultibandSaturatorConfig;
 use crate::components::widgets::common::{
-    SPACING_NORMAL, SPACING_SECTION, SPACING_TIGHT, TEXT_SIZE_INFO, labeled_slider, stage_card,
-    StageViewState,
+    SPACING_NORMAL, SPACING_SECTION, SPACING_TIGHT, TEXT_SIZE_INFO, icon_button, labeled_slider,
+    stage_card, StageViewState,
 };
 use crate::messages::Message;
 use crate::tr;
@@ -23,6 +23,12 @@ pub enum MultibandSaturatorMessage {
     HighLevelChanged(f32),
     LowFreqChanged(f32),
     HighFreqChanged(f32),
+    LowMuteChanged(bool),
+    MidMuteChanged(bool),
+    HighMuteChanged(bool),
+    LowSoloChanged(bool),
+    MidSoloChanged(bool),
+    HighSoloChanged(bool),
 }
 
 // --- Apply ---
@@ -37,11 +43,65 @@ pub const fn apply(cfg: &mut MultibandSaturatorConfig, msg: MultibandSaturatorMe
         MultibandSaturatorMessage::HighLevelChanged(v) => { cfg.high_level = v; Some(ParamUpdate::Changed("high_level", v)) }
         MultibandSaturatorMessage::LowFreqChanged(v) => { cfg.low_freq = v; Some(ParamUpdate::Changed("low_freq", v)) }
         MultibandSaturatorMessage::HighFreqChanged(v) => { cfg.high_freq = v; Some(ParamUpdate::Changed("high_freq", v)) }
+        MultibandSaturatorMessage::LowMuteChanged(on) => { cfg.low_mute = on; Some(ParamUpdate::Changed("low_mute", switch(on))) }
+        MultibandSaturatorMessage::MidMuteChanged(on) => { cfg.mid_mute = on; Some(ParamUpdate::Changed("mid_mute", switch(on))) }
+        MultibandSaturatorMessage::HighMuteChanged(on) => { cfg.high_mute = on; Some(ParamUpdate::Changed("high_mute", switch(on))) }
+        MultibandSaturatorMessage::LowSoloChanged(on) => { cfg.low_solo = on; Some(ParamUpdate::Changed("low_solo", switch(on))) }
+        MultibandSaturatorMessage::MidSoloChanged(on) => { cfg.mid_solo = on; Some(ParamUpdate::Changed("mid_solo", switch(on))) }
+        MultibandSaturatorMessage::HighSoloChanged(on) => { cfg.high_solo = on; Some(ParamUpdate::Changed("high_solo", switch(on))) }
     }
 }
 
+/// Mute/solo switches are sent to the stage as 0.0/1.0.
+const fn switch(on: bool) -> f32 {
+    if on { 1.0 } else { 0.0 }
+}
+
 // --- View ---
 
+/// Band title with mute (M) and solo (S) toggles.
+fn band_header<'a>(
+    label: &'a str,
+    idx: usize,
+    muted: bool,
+    soloed: bool,
+    on_mute: fn(bool) -> MultibandSaturatorMessage,
+    on_solo: fn(bool) -> MultibandSaturatorMessage,
+) -> Element<'a, Message> {
+    let mute_btn = tooltip(
+        icon_button(
+            "M",
+            Some(Message::Stage(idx, StageMessage::MultibandSaturator(on_mute(!muted)))),
+            if muted {
+                iced::widget::button::danger
+            } else {
+                iced::widget::button::secondary
+            },
+        ),
+        tr!(band_mute_tooltip),
+        iced::widget::tooltip::Position::Bottom,
+    );
+
+    let solo_btn = tooltip(
+        icon_button(
+            "S",
+            Some(Message::Stage(idx, StageMessage::MultibandSaturator(on_solo(!soloed)))),
+            if soloed {
+                iced::widget::button::warning
+            } else {
+                iced::widget::button::secondary
+            },
+        ),
+        tr!(band_solo_tooltip),
+        iced::widget::tooltip::Position::Bottom,
+    );
+
+    row![text(label).size(TEXT_SIZE_INFO), space::horizontal(), mute_btn, solo_btn]
+        .spacing(SPACING_TIGHT)
+        .align_y(Alignment::Center)
+        .into()
+}
+
 pub fn view(
     idx: usize,
     cfg: &MultibandSaturatorConfig,
@@ -84,7 +144,14 @@ pub fn view(
             .spacing(SPACING_TIGHT);
 
             let low_band_section = column![
-                text(tr!(low_band)).size(TEXT_SIZE_INFO),
+                band_header(
+                    tr!(low_band),
+                    idx,
+                    cfg.low_mute,
+                    cfg.low_solo,
+                    MultibandSaturatorMessage::LowMuteChanged,
+                    MultibandSaturatorMessage::LowSoloChanged,
+                ),
                 labeled_slider(
                     tr!(drive),
                     0.0..=1.0,
@@ -115,7 +182,14 @@ pub fn view(
             .spacing(SPACING_TIGHT);
 
             let mid_band_section = column![
-                text(tr!(mid_band)).size(TEXT_SIZE_INFO),
+                band_header(
+                    tr!(mid_band),
+                    idx,
+                    cfg.mid_mute,
+                    cfg.mid_solo,
+                    MultibandSaturatorMessage::MidMuteChanged,
+                    MultibandSaturatorMessage::MidSoloChanged,
+                ),
                 labeled_slider(
                     tr!(drive),
                     0.0..=1.0,
@@ -146,7 +220,14 @@ pub fn view(
             .spacing(SPACING_TIGHT);
 
             let high_band_section = column![
-                text(tr!(high_band)).size(TEXT_SIZE_INFO),
+                band_header(
+                    tr!(high_band),
+                    idx,
+                    cfg.high_mute,
+                    cfg.high_solo,
+                    MultibandSaturatorMessage::HighMuteChanged,
+                    MultibandSaturatorMessage::HighSoloChanged,
+                ),
                 labeled_slider(
                     tr!(drive),
                     0.0..=1.0,
@@ -1,4 +1,4 @@
-use iced::widget::column;
+use iced::widget::{checkbox, column};
 use iced::Element;
 
 use rustortion_core::amp::stages::poweramp::{PowerAmpConfig, PowerAmpType};
@@ -18,22 +18,24 @@ pub enum PowerAmpMessage {
     DriveChanged(f32),
     SagChanged(f32),
     SagReleaseChanged(f32),
+    AutoLevelToggled(bool),
 }
 
 // --- Apply ---
 
 pub const fn apply(cfg: &mut PowerAmpConfig, msg: PowerAmpMessage) -> Option<ParamUpdate> {
     match msg {
-        PowerAmpMessage::TypeChanged(t) => { cfg.amp_type = t; Some(ParamUpdate::NeedsStageRebuild) }
+        PowerAmpMessage::TypeChanged(t) => { cfg.amp_type = t; Some(ParamUpdate::NeedsStageSwap) }
         PowerAmpMessage::DriveChanged(v) => { cfg.drive = v; Some(ParamUpdate::Changed("drive", v)) }
         PowerAmpMessage::SagChanged(v) => { cfg.sag = v; Some(ParamUpdate::Changed("sag", v)) }
         PowerAmpMessage::SagReleaseChanged(v) => { cfg.sag_release = v; Some(ParamUpdate::Changed("sag_release", v)) }
+        PowerAmpMessage::AutoLevelToggled(on) => { cfg.auto_level = on; Some(ParamUpdate::Changed("auto_level", if on { 1.0 } else { 0.0 })) }
     }
 }
 
 // --- View ---
 
-const POWER_AMP_TYPES: [PowerAmpType; 3] = [
+pub(crate) const POWER_AMP_TYPES: [PowerAmpType; 3] = [
     PowerAmpType::ClassA,
     PowerAmpType::ClassAB,
     PowerAmpType::ClassB,
@@ -86,6 +88,12 @@ pub fn view(
                     |v| format!("{v:.0} {}", tr!(ms)),
                     5.0
                 ),
+                checkbox(cfg.auto_level)
+                    .label(tr!(auto_level))
+                    .on_toggle(move |on| Message::Stage(
+                        idx,
+                        StageMessage::PowerAmp(PowerAmpMessage::AutoLevelToggled(on))
+                    )),
             ]
             .spacing(SPACING_TIGHT)
             .into()
@@ -1,4 +1,4 @@
-use iced::widget::column;
+use iced::widget::{checkbox, column};
 use iced::Element;
 
 use rustortion_core::amp::stages::clipper::ClipperType;
@@ -18,6 +18,7 @@ pub enum PreampMessage {
     GainChanged(f32),
     BiasChanged(f32),
     ClipperChanged(ClipperType),
+    AutoLevelToggled(bool),
 }
 
 // --- Apply ---
@@ -26,13 +27,14 @@ pub const fn apply(cfg: &mut PreampConfig, msg: PreampMessage) -> Option<ParamUp
     match msg {
         PreampMessage::GainChanged(v) => { cfg.gain = v; Some(ParamUpdate::Changed("gain", v)) }
         PreampMessage::BiasChanged(v) => { cfg.bias = v; Some(ParamUpdate::Changed("bias", v)) }
-        PreampMessage::ClipperChanged(c) => { cfg.clipper_type = c; Some(ParamUpdate::NeedsStageRebuild) }
+        PreampMessage::ClipperChanged(c) => { cfg.clipper_type = c; Some(ParamUpdate::NeedsStageSwap) }
+        PreampMessage::AutoLevelToggled(on) => { cfg.auto_level = on; Some(ParamUpdate::Changed("auto_level", if on { 1.0 } else { 0.0 })) }
     }
 }
 
 // --- View ---
 
-const CLIPPER_TYPES: [ClipperType; 6] = [
+pub(crate) const CLIPPER_TYPES: [ClipperType; 6] = [
     ClipperType::Soft,
     ClipperType::Medium,
     ClipperType::Hard,
@@ -67,6 +69,9 @@ pub fn view(
                 |v| format!("{v:.2}"),
                 0.1
             ),
+            checkbox(cfg.auto_level)
+                .label(tr!(auto_level))
+                .on_toggle(move |on| Message::Stage(idx, StageMessage::Preamp(PreampMessage::AutoLevelToggled(on)))),
         ]
         .spacing(SPACING_TIGHT)
         .into()
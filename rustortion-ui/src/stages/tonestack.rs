@@ -25,7 +25,7 @@ pub enum ToneStackMessage {
 
 pub const fn apply(cfg: &mut ToneStackConfig, msg: ToneStackMessage) -> Option<ParamUpdate> {
     match msg {
-        ToneStackMessage::ModelChanged(mo) => { cfg.model = mo; Some(ParamUpdate::NeedsStageRebuild) }
+        ToneStackMessage::ModelChanged(mo) => { cfg.model = mo; Some(ParamUpdate::NeedsStageSwap) }
         ToneStackMessage::BassChanged(v) => { cfg.bass = v; Some(ParamUpdate::Changed("bass", v)) }
         ToneStackMessage::MidChanged(v) => { cfg.mid = v; Some(ParamUpdate::Changed("mid", v)) }
         ToneStackMessage::TrebleChanged(v) => { cfg.treble = v; Some(ParamUpdate::Changed("treble", v)) }
@@ -35,7 +35,7 @@ pub const fn apply(cfg: &mut ToneStackConfig, msg: ToneStackMessage) -> Option<P
 
 // --- View ---
 
-const TONE_STACK_MODELS: [ToneStackModel; 4] = [
+pub(crate) const TONE_STACK_MODELS: [ToneStackModel; 4] = [
     ToneStackModel::Modern,
     ToneStackModel::British,
     ToneStackModel::American,
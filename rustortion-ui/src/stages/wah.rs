@@ -0,0 +1,118 @@
+use iced::widget::column;
+use iced::Element;
+
+use rustortion_core::amp::stages::wah::{WahConfig, WahMode};
+use crate::components::widgets::common::{
+    labeled_picker, labeled_slider, stage_card, StageViewState, SPACING_TIGHT,
+};
+use crate::messages::Message;
+use crate::tr;
+
+use super::{ParamUpdate, StageMessage};
+
+// --- Message ---
+
+#[derive(Debug, Clone)]
+pub enum WahMessage {
+    ModeChanged(WahMode),
+    PositionChanged(f32),
+    QChanged(f32),
+    SensitivityChanged(f32),
+    AttackChanged(f32),
+    ReleaseChanged(f32),
+}
+
+// --- Apply ---
+
+pub const fn apply(cfg: &mut WahConfig, msg: WahMessage) -> Option<ParamUpdate> {
+    match msg {
+        WahMessage::ModeChanged(m) => {
+            cfg.mode = m;
+            Some(ParamUpdate::NeedsStageSwap)
+        }
+        WahMessage::PositionChanged(v) => {
+            cfg.position = v;
+            Some(ParamUpdate::Changed("position", v))
+        }
+        WahMessage::QChanged(v) => {
+            cfg.q = v;
+            Some(ParamUpdate::Changed("q", v))
+        }
+        WahMessage::SensitivityChanged(v) => {
+            cfg.sensitivity = v;
+            Some(ParamUpdate::Changed("sensitivity", v))
+        }
+        WahMessage::AttackChanged(v) => {
+            cfg.attack_ms = v;
+            Some(ParamUpdate::Changed("attack", v))
+        }
+        WahMessage::ReleaseChanged(v) => {
+            cfg.release_ms = v;
+            Some(ParamUpdate::Changed("release", v))
+        }
+    }
+}
+
+// --- View ---
+
+pub(crate) const WAH_MODES: [WahMode; 3] = [WahMode::Manual, WahMode::Auto, WahMode::Fixed];
+
+pub fn view(idx: usize, cfg: &WahConfig, state: StageViewState) -> Element<'_, Message> {
+    stage_card(tr!(stage_wah), idx, state, || {
+        let mut content = column![
+            labeled_picker(tr!(mode), WAH_MODES, Some(cfg.mode), move |m| {
+                Message::Stage(idx, StageMessage::Wah(WahMessage::ModeChanged(m)))
+            }),
+            labeled_slider(
+                tr!(wah_position),
+                0.0..=1.0,
+                cfg.position,
+                move |v| Message::Stage(idx, StageMessage::Wah(WahMessage::PositionChanged(v))),
+                |v| format!("{:.0}%", v * 100.0),
+                0.01
+            ),
+            labeled_slider(
+                tr!(wah_q),
+                0.5..=10.0,
+                cfg.q,
+                move |v| Message::Stage(idx, StageMessage::Wah(WahMessage::QChanged(v))),
+                |v| format!("{v:.1}"),
+                0.1
+            ),
+        ]
+        .spacing(SPACING_TIGHT);
+
+        // Envelope controls only matter in auto-wah mode.
+        if cfg.mode == WahMode::Auto {
+            content = content
+                .push(labeled_slider(
+                    tr!(sensitivity),
+                    0.0..=1.0,
+                    cfg.sensitivity,
+                    move |v| {
+                        Message::Stage(idx, StageMessage::Wah(WahMessage::SensitivityChanged(v)))
+                    },
+                    |v| format!("{:.0}%", v * 100.0),
+                    0.01,
+                ))
+                .push(labeled_slider(
+                    tr!(attack),
+                    1.0..=100.0,
+                    cfg.attack_ms,
+                    move |v| Message::Stage(idx, StageMessage::Wah(WahMessage::AttackChanged(v))),
+                    |v| format!("{v:.0} {}", tr!(ms)),
+                    1.0,
+                ))
+                .push(labeled_slider(
+                    tr!(release),
+                    10.0..=1000.0,
+                    cfg.release_ms,
+                    move |v| Message::Stage(idx, StageMessage::Wah(WahMessage::ReleaseChanged(v))),
+                    |v| format!("{v:.0} {}", tr!(ms)),
+                    10.0,
+                ));
+        }
+
+        content.into()
+    })
+}
@@ -1,6 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use crate::stages::StageCategory;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Tab {
     #[default]
     Amp,
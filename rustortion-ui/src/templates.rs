@@ -0,0 +1,213 @@
+use rustortion_core::preset::locks::insert_locked_stages;
+
+use crate::stages::{StageCategory, StageConfig, StageType};
+
+/// A stage order for the amp section, applied around whatever the chain
+/// already holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainTemplate {
+    pub name: &'static str,
+    pub slots: &'static [StageType],
+}
+
+impl std::fmt::Display for ChainTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Templates offered next to "Add Stage". The filter in front of a stack is
+/// the input high-pass on the I/O tab, which isn't part of the chain.
+pub const CHAIN_TEMPLATES: &[ChainTemplate] = &[
+    ChainTemplate {
+        name: "British stack",
+        slots: &[
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::Preamp,
+            StageType::PowerAmp,
+            StageType::Level,
+        ],
+    },
+    ChainTemplate {
+        name: "American clean",
+        slots: &[
+            StageType::Compressor,
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+            StageType::Level,
+        ],
+    },
+    ChainTemplate {
+        name: "Modern high gain",
+        slots: &[
+            StageType::NoiseGate,
+            StageType::Preamp,
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+            StageType::Level,
+        ],
+    },
+    ChainTemplate {
+        name: "Single channel",
+        slots: &[
+            StageType::Preamp,
+            StageType::ToneStack,
+            StageType::PowerAmp,
+            StageType::Level,
+        ],
+    },
+];
+
+/// `stages` rearranged into `template`'s order. Each slot takes the first
+/// unclaimed amp stage of its type, in chain order, with its parameters;
+/// slots left empty get a stage with default settings. Amp stages no slot
+/// claims stay behind the stage they followed, and effects stay at the end.
+///
+/// Locked stages keep their relative place in the chain, so a locked last
+/// stage stays last, and each stands in for a slot of its type so the
+/// template doesn't add another.
+pub fn apply_template(stages: &[StageConfig], template: &ChainTemplate) -> Vec<StageConfig> {
+    let mut slots = template.slots.to_vec();
+    for stage in stages.iter().filter(|s| s.locked()) {
+        if stage.category() == StageCategory::Amp
+            && let Some(slot) = slots.iter().position(|&t| t == stage.stage_type())
+        {
+            slots.remove(slot);
+        }
+    }
+
+    let unlocked: Vec<StageConfig> = stages.iter().filter(|s| !s.locked()).cloned().collect();
+    insert_locked_stages(stages, arrange(&unlocked, &slots))
+}
+
+fn arrange(stages: &[StageConfig], slots: &[StageType]) -> Vec<StageConfig> {
+    let (amp, effects): (Vec<&StageConfig>, Vec<&StageConfig>) = stages
+        .iter()
+        .partition(|s| s.category() == StageCategory::Amp);
+
+    let mut slot_of = vec![None; amp.len()];
+    let claimed: Vec<Option<usize>> = slots
+        .iter()
+        .enumerate()
+        .map(|(slot, &stage_type)| {
+            let idx = (0..amp.len())
+                .find(|&i| slot_of[i].is_none() && amp[i].stage_type() == stage_type)?;
+            slot_of[idx] = Some(slot);
+            Some(idx)
+        })
+        .collect();
+
+    let mut front = Vec::new();
+    let mut after = vec![Vec::new(); slots.len()];
+    let mut owner = None;
+    for (stage, slot) in amp.iter().zip(&slot_of) {
+        match (slot, owner) {
+            (Some(slot), _) => owner = Some(*slot),
+            (None, Some(owner)) => after[owner].push((*stage).clone()),
+            (None, None) => front.push((*stage).clone()),
+        }
+    }
+
+    let mut result = front;
+    for ((&stage_type, idx), rest) in slots.iter().zip(claimed).zip(after) {
+        result.push(idx.map_or_else(|| StageConfig::from(stage_type), |i| amp[i].clone()));
+        result.extend(rest);
+    }
+    result.extend(effects.into_iter().cloned());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn british() -> &'static ChainTemplate {
+        &CHAIN_TEMPLATES[0]
+    }
+
+    fn preamp(gain: f32) -> StageConfig {
+        let mut cfg = StageConfig::from(StageType::Preamp);
+        if let StageConfig::Preamp(c) = &mut cfg {
+            c.gain = gain;
+        }
+        cfg
+    }
+
+    fn types(stages: &[StageConfig]) -> Vec<StageType> {
+        stages.iter().map(StageConfig::stage_type).collect()
+    }
+
+    #[test]
+    fn an_empty_chain_gets_the_template_with_defaults() {
+        let result = apply_template(&[], british());
+        assert_eq!(types(&result), british().slots);
+        assert_eq!(result[0], StageConfig::from(StageType::Preamp));
+    }
+
+    #[test]
+    fn existing_stages_keep_their_parameters_in_chain_order() {
+        let stages = vec![preamp(2.0), preamp(7.0)];
+        let result = apply_template(&stages, british());
+
+        assert_eq!(types(&result), british().slots);
+        assert_eq!(result[0], preamp(2.0));
+        assert_eq!(result[2], preamp(7.0));
+    }
+
+    #[test]
+    fn only_missing_stages_are_added() {
+        let stages = vec![StageConfig::from(StageType::PowerAmp), preamp(4.0)];
+        let result = apply_template(&stages, british());
+
+        assert_eq!(result.len(), british().slots.len());
+        assert_eq!(result[0], preamp(4.0));
+        assert_eq!(apply_template(&result, british()), result);
+    }
+
+    #[test]
+    fn unclaimed_stages_stay_beside_their_neighbours() {
+        let stages = vec![
+            StageConfig::from(StageType::NoiseGate),
+            preamp(3.0),
+            StageConfig::from(StageType::Compressor),
+            StageConfig::from(StageType::Delay),
+        ];
+        let result = apply_template(&stages, british());
+
+        assert_eq!(
+            types(&result),
+            [
+                StageType::NoiseGate,
+                StageType::Preamp,
+                StageType::Compressor,
+                StageType::ToneStack,
+                StageType::Preamp,
+                StageType::PowerAmp,
+                StageType::Level,
+                StageType::Delay,
+            ]
+        );
+        assert_eq!(result[1], preamp(3.0));
+    }
+
+    #[test]
+    fn a_locked_last_stage_stays_last_and_fills_its_slot() {
+        let mut level = StageConfig::from(StageType::Level);
+        level.set_locked(true);
+        let stages = vec![
+            preamp(4.0),
+            level.clone(),
+            StageConfig::from(StageType::Delay),
+        ];
+        let result = apply_template(&stages, british());
+
+        assert_eq!(
+            types(&result),
+            [british().slots, &[StageType::Delay][..]].concat()
+        );
+        assert_eq!(result[4], level);
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt::Display;
+
+use iced::Theme;
+use iced::theme::Mode;
+use serde::{Deserialize, Serialize};
+
+use crate::tr;
+
+/// The theme the user picked in settings.
+///
+/// Stored by display name so the settings file stays readable and a theme
+/// iced later drops or renames falls back to the default instead of failing
+/// to load.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ThemeChoice {
+    /// Light or dark to match the desktop, when the platform reports it.
+    System,
+    Builtin(Theme),
+}
+
+const SYSTEM_KEY: &str = "system";
+
+impl ThemeChoice {
+    /// Every choice offered by the settings picker, "follow system" first.
+    pub fn all() -> Vec<Self> {
+        std::iter::once(Self::System)
+            .chain(Theme::ALL.iter().cloned().map(Self::Builtin))
+            .collect()
+    }
+
+    /// The theme to draw with. `system` is the last mode the platform
+    /// reported; `Mode::None` (not detectable) keeps the default look.
+    pub fn resolve(&self, system: Mode) -> Theme {
+        match self {
+            Self::Builtin(theme) => theme.clone(),
+            Self::System => match system {
+                Mode::Light => Theme::Light,
+                Mode::Dark => Theme::Dark,
+                Mode::None => Theme::TokyoNight,
+            },
+        }
+    }
+}
+
+impl Default for ThemeChoice {
+    fn default() -> Self {
+        Self::Builtin(Theme::TokyoNight)
+    }
+}
+
+impl Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::System => write!(f, "{}", tr!(theme_follow_system)),
+            Self::Builtin(theme) => write!(f, "{theme}"),
+        }
+    }
+}
+
+impl From<String> for ThemeChoice {
+    fn from(name: String) -> Self {
+        if name == SYSTEM_KEY {
+            return Self::System;
+        }
+        Theme::ALL
+            .iter()
+            .find(|theme| theme.to_string() == name)
+            .cloned()
+            .map_or_else(Self::default, Self::Builtin)
+    }
+}
+
+impl From<ThemeChoice> for String {
+    fn from(choice: ThemeChoice) -> Self {
+        match choice {
+            ThemeChoice::System => SYSTEM_KEY.to_string(),
+            ThemeChoice::Builtin(theme) => theme.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_by_name() {
+        for choice in ThemeChoice::all() {
+            let json = serde_json::to_string(&choice).unwrap();
+            let back: ThemeChoice = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, choice);
+        }
+        assert_eq!(
+            serde_json::to_string(&ThemeChoice::System).unwrap(),
+            "\"system\""
+        );
+    }
+
+    #[test]
+    fn unknown_name_falls_back_to_default() {
+        let choice: ThemeChoice = serde_json::from_str("\"Not A Theme\"").unwrap();
+        assert_eq!(choice, ThemeChoice::default());
+    }
+
+    #[test]
+    fn system_follows_reported_mode() {
+        assert_eq!(ThemeChoice::System.resolve(Mode::Light), Theme::Light);
+        assert_eq!(ThemeChoice::System.resolve(Mode::Dark), Theme::Dark);
+        assert_eq!(ThemeChoice::System.resolve(Mode::None), Theme::TokyoNight);
+    }
+}
@@ -0,0 +1,68 @@
+#![allow(clippy::pedantic, clippy::nursery)]
+
+use rustortion_core::audio::engine::{Engine, EngineHandle};
+use rustortion_core::ir::cabinet::{ConvolverType, IrCabinet};
+use rustortion_ui::components::ir_cabinet_control::IrCabinetControl;
+
+const SAMPLE_RATE: usize = 48_000;
+const BLOCK: usize = 128;
+/// Messages the engine's queue holds.
+const QUEUE_CAPACITY: usize = 128;
+
+fn engine() -> (Engine, EngineHandle) {
+    let cabinet = IrCabinet::new(ConvolverType::Fir, 256);
+    let (engine, handle, _rt_drop_rx) =
+        Engine::new_for_plugin(SAMPLE_RATE, BLOCK, Some(cabinet), 1.0).unwrap();
+    (engine, handle)
+}
+
+fn process_block(engine: &mut Engine) {
+    let input = [0.0f32; BLOCK];
+    let mut output = [0.0f32; BLOCK];
+    engine.process(&input, &mut output).unwrap();
+}
+
+#[test]
+fn state_is_withheld_while_messages_are_queued() {
+    let (mut engine, handle) = engine();
+    handle.set_ir_gain(0.5);
+    assert!(handle.state().is_none());
+
+    process_block(&mut engine);
+    let state = handle.state().unwrap();
+    assert_eq!(state.ir_gain, 0.5);
+}
+
+#[test]
+fn a_dropped_bypass_is_undone_on_the_next_sync() {
+    let (mut engine, handle) = engine();
+    handle.set_ir_gain(0.5);
+    process_block(&mut engine);
+    let mut control = IrCabinetControl::new(false, 0.5);
+
+    // With the queue full the bypass never reaches the engine.
+    for _ in 0..QUEUE_CAPACITY {
+        handle.set_ir_gain(0.5);
+    }
+    control.set_bypassed(true);
+    handle.set_ir_bypass(true);
+
+    process_block(&mut engine);
+    let state = handle.state().unwrap();
+    assert!(!state.ir_bypassed);
+
+    control.sync_with_engine(&state, false);
+    assert!(!control.is_bypassed());
+}
+
+#[test]
+fn a_control_that_matches_the_engine_is_left_alone() {
+    let (mut engine, handle) = engine();
+    handle.set_ir_gain(0.5);
+    handle.set_ir_bypass(true);
+    process_block(&mut engine);
+
+    let mut control = IrCabinetControl::new(true, 0.5);
+    control.sync_with_engine(&handle.state().unwrap(), false);
+    assert!(control.is_bypassed());
+}